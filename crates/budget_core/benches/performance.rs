@@ -3,6 +3,7 @@ use budget_core::ledger::{
     category::{Category, CategoryKind},
     BudgetPeriod, Ledger,
 };
+use bufy_domain::{currency::CurrencyCode, Money};
 use bufy_storage_json::{
     load_ledger_from_path as load_ledger_from_file, save_ledger_to_path as save_ledger_to_file,
 };
@@ -10,6 +11,10 @@ use chrono::{Duration, NaiveDate};
 use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 use tempfile::tempdir;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 fn build_sample_ledger(txn_count: usize) -> Ledger {
     let mut ledger = Ledger::new("Benchmark", BudgetPeriod::default());
 
@@ -26,11 +31,11 @@ fn build_sample_ledger(txn_count: usize) -> Ledger {
             savings,
             Some(groceries),
             scheduled,
-            50.0 + (idx % 100) as f64,
+            usd(50.0 + (idx % 100) as f64),
         );
         if idx % 3 == 0 {
             txn.actual_date = Some(scheduled + Duration::days(1));
-            txn.actual_amount = Some(txn.budgeted_amount * 0.95);
+            txn.actual_amount = Some(usd(txn.budgeted_amount.to_major() * 0.95));
         }
         ledger.add_transaction(txn);
     }
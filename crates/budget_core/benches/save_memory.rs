@@ -0,0 +1,114 @@
+//! Peak heap usage while saving a 100k-transaction ledger.
+//!
+//! Compares the streaming writer used by the persistence layer with the
+//! previous approach of rendering the whole pretty JSON document into a
+//! `String` before writing it. Run with `cargo bench --bench save_memory`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fs,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use budget_core::ledger::{
+    account::{Account, AccountKind},
+    category::{Category, CategoryKind},
+    transaction::Transaction,
+    BudgetPeriod, Ledger,
+};
+use bufy_domain::{currency::CurrencyCode, Money};
+use bufy_storage_json::save_ledger_to_path;
+use chrono::{Duration, NaiveDate};
+use tempfile::tempdir;
+
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
+const TRANSACTION_COUNT: usize = 100_000;
+
+struct PeakAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+/// Runs `f` and returns the peak heap growth it caused, in bytes.
+fn peak_growth(f: impl FnOnce()) -> usize {
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    f();
+    PEAK.load(Ordering::Relaxed).saturating_sub(start)
+}
+
+fn build_ledger(txn_count: usize) -> Ledger {
+    let mut ledger = Ledger::new("Memory", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let savings = ledger.add_account(Account::new("Savings", AccountKind::Savings));
+    let groceries = ledger.add_category(Category::new("Groceries", CategoryKind::Expense));
+    let start_date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+    for idx in 0..txn_count {
+        let scheduled = start_date + Duration::days((idx % 365) as i64);
+        let mut txn = Transaction::new(
+            checking,
+            savings,
+            Some(groceries),
+            scheduled,
+            usd(50.0 + (idx % 100) as f64),
+        );
+        txn.notes = Some(format!("Weekly shop #{}", idx));
+        ledger.add_transaction(txn);
+    }
+    ledger
+}
+
+fn save_via_string(ledger: &Ledger, path: &Path) {
+    let json = serde_json::to_string_pretty(ledger).expect("serialize ledger");
+    fs::write(path, json).expect("write ledger");
+}
+
+fn mib(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+fn main() {
+    let ledger = build_ledger(TRANSACTION_COUNT);
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("ledger.bfy");
+
+    let buffered = peak_growth(|| save_via_string(&ledger, &path));
+    let file_size = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+    let streamed = peak_growth(|| save_ledger_to_path(&ledger, &path).expect("save ledger"));
+
+    println!(
+        "ledger_save_peak_memory_100k: file {:.1} MiB, in-memory string {:.1} MiB, streaming {:.1} MiB",
+        mib(file_size as usize),
+        mib(buffered),
+        mib(streamed)
+    );
+    assert!(
+        streamed * 10 < buffered,
+        "streaming save should use a fraction of the buffered peak ({} vs {} bytes)",
+        streamed,
+        buffered
+    );
+}
@@ -1,9 +1,22 @@
-use budget_core::{cli::run_cli, init};
+use budget_core::{
+    cli::{run_cli_with, CliOptions},
+    init_with,
+};
 
 fn main() {
-    init();
+    let options = match CliOptions::from_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            std::process::exit(2);
+        }
+    };
+    if let Err(err) = init_with(&options.log_settings()) {
+        eprintln!("Error: cannot open the log file: {err}");
+        std::process::exit(2);
+    }
 
-    if let Err(err) = run_cli() {
+    if let Err(err) = run_cli_with(options) {
         eprintln!("Error: {err}");
         std::process::exit(1);
     }
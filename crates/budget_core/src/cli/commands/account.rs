@@ -1,6 +1,12 @@
+pub(crate) mod balance;
+pub(crate) mod close;
 pub mod list_accounts;
+pub(crate) mod reconcile;
+pub(crate) mod statement;
 
+use crate::cli::commands::ordering::{self, Ordered};
 use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::entity_ref::expand_id_flags;
 use crate::cli::io;
 use crate::cli::menus::{account_menu, menu_error_to_command_error};
 use crate::cli::registry::CommandEntry;
@@ -9,19 +15,22 @@ pub(crate) fn definitions() -> Vec<CommandEntry> {
     vec![CommandEntry::new(
         "account",
         "Manage accounts via wizard flows",
-        "account <add|edit|list|remove|show>",
+        "account <add|balance|close|edit|list|move|reconcile|remove|reopen|show|star|statement|style|unstar>",
         cmd_account,
     )]
 }
 
 fn cmd_account(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let expanded = expand_id_flags(args);
+    let args: &[&str] = &expanded.iter().map(|arg| arg.as_ref()).collect::<Vec<_>>();
     if context.mode() == CliMode::Interactive && args.is_empty() {
         return run_account_menu(context);
     }
 
     if args.is_empty() {
         return Err(CommandError::InvalidArguments(
-            "usage: account <add|edit|list|remove|show>".into(),
+            "usage: account <add|balance|close|edit|list|move|reconcile|remove|reopen|show|star|statement|style|unstar>"
+                .into(),
         ));
     }
 
@@ -43,10 +52,19 @@ fn dispatch_account_action(
 ) -> CommandResult {
     match action.to_lowercase().as_str() {
         "add" => handle_add(context, args),
+        "balance" => balance::run_balance(context, args),
+        "close" => close::run_close(context, args),
         "edit" => handle_edit(context, args),
-        "list" => handle_list(context),
+        "list" => handle_list(context, args),
         "remove" => handle_remove(context),
-        "show" => handle_show(context),
+        "reopen" => close::run_reopen(context, args),
+        "show" => handle_show(context, args),
+        "move" => ordering::run_move(context, Ordered::Account, args),
+        "star" => ordering::run_star(context, Ordered::Account, args, true),
+        "unstar" => ordering::run_star(context, Ordered::Account, args, false),
+        "style" => ordering::run_style(context, Ordered::Account, args),
+        "reconcile" => reconcile::run_reconcile(context, args),
+        "statement" => statement::run_statement(context, args),
         other => Err(CommandError::InvalidArguments(format!(
             "unknown account subcommand `{}`",
             other
@@ -69,9 +87,7 @@ fn handle_edit(context: &mut ShellContext, args: &[&str]) -> CommandResult {
         ));
     }
     let index = if let Some(value) = args.first() {
-        value
-            .parse::<usize>()
-            .map_err(|_| CommandError::InvalidArguments("account index must be numeric".into()))?
+        context.account_index_from_arg(value)?
     } else {
         match context.select_account_index("Select an account to edit:")? {
             Some(index) => index,
@@ -81,12 +97,27 @@ fn handle_edit(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     context.run_account_edit_wizard(index)
 }
 
-fn handle_list(context: &mut ShellContext) -> CommandResult {
-    list_accounts::run_list_accounts(context)
+fn handle_list(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    list_accounts::run_list_accounts(context, include_closed(args)?)
 }
 
-fn handle_show(context: &mut ShellContext) -> CommandResult {
-    list_accounts::run_list_accounts(context)
+/// `account show <name|index|id>` shows one account; without one it lists
+/// them like `account list`.
+fn handle_show(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    match args {
+        [target] if !target.starts_with("--") => list_accounts::run_show_account(context, target),
+        _ => list_accounts::run_list_accounts(context, include_closed(args)?),
+    }
+}
+
+fn include_closed(args: &[&str]) -> Result<bool, CommandError> {
+    match args {
+        [] => Ok(false),
+        [flag] if flag.eq_ignore_ascii_case("--include-closed") => Ok(true),
+        _ => Err(CommandError::InvalidArguments(
+            "usage: account list [--include-closed]".into(),
+        )),
+    }
 }
 
 fn handle_remove(_context: &mut ShellContext) -> CommandResult {
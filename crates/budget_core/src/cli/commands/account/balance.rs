@@ -0,0 +1,99 @@
+//! `account balance [name] [--as-of YYYY-MM-DD] [--planned]`: point-in-time
+//! balance of one account, or of every open bank, cash, and savings account.
+
+use bufy_core::{CurrencyFormatter, DateFormatter};
+
+use crate::cli::core::{parse_date, CommandError, CommandResult, ShellContext};
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::AccountService;
+use crate::ledger::AccountKind;
+
+const BALANCE_USAGE: &str = "usage: account balance [name] [--as-of YYYY-MM-DD] [--planned]";
+
+pub(crate) fn run_balance(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let mut name = None;
+    let mut as_of = None;
+    let mut planned = false;
+    let mut iter = args.iter();
+    while let Some(token) = iter.next() {
+        match token.to_ascii_lowercase().as_str() {
+            "--as-of" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CommandError::InvalidArguments(BALANCE_USAGE.into()))?;
+                as_of = Some(parse_date(value)?);
+            }
+            "--planned" => planned = true,
+            _ if name.is_none() && !token.starts_with("--") => name = Some(*token),
+            _ => return Err(CommandError::InvalidArguments(BALANCE_USAGE.into())),
+        }
+    }
+    let as_of = as_of.unwrap_or_else(|| context.clock.today());
+
+    let balances = context.with_ledger(|ledger| {
+        let ids: Vec<_> = match name {
+            Some(name) => vec![
+                ledger
+                    .accounts
+                    .iter()
+                    .find(|account| account.name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| {
+                        CommandError::InvalidArguments(format!("unknown account `{}`", name))
+                    })?
+                    .id,
+            ],
+            None => ledger
+                .accounts
+                .iter()
+                .filter(|account| {
+                    !account.is_closed()
+                        && matches!(
+                            account.kind,
+                            AccountKind::Bank | AccountKind::Cash | AccountKind::Savings
+                        )
+                })
+                .map(|account| account.id)
+                .collect(),
+        };
+        ids.into_iter()
+            .map(|id| {
+                let balance = AccountService::balance(ledger, id, as_of)?;
+                let appearance = ledger
+                    .account(id)
+                    .map(|account| account.appearance.clone())
+                    .unwrap_or_default();
+                Ok((balance, appearance))
+            })
+            .collect::<Result<Vec<_>, CommandError>>()
+    })?;
+
+    let formatters = &context.formatters;
+    let mut columns = vec![
+        TableColumn::new("ACCOUNT", 24),
+        TableColumn::new("BALANCE", 16),
+    ];
+    if planned {
+        columns.push(TableColumn::new("PLANNED", 16));
+        columns.push(TableColumn::new("WITH PLANNED", 16));
+    }
+    let mut table = Table::new(
+        Some(format!("Balances as of {}", formatters.format_date(as_of))),
+        columns,
+    );
+    for (balance, appearance) in &balances {
+        let amount = |value: f64| formatters.format_amount(value, &balance.currency);
+        let mut row = vec![
+            context
+                .ui_style
+                .apply_entity_style(&balance.account_name, appearance),
+            amount(balance.balance),
+        ];
+        if planned {
+            row.push(amount(balance.planned));
+            row.push(amount(balance.with_planned()));
+        }
+        table.add_row(row);
+    }
+    TableRenderer::render(&table, &context.ui_style);
+    Ok(())
+}
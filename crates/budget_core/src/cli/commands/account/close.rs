@@ -0,0 +1,115 @@
+//! `account close <name> [--on YYYY-MM-DD] [--transfer-to <account>]` and
+//! `account reopen <name>`.
+
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use uuid::Uuid;
+
+use crate::cli::core::{parse_date, CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::core::services::AccountService;
+
+const CLOSE_USAGE: &str = "usage: account close <name> [--on YYYY-MM-DD] [--transfer-to <account>]";
+const REOPEN_USAGE: &str = "usage: account reopen <name>";
+
+pub(crate) fn run_close(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let interactive = context.mode() == CliMode::Interactive;
+    let mut name = None;
+    let mut on = None;
+    let mut transfer_to = None;
+    let mut iter = args.iter();
+    while let Some(token) = iter.next() {
+        match token.to_ascii_lowercase().as_str() {
+            "--on" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CommandError::InvalidArguments(CLOSE_USAGE.into()))?;
+                on = Some(parse_date(value)?);
+            }
+            "--transfer-to" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CommandError::InvalidArguments(CLOSE_USAGE.into()))?;
+                transfer_to = Some(account_id_by_name(context, value)?);
+            }
+            _ if name.is_none() && !token.starts_with("--") => name = Some(*token),
+            _ => return Err(CommandError::InvalidArguments(CLOSE_USAGE.into())),
+        }
+    }
+    let account_id = match name {
+        Some(name) => account_id_by_name(context, name)?,
+        None if interactive => match context.select_account_id("Select an account to close:")? {
+            Some(id) => id,
+            None => return Ok(()),
+        },
+        None => return Err(CommandError::InvalidArguments(CLOSE_USAGE.into())),
+    };
+    let on = on.unwrap_or_else(|| context.clock.today());
+
+    let balance =
+        context.with_ledger(|ledger| Ok(AccountService::balance(ledger, account_id, on)?))?;
+    if transfer_to.is_none() && balance.balance.abs() >= 0.005 {
+        if !interactive {
+            return Err(CommandError::InvalidArguments(format!(
+                "account `{}` still holds {}; pass --transfer-to <account>",
+                balance.account_name,
+                context
+                    .formatters
+                    .format_amount(balance.balance, &balance.currency)
+            )));
+        }
+        io::print_info(format!(
+            "`{}` holds {} on {}.",
+            balance.account_name,
+            context
+                .formatters
+                .format_amount(balance.balance, &balance.currency),
+            context.formatters.format_date(on)
+        ));
+        match context.select_account_id("Transfer the remaining balance to:")? {
+            Some(id) => transfer_to = Some(id),
+            None => {
+                io::print_info("Operation cancelled.");
+                return Ok(());
+            }
+        }
+    }
+
+    let transfer = context.with_ledger_mut(|ledger| {
+        Ok(AccountService::close(ledger, account_id, on, transfer_to)?)
+    })?;
+    if transfer.is_some() {
+        io::print_info(format!(
+            "Transferred the remaining {}.",
+            context
+                .formatters
+                .format_amount(balance.balance.abs(), &balance.currency)
+        ));
+    }
+    io::print_success(format!(
+        "Account `{}` closed on {}.",
+        balance.account_name,
+        context.formatters.format_date(on)
+    ));
+    Ok(())
+}
+
+pub(crate) fn run_reopen(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(REOPEN_USAGE.into()));
+    };
+    let account_id = account_id_by_name(context, name)?;
+    context.with_ledger_mut(|ledger| Ok(AccountService::reopen(ledger, account_id)?))?;
+    io::print_success(format!("Account `{}` reopened.", name));
+    Ok(())
+}
+
+fn account_id_by_name(context: &ShellContext, name: &str) -> Result<Uuid, CommandError> {
+    context.with_ledger(|ledger| {
+        ledger
+            .accounts
+            .iter()
+            .find(|account| account.name.eq_ignore_ascii_case(name))
+            .map(|account| account.id)
+            .ok_or_else(|| CommandError::InvalidArguments(format!("unknown account `{}`", name)))
+    })
+}
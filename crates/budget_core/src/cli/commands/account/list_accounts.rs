@@ -2,16 +2,22 @@ use std::collections::HashMap;
 
 use uuid::Uuid;
 
+use crate::cli::commands::list::closed_marker;
+use crate::cli::commands::ordering::favorite_marker;
 use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::entity_ref::{short_id, EntityRef};
+use crate::cli::formatters::CliFormatters;
 use crate::cli::io as cli_io;
 use crate::cli::ui::detail_actions::DetailAction;
 use crate::cli::ui::detail_view::DetailView;
 use crate::cli::ui::run_selectable_table;
+use crate::cli::ui::style::style;
 use crate::cli::ui::table_renderer::{Alignment, Table, TableColumn};
 use crate::core::services::{AccountService, BudgetService};
-use crate::ledger::AccountKind;
+use crate::ledger::{Account, AccountKind, Appearance, Ledger, Money};
+use bufy_core::{AccountStats, CurrencyFormatter, TransactionHighlight, STATS_AVERAGE_MONTHS};
 
-pub fn run_list_accounts(context: &mut ShellContext) -> CommandResult {
+pub fn run_list_accounts(context: &mut ShellContext, include_closed: bool) -> CommandResult {
     {
         let manager = context.manager();
         if manager.current_handle().is_none() {
@@ -20,87 +26,153 @@ pub fn run_list_accounts(context: &mut ShellContext) -> CommandResult {
         }
     }
 
+    let formatters = context.formatters.clone();
     run_selectable_table(
         context,
         "account_selector",
         "account_actions",
         Some("No accounts in this ledger."),
-        |ctx| gather_entries(ctx),
+        |ctx| gather_entries(ctx, include_closed),
         build_table,
-        build_detail_view,
+        |entry| build_detail_view(entry, &formatters),
         |_| build_actions(),
         |ctx, entry, action| execute_action(ctx, entry, action.id.as_str()),
     )
 }
 
+/// `account show <name|index|id>`: the details and quick stats of one
+/// account.
+pub(crate) fn run_show_account(context: &mut ShellContext, raw: &str) -> CommandResult {
+    let by_name = context.with_ledger(|ledger| {
+        Ok(ledger
+            .accounts
+            .iter()
+            .position(|account| account.name.eq_ignore_ascii_case(raw.trim())))
+    })?;
+    let index = match by_name {
+        Some(index) => index,
+        None if EntityRef::parse(raw).is_some() => context.account_index_from_arg(raw)?,
+        None => {
+            return Err(CommandError::InvalidArguments(format!(
+                "unknown account `{}`",
+                raw
+            )))
+        }
+    };
+    let entry = context.with_ledger(|ledger| {
+        let account = &ledger.accounts[index];
+        let totals = period_totals(ledger, context);
+        let stats = AccountService::stats(ledger, account.id, context.clock.today())?;
+        Ok(build_entry(ledger, index, account, &totals, Some(stats)))
+    })?;
+    cli_io::println_text(&build_detail_view(&entry, &context.formatters).render())
+        .map_err(CommandError::from)
+}
+
 struct AccountEntry {
     index: usize,
     id: Uuid,
     name: String,
+    closed: String,
+    appearance: Appearance,
+    favorite: bool,
     kind: AccountKind,
     category: String,
     currency: Option<String>,
     opening_balance: Option<f64>,
+    /// Currency its amounts are counted in: its own, else the base currency.
+    holding_currency: String,
     notes: Option<String>,
     budgeted: f64,
     actual: f64,
     transaction_count: usize,
+    stats: Option<AccountStats>,
 }
 
-fn gather_entries(context: &ShellContext) -> Result<Vec<AccountEntry>, CommandError> {
+fn gather_entries(
+    context: &ShellContext,
+    include_closed: bool,
+) -> Result<Vec<AccountEntry>, CommandError> {
     context.with_ledger(|ledger| {
         if ledger.accounts.is_empty() {
             return Ok(Vec::new());
         }
-        let summary = BudgetService::summarize_current_period(ledger, context.clock.as_ref());
-        let totals: HashMap<Uuid, (f64, f64)> = summary
-            .per_account
-            .iter()
-            .map(|entry| (entry.account_id, (entry.totals.budgeted, entry.totals.real)))
-            .collect();
+        let totals = period_totals(ledger, context);
+        let mut stats = AccountService::stats_by_account(ledger, context.clock.today());
 
         let entries = ledger
             .accounts
             .iter()
             .enumerate()
+            .filter(|(_, account)| include_closed || !account.is_closed())
             .map(|(index, account)| {
-                let (budgeted, actual) = totals.get(&account.id).copied().unwrap_or((0.0, 0.0));
-                let category = account
-                    .category_id
-                    .and_then(|id| ledger.category(id))
-                    .map(|category| category.name.clone())
-                    .unwrap_or_else(|| "—".into());
-                let transaction_count = ledger
-                    .transactions
-                    .iter()
-                    .filter(|txn| txn.from_account == account.id || txn.to_account == account.id)
-                    .count();
-
-                AccountEntry {
-                    index,
-                    id: account.id,
-                    name: account.name.clone(),
-                    kind: account.kind.clone(),
-                    category,
-                    currency: account.currency.clone(),
-                    opening_balance: account.opening_balance,
-                    notes: account.notes.clone(),
-                    budgeted,
-                    actual,
-                    transaction_count,
-                }
+                build_entry(ledger, index, account, &totals, stats.remove(&account.id))
             })
             .collect();
         Ok(entries)
     })
 }
 
+/// Budgeted and actual totals of each account in the current period.
+fn period_totals(ledger: &Ledger, context: &ShellContext) -> HashMap<Uuid, (f64, f64)> {
+    BudgetService::summarize_current_period(ledger, context.clock.as_ref())
+        .per_account
+        .iter()
+        .map(|entry| (entry.account_id, (entry.totals.budgeted, entry.totals.real)))
+        .collect()
+}
+
+fn build_entry(
+    ledger: &Ledger,
+    index: usize,
+    account: &Account,
+    totals: &HashMap<Uuid, (f64, f64)>,
+    stats: Option<AccountStats>,
+) -> AccountEntry {
+    let (budgeted, actual) = totals.get(&account.id).copied().unwrap_or((0.0, 0.0));
+    let category = account
+        .category_id
+        .and_then(|id| ledger.category(id))
+        .map(|category| category.name.clone())
+        .unwrap_or_else(|| "—".into());
+    let transaction_count = ledger
+        .transactions
+        .iter()
+        .filter(|txn| txn.from_account == account.id || txn.to_account == account.id)
+        .count();
+
+    AccountEntry {
+        index,
+        id: account.id,
+        name: account.name.clone(),
+        closed: closed_marker(account),
+        appearance: account.appearance.clone(),
+        favorite: account.favorite,
+        kind: account.kind.clone(),
+        category,
+        currency: account.currency.clone(),
+        opening_balance: account.opening_balance.as_ref().map(Money::to_major),
+        holding_currency: ledger.holding_currency(account).as_str().to_string(),
+        notes: account.notes.clone(),
+        budgeted,
+        actual,
+        transaction_count,
+        stats,
+    }
+}
+
 fn build_table(entries: &[AccountEntry]) -> Table {
+    let style = style();
     let rows = entries
         .iter()
         .map(|entry| {
             vec![
-                entry.name.clone(),
+                format!(
+                    "{}{}{}",
+                    favorite_marker(entry.favorite),
+                    style.apply_entity_style(&entry.name, &entry.appearance),
+                    entry.closed
+                ),
                 entry.kind.to_string(),
                 entry.category.clone(),
                 format!("{:.2} / {:.2}", entry.budgeted, entry.actual),
@@ -141,13 +213,15 @@ fn build_table(entries: &[AccountEntry]) -> Table {
     }
 }
 
-fn build_detail_view(entry: &AccountEntry) -> DetailView {
+fn build_detail_view(entry: &AccountEntry, formatters: &CliFormatters) -> DetailView {
+    let amount = |value: f64| formatters.format_amount(value, &entry.holding_currency);
     let mut view = DetailView::new(format!("Account: {}", entry.name))
         .with_field("name", format!("\"{}\"", entry.name))
+        .with_field("id", short_id(entry.id))
         .with_field("type", entry.kind.to_string())
         .with_field("category", entry.category.clone())
-        .with_field("budgeted_total", format!("{:.2}", entry.budgeted))
-        .with_field("actual_total", format!("{:.2}", entry.actual))
+        .with_field("budgeted_total", amount(entry.budgeted))
+        .with_field("actual_total", amount(entry.actual))
         .with_field(
             "currency",
             entry.currency.clone().unwrap_or_else(|| "—".into()),
@@ -155,7 +229,34 @@ fn build_detail_view(entry: &AccountEntry) -> DetailView {
         .with_field("linked_transactions", entry.transaction_count.to_string());
 
     if let Some(balance) = entry.opening_balance {
-        view = view.with_field("opening_balance", format!("{:.2}", balance));
+        view = view.with_field("opening_balance", amount(balance));
+    }
+
+    if let Some(stats) = &entry.stats {
+        view = view
+            .with_field("balance", amount(stats.balance))
+            .with_field("net_current_period", amount(stats.period_net))
+            .with_field(
+                format!("monthly_average_{}m", STATS_AVERAGE_MONTHS),
+                amount(stats.monthly_average),
+            )
+            .with_field(
+                "largest_transaction",
+                stats
+                    .largest_transaction
+                    .as_ref()
+                    .map(|txn: &TransactionHighlight| {
+                        format!("{} on {}", amount(txn.amount), txn.date)
+                    })
+                    .unwrap_or_else(|| "—".into()),
+            )
+            .with_field(
+                "last_activity",
+                stats
+                    .last_activity
+                    .map(|date| date.to_string())
+                    .unwrap_or_else(|| "—".into()),
+            );
     }
 
     if let Some(notes) = entry
@@ -202,8 +303,8 @@ fn delete_account(context: &mut ShellContext, entry: &AccountEntry) -> CommandRe
         }
     }
 
-    context.with_ledger_mut(|ledger| {
-        AccountService::remove(ledger, entry.id).map_err(CommandError::from)
+    context.with_ledger_journaled(|ledger, journal| {
+        AccountService::remove_journaled(ledger, entry.id, journal).map_err(CommandError::from)
     })?;
     cli_io::print_success(format!("Account `{}` deleted.", entry.name));
     Ok(())
@@ -0,0 +1,301 @@
+//! `account reconcile <name> [<statement date> <closing balance>]`: tick off
+//! completed transactions against a bank statement until the balances agree.
+
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use uuid::Uuid;
+
+use crate::cli::core::{parse_date, CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::{ReconciliationReport, ReconciliationService};
+
+const RECONCILE_USAGE: &str =
+    "usage: account reconcile <name> [<statement date YYYY-MM-DD> <closing balance>]";
+
+const NEXT_STEPS: [&str; 3] = [
+    "Tick cleared transactions",
+    "Finish reconciliation",
+    "Leave the statement open",
+];
+
+pub(crate) fn run_reconcile(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let interactive = context.mode() == CliMode::Interactive;
+    let account_id = match args.first() {
+        Some(name) => account_id_by_name(context, name)?,
+        None if interactive => {
+            match context.select_account_id("Select an account to reconcile:")? {
+                Some(id) => id,
+                None => return Ok(()),
+            }
+        }
+        None => return Err(CommandError::InvalidArguments(RECONCILE_USAGE.into())),
+    };
+    let statement = match args.get(1..).unwrap_or_default() {
+        [date, balance] => Some((parse_date(date)?, parse_amount(balance)?)),
+        [] if interactive => match prompt_statement(context, account_id)? {
+            Some(statement) => Some(statement),
+            None => {
+                io::print_info("Operation cancelled.");
+                return Ok(());
+            }
+        },
+        [] => None,
+        _ => return Err(CommandError::InvalidArguments(RECONCILE_USAGE.into())),
+    };
+
+    if let Some((date, balance)) = statement {
+        let matched = context.with_ledger_mut(|ledger| {
+            ReconciliationService::start(ledger, account_id, date, balance)?;
+            let resumed = ReconciliationService::open_statement(ledger, account_id)?
+                .is_some_and(|open| !open.cleared.is_empty());
+            if resumed {
+                return Ok(None);
+            }
+            Ok(Some(ReconciliationService::match_completed(
+                ledger, account_id,
+            )?))
+        })?;
+        if let Some(matched) = matched {
+            io::print_info(format!(
+                "Matched {} completed transaction(s) up to {}.",
+                matched,
+                context.formatters.format_date(date)
+            ));
+        }
+    }
+
+    if interactive {
+        review(context, account_id)
+    } else {
+        let report = render_report(context, account_id)?;
+        if report.reconciled {
+            return Ok(());
+        }
+        finish(context, account_id)
+    }
+}
+
+/// Lets the user untick what the bank has not seen yet, then finish.
+fn review(context: &mut ShellContext, account_id: Uuid) -> CommandResult {
+    loop {
+        let report = render_report(context, account_id)?;
+        if report.reconciled {
+            return Ok(());
+        }
+        match io::prompt_select_index("Next step", &NEXT_STEPS)? {
+            0 => tick_cleared(context, account_id, &report)?,
+            1 => match finish(context, account_id) {
+                Ok(()) => return Ok(()),
+                Err(err) => io::print_warning(err),
+            },
+            _ => {
+                io::print_info("Statement left open; run `account reconcile` again to continue.");
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn tick_cleared(
+    context: &mut ShellContext,
+    account_id: Uuid,
+    report: &ReconciliationReport,
+) -> CommandResult {
+    let lines: Vec<_> = report.cleared.iter().chain(&report.uncleared).collect();
+    if lines.is_empty() {
+        io::print_info("No completed transactions to tick.");
+        return Ok(());
+    }
+    let checked: Vec<bool> = (0..lines.len()).map(|i| i < report.cleared.len()).collect();
+    let labels = context.with_ledger(|ledger| {
+        Ok(lines
+            .iter()
+            .map(|line| {
+                let txn = ledger.transaction(line.transaction_id);
+                format!(
+                    "{}  {}  {}  {}",
+                    txn.and_then(|txn| txn.short_ref())
+                        .map(|code| code.to_string())
+                        .unwrap_or_default(),
+                    context.formatters.format_date(line.date),
+                    describe(ledger, account_id, line.transaction_id),
+                    context
+                        .formatters
+                        .format_amount(line.amount, ledger.base_currency().as_str())
+                )
+            })
+            .collect::<Vec<_>>())
+    })?;
+    let Some(ticked) = io::prompt_multi_select("Cleared on the statement", &labels, &checked)?
+    else {
+        return Ok(());
+    };
+    context.with_ledger_mut(|ledger| {
+        for (index, line) in lines.iter().enumerate() {
+            let cleared = ticked.contains(&index);
+            if cleared != checked[index] {
+                ReconciliationService::set_cleared(
+                    ledger,
+                    account_id,
+                    line.transaction_id,
+                    cleared,
+                )?;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn finish(context: &mut ShellContext, account_id: Uuid) -> CommandResult {
+    let clock = context.clock.clone();
+    let result = context.with_ledger_mut(|ledger| {
+        ReconciliationService::finish(ledger, account_id, clock.as_ref())
+            .map_err(CommandError::from)
+    });
+    match result {
+        Ok(report) => {
+            io::print_success(format!(
+                "Statement of {} reconciled with {} cleared transaction(s).",
+                context.formatters.format_date(report.statement_date),
+                report.cleared.len()
+            ));
+            Ok(())
+        }
+        Err(err) if context.mode() == CliMode::Interactive => Err(err),
+        Err(err) => {
+            io::print_warning(format!("{}; the statement stays open.", err));
+            Ok(())
+        }
+    }
+}
+
+fn render_report(
+    context: &ShellContext,
+    account_id: Uuid,
+) -> Result<ReconciliationReport, CommandError> {
+    let formatters = context.formatters.clone();
+    let style = context.ui_style.clone();
+    context.with_ledger(|ledger| {
+        let report =
+            ReconciliationService::report(ledger, account_id).map_err(CommandError::from)?;
+        let account = ledger
+            .account(account_id)
+            .map(|account| account.name.clone())
+            .unwrap_or_default();
+        let currency = ledger.base_currency().as_str();
+        let amount = |value: f64| formatters.format_amount(value, currency);
+        let mut table = Table::new(
+            Some(format!(
+                "Reconciling {} to the statement of {}",
+                account,
+                formatters.format_date(report.statement_date)
+            )),
+            vec![
+                TableColumn::new("", 2),
+                TableColumn::new("REF", 8),
+                TableColumn::new("DATE", 12),
+                TableColumn::new("DESCRIPTION", 24),
+                TableColumn::new("AMOUNT", 14),
+            ],
+        );
+        let rows = report
+            .cleared
+            .iter()
+            .map(|line| ("✔", line))
+            .chain(report.uncleared.iter().map(|line| ("", line)));
+        for (mark, line) in rows {
+            table.add_row(vec![
+                mark.to_string(),
+                ledger
+                    .transaction(line.transaction_id)
+                    .and_then(|txn| txn.short_ref())
+                    .map(|code| code.to_string())
+                    .unwrap_or_default(),
+                formatters.format_date(line.date),
+                describe(ledger, account_id, line.transaction_id),
+                amount(line.amount),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        io::print_info(format!(
+            "Opening balance {} | Cleared {} | Uncleared {}",
+            amount(report.opening_balance),
+            amount(report.cleared_total),
+            amount(report.uncleared_total)
+        ));
+        io::print_info(format!(
+            "Cleared balance {} | Statement balance {} | Difference {}",
+            amount(report.cleared_balance()),
+            amount(report.closing_balance),
+            amount(report.difference())
+        ));
+        if report.reconciled {
+            io::print_info("This statement is already reconciled.");
+        }
+        Ok(report)
+    })
+}
+
+/// Asks for the statement date and closing balance, offering an open
+/// statement's values as defaults.
+fn prompt_statement(
+    context: &ShellContext,
+    account_id: Uuid,
+) -> Result<Option<(chrono::NaiveDate, f64)>, CommandError> {
+    let open = context.with_ledger(|ledger| {
+        Ok(ReconciliationService::open_statement(ledger, account_id)?
+            .map(|open| (open.statement_date, open.closing_balance)))
+    })?;
+    let default_date = open
+        .map(|(date, _)| date)
+        .unwrap_or_else(|| context.clock.today())
+        .format("%Y-%m-%d")
+        .to_string();
+    let Some(date) = io::prompt_text("Statement date (YYYY-MM-DD)", Some(&default_date))? else {
+        return Ok(None);
+    };
+    let default_balance = open.map(|(_, balance)| format!("{:.2}", balance));
+    let Some(balance) = io::prompt_text("Closing balance", default_balance.as_deref())? else {
+        return Ok(None);
+    };
+    Ok(Some((
+        parse_date(date.trim())?,
+        parse_amount(balance.trim())?,
+    )))
+}
+
+fn account_id_by_name(context: &ShellContext, name: &str) -> Result<Uuid, CommandError> {
+    context.with_ledger(|ledger| {
+        ledger
+            .accounts
+            .iter()
+            .find(|account| account.name.eq_ignore_ascii_case(name))
+            .map(|account| account.id)
+            .ok_or_else(|| CommandError::InvalidArguments(format!("unknown account `{}`", name)))
+    })
+}
+
+/// Counterparty name, with notes when the transaction has them.
+fn describe(ledger: &crate::ledger::Ledger, account_id: Uuid, transaction_id: Uuid) -> String {
+    let Some(txn) = ledger.transaction(transaction_id) else {
+        return String::new();
+    };
+    let counterparty = if txn.to_account == account_id {
+        txn.from_account
+    } else {
+        txn.to_account
+    };
+    let name = ledger
+        .account(counterparty)
+        .map(|account| account.name.clone())
+        .unwrap_or_default();
+    match &txn.notes {
+        Some(notes) => format!("{} ({})", name, notes),
+        None => name,
+    }
+}
+
+fn parse_amount(value: &str) -> Result<f64, CommandError> {
+    io::parse_number(value)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("invalid amount `{}`", value)))
+}
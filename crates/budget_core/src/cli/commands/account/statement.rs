@@ -0,0 +1,151 @@
+//! `account statement <name> [period] [--format text|csv|html] [--output path]`:
+//! opening balance, every completed movement, and closing balance for one
+//! account over a budget window.
+
+use std::fs;
+use std::path::PathBuf;
+
+use bufy_core::{CurrencyFormatter, DateFormatter};
+
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io as cli_io;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::{AccountService, ExportService};
+
+const STATEMENT_USAGE: &str =
+    "usage: account statement <name> [current|past [n]|future [n]|custom <start> <end>] [--format text|csv|html] [--output path]";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StatementFormat {
+    Text,
+    Csv,
+    Html,
+}
+
+pub(crate) fn run_statement(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((name, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(STATEMENT_USAGE.into()));
+    };
+    let mut period = Vec::new();
+    let mut format = None;
+    let mut output = None;
+    let mut iter = rest.iter();
+    while let Some(token) = iter.next() {
+        match token.to_ascii_lowercase().as_str() {
+            "--format" => {
+                format = Some(match iter.next().map(|value| value.to_ascii_lowercase()) {
+                    Some(value) if value == "text" => StatementFormat::Text,
+                    Some(value) if value == "csv" => StatementFormat::Csv,
+                    Some(value) if value == "html" => StatementFormat::Html,
+                    _ => return Err(CommandError::InvalidArguments(STATEMENT_USAGE.into())),
+                })
+            }
+            "--output" => {
+                output = Some(PathBuf::from(iter.next().ok_or_else(|| {
+                    CommandError::InvalidArguments("--output requires a path".into())
+                })?))
+            }
+            _ => period.push(*token),
+        }
+    }
+    let format = format.unwrap_or_else(|| {
+        match output
+            .as_ref()
+            .and_then(|path| path.extension())
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("csv") => StatementFormat::Csv,
+            Some("html" | "htm") => StatementFormat::Html,
+            _ => StatementFormat::Text,
+        }
+    });
+
+    let today = context.clock.today();
+    let formatters = context.formatters.clone();
+    let statement = context.with_ledger(|ledger| {
+        let account = ledger
+            .accounts
+            .iter()
+            .find(|account| account.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| CommandError::InvalidArguments(format!("unknown account `{}`", name)))?;
+        let (window, _) = context.resolve_summary_window(ledger, &period, today)?;
+        AccountService::statement(ledger, account.id, window).map_err(CommandError::from)
+    })?;
+
+    let rendered = match format {
+        StatementFormat::Csv => ExportService::statement_csv(&statement),
+        StatementFormat::Html => ExportService::statement_html(&statement, &formatters),
+        StatementFormat::Text if output.is_some() => {
+            ExportService::statement_text(&statement, &formatters)
+        }
+        StatementFormat::Text => {
+            let style = context.ui_style.clone();
+            let amount = |value: f64| formatters.format_amount(value, &statement.currency);
+            let mut table = Table::new(
+                Some(format!(
+                    "Statement for {} ({} to {})",
+                    statement.account_name,
+                    formatters.format_date(statement.window.start),
+                    formatters.format_date(
+                        statement
+                            .window
+                            .end
+                            .pred_opt()
+                            .unwrap_or(statement.window.end)
+                    )
+                )),
+                vec![
+                    TableColumn::new("DATE", 12),
+                    TableColumn::new("DESCRIPTION", 24),
+                    TableColumn::new("CATEGORY", 14),
+                    TableColumn::new("AMOUNT", 14),
+                    TableColumn::new("BALANCE", 14),
+                ],
+            );
+            table.add_row(vec![
+                formatters.format_date(statement.window.start),
+                "Opening balance".into(),
+                String::new(),
+                String::new(),
+                amount(statement.opening_balance),
+            ]);
+            for line in &statement.lines {
+                table.add_row(vec![
+                    formatters.format_date(line.date),
+                    match &line.notes {
+                        Some(notes) => format!("{} ({})", line.counterparty, notes),
+                        None => line.counterparty.clone(),
+                    },
+                    line.category.clone().unwrap_or_default(),
+                    amount(line.amount),
+                    amount(line.balance),
+                ]);
+            }
+            table.add_row(vec![
+                String::new(),
+                "Closing balance".into(),
+                String::new(),
+                String::new(),
+                amount(statement.closing_balance),
+            ]);
+            TableRenderer::render(&table, &style);
+            return Ok(());
+        }
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(&path, rendered)?;
+            cli_io::print_success(format!(
+                "Statement for {} with {} movement(s) written to {}.",
+                statement.account_name,
+                statement.lines.len(),
+                path.display()
+            ));
+        }
+        None => cli_io::println_text(rendered.trim_end())?,
+    }
+    Ok(())
+}
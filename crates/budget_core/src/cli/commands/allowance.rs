@@ -0,0 +1,246 @@
+//! Simplified commands for allowance sub-ledgers.
+
+use crate::cli::core::{parse_date, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::SubLedgerService;
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use bufy_domain::sub_ledger::SubLedger;
+
+const ALLOWANCE_USAGE: &str = "usage: allowance <new|deposit|spend|list|show|remove>";
+/// Number of most recent entries listed by `allowance show`.
+const RECENT_ENTRIES: usize = 10;
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "allowance",
+        "Track allowances in sub-ledgers kept apart from the main budget",
+        "allowance <new <name> [--member M] [--opening N]|deposit|spend <name> <amount> [note] [--date D]|list|show <name>|remove <name>>",
+        cmd_allowance,
+    )]
+}
+
+fn cmd_allowance(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((subcommand, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(ALLOWANCE_USAGE.into()));
+    };
+    match subcommand.to_ascii_lowercase().as_str() {
+        "new" | "add" => handle_new(context, rest),
+        "deposit" => handle_entry(context, rest, EntryKind::Deposit),
+        "spend" => handle_entry(context, rest, EntryKind::Spend),
+        "list" | "ls" => handle_list(context),
+        "show" => handle_show(context, rest),
+        "remove" | "delete" => handle_remove(context, rest),
+        other => Err(CommandError::InvalidArguments(format!(
+            "unknown allowance subcommand `{}`. Available: new, deposit, spend, list, show, remove",
+            other
+        ))),
+    }
+}
+
+fn handle_new(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let usage = "usage: allowance new <name> [--member M] [--opening N]";
+    let Some((name, flags)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(usage.into()));
+    };
+    let mut member = None;
+    let mut opening = 0.0;
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        let value = iter
+            .next()
+            .ok_or_else(|| CommandError::InvalidArguments(format!("{} requires a value", flag)))?;
+        match flag.to_ascii_lowercase().as_str() {
+            "--member" => member = Some(*value),
+            "--opening" => opening = parse_amount(value)?,
+            _ => return Err(CommandError::InvalidArguments(usage.into())),
+        }
+    }
+    context.with_ledger_mut(|ledger| {
+        let member_id = match member {
+            Some(name) => Some(
+                ledger
+                    .member_by_name(name)
+                    .map(|member| member.id)
+                    .ok_or_else(|| {
+                        CommandError::InvalidArguments(format!("unknown member `{}`", name))
+                    })?,
+            ),
+            None => None,
+        };
+        SubLedgerService::create(ledger, name, member_id, opening, context.clock.as_ref())
+            .map_err(CommandError::from)
+    })?;
+    io::print_success(format!("Allowance `{}` created.", name.trim()));
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum EntryKind {
+    Deposit,
+    Spend,
+}
+
+fn handle_entry(context: &mut ShellContext, args: &[&str], kind: EntryKind) -> CommandResult {
+    let verb = match kind {
+        EntryKind::Deposit => "deposit",
+        EntryKind::Spend => "spend",
+    };
+    let [name, amount, note @ ..] = args else {
+        return Err(CommandError::InvalidArguments(format!(
+            "usage: allowance {} <name> <amount> [note] [--date YYYY-MM-DD]",
+            verb
+        )));
+    };
+    let amount = parse_amount(amount)?;
+    let mut date = context.clock.today();
+    let mut words = Vec::new();
+    let mut iter = note.iter();
+    while let Some(word) = iter.next() {
+        if word.eq_ignore_ascii_case("--date") {
+            let value = iter
+                .next()
+                .ok_or_else(|| CommandError::InvalidArguments("--date requires a value".into()))?;
+            date = parse_date(value)?;
+        } else {
+            words.push(*word);
+        }
+    }
+    let notes = (!words.is_empty()).then(|| words.join(" "));
+    let formatters = context.formatters.clone();
+    let (display_name, balance) = context.with_ledger_mut(|ledger| {
+        match kind {
+            EntryKind::Deposit => SubLedgerService::deposit(ledger, name, amount, date, notes),
+            EntryKind::Spend => SubLedgerService::spend(ledger, name, amount, date, notes),
+        }
+        .map_err(CommandError::from)?;
+        let summary = SubLedgerService::summary(ledger, name, context.clock.today())
+            .map_err(CommandError::from)?;
+        let balance = formatters.format_amount(summary.balance, ledger.base_currency().as_str());
+        Ok((summary.name, balance))
+    })?;
+    io::print_success(format!(
+        "Recorded {} for `{}`. Balance: {}",
+        verb, display_name, balance
+    ));
+    Ok(())
+}
+
+fn handle_list(context: &mut ShellContext) -> CommandResult {
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    let today = context.clock.today();
+    context.with_ledger(|ledger| {
+        if ledger.sub_ledgers.is_empty() {
+            io::print_warning("No allowances yet. Create one with `allowance new <name>`.");
+            return Ok(());
+        }
+        let currency = ledger.base_currency().as_str();
+        let mut table = Table::new(
+            Some("Allowances"),
+            vec![
+                TableColumn::new("NAME", 20),
+                TableColumn::new("MEMBER", 16),
+                TableColumn::new("BALANCE", 14),
+                TableColumn::new("ENTRIES", 8),
+            ],
+        );
+        for sub in &ledger.sub_ledgers {
+            let summary =
+                SubLedgerService::summary(ledger, &sub.name, today).map_err(CommandError::from)?;
+            let member = sub
+                .member_id
+                .and_then(|id| ledger.member(id))
+                .map(|member| member.name.clone())
+                .unwrap_or_else(|| "—".into());
+            table.add_row(vec![
+                sub.name.clone(),
+                member,
+                formatters.format_amount(summary.balance, currency),
+                summary.transaction_count.to_string(),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_show(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: allowance show <name>".into(),
+        ));
+    };
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    let today = context.clock.today();
+    context.with_ledger(|ledger| {
+        let summary = SubLedgerService::summary(ledger, name, today).map_err(CommandError::from)?;
+        let sub = ledger
+            .sub_ledger(name)
+            .expect("sub-ledger exists after summary");
+        let currency = ledger.base_currency().as_str();
+        io::print_info(format!("Allowance: {}", summary.name));
+        io::print_info(format!(
+            "Balance: {}",
+            formatters.format_amount(summary.balance, currency)
+        ));
+        io::print_info(format!(
+            "This period: {} deposited / {} spent",
+            formatters.format_amount(summary.period_deposits, currency),
+            formatters.format_amount(summary.period_spending, currency)
+        ));
+        if sub.transactions.is_empty() {
+            io::print_info("No entries yet.");
+            return Ok(());
+        }
+        let wallet = sub
+            .account_by_name(SubLedger::WALLET)
+            .map(|account| account.id);
+        let mut table = Table::new(
+            Some("Recent entries"),
+            vec![
+                TableColumn::new("DATE", 12),
+                TableColumn::new("TYPE", 8),
+                TableColumn::new("AMOUNT", 12),
+                TableColumn::new("NOTE", 28),
+            ],
+        );
+        let mut entries: Vec<_> = sub.transactions.iter().collect();
+        entries.sort_by_key(|txn| std::cmp::Reverse(txn.scheduled_date));
+        for txn in entries.into_iter().take(RECENT_ENTRIES) {
+            let kind = if Some(txn.to_account) == wallet {
+                "Deposit"
+            } else {
+                "Spend"
+            };
+            table.add_row(vec![
+                formatters.format_date(txn.scheduled_date),
+                kind.into(),
+                formatters.format_amount(txn.budgeted_amount.to_major(), currency),
+                txn.notes.clone().unwrap_or_default(),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_remove(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: allowance remove <name>".into(),
+        ));
+    };
+    let removed = context.with_ledger_mut(|ledger| {
+        SubLedgerService::remove(ledger, name).map_err(CommandError::from)
+    })?;
+    io::print_success(format!("Allowance `{}` removed.", removed.name));
+    Ok(())
+}
+
+fn parse_amount(value: &str) -> Result<f64, CommandError> {
+    io::parse_number(value)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("invalid amount `{}`", value)))
+}
@@ -0,0 +1,187 @@
+//! `assert` command for scripted checks against the loaded ledger.
+//!
+//! Each failed assertion is reported and counted; script mode exits with a
+//! non-zero status once input ends if any assertion failed.
+
+use bufy_core::CurrencyFormatter;
+use bufy_domain::ledger::CategoryBudgetSummary;
+
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::core::services::{AccountService, AlertService, SubLedgerService, SummaryService};
+
+const ASSERT_USAGE: &str = "usage: assert <balance <account>|category-budget <category> <remaining|spent|budget>|net-worth|alerts> <op> <value>";
+/// Amounts closer than this compare equal.
+const TOLERANCE: f64 = 1e-9;
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "assert",
+        "Check a ledger condition; failures make script mode exit non-zero",
+        "assert <balance <account>|category-budget <category> <remaining|spent|budget>|net-worth|alerts> <>=|>|<=|<|==|!=> <value>",
+        cmd_assert,
+    )]
+}
+
+#[derive(Clone, Copy)]
+enum Comparison {
+    Ge,
+    Gt,
+    Le,
+    Lt,
+    Eq,
+    Ne,
+}
+
+impl Comparison {
+    fn parse(token: &str) -> Option<Self> {
+        match token {
+            ">=" => Some(Self::Ge),
+            ">" => Some(Self::Gt),
+            "<=" => Some(Self::Le),
+            "<" => Some(Self::Lt),
+            "==" | "=" => Some(Self::Eq),
+            "!=" => Some(Self::Ne),
+            _ => None,
+        }
+    }
+
+    fn holds(self, actual: f64, expected: f64) -> bool {
+        let equal = (actual - expected).abs() < TOLERANCE;
+        match self {
+            Self::Ge => actual > expected || equal,
+            Self::Gt => actual > expected && !equal,
+            Self::Le => actual < expected || equal,
+            Self::Lt => actual < expected && !equal,
+            Self::Eq => equal,
+            Self::Ne => !equal,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Self::Ge => ">=",
+            Self::Gt => ">",
+            Self::Le => "<=",
+            Self::Lt => "<",
+            Self::Eq => "==",
+            Self::Ne => "!=",
+        }
+    }
+}
+
+/// Quantity checked by an assertion and whether it is a money amount.
+struct Measure {
+    label: String,
+    value: f64,
+    is_amount: bool,
+}
+
+fn cmd_assert(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let (subject, condition) = match args {
+        [subject, rest @ ..] if rest.len() >= 2 => (*subject, rest),
+        _ => return Err(CommandError::InvalidArguments(ASSERT_USAGE.into())),
+    };
+    let [target @ .., op, expected] = condition else {
+        return Err(CommandError::InvalidArguments(ASSERT_USAGE.into()));
+    };
+    let comparison = Comparison::parse(op).ok_or_else(|| {
+        CommandError::InvalidArguments(format!(
+            "unknown comparison `{}` (use >=, >, <=, <, ==, !=)",
+            op
+        ))
+    })?;
+    let expected: f64 = io::parse_number(expected)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("invalid number `{}`", expected)))?;
+
+    let today = context.clock.today();
+    let formatters = context.formatters.clone();
+    let (measure, currency) = context.with_ledger(|ledger| {
+        let measure = match (subject.to_ascii_lowercase().as_str(), target) {
+            ("balance", [account]) => {
+                let found = ledger
+                    .accounts
+                    .iter()
+                    .find(|candidate| candidate.name.eq_ignore_ascii_case(account))
+                    .ok_or_else(|| {
+                        CommandError::InvalidArguments(format!("unknown account `{}`", account))
+                    })?;
+                let stats =
+                    AccountService::stats(ledger, found.id, today).map_err(CommandError::from)?;
+                Measure {
+                    label: format!("{} balance", found.name),
+                    value: stats.balance,
+                    is_amount: true,
+                }
+            }
+            ("category-budget", [category, field]) => {
+                let window = ledger.budget_window_containing(today);
+                let scope = window.scope(today);
+                let summary = SummaryService::category_budget_summaries(ledger, window, scope)
+                    .into_iter()
+                    .find(|summary| summary.name.eq_ignore_ascii_case(category))
+                    .ok_or_else(|| {
+                        CommandError::InvalidArguments(format!(
+                            "category `{}` has no budget this period",
+                            category
+                        ))
+                    })?;
+                budget_measure(&summary, field)?
+            }
+            ("net-worth", []) => Measure {
+                label: "Net worth".into(),
+                value: SubLedgerService::net_worth(ledger, today).total,
+                is_amount: true,
+            },
+            ("alerts", []) => Measure {
+                label: "Active alerts".into(),
+                value: AlertService::evaluate(ledger, today).len() as f64,
+                is_amount: false,
+            },
+            _ => return Err(CommandError::InvalidArguments(ASSERT_USAGE.into())),
+        };
+        Ok((measure, ledger.base_currency().as_str().to_string()))
+    })?;
+
+    let render = |value: f64| {
+        if measure.is_amount {
+            formatters.format_amount(value, &currency)
+        } else {
+            format!("{}", value)
+        }
+    };
+    let statement = format!(
+        "{} is {} ({} {})",
+        measure.label,
+        render(measure.value),
+        comparison.symbol(),
+        render(expected)
+    );
+    if comparison.holds(measure.value, expected) {
+        io::print_success(format!("Assertion passed: {}", statement));
+        Ok(())
+    } else {
+        context.failed_assertions += 1;
+        Err(CommandError::AssertionFailed(statement))
+    }
+}
+
+fn budget_measure(summary: &CategoryBudgetSummary, field: &str) -> Result<Measure, CommandError> {
+    let (label, value) = match field.to_ascii_lowercase().as_str() {
+        "remaining" => ("remaining", summary.remaining_amount),
+        "spent" => ("spent", summary.spent_amount),
+        "budget" => ("budget", summary.budget_amount),
+        other => {
+            return Err(CommandError::InvalidArguments(format!(
+                "unknown budget field `{}` (use remaining, spent, or budget)",
+                other
+            )))
+        }
+    };
+    Ok(Measure {
+        label: format!("{} {}", summary.name, label),
+        value,
+        is_amount: true,
+    })
+}
@@ -0,0 +1,175 @@
+//! `calendar <show|import|refresh|clear|countries>`: the public holidays
+//! that business-day calculations skip, from a built-in country table or an
+//! iCalendar file.
+
+use std::{fs, path::Path};
+
+use chrono::Datelike;
+
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::HolidayService;
+use bufy_core::{DateFormatter, HolidayImport};
+use bufy_domain::{HolidayCountry, HolidaySource};
+
+const CALENDAR_USAGE: &str =
+    "usage: calendar [show [year]|import <country|file.ics>|refresh|clear|countries]";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "calendar",
+        "Import public holidays that business-day calculations skip",
+        "calendar [show [year]|import <country|file.ics>|refresh|clear|countries]",
+        cmd_calendar,
+    )]
+}
+
+fn cmd_calendar(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    match args {
+        [] => handle_show(context, None),
+        [action] if action.eq_ignore_ascii_case("show") => handle_show(context, None),
+        [action, year] if action.eq_ignore_ascii_case("show") => {
+            let year = year
+                .parse()
+                .map_err(|_| CommandError::InvalidArguments(format!("invalid year `{}`", year)))?;
+            handle_show(context, Some(year))
+        }
+        [action, source] if action.eq_ignore_ascii_case("import") => handle_import(context, source),
+        [action] if action.eq_ignore_ascii_case("refresh") => handle_refresh(context),
+        [action] if action.eq_ignore_ascii_case("clear") => handle_clear(context),
+        [action] if action.eq_ignore_ascii_case("countries") => {
+            handle_countries(context);
+            Ok(())
+        }
+        _ => Err(CommandError::InvalidArguments(CALENDAR_USAGE.into())),
+    }
+}
+
+fn handle_show(context: &mut ShellContext, year: Option<i32>) -> CommandResult {
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    let today = context.clock.today();
+    let year = year.unwrap_or(today.year());
+    context.with_ledger(|ledger| {
+        let calendar = &ledger.holidays;
+        let Some(source) = &calendar.source else {
+            io::print_warning(
+                "No holiday calendar yet. Import one with `calendar import <country|file.ics>`; `calendar countries` lists the built-in tables.",
+            );
+            return Ok(());
+        };
+        let holidays: Vec<_> = calendar.in_year(year).collect();
+        if holidays.is_empty() {
+            io::print_info(format!("No holidays in {}.", year));
+        } else {
+            let mut table = Table::new(
+                Some(format!("Holidays in {} — {}", year, source.label())),
+                vec![
+                    TableColumn::new("DATE", 12),
+                    TableColumn::new("DAY", 10),
+                    TableColumn::new("NAME", 36),
+                ],
+            );
+            for holiday in holidays {
+                table.add_row(vec![
+                    formatters.format_date(holiday.date),
+                    holiday.date.weekday().to_string(),
+                    holiday.name.clone(),
+                ]);
+            }
+            TableRenderer::render(&table, &style);
+        }
+        if calendar.needs_refresh(today) {
+            io::print_warning(format!(
+                "The calendar ends with {}; run `calendar refresh` to add next year's holidays.",
+                calendar
+                    .through_year
+                    .map_or_else(|| "no year".to_string(), |year| year.to_string())
+            ));
+        }
+        Ok(())
+    })
+}
+
+fn handle_import(context: &mut ShellContext, source: &str) -> CommandResult {
+    let today = context.clock.today();
+    let path = Path::new(source);
+    let report = if path.is_file() {
+        let contents = read_calendar_file(path)?;
+        let stored = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        context.with_ledger_mut(|ledger| {
+            HolidayService::import_ics(ledger, &stored.to_string_lossy(), &contents, today)
+                .map_err(CommandError::from)
+        })?
+    } else if HolidayCountry::lookup(source).is_some() {
+        context.with_ledger_mut(|ledger| {
+            HolidayService::import_country(ledger, source, today).map_err(CommandError::from)
+        })?
+    } else {
+        return Err(CommandError::InvalidArguments(format!(
+            "`{}` is neither a file nor a built-in country; see `calendar countries`",
+            source
+        )));
+    };
+    print_report("Imported", &report);
+    Ok(())
+}
+
+fn handle_refresh(context: &mut ShellContext) -> CommandResult {
+    let today = context.clock.today();
+    let source = context.with_ledger(|ledger| Ok(ledger.holidays.source.clone()))?;
+    let contents = match &source {
+        Some(HolidaySource::File(path)) => Some(read_calendar_file(Path::new(path))?),
+        _ => None,
+    };
+    let report = context.with_ledger_mut(|ledger| {
+        HolidayService::refresh(ledger, contents.as_deref(), today).map_err(CommandError::from)
+    })?;
+    print_report("Refreshed", &report);
+    Ok(())
+}
+
+fn handle_clear(context: &mut ShellContext) -> CommandResult {
+    let cleared = context.with_ledger_mut(|ledger| Ok(HolidayService::clear(ledger)))?;
+    if cleared {
+        io::print_success("Holiday calendar removed; only weekends are skipped now.");
+    } else {
+        io::print_info("No holiday calendar to remove.");
+    }
+    Ok(())
+}
+
+fn handle_countries(context: &ShellContext) {
+    let mut table = Table::new(
+        Some("Built-in holiday calendars"),
+        vec![TableColumn::new("CODE", 6), TableColumn::new("COUNTRY", 36)],
+    );
+    for country in HolidayCountry::all() {
+        table.add_row(vec![country.code.to_string(), country.name.to_string()]);
+    }
+    TableRenderer::render(&table, &context.ui_style);
+}
+
+fn read_calendar_file(path: &Path) -> Result<String, CommandError> {
+    fs::read_to_string(path).map_err(|err| {
+        CommandError::InvalidArguments(format!("cannot read {}: {}", path.display(), err))
+    })
+}
+
+fn print_report(verb: &str, report: &HolidayImport) {
+    io::print_success(format!(
+        "{} {} holiday(s) from {}, through {}.",
+        verb,
+        report.holidays,
+        report.source.label(),
+        report.through_year
+    ));
+    if report.skipped > 0 {
+        io::print_info(format!(
+            "Skipped {} event(s) that do not repeat yearly or have no start date.",
+            report.skipped
+        ));
+    }
+}
@@ -1,6 +1,9 @@
+mod allocate;
 pub mod list_categories;
 
+use crate::cli::commands::ordering::{self, Ordered};
 use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::entity_ref::expand_id_flags;
 use crate::cli::io;
 use crate::cli::menus::{category_menu, menu_error_to_command_error};
 use crate::cli::registry::CommandEntry;
@@ -9,19 +12,22 @@ pub(crate) fn definitions() -> Vec<CommandEntry> {
     vec![CommandEntry::new(
         "category",
         "Manage categories and budgets",
-        "category <add|edit|list|remove|show|budget>",
+        "category <add|edit|list [--all]|move|remove|show [--all]|star|style|unstar|budget|group>",
         cmd_category,
     )]
 }
 
 fn cmd_category(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let expanded = expand_id_flags(args);
+    let args: &[&str] = &expanded.iter().map(|arg| arg.as_ref()).collect::<Vec<_>>();
     if context.mode() == CliMode::Interactive && args.is_empty() {
         return run_category_menu(context);
     }
 
     if args.is_empty() {
         return Err(CommandError::InvalidArguments(
-            "usage: category <add|edit|list|remove|show|budget>".into(),
+            "usage: category <add|edit|list|move|remove|show|star|style|unstar|budget|group>"
+                .into(),
         ));
     }
 
@@ -44,10 +50,15 @@ fn dispatch_category_action(
     match action.to_lowercase().as_str() {
         "add" => handle_add(context, args),
         "edit" => handle_edit(context, args),
-        "list" => handle_list(context),
-        "show" => handle_show(context),
+        "list" => handle_list(context, args),
+        "show" => handle_show(context, args),
         "remove" => handle_remove(context),
         "budget" => handle_budget(context, args),
+        "group" => handle_group(context, args),
+        "move" => ordering::run_move(context, Ordered::Category, args),
+        "star" => ordering::run_star(context, Ordered::Category, args, true),
+        "unstar" => ordering::run_star(context, Ordered::Category, args, false),
+        "style" => ordering::run_style(context, Ordered::Category, args),
         other => Err(CommandError::InvalidArguments(format!(
             "unknown category subcommand `{}`",
             other
@@ -70,9 +81,7 @@ fn handle_edit(context: &mut ShellContext, args: &[&str]) -> CommandResult {
         ));
     }
     let index = if let Some(value) = args.first() {
-        value
-            .parse::<usize>()
-            .map_err(|_| CommandError::InvalidArguments("category index must be numeric".into()))?
+        context.category_index_from_arg(value)?
     } else {
         match context.select_category_index("Select a category to edit:")? {
             Some(index) => index,
@@ -82,12 +91,30 @@ fn handle_edit(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     context.run_category_edit_wizard(index)
 }
 
-fn handle_list(context: &mut ShellContext) -> CommandResult {
-    list_categories::run_list_categories(context)
+fn handle_list(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    list_categories::run_list_categories_with_hidden(context, show_all_flag(args)?)
 }
 
-fn handle_show(context: &mut ShellContext) -> CommandResult {
-    list_categories::run_list_categories(context)
+/// `category show <name|index|id>` shows one category; without one it lists
+/// them like `category list`.
+fn handle_show(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    match args {
+        [target] if !target.starts_with("--") => {
+            list_categories::run_show_category(context, target)
+        }
+        _ => list_categories::run_list_categories_with_hidden(context, show_all_flag(args)?),
+    }
+}
+
+/// `--all` includes hidden system categories in listings.
+fn show_all_flag(args: &[&str]) -> Result<bool, CommandError> {
+    match args {
+        [] => Ok(false),
+        [flag] if flag.eq_ignore_ascii_case("--all") => Ok(true),
+        _ => Err(CommandError::InvalidArguments(
+            "usage: category list [--all]".into(),
+        )),
+    }
 }
 
 fn handle_remove(_context: &mut ShellContext) -> CommandResult {
@@ -98,12 +125,15 @@ fn handle_remove(_context: &mut ShellContext) -> CommandResult {
 fn handle_budget(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     if args.is_empty() {
         return Err(CommandError::InvalidArguments(
-            "usage: category budget <set|show|clear> ...".into(),
+            "usage: category budget <set|show|allocate|proration|note|clear> ...".into(),
         ));
     }
     match args[0].to_lowercase().as_str() {
         "set" => context.category_budget_set(&args[1..]),
         "show" => context.category_budget_show(&args[1..]),
+        "allocate" => allocate::run_allocate(context, &args[1..]),
+        "proration" => context.category_budget_proration(&args[1..]),
+        "note" => context.category_budget_note(&args[1..]),
         "clear" => context.category_budget_clear(&args[1..]),
         other => Err(CommandError::InvalidArguments(format!(
             "unknown category budget action `{}`",
@@ -111,3 +141,25 @@ fn handle_budget(context: &mut ShellContext, args: &[&str]) -> CommandResult {
         ))),
     }
 }
+
+fn handle_group(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some(action) = args.first() else {
+        return Err(CommandError::InvalidArguments(
+            "usage: category group <add|remove|assign|unassign|budget|clear-budget|list> ..."
+                .into(),
+        ));
+    };
+    match action.to_lowercase().as_str() {
+        "add" => context.category_group_add(&args[1..]),
+        "remove" => context.category_group_remove(&args[1..]),
+        "assign" => context.category_group_assign(&args[1..]),
+        "unassign" => context.category_group_unassign(&args[1..]),
+        "budget" => context.category_group_budget(&args[1..]),
+        "clear-budget" => context.category_group_clear_budget(&args[1..]),
+        "list" => context.category_group_list(&args[1..]),
+        other => Err(CommandError::InvalidArguments(format!(
+            "unknown category group action `{}`",
+            other
+        ))),
+    }
+}
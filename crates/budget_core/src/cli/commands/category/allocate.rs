@@ -0,0 +1,209 @@
+//! `category budget allocate`: split detected income across expense
+//! categories, one wizard step per category, and save every budget at once.
+
+use uuid::Uuid;
+
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::forms::{
+    AllocationStep, AllocationWizard, FormEngine, FormResult, WizardInteraction,
+};
+use crate::cli::io;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::{AllocationPlan, AllocationService};
+use bufy_core::{CurrencyFormatter, DateFormatter};
+
+const USAGE: &str = "usage: category budget allocate [<category>=<amount|rest> ...]";
+
+pub(crate) fn run_allocate(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    context.ensure_base_mode("Category budgets")?;
+    let today = context.clock.today();
+    let (plan, currency) = context.with_ledger(|ledger| {
+        Ok((
+            AllocationService::plan(ledger, today),
+            ledger.base_currency().as_str().to_string(),
+        ))
+    })?;
+    print_plan(context, &plan, &currency);
+    if plan.lines.is_empty() {
+        io::print_warning("No expense categories to allocate to.");
+        return Ok(());
+    }
+
+    let allocations = if !args.is_empty() {
+        parse_allocations(&plan, args)?
+    } else if context.can_prompt() {
+        let steps = plan
+            .lines
+            .iter()
+            .map(|line| AllocationStep {
+                category_id: line.category_id,
+                name: line.name.clone(),
+                current: line.allocated,
+                average_spend: line.average_spend,
+            })
+            .collect();
+        let wizard = AllocationWizard::new(steps, plan.income);
+        match FormEngine::new(&wizard)
+            .run(&mut WizardInteraction::new())
+            .unwrap()
+        {
+            FormResult::Completed(allocations) => allocations,
+            FormResult::Cancelled => {
+                io::print_info("Allocation cancelled.");
+                return Ok(());
+            }
+        }
+    } else {
+        return Ok(());
+    };
+
+    let changed = context
+        .with_ledger_mut(|ledger| Ok(AllocationService::apply(ledger, today, &allocations)?))?;
+    let allocated: f64 = plan
+        .lines
+        .iter()
+        .map(|line| {
+            allocations
+                .iter()
+                .find(|(id, _)| *id == line.category_id)
+                .map_or(line.allocated, |(_, amount)| *amount)
+        })
+        .sum();
+    let formatters = &context.formatters;
+    if changed == 0 {
+        io::print_info("Budgets unchanged.");
+    } else {
+        io::print_success(format!(
+            "Updated {} budget{}.",
+            changed,
+            if changed == 1 { "" } else { "s" }
+        ));
+    }
+    print_remainder(plan.income - allocated, |amount| {
+        formatters.format_amount(amount, &currency)
+    });
+    Ok(())
+}
+
+fn print_plan(context: &ShellContext, plan: &AllocationPlan, currency: &str) {
+    let formatters = &context.formatters;
+    let amount = |value: f64| formatters.format_amount(value, currency);
+    let mut income = Table::new(
+        Some("Recent income"),
+        vec![
+            TableColumn::new("PERIOD", 26),
+            TableColumn::new("RECEIVED", 14),
+        ],
+    );
+    for period in &plan.recent_income {
+        income.add_row(vec![
+            format!(
+                "{} – {}",
+                formatters.format_date(period.window.start),
+                formatters.format_date(period.window.end.pred_opt().unwrap_or(period.window.end))
+            ),
+            amount(period.amount),
+        ]);
+    }
+    TableRenderer::render(&income, &context.ui_style);
+    if plan.recent_income.iter().all(|period| period.amount == 0.0) {
+        io::print_info(format!(
+            "No income received recently; using {} planned for this period.",
+            amount(plan.current_income)
+        ));
+    } else {
+        io::print_info(format!("Income per period: {}", amount(plan.income)));
+    }
+
+    let mut categories = Table::new(
+        Some("Allocations"),
+        vec![
+            TableColumn::new("CATEGORY", 20),
+            TableColumn::new("BUDGETED", 14),
+            TableColumn::new("AVG SPENT", 14),
+        ],
+    );
+    for line in &plan.lines {
+        categories.add_row(vec![
+            line.name.clone(),
+            amount(line.allocated),
+            amount(line.average_spend),
+        ]);
+    }
+    TableRenderer::render(&categories, &context.ui_style);
+    io::print_info(format!("Allocated: {}", amount(plan.allocated())));
+    print_remainder(plan.unassigned(), amount);
+}
+
+fn print_remainder(remainder: f64, amount: impl Fn(f64) -> String) {
+    if remainder < -0.005 {
+        io::print_warning(format!("Budgets exceed income by {}.", amount(-remainder)));
+    } else {
+        io::print_info(format!("Unassigned: {}", amount(remainder.max(0.0))));
+    }
+}
+
+/// Reads `<category>=<amount>` pairs. One category may take `rest`: the
+/// income left once every other budget is counted.
+fn parse_allocations(
+    plan: &AllocationPlan,
+    args: &[&str],
+) -> Result<Vec<(Uuid, f64)>, CommandError> {
+    let mut allocations: Vec<(Uuid, Option<f64>)> = Vec::new();
+    for arg in args {
+        let (name, value) = arg
+            .split_once('=')
+            .ok_or_else(|| CommandError::InvalidArguments(USAGE.into()))?;
+        let line = plan
+            .lines
+            .iter()
+            .find(|line| line.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| {
+                CommandError::InvalidArguments(format!(
+                    "`{}` is not a visible expense category",
+                    name.trim()
+                ))
+            })?;
+        if allocations.iter().any(|(id, _)| *id == line.category_id) {
+            return Err(CommandError::InvalidArguments(format!(
+                "`{}` is allocated more than once",
+                line.name
+            )));
+        }
+        let amount = if value.trim().eq_ignore_ascii_case("rest") {
+            if allocations.iter().any(|(_, amount)| amount.is_none()) {
+                return Err(CommandError::InvalidArguments(
+                    "only one category can take the rest".into(),
+                ));
+            }
+            None
+        } else {
+            let amount = io::parse_number(value.trim()).ok_or_else(|| {
+                CommandError::InvalidArguments(format!("invalid amount `{}`", value.trim()))
+            })?;
+            if amount < 0.0 {
+                return Err(CommandError::InvalidArguments(
+                    "amount must be zero or positive".into(),
+                ));
+            }
+            Some(amount)
+        };
+        allocations.push((line.category_id, amount));
+    }
+
+    let claimed: f64 = plan
+        .lines
+        .iter()
+        .map(
+            |line| match allocations.iter().find(|(id, _)| *id == line.category_id) {
+                Some((_, amount)) => amount.unwrap_or(0.0),
+                None => line.allocated,
+            },
+        )
+        .sum();
+    let rest = (plan.income - claimed).max(0.0);
+    Ok(allocations
+        .into_iter()
+        .map(|(id, amount)| (id, amount.unwrap_or(rest)))
+        .collect())
+}
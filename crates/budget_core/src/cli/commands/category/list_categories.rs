@@ -2,19 +2,33 @@ use std::collections::HashMap;
 
 use uuid::Uuid;
 
+use crate::cli::commands::ordering::favorite_marker;
 use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::entity_ref::{short_id, EntityRef};
+use crate::cli::formatters::CliFormatters;
 use crate::cli::io as cli_io;
 use crate::cli::ui::detail_actions::{DetailAction, DetailActionResult, DetailActionsMenu};
 use crate::cli::ui::detail_view::DetailView;
 use crate::cli::ui::run_selectable_table;
+use crate::cli::ui::style::style;
 use crate::cli::ui::table_renderer::{Alignment, Table, TableColumn};
 use crate::cli::ui::test_mode;
 use crate::core::services::{BudgetService, CategoryService};
+use crate::ledger::{Appearance, Category, Ledger};
+use bufy_core::{CategoryStats, CurrencyFormatter, TransactionHighlight, STATS_AVERAGE_MONTHS};
 use bufy_domain::category::{CategoryBudgetDefinition, CategoryKind};
 
 const NO_VALUE: &str = "—";
 
 pub fn run_list_categories(context: &mut ShellContext) -> CommandResult {
+    run_list_categories_with_hidden(context, false)
+}
+
+/// Lists categories, including hidden system ones when `show_all` is set.
+pub fn run_list_categories_with_hidden(
+    context: &mut ShellContext,
+    show_all: bool,
+) -> CommandResult {
     {
         let manager = context.manager();
         if manager.current_handle().is_none() {
@@ -23,88 +37,166 @@ pub fn run_list_categories(context: &mut ShellContext) -> CommandResult {
         }
     }
 
+    let formatters = context.formatters.clone();
     run_selectable_table(
         context,
         "category_selector",
         "category_actions",
         Some("No categories in this ledger."),
-        |ctx| gather_entries(ctx),
+        |ctx| gather_entries(ctx, show_all),
         build_table,
-        build_detail_view,
+        |entry| build_detail_view(entry, &formatters),
         |_| build_actions(),
         |ctx, entry, action| execute_action(ctx, entry, action.id.as_str()),
     )
 }
 
+/// `category show <name|index|id>`: the details and quick stats of one
+/// category.
+pub(crate) fn run_show_category(context: &mut ShellContext, raw: &str) -> CommandResult {
+    let by_name = context.with_ledger(|ledger| {
+        Ok(ledger
+            .categories
+            .iter()
+            .position(|category| category.name.eq_ignore_ascii_case(raw.trim())))
+    })?;
+    let index = match by_name {
+        Some(index) => index,
+        None if EntityRef::parse(raw).is_some() => context.category_index_from_arg(raw)?,
+        None => {
+            return Err(CommandError::InvalidArguments(format!(
+                "unknown category `{}`",
+                raw
+            )))
+        }
+    };
+    let entry = context.with_ledger(|ledger| {
+        let category = &ledger.categories[index];
+        let spent = period_spend(ledger, context);
+        let counts = transaction_counts(ledger);
+        let stats = CategoryService::stats(ledger, category.id, context.clock.today())?;
+        Ok(build_entry(
+            ledger,
+            index,
+            category,
+            &spent,
+            &counts,
+            Some(stats),
+        ))
+    })?;
+    cli_io::println_text(&build_detail_view(&entry, &context.formatters).render())
+        .map_err(CommandError::from)
+}
+
 struct CategoryEntry {
     index: usize,
     id: Uuid,
     name: String,
+    appearance: Appearance,
+    favorite: bool,
+    hidden: bool,
     kind: CategoryKind,
     budget: Option<CategoryBudgetDefinition>,
     spent: f64,
     associated_accounts: Vec<String>,
     transaction_count: usize,
     notes: Option<String>,
+    /// The ledger's base currency, which category totals are counted in.
+    currency: String,
+    stats: Option<CategoryStats>,
 }
 
-fn gather_entries(context: &ShellContext) -> Result<Vec<CategoryEntry>, CommandError> {
+fn gather_entries(
+    context: &ShellContext,
+    show_all: bool,
+) -> Result<Vec<CategoryEntry>, CommandError> {
     context.with_ledger(|ledger| {
         if ledger.categories.is_empty() {
             return Ok(Vec::new());
         }
-        let summary = BudgetService::summarize_current_period(ledger, context.clock.as_ref());
-        let spent_map: HashMap<Uuid, f64> = summary
-            .per_category
-            .iter()
-            .filter_map(|entry| entry.category_id.map(|id| (id, entry.totals.real)))
-            .collect();
-
-        let mut txn_counts: HashMap<Uuid, usize> = HashMap::new();
-        for txn in &ledger.transactions {
-            if let Some(category_id) = txn.category_id {
-                *txn_counts.entry(category_id).or_insert(0) += 1;
-            }
-        }
+        let spent = period_spend(ledger, context);
+        let counts = transaction_counts(ledger);
+        let mut stats = CategoryService::stats_by_category(ledger, context.clock.today());
 
         let entries = ledger
             .categories
             .iter()
             .enumerate()
+            .filter(|(_, category)| show_all || !category.hidden)
             .map(|(index, category)| {
-                let spent = spent_map.get(&category.id).copied().unwrap_or(0.0);
-                let transaction_count = txn_counts.get(&category.id).copied().unwrap_or(0);
-                let mut associated_accounts: Vec<String> = ledger
-                    .accounts
-                    .iter()
-                    .filter(|account| account.category_id == Some(category.id))
-                    .map(|account| account.name.clone())
-                    .collect();
-                associated_accounts.sort();
-
-                CategoryEntry {
-                    index,
-                    id: category.id,
-                    name: category.name.clone(),
-                    kind: category.kind.clone(),
-                    budget: category.budget.clone(),
-                    spent,
-                    associated_accounts,
-                    transaction_count,
-                    notes: category.notes.clone(),
-                }
+                let stats = stats.remove(&category.id);
+                build_entry(ledger, index, category, &spent, &counts, stats)
             })
             .collect();
         Ok(entries)
     })
 }
 
+/// Spend filed under each category in the current period.
+fn period_spend(ledger: &Ledger, context: &ShellContext) -> HashMap<Uuid, f64> {
+    BudgetService::summarize_current_period(ledger, context.clock.as_ref())
+        .per_category
+        .iter()
+        .filter_map(|entry| entry.category_id.map(|id| (id, entry.totals.real)))
+        .collect()
+}
+
+fn transaction_counts(ledger: &Ledger) -> HashMap<Uuid, usize> {
+    let mut counts: HashMap<Uuid, usize> = HashMap::new();
+    for txn in &ledger.transactions {
+        if let Some(category_id) = txn.category_id {
+            *counts.entry(category_id).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+fn build_entry(
+    ledger: &Ledger,
+    index: usize,
+    category: &Category,
+    spent: &HashMap<Uuid, f64>,
+    counts: &HashMap<Uuid, usize>,
+    stats: Option<CategoryStats>,
+) -> CategoryEntry {
+    let mut associated_accounts: Vec<String> = ledger
+        .accounts
+        .iter()
+        .filter(|account| account.category_id == Some(category.id))
+        .map(|account| account.name.clone())
+        .collect();
+    associated_accounts.sort();
+
+    CategoryEntry {
+        index,
+        id: category.id,
+        name: category.name.clone(),
+        appearance: category.appearance.clone(),
+        favorite: category.favorite,
+        hidden: category.hidden,
+        kind: category.kind.clone(),
+        budget: category.budget.clone(),
+        spent: spent.get(&category.id).copied().unwrap_or(0.0),
+        associated_accounts,
+        transaction_count: counts.get(&category.id).copied().unwrap_or(0),
+        notes: category.notes.clone(),
+        currency: ledger.base_currency().as_str().to_string(),
+        stats,
+    }
+}
+
 fn build_table(entries: &[CategoryEntry]) -> Table {
+    let style = style();
     let rows = entries
         .iter()
         .map(|entry| {
+            let label = style.apply_entity_style(&entry.name, &entry.appearance);
             vec![
-                entry.name.clone(),
+                if entry.hidden {
+                    format!("{}{} (hidden)", favorite_marker(entry.favorite), label)
+                } else {
+                    format!("{}{}", favorite_marker(entry.favorite), label)
+                },
                 entry.kind.to_string(),
                 format_budget_text(entry.budget.as_ref()),
                 format!("{:.2}", entry.spent),
@@ -163,7 +255,8 @@ fn format_budget_period(period: &bufy_domain::common::BudgetPeriod) -> String {
     }
 }
 
-fn build_detail_view(entry: &CategoryEntry) -> DetailView {
+fn build_detail_view(entry: &CategoryEntry, formatters: &CliFormatters) -> DetailView {
+    let amount = |value: f64| formatters.format_amount(value, &entry.currency);
     let associated = if entry.associated_accounts.is_empty() {
         NO_VALUE.into()
     } else {
@@ -172,16 +265,44 @@ fn build_detail_view(entry: &CategoryEntry) -> DetailView {
 
     let mut view = DetailView::new(format!("Category: {}", entry.name))
         .with_field("name", format!("\"{}\"", entry.name))
+        .with_field("id", short_id(entry.id))
         .with_field("type", entry.kind.to_string())
-        .with_field("spent_current_period", format!("{:.2}", entry.spent))
+        .with_field("spent_current_period", amount(entry.spent))
         .with_field("transaction_count", entry.transaction_count.to_string())
-        .with_field("associated_accounts", associated)
+        .with_field("associated_accounts", associated);
+
+    if let Some(stats) = &entry.stats {
+        view = view
+            .with_field(
+                format!("monthly_average_{}m", STATS_AVERAGE_MONTHS),
+                amount(stats.monthly_average),
+            )
+            .with_field(
+                "largest_transaction",
+                stats
+                    .largest_transaction
+                    .as_ref()
+                    .map(|txn: &TransactionHighlight| {
+                        format!("{} on {}", amount(txn.amount), txn.date)
+                    })
+                    .unwrap_or_else(|| NO_VALUE.into()),
+            )
+            .with_field(
+                "last_activity",
+                stats
+                    .last_activity
+                    .map(|date| date.to_string())
+                    .unwrap_or_else(|| NO_VALUE.into()),
+            );
+    }
+
+    view = view
         .with_field("created_at", NO_VALUE)
         .with_field("last_modified", NO_VALUE);
 
     if let Some(definition) = &entry.budget {
         view = view
-            .with_field("budget.amount", format!("{:.2}", definition.amount))
+            .with_field("budget.amount", definition.amount.to_string())
             .with_field("budget.period", format_budget_period(&definition.period))
             .with_field(
                 "budget.start_date",
@@ -242,8 +363,8 @@ fn delete_category(context: &mut ShellContext, entry: &CategoryEntry) -> Command
         }
     }
 
-    context.with_ledger_mut(|ledger| {
-        CategoryService::remove(ledger, entry.id).map_err(CommandError::from)
+    context.with_ledger_journaled(|ledger, journal| {
+        CategoryService::remove_journaled(ledger, entry.id, journal).map_err(CommandError::from)
     })?;
     cli_io::print_success(format!("Category `{}` deleted.", entry.name));
     Ok(())
@@ -2,11 +2,16 @@
 
 use chrono::{NaiveDate, Weekday};
 
-use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::core::{CliError, CommandError, CommandResult, ShellContext};
 use crate::cli::io;
 use crate::cli::registry::CommandEntry;
-use bufy_domain::currency::{
-    CurrencyCode, DateFormatStyle, LocaleConfig, NegativeStyle, ValuationPolicy,
+use crate::config::{self, SecretStore};
+use bufy_domain::{
+    currency::{
+        CurrencyCode, DateFormatStyle, LocaleConfig, NegativeStyle, SymbolPosition, ValuationPolicy,
+    },
+    locale::LocaleData,
+    transaction::DateBasis,
 };
 
 pub(crate) fn definitions() -> Vec<CommandEntry> {
@@ -18,26 +23,6 @@ pub(crate) fn definitions() -> Vec<CommandEntry> {
     )]
 }
 
-fn locale_template(tag: &str) -> LocaleConfig {
-    match tag {
-        "fr-FR" => LocaleConfig {
-            language_tag: "fr-FR".into(),
-            decimal_separator: ',',
-            grouping_separator: ' ',
-            date_format: DateFormatStyle::Long,
-            first_weekday: Weekday::Mon,
-        },
-        "en-GB" => LocaleConfig {
-            language_tag: "en-GB".into(),
-            decimal_separator: '.',
-            grouping_separator: ',',
-            date_format: DateFormatStyle::Long,
-            first_weekday: Weekday::Mon,
-        },
-        _ => LocaleConfig::default(),
-    }
-}
-
 fn cmd_config(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     if args.is_empty() || args[0].eq_ignore_ascii_case("show") {
         return context.show_config();
@@ -47,7 +32,7 @@ fn cmd_config(context: &mut ShellContext, args: &[&str]) -> CommandResult {
         "set" => {
             if args.len() < 3 {
                 return Err(CommandError::InvalidArguments(
-                    "usage: config set <locale|currency|theme|ui_color_enabled|plain_output|high_contrast|last_opened_ledger|default_budget_period|default_currency_precision> <value>".into(),
+                    "usage: config set <locale|currency|theme|ui_color_enabled|plain_output|high_contrast|last_opened_ledger|default_budget_period|default_currency_precision|change_feed|audit_actor|pager|summary_category_rows|forecast_transaction_rows|simulation_overlay|default_transaction_status|default_transaction_date|autofill_actuals|simulation_backup_minutes|stale_rate_days|prompt|banner> <value>".into(),
                 ));
             }
             let key = args[1];
@@ -85,26 +70,53 @@ fn cmd_config(context: &mut ShellContext, args: &[&str]) -> CommandResult {
             let code = args.get(1).ok_or_else(|| {
                 CommandError::InvalidArguments("usage: config base-currency <ISO>".into())
             })?;
+            let currency = CurrencyCode::new(*code);
             context.with_ledger_mut(|ledger| {
-                ledger.base_currency = CurrencyCode::new(*code);
-                Ok(())
+                ledger
+                    .change_currencies(|ledger| ledger.base_currency = currency.clone())
+                    .map_err(CommandError::from_core)
             })?;
             io::print_success(format!(
-                "Base currency set to {}.",
-                CurrencyCode::new(*code).as_str()
+                "Base currency set to {}; amounts without their own currency are now counted in it.",
+                currency.as_str()
             ));
             Ok(())
         }
         "locale" => {
-            let tag = args.get(1).ok_or_else(|| {
-                CommandError::InvalidArguments("usage: config locale <tag>".into())
+            let locale = parse_locale_args(&args[1..])?;
+            let summary = describe_locale(&locale);
+            context.with_ledger_mut(|ledger| {
+                ledger.locale = locale;
+                Ok(())
+            })?;
+            io::print_success(format!("Locale set to {}.", summary));
+            Ok(())
+        }
+        "first-weekday" => {
+            let day = args.get(1).ok_or_else(|| {
+                CommandError::InvalidArguments("usage: config first-weekday <mon..sun>".into())
+            })?;
+            let weekday = parse_weekday(day)?;
+            context.with_ledger_mut(|ledger| {
+                ledger.locale.first_weekday = weekday;
+                Ok(())
             })?;
-            let locale = locale_template(tag);
+            io::print_success(format!("First day of week set to {}.", weekday));
+            Ok(())
+        }
+        "weekend" => {
+            if args.len() < 2 {
+                return Err(CommandError::InvalidArguments(
+                    "usage: config weekend <day[,day...]|none>".into(),
+                ));
+            }
+            let weekend = parse_weekend(&args[1..].join(","))?;
+            let label = describe_weekend(&weekend);
             context.with_ledger_mut(|ledger| {
-                ledger.locale = locale.clone();
+                ledger.locale.weekend = weekend;
                 Ok(())
             })?;
-            io::print_success(format!("Locale set to {}.", locale.language_tag));
+            io::print_success(format!("Weekend set to {}.", label));
             Ok(())
         }
         "negative-style" => {
@@ -190,6 +202,22 @@ fn cmd_config(context: &mut ShellContext, args: &[&str]) -> CommandResult {
             io::print_success("Valuation policy updated.");
             Ok(())
         }
+        "date-basis" => {
+            let usage = "usage: config date-basis <actual|booking|value>";
+            let basis = match args.get(1).map(|value| value.to_lowercase()).as_deref() {
+                Some("actual") => DateBasis::Actual,
+                Some("booking") => DateBasis::Booking,
+                Some("value") => DateBasis::Value,
+                _ => return Err(CommandError::InvalidArguments(usage.into())),
+            };
+            context.with_ledger_mut(|ledger| {
+                ledger.summary_date_basis = basis;
+                Ok(())
+            })?;
+            io::print_success(format!("Summaries now use the {} date.", basis));
+            Ok(())
+        }
+        "secrets" => handle_secrets(context, &args[1..]),
         "audio-feedback" => {
             let mode = args.get(1).ok_or_else(|| {
                 CommandError::InvalidArguments("usage: config audio-feedback <on|off>".into())
@@ -210,7 +238,211 @@ fn cmd_config(context: &mut ShellContext, args: &[&str]) -> CommandResult {
             Ok(())
         }
         _ => Err(CommandError::InvalidArguments(
-            "usage: config [show|set <key> <value>|backup [note]|backups|restore [name]|base-currency <ISO>|locale <tag>|negative-style <sign|parentheses>|screen-reader <on|off>|high-contrast <on|off>|audio-feedback <on|off>|valuation <transaction|report|custom> [date]]".into(),
+            "usage: config [show|set <key> <value>|backup [note]|backups|restore [name]|base-currency <ISO>|locale <tag>|first-weekday <day>|weekend <days>|negative-style <sign|parentheses>|screen-reader <on|off>|high-contrast <on|off>|audio-feedback <on|off>|valuation <transaction|report|custom> [date]|date-basis <actual|booking|value>|secrets <list|set|forget|backend>]".into(),
         )),
     }
 }
+
+fn handle_secrets(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let store = config::secret_store();
+    match args {
+        [] | ["list"] => {
+            let keys = store.keys().map_err(CliError::from)?;
+            if keys.is_empty() {
+                io::print_info(format!("No passphrases stored ({}).", store.backend()));
+            } else {
+                io::print_info(format!("Passphrases stored in {}:", store.backend()));
+                for key in keys {
+                    io::print_info(format!("  {}", key));
+                }
+            }
+            Ok(())
+        }
+        ["backend"] => {
+            io::print_info(format!("Secret backend: {}", store.backend()));
+            Ok(())
+        }
+        ["set", ledger] => {
+            if !context.can_prompt() {
+                return Err(CommandError::InvalidArguments(
+                    "usage: config secrets set <ledger> <passphrase>".into(),
+                ));
+            }
+            let passphrase = io::prompt_password(&format!("Passphrase for `{}`", ledger))?;
+            store_passphrase(store.as_ref(), ledger, &passphrase)
+        }
+        ["set", ledger, passphrase] => store_passphrase(store.as_ref(), ledger, passphrase),
+        ["forget", ledger] => {
+            if store.delete(ledger).map_err(CliError::from)? {
+                io::print_success(format!("Forgot the passphrase for `{}`.", ledger));
+            } else {
+                io::print_warning(format!("No passphrase stored for `{}`.", ledger));
+            }
+            Ok(())
+        }
+        _ => Err(CommandError::InvalidArguments(
+            "usage: config secrets [list|backend|set <ledger> [passphrase]|forget <ledger>]".into(),
+        )),
+    }
+}
+
+fn store_passphrase(store: &dyn SecretStore, ledger: &str, passphrase: &str) -> CommandResult {
+    if passphrase.is_empty() {
+        return Err(CommandError::InvalidArguments(
+            "passphrase cannot be empty".into(),
+        ));
+    }
+    store.set(ledger, passphrase).map_err(CliError::from)?;
+    io::print_success(format!(
+        "Passphrase for `{}` saved in {}.",
+        ledger,
+        store.backend()
+    ));
+    Ok(())
+}
+
+fn parse_weekday(input: &str) -> Result<Weekday, CommandError> {
+    input
+        .trim()
+        .parse::<Weekday>()
+        .map_err(|_| CommandError::InvalidArguments(format!("unknown weekday `{}`", input)))
+}
+
+fn parse_weekend(input: &str) -> Result<Vec<Weekday>, CommandError> {
+    if input.trim().eq_ignore_ascii_case("none") {
+        return Ok(Vec::new());
+    }
+    let mut days = Vec::new();
+    for token in input.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        let day = parse_weekday(token)?;
+        if !days.contains(&day) {
+            days.push(day);
+        }
+    }
+    if days.is_empty() {
+        return Err(CommandError::InvalidArguments(
+            "usage: config weekend <day[,day...]|none>".into(),
+        ));
+    }
+    Ok(days)
+}
+
+pub(crate) fn describe_weekend(days: &[Weekday]) -> String {
+    if days.is_empty() {
+        return "none".into();
+    }
+    days.iter()
+        .map(|day| day.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+const LOCALE_USAGE: &str = "usage: config locale <tag> [--decimal C] [--grouping C|space] [--date-style short|medium|long] [--first-weekday mon..sun] [--symbol before|after] [--symbol-space on|off]";
+
+/// Resolves a locale tag to its built-in conventions, then applies any
+/// explicit overrides.
+fn parse_locale_args(args: &[&str]) -> Result<LocaleConfig, CommandError> {
+    let Some((tag, flags)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(LOCALE_USAGE.into()));
+    };
+    let mut locale = LocaleConfig::for_tag(tag).ok_or_else(|| {
+        let known: Vec<&str> = bufy_domain::locale::LOCALES
+            .iter()
+            .map(|data| data.tag)
+            .collect();
+        CommandError::InvalidArguments(format!(
+            "unknown locale `{}`. Known locales: {}",
+            tag,
+            known.join(", ")
+        ))
+    })?;
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        let value = iter
+            .next()
+            .ok_or_else(|| CommandError::InvalidArguments(format!("{} requires a value", flag)))?;
+        match flag.to_ascii_lowercase().as_str() {
+            "--decimal" => locale.decimal_separator = parse_separator(value)?,
+            "--grouping" => locale.grouping_separator = parse_separator(value)?,
+            "--date-style" => {
+                locale.date_format = match value.to_ascii_lowercase().as_str() {
+                    "short" => DateFormatStyle::Short,
+                    "medium" => DateFormatStyle::Medium,
+                    "long" => DateFormatStyle::Long,
+                    other => {
+                        return Err(CommandError::InvalidArguments(format!(
+                            "unknown date style `{}`",
+                            other
+                        )))
+                    }
+                }
+            }
+            "--first-weekday" => locale.first_weekday = parse_weekday(value)?,
+            "--symbol" => {
+                locale.symbol_position = Some(match value.to_ascii_lowercase().as_str() {
+                    "before" => SymbolPosition::Prefix,
+                    "after" => SymbolPosition::Suffix,
+                    other => {
+                        return Err(CommandError::InvalidArguments(format!(
+                            "unknown symbol position `{}` (use before or after)",
+                            other
+                        )))
+                    }
+                })
+            }
+            "--symbol-space" => {
+                locale.symbol_spacing = Some(match value.to_ascii_lowercase().as_str() {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(CommandError::InvalidArguments(LOCALE_USAGE.into())),
+                })
+            }
+            _ => return Err(CommandError::InvalidArguments(LOCALE_USAGE.into())),
+        }
+    }
+    if locale.decimal_separator == locale.grouping_separator {
+        return Err(CommandError::InvalidArguments(
+            "decimal and grouping separators must differ".into(),
+        ));
+    }
+    Ok(locale)
+}
+
+fn parse_separator(value: &str) -> Result<char, CommandError> {
+    if value.eq_ignore_ascii_case("space") {
+        return Ok(' ');
+    }
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) if !ch.is_ascii_digit() => Ok(ch),
+        _ => Err(CommandError::InvalidArguments(format!(
+            "invalid separator `{}` (use a single non-digit character or `space`)",
+            value
+        ))),
+    }
+}
+
+fn describe_locale(locale: &LocaleConfig) -> String {
+    let symbol = match (
+        locale.resolved_symbol_position(),
+        locale.resolved_symbol_spacing(),
+    ) {
+        (SymbolPosition::Prefix, false) => "before",
+        (SymbolPosition::Prefix, true) => "before, spaced",
+        (SymbolPosition::Suffix, false) => "after",
+        (SymbolPosition::Suffix, true) => "after, spaced",
+    };
+    format!(
+        "{} (decimal '{}', grouping '{}', symbol {}, week starts {})",
+        locale.language_tag,
+        locale.decimal_separator,
+        locale.grouping_separator,
+        symbol,
+        locale.first_weekday
+    )
+}
+
+/// Canonical tag for a known locale, used to validate `config set locale`.
+pub(crate) fn canonical_locale_tag(tag: &str) -> Option<&'static str> {
+    LocaleData::lookup(tag).map(|data| data.tag)
+}
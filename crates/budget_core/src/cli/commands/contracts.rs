@@ -0,0 +1,95 @@
+//! `contracts`: renewal windows of recurring bills under contract, so they
+//! can be renegotiated before renewing automatically.
+
+use bufy_core::{CurrencyFormatter, DateFormatter};
+
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::{ContractService, ContractStage};
+
+const CONTRACTS_USAGE: &str = "usage: contracts [--within <days>]";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "contracts",
+        "List contract renewal windows and notice deadlines",
+        "contracts [--within <days>]",
+        cmd_contracts,
+    )]
+}
+
+fn cmd_contracts(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let within = match args {
+        [] => None,
+        [flag, days] if flag.eq_ignore_ascii_case("--within") => {
+            Some(days.parse::<i64>().map_err(|_| {
+                CommandError::InvalidArguments(format!("invalid number of days `{}`", days))
+            })?)
+        }
+        _ => return Err(CommandError::InvalidArguments(CONTRACTS_USAGE.into())),
+    };
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    let today = context.clock.today();
+    context.with_ledger(|ledger| {
+        let windows: Vec<_> = ContractService::windows(ledger, today)
+            .into_iter()
+            .filter(|window| within.is_none_or(|days| window.days_to_notice <= days))
+            .collect();
+        if windows.is_empty() {
+            io::print_warning(match within {
+                Some(days) => format!("No notice deadlines in the next {} day(s).", days),
+                None => "No contracts yet. Add one with `transaction recurring contract <index> <provider> <end>`.".into(),
+            });
+            return Ok(());
+        }
+        let currency = ledger.base_currency().as_str();
+        let mut table = Table::new(
+            Some("Contracts"),
+            vec![
+                TableColumn::new("REF", 8),
+                TableColumn::new("PROVIDER", 18),
+                TableColumn::new("AMOUNT", 12),
+                TableColumn::new("EVERY", 10),
+                TableColumn::new("NOTICE BY", 12),
+                TableColumn::new("ENDS", 12),
+                TableColumn::new("STATUS", 12),
+            ],
+        );
+        for window in &windows {
+            table.add_row(vec![
+                ledger
+                    .transaction(window.transaction_id)
+                    .and_then(|txn| txn.short_ref())
+                    .map(|code| code.to_string())
+                    .unwrap_or_default(),
+                window.provider.clone(),
+                formatters.format_amount(window.amount, currency),
+                window.interval.label(),
+                formatters.format_date(window.notice_deadline),
+                formatters.format_date(window.end_date),
+                window.stage.to_string(),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        for window in &windows {
+            match window.stage {
+                ContractStage::NoticeDue => io::print_warning(format!(
+                    "Give {} notice within {} day(s) to renegotiate before it renews on {}.",
+                    window.provider,
+                    window.days_to_notice,
+                    formatters.format_date(window.end_date)
+                )),
+                ContractStage::Lapsed => io::print_info(format!(
+                    "{} ended on {}; record the new end date if it renewed.",
+                    window.provider,
+                    formatters.format_date(window.end_date)
+                )),
+                ContractStage::Running | ContractStage::Renewing => {}
+            }
+        }
+        Ok(())
+    })
+}
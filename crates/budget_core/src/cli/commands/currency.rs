@@ -0,0 +1,212 @@
+//! `currency rates override <list|add|remove>`: exchange rates fixed on the
+//! ledger for a date range, used whenever an amount must be converted.
+//! `currency rates refresh` replaces an open-ended rate that has gone stale.
+
+use crate::cli::core::{parse_date, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::ExchangeRateService;
+use bufy_domain::{currency::CurrencyCode, exchange_rate::ExchangeRateOverride};
+
+const CURRENCY_USAGE: &str = "usage: currency rates <override <list|add <FROM> <TO> <rate> --from <date> [--until <date>] [--note <text>]|remove <index>>|refresh <FROM> <TO> <rate> [--from <date>]>";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "currency",
+        "Fix exchange rates for a date range on this ledger",
+        "currency rates <override <list|add <FROM> <TO> <rate> --from <date> [--until <date>] [--note <text>]|remove <index>>|refresh <FROM> <TO> <rate> [--from <date>]>",
+        cmd_currency,
+    )]
+}
+
+fn cmd_currency(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [rates, group, rest @ ..] = args else {
+        return Err(CommandError::InvalidArguments(CURRENCY_USAGE.into()));
+    };
+    if !rates.eq_ignore_ascii_case("rates") {
+        return Err(CommandError::InvalidArguments(CURRENCY_USAGE.into()));
+    }
+    if group.eq_ignore_ascii_case("refresh") {
+        return match rest {
+            [from, to, rate, flags @ ..] => handle_refresh(context, from, to, rate, flags),
+            _ => Err(CommandError::InvalidArguments(CURRENCY_USAGE.into())),
+        };
+    }
+    if !group.eq_ignore_ascii_case("override") {
+        return Err(CommandError::InvalidArguments(CURRENCY_USAGE.into()));
+    }
+    match rest {
+        [] => handle_list(context),
+        [action] if action.eq_ignore_ascii_case("list") || action.eq_ignore_ascii_case("ls") => {
+            handle_list(context)
+        }
+        [action, from, to, rate, flags @ ..] if action.eq_ignore_ascii_case("add") => {
+            handle_add(context, from, to, rate, flags)
+        }
+        [action, index] if action.eq_ignore_ascii_case("remove") => handle_remove(context, index),
+        _ => Err(CommandError::InvalidArguments(CURRENCY_USAGE.into())),
+    }
+}
+
+fn handle_list(context: &mut ShellContext) -> CommandResult {
+    let style = context.ui_style.clone();
+    context.with_ledger(|ledger| {
+        if ledger.exchange_rate_overrides.is_empty() {
+            io::print_warning(
+                "No exchange rate overrides yet. Add one with `currency rates override add <FROM> <TO> <rate> --from <date>`.",
+            );
+            return Ok(());
+        }
+        let mut table = Table::new(
+            Some("Exchange rate overrides"),
+            vec![
+                TableColumn::new("#", 4),
+                TableColumn::new("PAIR", 10),
+                TableColumn::new("RATE", 12),
+                TableColumn::new("VALID", 26),
+                TableColumn::new("NOTE", 24),
+            ],
+        );
+        for (index, rate) in ledger.exchange_rate_overrides.iter().enumerate() {
+            table.add_row(vec![
+                index.to_string(),
+                format!("{}/{}", rate.from.as_str(), rate.to.as_str()),
+                rate.rate.to_string(),
+                rate.validity_label(),
+                rate.note.clone().unwrap_or_default(),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_add(
+    context: &mut ShellContext,
+    from: &str,
+    to: &str,
+    rate: &str,
+    flags: &[&str],
+) -> CommandResult {
+    let value = io::parse_number(rate).ok_or_else(|| {
+        CommandError::InvalidArguments(format!("rate must be numeric, got `{}`", rate))
+    })?;
+    let mut valid_from = None;
+    let mut valid_until = None;
+    let mut note = None;
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        match flag.to_ascii_lowercase().as_str() {
+            "--from" => valid_from = Some(parse_date(flag_value(flag, iter.next())?)?),
+            "--until" => valid_until = Some(parse_date(flag_value(flag, iter.next())?)?),
+            "--note" => {
+                let text = iter.by_ref().copied().collect::<Vec<_>>().join(" ");
+                if text.is_empty() {
+                    return Err(CommandError::InvalidArguments(
+                        "--note requires a value".into(),
+                    ));
+                }
+                note = Some(text);
+            }
+            _ => return Err(CommandError::InvalidArguments(CURRENCY_USAGE.into())),
+        }
+    }
+    let valid_from = valid_from.ok_or_else(|| {
+        CommandError::InvalidArguments("--from <date> is required for a rate override".into())
+    })?;
+
+    let mut rate = ExchangeRateOverride::new(
+        CurrencyCode::new(from),
+        CurrencyCode::new(to),
+        value,
+        valid_from,
+        valid_until,
+        context.clock.now(),
+    );
+    rate.note = note;
+    let summary = format!(
+        "1 {} = {} {}, {}",
+        rate.from.as_str(),
+        rate.rate,
+        rate.to.as_str(),
+        rate.validity_label()
+    );
+    context.with_ledger_mut(|ledger| {
+        ExchangeRateService::add_override(ledger, rate).map_err(CommandError::from)
+    })?;
+    io::print_success(format!("Added exchange rate override: {}.", summary));
+    Ok(())
+}
+
+/// Starts `rate` today, or on `--from`, ending the open-ended rate it
+/// replaces the day before.
+fn handle_refresh(
+    context: &mut ShellContext,
+    from: &str,
+    to: &str,
+    rate: &str,
+    flags: &[&str],
+) -> CommandResult {
+    let value = io::parse_number(rate).ok_or_else(|| {
+        CommandError::InvalidArguments(format!("rate must be numeric, got `{}`", rate))
+    })?;
+    let valid_from = match flags {
+        [] => context.clock.today(),
+        [flag, date] if flag.eq_ignore_ascii_case("--from") => parse_date(date)?,
+        _ => return Err(CommandError::InvalidArguments(CURRENCY_USAGE.into())),
+    };
+    let rate = ExchangeRateOverride::new(
+        CurrencyCode::new(from),
+        CurrencyCode::new(to),
+        value,
+        valid_from,
+        None,
+        context.clock.now(),
+    );
+    let summary = format!(
+        "1 {} = {} {}, {}",
+        rate.from.as_str(),
+        rate.rate,
+        rate.to.as_str(),
+        rate.validity_label()
+    );
+    context.with_ledger_mut(|ledger| {
+        ExchangeRateService::refresh_override(ledger, rate).map_err(CommandError::from)
+    })?;
+    io::print_success(format!("Refreshed exchange rate: {}.", summary));
+    Ok(())
+}
+
+fn handle_remove(context: &mut ShellContext, index: &str) -> CommandResult {
+    let index: usize = index
+        .parse()
+        .map_err(|_| CommandError::InvalidArguments(CURRENCY_USAGE.into()))?;
+    let removed = context.with_ledger_mut(|ledger| {
+        let id = ledger
+            .exchange_rate_overrides
+            .get(index)
+            .map(|rate| rate.id)
+            .ok_or_else(|| {
+                CommandError::InvalidArguments(format!(
+                    "no exchange rate override at index {}",
+                    index
+                ))
+            })?;
+        ExchangeRateService::remove_override(ledger, id).map_err(CommandError::from)
+    })?;
+    io::print_success(format!(
+        "Removed the {}/{} override valid {}.",
+        removed.from.as_str(),
+        removed.to.as_str(),
+        removed.validity_label()
+    ));
+    Ok(())
+}
+
+fn flag_value<'a>(flag: &str, value: Option<&&'a str>) -> Result<&'a str, CommandError> {
+    value
+        .copied()
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| CommandError::InvalidArguments(format!("{} requires a value", flag)))
+}
@@ -0,0 +1,88 @@
+//! `export <transactions|summary|budget> <path> [--window ...]`: write ledger
+//! data as CSV or JSON for spreadsheets and other programs.
+
+use std::fs;
+use std::path::PathBuf;
+
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::core::services::{ExportService, SummaryService};
+use crate::ledger::{DateWindow, Transaction};
+use bufy_domain::view::ViewColumn;
+
+const EXPORT_USAGE: &str = "usage: export <transactions|summary|budget> <path> [--window current|past [n]|future [n]|custom <start> <end>]";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "export",
+        "Export transactions (CSV), a summary (JSON), or a budget report (CSV)",
+        "export <transactions|summary|budget> <path> [--window current|past [n]|future [n]|custom <start> <end>]",
+        cmd_export,
+    )]
+}
+
+fn cmd_export(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [what, path, rest @ ..] = args else {
+        return Err(CommandError::InvalidArguments(EXPORT_USAGE.into()));
+    };
+    let window_args = match rest {
+        [] => None,
+        [flag, window @ ..] if flag.eq_ignore_ascii_case("--window") && !window.is_empty() => {
+            Some(window)
+        }
+        _ => return Err(CommandError::InvalidArguments(EXPORT_USAGE.into())),
+    };
+    let path = PathBuf::from(path);
+    let today = context.clock.today();
+
+    let described = context.with_ledger(|ledger| {
+        let (window, scope) =
+            context.resolve_summary_window(ledger, window_args.unwrap_or(&[]), today)?;
+        match what.to_ascii_lowercase().as_str() {
+            "transactions" => {
+                let in_window = |txn: &&Transaction| {
+                    window_args.is_none()
+                        || window.contains(
+                            txn.reporting_date(ledger.summary_date_basis)
+                                .unwrap_or(txn.scheduled_date),
+                        )
+                };
+                let transactions: Vec<&Transaction> =
+                    ledger.transactions.iter().filter(in_window).collect();
+                let csv = ExportService::transactions_csv(
+                    ledger,
+                    &transactions,
+                    &ViewColumn::default_columns(),
+                );
+                fs::write(&path, csv)?;
+                Ok(format!("{} transaction(s)", transactions.len()))
+            }
+            "summary" => {
+                let summary = SummaryService::summarize_window(ledger, window, scope);
+                let budgets = SummaryService::category_budget_summaries(ledger, window, scope);
+                let json = ExportService::summary_json(ledger, &summary, &budgets)
+                    .map_err(CommandError::from)?;
+                fs::write(&path, json)?;
+                Ok(format!("the summary for {}", window_label(window)))
+            }
+            "budget" => {
+                let summary = SummaryService::summarize_window(ledger, window, scope);
+                let budgets = SummaryService::category_budget_summaries(ledger, window, scope);
+                fs::write(
+                    &path,
+                    ExportService::budget_report_csv(ledger, &summary, &budgets),
+                )?;
+                Ok(format!("the budget report for {}", window_label(window)))
+            }
+            _ => Err(CommandError::InvalidArguments(EXPORT_USAGE.into())),
+        }
+    })?;
+    io::print_success(format!("Exported {} to {}.", described, path.display()));
+    Ok(())
+}
+
+fn window_label(window: DateWindow) -> String {
+    let last = window.end.pred_opt().unwrap_or(window.end);
+    format!("{} to {}", window.start, last)
+}
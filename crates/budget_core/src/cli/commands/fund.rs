@@ -0,0 +1,216 @@
+//! Commands for sinking funds that save towards annual or irregular bills.
+
+use crate::cli::core::{
+    parse_date, parse_time_interval_str, CommandError, CommandResult, ShellContext,
+};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::SinkingFundService;
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use bufy_domain::common::{TimeInterval, TimeUnit};
+
+const FUND_USAGE: &str = "usage: fund <add|contribute|pay|list|remove>";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "fund",
+        "Save towards annual or irregular bills with sinking funds",
+        "fund <add <name> <amount> <due YYYY-MM-DD> [--every INTERVAL] [--category C]|contribute <name> <amount>|pay <name>|list|remove <name>>",
+        cmd_fund,
+    )]
+}
+
+fn cmd_fund(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((subcommand, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(FUND_USAGE.into()));
+    };
+    match subcommand.to_ascii_lowercase().as_str() {
+        "add" | "new" => handle_add(context, rest),
+        "contribute" | "save" => handle_contribute(context, rest),
+        "pay" => handle_pay(context, rest),
+        "list" | "ls" => handle_list(context),
+        "remove" | "delete" => handle_remove(context, rest),
+        other => Err(CommandError::InvalidArguments(format!(
+            "unknown fund subcommand `{}`. Available: add, contribute, pay, list, remove",
+            other
+        ))),
+    }
+}
+
+fn handle_add(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let usage =
+        "usage: fund add <name> <amount> <due YYYY-MM-DD> [--every INTERVAL] [--category C]";
+    let [name, amount, due, flags @ ..] = args else {
+        return Err(CommandError::InvalidArguments(usage.into()));
+    };
+    let amount = parse_amount(amount)?;
+    let due = parse_date(due)?;
+    let mut interval = TimeInterval {
+        every: 1,
+        unit: TimeUnit::Year,
+    };
+    let mut category = None;
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        let value = iter
+            .next()
+            .ok_or_else(|| CommandError::InvalidArguments(format!("{} requires a value", flag)))?;
+        match flag.to_ascii_lowercase().as_str() {
+            "--every" => interval = parse_time_interval_str(value)?,
+            "--category" => category = Some(*value),
+            _ => return Err(CommandError::InvalidArguments(usage.into())),
+        }
+    }
+    let today = context.clock.today();
+    let formatters = context.formatters.clone();
+    let monthly = context.with_ledger_mut(|ledger| {
+        let category_id = match category {
+            Some(name) => Some(
+                ledger
+                    .categories
+                    .iter()
+                    .find(|category| category.name.eq_ignore_ascii_case(name))
+                    .map(|category| category.id)
+                    .ok_or_else(|| {
+                        CommandError::InvalidArguments(format!("unknown category `{}`", name))
+                    })?,
+            ),
+            None => None,
+        };
+        SinkingFundService::create(
+            ledger,
+            name,
+            amount,
+            due,
+            interval,
+            category_id,
+            context.clock.as_ref(),
+        )
+        .map_err(CommandError::from)?;
+        let fund = ledger.sinking_fund(name).expect("fund exists after create");
+        let status = SinkingFundService::status(fund, today);
+        Ok(formatters.format_amount(status.monthly_set_aside, ledger.base_currency().as_str()))
+    })?;
+    io::print_success(format!(
+        "Sinking fund `{}` created. Set aside {} a month to cover it.",
+        name.trim(),
+        monthly
+    ));
+    Ok(())
+}
+
+fn handle_contribute(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name, amount] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: fund contribute <name> <amount>".into(),
+        ));
+    };
+    let amount = parse_amount(amount)?;
+    let formatters = context.formatters.clone();
+    let (display_name, saved) = context.with_ledger_mut(|ledger| {
+        let saved =
+            SinkingFundService::contribute(ledger, name, amount).map_err(CommandError::from)?;
+        let fund = ledger
+            .sinking_fund(name)
+            .expect("fund exists after contribute");
+        let saved = formatters.format_amount(saved, ledger.base_currency().as_str());
+        Ok((fund.name.clone(), saved))
+    })?;
+    io::print_success(format!(
+        "Set aside for `{}`. Saved so far: {}",
+        display_name, saved
+    ));
+    Ok(())
+}
+
+fn handle_pay(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: fund pay <name>".into(),
+        ));
+    };
+    let formatters = context.formatters.clone();
+    let (display_name, next_due) = context.with_ledger_mut(|ledger| {
+        let next_due = SinkingFundService::pay(ledger, name).map_err(CommandError::from)?;
+        let fund = ledger.sinking_fund(name).expect("fund exists after pay");
+        Ok((fund.name.clone(), formatters.format_date(next_due)))
+    })?;
+    io::print_success(format!(
+        "Bill for `{}` paid. Next due {}.",
+        display_name, next_due
+    ));
+    Ok(())
+}
+
+fn handle_list(context: &mut ShellContext) -> CommandResult {
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    let today = context.clock.today();
+    context.with_ledger(|ledger| {
+        if ledger.sinking_funds.is_empty() {
+            io::print_warning(
+                "No sinking funds yet. Create one with `fund add <name> <amount> <due>`.",
+            );
+            return Ok(());
+        }
+        let currency = ledger.base_currency().as_str();
+        let mut table = Table::new(
+            Some("Sinking funds"),
+            vec![
+                TableColumn::new("NAME", 18),
+                TableColumn::new("BILL", 12),
+                TableColumn::new("DUE", 12),
+                TableColumn::new("SAVED", 12),
+                TableColumn::new("ON TRACK AT", 12),
+                TableColumn::new("MONTHLY", 12),
+                TableColumn::new("STATUS", 12),
+            ],
+        );
+        let statuses = SinkingFundService::statuses(ledger, today);
+        for status in &statuses {
+            table.add_row(vec![
+                status.name.clone(),
+                formatters.format_amount(status.amount, currency),
+                formatters.format_date(status.next_due),
+                formatters.format_amount(status.saved, currency),
+                formatters.format_amount(status.expected, currency),
+                formatters.format_amount(status.monthly_set_aside, currency),
+                if status.is_underfunded() {
+                    "Underfunded".into()
+                } else {
+                    "On track".into()
+                },
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        for status in statuses.iter().filter(|status| status.is_underfunded()) {
+            io::print_warning(format!(
+                "`{}` is {} behind; set aside {} a month to be ready by {}.",
+                status.name,
+                formatters.format_amount(status.shortfall, currency),
+                formatters.format_amount(status.monthly_set_aside, currency),
+                formatters.format_date(status.next_due)
+            ));
+        }
+        Ok(())
+    })
+}
+
+fn handle_remove(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: fund remove <name>".into(),
+        ));
+    };
+    let removed = context.with_ledger_mut(|ledger| {
+        SinkingFundService::remove(ledger, name).map_err(CommandError::from)
+    })?;
+    io::print_success(format!("Sinking fund `{}` removed.", removed.name));
+    Ok(())
+}
+
+fn parse_amount(value: &str) -> Result<f64, CommandError> {
+    io::parse_number(value)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("invalid amount `{}`", value)))
+}
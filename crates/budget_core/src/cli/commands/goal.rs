@@ -0,0 +1,263 @@
+//! Commands for savings goals tracked through a linked account or category.
+
+use crate::cli::core::{
+    parse_date, parse_time_interval_str, CommandError, CommandResult, ShellContext,
+};
+use crate::cli::formatters::CliFormatters;
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::{GoalProgress, GoalService};
+use crate::ledger::Ledger;
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use bufy_domain::common::{TimeInterval, TimeUnit};
+use bufy_domain::goal::{FundingSchedule, GoalLink};
+
+const GOAL_USAGE: &str = "usage: goal <add|list|show>";
+const ADD_USAGE: &str = "usage: goal add <name> <target> <by YYYY-MM-DD> (--account A|--category C) [--contribute N [--every INTERVAL] [--from YYYY-MM-DD]]";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "goal",
+        "Save towards a target amount by a date",
+        "goal <add <name> <target> <by YYYY-MM-DD> (--account A|--category C) [--contribute N [--every INTERVAL] [--from YYYY-MM-DD]]|list|show <name>>",
+        cmd_goal,
+    )]
+}
+
+fn cmd_goal(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((subcommand, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(GOAL_USAGE.into()));
+    };
+    match subcommand.to_ascii_lowercase().as_str() {
+        "add" | "new" => handle_add(context, rest),
+        "list" | "ls" => handle_list(context),
+        "show" => handle_show(context, rest),
+        other => Err(CommandError::InvalidArguments(format!(
+            "unknown goal subcommand `{}`. Available: add, list, show",
+            other
+        ))),
+    }
+}
+
+fn handle_add(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name, target, by, flags @ ..] = args else {
+        return Err(CommandError::InvalidArguments(ADD_USAGE.into()));
+    };
+    let target = parse_amount(target)?;
+    let target_date = parse_date(by)?;
+    let (mut account, mut category, mut contribute, mut from) = (None, None, None, None);
+    let mut interval = TimeInterval {
+        every: 1,
+        unit: TimeUnit::Month,
+    };
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        let value = iter
+            .next()
+            .ok_or_else(|| CommandError::InvalidArguments(format!("{} requires a value", flag)))?;
+        match flag.to_ascii_lowercase().as_str() {
+            "--account" => account = Some(*value),
+            "--category" => category = Some(*value),
+            "--contribute" => contribute = Some(parse_amount(value)?),
+            "--every" => interval = parse_time_interval_str(value)?,
+            "--from" => from = Some(parse_date(value)?),
+            _ => return Err(CommandError::InvalidArguments(ADD_USAGE.into())),
+        }
+    }
+    let today = context.clock.today();
+    let funding = contribute.map(|amount| FundingSchedule {
+        amount,
+        interval,
+        start: from.unwrap_or(today),
+    });
+    let formatters = context.formatters.clone();
+    let required = context.with_ledger_mut(|ledger| {
+        let link = match (account, category) {
+            (Some(name), None) => GoalLink::Account(
+                ledger
+                    .accounts
+                    .iter()
+                    .find(|account| account.name.eq_ignore_ascii_case(name))
+                    .map(|account| account.id)
+                    .ok_or_else(|| {
+                        CommandError::InvalidArguments(format!("unknown account `{}`", name))
+                    })?,
+            ),
+            (None, Some(name)) => GoalLink::Category(
+                ledger
+                    .categories
+                    .iter()
+                    .find(|category| category.name.eq_ignore_ascii_case(name))
+                    .map(|category| category.id)
+                    .ok_or_else(|| {
+                        CommandError::InvalidArguments(format!("unknown category `{}`", name))
+                    })?,
+            ),
+            _ => {
+                return Err(CommandError::InvalidArguments(
+                    "link the goal to exactly one of --account or --category".into(),
+                ))
+            }
+        };
+        GoalService::create(
+            ledger,
+            name,
+            target,
+            target_date,
+            link,
+            funding,
+            context.clock.as_ref(),
+        )
+        .map_err(CommandError::from)?;
+        let goal = ledger.goal(name).expect("goal exists after create");
+        let progress = GoalService::progress(ledger, goal, today);
+        Ok(formatters.format_amount(progress.required_monthly, ledger.base_currency().as_str()))
+    })?;
+    io::print_success(format!(
+        "Goal `{}` created. Save {} a month to reach it by {}.",
+        name.trim(),
+        required,
+        formatters.format_date(target_date)
+    ));
+    Ok(())
+}
+
+fn handle_list(context: &mut ShellContext) -> CommandResult {
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    let today = context.clock.today();
+    context.with_ledger(|ledger| {
+        if ledger.goals.is_empty() {
+            io::print_warning(
+                "No goals yet. Create one with `goal add <name> <target> <by> --account <name>`.",
+            );
+            return Ok(());
+        }
+        let currency = ledger.base_currency().as_str();
+        let mut table = Table::new(
+            Some("Goals"),
+            vec![
+                TableColumn::new("NAME", 18),
+                TableColumn::new("TARGET", 12),
+                TableColumn::new("BY", 12),
+                TableColumn::new("SAVED", 12),
+                TableColumn::new("PROGRESS", 9),
+                TableColumn::new("MONTHLY", 12),
+                TableColumn::new("REACHED ON", 12),
+                TableColumn::new("STATUS", 10),
+            ],
+        );
+        for progress in GoalService::progress_all(ledger, today) {
+            table.add_row(vec![
+                progress.name.clone(),
+                formatters.format_amount(progress.target_amount, currency),
+                formatters.format_date(progress.target_date),
+                formatters.format_amount(progress.saved, currency),
+                format!("{:.0}%", progress.percent),
+                formatters.format_amount(progress.required_monthly, currency),
+                progress
+                    .projected_completion
+                    .map(|date| formatters.format_date(date))
+                    .unwrap_or_else(|| "-".into()),
+                status_label(&progress).into(),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_show(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: goal show <name>".into(),
+        ));
+    };
+    let formatters = context.formatters.clone();
+    let today = context.clock.today();
+    context.with_ledger(|ledger| {
+        let goal = ledger
+            .goal(name)
+            .ok_or_else(|| CommandError::InvalidArguments(format!("goal `{}` not found", name)))?;
+        let progress = GoalService::progress(ledger, goal, today);
+        let currency = ledger.base_currency().as_str();
+        io::print_info(format!("Goal `{}`", goal.name));
+        io::print_info(format!("  Tracked by: {}", link_label(ledger, goal.link)));
+        io::print_info(format!(
+            "  Target: {} by {}",
+            formatters.format_amount(progress.target_amount, currency),
+            formatters.format_date(progress.target_date)
+        ));
+        io::print_info(format!(
+            "  Saved: {} ({:.0}%), {} to go",
+            formatters.format_amount(progress.saved, currency),
+            progress.percent,
+            formatters.format_amount(progress.remaining, currency)
+        ));
+        io::print_info(format!(
+            "  Needed: {} a month",
+            formatters.format_amount(progress.required_monthly, currency)
+        ));
+        match &goal.funding {
+            Some(funding) => io::print_info(format!(
+                "  Plan: {} {} from {}",
+                formatters.format_amount(funding.amount, currency),
+                funding.interval.label().to_lowercase(),
+                formatters.format_date(funding.start)
+            )),
+            None => io::print_info("  Plan: none; add `--contribute` to project a finish date"),
+        }
+        report_projection(&formatters, &progress);
+        Ok(())
+    })
+}
+
+fn report_projection(formatters: &CliFormatters, progress: &GoalProgress) {
+    if progress.is_reached() {
+        io::print_success("Target reached.");
+        return;
+    }
+    match progress.projected_completion {
+        Some(date) if progress.is_on_track() => io::print_success(format!(
+            "On track: the plan reaches the target on {}.",
+            formatters.format_date(date)
+        )),
+        Some(date) => io::print_warning(format!(
+            "Behind: the plan reaches the target on {}, after {}.",
+            formatters.format_date(date),
+            formatters.format_date(progress.target_date)
+        )),
+        None => {}
+    }
+}
+
+fn status_label(progress: &GoalProgress) -> &'static str {
+    if progress.is_reached() {
+        "Reached"
+    } else if progress.projected_completion.is_none() {
+        "No plan"
+    } else if progress.is_on_track() {
+        "On track"
+    } else {
+        "Behind"
+    }
+}
+
+fn link_label(ledger: &Ledger, link: GoalLink) -> String {
+    match link {
+        GoalLink::Account(id) => ledger
+            .account(id)
+            .map(|account| format!("account `{}`", account.name))
+            .unwrap_or_else(|| "(deleted account)".into()),
+        GoalLink::Category(id) => ledger
+            .category(id)
+            .map(|category| format!("category `{}`", category.name))
+            .unwrap_or_else(|| "(deleted category)".into()),
+    }
+}
+
+fn parse_amount(value: &str) -> Result<f64, CommandError> {
+    io::parse_number(value)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("invalid amount `{}`", value)))
+}
@@ -0,0 +1,67 @@
+//! Undo and redo of account, category, and transaction edits.
+
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![
+        CommandEntry::new(
+            "undo",
+            "Revert the latest account, category, or transaction edit",
+            "undo [list]",
+            cmd_undo,
+        ),
+        CommandEntry::new(
+            "redo",
+            "Apply the latest undone edit again",
+            "redo",
+            cmd_redo,
+        ),
+    ]
+}
+
+fn cmd_undo(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    match args {
+        [] => {
+            let undone = context.manager().undo().map_err(CommandError::from_core)?;
+            match undone {
+                Some(description) => io::print_success(format!("Undid {}.", description)),
+                None => io::print_info("Nothing to undo."),
+            }
+            Ok(())
+        }
+        [list] if list.eq_ignore_ascii_case("list") => print_history(context),
+        _ => Err(CommandError::InvalidArguments("usage: undo [list]".into())),
+    }
+}
+
+fn cmd_redo(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    if !args.is_empty() {
+        return Err(CommandError::InvalidArguments("usage: redo".into()));
+    }
+    let redone = context.manager().redo().map_err(CommandError::from_core)?;
+    match redone {
+        Some(description) => io::print_success(format!("Redid {}.", description)),
+        None => io::print_info("Nothing to redo."),
+    }
+    Ok(())
+}
+
+fn print_history(context: &ShellContext) -> CommandResult {
+    let (undo, redo) = context
+        .manager()
+        .journal_history()
+        .map_err(CommandError::from_core)?;
+    if undo.is_empty() && redo.is_empty() {
+        io::print_info("No edits recorded since the ledger was opened.");
+        return Ok(());
+    }
+    for (index, description) in undo.iter().enumerate() {
+        io::print_info(format!("  undo {:>2}: {}", index + 1, description));
+    }
+    for (index, description) in redo.iter().enumerate() {
+        io::print_info(format!("  redo {:>2}: {}", index + 1, description));
+    }
+    Ok(())
+}
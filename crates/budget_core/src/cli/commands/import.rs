@@ -0,0 +1,182 @@
+//! Guided migration from other budgeting apps.
+
+use std::{fs, fs::File, io::BufReader, path::Path};
+
+use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::core::services::ImportService;
+use crate::ledger::{AccountKind, Ledger};
+use bufy_core::{ImportBundle, ImportMapping, ImportReport, ImportTarget};
+
+const IMPORT_USAGE: &str = "usage: import <ynab <export.zip>|mint <transactions.csv>> [--yes]";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "import",
+        "Migrate accounts, categories, budgets, and transactions from YNAB or Mint",
+        "import <ynab <export.zip>|mint <transactions.csv>> [--yes]",
+        cmd_import,
+    )]
+}
+
+fn cmd_import(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    context.ensure_base_mode("Import")?;
+    let (flags, positional): (Vec<&str>, Vec<&str>) =
+        args.iter().partition(|arg| arg.starts_with("--"));
+    let assume_yes = match flags.as_slice() {
+        [] => false,
+        [flag] if flag.eq_ignore_ascii_case("--yes") => true,
+        _ => return Err(CommandError::InvalidArguments(IMPORT_USAGE.into())),
+    };
+    let [source, path] = positional.as_slice() else {
+        return Err(CommandError::InvalidArguments(IMPORT_USAGE.into()));
+    };
+    let path = Path::new(path);
+    let bundle = match source.to_ascii_lowercase().as_str() {
+        "ynab" => {
+            let file = File::open(path).map_err(|err| {
+                CommandError::InvalidArguments(format!("cannot open {}: {}", path.display(), err))
+            })?;
+            ImportService::read_ynab_zip(BufReader::new(file))
+        }
+        "mint" => {
+            let contents = fs::read_to_string(path).map_err(|err| {
+                CommandError::InvalidArguments(format!("cannot read {}: {}", path.display(), err))
+            })?;
+            ImportService::parse_mint(&contents)
+        }
+        other => {
+            return Err(CommandError::InvalidArguments(format!(
+                "unknown import source `{}`. Available: ynab, mint",
+                other
+            )))
+        }
+    }
+    .map_err(CommandError::from)?;
+    if bundle.transactions.is_empty() {
+        io::print_info(format!("The {} export has no transactions.", bundle.source));
+        return Ok(());
+    }
+
+    let interactive = context.mode() == CliMode::Interactive && !assume_yes;
+    let mapping = context.with_ledger(|ledger| {
+        let mut mapping = ImportMapping::suggest(ledger, &bundle);
+        if interactive {
+            prompt_mapping(ledger, &bundle, &mut mapping)?;
+        }
+        Ok(mapping)
+    })?;
+    if interactive
+        && !io::confirm_action(&format!(
+            "Import {} {} transaction(s)?",
+            bundle.transactions.len(),
+            bundle.source
+        ))
+        .map_err(CommandError::from)?
+    {
+        io::print_info("Import cancelled.");
+        return Ok(());
+    }
+
+    let report = context.with_ledger_mut(|ledger| {
+        ImportService::apply(ledger, &bundle, &mapping).map_err(CommandError::from)
+    })?;
+    print_report(&bundle, &report);
+    Ok(())
+}
+
+/// Summarizes what [`ImportService::apply`] changed.
+pub(crate) fn print_report(bundle: &ImportBundle, report: &ImportReport) {
+    io::print_success(format!(
+        "Imported {} transaction(s) from {}: {} account(s) and {} categor{} created, {} budget(s) set.",
+        report.transactions_imported,
+        bundle.source,
+        report.accounts_created,
+        report.categories_created,
+        if report.categories_created == 1 { "y" } else { "ies" },
+        report.budgets_set
+    ));
+    if report.duplicates_skipped > 0 {
+        io::print_info(format!(
+            "Skipped {} transaction(s) already in the ledger.",
+            report.duplicates_skipped
+        ));
+    }
+    if report.rows_skipped > 0 {
+        io::print_info(format!(
+            "Skipped {} transaction(s) from unmapped accounts.",
+            report.rows_skipped
+        ));
+    }
+}
+
+/// Asks where each imported account and category should go, starting from
+/// the suggested mapping.
+pub(crate) fn prompt_mapping(
+    ledger: &Ledger,
+    bundle: &ImportBundle,
+    mapping: &mut ImportMapping,
+) -> CommandResult {
+    let accounts: Vec<_> = ledger
+        .accounts
+        .iter()
+        .filter(|account| {
+            matches!(
+                account.kind,
+                AccountKind::Bank | AccountKind::Cash | AccountKind::Savings
+            )
+        })
+        .map(|account| (account.id, account.name.clone()))
+        .collect();
+    for (name, target) in mapping.accounts.iter_mut() {
+        *target = prompt_target(&format!("Account `{}`", name), *target, &accounts)?;
+    }
+
+    let categories: Vec<_> = ledger
+        .categories
+        .iter()
+        .filter(|category| !category.hidden)
+        .map(|category| (category.id, category.name.clone()))
+        .collect();
+    for category in bundle.categories() {
+        if let Some(target) = mapping.categories.get_mut(&category.name) {
+            *target = prompt_target(
+                &format!("Category `{}` ({})", category.name, category.kind),
+                *target,
+                &categories,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn prompt_target(
+    label: &str,
+    suggested: ImportTarget,
+    existing: &[(uuid::Uuid, String)],
+) -> Result<ImportTarget, CommandError> {
+    let mut targets = vec![suggested];
+    targets.push(ImportTarget::Create);
+    targets.extend(existing.iter().map(|(id, _)| ImportTarget::Existing(*id)));
+    targets.push(ImportTarget::Skip);
+    let mut seen = Vec::new();
+    targets.retain(|target| {
+        let fresh = !seen.contains(target);
+        seen.push(*target);
+        fresh
+    });
+    let describe = |target: &ImportTarget| match target {
+        ImportTarget::Create => "Create new".to_string(),
+        ImportTarget::Skip => "Skip".to_string(),
+        ImportTarget::Existing(id) => existing
+            .iter()
+            .find(|(candidate, _)| candidate == id)
+            .map(|(_, name)| format!("Use `{}`", name))
+            .unwrap_or_else(|| "Use existing".into()),
+    };
+    let mut options: Vec<String> = targets.iter().map(describe).collect();
+    options[0] = format!("{} (suggested)", options[0]);
+    let index = io::prompt_select_index(label, &options).map_err(CommandError::from)?;
+    Ok(targets[index])
+}
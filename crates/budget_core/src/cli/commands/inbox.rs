@@ -0,0 +1,213 @@
+//! Review queue for transaction drafts submitted by external clients.
+
+use uuid::Uuid;
+
+use crate::cli::core::{parse_date, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::DraftService;
+use crate::ledger::Ledger;
+use bufy_core::{CurrencyFormatter, DateFormatter};
+
+const DRAFT_FLAGS: &str =
+    "[--date D] [--amount N] [--payee P] [--from ACCOUNT] [--to ACCOUNT] [--category C] [--notes N]";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "inbox",
+        "Review transaction drafts submitted by other apps",
+        "inbox <list|edit <index> [flags]|approve <index> [flags]|discard <index>>",
+        cmd_inbox,
+    )]
+}
+
+fn cmd_inbox(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((subcommand, rest)) = args.split_first() else {
+        return handle_list(context);
+    };
+    match subcommand.to_ascii_lowercase().as_str() {
+        "list" | "ls" => handle_list(context),
+        "edit" => handle_edit(context, rest),
+        "approve" => handle_approve(context, rest),
+        "discard" | "remove" => handle_discard(context, rest),
+        other => Err(CommandError::InvalidArguments(format!(
+            "unknown inbox subcommand `{}`. Available: list, edit, approve, discard",
+            other
+        ))),
+    }
+}
+
+fn handle_list(context: &mut ShellContext) -> CommandResult {
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        if ledger.pending_drafts.is_empty() {
+            io::print_info("Inbox is empty.");
+            return Ok(());
+        }
+        let currency = ledger.base_currency().as_str();
+        let mut table = Table::new(
+            Some("Pending drafts"),
+            vec![
+                TableColumn::new("#", 4),
+                TableColumn::new("SOURCE", 10),
+                TableColumn::new("DATE", 12),
+                TableColumn::new("AMOUNT", 12),
+                TableColumn::new("PAYEE", 18),
+                TableColumn::new("CATEGORY", 14),
+                TableColumn::new("MISSING", 24),
+            ],
+        );
+        for (index, draft) in ledger.pending_drafts.iter().enumerate() {
+            let missing = draft.missing_fields();
+            table.add_row(vec![
+                index.to_string(),
+                draft.source.clone(),
+                draft
+                    .date
+                    .map(|date| formatters.format_date(date))
+                    .unwrap_or_else(|| "—".into()),
+                draft
+                    .amount
+                    .map(|amount| formatters.format_amount(amount, currency))
+                    .unwrap_or_else(|| "—".into()),
+                draft.payee.clone().unwrap_or_else(|| "—".into()),
+                draft
+                    .category_id
+                    .and_then(|id| ledger.category(id))
+                    .map(|category| category.name.clone())
+                    .unwrap_or_else(|| "—".into()),
+                if missing.is_empty() {
+                    "ready".into()
+                } else {
+                    missing.join(", ")
+                },
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_edit(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let usage = format!("usage: inbox edit <index> {}", DRAFT_FLAGS);
+    let [index, flags @ ..] = args else {
+        return Err(CommandError::InvalidArguments(usage));
+    };
+    if flags.is_empty() {
+        return Err(CommandError::InvalidArguments(usage));
+    }
+    let index = parse_index(index)?;
+    context.with_ledger_mut(|ledger| {
+        let id = draft_id(ledger, index)?;
+        apply_flags(ledger, id, flags, &usage)
+    })?;
+    io::print_success(format!("Draft [{}] updated.", index));
+    Ok(())
+}
+
+fn handle_approve(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    context.ensure_base_mode("Draft approval")?;
+    let usage = format!("usage: inbox approve <index> {}", DRAFT_FLAGS);
+    let [index, flags @ ..] = args else {
+        return Err(CommandError::InvalidArguments(usage));
+    };
+    let index = parse_index(index)?;
+    let transaction_index = context.with_ledger_mut(|ledger| {
+        let id = draft_id(ledger, index)?;
+        if !flags.is_empty() {
+            apply_flags(ledger, id, flags, &usage)?;
+        }
+        let txn_id = DraftService::approve(ledger, id).map_err(CommandError::from)?;
+        Ok(ledger
+            .transactions
+            .iter()
+            .position(|txn| txn.id == txn_id)
+            .unwrap_or_default())
+    })?;
+    io::print_success(format!(
+        "Draft [{}] approved as transaction [{}].",
+        index, transaction_index
+    ));
+    Ok(())
+}
+
+fn handle_discard(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [index] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: inbox discard <index>".into(),
+        ));
+    };
+    let index = parse_index(index)?;
+    context.with_ledger_mut(|ledger| {
+        let id = draft_id(ledger, index)?;
+        DraftService::discard(ledger, id).map_err(CommandError::from)
+    })?;
+    io::print_success(format!("Draft [{}] discarded.", index));
+    Ok(())
+}
+
+fn apply_flags(ledger: &mut Ledger, id: Uuid, flags: &[&str], usage: &str) -> CommandResult {
+    let mut edited = ledger
+        .pending_drafts
+        .iter()
+        .find(|draft| draft.id == id)
+        .cloned()
+        .ok_or_else(|| CommandError::InvalidArguments("draft not found".into()))?;
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        let value = *iter
+            .next()
+            .ok_or_else(|| CommandError::InvalidArguments(format!("{} requires a value", flag)))?;
+        match flag.to_ascii_lowercase().as_str() {
+            "--date" => edited.date = Some(parse_date(value)?),
+            "--amount" => {
+                edited.amount = Some(io::parse_number(value).ok_or_else(|| {
+                    CommandError::InvalidArguments(format!("invalid amount `{}`", value))
+                })?)
+            }
+            "--payee" => edited.payee = Some(value.to_string()),
+            "--notes" => edited.notes = Some(value.to_string()),
+            "--from" => edited.from_account = Some(account_id(ledger, value)?),
+            "--to" => edited.to_account = Some(account_id(ledger, value)?),
+            "--category" => {
+                edited.category_id = Some(
+                    ledger
+                        .categories
+                        .iter()
+                        .find(|category| category.name.eq_ignore_ascii_case(value))
+                        .map(|category| category.id)
+                        .ok_or_else(|| {
+                            CommandError::InvalidArguments(format!("unknown category `{}`", value))
+                        })?,
+                )
+            }
+            _ => return Err(CommandError::InvalidArguments(usage.into())),
+        }
+    }
+    DraftService::update(ledger, id, |draft| *draft = edited).map_err(CommandError::from)
+}
+
+fn account_id(ledger: &Ledger, name: &str) -> Result<Uuid, CommandError> {
+    ledger
+        .accounts
+        .iter()
+        .find(|account| account.name.eq_ignore_ascii_case(name))
+        .map(|account| account.id)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("unknown account `{}`", name)))
+}
+
+fn draft_id(ledger: &Ledger, index: usize) -> Result<Uuid, CommandError> {
+    ledger
+        .pending_drafts
+        .get(index)
+        .map(|draft| draft.id)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("no draft at index {}", index)))
+}
+
+fn parse_index(value: &str) -> Result<usize, CommandError> {
+    value
+        .parse::<usize>()
+        .map_err(|_| CommandError::InvalidArguments(format!("invalid draft index `{}`", value)))
+}
@@ -79,6 +79,7 @@ fn build_detail_view(entry: &LedgerMetadata) -> DetailView {
         .with_field("category_count", entry.category_count.to_string())
         .with_field("transaction_count", entry.transaction_count.to_string())
         .with_field("simulation_count", entry.simulation_count.to_string())
+        .with_field("indexed_at", entry.indexed_at.to_rfc3339())
 }
 
 fn build_actions() -> Vec<DetailAction> {
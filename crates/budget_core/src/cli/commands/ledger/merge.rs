@@ -0,0 +1,128 @@
+//! `ledger merge <base_path> <theirs_path> [--prefer ours|theirs]`: fold
+//! another copy of the open ledger into it, resolving conflicting edits.
+
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::core::services::LedgerService;
+use bufy_core::{MergeConflict, MergeSide};
+use bufy_domain::Money;
+
+const MERGE_USAGE: &str = "usage: ledger merge <base_path> <theirs_path> [--prefer ours|theirs]";
+
+pub(crate) fn handle_merge(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let mut paths = Vec::new();
+    let mut prefer = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg.eq_ignore_ascii_case("--prefer") {
+            prefer = match iter
+                .next()
+                .map(|value| value.to_ascii_lowercase())
+                .as_deref()
+            {
+                Some("ours") => Some(MergeSide::Ours),
+                Some("theirs") => Some(MergeSide::Theirs),
+                _ => return Err(CommandError::InvalidArguments(MERGE_USAGE.into())),
+            };
+        } else {
+            paths.push(*arg);
+        }
+    }
+    let [base_path, theirs_path] = paths[..] else {
+        return Err(CommandError::InvalidArguments(MERGE_USAGE.into()));
+    };
+
+    let (base, theirs) = {
+        let manager = context.manager();
+        let storage = manager.storage();
+        let base = storage
+            .load_ledger_from_path(Path::new(base_path))
+            .map_err(CommandError::from)?;
+        let theirs = storage
+            .load_ledger_from_path(Path::new(theirs_path))
+            .map_err(CommandError::from)?;
+        (base, theirs)
+    };
+    let ours = context.with_ledger(|ledger| Ok(ledger.clone()))?;
+    let mut merge = LedgerService::merge(&base, &theirs, &ours).map_err(CommandError::from)?;
+
+    if let Some(side) = prefer {
+        merge.resolve_all(side);
+    } else if context.mode() == CliMode::Interactive {
+        for index in 0..merge.conflicts().len() {
+            let conflict = &merge.conflicts()[index];
+            let options = [
+                format!("Keep ours: {}", value_label(conflict.ours.as_ref())),
+                format!("Take theirs: {}", value_label(conflict.theirs.as_ref())),
+            ];
+            let prompt = format!(
+                "Conflict {} of {}: {}",
+                index + 1,
+                merge.conflicts().len(),
+                conflict.describe()
+            );
+            let side = match io::prompt_select_index(&prompt, &options) {
+                Ok(1) => MergeSide::Theirs,
+                Ok(_) => MergeSide::Ours,
+                Err(_) => {
+                    io::print_info("Merge cancelled.");
+                    return Ok(());
+                }
+            };
+            merge.resolve(index, side).map_err(CommandError::from)?;
+        }
+    }
+
+    for (index, conflict) in merge.conflicts().iter().enumerate() {
+        print_conflict(index, conflict);
+    }
+    let conflicts = merge.conflicts().len();
+    let merged = merge.into_ledger().map_err(CommandError::from)?;
+    context.with_ledger_mut(|ledger| {
+        *ledger = merged;
+        ledger.touch();
+        Ok(())
+    })?;
+    if conflicts == 0 {
+        io::print_success(format!("Merged `{}` without conflicts.", theirs_path));
+    } else {
+        io::print_success(format!(
+            "Merged `{}`; {} conflict(s) resolved.",
+            theirs_path, conflicts
+        ));
+        if prefer.is_none() && context.mode() != CliMode::Interactive {
+            io::print_hint(
+                "Conflicts kept our values. Use --prefer theirs to take theirs instead.",
+            );
+        }
+    }
+    Ok(())
+}
+
+fn print_conflict(index: usize, conflict: &MergeConflict) {
+    io::print_info(format!(
+        "  {:>2}. {}: ours {}, theirs {} -> kept {}",
+        index + 1,
+        conflict.describe(),
+        value_label(conflict.ours.as_ref()),
+        value_label(conflict.theirs.as_ref()),
+        conflict.resolution
+    ));
+}
+
+/// Scalars and amounts print as-is; records and lists are summarized.
+fn value_label(value: Option<&Value>) -> String {
+    match value {
+        None => "(removed)".into(),
+        Some(Value::String(text)) => text.clone(),
+        Some(record @ Value::Object(_)) => Money::deserialize(record)
+            .map_or_else(|_| "(record)".into(), |amount| amount.to_string()),
+        Some(Value::Array(items)) => format!("({} item(s))", items.len()),
+        Some(other) => other.to_string(),
+    }
+}
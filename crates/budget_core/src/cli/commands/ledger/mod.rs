@@ -1,38 +1,67 @@
 //! Root ledger command plus list/summary/forecast entry points.
 
 pub mod list_ledgers;
+mod merge;
+pub mod summary_drilldown;
+
+pub(crate) use summary_drilldown::run_summary_drilldown;
 
 use std::path::PathBuf;
 
 use chrono::Utc;
 
 use crate::cli::commands::backup::list_backups;
-use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::core::{
+    parse_date, split_row_limit, CliMode, CommandError, CommandResult, ShellContext,
+};
 use crate::cli::io;
 use crate::cli::menus::{ledger_menu, menu_error_to_command_error};
 use crate::cli::registry::CommandEntry;
-use crate::core::services::SummaryService;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::{
+    AttachmentService, ExportService, ForecastService, RetentionService, SubLedgerService,
+    SummaryService,
+};
+use bufy_core::{CurrencyFormatter, LedgerChange, LOW_BALANCE_THRESHOLD};
+
+const FORECAST_PERIODS_USAGE: &str = "usage: forecast [simulation_name] periods <n>";
+const FORECAST_BALANCES_USAGE: &str =
+    "usage: forecast [simulation_name] balances [<number> <unit> | custom <start> <end>] [--min <amount>] [--daily]";
+const FORECAST_EXPORT_USAGE: &str =
+    "usage: forecast [simulation_name] export ics <path> [<number> <unit> | custom <start> <end>]";
+const AUDIT_USAGE: &str =
+    "usage: ledger audit [list] [<entity>] [--actor <name>] [--limit <n>|--full]";
+const PURGE_USAGE: &str =
+    "usage: ledger purge --before <YYYY-MM-DD> [--dry-run] [--confirm <ledger name>]";
+/// Newest audit entries shown without `--limit` or `--full`.
+const AUDIT_ROWS: usize = 20;
 
 pub(crate) fn definitions() -> Vec<CommandEntry> {
     vec![
         CommandEntry::new(
             "ledger",
             "Ledger operations (new, load, save, backup, restore...)",
-            "ledger <new|load|load-ledger|save|save-ledger|backup|list-backups|restore>",
+            "ledger <new|load|load-ledger|open-sample|save|save-ledger|backup|list-backups|restore|changes|audit|merge|purge>",
             cmd_ledger,
         ),
         CommandEntry::new(
             "summary",
             "Show ledger summary",
-            "summary [simulation_name] [past|future <n>] | summary custom <start YYYY-MM-DD> <end YYYY-MM-DD>",
+            "summary [simulation_name] [past|future <n>] [--by-member] [--by-tag] [--price-changes] [--expand-groups] [--rollup|--expand-categories] [--limit <n>|--full] | summary custom <start YYYY-MM-DD> <end YYYY-MM-DD>",
             cmd_summary,
         ),
         CommandEntry::new(
             "forecast",
             "Forecast upcoming activity",
-            "forecast [simulation_name] [<number> <unit> | custom <start YYYY-MM-DD> <end YYYY-MM-DD>]",
+            "forecast [simulation_name] [<number> <unit> | custom <start YYYY-MM-DD> <end YYYY-MM-DD> | periods <n> | balances [window] [--min <amount>] [--daily] | export ics <path> [window]] [--limit <n>|--full]",
             cmd_forecast,
         ),
+        CommandEntry::new(
+            "net-worth",
+            "Show net worth with allowance sub-ledgers consolidated",
+            "net-worth",
+            cmd_net_worth,
+        ),
     ]
 }
 
@@ -43,7 +72,7 @@ fn cmd_ledger(context: &mut ShellContext, args: &[&str]) -> CommandResult {
 
     if args.is_empty() {
         return Err(CommandError::InvalidArguments(
-            "usage: ledger <new|load|load-ledger|save|save-ledger|backup|list-backups|restore>"
+            "usage: ledger <new|load|load-ledger|open-sample|save|save-ledger|backup|list-backups|restore|changes|audit|merge|purge>"
                 .into(),
         ));
     }
@@ -57,13 +86,18 @@ fn dispatch_action(context: &mut ShellContext, subcommand: &str, args: &[&str])
         "new" => handle_new(context, args),
         "load" => handle_load(context, args),
         "load-ledger" | "load-named" => handle_load_named(context, args),
+        "open-sample" => handle_open_sample(context, args),
         "save" => handle_save(context, args),
         "save-ledger" | "save-named" => handle_save_named(context, args),
         "backup" | "backup-ledger" => handle_backup(context, args),
         "list-backups" | "backups" => handle_list_backups(context),
         "restore" | "restore-ledger" => handle_restore(context, args),
+        "changes" => handle_changes(context, args),
+        "audit" => handle_audit(context, args),
+        "merge" => merge::handle_merge(context, args),
+        "purge" => handle_purge(context, args),
         other => Err(CommandError::InvalidArguments(format!(
-            "unknown ledger subcommand `{}`. Available: new, load, load-ledger, save, save-ledger, backup, list-backups, restore",
+            "unknown ledger subcommand `{}`. Available: new, load, load-ledger, open-sample, save, save-ledger, backup, list-backups, restore, changes, audit, merge, purge",
             other
         ))),
     }
@@ -95,6 +129,34 @@ fn cmd_forecast(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     handle_forecast(context, args)
 }
 
+fn cmd_net_worth(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    if !args.is_empty() {
+        return Err(CommandError::InvalidArguments("usage: net-worth".into()));
+    }
+    let formatters = context.formatters.clone();
+    let today = context.clock.today();
+    context.with_ledger(|ledger| {
+        let worth = SubLedgerService::net_worth(ledger, today);
+        let currency = ledger.base_currency().as_str();
+        io::print_info(format!(
+            "Main accounts: {}",
+            formatters.format_amount(worth.main, currency)
+        ));
+        for (name, balance) in &worth.sub_ledgers {
+            io::print_info(format!(
+                "Allowance {}: {}",
+                name,
+                formatters.format_amount(*balance, currency)
+            ));
+        }
+        io::print_success(format!(
+            "Net worth: {}",
+            formatters.format_amount(worth.total, currency)
+        ));
+        Ok(())
+    })
+}
+
 fn handle_new(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     match context.mode() {
         CliMode::Interactive => context.run_new_ledger_interactive(),
@@ -139,6 +201,15 @@ fn handle_load_named(context: &mut ShellContext, args: &[&str]) -> CommandResult
     context.load_named_ledger(&name)
 }
 
+fn handle_open_sample(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    if !args.is_empty() {
+        return Err(CommandError::InvalidArguments(
+            "usage: ledger open-sample".into(),
+        ));
+    }
+    context.open_sample_ledger()
+}
+
 fn handle_save(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     if let Some(path) = args.first() {
         let path = PathBuf::from(path);
@@ -249,10 +320,291 @@ fn handle_restore(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     }
 }
 
+fn handle_changes(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let after = match args {
+        [] => 0,
+        [keyword, seq] if keyword.eq_ignore_ascii_case("after") => {
+            seq.parse::<u64>().map_err(|_| {
+                CommandError::InvalidArguments(format!("invalid sequence number `{}`", seq))
+            })?
+        }
+        [keyword] if keyword.eq_ignore_ascii_case("compact") => {
+            let event = context
+                .manager_mut()
+                .compact_change_feed()
+                .map_err(CommandError::from_core)?;
+            io::print_success(format!(
+                "Change feed compacted to a snapshot at sequence {}.",
+                event.seq
+            ));
+            return Ok(());
+        }
+        _ => {
+            return Err(CommandError::InvalidArguments(
+                "usage: ledger changes [after <seq>|compact]".into(),
+            ))
+        }
+    };
+    let (events, enabled) = {
+        let manager = context.manager();
+        let events = manager
+            .change_feed_events(after)
+            .map_err(CommandError::from_core)?;
+        (events, manager.change_feed_enabled())
+    };
+    if !enabled {
+        io::print_hint("The change feed is off. Turn it on with `config set change_feed on`.");
+    }
+    if events.is_empty() {
+        io::print_info("No change feed events.");
+        return Ok(());
+    }
+    let mut table = Table::new(
+        Some("Change feed"),
+        vec![
+            TableColumn::new("SEQ", 6),
+            TableColumn::new("RECORDED", 20),
+            TableColumn::new("CHANGE", 14),
+            TableColumn::new("TARGET", 48),
+        ],
+    );
+    for event in &events {
+        let (kind, target) = match &event.change {
+            LedgerChange::Snapshot { .. } => ("snapshot", String::new()),
+            LedgerChange::Upserted { collection, id, .. } => {
+                ("upserted", format!("{} {}", collection, id))
+            }
+            LedgerChange::Removed { collection, id } => {
+                ("removed", format!("{} {}", collection, id))
+            }
+            LedgerChange::FieldChanged { field, .. } => ("field_changed", field.clone()),
+        };
+        table.add_row(vec![
+            event.seq.to_string(),
+            event.recorded_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            kind.into(),
+            target,
+        ]);
+    }
+    TableRenderer::render(&table, &context.ui_style);
+    Ok(())
+}
+
+fn handle_audit(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let args = match args.split_first() {
+        Some((first, rest)) if first.eq_ignore_ascii_case("list") => rest,
+        _ => args,
+    };
+    let (limit, args) = split_row_limit(args)?;
+    let mut actor = None;
+    let mut entity = None;
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if arg.eq_ignore_ascii_case("--actor") {
+            actor =
+                Some(iter.next().ok_or_else(|| {
+                    CommandError::InvalidArguments("--actor requires a name".into())
+                })?);
+        } else if entity.is_none() {
+            entity = Some(arg);
+        } else {
+            return Err(CommandError::InvalidArguments(AUDIT_USAGE.into()));
+        }
+    }
+    let mut entries = context
+        .manager()
+        .audit_entries()
+        .map_err(CommandError::from_core)?;
+    entries.retain(|entry| {
+        entity.is_none_or(|entity| entry.matches_entity(entity))
+            && actor.is_none_or(|actor| entry.actor.eq_ignore_ascii_case(actor))
+    });
+    if entries.is_empty() {
+        io::print_info("No audit entries.");
+        return Ok(());
+    }
+    let shown = limit.resolve(AUDIT_ROWS).min(entries.len());
+    let mut table = Table::new(
+        Some("Audit log"),
+        vec![
+            TableColumn::new("SEQ", 6),
+            TableColumn::new("RECORDED", 20),
+            TableColumn::new("ACTOR", 12),
+            TableColumn::new("ACTION", 8),
+            TableColumn::new("ENTITY", 14),
+            TableColumn::new("RECORD", 20),
+            TableColumn::new("BEFORE", 10),
+            TableColumn::new("AFTER", 10),
+        ],
+    );
+    let snapshot = |id: &Option<String>| {
+        id.as_deref()
+            .map(|id| id.chars().take(8).collect())
+            .unwrap_or_else(|| "-".into())
+    };
+    for entry in &entries[entries.len() - shown..] {
+        let change = &entry.change;
+        table.add_row(vec![
+            entry.seq.to_string(),
+            entry.recorded_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            entry.actor.clone(),
+            change.action.to_string(),
+            change.entity.clone(),
+            change
+                .label
+                .clone()
+                .unwrap_or_else(|| change.entity_id.chars().take(8).collect()),
+            snapshot(&change.before),
+            snapshot(&change.after),
+        ]);
+    }
+    TableRenderer::render(&table, &context.ui_style);
+    if shown < entries.len() {
+        io::print_hint(format!(
+            "{} older entries hidden. Use --limit <n> or --full to see more.",
+            entries.len() - shown
+        ));
+    }
+    Ok(())
+}
+
 fn handle_overview(context: &mut ShellContext) -> CommandResult {
     list_ledgers::run_list_ledgers(context)
 }
 
+fn handle_purge(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let mut before = None;
+    let mut dry_run = false;
+    let mut confirmation = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.to_ascii_lowercase().as_str() {
+            "--before" => {
+                let value = iter.next().ok_or_else(|| {
+                    CommandError::InvalidArguments("--before requires a date".into())
+                })?;
+                before = Some(parse_date(value)?);
+            }
+            "--dry-run" => dry_run = true,
+            "--confirm" => {
+                confirmation = Some(*iter.next().ok_or_else(|| {
+                    CommandError::InvalidArguments("--confirm requires the ledger name".into())
+                })?);
+            }
+            _ => return Err(CommandError::InvalidArguments(PURGE_USAGE.into())),
+        }
+    }
+    let before = before.ok_or_else(|| CommandError::InvalidArguments(PURGE_USAGE.into()))?;
+    if before > context.clock.today() {
+        return Err(CommandError::InvalidArguments(
+            "--before cannot be later than today".into(),
+        ));
+    }
+
+    let report = context.with_ledger(|ledger| Ok(RetentionService::preview(ledger, before)))?;
+    let (name, backups) = {
+        let manager = context.manager();
+        let name = manager.current_name().map(str::to_string).ok_or_else(|| {
+            CommandError::InvalidArguments(
+                "save the ledger under a name with `ledger save-ledger <name>` before purging"
+                    .into(),
+            )
+        })?;
+        let backups = manager
+            .list_backups(&name)
+            .map_err(CommandError::from_core)?
+            .len();
+        (name, backups)
+    };
+    io::print_info(format!(
+        "{} transaction(s) dated before {} would be removed from `{}`; {} backup(s), the audit log, and the change feed are purged too.",
+        report.transactions, before, name, backups
+    ));
+    if report.recurring_kept > 0 {
+        io::print_info(format!(
+            "{} transaction(s) of recurring series are kept so the series do not schedule them again.",
+            report.recurring_kept
+        ));
+    }
+    if report.attachments > 0 {
+        io::print_info(format!(
+            "{} attached file(s) used only by those transactions would be deleted.",
+            report.attachments
+        ));
+    }
+    if report.assets > 0 {
+        io::print_info(format!(
+            "{} purchased-item record(s) on those transactions would be removed.",
+            report.assets
+        ));
+    }
+    if report.simulation_changes > 0 {
+        io::print_info(format!(
+            "{} simulation change(s) aimed at those transactions would be dropped.",
+            report.simulation_changes
+        ));
+    }
+    if dry_run {
+        io::print_hint("Dry run: nothing was changed.");
+        return Ok(());
+    }
+
+    let confirmed = match confirmation {
+        Some(typed) => typed == name,
+        None if context.mode() == CliMode::Interactive => {
+            io::print_warning("Purged data cannot be restored, not even from backups.");
+            io::confirm_action(&format!("Permanently purge data before {}?", before))
+                .map_err(CommandError::from)?
+                && io::prompt_text("Type the ledger name to confirm", None)
+                    .map_err(CommandError::from)?
+                    .is_some_and(|typed| typed.trim() == name)
+        }
+        None => {
+            io::print_hint(format!(
+                "Run `ledger purge --before {} --confirm {}` to purge.",
+                before, name
+            ));
+            return Ok(());
+        }
+    };
+    if !confirmed {
+        io::print_warning("Purge cancelled; the ledger name did not match.");
+        return Ok(());
+    }
+
+    let attachments_dir = context
+        .ledger_path()
+        .map(|path| AttachmentService::attachments_dir(&path));
+    let outcome = context
+        .manager_mut()
+        .purge_history(before, attachments_dir.as_deref())
+        .map_err(CommandError::from_core)?;
+    io::print_success(format!(
+        "Purged {} transaction(s) dated before {}; rewrote {} backup(s) and removed {} audit entr{}.",
+        outcome.report.transactions,
+        before,
+        outcome.backups_rewritten,
+        outcome.audit_entries_removed,
+        if outcome.audit_entries_removed == 1 { "y" } else { "ies" }
+    ));
+    if outcome.report.attachments > 0 {
+        io::print_info(format!(
+            "Deleted {} attached file(s) of purged transactions.",
+            outcome.report.attachments
+        ));
+    }
+    if outcome.change_feed_compacted {
+        io::print_info("The change feed was compacted to a snapshot of the purged ledger.");
+    }
+    if outcome.report.simulation_changes > 0 {
+        io::print_info(format!(
+            "Dropped {} simulation change(s) aimed at purged transactions.",
+            outcome.report.simulation_changes
+        ));
+    }
+    Ok(())
+}
+
 fn handle_delete(context: &mut ShellContext) -> CommandResult {
     list_ledgers::run_list_ledgers(context)
 }
@@ -262,17 +614,112 @@ fn handle_summary(context: &mut ShellContext, args: &[&str]) -> CommandResult {
 }
 
 fn handle_forecast(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let (limit, args) = split_row_limit(args)?;
+    let args = args.as_slice();
     context.with_ledger(|ledger| {
         let today = Utc::now().date_naive();
-        let (simulation, remainder) = if !args.is_empty() && ledger.simulation(args[0]).is_some() {
-            (Some(args[0]), &args[1..])
-        } else {
-            (None, args)
-        };
+        let (simulation, overlay, remainder) =
+            if !args.is_empty() && ledger.simulation(args[0]).is_some() {
+                (Some(args[0].to_string()), false, &args[1..])
+            } else {
+                let overlay = context.overlay_simulation();
+                let active = overlay.is_some();
+                (overlay, active, args)
+            };
+        if let Some((first, rest)) = remainder.split_first() {
+            if first.eq_ignore_ascii_case("periods") {
+                let [count] = rest else {
+                    return Err(CommandError::InvalidArguments(
+                        FORECAST_PERIODS_USAGE.into(),
+                    ));
+                };
+                let count = count
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|count| *count > 0)
+                    .ok_or_else(|| CommandError::InvalidArguments(FORECAST_PERIODS_USAGE.into()))?;
+                let periods = ForecastService::periods(ledger, today, count, simulation.as_deref())
+                    .map_err(CommandError::from)?;
+                let base = if overlay {
+                    Some(
+                        ForecastService::periods(ledger, today, count, None)
+                            .map_err(CommandError::from)?,
+                    )
+                } else {
+                    None
+                };
+                context.print_forecast_periods(
+                    ledger,
+                    simulation.as_deref(),
+                    &periods,
+                    base.as_deref(),
+                );
+                return Ok(());
+            }
+            if first.eq_ignore_ascii_case("balances") {
+                let mut minimum = LOW_BALANCE_THRESHOLD;
+                let mut daily = false;
+                let mut window = Vec::new();
+                let mut iter = rest.iter();
+                while let Some(arg) = iter.next() {
+                    if arg.eq_ignore_ascii_case("--min") {
+                        minimum = iter
+                            .next()
+                            .and_then(|value| io::parse_number(value))
+                            .ok_or_else(|| {
+                                CommandError::InvalidArguments(FORECAST_BALANCES_USAGE.into())
+                            })?;
+                    } else if arg.eq_ignore_ascii_case("--daily") {
+                        daily = true;
+                    } else {
+                        window.push(*arg);
+                    }
+                }
+                let window = context.resolve_forecast_window(&window, today)?;
+                let forecast =
+                    ForecastService::balances(ledger, window, today, simulation.as_deref())
+                        .map_err(CommandError::from)?;
+                context.print_balance_forecast(
+                    ledger,
+                    simulation.as_deref(),
+                    &forecast,
+                    minimum,
+                    daily,
+                );
+                return Ok(());
+            }
+            if first.eq_ignore_ascii_case("export") {
+                let [format, path, window @ ..] = rest else {
+                    return Err(CommandError::InvalidArguments(FORECAST_EXPORT_USAGE.into()));
+                };
+                if !format.eq_ignore_ascii_case("ics") {
+                    return Err(CommandError::InvalidArguments(FORECAST_EXPORT_USAGE.into()));
+                }
+                let window = context.resolve_forecast_window(window, today)?;
+                let events =
+                    ExportService::forecast_events(ledger, window, simulation.as_deref(), today)
+                        .map_err(CommandError::from)?;
+                std::fs::write(
+                    path,
+                    ExportService::events_ics(ledger, &events, context.clock.as_ref()),
+                )?;
+                io::print_success(format!(
+                    "Exported {} calendar event(s) to {}.",
+                    events.len(),
+                    path
+                ));
+                return Ok(());
+            }
+        }
         let window = context.resolve_forecast_window(remainder, today)?;
-        let report = SummaryService::forecast_window(ledger, window, today, simulation)
+        let report = SummaryService::forecast_window(ledger, window, today, simulation.as_deref())
             .map_err(CommandError::from)?;
-        context.print_forecast_report(ledger, simulation, &report);
+        context.print_forecast_report(ledger, simulation.as_deref(), &report, limit);
+        if overlay {
+            let base = SummaryService::forecast_window(ledger, window, today, None)
+                .map_err(CommandError::from)?;
+            context.print_forecast_overlay(ledger, &base, &report);
+        }
         Ok(())
     })
 }
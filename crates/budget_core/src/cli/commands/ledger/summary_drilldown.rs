@@ -0,0 +1,202 @@
+//! Interactive drill-down from a rendered budget summary into category
+//! transactions and individual transaction details.
+
+use uuid::Uuid;
+
+use crate::cli::commands::transaction::list_transactions::{
+    build_detail_view, build_table, gather_matching_entries,
+};
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io as cli_io;
+use crate::cli::ui::formatting::Formatter;
+use crate::cli::ui::list_interaction::{select_row, RowSelection};
+use crate::cli::ui::prompts;
+use crate::cli::ui::table_renderer::{Alignment, Table, TableColumn};
+use crate::ledger::{BudgetSummary, CategoryBudget, DateWindow, Transaction};
+use bufy_core::CurrencyFormatter;
+use bufy_domain::transaction::DateBasis;
+
+const BREADCRUMB_SEPARATOR: &str = " › ";
+
+#[derive(Clone)]
+enum DrillLevel {
+    Categories,
+    Transactions {
+        /// The selected category and, for rolled-up rows, its subcategories.
+        category_ids: Vec<Option<Uuid>>,
+        name: String,
+    },
+    Detail {
+        transaction_id: Uuid,
+        label: String,
+    },
+}
+
+impl DrillLevel {
+    fn crumb(&self) -> &str {
+        match self {
+            DrillLevel::Categories => "Summary",
+            DrillLevel::Transactions { name, .. } => name,
+            DrillLevel::Detail { label, .. } => label,
+        }
+    }
+}
+
+/// Runs the summary → category → transaction navigation stack. ESC pops one
+/// level; leaving the category list returns to the caller.
+pub fn run_summary_drilldown(context: &ShellContext, summary: &BudgetSummary) -> CommandResult {
+    if summary.per_category.is_empty() {
+        return context.await_menu_escape();
+    }
+
+    let window = summary.window;
+    let basis = context.with_ledger(|ledger| Ok(ledger.summary_date_basis))?;
+    let mut stack = vec![DrillLevel::Categories];
+    while let Some(level) = stack.last().cloned() {
+        print_breadcrumbs(&stack);
+        match level {
+            DrillLevel::Categories => {
+                match select_row("summary_categories", &category_table(context, summary)) {
+                    RowSelection::Exit => {
+                        stack.pop();
+                    }
+                    RowSelection::Index(index) => {
+                        let entry = &summary.per_category[index];
+                        stack.push(DrillLevel::Transactions {
+                            category_ids: row_category_ids(entry),
+                            name: entry.name.clone(),
+                        });
+                    }
+                }
+            }
+            DrillLevel::Transactions { category_ids, .. } => {
+                let entries = gather_matching_entries(context, |txn| {
+                    category_ids.contains(&txn.category_id) && touches_window(txn, &window, basis)
+                })?;
+                if entries.is_empty() {
+                    cli_io::print_warning("No transactions for this category in the window.");
+                    stack.pop();
+                    continue;
+                }
+                match select_row("summary_transactions", &build_table(&entries)) {
+                    RowSelection::Exit => {
+                        stack.pop();
+                    }
+                    RowSelection::Index(index) => {
+                        let entry = &entries[index];
+                        stack.push(DrillLevel::Detail {
+                            transaction_id: entry.id(),
+                            label: entry.date_label().to_string(),
+                        });
+                    }
+                }
+            }
+            DrillLevel::Detail { transaction_id, .. } => {
+                let entries = gather_matching_entries(context, |txn| txn.id == transaction_id)?;
+                if let Some(entry) = entries.first() {
+                    let _ = cli_io::println_text("");
+                    let _ = cli_io::println_text(&build_detail_view(entry).render());
+                    Formatter::new().print_detail("Press ESC to go back.");
+                    prompts::wait_for_escape().map_err(CommandError::Io)?;
+                }
+                stack.pop();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn row_category_ids(entry: &CategoryBudget) -> Vec<Option<Uuid>> {
+    let mut ids = vec![entry.category_id];
+    for child in &entry.children {
+        ids.extend(row_category_ids(child));
+    }
+    ids
+}
+
+/// Whether the summary counted `txn` in `window`: by its scheduled date, or
+/// by the date the ledger's summary basis uses for actual amounts.
+fn touches_window(txn: &Transaction, window: &DateWindow, basis: DateBasis) -> bool {
+    window.contains(txn.scheduled_date)
+        || txn
+            .reporting_date(basis)
+            .is_some_and(|date| window.contains(date))
+}
+
+fn print_breadcrumbs(stack: &[DrillLevel]) {
+    let trail = stack
+        .iter()
+        .map(DrillLevel::crumb)
+        .collect::<Vec<_>>()
+        .join(BREADCRUMB_SEPARATOR);
+    Formatter::new().print_detail(trail);
+}
+
+fn category_table(context: &ShellContext, summary: &BudgetSummary) -> Table {
+    let formatters = &context.formatters;
+    let rows = summary
+        .per_category
+        .iter()
+        .map(|entry| {
+            vec![
+                entry.name.clone(),
+                formatters.format_amount(entry.totals.budgeted, ""),
+                formatters.format_amount(entry.totals.real, ""),
+                format!("{:?}", entry.totals.status),
+            ]
+        })
+        .collect();
+
+    Table {
+        columns: vec![
+            TableColumn {
+                header: "CATEGORY".into(),
+                min_width: 16,
+                max_width: None,
+                alignment: Alignment::Left,
+            },
+            TableColumn {
+                header: "BUDGETED".into(),
+                min_width: 12,
+                max_width: None,
+                alignment: Alignment::Right,
+            },
+            TableColumn {
+                header: "REAL".into(),
+                min_width: 12,
+                max_width: None,
+                alignment: Alignment::Right,
+            },
+            TableColumn {
+                header: "STATUS".into(),
+                min_width: 10,
+                max_width: None,
+                alignment: Alignment::Left,
+            },
+        ],
+        rows,
+        show_headers: true,
+        padding: 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bufy_domain::{currency::CurrencyCode, Money};
+    use chrono::NaiveDate;
+
+    #[test]
+    fn transactions_follow_the_summary_date_basis() {
+        let date = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+        let march = DateWindow::new(date(3, 1), date(4, 1)).unwrap();
+        let amount = Money::from_minor(2000, CurrencyCode::new("USD"));
+        let mut txn = Transaction::new(Uuid::nil(), Uuid::nil(), None, date(2, 27), amount.clone());
+        txn.mark_completed(date(2, 28), amount);
+        txn.booking_date = Some(date(3, 2));
+
+        assert!(!touches_window(&txn, &march, DateBasis::Actual));
+        assert!(touches_window(&txn, &march, DateBasis::Booking));
+        assert!(!touches_window(&txn, &march, DateBasis::Value));
+    }
+}
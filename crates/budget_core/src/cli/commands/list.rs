@@ -1,21 +1,25 @@
 use std::collections::HashMap;
 
+use crate::cli::commands::ordering::favorite_marker;
+use crate::cli::commands::transaction::filters::{
+    transactions_json, transactions_table, TransactionFilterArgs,
+};
 use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
-use crate::cli::formatters::CliFormatters;
 use crate::cli::io as cli_io;
 use crate::cli::menus::{list_menu, menu_error_to_command_error};
 use crate::cli::registry::CommandEntry;
 use crate::cli::ui::{Table, TableColumn, TableRenderer};
 use crate::core::errors::CliError;
-use crate::core::services::BudgetService;
-use crate::ledger::{Ledger, TimeInterval, Transaction};
+use crate::core::services::{BudgetService, SimulationService, TransactionService};
+use crate::ledger::{Account, AccountStatus, SimulationChange, TimeInterval, Transaction};
 use bufy_core::{CurrencyFormatter, DateFormatter};
+use uuid::Uuid;
 
 pub(crate) fn definitions() -> Vec<CommandEntry> {
     vec![CommandEntry::new(
         "list",
         "List accounts, categories, transactions, simulations, ledgers...",
-        "list <accounts|categories|transactions|simulations|ledgers|backups|recurring>",
+        "list <accounts [--include-closed]|categories [--all]|transactions [filters]|simulations|ledgers|backups|recurring>",
         cmd_list,
     )]
 }
@@ -41,9 +45,17 @@ pub fn handle_list_command(context: &ShellContext, args: &[&str]) -> Result<(),
         .to_ascii_lowercase();
     match target.as_str() {
         "ledgers" => list_ledgers(context),
-        "accounts" => list_accounts(context),
-        "categories" => list_categories(context),
-        "transactions" => list_transactions(context),
+        "accounts" => list_accounts(
+            context,
+            args.get(1)
+                .is_some_and(|flag| flag.eq_ignore_ascii_case("--include-closed")),
+        ),
+        "categories" => list_categories(
+            context,
+            args.get(1)
+                .is_some_and(|flag| flag.eq_ignore_ascii_case("--all")),
+        ),
+        "transactions" => list_transactions(context, args.get(1..).unwrap_or_default()),
         "simulations" => list_simulations(context),
         "backups" => list_backups(context),
         "recurring" => list_recurring(context),
@@ -79,7 +91,15 @@ fn list_ledgers(context: &ShellContext) -> Result<(), CliError> {
     Ok(())
 }
 
-fn list_accounts(context: &ShellContext) -> Result<(), CliError> {
+/// Suffix marking a closed account in listings.
+pub(crate) fn closed_marker(account: &Account) -> String {
+    match account.status {
+        AccountStatus::Active => String::new(),
+        AccountStatus::Closed { on } => format!(" (closed {})", on),
+    }
+}
+
+fn list_accounts(context: &ShellContext, include_closed: bool) -> Result<(), CliError> {
     let style = context.ui_style.clone();
     let formatters = context.formatters.clone();
     context
@@ -106,7 +126,11 @@ fn list_accounts(context: &ShellContext) -> Result<(), CliError> {
                 ],
             );
 
-            for account in &ledger.accounts {
+            for account in ledger
+                .accounts
+                .iter()
+                .filter(|account| include_closed || !account.is_closed())
+            {
                 let category = account
                     .category_id
                     .and_then(|id| ledger.category(id))
@@ -117,7 +141,12 @@ fn list_accounts(context: &ShellContext) -> Result<(), CliError> {
                     .map(|entry| (entry.budgeted, entry.real))
                     .unwrap_or((0.0, 0.0));
                 table.add_row(vec![
-                    account.name.clone(),
+                    format!(
+                        "{}{}{}",
+                        favorite_marker(account.favorite),
+                        style.apply_entity_style(&account.name, &account.appearance),
+                        closed_marker(account)
+                    ),
                     account.kind.to_string(),
                     category,
                     formatters.format_amount(totals.0, ""),
@@ -131,7 +160,7 @@ fn list_accounts(context: &ShellContext) -> Result<(), CliError> {
         .map_err(CliError::from)
 }
 
-fn list_categories(context: &ShellContext) -> Result<(), CliError> {
+fn list_categories(context: &ShellContext, show_all: bool) -> Result<(), CliError> {
     let style = context.ui_style.clone();
     let formatters = context.formatters.clone();
     context
@@ -158,22 +187,28 @@ fn list_categories(context: &ShellContext) -> Result<(), CliError> {
                 .filter_map(|entry| entry.category_id.map(|id| (id, entry.totals.clone())))
                 .collect();
 
-            for category in &ledger.categories {
+            for category in ledger
+                .categories
+                .iter()
+                .filter(|category| show_all || !category.hidden)
+            {
                 let budget = category
                     .budget
                     .as_ref()
-                    .map(|budget| formatters.format_amount(budget.amount, ""))
+                    .map(|budget| formatters.format_amount(budget.amount.to_major(), ""))
                     .unwrap_or_else(|| "—".into());
                 let spent = totals
                     .get(&category.id)
                     .map(|entry| formatters.format_amount(entry.real, ""))
                     .unwrap_or_else(|| formatters.format_amount(0.0, ""));
-                table.add_row(vec![
-                    category.name.clone(),
-                    category.kind.to_string(),
-                    budget,
-                    spent,
-                ]);
+                let marker = favorite_marker(category.favorite);
+                let label = style.apply_entity_style(&category.name, &category.appearance);
+                let name = if category.hidden {
+                    format!("{}{} (hidden)", marker, label)
+                } else {
+                    format!("{}{}", marker, label)
+                };
+                table.add_row(vec![name, category.kind.to_string(), budget, spent]);
             }
 
             TableRenderer::render(&table, &style);
@@ -182,66 +217,79 @@ fn list_categories(context: &ShellContext) -> Result<(), CliError> {
         .map_err(CliError::from)
 }
 
-fn list_transactions(context: &ShellContext) -> Result<(), CliError> {
+fn list_transactions(context: &ShellContext, args: &[&str]) -> Result<(), CliError> {
+    let filters = TransactionFilterArgs::parse(args)?;
     let style = context.ui_style.clone();
     let formatters = context.formatters.clone();
     context
         .with_ledger(|ledger| {
-            if ledger.transactions.is_empty() {
-                cli_io::print_warning("No transactions recorded.");
+            let query = filters.resolve(ledger)?;
+            let transactions = TransactionService::query(ledger, &query);
+            if filters.json {
+                cli_io::println_text(&transactions_json(&transactions)?)?;
                 return Ok(());
             }
-            let account_names: HashMap<_, _> = ledger
-                .accounts
-                .iter()
-                .map(|acct| (acct.id, acct.name.clone()))
-                .collect();
-
-            let mut table = Table::new(
-                Some("Transactions"),
-                vec![
-                    TableColumn::new("DATE", 12),
-                    TableColumn::new("FROM", 16),
-                    TableColumn::new("TO", 16),
-                    TableColumn::new("CATEGORY", 18),
-                    TableColumn::new("BUDGETED", 12),
-                    TableColumn::new("STATUS", 10),
-                ],
-            );
-            for txn in &ledger.transactions {
-                table.add_row(transaction_row(txn, ledger, &account_names, &formatters));
+            if transactions.is_empty() {
+                if filters.is_unfiltered() {
+                    cli_io::print_warning("No transactions recorded.");
+                } else {
+                    cli_io::print_warning("No transactions match the supplied filters.");
+                }
+                return Ok(());
             }
+            let table = transactions_table(
+                "Transactions",
+                ledger,
+                &transactions,
+                &filters.columns_or_default(),
+                &formatters,
+            );
             TableRenderer::render(&table, &style);
+            if let Some(name) = context.overlay_simulation() {
+                let simulated = SimulationService::run(ledger, &name)?;
+                let touched: Vec<Uuid> = SimulationService::change_chain(ledger, &name)?
+                    .iter()
+                    .filter_map(|change| match change {
+                        SimulationChange::AddTransaction { transaction } => Some(transaction.id),
+                        other => other.target_transaction(),
+                    })
+                    .collect();
+                let changed: Vec<&Transaction> = TransactionService::query(&simulated, &query)
+                    .into_iter()
+                    .filter(|txn| touched.contains(&txn.id))
+                    .collect();
+                let excluded = transactions
+                    .iter()
+                    .filter(|txn| simulated.transaction(txn.id).is_none())
+                    .count();
+                if changed.is_empty() && excluded == 0 {
+                    cli_io::print_info(format!(
+                        "Simulation `{}` does not change these transactions.",
+                        name
+                    ));
+                }
+                if !changed.is_empty() {
+                    let table = transactions_table(
+                        format!("Simulated in `{}`", name),
+                        &simulated,
+                        &changed,
+                        &filters.columns_or_default(),
+                        &formatters,
+                    );
+                    TableRenderer::render(&table, &style);
+                }
+                if excluded > 0 {
+                    cli_io::print_info(format!(
+                        "{} transaction(s) excluded in `{}`.",
+                        excluded, name
+                    ));
+                }
+            }
             Ok(())
         })
         .map_err(CliError::from)
 }
 
-fn transaction_row(
-    txn: &Transaction,
-    ledger: &Ledger,
-    account_names: &HashMap<uuid::Uuid, String>,
-    formatters: &CliFormatters,
-) -> Vec<String> {
-    vec![
-        formatters.format_date(txn.scheduled_date),
-        account_names
-            .get(&txn.from_account)
-            .cloned()
-            .unwrap_or_else(|| "Unknown".into()),
-        account_names
-            .get(&txn.to_account)
-            .cloned()
-            .unwrap_or_else(|| "Unknown".into()),
-        txn.category_id
-            .and_then(|id| ledger.category(id))
-            .map(|cat| cat.name.clone())
-            .unwrap_or_else(|| "—".into()),
-        formatters.format_amount(txn.budgeted_amount, ""),
-        txn.status.to_string(),
-    ]
-}
-
 fn list_simulations(context: &ShellContext) -> Result<(), CliError> {
     let style = context.ui_style.clone();
     let formatters = context.formatters.clone();
@@ -0,0 +1,156 @@
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::entity_ref::expand_id_flags;
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::MemberService;
+use crate::ledger::Ledger;
+
+const MEMBER_USAGE: &str = "usage: member <add|list|rename|remove|assign>";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "member",
+        "Manage household members and transaction attribution",
+        "member <add <name>|list|rename <name> <new>|remove <name>|assign <transaction_index> <name|none>>",
+        cmd_member,
+    )]
+}
+
+fn cmd_member(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let expanded = expand_id_flags(args);
+    let args: &[&str] = &expanded.iter().map(|arg| arg.as_ref()).collect::<Vec<_>>();
+    let Some((subcommand, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(MEMBER_USAGE.into()));
+    };
+    match subcommand.to_ascii_lowercase().as_str() {
+        "add" => handle_add(context, rest),
+        "list" | "ls" => handle_list(context),
+        "rename" => handle_rename(context, rest),
+        "remove" | "delete" => handle_remove(context, rest),
+        "assign" => handle_assign(context, rest),
+        other => Err(CommandError::InvalidArguments(format!(
+            "unknown member subcommand `{}`. Available: add, list, rename, remove, assign",
+            other
+        ))),
+    }
+}
+
+fn handle_add(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: member add <name>".into(),
+        ));
+    };
+    context
+        .with_ledger_mut(|ledger| MemberService::add(ledger, name).map_err(CommandError::from))?;
+    io::print_success(format!("Member `{}` added.", name.trim()));
+    Ok(())
+}
+
+fn handle_list(context: &mut ShellContext) -> CommandResult {
+    let style = context.ui_style.clone();
+    context.with_ledger(|ledger| {
+        if ledger.members.is_empty() {
+            io::print_warning("No household members defined.");
+            return Ok(());
+        }
+        let mut table = Table::new(
+            Some("Members"),
+            vec![
+                TableColumn::new("NAME", 24),
+                TableColumn::new("TRANSACTIONS", 12),
+            ],
+        );
+        for member in &ledger.members {
+            let count = ledger
+                .transactions
+                .iter()
+                .filter(|txn| txn.member_id == Some(member.id))
+                .count();
+            table.add_row(vec![member.name.clone(), count.to_string()]);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_rename(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name, new_name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: member rename <name> <new-name>".into(),
+        ));
+    };
+    context.with_ledger_mut(|ledger| {
+        let id = member_id(ledger, name)?;
+        MemberService::rename(ledger, id, new_name).map_err(CommandError::from)
+    })?;
+    io::print_success(format!(
+        "Member `{}` renamed to `{}`.",
+        name,
+        new_name.trim()
+    ));
+    Ok(())
+}
+
+fn handle_remove(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: member remove <name>".into(),
+        ));
+    };
+    let removed = context.with_ledger_mut(|ledger| {
+        let id = member_id(ledger, name)?;
+        MemberService::remove(ledger, id).map_err(CommandError::from)
+    })?;
+    io::print_success(format!(
+        "Member `{}` removed; their transactions are now unassigned.",
+        removed.name
+    ));
+    Ok(())
+}
+
+fn handle_assign(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    context.ensure_base_mode("Member assignment")?;
+    let usage = "usage: member assign <transaction_index> <name|none>";
+    let [index, name] = args else {
+        return Err(CommandError::InvalidArguments(usage.into()));
+    };
+    let Some(index) = context.transaction_index_from_arg(Some(index), usage, "")? else {
+        return Ok(());
+    };
+    let clear = name.eq_ignore_ascii_case("none");
+    let assigned = context.with_ledger_mut(|ledger| {
+        let txn_id = ledger
+            .transactions
+            .get(index)
+            .map(|txn| txn.id)
+            .ok_or_else(|| {
+                CommandError::InvalidArguments("transaction index out of range".into())
+            })?;
+        let member = if clear {
+            None
+        } else {
+            Some(member_id(ledger, name)?)
+        };
+        MemberService::assign(ledger, txn_id, member).map_err(CommandError::from)?;
+        Ok(member
+            .and_then(|id| ledger.member(id))
+            .map(|member| member.name.clone()))
+    })?;
+    match assigned {
+        Some(member) => io::print_success(format!(
+            "Transaction [{}] attributed to `{}`.",
+            index, member
+        )),
+        None => io::print_success(format!("Transaction [{}] is no longer attributed.", index)),
+    }
+    Ok(())
+}
+
+fn member_id(ledger: &Ledger, name: &str) -> Result<uuid::Uuid, CommandError> {
+    ledger
+        .member_by_name(name)
+        .map(|member| member.id)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("unknown member `{}`", name)))
+}
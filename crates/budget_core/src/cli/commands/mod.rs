@@ -1,13 +1,33 @@
 pub mod account;
+pub mod allowance;
+pub mod assertion;
 pub mod backup;
+pub mod calendar;
 pub mod category;
 pub mod config;
+pub mod contracts;
+pub mod currency;
+pub mod export;
+pub mod fund;
+pub mod goal;
+pub mod history;
+pub mod import;
+pub mod inbox;
 pub mod ledger;
 pub mod list;
+pub mod member;
+pub(crate) mod ordering;
+pub mod palette;
+pub mod paycheck;
 pub mod recurring;
+pub mod report;
+pub mod review;
+pub mod rules;
 pub mod simulation;
+pub mod sync;
 pub mod system;
 pub mod transaction;
+pub mod view;
 
 use crate::cli::registry::{CommandEntry, CommandRegistry};
 
@@ -16,11 +36,32 @@ const ROOT_COMMAND_ORDER: &[&str] = &[
     "account",
     "category",
     "transaction",
+    "inbox",
+    "import",
+    "sync",
+    "member",
+    "allowance",
+    "fund",
+    "goal",
+    "contracts",
+    "paycheck",
     "simulation",
     "list",
+    "view",
+    "rules",
     "summary",
+    "report",
+    "export",
+    "review",
     "forecast",
+    "net-worth",
+    "currency",
+    "calendar",
+    "assert",
+    "undo",
+    "redo",
     "config",
+    "palette",
     "help",
     "version",
     "exit",
@@ -33,8 +74,27 @@ pub(crate) fn all_entries() -> Vec<CommandEntry> {
     commands.extend(account::definitions());
     commands.extend(category::definitions());
     commands.extend(transaction::definitions());
+    commands.extend(inbox::definitions());
+    commands.extend(import::definitions());
+    commands.extend(sync::definitions());
+    commands.extend(member::definitions());
+    commands.extend(allowance::definitions());
+    commands.extend(fund::definitions());
+    commands.extend(goal::definitions());
+    commands.extend(contracts::definitions());
+    commands.extend(paycheck::definitions());
     commands.extend(simulation::definitions());
+    commands.extend(view::definitions());
+    commands.extend(rules::definitions());
+    commands.extend(report::definitions());
+    commands.extend(export::definitions());
+    commands.extend(review::definitions());
+    commands.extend(assertion::definitions());
+    commands.extend(history::definitions());
+    commands.extend(currency::definitions());
+    commands.extend(calendar::definitions());
     commands.extend(config::definitions());
+    commands.extend(palette::definitions());
     commands.extend(system::definitions());
     commands
 }
@@ -0,0 +1,316 @@
+//! `account|category move <name> <up|down|top|bottom>`,
+//! `account|category star|unstar <name>`, and
+//! `account|category style <name> [--color <color>] [--icon <icon>]`:
+//! arrange the display order every list and picker follows, with starred
+//! items pinned to the top, and mark items with a color and icon.
+
+use uuid::Uuid;
+
+use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::core::services::{AccountService, CategoryService};
+use crate::ledger::{Appearance, EntityColor};
+use bufy_domain::MoveDirection;
+
+const DIRECTIONS: [MoveDirection; 4] = [
+    MoveDirection::Up,
+    MoveDirection::Down,
+    MoveDirection::Top,
+    MoveDirection::Bottom,
+];
+
+/// Which ledger list a command arranges.
+#[derive(Clone, Copy)]
+pub(crate) enum Ordered {
+    Account,
+    Category,
+}
+
+impl Ordered {
+    fn noun(self) -> &'static str {
+        match self {
+            Ordered::Account => "account",
+            Ordered::Category => "category",
+        }
+    }
+
+    fn select(self, context: &ShellContext, prompt: &str) -> Result<Option<Uuid>, CommandError> {
+        match self {
+            Ordered::Account => context.select_account_id(prompt),
+            Ordered::Category => context.select_category_id(prompt),
+        }
+    }
+
+    /// Id and name of the entry with id `needle`, or named `needle`.
+    fn lookup(
+        self,
+        context: &ShellContext,
+        needle: Result<&str, Uuid>,
+    ) -> Result<(Uuid, String), CommandError> {
+        context.with_ledger(|ledger| {
+            let entries: Vec<(Uuid, &str)> = match self {
+                Ordered::Account => ledger
+                    .accounts
+                    .iter()
+                    .map(|account| (account.id, account.name.as_str()))
+                    .collect(),
+                Ordered::Category => ledger
+                    .categories
+                    .iter()
+                    .map(|category| (category.id, category.name.as_str()))
+                    .collect(),
+            };
+            let found = match needle {
+                Ok(name) => entries
+                    .iter()
+                    .find(|(_, candidate)| candidate.eq_ignore_ascii_case(name)),
+                Err(id) => entries.iter().find(|(candidate, _)| *candidate == id),
+            };
+            found
+                .map(|(id, name)| (*id, name.to_string()))
+                .ok_or_else(|| {
+                    CommandError::InvalidArguments(match needle {
+                        Ok(name) => format!("unknown {} `{}`", self.noun(), name),
+                        Err(_) => format!("{} not found", self.noun()),
+                    })
+                })
+        })
+    }
+
+    /// The named entry, or one picked interactively; `None` when cancelled.
+    fn resolve(
+        self,
+        context: &ShellContext,
+        name: Option<&str>,
+        usage: &str,
+        prompt: &str,
+    ) -> Result<Option<(Uuid, String)>, CommandError> {
+        if let Some(name) = name {
+            return self.lookup(context, Ok(name)).map(Some);
+        }
+        if context.mode() != CliMode::Interactive {
+            return Err(CommandError::InvalidArguments(usage.into()));
+        }
+        match self.select(context, prompt)? {
+            Some(id) => self.lookup(context, Err(id)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+pub(crate) fn run_move(
+    context: &mut ShellContext,
+    target: Ordered,
+    args: &[&str],
+) -> CommandResult {
+    let noun = target.noun();
+    let usage = format!("usage: {} move <name> <up|down|top|bottom>", noun);
+    context.ensure_base_mode("Reordering")?;
+    if args.len() > 2 {
+        return Err(CommandError::InvalidArguments(usage));
+    }
+    let Some((id, name)) = target.resolve(
+        context,
+        args.first().copied(),
+        &usage,
+        &format!("Select the {} to move:", noun),
+    )?
+    else {
+        io::print_info("Operation cancelled.");
+        return Ok(());
+    };
+    let direction = match args.get(1) {
+        Some(value) => MoveDirection::parse(value)
+            .ok_or_else(|| CommandError::InvalidArguments(usage.clone()))?,
+        None if context.mode() == CliMode::Interactive => {
+            DIRECTIONS[io::prompt_select_index("Move", &DIRECTIONS)?]
+        }
+        None => return Err(CommandError::InvalidArguments(usage)),
+    };
+
+    let (before, after) = context.with_ledger_mut(|ledger| {
+        let before = position(ledger, target, id);
+        let after = match target {
+            Ordered::Account => AccountService::reorder(ledger, id, direction)?,
+            Ordered::Category => CategoryService::reorder(ledger, id, direction)?,
+        };
+        Ok((before, after))
+    })?;
+    if before == Some(after) {
+        io::print_info(format!(
+            "{} is already at the {} of its group.",
+            name,
+            edge(direction)
+        ));
+    } else {
+        io::print_success(format!("Moved {} {} {}.", noun, name, direction));
+    }
+    Ok(())
+}
+
+pub(crate) fn run_star(
+    context: &mut ShellContext,
+    target: Ordered,
+    args: &[&str],
+    favorite: bool,
+) -> CommandResult {
+    let noun = target.noun();
+    let verb = if favorite { "star" } else { "unstar" };
+    let usage = format!("usage: {} {} <name>", noun, verb);
+    context.ensure_base_mode("Starring")?;
+    if args.len() > 1 {
+        return Err(CommandError::InvalidArguments(usage));
+    }
+    let Some((id, name)) = target.resolve(
+        context,
+        args.first().copied(),
+        &usage,
+        &format!("Select the {} to {}:", noun, verb),
+    )?
+    else {
+        io::print_info("Operation cancelled.");
+        return Ok(());
+    };
+    context.with_ledger_mut(|ledger| {
+        match target {
+            Ordered::Account => AccountService::set_favorite(ledger, id, favorite)?,
+            Ordered::Category => CategoryService::set_favorite(ledger, id, favorite)?,
+        };
+        Ok(())
+    })?;
+    if favorite {
+        io::print_success(format!("Starred {} {}; it now lists first.", noun, name));
+    } else {
+        io::print_success(format!("Unstarred {} {}.", noun, name));
+    }
+    Ok(())
+}
+
+pub(crate) fn run_style(
+    context: &mut ShellContext,
+    target: Ordered,
+    args: &[&str],
+) -> CommandResult {
+    let noun = target.noun();
+    let usage = format!(
+        "usage: {} style <name> [--color <{}|none>] [--icon <icon|none>]",
+        noun,
+        EntityColor::ALL.map(|color| color.to_string()).join("|")
+    );
+    context.ensure_base_mode("Styling")?;
+    let mut name = None;
+    let mut color = None;
+    let mut icon = None;
+    let mut iter = args.iter();
+    while let Some(token) = iter.next() {
+        match token.to_ascii_lowercase().as_str() {
+            "--color" | "--colour" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CommandError::InvalidArguments(usage.clone()))?;
+                color = Some(match *value {
+                    value if value.eq_ignore_ascii_case("none") => None,
+                    value => Some(EntityColor::parse(value).ok_or_else(|| {
+                        CommandError::InvalidArguments(format!("unknown color `{}`", value))
+                    })?),
+                });
+            }
+            "--icon" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| CommandError::InvalidArguments(usage.clone()))?;
+                icon = Some((!value.eq_ignore_ascii_case("none")).then(|| value.to_string()));
+            }
+            _ if name.is_none() && !token.starts_with("--") => name = Some(*token),
+            _ => return Err(CommandError::InvalidArguments(usage)),
+        }
+    }
+    let Some((id, name)) = target.resolve(
+        context,
+        name,
+        &usage,
+        &format!("Select the {} to style:", noun),
+    )?
+    else {
+        io::print_info("Operation cancelled.");
+        return Ok(());
+    };
+
+    let current = context.with_ledger(|ledger| {
+        let appearance = match target {
+            Ordered::Account => ledger.account(id).map(|account| &account.appearance),
+            Ordered::Category => ledger.category(id).map(|category| &category.appearance),
+        };
+        Ok(appearance.cloned().unwrap_or_default())
+    })?;
+    if color.is_none() && icon.is_none() {
+        io::print_info(format!(
+            "{} {}: {}.",
+            capitalize(noun),
+            name,
+            describe_appearance(&current)
+        ));
+        return Ok(());
+    }
+    let appearance = Appearance {
+        color: color.unwrap_or(current.color),
+        icon: icon.unwrap_or(current.icon),
+    };
+    context.with_ledger_mut(|ledger| {
+        match target {
+            Ordered::Account => AccountService::set_appearance(ledger, id, appearance.clone())?,
+            Ordered::Category => CategoryService::set_appearance(ledger, id, appearance.clone())?,
+        };
+        Ok(())
+    })?;
+    io::print_success(format!(
+        "Styled {} {}: {}.",
+        noun,
+        name,
+        describe_appearance(&appearance)
+    ));
+    Ok(())
+}
+
+fn describe_appearance(appearance: &Appearance) -> String {
+    match (appearance.color, appearance.icon.as_deref()) {
+        (None, None) => "no color or icon".into(),
+        (Some(color), None) => format!("color {}", color),
+        (None, Some(icon)) => format!("icon {}", icon),
+        (Some(color), Some(icon)) => format!("color {}, icon {}", color, icon),
+    }
+}
+
+fn capitalize(noun: &str) -> String {
+    let mut chars = noun.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// Marker shown before the names of starred accounts and categories.
+pub(crate) fn favorite_marker(favorite: bool) -> &'static str {
+    if favorite {
+        "★ "
+    } else {
+        ""
+    }
+}
+
+fn position(ledger: &crate::ledger::Ledger, target: Ordered, id: Uuid) -> Option<usize> {
+    match target {
+        Ordered::Account => ledger.accounts.iter().position(|account| account.id == id),
+        Ordered::Category => ledger
+            .categories
+            .iter()
+            .position(|category| category.id == id),
+    }
+}
+
+fn edge(direction: MoveDirection) -> &'static str {
+    match direction {
+        MoveDirection::Up | MoveDirection::Top => "top",
+        MoveDirection::Down | MoveDirection::Bottom => "bottom",
+    }
+}
@@ -0,0 +1,87 @@
+use crate::cli::core::{CliMode, CommandError, CommandResult, LoopControl, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::shell::parse_command_line;
+use crate::cli::ui::fuzzy_finder::{FuzzyFinder, FuzzyItem};
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+
+const PALETTE_TITLE: &str = "Command palette";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "palette",
+        "Search commands by name or description (Ctrl-P in the main menu)",
+        "palette [query]",
+        cmd_palette,
+    )]
+}
+
+fn cmd_palette(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let query = args.join(" ");
+    let finder = FuzzyFinder::new(PALETTE_TITLE, palette_items(context)).with_query(&query);
+    if context.mode() != CliMode::Interactive {
+        print_matches(context, &finder, &query);
+        return Ok(());
+    }
+
+    let Some(line) = finder.run().map_err(CommandError::Io)? else {
+        return Ok(());
+    };
+    run_line(context, &line)
+}
+
+/// Recent lines first, then every registered command in registry order.
+fn palette_items(context: &ShellContext) -> Vec<FuzzyItem> {
+    let recent = context
+        .recent_commands
+        .iter()
+        .map(|line| FuzzyItem::new(line.clone(), line.clone(), "recent"));
+    let commands = context.registry.list().into_iter().map(|entry| {
+        FuzzyItem::new(entry.name, entry.name, entry.description)
+            .with_help(format!("usage: {}", entry.usage))
+    });
+    recent.chain(commands).collect()
+}
+
+fn print_matches(context: &ShellContext, finder: &FuzzyFinder, query: &str) {
+    let matches = finder.matches(query);
+    if matches.is_empty() {
+        io::print_warning(format!("No commands match `{}`.", query));
+        return;
+    }
+    let mut table = Table::new(
+        Some(PALETTE_TITLE.to_string()),
+        vec![
+            TableColumn::new("COMMAND", 14),
+            TableColumn::new("DESCRIPTION", 40),
+            TableColumn::new("USAGE", 30),
+        ],
+    );
+    for item in matches {
+        let usage = item
+            .help
+            .as_deref()
+            .and_then(|help| help.strip_prefix("usage: "))
+            .unwrap_or("");
+        table.add_row(vec![
+            item.label.clone(),
+            item.description.clone(),
+            usage.to_string(),
+        ]);
+    }
+    TableRenderer::render(&table, &context.ui_style);
+}
+
+fn run_line(context: &mut ShellContext, line: &str) -> CommandResult {
+    let tokens =
+        parse_command_line(line).map_err(|err| CommandError::InvalidArguments(err.to_string()))?;
+    let Some((raw, rest)) = tokens.split_first() else {
+        return Ok(());
+    };
+    let args: Vec<&str> = rest.iter().map(String::as_str).collect();
+    context.remember_command(line);
+    match context.dispatch(&raw.to_lowercase(), raw, &args)? {
+        LoopControl::Continue => Ok(()),
+        LoopControl::Exit => Err(CommandError::ExitRequested),
+    }
+}
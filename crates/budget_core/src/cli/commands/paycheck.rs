@@ -0,0 +1,352 @@
+//! Commands for paycheck profiles that split one salary into allocations.
+
+use crate::cli::commands::rules::report_auto_transfers;
+use crate::cli::core::{parse_date, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::{PaycheckLineKind, PaycheckService};
+use crate::ledger::Ledger;
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use bufy_domain::paycheck::{PaycheckAmount, PaycheckLine};
+use uuid::Uuid;
+
+const PAYCHECK_USAGE: &str = "usage: paycheck <profile <add|deduct|allocate|show|list|remove>|receive <name> [YYYY-MM-DD] [--gross N]>";
+const PROFILE_USAGE: &str = "usage: paycheck profile <add <name> --from <income account> --to <deposit account> --gross N [--category C]|deduct <name> <amount|N%> <account> [--category C]|allocate <name> <amount|N%> <account> [--category C]|show <name>|list|remove <name>>";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "paycheck",
+        "Split each paycheck into deductions and allocations automatically",
+        "paycheck <profile <add|deduct|allocate|show|list|remove>|receive <name> [YYYY-MM-DD] [--gross N]>",
+        cmd_paycheck,
+    )]
+}
+
+fn cmd_paycheck(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((subcommand, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(PAYCHECK_USAGE.into()));
+    };
+    match subcommand.to_ascii_lowercase().as_str() {
+        "profile" => cmd_profile(context, rest),
+        "receive" => receive(context, rest),
+        other => Err(CommandError::InvalidArguments(format!(
+            "unknown paycheck subcommand `{}`. Available: profile, receive",
+            other
+        ))),
+    }
+}
+
+fn cmd_profile(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((action, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(PROFILE_USAGE.into()));
+    };
+    match action.to_ascii_lowercase().as_str() {
+        "add" | "new" => handle_add(context, rest),
+        "deduct" => handle_line(context, PaycheckLineKind::Deduction, rest),
+        "allocate" => handle_line(context, PaycheckLineKind::Allocation, rest),
+        "show" => handle_show(context, rest),
+        "list" | "ls" => handle_list(context),
+        "remove" | "delete" => handle_remove(context, rest),
+        _ => Err(CommandError::InvalidArguments(PROFILE_USAGE.into())),
+    }
+}
+
+fn handle_add(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let usage = "usage: paycheck profile add <name> --from <income account> --to <deposit account> --gross N [--category C]";
+    let [name, flags @ ..] = args else {
+        return Err(CommandError::InvalidArguments(usage.into()));
+    };
+    let (mut from, mut to, mut gross, mut category) = (None, None, None, None);
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        let value = iter
+            .next()
+            .ok_or_else(|| CommandError::InvalidArguments(format!("{} requires a value", flag)))?;
+        match flag.to_ascii_lowercase().as_str() {
+            "--from" => from = Some(*value),
+            "--to" => to = Some(*value),
+            "--gross" => gross = Some(parse_amount(value)?),
+            "--category" => category = Some(*value),
+            _ => return Err(CommandError::InvalidArguments(usage.into())),
+        }
+    }
+    let (Some(from), Some(to), Some(gross)) = (from, to, gross) else {
+        return Err(CommandError::InvalidArguments(usage.into()));
+    };
+    let clock = context.clock.clone();
+    context.with_ledger_mut(|ledger| {
+        let income_account = account_id(ledger, from)?;
+        let deposit_account = account_id(ledger, to)?;
+        let category_id = category.map(|name| category_id(ledger, name)).transpose()?;
+        PaycheckService::create(
+            ledger,
+            name,
+            income_account,
+            deposit_account,
+            gross,
+            category_id,
+            clock.as_ref(),
+        )
+        .map_err(CommandError::from)
+    })?;
+    io::print_success(format!(
+        "Paycheck profile `{}` created. Add lines with `paycheck profile deduct` or `paycheck profile allocate`.",
+        name.trim()
+    ));
+    Ok(())
+}
+
+fn handle_line(context: &mut ShellContext, kind: PaycheckLineKind, args: &[&str]) -> CommandResult {
+    let verb = match kind {
+        PaycheckLineKind::Deduction => "deduct",
+        PaycheckLineKind::Allocation => "allocate",
+    };
+    let usage = format!(
+        "usage: paycheck profile {} <name> <amount|N%> <account> [--category C]",
+        verb
+    );
+    let (name, amount, account, category) = match args {
+        [name, amount, account] => (name, amount, account, None),
+        [name, amount, account, flag, category] if flag.eq_ignore_ascii_case("--category") => {
+            (name, amount, account, Some(*category))
+        }
+        _ => return Err(CommandError::InvalidArguments(usage)),
+    };
+    let amount = match amount.strip_suffix('%') {
+        Some(percent) => PaycheckAmount::Percent(parse_amount(percent)?),
+        None => PaycheckAmount::Fixed(parse_amount(amount)?),
+    };
+    let account_name = context.with_ledger_mut(|ledger| {
+        let line = PaycheckLine {
+            amount,
+            account: account_id(ledger, account)?,
+            category_id: category.map(|name| category_id(ledger, name)).transpose()?,
+        };
+        PaycheckService::add_line(ledger, name, kind, line.clone()).map_err(CommandError::from)?;
+        Ok(account_name(ledger, line.account))
+    })?;
+    let label = match kind {
+        PaycheckLineKind::Deduction => "Deduction",
+        PaycheckLineKind::Allocation => "Allocation",
+    };
+    io::print_success(format!(
+        "{} of {} to `{}` added to paycheck `{}`.",
+        label,
+        amount,
+        account_name,
+        name.trim()
+    ));
+    Ok(())
+}
+
+fn handle_show(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: paycheck profile show <name>".into(),
+        ));
+    };
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        let profile = ledger.paycheck_profile(name).ok_or_else(|| {
+            CommandError::InvalidArguments(format!("paycheck profile `{}` not found", name))
+        })?;
+        let split = PaycheckService::split(profile, profile.gross).map_err(CommandError::from)?;
+        let currency = ledger.base_currency().as_str();
+        let title = format!(
+            "Paycheck `{}`: {} → {}",
+            profile.name,
+            account_name(ledger, profile.income_account),
+            account_name(ledger, profile.deposit_account)
+        );
+        let mut table = Table::new(
+            Some(title.as_str()),
+            vec![
+                TableColumn::new("LINE", 12),
+                TableColumn::new("SHARE", 10),
+                TableColumn::new("ACCOUNT", 20),
+                TableColumn::new("CATEGORY", 18),
+                TableColumn::new("AMOUNT", 12),
+            ],
+        );
+        table.add_row(vec![
+            "Gross".into(),
+            String::new(),
+            account_name(ledger, profile.income_account),
+            category_name(ledger, profile.income_category),
+            formatters.format_amount(split.gross, currency),
+        ]);
+        for (label, lines) in [
+            ("Deduction", &split.deductions),
+            ("Allocation", &split.allocations),
+        ] {
+            for (line, amount) in lines {
+                table.add_row(vec![
+                    label.into(),
+                    line.amount.to_string(),
+                    account_name(ledger, line.account),
+                    category_name(ledger, line.category_id),
+                    formatters.format_amount(*amount, currency),
+                ]);
+            }
+        }
+        table.add_row(vec![
+            "Remaining".into(),
+            String::new(),
+            account_name(ledger, profile.deposit_account),
+            String::new(),
+            formatters.format_amount(split.unallocated, currency),
+        ]);
+        TableRenderer::render(&table, &style);
+        io::print_info(format!(
+            "Net pay {}; percentages of deductions apply to gross pay, allocations to net pay.",
+            formatters.format_amount(split.net, currency)
+        ));
+        Ok(())
+    })
+}
+
+fn handle_list(context: &mut ShellContext) -> CommandResult {
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        if ledger.paycheck_profiles.is_empty() {
+            io::print_warning(
+                "No paycheck profiles yet. Create one with `paycheck profile add <name> --from <account> --to <account> --gross N`.",
+            );
+            return Ok(());
+        }
+        let currency = ledger.base_currency().as_str();
+        let mut table = Table::new(
+            Some("Paycheck profiles"),
+            vec![
+                TableColumn::new("NAME", 18),
+                TableColumn::new("FROM", 18),
+                TableColumn::new("INTO", 18),
+                TableColumn::new("GROSS", 12),
+                TableColumn::new("LINES", 6),
+            ],
+        );
+        for profile in &ledger.paycheck_profiles {
+            table.add_row(vec![
+                profile.name.clone(),
+                account_name(ledger, profile.income_account),
+                account_name(ledger, profile.deposit_account),
+                formatters.format_amount(profile.gross, currency),
+                (profile.deductions.len() + profile.allocations.len()).to_string(),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_remove(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: paycheck profile remove <name>".into(),
+        ));
+    };
+    let removed = context.with_ledger_mut(|ledger| {
+        PaycheckService::remove(ledger, name).map_err(CommandError::from)
+    })?;
+    io::print_success(format!(
+        "Paycheck profile `{}` removed. Paychecks it recorded are kept.",
+        removed.name
+    ));
+    Ok(())
+}
+
+/// Records a paycheck from a profile. Shared by `paycheck receive` and
+/// `transaction add --paycheck`.
+pub(crate) fn receive(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let usage = "usage: paycheck receive <name> [YYYY-MM-DD] [--gross N]";
+    let Some((name, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(usage.into()));
+    };
+    let mut date = None;
+    let mut gross = None;
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        if arg.eq_ignore_ascii_case("--gross") {
+            let value = iter
+                .next()
+                .ok_or_else(|| CommandError::InvalidArguments("--gross requires a value".into()))?;
+            gross = Some(parse_amount(value)?);
+        } else if date.is_none() {
+            date = Some(parse_date(arg)?);
+        } else {
+            return Err(CommandError::InvalidArguments(usage.into()));
+        }
+    }
+    let date = date.unwrap_or_else(|| context.clock.today());
+    let formatters = context.formatters.clone();
+    let (receipt, lines, currency) = context.with_ledger_mut(|ledger| {
+        let receipt =
+            PaycheckService::receive(ledger, name, date, gross).map_err(CommandError::from)?;
+        let currency = ledger.base_currency().as_str().to_string();
+        let lines: Vec<String> = receipt
+            .split
+            .deductions
+            .iter()
+            .chain(receipt.split.allocations.iter())
+            .map(|(line, amount)| {
+                format!(
+                    "  {} → `{}`",
+                    formatters.format_amount(*amount, &currency),
+                    account_name(ledger, line.account)
+                )
+            })
+            .collect();
+        Ok((receipt, lines, currency))
+    })?;
+    io::print_success(format!(
+        "Paycheck `{}` of {} recorded on {} with {} allocation transaction(s); {} left unallocated.",
+        name.trim(),
+        formatters.format_amount(receipt.split.gross, &currency),
+        formatters.format_date(date),
+        receipt.line_ids.len(),
+        formatters.format_amount(receipt.split.unallocated, &currency)
+    ));
+    for line in lines {
+        io::print_info(line);
+    }
+    report_auto_transfers(context, &receipt.transfer_ids)
+}
+
+fn parse_amount(value: &str) -> Result<f64, CommandError> {
+    io::parse_number(value)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("invalid amount `{}`", value)))
+}
+
+fn account_id(ledger: &Ledger, name: &str) -> Result<Uuid, CommandError> {
+    ledger
+        .accounts
+        .iter()
+        .find(|account| account.name.eq_ignore_ascii_case(name))
+        .map(|account| account.id)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("unknown account `{}`", name)))
+}
+
+fn category_id(ledger: &Ledger, name: &str) -> Result<Uuid, CommandError> {
+    ledger
+        .categories
+        .iter()
+        .find(|category| category.name.eq_ignore_ascii_case(name))
+        .map(|category| category.id)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("unknown category `{}`", name)))
+}
+
+fn account_name(ledger: &Ledger, id: Uuid) -> String {
+    ledger
+        .account(id)
+        .map(|account| account.name.clone())
+        .unwrap_or_else(|| "(deleted)".into())
+}
+
+fn category_name(ledger: &Ledger, id: Option<Uuid>) -> String {
+    id.and_then(|id| ledger.category(id))
+        .map(|category| category.name.clone())
+        .unwrap_or_default()
+}
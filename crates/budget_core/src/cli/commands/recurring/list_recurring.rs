@@ -73,7 +73,7 @@ fn gather_entries(context: &ShellContext) -> Result<Vec<RecurringEntry>, Command
             };
             entries.push(RecurringEntry {
                 index,
-                summary: format_summary(&account_names, &txn, &category_names),
+                summary: format_summary(&account_names, txn, &category_names),
                 frequency: snapshot.interval_label.clone(),
                 next_due: snapshot.next_due,
                 start_date: recurrence.start_date,
@@ -93,7 +93,7 @@ fn gather_entries(context: &ShellContext) -> Result<Vec<RecurringEntry>, Command
                     .and_then(|id| category_names.get(&id))
                     .cloned()
                     .unwrap_or_else(|| "—".into()),
-                amount: txn.budgeted_amount,
+                amount: txn.budgeted_amount.to_major(),
                 recurrence,
             });
         }
@@ -101,13 +101,13 @@ fn gather_entries(context: &ShellContext) -> Result<Vec<RecurringEntry>, Command
         if entries.is_empty() {
             return Ok(Vec::new());
         }
-        entries.sort_by(|a, b| a.next_due.cmp(&b.next_due));
+        entries.sort_by_key(|a| a.next_due);
         Ok(entries)
     })
 }
 
 fn snapshot_map(ledger: &Ledger, reference: NaiveDate) -> HashMap<Uuid, RecurrenceSnapshot> {
-    snapshot_recurrences(&ledger.transactions, reference)
+    snapshot_recurrences(&ledger.transactions, reference, ledger.business_calendar())
         .into_iter()
         .map(|snap| (snap.series_id, snap))
         .collect()
@@ -0,0 +1,485 @@
+//! Aggregated spending reports.
+
+use chrono::{Datelike, Days};
+
+use crate::cli::core::{parse_date, CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::formatters::CliFormatters;
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::{
+    AssetService, AssetStatus, HeatmapService, MerchantService, UncategorizedReport,
+    UncategorizedService,
+};
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use bufy_domain::DateWindow;
+use uuid::Uuid;
+
+const REPORT_USAGE: &str = "usage: report <merchants [--from YYYY-MM-DD] [--to YYYY-MM-DD] [--top N]|heatmap [YYYY-MM]|uncategorized [assign <group> <category>]|assets [--all]>";
+const HEATMAP_USAGE: &str = "usage: report heatmap [YYYY-MM]";
+/// Shades for a day's spending, from none to the month's busiest day.
+const HEAT_SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+const UNCATEGORIZED_USAGE: &str = "usage: report uncategorized [assign <group> <category>]";
+const DEFAULT_TOP_MERCHANTS: usize = 10;
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "report",
+        "Aggregate spending, such as top merchants, uncategorized payees, or assets",
+        "report <merchants [--from YYYY-MM-DD] [--to YYYY-MM-DD] [--top N]|heatmap [YYYY-MM]|uncategorized [assign <group> <category>]|assets [--all]>",
+        cmd_report,
+    )]
+}
+
+fn cmd_report(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    match args.split_first() {
+        Some((report, rest)) if report.eq_ignore_ascii_case("merchants") => {
+            handle_merchants(context, rest)
+        }
+        Some((report, rest)) if report.eq_ignore_ascii_case("heatmap") => {
+            handle_heatmap(context, rest)
+        }
+        Some((report, rest)) if report.eq_ignore_ascii_case("uncategorized") => {
+            handle_uncategorized(context, rest)
+        }
+        Some((report, rest)) if report.eq_ignore_ascii_case("assets") => {
+            handle_assets(context, rest)
+        }
+        _ => Err(CommandError::InvalidArguments(REPORT_USAGE.into())),
+    }
+}
+
+fn handle_merchants(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let (mut from, mut to, mut top) = (None, None, DEFAULT_TOP_MERCHANTS);
+    let mut iter = args.iter();
+    while let Some(flag) = iter.next() {
+        let value = iter
+            .next()
+            .ok_or_else(|| CommandError::InvalidArguments(format!("{} requires a value", flag)))?;
+        match flag.to_ascii_lowercase().as_str() {
+            "--from" => from = Some(parse_date(value)?),
+            "--to" => to = Some(parse_date(value)?),
+            "--top" => {
+                top = value.parse().ok().filter(|n| *n > 0).ok_or_else(|| {
+                    CommandError::InvalidArguments(format!(
+                        "--top must be a positive whole number, got `{}`",
+                        value
+                    ))
+                })?
+            }
+            _ => return Err(CommandError::InvalidArguments(REPORT_USAGE.into())),
+        }
+    }
+    let today = context.clock.today();
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        let period = ledger.budget_window_containing(today);
+        let start = from.unwrap_or(period.start);
+        // `--to` is inclusive; windows end the day after.
+        let end = to
+            .and_then(|date| date.checked_add_days(Days::new(1)))
+            .unwrap_or(period.end);
+        let window = DateWindow::new(start, end).map_err(|_| {
+            CommandError::InvalidArguments("--from must be on or before --to".into())
+        })?;
+        let report = MerchantService::top_merchants(ledger, &window, top);
+        let last_day = end.pred_opt().unwrap_or(end);
+        if report.merchants.is_empty() {
+            io::print_warning(format!(
+                "No spending with a merchant between {} and {}.",
+                formatters.format_date(start),
+                formatters.format_date(last_day)
+            ));
+        } else {
+            let currency = ledger.base_currency().as_str();
+            let title = format!(
+                "Top merchants, {} – {}",
+                formatters.format_date(start),
+                formatters.format_date(last_day)
+            );
+            let mut table = Table::new(
+                Some(title.as_str()),
+                vec![
+                    TableColumn::new("MERCHANT", 24),
+                    TableColumn::new("SPENT", 14),
+                    TableColumn::new("COUNT", 6),
+                    TableColumn::new("LAST", 12),
+                ],
+            );
+            for merchant in &report.merchants {
+                table.add_row(vec![
+                    merchant.merchant.clone(),
+                    formatters.format_amount(merchant.total, currency),
+                    merchant.transactions.to_string(),
+                    formatters.format_date(merchant.last_date),
+                ]);
+            }
+            TableRenderer::render(&table, &style);
+        }
+        if report.unconverted > 0 {
+            io::print_warning(format!(
+                "{} transaction(s) in other currencies were left out.",
+                report.unconverted
+            ));
+        }
+        Ok(())
+    })
+}
+
+fn handle_heatmap(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let today = context.clock.today();
+    let (year, month) = match args {
+        [] => (today.year(), today.month()),
+        [value] => value
+            .split_once('-')
+            .and_then(|(year, month)| Some((year.parse().ok()?, month.parse().ok()?)))
+            .ok_or_else(|| CommandError::InvalidArguments(HEATMAP_USAGE.into()))?,
+        _ => return Err(CommandError::InvalidArguments(HEATMAP_USAGE.into())),
+    };
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        let heatmap = HeatmapService::month(ledger, year, month)?;
+        let weekend = ledger.locale.weekend.clone();
+        let title = format!("Spending, {}", heatmap.month.format("%B %Y"));
+        let columns = heatmap
+            .weekdays
+            .iter()
+            .map(|day| {
+                let mark = if weekend.contains(day) { "*" } else { "" };
+                TableColumn::new(format!("{}{}", day.to_string().to_uppercase(), mark), 6)
+            })
+            .collect();
+        let mut table = Table::new(Some(title.as_str()), columns);
+        for week in &heatmap.weeks {
+            table.add_row(
+                week.iter()
+                    .map(|cell| match cell {
+                        Some(day) => format!(
+                            "{:>2} {}",
+                            day.date.day(),
+                            heat_shade(day.total, heatmap.busiest)
+                        ),
+                        None => String::new(),
+                    })
+                    .collect(),
+            );
+        }
+        TableRenderer::render(&table, &style);
+        io::print_hint(format!(
+            "{} none to {} busiest day ({}); * weekend",
+            HEAT_SHADES[0],
+            HEAT_SHADES[HEAT_SHADES.len() - 1],
+            formatters.format_amount(heatmap.busiest, ledger.base_currency().as_str())
+        ));
+        if heatmap.unconverted > 0 {
+            io::print_warning(format!(
+                "{} transaction(s) in other currencies were left out.",
+                heatmap.unconverted
+            ));
+        }
+        Ok(())
+    })
+}
+
+/// The shade for `total` on a scale up to `busiest`.
+fn heat_shade(total: f64, busiest: f64) -> char {
+    if total <= 0.0 || busiest <= 0.0 {
+        return HEAT_SHADES[0];
+    }
+    let steps = (HEAT_SHADES.len() - 1) as f64;
+    let level = ((total / busiest) * steps).ceil().clamp(1.0, steps);
+    HEAT_SHADES[level as usize]
+}
+
+fn handle_uncategorized(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    match args {
+        [] => {
+            let report = show_uncategorized(context)?;
+            if context.mode() == CliMode::Interactive && !report.groups.is_empty() {
+                assign_interactively(context, report)?;
+            }
+            Ok(())
+        }
+        [action, group, category] if action.eq_ignore_ascii_case("assign") => {
+            let report = uncategorized_report(context)?;
+            let index = group
+                .parse::<usize>()
+                .ok()
+                .filter(|index| (1..=report.groups.len()).contains(index))
+                .ok_or_else(|| {
+                    CommandError::InvalidArguments(format!(
+                        "group must be between 1 and {}, got `{}`",
+                        report.groups.len(),
+                        group
+                    ))
+                })?;
+            let category_id = context.with_ledger(|ledger| {
+                ledger
+                    .categories
+                    .iter()
+                    .find(|candidate| candidate.name.eq_ignore_ascii_case(category))
+                    .map(|candidate| candidate.id)
+                    .ok_or_else(|| {
+                        CommandError::InvalidArguments(format!("unknown category `{}`", category))
+                    })
+            })?;
+            assign_group(context, &report, index - 1, category_id)
+        }
+        _ => Err(CommandError::InvalidArguments(UNCATEGORIZED_USAGE.into())),
+    }
+}
+
+fn uncategorized_report(context: &mut ShellContext) -> Result<UncategorizedReport, CommandError> {
+    let today = context.clock.today();
+    context.with_ledger(|ledger| Ok(UncategorizedService::report(ledger, today)))
+}
+
+/// Renders the uncategorized report and returns it, so groups can be
+/// addressed by the numbers shown.
+fn show_uncategorized(context: &mut ShellContext) -> Result<UncategorizedReport, CommandError> {
+    let report = uncategorized_report(context)?;
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        if report.groups.is_empty() {
+            io::print_success("Every transaction has a category.");
+            return Ok(());
+        }
+        let currency = ledger.base_currency().as_str();
+        let mut table = Table::new(
+            Some("Uncategorized by payee"),
+            vec![
+                TableColumn::new("#", 4),
+                TableColumn::new("PAYEE", 24),
+                TableColumn::new("TYPE", 8),
+                TableColumn::new("COUNT", 6),
+                TableColumn::new("TOTAL", 14),
+                TableColumn::new("THIS PERIOD", 14),
+                TableColumn::new("SUGGESTED", 16),
+            ],
+        );
+        for (index, group) in report.groups.iter().enumerate() {
+            table.add_row(vec![
+                (index + 1).to_string(),
+                group.payee.clone(),
+                group.kind.to_string(),
+                group.transaction_ids.len().to_string(),
+                formatters.format_amount(group.total, currency),
+                formatters.format_amount(group.period_total, currency),
+                group
+                    .suggestion
+                    .and_then(|id| ledger.category(id))
+                    .map(|category| category.name.clone())
+                    .unwrap_or_default(),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        let last_day = report.window.end.pred_opt().unwrap_or(report.window.end);
+        io::print_info(format!(
+            "{} uncategorized transaction(s). Impact on {} – {}: income {}, expenses {}, net {}.",
+            report.transaction_count(),
+            formatters.format_date(report.window.start),
+            formatters.format_date(last_day),
+            formatters.format_amount(report.period_income, currency),
+            formatters.format_amount(report.period_expense, currency),
+            formatters.format_amount(report.period_income - report.period_expense, currency)
+        ));
+        if report.unconverted > 0 {
+            io::print_warning(format!(
+                "{} transaction(s) in other currencies were left out of the period totals.",
+                report.unconverted
+            ));
+        }
+        Ok(())
+    })?;
+    Ok(report)
+}
+
+/// Lets the user pick a payee group and a category for it until they stop
+/// or nothing is left uncategorized.
+fn assign_interactively(
+    context: &mut ShellContext,
+    mut report: UncategorizedReport,
+) -> CommandResult {
+    loop {
+        let mut options: Vec<String> = report
+            .groups
+            .iter()
+            .map(|group| {
+                format!(
+                    "{} ({}, {} transaction(s))",
+                    group.payee,
+                    group.kind,
+                    group.transaction_ids.len()
+                )
+            })
+            .collect();
+        options.push("Done".into());
+        let group = match io::prompt_select_index("Categorize which payee?", &options) {
+            Ok(choice) if choice < report.groups.len() => choice,
+            _ => return Ok(()),
+        };
+        let suggestion = report.groups[group].suggestion;
+        let categories = context.with_ledger(|ledger| {
+            let mut choices: Vec<_> = ledger
+                .categories
+                .iter()
+                .filter(|category| !category.hidden)
+                .map(|category| (category.id, category.name.clone()))
+                .collect();
+            if let Some(index) = choices.iter().position(|(id, _)| Some(*id) == suggestion) {
+                let suggested = choices.remove(index);
+                choices.insert(0, suggested);
+            }
+            Ok(choices)
+        })?;
+        if categories.is_empty() {
+            io::print_warning("No categories yet. Add one with `category add`.");
+            return Ok(());
+        }
+        let mut names: Vec<String> = categories.iter().map(|(_, name)| name.clone()).collect();
+        names.push("Back".into());
+        let Some((category_id, _)) = io::prompt_select_index("Category", &names)
+            .ok()
+            .and_then(|choice| categories.get(choice))
+        else {
+            continue;
+        };
+        assign_group(context, &report, group, *category_id)?;
+        report = show_uncategorized(context)?;
+        if report.groups.is_empty() {
+            return Ok(());
+        }
+    }
+}
+
+fn assign_group(
+    context: &mut ShellContext,
+    report: &UncategorizedReport,
+    index: usize,
+    category_id: Uuid,
+) -> CommandResult {
+    let group = &report.groups[index];
+    let clock = context.clock.clone();
+    let (assigned, category) = context.with_ledger_mut(|ledger| {
+        let assigned = UncategorizedService::assign(
+            ledger,
+            &group.transaction_ids,
+            category_id,
+            clock.as_ref(),
+        )
+        .map_err(CommandError::from)?;
+        let category = ledger
+            .category(category_id)
+            .map(|category| category.name.clone())
+            .unwrap_or_default();
+        Ok((assigned, category))
+    })?;
+    io::print_success(format!(
+        "Categorized {} transaction(s) from `{}` as {}.",
+        assigned, group.payee, category
+    ));
+    Ok(())
+}
+
+fn handle_assets(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let show_all = match args {
+        [] => false,
+        [flag] if flag.eq_ignore_ascii_case("--all") => true,
+        _ => {
+            return Err(CommandError::InvalidArguments(
+                "usage: report assets [--all]".into(),
+            ))
+        }
+    };
+    let today = context.clock.today();
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        let report = AssetService::report(ledger, today);
+        let retired = report
+            .assets
+            .iter()
+            .filter(|asset| !asset.in_service(today))
+            .count();
+        let assets: Vec<&AssetStatus> = report
+            .assets
+            .iter()
+            .filter(|asset| show_all || asset.in_service(today))
+            .collect();
+        if assets.is_empty() {
+            io::print_warning(
+                "No assets yet. Record one with `transaction asset <index> <name> --warranty <date> --lifetime 3y`.",
+            );
+            return Ok(());
+        }
+        let mut table = Table::new(
+            Some("Assets"),
+            vec![
+                TableColumn::new("ITEM", 20),
+                TableColumn::new("BOUGHT", 12),
+                TableColumn::new("COST", 12),
+                TableColumn::new("WARRANTY", 22),
+                TableColumn::new("LIFETIME", 9),
+                TableColumn::new("PER MONTH", 12),
+                TableColumn::new("VALUE LEFT", 12),
+            ],
+        );
+        for asset in assets {
+            table.add_row(vec![
+                asset.name.clone(),
+                formatters.format_date(asset.purchased_on),
+                formatters.format_amount(asset.cost, &asset.currency),
+                warranty_label(&formatters, asset),
+                asset
+                    .lifetime_months
+                    .map(|months| format!("{}m", months))
+                    .unwrap_or_else(|| "-".into()),
+                asset
+                    .monthly_cost
+                    .map(|cost| formatters.format_amount(cost, &asset.currency))
+                    .unwrap_or_else(|| "-".into()),
+                asset
+                    .remaining_value
+                    .map(|value| formatters.format_amount(value, &asset.currency))
+                    .unwrap_or_else(|| "-".into()),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        let active = report
+            .assets
+            .iter()
+            .filter(|asset| asset.has_active_warranty())
+            .count();
+        io::print_info(format!(
+            "{} active warranty(ies). Items in service cost {} a month over their lifetimes.",
+            active,
+            formatters.format_amount(report.monthly_cost, ledger.base_currency().as_str())
+        ));
+        if report.unconverted > 0 {
+            io::print_warning(format!(
+                "{} item(s) in other currencies were left out of the monthly cost.",
+                report.unconverted
+            ));
+        }
+        if !show_all && retired > 0 {
+            io::print_hint(format!(
+                "{} item(s) past their lifetime hidden. Use --all to list them.",
+                retired
+            ));
+        }
+        Ok(())
+    })
+}
+
+fn warranty_label(formatters: &CliFormatters, asset: &AssetStatus) -> String {
+    match (asset.warranty_until, asset.warranty_days_left) {
+        (Some(until), Some(days)) if days >= 0 => {
+            format!("until {} ({}d)", formatters.format_date(until), days)
+        }
+        (Some(until), _) => format!("expired {}", formatters.format_date(until)),
+        _ => "-".into(),
+    }
+}
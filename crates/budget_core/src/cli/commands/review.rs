@@ -0,0 +1,428 @@
+//! `review`: a guided pass over uncategorized transactions, over-budget
+//! categories, upcoming bills, and budget adjustments.
+//!
+//! Interactive sessions prompt item by item; in scripts each decision is its
+//! own subcommand. Either way progress is stored with the ledger, so a review
+//! left half-way resumes at the same step.
+
+use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::core::services::{ReviewItem, ReviewService};
+use crate::ledger::Ledger;
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use bufy_domain::review::ReviewStep;
+use uuid::Uuid;
+
+const REVIEW_USAGE: &str =
+    "usage: review [status|next|categorize <n> <category>|adjust <n> [amount]|dismiss <n>|log]";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "review",
+        "Walk through a guided budget review",
+        "review [status|next|categorize <n> <category>|adjust <n> [amount]|dismiss <n>|log]",
+        cmd_review,
+    )]
+}
+
+fn cmd_review(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((subcommand, rest)) = args.split_first() else {
+        return if context.mode() == CliMode::Interactive {
+            run_guided(context)
+        } else {
+            handle_status(context)
+        };
+    };
+    match (subcommand.to_ascii_lowercase().as_str(), rest) {
+        ("status", []) => handle_status(context),
+        ("next", []) => handle_next(context),
+        ("categorize", [index, category]) => handle_categorize(context, index, category),
+        ("adjust", [index]) => handle_adjust(context, index, None),
+        ("adjust", [index, amount]) => handle_adjust(context, index, Some(amount)),
+        ("dismiss", [index]) => handle_dismiss(context, index),
+        ("log", []) => handle_log(context),
+        _ => Err(CommandError::InvalidArguments(REVIEW_USAGE.into())),
+    }
+}
+
+/// Prompts through every remaining step until the review is finished or the
+/// user stops.
+fn run_guided(context: &mut ShellContext) -> CommandResult {
+    let step = start(context)?;
+    if step == ReviewStep::Done {
+        return Ok(());
+    }
+    let clock = context.clock.clone();
+    loop {
+        let (step, items) = current(context)?;
+        if step == ReviewStep::Done {
+            io::print_success("Review complete.");
+            return Ok(());
+        }
+        io::print_info(heading(step));
+        if items.is_empty() {
+            io::print_info("Nothing to review here.");
+        }
+        for item in &items {
+            io::print_info(describe(context, item)?);
+            let mut options: Vec<String> = Vec::new();
+            let mut categories = Vec::new();
+            match item {
+                ReviewItem::Uncategorized { suggestion, .. } => {
+                    categories =
+                        context.with_ledger(|ledger| Ok(category_choices(ledger, *suggestion)))?;
+                    options.extend(categories.iter().map(|(_, name)| name.clone()));
+                    options.push("Skip".into());
+                }
+                ReviewItem::OverBudget { proposed, .. }
+                | ReviewItem::BudgetAdjustment { proposed, .. } => {
+                    options.push(format!(
+                        "Change the budget to {}",
+                        amount_text(context, *proposed)?
+                    ));
+                    options.push("Keep the budget".into());
+                }
+                ReviewItem::UpcomingBill { .. } => options.push("Looks right".into()),
+            }
+            options.push("Stop for now".into());
+            let choice = match io::prompt_select_index("Choose", &options) {
+                Ok(choice) if choice + 1 < options.len() => choice,
+                _ => {
+                    io::print_info("Review paused. Run `review` to continue where you left off.");
+                    return Ok(());
+                }
+            };
+            context.with_ledger_mut(|ledger| {
+                match (item, categories.get(choice)) {
+                    (ReviewItem::Uncategorized { transaction_id, .. }, Some((category_id, _))) => {
+                        ReviewService::categorize(
+                            ledger,
+                            *transaction_id,
+                            *category_id,
+                            clock.as_ref(),
+                        )
+                    }
+                    (
+                        ReviewItem::OverBudget {
+                            category_id,
+                            proposed,
+                            ..
+                        },
+                        _,
+                    )
+                    | (
+                        ReviewItem::BudgetAdjustment {
+                            category_id,
+                            proposed,
+                            ..
+                        },
+                        _,
+                    ) if choice == 0 => ReviewService::adjust_budget(
+                        ledger,
+                        *category_id,
+                        *proposed,
+                        clock.as_ref(),
+                    ),
+                    _ => ReviewService::dismiss(ledger, item.subject_id(), clock.as_ref()),
+                }
+                .map_err(CommandError::from)
+            })?;
+        }
+        context.with_ledger_mut(|ledger| {
+            ReviewService::advance(ledger, clock.as_ref()).map_err(CommandError::from)
+        })?;
+    }
+}
+
+fn handle_status(context: &mut ShellContext) -> CommandResult {
+    let step = start(context)?;
+    if step == ReviewStep::Done {
+        return Ok(());
+    }
+    let (step, items) = current(context)?;
+    io::print_info(heading(step));
+    if items.is_empty() {
+        io::print_info("Nothing to review here. Run `review next` to move on.");
+        return Ok(());
+    }
+    for (index, item) in items.iter().enumerate() {
+        io::print_info(format!("[{}] {}", index, describe(context, item)?));
+    }
+    io::print_hint(match step {
+        ReviewStep::Uncategorized => {
+            "Use `review categorize <n> <category>` or `review dismiss <n>`, then `review next`."
+        }
+        ReviewStep::UpcomingBills => "Use `review dismiss <n>` once checked, then `review next`.",
+        _ => "Use `review adjust <n> [amount]` or `review dismiss <n>`, then `review next`.",
+    });
+    Ok(())
+}
+
+fn handle_next(context: &mut ShellContext) -> CommandResult {
+    let clock = context.clock.clone();
+    let step = context.with_ledger_mut(|ledger| {
+        ReviewService::start_or_resume(ledger, clock.as_ref());
+        ReviewService::advance(ledger, clock.as_ref()).map_err(CommandError::from)
+    })?;
+    if step == ReviewStep::Done {
+        io::print_success("Review complete.");
+        Ok(())
+    } else {
+        handle_status(context)
+    }
+}
+
+fn handle_categorize(context: &mut ShellContext, index: &str, category: &str) -> CommandResult {
+    let item = pending_item(context, index)?;
+    let ReviewItem::Uncategorized { transaction_id, .. } = item else {
+        return Err(CommandError::InvalidArguments(
+            "only uncategorized transactions can be categorized".into(),
+        ));
+    };
+    let clock = context.clock.clone();
+    let name = context.with_ledger_mut(|ledger| {
+        let category = ledger
+            .categories
+            .iter()
+            .find(|c| !c.hidden && c.name.eq_ignore_ascii_case(category))
+            .map(|c| (c.id, c.name.clone()))
+            .ok_or_else(|| {
+                CommandError::InvalidArguments(format!("unknown category `{}`", category))
+            })?;
+        ReviewService::categorize(ledger, transaction_id, category.0, clock.as_ref())
+            .map_err(CommandError::from)?;
+        Ok(category.1)
+    })?;
+    io::print_success(format!("Categorized as {}.", name));
+    Ok(())
+}
+
+fn handle_adjust(context: &mut ShellContext, index: &str, amount: Option<&str>) -> CommandResult {
+    let item = pending_item(context, index)?;
+    let Some(proposed) = item.proposed_budget() else {
+        return Err(CommandError::InvalidArguments(
+            "only budget items can be adjusted".into(),
+        ));
+    };
+    let value = match amount {
+        Some(raw) => io::parse_number(raw)
+            .ok_or_else(|| CommandError::InvalidArguments(format!("invalid amount `{}`", raw)))?,
+        None => proposed,
+    };
+    let clock = context.clock.clone();
+    context.with_ledger_mut(|ledger| {
+        ReviewService::adjust_budget(ledger, item.subject_id(), value, clock.as_ref())
+            .map_err(CommandError::from)
+    })?;
+    io::print_success(format!("Budget set to {}.", amount_text(context, value)?));
+    Ok(())
+}
+
+fn handle_dismiss(context: &mut ShellContext, index: &str) -> CommandResult {
+    let item = pending_item(context, index)?;
+    let clock = context.clock.clone();
+    context.with_ledger_mut(|ledger| {
+        ReviewService::dismiss(ledger, item.subject_id(), clock.as_ref())
+            .map_err(CommandError::from)
+    })?;
+    io::print_success("Marked as reviewed.");
+    Ok(())
+}
+
+fn handle_log(context: &mut ShellContext) -> CommandResult {
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        let Some(review) = ledger.reviews.last() else {
+            io::print_warning("No reviews yet. Start one with `review`.");
+            return Ok(());
+        };
+        io::print_info(format!(
+            "Review started {}{}",
+            formatters.format_date(review.started_at.date_naive()),
+            match review.completed_at {
+                Some(done) => format!(", finished {}", formatters.format_date(done.date_naive())),
+                None => format!(", paused at {}", review.step),
+            }
+        ));
+        for entry in &review.log {
+            io::print_info(format!("  {}: {}", entry.step, entry.message));
+        }
+        Ok(())
+    })
+}
+
+/// Starts or resumes a review and says which. Returns the current step.
+fn start(context: &mut ShellContext) -> Result<ReviewStep, CommandError> {
+    let clock = context.clock.clone();
+    let formatters = context.formatters.clone();
+    let (resumed, step, started, last) = context.with_ledger_mut(|ledger| {
+        let (_, resumed) = ReviewService::start_or_resume(ledger, clock.as_ref());
+        let last = ReviewService::last_completed(ledger)
+            .and_then(|review| review.completed_at)
+            .map(|at| formatters.format_date(at.date_naive()));
+        let review = ledger.open_review().expect("review is open after start");
+        Ok((
+            resumed,
+            review.step,
+            formatters.format_date(review.started_at.date_naive()),
+            last,
+        ))
+    })?;
+    if resumed {
+        io::print_info(format!(
+            "Resuming the review started {} at step {} of {}.",
+            started,
+            step_number(step),
+            ReviewStep::ALL.len()
+        ));
+    } else {
+        io::print_info(match last {
+            Some(date) => format!("Starting a budget review. The last one finished {}.", date),
+            None => "Starting your first budget review.".into(),
+        });
+    }
+    Ok(step)
+}
+
+fn current(context: &ShellContext) -> Result<(ReviewStep, Vec<ReviewItem>), CommandError> {
+    let today = context.clock.today();
+    context.with_ledger(|ledger| {
+        let step = ledger
+            .open_review()
+            .map(|review| review.step)
+            .unwrap_or(ReviewStep::Done);
+        Ok((step, ReviewService::pending_items(ledger, today)))
+    })
+}
+
+fn pending_item(context: &mut ShellContext, index: &str) -> Result<ReviewItem, CommandError> {
+    let index: usize = index
+        .parse()
+        .map_err(|_| CommandError::InvalidArguments(REVIEW_USAGE.into()))?;
+    let (_, items) = current(context)?;
+    items.into_iter().nth(index).ok_or_else(|| {
+        CommandError::InvalidArguments(format!(
+            "no review item {}; run `review status` to list them",
+            index
+        ))
+    })
+}
+
+fn step_number(step: ReviewStep) -> usize {
+    ReviewStep::ALL
+        .iter()
+        .position(|candidate| *candidate == step)
+        .map_or(ReviewStep::ALL.len(), |index| index + 1)
+}
+
+fn heading(step: ReviewStep) -> String {
+    format!(
+        "Step {} of {}: {}",
+        step_number(step),
+        ReviewStep::ALL.len(),
+        step
+    )
+}
+
+fn describe(context: &ShellContext, item: &ReviewItem) -> Result<String, CommandError> {
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        let currency = ledger.base_currency().as_str();
+        let money = |value: f64| formatters.format_amount(value, currency);
+        Ok(match item {
+            ReviewItem::Uncategorized {
+                transaction_id,
+                suggestion,
+            } => {
+                let Some(txn) = ledger.transaction(*transaction_id) else {
+                    return Ok(transaction_id.to_string());
+                };
+                let mut text = format!(
+                    "{}  {}  {} → {}",
+                    formatters.format_date(txn.actual_date.unwrap_or(txn.scheduled_date)),
+                    money(txn.amount().to_major()),
+                    account_name(ledger, txn.from_account),
+                    account_name(ledger, txn.to_account)
+                );
+                if let Some(notes) = txn.notes.as_deref().filter(|n| !n.trim().is_empty()) {
+                    text.push_str(&format!("  {}", notes));
+                }
+                if let Some(category) = suggestion.and_then(|id| ledger.category(id)) {
+                    text.push_str(&format!(" (suggested: {})", category.name));
+                }
+                text
+            }
+            ReviewItem::OverBudget {
+                category_id,
+                budget,
+                spent,
+                proposed,
+            } => format!(
+                "{} spent {} of {}; a budget of {} would cover it",
+                category_name(ledger, *category_id),
+                money(*spent),
+                money(*budget),
+                money(*proposed)
+            ),
+            ReviewItem::UpcomingBill {
+                transaction_id,
+                due,
+                amount,
+            } => format!(
+                "{}  {}  to {}",
+                formatters.format_date(*due),
+                money(*amount),
+                ledger
+                    .transaction(*transaction_id)
+                    .map(|txn| account_name(ledger, txn.to_account))
+                    .unwrap_or_default()
+            ),
+            ReviewItem::BudgetAdjustment {
+                category_id,
+                current,
+                proposed,
+            } => format!(
+                "{} is budgeted {} a month but averages {}",
+                category_name(ledger, *category_id),
+                money(*current),
+                money(*proposed)
+            ),
+        })
+    })
+}
+
+fn amount_text(context: &ShellContext, value: f64) -> Result<String, CommandError> {
+    let formatters = context.formatters.clone();
+    context
+        .with_ledger(|ledger| Ok(formatters.format_amount(value, ledger.base_currency().as_str())))
+}
+
+/// Visible categories, with the suggested one first.
+fn category_choices(ledger: &Ledger, suggestion: Option<Uuid>) -> Vec<(Uuid, String)> {
+    let mut choices: Vec<_> = ledger
+        .categories
+        .iter()
+        .filter(|category| !category.hidden)
+        .map(|category| (category.id, category.name.clone()))
+        .collect();
+    if let Some(index) = choices.iter().position(|(id, _)| Some(*id) == suggestion) {
+        let suggested = choices.remove(index);
+        choices.insert(0, suggested);
+    }
+    choices
+}
+
+fn account_name(ledger: &Ledger, id: Uuid) -> String {
+    ledger
+        .account(id)
+        .map(|account| account.name.clone())
+        .unwrap_or_else(|| "(deleted)".into())
+}
+
+fn category_name(ledger: &Ledger, id: Uuid) -> String {
+    ledger
+        .category(id)
+        .map(|category| category.name.clone())
+        .unwrap_or_else(|| "(deleted)".into())
+}
@@ -0,0 +1,229 @@
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::{CategorizationService, TransferRuleService};
+use crate::ledger::Ledger;
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use uuid::Uuid;
+
+const RULES_USAGE: &str = "usage: rules <learned <list|forget <pattern>|forget --all>|transfer <list|add <from> <to> <percent>|remove <index>>>";
+const TRANSFER_USAGE: &str =
+    "usage: rules transfer <list|add <from_account> <to_account> <percent>|remove <index>>";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "rules",
+        "Review learned categorization rules and set up automatic transfers",
+        "rules <learned <list|forget <pattern>|forget --all>|transfer <list|add <from> <to> <percent>|remove <index>>>",
+        cmd_rules,
+    )]
+}
+
+fn cmd_rules(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    match args {
+        [group, rest @ ..] if group.eq_ignore_ascii_case("learned") => match rest {
+            [] => handle_list(context),
+            [action] if is_list(action) => handle_list(context),
+            [action, target] if action.eq_ignore_ascii_case("forget") => {
+                handle_forget(context, target)
+            }
+            _ => Err(CommandError::InvalidArguments(RULES_USAGE.into())),
+        },
+        [group, rest @ ..] if group.eq_ignore_ascii_case("transfer") => match rest {
+            [] => handle_transfer_list(context),
+            [action] if is_list(action) => handle_transfer_list(context),
+            [action, from, to, percent] if action.eq_ignore_ascii_case("add") => {
+                handle_transfer_add(context, from, to, percent)
+            }
+            [action, index] if action.eq_ignore_ascii_case("remove") => {
+                handle_transfer_remove(context, index)
+            }
+            _ => Err(CommandError::InvalidArguments(TRANSFER_USAGE.into())),
+        },
+        _ => Err(CommandError::InvalidArguments(RULES_USAGE.into())),
+    }
+}
+
+fn is_list(action: &str) -> bool {
+    action.eq_ignore_ascii_case("list") || action.eq_ignore_ascii_case("ls")
+}
+
+fn handle_list(context: &mut ShellContext) -> CommandResult {
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        if ledger.learned_rules().is_empty() {
+            io::print_warning("No learned rules yet. Recategorize a transaction to teach one.");
+            return Ok(());
+        }
+        let mut rules: Vec<_> = ledger.learned_rules().iter().collect();
+        rules.sort_by(|a, b| b.hits.cmp(&a.hits).then_with(|| a.pattern.cmp(&b.pattern)));
+        let mut table = Table::new(
+            Some("Learned rules"),
+            vec![
+                TableColumn::new("PATTERN", 28),
+                TableColumn::new("CATEGORY", 20),
+                TableColumn::new("HITS", 6),
+                TableColumn::new("UPDATED", 12),
+            ],
+        );
+        for rule in rules {
+            let category = ledger
+                .category(rule.category_id)
+                .map(|category| category.name.clone())
+                .unwrap_or_else(|| "(deleted)".into());
+            table.add_row(vec![
+                rule.pattern.clone(),
+                category,
+                rule.hits.to_string(),
+                formatters.format_date(rule.updated_at.date_naive()),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_forget(context: &mut ShellContext, target: &str) -> CommandResult {
+    if target.eq_ignore_ascii_case("--all") {
+        let removed =
+            context.with_ledger_mut(|ledger| Ok(CategorizationService::forget_all(ledger)))?;
+        io::print_success(format!("Forgot {} learned rule(s).", removed));
+        return Ok(());
+    }
+    let removed = context.with_ledger_mut(|ledger| {
+        CategorizationService::forget(ledger, target).map_err(|_| {
+            CommandError::InvalidArguments(format!("no learned rule for `{}`", target))
+        })
+    })?;
+    io::print_success(format!("Forgot learned rule `{}`.", removed.pattern));
+    Ok(())
+}
+
+fn handle_transfer_list(context: &mut ShellContext) -> CommandResult {
+    let style = context.ui_style.clone();
+    context.with_ledger(|ledger| {
+        if ledger.transfer_rules.is_empty() {
+            io::print_warning(
+                "No transfer rules yet. Add one with `rules transfer add <from> <to> <percent>`.",
+            );
+            return Ok(());
+        }
+        let mut table = Table::new(
+            Some("Transfer rules"),
+            vec![
+                TableColumn::new("#", 4),
+                TableColumn::new("INCOME INTO", 20),
+                TableColumn::new("MOVE TO", 20),
+                TableColumn::new("SHARE", 8),
+            ],
+        );
+        for (index, rule) in ledger.transfer_rules.iter().enumerate() {
+            table.add_row(vec![
+                index.to_string(),
+                account_name(ledger, rule.from_account),
+                account_name(ledger, rule.to_account),
+                format!("{}%", rule.percent),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_transfer_add(
+    context: &mut ShellContext,
+    from: &str,
+    to: &str,
+    percent: &str,
+) -> CommandResult {
+    let percent = io::parse_number(percent.trim_end_matches('%')).ok_or_else(|| {
+        CommandError::InvalidArguments(format!("percent must be numeric, got `{}`", percent))
+    })?;
+    let clock = context.clock.clone();
+    let (updated, from, to) = context.with_ledger_mut(|ledger| {
+        let from = account_id(ledger, from)?;
+        let to = account_id(ledger, to)?;
+        let updated = ledger
+            .transfer_rules
+            .iter()
+            .any(|rule| rule.from_account == from && rule.to_account == to);
+        TransferRuleService::set(ledger, from, to, percent, clock.as_ref())
+            .map_err(CommandError::from)?;
+        Ok((
+            updated,
+            account_name(ledger, from),
+            account_name(ledger, to),
+        ))
+    })?;
+    io::print_success(format!(
+        "{} rule: {}% of each income into `{}` moves to `{}`.",
+        if updated { "Updated" } else { "Added" },
+        percent,
+        from,
+        to
+    ));
+    Ok(())
+}
+
+fn handle_transfer_remove(context: &mut ShellContext, index: &str) -> CommandResult {
+    let index: usize = index
+        .parse()
+        .map_err(|_| CommandError::InvalidArguments(TRANSFER_USAGE.into()))?;
+    context.with_ledger_mut(|ledger| {
+        let id = ledger
+            .transfer_rules
+            .get(index)
+            .map(|rule| rule.id)
+            .ok_or_else(|| {
+                CommandError::InvalidArguments(format!("no transfer rule at index {}", index))
+            })?;
+        TransferRuleService::remove(ledger, id).map_err(CommandError::from)
+    })?;
+    io::print_success(format!(
+        "Removed transfer rule {}. Transfers it already made are kept.",
+        index
+    ));
+    Ok(())
+}
+
+/// Tells the user about transfers that transfer rules added when a
+/// transaction was completed.
+pub(crate) fn report_auto_transfers(context: &ShellContext, ids: &[Uuid]) -> CommandResult {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        for txn in ids.iter().filter_map(|id| ledger.transaction(*id)) {
+            let currency = txn
+                .currency
+                .as_deref()
+                .unwrap_or(ledger.base_currency().as_str());
+            io::print_info(format!(
+                "Transfer rule moved {} from `{}` to `{}`.",
+                formatters.format_amount(txn.budgeted_amount.to_major(), currency),
+                account_name(ledger, txn.from_account),
+                account_name(ledger, txn.to_account)
+            ));
+        }
+        Ok(())
+    })
+}
+
+fn account_id(ledger: &Ledger, name: &str) -> Result<Uuid, CommandError> {
+    ledger
+        .accounts
+        .iter()
+        .find(|account| account.name.eq_ignore_ascii_case(name))
+        .map(|account| account.id)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("unknown account `{}`", name)))
+}
+
+fn account_name(ledger: &Ledger, id: Uuid) -> String {
+    ledger
+        .account(id)
+        .map(|account| account.name.clone())
+        .unwrap_or_else(|| "(deleted)".into())
+}
@@ -2,23 +2,31 @@ pub mod list_simulations;
 
 use chrono::Local;
 
+use crate::cli::commands::transaction::income::IncomeFlags;
 use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::entity_ref::expand_id_flags;
 use crate::cli::io;
 use crate::cli::menus::{menu_error_to_command_error, simulation_menu};
 use crate::cli::registry::CommandEntry;
 use crate::core::services::SimulationService;
-use crate::ledger::SimulationStatus;
+use crate::ledger::{IncomeModelPatch, Ledger, SimulationStatus};
+
+const CREATE_USAGE: &str = "usage: simulation create <name> [--from <simulation>]";
+const APPLY_USAGE: &str = "usage: simulation apply <name> [--changes <n,n,...>]";
+const ROLLBACK_USAGE: &str = "usage: simulation rollback <name> [--force|--backup]";
 
 pub(crate) fn definitions() -> Vec<CommandEntry> {
     vec![CommandEntry::new(
         "simulation",
         "Manage simulations and what-if scenarios",
-        "simulation <list|create|enter|leave|apply|discard|changes|add|modify|exclude>",
+        "simulation <list|create|enter|leave|apply|rollback|discard|changes|add|modify|exclude|income|check|overlay>",
         cmd_simulation,
     )]
 }
 
 fn cmd_simulation(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let expanded = expand_id_flags(args);
+    let args: &[&str] = &expanded.iter().map(|arg| arg.as_ref()).collect::<Vec<_>>();
     if context.mode() == CliMode::Interactive && args.is_empty() {
         let selection = simulation_menu::show(context).map_err(menu_error_to_command_error)?;
         if let Some(action) = selection {
@@ -31,7 +39,7 @@ fn cmd_simulation(context: &mut ShellContext, args: &[&str]) -> CommandResult {
         dispatch_action(context, subcommand, rest)
     } else {
         Err(CommandError::InvalidArguments(
-            "usage: simulation <list|create|enter|leave|apply|discard|changes|add|modify|exclude>"
+            "usage: simulation <list|create|enter|leave|apply|rollback|discard|changes|add|modify|exclude|income|check|overlay>"
                 .into(),
         ))
     }
@@ -44,21 +52,31 @@ fn dispatch_action(context: &mut ShellContext, action: &str, args: &[&str]) -> C
         "enter" => handle_enter(context, args),
         "leave" => handle_leave(context),
         "apply" => handle_apply(context, args),
+        "rollback" => handle_rollback(context, args),
         "discard" => handle_discard(context, args),
         "changes" | "show" => handle_workflow_action(context, "changes", args),
         "add" => handle_workflow_action(context, "add", args),
         "modify" => handle_workflow_action(context, "modify", args),
         "exclude" => handle_workflow_action(context, "exclude", args),
+        "income" => handle_income(context, args),
+        "check" => handle_check(context, args),
+        "overlay" => handle_overlay(context, args),
         other => Err(CommandError::InvalidArguments(format!(
-            "unknown simulation subcommand `{}`. Available: list, create, enter, leave, apply, discard, changes, add, modify, exclude",
+            "unknown simulation subcommand `{}`. Available: list, create, enter, leave, apply, rollback, discard, changes, add, modify, exclude, income, check, overlay",
             other
         ))),
     }
 }
 
 fn handle_create(context: &mut ShellContext, args: &[&str]) -> CommandResult {
-    let name = if let Some(name) = args.first() {
-        (*name).to_string()
+    let (name, parent) = match args {
+        [] => (None, None),
+        [name] => (Some(*name), None),
+        [name, flag, parent] if flag.eq_ignore_ascii_case("--from") => (Some(*name), Some(*parent)),
+        _ => return Err(CommandError::InvalidArguments(CREATE_USAGE.into())),
+    };
+    let name = if let Some(name) = name {
+        name.to_string()
     } else {
         loop {
             let value = io::prompt_text("Simulation name", None).map_err(CommandError::from)?;
@@ -90,11 +108,20 @@ fn handle_create(context: &mut ShellContext, args: &[&str]) -> CommandResult {
         None
     };
     context.with_ledger_mut(|ledger| {
-        SimulationService::create(ledger, name.clone(), notes.clone(), context.clock.as_ref())
-            .map(|_| ())
-            .map_err(CommandError::from)
+        let clock = context.clock.as_ref();
+        match parent {
+            Some(parent) => SimulationService::branch(ledger, parent, name.clone(), notes, clock),
+            None => SimulationService::create(ledger, name.clone(), notes, clock),
+        }
+        .map(|_| ())
+        .map_err(CommandError::from)
     })?;
-    io::print_success(format!("Simulation `{}` created.", name));
+    match parent {
+        Some(parent) => {
+            io::print_success(format!("Simulation `{}` created from `{}`.", name, parent))
+        }
+        None => io::print_success(format!("Simulation `{}` created.", name)),
+    }
     Ok(())
 }
 
@@ -140,24 +167,89 @@ fn handle_leave(context: &mut ShellContext) -> CommandResult {
 }
 
 pub(super) fn handle_apply(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let (name_arg, flags) = match args.split_first() {
+        Some((first, rest)) if !first.starts_with("--") => (Some(*first), rest),
+        _ => (None, args),
+    };
+    let mut selection = match flags {
+        [] => None,
+        [flag, list] if flag.eq_ignore_ascii_case("--changes") => Some(parse_change_list(list)?),
+        _ => return Err(CommandError::InvalidArguments(APPLY_USAGE.into())),
+    };
     let name = resolve_simulation_name(
         context,
-        args.first().copied(),
+        name_arg,
         "Select a simulation to apply:",
         false,
-        "usage: simulation apply <name>",
+        APPLY_USAGE,
     )?;
-    let created = context.with_ledger(|ledger| {
+    let (created, changes) = context.with_ledger(|ledger| {
         ledger
             .simulation(&name)
-            .map(|sim| sim.created_at)
+            .map(|sim| {
+                let changes: Vec<String> = sim
+                    .changes
+                    .iter()
+                    .enumerate()
+                    .map(|(index, change)| format!("[{:>2}] {}", index, change.summary()))
+                    .collect();
+                (sim.created_at, changes)
+            })
             .ok_or_else(|| {
                 CommandError::InvalidArguments(format!("simulation `{}` not found", name))
             })
     })?;
-    context.with_ledger_mut(|ledger| {
-        SimulationService::apply(ledger, &name, context.clock.as_ref()).map_err(CommandError::from)
-    })?;
+    if selection.is_none() && context.mode() == CliMode::Interactive && changes.len() > 1 {
+        let checked = vec![true; changes.len()];
+        let Some(ticked) = io::prompt_multi_select("Changes to apply", &changes, &checked)? else {
+            io::print_info("Operation cancelled.");
+            return Ok(());
+        };
+        if ticked.is_empty() {
+            io::print_info("No changes selected.");
+            return Ok(());
+        }
+        if ticked.len() < changes.len() {
+            selection = Some(ticked);
+        }
+    }
+
+    let backup = context.ensure_pre_apply_backup()?;
+    let remaining = match selection {
+        Some(indices) => {
+            let (applied, remaining) = context.with_ledger_mut(|ledger| {
+                let applied = SimulationService::apply_changes(
+                    ledger,
+                    &name,
+                    &indices,
+                    context.clock.as_ref(),
+                )
+                .map_err(CommandError::from)?;
+                record_pre_apply_backup(ledger, &name, backup.as_deref());
+                let remaining = ledger.simulation(&name).map_or(0, |sim| sim.changes.len());
+                Ok((applied.len(), remaining))
+            })?;
+            if remaining > 0 {
+                io::print_success(format!(
+                    "Applied {} change(s) from simulation `{}`; {} change(s) remain pending.",
+                    applied, name, remaining
+                ));
+            }
+            remaining
+        }
+        None => {
+            context.with_ledger_mut(|ledger| {
+                SimulationService::apply(ledger, &name, context.clock.as_ref())
+                    .map_err(CommandError::from)?;
+                record_pre_apply_backup(ledger, &name, backup.as_deref());
+                Ok(())
+            })?;
+            0
+        }
+    };
+    if remaining > 0 {
+        return Ok(());
+    }
     if context
         .active_simulation_name()
         .map(|active| active.eq_ignore_ascii_case(&name))
@@ -174,6 +266,96 @@ pub(super) fn handle_apply(context: &mut ShellContext, args: &[&str]) -> Command
     Ok(())
 }
 
+fn record_pre_apply_backup(ledger: &mut Ledger, name: &str, backup: Option<&str>) {
+    if let (Some(sim), Some(backup)) = (ledger.simulation_mut(name), backup) {
+        sim.pre_apply_backup = Some(backup.to_string());
+    }
+}
+
+/// Undoes an applied simulation from the change-set recorded when it was
+/// applied, or with `--backup` by restoring the pre-apply backup.
+fn handle_rollback(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let (name_arg, flags) = match args.split_first() {
+        Some((first, rest)) if !first.starts_with("--") => (Some(*first), rest),
+        _ => (None, args),
+    };
+    let mut force = false;
+    let mut from_backup = false;
+    for flag in flags {
+        match flag.to_ascii_lowercase().as_str() {
+            "--force" => force = true,
+            "--backup" => from_backup = true,
+            _ => return Err(CommandError::InvalidArguments(ROLLBACK_USAGE.into())),
+        }
+    }
+    let name = resolve_simulation_name(
+        context,
+        name_arg,
+        "Select a simulation to roll back:",
+        false,
+        ROLLBACK_USAGE,
+    )?;
+    let (recorded, backup) = context.with_ledger(|ledger| {
+        let sim = ledger.simulation(&name).ok_or_else(|| {
+            CommandError::InvalidArguments(format!("simulation `{}` not found", name))
+        })?;
+        Ok((!sim.applied.is_empty(), sim.pre_apply_backup.clone()))
+    })?;
+
+    if from_backup || !recorded {
+        let backup = backup.ok_or_else(|| {
+            CommandError::InvalidArguments(format!(
+                "simulation `{}` has no recorded apply or pre-apply backup to roll back",
+                name
+            ))
+        })?;
+        let ledger_name = context.require_named_ledger()?;
+        return context.restore_backup_from_name(&ledger_name, backup);
+    }
+
+    let conflicts = context.with_ledger(|ledger| {
+        SimulationService::rollback_conflicts(ledger, &name).map_err(CommandError::from)
+    })?;
+    if !conflicts.is_empty() && !force {
+        for conflict in &conflicts {
+            io::print_warning(conflict.to_string());
+        }
+        let overwrite = context.mode() == CliMode::Interactive
+            && io::confirm_action("Roll back anyway and overwrite these changes?")
+                .map_err(CommandError::from)?;
+        if !overwrite {
+            return Err(CommandError::InvalidArguments(format!(
+                "{} transaction(s) changed since `{}` was applied; `--force` overwrites them and `--backup` restores the pre-apply backup instead",
+                conflicts.len(),
+                name
+            )));
+        }
+        force = true;
+    }
+    let reverted = context.with_ledger_mut(|ledger| {
+        SimulationService::rollback(ledger, &name, force, context.clock.as_ref())
+            .map_err(CommandError::from)
+    })?;
+    io::print_success(format!(
+        "Simulation `{}` rolled back; {} transaction(s) restored.",
+        name, reverted
+    ));
+    Ok(())
+}
+
+/// Parses `--changes 0,2,4`, using the numbers shown by `simulation changes`.
+fn parse_change_list(list: &str) -> Result<Vec<usize>, CommandError> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            item.parse::<usize>().map_err(|_| {
+                CommandError::InvalidArguments(format!("invalid change number `{}`", item))
+            })
+        })
+        .collect()
+}
+
 pub(super) fn handle_discard(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     let name = resolve_simulation_name(
         context,
@@ -239,7 +421,7 @@ pub(super) fn handle_workflow_action(
         "modify" => "Select a simulation to modify:",
         _ => "Select a simulation to inspect:",
     };
-    let name = resolve_simulation_name(context, args.get(0).copied(), prompt, true, usage)?;
+    let name = resolve_simulation_name(context, args.first().copied(), prompt, true, usage)?;
     match action {
         "changes" | "show" => context.print_simulation_changes(&name),
         "add" => context.simulation_add_transaction(&name),
@@ -252,6 +434,128 @@ pub(super) fn handle_workflow_action(
     }
 }
 
+fn handle_income(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let usage = "usage: simulation income <simulation_name> <transaction_index> [--gross N] [--withholding P] [--benefits N]";
+    let [sim_name, index, flags @ ..] = args else {
+        return Err(CommandError::InvalidArguments(usage.into()));
+    };
+    let Some(index) = context.transaction_index_from_arg(Some(index), usage, "")? else {
+        return Ok(());
+    };
+    let flags = IncomeFlags::parse(flags, usage)?;
+    context.with_ledger_mut(|ledger| {
+        let transaction_id = ledger
+            .transactions
+            .get(index)
+            .map(|txn| txn.id)
+            .ok_or_else(|| {
+                CommandError::InvalidArguments("transaction index out of range".into())
+            })?;
+        let patch = IncomeModelPatch {
+            transaction_id,
+            gross: flags.gross,
+            withholding_percent: flags.withholding_percent,
+            benefits: flags.benefits,
+        };
+        SimulationService::modify_income(ledger, sim_name, patch).map_err(CommandError::from)
+    })?;
+    io::print_success(format!(
+        "Income for transaction [{}] modified in `{}`",
+        index, sim_name
+    ));
+    Ok(())
+}
+
+fn handle_check(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let usage = "usage: simulation check [simulation_name] [--prune]";
+    let prune_flag = args.iter().any(|arg| arg.eq_ignore_ascii_case("--prune"));
+    let names: Vec<&str> = args
+        .iter()
+        .copied()
+        .filter(|arg| !arg.eq_ignore_ascii_case("--prune"))
+        .collect();
+    if names.len() > 1 {
+        return Err(CommandError::InvalidArguments(usage.into()));
+    }
+    let orphaned = context.with_ledger(|ledger| match names.first() {
+        Some(name) => SimulationService::orphaned_changes(ledger, name).map_err(CommandError::from),
+        None => Ok(SimulationService::check(ledger)),
+    })?;
+    if orphaned.is_empty() {
+        io::print_success("All simulation changes refer to existing transactions.");
+        return Ok(());
+    }
+    io::print_warning(format!(
+        "{} simulation change(s) refer to transactions that no longer exist:",
+        orphaned.len()
+    ));
+    for orphan in &orphaned {
+        io::print_info(format!(
+            "  `{}` change {}: {}",
+            orphan.simulation,
+            orphan.index + 1,
+            orphan.summary
+        ));
+    }
+
+    let prune = if prune_flag {
+        true
+    } else if context.mode() == CliMode::Interactive {
+        io::confirm_action(&format!("Remove {} orphaned change(s)?", orphaned.len()))
+            .map_err(CommandError::from)?
+    } else {
+        io::print_hint("Run `simulation check --prune` to remove them.");
+        false
+    };
+    if !prune {
+        return Ok(());
+    }
+    let mut simulations: Vec<&str> = orphaned
+        .iter()
+        .map(|orphan| orphan.simulation.as_str())
+        .collect();
+    simulations.dedup();
+    for name in simulations {
+        let removed = context.with_ledger_mut(|ledger| {
+            SimulationService::prune_orphans(ledger, name, context.clock.as_ref())
+                .map_err(CommandError::from)
+        })?;
+        io::print_success(format!(
+            "Removed {} orphaned change(s) from `{}`.",
+            removed, name
+        ));
+    }
+    Ok(())
+}
+
+fn handle_overlay(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let enabled = match args {
+        [] => context.simulation_overlay_enabled(),
+        [value] if value.eq_ignore_ascii_case("on") => true,
+        [value] if value.eq_ignore_ascii_case("off") => false,
+        _ => {
+            return Err(CommandError::InvalidArguments(
+                "usage: simulation overlay [on|off]".into(),
+            ))
+        }
+    };
+    if !args.is_empty() {
+        context.config_write().simulation_overlay = enabled;
+        context.persist_config()?;
+    }
+    match context.active_simulation_name() {
+        _ if !enabled => io::print_info("Simulation overlay is off."),
+        Some(name) => io::print_info(format!(
+            "Simulation overlay is on: summary, forecast, and list transactions show `{}` next to the base numbers.",
+            name
+        )),
+        None => io::print_info(
+            "Simulation overlay is on. It takes effect once you `simulation enter` a simulation.",
+        ),
+    }
+    Ok(())
+}
+
 fn resolve_simulation_name(
     context: &mut ShellContext,
     arg: Option<&str>,
@@ -38,6 +38,7 @@ struct SimulationEntry {
     is_active: bool,
     change_summaries: Vec<String>,
     notes: Option<String>,
+    based_on: Option<String>,
 }
 
 fn gather_entries(context: &ShellContext) -> Result<Vec<SimulationEntry>, CommandError> {
@@ -60,6 +61,11 @@ fn gather_entries(context: &ShellContext) -> Result<Vec<SimulationEntry>, Comman
                     .unwrap_or(false),
                 change_summaries: sim.changes.iter().map(|change| change.summary()).collect(),
                 notes: sim.notes.clone(),
+                based_on: sim.parent.map(|parent| {
+                    sims.iter()
+                        .find(|other| other.id == parent)
+                        .map_or_else(|| parent.to_string(), |other| other.name.clone())
+                }),
             })
             .collect();
         Ok(entries)
@@ -131,6 +137,10 @@ fn build_detail_view(entry: &SimulationEntry) -> DetailView {
         .with_field("created_at", entry.created_at.clone())
         .with_field("last_modified", entry.updated_at.clone());
 
+    if let Some(parent) = &entry.based_on {
+        view = view.with_field("based_on", format!("\"{}\"", parent));
+    }
+
     if !entry.change_summaries.is_empty() {
         view = view.with_field("change_summary", entry.change_summaries.join(", "));
     }
@@ -0,0 +1,184 @@
+//! Bank connections: `sync connect|disconnect|connections` keep the registry
+//! in the config, and `sync bank <connection>` imports what the bank reports
+//! since the last sync.
+
+use std::{fs, path::Path};
+
+use crate::cli::commands::import::{print_report, prompt_mapping};
+use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::config::{BankConnection, BankProvider};
+use crate::core::services::ImportService;
+use bufy_core::{BankConnector, BankSyncService, ImportMapping, MockBankConnector};
+
+const SYNC_USAGE: &str = "usage: sync <bank <connection> [--full] [--yes]|connect <name> mock <file>|disconnect <name>|connections>";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "sync",
+        "Import transactions from connected banks",
+        "sync <bank <connection> [--full] [--yes]|connect <name> mock <file>|disconnect <name>|connections>",
+        cmd_sync,
+    )]
+}
+
+fn cmd_sync(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((action, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(SYNC_USAGE.into()));
+    };
+    match action.to_ascii_lowercase().as_str() {
+        "bank" => sync_bank(context, rest),
+        "connect" => connect(context, rest),
+        "disconnect" => disconnect(context, rest),
+        "connections" => list_connections(context),
+        _ => Err(CommandError::InvalidArguments(SYNC_USAGE.into())),
+    }
+}
+
+fn connect(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name, provider, source] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: sync connect <name> mock <file>".into(),
+        ));
+    };
+    let provider = BankProvider::parse(provider).ok_or_else(|| {
+        CommandError::InvalidArguments(format!(
+            "unknown bank provider `{}`. Available: mock",
+            provider
+        ))
+    })?;
+    let source = std::path::absolute(source).map_err(|err| {
+        CommandError::InvalidArguments(format!("invalid path `{}`: {}", source, err))
+    })?;
+    let replaced = context
+        .config_write()
+        .bank_connections
+        .insert(
+            name.to_string(),
+            BankConnection::new(provider, source.display().to_string()),
+        )
+        .is_some();
+    context.persist_config()?;
+    if replaced {
+        io::print_success(format!(
+            "Bank connection `{}` replaced; the next sync starts over.",
+            name
+        ));
+    } else {
+        io::print_success(format!("Bank connection `{}` added.", name));
+    }
+    Ok(())
+}
+
+fn disconnect(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: sync disconnect <name>".into(),
+        ));
+    };
+    if context
+        .config_write()
+        .bank_connections
+        .remove(*name)
+        .is_none()
+    {
+        return Err(unknown_connection(name));
+    }
+    context.persist_config()?;
+    io::print_success(format!("Bank connection `{}` removed.", name));
+    Ok(())
+}
+
+fn list_connections(context: &mut ShellContext) -> CommandResult {
+    let connections = context.config_write().bank_connections.clone();
+    if connections.is_empty() {
+        io::print_info("No bank connections. Add one with `sync connect`.");
+        return Ok(());
+    }
+    for (name, connection) in connections {
+        io::print_info(format!(
+            "{}: {} {} ({} account(s) synced)",
+            name,
+            connection.provider,
+            connection.source,
+            connection.cursors.len()
+        ));
+    }
+    Ok(())
+}
+
+fn sync_bank(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    const USAGE: &str = "usage: sync bank <connection> [--full] [--yes]";
+    context.ensure_base_mode("Bank sync")?;
+    let mut name = None;
+    let mut full = false;
+    let mut assume_yes = false;
+    for arg in args {
+        match arg.to_ascii_lowercase().as_str() {
+            "--full" => full = true,
+            "--yes" => assume_yes = true,
+            _ if name.is_none() && !arg.starts_with("--") => name = Some(*arg),
+            _ => return Err(CommandError::InvalidArguments(USAGE.into())),
+        }
+    }
+    let name = name.ok_or_else(|| CommandError::InvalidArguments(USAGE.into()))?;
+    let connection = context
+        .config_write()
+        .bank_connections
+        .get(name)
+        .cloned()
+        .ok_or_else(|| unknown_connection(name))?;
+
+    let connector = open_connector(&connection)?;
+    let cursors = if full {
+        Default::default()
+    } else {
+        connection.cursors.clone()
+    };
+    let sync = BankSyncService::fetch(connector.as_ref(), &cursors).map_err(CommandError::from)?;
+    let bundle = sync.bundle;
+    if !bundle.transactions.is_empty() {
+        let interactive = context.mode() == CliMode::Interactive && !assume_yes;
+        let mapping = context.with_ledger(|ledger| {
+            let mut mapping = ImportMapping::suggest(ledger, &bundle);
+            if interactive {
+                prompt_mapping(ledger, &bundle, &mut mapping)?;
+            }
+            Ok(mapping)
+        })?;
+        let report = context.with_ledger_mut(|ledger| {
+            ImportService::apply(ledger, &bundle, &mapping).map_err(CommandError::from)
+        })?;
+        print_report(&bundle, &report);
+    } else {
+        io::print_info(format!("No new transactions from `{}`.", name));
+    }
+
+    if let Some(stored) = context.config_write().bank_connections.get_mut(name) {
+        stored.cursors = sync.cursors;
+    }
+    context.persist_config()
+}
+
+/// The connector behind `connection`.
+fn open_connector(connection: &BankConnection) -> Result<Box<dyn BankConnector>, CommandError> {
+    match connection.provider {
+        BankProvider::Mock => {
+            let path = Path::new(&connection.source);
+            let json = fs::read_to_string(path).map_err(|err| {
+                CommandError::InvalidArguments(format!("cannot read {}: {}", path.display(), err))
+            })?;
+            Ok(Box::new(
+                MockBankConnector::from_json(&json).map_err(CommandError::from)?,
+            ))
+        }
+    }
+}
+
+fn unknown_connection(name: &str) -> CommandError {
+    CommandError::InvalidArguments(format!(
+        "unknown bank connection `{}`. See `sync connections`",
+        name
+    ))
+}
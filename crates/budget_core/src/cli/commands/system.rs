@@ -4,7 +4,7 @@ use crate::cli::registry::CommandEntry;
 use crate::cli::ui::{Table, TableColumn, TableRenderer};
 use crate::config::CONFIG_BACKUP_SCHEMA_VERSION;
 use crate::utils::build_info;
-use bufy_domain::CURRENT_SCHEMA_VERSION;
+use bufy_domain::{CURRENT_SCHEMA_MINOR, CURRENT_SCHEMA_VERSION};
 
 pub(crate) fn definitions() -> Vec<CommandEntry> {
     vec![
@@ -31,7 +31,7 @@ fn cmd_version(context: &mut ShellContext, _args: &[&str]) -> CommandResult {
     ]);
     table.add_row(vec![
         "Schema ver".to_string(),
-        format!("v{}", CURRENT_SCHEMA_VERSION),
+        format!("v{}.{}", CURRENT_SCHEMA_VERSION, CURRENT_SCHEMA_MINOR),
     ]);
     table.add_row(vec![
         "Config schema".to_string(),
@@ -1,22 +1,36 @@
+pub(crate) mod asset;
+pub(crate) mod attachment;
+pub(crate) mod contract;
+pub(crate) mod dates;
+pub(crate) mod export;
+pub(crate) mod filters;
+pub(crate) mod income;
 pub mod list_transactions;
+pub(crate) mod price;
+pub(crate) mod search;
+pub(crate) mod tag;
 
 use chrono::Utc;
 
+use crate::cli::commands::paycheck;
 use crate::cli::core::{CliMode, CommandError, CommandResult, RecurrenceListFilter, ShellContext};
+use crate::cli::entity_ref::expand_id_flags;
 use crate::cli::io;
 use crate::cli::menus::{menu_error_to_command_error, transaction_menu};
 use crate::cli::registry::CommandEntry;
-use crate::ledger::RecurrenceStatus;
+use crate::ledger::{BusinessDayShift, RecurrenceStatus};
 pub(crate) fn definitions() -> Vec<CommandEntry> {
     vec![CommandEntry::new(
         "transaction",
         "Manage transactions via wizard flows",
-        "transaction <add|edit|remove|show|list|complete|recurring>",
+        "transaction <add|edit|remove|show|list|search|export|complete|dates|recurring|asset|tag|attach|attachments>",
         cmd_transaction,
     )]
 }
 
 fn cmd_transaction(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let expanded = expand_id_flags(args);
+    let args: &[&str] = &expanded.iter().map(|arg| arg.as_ref()).collect::<Vec<_>>();
     if context.mode() == CliMode::Interactive && args.is_empty() {
         return run_transaction_menu(context);
     }
@@ -25,7 +39,8 @@ fn cmd_transaction(context: &mut ShellContext, args: &[&str]) -> CommandResult {
         dispatch_transaction_action(context, subcommand, rest)
     } else {
         Err(CommandError::InvalidArguments(
-            "usage: transaction <add|edit|remove|show|list|complete|recurring>".into(),
+            "usage: transaction <add|edit|remove|show|list|search|export|complete|dates|recurring|asset|tag|attach|attachments>"
+                .into(),
         ))
     }
 }
@@ -48,9 +63,16 @@ fn dispatch_transaction_action(
         "edit" => handle_edit(context, args),
         "remove" => handle_remove(context, args),
         "show" => handle_show(context, args),
-        "list" => handle_list(context),
+        "list" => handle_list(context, args),
+        "search" => search::run_search(context, args),
+        "export" => export::run_export(context, args),
         "complete" => handle_complete(context, args),
+        "dates" => dates::run_dates(context, args),
         "recurring" => handle_recurring(context, args),
+        "asset" => asset::run_asset(context, args),
+        "tag" => tag::run_tag(context, args),
+        "attach" => attachment::run_attach(context, args),
+        "attachments" => attachment::run_attachments(context, args),
         other => Err(CommandError::InvalidArguments(format!(
             "unknown transaction subcommand `{}`",
             other
@@ -59,6 +81,11 @@ fn dispatch_transaction_action(
 }
 
 fn handle_add(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    if let Some((flag, rest)) = args.split_first() {
+        if flag.eq_ignore_ascii_case("--paycheck") {
+            return paycheck::receive(context, rest);
+        }
+    }
     context.transaction_add(args)
 }
 
@@ -74,8 +101,8 @@ fn handle_show(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     context.transaction_show(args)
 }
 
-fn handle_list(context: &mut ShellContext) -> CommandResult {
-    list_transactions::run_list_transactions(context)
+fn handle_list(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    list_transactions::run_filtered_list_transactions(context, args)
 }
 
 fn handle_complete(context: &mut ShellContext, args: &[&str]) -> CommandResult {
@@ -161,6 +188,29 @@ fn handle_recurring(context: &mut ShellContext, args: &[&str]) -> CommandResult
             };
             context.recurrence_skip_date(idx, date)
         }
+        "business-days" => {
+            const USAGE: &str =
+                "usage: transaction recurring business-days <transaction_index> <following|preceding|keep>";
+            let idx = match context.transaction_index_from_arg(
+                args.get(1).copied(),
+                USAGE,
+                "Select a transaction to move off non-business days:",
+            )? {
+                Some(idx) => idx,
+                None => return Ok(()),
+            };
+            let shift = match args.get(2).map(|value| value.to_lowercase()).as_deref() {
+                Some("following") => BusinessDayShift::Following,
+                Some("preceding") => BusinessDayShift::Preceding,
+                Some("keep") => BusinessDayShift::Keep,
+                _ => return Err(CommandError::InvalidArguments(USAGE.into())),
+            };
+            context.recurrence_set_business_day_shift(idx, shift)
+        }
+        "income" => income::run_income(context, &args[1..]),
+        "contract" => contract::run_contract(context, &args[1..]),
+        "price" => price::run_price(context, &args[1..]),
+        "price-history" => price::run_price_history(context, &args[1..]),
         "sync" => {
             let reference = if args.len() > 1 {
                 crate::cli::core::parse_date(args[1])?
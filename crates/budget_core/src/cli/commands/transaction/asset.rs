@@ -0,0 +1,93 @@
+//! `transaction asset`: record the item a purchase bought, with its warranty
+//! end and expected lifetime.
+
+use bufy_domain::{common::TimeUnit, transaction::AssetRecord};
+
+use crate::cli::core::{
+    parse_date, parse_time_interval_str, CommandError, CommandResult, ShellContext,
+};
+use crate::cli::io as cli_io;
+use crate::core::services::AssetService;
+
+const ASSET_USAGE: &str = "usage: transaction asset <transaction_index> <name> [--warranty YYYY-MM-DD] [--lifetime INTERVAL] | transaction asset <transaction_index> clear";
+
+pub(crate) fn run_asset(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((index, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(ASSET_USAGE.into()));
+    };
+    let Some(index) = context.transaction_index_from_arg(Some(index), ASSET_USAGE, "")? else {
+        return Ok(());
+    };
+    let record = match rest {
+        [clear] if clear.eq_ignore_ascii_case("clear") => None,
+        [name, flags @ ..] => {
+            let mut record = AssetRecord {
+                name: name.to_string(),
+                warranty_until: None,
+                lifetime_months: None,
+            };
+            let mut iter = flags.iter();
+            while let Some(flag) = iter.next() {
+                let value = iter.next().ok_or_else(|| {
+                    CommandError::InvalidArguments(format!("{} requires a value", flag))
+                })?;
+                match flag.to_ascii_lowercase().as_str() {
+                    "--warranty" => record.warranty_until = Some(parse_date(value)?),
+                    "--lifetime" => record.lifetime_months = Some(parse_lifetime(value)?),
+                    _ => return Err(CommandError::InvalidArguments(ASSET_USAGE.into())),
+                }
+            }
+            Some(record)
+        }
+        _ => return Err(CommandError::InvalidArguments(ASSET_USAGE.into())),
+    };
+
+    let Some(record) = record else {
+        let cleared = context.with_ledger_mut(|ledger| {
+            let id = transaction_id(ledger, index)?;
+            AssetService::clear(ledger, id).map_err(CommandError::from)
+        })?;
+        if cleared {
+            cli_io::print_success(format!("Asset removed from transaction [{}].", index));
+        } else {
+            cli_io::print_info(format!("Transaction [{}] has no asset.", index));
+        }
+        return Ok(());
+    };
+    let name = record.name.trim().to_string();
+    context.with_ledger_mut(|ledger| {
+        let id = transaction_id(ledger, index)?;
+        AssetService::set(ledger, id, record).map_err(CommandError::from)
+    })?;
+    cli_io::print_success(format!(
+        "Asset `{}` recorded on transaction [{}].",
+        name, index
+    ));
+    Ok(())
+}
+
+/// Lifetimes are kept in months; `36`, `36m`, `3y`, and `3 years` all work.
+fn parse_lifetime(value: &str) -> Result<u32, CommandError> {
+    if let Ok(months) = value.trim().parse::<u32>() {
+        return Ok(months);
+    }
+    let interval = parse_time_interval_str(value)?;
+    match interval.unit {
+        TimeUnit::Month => Ok(interval.every),
+        TimeUnit::Year => Ok(interval.every.saturating_mul(12)),
+        _ => Err(CommandError::InvalidArguments(
+            "lifetime must be given in months or years".into(),
+        )),
+    }
+}
+
+fn transaction_id(
+    ledger: &crate::ledger::Ledger,
+    index: usize,
+) -> Result<uuid::Uuid, CommandError> {
+    ledger
+        .transactions
+        .get(index)
+        .map(|txn| txn.id)
+        .ok_or_else(|| CommandError::InvalidArguments("transaction index out of range".into()))
+}
@@ -0,0 +1,117 @@
+//! `transaction attach` and `transaction attachments`: receipts and links
+//! kept with a transaction, copied next to the ledger file.
+//!
+//! Attaching a file saves the ledger right away, so the copy and the
+//! ledger that points at it are written together; when the save fails the
+//! attachment is backed out and its copy removed.
+
+use std::path::{Path, PathBuf};
+
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io as cli_io;
+use crate::core::services::{AttachmentService, AttachmentStatus};
+use crate::ledger::{Ledger, Transaction};
+
+const ATTACH_USAGE: &str = "usage: transaction attach <transaction_index> <path|url>";
+const ATTACHMENTS_USAGE: &str = "usage: transaction attachments <transaction_index>";
+
+pub(crate) fn run_attach(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [index, source] = args else {
+        return Err(CommandError::InvalidArguments(ATTACH_USAGE.into()));
+    };
+    let Some(index) = context.transaction_index_from_arg(Some(index), ATTACH_USAGE, "")? else {
+        return Ok(());
+    };
+    let id = context.with_ledger(|ledger| Ok(transaction(ledger, index)?.id))?;
+    let attachment = if source.starts_with("http://") || source.starts_with("https://") {
+        context.with_ledger_mut(|ledger| {
+            AttachmentService::attach_link(ledger, id, source).map_err(CommandError::from)
+        })?
+    } else {
+        let dir = attachments_dir(context).ok_or_else(|| {
+            CommandError::InvalidArguments(
+                "save the ledger with `ledger save` before attaching files".into(),
+            )
+        })?;
+        let attachment = context.with_ledger_mut(|ledger| {
+            AttachmentService::attach_file(ledger, id, &dir, Path::new(source))
+                .map_err(CommandError::from)
+        })?;
+        if let Err(err) = save_ledger(context) {
+            context.with_ledger_mut(|ledger| {
+                AttachmentService::detach(ledger, id, &dir, &attachment.location)
+                    .map_err(CommandError::from)
+            })?;
+            return Err(err);
+        }
+        attachment
+    };
+    cli_io::print_success(format!(
+        "Attached {} to transaction [{}].",
+        attachment.name, index
+    ));
+    Ok(())
+}
+
+pub(crate) fn run_attachments(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [index] = args else {
+        return Err(CommandError::InvalidArguments(ATTACHMENTS_USAGE.into()));
+    };
+    let Some(index) = context.transaction_index_from_arg(Some(index), ATTACHMENTS_USAGE, "")?
+    else {
+        return Ok(());
+    };
+    let dir = attachments_dir(context);
+    context.with_ledger(|ledger| {
+        let attachments = &transaction(ledger, index)?.attachments;
+        if attachments.is_empty() {
+            cli_io::print_info(format!("Transaction [{}] has no attachments.", index));
+            return Ok(());
+        }
+        for attachment in attachments {
+            let status = match &dir {
+                Some(dir) => AttachmentService::status(dir, attachment),
+                None if attachment.is_link() => AttachmentStatus::Link,
+                None => AttachmentStatus::Missing,
+            };
+            let shown = match (status, &dir) {
+                (AttachmentStatus::Link, _) | (_, None) => attachment.location.clone(),
+                (_, Some(dir)) => dir.join(&attachment.location).display().to_string(),
+            };
+            let note = match status {
+                AttachmentStatus::Link | AttachmentStatus::Present => "",
+                AttachmentStatus::Missing => " (missing)",
+                AttachmentStatus::Modified => " (changed since attached)",
+            };
+            cli_io::print_info(format!("{:<24} {}{}", attachment.name, shown, note));
+        }
+        Ok(())
+    })
+}
+
+/// The current ledger's attachments folder; `None` until it has a file.
+fn attachments_dir(context: &ShellContext) -> Option<PathBuf> {
+    context
+        .ledger_path()
+        .map(|path| AttachmentService::attachments_dir(&path))
+}
+
+/// Writes the current ledger back where it came from.
+fn save_ledger(context: &mut ShellContext) -> CommandResult {
+    if let Some(name) = context.ledger_name().map(|name| name.to_string()) {
+        context.save_named_ledger(&name)
+    } else if let Some(path) = context.ledger_path() {
+        context.save_to_path(&path)
+    } else {
+        Err(CommandError::InvalidArguments(
+            "save the ledger with `ledger save` before attaching files".into(),
+        ))
+    }
+}
+
+fn transaction(ledger: &Ledger, index: usize) -> Result<&Transaction, CommandError> {
+    ledger
+        .transactions
+        .get(index)
+        .ok_or_else(|| CommandError::InvalidArguments("transaction index out of range".into()))
+}
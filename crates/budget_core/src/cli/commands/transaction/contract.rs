@@ -0,0 +1,77 @@
+//! `transaction recurring contract`: record who a recurring bill is
+//! contracted with, when the term ends, and how much notice it needs.
+
+use bufy_core::DateFormatter;
+use bufy_domain::transaction::ContractTerms;
+
+use crate::cli::core::{
+    parse_date, parse_time_interval_str, CommandError, CommandResult, ShellContext,
+};
+use crate::cli::io as cli_io;
+use crate::core::services::ContractService;
+
+const CONTRACT_USAGE: &str = "usage: transaction recurring contract <transaction_index> <provider> <end YYYY-MM-DD> [--notice INTERVAL] | transaction recurring contract <transaction_index> clear";
+
+pub(crate) fn run_contract(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((index, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(CONTRACT_USAGE.into()));
+    };
+    let Some(index) = context.transaction_index_from_arg(Some(index), CONTRACT_USAGE, "")? else {
+        return Ok(());
+    };
+    let terms = match rest {
+        [clear] if clear.eq_ignore_ascii_case("clear") => None,
+        [provider, end, flags @ ..] => {
+            let notice_period = match flags {
+                [] => None,
+                [flag, value] if flag.eq_ignore_ascii_case("--notice") => {
+                    Some(parse_time_interval_str(value)?)
+                }
+                _ => return Err(CommandError::InvalidArguments(CONTRACT_USAGE.into())),
+            };
+            Some(ContractTerms {
+                provider: provider.to_string(),
+                end_date: parse_date(end)?,
+                notice_period,
+            })
+        }
+        _ => return Err(CommandError::InvalidArguments(CONTRACT_USAGE.into())),
+    };
+
+    let Some(terms) = terms else {
+        let cleared = context.with_ledger_mut(|ledger| {
+            let id = transaction_id(ledger, index)?;
+            ContractService::clear(ledger, id).map_err(CommandError::from)
+        })?;
+        if cleared {
+            cli_io::print_success(format!("Contract removed from transaction [{}].", index));
+        } else {
+            cli_io::print_info(format!("Transaction [{}] has no contract.", index));
+        }
+        return Ok(());
+    };
+    let deadline = terms.notice_deadline();
+    let provider = terms.provider.trim().to_string();
+    context.with_ledger_mut(|ledger| {
+        let id = transaction_id(ledger, index)?;
+        ContractService::set(ledger, id, terms).map_err(CommandError::from)
+    })?;
+    cli_io::print_success(format!(
+        "Contract with {} recorded on transaction [{}]; give notice by {}.",
+        provider,
+        index,
+        context.formatters.format_date(deadline)
+    ));
+    Ok(())
+}
+
+fn transaction_id(
+    ledger: &crate::ledger::Ledger,
+    index: usize,
+) -> Result<uuid::Uuid, CommandError> {
+    ledger
+        .transactions
+        .get(index)
+        .map(|txn| txn.id)
+        .ok_or_else(|| CommandError::InvalidArguments("transaction index out of range".into()))
+}
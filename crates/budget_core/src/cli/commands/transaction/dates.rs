@@ -0,0 +1,68 @@
+//! `transaction dates <index> ...`: records the booking and value dates a
+//! bank statement gives for a transaction.
+
+use chrono::NaiveDate;
+
+use crate::cli::core::{parse_date, CommandError, CommandResult, ShellContext};
+use crate::cli::io as cli_io;
+use crate::core::services::TransactionService;
+
+const DATES_USAGE: &str =
+    "usage: transaction dates <transaction_index> [--booking <YYYY-MM-DD|none>] [--value <YYYY-MM-DD|none>]";
+
+pub(crate) fn run_dates(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((index, flags)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(DATES_USAGE.into()));
+    };
+    if flags.is_empty() {
+        return Err(CommandError::InvalidArguments(DATES_USAGE.into()));
+    }
+    let mut booking = None;
+    let mut value = None;
+    let mut iter = flags.iter();
+    while let Some(flag) = iter.next() {
+        let raw = iter
+            .next()
+            .ok_or_else(|| CommandError::InvalidArguments(format!("{} requires a value", flag)))?;
+        let date = parse_optional_date(raw)?;
+        match flag.to_ascii_lowercase().as_str() {
+            "--booking" => booking = Some(date),
+            "--value" => value = Some(date),
+            _ => return Err(CommandError::InvalidArguments(DATES_USAGE.into())),
+        }
+    }
+    let Some(index) = context.transaction_index_from_arg(Some(index), DATES_USAGE, "")? else {
+        return Ok(());
+    };
+    context.with_ledger_mut(|ledger| {
+        let id = ledger
+            .transactions
+            .get(index)
+            .map(|txn| txn.id)
+            .ok_or_else(|| {
+                CommandError::InvalidArguments("transaction index out of range".into())
+            })?;
+        TransactionService::update(ledger, id, |txn| {
+            if let Some(date) = booking {
+                txn.booking_date = date;
+            }
+            if let Some(date) = value {
+                txn.value_date = date;
+            }
+        })
+        .map_err(CommandError::from)
+    })?;
+    cli_io::print_success(format!(
+        "Statement dates updated for transaction [{}].",
+        index
+    ));
+    Ok(())
+}
+
+fn parse_optional_date(raw: &str) -> Result<Option<NaiveDate>, CommandError> {
+    if raw.eq_ignore_ascii_case("none") {
+        Ok(None)
+    } else {
+        parse_date(raw).map(Some)
+    }
+}
@@ -0,0 +1,143 @@
+//! `transaction export <path> [filters] [--view name] [--snapshot] [--redact]`:
+//! writes the filtered transaction set to CSV, or a reporting snapshot to CSV
+//! or Parquet. `--redact` leaves out merchant, location, and notes.
+
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+use crate::cli::commands::transaction::filters::{filter_error, TransactionFilterArgs};
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io as cli_io;
+use crate::core::services::{ExportService, TransactionService, ViewService};
+
+const EXPORT_USAGE: &str =
+    "usage: transaction export <path> [--from D] [--to D] [--account A] [--category C] [--member M] [--status S] [--min N] [--max N] [--tag T] [--columns a,b] [--view name] [--snapshot] [--redact]";
+
+pub(crate) fn run_export(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((path, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(EXPORT_USAGE.into()));
+    };
+    if path.starts_with("--") {
+        return Err(CommandError::InvalidArguments(EXPORT_USAGE.into()));
+    }
+    let (view_name, flags) = take_view_flag(rest)?;
+    let has_flag = |name: &str| flags.iter().any(|flag| flag.eq_ignore_ascii_case(name));
+    let snapshot = has_flag("--snapshot");
+    let redact = has_flag("--redact");
+    let flags: Vec<&str> = flags
+        .iter()
+        .copied()
+        .filter(|flag| {
+            !flag.eq_ignore_ascii_case("--snapshot") && !flag.eq_ignore_ascii_case("--redact")
+        })
+        .collect();
+    let parsed = TransactionFilterArgs::parse(&flags)?;
+    if parsed.json {
+        return Err(CommandError::InvalidArguments(
+            "--json is not supported by `transaction export`".into(),
+        ));
+    }
+    if view_name.is_some() && !parsed.is_unfiltered() {
+        return Err(CommandError::InvalidArguments(
+            "--view cannot be combined with filter flags".into(),
+        ));
+    }
+    if snapshot && !parsed.columns.is_empty() {
+        return Err(CommandError::InvalidArguments(
+            "--columns cannot be combined with --snapshot; snapshots use a fixed layout".into(),
+        ));
+    }
+    let path = PathBuf::from(path);
+    let parquet = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("parquet"));
+    if parquet && !snapshot {
+        return Err(CommandError::InvalidArguments(
+            "Parquet output requires --snapshot".into(),
+        ));
+    }
+    let today = context.clock.today();
+
+    let count = context.with_ledger(|ledger| {
+        let (transactions, columns) = match view_name.as_deref() {
+            Some(name) => {
+                let (view, transactions) = ViewService::run(ledger, name).map_err(filter_error)?;
+                let columns = if parsed.columns.is_empty() {
+                    view.columns.clone()
+                } else {
+                    parsed.columns.clone()
+                };
+                (transactions, columns)
+            }
+            None => {
+                let query = parsed.resolve(ledger)?;
+                (
+                    TransactionService::query(ledger, &query),
+                    parsed.columns_or_default(),
+                )
+            }
+        };
+        let count = transactions.len();
+        let redacted;
+        let transactions = if redact {
+            redacted = ExportService::redacted(&transactions);
+            redacted.iter().collect()
+        } else {
+            transactions
+        };
+        if !snapshot {
+            let csv = ExportService::transactions_csv(ledger, &transactions, &columns);
+            fs::write(&path, csv)?;
+            return Ok::<_, CommandError>(count);
+        }
+        let rows = ExportService::snapshot_rows(ledger, &transactions, today);
+        if parquet {
+            ExportService::snapshot_parquet(&rows, File::create(&path)?)
+                .map_err(CommandError::from)?;
+        } else {
+            fs::write(&path, ExportService::snapshot_csv(&rows))?;
+        }
+        Ok(count)
+    })?;
+
+    cli_io::print_success(format!(
+        "Exported {} transaction(s) to {}{}{}.",
+        count,
+        path.display(),
+        if snapshot {
+            " as a reporting snapshot"
+        } else {
+            ""
+        },
+        if redact {
+            " without merchant, location, or notes"
+        } else {
+            ""
+        }
+    ));
+    Ok(())
+}
+
+/// Removes `--view <name>` (or `--view=<name>`) from the flag list.
+fn take_view_flag<'a>(args: &[&'a str]) -> Result<(Option<String>, Vec<&'a str>), CommandError> {
+    let mut view = None;
+    let mut rest = Vec::with_capacity(args.len());
+    let mut iter = args.iter();
+    while let Some(token) = iter.next() {
+        if token.eq_ignore_ascii_case("--view") {
+            let name = iter
+                .next()
+                .ok_or_else(|| CommandError::InvalidArguments("--view requires a value".into()))?;
+            view = Some(name.to_string());
+        } else if let Some(name) = token
+            .split_once('=')
+            .filter(|(flag, _)| flag.eq_ignore_ascii_case("--view"))
+            .map(|(_, name)| name)
+        {
+            view = Some(name.to_string());
+        } else {
+            rest.push(*token);
+        }
+    }
+    Ok((view, rest))
+}
@@ -0,0 +1,348 @@
+//! Flag parsing and column rendering for filtered transaction listings
+//! (`list transactions`, `transaction list`, `view`).
+
+use chrono::NaiveDate;
+
+use crate::cli::core::{parse_date, CommandError};
+use crate::cli::formatters::CliFormatters;
+use crate::cli::io;
+use crate::cli::ui::{Table, TableColumn};
+use crate::core::services::{ServiceError, TransactionQuery, TransactionService};
+use crate::ledger::Ledger;
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use bufy_domain::transaction::{Transaction, TransactionStatus};
+use bufy_domain::view::{TransactionFilter, ViewColumn};
+
+const NO_VALUE: &str = "—";
+
+/// Filter flags as typed by the user, plus output options.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct TransactionFilterArgs {
+    pub filter: TransactionFilter,
+    /// Columns chosen with `--columns`; empty means the defaults.
+    pub columns: Vec<ViewColumn>,
+    pub json: bool,
+}
+
+impl TransactionFilterArgs {
+    pub(crate) fn parse(args: &[&str]) -> Result<Self, CommandError> {
+        let mut parsed = Self::default();
+        let filter = &mut parsed.filter;
+        let mut idx = 0;
+        while idx < args.len() {
+            let token = args[idx];
+            let (flag, inline) = match token.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (token, None),
+            };
+            let flag = flag.to_ascii_lowercase();
+            if flag == "--json" {
+                parsed.json = true;
+                idx += 1;
+                continue;
+            }
+            if !flag.starts_with("--") {
+                return Err(CommandError::InvalidArguments(format!(
+                    "unexpected argument `{}`",
+                    token
+                )));
+            }
+            let value = match inline {
+                Some(value) => value,
+                None => {
+                    idx += 1;
+                    args.get(idx)
+                        .map(|value| value.to_string())
+                        .ok_or_else(|| {
+                            CommandError::InvalidArguments(format!("{} requires a value", flag))
+                        })?
+                }
+            };
+            match flag.as_str() {
+                "--from" => filter.from = Some(parse_date(&value)?),
+                "--to" => filter.to = Some(parse_date(&value)?),
+                "--account" => filter.account = Some(value),
+                "--category" => filter.category = Some(value),
+                "--member" => filter.member = Some(value),
+                "--status" => filter.status = Some(parse_status(&value)?),
+                "--min" => filter.min_amount = Some(parse_filter_amount(&value)?),
+                "--max" => filter.max_amount = Some(parse_filter_amount(&value)?),
+                "--notes" => filter.notes = Some(value),
+                "--text" => filter.text = Some(value),
+                "--recurring" => filter.recurring = Some(parse_yes_no(&flag, &value)?),
+                "--tag" => filter.tag = Some(value),
+                "--columns" => parsed.columns = parse_columns(&value)?,
+                other => {
+                    return Err(CommandError::InvalidArguments(format!(
+                        "unknown flag `{}`",
+                        other
+                    )))
+                }
+            }
+            idx += 1;
+        }
+
+        if let (Some(from), Some(to)) = (filter.from, filter.to) {
+            if from > to {
+                return Err(CommandError::InvalidArguments(
+                    "--from must not be after --to".into(),
+                ));
+            }
+        }
+        if let (Some(min), Some(max)) = (filter.min_amount, filter.max_amount) {
+            if min > max {
+                return Err(CommandError::InvalidArguments(
+                    "--min must not exceed --max".into(),
+                ));
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Returns `true` when no narrowing criterion was supplied.
+    pub(crate) fn is_unfiltered(&self) -> bool {
+        self.filter.is_empty()
+    }
+
+    /// Resolves account and category names into a [`TransactionQuery`].
+    pub(crate) fn resolve(&self, ledger: &Ledger) -> Result<TransactionQuery, CommandError> {
+        TransactionService::resolve_filter(ledger, &self.filter).map_err(filter_error)
+    }
+
+    /// Columns to render, falling back to the defaults.
+    pub(crate) fn columns_or_default(&self) -> Vec<ViewColumn> {
+        if self.columns.is_empty() {
+            ViewColumn::default_columns()
+        } else {
+            self.columns.clone()
+        }
+    }
+}
+
+/// Converts filter and view lookup failures into user-facing messages.
+pub(crate) fn filter_error(err: ServiceError) -> CommandError {
+    match err {
+        ServiceError::AccountNotFound(name) => {
+            CommandError::InvalidArguments(format!("unknown account `{}`", name))
+        }
+        ServiceError::CategoryNotFound(name) => {
+            CommandError::InvalidArguments(format!("unknown category `{}`", name))
+        }
+        ServiceError::MemberNotFound(name) => {
+            CommandError::InvalidArguments(format!("unknown member `{}`", name))
+        }
+        ServiceError::ViewNotFound(name) => {
+            CommandError::InvalidArguments(format!("view `{}` not found", name))
+        }
+        other => CommandError::from(other),
+    }
+}
+
+/// Serializes the supplied transactions as pretty-printed JSON.
+pub(crate) fn transactions_json(transactions: &[&Transaction]) -> Result<String, CommandError> {
+    serde_json::to_string_pretty(transactions).map_err(CommandError::from)
+}
+
+/// Builds a table with one row per transaction and the requested columns.
+pub(crate) fn transactions_table(
+    title: impl Into<String>,
+    ledger: &Ledger,
+    transactions: &[&Transaction],
+    columns: &[ViewColumn],
+    formatters: &CliFormatters,
+) -> Table {
+    let mut table = Table::new(
+        Some(title.into()),
+        columns
+            .iter()
+            .map(|column| {
+                TableColumn::new(column.to_string().to_uppercase(), column_width(*column))
+            })
+            .collect(),
+    );
+    for txn in transactions {
+        table.add_row(
+            columns
+                .iter()
+                .map(|column| column_value(ledger, txn, *column, formatters))
+                .collect(),
+        );
+    }
+    table
+}
+
+/// Describes a filter in `--flag value` form, e.g. for `view show`.
+pub(crate) fn describe_filter(filter: &TransactionFilter) -> String {
+    let mut parts = Vec::new();
+    if let Some(from) = filter.from {
+        parts.push(format!("--from {}", from));
+    }
+    if let Some(to) = filter.to {
+        parts.push(format!("--to {}", to));
+    }
+    if let Some(account) = &filter.account {
+        parts.push(format!("--account \"{}\"", account));
+    }
+    if let Some(category) = &filter.category {
+        parts.push(format!("--category \"{}\"", category));
+    }
+    if let Some(member) = &filter.member {
+        parts.push(format!("--member \"{}\"", member));
+    }
+    if let Some(status) = &filter.status {
+        parts.push(format!(
+            "--status {}",
+            status.to_string().to_ascii_lowercase()
+        ));
+    }
+    if let Some(min) = filter.min_amount {
+        parts.push(format!("--min {}", min));
+    }
+    if let Some(max) = filter.max_amount {
+        parts.push(format!("--max {}", max));
+    }
+    if let Some(notes) = &filter.notes {
+        parts.push(format!("--notes \"{}\"", notes));
+    }
+    if let Some(text) = &filter.text {
+        parts.push(format!("--text \"{}\"", text));
+    }
+    if let Some(recurring) = filter.recurring {
+        parts.push(format!(
+            "--recurring {}",
+            if recurring { "yes" } else { "no" }
+        ));
+    }
+    if let Some(tag) = &filter.tag {
+        parts.push(format!("--tag {}", tag));
+    }
+    if parts.is_empty() {
+        "(all transactions)".into()
+    } else {
+        parts.join(" ")
+    }
+}
+
+fn column_width(column: ViewColumn) -> usize {
+    match column {
+        ViewColumn::Reference => 8,
+        ViewColumn::Date
+        | ViewColumn::ActualDate
+        | ViewColumn::BookingDate
+        | ViewColumn::ValueDate => 12,
+        ViewColumn::From | ViewColumn::To => 16,
+        ViewColumn::Category => 18,
+        ViewColumn::Member => 14,
+        ViewColumn::Budgeted | ViewColumn::Actual => 12,
+        ViewColumn::Currency => 8,
+        ViewColumn::Status => 10,
+        ViewColumn::Notes => 24,
+        ViewColumn::Merchant => 18,
+        ViewColumn::Location => 24,
+        ViewColumn::Tags => 20,
+    }
+}
+
+fn column_value(
+    ledger: &Ledger,
+    txn: &Transaction,
+    column: ViewColumn,
+    formatters: &CliFormatters,
+) -> String {
+    let account_name = |id| {
+        ledger
+            .account(id)
+            .map(|account| account.name.clone())
+            .unwrap_or_else(|| "Unknown".into())
+    };
+    match column {
+        ViewColumn::Reference => txn
+            .short_ref()
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| NO_VALUE.into()),
+        ViewColumn::Date => formatters.format_date(txn.scheduled_date),
+        ViewColumn::ActualDate => optional_date(txn.actual_date, formatters),
+        ViewColumn::BookingDate => optional_date(txn.booking_date, formatters),
+        ViewColumn::ValueDate => optional_date(txn.value_date, formatters),
+        ViewColumn::From => account_name(txn.from_account),
+        ViewColumn::To => account_name(txn.to_account),
+        ViewColumn::Category => txn
+            .category_id
+            .and_then(|id| ledger.category(id))
+            .map(|category| category.name.clone())
+            .unwrap_or_else(|| NO_VALUE.into()),
+        ViewColumn::Member => txn
+            .member_id
+            .and_then(|id| ledger.member(id))
+            .map(|member| member.name.clone())
+            .unwrap_or_else(|| NO_VALUE.into()),
+        ViewColumn::Budgeted => formatters.format_amount(txn.budgeted_amount.to_major(), ""),
+        ViewColumn::Actual => txn
+            .actual_amount
+            .as_ref()
+            .map(|amount| formatters.format_amount(amount.to_major(), ""))
+            .unwrap_or_else(|| NO_VALUE.into()),
+        ViewColumn::Currency => ledger.transaction_currency(txn).as_str().to_string(),
+        ViewColumn::Status => txn.status.to_string(),
+        ViewColumn::Notes => txn.notes.clone().unwrap_or_else(|| NO_VALUE.into()),
+        ViewColumn::Merchant => txn.merchant.clone().unwrap_or_else(|| NO_VALUE.into()),
+        ViewColumn::Location => txn
+            .location
+            .as_ref()
+            .map(|location| location.to_string())
+            .unwrap_or_else(|| NO_VALUE.into()),
+        ViewColumn::Tags if txn.tags.is_empty() => NO_VALUE.into(),
+        ViewColumn::Tags => txn.tags.join(", "),
+    }
+}
+
+fn optional_date(date: Option<NaiveDate>, formatters: &CliFormatters) -> String {
+    date.map(|date| formatters.format_date(date))
+        .unwrap_or_else(|| NO_VALUE.into())
+}
+
+fn parse_status(value: &str) -> Result<TransactionStatus, CommandError> {
+    match value.to_ascii_lowercase().as_str() {
+        "planned" => Ok(TransactionStatus::Planned),
+        "completed" => Ok(TransactionStatus::Completed),
+        "missed" => Ok(TransactionStatus::Missed),
+        "simulated" => Ok(TransactionStatus::Simulated),
+        other => Err(CommandError::InvalidArguments(format!(
+            "unknown status `{}` (use planned, completed, missed, or simulated)",
+            other
+        ))),
+    }
+}
+
+fn parse_yes_no(flag: &str, value: &str) -> Result<bool, CommandError> {
+    match value.to_ascii_lowercase().as_str() {
+        "yes" | "true" | "y" => Ok(true),
+        "no" | "false" | "n" => Ok(false),
+        _ => Err(CommandError::InvalidArguments(format!(
+            "{} expects yes or no, got `{}`",
+            flag, value
+        ))),
+    }
+}
+
+fn parse_filter_amount(value: &str) -> Result<f64, CommandError> {
+    io::parse_number(value)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("invalid amount `{}`", value)))
+}
+
+fn parse_columns(value: &str) -> Result<Vec<ViewColumn>, CommandError> {
+    value
+        .split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            ViewColumn::from_key(part).ok_or_else(|| {
+                let known: Vec<_> = ViewColumn::ALL.iter().map(ViewColumn::key).collect();
+                CommandError::InvalidArguments(format!(
+                    "unknown column `{}` (available: {})",
+                    part.trim(),
+                    known.join(", ")
+                ))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,92 @@
+//! `transaction recurring income <index> ...`: attaches a gross pay,
+//! withholding, and benefits breakdown to a recurring paycheck.
+
+use bufy_core::CurrencyFormatter;
+
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io as cli_io;
+use crate::core::services::IncomeService;
+use crate::ledger::IncomeModel;
+
+const INCOME_USAGE: &str = "usage: transaction recurring income <transaction_index> <--gross N --withholding P [--benefits N]|clear>";
+
+/// Income model values given on the command line; absent flags stay `None`.
+#[derive(Debug, Default)]
+pub(crate) struct IncomeFlags {
+    pub gross: Option<f64>,
+    pub withholding_percent: Option<f64>,
+    pub benefits: Option<f64>,
+}
+
+impl IncomeFlags {
+    pub(crate) fn parse(args: &[&str], usage: &str) -> Result<Self, CommandError> {
+        let mut flags = Self::default();
+        let mut iter = args.iter();
+        while let Some(flag) = iter.next() {
+            let value = iter.next().ok_or_else(|| {
+                CommandError::InvalidArguments(format!("{} requires a value", flag))
+            })?;
+            let number = cli_io::parse_number(value.trim_end_matches('%')).ok_or_else(|| {
+                CommandError::InvalidArguments(format!("invalid number `{}`", value))
+            })?;
+            match flag.to_ascii_lowercase().as_str() {
+                "--gross" => flags.gross = Some(number),
+                "--withholding" => flags.withholding_percent = Some(number),
+                "--benefits" => flags.benefits = Some(number),
+                _ => return Err(CommandError::InvalidArguments(usage.into())),
+            }
+        }
+        Ok(flags)
+    }
+}
+
+pub(crate) fn run_income(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    context.ensure_base_mode("Income model changes")?;
+    let Some((index, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(INCOME_USAGE.into()));
+    };
+    let Some(index) = context.transaction_index_from_arg(Some(index), INCOME_USAGE, "")? else {
+        return Ok(());
+    };
+    let template_id = context.with_ledger(|ledger| {
+        ledger
+            .transactions
+            .get(index)
+            .map(|txn| txn.id)
+            .ok_or_else(|| CommandError::InvalidArguments("transaction index out of range".into()))
+    })?;
+
+    if let [action] = rest {
+        if action.eq_ignore_ascii_case("clear") {
+            context.with_ledger_mut(|ledger| {
+                IncomeService::clear_model(ledger, template_id).map_err(CommandError::from)
+            })?;
+            cli_io::print_success(format!(
+                "Income model removed from transaction [{}].",
+                index
+            ));
+            return Ok(());
+        }
+    }
+
+    let flags = IncomeFlags::parse(rest, INCOME_USAGE)?;
+    let (Some(gross), Some(withholding_percent)) = (flags.gross, flags.withholding_percent) else {
+        return Err(CommandError::InvalidArguments(INCOME_USAGE.into()));
+    };
+    let model = IncomeModel {
+        gross,
+        withholding_percent,
+        benefits: flags.benefits.unwrap_or(0.0),
+    };
+    let formatters = context.formatters.clone();
+    let net = context.with_ledger_mut(|ledger| {
+        let net =
+            IncomeService::set_model(ledger, template_id, model).map_err(CommandError::from)?;
+        Ok(formatters.format_amount(net, ledger.base_currency().as_str()))
+    })?;
+    cli_io::print_success(format!(
+        "Income model set on transaction [{}]. Net pay: {}",
+        index, net
+    ));
+    Ok(())
+}
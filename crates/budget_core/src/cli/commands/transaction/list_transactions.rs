@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use chrono::Utc;
 use uuid::Uuid;
 
+use crate::cli::commands::rules;
+use crate::cli::commands::transaction::filters::{transactions_json, TransactionFilterArgs};
 use crate::cli::core::{CliMode, CommandError, CommandResult, ShellContext};
 use crate::cli::io as cli_io;
 use crate::cli::ui::detail_actions::{DetailAction, DetailActionResult, DetailActionsMenu};
@@ -11,11 +13,18 @@ use crate::cli::ui::run_selectable_table;
 use crate::cli::ui::table_renderer::{Alignment, Table, TableColumn};
 use crate::cli::ui::test_mode;
 use crate::core::services::TransactionService;
-use bufy_domain::transaction::TransactionStatus;
+use bufy_domain::transaction::{Transaction, TransactionStatus};
+use bufy_domain::Money;
 
 const NO_VALUE: &str = "—";
 
 pub fn run_list_transactions(context: &mut ShellContext) -> CommandResult {
+    run_filtered_list_transactions(context, &[])
+}
+
+/// Runs the transaction list narrowed by the supplied filter flags. With
+/// `--json` the matching transactions are printed instead of the selector.
+pub fn run_filtered_list_transactions(context: &mut ShellContext, args: &[&str]) -> CommandResult {
     {
         let manager = context.manager();
         if manager.current_handle().is_none() {
@@ -24,12 +33,26 @@ pub fn run_list_transactions(context: &mut ShellContext) -> CommandResult {
         }
     }
 
+    let filters = TransactionFilterArgs::parse(args)?;
+    let query = context.with_ledger(|ledger| filters.resolve(ledger))?;
+    if filters.json {
+        let json = context
+            .with_ledger(|ledger| transactions_json(&TransactionService::query(ledger, &query)))?;
+        cli_io::println_text(&json)?;
+        return Ok(());
+    }
+    let empty_message = if filters.is_unfiltered() {
+        "No transactions recorded."
+    } else {
+        "No transactions match the supplied filters."
+    };
+
     run_selectable_table(
         context,
         "transaction_selector",
         "transaction_actions",
-        Some("No transactions recorded."),
-        |ctx| gather_entries(ctx),
+        Some(empty_message),
+        |ctx| gather_matching_entries(ctx, |txn| query.matches(txn)),
         build_table,
         build_detail_view,
         build_actions,
@@ -37,9 +60,10 @@ pub fn run_list_transactions(context: &mut ShellContext) -> CommandResult {
     )
 }
 
-struct TransactionEntry {
+pub(crate) struct TransactionEntry {
     index: usize,
     id: Uuid,
+    reference: String,
     summary: String,
     date_planned: String,
     actual_date: Option<String>,
@@ -53,7 +77,24 @@ struct TransactionEntry {
     notes: Option<String>,
 }
 
-fn gather_entries(context: &ShellContext) -> Result<Vec<TransactionEntry>, CommandError> {
+impl TransactionEntry {
+    pub(crate) fn id(&self) -> Uuid {
+        self.id
+    }
+
+    pub(crate) fn date_label(&self) -> &str {
+        &self.date_planned
+    }
+}
+
+/// Collects list entries for the transactions accepted by `filter`.
+pub(crate) fn gather_matching_entries<F>(
+    context: &ShellContext,
+    filter: F,
+) -> Result<Vec<TransactionEntry>, CommandError>
+where
+    F: Fn(&Transaction) -> bool,
+{
     context.with_ledger(|ledger| {
         if ledger.transactions.is_empty() {
             return Ok(Vec::new());
@@ -73,9 +114,14 @@ fn gather_entries(context: &ShellContext) -> Result<Vec<TransactionEntry>, Comma
             .transactions
             .iter()
             .enumerate()
+            .filter(|(_, txn)| filter(txn))
             .map(|(index, txn)| TransactionEntry {
                 index,
                 id: txn.id,
+                reference: txn
+                    .short_ref()
+                    .map(|code| code.to_string())
+                    .unwrap_or_else(|| NO_VALUE.into()),
                 summary: format!(
                     "{} -> {} on {}",
                     account_names
@@ -103,8 +149,8 @@ fn gather_entries(context: &ShellContext) -> Result<Vec<TransactionEntry>, Comma
                     .and_then(|id| category_names.get(&id))
                     .cloned()
                     .unwrap_or_else(|| NO_VALUE.into()),
-                budgeted: txn.budgeted_amount,
-                actual: txn.actual_amount,
+                budgeted: txn.budgeted_amount.to_major(),
+                actual: txn.actual_amount.as_ref().map(Money::to_major),
                 status: txn.status.clone(),
                 recurrence: txn
                     .recurrence
@@ -117,11 +163,12 @@ fn gather_entries(context: &ShellContext) -> Result<Vec<TransactionEntry>, Comma
     })
 }
 
-fn build_table(entries: &[TransactionEntry]) -> Table {
+pub(crate) fn build_table(entries: &[TransactionEntry]) -> Table {
     let rows = entries
         .iter()
         .map(|entry| {
             vec![
+                entry.reference.clone(),
                 entry.date_planned.clone(),
                 entry.from_account.clone(),
                 entry.to_account.clone(),
@@ -134,6 +181,12 @@ fn build_table(entries: &[TransactionEntry]) -> Table {
 
     Table {
         columns: vec![
+            TableColumn {
+                header: "REF".into(),
+                min_width: 6,
+                max_width: None,
+                alignment: Alignment::Left,
+            },
             TableColumn {
                 header: "DATE".into(),
                 min_width: 10,
@@ -184,11 +237,12 @@ fn format_amount_pair(budgeted: f64, actual: Option<f64>) -> String {
     }
 }
 
-fn build_detail_view(entry: &TransactionEntry) -> DetailView {
+pub(crate) fn build_detail_view(entry: &TransactionEntry) -> DetailView {
     let mut view = DetailView::new(format!(
         "Transaction: {} - {}",
         entry.date_planned, entry.category
     ))
+    .with_field("reference", entry.reference.clone())
     .with_field("status", entry.status.to_string())
     .with_field("from_account", entry.from_account.clone())
     .with_field("to_account", entry.to_account.clone())
@@ -276,8 +330,8 @@ fn delete_transaction(context: &mut ShellContext, entry: &TransactionEntry) -> C
         }
     }
 
-    context.with_ledger_mut(|ledger| {
-        TransactionService::remove(ledger, entry.id).map_err(CommandError::from)
+    context.with_ledger_journaled(|ledger, journal| {
+        TransactionService::remove_journaled(ledger, entry.id, journal).map_err(CommandError::from)
     })?;
     cli_io::print_success(format!("Transaction removed: {}", entry.summary));
     Ok(())
@@ -316,12 +370,11 @@ fn auto_complete_transaction(
 ) -> CommandResult {
     let today = Utc::now().date_naive();
     let amount = entry.actual.unwrap_or(entry.budgeted);
-    context.with_ledger_mut(|ledger| {
-        TransactionService::update(ledger, entry.id, |txn| txn.mark_completed(today, amount))
-            .map_err(CommandError::from)
+    let transfers = context.with_ledger_mut(|ledger| {
+        TransactionService::complete(ledger, entry.id, today, amount).map_err(CommandError::from)
     })?;
     cli_io::print_success(format!("Transaction completed: {}", entry.summary));
-    Ok(())
+    rules::report_auto_transfers(context, &transfers)
 }
 
 fn manual_complete_transaction(
@@ -0,0 +1,101 @@
+//! `transaction recurring price` and `price-history`: record changes to a
+//! recurring amount and review the prices a series has had.
+
+use bufy_core::{CurrencyFormatter, DateFormatter};
+use uuid::Uuid;
+
+use crate::cli::core::{parse_date, CommandError, CommandResult, ShellContext};
+use crate::cli::io as cli_io;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::RecurrenceService;
+use crate::ledger::Ledger;
+
+const PRICE_USAGE: &str =
+    "usage: transaction recurring price <transaction_index> <amount> [YYYY-MM-DD]";
+const HISTORY_USAGE: &str = "usage: transaction recurring price-history <transaction_index>";
+
+pub(crate) fn run_price(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    context.ensure_base_mode("Price changes")?;
+    let (index, amount, date) = match args {
+        [index, amount] => (*index, *amount, None),
+        [index, amount, date] => (*index, *amount, Some(*date)),
+        _ => return Err(CommandError::InvalidArguments(PRICE_USAGE.into())),
+    };
+    let amount = cli_io::parse_number(amount)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("invalid amount `{}`", amount)))?;
+    let effective = match date {
+        Some(date) => parse_date(date)?,
+        None => context.clock.today(),
+    };
+    let Some(index) = context.transaction_index_from_arg(Some(index), PRICE_USAGE, "")? else {
+        return Ok(());
+    };
+    let formatters = context.formatters.clone();
+    let (previous, current) = context.with_ledger_mut(|ledger| {
+        let id = transaction_id(ledger, index)?;
+        let previous = RecurrenceService::change_price(ledger, id, amount, effective)
+            .map_err(CommandError::from)?;
+        let currency = ledger.base_currency().as_str();
+        Ok((
+            formatters.format_amount(previous, currency),
+            formatters.format_amount(amount, currency),
+        ))
+    })?;
+    cli_io::print_success(format!(
+        "Price of transaction [{}] changed from {} to {} effective {}.",
+        index,
+        previous,
+        current,
+        formatters.format_date(effective)
+    ));
+    Ok(())
+}
+
+pub(crate) fn run_price_history(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [index] = args else {
+        return Err(CommandError::InvalidArguments(HISTORY_USAGE.into()));
+    };
+    let Some(index) = context.transaction_index_from_arg(Some(index), HISTORY_USAGE, "")? else {
+        return Ok(());
+    };
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        let id = transaction_id(ledger, index)?;
+        let history = RecurrenceService::price_history(ledger, id).map_err(CommandError::from)?;
+        let currency = ledger.base_currency().as_str();
+        let mut table = Table::new(
+            Some(format!("Price history for transaction [{}]", index)),
+            vec![
+                TableColumn::new("EFFECTIVE", 12),
+                TableColumn::new("AMOUNT", 14),
+                TableColumn::new("CHANGE", 14),
+            ],
+        );
+        let mut previous: Option<f64> = None;
+        for point in &history {
+            let change = match previous {
+                Some(previous) if previous.abs() > f64::EPSILON => {
+                    format!("{:+.1}%", (point.amount - previous) / previous * 100.0)
+                }
+                _ => "—".into(),
+            };
+            table.add_row(vec![
+                formatters.format_date(point.effective),
+                formatters.format_amount(point.amount, currency),
+                change,
+            ]);
+            previous = Some(point.amount);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn transaction_id(ledger: &Ledger, index: usize) -> Result<Uuid, CommandError> {
+    ledger
+        .transactions
+        .get(index)
+        .map(|txn| txn.id)
+        .ok_or_else(|| CommandError::InvalidArguments("transaction index out of range".into()))
+}
@@ -0,0 +1,138 @@
+//! `transaction search`: filtered, sorted, paged transaction queries.
+
+use crate::cli::commands::transaction::filters::{
+    transactions_json, transactions_table, TransactionFilterArgs,
+};
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::ui::TableRenderer;
+use crate::core::services::{TransactionService, TransactionSort, TransactionSortKey};
+
+const SEARCH_USAGE: &str = "usage: transaction search [--from <date>] [--to <date>] [--account <name>] [--category <name>] [--member <name>] [--status <status>] [--min <amount>] [--max <amount>] [--notes <text>] [--text <text>] [--recurring yes|no] [--tag <tag>] [--sort date|amount|ref] [--desc] [--page <n>] [--per-page <n>] [--columns <list>] [--json]";
+
+/// Results per page unless `--per-page` is given.
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Ordering and paging flags; everything else is a filter flag.
+#[derive(Debug, Clone, PartialEq)]
+struct SearchOptions {
+    sort: Option<TransactionSortKey>,
+    descending: bool,
+    page: usize,
+    per_page: usize,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            sort: None,
+            descending: false,
+            page: 1,
+            per_page: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// Removes the ordering and paging flags from `args`.
+    fn split<'a>(args: &[&'a str]) -> Result<(Self, Vec<&'a str>), CommandError> {
+        let mut options = Self::default();
+        let mut rest = Vec::with_capacity(args.len());
+        let mut iter = args.iter().copied();
+        while let Some(arg) = iter.next() {
+            match arg.to_ascii_lowercase().as_str() {
+                "--desc" => options.descending = true,
+                "--sort" => {
+                    let value = iter.next().ok_or_else(|| {
+                        CommandError::InvalidArguments("--sort requires a value".into())
+                    })?;
+                    options.sort = Some(parse_sort_key(value)?);
+                }
+                "--page" => options.page = positive(iter.next(), "--page")?,
+                "--per-page" => options.per_page = positive(iter.next(), "--per-page")?,
+                _ => rest.push(arg),
+            }
+        }
+        Ok((options, rest))
+    }
+
+    fn sort(&self) -> Option<TransactionSort> {
+        match (self.sort, self.descending) {
+            (Some(key), false) => Some(TransactionSort::ascending(key)),
+            (Some(key), true) => Some(TransactionSort::descending(key)),
+            // `--desc` alone lists the newest transactions first.
+            (None, true) => Some(TransactionSort::descending(TransactionSortKey::Date)),
+            (None, false) => None,
+        }
+    }
+}
+
+pub(crate) fn run_search(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let (options, rest) = SearchOptions::split(args)?;
+    let filters = TransactionFilterArgs::parse(&rest)
+        .map_err(|err| CommandError::InvalidArguments(format!("{}\n{}", err, SEARCH_USAGE)))?;
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        let mut query = filters.resolve(ledger)?;
+        query.sort = options.sort();
+        query.offset = (options.page - 1) * options.per_page;
+        query.limit = Some(options.per_page);
+        let total = TransactionService::count(ledger, &query);
+        let transactions = TransactionService::query(ledger, &query);
+        if filters.json {
+            io::println_text(&transactions_json(&transactions)?)?;
+            return Ok(());
+        }
+        if total == 0 {
+            io::print_warning("No transactions match the supplied filters.");
+            return Ok(());
+        }
+        let pages = total.div_ceil(options.per_page);
+        if transactions.is_empty() {
+            io::print_warning(format!(
+                "Page {} is past the end; there are {} page(s) of results.",
+                options.page, pages
+            ));
+            return Ok(());
+        }
+        let table = transactions_table(
+            "Search results",
+            ledger,
+            &transactions,
+            &filters.columns_or_default(),
+            &formatters,
+        );
+        TableRenderer::render(&table, &style);
+        let first = query.offset + 1;
+        let last = query.offset + transactions.len();
+        let mut footer = format!(
+            "Showing {}–{} of {} (page {} of {}).",
+            first, last, total, options.page, pages
+        );
+        if options.page < pages {
+            footer.push_str(&format!(" Use --page {} for more.", options.page + 1));
+        }
+        io::print_info(footer);
+        Ok(())
+    })
+}
+
+fn parse_sort_key(value: &str) -> Result<TransactionSortKey, CommandError> {
+    match value.to_ascii_lowercase().as_str() {
+        "date" => Ok(TransactionSortKey::Date),
+        "amount" => Ok(TransactionSortKey::Amount),
+        "ref" | "reference" => Ok(TransactionSortKey::Reference),
+        other => Err(CommandError::InvalidArguments(format!(
+            "unknown sort `{}` (use date, amount, or ref)",
+            other
+        ))),
+    }
+}
+
+fn positive(value: Option<&str>, flag: &str) -> Result<usize, CommandError> {
+    value
+        .and_then(|value| value.parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("{} needs a positive number", flag)))
+}
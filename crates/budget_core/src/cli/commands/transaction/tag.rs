@@ -0,0 +1,99 @@
+//! `transaction tag add|remove|list`: free-form labels that cut across
+//! categories, such as `vacation2025` or `reimbursable`.
+
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io as cli_io;
+use crate::core::services::TransactionService;
+use crate::ledger::{Ledger, Transaction};
+
+const TAG_USAGE: &str = "usage: transaction tag <add|remove> <transaction_index> <tag>... | transaction tag list [transaction_index]";
+
+pub(crate) fn run_tag(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((action, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(TAG_USAGE.into()));
+    };
+    match action.to_ascii_lowercase().as_str() {
+        "add" => change_tags(context, rest, true),
+        "remove" => change_tags(context, rest, false),
+        "list" => list_tags(context, rest),
+        _ => Err(CommandError::InvalidArguments(TAG_USAGE.into())),
+    }
+}
+
+fn change_tags(context: &mut ShellContext, args: &[&str], add: bool) -> CommandResult {
+    let Some((index, tags)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(TAG_USAGE.into()));
+    };
+    if tags.is_empty() {
+        return Err(CommandError::InvalidArguments(TAG_USAGE.into()));
+    }
+    let Some(index) = context.transaction_index_from_arg(Some(index), TAG_USAGE, "")? else {
+        return Ok(());
+    };
+    let changed = context.with_ledger_mut(|ledger| {
+        let id = transaction(ledger, index)?.id;
+        let changed = if add {
+            TransactionService::add_tags(ledger, id, tags)
+        } else {
+            TransactionService::remove_tags(ledger, id, tags)
+        };
+        changed.map_err(CommandError::from)
+    })?;
+    match (changed.is_empty(), add) {
+        (true, true) => {
+            cli_io::print_info(format!("Transaction [{}] already has those tags.", index))
+        }
+        (true, false) => {
+            cli_io::print_info(format!("Transaction [{}] has none of those tags.", index))
+        }
+        (false, true) => cli_io::print_success(format!(
+            "Tagged transaction [{}] with {}.",
+            index,
+            changed.join(", ")
+        )),
+        (false, false) => cli_io::print_success(format!(
+            "Removed {} from transaction [{}].",
+            changed.join(", "),
+            index
+        )),
+    }
+    Ok(())
+}
+
+fn list_tags(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    match args {
+        [] => context.with_ledger(|ledger| {
+            let counts = TransactionService::tag_counts(ledger);
+            if counts.is_empty() {
+                cli_io::print_info("No tags yet. Add one with `transaction tag add`.");
+            }
+            for (tag, count) in counts {
+                cli_io::print_info(format!("{:<24} {} transaction(s)", tag, count));
+            }
+            Ok(())
+        }),
+        [index] => {
+            let Some(index) = context.transaction_index_from_arg(Some(index), TAG_USAGE, "")?
+            else {
+                return Ok(());
+            };
+            context.with_ledger(|ledger| {
+                let tags = &transaction(ledger, index)?.tags;
+                if tags.is_empty() {
+                    cli_io::print_info(format!("Transaction [{}] has no tags.", index));
+                } else {
+                    cli_io::print_info(format!("[{}] {}", index, tags.join(", ")));
+                }
+                Ok(())
+            })
+        }
+        _ => Err(CommandError::InvalidArguments(TAG_USAGE.into())),
+    }
+}
+
+fn transaction(ledger: &Ledger, index: usize) -> Result<&Transaction, CommandError> {
+    ledger
+        .transactions
+        .get(index)
+        .ok_or_else(|| CommandError::InvalidArguments("transaction index out of range".into()))
+}
@@ -0,0 +1,180 @@
+use crate::cli::commands::transaction::filters::{
+    describe_filter, filter_error, transactions_json, transactions_table, TransactionFilterArgs,
+};
+use crate::cli::core::{CommandError, CommandResult, ShellContext};
+use crate::cli::io;
+use crate::cli::registry::CommandEntry;
+use crate::cli::ui::{Table, TableColumn, TableRenderer};
+use crate::core::services::ViewService;
+use bufy_core::DateFormatter;
+
+const VIEW_USAGE: &str = "usage: view <save|run|list|show|rename|remove>";
+
+pub(crate) fn definitions() -> Vec<CommandEntry> {
+    vec![CommandEntry::new(
+        "view",
+        "Save and run named transaction filters",
+        "view <save <name> [filters] [--columns a,b]|run <name> [--json]|list|show <name>|rename <name> <new>|remove <name>>",
+        cmd_view,
+    )]
+}
+
+fn cmd_view(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((subcommand, rest)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(VIEW_USAGE.into()));
+    };
+    match subcommand.to_ascii_lowercase().as_str() {
+        "save" => handle_save(context, rest),
+        "run" => handle_run(context, rest),
+        "list" | "ls" => handle_list(context),
+        "show" => handle_show(context, rest),
+        "rename" => handle_rename(context, rest),
+        "remove" | "delete" => handle_remove(context, rest),
+        other => Err(CommandError::InvalidArguments(format!(
+            "unknown view subcommand `{}`. Available: save, run, list, show, rename, remove",
+            other
+        ))),
+    }
+}
+
+fn handle_save(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((name, flags)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(
+            "usage: view save <name> [filters] [--columns a,b]".into(),
+        ));
+    };
+    let parsed = TransactionFilterArgs::parse(flags)?;
+    if parsed.json {
+        return Err(CommandError::InvalidArguments(
+            "--json applies to `view run`, not `view save`".into(),
+        ));
+    }
+    let saved_name = context.with_ledger_mut(|ledger| {
+        // Validate names now so a typo is caught when saving, not when running.
+        parsed.resolve(ledger)?;
+        ViewService::save(
+            ledger,
+            *name,
+            parsed.filter.clone(),
+            parsed.columns.clone(),
+            context.clock.as_ref(),
+        )
+        .map(|view| view.name.clone())
+        .map_err(CommandError::from)
+    })?;
+    io::print_success(format!("View `{}` saved.", saved_name));
+    Ok(())
+}
+
+fn handle_run(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let Some((name, flags)) = args.split_first() else {
+        return Err(CommandError::InvalidArguments(
+            "usage: view run <name> [--json]".into(),
+        ));
+    };
+    let json = match flags {
+        [] => false,
+        [flag] if flag.eq_ignore_ascii_case("--json") => true,
+        _ => {
+            return Err(CommandError::InvalidArguments(
+                "usage: view run <name> [--json]".into(),
+            ))
+        }
+    };
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        let (view, transactions) = ViewService::run(ledger, name).map_err(filter_error)?;
+        if json {
+            io::println_text(&transactions_json(&transactions)?)?;
+            return Ok(());
+        }
+        if transactions.is_empty() {
+            io::print_warning(format!("No transactions match view `{}`.", view.name));
+            return Ok(());
+        }
+        let table = transactions_table(
+            view.name.clone(),
+            ledger,
+            &transactions,
+            &view.columns,
+            &formatters,
+        );
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_list(context: &mut ShellContext) -> CommandResult {
+    let style = context.ui_style.clone();
+    let formatters = context.formatters.clone();
+    context.with_ledger(|ledger| {
+        if ledger.views().is_empty() {
+            io::print_warning("No saved views.");
+            return Ok(());
+        }
+        let mut table = Table::new(
+            Some("Saved views"),
+            vec![
+                TableColumn::new("NAME", 24),
+                TableColumn::new("FILTER", 40),
+                TableColumn::new("UPDATED", 12),
+            ],
+        );
+        for view in ledger.views() {
+            table.add_row(vec![
+                view.name.clone(),
+                describe_filter(&view.filter),
+                formatters.format_date(view.updated_at.date_naive()),
+            ]);
+        }
+        TableRenderer::render(&table, &style);
+        Ok(())
+    })
+}
+
+fn handle_show(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: view show <name>".into(),
+        ));
+    };
+    context.with_ledger(|ledger| {
+        let view = ledger
+            .view(name)
+            .ok_or_else(|| CommandError::InvalidArguments(format!("view `{}` not found", name)))?;
+        let columns: Vec<_> = view.columns.iter().map(|column| column.key()).collect();
+        io::print_info(format!("View: {}", view.name));
+        io::print_info(format!("Filter: {}", describe_filter(&view.filter)));
+        io::print_info(format!("Columns: {}", columns.join(", ")));
+        Ok(())
+    })
+}
+
+fn handle_rename(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name, new_name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: view rename <name> <new-name>".into(),
+        ));
+    };
+    context.with_ledger_mut(|ledger| {
+        ViewService::rename(ledger, name, new_name, context.clock.as_ref()).map_err(filter_error)
+    })?;
+    io::print_success(format!("View `{}` renamed to `{}`.", name, new_name));
+    Ok(())
+}
+
+fn handle_remove(context: &mut ShellContext, args: &[&str]) -> CommandResult {
+    let [name] = args else {
+        return Err(CommandError::InvalidArguments(
+            "usage: view remove <name>".into(),
+        ));
+    };
+    let removed = context.with_ledger_mut(|ledger| {
+        ViewService::remove(ledger, name)
+            .map(|view| view.name)
+            .map_err(filter_error)
+    })?;
+    io::print_success(format!("View `{}` removed.", removed));
+    Ok(())
+}
@@ -8,38 +8,51 @@ use std::{
     sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
 };
 
-use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use chrono::{Duration, Local, NaiveDate, Utc};
 use dialoguer::{theme::ColorfulTheme, Input, Select};
 use strsim::levenshtein;
 use uuid::Uuid;
 
 use crate::{
-    config::{self, Config, ConfigManager, Theme},
+    config::{
+        self, Config, ConfigManager, Theme, TransactionDateDefault, TransactionStatusDefault,
+    },
     core::errors::BudgetError,
     core::ledger_manager::LedgerManager,
     core::services::{
-        AccountService, CategoryBudgetStatus, CategoryBudgetSummary, CategoryService,
-        LedgerService, RecurrenceService, ServiceError, SimulationService, SummaryService,
-        TransactionService,
+        AccountService, AlertService, BalanceForecast, CategorizationService, CategoryBudgetStatus,
+        CategoryBudgetSummary, CategoryGroupService, CategoryService, ExchangeRateService,
+        LedgerService, PeriodForecast, RecurrenceService, ServiceError, SimulationService,
+        SummaryService, TransactionService,
     },
+    core::utils::{PathResolver, PathStrategy},
     ledger::{
-        account::AccountKind, category::CategoryKind, Account, BudgetPeriod, BudgetScope,
-        BudgetStatus, BudgetSummary, Category, DateWindow, ForecastReport, Ledger, LedgerExt,
-        Recurrence, RecurrenceEnd, RecurrenceMode, RecurrenceSnapshot, RecurrenceStatus,
-        ScheduledStatus, SimulationBudgetImpact, SimulationChange, SimulationTransactionPatch,
-        TimeInterval, TimeUnit, Transaction, TransactionStatus,
+        account::AccountKind,
+        category::{BudgetProration, CategoryKind},
+        Account, Appearance, BudgetPeriod, BudgetScope, BudgetStatus, BudgetSummary,
+        BusinessDayShift, Category, CategoryBudget, CategoryGroupSummary, DateWindow,
+        ForecastReport, Ledger, LedgerExt, Recurrence, RecurrenceEnd, RecurrenceMode,
+        RecurrenceSnapshot, RecurrenceStatus, ScheduledStatus, SimulationBudgetImpact,
+        SimulationChange, SimulationTransactionPatch, TimeInterval, TimeUnit, Transaction,
+        TransactionStatus,
     },
 };
-use bufy_core::{storage::LedgerStorage, Clock};
-use bufy_domain::currency::{
-    format_currency_value, format_currency_value_with_precision, format_date,
+use bufy_core::{storage::LedgerStorage, ChangeJournal, Clock};
+use bufy_domain::{
+    currency::{
+        format_currency_value, format_currency_value_with_precision, format_date, CurrencyCode,
+        LocaleConfig,
+    },
+    Money,
 };
 use bufy_storage_json::{
-    load_ledger_from_path, JsonLedgerStorage as JsonStorage, LedgerMetadata, StoragePaths,
+    load_ledger_from_path, parse_backup_timestamp, JsonLedgerStorage as JsonStorage,
+    LedgerMetadata, StoragePaths,
 };
 
 use bufy_domain::BudgetPeriod as CategoryBudgetPeriod;
 
+use crate::cli::entity_ref::{short_id, EntityRef};
 use crate::cli::formatters::CliFormatters;
 use crate::cli::forms::{
     AccountFormData, AccountInitialData, AccountWizard, CategoryFormData, CategoryInitialData,
@@ -61,11 +74,13 @@ use super::commands;
 use super::io as cli_io;
 use super::output::render_table as output_table;
 use super::registry::{CommandEntry, CommandRegistry};
+use crate::cli::output;
 pub use crate::cli::shell_context::{CliMode, ShellContext};
 use crate::cli::system_clock::SystemClock;
-use crate::cli::ui::banner::Banner;
+use crate::cli::ui::banner::{validate_template, Banner};
 use crate::cli::ui::formatting::Formatter;
 use crate::cli::ui::prompts;
+use crate::cli::ui::table_renderer::visible_width;
 use crate::cli::ui::test_mode;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -118,6 +133,46 @@ impl ShellContext {
         self.config.read().expect("Config lock poisoned")
     }
 
+    pub(crate) fn pager_enabled(&self) -> bool {
+        self.config_read().pager
+    }
+
+    pub(crate) fn simulation_overlay_enabled(&self) -> bool {
+        self.config_read().simulation_overlay
+    }
+
+    /// Status a new base-ledger transaction starts with.
+    pub(crate) fn default_transaction_status(&self) -> TransactionStatus {
+        match self.config_read().default_transaction_status {
+            TransactionStatusDefault::Planned => TransactionStatus::Planned,
+            TransactionStatusDefault::Completed => TransactionStatus::Completed,
+        }
+    }
+
+    /// Scheduled date a new transaction starts with: today, or the last one
+    /// entered when `default_transaction_date` is `last_used`.
+    pub(crate) fn default_transaction_date(&self) -> NaiveDate {
+        let today = self.clock.today();
+        match self.config_read().default_transaction_date {
+            TransactionDateDefault::Today => today,
+            TransactionDateDefault::LastUsed => self.last_transaction_date.unwrap_or(today),
+        }
+    }
+
+    pub(crate) fn autofill_actuals_enabled(&self) -> bool {
+        self.config_read().autofill_actuals
+    }
+
+    /// The custom prompt template, if one is set.
+    pub(crate) fn prompt_template(&self) -> Option<String> {
+        self.config_read().prompt_template.clone()
+    }
+
+    /// The startup banner template, if one is set.
+    pub(crate) fn banner_template(&self) -> Option<String> {
+        self.config_read().banner_template.clone()
+    }
+
     pub(crate) fn config_write(&self) -> RwLockWriteGuard<'_, Config> {
         self.config.write().expect("Config lock poisoned")
     }
@@ -162,15 +217,27 @@ impl ShellContext {
         let config_manager_raw = config::default_manager().map_err(CliError::from)?;
         let config = config_manager_raw.load().map_err(CliError::from)?;
 
+        let data_root = PathResolver::data_root();
         let storage_paths = StoragePaths {
-            ledger_root: config.resolve_default_ledger_root(),
-            backup_root: config.resolve_default_backup_root(),
+            ledger_root: match (&config.default_ledger_root, &data_root) {
+                (Some(root), _) => PathResolver::configured_root(root),
+                (None, Some(base)) => PathResolver::ledger_dir_in(base),
+                (None, None) => config.resolve_default_ledger_root(),
+            },
+            backup_root: match (&config.default_backup_root, &data_root) {
+                (Some(root), _) => PathResolver::configured_root(root),
+                (None, Some(base)) => PathResolver::backup_dir_in(base),
+                (None, None) => config.resolve_default_backup_root(),
+            },
         };
         let storage = JsonStorage::new(storage_paths)
             .map_err(BudgetError::from)
             .map_err(CliError::from)?;
-        let manager = Arc::new(RwLock::new(LedgerManager::new(Box::new(storage.clone()))));
-        let clock: Arc<dyn Clock> = Arc::new(SystemClock::default());
+        let mut ledger_manager = LedgerManager::new(Box::new(storage.clone()));
+        ledger_manager.set_change_feed(config.change_feed);
+        ledger_manager.set_audit_actor(config.audit_actor.clone());
+        let manager = Arc::new(RwLock::new(ledger_manager));
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
         cli_io::apply_config(&config);
         let config = Arc::new(RwLock::new(config));
         let formatters = CliFormatters::new(config.clone());
@@ -191,7 +258,11 @@ impl ShellContext {
             active_simulation_name: None,
             current_simulation: None,
             last_command: None,
+            recent_commands: Vec::new(),
             running: true,
+            failed_assertions: 0,
+            verbose: false,
+            last_transaction_date: None,
             ui_style,
         };
 
@@ -238,7 +309,7 @@ impl ShellContext {
         self.active_simulation_name = None;
     }
 
-    fn ensure_base_mode(&self, action: &str) -> Result<(), CommandError> {
+    pub(crate) fn ensure_base_mode(&self, action: &str) -> Result<(), CommandError> {
         if self.is_simulation_active() {
             Err(CommandError::InvalidArguments(format!(
                 "{} is unavailable while editing a simulation. Use `leave-simulation` first.",
@@ -263,6 +334,17 @@ impl ShellContext {
         )
     }
 
+    /// A category or account name styled like in tables, padded to the
+    /// summary's name column.
+    fn summary_label(&self, name: &str, appearance: Option<&Appearance>) -> String {
+        let label = match appearance {
+            Some(appearance) => self.ui_style.apply_entity_style(name, appearance),
+            None => name.to_string(),
+        };
+        let padding = 20usize.saturating_sub(visible_width(&label));
+        format!("{}{}", label, " ".repeat(padding))
+    }
+
     fn format_date(&self, ledger: &Ledger, date: NaiveDate) -> String {
         format_date(&ledger.locale, date)
     }
@@ -293,13 +375,19 @@ impl ShellContext {
             .budget
             .as_ref()
             .expect("row rendering requires budget details");
+        let amount = status.window_budget.unwrap_or(budget.amount.to_major());
         vec![
             status.name.clone(),
-            self.format_amount(ledger, budget.amount),
+            self.format_amount(ledger, amount),
             self.format_amount(ledger, status.totals.real),
-            self.format_amount(ledger, status.totals.remaining),
-            self.describe_budget_period_label(ledger, &budget.period, budget.reference_date),
+            self.format_amount(ledger, amount - status.totals.real),
+            format!(
+                "{} • {}",
+                self.describe_budget_period_label(ledger, &budget.period, budget.reference_date),
+                budget.proration
+            ),
             format!("{:?}", status.totals.status),
+            budget.note.clone().unwrap_or_default(),
         ]
     }
 
@@ -329,6 +417,14 @@ impl ShellContext {
                 "off"
             }
         ));
+        cli_io::print_info(format!(
+            "  Data folder: {}{}",
+            PathResolver::base_dir().display(),
+            match PathResolver::strategy() {
+                PathStrategy::Portable(_) => " (portable)",
+                PathStrategy::Home => "",
+            }
+        ));
         cli_io::print_info(format!(
             "  Last opened ledger: {}",
             config.last_opened_ledger.as_deref().unwrap_or("(none)")
@@ -344,6 +440,63 @@ impl ShellContext {
                 .map(|value| format!("{value} places"))
                 .unwrap_or_else(|| "auto".into())
         ));
+        cli_io::print_info(format!(
+            "  Summary rows: {} categories, {} projections",
+            config.summary_category_rows, config.forecast_transaction_rows
+        ));
+        cli_io::print_info(format!(
+            "  Change feed: {}",
+            if config.change_feed { "on" } else { "off" }
+        ));
+        cli_io::print_info(format!("  Audit actor: {}", self.manager().audit_actor()));
+        cli_io::print_info(format!(
+            "  Pager: {}",
+            if config.pager { "on" } else { "off" }
+        ));
+        cli_io::print_info(format!(
+            "  Simulation overlay: {}",
+            if config.simulation_overlay {
+                "on"
+            } else {
+                "off"
+            }
+        ));
+        cli_io::print_info(format!(
+            "  New transactions: {}, dated {}, actuals {}",
+            config.default_transaction_status,
+            config.default_transaction_date,
+            if config.autofill_actuals {
+                "auto-filled"
+            } else {
+                "left blank"
+            }
+        ));
+        cli_io::print_info(format!(
+            "  Backup before simulation apply: {}",
+            config
+                .simulation_backup_minutes
+                .map(|minutes| format!("within {minutes} minutes"))
+                .unwrap_or_else(|| "off".into())
+        ));
+        cli_io::print_info(format!(
+            "  Stale exchange rates: {}",
+            config
+                .stale_rate_days
+                .map(|days| format!("flagged after {days} days"))
+                .unwrap_or_else(|| "never flagged".into())
+        ));
+        cli_io::print_info(format!(
+            "  Prompt: {}",
+            config.prompt_template.as_deref().unwrap_or("default")
+        ));
+        cli_io::print_info(format!(
+            "  Startup banner: {}",
+            config.banner_template.as_deref().unwrap_or("off")
+        ));
+        drop(config);
+        if self.prompt_template().is_some() {
+            cli_io::print_info(format!("  Prompt preview: {}", Banner::text(self)));
+        }
         let _ = self.with_ledger(|ledger| {
             Formatter::new().print_header("Ledger Format");
             cli_io::print_info(format!(
@@ -351,6 +504,22 @@ impl ShellContext {
                 ledger.base_currency.as_str()
             ));
             cli_io::print_info(format!("  Locale: {}", ledger.locale.language_tag));
+            cli_io::print_info(format!(
+                "  First day of week: {}",
+                ledger.locale.first_weekday
+            ));
+            cli_io::print_info(format!(
+                "  Weekend: {}",
+                commands::config::describe_weekend(&ledger.locale.weekend)
+            ));
+            cli_io::print_info(format!(
+                "  Holidays: {}",
+                ledger
+                    .holidays
+                    .source
+                    .as_ref()
+                    .map_or_else(|| "none".to_string(), |source| source.label())
+            ));
             cli_io::print_info(format!(
                 "  Negative style: {:?}",
                 ledger.format.negative_style
@@ -372,6 +541,10 @@ impl ShellContext {
                 }
             ));
             cli_io::print_info(format!("  Valuation policy: {:?}", ledger.valuation_policy));
+            cli_io::print_info(format!(
+                "  Summary date basis: {}",
+                ledger.summary_date_basis
+            ));
             Ok(())
         });
         Ok(())
@@ -391,8 +564,21 @@ impl ShellContext {
                     ))),
                 }
             };
+            let parse_template = |raw: &str| -> Result<Option<String>, CommandError> {
+                if raw.is_empty() || raw.eq_ignore_ascii_case("default") {
+                    return Ok(None);
+                }
+                validate_template(raw).map_err(CommandError::InvalidArguments)?;
+                Ok(Some(raw.to_string()))
+            };
             match key.to_lowercase().as_str() {
-                "locale" => config.locale = value.to_string(),
+                "locale" => {
+                    let tag = crate::cli::commands::config::canonical_locale_tag(value)
+                        .ok_or_else(|| {
+                            CommandError::InvalidArguments(format!("unknown locale `{}`", value))
+                        })?;
+                    config.locale = tag.to_string();
+                }
                 "currency" => config.currency = value.to_string(),
                 "theme" => config.theme = Theme::from_str(value),
                 "plain_output" => {
@@ -443,6 +629,93 @@ impl ShellContext {
                         config.default_currency_precision = Some(parsed);
                     }
                 }
+                "summary_category_rows" | "forecast_transaction_rows" => {
+                    let rows = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|rows| *rows > 0)
+                        .ok_or_else(|| {
+                            CommandError::InvalidArguments(format!(
+                                "{} must be a positive number",
+                                key
+                            ))
+                        })?;
+                    if key.eq_ignore_ascii_case("summary_category_rows") {
+                        config.summary_category_rows = rows;
+                    } else {
+                        config.forecast_transaction_rows = rows;
+                    }
+                }
+                "change_feed" => {
+                    config.change_feed = parse_bool(value, "change_feed")?;
+                    self.manager_mut().set_change_feed(config.change_feed);
+                }
+                "pager" => {
+                    config.pager = parse_bool(value, "pager")?;
+                }
+                "simulation_overlay" => {
+                    config.simulation_overlay = parse_bool(value, "simulation_overlay")?;
+                }
+                "default_transaction_status" => {
+                    config.default_transaction_status = TransactionStatusDefault::parse(value)
+                        .ok_or_else(|| {
+                            CommandError::InvalidArguments(
+                                "default_transaction_status must be `planned` or `completed`"
+                                    .into(),
+                            )
+                        })?;
+                }
+                "default_transaction_date" => {
+                    config.default_transaction_date = TransactionDateDefault::parse(value)
+                        .ok_or_else(|| {
+                            CommandError::InvalidArguments(
+                                "default_transaction_date must be `today` or `last_used`".into(),
+                            )
+                        })?;
+                }
+                "autofill_actuals" => {
+                    config.autofill_actuals = parse_bool(value, "autofill_actuals")?;
+                }
+                "simulation_backup_minutes" => {
+                    config.simulation_backup_minutes = if value.eq_ignore_ascii_case("off") {
+                        None
+                    } else {
+                        Some(value.parse::<u32>().map_err(|_| {
+                            CommandError::InvalidArguments(
+                                "simulation_backup_minutes must be a number of minutes or `off`"
+                                    .into(),
+                            )
+                        })?)
+                    };
+                }
+                "stale_rate_days" => {
+                    config.stale_rate_days = if value.eq_ignore_ascii_case("off") {
+                        None
+                    } else {
+                        Some(value.parse::<u32>().map_err(|_| {
+                            CommandError::InvalidArguments(
+                                "stale_rate_days must be a number of days or `off`".into(),
+                            )
+                        })?)
+                    };
+                }
+                "prompt" | "prompt_template" => {
+                    config.prompt_template = parse_template(value)?;
+                }
+                "banner" | "banner_template" => {
+                    config.banner_template = if value.eq_ignore_ascii_case("off") {
+                        None
+                    } else {
+                        parse_template(value)?
+                    };
+                }
+                "audit_actor" => {
+                    config.audit_actor = Some(value.trim())
+                        .filter(|actor| !actor.is_empty() && !actor.eq_ignore_ascii_case("default"))
+                        .map(str::to_string);
+                    self.manager_mut()
+                        .set_audit_actor(config.audit_actor.clone());
+                }
                 other => {
                     return Err(CommandError::InvalidArguments(format!(
                         "unknown config key `{}`",
@@ -477,6 +750,53 @@ impl ShellContext {
         for warning in warnings {
             cli_io::print_warning(warning);
         }
+        let today = self.clock.today();
+        let alerts = self
+            .with_ledger(|ledger| Ok(AlertService::evaluate(ledger, today)))
+            .unwrap_or_default();
+        let stale_rates = self
+            .with_ledger(|ledger| Ok(self.stale_rate_notes(ledger, today)))
+            .unwrap_or_default();
+        for note in stale_rates {
+            cli_io::print_warning(note);
+        }
+        if !alerts.is_empty() {
+            cli_io::print_warning(format!("{} active alert(s):", alerts.len()));
+            for alert in alerts {
+                cli_io::print_warning(format!("  {}", alert.message));
+            }
+        }
+        let drafts = self
+            .with_ledger(|ledger| Ok(ledger.pending_drafts.len()))
+            .unwrap_or_default();
+        if drafts > 0 {
+            cli_io::print_info(format!(
+                "{} draft(s) waiting for review. Run `inbox` to approve or discard them.",
+                drafts
+            ));
+        }
+    }
+
+    /// One line per open-ended exchange rate override in force on `on` for
+    /// longer than `stale_rate_days`, with the command that refreshes it.
+    pub(crate) fn stale_rate_notes(&self, ledger: &Ledger, on: NaiveDate) -> Vec<String> {
+        let Some(days) = self.config_read().stale_rate_days else {
+            return Vec::new();
+        };
+        ExchangeRateService::stale_overrides(ledger, on, days)
+            .into_iter()
+            .map(|rate| {
+                format!(
+                    "{} → {} rate {} has not changed since {}; refresh it with `currency rates refresh {} {} <rate>`",
+                    rate.from.as_str(),
+                    rate.to.as_str(),
+                    rate.rate,
+                    rate.valid_from,
+                    rate.from.as_str(),
+                    rate.to.as_str()
+                )
+            })
+            .collect()
     }
 
     pub(crate) fn dispatch(
@@ -486,6 +806,7 @@ impl ShellContext {
         args: &[&str],
     ) -> Result<LoopControl, CommandError> {
         if let Some(handler) = self.registry.handler(command) {
+            self.refresh_input_locale();
             match handler(self, args) {
                 Ok(()) => Ok(LoopControl::Continue),
                 Err(CommandError::ExitRequested) => Ok(LoopControl::Exit),
@@ -516,7 +837,23 @@ impl ShellContext {
         self.dispatch(&command, &tokens[0], &args)
     }
 
+    /// Reads typed numbers with the loaded ledger's separators, or the
+    /// configured locale's when no ledger is open.
+    fn refresh_input_locale(&self) {
+        let locale = self
+            .manager()
+            .read()
+            .map(|ledger| ledger.locale.clone())
+            .unwrap_or_else(|_| {
+                LocaleConfig::for_tag(&self.config_read().locale).unwrap_or_default()
+            });
+        cli_io::set_input_locale(locale);
+    }
+
     pub(crate) fn suggest_command(&self, input: &str) {
+        if output::is_json() {
+            output::json::fail(format!("unknown command `{}`", input));
+        }
         cli_io::print_warning(format!(
             "Unknown command `{}`. Type `help` to see available commands.",
             input
@@ -543,6 +880,34 @@ impl ShellContext {
         cli_io::confirm_action("Exit shell?")
     }
 
+    /// Before an interactive shell closes, asks whether to save a ledger with
+    /// unsaved changes. Scripts save explicitly, so they are never asked.
+    pub(crate) fn offer_save_before_exit(&mut self) -> CommandResult {
+        if self.mode == CliMode::Script || !self.manager().has_unsaved_changes() {
+            return Ok(());
+        }
+        let name = self.ledger_name();
+        let target = match (&name, &self.ledger_path) {
+            (Some(name), _) => format!("`{}`", name),
+            (None, Some(path)) => path.display().to_string(),
+            (None, None) => "the current ledger".into(),
+        };
+        if !cli_io::confirm_action(&format!("Save changes to {} before exiting?", target))? {
+            return Ok(());
+        }
+        match (name, self.ledger_path.clone()) {
+            (Some(name), _) => self.save_named_ledger(&name),
+            (None, Some(path)) => self.save_to_path(&path),
+            (None, None) => match cli_io::prompt_text("Ledger name", None)? {
+                Some(name) if !name.trim().is_empty() => self.save_named_ledger(name.trim()),
+                _ => {
+                    cli_io::print_warning("Changes were not saved.");
+                    Ok(())
+                }
+            },
+        }
+    }
+
     pub(crate) fn report_error(&self, err: CommandError) -> Result<(), CliError> {
         match err {
             CommandError::ExitRequested => Ok(()),
@@ -605,6 +970,19 @@ impl ShellContext {
             .map_err(CommandError::from_core)?
     }
 
+    /// Like [`Self::with_ledger_mut`], also lending the change journal so
+    /// `undo` can revert the edit.
+    pub(crate) fn with_ledger_journaled<T>(
+        &self,
+        f: impl FnOnce(&mut Ledger, &mut ChangeJournal) -> Result<T, CommandError>,
+    ) -> Result<T, CommandError> {
+        let manager = self.manager();
+
+        manager
+            .with_current_journaled(|ledger, journal| f(ledger, journal))
+            .map_err(CommandError::from_core)?
+    }
+
     pub(crate) fn active_simulation_name(&self) -> Option<&str> {
         self.current_simulation
             .as_ref()
@@ -612,6 +990,15 @@ impl ShellContext {
             .or(self.active_simulation_name.as_deref())
     }
 
+    /// The active simulation when the overlay is on, so reports show it
+    /// next to the base numbers without naming it.
+    pub(crate) fn overlay_simulation(&self) -> Option<String> {
+        if !self.simulation_overlay_enabled() {
+            return None;
+        }
+        self.active_simulation_name().map(str::to_string)
+    }
+
     pub(crate) fn can_prompt(&self) -> bool {
         if self.mode == CliMode::Interactive {
             true
@@ -646,10 +1033,7 @@ impl ShellContext {
         }
     }
 
-    pub(crate) fn select_transaction_index(
-        &self,
-        prompt: &str,
-    ) -> Result<Option<usize>, CommandError> {
+    pub(crate) fn select_transaction_id(&self, prompt: &str) -> Result<Option<Uuid>, CommandError> {
         self.select_with(
             TransactionSelectionProvider::new(self),
             prompt,
@@ -657,6 +1041,24 @@ impl ShellContext {
         )
     }
 
+    /// Picks a transaction and returns where it sits in the ledger now.
+    pub(crate) fn select_transaction_index(
+        &self,
+        prompt: &str,
+    ) -> Result<Option<usize>, CommandError> {
+        let Some(id) = self.select_transaction_id(prompt)? else {
+            return Ok(None);
+        };
+        self.with_ledger(|ledger| {
+            ledger
+                .transactions
+                .iter()
+                .position(|txn| txn.id == id)
+                .map(Some)
+                .ok_or_else(|| CommandError::InvalidArguments("transaction not found".into()))
+        })
+    }
+
     fn select_simulation_name(&self, prompt: &str) -> Result<Option<String>, CommandError> {
         self.select_with(
             SimulationSelectionProvider::new(self),
@@ -687,7 +1089,7 @@ impl ShellContext {
         )
     }
 
-    pub(crate) fn select_account_index(&self, prompt: &str) -> Result<Option<usize>, CommandError> {
+    pub(crate) fn select_account_id(&self, prompt: &str) -> Result<Option<Uuid>, CommandError> {
         self.select_with(
             AccountSelectionProvider::new(self),
             prompt,
@@ -695,10 +1097,7 @@ impl ShellContext {
         )
     }
 
-    pub(crate) fn select_category_index(
-        &self,
-        prompt: &str,
-    ) -> Result<Option<usize>, CommandError> {
+    pub(crate) fn select_category_id(&self, prompt: &str) -> Result<Option<Uuid>, CommandError> {
         self.select_with(
             CategorySelectionProvider::new(self),
             prompt,
@@ -706,6 +1105,62 @@ impl ShellContext {
         )
     }
 
+    /// Picks an account and returns where it sits in the ledger now.
+    pub(crate) fn select_account_index(&self, prompt: &str) -> Result<Option<usize>, CommandError> {
+        let Some(id) = self.select_account_id(prompt)? else {
+            return Ok(None);
+        };
+        self.with_ledger(|ledger| {
+            ledger
+                .accounts
+                .iter()
+                .position(|account| account.id == id)
+                .map(Some)
+                .ok_or_else(|| CommandError::InvalidArguments("account not found".into()))
+        })
+    }
+
+    /// Picks a category and returns where it sits in the ledger now.
+    pub(crate) fn select_category_index(
+        &self,
+        prompt: &str,
+    ) -> Result<Option<usize>, CommandError> {
+        let Some(id) = self.select_category_id(prompt)? else {
+            return Ok(None);
+        };
+        self.with_ledger(|ledger| {
+            ledger
+                .categories
+                .iter()
+                .position(|category| category.id == id)
+                .map(Some)
+                .ok_or_else(|| CommandError::InvalidArguments("category not found".into()))
+        })
+    }
+
+    /// Position of the account `raw` points at: an index or an id.
+    pub(crate) fn account_index_from_arg(&self, raw: &str) -> Result<usize, CommandError> {
+        let target = EntityRef::parse(raw).ok_or_else(|| {
+            CommandError::InvalidArguments(format!("account `{}` must be an index or an id", raw))
+        })?;
+        self.with_ledger(|ledger| {
+            target.position(ledger.accounts.iter().map(|account| account.id), "account")
+        })
+    }
+
+    /// Position of the category `raw` points at: an index or an id.
+    pub(crate) fn category_index_from_arg(&self, raw: &str) -> Result<usize, CommandError> {
+        let target = EntityRef::parse(raw).ok_or_else(|| {
+            CommandError::InvalidArguments(format!("category `{}` must be an index or an id", raw))
+        })?;
+        self.with_ledger(|ledger| {
+            target.position(
+                ledger.categories.iter().map(|category| category.id),
+                "category",
+            )
+        })
+    }
+
     fn resolve_category_target(
         &self,
         name_arg: Option<&str>,
@@ -736,16 +1191,13 @@ impl ShellContext {
         if !self.can_prompt() {
             return Err(CommandError::InvalidArguments(usage.into()));
         }
-        match self.select_category_index(prompt)? {
-            Some(index) => self
+        match self.select_category_id(prompt)? {
+            Some(id) => self
                 .with_ledger(|ledger| {
                     ledger
-                        .categories
-                        .get(index)
+                        .category(id)
                         .map(|category| (category.id, category.name.clone()))
-                        .ok_or_else(|| {
-                            CommandError::InvalidArguments("category index out of range".into())
-                        })
+                        .ok_or_else(|| CommandError::InvalidArguments("category not found".into()))
                 })
                 .map(Some),
             None => Ok(None),
@@ -753,9 +1205,7 @@ impl ShellContext {
     }
 
     fn account_category_options(&self, ledger: &Ledger) -> Vec<(String, Option<Uuid>)> {
-        ledger
-            .categories
-            .iter()
+        CategoryService::visible(ledger)
             .map(|category| {
                 (
                     format!(
@@ -793,9 +1243,7 @@ impl ShellContext {
         ledger: &Ledger,
         exclude: &HashSet<Uuid>,
     ) -> Vec<(String, Option<Uuid>)> {
-        ledger
-            .categories
-            .iter()
+        CategoryService::visible(ledger)
             .filter(|category| !exclude.contains(&category.id))
             .map(|category| {
                 (
@@ -829,23 +1277,35 @@ impl ShellContext {
     }
 
     fn apply_account_form(&mut self, data: AccountFormData) -> CommandResult {
-        self.with_ledger_mut(|ledger| {
+        self.with_ledger_journaled(|ledger, journal| {
             match data.id {
                 Some(id) => {
                     let mut changes = Account::new(data.name.clone(), data.kind);
                     changes.id = id;
                     changes.category_id = data.category_id;
-                    changes.opening_balance = data.opening_balance;
+                    let currency = ledger.account(id).map_or_else(
+                        || ledger.base_currency().clone(),
+                        |account| ledger.holding_currency(account),
+                    );
+                    changes.opening_balance = data
+                        .opening_balance
+                        .map(|amount| Money::from_major(amount, currency))
+                        .transpose()
+                        .map_err(ServiceError::from)?;
                     changes.notes = data.notes.clone();
-                    AccountService::edit(ledger, id, changes)?;
+                    AccountService::edit_journaled(ledger, id, changes, journal)?;
                     cli_io::print_success(format!("Account `{}` updated.", data.name));
                 }
                 None => {
                     let mut account = Account::new(data.name.clone(), data.kind);
                     account.category_id = data.category_id;
-                    account.opening_balance = data.opening_balance;
+                    account.opening_balance = data
+                        .opening_balance
+                        .map(|amount| Money::from_major(amount, ledger.holding_currency(&account)))
+                        .transpose()
+                        .map_err(ServiceError::from)?;
                     account.notes = data.notes.clone();
-                    AccountService::add(ledger, account)?;
+                    AccountService::add_journaled(ledger, account, journal)?;
                     cli_io::print_success(format!("Account `{}` added.", data.name));
                 }
             }
@@ -855,7 +1315,7 @@ impl ShellContext {
     }
 
     fn apply_category_form(&mut self, data: CategoryFormData) -> CommandResult {
-        self.with_ledger_mut(|ledger| {
+        self.with_ledger_journaled(|ledger, journal| {
             match data.id {
                 Some(id) => {
                     let mut changes = Category::new(data.name.clone(), data.kind);
@@ -863,7 +1323,7 @@ impl ShellContext {
                     changes.parent_id = data.parent_id;
                     changes.is_custom = data.is_custom;
                     changes.notes = data.notes.clone();
-                    CategoryService::edit(ledger, id, changes)?;
+                    CategoryService::edit_journaled(ledger, id, changes, journal)?;
                     cli_io::print_success(format!("Category `{}` updated.", data.name));
                 }
                 None => {
@@ -871,7 +1331,7 @@ impl ShellContext {
                     category.parent_id = data.parent_id;
                     category.is_custom = data.is_custom;
                     category.notes = data.notes.clone();
-                    CategoryService::add(ledger, category)?;
+                    CategoryService::add_journaled(ledger, category, journal)?;
                     cli_io::print_success(format!("Category `{}` added.", data.name));
                 }
             }
@@ -880,25 +1340,47 @@ impl ShellContext {
         Ok(())
     }
 
-    fn populate_transaction_from_form(transaction: &mut Transaction, data: &TransactionFormData) {
+    /// The form's budgeted and actual amounts, counted in the currency its
+    /// accounts move.
+    fn transaction_form_amounts(
+        ledger: &Ledger,
+        data: &TransactionFormData,
+    ) -> Result<(Money, Option<Money>), CommandError> {
+        let currency = ledger.transfer_currency(data.from_account, data.to_account);
+        let budgeted = Money::from_major(data.budgeted_amount, currency.clone())
+            .map_err(ServiceError::from)?;
+        let actual = data
+            .actual_amount
+            .map(|amount| Money::from_major(amount, currency.clone()))
+            .transpose()
+            .map_err(ServiceError::from)?;
+        Ok((budgeted, actual))
+    }
+
+    fn populate_transaction_from_form(
+        transaction: &mut Transaction,
+        data: &TransactionFormData,
+        (budgeted_amount, mut actual_amount): (Money, Option<Money>),
+        autofill_actuals: bool,
+    ) {
         transaction.from_account = data.from_account;
         transaction.to_account = data.to_account;
         transaction.category_id = data.category_id;
         transaction.scheduled_date = data.scheduled_date;
-        transaction.budgeted_amount = data.budgeted_amount;
 
         let mut actual_date = data.actual_date;
-        let mut actual_amount = data.actual_amount;
 
-        if matches!(data.status, TransactionStatus::Completed) {
+        if autofill_actuals && matches!(data.status, TransactionStatus::Completed) {
             if actual_date.is_none() {
                 actual_date = Some(data.scheduled_date);
             }
             if actual_amount.is_none() {
-                actual_amount = Some(data.budgeted_amount);
+                actual_amount = Some(budgeted_amount.clone());
             }
         }
 
+        transaction.budgeted_amount = budgeted_amount;
+
         transaction.actual_date = actual_date;
         transaction.actual_amount = actual_amount;
         transaction.status = data.status.clone();
@@ -921,14 +1403,21 @@ impl ShellContext {
         data: TransactionFormData,
         simulation: Option<&str>,
     ) -> CommandResult {
+        let amounts = self.with_ledger(|ledger| Self::transaction_form_amounts(ledger, &data))?;
         let mut transaction = Transaction::new(
             data.from_account,
             data.to_account,
             data.category_id,
             data.scheduled_date,
-            data.budgeted_amount,
+            amounts.0.clone(),
         );
-        Self::populate_transaction_from_form(&mut transaction, &data);
+        Self::populate_transaction_from_form(
+            &mut transaction,
+            &data,
+            amounts,
+            self.autofill_actuals_enabled(),
+        );
+        self.last_transaction_date = Some(data.scheduled_date);
 
         let summary =
             self.with_ledger(|ledger| Ok(self.transaction_summary_line(ledger, &transaction)))?;
@@ -943,8 +1432,9 @@ impl ShellContext {
                 name, summary
             ));
         } else {
-            let id = self.with_ledger_mut(|ledger| {
-                TransactionService::add(ledger, transaction).map_err(CommandError::from)
+            let id = self.with_ledger_journaled(|ledger, journal| {
+                TransactionService::add_journaled(ledger, transaction, journal)
+                    .map_err(CommandError::from)
             })?;
             let summary = self.with_ledger(|ledger| {
                 let txn = ledger
@@ -961,11 +1451,51 @@ impl ShellContext {
         let txn_id = data.id.ok_or_else(|| {
             CommandError::InvalidArguments("transaction identifier missing".into())
         })?;
-        self.with_ledger_mut(|ledger| {
-            TransactionService::update(ledger, txn_id, |transaction| {
-                Self::populate_transaction_from_form(transaction, &data);
-            })
-            .map_err(CommandError::from)
+        let autofill_actuals = self.autofill_actuals_enabled();
+        self.with_ledger_journaled(|ledger, journal| {
+            let previous_category = ledger
+                .transaction(txn_id)
+                .and_then(|transaction| transaction.category_id);
+            let previous_amount = ledger
+                .transaction(txn_id)
+                .map(|transaction| transaction.budgeted_amount.clone());
+            let amounts = Self::transaction_form_amounts(ledger, &data)?;
+            TransactionService::update_journaled(
+                ledger,
+                txn_id,
+                |transaction| {
+                    Self::populate_transaction_from_form(
+                        transaction,
+                        &data,
+                        amounts,
+                        autofill_actuals,
+                    )
+                },
+                journal,
+            )
+            .map_err(CommandError::from)?;
+            // Amount edits on a recurring template become price changes from today.
+            let repriced = ledger.transaction(txn_id).is_some_and(|transaction| {
+                transaction.recurrence.is_some()
+                    && previous_amount
+                        .as_ref()
+                        .is_some_and(|previous| *previous != transaction.budgeted_amount)
+            });
+            if let (true, Some(previous)) = (repriced, previous_amount) {
+                RecurrenceService::record_price(
+                    ledger,
+                    txn_id,
+                    previous.to_major(),
+                    self.clock.today(),
+                )
+                .map_err(CommandError::from)?;
+            }
+            // Recategorizations teach the learned rules used by imports.
+            if data.category_id.is_some() && data.category_id != previous_category {
+                CategorizationService::learn(ledger, txn_id, self.clock.as_ref())
+                    .map_err(CommandError::from)?;
+            }
+            Ok(())
         })?;
         let summary = self.with_ledger(|ledger| {
             let txn = ledger
@@ -985,8 +1515,9 @@ impl ShellContext {
             let summary = self.transaction_summary_line(ledger, txn);
             Ok((txn.id, summary))
         })?;
-        self.with_ledger_mut(|ledger| {
-            TransactionService::remove(ledger, transaction_id).map_err(CommandError::from)
+        self.with_ledger_journaled(|ledger, journal| {
+            TransactionService::remove_journaled(ledger, transaction_id, journal)
+                .map_err(CommandError::from)
         })?;
         cli_io::print_success(format!("Transaction removed: {}", summary));
         Ok(())
@@ -998,7 +1529,13 @@ impl ShellContext {
                 CommandError::InvalidArguments("transaction index out of range".into())
             })?;
 
-            cli_io::print_info(format!("Transaction [{}]", index));
+            match txn.short_ref() {
+                Some(reference) => {
+                    cli_io::print_info(format!("Transaction [{}] {}", index, reference))
+                }
+                None => cli_io::print_info(format!("Transaction [{}]", index)),
+            }
+            cli_io::print_info(format!("Id: {}", short_id(txn.id)));
             let route = self.describe_transaction_route(ledger, txn);
             cli_io::print_info(format!("Route: {}", route));
             let category = txn
@@ -1011,7 +1548,7 @@ impl ShellContext {
                 self.format_date(ledger, txn.scheduled_date)
             ));
             let budget = format_currency_value(
-                txn.budgeted_amount,
+                txn.budgeted_amount.to_major(),
                 &ledger.transaction_currency(txn),
                 &ledger.locale,
                 &ledger.format,
@@ -1020,9 +1557,10 @@ impl ShellContext {
             if txn.actual_amount.is_some() || txn.actual_date.is_some() {
                 let amount_label = txn
                     .actual_amount
+                    .as_ref()
                     .map(|value| {
                         format_currency_value(
-                            value,
+                            value.to_major(),
                             &ledger.transaction_currency(txn),
                             &ledger.locale,
                             &ledger.format,
@@ -1035,12 +1573,69 @@ impl ShellContext {
                     .unwrap_or_else(|| "-".into());
                 cli_io::print_info(format!("Actual: {} on {}", amount_label, date_label));
             }
+            if let Some(date) = txn.booking_date {
+                cli_io::print_info(format!("Booking date: {}", self.format_date(ledger, date)));
+            }
+            if let Some(date) = txn.value_date {
+                cli_io::print_info(format!("Value date: {}", self.format_date(ledger, date)));
+            }
             cli_io::print_info(format!("Status: {:?}", txn.status));
+            if let Some(parent) = txn.installment_of.and_then(|id| ledger.transaction(id)) {
+                let label = parent
+                    .short_ref()
+                    .map(|reference| reference.to_string())
+                    .unwrap_or_else(|| "a planned transaction".into());
+                cli_io::print_info(format!("Installment of {}", label));
+            }
+            let installments = TransactionService::installments(ledger, txn.id);
+            if !installments.is_empty() {
+                let paid: f64 = installments
+                    .iter()
+                    .filter_map(|installment| installment.actual_amount.as_ref())
+                    .map(Money::to_major)
+                    .sum();
+                cli_io::print_info(format!(
+                    "Paid in part: {} over {} installment(s)",
+                    self.format_amount(ledger, paid),
+                    installments.len()
+                ));
+            }
+            if let Some(asset) = &txn.asset {
+                let mut details = Vec::new();
+                if let Some(until) = asset.warranty_until {
+                    details.push(format!(
+                        "warranty until {}",
+                        self.format_date(ledger, until)
+                    ));
+                }
+                if let Some(months) = asset.lifetime_months {
+                    details.push(format!("{} month lifetime", months));
+                }
+                if details.is_empty() {
+                    cli_io::print_info(format!("Asset: {}", asset.name));
+                } else {
+                    cli_io::print_info(format!("Asset: {} ({})", asset.name, details.join(", ")));
+                }
+            }
             if let Some(hint) = self.transaction_recurrence_hint(txn) {
                 cli_io::print_info(format!("Recurrence: {}", hint));
             } else if txn.recurrence.is_some() || txn.recurrence_series_id.is_some() {
                 cli_io::print_info("Recurrence: linked instance");
             }
+            if let Some(income) = txn.recurrence.as_ref().and_then(|r| r.income.as_ref()) {
+                cli_io::print_info(format!(
+                    "Income: {} gross, {}% withheld, {} benefits",
+                    self.format_amount(ledger, income.gross),
+                    income.withholding_percent,
+                    self.format_amount(ledger, income.benefits)
+                ));
+            }
+            if let Some(merchant) = &txn.merchant {
+                cli_io::print_info(format!("Merchant: {}", merchant));
+            }
+            if let Some(location) = &txn.location {
+                cli_io::print_info(format!("Location: {}", location));
+            }
             if let Some(notes) = &txn.notes {
                 if !notes.trim().is_empty() {
                     cli_io::print_info(format!("Notes: {}", notes));
@@ -1099,7 +1694,7 @@ impl ShellContext {
                 name: account.name.clone(),
                 kind: account.kind.clone(),
                 category_id: account.category_id,
-                opening_balance: account.opening_balance,
+                opening_balance: account.opening_balance.as_ref().map(Money::to_major),
                 notes: account.notes.clone(),
             };
             Ok((names, categories, initial))
@@ -1214,10 +1809,25 @@ impl ShellContext {
         prompt: &str,
     ) -> Result<Option<usize>, CommandError> {
         if let Some(raw) = arg {
-            let index = raw.parse::<usize>().map_err(|_| {
-                CommandError::InvalidArguments("transaction_index must be numeric".into())
+            let target = EntityRef::parse(raw).ok_or_else(|| {
+                CommandError::InvalidArguments(
+                    "transaction must be an index, a reference such as T-0012, or an id".into(),
+                )
             })?;
-            Ok(Some(index))
+            self.with_ledger(|ledger| match target {
+                EntityRef::Short(reference) => ledger
+                    .transaction_position(reference)
+                    .map(Some)
+                    .ok_or_else(|| {
+                        CommandError::InvalidArguments(format!(
+                            "transaction `{}` not found",
+                            reference
+                        ))
+                    }),
+                target => target
+                    .position(ledger.transactions.iter().map(|txn| txn.id), "transaction")
+                    .map(Some),
+            })
         } else if self.can_prompt() {
             self.select_transaction_index(prompt)
         } else {
@@ -1240,6 +1850,7 @@ impl ShellContext {
     }
 
     pub(crate) fn run_new_ledger_interactive(&mut self) -> CommandResult {
+        cli_io::release_capture();
         let name: String = Input::with_theme(&self.theme)
             .with_prompt("Ledger name")
             .validate_with(|input: &String| -> Result<(), &str> {
@@ -1260,8 +1871,7 @@ impl ShellContext {
     }
 
     pub(crate) fn edit_ledger(&mut self, meta: &LedgerMetadata) -> CommandResult {
-        let mut ledger =
-            load_ledger_from_path(&meta.path).map_err(|err| CommandError::from(err))?;
+        let mut ledger = load_ledger_from_path(&meta.path).map_err(CommandError::from)?;
         let response =
             cli_io::prompt_text("Ledger name", Some(&ledger.name)).map_err(CommandError::from)?;
         let Some(name_input) = response else {
@@ -1305,6 +1915,7 @@ impl ShellContext {
                 Some(meta.path.clone()),
                 Some(updated.name.clone()),
             );
+            self.manager().mark_saved();
             self.update_last_opened(Some(&updated.name))?;
         }
         cli_io::print_success(format!("Ledger `{}` updated.", ledger.name));
@@ -1331,17 +1942,20 @@ impl ShellContext {
     }
 
     fn prompt_budget_amount(&self, prompt: &str) -> Result<f64, CommandError> {
-        Input::<f64>::with_theme(&self.theme)
+        cli_io::release_capture();
+        let input: String = Input::with_theme(&self.theme)
             .with_prompt(prompt)
-            .validate_with(|value: &f64| -> Result<(), &str> {
-                if *value <= 0.0 {
-                    Err("Amount must be greater than 0")
-                } else {
-                    Ok(())
+            .validate_with(|value: &String| -> Result<(), &str> {
+                match cli_io::parse_number(value) {
+                    Some(amount) if amount > 0.0 => Ok(()),
+                    Some(_) => Err("Amount must be greater than 0"),
+                    None => Err("Enter a numeric amount"),
                 }
             })
-            .interact()
-            .map_err(CommandError::from)
+            .interact_text()
+            .map_err(CommandError::from)?;
+        cli_io::parse_number(&input)
+            .ok_or_else(|| CommandError::InvalidArguments("Invalid number supplied".into()))
     }
 
     fn prompt_budget_period(&self) -> Result<BudgetPeriod, CommandError> {
@@ -1353,6 +1967,7 @@ impl ShellContext {
         &self,
         default: CategoryBudgetPeriod,
     ) -> Result<CategoryBudgetPeriod, CommandError> {
+        cli_io::release_capture();
         let options = ["Monthly", "Weekly", "Daily", "Yearly", "Custom..."];
         let mut default_index = match default {
             CategoryBudgetPeriod::Monthly => 0,
@@ -1397,6 +2012,7 @@ impl ShellContext {
         &self,
         defaults: Option<&TimeInterval>,
     ) -> Result<TimeInterval, CommandError> {
+        cli_io::release_capture();
         let options = interval_options();
         let custom_index = options.len() - 1;
         let mut default_selection = 0;
@@ -1520,6 +2136,21 @@ impl ShellContext {
         Ok(())
     }
 
+    /// Opens the embedded sample without touching the last-opened ledger, so
+    /// the next start still resumes the user's own work.
+    pub(crate) fn open_sample_ledger(&mut self) -> CommandResult {
+        let report = self
+            .manager_mut()
+            .open_sample()
+            .map_err(CommandError::from_core)?;
+        self.ledger_path = None;
+        self.clear_active_simulation();
+        cli_io::print_success("Sample ledger opened (read-only).");
+        cli_io::print_info("Run `ledger save-ledger <name>` to keep an editable copy.");
+        self.report_load(&report.warnings, &report.migrations);
+        Ok(())
+    }
+
     pub(crate) fn save_to_path(&mut self, path: &Path) -> CommandResult {
         self.with_ledger(|ledger| {
             self.storage
@@ -1527,7 +2158,7 @@ impl ShellContext {
                 .map_err(CommandError::from_core)
         })?;
         self.ledger_path = Some(path.to_path_buf());
-        self.manager_mut().clear_name();
+        self.manager_mut().saved_to_path(path);
         cli_io::print_success(format!("Ledger saved to {}.", path.display()));
         self.update_last_opened(None)?;
         Ok(())
@@ -1575,6 +2206,39 @@ impl ShellContext {
         Ok(())
     }
 
+    /// Makes sure a backup of the current ledger is no older than
+    /// `simulation_backup_minutes`, taking one when needed, and returns its
+    /// ID. `None` when the setting is off.
+    pub(crate) fn ensure_pre_apply_backup(&mut self) -> Result<Option<String>, CommandError> {
+        let Some(minutes) = self.config_read().simulation_backup_minutes else {
+            return Ok(None);
+        };
+        let name = self.require_named_ledger().map_err(|_| {
+            CommandError::InvalidArguments(
+                "applying a simulation needs a backup; save the ledger with `save-ledger <name>` or set simulation_backup_minutes off".into(),
+            )
+        })?;
+        let cutoff = Utc::now() - Duration::minutes(i64::from(minutes));
+        let backups = self
+            .manager()
+            .list_backups(&name)
+            .map_err(CommandError::from_core)?;
+        let recent = backups
+            .into_iter()
+            .filter_map(|entry| parse_backup_timestamp(&entry.id).map(|taken| (taken, entry.id)))
+            .filter(|(taken, _)| *taken >= cutoff)
+            .max_by_key(|(taken, _)| *taken);
+        if let Some((_, id)) = recent {
+            return Ok(Some(id));
+        }
+        let backup = self
+            .manager()
+            .backup(Some("pre-apply"))
+            .map_err(CommandError::from_core)?;
+        cli_io::print_info(format!("Backup `{}` created before applying.", backup.id));
+        Ok(Some(backup.id))
+    }
+
     pub(crate) fn restore_backup(&mut self, name: &str, reference: &str) -> CommandResult {
         let backups = self
             .manager()
@@ -1745,8 +2409,8 @@ impl ShellContext {
         let name = args[0].to_string();
         let kind = parse_account_kind(args[1])?;
         let account = Account::new(name, kind);
-        self.with_ledger_mut(|ledger| {
-            AccountService::add(ledger, account).map_err(CommandError::from)
+        self.with_ledger_journaled(|ledger, journal| {
+            AccountService::add_journaled(ledger, account, journal).map_err(CommandError::from)
         })?;
         cli_io::print_success("Account added.");
         Ok(())
@@ -1767,8 +2431,8 @@ impl ShellContext {
         let name = args[0].to_string();
         let kind = parse_category_kind(args[1])?;
         let category = Category::new(name, kind);
-        self.with_ledger_mut(|ledger| {
-            CategoryService::add(ledger, category).map_err(CommandError::from)
+        self.with_ledger_journaled(|ledger, journal| {
+            CategoryService::add_journaled(ledger, category, journal).map_err(CommandError::from)
         })?;
         cli_io::print_success("Category added.");
         Ok(())
@@ -1838,7 +2502,7 @@ impl ShellContext {
         };
 
         self.with_ledger_mut(|ledger| {
-            CategoryService::set_budget(ledger, category_id, amount, period.clone(), None)
+            CategoryService::set_budget(ledger, category_id, amount, period, None)
                 .map_err(CommandError::from)
         })?;
 
@@ -1861,6 +2525,64 @@ impl ShellContext {
         Ok(())
     }
 
+    pub(crate) fn category_budget_proration(&mut self, args: &[&str]) -> CommandResult {
+        self.ensure_base_mode("Category budgets")?;
+        let usage = "usage: category budget proration <category_name> <prorate|align|strict>";
+        let [name, rule] = args else {
+            return Err(CommandError::InvalidArguments(usage.into()));
+        };
+        let proration = match rule.to_ascii_lowercase().as_str() {
+            "prorate" => BudgetProration::Prorate,
+            "align" | "align-to-ledger" => BudgetProration::AlignToLedger,
+            "strict" => BudgetProration::Strict,
+            _ => return Err(CommandError::InvalidArguments(usage.into())),
+        };
+        let Some((category_id, category_name)) =
+            self.resolve_category_target(Some(name), usage, "")?
+        else {
+            return Ok(());
+        };
+        self.with_ledger_mut(|ledger| {
+            CategoryService::set_budget_proration(ledger, category_id, proration)
+                .map_err(CommandError::from)
+        })?;
+        cli_io::print_success(format!(
+            "Budget for `{}` now uses the {} rule.",
+            category_name, proration
+        ));
+        Ok(())
+    }
+
+    pub(crate) fn category_budget_note(&mut self, args: &[&str]) -> CommandResult {
+        self.ensure_base_mode("Category budgets")?;
+        let usage = "usage: category budget note <category_name> <text...|clear>";
+        let [name, words @ ..] = args else {
+            return Err(CommandError::InvalidArguments(usage.into()));
+        };
+        if words.is_empty() {
+            return Err(CommandError::InvalidArguments(usage.into()));
+        }
+        let note = match words {
+            [clear] if clear.eq_ignore_ascii_case("clear") => None,
+            _ => Some(words.join(" ")),
+        };
+        let Some((category_id, category_name)) =
+            self.resolve_category_target(Some(name), usage, "")?
+        else {
+            return Ok(());
+        };
+        self.with_ledger_mut(|ledger| {
+            CategoryService::set_budget_note(ledger, category_id, note.as_deref())
+                .map_err(CommandError::from)
+        })?;
+        if note.is_some() {
+            cli_io::print_success(format!("Note saved for the `{}` budget.", category_name));
+        } else {
+            cli_io::print_success(format!("Note removed from the `{}` budget.", category_name));
+        }
+        Ok(())
+    }
+
     pub(crate) fn category_budget_clear(&mut self, args: &[&str]) -> CommandResult {
         self.ensure_base_mode("Category budgets")?;
         if args.len() > 1 {
@@ -1869,7 +2591,7 @@ impl ShellContext {
             ));
         }
         let target = self.resolve_category_target(
-            args.get(0).copied(),
+            args.first().copied(),
             "usage: category budget clear <category_name>",
             "Select a category to clear:",
         )?;
@@ -1893,6 +2615,195 @@ impl ShellContext {
         Ok(())
     }
 
+    pub(crate) fn category_group_add(&mut self, args: &[&str]) -> CommandResult {
+        self.ensure_base_mode("Category groups")?;
+        let name = args.join(" ");
+        if name.trim().is_empty() {
+            return Err(CommandError::InvalidArguments(
+                "usage: category group add <name>".into(),
+            ));
+        }
+        self.with_ledger_mut(|ledger| {
+            CategoryGroupService::create(ledger, &name).map_err(CommandError::from)
+        })?;
+        cli_io::print_success(format!(
+            "Category group `{}` added. Use `category group assign` to add categories.",
+            name.trim()
+        ));
+        Ok(())
+    }
+
+    pub(crate) fn category_group_remove(&mut self, args: &[&str]) -> CommandResult {
+        self.ensure_base_mode("Category groups")?;
+        let name = args.join(" ");
+        if name.trim().is_empty() {
+            return Err(CommandError::InvalidArguments(
+                "usage: category group remove <name>".into(),
+            ));
+        }
+        self.with_ledger_mut(|ledger| {
+            CategoryGroupService::remove(ledger, &name).map_err(CommandError::from)
+        })?;
+        cli_io::print_success(format!(
+            "Category group `{}` removed; its categories are kept.",
+            name.trim()
+        ));
+        Ok(())
+    }
+
+    pub(crate) fn category_group_assign(&mut self, args: &[&str]) -> CommandResult {
+        self.ensure_base_mode("Category groups")?;
+        let usage = "usage: category group assign <group> <category_name>";
+        let [group, category] = args else {
+            return Err(CommandError::InvalidArguments(usage.into()));
+        };
+        let Some((category_id, category_name)) =
+            self.resolve_category_target(Some(category), usage, "")?
+        else {
+            return Ok(());
+        };
+        let group_name = self.with_ledger_mut(|ledger| {
+            CategoryGroupService::assign(ledger, group, category_id).map_err(CommandError::from)?;
+            Ok(ledger
+                .group_of(category_id)
+                .map(|group| group.name.clone())
+                .unwrap_or_default())
+        })?;
+        cli_io::print_success(format!(
+            "`{}` is now in the `{}` group.",
+            category_name, group_name
+        ));
+        Ok(())
+    }
+
+    pub(crate) fn category_group_unassign(&mut self, args: &[&str]) -> CommandResult {
+        self.ensure_base_mode("Category groups")?;
+        let usage = "usage: category group unassign <category_name>";
+        let [category] = args else {
+            return Err(CommandError::InvalidArguments(usage.into()));
+        };
+        let Some((category_id, category_name)) =
+            self.resolve_category_target(Some(category), usage, "")?
+        else {
+            return Ok(());
+        };
+        let removed =
+            self.with_ledger_mut(|ledger| Ok(CategoryGroupService::unassign(ledger, category_id)))?;
+        if removed {
+            cli_io::print_success(format!("`{}` is no longer in a group.", category_name));
+        } else {
+            cli_io::print_info(format!("`{}` is not in a group.", category_name));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn category_group_budget(&mut self, args: &[&str]) -> CommandResult {
+        self.ensure_base_mode("Category groups")?;
+        let usage = "usage: category group budget <group> <amount> [--period <period>]";
+        let (positionals, period_arg) = split_period_flag(args);
+        let [group, amount] = positionals.as_slice() else {
+            return Err(CommandError::InvalidArguments(usage.into()));
+        };
+        let amount = parse_budget_amount(amount)?;
+        let period = match period_arg.as_deref() {
+            Some("") => {
+                return Err(CommandError::InvalidArguments(
+                    "missing value for --period".into(),
+                ))
+            }
+            Some(value) if !value.eq_ignore_ascii_case("default") => {
+                parse_category_budget_period_str(value)?
+            }
+            _ => self.config_default_category_period(),
+        };
+        let (group_name, amount_label, period_label) = self.with_ledger_mut(|ledger| {
+            CategoryGroupService::set_budget(ledger, group, amount, period, None)
+                .map_err(CommandError::from)?;
+            let name = ledger
+                .category_group(group)
+                .map(|group| group.name.clone())
+                .unwrap_or_default();
+            Ok((
+                name,
+                self.format_amount(ledger, amount),
+                self.describe_budget_period_label(ledger, &period, None),
+            ))
+        })?;
+        cli_io::print_success(format!(
+            "Budget for group `{}` set to {} ({})",
+            group_name, amount_label, period_label
+        ));
+        Ok(())
+    }
+
+    pub(crate) fn category_group_clear_budget(&mut self, args: &[&str]) -> CommandResult {
+        self.ensure_base_mode("Category groups")?;
+        let name = args.join(" ");
+        if name.trim().is_empty() {
+            return Err(CommandError::InvalidArguments(
+                "usage: category group clear-budget <group>".into(),
+            ));
+        }
+        let removed = self.with_ledger_mut(|ledger| {
+            CategoryGroupService::clear_budget(ledger, &name).map_err(CommandError::from)
+        })?;
+        if removed {
+            cli_io::print_success(format!(
+                "Budget cleared for group `{}`; member budgets are summed instead.",
+                name.trim()
+            ));
+        } else {
+            cli_io::print_info(format!("Group `{}` has no budget of its own.", name.trim()));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn category_group_list(&self, args: &[&str]) -> CommandResult {
+        if !args.is_empty() {
+            return Err(CommandError::InvalidArguments(
+                "usage: category group list".into(),
+            ));
+        }
+        self.with_ledger(|ledger| {
+            if ledger.category_groups.is_empty() {
+                cli_io::print_info(
+                    "No category groups yet. Create one with `category group add <name>`.",
+                );
+                return Ok(());
+            }
+            for group in &ledger.category_groups {
+                let budget = match &group.budget {
+                    Some(budget) => format!(
+                        "{} ({})",
+                        self.format_amount(ledger, budget.amount.to_major()),
+                        self.describe_budget_period_label(
+                            ledger,
+                            &budget.period,
+                            budget.reference_date
+                        )
+                    ),
+                    None => "sum of member budgets".into(),
+                };
+                let members: Vec<&str> = group
+                    .category_ids
+                    .iter()
+                    .filter_map(|id| ledger.category(*id))
+                    .map(|category| category.name.as_str())
+                    .collect();
+                cli_io::print_info(format!("{:<20} Budget {}", group.name, budget));
+                cli_io::print_info(format!(
+                    "  Categories: {}",
+                    if members.is_empty() {
+                        "none".into()
+                    } else {
+                        members.join(", ")
+                    }
+                ));
+            }
+            Ok(())
+        })
+    }
+
     pub(crate) fn category_budget_show(&self, args: &[&str]) -> CommandResult {
         if args.len() > 1 {
             return Err(CommandError::InvalidArguments(
@@ -1949,19 +2860,27 @@ impl ShellContext {
                 cli_io::print_warning("No category budgets configured.");
                 false
             }
-            Some((heading, rows)) => {
+            Some((heading, mut rows)) => {
                 Formatter::new().print_header(heading);
-                output_table(
-                    &[
-                        "Category",
-                        "Budget",
-                        "Spent",
-                        "Remaining",
-                        "Period",
-                        "Status",
-                    ],
-                    &rows,
-                );
+                let mut headers = vec![
+                    "Category",
+                    "Budget",
+                    "Spent",
+                    "Remaining",
+                    "Period",
+                    "Status",
+                ];
+                if rows
+                    .iter()
+                    .any(|row| row.last().is_some_and(|note| !note.is_empty()))
+                {
+                    headers.push("Note");
+                } else {
+                    rows.iter_mut().for_each(|row| {
+                        row.pop();
+                    });
+                }
+                output_table(&headers, &rows);
                 true
             }
         };
@@ -1977,26 +2896,28 @@ impl ShellContext {
     }
 
     pub(crate) fn add_transaction_script(&mut self, args: &[&str]) -> CommandResult {
-        if args.len() < 4 {
+        if args.len() < 3 {
             return Err(CommandError::InvalidArguments(
-                "usage: add transaction <from_account_index> <to_account_index> <YYYY-MM-DD> <amount>"
+                "usage: add transaction <from_account_index> <to_account_index> [<YYYY-MM-DD>] <amount>"
                     .into(),
             ));
         }
 
         let sim = self.active_simulation_name().map(|s| s.to_string());
 
-        let from_index: usize = args[0].parse().map_err(|_| {
-            CommandError::InvalidArguments("from_account_index must be numeric".into())
-        })?;
-        let to_index: usize = args[1].parse().map_err(|_| {
-            CommandError::InvalidArguments("to_account_index must be numeric".into())
-        })?;
-        let date = NaiveDate::parse_from_str(args[2], "%Y-%m-%d")
-            .map_err(|_| CommandError::InvalidArguments("invalid date".into()))?;
-        let amount: f64 = args[3]
-            .parse()
-            .map_err(|_| CommandError::InvalidArguments("invalid amount".into()))?;
+        let from_index = self.account_index_from_arg(args[0])?;
+        let to_index = self.account_index_from_arg(args[1])?;
+        let (date, amount_arg) = match args {
+            [_, _, amount] => (self.default_transaction_date(), *amount),
+            [_, _, date, amount, ..] => (
+                NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                    .map_err(|_| CommandError::InvalidArguments("invalid date".into()))?,
+                *amount,
+            ),
+            _ => unreachable!("at least three arguments"),
+        };
+        let amount = cli_io::parse_number(amount_arg)
+            .ok_or_else(|| CommandError::InvalidArguments("invalid amount".into()))?;
 
         let (from_id, to_id) = self.with_ledger(|ledger| {
             if ledger.accounts.is_empty() {
@@ -2011,8 +2932,20 @@ impl ShellContext {
             }
             Ok((ledger.accounts[from_index].id, ledger.accounts[to_index].id))
         })?;
+        let amount = self.with_ledger(|ledger| {
+            Money::from_major(amount, ledger.transfer_currency(from_id, to_id))
+                .map_err(|err| CommandError::from(ServiceError::from(err)))
+        })?;
 
-        let transaction = Transaction::new(from_id, to_id, None, date, amount);
+        let mut transaction = Transaction::new(from_id, to_id, None, date, amount.clone());
+        if sim.is_none() && self.default_transaction_status() == TransactionStatus::Completed {
+            if self.autofill_actuals_enabled() {
+                transaction.mark_completed(date, amount);
+            } else {
+                transaction.status = TransactionStatus::Completed;
+            }
+        }
+        self.last_transaction_date = Some(date);
         let summary =
             self.with_ledger(|ledger| Ok(self.transaction_summary_line(ledger, &transaction)))?;
 
@@ -2026,8 +2959,9 @@ impl ShellContext {
                 sim_name, summary
             ));
         } else {
-            let id = self.with_ledger_mut(|ledger| {
-                TransactionService::add(ledger, transaction).map_err(CommandError::from)
+            let id = self.with_ledger_journaled(|ledger, journal| {
+                TransactionService::add_journaled(ledger, transaction, journal)
+                    .map_err(CommandError::from)
             })?;
             let summary = self.with_ledger(|ledger| {
                 let txn = ledger
@@ -2056,10 +2990,16 @@ impl ShellContext {
         let default_status = if simulation.is_some() {
             TransactionStatus::Simulated
         } else {
-            TransactionStatus::Planned
+            self.default_transaction_status()
         };
-        let wizard =
-            TransactionWizard::new_create(accounts, categories, today, min_date, default_status);
+        let wizard = TransactionWizard::new_create(
+            accounts,
+            categories,
+            today,
+            min_date,
+            default_status,
+            self.default_transaction_date(),
+        );
         Banner::render(self);
         let mut interaction = WizardInteraction::new();
         match FormEngine::new(&wizard).run(&mut interaction).unwrap() {
@@ -2095,8 +3035,8 @@ impl ShellContext {
                 category_id: txn.category_id,
                 scheduled_date: txn.scheduled_date,
                 actual_date: txn.actual_date,
-                budgeted_amount: txn.budgeted_amount,
-                actual_amount: txn.actual_amount,
+                budgeted_amount: txn.budgeted_amount.to_major(),
+                actual_amount: txn.actual_amount.as_ref().map(Money::to_major),
                 recurrence: txn.recurrence.clone(),
                 status: txn.status.clone(),
                 notes: txn.notes.clone(),
@@ -2202,6 +3142,12 @@ impl ShellContext {
             cli_io::print_warning("No transactions available.");
             return Ok(());
         }
+        let partial = args.iter().any(|arg| arg.eq_ignore_ascii_case("--partial"));
+        let args: Vec<&str> = args
+            .iter()
+            .copied()
+            .filter(|arg| !arg.eq_ignore_ascii_case("--partial"))
+            .collect();
         let selection = self.transaction_index_from_arg(args.first().copied(), usage, prompt)?;
         let Some(idx) = selection else {
             return Ok(());
@@ -2211,15 +3157,13 @@ impl ShellContext {
             let txn = ledger.transactions.get(idx).ok_or_else(|| {
                 CommandError::InvalidArguments("transaction index out of range".into())
             })?;
-            Ok((
-                txn.scheduled_date,
-                txn.actual_amount.unwrap_or(txn.budgeted_amount),
-            ))
+            Ok((txn.scheduled_date, txn.amount().to_major()))
         })?;
 
         let actual_date = if let Some(raw) = args.get(1) {
             parse_date(raw)?
         } else if self.mode == CliMode::Interactive {
+            cli_io::release_capture();
             let prompt = format!("Completion date for transaction {} (YYYY-MM-DD)", idx);
             let input = Input::<String>::with_theme(&self.theme)
                 .with_prompt(prompt)
@@ -2232,19 +3176,18 @@ impl ShellContext {
         };
 
         let amount: f64 = if let Some(raw) = args.get(2) {
-            raw.parse()
-                .map_err(|_| CommandError::InvalidArguments("amount must be numeric".into()))?
+            cli_io::parse_number(raw)
+                .ok_or_else(|| CommandError::InvalidArguments("amount must be numeric".into()))?
         } else if self.mode == CliMode::Interactive {
+            cli_io::release_capture();
             let prompt = format!("Actual amount for transaction {}", idx);
             let input = Input::<String>::with_theme(&self.theme)
                 .with_prompt(prompt)
                 .with_initial_text(format!("{:.2}", budget_default))
                 .interact_text()
                 .map_err(CommandError::from)?;
-            input
-                .trim()
-                .parse()
-                .map_err(|_| CommandError::InvalidArguments("amount must be numeric".into()))?
+            cli_io::parse_number(&input)
+                .ok_or_else(|| CommandError::InvalidArguments("amount must be numeric".into()))?
         } else {
             return Err(CommandError::InvalidArguments(usage.into()));
         };
@@ -2256,20 +3199,35 @@ impl ShellContext {
             Ok(txn.id)
         })?;
 
-        self.with_ledger_mut(|ledger| {
-            TransactionService::update(ledger, txn_id, |txn| {
-                txn.mark_completed(actual_date, amount);
-            })
-            .map_err(CommandError::from)
+        if partial {
+            let (remaining, transfers) = self.with_ledger_mut(|ledger| {
+                let (_, transfers) =
+                    TransactionService::complete_partial(ledger, txn_id, actual_date, amount)
+                        .map_err(CommandError::from)?;
+                let remaining = ledger
+                    .transaction(txn_id)
+                    .map(|txn| self.format_amount(ledger, txn.budgeted_amount.to_major()))
+                    .unwrap_or_default();
+                Ok((remaining, transfers))
+            })?;
+            cli_io::print_success(format!(
+                "Installment recorded for transaction {}; {} still planned.",
+                idx, remaining
+            ));
+            return commands::rules::report_auto_transfers(self, &transfers);
+        }
+        let transfers = self.with_ledger_mut(|ledger| {
+            TransactionService::complete(ledger, txn_id, actual_date, amount)
+                .map_err(CommandError::from)
         })?;
         cli_io::print_success(format!("Transaction {} marked completed", idx));
-        Ok(())
+        commands::rules::report_auto_transfers(self, &transfers)
     }
 
     pub(crate) fn transaction_complete(&mut self, args: &[&str]) -> CommandResult {
         self.transaction_complete_internal(
             args,
-            "usage: transaction complete <transaction_index> <YYYY-MM-DD> <amount>",
+            "usage: transaction complete <transaction_index> <YYYY-MM-DD> <amount> [--partial]",
             "Select a transaction to complete:",
         )
     }
@@ -2279,6 +3237,7 @@ impl ShellContext {
         default_start: NaiveDate,
         existing: Option<&Recurrence>,
     ) -> Result<Recurrence, CommandError> {
+        cli_io::release_capture();
         let start_default = existing.map(|r| r.start_date).unwrap_or(default_start);
         let start_input = Input::<String>::with_theme(&self.theme)
             .with_prompt("Start date (YYYY-MM-DD)")
@@ -2369,42 +3328,105 @@ impl ShellContext {
             recurrence.last_completed = existing.last_completed;
             recurrence.generated_occurrences = existing.generated_occurrences;
             recurrence.next_scheduled = existing.next_scheduled;
+            recurrence.income = existing.income.clone();
+            recurrence.price_history = existing.price_history.clone();
         }
         Ok(recurrence)
     }
 
     pub(crate) fn show_budget_summary(&self, args: &[&str]) -> CommandResult {
-        let displayed = self.with_ledger(|ledger| {
+        let by_member = args
+            .iter()
+            .any(|arg| arg.eq_ignore_ascii_case("--by-member"));
+        let by_tag = args.iter().any(|arg| arg.eq_ignore_ascii_case("--by-tag"));
+        let price_changes = args
+            .iter()
+            .any(|arg| arg.eq_ignore_ascii_case("--price-changes"));
+        let expand_groups = args
+            .iter()
+            .any(|arg| arg.eq_ignore_ascii_case("--expand-groups"));
+        let expand_categories = args
+            .iter()
+            .any(|arg| arg.eq_ignore_ascii_case("--expand-categories"));
+        let rollup =
+            expand_categories || args.iter().any(|arg| arg.eq_ignore_ascii_case("--rollup"));
+        let (limit, args) = split_row_limit(args)?;
+        let args: Vec<&str> = args
+            .into_iter()
+            .filter(|arg| {
+                !arg.eq_ignore_ascii_case("--by-member")
+                    && !arg.eq_ignore_ascii_case("--by-tag")
+                    && !arg.eq_ignore_ascii_case("--price-changes")
+                    && !arg.eq_ignore_ascii_case("--expand-groups")
+                    && !arg.eq_ignore_ascii_case("--expand-categories")
+                    && !arg.eq_ignore_ascii_case("--rollup")
+            })
+            .collect();
+        let args = args.as_slice();
+        let drilldown = self.with_ledger(|ledger| {
             let today = Utc::now().date_naive();
 
             let (simulation_name, remainder) =
                 if !args.is_empty() && ledger.simulation(args[0]).is_some() {
-                    (Some(args[0]), &args[1..])
+                    (Some(args[0].to_string()), &args[1..])
                 } else {
-                    (None, args)
+                    (self.overlay_simulation(), args)
                 };
 
             let (window, scope) = self.resolve_summary_window(ledger, remainder, today)?;
 
-            if let Some(name) = simulation_name {
+            if let Some(name) = simulation_name.as_deref() {
                 let impact = SummaryService::summarize_simulation(ledger, name, window, scope)
                     .map_err(CommandError::from)?;
-                self.print_simulation_impact(ledger, &impact);
-                return Ok(true);
+                self.print_simulation_impact(ledger, &impact, limit);
+                let orphaned = SimulationService::orphaned_changes(ledger, name)
+                    .map_err(CommandError::from)?
+                    .len();
+                if orphaned > 0 {
+                    cli_io::print_warning(format!(
+                        "{} change(s) in `{}` refer to deleted transactions and were skipped. Run `simulation check {}` to review them.",
+                        orphaned, name, name
+                    ));
+                }
+                return Ok(None);
             }
 
-            let summary = SummaryService::summarize_window(ledger, window, scope);
+            let summary = if rollup {
+                SummaryService::summarize_window_with_rollup(ledger, window, scope)
+            } else {
+                SummaryService::summarize_window_checked(ledger, window, scope)
+            }
+            .map_err(CommandError::from)?;
             let category_budgets = SummaryService::category_budget_summaries(ledger, window, scope);
-            self.print_budget_summary(ledger, &summary, &category_budgets);
-            Ok(true)
+            self.print_budget_summary(
+                ledger,
+                &summary,
+                &category_budgets,
+                limit,
+                expand_categories,
+            );
+            let groups = CategoryGroupService::summaries(ledger, window, scope);
+            self.print_category_group_section(ledger, &groups, expand_groups);
+            if by_member {
+                self.print_member_breakdown(ledger, &summary);
+            }
+            if by_tag {
+                self.print_tag_breakdown(ledger, &summary);
+            }
+            if price_changes {
+                self.print_price_changes(ledger, window);
+            }
+            Ok(Some(summary))
         })?;
-        if displayed {
-            self.await_menu_escape()?;
+        match drilldown {
+            Some(summary) if self.mode == CliMode::Interactive => {
+                commands::ledger::run_summary_drilldown(self, &summary)
+            }
+            _ => self.await_menu_escape(),
         }
-        Ok(())
     }
 
-    fn resolve_summary_window(
+    pub(crate) fn resolve_summary_window(
         &self,
         ledger: &Ledger,
         args: &[&str],
@@ -2493,7 +3515,10 @@ impl ShellContext {
         ledger: &Ledger,
         summary: &BudgetSummary,
         category_budgets: &[CategoryBudgetSummary],
+        limit: RowLimit,
+        expand_categories: bool,
     ) {
+        let rows = limit.resolve(self.config_read().summary_category_rows);
         let end_display = summary
             .window
             .end
@@ -2538,50 +3563,195 @@ impl ShellContext {
             cli_io::print_info("No category data for this window.");
         } else {
             cli_io::print_info("Categories:");
-            for cat in summary.per_category.iter().take(5) {
-                cli_io::print_info(format!(
-                    "  {:<20} {} budgeted / {} real ({:?})",
-                    cat.name,
-                    self.format_amount(ledger, cat.totals.budgeted),
-                    self.format_amount(ledger, cat.totals.real),
-                    cat.totals.status
-                ));
+            for cat in summary.per_category.iter().take(rows) {
+                self.print_category_row(ledger, cat, 1, expand_categories);
             }
-            if summary.per_category.len() > 5 {
+            if summary.per_category.len() > rows {
                 cli_io::print_info(format!(
                     "  ... {} more categories",
-                    summary.per_category.len() - 5
+                    summary.per_category.len() - rows
                 ));
             }
+            let rolled_up = summary
+                .per_category
+                .iter()
+                .any(|cat| !cat.children.is_empty());
+            if rolled_up && !expand_categories {
+                self.print_hint(
+                    "Hint: Add `--expand-categories` to list each category's subcategories.",
+                );
+            }
         }
 
         if !summary.per_account.is_empty() {
             cli_io::print_info("Accounts:");
-            for acct in summary.per_account.iter().take(5) {
+            for acct in summary.per_account.iter().take(rows) {
+                let appearance = ledger
+                    .account(acct.account_id)
+                    .map(|account| &account.appearance);
                 cli_io::print_info(format!(
-                    "  {:<20} {} budgeted / {} real ({:?})",
-                    acct.name,
+                    "  {} {} budgeted / {} real ({:?})",
+                    self.summary_label(&acct.name, appearance),
                     self.format_amount(ledger, acct.totals.budgeted),
                     self.format_amount(ledger, acct.totals.real),
                     acct.totals.status
                 ));
             }
-            if summary.per_account.len() > 5 {
+            if summary.per_account.len() > rows {
                 cli_io::print_info(format!(
                     "  ... {} more accounts",
-                    summary.per_account.len() - 5
+                    summary.per_account.len() - rows
+                ));
+            }
+        }
+
+        if let Some(gain) = summary.fx_revaluation {
+            cli_io::print_info(format!(
+                "Unrealized FX gain/loss: {}",
+                self.format_amount(ledger, gain)
+            ));
+        }
+
+        if !summary.earmarked.is_empty() {
+            let total: f64 = summary.earmarked.iter().map(|accrual| accrual.amount).sum();
+            cli_io::print_info(format!(
+                "Earmarked for sinking funds: {}",
+                self.format_amount(ledger, total)
+            ));
+            for accrual in &summary.earmarked {
+                cli_io::print_info(format!(
+                    "  {:<20} {}",
+                    accrual.name,
+                    self.format_amount(ledger, accrual.amount)
                 ));
             }
         }
 
-        if !summary.disclosures.is_empty() {
+        let report_date = summary.window.end.pred_opt().unwrap_or(summary.window.end);
+        let stale_rates = self.stale_rate_notes(ledger, report_date);
+        if !summary.disclosures.is_empty() || !stale_rates.is_empty() {
             cli_io::print_info("Disclosures:");
-            for note in &summary.disclosures {
+            for note in summary.disclosures.iter().chain(&stale_rates) {
                 cli_io::print_info(format!("  - {}", note));
             }
         }
 
-        self.print_category_budget_section(ledger, "Category Budgets", category_budgets);
+        self.print_category_budget_section(ledger, "Category Budgets", category_budgets, limit);
+    }
+
+    /// One summary line for `cat`; with `expand` its subcategory subtotals
+    /// follow, one level deeper each.
+    fn print_category_row(
+        &self,
+        ledger: &Ledger,
+        cat: &CategoryBudget,
+        depth: usize,
+        expand: bool,
+    ) {
+        let note = cat
+            .note
+            .as_deref()
+            .map(|note| format!(" — {}", note))
+            .unwrap_or_default();
+        let appearance = cat
+            .category_id
+            .and_then(|id| ledger.category(id))
+            .map(|category| &category.appearance);
+        let subcategories = if cat.children.is_empty() || expand {
+            String::new()
+        } else {
+            format!(" [+{} subcategories]", cat.children.len())
+        };
+        cli_io::print_info(format!(
+            "{}{} {} budgeted / {} real ({:?}){}{}",
+            "  ".repeat(depth),
+            self.summary_label(&cat.name, appearance),
+            self.format_amount(ledger, cat.totals.budgeted),
+            self.format_amount(ledger, cat.totals.real),
+            cat.totals.status,
+            note,
+            subcategories
+        ));
+        if expand {
+            for child in &cat.children {
+                self.print_category_row(ledger, child, depth + 1, expand);
+            }
+        }
+    }
+
+    /// Compares `window` with the window before it, splitting each category's
+    /// change in actual spending into recurring price changes and usage.
+    fn print_price_changes(&self, ledger: &Ledger, window: DateWindow) {
+        let previous = if ledger.budget_window_for(window.start) == window {
+            window.shift(&ledger.budget_period.0, -1)
+        } else {
+            let length = window.end - window.start;
+            DateWindow {
+                start: window.start - length,
+                end: window.start,
+            }
+        };
+        let changes = RecurrenceService::category_price_changes(ledger, previous, window);
+        let end_display = previous.end - Duration::days(1);
+        cli_io::print_info(format!(
+            "Price vs usage (compared with {} → {}):",
+            self.format_date(ledger, previous.start),
+            self.format_date(ledger, end_display)
+        ));
+        if changes.is_empty() {
+            cli_io::print_info("  No category spending changed.");
+            return;
+        }
+        for change in &changes {
+            cli_io::print_info(format!(
+                "  {:<20} {} → {} | price {} | usage {}",
+                change.name,
+                self.format_amount(ledger, change.previous),
+                self.format_amount(ledger, change.current),
+                self.format_amount(ledger, change.price_effect),
+                self.format_amount(ledger, change.usage_effect)
+            ));
+        }
+    }
+
+    fn print_member_breakdown(&self, ledger: &Ledger, summary: &BudgetSummary) {
+        if ledger.members.is_empty() {
+            cli_io::print_warning(
+                "No household members defined. Add one with `member add <name>`.",
+            );
+            return;
+        }
+        if summary.per_member.is_empty() {
+            cli_io::print_info("Members: no activity in this window.");
+            return;
+        }
+        cli_io::print_info("Members:");
+        for member in &summary.per_member {
+            cli_io::print_info(format!(
+                "  {:<20} {} budgeted / {} real ({:?})",
+                member.name,
+                self.format_amount(ledger, member.totals.budgeted),
+                self.format_amount(ledger, member.totals.real),
+                member.totals.status
+            ));
+        }
+    }
+
+    fn print_tag_breakdown(&self, ledger: &Ledger, summary: &BudgetSummary) {
+        if summary.per_tag.is_empty() {
+            cli_io::print_info("Tags: no tagged activity in this window.");
+            return;
+        }
+        cli_io::print_info("Tags:");
+        for tag in &summary.per_tag {
+            cli_io::print_info(format!(
+                "  {:<20} {} budgeted / {} real ({:?})",
+                tag.tag,
+                self.format_amount(ledger, tag.totals.budgeted),
+                self.format_amount(ledger, tag.totals.real),
+                tag.totals.status
+            ));
+        }
     }
 
     fn print_category_budget_section(
@@ -2589,13 +3759,15 @@ impl ShellContext {
         ledger: &Ledger,
         heading: &str,
         budgets: &[CategoryBudgetSummary],
+        limit: RowLimit,
     ) {
         if budgets.is_empty() {
             cli_io::print_info(format!("{heading}: no category budgets configured."));
             return;
         }
         cli_io::print_info(heading);
-        for summary in budgets.iter().take(8) {
+        let rows = limit.resolve(CATEGORY_BUDGET_ROWS);
+        for summary in budgets.iter().take(rows) {
             let icon = self.category_budget_status_icon(&summary.status);
             let utilization = summary
                 .utilization_percent
@@ -2611,8 +3783,61 @@ impl ShellContext {
                 self.describe_budget_period_label(ledger, &summary.period, summary.reference_date)
             ));
         }
-        if budgets.len() > 8 {
-            cli_io::print_info(format!("  ... {} more categories", budgets.len() - 8));
+        if budgets.len() > rows {
+            cli_io::print_info(format!("  ... {} more categories", budgets.len() - rows));
+        }
+    }
+
+    /// One row per group; with `expand` each member follows its group.
+    fn print_category_group_section(
+        &self,
+        ledger: &Ledger,
+        groups: &[CategoryGroupSummary],
+        expand: bool,
+    ) {
+        if groups.is_empty() {
+            return;
+        }
+        cli_io::print_info("Category Groups");
+        for group in groups {
+            let icon = self.category_budget_status_icon(&group.totals.status);
+            let budget = match group.budget_amount {
+                Some(amount) if group.group_budget => self.format_amount(ledger, amount),
+                Some(amount) => format!("{} (members)", self.format_amount(ledger, amount)),
+                None => "-".into(),
+            };
+            let utilization = group
+                .totals
+                .percent_used
+                .filter(|_| group.budget_amount.is_some())
+                .map(|value| format!("{value:.0}%"))
+                .unwrap_or_else(|| "-".into());
+            cli_io::print_info(format!(
+                "  {icon} {:<20} Budget {} | Spent {} | Used {} | {} categories",
+                group.name,
+                budget,
+                self.format_amount(ledger, group.totals.real),
+                utilization,
+                group.members.len()
+            ));
+            if !expand {
+                continue;
+            }
+            for member in &group.members {
+                let budget = member
+                    .window_budget
+                    .map(|amount| self.format_amount(ledger, amount))
+                    .unwrap_or_else(|| "-".into());
+                cli_io::print_info(format!(
+                    "      {:<18} Budget {} | Spent {}",
+                    member.name,
+                    budget,
+                    self.format_amount(ledger, member.totals.real)
+                ));
+            }
+        }
+        if !expand {
+            self.print_hint("Hint: Add `--expand-groups` to list each group's categories.");
         }
     }
 
@@ -2625,7 +3850,12 @@ impl ShellContext {
         }
     }
 
-    fn print_simulation_impact(&self, ledger: &Ledger, impact: &SimulationBudgetImpact) {
+    fn print_simulation_impact(
+        &self,
+        ledger: &Ledger,
+        impact: &SimulationBudgetImpact,
+        limit: RowLimit,
+    ) {
         Formatter::new().print_header(format!("Simulation `{}`", impact.simulation_name));
         cli_io::print_info("Base totals:");
         cli_io::print_info(format!(
@@ -2655,19 +3885,218 @@ impl ShellContext {
             ledger,
             "Category Budgets (Base)",
             &impact.category_budgets_base,
+            limit,
         );
         self.print_category_budget_section(
             ledger,
             "Category Budgets (Simulated)",
             &impact.category_budgets_simulated,
+            limit,
+        );
+    }
+
+    /// Opening, lowest, and closing balance per account, with a warning for
+    /// each account projected to fall below `minimum`; `daily` adds the
+    /// balance of every account on each day money moves.
+    pub(crate) fn print_balance_forecast(
+        &self,
+        ledger: &Ledger,
+        simulation: Option<&str>,
+        forecast: &BalanceForecast,
+        minimum: f64,
+        daily: bool,
+    ) {
+        let window = forecast.window;
+        let last = window.end.pred_opt().unwrap_or(window.end);
+        let header = simulation
+            .map(|name| format!("Projected balances `{}`", name))
+            .unwrap_or_else(|| "Projected balances".to_string());
+        Formatter::new().print_header(format!(
+            "{header} {} → {}",
+            self.format_date(ledger, window.start),
+            self.format_date(ledger, last)
+        ));
+        if forecast.accounts.is_empty() {
+            cli_io::print_warning("No bank, cash, or savings accounts to project.");
+            return;
+        }
+        let amount = |value: f64, currency: &str| {
+            format_currency_value(
+                value,
+                &CurrencyCode::new(currency.to_string()),
+                &ledger.locale,
+                &ledger.format,
+            )
+        };
+        let rows: Vec<Vec<String>> = forecast
+            .accounts
+            .iter()
+            .map(|account| {
+                let (lowest_on, lowest) = account.lowest();
+                vec![
+                    account.name.clone(),
+                    amount(account.opening_balance, &account.currency),
+                    amount(lowest, &account.currency),
+                    self.format_date(ledger, lowest_on),
+                    amount(account.closing_balance, &account.currency),
+                ]
+            })
+            .collect();
+        output_table(
+            &["Account", "Opening", "Lowest", "Lowest on", "Closing"],
+            &rows,
+        );
+
+        if daily {
+            let mut dates: Vec<NaiveDate> = forecast
+                .accounts
+                .iter()
+                .flat_map(|account| account.points.iter().map(|(date, _)| *date))
+                .collect();
+            dates.sort();
+            dates.dedup();
+            let mut headers = vec!["Date"];
+            headers.extend(
+                forecast
+                    .accounts
+                    .iter()
+                    .map(|account| account.name.as_str()),
+            );
+            let rows: Vec<Vec<String>> = dates
+                .iter()
+                .map(|date| {
+                    let mut row = vec![self.format_date(ledger, *date)];
+                    row.extend(forecast.accounts.iter().map(|account| {
+                        let balance = account
+                            .points
+                            .iter()
+                            .take_while(|(day, _)| day <= date)
+                            .last()
+                            .map_or(account.opening_balance, |(_, balance)| *balance);
+                        amount(balance, &account.currency)
+                    }));
+                    row
+                })
+                .collect();
+            output_table(&headers, &rows);
+        }
+
+        for account in &forecast.accounts {
+            if let Some((date, balance)) = account.first_below(minimum) {
+                cli_io::print_warning(format!(
+                    "{} is projected to fall below {} on {} ({}).",
+                    account.name,
+                    amount(minimum, &account.currency),
+                    self.format_date(ledger, date),
+                    amount(balance, &account.currency)
+                ));
+            }
+        }
+    }
+
+    /// One row per budget window; `base` adds the change in closing balance
+    /// against the ledger without the simulation.
+    pub(crate) fn print_forecast_periods(
+        &self,
+        ledger: &Ledger,
+        simulation: Option<&str>,
+        periods: &[PeriodForecast],
+        base: Option<&[PeriodForecast]>,
+    ) {
+        let header = simulation
+            .map(|name| format!("Forecast `{}` by period", name))
+            .unwrap_or_else(|| "Forecast by period".to_string());
+        Formatter::new().print_header(header);
+        let mut headers = vec![
+            "Period",
+            "Opening",
+            "Inflow",
+            "Outflow",
+            "Net",
+            "Closing",
+            "Projected",
+        ];
+        if base.is_some() {
+            headers.push("Δ Closing");
+        }
+        let rows: Vec<Vec<String>> = periods
+            .iter()
+            .enumerate()
+            .map(|(index, period)| {
+                let last = period.window.end.pred_opt().unwrap_or(period.window.end);
+                let mut row = vec![
+                    format!(
+                        "{} → {}",
+                        self.format_date(ledger, period.window.start),
+                        self.format_date(ledger, last)
+                    ),
+                    self.format_amount(ledger, period.opening_balance),
+                    self.format_amount(ledger, period.inflow),
+                    self.format_amount(ledger, period.outflow),
+                    self.format_amount(ledger, period.net()),
+                    self.format_amount(ledger, period.closing_balance),
+                    period.projected.to_string(),
+                ];
+                if let Some(base) = base.and_then(|base| base.get(index)) {
+                    row.push(
+                        self.format_amount(ledger, period.closing_balance - base.closing_balance),
+                    );
+                }
+                row
+            })
+            .collect();
+        output_table(&headers, &rows);
+        cli_io::print_info(
+            "Balances combine bank, cash, and savings accounts; planned transactions count on their scheduled date.",
         );
     }
 
+    /// Base and simulated forecast totals side by side, with the change.
+    pub(crate) fn print_forecast_overlay(
+        &self,
+        ledger: &Ledger,
+        base: &ForecastReport,
+        simulated: &ForecastReport,
+    ) {
+        cli_io::print_info("Base → simulated:");
+        let rows = [
+            (
+                "Inflow",
+                base.forecast.totals.projected_inflow,
+                simulated.forecast.totals.projected_inflow,
+            ),
+            (
+                "Outflow",
+                base.forecast.totals.projected_outflow,
+                simulated.forecast.totals.projected_outflow,
+            ),
+            (
+                "Net",
+                base.forecast.totals.net,
+                simulated.forecast.totals.net,
+            ),
+            (
+                "Remaining",
+                base.summary.totals.remaining,
+                simulated.summary.totals.remaining,
+            ),
+        ];
+        for (label, base, simulated) in rows {
+            cli_io::print_info(format!(
+                "  {label:<9} {} → {} (Δ {})",
+                self.format_amount(ledger, base),
+                self.format_amount(ledger, simulated),
+                self.format_amount(ledger, simulated - base)
+            ));
+        }
+    }
+
     pub(crate) fn print_forecast_report(
         &self,
         ledger: &Ledger,
         simulation: Option<&str>,
         report: &ForecastReport,
+        limit: RowLimit,
     ) {
         let window = report.forecast.window;
         let header = simulation
@@ -2728,6 +4157,7 @@ impl ShellContext {
             ledger,
             "Category Budgets (Projected)",
             &report.category_budgets,
+            limit,
         );
         if !report.summary.disclosures.is_empty() {
             cli_io::print_info("Disclosures:");
@@ -2736,13 +4166,38 @@ impl ShellContext {
             }
         }
 
+        if !report.goal_contributions.is_empty() {
+            let total: f64 = report
+                .goal_contributions
+                .iter()
+                .map(|contribution| contribution.amount)
+                .sum();
+            cli_io::print_info(format!(
+                "Planned goal contributions: {}",
+                self.format_amount(ledger, total)
+            ));
+            for contribution in report
+                .goal_contributions
+                .iter()
+                .take(limit.resolve(self.config_read().forecast_transaction_rows))
+            {
+                cli_io::print_info(format!(
+                    "  {} | {} | {}",
+                    self.format_date(ledger, contribution.date),
+                    self.format_amount(ledger, contribution.amount),
+                    contribution.name
+                ));
+            }
+        }
+
         if report.forecast.transactions.is_empty() {
             cli_io::print_info("No additional projections required within this window.");
             return;
         }
 
         cli_io::print_info("Upcoming projections:");
-        for item in report.forecast.transactions.iter().take(8) {
+        let rows = limit.resolve(self.config_read().forecast_transaction_rows);
+        for item in report.forecast.transactions.iter().take(rows) {
             let status = self.scheduled_status_label(item.status);
             let route = self.describe_transaction_route(ledger, &item.transaction);
             let category = item
@@ -2752,7 +4207,7 @@ impl ShellContext {
                 .unwrap_or_else(|| "Uncategorized".into());
             let txn_currency = ledger.transaction_currency(&item.transaction);
             let amount = format_currency_value(
-                item.transaction.budgeted_amount,
+                item.transaction.budgeted_amount.to_major(),
                 &txn_currency,
                 &ledger.locale,
                 &ledger.format,
@@ -2766,10 +4221,10 @@ impl ShellContext {
                 category = category
             ));
         }
-        if report.forecast.transactions.len() > 8 {
+        if report.forecast.transactions.len() > rows {
             cli_io::print_info(format!(
                 "  ... {} additional projections",
-                report.forecast.transactions.len() - 8
+                report.forecast.transactions.len() - rows
             ));
         }
     }
@@ -2800,7 +4255,7 @@ impl ShellContext {
             .and_then(|id| self.lookup_category_name(ledger, id))
             .unwrap_or_else(|| "Uncategorized".into());
         let amount = format_currency_value(
-            txn.budgeted_amount,
+            txn.budgeted_amount.to_major(),
             &ledger.transaction_currency(txn),
             &ledger.locale,
             &ledger.format,
@@ -2826,6 +4281,11 @@ impl ShellContext {
             RecurrenceStatus::Paused => parts.push("paused".into()),
             RecurrenceStatus::Completed => parts.push("completed".into()),
         }
+        match rule.business_day_shift {
+            BusinessDayShift::Keep => {}
+            BusinessDayShift::Following => parts.push("following business day".into()),
+            BusinessDayShift::Preceding => parts.push("preceding business day".into()),
+        }
         if let Some(next) = rule.next_scheduled {
             parts.push(format!("next {}", next));
         }
@@ -2995,6 +4455,37 @@ impl ShellContext {
         Ok(())
     }
 
+    pub(crate) fn recurrence_set_business_day_shift(
+        &mut self,
+        index: usize,
+        shift: BusinessDayShift,
+    ) -> CommandResult {
+        self.ensure_base_mode("Recurrence business-day change")?;
+        let transaction_id = self.with_ledger(|ledger| {
+            ledger
+                .transactions
+                .get(index)
+                .map(|txn| txn.id)
+                .ok_or_else(|| {
+                    CommandError::InvalidArguments("transaction index out of range".into())
+                })
+        })?;
+        self.with_ledger_mut(|ledger| {
+            RecurrenceService::set_business_day_shift(ledger, transaction_id, shift)
+                .map_err(CommandError::from)
+        })?;
+        let message = match shift {
+            BusinessDayShift::Keep => "keep dates that fall on non-business days",
+            BusinessDayShift::Following => "move to the following business day",
+            BusinessDayShift::Preceding => "move to the preceding business day",
+        };
+        cli_io::print_success(format!(
+            "Occurrences of transaction {} now {}.",
+            index, message
+        ));
+        Ok(())
+    }
+
     pub(crate) fn recurrence_skip_date(&mut self, index: usize, date: NaiveDate) -> CommandResult {
         self.ensure_base_mode("Recurrence exception editing")?;
         let transaction_id = self.with_ledger(|ledger| {
@@ -3100,6 +4591,15 @@ impl ShellContext {
                 CommandError::InvalidArguments(format!("simulation `{}` not found", sim_name))
             })?;
             cli_io::print_info(format!("Simulation `{}` ({:?})", sim.name, sim.status));
+            if let Some(parent) = sim
+                .parent
+                .and_then(|id| ledger.simulations().iter().find(|other| other.id == id))
+            {
+                cli_io::print_info(format!(
+                    "Based on `{}`; its pending changes are included in previews.",
+                    parent.name
+                ));
+            }
             if sim.changes.is_empty() {
                 cli_io::print_info("No pending changes.");
             } else {
@@ -3125,6 +4625,24 @@ impl ShellContext {
                                 idx, transaction_id
                             ))
                         }
+                        SimulationChange::ModifyIncome(patch) => {
+                            let mut parts = Vec::new();
+                            if let Some(gross) = patch.gross {
+                                parts.push(format!("gross {:.2}", gross));
+                            }
+                            if let Some(percent) = patch.withholding_percent {
+                                parts.push(format!("withholding {}%", percent));
+                            }
+                            if let Some(benefits) = patch.benefits {
+                                parts.push(format!("benefits {:.2}", benefits));
+                            }
+                            cli_io::print_info(format!(
+                                "  [{:>2}] Modify income {} ({})",
+                                idx,
+                                patch.transaction_id,
+                                parts.join(", ")
+                            ))
+                        }
                     }
                 }
             }
@@ -3137,7 +4655,9 @@ impl ShellContext {
     }
 
     pub(crate) fn simulation_exclude_transaction(&mut self, sim_name: &str) -> CommandResult {
-        let txn_id = self.select_transaction_id("Exclude which transaction?")?;
+        let Some(txn_id) = self.select_transaction_id("Exclude which transaction?")? else {
+            return Ok(());
+        };
         self.with_ledger_mut(|ledger| {
             SimulationService::exclude_transaction(ledger, sim_name, txn_id)
                 .map_err(CommandError::from)
@@ -3147,7 +4667,9 @@ impl ShellContext {
     }
 
     pub(crate) fn simulation_modify_transaction(&mut self, sim_name: &str) -> CommandResult {
-        let txn_id = self.select_transaction_id("Modify which transaction?")?;
+        let Some(txn_id) = self.select_transaction_id("Modify which transaction?")? else {
+            return Ok(());
+        };
 
         let budgeted_input =
             self.prompt_optional_f64("New budgeted amount (leave blank to keep)")?;
@@ -3184,48 +4706,8 @@ impl ShellContext {
         Ok(())
     }
 
-    fn select_transaction_id(&self, prompt: &str) -> Result<Uuid, CommandError> {
-        let items = self.with_ledger(|ledger| {
-            if ledger.transactions.is_empty() {
-                return Err(CommandError::InvalidArguments(
-                    "No transactions available".into(),
-                ));
-            }
-            let items: Vec<String> = ledger
-                .transactions
-                .iter()
-                .enumerate()
-                .map(|(idx, txn)| {
-                    format!(
-                        "[{}] {} -> {} | {} | {:.2}",
-                        idx,
-                        txn.from_account,
-                        txn.to_account,
-                        txn.scheduled_date,
-                        txn.budgeted_amount
-                    )
-                })
-                .collect();
-            Ok(items)
-        })?;
-        let selection = Select::with_theme(&self.theme)
-            .with_prompt(prompt)
-            .items(&items)
-            .default(0)
-            .interact()
-            .map_err(CommandError::from)?;
-        self.with_ledger(|ledger| {
-            ledger
-                .transactions
-                .get(selection)
-                .map(|txn| txn.id)
-                .ok_or_else(|| {
-                    CommandError::InvalidArguments("transaction index out of range".into())
-                })
-        })
-    }
-
     fn prompt_optional_f64(&self, prompt: &str) -> Result<Option<f64>, CommandError> {
+        cli_io::release_capture();
         let input: String = Input::with_theme(&self.theme)
             .with_prompt(prompt)
             .interact_text()
@@ -3233,11 +4715,9 @@ impl ShellContext {
         if input.trim().is_empty() {
             Ok(None)
         } else {
-            input
-                .trim()
-                .parse::<f64>()
+            cli_io::parse_number(&input)
                 .map(Some)
-                .map_err(|_| CommandError::InvalidArguments("Invalid number supplied".into()))
+                .ok_or_else(|| CommandError::InvalidArguments("Invalid number supplied".into()))
         }
     }
 
@@ -3245,6 +4725,7 @@ impl ShellContext {
         &self,
         prompt: &str,
     ) -> Result<Option<Option<f64>>, CommandError> {
+        cli_io::release_capture();
         let input: String = Input::with_theme(&self.theme)
             .with_prompt(prompt)
             .interact_text()
@@ -3255,14 +4736,14 @@ impl ShellContext {
         } else if trimmed.eq_ignore_ascii_case("none") {
             Ok(Some(None))
         } else {
-            let value = trimmed
-                .parse::<f64>()
-                .map_err(|_| CommandError::InvalidArguments("Invalid number supplied".into()))?;
+            let value = cli_io::parse_number(trimmed)
+                .ok_or_else(|| CommandError::InvalidArguments("Invalid number supplied".into()))?;
             Ok(Some(Some(value)))
         }
     }
 
     fn prompt_optional_date(&self, prompt: &str) -> Result<Option<NaiveDate>, CommandError> {
+        cli_io::release_capture();
         let input: String = Input::with_theme(&self.theme)
             .with_prompt(prompt)
             .interact_text()
@@ -3281,6 +4762,7 @@ impl ShellContext {
         &self,
         prompt: &str,
     ) -> Result<Option<Option<NaiveDate>>, CommandError> {
+        cli_io::release_capture();
         let input: String = Input::with_theme(&self.theme)
             .with_prompt(prompt)
             .interact_text()
@@ -3306,7 +4788,7 @@ fn interval_options() -> &'static [&'static str] {
     &["Monthly", "Weekly", "Daily", "Yearly", "Custom..."]
 }
 
-fn parse_time_interval_str(input: &str) -> Result<TimeInterval, CommandError> {
+pub(crate) fn parse_time_interval_str(input: &str) -> Result<TimeInterval, CommandError> {
     let normalized = input.trim().to_lowercase();
     if normalized.is_empty() {
         return Err(CommandError::InvalidArguments(
@@ -3435,9 +4917,8 @@ fn describe_category_budget_period(period: &CategoryBudgetPeriod) -> String {
 }
 
 fn parse_budget_amount(value: &str) -> Result<f64, CommandError> {
-    let amount: f64 = value
-        .parse()
-        .map_err(|_| CommandError::InvalidArguments(format!("invalid amount `{}`", value)))?;
+    let amount = cli_io::parse_number(value)
+        .ok_or_else(|| CommandError::InvalidArguments(format!("invalid amount `{}`", value)))?;
     if amount <= 0.0 {
         return Err(CommandError::InvalidArguments(
             "amount must be greater than 0".into(),
@@ -3475,43 +4956,13 @@ fn split_period_flag(args: &[&str]) -> (Vec<String>, Option<String>) {
 }
 
 fn format_backup_label(file_name: &str) -> String {
-    let trimmed = trim_backup_extension(file_name);
-    let segments: Vec<&str> = trimmed.split('_').collect();
-    if segments.len() < 3 {
-        return file_name.to_string();
-    }
-    let date_part = segments[segments.len() - 2];
-    let time_part = segments[segments.len() - 1];
-    let is_well_formed = date_part.len() == 8
-        && time_part.len() == 4
-        && date_part.chars().all(|c| c.is_ascii_digit())
-        && time_part.chars().all(|c| c.is_ascii_digit());
-    if !is_well_formed {
-        return file_name.to_string();
-    }
-    let raw = format!("{}{}", date_part, time_part);
-    let timestamp = NaiveDateTime::parse_from_str(&raw, "%Y%m%d%H%M")
-        .ok()
-        .map(|naive| DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc));
-    if let Some(utc) = timestamp {
-        let local = utc.with_timezone(&Local);
-        format!(
+    match parse_backup_timestamp(file_name) {
+        Some(utc) => format!(
             "{} (Created: {})",
             file_name,
-            local.format("%Y-%m-%d %H:%M")
-        )
-    } else {
-        file_name.to_string()
-    }
-}
-
-fn trim_backup_extension(name: &str) -> &str {
-    if let Some(stripped) = name.strip_suffix(".bbfy") {
-        stripped
-    } else if let Some(stripped) = name.strip_suffix(".json") {
-        stripped
-    } else {
-        name
+            utc.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+        ),
+        None => file_name.to_string(),
     }
 }
 
@@ -3623,18 +5074,62 @@ fn parse_positive_or_default(arg: Option<&&str>, default: usize) -> Result<usize
     }
 }
 
+/// Category budget rows shown by summaries and forecasts unless `--limit`
+/// or `--full` is given.
+const CATEGORY_BUDGET_ROWS: usize = 8;
+
+/// Row cap requested for long report sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RowLimit {
+    /// Use the section's configured default.
+    Default,
+    Rows(usize),
+    /// Show every row.
+    Full,
+}
+
+impl RowLimit {
+    pub(crate) fn resolve(self, default: usize) -> usize {
+        match self {
+            RowLimit::Default => default,
+            RowLimit::Rows(rows) => rows,
+            RowLimit::Full => usize::MAX,
+        }
+    }
+}
+
+/// Removes `--limit <n>` and `--full` from report arguments.
+pub(crate) fn split_row_limit<'a>(
+    args: &[&'a str],
+) -> Result<(RowLimit, Vec<&'a str>), CommandError> {
+    let mut limit = RowLimit::Default;
+    let mut rest = Vec::with_capacity(args.len());
+    let mut iter = args.iter().copied();
+    while let Some(arg) = iter.next() {
+        if arg.eq_ignore_ascii_case("--full") {
+            limit = RowLimit::Full;
+        } else if arg.eq_ignore_ascii_case("--limit") {
+            let rows = iter
+                .next()
+                .and_then(|value| value.parse::<usize>().ok())
+                .filter(|rows| *rows > 0)
+                .ok_or_else(|| {
+                    CommandError::InvalidArguments("--limit needs a positive row count".into())
+                })?;
+            limit = RowLimit::Rows(rows);
+        } else {
+            rest.push(arg);
+        }
+    }
+    Ok((limit, rest))
+}
+
 pub(crate) fn parse_date(input: &str) -> Result<NaiveDate, CommandError> {
     NaiveDate::parse_from_str(input, "%Y-%m-%d").map_err(|_| {
         CommandError::InvalidArguments(format!("invalid date `{}` (use YYYY-MM-DD)", input))
     })
 }
 
-fn short_id(id: Uuid) -> String {
-    let mut short = id.simple().to_string();
-    short.truncate(8);
-    short
-}
-
 #[derive(Debug, thiserror::Error)]
 pub enum CommandError {
     #[error("Ledger not loaded. Use `ledger new` or `ledger load` first.")]
@@ -3651,6 +5146,8 @@ pub enum CommandError {
     Core(#[from] BudgetError),
     #[error(transparent)]
     Dialoguer(#[from] dialoguer::Error),
+    #[error("Assertion failed: {0}")]
+    AssertionFailed(String),
     #[error("exit requested")]
     ExitRequested,
 }
@@ -3663,11 +5160,16 @@ impl From<ServiceError> for CommandError {
             | ServiceError::AccountNotFound(message)
             | ServiceError::CategoryNotFound(message)
             | ServiceError::SimulationNotFound(message)
+            | ServiceError::MemberNotFound(message)
+            | ServiceError::ViewNotFound(message)
+            | ServiceError::RuleNotFound(message)
+            | ServiceError::DraftNotFound(message)
             | ServiceError::Validation(message)
             | ServiceError::InvalidOperation(message) => CommandError::InvalidArguments(message),
             ServiceError::TransactionNotFound(id) => {
                 CommandError::InvalidArguments(format!("transaction {} not found", id))
             }
+            ServiceError::Invalid(issue) => CommandError::InvalidArguments(issue.to_string()),
             other => CommandError::from_core(other),
         }
     }
@@ -3855,7 +5357,8 @@ mod tests {
         ledger.add_category(category);
 
         let date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
-        let mut txn = Transaction::new(bank, savings, Some(category_id), date, 100.0);
+        let amount = Money::from_major(100.0, ledger.base_currency().clone()).unwrap();
+        let mut txn = Transaction::new(bank, savings, Some(category_id), date, amount);
         let interval = TimeInterval {
             every: 1,
             unit: TimeUnit::Month,
@@ -3869,11 +5372,15 @@ mod tests {
             id: Uuid::new_v4(),
             name: "Scenario".into(),
             notes: None,
+            parent: None,
             status: SimulationStatus::Pending,
             created_at: now,
             updated_at: now,
             applied_at: None,
+            pre_apply_backup: None,
             changes: Vec::new(),
+            applied: Vec::new(),
+            extra: Default::default(),
         });
 
         ledger
@@ -3895,7 +5402,10 @@ mod tests {
             })
             .unwrap();
         match outcome {
-            SelectionOutcome::Selected(id) => assert_eq!(id, 1),
+            SelectionOutcome::Selected(id) => {
+                let expected = context.with_ledger(|ledger| Ok(ledger.accounts[1].id));
+                assert_eq!(id, expected.unwrap());
+            }
             _ => panic!("expected account selection"),
         }
     }
@@ -3917,7 +5427,10 @@ mod tests {
                 Ok(Some(0))
             })
             .unwrap();
-        assert!(matches!(outcome, SelectionOutcome::Selected(0)));
+        let first_visible = context
+            .with_ledger(|ledger| Ok(ledger.categories.iter().find(|c| !c.hidden).unwrap().id))
+            .unwrap();
+        assert!(matches!(outcome, SelectionOutcome::Selected(id) if id == first_visible));
 
         let outcome = SelectionManager::new(CategorySelectionProvider::new(&context))
             .choose_with("Select category", "No categories available.", |_, _| {
@@ -3937,7 +5450,10 @@ mod tests {
                 |_, _| Ok(Some(0)),
             )
             .unwrap();
-        assert!(matches!(outcome, SelectionOutcome::Selected(0)));
+        let first = context
+            .with_ledger(|ledger| Ok(ledger.transactions[0].id))
+            .unwrap();
+        assert!(matches!(outcome, SelectionOutcome::Selected(id) if id == first));
 
         let outcome = SelectionManager::new(TransactionSelectionProvider::new(&context))
             .choose_with(
@@ -4015,8 +5531,10 @@ mod tests {
         let manager = Arc::new(RwLock::new(
             ConfigManager::with_base_dir(temp.path().to_path_buf()).unwrap(),
         ));
-        let mut config = Config::default();
-        config.locale = "en-GB".into();
+        let config = Config {
+            locale: "en-GB".into(),
+            ..Config::default()
+        };
         {
             let manager_guard = manager.read().unwrap();
             manager_guard.save(&config).unwrap();
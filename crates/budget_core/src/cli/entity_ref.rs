@@ -0,0 +1,128 @@
+//! Addressing accounts, categories, and transactions by something that
+//! survives sorting and filtering.
+//!
+//! List indexes shift whenever a list is reordered, filtered, or grows, so
+//! every command that takes an index also accepts the record's id: the full
+//! UUID or a unique prefix of it, such as the `ab12cd34` shown by `show`
+//! commands. Transactions also accept their short reference (`T-0012`).
+//!
+//! Plain digits stay an index. `@ab12cd34`, or `--id ab12cd34` anywhere in
+//! a command, forces the id reading for prefixes that are all digits; a
+//! short reference reads the same with or without them.
+
+use std::borrow::Cow;
+
+use bufy_domain::ids::ShortRef;
+use uuid::Uuid;
+
+use crate::cli::core::CommandError;
+
+/// Shortest id prefix accepted, to keep typos from matching by chance.
+pub(crate) const MIN_ID_PREFIX: usize = 4;
+
+/// How a command argument points at a record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum EntityRef {
+    /// Position in the ledger list.
+    Index(usize),
+    /// Lowercase hex digits the record's id starts with, dashes removed.
+    Id(String),
+    /// A transaction's short reference, such as `T-0012`.
+    Short(ShortRef),
+}
+
+impl EntityRef {
+    /// Reads an index, a transaction reference, `@<id>`, or an id prefix;
+    /// `None` when `raw` is none of these.
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        let forced = raw.strip_prefix('@');
+        if let Some(reference) = ShortRef::parse(forced.unwrap_or(raw))
+            .filter(|reference| reference.prefix == ShortRef::TRANSACTION)
+        {
+            return Some(EntityRef::Short(reference));
+        }
+        if let Some(forced) = forced {
+            return id_prefix(forced).map(EntityRef::Id);
+        }
+        if let Ok(index) = raw.parse::<usize>() {
+            return Some(EntityRef::Index(index));
+        }
+        id_prefix(raw).map(EntityRef::Id)
+    }
+
+    /// Position of the referenced record among `ids`. Short references are
+    /// resolved by the ledger, see [`bufy_domain::Ledger::transaction_position`].
+    pub(crate) fn position(
+        &self,
+        ids: impl IntoIterator<Item = Uuid>,
+        noun: &str,
+    ) -> Result<usize, CommandError> {
+        match self {
+            EntityRef::Index(index) => {
+                ids.into_iter().nth(*index).map(|_| *index).ok_or_else(|| {
+                    CommandError::InvalidArguments(format!("{} index out of range", noun))
+                })
+            }
+            EntityRef::Id(prefix) => {
+                let mut matches = ids
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(_, id)| id.simple().to_string().starts_with(prefix.as_str()))
+                    .map(|(position, _)| position);
+                match (matches.next(), matches.next()) {
+                    (Some(position), None) => Ok(position),
+                    (None, _) => Err(CommandError::InvalidArguments(format!(
+                        "no {} with id `{}`",
+                        noun, prefix
+                    ))),
+                    (Some(_), Some(_)) => Err(CommandError::InvalidArguments(format!(
+                        "id `{}` matches more than one {}; type more of it",
+                        prefix, noun
+                    ))),
+                }
+            }
+            EntityRef::Short(reference) => Err(CommandError::InvalidArguments(format!(
+                "`{}` is a transaction reference; {} ids look like ab12cd34",
+                reference, noun
+            ))),
+        }
+    }
+}
+
+/// The id prefix shown for a record, as accepted back by commands.
+pub(crate) fn short_id(id: Uuid) -> String {
+    let mut short = id.simple().to_string();
+    short.truncate(8);
+    short
+}
+
+/// Rewrites `--id <value>` into the positional `@<value>` form. Only the
+/// commands that read an index call it, so `--id` stays free for other
+/// commands' own flags.
+pub(crate) fn expand_id_flags<'a>(args: &[&'a str]) -> Vec<Cow<'a, str>> {
+    let mut expanded = Vec::with_capacity(args.len());
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match iter.clone().next() {
+            Some(value) if arg.eq_ignore_ascii_case("--id") => {
+                iter.next();
+                expanded.push(Cow::Owned(format!("@{}", value)));
+            }
+            _ => expanded.push(Cow::Borrowed(*arg)),
+        }
+    }
+    expanded
+}
+
+fn id_prefix(raw: &str) -> Option<String> {
+    let prefix: String = raw
+        .chars()
+        .filter(|c| *c != '-')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    (prefix.len() >= MIN_ID_PREFIX
+        && prefix.len() <= 32
+        && prefix.chars().all(|c| c.is_ascii_hexdigit()))
+    .then_some(prefix)
+}
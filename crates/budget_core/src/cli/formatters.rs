@@ -2,6 +2,7 @@ use std::sync::{Arc, RwLock};
 
 use bufy_config::Config;
 use bufy_core::{CurrencyFormatter, DateFormatter};
+use bufy_domain::locale::LocaleData;
 use chrono::NaiveDate;
 
 /// Lightweight formatter implementations backed by the active CLI configuration.
@@ -23,10 +24,9 @@ impl CliFormatters {
     }
 
     fn date_pattern(&self, locale: &str) -> &'static str {
-        match locale {
-            "en-GB" | "fr-FR" => "%d/%m/%Y",
-            _ => "%Y-%m-%d",
-        }
+        LocaleData::lookup(locale)
+            .map(|data| data.short_date_pattern)
+            .unwrap_or("%Y-%m-%d")
     }
 }
 
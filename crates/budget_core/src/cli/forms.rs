@@ -5,6 +5,7 @@
 //! framework in subsequent phases by describing their fields and leveraging the
 //! generic form engine implemented here.
 
+use std::borrow::Cow;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::Infallible;
 use std::fmt;
@@ -121,10 +122,8 @@ impl Validator {
                 .parse::<i64>()
                 .map(|v| v.to_string())
                 .map_err(|_| ValidationError::new("Enter a whole number (e.g., 42)")),
-            Validator::PositiveNumber => input
-                .trim()
-                .parse::<f64>()
-                .map_err(|_| ValidationError::new("Enter a numeric value"))
+            Validator::PositiveNumber => io::parse_number(input)
+                .ok_or_else(|| ValidationError::new("Enter a numeric value"))
                 .and_then(|v| {
                     if v > 0.0 {
                         Ok(v.to_string())
@@ -132,11 +131,9 @@ impl Validator {
                         Err(ValidationError::new("Value must be greater than zero"))
                     }
                 }),
-            Validator::Decimal => input
-                .trim()
-                .parse::<f64>()
+            Validator::Decimal => io::parse_number(input)
                 .map(|v| v.to_string())
-                .map_err(|_| ValidationError::new("Enter a numeric value")),
+                .ok_or_else(|| ValidationError::new("Enter a numeric value")),
             Validator::Date => NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d")
                 .map(|d| d.to_string())
                 .map_err(|_| ValidationError::new("Use YYYY-MM-DD format")),
@@ -164,24 +161,24 @@ impl Validator {
 /// Declarative description of a single form field.
 #[derive(Clone)]
 pub struct FieldDescriptor {
-    pub key: &'static str,
-    pub label: &'static str,
+    pub key: Cow<'static, str>,
+    pub label: Cow<'static, str>,
     pub kind: FieldKind,
     pub required: bool,
-    pub help: Option<&'static str>,
+    pub help: Option<Cow<'static, str>>,
     pub validator: Validator,
 }
 
 impl FieldDescriptor {
     pub fn new(
-        key: &'static str,
-        label: &'static str,
+        key: impl Into<Cow<'static, str>>,
+        label: impl Into<Cow<'static, str>>,
         kind: FieldKind,
         validator: Validator,
     ) -> Self {
         Self {
-            key,
-            label,
+            key: key.into(),
+            label: label.into(),
             kind,
             required: true,
             help: None,
@@ -194,8 +191,8 @@ impl FieldDescriptor {
         self
     }
 
-    pub fn with_help(mut self, help: &'static str) -> Self {
-        self.help = Some(help);
+    pub fn with_help(mut self, help: impl Into<Cow<'static, str>>) -> Self {
+        self.help = Some(help.into());
         self
     }
 }
@@ -298,10 +295,9 @@ fn make_optional_decimal_validator() -> Validator {
         if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("none") {
             Ok(String::new())
         } else {
-            trimmed
-                .parse::<f64>()
+            io::parse_number(trimmed)
                 .map(|value| value.to_string())
-                .map_err(|_| "Enter a numeric amount".into())
+                .ok_or_else(|| "Enter a numeric amount".into())
         }
     }))
 }
@@ -312,9 +308,8 @@ fn make_non_negative_decimal_validator() -> Validator {
         if trimmed.is_empty() {
             return Err("Amount is required".into());
         }
-        trimmed
-            .parse::<f64>()
-            .map_err(|_| "Enter a numeric amount".into())
+        io::parse_number(trimmed)
+            .ok_or_else(|| "Enter a numeric amount".into())
             .and_then(|value| {
                 if value < 0.0 {
                     Err("Amount must be zero or positive".into())
@@ -335,9 +330,8 @@ fn make_optional_non_negative_decimal_validator(default: Option<f64>) -> Validat
                 Ok(String::new())
             }
         } else {
-            trimmed
-                .parse::<f64>()
-                .map_err(|_| "Enter a numeric amount".into())
+            io::parse_number(trimmed)
+                .ok_or_else(|| "Enter a numeric amount".into())
                 .and_then(|value| {
                     if value < 0.0 {
                         Err("Amount must be zero or positive".into())
@@ -452,7 +446,7 @@ fn parse_optional_f64(value: &str) -> Option<f64> {
     if trimmed.is_empty() {
         None
     } else {
-        trimmed.parse::<f64>().ok()
+        io::parse_number(trimmed)
     }
 }
 
@@ -728,6 +722,7 @@ pub struct TransactionInitialData {
 enum TransactionWizardMode {
     Create {
         default_status: TransactionStatus,
+        default_date: NaiveDate,
     },
     Edit {
         initial: Box<TransactionInitialData>,
@@ -759,13 +754,17 @@ impl TransactionWizard {
         today: NaiveDate,
         min_date: NaiveDate,
         default_status: TransactionStatus,
+        default_date: NaiveDate,
     ) -> Self {
         Self::build(
             accounts,
             categories,
             today,
             min_date,
-            TransactionWizardMode::Create { default_status },
+            TransactionWizardMode::Create {
+                default_status,
+                default_date,
+            },
         )
     }
 
@@ -845,7 +844,7 @@ impl TransactionWizard {
         let mut keep_display: Option<String> = None;
 
         let default_status = match &mode {
-            TransactionWizardMode::Create { default_status } => default_status.clone(),
+            TransactionWizardMode::Create { default_status, .. } => default_status.clone(),
             TransactionWizardMode::Edit { initial } => initial.status.clone(),
         };
 
@@ -999,7 +998,8 @@ impl TransactionWizard {
         }
 
         match &mode {
-            TransactionWizardMode::Create { .. } => {
+            TransactionWizardMode::Create { default_date, .. } => {
+                defaults.insert("scheduled_date".into(), default_date.to_string());
                 defaults.insert("actual_date".into(), String::new());
                 defaults.insert("actual_amount".into(), String::new());
             }
@@ -1079,6 +1079,8 @@ impl TransactionWizard {
                 recurrence.last_completed = existing.last_completed;
                 recurrence.generated_occurrences = existing.generated_occurrences;
                 recurrence.next_scheduled = existing.next_scheduled;
+                recurrence.income = existing.income.clone();
+                recurrence.price_history = existing.price_history.clone();
             }
         }
     }
@@ -1529,6 +1531,108 @@ impl FormFlow for CategoryWizard {
     }
 }
 
+/// One category step of the [`AllocationWizard`].
+pub struct AllocationStep {
+    pub category_id: Uuid,
+    pub name: String,
+    pub current: f64,
+    pub average_spend: f64,
+}
+
+/// Walks every expense category in turn so the detected income can be split
+/// across them; the review screen shows what is left unassigned.
+pub struct AllocationWizard {
+    descriptor: FormDescriptor,
+    defaults: BTreeMap<String, String>,
+    ids: Vec<(String, Uuid)>,
+    income: f64,
+}
+
+impl AllocationWizard {
+    pub fn new(steps: Vec<AllocationStep>, income: f64) -> Self {
+        let mut fields = Vec::new();
+        let mut defaults = BTreeMap::new();
+        let mut ids = Vec::new();
+        for step in steps {
+            let key = step.category_id.to_string();
+            fields.push(
+                FieldDescriptor::new(
+                    key.clone(),
+                    step.name,
+                    FieldKind::Decimal,
+                    make_non_negative_decimal_validator(),
+                )
+                .with_help(format!(
+                    "Budgeted now: {} • spent about {} per period",
+                    format_amount(step.current),
+                    format_amount(step.average_spend)
+                )),
+            );
+            defaults.insert(key.clone(), format_amount(step.current));
+            ids.push((key, step.category_id));
+        }
+        Self {
+            descriptor: FormDescriptor::new("allocation", fields),
+            defaults,
+            ids,
+            income,
+        }
+    }
+
+    fn allocated(&self, values: &BTreeMap<String, String>) -> f64 {
+        self.ids
+            .iter()
+            .filter_map(|(key, _)| values.get(key).or_else(|| self.defaults.get(key)))
+            .filter_map(|value| io::parse_number(value))
+            .sum()
+    }
+}
+
+impl FormFlow for AllocationWizard {
+    type Output = Vec<(Uuid, f64)>;
+    type Error = Infallible;
+
+    fn descriptor(&self) -> &FormDescriptor {
+        &self.descriptor
+    }
+
+    fn defaults(&self) -> BTreeMap<String, String> {
+        self.defaults.clone()
+    }
+
+    fn review_notes(&self, values: &BTreeMap<String, String>) -> Vec<String> {
+        let allocated = self.allocated(values);
+        let remainder = self.income - allocated;
+        vec![
+            format!("  Income: {}", format_amount(self.income)),
+            format!("  Allocated: {}", format_amount(allocated)),
+            if remainder < 0.0 {
+                format!("  Over-allocated by: {}", format_amount(-remainder))
+            } else {
+                format!("  Unassigned: {}", format_amount(remainder))
+            },
+        ]
+    }
+
+    fn commit(&self, values: BTreeMap<String, String>) -> Result<Self::Output, Self::Error> {
+        Ok(self
+            .ids
+            .iter()
+            .filter_map(|(key, id)| {
+                values
+                    .get(key)
+                    .or_else(|| self.defaults.get(key))
+                    .and_then(|value| io::parse_number(value))
+                    .map(|amount| (*id, amount))
+            })
+            .collect())
+    }
+
+    fn cancel(&self) -> Self::Error {
+        unreachable!()
+    }
+}
+
 /// Snapshot of collected data displayed before final confirmation.
 #[derive(Default)]
 pub struct FormSummary {
@@ -1554,6 +1658,12 @@ pub trait FormInteraction {
 /// prompt components for consistent UX.
 pub struct WizardInteraction;
 
+impl Default for WizardInteraction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl WizardInteraction {
     pub fn new() -> Self {
         Self
@@ -1561,7 +1671,7 @@ impl WizardInteraction {
 
     fn prompt_text(&mut self, context: &PromptContext<'_>) -> PromptResponse {
         self.print_step_header(context);
-        match text_input(context.descriptor.label, context.default) {
+        match text_input(&context.descriptor.label, context.default) {
             Ok(TextPromptResult::Value(value)) => PromptResponse::Value(value),
             Ok(TextPromptResult::Keep) => PromptResponse::Keep,
             Ok(TextPromptResult::Back) => PromptResponse::Back,
@@ -1579,7 +1689,7 @@ impl WizardInteraction {
 
     fn prompt_choice(&mut self, context: &PromptContext<'_>, options: &[String]) -> PromptResponse {
         let mut lines = self.choice_context_lines(context);
-        if let Some(help) = context.descriptor.help {
+        if let Some(help) = &context.descriptor.help {
             lines.push(help.to_string());
         }
         let title = self.step_title(context);
@@ -1610,7 +1720,7 @@ impl WizardInteraction {
             }
         });
         let mut lines = self.choice_context_lines(context);
-        if let Some(help) = context.descriptor.help {
+        if let Some(help) = &context.descriptor.help {
             lines.push(help.to_string());
         }
         let title = self.step_title(context);
@@ -1731,9 +1841,9 @@ impl<'a> FormSession<'a> {
                 descriptor: field,
                 default: self
                     .values
-                    .get(field.key)
+                    .get(field.key.as_ref())
                     .cloned()
-                    .or_else(|| self.defaults.get(field.key).cloned()),
+                    .or_else(|| self.defaults.get(field.key.as_ref()).cloned()),
                 index: self.index,
                 total: self.descriptor.fields.len(),
             })
@@ -1766,7 +1876,7 @@ impl<'a> FormSession<'a> {
                 }
             }
             PromptResponse::Help => {
-                if let Some(help) = field.help {
+                if let Some(help) = &field.help {
                     io::print_info(help);
                 } else {
                     io::print_info("No additional information available for this field.");
@@ -1774,7 +1884,7 @@ impl<'a> FormSession<'a> {
                 Ok(FormSessionEvent::Repeat)
             }
             PromptResponse::Keep => {
-                if let Some(existing) = self.values.get(field.key) {
+                if let Some(existing) = self.values.get(field.key.as_ref()) {
                     self.values.insert(field.key.to_string(), existing.clone());
                     self.index += 1;
                     Ok(FormSessionEvent::Moved)
@@ -1782,7 +1892,7 @@ impl<'a> FormSession<'a> {
                     io::print_warning("This field is required.");
                     Ok(FormSessionEvent::Repeat)
                 } else {
-                    self.values.remove(field.key);
+                    self.values.remove(field.key.as_ref());
                     self.index += 1;
                     Ok(FormSessionEvent::Moved)
                 }
@@ -1898,6 +2008,11 @@ pub trait FormFlow {
         BTreeMap::new()
     }
 
+    /// Extra lines shown under the entries on the review screen.
+    fn review_notes(&self, _values: &BTreeMap<String, String>) -> Vec<String> {
+        Vec::new()
+    }
+
     /// Builds the concrete output after successful completion.
     fn commit(&self, values: BTreeMap<String, String>) -> Result<Self::Output, Self::Error>;
 
@@ -1931,7 +2046,12 @@ impl<'a, F: FormFlow> FormEngine<'a, F> {
 
             if session.is_complete() {
                 let summary = build_summary(descriptor, session.values());
-                let summary_lines = format_summary_lines(&summary);
+                let mut summary_lines = format_summary_lines(&summary);
+                let notes = self.flow.review_notes(session.values());
+                if !notes.is_empty() {
+                    summary_lines.push(String::new());
+                    summary_lines.extend(notes);
+                }
                 match interaction.confirm(&summary, &summary_lines) {
                     ConfirmationResponse::Confirm => {
                         session.mark_complete();
@@ -2006,7 +2126,7 @@ fn render_prompt(context: &PromptContext<'_>) {
     if let Some(default_value) = context.default {
         formatter.print_detail(format!("Default: {}", default_value));
     }
-    if let Some(help) = context.descriptor.help {
+    if let Some(help) = &context.descriptor.help {
         formatter.print_detail(help);
     }
     let mut instructions = vec!["Type a value and press Enter to continue.".to_string()];
@@ -2034,7 +2154,7 @@ fn format_summary_lines(summary: &FormSummary) -> Vec<String> {
 fn build_summary(descriptor: &FormDescriptor, values: &BTreeMap<String, String>) -> FormSummary {
     let mut summary = FormSummary::default();
     for field in &descriptor.fields {
-        if let Some(value) = values.get(field.key) {
+        if let Some(value) = values.get(field.key.as_ref()) {
             summary
                 .entries
                 .push((field.label.to_string(), value.to_string()));
@@ -2274,6 +2394,7 @@ mod tests {
             today,
             min_date,
             TransactionStatus::Planned,
+            today,
         );
 
         let prompts = vec![
@@ -2320,6 +2441,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn transaction_wizard_create_starts_from_configured_defaults() {
+        let from_id = Uuid::new_v4();
+        let to_id = Uuid::new_v4();
+        let accounts = vec![("From".to_string(), from_id), ("To".to_string(), to_id)];
+        let last_used = NaiveDate::from_ymd_opt(2024, 2, 10).unwrap();
+        let wizard = TransactionWizard::new_create(
+            accounts,
+            Vec::new(),
+            NaiveDate::from_ymd_opt(2024, 2, 14).unwrap(),
+            NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(),
+            TransactionStatus::Completed,
+            last_used,
+        );
+
+        let prompts = vec![
+            PromptResponse::Keep,              // from account
+            PromptResponse::Value("2".into()), // to account
+            PromptResponse::Keep,              // category none
+            PromptResponse::Keep,              // scheduled date
+            PromptResponse::Keep,              // actual date empty
+            PromptResponse::Value("12.5".into()),
+            PromptResponse::Keep, // actual amount blank
+            PromptResponse::Keep, // recurrence none
+            PromptResponse::Keep, // every N days
+            PromptResponse::Keep, // status
+            PromptResponse::Keep, // notes
+        ];
+        let mut interaction = MockInteraction::new(prompts, vec![ConfirmationResponse::Confirm]);
+
+        match FormEngine::new(&wizard).run(&mut interaction).unwrap() {
+            FormResult::Completed(data) => {
+                assert_eq!(data.scheduled_date, last_used);
+                assert_eq!(data.status, TransactionStatus::Completed);
+                assert!(data.actual_date.is_none());
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
     #[test]
     fn transaction_wizard_edit_keep_existing() {
         let from_id = Uuid::new_v4();
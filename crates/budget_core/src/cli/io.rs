@@ -2,12 +2,16 @@ use std::{
     fmt::Display,
     io::{self, Write},
     ops::Deref,
-    sync::{OnceLock, RwLock, RwLockReadGuard},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, OnceLock, RwLock, RwLockReadGuard,
+    },
+    time::{Duration, Instant},
 };
 
 use dialoguer::{
     theme::{ColorfulTheme, SimpleTheme, Theme},
-    Confirm, Select,
+    Confirm, MultiSelect, Password, Select,
 };
 
 use crate::{
@@ -20,9 +24,13 @@ use crate::{
     },
     config::{Config, Theme as UiTheme},
 };
+use bufy_domain::currency::{self, LocaleConfig};
 
 static THEME: OnceLock<RwLock<Box<dyn Theme + Send + Sync>>> = OnceLock::new();
 static LOCALE: OnceLock<RwLock<String>> = OnceLock::new();
+static INPUT_LOCALE: OnceLock<RwLock<LocaleConfig>> = OnceLock::new();
+static CAPTURE: Mutex<Option<String>> = Mutex::new(None);
+static RENDER_NANOS: AtomicU64 = AtomicU64::new(0);
 
 fn theme_lock() -> &'static RwLock<Box<dyn Theme + Send + Sync>> {
     THEME.get_or_init(|| RwLock::new(Box::new(ColorfulTheme::default())))
@@ -32,6 +40,10 @@ fn locale_lock() -> &'static RwLock<String> {
     LOCALE.get_or_init(|| RwLock::new(String::from("en-US")))
 }
 
+fn input_locale_lock() -> &'static RwLock<LocaleConfig> {
+    INPUT_LOCALE.get_or_init(|| RwLock::new(LocaleConfig::default()))
+}
+
 fn theme_guard() -> RwLockReadGuard<'static, Box<dyn Theme + Send + Sync>> {
     theme_lock().read().expect("io theme lock poisoned")
 }
@@ -58,6 +70,7 @@ pub fn apply_config(config: &Config) {
             .expect("locale lock poisoned for write");
         *guard = config.locale.clone();
     }
+    set_input_locale(LocaleConfig::for_tag(&config.locale).unwrap_or_default());
 
     output::set_preferences(OutputPreferences {
         plain_mode: plain,
@@ -70,6 +83,23 @@ pub fn apply_config(config: &Config) {
     refresh_style();
 }
 
+/// Set the separators used to read numbers typed at prompts and in command
+/// arguments, normally those of the loaded ledger.
+pub fn set_input_locale(locale: LocaleConfig) {
+    *input_locale_lock()
+        .write()
+        .expect("input locale lock poisoned for write") = locale;
+}
+
+/// Parse a user-entered number with the active input locale's separators.
+/// Plain `1234.56` is accepted under every locale.
+pub fn parse_number(raw: &str) -> Option<f64> {
+    let locale = input_locale_lock()
+        .read()
+        .expect("input locale lock poisoned");
+    currency::parse_number(&locale, raw)
+}
+
 fn guard_to_theme<'a>(
     guard: &'a RwLockReadGuard<'static, Box<dyn Theme + Send + Sync>>,
 ) -> &'a dyn Theme {
@@ -79,6 +109,7 @@ fn guard_to_theme<'a>(
 /// Prompt the user for free-form text input with an optional default.
 /// Returns `Ok(None)` when the user cancels with ESC/back/cancel controls.
 pub fn prompt_text(label: &str, default: Option<&str>) -> Result<Option<String>, CliError> {
+    release_capture();
     let formatter = Formatter::new();
     formatter.print_detail(format!("{label}:"));
     if let Some(value) = default {
@@ -104,6 +135,18 @@ pub fn prompt_text(label: &str, default: Option<&str>) -> Result<Option<String>,
     }
 }
 
+/// Prompt for a secret without echoing it, asking twice to confirm.
+pub fn prompt_password(label: &str) -> Result<String, CliError> {
+    release_capture();
+    let guard = theme_guard();
+    let theme = guard_to_theme(&guard);
+    Password::with_theme(theme)
+        .with_prompt(label)
+        .with_confirmation("Repeat to confirm", "Entries do not match.")
+        .interact()
+        .map_err(|err| CliError::Input(err.to_string()))
+}
+
 /// Prompt the user to choose a value from the provided options, returning the index.
 pub fn prompt_select_index<T>(label: &str, options: &[T]) -> Result<usize, CliError>
 where
@@ -112,6 +155,7 @@ where
     if options.is_empty() {
         return Err(CliError::Input("no options available".into()));
     }
+    release_capture();
     let guard = theme_guard();
     let theme = guard_to_theme(&guard);
     Select::with_theme(theme)
@@ -131,8 +175,30 @@ where
     Ok(options[index].clone())
 }
 
+/// Prompt the user to tick any number of options, starting from `checked`.
+/// Returns the ticked indexes, or `None` when cancelled.
+pub fn prompt_multi_select<T>(
+    label: &str,
+    options: &[T],
+    checked: &[bool],
+) -> Result<Option<Vec<usize>>, CliError>
+where
+    T: Display,
+{
+    release_capture();
+    let guard = theme_guard();
+    let theme = guard_to_theme(&guard);
+    MultiSelect::with_theme(theme)
+        .with_prompt(label)
+        .items(options)
+        .defaults(checked)
+        .interact_opt()
+        .map_err(|err| CliError::Input(err.to_string()))
+}
+
 /// Prompt the user for confirmation (yes/no).
 pub fn confirm_action(label: &str) -> Result<bool, CliError> {
+    release_capture();
     let guard = theme_guard();
     let theme = guard_to_theme(&guard);
     Confirm::with_theme(theme)
@@ -166,8 +232,51 @@ pub fn write_line<W: Write>(mut out: W, text: &str) -> io::Result<()> {
 }
 
 pub fn println_text(text: &str) -> io::Result<()> {
-    let mut out = io::stdout();
-    write_line(&mut out, text)
+    if output::is_json() {
+        output::json::text(text);
+        return Ok(());
+    }
+    let started = Instant::now();
+    let result = match CAPTURE.lock().expect("capture lock poisoned").as_mut() {
+        Some(buffer) => {
+            buffer.push_str(text);
+            buffer.push('\n');
+            Ok(())
+        }
+        None => write_line(io::stdout(), text),
+    };
+    let elapsed = u64::try_from(started.elapsed().as_nanos()).unwrap_or(u64::MAX);
+    RENDER_NANOS.fetch_add(elapsed, Ordering::Relaxed);
+    result
+}
+
+/// Time spent in [`println_text`] since the previous call, which resets it.
+pub fn take_render_time() -> Duration {
+    Duration::from_nanos(RENDER_NANOS.swap(0, Ordering::Relaxed))
+}
+
+/// Collect text printed through [`println_text`] instead of writing it, so
+/// the caller can decide how to show it once the command finishes.
+pub fn begin_capture() {
+    *CAPTURE.lock().expect("capture lock poisoned") = Some(String::new());
+}
+
+/// Stop collecting and hand back everything printed since [`begin_capture`].
+pub fn end_capture() -> Option<String> {
+    CAPTURE.lock().expect("capture lock poisoned").take()
+}
+
+/// Write out anything captured so far and stop collecting. Prompts call this
+/// before reading input so the text leading up to them stays visible.
+pub fn release_capture() {
+    if let Some(text) = end_capture() {
+        let mut out = io::stdout();
+        for line in text.lines() {
+            let _ = out.write_all(line.as_bytes());
+            let _ = out.write_all(b"\r\n");
+        }
+        let _ = out.flush();
+    }
 }
 
 pub fn print_success(message: impl Display) {
@@ -12,7 +12,8 @@ pub fn show(context: &ShellContext) -> Result<Option<String>, MenuError> {
     let state = MenuContextState::capture(context);
     let menu = MenuUI::new("Main menu", main_menu_items(&state))
         .with_context(banner)
-        .with_footer_hint(MAIN_MENU_HINT);
+        .with_footer_hint(MAIN_MENU_HINT)
+        .with_shortcut('p', "palette");
     renderer.show(&menu)
 }
 
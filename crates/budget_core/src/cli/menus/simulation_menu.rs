@@ -21,6 +21,8 @@ fn menu_items(state: &MenuContextState) -> Vec<MenuUIItem> {
             .with_enabled(state.has_active_simulation),
         MenuUIItem::new("apply", "apply", "Apply simulation changes")
             .with_enabled(state.has_pending_simulations),
+        MenuUIItem::new("rollback", "rollback", "Undo an applied simulation")
+            .with_enabled(state.has_simulations),
         MenuUIItem::new("discard", "discard", "Discard a simulation")
             .with_enabled(state.has_pending_simulations),
         MenuUIItem::new("list", "list", "List simulations").with_enabled(state.has_loaded_ledger),
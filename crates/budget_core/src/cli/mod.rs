@@ -1,17 +1,20 @@
 pub mod commands;
 pub mod core;
+pub mod entity_ref;
 pub mod formatters;
 pub mod forms;
 pub mod help;
 pub mod io;
 pub mod menus;
 pub mod output;
+pub mod pager;
 pub mod registry;
 pub mod selection;
 pub mod selectors;
 pub mod shell;
 pub mod shell_context;
+pub mod shutdown;
 pub mod system_clock;
 pub mod ui;
 
-pub use shell::run_cli;
+pub use shell::{run_cli, run_cli_with, CliOptions};
@@ -0,0 +1,213 @@
+//! `--output json`: everything a command prints is collected instead of
+//! written, then emitted as one JSON object per command on its own line.
+//!
+//! Messages keep their level, tables become rows keyed by column header,
+//! and detail views become objects. Any other line a command prints lands
+//! in `text`, so nothing is lost.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::cli::io::write_line;
+
+static RECORD: Mutex<Option<CommandRecord>> = Mutex::new(None);
+
+/// Everything one command printed.
+#[derive(Debug, Default, Serialize)]
+pub struct CommandRecord {
+    /// The command line as typed; absent for output printed between commands.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    pub status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub messages: Vec<JsonMessage>,
+    pub tables: Vec<JsonTable>,
+    pub details: Vec<JsonDetail>,
+    pub text: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonMessage {
+    pub level: &'static str,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonTable {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub columns: Vec<String>,
+    pub rows: Vec<Map<String, Value>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonDetail {
+    pub title: String,
+    pub fields: Map<String, Value>,
+}
+
+/// Starts collecting output for `command`, emitting anything collected
+/// since the last command first.
+pub fn begin(command: &str) {
+    flush();
+    *lock() = Some(CommandRecord {
+        command: Some(command.trim().to_string()),
+        ..CommandRecord::default()
+    });
+}
+
+/// Emits the current command's record, marked failed when `error` is set.
+pub fn finish(error: Option<String>) {
+    if let Some(record) = lock().as_mut() {
+        if error.is_some() {
+            record.error = error;
+        }
+    }
+    flush();
+}
+
+/// Marks the current command failed even though it returned normally, as
+/// for an unknown command.
+pub fn fail(error: impl Into<String>) {
+    with_record(|record| record.error = Some(error.into()));
+}
+
+/// Emits whatever was collected, if anything.
+pub fn flush() {
+    let Some(mut record) = lock().take() else {
+        return;
+    };
+    record.status = if record.error.is_some() {
+        "error"
+    } else {
+        "ok"
+    };
+    if let Ok(line) = serde_json::to_string(&record) {
+        let _ = write_line(std::io::stdout(), &line);
+    }
+}
+
+pub fn message(level: &'static str, text: impl ToString) {
+    let text = strip_ansi(&text.to_string()).trim().to_string();
+    if text.is_empty() {
+        return;
+    }
+    with_record(|record| record.messages.push(JsonMessage { level, text }));
+}
+
+/// Records a table; cells are matched to `columns` by position.
+pub fn table(title: Option<&str>, columns: &[String], rows: &[Vec<String>]) {
+    let columns: Vec<String> = columns.iter().map(|column| strip_ansi(column)).collect();
+    let rows = rows
+        .iter()
+        .map(|cells| {
+            columns
+                .iter()
+                .zip(
+                    cells
+                        .iter()
+                        .map(String::as_str)
+                        .chain(std::iter::repeat("")),
+                )
+                .map(|(column, cell)| (column.clone(), Value::String(strip_ansi(cell))))
+                .collect()
+        })
+        .collect();
+    let table = JsonTable {
+        title: title.map(strip_ansi),
+        columns,
+        rows,
+    };
+    with_record(|record| record.tables.push(table));
+}
+
+/// Records a detail view. Values that are already JSON stay typed; others
+/// become strings.
+pub fn detail<'a>(title: &str, fields: impl IntoIterator<Item = (&'a str, &'a str)>) {
+    let fields = fields
+        .into_iter()
+        .map(|(label, value)| {
+            let value = strip_ansi(value);
+            let parsed = serde_json::from_str(&value).unwrap_or(Value::String(value));
+            (label.to_string(), parsed)
+        })
+        .collect();
+    let detail = JsonDetail {
+        title: strip_ansi(title),
+        fields,
+    };
+    with_record(|record| record.details.push(detail));
+}
+
+/// Records a line printed outside the structured helpers.
+pub fn text(line: &str) {
+    let lines: Vec<String> = strip_ansi(line)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.trim_end().to_string())
+        .collect();
+    if !lines.is_empty() {
+        with_record(|record| record.text.extend(lines));
+    }
+}
+
+fn with_record(update: impl FnOnce(&mut CommandRecord)) {
+    let mut guard = lock();
+    update(guard.get_or_insert_with(CommandRecord::default));
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Option<CommandRecord>> {
+    RECORD
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Drops ANSI escape sequences such as colours.
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\u{1b}' {
+            out.push(ch);
+            continue;
+        }
+        if chars.next_if_eq(&'[').is_some() {
+            for next in chars.by_ref() {
+                if ('\u{40}'..='\u{7e}').contains(&next) {
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_colours_and_keys_rows_by_header() {
+        assert_eq!(strip_ansi("\u{1b}[32m✔ Saved\u{1b}[0m"), "✔ Saved");
+        *lock() = Some(CommandRecord::default());
+        table(
+            Some("Accounts"),
+            &["NAME".to_string(), "BALANCE".to_string()],
+            &[vec!["Checking".to_string()]],
+        );
+        detail("Account", [("name", "\"Checking\""), ("kind", "Bank")]);
+        let record = lock().take().unwrap();
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(
+            json["tables"][0]["rows"][0],
+            serde_json::json!({ "NAME": "Checking", "BALANCE": "" })
+        );
+        assert_eq!(
+            json["details"][0]["fields"],
+            serde_json::json!({ "name": "Checking", "kind": "Bank" })
+        );
+    }
+}
@@ -1,4 +1,7 @@
+pub mod json;
+
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{OnceLock, RwLock};
 
 use colored::Colorize;
@@ -6,6 +9,36 @@ use colored::Colorize;
 use crate::cli::io::println_text;
 use crate::cli::ui::style::refresh_style;
 
+/// How commands report back: styled text for people, or one JSON object
+/// per command for scripts (see [`json`]).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl OutputFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "text" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_format(format: OutputFormat) {
+    JSON_OUTPUT.store(format == OutputFormat::Json, Ordering::Relaxed);
+}
+
+/// Whether output is being collected for [`json`] instead of printed.
+pub fn is_json() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
 /// Message categories used by the CLI output helpers.
 #[allow(dead_code)]
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -138,6 +171,12 @@ fn apply_style(kind: MessageKind, message: impl fmt::Display, prefs: &OutputPref
 }
 
 pub fn print(kind: MessageKind, message: impl fmt::Display) {
+    if is_json() {
+        if let Some(level) = json_level(kind) {
+            json::message(level, message);
+        }
+        return;
+    }
     let prefs = preferences();
     if should_skip(kind, &prefs) {
         return;
@@ -157,6 +196,19 @@ pub fn print(kind: MessageKind, message: impl fmt::Display) {
     }
 }
 
+fn json_level(kind: MessageKind) -> Option<&'static str> {
+    match kind {
+        MessageKind::Info => Some("info"),
+        MessageKind::Hint => Some("hint"),
+        MessageKind::Success => Some("success"),
+        MessageKind::Warning => Some("warning"),
+        MessageKind::Error => Some("error"),
+        MessageKind::Prompt => Some("prompt"),
+        MessageKind::Section => Some("heading"),
+        MessageKind::Separator => None,
+    }
+}
+
 #[allow(dead_code)]
 pub fn info(message: impl fmt::Display) {
     print(MessageKind::Info, message);
@@ -285,6 +337,11 @@ pub fn render_table(headers: &[&str], rows: &[Vec<String>]) {
     if headers.is_empty() {
         return;
     }
+    if is_json() {
+        let columns: Vec<String> = headers.iter().map(|header| header.to_string()).collect();
+        json::table(None, &columns, rows);
+        return;
+    }
     let prefs = preferences();
     let mut widths: Vec<usize> = headers.iter().map(|header| char_width(header)).collect();
     for row in rows {
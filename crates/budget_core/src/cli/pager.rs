@@ -0,0 +1,104 @@
+//! Shows command output taller than the terminal through `$PAGER`.
+//!
+//! Output is captured at [`io::println_text`](crate::cli::io::println_text)
+//! while a command runs and only reaches the pager once the command returns.
+//! Prompts release the capture early, so interactive commands behave as if
+//! paging were off.
+
+use std::{
+    env,
+    io::{self, IsTerminal, Write},
+    process::{Command, Stdio},
+};
+
+use crossterm::terminal;
+
+use crate::cli::io as cli_io;
+
+const DEFAULT_PAGER: &str = "less";
+/// Quit when the text fits, keep colours, and leave the text on screen.
+const DEFAULT_LESS_FLAGS: &str = "FRX";
+
+/// Runs `command`, sending whatever it prints through the pager when the
+/// text would not fit on screen. Does nothing special unless `enabled` is
+/// set and stdout is a terminal.
+pub fn run_paged<T>(enabled: bool, command: impl FnOnce() -> T) -> T {
+    if !enabled || !io::stdout().is_terminal() {
+        return command();
+    }
+    cli_io::begin_capture();
+    let result = command();
+    if let Some(text) = cli_io::end_capture() {
+        show(&text);
+    }
+    result
+}
+
+fn show(text: &str) {
+    let rows = terminal::size().map(|(_, rows)| rows as usize).ok();
+    let paged = needs_pager(text.lines().count(), rows)
+        && pager_command(env::var("PAGER").ok().as_deref())
+            .map(|argv| page(&argv, text))
+            .unwrap_or(false);
+    if !paged {
+        for line in text.lines() {
+            let _ = cli_io::println_text(line);
+        }
+    }
+}
+
+/// One row stays free for the shell prompt that follows the output.
+fn needs_pager(lines: usize, terminal_rows: Option<usize>) -> bool {
+    terminal_rows.is_some_and(|rows| lines >= rows)
+}
+
+/// Splits `$PAGER` into a program and its arguments. An unset variable means
+/// `less`; a blank one turns paging off.
+fn pager_command(value: Option<&str>) -> Option<Vec<String>> {
+    match value {
+        None => Some(vec![DEFAULT_PAGER.to_string()]),
+        Some(raw) => shell_words::split(raw).ok().filter(|argv| !argv.is_empty()),
+    }
+}
+
+/// Returns `false` when the pager could not be started, leaving the caller to
+/// print the text itself.
+fn page(argv: &[String], text: &str) -> bool {
+    let mut command = Command::new(&argv[0]);
+    command.args(&argv[1..]).stdin(Stdio::piped());
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", DEFAULT_LESS_FLAGS);
+    }
+    let Ok(mut child) = command.spawn() else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // The reader may quit before reading everything.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let _ = child.wait();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_only_when_output_fills_the_terminal() {
+        assert!(!needs_pager(23, Some(24)));
+        assert!(needs_pager(24, Some(24)));
+        assert!(!needs_pager(500, None));
+    }
+
+    #[test]
+    fn pager_command_defaults_to_less_and_blank_disables() {
+        assert_eq!(pager_command(None), Some(vec!["less".to_string()]));
+        assert_eq!(
+            pager_command(Some("less -S")),
+            Some(vec!["less".to_string(), "-S".to_string()])
+        );
+        assert_eq!(pager_command(Some("  ")), None);
+        assert_eq!(pager_command(Some("less 'unterminated")), None);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::cli::io::release_capture;
 use crate::cli::selectors::{SelectionItem, SelectionOutcome, SelectionProvider};
 use crate::cli::ui::formatting::Formatter;
 use dialoguer::{theme::ColorfulTheme, Select};
@@ -66,6 +67,7 @@ where
         theme: &ColorfulTheme,
     ) -> Result<SelectionOutcome<P::Id>, SelectionError<P::Error>> {
         self.choose_with(prompt, empty_message, |prompt, labels| {
+            release_capture();
             Select::with_theme(theme)
                 .with_prompt(prompt)
                 .items(labels)
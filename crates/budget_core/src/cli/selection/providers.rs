@@ -6,7 +6,9 @@ use crate::{
     config::ConfigManager,
     ledger::{Account, Category, Ledger, Simulation, Transaction},
 };
-use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use bufy_storage_json::parse_backup_timestamp;
+use chrono::Local;
+use uuid::Uuid;
 
 #[derive(Debug)]
 pub enum ProviderError {
@@ -31,20 +33,13 @@ impl<'a> AccountSelectionProvider<'a> {
 }
 
 impl<'a> SelectionProvider for AccountSelectionProvider<'a> {
-    type Id = usize;
+    type Id = Uuid;
     type Error = ProviderError;
 
     fn items(&mut self) -> Result<Vec<SelectionItem<Self::Id>>, Self::Error> {
         let manager = self.context.manager();
         manager
-            .with_current(|ledger| {
-                ledger
-                    .accounts
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, account)| account_item(idx, account))
-                    .collect()
-            })
+            .with_current(|ledger| ledger.accounts.iter().map(account_item).collect())
             .map_err(|_| ProviderError::MissingLedger)
     }
 }
@@ -60,7 +55,7 @@ impl<'a> CategorySelectionProvider<'a> {
 }
 
 impl<'a> SelectionProvider for CategorySelectionProvider<'a> {
-    type Id = usize;
+    type Id = Uuid;
     type Error = ProviderError;
 
     fn items(&mut self) -> Result<Vec<SelectionItem<Self::Id>>, Self::Error> {
@@ -70,8 +65,8 @@ impl<'a> SelectionProvider for CategorySelectionProvider<'a> {
                 ledger
                     .categories
                     .iter()
-                    .enumerate()
-                    .map(|(idx, category)| category_item(idx, category))
+                    .filter(|category| !category.hidden)
+                    .map(category_item)
                     .collect()
             })
             .map_err(|_| ProviderError::MissingLedger)
@@ -89,7 +84,7 @@ impl<'a> TransactionSelectionProvider<'a> {
 }
 
 impl<'a> SelectionProvider for TransactionSelectionProvider<'a> {
-    type Id = usize;
+    type Id = Uuid;
     type Error = ProviderError;
 
     fn items(&mut self) -> Result<Vec<SelectionItem<Self::Id>>, Self::Error> {
@@ -189,27 +184,33 @@ impl SelectionProvider for ConfigBackupSelectionProvider {
     }
 }
 
-fn account_item(index: usize, account: &Account) -> SelectionItem<usize> {
+fn account_item(account: &Account) -> SelectionItem<Uuid> {
     let mut subtitle = format!("{:?}", account.kind);
-    if let Some(balance) = account.opening_balance {
-        subtitle.push_str(&format!(" • balance {:.2}", balance));
+    if let Some(balance) = &account.opening_balance {
+        subtitle.push_str(&format!(" • balance {:.2}", balance.to_major()));
     }
-    SelectionItem::new(index, account.name.clone()).with_subtitle(subtitle)
+    if account.favorite {
+        subtitle.push_str(" • ★ favorite");
+    }
+    SelectionItem::new(account.id, account.name.clone()).with_subtitle(subtitle)
 }
 
-fn category_item(index: usize, category: &Category) -> SelectionItem<usize> {
+fn category_item(category: &Category) -> SelectionItem<Uuid> {
     let mut subtitle = format!("{:?}", category.kind);
     if category.budget.is_some() {
         subtitle.push_str(" • budget set");
     }
-    let mut item = SelectionItem::new(index, category.name.clone()).with_subtitle(subtitle);
+    if category.favorite {
+        subtitle.push_str(" • ★ favorite");
+    }
+    let mut item = SelectionItem::new(category.id, category.name.clone()).with_subtitle(subtitle);
     if let Some(parent) = category.parent_id {
         item = item.with_category(format!("parent: {}", parent));
     }
     item
 }
 
-fn transaction_item(index: usize, txn: &Transaction, ledger: &Ledger) -> SelectionItem<usize> {
+fn transaction_item(index: usize, txn: &Transaction, ledger: &Ledger) -> SelectionItem<Uuid> {
     let from = ledger
         .account(txn.from_account)
         .map(|acct| acct.name.as_str())
@@ -223,13 +224,17 @@ fn transaction_item(index: usize, txn: &Transaction, ledger: &Ledger) -> Selecti
         .and_then(|id| ledger.category(id))
         .map(|cat| cat.name.as_str())
         .unwrap_or("Uncategorized");
-    let amount = txn.actual_amount.unwrap_or(txn.budgeted_amount);
+    let amount = txn.amount().to_major();
     let status = format!("{:?}", txn.status);
+    let reference = txn
+        .short_ref()
+        .map(|reference| format!("{} ", reference))
+        .unwrap_or_default();
     let label = format!(
-        "[{:>3}] {} | {} -> {} | {:.2} | {} | {}",
-        index, txn.scheduled_date, from, to, amount, category, status
+        "[{:>3}] {}{} | {} -> {} | {:.2} | {} | {}",
+        index, reference, txn.scheduled_date, from, to, amount, category, status
     );
-    SelectionItem::new(index, label)
+    SelectionItem::new(txn.id, label)
 }
 
 fn simulation_item(sim: &Simulation) -> SelectionItem<String> {
@@ -246,35 +251,12 @@ fn simulation_item(sim: &Simulation) -> SelectionItem<String> {
 }
 
 fn backup_label(name: &str) -> String {
-    let trimmed = trim_backup_extension(name);
-    let segments: Vec<&str> = trimmed.split('_').collect();
-    if segments.len() < 3 {
-        return name.to_string();
-    }
-    let date_part = segments[segments.len() - 2];
-    let time_part = segments[segments.len() - 1];
-    if date_part.len() == 8
-        && time_part.len() == 4
-        && date_part.chars().all(|c| c.is_ascii_digit())
-        && time_part.chars().all(|c| c.is_ascii_digit())
-    {
-        if let Ok(naive) =
-            NaiveDateTime::parse_from_str(&format!("{}{}", date_part, time_part), "%Y%m%d%H%M")
-        {
-            let utc = DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc);
-            let local = utc.with_timezone(&Local);
-            return format!("{:<30} (Created: {})", name, local.format("%Y-%m-%d %H:%M"));
-        }
-    }
-    name.to_string()
-}
-
-fn trim_backup_extension(name: &str) -> &str {
-    if let Some(stripped) = name.strip_suffix(".bbfy") {
-        stripped
-    } else if let Some(stripped) = name.strip_suffix(".json") {
-        stripped
-    } else {
-        name
+    match parse_backup_timestamp(name) {
+        Some(utc) => format!(
+            "{:<30} (Created: {})",
+            name,
+            utc.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S")
+        ),
+        None => name.to_string(),
     }
 }
@@ -2,20 +2,135 @@ use shell_words::split;
 use std::{
     fmt,
     io::{self, BufRead},
+    path::PathBuf,
+    time::{Duration, Instant},
 };
 
 use crate::cli::core::{CliError, CliMode, CommandError, LoopControl, ShellContext};
+use crate::cli::io as cli_io;
 use crate::cli::menus::{main_menu, MenuError};
+use crate::cli::output::{self, json, OutputFormat};
+use crate::cli::pager;
+use crate::cli::shutdown;
+use crate::cli::ui::banner::Banner;
 use crate::cli::ui::formatting::Formatter;
+use crate::cli::ui::test_mode;
+use crate::core::utils::{PathResolver, PathStrategy};
+use crate::utils::LogSettings;
+
+const CLI_USAGE: &str = "usage: budget_core_cli [--verbose] [--portable [DIR]] [--log-file FILE] [--log-sensitive] [--output text|json]";
+
+/// Environment variable that selects the output format when `--output` is
+/// not given.
+pub const OUTPUT_ENV: &str = "BUFY_OUTPUT";
+
+/// Flags accepted on the `budget_core_cli` command line.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CliOptions {
+    /// Print parse, service, and render timings plus tracing spans for each
+    /// command to stderr.
+    pub verbose: bool,
+    /// Keep all data in this folder instead of the home directory. `--portable`
+    /// without a folder uses the executable's own.
+    pub portable: Option<PathBuf>,
+    /// Append logs, including every service call with its duration, to
+    /// this file instead of stderr.
+    pub log_file: Option<PathBuf>,
+    /// Write amounts and notes to the logs instead of `[redacted]`.
+    pub log_sensitive: bool,
+    /// `--output json` reads commands from stdin without prompting and
+    /// prints one JSON object per command. `None` defers to [`OUTPUT_ENV`].
+    pub output: Option<OutputFormat>,
+}
+
+impl CliOptions {
+    pub fn from_args<I>(args: I) -> Result<Self, CliError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut options = Self::default();
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            match arg.as_ref() {
+                "--verbose" | "-v" => options.verbose = true,
+                "--portable" => {
+                    let dir = match args.next_if(|next| !next.as_ref().starts_with('-')) {
+                        Some(dir) => PathBuf::from(dir.as_ref()),
+                        None => PathResolver::executable_dir().ok_or_else(|| {
+                            CliError::Input(
+                                "cannot locate the executable's folder; pass one to --portable"
+                                    .into(),
+                            )
+                        })?,
+                    };
+                    options.portable = Some(dir);
+                }
+                "--log-file" => {
+                    let file = args
+                        .next_if(|next| !next.as_ref().starts_with('-'))
+                        .ok_or_else(|| CliError::Input("--log-file requires a file".into()))?;
+                    options.log_file = Some(PathBuf::from(file.as_ref()));
+                }
+                "--log-sensitive" => options.log_sensitive = true,
+                "--output" => {
+                    let value = args.next().ok_or_else(|| {
+                        CliError::Input("--output requires `text` or `json`".into())
+                    })?;
+                    options.output = Some(parse_output(value.as_ref())?);
+                }
+                other => {
+                    return Err(CliError::Input(format!(
+                        "unknown option `{}`. {}",
+                        other, CLI_USAGE
+                    )))
+                }
+            }
+        }
+        Ok(options)
+    }
+
+    /// The `--output` format, else [`OUTPUT_ENV`], else text.
+    pub fn output_format(&self) -> Result<OutputFormat, CliError> {
+        match (self.output, std::env::var(OUTPUT_ENV)) {
+            (Some(format), _) => Ok(format),
+            (None, Ok(value)) if !value.trim().is_empty() => parse_output(&value),
+            (None, _) => Ok(OutputFormat::Text),
+        }
+    }
+
+    pub fn log_settings(&self) -> LogSettings {
+        LogSettings {
+            verbose: self.verbose,
+            file: self.log_file.clone(),
+            sensitive: self.log_sensitive,
+        }
+    }
+}
 
 pub fn run_cli() -> Result<(), CliError> {
-    let mode = if std::env::var_os("BUDGET_CORE_CLI_SCRIPT").is_some() {
-        CliMode::Script
-    } else {
-        CliMode::Interactive
-    };
+    run_cli_with(CliOptions::default())
+}
+
+pub fn run_cli_with(options: CliOptions) -> Result<(), CliError> {
+    let format = options.output_format()?;
+    output::set_format(format);
+    let mode =
+        if std::env::var_os("BUDGET_CORE_CLI_SCRIPT").is_some() || format == OutputFormat::Json {
+            CliMode::Script
+        } else {
+            CliMode::Interactive
+        };
 
+    if let Some(dir) = options.portable {
+        PathResolver::set_strategy(PathStrategy::portable_in(dir).map_err(CliError::from)?);
+    }
+    test_mode::install_id_generator();
     let mut context = ShellContext::new(mode)?;
+    context.verbose = options.verbose;
+    if let Err(err) = shutdown::install(context.ledger_manager.clone()) {
+        tracing::warn!("signal handling unavailable: {err}");
+    }
 
     match mode {
         CliMode::Interactive => run_interactive(&mut context),
@@ -24,6 +139,7 @@ pub fn run_cli() -> Result<(), CliError> {
 }
 
 fn run_interactive(context: &mut ShellContext) -> Result<(), CliError> {
+    Banner::render_startup(context);
     loop {
         if !context.running {
             break;
@@ -38,7 +154,11 @@ fn run_interactive(context: &mut ShellContext) -> Result<(), CliError> {
                 match handle_line(context, trimmed) {
                     Ok(LoopControl::Continue) => {}
                     Ok(LoopControl::Exit) => break,
-                    Err(err) => context.report_error(err)?,
+                    Err(err) => {
+                        // A prompt cancelled with Ctrl-C can leave the cursor hidden.
+                        shutdown::restore_terminal();
+                        context.report_error(err)?
+                    }
                 }
             }
             Ok(None) => {
@@ -58,6 +178,10 @@ fn run_interactive(context: &mut ShellContext) -> Result<(), CliError> {
         }
     }
 
+    if let Err(err) = context.offer_save_before_exit() {
+        shutdown::restore_terminal();
+        context.report_error(err)?;
+    }
     Ok(())
 }
 
@@ -68,16 +192,33 @@ fn run_script(context: &mut ShellContext) -> Result<(), CliError> {
             break;
         }
         let line = line?;
-        match handle_line(context, &line) {
-            Ok(LoopControl::Continue) => {}
-            Ok(LoopControl::Exit) => break,
-            Err(err) => context.report_error(err)?,
+        let outcome = handle_line(context, &line);
+        let error = outcome.as_ref().err().map(ToString::to_string);
+        let exit = matches!(outcome, Ok(LoopControl::Exit));
+        if let Err(err) = outcome {
+            context.report_error(err)?;
+        }
+        if output::is_json() {
+            json::finish(error);
         }
+        if exit {
+            break;
+        }
+    }
+    json::flush();
+    if context.failed_assertions > 0 {
+        return Err(CliError::Command(format!(
+            "{} assertion(s) failed",
+            context.failed_assertions
+        )));
     }
     Ok(())
 }
 
 fn handle_line(context: &mut ShellContext, line: &str) -> Result<LoopControl, CommandError> {
+    let span = tracing::debug_span!("command", line = line.trim());
+    let _entered = span.enter();
+    let started = Instant::now();
     let tokens = match parse_command_line(line) {
         Ok(tokens) => tokens,
         Err(err) => {
@@ -90,13 +231,42 @@ fn handle_line(context: &mut ShellContext, line: &str) -> Result<LoopControl, Co
         return Ok(LoopControl::Continue);
     }
 
+    if output::is_json() {
+        json::begin(line);
+    }
     let raw = &tokens[0];
     let command = raw.to_lowercase();
     let args: Vec<&str> = tokens.iter().skip(1).map(String::as_str).collect();
 
     context.last_command = Some(line.trim().to_string());
+    if command != "palette" {
+        context.remember_command(line);
+    }
+    let parse_time = started.elapsed();
 
-    match context.dispatch(&command, raw, &args) {
+    let paged = context.mode() == CliMode::Interactive && context.pager_enabled();
+    cli_io::take_render_time();
+    let mut service_time = Duration::ZERO;
+    let outcome = pager::run_paged(paged, || {
+        let started = Instant::now();
+        let outcome = tracing::debug_span!("dispatch", command = %command)
+            .in_scope(|| context.dispatch(&command, raw, &args));
+        service_time = started.elapsed();
+        outcome
+    });
+    if context.verbose {
+        let render_time = cli_io::take_render_time();
+        tracing::info!(
+            target: "budget_core::timing",
+            command = %command,
+            parse_ms = %millis(parse_time),
+            service_ms = %millis(service_time.saturating_sub(render_time)),
+            render_ms = %millis(render_time),
+            ok = outcome.is_ok(),
+        );
+    }
+
+    match outcome {
         Ok(LoopControl::Exit) => {
             context.running = false;
             Ok(LoopControl::Exit)
@@ -105,6 +275,19 @@ fn handle_line(context: &mut ShellContext, line: &str) -> Result<LoopControl, Co
     }
 }
 
+fn parse_output(value: &str) -> Result<OutputFormat, CliError> {
+    OutputFormat::parse(value).ok_or_else(|| {
+        CliError::Input(format!(
+            "unknown output format `{}`; use `text` or `json`",
+            value
+        ))
+    })
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
 pub(crate) fn parse_command_line(input: &str) -> Result<Vec<String>, ParseError> {
     split(input).map_err(|err| ParseError {
         message: err.to_string(),
@@ -5,6 +5,7 @@ use std::{
     sync::{Arc, RwLock},
 };
 
+use chrono::NaiveDate;
 use dialoguer::theme::ColorfulTheme;
 
 use crate::{
@@ -18,6 +19,9 @@ use bufy_storage_json::JsonLedgerStorage as JsonStorage;
 use super::{formatters::CliFormatters, registry::CommandRegistry};
 use crate::cli::ui::style::{self, UiStyle};
 
+/// How many recent command lines the palette offers.
+pub const RECENT_COMMAND_LIMIT: usize = 5;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CliMode {
     Interactive,
@@ -38,7 +42,16 @@ pub struct ShellContext {
     pub active_simulation_name: Option<String>,
     pub current_simulation: Option<Simulation>,
     pub last_command: Option<String>,
+    /// Lines run this session, newest first, offered by the command palette.
+    pub recent_commands: Vec<String>,
     pub running: bool,
+    /// Number of `assert` commands that failed this session.
+    pub failed_assertions: usize,
+    /// Reports per-command timings on stderr (`--verbose`).
+    pub verbose: bool,
+    /// Scheduled date of the transaction entered last this session, offered
+    /// again when `default_transaction_date` is `last_used`.
+    pub last_transaction_date: Option<NaiveDate>,
     pub ui_style: UiStyle,
 }
 
@@ -58,6 +71,18 @@ impl ShellContext {
         )
     }
 
+    /// Records `line` as the newest recent command, dropping an earlier copy
+    /// and anything past [`RECENT_COMMAND_LIMIT`].
+    pub fn remember_command(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        self.recent_commands.retain(|recent| recent != line);
+        self.recent_commands.insert(0, line.to_string());
+        self.recent_commands.truncate(RECENT_COMMAND_LIMIT);
+    }
+
     pub fn refresh_ui_style(&mut self) {
         self.ui_style = style::style();
     }
@@ -0,0 +1,123 @@
+//! Graceful shutdown: puts the terminal back after interrupted prompts and
+//! keeps unsaved ledger changes when the shell is stopped by a signal.
+
+use std::{
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock, TryLockError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossterm::{cursor, terminal, ExecutableCommand};
+
+use crate::core::{errors::BudgetError, ledger_manager::LedgerManager};
+
+/// Note on backups written when a signal stops the shell.
+pub const AUTOSAVE_NOTE: &str = "autosave";
+
+/// How long a signal waits for a command holding the ledger before giving up.
+const LOCK_WAIT: Duration = Duration::from_secs(2);
+
+/// Leaves raw mode and shows the cursor, which an interrupted prompt or menu
+/// may have left hidden.
+pub fn restore_terminal() {
+    if !io::stdout().is_terminal() {
+        return;
+    }
+    terminal::disable_raw_mode().ok();
+    io::stdout().execute(cursor::Show).ok();
+}
+
+/// Handles SIGINT, SIGTERM, and SIGHUP on a background thread: the terminal
+/// is restored, unsaved changes are autosaved, and the process exits with
+/// status `128 + signal`.
+#[cfg(unix)]
+pub fn install(manager: Arc<RwLock<LedgerManager>>) -> io::Result<()> {
+    use signal_hook::{
+        consts::{SIGHUP, SIGINT, SIGTERM},
+        iterator::Signals,
+    };
+
+    let mut signals = Signals::new([SIGINT, SIGTERM, SIGHUP])?;
+    thread::Builder::new()
+        .name("shutdown".into())
+        .spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                restore_terminal();
+                match autosave(&manager) {
+                    Ok(Some(message)) => eprintln!("{message}"),
+                    Ok(None) => {}
+                    Err(err) => eprintln!("Unsaved changes could not be kept: {err}"),
+                }
+                std::process::exit(128 + signal);
+            }
+        })?;
+    Ok(())
+}
+
+/// Console close events are left to the default handler off Unix.
+#[cfg(not(unix))]
+pub fn install(_manager: Arc<RwLock<LedgerManager>>) -> io::Result<()> {
+    Ok(())
+}
+
+/// Writes unsaved changes without overwriting the saved ledger: a backup for
+/// named ledgers, a `.autosave` copy beside ledgers opened from a file.
+/// Returns a message for the user, or `None` when nothing was unsaved.
+pub fn autosave(manager: &RwLock<LedgerManager>) -> Result<Option<String>, BudgetError> {
+    let deadline = Instant::now() + LOCK_WAIT;
+    let manager = wait_for(deadline, || manager.try_read())?;
+    if !manager.has_unsaved_changes() {
+        return Ok(None);
+    }
+    let handle = manager
+        .current_handle()
+        .ok_or(BudgetError::LedgerNotLoaded)?;
+    let ledger = wait_for(deadline, || handle.try_read())?;
+    let storage = manager.storage();
+    if let Some(name) = manager.current_name() {
+        storage.backup_ledger(name, &ledger, Some(AUTOSAVE_NOTE))?;
+        return Ok(Some(format!(
+            "Unsaved changes to `{}` were kept in an autosave backup. Use `ledger restore` to get them back.",
+            name
+        )));
+    }
+    let path = manager.current_path().map(autosave_path).ok_or_else(|| {
+        BudgetError::StorageError("the ledger has no name or file to save beside".into())
+    })?;
+    storage.save_ledger_to_path(&ledger, &path)?;
+    Ok(Some(format!(
+        "Unsaved changes were kept in {}.",
+        path.display()
+    )))
+}
+
+/// `budget.json` becomes `budget.json.autosave`.
+pub fn autosave_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".autosave");
+    PathBuf::from(name)
+}
+
+/// Retries a non-blocking lock until `deadline`, so a command stuck holding
+/// the ledger cannot keep the process from exiting.
+fn wait_for<G>(
+    deadline: Instant,
+    mut attempt: impl FnMut() -> Result<G, TryLockError<G>>,
+) -> Result<G, BudgetError> {
+    loop {
+        match attempt() {
+            Ok(guard) => return Ok(guard),
+            Err(TryLockError::Poisoned(poisoned)) => return Ok(poisoned.into_inner()),
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                thread::sleep(Duration::from_millis(20));
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(BudgetError::StorageError(
+                    "the ledger was still in use by a command".into(),
+                ))
+            }
+        }
+    }
+}
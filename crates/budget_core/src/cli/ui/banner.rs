@@ -1,6 +1,28 @@
+//! The context line shown above interactive menus and the banner printed
+//! when the shell starts. Both can be replaced by templates set with
+//! `config set prompt` and `config set banner`; `{name}` placeholders are
+//! filled from the current ledger and `{{`/`}}` print literal braces.
+
 use crate::cli::core::ShellContext;
 use crate::cli::output::current_preferences;
 use crate::cli::ui::formatting::Formatter;
+use crate::utils::build_info;
+use bufy_core::DateFormatter;
+
+/// Placeholders a prompt or banner template may use.
+pub const TEMPLATE_VARIABLES: &[(&str, &str)] = &[
+    ("ledger", "ledger name, or `no-ledger`"),
+    ("simulation", "active simulation name, empty when none"),
+    ("period", "dates of the current budget period"),
+    (
+        "progress",
+        "share of the current budget period elapsed, like `40%`",
+    ),
+    ("dirty", "`*` when the ledger has unsaved changes"),
+    ("arrow", "the prompt arrow for the current theme"),
+    ("date", "today's date"),
+    ("version", "CLI version"),
+];
 
 pub struct Banner;
 
@@ -11,6 +33,10 @@ impl Banner {
     }
 
     pub fn text(context: &ShellContext) -> String {
+        if let Some(template) = context.prompt_template() {
+            return render_template(&template, &TemplateValues::capture(context));
+        }
+
         let ledger_segment = {
             let manager = context.manager();
             manager
@@ -24,12 +50,215 @@ impl Banner {
             .map(|name| format!(" (simulation: {})", name))
             .unwrap_or_default();
 
-        let arrow = if current_preferences().plain_mode {
-            ">"
-        } else {
-            "⮞"
+        format!("{ledger_segment}{simulation_segment} {}", arrow())
+    }
+
+    /// Prints the banner set with `config set banner`, if any.
+    pub fn render_startup(context: &ShellContext) {
+        if let Some(template) = context.banner_template() {
+            let text = render_template(&template, &TemplateValues::capture(context));
+            if !text.trim().is_empty() {
+                Formatter::new().print_header(text);
+            }
+        }
+    }
+}
+
+/// Checks that every placeholder in `template` is known and every brace is
+/// closed, so mistakes surface when the template is set.
+pub fn validate_template(template: &str) -> Result<(), String> {
+    let mut unknown = Vec::new();
+    for piece in parse(template) {
+        match piece {
+            Piece::Variable(name) if !is_variable(name) => unknown.push(format!("{{{}}}", name)),
+            Piece::Unclosed => return Err("unclosed `{` in template; use `{{` for a brace".into()),
+            _ => {}
+        }
+    }
+    if unknown.is_empty() {
+        Ok(())
+    } else {
+        let known: Vec<String> = TEMPLATE_VARIABLES
+            .iter()
+            .map(|(name, _)| format!("{{{}}}", name))
+            .collect();
+        Err(format!(
+            "unknown template variable(s) {}; available: {}",
+            unknown.join(", "),
+            known.join(" ")
+        ))
+    }
+}
+
+/// Fills `template` from `values`. Unknown placeholders are kept as written.
+pub fn render_template(template: &str, values: &TemplateValues) -> String {
+    let mut out = String::with_capacity(template.len());
+    for piece in parse(template) {
+        match piece {
+            Piece::Text(text) => out.push_str(text),
+            Piece::Variable(name) => match values.get(name) {
+                Some(value) => out.push_str(value),
+                None => {
+                    out.push('{');
+                    out.push_str(name);
+                    out.push('}');
+                }
+            },
+            Piece::Unclosed => {}
+        }
+    }
+    out
+}
+
+/// What the template placeholders stand for right now.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemplateValues {
+    pub ledger: String,
+    pub simulation: String,
+    pub period: String,
+    pub progress: String,
+    pub dirty: bool,
+    pub arrow: String,
+    pub date: String,
+}
+
+impl TemplateValues {
+    pub fn capture(context: &ShellContext) -> Self {
+        let today = context.clock.today();
+        let formatters = &context.formatters;
+        let named = context.manager().current_name().map(str::to_string);
+        let loaded = context
+            .with_ledger(|ledger| {
+                let window = ledger.budget_window_containing(today);
+                let last = window.end.pred_opt().unwrap_or(window.end);
+                let total = (window.end - window.start).num_days().max(1);
+                let elapsed = ((today - window.start).num_days() + 1).clamp(0, total);
+                Ok((
+                    ledger.name.clone(),
+                    format!(
+                        "{} – {}",
+                        formatters.format_date(window.start),
+                        formatters.format_date(last)
+                    ),
+                    format!("{}%", elapsed * 100 / total),
+                ))
+            })
+            .ok();
+        let (ledger, period, progress) = match loaded {
+            Some((name, period, progress)) => (named.unwrap_or(name), period, progress),
+            None => ("no-ledger".to_string(), String::new(), String::new()),
         };
+        Self {
+            ledger,
+            simulation: context
+                .active_simulation_name()
+                .unwrap_or_default()
+                .to_string(),
+            period,
+            progress,
+            dirty: context.manager().has_unsaved_changes(),
+            arrow: arrow().to_string(),
+            date: formatters.format_date(today),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        Some(match name {
+            "ledger" => self.ledger.as_str(),
+            "simulation" => self.simulation.as_str(),
+            "period" => self.period.as_str(),
+            "progress" => self.progress.as_str(),
+            "dirty" => {
+                if self.dirty {
+                    "*"
+                } else {
+                    ""
+                }
+            }
+            "arrow" => self.arrow.as_str(),
+            "date" => self.date.as_str(),
+            "version" => build_info::CLI_VERSION,
+            _ => return None,
+        })
+    }
+}
+
+fn arrow() -> &'static str {
+    if current_preferences().plain_mode {
+        ">"
+    } else {
+        "⮞"
+    }
+}
+
+fn is_variable(name: &str) -> bool {
+    TEMPLATE_VARIABLES.iter().any(|(known, _)| *known == name)
+}
 
-        format!("{ledger_segment}{simulation_segment} {arrow}")
+#[derive(Debug, PartialEq)]
+enum Piece<'a> {
+    Text(&'a str),
+    Variable(&'a str),
+    Unclosed,
+}
+
+fn parse(template: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut rest = template;
+    while let Some(index) = rest.find(['{', '}']) {
+        if index > 0 {
+            pieces.push(Piece::Text(&rest[..index]));
+        }
+        let tail = &rest[index..];
+        if let Some(after) = tail.strip_prefix("{{") {
+            pieces.push(Piece::Text("{"));
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("}}") {
+            pieces.push(Piece::Text("}"));
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix('{') {
+            match after.find('}') {
+                Some(end) => {
+                    pieces.push(Piece::Variable(after[..end].trim()));
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    pieces.push(Piece::Unclosed);
+                    return pieces;
+                }
+            }
+        } else {
+            pieces.push(Piece::Text("}"));
+            rest = &tail[1..];
+        }
+    }
+    if !rest.is_empty() {
+        pieces.push(Piece::Text(rest));
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_substitute_known_variables_and_escape_braces() {
+        let values = TemplateValues {
+            ledger: "Household".into(),
+            progress: "40%".into(),
+            dirty: true,
+            arrow: ">".into(),
+            ..TemplateValues::default()
+        };
+        assert_eq!(
+            render_template("[{ledger}{dirty} {progress}]{{x}} {arrow}", &values),
+            "[Household* 40%]{x} >"
+        );
+        assert_eq!(render_template("{ nope }", &values), "{nope}");
+        assert!(validate_template("{ledger} {{literal}}").is_ok());
+        assert!(validate_template("{ledger").is_err());
+        let err = validate_template("{ledger} {mood}").unwrap_err();
+        assert!(err.contains("{mood}"), "{err}");
     }
 }
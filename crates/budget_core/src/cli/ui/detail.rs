@@ -1,4 +1,8 @@
-use crate::cli::{io, ui::style::UiStyle};
+use crate::cli::{
+    io,
+    output::{is_json, json},
+    ui::style::UiStyle,
+};
 
 /// Renders JSON-like detail views for single entities.
 pub struct DetailViewRenderer;
@@ -9,6 +13,13 @@ impl DetailViewRenderer {
         T: AsRef<str>,
     {
         let title = title.as_ref();
+        if is_json() {
+            let pairs = fields
+                .iter()
+                .map(|field| (field.label.as_str(), field.value.as_str()));
+            json::detail(title, pairs);
+            return;
+        }
         let prefix = if style.use_icons { "🔎 " } else { "" };
         let heading = format!("{prefix}{title}");
         let _ = io::println_text(&style.apply_header_style(&heading));
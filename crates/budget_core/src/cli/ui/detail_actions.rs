@@ -9,7 +9,7 @@ use crossterm::{
 
 use crate::cli::ui::style::{format_header, style};
 use crate::cli::{
-    io::write_line,
+    io::{release_capture, write_line},
     ui::navigation::{navigation_loop, NavKey},
 };
 
@@ -80,6 +80,7 @@ impl DetailActionsMenu {
             return DetailActionResult::Empty;
         }
 
+        release_capture();
         if terminal::enable_raw_mode().is_err() {
             return DetailActionResult::Escaped;
         }
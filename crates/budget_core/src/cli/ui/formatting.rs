@@ -4,7 +4,7 @@ use colored::Colorize;
 
 use crate::cli::{
     io::println_text,
-    output::{current_preferences, OutputPreferences},
+    output::{current_preferences, is_json, json, OutputPreferences},
 };
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -21,6 +21,12 @@ pub struct Formatter {
     prefs: OutputPreferences,
 }
 
+impl Default for Formatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Formatter {
     pub fn new() -> Self {
         Self {
@@ -29,6 +35,9 @@ impl Formatter {
     }
 
     pub fn print_header(&self, title: impl fmt::Display) {
+        if is_json() {
+            return json::message("heading", title);
+        }
         let text = self.header_text(title);
         let _ = println_text("");
         let _ = println_text(&text);
@@ -40,10 +49,16 @@ impl Formatter {
     }
 
     pub fn print_info(&self, message: impl fmt::Display) {
+        if is_json() {
+            return json::message("info", message);
+        }
         let _ = println_text(&self.apply_style(Style::Info, message));
     }
 
     pub fn print_detail(&self, message: impl fmt::Display) {
+        if is_json() {
+            return json::message("detail", message);
+        }
         let _ = println_text(&self.apply_style(Style::Detail, message));
     }
 
@@ -64,6 +79,14 @@ impl Formatter {
     }
 
     fn print_line(&self, style: Style, message: impl fmt::Display) {
+        if is_json() {
+            let level = match style {
+                Style::Success => "success",
+                Style::Warning => "warning",
+                _ => "error",
+            };
+            return json::message(level, message);
+        }
         if self.prefs.audio_feedback && matches!(style, Style::Warning | Style::Error) {
             print!("\x07");
         }
@@ -0,0 +1,279 @@
+use std::io::{self, Stdout};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    terminal::{self, ClearType},
+    ExecutableCommand,
+};
+
+use crate::cli::{
+    io::{release_capture, write_line},
+    ui::{
+        style::{format_header, style},
+        test_mode,
+    },
+};
+
+const FOOTER_HINT: &str = "Type to filter, ↑ ↓ to move, Enter to run, ESC to close.";
+const VISIBLE_ROWS: usize = 12;
+
+/// Scores `candidate` against `query` as an in-order, case-insensitive
+/// subsequence. Higher is better; `None` when some query character is missing.
+/// Consecutive characters, word starts, and a match at the very start earn
+/// extra points.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let mut score = 0u32;
+    let mut pending = query.iter().peekable();
+    let mut previous_matched = false;
+    let mut previous: Option<char> = None;
+    for (position, c) in candidate.chars().enumerate() {
+        let Some(&&wanted) = pending.peek() else {
+            break;
+        };
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        if lower == wanted {
+            pending.next();
+            score += 1;
+            if previous_matched {
+                score += 4;
+            }
+            if position == 0 {
+                score += 8;
+            } else if previous.is_some_and(|p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous = Some(c);
+    }
+    pending.peek().is_none().then_some(score)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyItem {
+    /// Value returned when the item is chosen.
+    pub key: String,
+    pub label: String,
+    /// Searched together with the label, but weighted lower.
+    pub description: String,
+    /// Shown under the list while the item is highlighted.
+    pub help: Option<String>,
+}
+
+impl FuzzyItem {
+    pub fn new(
+        key: impl Into<String>,
+        label: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        Self {
+            key: key.into(),
+            label: label.into(),
+            description: description.into(),
+            help: None,
+        }
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    fn score(&self, query: &str) -> Option<u32> {
+        let label = fuzzy_score(query, &self.label).map(|score| score * 2);
+        let description = fuzzy_score(query, &self.description);
+        label.max(description)
+    }
+}
+
+/// Picker that narrows a list as the user types.
+pub struct FuzzyFinder {
+    pub title: String,
+    pub items: Vec<FuzzyItem>,
+    pub query: String,
+}
+
+impl FuzzyFinder {
+    pub fn new(title: impl Into<String>, items: Vec<FuzzyItem>) -> Self {
+        Self {
+            title: title.into(),
+            items,
+            query: String::new(),
+        }
+    }
+
+    pub fn with_query(mut self, query: impl Into<String>) -> Self {
+        self.query = query.into();
+        self
+    }
+
+    /// Items matching `query`, best first. Ties keep list order, so an empty
+    /// query shows the list unchanged.
+    pub fn matches(&self, query: &str) -> Vec<&FuzzyItem> {
+        let mut scored: Vec<(u32, usize, &FuzzyItem)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| item.score(query).map(|score| (score, index, item)))
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        scored.into_iter().map(|(_, _, item)| item).collect()
+    }
+
+    /// Runs the finder and returns the chosen item's key, or `None` on ESC.
+    pub fn run(&self) -> io::Result<Option<String>> {
+        if let Some(keys) = test_mode::next_selector_events(&self.title) {
+            return Ok(self.run_simulated(&keys));
+        }
+
+        release_capture();
+        let mut stdout = io::stdout();
+        terminal::enable_raw_mode()?;
+        stdout.execute(cursor::Hide)?;
+
+        let mut query = self.query.clone();
+        let mut selected = 0usize;
+        let result = loop {
+            let matches = self.matches(&query);
+            selected = selected.min(matches.len().saturating_sub(1));
+            if let Err(err) = self.draw(&mut stdout, &query, &matches, selected) {
+                break Err(err);
+            }
+            let key = match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+                Ok(_) => continue,
+                Err(err) => break Err(err),
+            };
+            if key.modifiers.contains(KeyModifiers::CONTROL) {
+                match key.code {
+                    KeyCode::Char('c') | KeyCode::Char('C') => break Ok(None),
+                    KeyCode::Char('u') | KeyCode::Char('U') => query.clear(),
+                    _ => {}
+                }
+                continue;
+            }
+            match self.apply_key(key.code, &mut query, &mut selected) {
+                Step::Continue => {}
+                Step::Done(choice) => break Ok(choice),
+            }
+        };
+
+        stdout.execute(terminal::Clear(ClearType::All)).ok();
+        stdout.execute(cursor::MoveTo(0, 0)).ok();
+        stdout.execute(cursor::Show).ok();
+        terminal::disable_raw_mode().ok();
+        result
+    }
+
+    /// Replays `keys` without a terminal; printable keys are typed into the
+    /// query.
+    pub fn run_simulated(&self, keys: &[KeyCode]) -> Option<String> {
+        let mut query = self.query.clone();
+        let mut selected = 0usize;
+        for key in keys {
+            if let Step::Done(choice) = self.apply_key(*key, &mut query, &mut selected) {
+                return choice;
+            }
+        }
+        None
+    }
+
+    fn apply_key(&self, key: KeyCode, query: &mut String, selected: &mut usize) -> Step {
+        let count = self.matches(query).len();
+        match key {
+            KeyCode::Up if count > 0 => {
+                *selected = selected.checked_sub(1).unwrap_or(count - 1);
+            }
+            KeyCode::Down if count > 0 => {
+                *selected = (*selected + 1) % count;
+            }
+            KeyCode::Backspace => {
+                query.pop();
+                *selected = 0;
+            }
+            KeyCode::Char(c) => {
+                query.push(c);
+                *selected = 0;
+            }
+            KeyCode::Enter => {
+                if let Some(item) = self.matches(query).get(*selected) {
+                    return Step::Done(Some(item.key.clone()));
+                }
+            }
+            KeyCode::Esc => return Step::Done(None),
+            _ => {}
+        }
+        Step::Continue
+    }
+
+    fn draw(
+        &self,
+        stdout: &mut Stdout,
+        query: &str,
+        matches: &[&FuzzyItem],
+        selected: usize,
+    ) -> io::Result<()> {
+        stdout.execute(terminal::Clear(ClearType::All))?;
+        stdout.execute(cursor::MoveTo(0, 0))?;
+        for line in self.layout_lines(query, matches, selected) {
+            write_line(&mut *stdout, &line)?;
+        }
+        Ok(())
+    }
+
+    fn layout_lines(&self, query: &str, matches: &[&FuzzyItem], selected: usize) -> Vec<String> {
+        let ui = style();
+        let label_width = matches
+            .iter()
+            .map(|item| item.label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let first = selected.saturating_sub(VISIBLE_ROWS - 1);
+        let mut lines = vec![
+            format_header(&self.title),
+            format!("  {} {}", ui.highlight_marker, query),
+            ui.horizontal_line(FOOTER_HINT.chars().count()),
+        ];
+        if matches.is_empty() {
+            lines.push("    No matching commands.".into());
+        }
+        for (index, item) in matches.iter().enumerate().skip(first).take(VISIBLE_ROWS) {
+            let line = format!(
+                "    {:width$}  {}",
+                item.label,
+                item.description,
+                width = label_width
+            );
+            lines.push(if index == selected {
+                ui.apply_highlight_style(&line)
+            } else {
+                line
+            });
+        }
+        if matches.len() > VISIBLE_ROWS {
+            lines.push(format!("    ({} matches)", matches.len()));
+        }
+        lines.push(ui.horizontal_line(FOOTER_HINT.chars().count()));
+        if let Some(help) = matches.get(selected).and_then(|item| item.help.as_deref()) {
+            lines.push(help.to_string());
+        }
+        lines.push(FOOTER_HINT.to_string());
+        lines
+    }
+}
+
+enum Step {
+    Continue,
+    Done(Option<String>),
+}
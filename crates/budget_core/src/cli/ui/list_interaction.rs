@@ -10,6 +10,7 @@ use crate::cli::ui::test_mode;
 ///
 /// Each command provides callbacks for gathering entries, building a table,
 /// rendering detail views, supplying actions, and executing the selected action.
+#[allow(clippy::too_many_arguments)]
 pub fn run_selectable_table<T, GatherFn, TableFn, DetailFn, ActionsFn, HandleFn>(
     context: &mut ShellContext,
     selector_label: &'static str,
@@ -64,12 +65,13 @@ where
     }
 }
 
-enum RowSelection {
+pub(crate) enum RowSelection {
     Index(usize),
     Exit,
 }
 
-fn select_row(label: &str, table: &Table) -> RowSelection {
+/// Lets the user pick a table row, honoring scripted selector events in test mode.
+pub(crate) fn select_row(label: &str, table: &Table) -> RowSelection {
     if let Some(keys) = test_mode::next_selector_events(label) {
         return match ListSelector::new(table).run_simulated(&keys) {
             ListSelectionResult::Selected(index) => RowSelection::Index(index),
@@ -10,7 +10,7 @@ use crossterm::{
 use crate::cli::ui::style::style;
 use crate::cli::ui::table_renderer::{horizontal_rule, visible_width, Table};
 use crate::cli::{
-    io::write_line,
+    io::{release_capture, write_line},
     ui::navigation::{navigation_loop, NavKey},
 };
 
@@ -51,6 +51,7 @@ impl<'a> ListSelector<'a> {
             return ListSelectionResult::Empty;
         }
 
+        release_capture();
         if terminal::enable_raw_mode().is_err() {
             return ListSelectionResult::Escaped;
         }
@@ -9,7 +9,7 @@ use crossterm::{
 };
 
 use crate::cli::{
-    io::write_line,
+    io::{release_capture, write_line},
     ui::{
         style::{format_header, style},
         table_renderer::visible_width,
@@ -26,6 +26,8 @@ pub struct MenuUI {
     pub items: Vec<MenuUIItem>,
     pub initial_index: Option<usize>,
     pub footer_hint: Option<String>,
+    /// Ctrl+letter keys that return a key without moving the selection.
+    pub shortcuts: Vec<(char, String)>,
 }
 
 impl MenuUI {
@@ -36,6 +38,7 @@ impl MenuUI {
             items,
             initial_index: None,
             footer_hint: None,
+            shortcuts: Vec::new(),
         }
     }
 
@@ -53,6 +56,21 @@ impl MenuUI {
         self.footer_hint = Some(hint.into());
         self
     }
+
+    /// Makes Ctrl+`letter` return `key`, as if that item had been chosen.
+    pub fn with_shortcut(mut self, letter: char, key: impl Into<String>) -> Self {
+        self.shortcuts
+            .push((letter.to_ascii_lowercase(), key.into()));
+        self
+    }
+
+    fn shortcut(&self, letter: char) -> Option<&str> {
+        let letter = letter.to_ascii_lowercase();
+        self.shortcuts
+            .iter()
+            .find(|(candidate, _)| *candidate == letter)
+            .map(|(_, key)| key.as_str())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -111,6 +129,12 @@ impl From<io::Error> for MenuRenderError {
 
 pub struct MenuRenderer;
 
+impl Default for MenuRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MenuRenderer {
     pub fn new() -> Self {
         Self
@@ -125,6 +149,7 @@ impl MenuRenderer {
             return self.show_with_script(menu, events);
         }
 
+        release_capture();
         let mut stdout = io::stdout();
         terminal::enable_raw_mode()?;
         stdout.execute(cursor::Hide)?;
@@ -146,6 +171,10 @@ impl MenuRenderer {
                             KeyCode::Char('d') | KeyCode::Char('D') => {
                                 break Err(MenuRenderError::EndOfInput)
                             }
+                            KeyCode::Char(letter) => match menu.shortcut(letter) {
+                                Some(key) => break Ok(Some(key.to_string())),
+                                None => continue,
+                            },
                             _ => continue,
                         }
                     }
@@ -173,11 +202,9 @@ impl MenuRenderer {
                         KeyCode::PageDown => {
                             selected_index = Self::page_down_index(&menu.items, selected_index);
                         }
-                        KeyCode::Enter => {
-                            if menu.items[selected_index].enabled {
-                                let key = menu.items[selected_index].key.clone();
-                                break Ok(Some(key));
-                            }
+                        KeyCode::Enter if menu.items[selected_index].enabled => {
+                            let key = menu.items[selected_index].key.clone();
+                            break Ok(Some(key));
                         }
                         KeyCode::Esc => break Ok(None),
                         _ => {}
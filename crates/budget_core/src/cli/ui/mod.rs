@@ -3,6 +3,7 @@ pub mod detail;
 pub mod detail_actions;
 pub mod detail_view;
 pub mod formatting;
+pub mod fuzzy_finder;
 pub mod list_interaction;
 pub mod list_selector;
 pub mod menu;
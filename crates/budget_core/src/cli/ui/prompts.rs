@@ -7,7 +7,7 @@ use crossterm::{
     ExecutableCommand,
 };
 
-use crate::cli::io::println_text;
+use crate::cli::io::{println_text, release_capture};
 use crate::cli::ui::menu_renderer::{MenuRenderer, MenuUI, MenuUIItem};
 use crate::cli::ui::test_mode::{self, TextTestInput};
 
@@ -254,6 +254,7 @@ struct RawModeGuard {
 
 impl RawModeGuard {
     fn activate() -> io::Result<Self> {
+        release_capture();
         terminal::enable_raw_mode()?;
         Ok(Self { active: true })
     }
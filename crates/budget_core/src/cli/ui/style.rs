@@ -6,6 +6,7 @@ use std::{
 use colored::{Color, Colorize};
 
 use crate::cli::output::current_preferences;
+use crate::ledger::{Appearance, EntityColor};
 
 #[derive(Clone)]
 pub struct UiStyle {
@@ -99,6 +100,32 @@ impl UiStyle {
             text.to_string()
         }
     }
+
+    /// `name` with its account or category icon before it, in its color.
+    /// Plain and screen-reader modes drop the icon; `NO_COLOR` and
+    /// non-terminal output drop the color.
+    pub fn apply_entity_style(&self, name: &str, appearance: &Appearance) -> String {
+        let label = match appearance.icon.as_deref() {
+            Some(icon) if self.use_icons => format!("{} {}", icon, name),
+            _ => name.to_string(),
+        };
+        match appearance.color {
+            Some(color) if self.use_color => label.color(entity_color(color)).to_string(),
+            _ => label,
+        }
+    }
+}
+
+fn entity_color(color: EntityColor) -> Color {
+    match color {
+        EntityColor::Red => Color::Red,
+        EntityColor::Green => Color::Green,
+        EntityColor::Yellow => Color::Yellow,
+        EntityColor::Blue => Color::Blue,
+        EntityColor::Magenta => Color::Magenta,
+        EntityColor::Cyan => Color::Cyan,
+        EntityColor::White => Color::White,
+    }
 }
 
 pub fn format_header(title: &str) -> String {
@@ -1,4 +1,8 @@
-use crate::cli::{io, ui::style::UiStyle};
+use crate::cli::{
+    io,
+    output::{is_json, json},
+    ui::style::UiStyle,
+};
 
 /// Declarative description of a table column.
 #[derive(Debug, Clone)]
@@ -52,6 +56,12 @@ pub struct TableRenderer;
 
 impl TableRenderer {
     pub fn render(table: &Table, style: &UiStyle) {
+        if is_json() {
+            let columns: Vec<String> = table.columns.iter().map(|col| col.header.clone()).collect();
+            let rows: Vec<Vec<String>> = table.rows.iter().map(|row| row.cells.clone()).collect();
+            json::table(table.title.as_deref(), &columns, &rows);
+            return;
+        }
         if let Some(title) = &table.title {
             let prefix = if style.use_icons { "📋 " } else { "" };
             let header = format!("{prefix}{title}");
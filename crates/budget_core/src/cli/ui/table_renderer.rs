@@ -1,3 +1,5 @@
+use unicode_width::UnicodeWidthChar;
+
 use crate::cli::ui::style::style;
 
 const COLUMN_GAP: &str = "  ";
@@ -141,7 +143,7 @@ pub fn visible_width(text: &str) -> usize {
         }
 
         if let Some(ch) = text[idx..].chars().next() {
-            width += 1;
+            width += char_width(ch);
             idx += ch.len_utf8();
         } else {
             break;
@@ -151,6 +153,12 @@ pub fn visible_width(text: &str) -> usize {
     width
 }
 
+/// Terminal columns `ch` takes: two for wide characters such as most emoji,
+/// none for combining marks and control characters.
+fn char_width(ch: char) -> usize {
+    ch.width().unwrap_or(0)
+}
+
 fn truncate_text(text: &str, width: usize) -> String {
     if width == 0 {
         return String::new();
@@ -192,11 +200,11 @@ fn truncate_text(text: &str, width: usize) -> String {
 
         if let Some(ch) = text[idx..].chars().next() {
             let len = ch.len_utf8();
-            if visible + 1 > target {
+            if visible + char_width(ch) > target {
                 break;
             }
             result.push_str(&text[idx..idx + len]);
-            visible += 1;
+            visible += char_width(ch);
             idx += len;
         } else {
             break;
@@ -1,6 +1,11 @@
+use bufy_domain::ids::{set_id_generator, SequentialIds};
 use crossterm::event::KeyCode;
 use once_cell::sync::Lazy;
-use std::{collections::VecDeque, env, sync::Mutex};
+use std::{
+    collections::VecDeque,
+    env,
+    sync::{Arc, Mutex},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MenuTestEvent {
@@ -157,6 +162,14 @@ pub fn is_enabled() -> bool {
             .enabled
 }
 
+/// With `BUFY_TEST_IDS=sequential`, new records get sequential ids instead
+/// of random ones, so script output and saved ledgers repeat exactly.
+pub fn install_id_generator() {
+    if env::var("BUFY_TEST_IDS").is_ok_and(|value| value.eq_ignore_ascii_case("sequential")) {
+        set_id_generator(Arc::new(SequentialIds::new()));
+    }
+}
+
 pub fn next_menu_events(label: &str) -> Option<Vec<MenuTestEvent>> {
     let mut guard = MENU_EVENTS.lock().expect("menu event queue poisoned");
     if !guard.enabled {
@@ -1,7 +1,10 @@
 use std::path::PathBuf;
 
 pub use bufy_config::manager::CONFIG_BACKUP_SCHEMA_VERSION;
-pub use bufy_config::{AccessibilitySettings, Config, ConfigError, ConfigManager, Theme};
+pub use bufy_config::{
+    AccessibilitySettings, BankConnection, BankProvider, Config, ConfigError, ConfigManager,
+    SecretStore, Theme, TransactionDateDefault, TransactionStatusDefault,
+};
 
 use crate::core::utils::PathResolver;
 
@@ -12,3 +15,9 @@ pub fn default_manager() -> Result<ConfigManager, ConfigError> {
 pub fn manager_with_base(base: PathBuf) -> Result<ConfigManager, ConfigError> {
     ConfigManager::with_base_dir(base)
 }
+
+/// Passphrase store for the active home directory: the OS keychain when
+/// available, otherwise a private file next to the config.
+pub fn secret_store() -> Box<dyn SecretStore> {
+    bufy_config::secrets::detect(&PathResolver::config_dir())
+}
@@ -56,6 +56,12 @@ impl From<bufy_domain::ledger::DateWindowError> for BudgetError {
     }
 }
 
+impl From<bufy_domain::MoneyError> for BudgetError {
+    fn from(err: bufy_domain::MoneyError) -> Self {
+        BudgetError::InvalidInput(err.to_string())
+    }
+}
+
 impl From<ServiceCoreError> for BudgetError {
     fn from(err: ServiceCoreError) -> Self {
         match err {
@@ -69,9 +75,18 @@ impl From<ServiceCoreError> for BudgetError {
                 BudgetError::TransactionError(format!("transaction {} not found", id))
             }
             ServiceCoreError::SimulationNotFound(message)
+            | ServiceCoreError::MemberNotFound(message)
+            | ServiceCoreError::ViewNotFound(message)
+            | ServiceCoreError::RuleNotFound(message)
+            | ServiceCoreError::DraftNotFound(message)
             | ServiceCoreError::InvalidOperation(message)
-            | ServiceCoreError::Validation(message) => BudgetError::InvalidInput(message),
+            | ServiceCoreError::Validation(message)
+            | ServiceCoreError::NumericOverflow(message) => BudgetError::InvalidInput(message),
+            ServiceCoreError::Invalid(issue) => BudgetError::InvalidInput(issue.to_string()),
             ServiceCoreError::Io(err) => BudgetError::StorageError(err.to_string()),
+            err @ ServiceCoreError::AtomicWrite { .. } => {
+                BudgetError::StorageError(err.to_string())
+            }
         }
     }
 }
@@ -80,7 +95,9 @@ impl From<CliConfigError> for BudgetError {
     fn from(err: CliConfigError) -> Self {
         match err {
             CliConfigError::Io(io) => BudgetError::StorageError(io.to_string()),
-            CliConfigError::Serde(message) => BudgetError::ConfigError(message),
+            CliConfigError::Serde(message) | CliConfigError::Secret(message) => {
+                BudgetError::ConfigError(message)
+            }
         }
     }
 }
@@ -1,12 +1,21 @@
 //! Manages ledger persistence, backups, and in-memory state.
 
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::core::errors::BudgetError;
 use crate::ledger::Ledger;
-use bufy_core::storage::{ledger_warnings, LedgerBackupInfo, LedgerStorage};
-use bufy_domain::CURRENT_SCHEMA_VERSION;
+use bufy_core::storage::{prepare_loaded_ledger, LedgerBackupInfo, LedgerStorage, LoadReport};
+use bufy_core::{
+    AuditEntry, AuditService, ChangeFeedService, ChangeJournal, LedgerEvent, PurgeReport,
+    RetentionService,
+};
+use bufy_storage_json::{EmbeddedLedgerStorage, SAMPLE_LEDGER_NAME};
+use chrono::NaiveDate;
+
+const READ_ONLY_HINT: &str =
+    "the sample ledger is read-only; run `ledger save-ledger <name>` to keep an editable copy";
 
 /// Metadata describing the outcome of a load operation.
 #[derive(Debug, Clone)]
@@ -18,12 +27,38 @@ pub struct LoadMetadata {
     pub schema_version: u8,
 }
 
+/// What `LedgerManager::purge_history` removed besides the ledger records.
+#[derive(Debug, Clone)]
+pub struct PurgeOutcome {
+    pub report: PurgeReport,
+    pub backups_rewritten: usize,
+    pub audit_entries_removed: usize,
+    pub change_feed_compacted: bool,
+}
+
 /// Facade that coordinates ledger state, persistence, and backups.
 ///
 pub struct LedgerManager {
     pub current: Option<Arc<RwLock<Ledger>>>,
     current_name: Option<String>,
+    /// File the current ledger was loaded from or saved to, if not by name.
+    current_path: Option<PathBuf>,
+    /// Set by every mutable borrow of the ledger, cleared by loads and saves.
+    unsaved: AtomicBool,
+    /// Set while the embedded sample is open; refuses mutable borrows and
+    /// saves until the ledger is stored under a name or path of its own.
+    read_only: bool,
     storage: Box<dyn LedgerStorage>,
+    change_feed: bool,
+    /// Last state written to the change feed, keyed by ledger name.
+    feed_baseline: Option<(String, Ledger)>,
+    /// Undo and redo history of the current ledger; reset whenever another
+    /// ledger replaces it.
+    journal: Mutex<ChangeJournal>,
+    /// Name recorded as the author of audited changes.
+    audit_actor: String,
+    /// Last state written to the audit log, keyed by ledger name.
+    audit_baseline: Option<(String, Ledger)>,
 }
 
 impl LedgerManager {
@@ -31,25 +66,86 @@ impl LedgerManager {
         Self {
             current: None,
             current_name: None,
+            current_path: None,
+            unsaved: AtomicBool::new(false),
+            read_only: false,
             storage,
+            change_feed: false,
+            feed_baseline: None,
+            journal: Mutex::new(ChangeJournal::new()),
+            audit_actor: default_audit_actor(),
+            audit_baseline: None,
         }
     }
 
+    /// Sets the name recorded as the author of changes saved from now on;
+    /// `None` goes back to the login name.
+    pub fn set_audit_actor(&mut self, actor: Option<String>) {
+        self.audit_actor = actor.unwrap_or_else(default_audit_actor);
+    }
+
+    pub fn audit_actor(&self) -> &str {
+        &self.audit_actor
+    }
+
+    /// Every entry in the current ledger's audit log, oldest first.
+    pub fn audit_entries(&self) -> Result<Vec<AuditEntry>, BudgetError> {
+        let name = self.current_name.as_deref().ok_or_else(|| {
+            BudgetError::StorageError("audit logs are kept for named ledgers only".into())
+        })?;
+        self.storage.read_audit(name).map_err(BudgetError::from)
+    }
+
+    /// Turns change-feed writing on or off for named ledgers. A feed that
+    /// was off may have missed saves, so the first save after turning it on
+    /// starts with a snapshot.
+    pub fn set_change_feed(&mut self, enabled: bool) {
+        self.change_feed = enabled;
+        self.feed_baseline = None;
+    }
+
+    pub fn change_feed_enabled(&self) -> bool {
+        self.change_feed
+    }
+
+    /// Events numbered after `after` in the current ledger's change feed.
+    pub fn change_feed_events(&self, after: u64) -> Result<Vec<LedgerEvent>, BudgetError> {
+        let name = self.feed_name()?;
+        self.storage
+            .read_changes(name, after)
+            .map_err(BudgetError::from)
+    }
+
+    /// Rewrites the current ledger's change feed as a snapshot of its saved
+    /// state.
+    pub fn compact_change_feed(&mut self) -> Result<LedgerEvent, BudgetError> {
+        let name = self.feed_name()?.to_string();
+        let saved = self.storage.load_ledger(&name)?;
+        let event = self.storage.compact_changes(&name, &saved)?;
+        self.feed_baseline = Some((name, saved));
+        Ok(event)
+    }
+
     pub fn storage(&self) -> &dyn LedgerStorage {
         self.storage.as_ref()
     }
 
     pub fn load(&mut self, name: &str) -> Result<LoadMetadata, BudgetError> {
-        let mut ledger = self.storage.load_ledger(name)?;
-        let meta = self.process_loaded_ledger(&mut ledger)?;
+        let (ledger, meta) = self.storage.load_migrated(name)?;
+        self.prime_change_feed(name, &ledger);
+        self.audit_baseline = Some((name.to_string(), ledger.clone()));
         self.current = Some(Arc::new(RwLock::new(ledger)));
+        self.reset_journal();
         self.current_name = Some(name.to_string());
+        self.current_path = None;
+        self.read_only = false;
+        self.mark_saved();
         Ok(LoadMetadata {
             warnings: meta.warnings,
             migrations: meta.migrations,
             path: None,
             name: Some(name.to_string()),
-            schema_version: meta.original_version,
+            schema_version: meta.schema_version,
         })
     }
 
@@ -57,42 +153,161 @@ impl LedgerManager {
         let mut ledger = self.storage.load_ledger_from_path(path)?;
         let meta = self.process_loaded_ledger(&mut ledger)?;
         self.current = Some(Arc::new(RwLock::new(ledger)));
+        self.reset_journal();
         self.current_name = None;
+        self.current_path = Some(path.to_path_buf());
+        self.read_only = false;
+        self.mark_saved();
         Ok(LoadMetadata {
             warnings: meta.warnings,
             migrations: meta.migrations,
             path: Some(path.to_path_buf()),
             name: None,
-            schema_version: meta.original_version,
+            schema_version: meta.schema_version,
+        })
+    }
+
+    /// Opens the sample ledger embedded in the binary. It has no name or
+    /// path and stays read-only until saved elsewhere.
+    pub fn open_sample(&mut self) -> Result<LoadMetadata, BudgetError> {
+        let mut ledger = EmbeddedLedgerStorage::new().load_ledger(SAMPLE_LEDGER_NAME)?;
+        let meta = self.process_loaded_ledger(&mut ledger)?;
+        self.current = Some(Arc::new(RwLock::new(ledger)));
+        self.reset_journal();
+        self.current_name = None;
+        self.current_path = None;
+        self.feed_baseline = None;
+        self.read_only = true;
+        self.mark_saved();
+        Ok(LoadMetadata {
+            warnings: meta.warnings,
+            migrations: meta.migrations,
+            path: None,
+            name: None,
+            schema_version: meta.schema_version,
         })
     }
 
+    /// Whether the current ledger refuses changes.
+    pub fn is_read_only(&self) -> bool {
+        self.current.is_some() && self.read_only
+    }
+
     pub fn save(&mut self) -> Result<(), BudgetError> {
+        if self.read_only {
+            return Err(BudgetError::StorageError(READ_ONLY_HINT.into()));
+        }
         let name = self
             .current_name
             .as_deref()
             .ok_or_else(|| BudgetError::StorageError("unnamed ledger cannot be saved".into()))?;
-        {
+        let name = name.to_string();
+        self.save_named(&name)
+    }
+
+    pub fn save_as(&mut self, name: &str) -> Result<(), BudgetError> {
+        self.save_named(name)?;
+        self.current_name = Some(name.to_string());
+        self.current_path = None;
+        self.read_only = false;
+        Ok(())
+    }
+
+    /// Whether the current ledger changed since it was last loaded or saved.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.current.is_some() && self.unsaved.load(Ordering::SeqCst)
+    }
+
+    /// Records that the current ledger was written outside the manager.
+    pub fn mark_saved(&self) {
+        self.unsaved.store(false, Ordering::SeqCst);
+    }
+
+    /// Records a save to an explicit file, which from now on identifies the
+    /// ledger instead of a name.
+    pub fn saved_to_path(&mut self, path: &Path) {
+        self.current_name = None;
+        self.current_path = Some(path.to_path_buf());
+        self.read_only = false;
+        self.mark_saved();
+    }
+
+    pub fn current_path(&self) -> Option<&Path> {
+        self.current_path.as_deref()
+    }
+
+    fn save_named(&mut self, name: &str) -> Result<(), BudgetError> {
+        let saved = {
             let ledger = self.read()?;
             self.storage
                 .save_ledger(name, &ledger)
                 .map_err(BudgetError::from)?;
+            ledger.clone()
+        };
+        self.record_audit(name, &saved)?;
+        if self.change_feed {
+            let previous = self
+                .feed_baseline
+                .take()
+                .filter(|(baseline, _)| baseline == name)
+                .map(|(_, ledger)| ledger);
+            let changes = ChangeFeedService::diff(previous.as_ref(), &saved)?;
+            self.storage.append_changes(name, changes)?;
+            self.feed_baseline = Some((name.to_string(), saved));
         }
+        self.mark_saved();
         Ok(())
     }
 
-    pub fn save_as(&mut self, name: &str) -> Result<(), BudgetError> {
-        {
-            let ledger = self.read()?;
-            self.storage
-                .save_ledger(name, &ledger)
-                .map_err(BudgetError::from)?;
+    /// Uses a freshly loaded ledger as the diff base when its feed already
+    /// exists; otherwise the next save starts the feed with a snapshot.
+    fn prime_change_feed(&mut self, name: &str, ledger: &Ledger) {
+        self.feed_baseline = match self.change_feed {
+            true if matches!(self.storage.change_feed_head(name), Ok(Some(_))) => {
+                Some((name.to_string(), ledger.clone()))
+            }
+            _ => None,
+        };
+    }
+
+    /// Records a restored backup as changes against the last fed state.
+    /// Without one, the feed's state is unknown and the next save starts
+    /// over with a snapshot.
+    fn record_restore(&mut self, name: &str, restored: &Ledger) -> Result<(), BudgetError> {
+        let previous = self
+            .feed_baseline
+            .take()
+            .filter(|(baseline, _)| self.change_feed && baseline == name);
+        if let Some((_, previous)) = previous {
+            let changes = ChangeFeedService::diff(Some(&previous), restored)?;
+            self.storage.append_changes(name, changes)?;
+            self.feed_baseline = Some((name.to_string(), restored.clone()));
         }
-        self.current_name = Some(name.to_string());
         Ok(())
     }
 
-    pub fn backup(&self, note: Option<&str>) -> Result<(), BudgetError> {
+    /// Appends the records changed since the last audited state of `name`.
+    /// Without one, every record of `saved` is logged as added.
+    fn record_audit(&mut self, name: &str, saved: &Ledger) -> Result<(), BudgetError> {
+        let previous = self
+            .audit_baseline
+            .take()
+            .filter(|(baseline, _)| baseline == name)
+            .map(|(_, ledger)| ledger);
+        let changes = AuditService::changes(previous.as_ref(), saved)?;
+        self.storage
+            .append_audit(name, &self.audit_actor, changes)?;
+        self.audit_baseline = Some((name.to_string(), saved.clone()));
+        Ok(())
+    }
+
+    fn feed_name(&self) -> Result<&str, BudgetError> {
+        self.current_name.as_deref().ok_or_else(|| {
+            BudgetError::StorageError("change feeds are kept for named ledgers only".into())
+        })
+    }
+
+    pub fn backup(&self, note: Option<&str>) -> Result<LedgerBackupInfo, BudgetError> {
         let ledger = self.read()?;
         let name = self
             .current_name
@@ -100,7 +315,6 @@ impl LedgerManager {
             .ok_or_else(|| BudgetError::StorageError("current ledger is unnamed".into()))?;
         self.storage
             .backup_ledger(name, &ledger, note)
-            .map(|_| ())
             .map_err(BudgetError::from)
     }
 
@@ -128,26 +342,88 @@ impl LedgerManager {
             .restore_backup(&backup)
             .map_err(BudgetError::from)?;
         let meta = self.process_loaded_ledger(&mut ledger)?;
+        self.record_restore(name, &ledger)?;
+        self.record_audit(name, &ledger)?;
         self.current = Some(Arc::new(RwLock::new(ledger)));
+        self.reset_journal();
         self.current_name = Some(name.to_string());
+        self.current_path = None;
+        self.read_only = false;
+        self.mark_saved();
         Ok(LoadMetadata {
             warnings: meta.warnings,
             migrations: meta.migrations,
             path: None,
             name: Some(name.to_string()),
-            schema_version: meta.original_version,
+            schema_version: meta.schema_version,
         })
     }
 
+    /// Permanently removes transactions dated before `before` from the
+    /// current ledger, its backups, its audit log, and its change feed, then
+    /// saves it. Their files in `attachments_dir` are deleted too. The undo
+    /// history is cleared so nothing purged can return.
+    pub fn purge_history(
+        &mut self,
+        before: NaiveDate,
+        attachments_dir: Option<&Path>,
+    ) -> Result<PurgeOutcome, BudgetError> {
+        if self.read_only {
+            return Err(BudgetError::StorageError(READ_ONLY_HINT.into()));
+        }
+        let name = self
+            .current_name
+            .clone()
+            .ok_or_else(|| BudgetError::StorageError("only named ledgers can be purged".into()))?;
+        let report = {
+            let mut ledger = self.write()?;
+            RetentionService::purge(&mut ledger, before, attachments_dir)?
+        };
+        self.save_named(&name)?;
+
+        let mut backups_rewritten = 0;
+        for backup in self.storage.list_backups(&name)? {
+            let mut ledger = self.storage.load_ledger_from_path(&backup.path)?;
+            if RetentionService::purge(&mut ledger, before, None)?.is_empty() {
+                continue;
+            }
+            self.storage.rewrite_backup(&backup, &ledger)?;
+            backups_rewritten += 1;
+        }
+        let cutoff = before.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let audit_entries_removed = self.storage.purge_audit(&name, cutoff)?;
+        let change_feed_compacted = matches!(self.storage.change_feed_head(&name), Ok(Some(_)));
+        if change_feed_compacted {
+            self.compact_change_feed()?;
+        }
+        self.reset_journal();
+        Ok(PurgeOutcome {
+            report,
+            backups_rewritten,
+            audit_entries_removed,
+            change_feed_compacted,
+        })
+    }
+
+    /// Replaces the current ledger, which counts as unsaved until written.
     pub fn set_current(&mut self, ledger: Ledger, path: Option<PathBuf>, name: Option<String>) {
-        let _ = path;
+        self.unsaved.store(true, Ordering::SeqCst);
         self.current = Some(Arc::new(RwLock::new(ledger)));
+        self.reset_journal();
         self.current_name = name;
+        self.current_path = path;
+        self.feed_baseline = None;
+        self.read_only = false;
     }
 
     pub fn clear(&mut self) {
         self.current = None;
+        self.reset_journal();
         self.current_name = None;
+        self.current_path = None;
+        self.feed_baseline = None;
+        self.read_only = false;
+        self.mark_saved();
     }
 
     pub fn current_name(&self) -> Option<&str> {
@@ -178,6 +454,52 @@ impl LedgerManager {
         Ok(f(&mut ledger))
     }
 
+    /// Like [`Self::with_current_mut`], also lending the change journal so
+    /// the edit can be undone.
+    pub fn with_current_journaled<T, F>(&self, f: F) -> Result<T, BudgetError>
+    where
+        F: FnOnce(&mut Ledger, &mut ChangeJournal) -> T,
+    {
+        let mut ledger = self.write()?;
+        let mut journal = self.journal()?;
+        Ok(f(&mut ledger, &mut journal))
+    }
+
+    /// Reverts the latest journaled edit, returning its description, or
+    /// `None` when there is nothing to undo.
+    pub fn undo(&self) -> Result<Option<String>, BudgetError> {
+        let mut ledger = self.write()?;
+        let mut journal = self.journal()?;
+        journal.undo(&mut ledger).map_err(BudgetError::from)
+    }
+
+    /// Applies the latest undone edit again, returning its description, or
+    /// `None` when there is nothing to redo.
+    pub fn redo(&self) -> Result<Option<String>, BudgetError> {
+        let mut ledger = self.write()?;
+        let mut journal = self.journal()?;
+        journal.redo(&mut ledger).map_err(BudgetError::from)
+    }
+
+    /// Descriptions of the edits that can be undone (newest first) and
+    /// redone (next first).
+    pub fn journal_history(&self) -> Result<(Vec<String>, Vec<String>), BudgetError> {
+        let journal = self.journal()?;
+        Ok((journal.undo_history(), journal.redo_history()))
+    }
+
+    fn journal(&self) -> Result<MutexGuard<'_, ChangeJournal>, BudgetError> {
+        self.journal
+            .lock()
+            .map_err(|_| BudgetError::StorageError("journal lock poisoned".into()))
+    }
+
+    fn reset_journal(&mut self) {
+        if let Ok(journal) = self.journal.get_mut() {
+            journal.clear();
+        }
+    }
+
     pub fn current_handle(&self) -> Option<Arc<RwLock<Ledger>>> {
         self.current.as_ref().map(Arc::clone)
     }
@@ -191,45 +513,39 @@ impl LedgerManager {
 
     pub fn write(&self) -> Result<RwLockWriteGuard<'_, Ledger>, BudgetError> {
         let handle = self.current.as_ref().ok_or(BudgetError::LedgerNotLoaded)?;
-        handle
+        if self.read_only {
+            return Err(BudgetError::StorageError(READ_ONLY_HINT.into()));
+        }
+        let guard = handle
             .write()
-            .map_err(|_| BudgetError::StorageError("ledger lock poisoned".into()))
+            .map_err(|_| BudgetError::StorageError("ledger lock poisoned".into()))?;
+        self.unsaved.store(true, Ordering::SeqCst);
+        Ok(guard)
     }
 
-    fn process_loaded_ledger(&self, ledger: &mut Ledger) -> Result<LoadEffects, BudgetError> {
-        let original_version = ledger.schema_version;
-        self.ensure_schema_support(original_version)?;
-        let migrations = ledger.migrate_from_schema(original_version);
-        ledger.refresh_recurrence_metadata();
-        let warnings = ledger_warnings(ledger);
-        Ok(LoadEffects {
-            original_version,
-            migrations,
-            warnings,
-        })
-    }
-
-    fn ensure_schema_support(&self, schema_version: u8) -> Result<(), BudgetError> {
-        if schema_version > CURRENT_SCHEMA_VERSION {
-            return Err(BudgetError::StorageError(format!(
-                "ledger schema v{} is newer than supported v{}",
-                schema_version, CURRENT_SCHEMA_VERSION
-            )));
-        }
-        Ok(())
+    fn process_loaded_ledger(&self, ledger: &mut Ledger) -> Result<LoadReport, BudgetError> {
+        prepare_loaded_ledger(ledger).map_err(BudgetError::from)
     }
 }
 
-struct LoadEffects {
-    original_version: u8,
-    migrations: Vec<String>,
-    warnings: Vec<String>,
+/// Login name of the user running the process, for audit entries.
+fn default_audit_actor() -> String {
+    ["USER", "USERNAME"]
+        .iter()
+        .find_map(|key| {
+            std::env::var(key)
+                .ok()
+                .filter(|value| !value.trim().is_empty())
+        })
+        .unwrap_or_else(|| "unknown".into())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ledger::BudgetPeriod;
+    use bufy_core::LedgerChange;
+    use bufy_domain::{CURRENT_SCHEMA_MINOR, CURRENT_SCHEMA_VERSION};
     use bufy_storage_json::{JsonLedgerStorage as JsonStorage, StoragePaths};
     use std::fs;
     use tempfile::tempdir;
@@ -272,7 +588,36 @@ mod tests {
             .expect("create backup");
         let backups = manager.list_backups("household-budget").unwrap();
         assert!(!backups.is_empty());
-        assert!(backups[0].id.starts_with("household_budget_"));
+        assert!(backups[0].id.starts_with("household_budget-"));
+    }
+
+    #[test]
+    fn tracks_unsaved_changes_between_saves() {
+        let temp = tempdir().unwrap();
+        let mut manager = LedgerManager::new(Box::new(temp_storage(&temp)));
+        assert!(!manager.has_unsaved_changes());
+
+        let ledger = Ledger::new("Draft", BudgetPeriod::monthly());
+        manager.set_current(ledger, None, Some("draft".into()));
+        assert!(manager.has_unsaved_changes());
+        manager.save().unwrap();
+        assert!(!manager.has_unsaved_changes());
+
+        manager.with_current(|ledger| ledger.name.clone()).unwrap();
+        assert!(!manager.has_unsaved_changes());
+        manager
+            .with_current_mut(|ledger| ledger.name = "Renamed".into())
+            .unwrap();
+        assert!(manager.has_unsaved_changes());
+
+        manager.load("draft").unwrap();
+        assert!(!manager.has_unsaved_changes());
+        manager.write().unwrap().touch();
+        let path = temp.path().join("draft.json");
+        manager.saved_to_path(&path);
+        assert!(!manager.has_unsaved_changes());
+        assert_eq!(manager.current_path(), Some(path.as_path()));
+        assert!(manager.current_name().is_none());
     }
 
     #[test]
@@ -297,6 +642,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn newer_minor_schema_loads_with_warning_and_keeps_unknown_fields() {
+        let temp = tempdir().unwrap();
+        let store = temp_storage(&temp);
+        let mut manager = LedgerManager::new(Box::new(store));
+
+        let path = temp.path().join("newer-minor.json");
+        let ledger = Ledger::new("Newer", BudgetPeriod::monthly());
+        let mut value = serde_json::to_value(&ledger).unwrap();
+        value["schema_minor"] = serde_json::json!(CURRENT_SCHEMA_MINOR + 2);
+        value["wishlists"] = serde_json::json!([{ "name": "Holiday" }]);
+        fs::write(&path, serde_json::to_string(&value).unwrap()).unwrap();
+
+        let metadata = manager.load_from_path(&path).expect("newer minor loads");
+        assert!(
+            metadata
+                .warnings
+                .iter()
+                .any(|warning| warning.contains("newer BUFY")),
+            "missing warning: {:?}",
+            metadata.warnings
+        );
+        manager.save_as("newer-minor").expect("save ledger");
+        manager.clear();
+        manager.load("newer-minor").expect("reload");
+        let (minor, wishlists) = manager
+            .with_current(|ledger| (ledger.schema_minor, ledger.extra.get("wishlists").cloned()))
+            .unwrap();
+        assert_eq!(minor, CURRENT_SCHEMA_MINOR + 2);
+        assert_eq!(wishlists, Some(serde_json::json!([{ "name": "Holiday" }])));
+    }
+
     #[test]
     fn with_current_helpers_access_loaded_ledger() {
         let temp = tempdir().unwrap();
@@ -318,4 +695,37 @@ mod tests {
             .expect("ledger present");
         assert_eq!(updated, "Helpers Updated");
     }
+
+    #[test]
+    fn change_feed_records_saves_and_compacts() {
+        let temp = tempdir().unwrap();
+        let store = temp_storage(&temp);
+        let mut manager = LedgerManager::new(Box::new(store));
+        manager.set_current(Ledger::new("Feed", BudgetPeriod::monthly()), None, None);
+        manager.save_as("feed").expect("save without feed");
+        assert!(manager.change_feed_events(0).unwrap().is_empty());
+
+        manager.set_change_feed(true);
+        manager.save().expect("first feed save");
+        manager
+            .with_current_mut(|ledger| ledger.name = "Feed Renamed".into())
+            .unwrap();
+        manager.save().expect("second feed save");
+        manager.clear();
+        manager.load("feed").expect("reload");
+        manager.save().expect("unchanged save");
+
+        let events = manager.change_feed_events(0).unwrap();
+        assert!(matches!(events[0].change, LedgerChange::Snapshot { .. }));
+        assert!(events[1..].iter().any(|event| matches!(
+            &event.change,
+            LedgerChange::FieldChanged { field, .. } if field == "name"
+        )));
+        let replayed = ChangeFeedService::replay(&events).unwrap();
+        assert_eq!(replayed.name, "Feed Renamed");
+
+        let snapshot = manager.compact_change_feed().expect("compact");
+        assert_eq!(snapshot.seq, events.last().unwrap().seq + 1);
+        assert_eq!(manager.change_feed_events(0).unwrap(), vec![snapshot]);
+    }
 }
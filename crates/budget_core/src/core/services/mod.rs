@@ -3,8 +3,17 @@ pub use crate::ledger::{
     CategoryBudgetSummaryKind,
 };
 pub use bufy_core::{
-    AccountService, BudgetService, CategoryService, ForecastService, LedgerService,
-    RecurrenceService, SimulationService, SummaryService, TransactionService,
+    AccountProjection, AccountService, AlertService, AllocationPlan, AllocationService,
+    AssetReport, AssetService, AssetStatus, AttachmentService, AttachmentStatus, BalanceForecast,
+    BudgetService, CategorizationService, CategoryGroupService, CategoryService, ContractService,
+    ContractStage, ContractWindow, DraftService, ExchangeRateService, ExportService,
+    ForecastService, GoalProgress, GoalService, HeatmapService, HolidayService, ImportService,
+    IncomeService, LedgerService, MemberService, MerchantService, PaycheckLineKind,
+    PaycheckService, PeriodForecast, ReconciliationReport, ReconciliationService,
+    RecurrenceService, RetentionService, ReviewItem, ReviewService, SimulationService,
+    SinkingFundService, SubLedgerService, SummaryService, TransactionQuery, TransactionService,
+    TransactionSort, TransactionSortKey, TransferRuleService, UncategorizedReport,
+    UncategorizedService, ViewService,
 };
 
 pub type ServiceError = bufy_core::CoreError;
@@ -1,6 +1,8 @@
 use chrono::Utc;
 use tracing::warn;
 
+use bufy_domain::Money;
+
 use crate::{
     core::errors::BudgetError,
     ledger::{Ledger, Transaction},
@@ -83,7 +85,7 @@ impl SimulationEngine {
                                 patch.transaction_id
                             ))
                         })?;
-                    apply_patch(txn, patch);
+                    apply_patch(txn, patch)?;
                 }
                 SimulationChange::ExcludeTransaction { transaction_id } => {
                     let before = transactions.len();
@@ -95,13 +97,25 @@ impl SimulationEngine {
                         )));
                     }
                 }
+                SimulationChange::ModifyIncome(patch) => {
+                    if !patch.apply(transactions) {
+                        return Err(BudgetError::InvalidReference(format!(
+                            "income transaction {} not found",
+                            patch.transaction_id
+                        )));
+                    }
+                }
             }
         }
         Ok(())
     }
 }
 
-fn apply_patch(txn: &mut Transaction, patch: &SimulationTransactionPatch) {
+fn apply_patch(
+    txn: &mut Transaction,
+    patch: &SimulationTransactionPatch,
+) -> Result<(), BudgetError> {
+    let currency = txn.budgeted_amount.currency().clone();
     if let Some(account) = patch.from_account {
         txn.from_account = account;
     }
@@ -118,9 +132,12 @@ fn apply_patch(txn: &mut Transaction, patch: &SimulationTransactionPatch) {
         txn.actual_date = actual_date;
     }
     if let Some(amount) = patch.budgeted_amount {
-        txn.budgeted_amount = amount;
+        txn.budgeted_amount = Money::from_major(amount, currency.clone())?;
     }
     if let Some(amount) = patch.actual_amount {
-        txn.actual_amount = amount;
+        txn.actual_amount = amount
+            .map(|amount| Money::from_major(amount, currency))
+            .transpose()?;
     }
+    Ok(())
 }
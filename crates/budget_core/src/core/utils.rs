@@ -1,22 +1,117 @@
 use std::{
     env, fs,
     path::{Path, PathBuf},
+    sync::RwLock,
 };
 
 use crate::core::errors::BudgetError;
 
+/// File whose presence next to the executable turns on portable mode.
+pub const PORTABLE_MARKER: &str = "bufy.portable";
+
+static STRATEGY: RwLock<Option<PathStrategy>> = RwLock::new(None);
+
+/// Where config, ledgers, and backups are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathStrategy {
+    /// Config under `~/.budget_core` (or `BUDGET_CORE_HOME`); ledgers and
+    /// backups in the configured roots.
+    Home,
+    /// Everything inside one folder, e.g. on a USB stick.
+    Portable(PathBuf),
+}
+
+impl PathStrategy {
+    /// Portable when the executable's folder holds [`PORTABLE_MARKER`] and
+    /// `BUDGET_CORE_HOME` is not set; otherwise [`PathStrategy::Home`].
+    pub fn detect() -> Self {
+        if PathResolver::home_override().is_some() {
+            return PathStrategy::Home;
+        }
+        match PathResolver::executable_dir() {
+            Some(dir) if dir.join(PORTABLE_MARKER).is_file() => PathStrategy::Portable(dir),
+            _ => PathStrategy::Home,
+        }
+    }
+
+    /// Marks `dir` as a portable install, creating it when needed.
+    pub fn portable_in(dir: PathBuf) -> Result<Self, BudgetError> {
+        ensure_dir(&dir)?;
+        let marker = dir.join(PORTABLE_MARKER);
+        if !marker.exists() {
+            fs::write(&marker, "").map_err(|err| {
+                BudgetError::StorageError(format!(
+                    "failed to create `{}`: {}",
+                    marker.display(),
+                    err
+                ))
+            })?;
+        }
+        Ok(PathStrategy::Portable(dir))
+    }
+}
+
 pub struct PathResolver;
 
 impl PathResolver {
+    /// The strategy in effect: the one set with [`PathResolver::set_strategy`],
+    /// else [`PathStrategy::detect`].
+    pub fn strategy() -> PathStrategy {
+        STRATEGY
+            .read()
+            .ok()
+            .and_then(|strategy| strategy.clone())
+            .unwrap_or_else(PathStrategy::detect)
+    }
+
+    pub fn set_strategy(strategy: PathStrategy) {
+        if let Ok(mut current) = STRATEGY.write() {
+            *current = Some(strategy);
+        }
+    }
+
+    pub fn executable_dir() -> Option<PathBuf> {
+        env::current_exe()
+            .ok()?
+            .parent()
+            .map(|dir| dir.to_path_buf())
+    }
+
     pub fn base_dir() -> PathBuf {
-        if let Some(custom) = env::var_os("BUDGET_CORE_HOME") {
-            return PathBuf::from(custom);
+        if let PathStrategy::Portable(dir) = Self::strategy() {
+            return dir;
+        }
+        if let Some(custom) = Self::home_override() {
+            return custom;
         }
         dirs::home_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join(".budget_core")
     }
 
+    /// Folder that also holds ledgers and backups when no root is configured:
+    /// the portable folder or `BUDGET_CORE_HOME`.
+    pub fn data_root() -> Option<PathBuf> {
+        match Self::strategy() {
+            PathStrategy::Portable(dir) => Some(dir),
+            PathStrategy::Home => Self::home_override(),
+        }
+    }
+
+    /// Resolves a configured ledger or backup root. Portable installs read
+    /// relative roots from the portable folder, so the data follows the drive.
+    pub fn configured_root(root: &Path) -> PathBuf {
+        match Self::strategy() {
+            PathStrategy::Portable(dir) if root.is_relative() => dir.join(root),
+            _ => root.to_path_buf(),
+        }
+    }
+
+    /// Returns the explicit `BUDGET_CORE_HOME` override, when one is set.
+    pub fn home_override() -> Option<PathBuf> {
+        env::var_os("BUDGET_CORE_HOME").map(PathBuf::from)
+    }
+
     pub fn resolve_base(root: Option<PathBuf>) -> PathBuf {
         root.unwrap_or_else(Self::base_dir)
     }
@@ -1,4 +1,5 @@
 use super::time_interval::TimeInterval;
+use bufy_domain::ids::new_id;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -15,7 +16,7 @@ pub struct Budget {
 impl Budget {
     pub fn new(category_id: Uuid, limit_amount: f64, recurrence: TimeInterval) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: new_id(),
             category_id,
             limit_amount,
             recurrence,
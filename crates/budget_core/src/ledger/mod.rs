@@ -8,19 +8,22 @@ pub mod recurring;
 pub mod time_interval;
 pub mod transaction;
 
-pub use account::{Account, AccountKind};
+pub use account::{Account, AccountKind, AccountStatus};
 pub use budget::Budget;
 pub use bufy_domain::{
+    appearance::{Appearance, EntityColor},
+    business_days::BusinessDayShift,
     ledger::{
         AccountBudget, BudgetScope, BudgetStatus, BudgetSummary, BudgetTotals, BudgetTotalsDelta,
         CategoryBudget, CategoryBudgetAssignment, CategoryBudgetStatus, CategoryBudgetSummary,
-        CategoryBudgetSummaryKind, DateWindow,
+        CategoryBudgetSummaryKind, CategoryGroupSummary, DateWindow,
     },
     ledger_data::{
         ConversionContext, CurrencyConversionError, ForecastReport, Ledger, LedgerBudgetPeriod,
     },
+    money::Money,
     simulation::{
-        Simulation, SimulationBudgetImpact, SimulationChange, SimulationStatus,
+        IncomeModelPatch, Simulation, SimulationBudgetImpact, SimulationChange, SimulationStatus,
         SimulationTransactionPatch,
     },
 };
@@ -31,6 +34,7 @@ pub use recurring::{
 };
 pub use time_interval::{TimeInterval, TimeUnit};
 pub use transaction::{
-    Recurrence, RecurrenceEnd, RecurrenceMode, RecurrenceStatus, Transaction, TransactionStatus,
+    IncomeModel, Recurrence, RecurrenceEnd, RecurrenceMode, RecurrenceStatus, Transaction,
+    TransactionStatus,
 };
 pub use LedgerBudgetPeriod as BudgetPeriod;
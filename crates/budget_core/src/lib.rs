@@ -24,6 +24,25 @@ pub fn init() {
     });
 }
 
+/// Like [`init`], but also logs debug events and span timings.
+pub fn init_verbose() {
+    INIT_TRACING.call_once(|| {
+        utils::init_verbose_tracing();
+        tracing::info!("Budget Core tracing initialized (verbose).");
+    });
+}
+
+/// Initializes tracing from the command-line log settings. Fails only when
+/// the log file cannot be opened.
+pub fn init_with(settings: &utils::LogSettings) -> std::io::Result<()> {
+    let mut result = Ok(());
+    INIT_TRACING.call_once(|| {
+        result = utils::init_tracing_with(settings);
+        tracing::info!("Budget Core tracing initialized.");
+    });
+    result
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -1,17 +1,160 @@
 pub mod build_info;
 
-use std::{io, sync::Once};
+use std::{
+    fmt,
+    fs::OpenOptions,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    sync::{Mutex, Once},
+};
+
+use tracing::field::{Field, Visit};
+use tracing_subscriber::{
+    field::RecordFields,
+    fmt::{format::Writer, FormatFields},
+};
 
 static TRACING_INIT: Once = Once::new();
 
+/// Field names whose values are written as `[redacted]` unless
+/// [`LogSettings::sensitive`] is set: money amounts, free-text notes, and
+/// the raw command line, which can hold both.
+pub const REDACTED_FIELDS: &[&str] = &[
+    "line",
+    "amount",
+    "actual_amount",
+    "budgeted_amount",
+    "balance",
+    "notes",
+    "note",
+    "memo",
+];
+
+/// Where tracing output goes and how much of it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LogSettings {
+    /// Debug events and span timings instead of info only.
+    pub verbose: bool,
+    /// Append to this file instead of writing to stderr. File logs always
+    /// include the service spans with their durations.
+    pub file: Option<PathBuf>,
+    /// Keep [`REDACTED_FIELDS`] values in the output.
+    pub sensitive: bool,
+}
+
 /// Initializes the global tracing subscriber with sensible defaults.
 pub fn init_tracing() {
-    TRACING_INIT.call_once(|| {
-        use tracing_subscriber::{fmt, EnvFilter};
+    let _ = init_tracing_with(&LogSettings::default());
+}
 
-        let filter =
-            EnvFilter::from_default_env().add_directive("budget_core=info".parse().unwrap());
+/// Initializes tracing for `--verbose` runs: debug events from this crate,
+/// plus a line with the elapsed time whenever a span closes.
+pub fn init_verbose_tracing() {
+    let _ = init_tracing_with(&LogSettings {
+        verbose: true,
+        ..LogSettings::default()
+    });
+}
+
+/// Initializes the global subscriber from `settings`. Fails only when the
+/// log file cannot be opened; later calls do nothing.
+pub fn init_tracing_with(settings: &LogSettings) -> io::Result<()> {
+    use tracing_subscriber::{fmt, fmt::format::FmtSpan, EnvFilter};
 
-        fmt().with_env_filter(filter).with_writer(io::stderr).init();
+    let file = match &settings.file {
+        Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+        None => None,
+    };
+    TRACING_INIT.call_once(|| {
+        let level = if settings.verbose || file.is_some() {
+            "debug"
+        } else {
+            "info"
+        };
+        let filter = EnvFilter::from_default_env()
+            .add_directive(format!("budget_core={level}").parse().unwrap())
+            .add_directive(format!("bufy_core={level}").parse().unwrap());
+        let builder = fmt()
+            .with_env_filter(filter)
+            .fmt_fields(RedactingFields::new(!settings.sensitive));
+
+        match file {
+            Some(file) => builder
+                .with_span_events(FmtSpan::CLOSE)
+                .with_ansi(false)
+                .with_writer(Mutex::new(file))
+                .init(),
+            None if settings.verbose => builder
+                .with_span_events(FmtSpan::CLOSE)
+                .with_ansi(io::stderr().is_terminal())
+                .with_writer(io::stderr)
+                .init(),
+            None => builder.with_writer(io::stderr).init(),
+        }
     });
+    Ok(())
+}
+
+/// Formats event and span fields as `name=value`, replacing the values of
+/// [`REDACTED_FIELDS`] when `redact` is set.
+#[derive(Debug, Clone, Copy)]
+pub struct RedactingFields {
+    redact: bool,
+}
+
+impl RedactingFields {
+    pub fn new(redact: bool) -> Self {
+        Self { redact }
+    }
+}
+
+impl<'writer> FormatFields<'writer> for RedactingFields {
+    fn format_fields<R: RecordFields>(&self, writer: Writer<'writer>, fields: R) -> fmt::Result {
+        let mut visitor = RedactingVisitor {
+            writer,
+            redact: self.redact,
+            first: true,
+            result: Ok(()),
+        };
+        fields.record(&mut visitor);
+        visitor.result
+    }
+}
+
+struct RedactingVisitor<'writer> {
+    writer: Writer<'writer>,
+    redact: bool,
+    first: bool,
+    result: fmt::Result,
+}
+
+impl RedactingVisitor<'_> {
+    fn write(&mut self, args: fmt::Arguments<'_>) {
+        if self.result.is_err() {
+            return;
+        }
+        let separator = if self.first { "" } else { " " };
+        self.first = false;
+        self.result = write!(self.writer, "{}{}", separator, args);
+    }
+}
+
+impl Visit for RedactingVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.write(format_args!("{}", value));
+        } else {
+            self.record_debug(field, &value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => self.write(format_args!("{:?}", value)),
+            name if self.redact && REDACTED_FIELDS.contains(&name) => {
+                self.write(format_args!("{}=[redacted]", name))
+            }
+            name => self.write(format_args!("{}={:?}", name, value)),
+        }
+    }
 }
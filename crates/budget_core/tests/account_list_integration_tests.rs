@@ -37,7 +37,7 @@ fn build_context(temp: &TempDir) -> ShellContext {
     ));
     let config = Arc::new(RwLock::new(Config::default()));
     let formatters = CliFormatters::new(config.clone());
-    let clock: Arc<dyn Clock> = Arc::new(SystemClock::default());
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
     ShellContext {
         mode: CliMode::Script,
         registry: CommandRegistry::new(),
@@ -52,7 +52,11 @@ fn build_context(temp: &TempDir) -> ShellContext {
         active_simulation_name: None,
         current_simulation: None,
         last_command: None,
+        recent_commands: Vec::new(),
         running: true,
+        failed_assertions: 0,
+        verbose: false,
+        last_transaction_date: None,
         ui_style: style::style(),
     }
 }
@@ -82,7 +86,7 @@ fn delete_action_removes_account() {
         vec![vec![KeyCode::Enter], vec![KeyCode::Esc]],
         vec![vec![KeyCode::Down, KeyCode::Enter]],
     );
-    list_accounts::run_list_accounts(&mut context).unwrap();
+    list_accounts::run_list_accounts(&mut context, false).unwrap();
 
     let manager = context.ledger_manager.read().unwrap();
     let handle = manager.current_handle().expect("ledger loaded");
@@ -98,7 +102,7 @@ fn escape_exits_without_changes() {
     set_loaded_ledger(&mut context, ledger);
 
     let _script = TestModeScript::new(vec![vec![KeyCode::Esc]], Vec::new());
-    list_accounts::run_list_accounts(&mut context).unwrap();
+    list_accounts::run_list_accounts(&mut context, false).unwrap();
 
     let manager = context.ledger_manager.read().unwrap();
     let handle = manager.current_handle().expect("ledger loaded");
@@ -36,7 +36,7 @@ fn build_context(temp: &TempDir) -> ShellContext {
     ));
     let config = Arc::new(RwLock::new(Config::default()));
     let formatters = CliFormatters::new(config.clone());
-    let clock: Arc<dyn Clock> = Arc::new(SystemClock::default());
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
     ShellContext {
         mode: CliMode::Script,
         registry: CommandRegistry::new(),
@@ -51,7 +51,11 @@ fn build_context(temp: &TempDir) -> ShellContext {
         active_simulation_name: None,
         current_simulation: None,
         last_command: None,
+        recent_commands: Vec::new(),
         running: true,
+        failed_assertions: 0,
+        verbose: false,
+        last_transaction_date: None,
         ui_style: style::style(),
     }
 }
@@ -3,8 +3,13 @@ use budget_core::ledger::{
     Category, DateWindow, Ledger, TimeInterval, TimeUnit, Transaction,
 };
 use bufy_core::BudgetService;
+use bufy_domain::{currency::CurrencyCode, Money};
 use chrono::NaiveDate;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 fn sample_date(y: i32, m: u32, d: u32) -> NaiveDate {
     NaiveDate::from_ymd_opt(y, m, d).unwrap()
 }
@@ -28,10 +33,10 @@ fn summarizes_budgeted_vs_real_by_period() {
         savings,
         Some(groceries),
         sample_date(2025, 1, 5),
-        200.0,
+        usd(200.0),
     );
     txn1.actual_date = Some(sample_date(2025, 1, 6));
-    txn1.actual_amount = Some(180.0);
+    txn1.actual_amount = Some(usd(180.0));
     ledger.add_transaction(txn1);
 
     let txn2 = Transaction::new(
@@ -39,7 +44,7 @@ fn summarizes_budgeted_vs_real_by_period() {
         savings,
         Some(groceries),
         sample_date(2025, 1, 20),
-        150.0,
+        usd(150.0),
     );
     // pending actuals
     ledger.add_transaction(txn2);
@@ -49,10 +54,10 @@ fn summarizes_budgeted_vs_real_by_period() {
         savings,
         Some(groceries),
         sample_date(2025, 2, 2),
-        120.0,
+        usd(120.0),
     );
     txn3.actual_date = Some(sample_date(2025, 2, 3));
-    txn3.actual_amount = Some(140.0);
+    txn3.actual_amount = Some(usd(140.0));
     ledger.add_transaction(txn3);
 
     let january = BudgetService::summarize_period_containing(&ledger, sample_date(2025, 1, 15));
@@ -80,9 +85,9 @@ fn summarizes_custom_range() {
     );
     let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
 
-    let mut txn = Transaction::new(checking, checking, None, sample_date(2025, 3, 1), 50.0);
+    let mut txn = Transaction::new(checking, checking, None, sample_date(2025, 3, 1), usd(50.0));
     txn.actual_date = Some(sample_date(2025, 3, 1));
-    txn.actual_amount = Some(55.0);
+    txn.actual_amount = Some(usd(55.0));
     ledger.add_transaction(txn);
 
     let window =
@@ -92,3 +97,47 @@ fn summarizes_custom_range() {
     assert_eq!(summary.totals.real, 55.0);
     assert_eq!(summary.scope, BudgetScope::Custom);
 }
+
+#[test]
+fn weekly_windows_follow_locale_first_weekday() {
+    let mut ledger = Ledger::new(
+        "Weekly",
+        BudgetPeriod(TimeInterval {
+            every: 1,
+            unit: TimeUnit::Week,
+        }),
+    );
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    // 2025-01-08 is a Wednesday.
+    ledger.add_transaction(Transaction::new(
+        checking,
+        checking,
+        None,
+        sample_date(2025, 1, 8),
+        usd(10.0),
+    ));
+
+    let monday_window = ledger.budget_window_containing(sample_date(2025, 1, 10));
+    assert_eq!(monday_window.start, sample_date(2025, 1, 6));
+
+    ledger.locale.first_weekday = chrono::Weekday::Sun;
+    let sunday_window = ledger.budget_window_containing(sample_date(2025, 1, 10));
+    assert_eq!(sunday_window.start, sample_date(2025, 1, 5));
+    assert_eq!(sunday_window.end, sample_date(2025, 1, 12));
+}
+
+#[test]
+fn locale_weekend_drives_business_day_shifting() {
+    let mut ledger = Ledger::new("Weekend", BudgetPeriod::default());
+    ledger.locale.weekend = vec![chrono::Weekday::Fri, chrono::Weekday::Sat];
+
+    // 2025-01-10 is a Friday.
+    let friday = sample_date(2025, 1, 10);
+    assert!(ledger.locale.is_weekend(friday));
+    assert!(!ledger.locale.is_weekend(sample_date(2025, 1, 12)));
+    assert_eq!(ledger.next_business_day(friday), sample_date(2025, 1, 12));
+    assert_eq!(
+        ledger.previous_business_day(friday),
+        sample_date(2025, 1, 9)
+    );
+}
@@ -2,8 +2,13 @@ use bufy_domain::{
     category::{Category, CategoryBudgetDefinition, CategoryKind},
     BudgetPeriod,
 };
+use bufy_domain::{currency::CurrencyCode, Money};
 use chrono::NaiveDate;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 #[test]
 fn category_budget_defaults_to_none() {
     let category = Category::new("Travel", CategoryKind::Expense);
@@ -15,10 +20,10 @@ fn category_budget_defaults_to_none() {
 fn category_budget_can_be_assigned_and_cleared() {
     let mut category = Category::new("Housing", CategoryKind::Expense);
     let reference = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
-    category.set_budget(1250.0, BudgetPeriod::Monthly, Some(reference));
+    category.set_budget(usd(1250.0), BudgetPeriod::Monthly, Some(reference));
 
     let budget = category.budget().expect("budget assigned");
-    assert!((budget.amount - 1250.0).abs() < f64::EPSILON);
+    assert!((budget.amount.to_major() - 1250.0).abs() < f64::EPSILON);
     assert_eq!(budget.period, BudgetPeriod::Monthly);
     assert_eq!(budget.reference_date, Some(reference));
     assert!(category.has_budget());
@@ -30,7 +35,7 @@ fn category_budget_can_be_assigned_and_cleared() {
 #[test]
 fn category_budget_survives_serialization_roundtrip() {
     let mut category = Category::new("Subscriptions", CategoryKind::Expense);
-    let definition = CategoryBudgetDefinition::new(89.99, BudgetPeriod::Monthly)
+    let definition = CategoryBudgetDefinition::new(usd(89.99), BudgetPeriod::Monthly)
         .with_reference_date(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
     category.set_budget_definition(definition.clone());
 
@@ -40,3 +45,17 @@ fn category_budget_survives_serialization_roundtrip() {
     let restored: Category = serde_json::from_str(&json).expect("deserialize");
     assert_eq!(restored.budget(), Some(&definition));
 }
+
+#[test]
+fn category_budget_note_survives_reassignment() {
+    let mut category = Category::new("Sports", CategoryKind::Expense);
+    category.set_budget_definition(
+        CategoryBudgetDefinition::new(usd(80.0), BudgetPeriod::Monthly)
+            .with_note("includes gym + sports gear"),
+    );
+    category.set_budget(usd(90.0), BudgetPeriod::Monthly, None);
+
+    let budget = category.budget().expect("budget assigned");
+    assert!((budget.amount.to_major() - 90.0).abs() < f64::EPSILON);
+    assert_eq!(budget.note.as_deref(), Some("includes gym + sports gear"));
+}
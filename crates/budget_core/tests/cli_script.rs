@@ -1,14 +1,24 @@
 use assert_cmd::Command;
 use budget_core::ledger::{
+    category::{Category, CategoryKind},
     transaction::{Recurrence, RecurrenceMode},
     Account, AccountKind, BudgetPeriod, Ledger, TimeInterval, TimeUnit, Transaction,
+    TransactionStatus,
 };
-use bufy_storage_json::save_ledger_to_path;
+use bufy_domain::{currency::CurrencyCode, Money};
+use bufy_storage_json::{load_ledger_from_path, save_ledger_to_path};
 use chrono::NaiveDate;
-use predicates::{prelude::PredicateBooleanExt, str::contains};
+use predicates::{
+    prelude::PredicateBooleanExt,
+    str::{contains, is_match},
+};
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 #[test]
 fn script_mode_runs_basic_flow() {
     let home = tempfile::tempdir().unwrap();
@@ -37,7 +47,7 @@ fn forecast_command_outputs_projection() {
     let from = ledger.add_account(Account::new("Checking", AccountKind::Bank));
     let to = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
     let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
-    let mut txn = Transaction::new(from, to, None, start, 900.0);
+    let mut txn = Transaction::new(from, to, None, start, usd(900.0));
     txn.set_recurrence(Some(Recurrence::new(
         start,
         TimeInterval {
@@ -122,3 +132,3114 @@ exit
                 .and(contains("No category budgets configured")),
         );
 }
+
+#[test]
+fn category_groups_roll_up_in_the_summary() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Groups", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let store = ledger.add_account(Account::new("Store", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+    for (name, budget, spent) in [("Rent", 800.0, 800.0), ("Groceries", 300.0, 260.0)] {
+        let mut category = Category::new(name, CategoryKind::Expense);
+        category.set_budget(usd(budget), bufy_domain::BudgetPeriod::Monthly, None);
+        let category = ledger.add_category(category);
+        let mut txn = Transaction::new(checking, store, Some(category), date, usd(spent));
+        txn.mark_completed(date, usd(spent));
+        ledger.add_transaction(txn);
+    }
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\ncategory group add Essentials\ncategory group assign essentials Rent\ncategory group assign Essentials Groceries\ncategory group budget Essentials 1000 --period monthly\ncategory group list\nsummary custom 2025-03-01 2025-03-31 --expand-groups\ncategory group assign Lifestyle Rent\nledger save\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("Category group `Essentials` added.")
+                .and(contains("`Groceries` is now in the `Essentials` group."))
+                .and(contains("Budget for group `Essentials` set to"))
+                .and(contains("Categories: Rent, Groceries"))
+                .and(contains("Category Groups"))
+                .and(contains("❌ Essentials"))
+                .and(contains("2 categories"))
+                .and(contains("      Rent"))
+                .and(contains("      Groceries"))
+                .and(contains("category group `Lifestyle` not found")),
+        );
+
+    let saved = load_ledger_from_path(tmp.path()).unwrap();
+    let group = saved.category_group("Essentials").unwrap();
+    assert_eq!(group.category_ids.len(), 2);
+    assert_eq!(
+        group.budget.as_ref().map(|budget| budget.amount.to_major()),
+        Some(1000.0)
+    );
+}
+
+#[test]
+fn sequential_test_ids_make_runs_repeatable() {
+    let run = || {
+        let home = tempfile::tempdir().unwrap();
+        let script = "\
+ledger new Repeat monthly
+account add Checking bank
+account add Grocer expense
+transaction add 0 1 2025-03-04 42
+list transactions --json
+list transactions
+exit
+";
+        let output = Command::cargo_bin("budget_core_cli")
+            .unwrap()
+            .env("BUDGET_CORE_CLI_SCRIPT", "1")
+            .env("BUDGET_CORE_HOME", home.path())
+            .env("BUFY_TEST_IDS", "sequential")
+            .write_stdin(script)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let first = run();
+    assert!(
+        first.contains("\"id\": \"00000000-0000-0000-0000-"),
+        "{first}"
+    );
+    assert!(first.contains("T-0001"), "{first}");
+    let ids = |output: &str| -> Vec<String> {
+        output
+            .lines()
+            .filter(|line| line.contains("-0000-0000-"))
+            .map(str::to_string)
+            .collect()
+    };
+    assert_eq!(ids(&first), ids(&run()));
+}
+
+#[test]
+fn transactions_can_be_addressed_by_reference() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Refs monthly
+account add Checking bank
+account add Grocer expense
+transaction add 0 1 2025-03-04 42
+transaction add 0 1 2025-03-11 17
+transaction remove T-0001
+transaction show t-2
+transaction show T-0001
+transaction show X
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("Transaction removed:")
+                .and(contains("Transaction [0] T-0002"))
+                .and(contains("17.00"))
+                .and(contains("transaction `T-0001` not found"))
+                .and(contains(
+                    "transaction must be an index, a reference such as T-0012, or an id",
+                )),
+        );
+}
+
+#[test]
+fn id_flags_accept_transaction_references() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Refs monthly
+account add Checking bank
+account add Grocer expense
+transaction add 0 1 2025-03-04 42
+transaction add 0 1 2025-03-11 17
+transaction show --id T-0001
+transaction complete --id T-0002 2025-03-12 18
+transaction show @t-2
+transaction remove --id T-0001
+transaction show --id T-0001
+account show --id T-0002
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("Transaction [0] T-0001")
+                .and(contains("18.00"))
+                .and(contains("Transaction removed:"))
+                .and(contains("transaction `T-0001` not found"))
+                .and(contains(
+                    "`T-0002` is a transaction reference; account ids look like ab12cd34",
+                ))
+                .and(contains("must be an index").not()),
+        );
+}
+
+#[test]
+fn configured_defaults_shape_quick_added_transactions() {
+    let home = tempfile::tempdir().unwrap();
+    let tmp = NamedTempFile::new().unwrap();
+    let script = format!(
+        "\
+ledger new Receipts monthly
+account add Checking bank
+account add Grocer expense
+config set default_transaction_status completed
+config set default_transaction_date last_used
+transaction add 0 1 2025-03-04 42
+transaction add 0 1 17
+config set autofill_actuals off
+transaction add 0 1 5
+config set default_transaction_status sometimes
+ledger save {}
+exit
+",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(contains(
+            "default_transaction_status must be `planned` or `completed`",
+        ));
+
+    let ledger = load_ledger_from_path(tmp.path()).unwrap();
+    let march_4 = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+    let txns = &ledger.transactions;
+    assert_eq!(txns.len(), 3);
+    assert!(txns.iter().all(|txn| txn.scheduled_date == march_4));
+    assert!(txns
+        .iter()
+        .all(|txn| txn.status == TransactionStatus::Completed));
+    assert_eq!(txns[1].actual_date, Some(march_4));
+    assert_eq!(txns[1].actual_amount, Some(usd(17.0)));
+    assert_eq!(txns[2].actual_amount, None);
+}
+
+#[test]
+fn palette_lists_commands_matching_a_query() {
+    let home = tempfile::tempdir().unwrap();
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin("palette netw\npalette zzzq\nexit\n")
+        .assert()
+        .stdout(
+            contains("Command palette")
+                .and(contains("net-worth"))
+                .and(contains("No commands match `zzzq`.")),
+        );
+}
+
+#[test]
+fn transaction_search_sorts_and_pages_results() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Search monthly
+account add Checking bank
+account add Grocer expense
+transaction add 0 1 2025-03-04 42
+transaction add 0 1 2025-03-01 17
+transaction add 0 1 2025-03-09 8
+transaction search --sort amount --desc --per-page 2 --columns ref,budgeted
+transaction search --sort date --per-page 2 --page 2 --columns ref,date
+transaction search --notes nothing
+transaction search --sort size
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("Showing 1–2 of 3 (page 1 of 2). Use --page 2 for more.")
+                .and(is_match(r"(?s)T-0001 +42\.00.*T-0002 +17\.00").unwrap())
+                .and(contains("Showing 3–3 of 3 (page 2 of 2)."))
+                .and(contains("No transactions match the supplied filters."))
+                .and(contains("unknown sort `size`")),
+        );
+}
+
+#[test]
+fn list_transactions_applies_filter_flags() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Filters", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let landlord = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
+    let grocer = ledger.add_account(Account::new("Grocer", AccountKind::ExpenseDestination));
+    ledger.add_transaction(Transaction::new(
+        checking,
+        landlord,
+        None,
+        NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+        usd(900.0),
+    ));
+    ledger.add_transaction(Transaction::new(
+        checking,
+        grocer,
+        None,
+        NaiveDate::from_ymd_opt(2025, 2, 8).unwrap(),
+        usd(45.5),
+    ));
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\nlist transactions --account grocer --json\nlist transactions --min 500 --to 2025-02-05\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("\"minor\": 4550")
+                .and(contains("\"minor\": 90000").not())
+                .and(contains("Landlord")),
+        );
+}
+
+#[test]
+fn saved_views_round_trip_through_script_mode() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Views", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let landlord = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
+    let grocer = ledger.add_account(Account::new("Grocer", AccountKind::ExpenseDestination));
+    let mut rent = Transaction::new(
+        checking,
+        landlord,
+        None,
+        NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+        usd(900.0),
+    );
+    rent.merchant = Some("Reimbursable deposit".into());
+    ledger.add_transaction(rent);
+    ledger.add_transaction(Transaction::new(
+        checking,
+        grocer,
+        None,
+        NaiveDate::from_ymd_opt(2025, 2, 8).unwrap(),
+        usd(45.5),
+    ));
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\nview save \"Big rent\" --account landlord --min 500 --columns date,to,budgeted\nview save Reimbursable --text reimbursable --columns date,to\nview list\nview run \"big rent\"\nview run reimbursable\nview remove \"Big rent\"\nview run \"Big rent\"\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("View `Big rent` saved.")
+                .and(contains("--account \"landlord\" --min 500"))
+                .and(contains("--text \"reimbursable\""))
+                .and(contains("Landlord"))
+                .and(contains("Grocer").not())
+                .and(contains("View `Big rent` removed."))
+                .and(contains("view `Big rent` not found")),
+        );
+}
+
+#[test]
+fn transaction_export_writes_filtered_csv() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Export", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let landlord = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
+    let grocer = ledger.add_account(Account::new("Grocer", AccountKind::ExpenseDestination));
+    let mut rent = Transaction::new(
+        checking,
+        landlord,
+        None,
+        NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+        usd(1250.0),
+    );
+    rent.notes = Some("Rent, \"February\"".into());
+    ledger.add_transaction(rent);
+    ledger.add_transaction(Transaction::new(
+        checking,
+        grocer,
+        None,
+        NaiveDate::from_ymd_opt(2025, 2, 8).unwrap(),
+        usd(45.5),
+    ));
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let export_path = home.path().join("rent.csv");
+
+    let script = format!(
+        "ledger load {}\ntransaction export \"{}\" --account landlord --columns date,to,budgeted,notes\nexit\n",
+        tmp.path().display(),
+        export_path.display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(contains("Exported 1 transaction(s)"));
+
+    let csv = std::fs::read_to_string(&export_path).unwrap();
+    assert_eq!(
+        csv,
+        "date,to,budgeted,notes\r\n2025-02-01,Landlord,1250.00,\"Rent, \"\"February\"\"\"\r\n"
+    );
+}
+
+#[test]
+fn learned_rules_list_and_forget() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Rules", BudgetPeriod::default());
+    let groceries = ledger.add_category(budget_core::ledger::Category::new(
+        "Groceries",
+        budget_core::ledger::CategoryKind::Expense,
+    ));
+    let now = chrono::Utc::now();
+    ledger.learned_rules = vec![
+        bufy_domain::LearnedRule::new("whole foods", groceries, now),
+        bufy_domain::LearnedRule::new("market", groceries, now),
+    ];
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\nrules learned list\nrules learned forget \"Whole Foods\"\nrules learned forget --all\nrules learned list\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("whole foods")
+                .and(contains("Groceries"))
+                .and(contains("Forgot learned rule `whole foods`."))
+                .and(contains("Forgot 1 learned rule(s)."))
+                .and(contains("No learned rules yet.")),
+        );
+}
+
+#[test]
+fn member_attribution_filters_and_summarizes() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Members", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let grocer = ledger.add_account(Account::new("Grocer", AccountKind::ExpenseDestination));
+    for amount in [64.0, 18.0] {
+        ledger.add_transaction(Transaction::new(
+            checking,
+            grocer,
+            None,
+            NaiveDate::from_ymd_opt(2025, 3, 4).unwrap(),
+            usd(amount),
+        ));
+    }
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\nmember add Sam\nmember assign 0 sam\nlist transactions --member Sam --json\nsummary custom 2025-03-01 2025-03-31 --by-member\nmember assign 0 nobody\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("Member `Sam` added.")
+                .and(contains("Transaction [0] attributed to `Sam`."))
+                .and(contains("\"minor\": 6400"))
+                .and(contains("\"minor\": 1800").not())
+                .and(contains("Members:"))
+                .and(contains("Unassigned"))
+                .and(contains("unknown member `nobody`")),
+        );
+}
+
+#[test]
+fn allowance_sub_ledger_flow() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Allowance", BudgetPeriod::default());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(200.0));
+    ledger.add_account(checking);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\nallowance new Leo --opening 2\nallowance deposit leo 10 weekly --date 2025-01-05\nallowance spend Leo 4.5 stickers --date 2025-01-06\nallowance list\nallowance show Leo\nnet-worth\nallowance spend Nobody 1\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("Allowance `Leo` created.")
+                .and(contains("Recorded spend for `Leo`."))
+                .and(contains("stickers"))
+                .and(contains("Allowance Leo:"))
+                .and(contains("Net worth:"))
+                .and(contains("207.50"))
+                .and(contains("sub-ledger `Nobody` not found")),
+        );
+}
+
+#[test]
+fn ledger_load_reports_active_alerts() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Alerts", BudgetPeriod::default());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    ledger.add_account(checking);
+    let store = Account::new("Store", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    ledger.add_account(store);
+    let today = chrono::Utc::now().date_naive();
+    let mut purchase = Transaction::new(checking_id, store_id, None, today, usd(40.0));
+    purchase.mark_completed(today, usd(40.0));
+    ledger.add_transaction(purchase);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!("ledger load {}\nexit\n", tmp.path().display());
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(contains("1 active alert(s):").and(contains("Checking balance is -40.00")));
+}
+
+#[test]
+fn assert_command_fails_script_when_a_check_does_not_hold() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Assert", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let store = ledger.add_account(Account::new("Store", AccountKind::ExpenseDestination));
+    let mut groceries = Category::new("Groceries", CategoryKind::Expense);
+    groceries.set_budget(usd(200.0), bufy_domain::BudgetPeriod::Monthly, None);
+    let groceries = ledger.add_category(groceries);
+    let today = chrono::Utc::now().date_naive();
+    let mut deposit = Transaction::new(store, checking, None, today, usd(500.0));
+    deposit.mark_completed(today, usd(500.0));
+    ledger.add_transaction(deposit);
+    let mut purchase = Transaction::new(checking, store, Some(groceries), today, usd(120.0));
+    purchase.mark_completed(today, usd(120.0));
+    ledger.add_transaction(purchase);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let passing = format!(
+        "ledger load {}\nassert balance checking >= 0\nassert category-budget Groceries remaining > 50\nexit\n",
+        tmp.path().display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(passing)
+        .assert()
+        .success()
+        .stdout(
+            contains("Assertion passed: Checking balance is 380.00 USD (>= 0.00 USD)").and(
+                contains("Assertion passed: Groceries remaining is 80.00 USD (> 50.00 USD)"),
+            ),
+        );
+
+    let failing = format!(
+        "ledger load {}\nassert category-budget Groceries remaining > 100\nassert balance Checking >= 0\nexit\n",
+        tmp.path().display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(failing)
+        .assert()
+        .failure()
+        .stdout(
+            contains("Assertion failed: Groceries remaining is 80.00 USD (> 100.00 USD)")
+                .and(contains("Assertion passed: Checking balance")),
+        )
+        .stderr(contains("1 assertion(s) failed"));
+}
+
+#[test]
+fn fund_commands_track_sinking_funds() {
+    let home = tempfile::tempdir().unwrap();
+    let due = chrono::Utc::now().date_naive() + chrono::Months::new(6);
+    let input = format!(
+        "ledger new Bills monthly\nfund add Insurance 1200 {}\nfund list\nfund contribute insurance 700\nsummary current\nfund pay Insurance\nexit\n",
+        due
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(
+            contains("Sinking fund `Insurance` created.")
+                .and(contains("Underfunded"))
+                .and(contains("Saved so far: 700.00 USD"))
+                .and(contains("Earmarked for sinking funds:"))
+                .and(contains("Bill for `Insurance` paid.")),
+        );
+}
+
+#[test]
+fn income_model_drives_paycheck_and_simulated_withholding() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Payroll", BudgetPeriod::default());
+    let employer = ledger.add_account(Account::new("Employer", AccountKind::IncomeSource));
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let start = chrono::Utc::now().date_naive();
+    let paycheck = Transaction::new(employer, checking, None, start, usd(0.0)).with_recurrence(
+        Recurrence::new(
+            start,
+            TimeInterval {
+                every: 1,
+                unit: TimeUnit::Month,
+            },
+            RecurrenceMode::FixedSchedule,
+        ),
+    );
+    ledger.add_transaction(paycheck);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\ntransaction recurring income 0 --gross 5000 --withholding 20 --benefits 200\nsimulation create Withholding\nsimulation income Withholding 0 --withholding 150\nsimulation income Withholding 0 --withholding 25\nsimulation changes Withholding\nexit\n",
+        tmp.path().display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Income model set on transaction [0]. Net pay: 3800.00 USD")
+                .and(contains("withholding must be between 0 and 100 percent"))
+                .and(contains(
+                    "Income for transaction [0] modified in `Withholding`",
+                ))
+                .and(contains("(withholding 25%)")),
+        );
+}
+
+#[test]
+fn recurring_price_changes_are_recorded_in_price_history() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Utilities", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let utility = ledger.add_account(Account::new("Power Co", AccountKind::ExpenseDestination));
+    let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+    let bill = Transaction::new(checking, utility, None, start, usd(100.0)).with_recurrence(
+        Recurrence::new(
+            start,
+            TimeInterval {
+                every: 1,
+                unit: TimeUnit::Month,
+            },
+            RecurrenceMode::FixedSchedule,
+        ),
+    );
+    ledger.add_transaction(bill);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\ntransaction recurring price 0 110 2025-02-01\ntransaction recurring price 0 110\ntransaction recurring price-history 0\nsummary --price-changes\nexit\n",
+        tmp.path().display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Price of transaction [0] changed from 100.00 USD to 110.00 USD")
+                .and(contains("amount is already 110.00"))
+                .and(contains("Price history for transaction [0]"))
+                .and(contains("+10.0%"))
+                .and(contains("Price vs usage")),
+        );
+}
+
+#[test]
+fn inbox_reviews_drafts_submitted_by_other_clients() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Inbox", BudgetPeriod::default());
+    ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    ledger.add_account(Account::new("Cafe", AccountKind::ExpenseDestination));
+    let mut draft = bufy_domain::PendingDraft::new("mobile", chrono::Utc::now());
+    draft.amount = Some(4.5);
+    draft.payee = Some("Corner Cafe".into());
+    ledger.pending_drafts.push(draft);
+    ledger
+        .pending_drafts
+        .push(bufy_domain::PendingDraft::new("email", chrono::Utc::now()));
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\ninbox\ninbox approve 0\ninbox edit 0 --date 2025-03-04 --from Checking\ninbox approve 0 --to Cafe\ninbox discard 0\ninbox list\nexit\n",
+        tmp.path().display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("2 draft(s) waiting for review.")
+                .and(contains("Corner Cafe"))
+                .and(contains("draft is missing date, from account, to account"))
+                .and(contains("Draft [0] updated."))
+                .and(contains("Draft [0] approved as transaction [0]."))
+                .and(contains("Draft [0] discarded."))
+                .and(contains("Inbox is empty.")),
+        );
+}
+
+#[test]
+fn statement_dates_are_recorded_and_choose_summary_basis() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Statements", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let store = ledger.add_account(Account::new("Store", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 1, 30).unwrap();
+    let mut purchase = Transaction::new(checking, store, None, date, usd(80.0));
+    purchase.mark_completed(date, usd(80.0));
+    ledger.add_transaction(purchase);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let export = home.path().join("dates.csv");
+
+    let script = format!(
+        "ledger load {}\ntransaction dates 0 --booking 2025-02-01 --value none\ntransaction dates 0 --booking someday\nconfig date-basis booking\nconfig date-basis posted\ntransaction export {} --columns date,booking_date,value_date\nexit\n",
+        tmp.path().display(),
+        export.display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Statement dates updated for transaction [0].")
+                .and(contains("Summaries now use the booking date."))
+                .and(contains("usage: config date-basis <actual|booking|value>")),
+        );
+    let csv = std::fs::read_to_string(export).unwrap();
+    assert_eq!(
+        csv,
+        "date,booking_date,value_date\r\n2025-01-30,2025-02-01,\r\n"
+    );
+}
+
+#[test]
+fn goal_commands_track_progress_and_feed_forecasts() {
+    let home = tempfile::tempdir().unwrap();
+    let by = chrono::Utc::now().date_naive() + chrono::Months::new(12);
+    let input = format!(
+        "ledger new Goals monthly\naccount add Savings savings\ngoal add Holiday 1200 {} --account Savings --contribute 100\ngoal list\ngoal show holiday\nforecast 3 months\nexit\n",
+        by
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(input)
+        .assert()
+        .success()
+        .stdout(
+            contains("Goal `Holiday` created.")
+                .and(contains("On track"))
+                .and(contains("Tracked by: account `Savings`"))
+                .and(contains("Planned goal contributions:")),
+        );
+}
+
+#[test]
+fn config_locale_applies_regional_defaults_and_overrides() {
+    let home = tempfile::tempdir().unwrap();
+    let tmp = NamedTempFile::new().unwrap();
+    let input = format!(
+        "ledger new Demo monthly\nconfig locale de_de --grouping space\nconfig locale xx-YY\nconfig set locale fr\nledger save {}\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(input)
+        .assert()
+        .stdout(
+            contains("Locale set to de-DE (decimal ',', grouping ' ', symbol after, spaced, week starts Mon).")
+                .and(contains("unknown locale `xx-YY`"))
+                .and(contains("Configuration updated.")),
+        );
+
+    let json = std::fs::read_to_string(tmp.path()).unwrap();
+    assert!(json.contains("\"language_tag\": \"de-DE\""));
+    assert!(json.contains("\"decimal_separator\": \",\""));
+    assert!(json.contains("\"grouping_separator\": \" \""));
+}
+
+#[test]
+fn summary_places_the_currency_symbol_per_locale() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("Euro", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Girokonto", AccountKind::Bank));
+    let store = ledger.add_account(Account::new("Markt", AccountKind::ExpenseDestination));
+    let category = ledger.add_category(Category::new("Lebensmittel", CategoryKind::Expense));
+    let date = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+    let mut txn = Transaction::new(checking, store, Some(category), date, usd(1234.56));
+    txn.mark_completed(date, usd(1234.56));
+    ledger.add_transaction(txn);
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\nconfig base-currency EUR\nconfig locale de-DE\nsummary custom 2025-03-01 2025-03-31\nconfig locale de-DE --symbol before --symbol-space off\nsummary custom 2025-03-01 2025-03-31\nconfig locale de-DE --symbol left\nexit\n",
+        tmp.path().display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("1.234,56 €")
+                .and(contains("symbol before, week starts Mon"))
+                .and(contains("€1.234,56"))
+                .and(contains("unknown symbol position `left`")),
+        );
+}
+
+#[test]
+fn config_secrets_manage_ledger_passphrases_in_the_file_store() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "config secrets backend\nconfig secrets set household hunter2\nconfig secrets list\nconfig secrets forget household\nconfig secrets forget household\nconfig secrets\nexit\n";
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .env("BUFY_SECRET_BACKEND", "file")
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Secret backend: file")
+                .and(contains("Passphrase for `household` saved in file."))
+                .and(contains("  household"))
+                .and(contains("Forgot the passphrase for `household`."))
+                .and(contains("No passphrase stored for `household`."))
+                .and(contains("No passphrases stored (file).")),
+        );
+    assert!(
+        !std::fs::read_to_string(home.path().join("config/secrets.json"))
+            .unwrap()
+            .contains("hunter2")
+    );
+}
+
+#[test]
+fn transaction_export_snapshot_writes_csv_and_parquet() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Snapshot", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let grocer = ledger.add_account(Account::new("Grocer", AccountKind::ExpenseDestination));
+    ledger.add_transaction(Transaction::new(
+        checking,
+        grocer,
+        None,
+        NaiveDate::from_ymd_opt(2025, 2, 8).unwrap(),
+        usd(45.5),
+    ));
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let csv_path = home.path().join("snapshot.csv");
+    let parquet_path = home.path().join("snapshot.parquet");
+
+    let script = format!(
+        "ledger load {}\ntransaction export \"{}\" --snapshot\ntransaction export \"{}\" --snapshot\ntransaction export \"{}\"\nexit\n",
+        tmp.path().display(),
+        csv_path.display(),
+        parquet_path.display(),
+        parquet_path.display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Exported 1 transaction(s)")
+                .and(contains("as a reporting snapshot"))
+                .and(contains("Parquet output requires --snapshot")),
+        );
+
+    let csv = std::fs::read_to_string(&csv_path).unwrap();
+    assert!(csv.starts_with("transaction_id,scheduled_date,"));
+    assert!(csv.contains(",Checking,Bank,Grocer,Expense Destination,"));
+    let parquet = std::fs::read(&parquet_path).unwrap();
+    assert!(parquet.starts_with(b"PAR1") && parquet.ends_with(b"PAR1"));
+}
+
+#[test]
+fn category_budget_proration_rule_is_selectable() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Demo monthly
+category add Groceries expense
+category budget proration Groceries strict
+category budget set Groceries 100 --period weekly
+category budget proration Groceries sometimes
+category budget proration Groceries strict
+category budget show Groceries
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("has no budget")
+                .and(contains("usage: category budget proration"))
+                .and(contains("Budget for `Groceries` now uses the strict rule."))
+                .and(contains("Weekly • strict"))
+                .and(contains("100.00")),
+        );
+}
+
+#[test]
+fn category_budget_note_is_shown_with_the_budget() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Demo monthly
+category add Sports expense
+category budget note Sports includes gym
+category budget set Sports 80
+category budget note Sports includes gym + sports gear
+category budget set Sports 90
+category budget show
+category budget note Sports clear
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("has no budget")
+                .and(contains("Note saved for the `Sports` budget."))
+                .and(contains("Note"))
+                .and(contains("includes gym + sports gear"))
+                .and(contains("90.00"))
+                .and(contains("Note removed from the `Sports` budget.")),
+        );
+}
+
+#[test]
+fn hidden_categories_only_listed_with_all_flag() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Hidden", BudgetPeriod::default());
+    ledger.add_category(budget_core::ledger::Category::new(
+        "Groceries",
+        budget_core::ledger::CategoryKind::Expense,
+    ));
+    ledger.add_category(budget_core::ledger::Category::system(
+        bufy_domain::category::SystemCategory::Rounding,
+    ));
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let run = |command: &str| {
+        let script = format!("ledger load {}\n{}\nexit\n", tmp.path().display(), command);
+        let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+        let output = cmd
+            .env("BUDGET_CORE_CLI_SCRIPT", "1")
+            .env("BUDGET_CORE_HOME", home.path())
+            .write_stdin(script)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output).unwrap()
+    };
+
+    let visible = run("list categories");
+    assert!(visible.contains("Groceries"));
+    assert!(!visible.contains("Rounding Differences"));
+    let all = run("list categories --all");
+    assert!(all.contains("Rounding Differences (hidden)"));
+}
+
+#[test]
+fn account_statement_writes_file_in_requested_format() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Statement", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let grocer = ledger.add_account(Account::new("Grocer", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 1, 12).unwrap();
+    let mut txn = Transaction::new(checking, grocer, None, date, usd(42.0));
+    txn.mark_completed(date, usd(42.0));
+    ledger.add_transaction(txn);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let csv_path = home.path().join("statement.csv");
+    let html_path = home.path().join("statement.html");
+
+    let script = format!(
+        "ledger load {}\naccount statement Checking custom 2025-01-01 2025-02-01 --output \"{}\"\naccount statement Checking custom 2025-01-01 2025-02-01 --output \"{}\"\naccount statement Checking custom 2025-01-01 2025-02-01\nexit\n",
+        tmp.path().display(),
+        csv_path.display(),
+        html_path.display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Statement for Checking with 1 movement(s) written to")
+                .and(contains("Grocer")),
+        );
+
+    let csv = std::fs::read_to_string(&csv_path).unwrap();
+    assert!(csv.starts_with("date,description,category,notes,amount,balance"));
+    assert!(csv.contains("2025-01-12,Grocer,,,-42.00,-42.00"));
+    let html = std::fs::read_to_string(&html_path).unwrap();
+    assert!(html.contains("<table>"));
+}
+
+#[test]
+fn import_mint_creates_accounts_and_skips_duplicates() {
+    let home = tempfile::tempdir().unwrap();
+    let ledger = Ledger::new("CLI Import", BudgetPeriod::default());
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let csv_path = home.path().join("mint.csv");
+    std::fs::write(
+        &csv_path,
+        "Date,Description,Original Description,Amount,Transaction Type,Category,Account Name,Labels,Notes\n\
+         1/04/2025,Grocer,GROCER 42,61.20,debit,Groceries,Checking,,\n\
+         1/05/2025,Employer,PAYROLL,1500.00,credit,Paycheck,Checking,,\n",
+    )
+    .unwrap();
+
+    let script = format!(
+        "ledger load {}\nimport mint \"{}\"\nimport mint \"{}\"\nlist accounts\nexit\n",
+        tmp.path().display(),
+        csv_path.display(),
+        csv_path.display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Imported 2 transaction(s) from Mint: 3 account(s) and 2 categories created")
+                .and(contains("Skipped 2 transaction(s) already in the ledger."))
+                .and(contains("Grocer")),
+        );
+}
+
+#[test]
+fn sync_bank_imports_new_transactions_once() {
+    let home = tempfile::tempdir().unwrap();
+    let ledger = Ledger::new("CLI Bank", BudgetPeriod::default());
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let bank_path = home.path().join("bank.json");
+    std::fs::write(
+        &bank_path,
+        r#"{"accounts": [{"id": "chk", "name": "Checking", "transactions": [
+            {"id": "t1", "date": "2025-01-04", "amount": -61.2, "payee": "Grocer"},
+            {"id": "t2", "date": "2025-01-05", "amount": 1500.0, "payee": "Employer"}
+        ]}]}"#,
+    )
+    .unwrap();
+
+    let script = format!(
+        "ledger load {}\nsync connect mybank mock \"{}\"\nsync bank mybank\nsync bank mybank\nsync connections\nsync bank otherbank\nexit\n",
+        tmp.path().display(),
+        bank_path.display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Bank connection `mybank` added.")
+                .and(contains("Imported 2 transaction(s) from bank"))
+                .and(contains("No new transactions from `mybank`."))
+                .and(contains("mybank: mock"))
+                .and(contains("(1 account(s) synced)"))
+                .and(contains("unknown bank connection `otherbank`")),
+        );
+}
+
+#[test]
+fn summary_row_limits_follow_flags_and_config() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Limits", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    for (day, name) in ["Rent", "Food", "Fuel", "Gym", "Books", "Games", "Gifts"]
+        .into_iter()
+        .enumerate()
+    {
+        let category = ledger.add_category(Category::new(name, CategoryKind::Expense));
+        ledger.add_transaction(Transaction::new(
+            checking,
+            shop,
+            Some(category),
+            NaiveDate::from_ymd_opt(2025, 3, day as u32 + 1).unwrap(),
+            usd(10.0),
+        ));
+    }
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let run = |commands: &str| {
+        let script = format!("ledger load {}\n{}\nexit\n", tmp.path().display(), commands);
+        let output = Command::cargo_bin("budget_core_cli")
+            .unwrap()
+            .env("BUDGET_CORE_CLI_SCRIPT", "1")
+            .env("BUDGET_CORE_HOME", home.path())
+            .write_stdin(script)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let window = "summary custom 2025-03-01 2025-03-31";
+
+    assert!(run(window).contains("... 2 more categories"));
+    assert!(run(&format!("{window} --limit 3")).contains("... 4 more categories"));
+    assert!(!run(&format!("{window} --full")).contains("more categories"));
+    let configured = run(&format!("config set summary_category_rows 6\n{window}"));
+    assert!(configured.contains("... 1 more categories"));
+    assert!(run(&format!("{window} --limit 0")).contains("--limit needs a positive row count"));
+}
+
+#[test]
+fn summary_rollup_folds_subcategories_and_expands_on_request() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Rollup", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let food = ledger.add_category(Category::new("Food", CategoryKind::Expense));
+    for (day, name) in ["Groceries", "Restaurants"].into_iter().enumerate() {
+        let mut category = Category::new(name, CategoryKind::Expense);
+        category.parent_id = Some(food);
+        let category = ledger.add_category(category);
+        ledger.add_transaction(Transaction::new(
+            checking,
+            shop,
+            Some(category),
+            NaiveDate::from_ymd_opt(2025, 3, day as u32 + 1).unwrap(),
+            usd(20.0),
+        ));
+    }
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let run = |commands: &str| {
+        let script = format!("ledger load {}\n{}\nexit\n", tmp.path().display(), commands);
+        let output = Command::cargo_bin("budget_core_cli")
+            .unwrap()
+            .env("BUDGET_CORE_CLI_SCRIPT", "1")
+            .env("BUDGET_CORE_HOME", home.path())
+            .write_stdin(script)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    let window = "summary custom 2025-03-01 2025-03-31";
+
+    let flat = run(window);
+    assert!(flat.contains("Groceries"));
+    assert!(!flat.contains("subcategories"));
+    let rolled = run(&format!("{window} --rollup"));
+    assert!(rolled.contains("Food"));
+    assert!(rolled.contains("[+2 subcategories]"));
+    assert!(rolled.contains("--expand-categories"));
+    assert!(!rolled.contains("Groceries"));
+    let expanded = run(&format!("{window} --expand-categories"));
+    assert!(expanded.contains("    Groceries"));
+    assert!(expanded.contains("    Restaurants"));
+    assert!(!expanded.contains("[+2 subcategories]"));
+}
+
+#[test]
+fn transaction_tags_drive_search_and_summary_breakdowns() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Tags", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    for (day, notes) in [(1, "Hotel"), (2, "Groceries")] {
+        let mut txn = Transaction::new(
+            checking,
+            shop,
+            None,
+            NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+            usd(50.0 * day as f64),
+        );
+        txn.notes = Some(notes.into());
+        ledger.add_transaction(txn);
+    }
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\ntransaction tag add 0 Vacation2025 reimbursable\ntransaction tag add 0 vacation2025\ntransaction tag list\ntransaction search --tag vacation2025 --columns notes,tags\nsummary custom 2025-03-01 2025-03-31 --by-tag\ntransaction tag remove 0 reimbursable\ntransaction tag list 0\ntransaction tag list 1\nexit\n",
+        tmp.path().display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Tagged transaction [0] with vacation2025, reimbursable.")
+                .and(contains("Transaction [0] already has those tags."))
+                .and(contains("reimbursable             1 transaction(s)"))
+                .and(contains("reimbursable, vacation2025"))
+                .and(contains("Groceries").not())
+                .and(contains("Tags:"))
+                .and(contains("vacation2025"))
+                .and(contains("Removed reimbursable from transaction [0]."))
+                .and(contains("[0] vacation2025"))
+                .and(contains("Transaction [1] has no tags.")),
+        );
+}
+
+#[test]
+fn log_file_records_service_spans_without_amounts() {
+    let home = tempfile::tempdir().unwrap();
+    let logs = tempfile::tempdir().unwrap();
+    let script = "ledger new Logs monthly\naccount add Checking bank\naccount add Shops expense\ntransaction add 0 1 2025-03-05 1234.56\nexit\n";
+    let run = |log: &std::path::Path, extra: &[&str]| {
+        Command::cargo_bin("budget_core_cli")
+            .unwrap()
+            .arg("--log-file")
+            .arg(log)
+            .args(extra)
+            .env("BUDGET_CORE_CLI_SCRIPT", "1")
+            .env("BUDGET_CORE_HOME", home.path())
+            .write_stdin(script)
+            .assert()
+            .success()
+            .stderr(contains("DEBUG").not());
+        std::fs::read_to_string(log).unwrap()
+    };
+
+    let redacted = run(&logs.path().join("bufy.log"), &[]);
+    assert!(redacted.contains("bufy_core::transaction_service: close time.busy="));
+    assert!(redacted.contains("amount=[redacted]"));
+    assert!(redacted.contains("line=[redacted]"));
+    assert!(!redacted.contains("1234.56"));
+
+    let sensitive = run(&logs.path().join("full.log"), &["--log-sensitive"]);
+    assert!(sensitive.contains("amount=1234.56"));
+}
+
+#[test]
+fn verbose_flag_reports_command_timings_on_stderr() {
+    let home = tempfile::tempdir().unwrap();
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .arg("--verbose")
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin("help\nexit\n")
+        .assert()
+        .success()
+        .stdout(contains("parse_ms").not())
+        .stderr(
+            contains("budget_core::timing: command=help parse_ms=")
+                .and(contains("service_ms="))
+                .and(contains("render_ms="))
+                .and(contains("dispatch{command=help}")),
+        );
+
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .arg("--verbos")
+        .env("BUDGET_CORE_HOME", home.path())
+        .assert()
+        .code(2)
+        .stderr(contains("unknown option `--verbos`"));
+}
+
+#[test]
+fn transfer_rules_follow_completed_income() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Auto Savings", BudgetPeriod::default());
+    let employer = ledger.add_account(Account::new("Employer", AccountKind::IncomeSource));
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    ledger.add_account(Account::new("Savings", AccountKind::Savings));
+    ledger.add_transaction(Transaction::new(
+        employer,
+        checking,
+        None,
+        NaiveDate::from_ymd_opt(2025, 3, 25).unwrap(),
+        usd(2000.0),
+    ));
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\nrules transfer add Checking Savings 200\nrules transfer add checking savings 10%\nrules transfer list\ntransaction complete 0 2025-03-25 2500\ntransaction complete 0 2025-03-25 2500\nexit\n",
+        tmp.path().display()
+    );
+    let output = Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let combined = format!("{stdout}{stderr}");
+    assert!(combined.contains("percent must be greater than 0 and at most 100"));
+    assert!(stdout.contains("Added rule: 10% of each income into `Checking` moves to `Savings`."));
+    assert!(stdout.contains("Transfer rules"));
+    assert_eq!(
+        stdout
+            .matches("Transfer rule moved 250.00 USD from `Checking` to `Savings`.")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn amounts_accept_the_ledger_locale_separators() {
+    let home = tempfile::tempdir().unwrap();
+    let tmp = NamedTempFile::new().unwrap();
+    let input = format!(
+        "ledger new Demo monthly\nconfig locale de-DE\nfund add Insurance 1.234,50 2030-01-01\nfund contribute Insurance 100.25\nfund contribute Insurance 1,234.5\nledger save {}\nexit\n",
+        tmp.path().display()
+    );
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(input)
+        .assert()
+        .stdout(contains("Saved so far: 100.25 USD").and(contains("invalid amount `1,234.5`")));
+
+    let json = std::fs::read_to_string(tmp.path()).unwrap();
+    assert!(json.contains("\"amount\": 1234.5"));
+    assert!(json.contains("\"saved\": 100.25"));
+}
+
+#[test]
+fn paycheck_profile_splits_a_salary_into_allocations() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Paycheck", BudgetPeriod::default());
+    ledger.add_account(Account::new("Employer", AccountKind::IncomeSource));
+    ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    ledger.add_account(Account::new("Savings", AccountKind::Savings));
+    ledger.add_account(Account::new("Taxes", AccountKind::ExpenseDestination));
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {path}\npaycheck profile add Salary --from Employer --to Checking --gross 3000\npaycheck profile deduct Salary 20% Taxes\npaycheck profile allocate Salary 500 Savings\npaycheck profile allocate Salary 120% Savings\npaycheck profile show Salary\ntransaction add --paycheck Salary 2025-03-25\nledger save {path}\nexit\n",
+        path = tmp.path().display()
+    );
+    let output = Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Paycheck profile `Salary` created."));
+    assert!(stdout.contains("Allocation of 500.00 to `Savings` added to paycheck `Salary`."));
+    assert!(stdout.contains("a percentage cannot exceed 100"));
+    assert!(stdout.contains("Net pay 2400.00 USD"));
+    assert!(stdout.contains(
+        "Paycheck `Salary` of 3000.00 USD recorded on 2025-03-25 with 2 allocation transaction(s); 1900.00 USD left unallocated."
+    ));
+
+    let saved = load_ledger_from_path(tmp.path()).unwrap();
+    assert_eq!(saved.transactions.len(), 3);
+    assert_eq!(saved.paycheck_profiles[0].allocations.len(), 1);
+}
+
+#[test]
+fn report_merchants_ranks_spend_and_export_can_redact() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Merchants", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shops = ledger.add_account(Account::new("Shops", AccountKind::ExpenseDestination));
+    for (day, amount, merchant) in [
+        (3, 4.5, "Corner Cafe"),
+        (9, 5.5, "Corner Cafe"),
+        (12, 60.0, "Grocer"),
+    ] {
+        let date = NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        let mut txn = Transaction::new(checking, shops, None, date, usd(amount));
+        txn.mark_completed(date, usd(amount));
+        txn.merchant = Some(merchant.into());
+        txn.notes = Some("bought with Alex".into());
+        ledger.add_transaction(txn);
+    }
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let export = home.path().join("redacted.csv");
+
+    let script = format!(
+        "ledger load {}\nreport merchants --from 2025-03-01 --to 2025-03-31 --top 1\nreport merchants --from 2025-03-10 --to 2025-03-01\ntransaction export {} --columns date,merchant,notes,actual --redact\nexit\n",
+        tmp.path().display(),
+        export.display()
+    );
+    let output = Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Top merchants"));
+    assert!(stdout.contains("Grocer"));
+    assert!(!stdout.contains("Corner Cafe"));
+    assert!(stdout.contains("--from must be on or before --to"));
+    assert!(stdout.contains("without merchant, location, or notes"));
+
+    let csv = std::fs::read_to_string(&export).unwrap();
+    assert!(csv.starts_with("date,merchant,notes,actual"));
+    assert!(csv.contains("2025-03-12,,,60.00"));
+    assert!(!csv.contains("Grocer") && !csv.contains("Alex"));
+}
+
+#[test]
+fn recurrences_shift_off_the_weekend_and_heatmap_starts_on_first_weekday() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Heatmap", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shops = ledger.add_account(Account::new("Shops", AccountKind::ExpenseDestination));
+    let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+    let mut txn = Transaction::new(checking, shops, None, date(3), usd(40.0));
+    txn.mark_completed(date(3), usd(40.0));
+    ledger.add_transaction(txn);
+    let mut rent = Transaction::new(checking, shops, None, date(5), usd(900.0));
+    rent.set_recurrence(Some(Recurrence::new(
+        date(5),
+        TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        RecurrenceMode::FixedSchedule,
+    )));
+    ledger.add_transaction(rent);
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\nconfig first-weekday sunday\nconfig weekend fri,sat\ntransaction recurring business-days 1 following\ntransaction recurring business-days 1 sideways\ntransaction recurring sync 2025-04-10\nreport heatmap 2025-03\nreport heatmap March\nledger save\nexit\n",
+        tmp.path().display()
+    );
+    let output = Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("now move to the following business day"));
+    assert!(stdout.contains("<following|preceding|keep>"));
+    assert!(stdout.contains("Spending, March 2025"));
+    let header = stdout
+        .lines()
+        .find(|line| line.contains("SUN"))
+        .expect("weekday header");
+    assert!(header.find("SUN") < header.find("MON"));
+    assert!(header.contains("FRI*") && header.contains("SAT*"));
+    assert!(stdout.contains(" 3 █"));
+    assert!(stdout.contains("usage: report heatmap [YYYY-MM]"));
+
+    let saved = load_ledger_from_path(tmp.path()).unwrap();
+    // 5 April 2025 is a Saturday.
+    let april = NaiveDate::from_ymd_opt(2025, 4, 6).unwrap();
+    assert!(saved
+        .transactions
+        .iter()
+        .any(|txn| txn.scheduled_date == april && txn.recurrence_series_id.is_some()));
+}
+
+#[test]
+fn review_resumes_at_the_step_where_it_stopped() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Review", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shops = ledger.add_account(Account::new("Shops", AccountKind::ExpenseDestination));
+    ledger.add_category(Category::new("Groceries", CategoryKind::Expense));
+    let date = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+    let mut txn = Transaction::new(checking, shops, None, date, usd(12.0));
+    txn.mark_completed(date, usd(12.0));
+    let txn_id = ledger.add_transaction(txn);
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let run = |script: String| {
+        let output = Command::cargo_bin("budget_core_cli")
+            .unwrap()
+            .env("BUDGET_CORE_CLI_SCRIPT", "1")
+            .env("BUDGET_CORE_HOME", home.path())
+            .write_stdin(script)
+            .output()
+            .unwrap();
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    let path = tmp.path().display();
+    let first = run(format!(
+        "ledger load {path}\nreview\nreview adjust 0\nreview categorize 0 Groceries\nreview next\nledger save {path}\nexit\n"
+    ));
+    assert!(first.contains("Starting your first budget review."));
+    assert!(first.contains("Step 1 of 4: Uncategorized transactions"));
+    assert!(first.contains("[0]"));
+    assert!(first.contains("only budget items can be adjusted"));
+    assert!(first.contains("Categorized as Groceries."));
+    assert!(first.contains("Step 2 of 4: Over-budget categories"));
+
+    let second = run(format!("ledger load {path}\nreview\nreview log\nexit\n"));
+    assert!(second.contains("at step 2 of 4"));
+    assert!(second.contains("paused at Over-budget categories"));
+    assert!(second.contains("as Groceries"));
+
+    let saved = load_ledger_from_path(tmp.path()).unwrap();
+    assert!(saved.transaction(txn_id).unwrap().category_id.is_some());
+    assert!(saved.open_review().is_some());
+}
+
+#[test]
+fn portable_flag_keeps_config_and_ledgers_in_one_folder() {
+    let home = tempfile::tempdir().unwrap();
+    let stick = tempfile::tempdir().unwrap();
+    let portable = stick.path().join("bufy");
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .arg("--portable")
+        .arg(&portable)
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin("ledger new Demo monthly\nledger save-ledger demo\nconfig show\nexit\n")
+        .assert()
+        .success()
+        .stdout(contains("Ledger `demo` saved").and(contains("(portable)")));
+
+    assert!(portable.join("bufy.portable").is_file());
+    assert!(portable.join("config").join("config.json").is_file());
+    assert!(portable
+        .join("ledgers")
+        .read_dir()
+        .unwrap()
+        .next()
+        .is_some());
+    assert!(home.path().read_dir().unwrap().next().is_none());
+
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .args(["--portable", "--verbose", "--bogus"])
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin("exit\n")
+        .assert()
+        .failure()
+        .stderr(contains("[--portable [DIR]]"));
+}
+
+#[cfg(unix)]
+#[test]
+fn termination_signal_autosaves_unsaved_changes() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::process::Stdio;
+
+    let home = tempfile::tempdir().unwrap();
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("budget_core_cli"))
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    writeln!(stdin, "ledger new Draft monthly\naccount add Checking bank").unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = String::new();
+    while !line.contains("Account added") {
+        line.clear();
+        assert!(
+            stdout.read_line(&mut line).unwrap() > 0,
+            "shell exited early"
+        );
+    }
+
+    std::process::Command::new("kill")
+        .args(["-TERM", &child.id().to_string()])
+        .status()
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(output.status.code(), Some(143));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Unsaved changes to `Draft` were kept in an autosave backup"));
+    drop(stdin);
+
+    let backups: Vec<_> = std::fs::read_dir(home.path().join("backups"))
+        .unwrap()
+        .flat_map(|entry| std::fs::read_dir(entry.unwrap().path()).unwrap())
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert!(
+        backups.iter().any(|name| name.contains("autosave")),
+        "{backups:?}"
+    );
+}
+
+#[test]
+fn sample_ledger_opens_read_only_until_saved_under_a_name() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger open-sample
+summary
+account add Wallet cash
+ledger save-ledger my-sample
+account add Wallet cash
+list accounts
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("Sample ledger opened (read-only).")
+                .and(contains("Groceries"))
+                .and(contains("the sample ledger is read-only"))
+                .and(contains("Ledger `my-sample` saved to"))
+                .and(contains("Wallet")),
+        );
+}
+
+#[test]
+fn account_reconcile_matches_completed_transactions_to_a_statement() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Reconcile monthly
+account add Checking bank
+account add Grocer expense
+transaction add 0 1 2025-03-04 42
+transaction add 0 1 2025-03-11 17
+transaction add 0 1 2025-03-20 9
+transaction complete 0 2025-03-04 42
+transaction complete 1 2025-03-11 17
+account reconcile Checking 2025-03-15 -50
+account reconcile Checking 2025-03-15 -59
+account reconcile Checking
+account reconcile Grocer 2025-03-15 0
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("Matched 2 completed transaction(s)")
+                .and(contains("Reconciling Checking to the statement of"))
+                .and(contains("the statement stays open"))
+                .and(contains("reconciled with 2 cleared transaction(s)."))
+                .and(contains("This statement is already reconciled."))
+                .and(contains("does not hold money")),
+        );
+}
+
+#[test]
+fn contracts_report_lists_notice_deadlines_of_recurring_bills() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Contracts", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let isp = ledger.add_account(Account::new("Netline", AccountKind::ExpenseDestination));
+    let start = chrono::Utc::now().date_naive();
+    let bill =
+        Transaction::new(checking, isp, None, start, usd(45.0)).with_recurrence(Recurrence::new(
+            start,
+            TimeInterval {
+                every: 1,
+                unit: TimeUnit::Month,
+            },
+            RecurrenceMode::FixedSchedule,
+        ));
+    ledger.add_transaction(bill);
+    ledger.add_transaction(Transaction::new(checking, isp, None, start, usd(90.0)));
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let end = start + chrono::Duration::days(40);
+    let script = format!(
+        "ledger load {}\ncontracts\ntransaction recurring contract 1 Netline {}\ntransaction recurring contract 0 Netline {} --notice 30d\ncontracts\ncontracts --within 5\ntransaction recurring contract 0 clear\nexit\n",
+        tmp.path().display(),
+        end,
+        end
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("No contracts yet.")
+                .and(contains("transaction has no recurrence"))
+                .and(contains(
+                    "Contract with Netline recorded on transaction [0]",
+                ))
+                .and(contains("Notice due"))
+                .and(contains("Give Netline notice within 10 day(s)"))
+                .and(contains("No notice deadlines in the next 5 day(s)."))
+                .and(contains("Contract removed from transaction [0].")),
+        );
+}
+
+#[test]
+fn report_uncategorized_groups_by_payee_and_assigns_in_batches() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Uncategorized monthly
+account add Checking bank
+account add Grocer expense
+account add Employer income
+category add Groceries expense
+transaction add 0 1 2025-03-04 42
+transaction add 0 1 2025-03-11 17
+transaction add 2 0 2025-03-01 2500
+report uncategorized
+report uncategorized assign 2 Groceries
+report uncategorized assign 9 Groceries
+report uncategorized
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("Uncategorized by payee")
+                .and(contains("3 uncategorized transaction(s)."))
+                .and(contains(
+                    "Categorized 2 transaction(s) from `Grocer` as Groceries.",
+                ))
+                .and(contains("group must be between 1 and 1, got `9`"))
+                .and(contains("1 uncategorized transaction(s).")),
+        );
+}
+
+#[test]
+fn undo_and_redo_revert_ledger_edits() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new History monthly
+undo
+account add Checking bank
+account add Grocer expense
+transaction add 0 1 2025-03-04 42
+undo list
+undo
+list transactions
+redo
+redo
+undo
+undo
+list accounts
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Nothing to undo.")
+                .and(contains("undo  3: add account `Checking`"))
+                .and(contains("Undid add transaction"))
+                .and(contains("Redid add transaction"))
+                .and(contains("Nothing to redo."))
+                .and(contains("Undid add account `Grocer`.")),
+        );
+}
+
+#[test]
+fn ledger_audit_lists_saved_changes_by_actor_and_entity() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Household monthly
+config set audit_actor alex
+account add Checking bank
+account add Grocer expense
+ledger save-ledger household
+transaction add 0 1 2025-03-04 42
+config set audit_actor sam
+ledger save
+ledger audit
+ledger audit list transactions
+ledger audit --actor alex
+ledger audit Grocer --limit 1
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Audit log")
+                .and(contains("alex"))
+                .and(contains("sam"))
+                .and(contains("T-0001"))
+                .and(contains("Checking"))
+                .and(contains("Grocer")),
+        );
+    let log = std::fs::read_dir(home.path())
+        .unwrap()
+        .flatten()
+        .flat_map(|entry| walkdir_files(&entry.path()))
+        .find(|path| path.to_string_lossy().ends_with("household.audit.jsonl"))
+        .expect("audit log next to the ledger");
+    let lines = std::fs::read_to_string(log).unwrap();
+    assert_eq!(lines.lines().count(), 3);
+}
+
+#[test]
+fn account_balance_reports_the_balance_on_a_given_day() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Household monthly
+account add Checking bank
+account add Grocer expense
+transaction add 0 1 2025-03-04 42
+transaction complete 0 2025-03-04 42
+transaction add 0 1 2025-03-09 8
+account balance Checking --as-of 2025-03-03
+account balance --as-of 2025-03-10 --planned
+account balance Nope
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Balances as of 2025-03-03")
+                .and(contains("0.00 USD"))
+                .and(contains("WITH PLANNED"))
+                .and(contains("-42.00 USD"))
+                .and(contains("-50.00 USD"))
+                .and(contains("unknown account `Nope`")),
+        );
+}
+
+#[test]
+fn show_prints_one_account_or_category_with_its_stats() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Household monthly
+account add Checking bank
+account add Grocer expense
+category add Groceries expense
+transaction add 0 1 2025-03-04 42
+transaction complete 0 2025-03-04 42
+report uncategorized assign 1 Groceries
+account show Checking
+account show 1
+account show --include-closed
+account show Nope
+category show Groceries
+category show 0
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Account: Checking")
+                .and(contains("-42.00 USD on 2025-03-04"))
+                .and(contains("Account: Grocer"))
+                .and(contains("42.00 USD on 2025-03-04"))
+                .and(contains("unknown account `Nope`"))
+                .and(contains("Category: Groceries"))
+                .and(is_match(r#"transaction_count\s*":\s*1"#).unwrap())
+                .and(contains("usage: account list").not())
+                .and(contains("usage: category list").not()),
+        );
+}
+
+#[test]
+fn account_close_transfers_the_balance_and_hides_the_account() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Household monthly
+account add Checking bank
+account add Savings savings
+account add Salary income
+transaction add 2 0 2025-03-01 120
+transaction complete 0 2025-03-01 120
+account close Checking --on 2025-03-10
+account close Checking --on 2025-03-10 --transfer-to Savings
+transaction add 2 0 2025-03-12 5
+list accounts
+account balance Savings --as-of 2025-03-31
+list accounts --include-closed
+account reopen Checking
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("pass --transfer-to <account>")
+                .and(contains("Transferred the remaining 120.00 USD."))
+                .and(contains("Account `Checking` closed on 2025-03-10."))
+                .and(contains("account `Checking` was closed on 2025-03-10"))
+                .and(contains("Checking (closed 2025-03-10)"))
+                .and(contains("Account `Checking` reopened.")),
+        );
+}
+
+#[test]
+fn ledger_purge_removes_old_transactions_from_ledger_and_backups() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Household monthly
+account add Checking bank
+account add Grocer expense
+transaction add 0 1 2020-01-05 42
+transaction complete 0 2020-01-05 42
+ledger save-ledger household
+transaction add 0 1 2025-03-04 17
+ledger save
+ledger purge --before 2021-01-01 --dry-run
+ledger purge --before 2021-01-01
+ledger purge --before 2021-01-01 --confirm wrong
+ledger purge --before 2021-01-01 --confirm household
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("1 transaction(s) dated before 2021-01-01 would be removed")
+                .and(contains("Dry run: nothing was changed."))
+                .and(contains("--confirm household"))
+                .and(contains("Purge cancelled"))
+                .and(contains("Purged 1 transaction(s) dated before 2021-01-01")),
+        );
+    let files: Vec<_> = walkdir_files(home.path());
+    let ledger_file = files
+        .iter()
+        .find(|path| path.to_string_lossy().ends_with("household.bfy"))
+        .expect("saved ledger");
+    let ledger = load_ledger_from_path(ledger_file).unwrap();
+    assert_eq!(ledger.transactions.len(), 1);
+    assert_eq!(ledger.accounts[0].opening_balance, Some(usd(-42.0)));
+    assert_eq!(ledger.accounts[1].opening_balance, Some(usd(42.0)));
+    let backups: Vec<_> = files
+        .iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "bbfy"))
+        .collect();
+    assert!(!backups.is_empty());
+    for path in backups {
+        let contents = std::fs::read_to_string(path).unwrap();
+        assert!(
+            !contents.contains("2020-01-05"),
+            "{} still holds purged data",
+            path.display()
+        );
+    }
+}
+
+fn walkdir_files(path: &std::path::Path) -> Vec<std::path::PathBuf> {
+    if path.is_dir() {
+        std::fs::read_dir(path)
+            .unwrap()
+            .flatten()
+            .flat_map(|entry| walkdir_files(&entry.path()))
+            .collect()
+    } else {
+        vec![path.to_path_buf()]
+    }
+}
+
+#[test]
+fn transaction_complete_partial_records_an_installment() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Installments monthly
+account add Checking bank
+account add Insurer expense
+transaction add 0 1 2025-03-10 300
+transaction complete 0 2025-03-05 120 --partial
+transaction show 0
+transaction show 1
+transaction complete 0 2025-03-06 500 --partial
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Installment recorded for transaction 0;")
+                .and(contains("180.00 still planned."))
+                .and(contains("Paid in part:"))
+                .and(contains("Installment of T-0001"))
+                .and(contains(
+                    "amount must be less than the $180.00 still planned",
+                )),
+        );
+}
+
+#[test]
+fn transaction_asset_feeds_the_assets_report() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "\
+ledger new Assets monthly
+account add Checking bank
+account add Store expense
+transaction add 0 1 2025-01-15 1200
+transaction add 0 1 2020-01-15 60
+transaction asset 0 Laptop --warranty 2099-01-15 --lifetime 100y
+transaction asset 1 Kettle --lifetime 6
+transaction show 0
+report assets
+report assets --all
+transaction asset 1 clear
+transaction asset 1 clear
+exit
+";
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Asset `Laptop` recorded on transaction [0].")
+                .and(contains("Asset: Laptop (warranty until"))
+                .and(contains("1 active warranty(ies)."))
+                .and(contains("1 item(s) past their lifetime hidden."))
+                .and(contains("Kettle"))
+                .and(contains("Asset removed from transaction [1]."))
+                .and(contains("Transaction [1] has no asset.")),
+        );
+}
+
+#[test]
+fn ledger_merge_combines_copies_and_reports_conflicts() {
+    let home = tempfile::tempdir().unwrap();
+    let mut base = Ledger::new("Shared", BudgetPeriod::default());
+    let checking = base.add_account(Account::new("Checking", AccountKind::Bank));
+    let grocer = base.add_account(Account::new("Grocer", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 1, 12).unwrap();
+    base.add_transaction(Transaction::new(checking, grocer, None, date, usd(40.0)));
+
+    let mut theirs = base.clone();
+    theirs.accounts[1].name = "Grocery Store".into();
+    theirs.transactions[0].budgeted_amount = usd(45.0);
+    let mut ours = base.clone();
+    ours.transactions[0].budgeted_amount = usd(50.0);
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = |name: &str| dir.path().join(name);
+    save_ledger_to_path(&base, &path("base.json")).unwrap();
+    save_ledger_to_path(&theirs, &path("theirs.json")).unwrap();
+    save_ledger_to_path(&ours, &path("ours.json")).unwrap();
+
+    let run = |flags: &str, out: &str| {
+        let script = format!(
+            "ledger load {}\nledger merge {} {} {}\nledger save {}\nexit\n",
+            path("ours.json").display(),
+            path("base.json").display(),
+            path("theirs.json").display(),
+            flags,
+            path(out).display()
+        );
+        let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+        let output = cmd
+            .env("BUDGET_CORE_CLI_SCRIPT", "1")
+            .env("BUDGET_CORE_HOME", home.path())
+            .write_stdin(script)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        String::from_utf8(output).unwrap()
+    };
+
+    let output = run("", "kept-ours.json");
+    assert!(output.contains("`budgeted_amount`: ours 50.00 USD, theirs 45.00 USD -> kept ours"));
+    assert!(output.contains("1 conflict(s) resolved."));
+    let merged = load_ledger_from_path(&path("kept-ours.json")).unwrap();
+    assert_eq!(merged.accounts[1].name, "Grocery Store");
+    assert_eq!(merged.transactions[0].budgeted_amount, usd(50.0));
+
+    let output = run("--prefer theirs", "kept-theirs.json");
+    assert!(output.contains("-> kept theirs"));
+    let merged = load_ledger_from_path(&path("kept-theirs.json")).unwrap();
+    assert_eq!(merged.transactions[0].budgeted_amount, usd(45.0));
+}
+
+#[test]
+fn simulation_check_flags_and_prunes_changes_for_deleted_transactions() {
+    use budget_core::ledger::{Simulation, SimulationChange};
+
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("What-if", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let gym = ledger.add_account(Account::new("Gym", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 1, 12).unwrap();
+    let removed = Transaction::new(checking, gym, None, date, usd(45.0));
+    let mut simulation = Simulation::new("Cheaper gym");
+    simulation
+        .changes
+        .push(SimulationChange::ExcludeTransaction {
+            transaction_id: removed.id,
+        });
+    ledger.simulations.push(simulation);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let script = format!(
+        "ledger load {}\nsimulation check\nsimulation apply \"Cheaper gym\"\nsimulation check --prune\nsimulation check\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("1 simulation change(s) refer to transactions that no longer exist")
+                .and(contains("`Cheaper gym` change 1: Remove transaction"))
+                .and(contains("Run `simulation check --prune` to remove them."))
+                .and(contains("run `simulation check` to remove them"))
+                .and(contains("Removed 1 orphaned change(s) from `Cheaper gym`."))
+                .and(contains(
+                    "All simulation changes refer to existing transactions.",
+                )),
+        );
+}
+
+#[test]
+fn simulation_apply_with_changes_applies_only_the_selected_ones() {
+    use budget_core::ledger::{Simulation, SimulationChange, SimulationStatus};
+
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("What-if", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let gym = ledger.add_account(Account::new("Gym", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 1, 12).unwrap();
+    let membership = ledger.add_transaction(Transaction::new(checking, gym, None, date, usd(45.0)));
+    let classes = ledger.add_transaction(Transaction::new(checking, gym, None, date, usd(20.0)));
+    let mut simulation = Simulation::new("Cheaper gym");
+    for transaction_id in [membership, classes] {
+        simulation
+            .changes
+            .push(SimulationChange::ExcludeTransaction { transaction_id });
+    }
+    ledger.simulations.push(simulation);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let script = format!(
+        "ledger load {path}\nsimulation apply \"Cheaper gym\" --changes 2\nsimulation apply \"Cheaper gym\" --changes 1\nledger save {path}\nexit\n",
+        path = tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("simulation `Cheaper gym` has no change 2").and(contains(
+                "Applied 1 change(s) from simulation `Cheaper gym`; 1 change(s) remain pending.",
+            )),
+        );
+    let saved = load_ledger_from_path(tmp.path()).unwrap();
+    assert!(saved.transaction(membership).is_some());
+    assert!(saved.transaction(classes).is_none());
+    let simulation = saved.simulation("Cheaper gym").unwrap();
+    assert_eq!(simulation.status, SimulationStatus::Pending);
+    assert_eq!(simulation.changes.len(), 1);
+}
+
+#[test]
+fn simulation_rollback_undoes_what_the_apply_changed() {
+    use budget_core::ledger::{Simulation, SimulationChange, SimulationStatus};
+
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("What-if", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let gym = ledger.add_account(Account::new("Gym", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 1, 12).unwrap();
+    let membership = ledger.add_transaction(Transaction::new(checking, gym, None, date, usd(45.0)));
+    let mut simulation = Simulation::new("Cheaper gym");
+    simulation
+        .changes
+        .push(SimulationChange::ExcludeTransaction {
+            transaction_id: membership,
+        });
+    ledger.simulations.push(simulation);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let script = format!(
+        "ledger load {path}\nsimulation apply \"Cheaper gym\"\nledger save {path}\nledger load {path}\nsimulation rollback \"Cheaper gym\"\nledger save {path}\nexit\n",
+        path = tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(contains(
+            "Simulation `Cheaper gym` rolled back; 1 transaction(s) restored.",
+        ));
+    let saved = load_ledger_from_path(tmp.path()).unwrap();
+    assert!(saved.transaction(membership).is_some());
+    let simulation = saved.simulation("Cheaper gym").unwrap();
+    assert_eq!(simulation.status, SimulationStatus::Pending);
+    assert!(simulation.applied.is_empty());
+}
+
+#[test]
+fn simulation_apply_takes_a_fresh_backup_that_rollback_restores() {
+    use budget_core::ledger::{Simulation, SimulationChange, SimulationStatus};
+
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("What-if", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let gym = ledger.add_account(Account::new("Gym", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 1, 12).unwrap();
+    let membership = ledger.add_transaction(Transaction::new(checking, gym, None, date, usd(45.0)));
+    let mut simulation = Simulation::new("Cheaper gym");
+    simulation
+        .changes
+        .push(SimulationChange::ExcludeTransaction {
+            transaction_id: membership,
+        });
+    ledger.simulations.push(simulation);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let script = format!(
+        "config set simulation_backup_minutes 30\nledger load {path}\nsimulation apply \"Cheaper gym\"\nledger save-ledger whatif\nsimulation apply \"Cheaper gym\"\nsimulation rollback \"Cheaper gym\" --backup\nledger save\nexit\n",
+        path = tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("applying a simulation needs a backup")
+                .and(contains("created before applying"))
+                .and(contains("Simulation `Cheaper gym` applied"))
+                .and(contains("loaded from backup")),
+        );
+    let saved_path = walkdir_files(home.path())
+        .into_iter()
+        .find(|path| path.to_string_lossy().ends_with("whatif.bfy"))
+        .expect("saved ledger");
+    let saved = load_ledger_from_path(&saved_path).unwrap();
+    assert!(saved.transaction(membership).is_some());
+    let simulation = saved.simulation("Cheaper gym").unwrap();
+    assert_eq!(simulation.status, SimulationStatus::Pending);
+    assert_eq!(simulation.pre_apply_backup, None);
+}
+
+#[test]
+fn simulation_create_from_branches_off_an_existing_simulation() {
+    use budget_core::ledger::{Simulation, SimulationChange};
+
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("What-if", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let gym = ledger.add_account(Account::new("Gym", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 1, 12).unwrap();
+    let membership = ledger.add_transaction(Transaction::new(checking, gym, None, date, usd(45.0)));
+    let mut simulation = Simulation::new("Cheaper gym");
+    simulation
+        .changes
+        .push(SimulationChange::ExcludeTransaction {
+            transaction_id: membership,
+        });
+    ledger.simulations.push(simulation);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let script = format!(
+        "ledger load {path}\nsimulation create \"No gym, more running\" --from \"Cheaper gym\"\nsimulation changes \"No gym, more running\"\nsimulation discard \"Cheaper gym\"\nsimulation apply \"No gym, more running\"\nledger save {path}\nexit\n",
+        path = tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Simulation `No gym, more running` created from `Cheaper gym`.")
+                .and(contains("Based on `Cheaper gym`"))
+                .and(contains("has branches (No gym, more running)"))
+                .and(contains("apply `Cheaper gym` first")),
+        );
+    let saved = load_ledger_from_path(tmp.path()).unwrap();
+    let parent = saved.simulation("Cheaper gym").unwrap();
+    let branch = saved.simulation("No gym, more running").unwrap();
+    assert_eq!(branch.parent, Some(parent.id));
+    assert!(saved.transaction(membership).is_some());
+}
+
+#[test]
+fn export_writes_transactions_summary_and_budget_report() {
+    let home = tempfile::tempdir().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = |name: &str| dir.path().join(name);
+    let script = format!(
+        "\
+ledger new Exports monthly
+account add Checking bank
+account add Shops expense
+category add Sports expense
+category budget set Sports 90
+transaction add 0 1 2025-03-05 40
+transaction add 0 1 2025-04-05 25
+export transactions {}
+export transactions {} --window custom 2025-03-01 2025-04-01
+export summary {} --window custom 2025-03-01 2025-04-01
+export budget {} --window custom 2025-03-01 2025-04-01
+export ledger {}
+exit
+",
+        path("all.csv").display(),
+        path("march.csv").display(),
+        path("summary.json").display(),
+        path("budget.csv").display(),
+        path("other").display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Exported 2 transaction(s) to")
+                .and(contains("Exported 1 transaction(s) to"))
+                .and(contains(
+                    "Exported the summary for 2025-03-01 to 2025-03-31 to",
+                ))
+                .and(contains(
+                    "Exported the budget report for 2025-03-01 to 2025-03-31",
+                ))
+                .and(contains("usage: export <transactions|summary|budget>")),
+        );
+
+    let march = std::fs::read_to_string(path("march.csv")).unwrap();
+    assert_eq!(march.lines().count(), 2);
+    let summary: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path("summary.json")).unwrap()).unwrap();
+    assert_eq!(summary["ledger"], "Exports");
+    assert_eq!(summary["totals"]["budgeted"], 40.0);
+    let budget = std::fs::read_to_string(path("budget.csv")).unwrap();
+    assert!(budget.starts_with("from,to,category,"));
+    assert!(budget.contains("Total,40.00,"));
+}
+
+#[test]
+fn simulation_overlay_shows_the_active_simulation_next_to_base_numbers() {
+    use budget_core::ledger::{Simulation, SimulationChange};
+
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("Overlay", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let gym = ledger.add_account(Account::new("Gym", AccountKind::ExpenseDestination));
+    let today = chrono::Utc::now().date_naive();
+    let membership = Transaction::new(checking, gym, None, today, usd(45.0));
+    let mut simulation = Simulation::new("Cancel gym");
+    simulation
+        .changes
+        .push(SimulationChange::ExcludeTransaction {
+            transaction_id: membership.id,
+        });
+    ledger.add_transaction(membership);
+    ledger.simulations.push(simulation);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let script = format!(
+        "ledger load {}\nsimulation overlay on\nsimulation enter \"Cancel gym\"\nsimulation overlay\nsummary\nforecast 30 days\nlist transactions\nsimulation overlay off\nsummary\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    let output = cmd
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert!(stdout.contains("It takes effect once you `simulation enter` a simulation."));
+    assert!(stdout.contains("list transactions show `Cancel gym` next to the base numbers"));
+    assert_eq!(stdout.matches("Simulation `Cancel gym`").count(), 1);
+    assert!(stdout.contains("Forecast `Cancel gym`"));
+    assert!(stdout.contains("Base → simulated:"));
+    assert!(stdout.contains("1 transaction(s) excluded in `Cancel gym`."));
+    assert!(stdout.contains("Simulation overlay is off."));
+}
+
+#[test]
+fn forecast_periods_lists_each_budget_window() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("Periods", BudgetPeriod::default());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(2000.0));
+    let from = ledger.add_account(checking);
+    let to = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
+    let start = chrono::Utc::now().date_naive();
+    let mut txn = Transaction::new(from, to, None, start, usd(900.0));
+    txn.set_recurrence(Some(Recurrence::new(
+        start,
+        TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        RecurrenceMode::FixedSchedule,
+    )));
+    ledger.add_transaction(txn);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let script = format!(
+        "ledger load {}\nforecast periods 3\nforecast periods none\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Forecast by period")
+                .and(contains("Closing"))
+                .and(contains("$1,100.00"))
+                .and(contains("$200.00"))
+                .and(contains("usage: forecast [simulation_name] periods <n>")),
+        );
+}
+
+#[test]
+fn forecast_export_writes_an_icalendar_feed() {
+    let home = tempfile::tempdir().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("Calendar", BudgetPeriod::default());
+    let from = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let to = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
+    let start = chrono::Utc::now().date_naive();
+    let mut txn = Transaction::new(from, to, None, start, usd(900.0));
+    txn.set_recurrence(Some(Recurrence::new(
+        start,
+        TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        RecurrenceMode::FixedSchedule,
+    )));
+    ledger.add_transaction(txn);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let ics = dir.path().join("bills.ics");
+    let script = format!(
+        "ledger load {}\nforecast export ics {} 70 days\nforecast export csv {}\nexit\n",
+        tmp.path().display(),
+        ics.display(),
+        ics.display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(contains("Exported 3 calendar event(s) to").and(contains(
+            "usage: forecast [simulation_name] export ics <path>",
+        )));
+
+    let feed = std::fs::read_to_string(&ics).unwrap();
+    assert_eq!(feed.matches("BEGIN:VEVENT").count(), 3);
+    assert!(feed.contains("SUMMARY:Landlord: $900.00"));
+    assert!(feed.contains(&format!("DTSTART;VALUE=DATE:{}", start.format("%Y%m%d"))));
+}
+
+#[test]
+fn forecast_balances_warns_before_an_account_runs_low() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("Balances", BudgetPeriod::default());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(1000.0));
+    let from = ledger.add_account(checking);
+    let to = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
+    let start = chrono::Utc::now().date_naive();
+    let mut txn = Transaction::new(from, to, None, start, usd(600.0));
+    txn.set_recurrence(Some(Recurrence::new(
+        start,
+        TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        RecurrenceMode::FixedSchedule,
+    )));
+    ledger.add_transaction(txn);
+
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+    let script = format!(
+        "ledger load {}\nforecast balances 45 days --daily\nforecast balances 10 days --min 500\nforecast balances --min lots\nexit\n",
+        tmp.path().display()
+    );
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Projected balances")
+                .and(contains("$400.00"))
+                .and(contains("$-200.00"))
+                .and(contains("Checking is projected to fall below $0.00 on"))
+                .and(contains("Checking is projected to fall below $500.00 on"))
+                .and(contains("usage: forecast [simulation_name] balances")),
+        );
+}
+
+#[test]
+fn currency_rate_overrides_are_added_listed_and_checked_for_overlap() {
+    let home = tempfile::tempdir().unwrap();
+    let tmp = NamedTempFile::new().unwrap();
+    let script = format!(
+        "ledger new Rates monthly\ncurrency rates override list\ncurrency rates override add EUR USD 1.1 --from 2025-01-01 --until 2025-03-31 --note Q1 contract\ncurrency rates override add usd eur 0.9 --from 2025-03-15\ncurrency rates override add EUR USD 1.2 --until 2025-06-30\ncurrency rates override add EUR USD 1.2 --from 2025-04-01\ncurrency rates override list\ncurrency rates override remove 1\nledger save {}\nexit\n",
+        tmp.path().display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("No exchange rate overrides yet.")
+                .and(contains(
+                    "Added exchange rate override: 1 EUR = 1.1 USD, 2025-01-01 to 2025-03-31.",
+                ))
+                .and(contains(
+                    "overlaps the EUR → USD rate of 1.1 valid 2025-01-01 to 2025-03-31",
+                ))
+                .and(contains("--from <date> is required for a rate override"))
+                .and(contains(
+                    "Added exchange rate override: 1 EUR = 1.2 USD, from 2025-04-01.",
+                ))
+                .and(contains("Q1 contract"))
+                .and(contains(
+                    "Removed the EUR/USD override valid from 2025-04-01.",
+                )),
+        );
+
+    let saved = load_ledger_from_path(tmp.path()).unwrap();
+    assert_eq!(saved.exchange_rate_overrides.len(), 1);
+    assert_eq!(saved.exchange_rate_overrides[0].rate, 1.1);
+}
+
+#[test]
+fn stale_currency_rates_are_flagged_and_refreshed() {
+    let home = tempfile::tempdir().unwrap();
+    let tmp = NamedTempFile::new().unwrap();
+    let script = format!(
+        "ledger new Rates monthly\ncurrency rates override add EUR USD 1.1 --from 2020-01-01\nledger save {path}\nledger load {path}\nsummary\ncurrency rates refresh GBP USD 1.3\ncurrency rates refresh EUR USD 1.2\nledger save {path}\nexit\n",
+        path = tmp.path().display()
+    );
+    let stale = "EUR → USD rate 1.1 has not changed since 2020-01-01; refresh it with `currency rates refresh EUR USD <rate>`";
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    let assert = cmd
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("no open-ended GBP → USD rate starting before")
+                .and(contains("Refreshed exchange rate: 1 EUR = 1.2 USD, from ")),
+        );
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout);
+    assert_eq!(stdout.matches(stale).count(), 2, "startup and summary");
+
+    let saved = load_ledger_from_path(tmp.path()).unwrap();
+    let today = chrono::Utc::now().date_naive();
+    let rates = &saved.exchange_rate_overrides;
+    assert_eq!(rates.len(), 2);
+    assert_eq!(rates[0].valid_until, today.pred_opt());
+    assert_eq!((rates[1].rate, rates[1].valid_from), (1.2, today));
+}
+
+#[test]
+fn calendar_import_fills_the_holiday_calendar() {
+    let home = tempfile::tempdir().unwrap();
+    let tmp = NamedTempFile::new().unwrap();
+    let ics = tempfile::Builder::new().suffix(".ics").tempfile().unwrap();
+    std::fs::write(
+        ics.path(),
+        "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20200610\r\nRRULE:FREQ=YEARLY\r\nSUMMARY:Founders Day\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n",
+    )
+    .unwrap();
+    let script = format!(
+        "ledger new Holidays monthly\ncalendar\ncalendar import XX\ncalendar import DE\ncalendar show\ncalendar refresh\ncalendar import {}\ncalendar show\nconfig show\nledger save {}\nexit\n",
+        ics.path().display(),
+        tmp.path().display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .stdout(
+            contains("No holiday calendar yet.")
+                .and(contains(
+                    "`XX` is neither a file nor a built-in country; see `calendar countries`",
+                ))
+                .and(contains("holiday(s) from Germany (DE), through"))
+                .and(contains("German Unity Day"))
+                .and(contains("Refreshed"))
+                .and(contains("Founders Day"))
+                .and(contains(".ics")),
+        );
+
+    let saved = load_ledger_from_path(tmp.path()).unwrap();
+    assert!(matches!(
+        saved.holidays.source,
+        Some(bufy_domain::HolidaySource::File(_))
+    ));
+    assert!(saved
+        .holidays
+        .holidays
+        .iter()
+        .all(|holiday| holiday.name == "Founders Day"));
+}
+
+#[test]
+fn prompt_and_banner_templates_are_validated_and_previewed() {
+    let home = tempfile::tempdir().unwrap();
+    let script = concat!(
+        "ledger new Household monthly\n",
+        "account add Checking bank\n",
+        "config set prompt \"[{ledger}{dirty} {progress}] {{{arrow}}}\"\n",
+        "config set banner {mood}\n",
+        "config set banner off\n",
+        "config show\n",
+        "exit\n"
+    );
+
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(contains("unknown template variable(s) {mood}"))
+        .stdout(contains("Prompt: [{ledger}{dirty} {progress}] {{{arrow}}}"))
+        .stdout(contains("Startup banner: off"))
+        .stdout(contains("Prompt preview: [Household* "))
+        .stdout(contains("%] {"));
+
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(
+            "config set prompt default\nconfig set banner {version} {date}\nconfig show\nexit\n",
+        )
+        .assert()
+        .success()
+        .stdout(contains("Prompt: default"))
+        .stdout(contains("Startup banner: {version} {date}"))
+        .stdout(contains("Prompt preview").not());
+}
+
+#[test]
+fn json_output_prints_one_object_per_command() {
+    let home = tempfile::tempdir().unwrap();
+    let script = "ledger new Demo monthly\ncalendar countries\ncalendar import XX\nnope\nconfig show\nexit\n";
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    let output = cmd
+        .args(["--output", "json"])
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let records: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every line is JSON"))
+        .collect();
+    assert_eq!(records.len(), 6);
+
+    assert_eq!(records[0]["command"], "ledger new Demo monthly");
+    assert_eq!(records[0]["status"], "ok");
+    assert_eq!(
+        records[0]["messages"][0],
+        serde_json::json!({ "level": "success", "text": "New ledger created." })
+    );
+    assert_eq!(
+        records[1]["tables"][0]["rows"][0],
+        serde_json::json!({ "CODE": "DE", "COUNTRY": "Germany" })
+    );
+    assert_eq!(records[2]["status"], "error");
+    assert!(records[2]["error"]
+        .as_str()
+        .unwrap()
+        .contains("neither a file nor a built-in country"));
+    assert_eq!(records[3]["status"], "error");
+    assert_eq!(records[3]["error"], "unknown command `nope`");
+    assert!(records[4]["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|message| message["text"] == "Holidays: none"));
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUFY_OUTPUT", "json")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin("version\nexit\n")
+        .assert()
+        .success()
+        .stdout(contains("{\"command\":\"version\",\"status\":\"ok\""));
+
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.args(["--output", "yaml"])
+        .env("BUDGET_CORE_HOME", home.path())
+        .assert()
+        .failure()
+        .stderr(contains("unknown output format `yaml`"));
+}
+
+#[test]
+fn accounts_and_categories_keep_manual_order_with_favorites_first() {
+    let home = tempfile::tempdir().unwrap();
+    let tmp = NamedTempFile::new().unwrap();
+    let script = format!(
+        concat!(
+            "ledger new Household monthly\n",
+            "account add Checking bank\n",
+            "account add Savings savings\n",
+            "account add Wallet cash\n",
+            "account move Wallet top\n",
+            "account move Wallet up\n",
+            "account star Savings\n",
+            "account move Checking sideways\n",
+            "category add Food expense\n",
+            "category add Rent expense\n",
+            "category star Rent\n",
+            "list accounts\n",
+            "ledger save {}\n",
+            "exit\n"
+        ),
+        tmp.path().display()
+    );
+
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(contains("Moved account Wallet top."))
+        .stdout(contains("Wallet is already at the top of its group."))
+        .stdout(contains("Starred account Savings; it now lists first."))
+        .stdout(contains("usage: account move <name> <up|down|top|bottom>"))
+        .stdout(contains("★ Savings"));
+
+    let ledger = load_ledger_from_path(tmp.path()).unwrap();
+    let accounts: Vec<_> = ledger
+        .accounts
+        .iter()
+        .map(|account| (account.name.as_str(), account.favorite))
+        .collect();
+    assert_eq!(
+        accounts,
+        [("Savings", true), ("Wallet", false), ("Checking", false)]
+    );
+    let categories: Vec<_> = ledger
+        .categories
+        .iter()
+        .map(|category| category.name.as_str())
+        .collect();
+    assert_eq!(categories, ["Rent", "Food"]);
+}
+
+#[test]
+fn account_and_category_styles_show_in_lists_unless_plain() {
+    let home = tempfile::tempdir().unwrap();
+    let tmp = NamedTempFile::new().unwrap();
+    let script = format!(
+        concat!(
+            "ledger new Household monthly\n",
+            "account add Checking bank\n",
+            "category add Food expense\n",
+            "account style Checking --color blue --icon 🏦\n",
+            "category style Food --icon 🍎\n",
+            "category style Food --color mauve\n",
+            "account style Checking\n",
+            "list accounts\n",
+            "list categories\n",
+            "ledger save {}\n",
+            "exit\n"
+        ),
+        tmp.path().display()
+    );
+
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Styled account Checking: color blue, icon 🏦.")
+                .and(contains("Styled category Food: icon 🍎."))
+                .and(contains("unknown color `mauve`"))
+                .and(contains("Account Checking: color blue, icon 🏦."))
+                .and(contains("🏦 Checking"))
+                .and(contains("🍎 Food")),
+        );
+
+    let ledger = load_ledger_from_path(tmp.path()).unwrap();
+    assert_eq!(ledger.accounts[0].appearance.icon.as_deref(), Some("🏦"));
+
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(format!(
+            "config set plain_output true\nledger load {}\nlist accounts\nexit\n",
+            tmp.path().display()
+        ))
+        .assert()
+        .success()
+        .stdout(contains("Checking").and(contains("🏦").not()));
+}
+
+#[test]
+fn budget_allocate_splits_detected_income_across_categories() {
+    let home = tempfile::tempdir().unwrap();
+    let tmp = NamedTempFile::new().unwrap();
+    let today = chrono::Utc::now().date_naive();
+    let script = format!(
+        concat!(
+            "ledger new Household monthly\n",
+            "account add Checking bank\n",
+            "account add Employer income\n",
+            "category add Food expense\n",
+            "category add Rent expense\n",
+            "transaction add 1 0 {} 3000\n",
+            "category budget allocate Rent=900\n",
+            "category budget allocate Food=rest\n",
+            "category budget allocate Fuel=50\n",
+            "category budget allocate Food=rest Rent=rest\n",
+            "ledger save {}\n",
+            "exit\n"
+        ),
+        today,
+        tmp.path().display()
+    );
+
+    Command::cargo_bin("budget_core_cli")
+        .unwrap()
+        .env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(contains("No income received recently; using"))
+        .stdout(contains("Updated 1 budget."))
+        .stdout(contains("Unassigned: 2100.00"))
+        .stdout(contains("Unassigned: 0.00"))
+        .stdout(contains("`Fuel` is not a visible expense category"))
+        .stdout(contains("only one category can take the rest"));
+
+    let ledger = load_ledger_from_path(tmp.path()).unwrap();
+    let budgets: Vec<_> = ledger
+        .categories
+        .iter()
+        .map(|category| {
+            (
+                category.name.as_str(),
+                category
+                    .budget
+                    .as_ref()
+                    .map(|budget| budget.amount.to_major()),
+            )
+        })
+        .collect();
+    assert_eq!(budgets, [("Food", Some(2100.0)), ("Rent", Some(900.0))]);
+}
+
+#[test]
+fn transaction_attachments_copy_receipts_next_to_the_ledger() {
+    let home = tempfile::tempdir().unwrap();
+    let data = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Receipts", BudgetPeriod::default());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    ledger.add_transaction(Transaction::new(
+        checking,
+        shop,
+        None,
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+        usd(42.0),
+    ));
+    let ledger_path = data.path().join("receipts.bfy");
+    save_ledger_to_path(&ledger, &ledger_path).unwrap();
+    let receipt = data.path().join("Receipt.PDF");
+    std::fs::write(&receipt, b"total 42.00").unwrap();
+
+    let run = |script: String| {
+        let output = Command::cargo_bin("budget_core_cli")
+            .unwrap()
+            .env("BUDGET_CORE_CLI_SCRIPT", "1")
+            .env("BUDGET_CORE_HOME", home.path())
+            .write_stdin(script)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    let attached = run(format!(
+        "ledger load {path}\ntransaction attachments 0\ntransaction attach 0 {receipt}\ntransaction attach 0 {receipt}\ntransaction attach 0 https://shop.example/orders/881\nledger save\nexit\n",
+        path = ledger_path.display(),
+        receipt = receipt.display()
+    ));
+    assert!(attached.contains("Transaction [0] has no attachments."));
+    assert!(attached.contains("Attached Receipt.PDF to transaction [0]."));
+    assert_eq!(
+        attached.matches("Ledger saved to").count(),
+        2,
+        "attaching the file saved the ledger before `ledger save`"
+    );
+    assert!(attached.contains("transaction already has `Receipt.PDF` attached"));
+    assert!(attached.contains("Attached https://shop.example/orders/881 to transaction [0]."));
+
+    let stored: Vec<_> = std::fs::read_dir(data.path().join("receipts.attachments"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].extension().unwrap(), "pdf");
+    let list = format!(
+        "ledger load {}\ntransaction attachments 0\nexit\n",
+        ledger_path.display()
+    );
+    let listed = run(list.clone());
+    assert!(listed.contains(&stored[0].display().to_string()));
+    assert!(listed.contains("https://shop.example/orders/881"));
+    assert!(!listed.contains("(changed since attached)"));
+
+    std::fs::write(&stored[0], b"total 99.00").unwrap();
+    assert!(run(list.clone()).contains("(changed since attached)"));
+    std::fs::remove_file(&stored[0]).unwrap();
+    assert!(run(list).contains("(missing)"));
+}
+
+#[test]
+fn index_commands_accept_ids_that_survive_reordering() {
+    let home = tempfile::tempdir().unwrap();
+    let mut ledger = Ledger::new("CLI Ids", BudgetPeriod::default());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.id = uuid::Uuid::from_u128(0xc0ffee00_0000_4000_8000_000000000001);
+    let checking = ledger.add_account(checking);
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    for (day, id) in [
+        (1, 0xab12cd34_0000_4000_8000_000000000001_u128),
+        (2, 0x12345678_0000_4000_8000_000000000002),
+        (3, 0xab12ff00_0000_4000_8000_000000000003),
+    ] {
+        let mut txn = Transaction::new(
+            checking,
+            shop,
+            None,
+            NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+            usd(10.0 * day as f64),
+        );
+        txn.id = uuid::Uuid::from_u128(id);
+        ledger.add_transaction(txn);
+    }
+    let tmp = NamedTempFile::new().unwrap();
+    save_ledger_to_path(&ledger, tmp.path()).unwrap();
+
+    let script = format!(
+        "ledger load {}\ntransaction tag add --id ab12cd34 first\ntransaction tag add @12345678 second\ntransaction tag add 12345678 third\ntransaction show ab12ff00\ntransaction show ab12\ntransaction show --id ffff0000\ntransaction add c0ffee00 1 2025-03-09 5\naccount move Shop top\ntransaction add --id c0ffee00 0 2025-03-10 6\nlist transactions --id ab12cd34\nexit\n",
+        tmp.path().display()
+    );
+    let mut cmd = Command::cargo_bin("budget_core_cli").unwrap();
+    cmd.env("BUDGET_CORE_CLI_SCRIPT", "1")
+        .env("BUDGET_CORE_HOME", home.path())
+        .write_stdin(script)
+        .assert()
+        .success()
+        .stdout(
+            contains("Tagged transaction [0] with first.")
+                .and(contains("Tagged transaction [1] with second."))
+                .and(contains("transaction index out of range"))
+                .and(contains("Transaction [2] T-0003"))
+                .and(contains("Id: ab12ff00"))
+                .and(contains(
+                    "id `ab12` matches more than one transaction; type more of it",
+                ))
+                .and(contains("no transaction with id `ffff0000`"))
+                .and(contains("(Checking → Shop ) on 09 Mar 2025"))
+                .and(contains("(Checking → Shop ) on 10 Mar 2025"))
+                .and(contains("unknown flag `--id`")),
+        );
+}
@@ -15,6 +15,7 @@ fn formats_currency_with_locale() {
         negative_style: NegativeStyle::Parentheses,
         screen_reader_mode: false,
         high_contrast_mode: false,
+        extra: Default::default(),
     };
     let code = CurrencyCode::new("EUR");
     let formatted = format_currency_value(-1234.5, &code, &locale, &options);
@@ -0,0 +1,71 @@
+use budget_core::cli::ui::fuzzy_finder::{fuzzy_score, FuzzyFinder, FuzzyItem};
+use crossterm::event::KeyCode;
+
+fn finder() -> FuzzyFinder {
+    FuzzyFinder::new(
+        "Commands",
+        vec![
+            FuzzyItem::new("ledger", "ledger", "Ledger operations"),
+            FuzzyItem::new("transaction", "transaction", "Manage transactions"),
+            FuzzyItem::new("summary", "summary", "Show ledger summary"),
+            FuzzyItem::new("net-worth", "net-worth", "Show assets minus debts"),
+        ],
+    )
+}
+
+fn keys(text: &str) -> Vec<KeyCode> {
+    text.chars().map(KeyCode::Char).collect()
+}
+
+#[test]
+fn subsequences_match_and_tighter_matches_score_higher() {
+    assert!(fuzzy_score("trn", "transaction").is_some());
+    assert!(fuzzy_score("xt", "transaction").is_none());
+    assert!(fuzzy_score("TRANS", "transaction") > fuzzy_score("tcn", "transaction"));
+    assert!(fuzzy_score("nw", "net-worth") > fuzzy_score("nw", "unwind"));
+}
+
+#[test]
+fn empty_query_keeps_list_order() {
+    let finder = finder();
+    let keys: Vec<_> = finder
+        .matches("")
+        .iter()
+        .map(|item| item.key.as_str())
+        .collect();
+    assert_eq!(keys, ["ledger", "transaction", "summary", "net-worth"]);
+}
+
+#[test]
+fn label_matches_rank_above_description_matches() {
+    let finder = finder();
+    let matches = finder.matches("led");
+    assert_eq!(matches[0].key, "ledger");
+    assert!(matches.iter().any(|item| item.key == "summary"));
+}
+
+#[test]
+fn typing_narrows_and_enter_picks_the_highlighted_item() {
+    let finder = finder();
+    let mut events = keys("sum");
+    events.push(KeyCode::Enter);
+    assert_eq!(finder.run_simulated(&events).as_deref(), Some("summary"));
+
+    let mut events = keys("nwx");
+    events.extend([KeyCode::Backspace, KeyCode::Enter]);
+    assert_eq!(finder.run_simulated(&events).as_deref(), Some("net-worth"));
+
+    assert_eq!(
+        finder
+            .run_simulated(&[KeyCode::Down, KeyCode::Enter])
+            .as_deref(),
+        Some("transaction")
+    );
+    assert_eq!(
+        finder
+            .run_simulated(&[KeyCode::Up, KeyCode::Enter])
+            .as_deref(),
+        Some("net-worth")
+    );
+    assert_eq!(finder.run_simulated(&[KeyCode::Esc]), None);
+}
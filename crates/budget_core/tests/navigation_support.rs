@@ -12,6 +12,12 @@ pub struct NavigationTestHarness {
     home: TempDir,
 }
 
+impl Default for NavigationTestHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NavigationTestHarness {
     pub fn new() -> Self {
         Self {
@@ -3,12 +3,17 @@ use budget_core::ledger::{
 };
 use bufy_core::storage::LedgerStorage;
 use bufy_domain::BudgetPeriod as CategoryBudgetPeriod;
+use bufy_domain::{currency::CurrencyCode, Money};
 use bufy_storage_json::{JsonLedgerStorage as JsonStorage, StoragePaths};
 use chrono::NaiveDate;
 use std::fs;
 use std::path::Path;
 use tempfile::tempdir;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 fn sample_transaction(ledger: &mut Ledger, amount: f64) {
     let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
     let savings = ledger.add_account(Account::new("Savings", AccountKind::Savings));
@@ -17,7 +22,7 @@ fn sample_transaction(ledger: &mut Ledger, amount: f64) {
         savings,
         None,
         NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
-        amount,
+        usd(amount),
     );
     ledger.add_transaction(txn);
 }
@@ -79,9 +84,9 @@ fn atomic_save_failure_preserves_original_file() {
     );
     assert!(
         backups.iter().any(|entry| {
-            entry.id.starts_with("reliable_ledger_") && entry.id.ends_with(".json")
+            entry.id.starts_with("reliable_ledger-") && entry.id.ends_with(".bbfy")
         }),
-        "backup filename should include the ledger slug and use the .json extension"
+        "backup filename should include the ledger slug and use the .bbfy extension"
     );
 
     let _ = fs::remove_dir_all(&tmp_path);
@@ -135,7 +140,7 @@ fn category_budget_field_roundtrips_through_storage() {
     let store = storage_with_retention(temp.path(), 2);
     let mut ledger = Ledger::new("Categories", BudgetPeriod::monthly());
     let mut groceries = Category::new("Groceries", CategoryKind::Expense);
-    groceries.set_budget(450.0, CategoryBudgetPeriod::Monthly, None);
+    groceries.set_budget(usd(450.0), CategoryBudgetPeriod::Monthly, None);
     let groceries_id = groceries.id;
     ledger.add_category(groceries);
     store
@@ -146,6 +151,6 @@ fn category_budget_field_roundtrips_through_storage() {
         .category(groceries_id)
         .expect("category present after load");
     let budget = category.budget.as_ref().expect("budget data persisted");
-    assert_eq!(budget.amount, 450.0);
+    assert_eq!(budget.amount, usd(450.0));
     assert_eq!(budget.reference_date, None);
 }
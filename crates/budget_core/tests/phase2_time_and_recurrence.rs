@@ -3,12 +3,17 @@ use budget_core::ledger::{
     transaction::{Recurrence, RecurrenceMode},
     Account, AccountKind, BudgetPeriod, DateWindow, Ledger, TimeInterval, TimeUnit, Transaction,
 };
+use bufy_domain::{currency::CurrencyCode, Money};
 use bufy_storage_json::{JsonLedgerStorage as JsonStorage, StoragePaths};
 use chrono::{NaiveDate, TimeZone, Utc};
 use serde_json::Value;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 #[test]
 fn test_timeinterval_next_date() {
     let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
@@ -106,7 +111,7 @@ fn test_serialization_roundtrip() {
         to,
         None,
         NaiveDate::from_ymd_opt(2025, 5, 5).unwrap(),
-        1200.55,
+        usd(1200.55),
     );
     transaction.set_recurrence(Some(Recurrence::new(
         NaiveDate::from_ymd_opt(2025, 5, 5).unwrap(),
@@ -166,7 +171,7 @@ fn test_materialize_and_forecast_flow() {
     let to = ledger.add_account(Account::new("Rent", AccountKind::ExpenseDestination));
 
     let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
-    let mut template = Transaction::new(from, to, None, start, 1500.0);
+    let mut template = Transaction::new(from, to, None, start, usd(1500.0));
     template.set_recurrence(Some(Recurrence::new(
         start,
         TimeInterval {
@@ -207,7 +212,7 @@ fn materialize_handles_backlog_across_multiple_periods() {
     let to = ledger.add_account(Account::new("Rent", AccountKind::ExpenseDestination));
 
     let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
-    let mut template = Transaction::new(from, to, None, start, 1500.0);
+    let mut template = Transaction::new(from, to, None, start, usd(1500.0));
     template.set_recurrence(Some(Recurrence::new(
         start,
         TimeInterval {
@@ -20,6 +20,7 @@ use bufy_domain::{
     category::{Category, CategoryKind},
     transaction::Transaction,
 };
+use bufy_domain::{currency::CurrencyCode, Money};
 use bufy_storage_json::{JsonLedgerStorage as JsonStorage, StoragePaths};
 use chrono::NaiveDate;
 use crossterm::event::KeyCode;
@@ -28,6 +29,10 @@ use once_cell::sync::Lazy;
 use std::sync::{Mutex, MutexGuard};
 use tempfile::TempDir;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 fn build_context(temp: &TempDir) -> ShellContext {
     let storage = {
         let paths = StoragePaths {
@@ -42,7 +47,7 @@ fn build_context(temp: &TempDir) -> ShellContext {
     ));
     let config = Arc::new(RwLock::new(Config::default()));
     let formatters = CliFormatters::new(config.clone());
-    let clock: Arc<dyn Clock> = Arc::new(SystemClock::default());
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
     ShellContext {
         mode: CliMode::Script,
         registry: CommandRegistry::new(),
@@ -57,7 +62,11 @@ fn build_context(temp: &TempDir) -> ShellContext {
         active_simulation_name: None,
         current_simulation: None,
         last_command: None,
+        recent_commands: Vec::new(),
         running: true,
+        failed_assertions: 0,
+        verbose: false,
+        last_transaction_date: None,
         ui_style: style::style(),
     }
 }
@@ -79,7 +88,7 @@ fn ledger_with_recurrence() -> Ledger {
         to_id,
         Some(category_id),
         NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
-        42.0,
+        usd(42.0),
     );
     txn.set_recurrence(Some(bufy_domain::transaction::Recurrence::new(
         NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
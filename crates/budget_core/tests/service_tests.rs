@@ -7,8 +7,13 @@ use bufy_domain::{
     category::{Category, CategoryKind},
     ledger::{BudgetScope, DateWindow},
 };
+use bufy_domain::{currency::CurrencyCode, Money};
 use chrono::NaiveDate;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 fn prepared_ledger() -> Ledger {
     let mut ledger = Ledger::new("Services", BudgetPeriod::monthly());
     let income = Category::new("Income", CategoryKind::Income);
@@ -22,8 +27,8 @@ fn prepared_ledger() -> Ledger {
     AccountService::add(&mut ledger, savings.clone()).unwrap();
 
     let date = NaiveDate::from_ymd_opt(2024, 2, 10).unwrap();
-    let mut txn = Transaction::new(checking.id, savings.id, Some(expense_id), date, 250.0);
-    txn.actual_amount = Some(250.0);
+    let mut txn = Transaction::new(checking.id, savings.id, Some(expense_id), date, usd(250.0));
+    txn.actual_amount = Some(usd(250.0));
     txn.actual_date = Some(date);
     TransactionService::add(&mut ledger, txn).unwrap();
     ledger
@@ -3,8 +3,13 @@ use budget_core::ledger::{
     account::AccountKind, category::CategoryKind, Account, BudgetPeriod, Ledger, LedgerExt,
     SimulationStatus, TimeInterval, TimeUnit, Transaction,
 };
+use bufy_domain::{currency::CurrencyCode, Money};
 use chrono::NaiveDate;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 fn date(y: i32, m: u32, d: u32) -> NaiveDate {
     NaiveDate::from_ymd_opt(y, m, d).unwrap()
 }
@@ -25,14 +30,14 @@ fn simulation_round_trip_and_apply() {
         CategoryKind::Expense,
     ));
 
-    let txn = Transaction::new(checking, savings, None, date(2025, 1, 5), 100.0);
+    let txn = Transaction::new(checking, savings, None, date(2025, 1, 5), usd(100.0));
     ledger.add_transaction(txn);
 
     let clock = SystemClock;
     ledger
         .create_simulation("WhatIf", Some("Test".into()), &clock)
         .unwrap();
-    let simulated = Transaction::new(checking, savings, None, date(2025, 1, 10), 250.0);
+    let simulated = Transaction::new(checking, savings, None, date(2025, 1, 10), usd(250.0));
     ledger
         .add_simulation_transaction("WhatIf", simulated)
         .unwrap();
@@ -91,7 +96,13 @@ fn simulation_exclusion_updates_budget_impact() {
         "Housing",
         CategoryKind::Expense,
     ));
-    let txn = Transaction::new(from, to, Some(housing_category), date(2025, 1, 5), 200.0);
+    let txn = Transaction::new(
+        from,
+        to,
+        Some(housing_category),
+        date(2025, 1, 5),
+        usd(200.0),
+    );
     let txn_id = ledger.add_transaction(txn);
 
     let clock = SystemClock;
@@ -6,30 +6,39 @@ use bufy_domain::{
     account::{Account, AccountKind},
     ledger::{BudgetScope, DateWindow},
 };
+use bufy_domain::{currency::CurrencyCode, Money};
 use chrono::NaiveDate;
 use uuid::Uuid;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 fn ledger_with_simulation() -> Ledger {
     let mut ledger = Ledger::new("Sim", BudgetPeriod::monthly());
     let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
     let cash = ledger.add_account(Account::new("Cash", AccountKind::Cash));
 
     let date = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
-    let txn = Transaction::new(checking, cash, None, date, 100.0);
+    let txn = Transaction::new(checking, cash, None, date, usd(100.0));
     TransactionService::add(&mut ledger, txn).unwrap();
 
     let mut simulation = Simulation {
         id: Uuid::new_v4(),
         name: "Raise".into(),
         notes: None,
+        parent: None,
         status: SimulationStatus::Pending,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
         applied_at: None,
+        pre_apply_backup: None,
         changes: Vec::new(),
+        applied: Vec::new(),
+        extra: Default::default(),
     };
     simulation.changes.push(SimulationChange::AddTransaction {
-        transaction: Transaction::new(cash, checking, None, date, 25.0),
+        transaction: Transaction::new(cash, checking, None, date, usd(25.0)),
     });
     ledger.simulations.push(simulation);
     ledger
@@ -3,8 +3,13 @@ use budget_core::{
     ledger::{Account, AccountKind, BudgetPeriod, Ledger, TimeInterval, TimeUnit, Transaction},
     simulation,
 };
+use bufy_domain::{currency::CurrencyCode, Money};
 use chrono::NaiveDate;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 #[test]
 fn ledger_simulation_smoke() {
     init();
@@ -18,7 +23,7 @@ fn ledger_simulation_smoke() {
         to_account,
         None,
         NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
-        42.0,
+        usd(42.0),
     );
     transaction.set_recurrence(Some(budget_core::ledger::Recurrence::new(
         NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
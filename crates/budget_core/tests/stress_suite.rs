@@ -4,10 +4,15 @@ use budget_core::ledger::{
     LedgerExt, TimeInterval, TimeUnit, Transaction,
 };
 use bufy_core::storage::LedgerStorage;
+use bufy_domain::{currency::CurrencyCode, Money};
 use bufy_storage_json::{JsonLedgerStorage as JsonStorage, StoragePaths};
 use chrono::{Duration, NaiveDate};
 use tempfile::tempdir;
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 fn seed_ledger() -> Ledger {
     let mut ledger = Ledger::new(
         "Stress Harness",
@@ -37,7 +42,7 @@ fn seed_ledger() -> Ledger {
     let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
 
     // Recurring rent template.
-    let mut rent = Transaction::new(checking, landlord, Some(housing), start, 1500.0);
+    let mut rent = Transaction::new(checking, landlord, Some(housing), start, usd(1500.0));
     rent.set_recurrence(Some(budget_core::ledger::transaction::Recurrence::new(
         start,
         TimeInterval {
@@ -49,7 +54,7 @@ fn seed_ledger() -> Ledger {
     ledger.add_transaction(rent);
 
     // Weekly groceries recurrence.
-    let mut groceries_txn = Transaction::new(checking, grocer, Some(groceries), start, 200.0);
+    let mut groceries_txn = Transaction::new(checking, grocer, Some(groceries), start, usd(200.0));
     groceries_txn.set_recurrence(Some(budget_core::ledger::transaction::Recurrence::new(
         start,
         TimeInterval {
@@ -67,8 +72,8 @@ fn seed_ledger() -> Ledger {
             unit: TimeUnit::Month,
         }
         .add_to(start, month);
-        let mut paycheck = Transaction::new(employer, checking, Some(income), payday, -4000.0);
-        paycheck.actual_amount = Some(-4000.0);
+        let mut paycheck = Transaction::new(employer, checking, Some(income), payday, usd(-4000.0));
+        paycheck.actual_amount = Some(usd(-4000.0));
         paycheck.actual_date = Some(payday);
         ledger.add_transaction(paycheck);
     }
@@ -102,7 +107,7 @@ fn stress_repeated_save_load_and_forecast_cycles() {
             .find(|c| c.name == "Housing")
             .map(|c| c.id),
         NaiveDate::from_ymd_opt(2025, 2, 15).unwrap(),
-        100.0,
+        usd(100.0),
     );
     ledger
         .add_simulation_transaction("Scenario", scenario_txn)
@@ -120,7 +125,7 @@ fn stress_repeated_save_load_and_forecast_cycles() {
             for txn in ledger.transactions.iter_mut() {
                 if txn.actual_date.is_none() && txn.scheduled_date <= reference {
                     txn.actual_date = Some(txn.scheduled_date);
-                    txn.actual_amount = Some(txn.budgeted_amount);
+                    txn.actual_amount = Some(txn.budgeted_amount.clone());
                 }
             }
         }
@@ -159,7 +164,7 @@ fn stress_repeated_save_load_and_forecast_cycles() {
                     .find(|c| c.name == "Groceries")
                     .map(|c| c.id),
                 reference,
-                45.0,
+                usd(45.0),
             );
             ledger
                 .add_simulation_transaction("Scenario", extra)
@@ -0,0 +1,160 @@
+use std::sync::{Arc, RwLock};
+
+use budget_core::cli::commands::ledger::summary_drilldown;
+use budget_core::cli::core::{CliMode, ShellContext};
+use budget_core::cli::formatters::CliFormatters;
+use budget_core::cli::registry::CommandRegistry;
+use budget_core::cli::system_clock::SystemClock;
+use budget_core::cli::ui::{
+    style,
+    test_mode::{install_selector_events, reset_selector_events},
+};
+use budget_core::config::{Config, ConfigManager};
+use budget_core::core::ledger_manager::LedgerManager;
+use budget_core::ledger::{BudgetPeriod, BudgetScope, DateWindow, Ledger};
+use bufy_core::{Clock, SummaryService};
+use bufy_domain::{
+    account::{Account, AccountKind},
+    category::{Category, CategoryKind},
+    transaction::Transaction,
+};
+use bufy_domain::{currency::CurrencyCode, Money};
+use bufy_storage_json::{JsonLedgerStorage as JsonStorage, StoragePaths};
+use chrono::NaiveDate;
+use crossterm::event::KeyCode;
+use dialoguer::theme::ColorfulTheme;
+use once_cell::sync::Lazy;
+use std::sync::{Mutex, MutexGuard};
+use tempfile::TempDir;
+
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
+fn build_context(temp: &TempDir) -> ShellContext {
+    let storage = {
+        let paths = StoragePaths {
+            ledger_root: temp.path().join("ledgers"),
+            backup_root: temp.path().join("backups"),
+        };
+        JsonStorage::with_retention(paths, 3).unwrap()
+    };
+    let manager = Arc::new(RwLock::new(LedgerManager::new(Box::new(storage.clone()))));
+    let config_manager = Arc::new(RwLock::new(
+        ConfigManager::with_base_dir(temp.path().to_path_buf()).unwrap(),
+    ));
+    let config = Arc::new(RwLock::new(Config::default()));
+    let formatters = CliFormatters::new(config.clone());
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+    ShellContext {
+        mode: CliMode::Interactive,
+        registry: CommandRegistry::new(),
+        ledger_manager: manager,
+        theme: ColorfulTheme::default(),
+        storage,
+        clock,
+        formatters,
+        config_manager,
+        config,
+        ledger_path: None,
+        active_simulation_name: None,
+        current_simulation: None,
+        last_command: None,
+        recent_commands: Vec::new(),
+        running: true,
+        failed_assertions: 0,
+        verbose: false,
+        last_transaction_date: None,
+        ui_style: style::style(),
+    }
+}
+
+fn sample_ledger() -> Ledger {
+    let mut ledger = Ledger::new("Demo", BudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let store = ledger.add_account(Account::new("Store", AccountKind::ExpenseDestination));
+    let food = ledger.add_category(Category::new("Food", CategoryKind::Expense));
+    let rent = ledger.add_category(Category::new("Rent", CategoryKind::Expense));
+
+    ledger.add_transaction(Transaction::new(
+        checking,
+        store,
+        Some(food),
+        NaiveDate::from_ymd_opt(2024, 5, 3).unwrap(),
+        usd(40.0),
+    ));
+    ledger.add_transaction(Transaction::new(
+        checking,
+        store,
+        Some(rent),
+        NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+        usd(900.0),
+    ));
+    ledger
+}
+
+fn may_window() -> DateWindow {
+    DateWindow::new(
+        NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn drilldown_walks_category_transactions_and_back() {
+    let temp = TempDir::new().unwrap();
+    let context = build_context(&temp);
+    let ledger = sample_ledger();
+    let summary = SummaryService::summarize_window(&ledger, may_window(), BudgetScope::Custom);
+    context
+        .ledger_manager
+        .write()
+        .unwrap()
+        .set_current(ledger, None, Some("Demo".into()));
+
+    // Category list -> transactions -> detail (auto-dismissed) -> back -> exit.
+    let _script = SelectorScript::new(vec![
+        vec![KeyCode::Enter],
+        vec![KeyCode::Enter],
+        vec![KeyCode::Esc],
+        vec![KeyCode::Esc],
+    ]);
+    summary_drilldown::run_summary_drilldown(&context, &summary).unwrap();
+}
+
+#[test]
+fn drilldown_escape_returns_immediately() {
+    let temp = TempDir::new().unwrap();
+    let context = build_context(&temp);
+    let ledger = sample_ledger();
+    let summary = SummaryService::summarize_window(&ledger, may_window(), BudgetScope::Custom);
+    context
+        .ledger_manager
+        .write()
+        .unwrap()
+        .set_current(ledger, None, Some("Demo".into()));
+
+    let _script = SelectorScript::new(vec![vec![KeyCode::Esc]]);
+    summary_drilldown::run_summary_drilldown(&context, &summary).unwrap();
+}
+
+static TEST_MODE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+struct SelectorScript {
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl SelectorScript {
+    fn new(selectors: Vec<Vec<KeyCode>>) -> Self {
+        let guard = TEST_MODE_LOCK.lock().expect("test-mode lock");
+        install_selector_events(selectors);
+        Self { _guard: guard }
+    }
+}
+
+impl Drop for SelectorScript {
+    fn drop(&mut self) {
+        reset_selector_events();
+    }
+}
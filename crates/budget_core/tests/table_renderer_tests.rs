@@ -56,6 +56,14 @@ fn render_cell_respects_alignment() {
     assert_eq!(center, "   X   ");
 }
 
+#[test]
+fn wide_characters_take_two_columns() {
+    set_preferences(OutputPreferences::default());
+
+    assert_eq!(render_cell("🏦 Bank", 8, &Alignment::Left, 0), "🏦 Bank ");
+    assert_eq!(render_cell("🏦🏦🏦", 5, &Alignment::Left, 0), "🏦🏦…");
+}
+
 #[test]
 fn truncation_adds_ellipsis_and_resets_styles() {
     set_preferences(OutputPreferences::default());
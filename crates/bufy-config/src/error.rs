@@ -9,4 +9,7 @@ pub enum ConfigError {
 
     #[error("Serialization error: {0}")]
     Serde(String),
+
+    #[error("Secret store error: {0}")]
+    Secret(String),
 }
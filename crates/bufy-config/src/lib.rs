@@ -6,7 +6,12 @@
 pub mod error;
 pub mod manager;
 pub mod model;
+pub mod secrets;
 
 pub use error::ConfigError;
 pub use manager::ConfigManager;
-pub use model::{AccessibilitySettings, Config, Theme};
+pub use model::{
+    AccessibilitySettings, BankConnection, BankProvider, Config, Theme, TransactionDateDefault,
+    TransactionStatusDefault,
+};
+pub use secrets::{FileSecretStore, KeychainSecretStore, SecretStore};
@@ -1,5 +1,5 @@
 use serde::{de::Deserializer, Deserialize, Serialize};
-use std::{fmt, path::PathBuf};
+use std::{collections::BTreeMap, fmt, path::PathBuf};
 
 /// Stores user-configurable CLI preferences and metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +20,55 @@ pub struct Config {
     pub default_budget_period: String,
     #[serde(default)]
     pub default_currency_precision: Option<u8>,
+    /// Appends each save of a named ledger to its change feed.
+    #[serde(default)]
+    pub change_feed: bool,
+    /// Name recorded in the audit log for saved changes; the login name when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit_actor: Option<String>,
+    /// Category and account rows shown by `summary` before the rest are counted.
+    #[serde(default = "Config::default_summary_category_rows")]
+    pub summary_category_rows: usize,
+    /// Projected transactions listed by `forecast` before the rest are counted.
+    #[serde(default = "Config::default_forecast_transaction_rows")]
+    pub forecast_transaction_rows: usize,
+    /// Pipes interactive output taller than the terminal through `$PAGER`.
+    #[serde(default = "Config::default_pager")]
+    pub pager: bool,
+    /// Shows the active simulation next to base numbers in `summary`,
+    /// `forecast`, and `list transactions`.
+    #[serde(default)]
+    pub simulation_overlay: bool,
+    /// Status new transactions start with in the wizard and `add transaction`.
+    #[serde(default)]
+    pub default_transaction_status: TransactionStatusDefault,
+    /// Date new transactions start with: today, or the date last entered
+    /// this session.
+    #[serde(default)]
+    pub default_transaction_date: TransactionDateDefault,
+    /// Copies the scheduled date and amount into the actuals of a completed
+    /// transaction when they are left blank.
+    #[serde(default = "Config::default_autofill_actuals")]
+    pub autofill_actuals: bool,
+    /// Minutes within which a backup must have been taken before
+    /// `simulation apply`; one is created when none is that recent. Unset
+    /// applies without a backup.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub simulation_backup_minutes: Option<u32>,
+    /// Days an open-ended exchange rate override may stay in force before
+    /// reports and startup flag it as stale. Unset never flags it.
+    #[serde(default = "Config::default_stale_rate_days")]
+    pub stale_rate_days: Option<u32>,
+    /// Bank connections `sync bank` fetches from, keyed by connection name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub bank_connections: BTreeMap<String, BankConnection>,
+    /// Template for the interactive prompt; the built-in prompt when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_template: Option<String>,
+    /// Template for the banner printed when the interactive shell starts;
+    /// no banner when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub banner_template: Option<String>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     /// Optional custom root directory for ledgers. Defaults to `~/Documents/Ledgers`.
@@ -42,6 +91,20 @@ impl Default for Config {
             audio_feedback: false,
             default_budget_period: Self::default_budget_period_value(),
             default_currency_precision: None,
+            change_feed: false,
+            audit_actor: None,
+            summary_category_rows: Self::default_summary_category_rows(),
+            forecast_transaction_rows: Self::default_forecast_transaction_rows(),
+            pager: Self::default_pager(),
+            simulation_overlay: false,
+            default_transaction_status: TransactionStatusDefault::default(),
+            default_transaction_date: TransactionDateDefault::default(),
+            autofill_actuals: Self::default_autofill_actuals(),
+            simulation_backup_minutes: None,
+            stale_rate_days: Self::default_stale_rate_days(),
+            bank_connections: BTreeMap::new(),
+            prompt_template: None,
+            banner_template: None,
             default_ledger_root: None,
             default_backup_root: None,
         }
@@ -57,6 +120,26 @@ impl Config {
         true
     }
 
+    pub fn default_summary_category_rows() -> usize {
+        5
+    }
+
+    pub fn default_forecast_transaction_rows() -> usize {
+        8
+    }
+
+    pub fn default_pager() -> bool {
+        true
+    }
+
+    pub fn default_autofill_actuals() -> bool {
+        true
+    }
+
+    pub fn default_stale_rate_days() -> Option<u32> {
+        Some(30)
+    }
+
     pub fn resolve_default_ledger_root(&self) -> PathBuf {
         if let Some(path) = &self.default_ledger_root {
             return path.clone();
@@ -82,20 +165,20 @@ impl Config {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Theme {
     Plain,
+    #[default]
     Iconic,
 }
 
 impl Theme {
     fn from_value(value: Option<String>) -> Self {
-        value
-            .map(|v| Theme::from_str(v.trim()))
-            .unwrap_or_else(Theme::default)
+        value.map(|v| Theme::from_str(v.trim())).unwrap_or_default()
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(value: &str) -> Self {
         match value.trim().to_ascii_lowercase().as_str() {
             "plain" => Theme::Plain,
@@ -104,12 +187,6 @@ impl Theme {
     }
 }
 
-impl Default for Theme {
-    fn default() -> Self {
-        Theme::Iconic
-    }
-}
-
 impl fmt::Display for Theme {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let label = match self {
@@ -130,19 +207,118 @@ impl<'de> Deserialize<'de> for Theme {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AccessibilitySettings {
-    #[serde(default)]
-    pub plain_output: bool,
-    #[serde(default)]
-    pub high_contrast: bool,
+/// Status a new transaction starts with. `Completed` suits logging
+/// spending after the fact.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionStatusDefault {
+    #[default]
+    Planned,
+    Completed,
 }
 
-impl Default for AccessibilitySettings {
-    fn default() -> Self {
+impl TransactionStatusDefault {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "planned" => Some(Self::Planned),
+            "completed" => Some(Self::Completed),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TransactionStatusDefault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Planned => "planned",
+            Self::Completed => "completed",
+        })
+    }
+}
+
+/// Date a new transaction starts with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionDateDefault {
+    #[default]
+    Today,
+    /// The date of the transaction entered last, so a batch of receipts
+    /// from one day needs the date typed once.
+    LastUsed,
+}
+
+impl TransactionDateDefault {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "today" => Some(Self::Today),
+            "last_used" | "last" => Some(Self::LastUsed),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TransactionDateDefault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Today => "today",
+            Self::LastUsed => "last_used",
+        })
+    }
+}
+
+/// Bank connector a connection uses. Adapters for live banking APIs add a
+/// variant here and keep their credentials in the [`crate::SecretStore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BankProvider {
+    /// Reads accounts and transactions from a JSON file, for trying the sync
+    /// flow and for tests.
+    Mock,
+}
+
+impl BankProvider {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "mock" => Some(Self::Mock),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for BankProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Mock => "mock",
+        })
+    }
+}
+
+/// A registered bank connection.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BankConnection {
+    pub provider: BankProvider,
+    /// Where the provider reads from, e.g. the file of a `mock` connection.
+    pub source: String,
+    /// Connector cursor after the last sync, per bank account id. Only the
+    /// connector interprets it.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub cursors: BTreeMap<String, String>,
+}
+
+impl BankConnection {
+    pub fn new(provider: BankProvider, source: impl Into<String>) -> Self {
         Self {
-            plain_output: false,
-            high_contrast: false,
+            provider,
+            source: source.into(),
+            cursors: BTreeMap::new(),
         }
     }
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccessibilitySettings {
+    #[serde(default)]
+    pub plain_output: bool,
+    #[serde(default)]
+    pub high_contrast: bool,
+}
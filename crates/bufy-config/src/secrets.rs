@@ -0,0 +1,389 @@
+//! Passphrase storage for encrypted ledgers.
+//!
+//! Secrets are kept in the operating system's credential store when one is
+//! reachable (macOS Keychain via `security`, the freedesktop Secret Service
+//! via `secret-tool`). Everywhere else they fall back to a JSON file in the
+//! config directory, readable only by the owner and protected with DPAPI on
+//! Windows. Keys are ledger names.
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use crate::ConfigError;
+
+/// Service name under which BUFY registers its keychain entries.
+pub const KEYCHAIN_SERVICE: &str = "bufy";
+/// Environment variable that forces a backend (`keychain` or `file`).
+pub const SECRET_BACKEND_ENV: &str = "BUFY_SECRET_BACKEND";
+
+const SECRETS_FILE: &str = "secrets.json";
+const KEYCHAIN_INDEX_FILE: &str = "secret_keys.json";
+
+/// Storage for ledger passphrases.
+pub trait SecretStore: Send + Sync {
+    /// Human-readable backend name, e.g. `macOS Keychain`.
+    fn backend(&self) -> &'static str;
+    fn get(&self, key: &str) -> Result<Option<String>, ConfigError>;
+    fn set(&self, key: &str, secret: &str) -> Result<(), ConfigError>;
+    /// Removes a secret, returning whether one was stored.
+    fn delete(&self, key: &str) -> Result<bool, ConfigError>;
+    /// Keys with a stored secret, sorted.
+    fn keys(&self) -> Result<Vec<String>, ConfigError>;
+}
+
+/// Picks the best available store for `config_dir`.
+///
+/// `BUFY_SECRET_BACKEND=file` or `keychain` overrides detection.
+pub fn detect(config_dir: &Path) -> Box<dyn SecretStore> {
+    let forced = env::var(SECRET_BACKEND_ENV)
+        .ok()
+        .map(|value| value.to_ascii_lowercase());
+    let keychain = KeychainTool::available();
+    match (forced.as_deref(), keychain) {
+        (Some("file"), _) | (_, None) => Box::new(FileSecretStore::for_platform(config_dir)),
+        (_, Some(tool)) => Box::new(KeychainSecretStore::new(tool, config_dir)),
+    }
+}
+
+/// How the file fallback protects values at rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileProtection {
+    /// Stored as-is; the file is only readable by its owner.
+    Plain,
+    /// Encrypted for the current Windows user via DPAPI.
+    Dpapi,
+}
+
+/// Secrets kept in `secrets.json` inside the config directory.
+#[derive(Debug, Clone)]
+pub struct FileSecretStore {
+    path: PathBuf,
+    protection: FileProtection,
+}
+
+impl FileSecretStore {
+    pub fn new(path: PathBuf, protection: FileProtection) -> Self {
+        Self { path, protection }
+    }
+
+    /// Plain storage everywhere except Windows, where DPAPI is used.
+    pub fn for_platform(config_dir: &Path) -> Self {
+        let protection = if cfg!(windows) {
+            FileProtection::Dpapi
+        } else {
+            FileProtection::Plain
+        };
+        Self::new(config_dir.join(SECRETS_FILE), protection)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, String>, ConfigError> {
+        read_json(&self.path)
+    }
+
+    fn write(&self, entries: &BTreeMap<String, String>) -> Result<(), ConfigError> {
+        write_private_json(&self.path, entries)
+    }
+}
+
+impl SecretStore for FileSecretStore {
+    fn backend(&self) -> &'static str {
+        match self.protection {
+            FileProtection::Plain => "file",
+            FileProtection::Dpapi => "file (Windows DPAPI)",
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, ConfigError> {
+        match self.read()?.remove(key) {
+            Some(stored) => match self.protection {
+                FileProtection::Plain => Ok(Some(stored)),
+                FileProtection::Dpapi => dpapi(DPAPI_UNPROTECT, &stored).map(Some),
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn set(&self, key: &str, secret: &str) -> Result<(), ConfigError> {
+        let stored = match self.protection {
+            FileProtection::Plain => secret.to_string(),
+            FileProtection::Dpapi => dpapi(DPAPI_PROTECT, secret)?,
+        };
+        let mut entries = self.read()?;
+        entries.insert(key.to_string(), stored);
+        self.write(&entries)
+    }
+
+    fn delete(&self, key: &str) -> Result<bool, ConfigError> {
+        let mut entries = self.read()?;
+        let removed = entries.remove(key).is_some();
+        if removed {
+            self.write(&entries)?;
+        }
+        Ok(removed)
+    }
+
+    fn keys(&self) -> Result<Vec<String>, ConfigError> {
+        Ok(self.read()?.into_keys().collect())
+    }
+}
+
+/// Command-line front end to the platform credential store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeychainTool {
+    /// macOS `security` (login keychain).
+    MacSecurity,
+    /// `secret-tool` from libsecret (GNOME Keyring, KWallet, KeePassXC).
+    SecretTool,
+}
+
+impl KeychainTool {
+    /// The tool usable on this platform, if it is installed.
+    pub fn available() -> Option<Self> {
+        let candidate = if cfg!(target_os = "macos") {
+            Self::MacSecurity
+        } else if cfg!(all(unix, not(target_os = "macos"))) {
+            Self::SecretTool
+        } else {
+            return None;
+        };
+        on_path(candidate.program()).then_some(candidate)
+    }
+
+    fn program(self) -> &'static str {
+        match self {
+            Self::MacSecurity => "security",
+            Self::SecretTool => "secret-tool",
+        }
+    }
+}
+
+/// Secrets in the OS keychain. The keychain cannot be enumerated per
+/// service portably, so the stored keys (never the secrets) are tracked in
+/// `secret_keys.json`.
+#[derive(Debug, Clone)]
+pub struct KeychainSecretStore {
+    tool: KeychainTool,
+    index_path: PathBuf,
+}
+
+impl KeychainSecretStore {
+    pub fn new(tool: KeychainTool, config_dir: &Path) -> Self {
+        Self {
+            tool,
+            index_path: config_dir.join(KEYCHAIN_INDEX_FILE),
+        }
+    }
+
+    fn run(&self, args: &[&str], stdin: Option<&str>) -> Result<Option<String>, ConfigError> {
+        let mut child = Command::new(self.tool.program())
+            .args(args)
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            pipe.write_all(input.as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if output.status.success() {
+            Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn index(&self) -> Result<Vec<String>, ConfigError> {
+        read_json(&self.index_path)
+    }
+
+    fn update_index(&self, key: &str, present: bool) -> Result<(), ConfigError> {
+        let mut keys = self.index()?;
+        keys.retain(|existing| existing != key);
+        if present {
+            keys.push(key.to_string());
+            keys.sort();
+        }
+        write_private_json(&self.index_path, &keys)
+    }
+
+    fn failure(&self, action: &str, key: &str) -> ConfigError {
+        ConfigError::Secret(format!(
+            "{} could not {} the passphrase for `{}`; set {}=file to use the file store instead",
+            self.backend(),
+            action,
+            key,
+            SECRET_BACKEND_ENV
+        ))
+    }
+}
+
+impl SecretStore for KeychainSecretStore {
+    fn backend(&self) -> &'static str {
+        match self.tool {
+            KeychainTool::MacSecurity => "macOS Keychain",
+            KeychainTool::SecretTool => "Secret Service",
+        }
+    }
+
+    fn get(&self, key: &str) -> Result<Option<String>, ConfigError> {
+        let output = match self.tool {
+            KeychainTool::MacSecurity => self.run(
+                &[
+                    "find-generic-password",
+                    "-s",
+                    KEYCHAIN_SERVICE,
+                    "-a",
+                    key,
+                    "-w",
+                ],
+                None,
+            )?,
+            KeychainTool::SecretTool => self.run(
+                &["lookup", "service", KEYCHAIN_SERVICE, "account", key],
+                None,
+            )?,
+        };
+        Ok(output.map(|secret| secret.trim_end_matches(['\r', '\n']).to_string()))
+    }
+
+    fn set(&self, key: &str, secret: &str) -> Result<(), ConfigError> {
+        let label = format!("BUFY ledger {}", key);
+        let stored = match self.tool {
+            // `security` only takes the password as an argument.
+            KeychainTool::MacSecurity => self.run(
+                &[
+                    "add-generic-password",
+                    "-U",
+                    "-s",
+                    KEYCHAIN_SERVICE,
+                    "-a",
+                    key,
+                    "-l",
+                    &label,
+                    "-w",
+                    secret,
+                ],
+                None,
+            )?,
+            KeychainTool::SecretTool => self.run(
+                &[
+                    "store",
+                    "--label",
+                    &label,
+                    "service",
+                    KEYCHAIN_SERVICE,
+                    "account",
+                    key,
+                ],
+                Some(secret),
+            )?,
+        };
+        if stored.is_none() {
+            return Err(self.failure("store", key));
+        }
+        self.update_index(key, true)
+    }
+
+    fn delete(&self, key: &str) -> Result<bool, ConfigError> {
+        let existed = self.get(key)?.is_some();
+        if existed {
+            let removed = match self.tool {
+                KeychainTool::MacSecurity => self.run(
+                    &["delete-generic-password", "-s", KEYCHAIN_SERVICE, "-a", key],
+                    None,
+                )?,
+                KeychainTool::SecretTool => self.run(
+                    &["clear", "service", KEYCHAIN_SERVICE, "account", key],
+                    None,
+                )?,
+            };
+            if removed.is_none() {
+                return Err(self.failure("remove", key));
+            }
+        }
+        self.update_index(key, false)?;
+        Ok(existed)
+    }
+
+    fn keys(&self) -> Result<Vec<String>, ConfigError> {
+        self.index()
+    }
+}
+
+const DPAPI_PROTECT: &str = "$s = [Console]::In.ReadToEnd(); \
+     ConvertTo-SecureString $s -AsPlainText -Force | ConvertFrom-SecureString";
+const DPAPI_UNPROTECT: &str = "$e = [Console]::In.ReadToEnd().Trim(); \
+     [System.Net.NetworkCredential]::new('', (ConvertTo-SecureString $e)).Password";
+
+fn dpapi(script: &str, input: &str) -> Result<String, ConfigError> {
+    let mut child = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut pipe) = child.stdin.take() {
+        pipe.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(ConfigError::Secret("DPAPI call failed".into()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches(['\r', '\n'])
+        .to_string())
+}
+
+fn on_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+fn read_json<T>(path: &Path) -> Result<T, ConfigError>
+where
+    T: serde::de::DeserializeOwned + Default,
+{
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let data = fs::read_to_string(path)?;
+    serde_json::from_str(&data).map_err(|err| ConfigError::Serde(err.to_string()))
+}
+
+fn write_private_json<T: serde::Serialize>(path: &Path, value: &T) -> Result<(), ConfigError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json =
+        serde_json::to_string_pretty(value).map_err(|err| ConfigError::Serde(err.to_string()))?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(json.as_bytes())?;
+    file.sync_all()?;
+    Ok(())
+}
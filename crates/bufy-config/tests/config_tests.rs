@@ -1,4 +1,5 @@
-use bufy_config::{Config, ConfigManager};
+use bufy_config::secrets::FileProtection;
+use bufy_config::{Config, ConfigManager, FileSecretStore, SecretStore};
 use tempfile::tempdir;
 
 #[test]
@@ -14,9 +15,11 @@ fn config_manager_persists_and_loads_config() {
     let dir = tempdir().expect("tempdir");
     let manager = ConfigManager::new(dir.path().join("config.json"), dir.path().join("backups"));
 
-    let mut cfg = Config::default();
-    cfg.currency = "USD".to_string();
-    cfg.locale = "en_US".to_string();
+    let cfg = Config {
+        currency: "USD".to_string(),
+        locale: "en_US".to_string(),
+        ..Config::default()
+    };
 
     manager.save(&cfg).expect("save config");
     let loaded = manager.load().expect("load config");
@@ -24,3 +27,32 @@ fn config_manager_persists_and_loads_config() {
     assert_eq!(loaded.currency, "USD");
     assert_eq!(loaded.locale, "en_US");
 }
+
+#[test]
+fn file_secret_store_round_trips_and_lists_keys() {
+    let dir = tempdir().expect("tempdir");
+    let store = FileSecretStore::new(dir.path().join("secrets.json"), FileProtection::Plain);
+
+    assert_eq!(store.get("household").unwrap(), None);
+    store.set("household", "correct horse").unwrap();
+    store.set("archive", "battery staple").unwrap();
+
+    assert_eq!(
+        store.get("household").unwrap().as_deref(),
+        Some("correct horse")
+    );
+    assert_eq!(store.keys().unwrap(), vec!["archive", "household"]);
+    assert!(store.delete("archive").unwrap());
+    assert!(!store.delete("archive").unwrap());
+    assert_eq!(store.keys().unwrap(), vec!["household"]);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(store.path())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -1,9 +1,28 @@
 //! Business logic helpers for validated account mutations.
 
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use tracing::instrument;
 use uuid::Uuid;
 
-use bufy_domain::{account::Account, Ledger};
+use bufy_domain::{
+    account::{Account, AccountStatus},
+    appearance::Appearance,
+    move_within_block, pin_favorites, set_favorite_at, DateWindow, Ledger, Money, MoveDirection,
+    Transaction, TransactionStatus,
+};
 
+use crate::journal::{ChangeJournal, JournalEntry};
+use crate::stats::{
+    account_balance, minor_in, to_major, trailing_window, AccountStats, ActivityTracker,
+    RunningBalance, STATS_AVERAGE_MONTHS,
+};
+use crate::transaction_service::TransactionService;
+use crate::validation::{
+    check_amount, check_date, check_icon, check_name, AmountRule, ValidationIssue,
+    ValidationIssueKind,
+};
 use crate::CoreError;
 
 /// Provides validated mutations for [`Account`] entities.
@@ -13,35 +32,149 @@ pub struct AccountService;
 
 impl AccountService {
     /// Adds a new account after validating uniqueness and linked category.
-    pub fn add(ledger: &mut Ledger, account: Account) -> Result<(), CoreError> {
-        Self::validate_name(ledger, None, &account.name)?;
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn add(ledger: &mut Ledger, mut account: Account) -> Result<(), CoreError> {
+        account.name = Self::validate_name(ledger, None, &account.name)?;
+        Self::validate_opening_balance(account.opening_balance.as_ref())?;
         if let Some(category_id) = account.category_id {
             Self::ensure_category_exists(ledger, category_id)?;
         }
+        let currency = ledger.holding_currency(&account);
+        if let Some(balance) = account.opening_balance.as_mut() {
+            *balance = balance.in_currency(&currency)?;
+        }
         ledger.add_account(account);
         Ok(())
     }
 
-    /// Updates an existing account by applying the provided changeset.
+    /// Updates an existing account by applying the provided changeset. Its
+    /// opening balance and the transactions through it are recounted in its
+    /// currency; an amount that does not fit that currency rejects the edit
+    /// before anything changes.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
     pub fn edit(ledger: &mut Ledger, id: Uuid, changes: Account) -> Result<(), CoreError> {
-        Self::validate_name(ledger, Some(id), &changes.name)?;
+        let name = Self::validate_name(ledger, Some(id), &changes.name)?;
+        Self::validate_opening_balance(changes.opening_balance.as_ref())?;
         if let Some(category_id) = changes.category_id {
             Self::ensure_category_exists(ledger, category_id)?;
         }
+        if ledger.account(id).is_none() {
+            return Err(CoreError::AccountNotFound(id.to_string()));
+        }
+        ledger.change_currencies(|ledger| {
+            let account = ledger.account_mut(id).expect("account checked above");
+            account.name = name;
+            account.kind = changes.kind;
+            account.category_id = changes.category_id;
+            account.opening_balance = changes.opening_balance;
+            account.notes = changes.notes;
+            account.currency = changes.currency;
+        })?;
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Closes an account as of `on`. A residual balance on that day is first
+    /// moved to `transfer_to` with a completed transfer, whose ID is
+    /// returned; without a target the balance must be zero. Closed accounts
+    /// keep their history but take no new transactions.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id, on = %on),
+        err(level = "debug")
+    )]
+    pub fn close(
+        ledger: &mut Ledger,
+        id: Uuid,
+        on: NaiveDate,
+        transfer_to: Option<Uuid>,
+    ) -> Result<Option<Uuid>, CoreError> {
+        let account = ledger
+            .account(id)
+            .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
+        if let AccountStatus::Closed { on } = account.status {
+            return Err(CoreError::InvalidOperation(format!(
+                "account `{}` was already closed on {}",
+                account.name, on
+            )));
+        }
+        check_date(ledger, "closing date", on)?;
+        let name = account.name.clone();
+        let balance = Self::balance(ledger, id, on)?.balance;
+        let transfer = match transfer_to {
+            _ if balance.abs() < 0.005 => None,
+            None => {
+                return Err(CoreError::InvalidOperation(format!(
+                    "account `{}` still holds {:.2}; transfer it to another account before closing",
+                    name, balance
+                )))
+            }
+            Some(target) if target == id => {
+                return Err(CoreError::InvalidOperation(
+                    "cannot transfer a closing balance to the same account".into(),
+                ))
+            }
+            Some(target) => {
+                let (from, to) = if balance > 0.0 {
+                    (id, target)
+                } else {
+                    (target, id)
+                };
+                let amount = Money::from_major(balance.abs(), ledger.transfer_currency(from, to))?;
+                let mut txn = Transaction::new(from, to, None, on, amount.clone());
+                txn.mark_completed(on, amount);
+                txn.notes = Some(format!("Closing balance of {}", name));
+                Some(TransactionService::add(ledger, txn)?)
+            }
+        };
         let account = ledger
             .account_mut(id)
             .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
-        account.name = changes.name;
-        account.kind = changes.kind;
-        account.category_id = changes.category_id;
-        account.opening_balance = changes.opening_balance;
-        account.notes = changes.notes;
-        account.currency = changes.currency;
+        account.status = AccountStatus::Closed { on };
+        ledger.touch();
+        Ok(transfer)
+    }
+
+    /// Reopens a closed account so it takes transactions again.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn reopen(ledger: &mut Ledger, id: Uuid) -> Result<(), CoreError> {
+        let account = ledger
+            .account_mut(id)
+            .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
+        if account.status.is_active() {
+            return Err(CoreError::InvalidOperation(format!(
+                "account `{}` is not closed",
+                account.name
+            )));
+        }
+        account.status = AccountStatus::Active;
         ledger.touch();
         Ok(())
     }
 
     /// Removes an account when no linked transactions exist.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
     pub fn remove(ledger: &mut Ledger, id: Uuid) -> Result<(), CoreError> {
         if ledger
             .transactions
@@ -57,33 +190,410 @@ impl AccountService {
         if ledger.accounts.len() == before {
             return Err(CoreError::AccountNotFound(id.to_string()));
         }
+        ledger
+            .transfer_rules
+            .retain(|rule| rule.from_account != id && rule.to_account != id);
         ledger.touch();
         Ok(())
     }
 
+    /// Like [`Self::add`], recording the edit in `journal` for undo.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn add_journaled(
+        ledger: &mut Ledger,
+        account: Account,
+        journal: &mut ChangeJournal,
+    ) -> Result<(), CoreError> {
+        let entry = JournalEntry::AccountAdded(account.clone());
+        Self::add(ledger, account)?;
+        journal.record(entry);
+        Ok(())
+    }
+
+    /// Like [`Self::edit`], recording the edit in `journal` for undo.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn edit_journaled(
+        ledger: &mut Ledger,
+        id: Uuid,
+        changes: Account,
+        journal: &mut ChangeJournal,
+    ) -> Result<(), CoreError> {
+        let before = ledger
+            .account(id)
+            .cloned()
+            .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
+        Self::edit(ledger, id, changes)?;
+        let after = ledger
+            .account(id)
+            .cloned()
+            .expect("account exists after edit");
+        journal.record(JournalEntry::AccountEdited { before, after });
+        Ok(())
+    }
+
+    /// Like [`Self::remove`], recording the edit in `journal` for undo.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn remove_journaled(
+        ledger: &mut Ledger,
+        id: Uuid,
+        journal: &mut ChangeJournal,
+    ) -> Result<(), CoreError> {
+        let index = ledger
+            .accounts
+            .iter()
+            .position(|account| account.id == id)
+            .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
+        let account = ledger.accounts[index].clone();
+        let transfer_rules = ledger
+            .transfer_rules
+            .iter()
+            .filter(|rule| rule.from_account == id || rule.to_account == id)
+            .cloned()
+            .collect();
+        Self::remove(ledger, id)?;
+        journal.record(JournalEntry::AccountRemoved {
+            index,
+            account,
+            transfer_rules,
+        });
+        Ok(())
+    }
+
+    /// Moves an account within its block (favorites or the rest) of the
+    /// display order. Returns its new index.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id, direction = %direction),
+        err(level = "debug")
+    )]
+    pub fn reorder(
+        ledger: &mut Ledger,
+        id: Uuid,
+        direction: MoveDirection,
+    ) -> Result<usize, CoreError> {
+        pin_favorites(&mut ledger.accounts);
+        let index = Self::position(ledger, id)?;
+        let moved = move_within_block(&mut ledger.accounts, index, direction, |_| true);
+        if moved != index {
+            ledger.touch();
+        }
+        Ok(moved)
+    }
+
+    /// Sets the color and icon shown with the account's name; an empty
+    /// [`Appearance`] clears both.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn set_appearance(
+        ledger: &mut Ledger,
+        id: Uuid,
+        appearance: Appearance,
+    ) -> Result<(), CoreError> {
+        let icon = match appearance.icon.as_deref() {
+            Some(icon) => check_icon("icon", icon)?,
+            None => None,
+        };
+        let account = ledger
+            .account_mut(id)
+            .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
+        account.appearance = Appearance {
+            color: appearance.color,
+            icon,
+        };
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Stars or unstars an account, pinning starred ones to the top of lists
+    /// and pickers. Returns its new index.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id, favorite),
+        err(level = "debug")
+    )]
+    pub fn set_favorite(ledger: &mut Ledger, id: Uuid, favorite: bool) -> Result<usize, CoreError> {
+        pin_favorites(&mut ledger.accounts);
+        let index = Self::position(ledger, id)?;
+        if ledger.accounts[index].favorite == favorite {
+            return Ok(index);
+        }
+        let moved = set_favorite_at(&mut ledger.accounts, index, favorite);
+        ledger.touch();
+        Ok(moved)
+    }
+
     /// Returns a snapshot of the accounts currently tracked in the ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn list(ledger: &Ledger) -> Vec<&Account> {
         ledger.accounts.iter().collect()
     }
 
+    /// Computes balance and activity figures for an account as of `today`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            id = %id,
+            today = %today
+        ),
+        err(level = "debug")
+    )]
+    pub fn stats(ledger: &Ledger, id: Uuid, today: NaiveDate) -> Result<AccountStats, CoreError> {
+        let account = ledger
+            .account(id)
+            .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
+        Ok(Self::stats_over(
+            ledger,
+            account,
+            &ledger.transactions,
+            today,
+        ))
+    }
+
+    /// [`Self::stats`] for every account, walking the transactions once.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn stats_by_account(ledger: &Ledger, today: NaiveDate) -> HashMap<Uuid, AccountStats> {
+        let mut through: HashMap<Uuid, Vec<&Transaction>> = HashMap::new();
+        for txn in &ledger.transactions {
+            through.entry(txn.from_account).or_default().push(txn);
+            if txn.to_account != txn.from_account {
+                through.entry(txn.to_account).or_default().push(txn);
+            }
+        }
+        ledger
+            .accounts
+            .iter()
+            .map(|account| {
+                let transactions = through.get(&account.id).into_iter().flatten().copied();
+                (
+                    account.id,
+                    Self::stats_over(ledger, account, transactions, today),
+                )
+            })
+            .collect()
+    }
+
+    /// Stats of `account` from `transactions`, which must include every
+    /// transaction through it.
+    fn stats_over<'a>(
+        ledger: &Ledger,
+        account: &Account,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+        today: NaiveDate,
+    ) -> AccountStats {
+        let period = ledger.budget_window_containing(today);
+        let trailing = trailing_window(today);
+        let currency = ledger.holding_currency(account);
+        let running = RunningBalance::completed(account, &currency, transactions, NaiveDate::MIN);
+        let mut balance = running.opening;
+        let mut period_net = 0i64;
+        let mut trailing_net = 0i64;
+        let mut activity = ActivityTracker::default();
+
+        for movement in running
+            .movements
+            .iter()
+            .take_while(|movement| movement.date <= today)
+        {
+            balance = balance.saturating_add(movement.amount);
+            if period.contains(movement.date) {
+                period_net = period_net.saturating_add(movement.amount);
+            }
+            if trailing.contains(movement.date) {
+                trailing_net = trailing_net.saturating_add(movement.amount);
+            }
+            activity.record(
+                movement.transaction,
+                movement.date,
+                running.major(movement.amount),
+            );
+        }
+
+        AccountStats {
+            balance: running.major(balance),
+            period_net: running.major(period_net),
+            monthly_average: running.major(trailing_net) / STATS_AVERAGE_MONTHS as f64,
+            largest_transaction: activity.largest,
+            last_activity: activity.last_activity,
+        }
+    }
+
+    /// Balance of an account at the end of `as_of`: its opening balance plus
+    /// every completed movement on or before that day. The net of planned
+    /// transactions scheduled up to the same day is reported alongside.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            id = %id,
+            as_of = %as_of
+        ),
+        err(level = "debug")
+    )]
+    pub fn balance(
+        ledger: &Ledger,
+        id: Uuid,
+        as_of: NaiveDate,
+    ) -> Result<AccountBalance, CoreError> {
+        let account = ledger
+            .account(id)
+            .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
+        let currency = ledger.holding_currency(account);
+        let planned = ledger
+            .transactions
+            .iter()
+            .filter(|txn| txn.status == TransactionStatus::Planned && txn.scheduled_date <= as_of)
+            .map(|txn| {
+                let amount = minor_in(&txn.budgeted_amount, &currency);
+                match (txn.to_account == id, txn.from_account == id) {
+                    (true, false) => amount,
+                    (false, true) => amount.saturating_neg(),
+                    _ => 0,
+                }
+            })
+            .fold(0i64, i64::saturating_add);
+        Ok(AccountBalance {
+            account_name: account.name.clone(),
+            currency: account
+                .currency
+                .clone()
+                .unwrap_or_else(|| ledger.base_currency().as_str().to_string()),
+            as_of,
+            balance: account_balance(account, &currency, &ledger.transactions, as_of),
+            planned: to_major(planned, &currency),
+        })
+    }
+
+    /// Builds a statement of completed activity through an account in
+    /// `window`: the balance carried in, each movement with a running
+    /// balance, and the balance carried out.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn statement(
+        ledger: &Ledger,
+        id: Uuid,
+        window: DateWindow,
+    ) -> Result<AccountStatement, CoreError> {
+        let account = ledger
+            .account(id)
+            .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
+        let currency = ledger.holding_currency(account);
+        let running =
+            RunningBalance::completed(account, &currency, &ledger.transactions, window.start);
+        let mut balance = running.opening;
+        let lines = running
+            .movements
+            .iter()
+            .take_while(|movement| movement.date < window.end)
+            .map(|movement| {
+                let txn = movement.transaction;
+                balance = balance.saturating_add(movement.amount);
+                let counterparty = if txn.to_account == id {
+                    txn.from_account
+                } else {
+                    txn.to_account
+                };
+                StatementLine {
+                    transaction_id: txn.id,
+                    date: movement.date,
+                    counterparty: ledger
+                        .account(counterparty)
+                        .map(|account| account.name.clone())
+                        .unwrap_or_default(),
+                    category: txn
+                        .category_id
+                        .and_then(|id| ledger.category(id))
+                        .map(|category| category.name.clone()),
+                    notes: txn.notes.clone(),
+                    amount: running.major(movement.amount),
+                    balance: running.major(balance),
+                }
+            })
+            .collect();
+        Ok(AccountStatement {
+            account_name: account.name.clone(),
+            currency: account
+                .currency
+                .clone()
+                .unwrap_or_else(|| ledger.base_currency().as_str().to_string()),
+            window,
+            opening_balance: running.major(running.opening),
+            closing_balance: running.major(balance),
+            lines,
+        })
+    }
+
+    fn position(ledger: &Ledger, id: Uuid) -> Result<usize, CoreError> {
+        ledger
+            .accounts
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))
+    }
+
     fn validate_name(
         ledger: &Ledger,
         exclude: Option<Uuid>,
         candidate: &str,
-    ) -> Result<(), CoreError> {
-        let normalized = candidate.trim().to_ascii_lowercase();
+    ) -> Result<String, CoreError> {
+        let name = check_name("account name", candidate)?;
+        let normalized = name.to_ascii_lowercase();
         let duplicate = ledger.accounts.iter().any(|account| {
-            let name = account.name.trim().to_ascii_lowercase();
-            name == normalized && (exclude != Some(account.id))
+            account.name.trim().to_ascii_lowercase() == normalized && (exclude != Some(account.id))
         });
         if duplicate {
-            Err(CoreError::Validation(format!(
-                "account `{}` already exists",
-                candidate
-            )))
+            Err(ValidationIssue::new(
+                "account name",
+                ValidationIssueKind::Duplicate { value: name },
+            )
+            .into())
         } else {
-            Ok(())
+            Ok(name)
+        }
+    }
+
+    fn validate_opening_balance(balance: Option<&Money>) -> Result<(), CoreError> {
+        if let Some(balance) = balance {
+            check_amount("opening balance", balance.to_major(), AmountRule::Signed)?;
         }
+        Ok(())
     }
 
     fn ensure_category_exists(ledger: &Ledger, category_id: Uuid) -> Result<(), CoreError> {
@@ -94,3 +604,47 @@ impl AccountService {
         }
     }
 }
+
+/// An account's balance at the end of one day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountBalance {
+    pub account_name: String,
+    pub currency: String,
+    pub as_of: NaiveDate,
+    /// Opening balance plus completed movements through `as_of`.
+    pub balance: f64,
+    /// Net of planned transactions scheduled through `as_of`.
+    pub planned: f64,
+}
+
+impl AccountBalance {
+    /// The balance once every planned transaction through `as_of` is done.
+    pub fn with_planned(&self) -> f64 {
+        self.balance + self.planned
+    }
+}
+
+/// Completed activity through one account over a window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountStatement {
+    pub account_name: String,
+    pub currency: String,
+    pub window: DateWindow,
+    pub opening_balance: f64,
+    pub closing_balance: f64,
+    pub lines: Vec<StatementLine>,
+}
+
+/// One movement on an [`AccountStatement`]; inflows are positive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatementLine {
+    pub transaction_id: Uuid,
+    pub date: NaiveDate,
+    /// The other account in the transfer.
+    pub counterparty: String,
+    pub category: Option<String>,
+    pub notes: Option<String>,
+    pub amount: f64,
+    /// Balance after this movement.
+    pub balance: f64,
+}
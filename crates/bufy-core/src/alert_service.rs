@@ -0,0 +1,156 @@
+//! Evaluates budget alerts that frontends surface as badges.
+
+use chrono::NaiveDate;
+use tracing::instrument;
+
+use bufy_domain::{
+    alert::{AlertKind, BudgetAlert},
+    ledger::BudgetStatus,
+    transaction::{RecurrenceStatus, Transaction},
+    Ledger,
+};
+
+use crate::{
+    category_group_service::CategoryGroupService,
+    contract_service::{ContractService, ContractStage},
+    sinking_fund_service::SinkingFundService,
+    stats::{account_balance, is_asset_account},
+    summary_service::SummaryService,
+};
+
+/// Asset account balances below this amount raise a low-balance alert.
+pub const LOW_BALANCE_THRESHOLD: f64 = 0.0;
+
+pub struct AlertService;
+
+impl AlertService {
+    /// Returns the alerts active on `today`: categories and budgeted category
+    /// groups over budget in the period containing `today`, asset accounts whose balance is below
+    /// [`LOW_BALANCE_THRESHOLD`], active recurrences with overdue occurrences,
+    /// sinking funds behind their saving schedule, and contracts whose notice
+    /// deadline is near.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn evaluate(ledger: &Ledger, today: NaiveDate) -> Vec<BudgetAlert> {
+        let currency = ledger.base_currency().as_str();
+        let mut alerts = Vec::new();
+
+        let window = ledger.budget_window_containing(today);
+        let scope = window.scope(today);
+        for summary in SummaryService::category_budget_summaries(ledger, window, scope) {
+            if summary.status != BudgetStatus::OverBudget {
+                continue;
+            }
+            alerts.push(BudgetAlert {
+                kind: AlertKind::OverBudget,
+                subject_id: summary.category_id,
+                message: format!(
+                    "{} spent {:.2} of {:.2} {}",
+                    summary.name, summary.spent_amount, summary.budget_amount, currency
+                ),
+                subject: summary.name,
+            });
+        }
+
+        for group in CategoryGroupService::summaries(ledger, window, scope) {
+            if !group.group_budget || group.totals.status != BudgetStatus::OverBudget {
+                continue;
+            }
+            alerts.push(BudgetAlert {
+                kind: AlertKind::OverBudget,
+                subject_id: group.group_id,
+                message: format!(
+                    "{} group spent {:.2} of {:.2} {}",
+                    group.name,
+                    group.totals.real,
+                    group.budget_amount.unwrap_or_default(),
+                    currency
+                ),
+                subject: group.name,
+            });
+        }
+
+        for account in ledger.accounts.iter().filter(|a| is_asset_account(a)) {
+            let balance = account_balance(
+                account,
+                &ledger.holding_currency(account),
+                &ledger.transactions,
+                today,
+            );
+            if balance < LOW_BALANCE_THRESHOLD {
+                alerts.push(BudgetAlert {
+                    kind: AlertKind::LowBalance,
+                    subject_id: account.id,
+                    subject: account.name.clone(),
+                    message: format!("{} balance is {:.2} {}", account.name, balance, currency),
+                });
+            }
+        }
+
+        for snapshot in ledger.recurrence_snapshots(today) {
+            if snapshot.overdue == 0 || snapshot.status != RecurrenceStatus::Active {
+                continue;
+            }
+            let subject = ledger
+                .transaction(snapshot.template_id)
+                .map(|txn| recurrence_label(ledger, txn))
+                .unwrap_or_else(|| snapshot.interval_label.clone());
+            alerts.push(BudgetAlert {
+                kind: AlertKind::OverdueRecurrence,
+                subject_id: snapshot.series_id,
+                message: format!("{} has {} overdue occurrence(s)", subject, snapshot.overdue),
+                subject,
+            });
+        }
+
+        for status in SinkingFundService::statuses(ledger, today) {
+            if !status.is_underfunded() {
+                continue;
+            }
+            alerts.push(BudgetAlert {
+                kind: AlertKind::UnderfundedSinkingFund,
+                subject_id: status.fund_id,
+                message: format!(
+                    "{} has {:.2} of {:.2} {} set aside; save {:.2} a month before {}",
+                    status.name,
+                    status.saved,
+                    status.expected,
+                    currency,
+                    status.monthly_set_aside,
+                    status.next_due
+                ),
+                subject: status.name,
+            });
+        }
+
+        for window in ContractService::windows(ledger, today) {
+            if window.stage != ContractStage::NoticeDue {
+                continue;
+            }
+            alerts.push(BudgetAlert {
+                kind: AlertKind::ContractNotice,
+                subject_id: window.series_id,
+                message: format!(
+                    "{} contract ends {}; give notice by {} ({} day(s) left)",
+                    window.provider, window.end_date, window.notice_deadline, window.days_to_notice
+                ),
+                subject: window.provider,
+            });
+        }
+
+        alerts
+    }
+}
+
+fn recurrence_label(ledger: &Ledger, txn: &Transaction) -> String {
+    txn.notes
+        .as_deref()
+        .map(str::trim)
+        .filter(|notes| !notes.is_empty())
+        .map(str::to_string)
+        .or_else(|| ledger.account(txn.to_account).map(|a| a.name.clone()))
+        .unwrap_or_else(|| "Recurring transaction".into())
+}
@@ -0,0 +1,233 @@
+//! Zero-based allocation: how much income a budget period brings in, how
+//! much of it category budgets already claim, and writing a new split.
+
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    account::AccountKind,
+    category::CategoryKind,
+    common::{BudgetPeriod, TimeUnit},
+    ledger::BudgetScope,
+    transaction::Transaction,
+    DateWindow, Ledger, Money,
+};
+
+use crate::budget_service::BudgetService;
+use crate::stats::completed_movement;
+use crate::validation::{check_amount, AmountRule};
+use crate::CoreError;
+
+/// Number of budget periods before the current one sampled for income.
+pub const ALLOCATION_INCOME_PERIODS: i32 = 3;
+
+/// Completed income that landed in one past budget period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodIncome {
+    pub window: DateWindow,
+    pub amount: f64,
+}
+
+/// One expense category's share of the period's income.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllocationLine {
+    pub category_id: Uuid,
+    pub name: String,
+    /// Current budget scaled to the budget period; 0 without a budget.
+    pub allocated: f64,
+    /// Average completed spending per sampled period.
+    pub average_spend: f64,
+}
+
+/// Income, current allocations, and what is left to assign for the budget
+/// period containing the reference date.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllocationPlan {
+    pub window: DateWindow,
+    pub recent_income: Vec<PeriodIncome>,
+    /// Income planned or received in the current period.
+    pub current_income: f64,
+    /// Average of `recent_income`, or `current_income` when no income was
+    /// recorded in the sampled periods.
+    pub income: f64,
+    pub lines: Vec<AllocationLine>,
+}
+
+impl AllocationPlan {
+    pub fn allocated(&self) -> f64 {
+        self.lines.iter().map(|line| line.allocated).sum()
+    }
+
+    /// Income not yet claimed by a category budget; negative when budgets
+    /// exceed it.
+    pub fn unassigned(&self) -> f64 {
+        self.income - self.allocated()
+    }
+}
+
+pub struct AllocationService;
+
+impl AllocationService {
+    /// Detects income over the last [`ALLOCATION_INCOME_PERIODS`] budget
+    /// periods and lists every visible expense category with its budget.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn plan(ledger: &Ledger, today: chrono::NaiveDate) -> AllocationPlan {
+        let window = ledger.budget_window_containing(today);
+        let interval = ledger.budget_period.interval();
+        let past: Vec<DateWindow> = (1..=ALLOCATION_INCOME_PERIODS)
+            .rev()
+            .map(|steps| window.shift(interval, -steps))
+            .collect();
+
+        let recent_income: Vec<PeriodIncome> = past
+            .iter()
+            .map(|past_window| PeriodIncome {
+                window: *past_window,
+                amount: ledger
+                    .transactions
+                    .iter()
+                    .filter(|txn| is_income(ledger, txn))
+                    .filter_map(completed_movement)
+                    .filter(|(date, _)| past_window.contains(*date))
+                    .map(|(_, amount)| amount)
+                    .sum(),
+            })
+            .collect();
+        let current_income = ledger
+            .transactions
+            .iter()
+            .filter(|txn| is_income(ledger, txn))
+            .map(|txn| {
+                completed_movement(txn)
+                    .unwrap_or((txn.scheduled_date, txn.budgeted_amount.to_major()))
+            })
+            .filter(|(date, _)| window.contains(*date))
+            .map(|(_, amount)| amount)
+            .sum();
+        let received: f64 = recent_income.iter().map(|period| period.amount).sum();
+        let income = if received > 0.0 {
+            received / recent_income.len() as f64
+        } else {
+            current_income
+        };
+
+        let past_spend: Vec<_> = past
+            .iter()
+            .map(|past_window| {
+                BudgetService::summarize_window_scope(ledger, *past_window, BudgetScope::Past)
+                    .per_category
+            })
+            .collect();
+        let lines = ledger
+            .categories
+            .iter()
+            .filter(|category| category.kind == CategoryKind::Expense && !category.hidden)
+            .map(|category| {
+                let spent: f64 = past_spend
+                    .iter()
+                    .flatten()
+                    .filter(|entry| entry.category_id == Some(category.id))
+                    .map(|entry| entry.totals.real)
+                    .sum();
+                AllocationLine {
+                    category_id: category.id,
+                    name: category.name.clone(),
+                    allocated: BudgetService::category_budget_amount(
+                        category.budget.as_ref(),
+                        window,
+                    )
+                    .unwrap_or(0.0),
+                    average_spend: spent / past.len() as f64,
+                }
+            })
+            .collect();
+
+        AllocationPlan {
+            window,
+            recent_income,
+            current_income,
+            income,
+            lines,
+        }
+    }
+
+    /// Sets each category's budget to its amount per budget period, all or
+    /// nothing. Amounts equal to the current allocation leave that budget
+    /// as it is. Returns how many budgets changed.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            allocations = allocations.len()
+        ),
+        err(level = "debug")
+    )]
+    pub fn apply(
+        ledger: &mut Ledger,
+        today: chrono::NaiveDate,
+        allocations: &[(Uuid, f64)],
+    ) -> Result<usize, CoreError> {
+        let window = ledger.budget_window_containing(today);
+        let mut changes = Vec::new();
+        for &(id, amount) in allocations {
+            check_amount("allocation", amount, AmountRule::NonNegative)?;
+            let category = ledger
+                .category(id)
+                .ok_or_else(|| CoreError::CategoryNotFound(id.to_string()))?;
+            let current = BudgetService::category_budget_amount(category.budget.as_ref(), window);
+            let unchanged = match current {
+                Some(current) => (current - amount).abs() < 0.005,
+                None => amount == 0.0,
+            };
+            if !unchanged {
+                changes.push((id, amount));
+            }
+        }
+        if changes.is_empty() {
+            return Ok(0);
+        }
+
+        let period = budget_period_for(ledger, window);
+        let base = ledger.base_currency().clone();
+        for &(id, amount) in &changes {
+            let amount = Money::from_major(amount, base.clone())?;
+            if let Some(category) = ledger.category_mut(id) {
+                category.set_budget(amount, period, Some(window.start));
+            }
+        }
+        ledger.touch();
+        Ok(changes.len())
+    }
+}
+
+/// Income for allocation: money from an income source or booked in an
+/// income category, whether or not it has arrived yet.
+fn is_income(ledger: &Ledger, txn: &Transaction) -> bool {
+    let from_income_source = ledger
+        .account(txn.from_account)
+        .is_some_and(|account| account.kind == AccountKind::IncomeSource);
+    let income_category = txn
+        .category_id
+        .and_then(|id| ledger.category(id))
+        .is_some_and(|category| category.kind == CategoryKind::Income);
+    from_income_source || income_category
+}
+
+/// The category budget period matching the ledger's, so an allocation counts
+/// once per budget period.
+fn budget_period_for(ledger: &Ledger, window: DateWindow) -> BudgetPeriod {
+    let interval = ledger.budget_period.interval();
+    match (interval.every, &interval.unit) {
+        (1, TimeUnit::Day) => BudgetPeriod::Daily,
+        (1, TimeUnit::Week) => BudgetPeriod::Weekly,
+        (1, TimeUnit::Month) => BudgetPeriod::Monthly,
+        (1, TimeUnit::Year) => BudgetPeriod::Yearly,
+        _ => BudgetPeriod::Custom((window.end - window.start).num_days().max(1) as u32),
+    }
+}
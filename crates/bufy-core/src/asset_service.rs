@@ -0,0 +1,187 @@
+//! Durable purchases: warranties still running and what each item costs per
+//! month when its price is spread over its expected lifetime.
+
+use chrono::{Months, NaiveDate};
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{transaction::AssetRecord, Ledger};
+
+use crate::{sinking_fund_service::months_until, CoreError};
+
+/// One purchased item on a given day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetStatus {
+    pub transaction_id: Uuid,
+    pub name: String,
+    pub purchased_on: NaiveDate,
+    /// Amount paid, in the transaction's currency.
+    pub cost: f64,
+    pub currency: String,
+    pub warranty_until: Option<NaiveDate>,
+    /// Days of warranty left; negative once it has expired.
+    pub warranty_days_left: Option<i64>,
+    pub lifetime_months: Option<u32>,
+    /// Cost spread evenly over the lifetime.
+    pub monthly_cost: Option<f64>,
+    /// Day the expected lifetime ends.
+    pub end_of_life: Option<NaiveDate>,
+    /// Share of the cost not yet used up, straight-line.
+    pub remaining_value: Option<f64>,
+}
+
+impl AssetStatus {
+    pub fn has_active_warranty(&self) -> bool {
+        self.warranty_days_left.is_some_and(|days| days >= 0)
+    }
+
+    /// Whether the item is within its expected lifetime; items without one
+    /// count as in service.
+    pub fn in_service(&self, today: NaiveDate) -> bool {
+        self.end_of_life.is_none_or(|end| end > today)
+    }
+}
+
+/// Every purchased item, with the monthly cost of those still in service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetReport {
+    pub assets: Vec<AssetStatus>,
+    /// Monthly cost of items in service, in the base currency.
+    pub monthly_cost: f64,
+    /// Items in service whose cost could not be converted to the base
+    /// currency and were left out of `monthly_cost`.
+    pub unconverted: usize,
+}
+
+pub struct AssetService;
+
+impl AssetService {
+    /// Records the item a transaction bought, replacing any record it had.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn set(
+        ledger: &mut Ledger,
+        transaction_id: Uuid,
+        record: AssetRecord,
+    ) -> Result<(), CoreError> {
+        let name = record.name.trim();
+        if name.is_empty() {
+            return Err(CoreError::Validation("asset name cannot be empty".into()));
+        }
+        if record.lifetime_months == Some(0) {
+            return Err(CoreError::Validation(
+                "lifetime must be at least one month".into(),
+            ));
+        }
+        let record = AssetRecord {
+            name: name.to_string(),
+            ..record
+        };
+        ledger
+            .transaction_mut(transaction_id)
+            .ok_or(CoreError::TransactionNotFound(transaction_id))?
+            .asset = Some(record);
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Removes a transaction's asset record, returning whether it had one.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn clear(ledger: &mut Ledger, transaction_id: Uuid) -> Result<bool, CoreError> {
+        let had_asset = ledger
+            .transaction_mut(transaction_id)
+            .ok_or(CoreError::TransactionNotFound(transaction_id))?
+            .asset
+            .take()
+            .is_some();
+        if had_asset {
+            ledger.touch();
+        }
+        Ok(had_asset)
+    }
+
+    /// Every asset on `today`, warranties ending soonest first and items
+    /// without a warranty last.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn report(ledger: &Ledger, today: NaiveDate) -> AssetReport {
+        let ctx = ledger.conversion_context(today);
+        let mut report = AssetReport {
+            assets: Vec::new(),
+            monthly_cost: 0.0,
+            unconverted: 0,
+        };
+        for txn in &ledger.transactions {
+            let Some(asset) = &txn.asset else {
+                continue;
+            };
+            let purchased_on = txn.actual_date.unwrap_or(txn.scheduled_date);
+            let cost = txn.amount().to_major();
+            let currency = ledger.transaction_currency(txn);
+            let end_of_life = asset
+                .lifetime_months
+                .and_then(|months| purchased_on.checked_add_months(Months::new(months)));
+            let monthly_cost = asset.lifetime_months.map(|months| cost / f64::from(months));
+            let remaining_value = asset.lifetime_months.zip(end_of_life).map(|(months, end)| {
+                if end <= today {
+                    0.0
+                } else if purchased_on >= today {
+                    cost
+                } else {
+                    let left = months_until(today, end).min(months);
+                    cost * f64::from(left) / f64::from(months)
+                }
+            });
+            let status = AssetStatus {
+                transaction_id: txn.id,
+                name: asset.name.clone(),
+                purchased_on,
+                cost,
+                currency: currency.as_str().to_string(),
+                warranty_until: asset.warranty_until,
+                warranty_days_left: asset.warranty_until.map(|until| (until - today).num_days()),
+                lifetime_months: asset.lifetime_months,
+                monthly_cost,
+                end_of_life,
+                remaining_value,
+            };
+            if let Some(monthly) = monthly_cost.filter(|_| status.in_service(today)) {
+                match ledger.convert_amount(monthly, &currency, purchased_on, &ctx) {
+                    Ok(converted) => report.monthly_cost += converted.amount,
+                    Err(_) => report.unconverted += 1,
+                }
+            }
+            report.assets.push(status);
+        }
+        report.assets.sort_by(|a, b| {
+            match (a.warranty_until, b.warranty_until) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+            .then_with(|| a.name.cmp(&b.name))
+        });
+        report
+    }
+}
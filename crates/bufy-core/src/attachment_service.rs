@@ -0,0 +1,266 @@
+//! Receipts and other documents kept with transactions.
+//!
+//! Files are copied into an attachments folder next to the ledger file,
+//! named after the SHA-256 of their contents, so attaching the same receipt
+//! twice stores it once and a changed or missing copy can be spotted later.
+//! Links are stored as given.
+
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use sha2::{Digest, Sha256};
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{transaction::AttachmentRef, Ledger, Transaction};
+
+use crate::CoreError;
+
+/// Longest extension kept on a stored file; longer ones are dropped.
+const MAX_EXTENSION_LEN: usize = 8;
+
+/// Whether an attachment can still be opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentStatus {
+    Link,
+    Present,
+    Missing,
+    /// The stored file no longer matches its checksum.
+    Modified,
+}
+
+pub struct AttachmentService;
+
+impl AttachmentService {
+    /// Folder holding the attachments of the ledger saved at `ledger_path`:
+    /// `home.bfy` keeps them in `home.attachments`.
+    pub fn attachments_dir(ledger_path: &Path) -> PathBuf {
+        ledger_path.with_extension("attachments")
+    }
+
+    /// Copies `source` into `dir` and attaches it to a transaction. A file
+    /// with the same contents already in `dir` is reused. Call
+    /// [`AttachmentService::detach`] if the ledger then fails to save, so
+    /// the copy does not outlive the change.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn attach_file(
+        ledger: &mut Ledger,
+        id: Uuid,
+        dir: &Path,
+        source: &Path,
+    ) -> Result<AttachmentRef, CoreError> {
+        if ledger.transaction(id).is_none() {
+            return Err(CoreError::TransactionNotFound(id));
+        }
+        let name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .ok_or_else(|| {
+                CoreError::Validation(format!("`{}` is not a file", source.display()))
+            })?;
+        let contents = fs::read(source)?;
+        let checksum = checksum(&contents);
+        let location = match stored_extension(source) {
+            Some(extension) => format!("{}.{}", checksum, extension),
+            None => checksum.clone(),
+        };
+        let attachment = AttachmentRef {
+            location,
+            name,
+            checksum: Some(checksum),
+        };
+        Self::ensure_new(ledger, id, &attachment)?;
+
+        if Self::status(dir, &attachment) != AttachmentStatus::Present {
+            let target = dir.join(&attachment.location);
+            fs::create_dir_all(dir)?;
+            if let Err(err) = fs::write(&target, &contents) {
+                // A partial copy would read as modified later.
+                let _ = fs::remove_file(&target);
+                return Err(err.into());
+            }
+        }
+        Self::push(ledger, id, attachment)
+    }
+
+    /// Attaches an `http` or `https` link to a transaction.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn attach_link(
+        ledger: &mut Ledger,
+        id: Uuid,
+        url: &str,
+    ) -> Result<AttachmentRef, CoreError> {
+        let url = url.trim();
+        let attachment = AttachmentRef {
+            location: url.to_string(),
+            name: url.to_string(),
+            checksum: None,
+        };
+        if !attachment.is_link() {
+            return Err(CoreError::Validation(format!(
+                "`{}` is not an http or https link",
+                url
+            )));
+        }
+        if ledger.transaction(id).is_none() {
+            return Err(CoreError::TransactionNotFound(id));
+        }
+        Self::ensure_new(ledger, id, &attachment)?;
+        Self::push(ledger, id, attachment)
+    }
+
+    /// Takes the attachment stored at `location` off a transaction, and
+    /// deletes its copy in `dir` unless another transaction uses it.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn detach(
+        ledger: &mut Ledger,
+        id: Uuid,
+        dir: &Path,
+        location: &str,
+    ) -> Result<AttachmentRef, CoreError> {
+        let txn = ledger
+            .transaction_mut(id)
+            .ok_or(CoreError::TransactionNotFound(id))?;
+        let position = txn
+            .attachments
+            .iter()
+            .position(|attachment| attachment.location == location)
+            .ok_or_else(|| {
+                CoreError::InvalidOperation(format!("transaction has no attachment `{}`", location))
+            })?;
+        let attachment = txn.attachments.remove(position);
+        ledger.touch();
+        Self::remove_unused(ledger, dir, std::slice::from_ref(&attachment))?;
+        Ok(attachment)
+    }
+
+    /// Deletes the copies in `dir` of `released` attachments that no
+    /// transaction in `ledger` uses any more, returning how many files
+    /// went. Copies already gone are skipped.
+    pub fn remove_unused(
+        ledger: &Ledger,
+        dir: &Path,
+        released: &[AttachmentRef],
+    ) -> Result<usize, CoreError> {
+        let in_use = stored_locations(&ledger.transactions);
+        let mut removed = 0;
+        for attachment in released {
+            if in_use.contains(attachment.location.as_str()) {
+                continue;
+            }
+            let Some(path) = stored_path(dir, attachment) else {
+                continue;
+            };
+            match fs::remove_file(path) {
+                Ok(()) => removed += 1,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Checks the stored copy of `attachment` in `dir` against its checksum.
+    pub fn status(dir: &Path, attachment: &AttachmentRef) -> AttachmentStatus {
+        if attachment.is_link() {
+            return AttachmentStatus::Link;
+        }
+        let Some(path) = stored_path(dir, attachment) else {
+            return AttachmentStatus::Missing;
+        };
+        match fs::read(path) {
+            Ok(contents) if attachment.checksum.as_deref() == Some(&checksum(&contents)) => {
+                AttachmentStatus::Present
+            }
+            Ok(_) => AttachmentStatus::Modified,
+            Err(_) => AttachmentStatus::Missing,
+        }
+    }
+
+    fn ensure_new(ledger: &Ledger, id: Uuid, attachment: &AttachmentRef) -> Result<(), CoreError> {
+        let attached = ledger.transaction(id).is_some_and(|txn| {
+            txn.attachments
+                .iter()
+                .any(|existing| existing.location == attachment.location)
+        });
+        if attached {
+            return Err(CoreError::InvalidOperation(format!(
+                "transaction already has `{}` attached",
+                attachment.name
+            )));
+        }
+        Ok(())
+    }
+
+    fn push(
+        ledger: &mut Ledger,
+        id: Uuid,
+        attachment: AttachmentRef,
+    ) -> Result<AttachmentRef, CoreError> {
+        let txn = ledger
+            .transaction_mut(id)
+            .ok_or(CoreError::TransactionNotFound(id))?;
+        txn.attachments.push(attachment.clone());
+        ledger.touch();
+        Ok(attachment)
+    }
+}
+
+/// Locations of the stored files `transactions` point at.
+pub(crate) fn stored_locations<'a>(
+    transactions: impl IntoIterator<Item = &'a Transaction>,
+) -> HashSet<&'a str> {
+    transactions
+        .into_iter()
+        .flat_map(|txn| &txn.attachments)
+        .filter(|attachment| !attachment.is_link())
+        .map(|attachment| attachment.location.as_str())
+        .collect()
+}
+
+/// Where the copy of `attachment` lives in `dir`. `None` for links and for
+/// locations that are not a plain file name, which a hand-edited ledger
+/// could use to point outside the folder.
+fn stored_path(dir: &Path, attachment: &AttachmentRef) -> Option<PathBuf> {
+    if attachment.is_link() {
+        return None;
+    }
+    let mut components = Path::new(&attachment.location).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(name)), None) => Some(dir.join(name)),
+        _ => None,
+    }
+}
+
+/// SHA-256 of `contents`, in hex.
+fn checksum(contents: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(contents))
+}
+
+/// The extension of `source`, lowercased, when it is short and plain
+/// enough to keep in a file name.
+fn stored_extension(source: &Path) -> Option<String> {
+    let extension = source.extension()?.to_str()?.to_ascii_lowercase();
+    (!extension.is_empty()
+        && extension.len() <= MAX_EXTENSION_LEN
+        && extension.chars().all(|c| c.is_ascii_alphanumeric()))
+    .then_some(extension)
+}
@@ -0,0 +1,192 @@
+//! Append-only audit log of who changed which ledger records, and when.
+//!
+//! Each save of a named ledger is compared with the state it last loaded or
+//! saved. Every record added, edited, or removed becomes an [`AuditEntry`]
+//! naming the actor and the snapshot ids of the record before and after the
+//! change. A snapshot id is a hash of the record's saved form, so two entries
+//! with the same id describe the same state. Storage backends decide where
+//! the log lives and number its entries.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use bufy_domain::{ids::ShortRef, Ledger};
+
+use crate::{
+    change_feed_service::{records, to_object},
+    CoreError,
+};
+
+/// Entity name used for ledger-level fields such as `name` or `locale`.
+pub const LEDGER_ENTITY: &str = "ledger";
+
+/// Ledger fields that change as a side effect of other edits.
+const UNAUDITED_FIELDS: &[&str] = &["updated_at", "next_reference"];
+
+/// A numbered entry in a ledger's audit log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Increases by one per entry and is never reused.
+    pub seq: u64,
+    pub recorded_at: DateTime<Utc>,
+    /// Who saved the change.
+    pub actor: String,
+    #[serde(flatten)]
+    pub change: AuditChange,
+}
+
+impl AuditEntry {
+    /// Whether the entry concerns `query`: an entity name such as
+    /// `transactions` (or `transaction`), the start of a record id, or a
+    /// record label such as an account name.
+    pub fn matches_entity(&self, query: &str) -> bool {
+        let query = query.trim();
+        let change = &self.change;
+        change.entity.eq_ignore_ascii_case(query)
+            || change
+                .entity
+                .strip_suffix('s')
+                .is_some_and(|singular| singular.eq_ignore_ascii_case(query))
+            || change
+                .entity_id
+                .to_ascii_lowercase()
+                .starts_with(&query.to_ascii_lowercase())
+            || change
+                .label
+                .as_deref()
+                .is_some_and(|label| label.eq_ignore_ascii_case(query))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Added,
+    Edited,
+    Removed,
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AuditAction::Added => "added",
+            AuditAction::Edited => "edited",
+            AuditAction::Removed => "removed",
+        })
+    }
+}
+
+/// One record changed between two saved states of a ledger.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditChange {
+    pub action: AuditAction,
+    /// Collection such as `accounts` or `transactions`, or [`LEDGER_ENTITY`].
+    pub entity: String,
+    /// Record id, or the field name for ledger-level changes.
+    pub entity_id: String,
+    /// Record name or transaction reference, when it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Snapshot id of the record before the change; `None` when added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    /// Snapshot id of the record after the change; `None` when removed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+pub struct AuditService;
+
+impl AuditService {
+    /// Lists the record changes that turn `previous` into `current`. Without
+    /// a previous state every record counts as added and ledger-level fields
+    /// are left out.
+    pub fn changes(
+        previous: Option<&Ledger>,
+        current: &Ledger,
+    ) -> Result<Vec<AuditChange>, CoreError> {
+        let current = to_object(current)?;
+        let previous = previous.map(to_object).transpose()?.unwrap_or_default();
+        let mut fields: Vec<&String> = current.keys().chain(previous.keys()).collect();
+        fields.sort();
+        fields.dedup();
+        let mut changes = Vec::new();
+        for field in fields {
+            let before = previous.get(field);
+            let after = current.get(field);
+            if before == after || UNAUDITED_FIELDS.contains(&field.as_str()) {
+                continue;
+            }
+            match (records(before), records(after)) {
+                (Some(before), Some(after)) => {
+                    for (id, record) in &after {
+                        let old = before.iter().find(|(old_id, _)| old_id == id);
+                        let (action, before) = match old {
+                            Some((_, old)) if old == record => continue,
+                            Some((_, old)) => (AuditAction::Edited, Some(snapshot_id(old))),
+                            None => (AuditAction::Added, None),
+                        };
+                        changes.push(AuditChange {
+                            action,
+                            entity: field.clone(),
+                            entity_id: id.to_string(),
+                            label: label(field, record),
+                            before,
+                            after: Some(snapshot_id(record)),
+                        });
+                    }
+                    for (id, record) in &before {
+                        if !after.iter().any(|(new_id, _)| new_id == id) {
+                            changes.push(AuditChange {
+                                action: AuditAction::Removed,
+                                entity: field.clone(),
+                                entity_id: id.to_string(),
+                                label: label(field, record),
+                                before: Some(snapshot_id(record)),
+                                after: None,
+                            });
+                        }
+                    }
+                }
+                _ if previous.is_empty() => {}
+                _ => changes.push(AuditChange {
+                    action: AuditAction::Edited,
+                    entity: LEDGER_ENTITY.into(),
+                    entity_id: field.clone(),
+                    label: None,
+                    before: before.map(snapshot_id),
+                    after: after.map(snapshot_id),
+                }),
+            }
+        }
+        Ok(changes)
+    }
+}
+
+/// Short hash of a record's saved form (64-bit FNV-1a, in hex).
+pub fn snapshot_id(value: &Value) -> String {
+    let hash = value
+        .to_string()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{:016x}", hash)
+}
+
+pub(crate) fn label(entity: &str, record: &Value) -> Option<String> {
+    if let Some(name) = record.get("name").and_then(Value::as_str) {
+        return Some(name.to_string());
+    }
+    match entity {
+        "transactions" => record
+            .get("reference")
+            .and_then(Value::as_u64)
+            .and_then(|number| u32::try_from(number).ok())
+            .map(|number| ShortRef::transaction(number).to_string()),
+        _ => None,
+    }
+}
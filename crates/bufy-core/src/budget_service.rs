@@ -3,34 +3,51 @@
 use std::collections::{BTreeSet, HashMap};
 
 use chrono::{Duration, NaiveDate};
+use tracing::instrument;
 use uuid::Uuid;
 
 use bufy_domain::{
     account::Account,
-    category::Category,
-    currency::ConvertedAmount,
+    amount::AmountOverflow,
+    category::{Category, CategoryBudgetDefinition},
+    currency::{ConvertedAmount, CurrencyCode, ValuationPolicy},
     ledger::{
         AccountBudget, BudgetScope, BudgetSummary, BudgetTotals, CategoryBudget,
         CategoryBudgetAssignment, CategoryBudgetStatus, CategoryBudgetSummary,
-        CategoryBudgetSummaryKind, DateWindow,
+        CategoryBudgetSummaryKind, DateWindow, MemberBudget, TagBudget,
     },
+    money::Money,
     transaction::Transaction,
-    Ledger,
+    ConversionContext, Ledger,
 };
 
-use crate::Clock;
+use crate::{
+    sinking_fund_service::SinkingFundService,
+    stats::{is_asset_account, sum_major, RunningBalance},
+    Clock, CoreError,
+};
 
 /// Stateless budgeting utilities that operate over [`Ledger`] snapshots.
 pub struct BudgetService;
 
 impl BudgetService {
     /// Summarizes the ledger's current budget period.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn summarize_current_period(ledger: &Ledger, clock: &dyn Clock) -> BudgetSummary {
         let today = clock.today();
         Self::summarize_period_containing(ledger, today)
     }
 
     /// Summarizes the budget period that contains the given reference date.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), date = %date)
+    )]
     pub fn summarize_period_containing(ledger: &Ledger, date: NaiveDate) -> BudgetSummary {
         let window = ledger.budget_window_containing(date);
         let scope = window.scope(date);
@@ -38,6 +55,11 @@ impl BudgetService {
     }
 
     /// Summarizes the supplied window and scope using the ledger transactions.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn summarize_window_scope(
         ledger: &Ledger,
         window: DateWindow,
@@ -47,6 +69,11 @@ impl BudgetService {
     }
 
     /// Summarizes the supplied window and scope against an override list of transactions.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn summarize_window_with_transactions(
         ledger: &Ledger,
         window: DateWindow,
@@ -57,6 +84,15 @@ impl BudgetService {
     }
 
     /// Returns the totals for a specific category within the provided window.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            category_id = %category_id
+        )
+    )]
     pub fn category_totals_in_window(
         ledger: &Ledger,
         category_id: Uuid,
@@ -72,6 +108,15 @@ impl BudgetService {
     }
 
     /// Returns the budget status for a single category, combining assigned budget data with totals.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            category_id = %category_id
+        )
+    )]
     pub fn category_budget_status(
         ledger: &Ledger,
         category_id: Uuid,
@@ -85,11 +130,17 @@ impl BudgetService {
                 category_id,
                 name: category.name.clone(),
                 budget: category.budget.clone(),
+                window_budget: Self::category_budget_amount(category.budget.as_ref(), window),
                 totals,
             })
     }
 
     /// Lists all categories and their budget usage for a window.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn category_budget_statuses(
         ledger: &Ledger,
         window: DateWindow,
@@ -104,10 +155,12 @@ impl BudgetService {
         ledger
             .categories
             .iter()
+            .filter(|category| !category.hidden)
             .map(|category| CategoryBudgetStatus {
                 category_id: category.id,
                 name: category.name.clone(),
                 budget: category.budget.clone(),
+                window_budget: Self::category_budget_amount(category.budget.as_ref(), window),
                 totals: totals_by_category
                     .get(&category.id)
                     .cloned()
@@ -116,7 +169,22 @@ impl BudgetService {
             .collect()
     }
 
+    /// Category budget available in `window`, applying the budget's
+    /// proration rule when its period differs from the window.
+    #[instrument(level = "debug", skip_all)]
+    pub fn category_budget_amount(
+        budget: Option<&CategoryBudgetDefinition>,
+        window: DateWindow,
+    ) -> Option<f64> {
+        budget.map(|budget| budget.amount_for_window(window).to_major())
+    }
+
     /// Lists every category with an assigned budget definition.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn categories_with_budgets(ledger: &Ledger) -> Vec<CategoryBudgetAssignment> {
         ledger
             .categories
@@ -135,6 +203,11 @@ impl BudgetService {
     }
 
     /// Builds detailed summaries for categories with budgets using canonical ledger totals.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn category_budget_summaries(
         ledger: &Ledger,
         window: DateWindow,
@@ -145,6 +218,11 @@ impl BudgetService {
     }
 
     /// Builds category budget summaries using an alternate set of transactions.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn category_budget_summaries_with_transactions(
         ledger: &Ledger,
         window: DateWindow,
@@ -161,6 +239,7 @@ impl BudgetService {
         ledger
             .categories
             .iter()
+            .filter(|category| !category.hidden)
             .filter_map(|category| {
                 let budget = category.budget.as_ref()?;
                 let spent = totals_by_category
@@ -171,6 +250,7 @@ impl BudgetService {
                     category.id,
                     category.name.clone(),
                     budget,
+                    window,
                     spent,
                     kind.clone(),
                 ))
@@ -178,16 +258,57 @@ impl BudgetService {
             .collect()
     }
 
+    /// Like [`Self::summarize_window_scope`], but fails with
+    /// [`CoreError::NumericOverflow`] instead of returning totals that have
+    /// lost precision.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn summarize_window_checked(
+        ledger: &Ledger,
+        window: DateWindow,
+        scope: BudgetScope,
+    ) -> Result<BudgetSummary, CoreError> {
+        match Self::aggregate(ledger, window, scope, None) {
+            (summary, None) => Ok(summary),
+            (_, Some(overflow)) => Err(overflow.into()),
+        }
+    }
+
+    /// Summaries whose totals overflowed are still returned, with a
+    /// disclosure saying the figures are approximate.
     pub(crate) fn summarize_window_internal(
         ledger: &Ledger,
         window: DateWindow,
         scope: BudgetScope,
         tx_override: Option<&[Transaction]>,
     ) -> BudgetSummary {
+        let (mut summary, overflow) = Self::aggregate(ledger, window, scope, tx_override);
+        if let Some(overflow) = overflow {
+            summary
+                .disclosures
+                .push(format!("Totals are approximate: {}", overflow));
+        }
+        summary
+    }
+
+    fn aggregate(
+        ledger: &Ledger,
+        window: DateWindow,
+        scope: BudgetScope,
+        tx_override: Option<&[Transaction]>,
+    ) -> (BudgetSummary, Option<AmountOverflow>) {
+        let mut overflow = None;
         let txs = tx_override.unwrap_or(&ledger.transactions);
-        let mut totals_acc = Accumulator::default();
+        let base = ledger.base_currency();
+        let mut totals_acc = Accumulator::new(base);
         let mut category_map: HashMap<Option<Uuid>, Accumulator> = HashMap::new();
         let mut account_map: HashMap<Uuid, Accumulator> = HashMap::new();
+        let mut member_map: HashMap<Option<Uuid>, Accumulator> = HashMap::new();
+        let mut tag_map: HashMap<&str, Accumulator> = HashMap::new();
         let mut orphaned = 0usize;
         let mut incomplete_transactions = 0usize;
         let mut warnings = Vec::new();
@@ -209,25 +330,41 @@ impl BudgetService {
             ledger.accounts.iter().map(|a| (a.id, a)).collect();
 
         for txn in txs {
+            let hidden = txn
+                .category_id
+                .and_then(|id| category_lookup.get(&id))
+                .is_some_and(|category| category.hidden);
+            if hidden {
+                continue;
+            }
             let budget_in = window.contains(txn.scheduled_date);
-            let actual_in = txn
-                .actual_date
+            let actual_date = txn.reporting_date(ledger.summary_date_basis);
+            let actual_in = actual_date
                 .map(|date| window.contains(date))
                 .unwrap_or(false);
-            let actual_amount = txn.actual_amount;
+            let actual_amount = txn.actual_amount.as_ref().map(Money::to_major);
 
             if !budget_in && !actual_in {
                 continue;
             }
 
             let mut txn_incomplete = false;
-            let cat_entry = category_map.entry(txn.category_id).or_default();
-            let account_entry = account_map.entry(txn.from_account).or_default();
+            let cat_entry = category_map
+                .entry(txn.category_id)
+                .or_insert_with(|| Accumulator::new(base));
+            let account_entry = account_map
+                .entry(txn.from_account)
+                .or_insert_with(|| Accumulator::new(base));
+            let member_entry = member_map
+                .entry(txn.member_id)
+                .or_insert_with(|| Accumulator::new(base));
+            // This transaction's share alone, added to each of its tags.
+            let mut txn_entry = Accumulator::new(base);
             let txn_currency = ledger.transaction_currency(txn);
 
             if budget_in {
                 match ledger.convert_amount(
-                    txn.budgeted_amount,
+                    txn.budgeted_amount.to_major(),
                     &txn_currency,
                     txn.scheduled_date,
                     &ctx,
@@ -237,12 +374,16 @@ impl BudgetService {
                         totals_acc.add_budgeted(converted.amount);
                         cat_entry.add_budgeted(converted.amount);
                         account_entry.add_budgeted(converted.amount);
+                        member_entry.add_budgeted(converted.amount);
+                        txn_entry.add_budgeted(converted.amount);
                     }
                     Err(err) => {
                         warnings.push(format!("{} budget conversion failed: {}", txn.id, err));
                         totals_acc.missing_budget = true;
                         cat_entry.missing_budget = true;
                         account_entry.missing_budget = true;
+                        member_entry.missing_budget = true;
+                        txn_entry.missing_budget = true;
                         txn_incomplete = true;
                     }
                 }
@@ -250,19 +391,23 @@ impl BudgetService {
 
             if actual_in {
                 if let Some(amount) = actual_amount {
-                    let actual_date = txn.actual_date.unwrap_or(txn.scheduled_date);
+                    let actual_date = actual_date.unwrap_or(txn.scheduled_date);
                     match ledger.convert_amount(amount, &txn_currency, actual_date, &ctx) {
                         Ok(converted) => {
                             record_disclosure(&mut disclosures, &converted);
                             totals_acc.add_real(converted.amount);
                             cat_entry.add_real(converted.amount);
                             account_entry.add_real(converted.amount);
+                            member_entry.add_real(converted.amount);
+                            txn_entry.add_real(converted.amount);
                         }
                         Err(err) => {
                             warnings.push(format!("{} actual conversion failed: {}", txn.id, err));
                             totals_acc.missing_real = true;
                             cat_entry.missing_real = true;
                             account_entry.missing_real = true;
+                            member_entry.missing_real = true;
+                            txn_entry.missing_real = true;
                             txn_incomplete = true;
                         }
                     }
@@ -270,6 +415,8 @@ impl BudgetService {
                     totals_acc.missing_real = true;
                     cat_entry.missing_real = true;
                     account_entry.missing_real = true;
+                    member_entry.missing_real = true;
+                    txn_entry.missing_real = true;
                     txn_incomplete = true;
                 }
             }
@@ -278,12 +425,16 @@ impl BudgetService {
                 totals_acc.missing_budget = true;
                 cat_entry.missing_budget = true;
                 account_entry.missing_budget = true;
+                member_entry.missing_budget = true;
+                txn_entry.missing_budget = true;
                 txn_incomplete = true;
             }
             if budget_in && txn.actual_amount.is_none() {
                 totals_acc.missing_real = true;
                 cat_entry.missing_real = true;
                 account_entry.missing_real = true;
+                member_entry.missing_real = true;
+                txn_entry.missing_real = true;
                 txn_incomplete = true;
             }
 
@@ -299,28 +450,33 @@ impl BudgetService {
             if txn_incomplete {
                 incomplete_transactions += 1;
             }
+            for tag in &txn.tags {
+                tag_map
+                    .entry(tag.as_str())
+                    .or_insert_with(|| Accumulator::new(base))
+                    .absorb(&txn_entry);
+            }
         }
 
-        let totals = BudgetTotals::from_parts(
-            totals_acc.budgeted,
-            totals_acc.real,
-            totals_acc.is_incomplete(),
-        );
+        let totals = totals_acc.totals(&mut overflow);
 
         let mut per_category: Vec<CategoryBudget> = category_map
             .into_iter()
             .map(|(category_id, acc)| {
-                let name = match category_id {
-                    Some(id) => match category_lookup.get(&id) {
-                        Some(cat) => cat.name.clone(),
-                        None => "Unknown Category".into(),
-                    },
-                    None => "Uncategorized".into(),
+                let category = category_id.and_then(|id| category_lookup.get(&id));
+                let name = match (category_id, category) {
+                    (_, Some(cat)) => cat.name.clone(),
+                    (Some(_), None) => "Unknown Category".into(),
+                    (None, None) => "Uncategorized".into(),
                 };
                 CategoryBudget {
                     category_id,
                     name,
-                    totals: BudgetTotals::from_parts(acc.budgeted, acc.real, acc.is_incomplete()),
+                    totals: acc.totals(&mut overflow),
+                    note: category
+                        .and_then(|cat| cat.budget.as_ref())
+                        .and_then(|budget| budget.note.clone()),
+                    children: Vec::new(),
                 }
             })
             .collect();
@@ -336,28 +492,83 @@ impl BudgetService {
                 AccountBudget {
                     account_id,
                     name,
-                    totals: BudgetTotals::from_parts(acc.budgeted, acc.real, acc.is_incomplete()),
+                    totals: acc.totals(&mut overflow),
                 }
             })
             .collect();
         per_account.sort_by(|a, b| a.name.cmp(&b.name));
 
+        let mut per_member: Vec<MemberBudget> = if ledger.members.is_empty() {
+            Vec::new()
+        } else {
+            member_map
+                .into_iter()
+                .map(|(member_id, acc)| {
+                    let name = match member_id {
+                        Some(id) => ledger
+                            .member(id)
+                            .map(|member| member.name.clone())
+                            .unwrap_or_else(|| "Unknown Member".into()),
+                        None => "Unassigned".into(),
+                    };
+                    MemberBudget {
+                        member_id,
+                        name,
+                        totals: acc.totals(&mut overflow),
+                    }
+                })
+                .collect()
+        };
+        // Unassigned activity is listed last, after the named members.
+        per_member.sort_by(|a, b| {
+            a.member_id
+                .is_none()
+                .cmp(&b.member_id.is_none())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let mut per_tag: Vec<TagBudget> = tag_map
+            .into_iter()
+            .map(|(tag, acc)| TagBudget {
+                tag: tag.to_string(),
+                totals: acc.totals(&mut overflow),
+            })
+            .collect();
+        per_tag.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+        let fx_revaluation = fx_revaluation(ledger, txs, &window, report_reference, &mut warnings);
         let mut disclosures_vec: Vec<String> = disclosures.into_iter().collect();
         disclosures_vec.extend(warnings);
 
-        BudgetSummary {
+        let earmarked = SinkingFundService::accruals(ledger, &window);
+
+        let summary = BudgetSummary {
             scope,
             window,
             totals,
             per_category,
             per_account,
+            per_member,
+            per_tag,
+            earmarked,
+            fx_revaluation,
             orphaned_transactions: orphaned,
             incomplete_transactions,
             disclosures: disclosures_vec,
-        }
+        };
+        (summary, overflow)
     }
 
     /// Convenience helper for retrieving category budget usage for the period containing `reference`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            reference = %reference
+        )
+    )]
     pub fn category_budget_statuses_at(
         ledger: &Ledger,
         reference: NaiveDate,
@@ -368,6 +579,66 @@ impl BudgetService {
     }
 }
 
+/// Unrealized exchange gain or loss over `window` on the asset accounts
+/// held in another currency: their balance valued at the `report_date`
+/// rate, less what it was worth coming in. The balance carried into the
+/// window counts at the rate of its first day, each later movement at the
+/// rate of its own day. Accounts missing a rate are left out with a
+/// warning.
+fn fx_revaluation(
+    ledger: &Ledger,
+    txs: &[Transaction],
+    window: &DateWindow,
+    report_date: NaiveDate,
+    warnings: &mut Vec<String>,
+) -> Option<f64> {
+    let base = ledger.base_currency();
+    let on_date = ConversionContext {
+        policy: ValuationPolicy::TransactionDate,
+        report_date,
+    };
+    let mut gains = Vec::new();
+    for account in ledger.accounts.iter().filter(|a| is_asset_account(a)) {
+        let currency = ledger.holding_currency(account);
+        if currency.as_str() == base.as_str() {
+            continue;
+        }
+        let running = RunningBalance::completed(account, &currency, txs, window.start);
+        let entries: Vec<(NaiveDate, i64)> = std::iter::once((window.start, running.opening))
+            .chain(
+                running
+                    .movements
+                    .iter()
+                    .take_while(|movement| movement.date <= report_date)
+                    .map(|movement| (movement.date, movement.amount)),
+            )
+            .filter(|(_, minor)| *minor != 0)
+            .collect();
+        if entries.is_empty() {
+            continue;
+        }
+        let value = |minor: i64, date: NaiveDate| {
+            ledger
+                .convert_amount(running.major(minor), &currency, date, &on_date)
+                .map(|converted| converted.amount)
+        };
+        let held = entries
+            .iter()
+            .fold(0i64, |total, (_, minor)| total.saturating_add(*minor));
+        let revalued = entries
+            .iter()
+            .try_fold(0.0, |cost, (date, minor)| {
+                value(*minor, *date).map(|amount| cost + amount)
+            })
+            .and_then(|cost| value(held, report_date).map(|worth| worth - cost));
+        match revalued {
+            Ok(gain) => gains.push(gain),
+            Err(err) => warnings.push(format!("{} FX revaluation skipped: {}", account.name, err)),
+        }
+    }
+    (!gains.is_empty()).then(|| sum_major(gains, base))
+}
+
 fn record_disclosure(disclosures: &mut BTreeSet<String>, converted: &ConvertedAmount) {
     disclosures.insert(format!(
         "{} → {} @ {:.6} on {} ({})",
@@ -379,24 +650,79 @@ fn record_disclosure(disclosures: &mut BTreeSet<String>, converted: &ConvertedAm
     ));
 }
 
-#[derive(Default)]
+/// Running totals for one bucket, summed in whole minor units of the base
+/// currency so that many small amounts add up without drift.
 struct Accumulator {
-    budgeted: f64,
-    real: f64,
+    budgeted: Money,
+    real: Money,
     missing_budget: bool,
     missing_real: bool,
+    /// First running total that left the exact range.
+    overflow: Option<AmountOverflow>,
 }
 
 impl Accumulator {
+    fn new(currency: &CurrencyCode) -> Self {
+        Self {
+            budgeted: Money::zero(currency.clone()),
+            real: Money::zero(currency.clone()),
+            missing_budget: false,
+            missing_real: false,
+            overflow: None,
+        }
+    }
+
     fn add_budgeted(&mut self, amount: f64) {
-        self.budgeted += amount;
+        match add_in_minor_units(&self.budgeted, amount) {
+            Ok(sum) => self.budgeted = sum,
+            Err(value) => self.record_overflow("budgeted", value),
+        }
     }
 
     fn add_real(&mut self, amount: f64) {
-        self.real += amount;
+        match add_in_minor_units(&self.real, amount) {
+            Ok(sum) => self.real = sum,
+            Err(value) => self.record_overflow("real", value),
+        }
+    }
+
+    /// Adds the sums and flags of `other`, such as one transaction's share.
+    fn absorb(&mut self, other: &Accumulator) {
+        self.add_budgeted(other.budgeted.to_major());
+        self.add_real(other.real.to_major());
+        self.missing_budget |= other.missing_budget;
+        self.missing_real |= other.missing_real;
+        if let Some(err) = other.overflow {
+            self.overflow.get_or_insert(err);
+        }
+    }
+
+    fn record_overflow(&mut self, total: &'static str, value: f64) {
+        self.overflow.get_or_insert(AmountOverflow { total, value });
+    }
+
+    /// The bucket's totals. The first overflow seen, while summing or in
+    /// the derived totals, is kept in `overflow`.
+    fn totals(&self, overflow: &mut Option<AmountOverflow>) -> BudgetTotals {
+        if let Some(err) = self.overflow {
+            overflow.get_or_insert(err);
+        }
+        let (budgeted, real) = (self.budgeted.to_major(), self.real.to_major());
+        BudgetTotals::checked(budgeted, real, self.is_incomplete()).unwrap_or_else(|err| {
+            overflow.get_or_insert(err);
+            BudgetTotals::from_parts(budgeted, real, self.is_incomplete())
+        })
     }
 
     fn is_incomplete(&self) -> bool {
         self.missing_budget || self.missing_real
     }
 }
+
+/// `sum + amount`, with `amount` rounded to the sum's minor units. On
+/// overflow, the `f64` value the total would have reached.
+fn add_in_minor_units(sum: &Money, amount: f64) -> Result<Money, f64> {
+    Money::from_major(amount, sum.currency().clone())
+        .and_then(|amount| sum.checked_add(&amount))
+        .map_err(|_| sum.to_major() + amount)
+}
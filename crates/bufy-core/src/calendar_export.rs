@@ -0,0 +1,167 @@
+//! iCalendar (RFC 5545) feed of upcoming bills and income, so due dates
+//! show up in calendar apps.
+//!
+//! Each planned transaction and each projected recurrence in the window
+//! becomes an all-day `VEVENT`. UIDs stay the same between exports, so
+//! re-importing a newer feed updates events instead of duplicating them:
+//! recorded transactions use their id, and projected occurrences use their
+//! series and date.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use uuid::Uuid;
+
+use bufy_domain::{
+    currency::format_currency_value, ledger::DateWindow, recurring::forecast_for_window,
+    transaction::TransactionStatus, Ledger, Transaction,
+};
+
+/// Longest content line, in octets, before it is folded.
+const LINE_LIMIT: usize = 75;
+
+/// One all-day calendar entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: String,
+}
+
+/// Planned transactions and projected occurrences due in `window`,
+/// earliest first. Completed and missed transactions are left out.
+pub fn calendar_events(
+    ledger: &Ledger,
+    transactions: &[Transaction],
+    window: DateWindow,
+    reference: NaiveDate,
+) -> Vec<CalendarEvent> {
+    let mut events: Vec<CalendarEvent> = transactions
+        .iter()
+        .filter(|txn| {
+            matches!(
+                txn.status,
+                TransactionStatus::Planned | TransactionStatus::Simulated
+            ) && window.contains(txn.scheduled_date)
+        })
+        .map(|txn| event(ledger, txn, txn.id.to_string()))
+        .collect();
+    let forecast = forecast_for_window(window, reference, transactions, ledger.business_calendar());
+    events.extend(forecast.transactions.iter().map(|item| {
+        let txn = &item.transaction;
+        let series = txn.recurrence_series().unwrap_or(txn.id);
+        event(ledger, txn, occurrence_uid(series, txn.scheduled_date))
+    }));
+    events.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.summary.cmp(&b.summary)));
+    events
+}
+
+/// Serializes `events` as a `VCALENDAR` named after the ledger, with CRLF
+/// line endings and long lines folded.
+pub fn to_ics(calendar_name: &str, events: &[CalendarEvent], stamp: DateTime<Utc>) -> String {
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//BUFY//Forecast//EN");
+    push_line(&mut out, "CALSCALE:GREGORIAN");
+    push_line(&mut out, &format!("X-WR-CALNAME:{}", escape(calendar_name)));
+    for event in events {
+        let next_day = event.date.succ_opt().unwrap_or(event.date);
+        push_line(&mut out, "BEGIN:VEVENT");
+        push_line(&mut out, &format!("UID:{}", event.uid));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        push_line(
+            &mut out,
+            &format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")),
+        );
+        push_line(
+            &mut out,
+            &format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")),
+        );
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&event.summary)));
+        if !event.description.is_empty() {
+            push_line(
+                &mut out,
+                &format!("DESCRIPTION:{}", escape(&event.description)),
+            );
+        }
+        push_line(&mut out, "TRANSP:TRANSPARENT");
+        push_line(&mut out, "END:VEVENT");
+    }
+    push_line(&mut out, "END:VCALENDAR");
+    out
+}
+
+fn event(ledger: &Ledger, txn: &Transaction, id: String) -> CalendarEvent {
+    let account_name = |id| {
+        ledger
+            .account(id)
+            .map(|account| account.name.clone())
+            .unwrap_or_default()
+    };
+    let amount = format_currency_value(
+        txn.budgeted_amount.to_major(),
+        &ledger.transaction_currency(txn),
+        &ledger.locale,
+        &ledger.format,
+    );
+    let payee = txn
+        .merchant
+        .clone()
+        .unwrap_or_else(|| account_name(txn.to_account));
+    let mut details = vec![format!(
+        "{} → {}",
+        account_name(txn.from_account),
+        account_name(txn.to_account)
+    )];
+    if let Some(category) = txn.category_id.and_then(|id| ledger.category(id)) {
+        details.push(format!("Category: {}", category.name));
+    }
+    if let Some(reference) = txn.short_ref() {
+        details.push(format!("Reference: {}", reference));
+    }
+    if let Some(notes) = txn.notes.as_deref().filter(|notes| !notes.is_empty()) {
+        details.push(notes.to_string());
+    }
+    CalendarEvent {
+        uid: format!("{}@bufy", id),
+        date: txn.scheduled_date,
+        summary: format!("{}: {}", payee, amount),
+        description: details.join("\n"),
+    }
+}
+
+fn occurrence_uid(series: Uuid, date: NaiveDate) -> String {
+    format!("{}-{}", series, date.format("%Y%m%d"))
+}
+
+/// Escapes a TEXT value per RFC 5545 §3.3.11.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Appends a content line, folding it into 75-octet pieces without splitting
+/// a character.
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > LINE_LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out.push_str("\r\n");
+}
@@ -0,0 +1,200 @@
+//! Learns category assignments from manual corrections and replays them.
+//!
+//! Each transaction is reduced to key phrases: the single words and adjacent
+//! word pairs found in its notes and counterparty account name. When the user
+//! recategorizes a transaction, every phrase is mapped to the new category.
+//! Suggestions score categories by the hits of matching phrases, weighting
+//! word pairs above single words because they are more specific.
+
+use std::collections::HashMap;
+
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{learned_rule::LearnedRule, transaction::Transaction, Ledger};
+
+use crate::{Clock, CoreError};
+
+/// Words shorter than this are ignored when extracting phrases.
+const MIN_WORD_LEN: usize = 3;
+
+pub struct CategorizationService;
+
+impl CategorizationService {
+    /// Normalized phrases describing `txn`, used as rule patterns.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction = %txn.id,
+            amount = %txn.budgeted_amount
+        )
+    )]
+    pub fn key_phrases(ledger: &Ledger, txn: &Transaction) -> Vec<String> {
+        let mut sources = Vec::new();
+        if let Some(notes) = txn.notes.as_deref() {
+            sources.push(notes.to_string());
+        }
+        if let Some(account) = ledger.account(txn.to_account) {
+            sources.push(account.name.clone());
+        }
+
+        let mut phrases = Vec::new();
+        for source in sources {
+            let words: Vec<String> = source
+                .split(|ch: char| !ch.is_alphanumeric())
+                .filter(|word| word.chars().count() >= MIN_WORD_LEN)
+                .filter(|word| !word.chars().all(|ch| ch.is_ascii_digit()))
+                .map(|word| word.to_lowercase())
+                .collect();
+            for word in &words {
+                if !phrases.contains(word) {
+                    phrases.push(word.clone());
+                }
+            }
+            for pair in words.windows(2) {
+                let phrase = pair.join(" ");
+                if !phrases.contains(&phrase) {
+                    phrases.push(phrase);
+                }
+            }
+        }
+        phrases
+    }
+
+    /// Records the category currently assigned to transaction `id` as the
+    /// preferred category for its key phrases. Returns the number of phrases
+    /// learned; transactions without a category or phrases teach nothing.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn learn(ledger: &mut Ledger, id: Uuid, clock: &dyn Clock) -> Result<usize, CoreError> {
+        let txn = ledger
+            .transaction(id)
+            .ok_or(CoreError::TransactionNotFound(id))?;
+        let Some(category_id) = txn.category_id else {
+            return Ok(0);
+        };
+        let phrases = Self::key_phrases(ledger, txn);
+        if phrases.is_empty() {
+            return Ok(0);
+        }
+        let now = clock.now();
+        for phrase in &phrases {
+            match ledger
+                .learned_rules
+                .iter_mut()
+                .find(|rule| &rule.pattern == phrase)
+            {
+                Some(rule) if rule.category_id == category_id => {
+                    rule.hits = rule.hits.saturating_add(1);
+                    rule.updated_at = now;
+                }
+                // A newer correction overrides whatever the phrase meant before.
+                Some(rule) => *rule = LearnedRule::new(phrase.clone(), category_id, now),
+                None => {
+                    ledger
+                        .learned_rules
+                        .push(LearnedRule::new(phrase.clone(), category_id, now))
+                }
+            }
+        }
+        ledger.touch();
+        Ok(phrases.len())
+    }
+
+    /// Suggests a category for `txn` from the learned rules.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction = %txn.id,
+            amount = %txn.budgeted_amount
+        )
+    )]
+    pub fn suggest(ledger: &Ledger, txn: &Transaction) -> Option<Uuid> {
+        if ledger.learned_rules.is_empty() {
+            return None;
+        }
+        let mut scores: HashMap<Uuid, u32> = HashMap::new();
+        for phrase in Self::key_phrases(ledger, txn) {
+            let Some(rule) = ledger
+                .learned_rules
+                .iter()
+                .find(|rule| rule.pattern == phrase)
+            else {
+                continue;
+            };
+            if ledger.category(rule.category_id).is_none() {
+                continue;
+            }
+            let weight = if phrase.contains(' ') { 2 } else { 1 };
+            *scores.entry(rule.category_id).or_default() += rule.hits * weight;
+        }
+        scores
+            .into_iter()
+            .max_by(|(a_id, a), (b_id, b)| a.cmp(b).then_with(|| b_id.cmp(a_id)))
+            .map(|(category_id, _)| category_id)
+    }
+
+    /// Assigns the suggested category to an uncategorized transaction.
+    /// Intended for import paths; returns `true` when a category was applied.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn apply(ledger: &Ledger, txn: &mut Transaction) -> bool {
+        if txn.category_id.is_some() {
+            return false;
+        }
+        match Self::suggest(ledger, txn) {
+            Some(category_id) => {
+                txn.category_id = Some(category_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the rule for `pattern` (case-insensitive).
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn forget(ledger: &mut Ledger, pattern: &str) -> Result<LearnedRule, CoreError> {
+        let normalized = pattern.trim().to_lowercase();
+        let index = ledger
+            .learned_rules
+            .iter()
+            .position(|rule| rule.pattern == normalized)
+            .ok_or_else(|| CoreError::RuleNotFound(pattern.trim().to_string()))?;
+        let removed = ledger.learned_rules.remove(index);
+        ledger.touch();
+        Ok(removed)
+    }
+
+    /// Removes every learned rule, returning how many were dropped.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn forget_all(ledger: &mut Ledger) -> usize {
+        let count = ledger.learned_rules.len();
+        if count > 0 {
+            ledger.learned_rules.clear();
+            ledger.touch();
+        }
+        count
+    }
+}
@@ -0,0 +1,226 @@
+//! Category groups: sets of categories budgeted and summarized as one row.
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    category::{CategoryBudgetDefinition, CategoryGroup},
+    common::BudgetPeriod,
+    ledger::{BudgetScope, BudgetTotals, CategoryGroupSummary, DateWindow},
+    Ledger, Money,
+};
+
+use crate::stats::sum_major;
+use crate::validation::{check_amount, AmountRule};
+use crate::{CoreError, SummaryService};
+
+pub struct CategoryGroupService;
+
+impl CategoryGroupService {
+    /// Adds an empty group called `name`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn create(ledger: &mut Ledger, name: &str) -> Result<Uuid, CoreError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(CoreError::Validation(
+                "category group name cannot be empty".into(),
+            ));
+        }
+        if ledger.category_group(name).is_some() {
+            return Err(CoreError::Validation(format!(
+                "category group `{}` already exists",
+                name
+            )));
+        }
+        let group = CategoryGroup::new(name);
+        let id = group.id;
+        ledger.category_groups.push(group);
+        ledger.touch();
+        Ok(id)
+    }
+
+    /// Deletes a group; its categories are kept and become ungrouped.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn remove(ledger: &mut Ledger, name: &str) -> Result<(), CoreError> {
+        let id = Self::group(ledger, name)?.id;
+        ledger.category_groups.retain(|group| group.id != id);
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Puts a category in the group called `name`, taking it out of any
+    /// group it was in before.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            category_id = %category_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn assign(ledger: &mut Ledger, name: &str, category_id: Uuid) -> Result<(), CoreError> {
+        if ledger.category(category_id).is_none() {
+            return Err(CoreError::CategoryNotFound(category_id.to_string()));
+        }
+        let id = Self::group(ledger, name)?.id;
+        for group in &mut ledger.category_groups {
+            if group.id == id {
+                if !group.contains(category_id) {
+                    group.category_ids.push(category_id);
+                }
+            } else {
+                group.category_ids.retain(|member| *member != category_id);
+            }
+        }
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Takes a category out of its group, returning whether it had one.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            category_id = %category_id
+        )
+    )]
+    pub fn unassign(ledger: &mut Ledger, category_id: Uuid) -> bool {
+        let mut removed = false;
+        for group in &mut ledger.category_groups {
+            let before = group.category_ids.len();
+            group.category_ids.retain(|member| *member != category_id);
+            removed |= group.category_ids.len() != before;
+        }
+        if removed {
+            ledger.touch();
+        }
+        removed
+    }
+
+    /// Sets a limit on the members' combined spending.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            amount = amount,
+            reference_date = ?reference_date
+        ),
+        err(level = "debug")
+    )]
+    pub fn set_budget(
+        ledger: &mut Ledger,
+        name: &str,
+        amount: f64,
+        period: BudgetPeriod,
+        reference_date: Option<NaiveDate>,
+    ) -> Result<(), CoreError> {
+        check_amount("budget amount", amount, AmountRule::NonNegative)?;
+        let amount = Money::from_major(amount, ledger.base_currency().clone())?;
+        let group = Self::group_mut(ledger, name)?;
+        let mut budget = CategoryBudgetDefinition::new(amount, period);
+        budget.reference_date = reference_date;
+        group.budget = Some(budget);
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Removes the group budget, returning whether there was one.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn clear_budget(ledger: &mut Ledger, name: &str) -> Result<bool, CoreError> {
+        let had_budget = Self::group_mut(ledger, name)?.budget.take().is_some();
+        if had_budget {
+            ledger.touch();
+        }
+        Ok(had_budget)
+    }
+
+    /// Spending of every group in `window`. A group without its own budget is
+    /// measured against the sum of its members' budgets.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn summaries(
+        ledger: &Ledger,
+        window: DateWindow,
+        scope: BudgetScope,
+    ) -> Vec<CategoryGroupSummary> {
+        if ledger.category_groups.is_empty() {
+            return Vec::new();
+        }
+        let statuses = SummaryService::category_budget_statuses(ledger, window, scope);
+        ledger
+            .category_groups
+            .iter()
+            .map(|group| {
+                let members: Vec<_> = statuses
+                    .iter()
+                    .filter(|status| group.contains(status.category_id))
+                    .cloned()
+                    .collect();
+                let base = ledger.base_currency();
+                let spent = sum_major(members.iter().map(|member| member.totals.real), base);
+                let budget_amount = match &group.budget {
+                    Some(budget) => Some(budget.amount_for_window(window).to_major()),
+                    None => {
+                        let budgets: Vec<f64> = members
+                            .iter()
+                            .filter_map(|member| member.window_budget)
+                            .collect();
+                        (!budgets.is_empty()).then(|| sum_major(budgets, base))
+                    }
+                };
+                CategoryGroupSummary {
+                    group_id: group.id,
+                    name: group.name.clone(),
+                    budget_amount,
+                    group_budget: group.budget.is_some(),
+                    totals: BudgetTotals::from_parts(budget_amount.unwrap_or(0.0), spent, false),
+                    members,
+                }
+            })
+            .collect()
+    }
+
+    fn group<'a>(ledger: &'a Ledger, name: &str) -> Result<&'a CategoryGroup, CoreError> {
+        ledger
+            .category_group(name)
+            .ok_or_else(|| Self::not_found(name))
+    }
+
+    fn group_mut<'a>(
+        ledger: &'a mut Ledger,
+        name: &str,
+    ) -> Result<&'a mut CategoryGroup, CoreError> {
+        ledger
+            .category_group_mut(name)
+            .ok_or_else(|| Self::not_found(name))
+    }
+
+    fn not_found(name: &str) -> CoreError {
+        CoreError::InvalidOperation(format!("category group `{}` not found", name.trim()))
+    }
+}
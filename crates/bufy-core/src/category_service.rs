@@ -1,10 +1,26 @@
 //! Business logic helpers for category management.
 
+use std::collections::HashMap;
+
 use chrono::NaiveDate;
+use tracing::instrument;
 use uuid::Uuid;
 
-use bufy_domain::{category::Category, BudgetPeriod, Ledger};
+use bufy_domain::{
+    appearance::Appearance,
+    category::{BudgetProration, Category, SystemCategory},
+    move_within_block, pin_favorites, set_favorite_at, BudgetPeriod, Ledger, Money, MoveDirection,
+    Transaction,
+};
 
+use crate::journal::{ChangeJournal, JournalEntry};
+use crate::stats::{
+    completed_amount, minor_in, to_major, trailing_window, ActivityTracker, CategoryStats,
+    STATS_AVERAGE_MONTHS,
+};
+use crate::validation::{
+    check_amount, check_icon, check_name, AmountRule, ValidationIssue, ValidationIssueKind,
+};
 use crate::CoreError;
 
 /// Provides validated operations for [`Category`] entities.
@@ -12,8 +28,14 @@ pub struct CategoryService;
 
 impl CategoryService {
     /// Adds a new category and ensures its name and parent are valid.
-    pub fn add(ledger: &mut Ledger, category: Category) -> Result<(), CoreError> {
-        Self::validate_name(ledger, None, &category.name)?;
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn add(ledger: &mut Ledger, mut category: Category) -> Result<(), CoreError> {
+        category.name = Self::validate_name(ledger, None, &category.name)?;
         if let Some(parent_id) = category.parent_id {
             Self::validate_parent(ledger, parent_id, None)?;
         }
@@ -21,16 +43,55 @@ impl CategoryService {
         Ok(())
     }
 
+    /// Returns the hidden category for an internal flow, creating it on
+    /// first use. A user category that already has the default name keeps
+    /// it; the system one gets a `(system)` suffix instead.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn ensure_system(ledger: &mut Ledger, role: SystemCategory) -> Uuid {
+        if let Some(existing) = ledger
+            .categories
+            .iter()
+            .find(|category| category.system == Some(role))
+        {
+            return existing.id;
+        }
+        let mut category = Category::system(role);
+        if Self::validate_name(ledger, None, &category.name).is_err() {
+            category.name = format!("{} (system)", category.name);
+        }
+        ledger.add_category(category)
+    }
+
+    /// Categories offered in pickers and listings unless `--all` is given.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn visible(ledger: &Ledger) -> impl Iterator<Item = &Category> {
+        ledger.categories.iter().filter(|category| !category.hidden)
+    }
+
     /// Applies updates to a category, respecting parentage rules.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
     pub fn edit(ledger: &mut Ledger, id: Uuid, changes: Category) -> Result<(), CoreError> {
-        Self::validate_name(ledger, Some(id), &changes.name)?;
+        let name = Self::validate_name(ledger, Some(id), &changes.name)?;
         if let Some(parent_id) = changes.parent_id {
             Self::validate_parent(ledger, parent_id, Some(id))?;
         }
         let category = ledger
             .category_mut(id)
             .ok_or_else(|| CoreError::CategoryNotFound(id.to_string()))?;
-        category.name = changes.name;
+        category.name = name;
         category.kind = changes.kind;
         category.parent_id = changes.parent_id;
         category.is_custom = changes.is_custom;
@@ -40,6 +101,12 @@ impl CategoryService {
     }
 
     /// Removes a category after verifying it has no children or transactions.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
     pub fn remove(ledger: &mut Ledger, id: Uuid) -> Result<(), CoreError> {
         if ledger
             .categories
@@ -64,11 +131,103 @@ impl CategoryService {
         if ledger.categories.len() == before {
             return Err(CoreError::CategoryNotFound(id.to_string()));
         }
+        for group in &mut ledger.category_groups {
+            group.category_ids.retain(|member| *member != id);
+        }
         ledger.touch();
         Ok(())
     }
 
+    /// Like [`Self::add`], recording the edit in `journal` for undo.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn add_journaled(
+        ledger: &mut Ledger,
+        category: Category,
+        journal: &mut ChangeJournal,
+    ) -> Result<(), CoreError> {
+        let entry = JournalEntry::CategoryAdded(category.clone());
+        Self::add(ledger, category)?;
+        journal.record(entry);
+        Ok(())
+    }
+
+    /// Like [`Self::edit`], recording the edit in `journal` for undo.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn edit_journaled(
+        ledger: &mut Ledger,
+        id: Uuid,
+        changes: Category,
+        journal: &mut ChangeJournal,
+    ) -> Result<(), CoreError> {
+        let before = ledger
+            .category(id)
+            .cloned()
+            .ok_or_else(|| CoreError::CategoryNotFound(id.to_string()))?;
+        Self::edit(ledger, id, changes)?;
+        let after = ledger
+            .category(id)
+            .cloned()
+            .expect("category exists after edit");
+        journal.record(JournalEntry::CategoryEdited { before, after });
+        Ok(())
+    }
+
+    /// Like [`Self::remove`], recording the edit in `journal` for undo.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn remove_journaled(
+        ledger: &mut Ledger,
+        id: Uuid,
+        journal: &mut ChangeJournal,
+    ) -> Result<(), CoreError> {
+        let index = ledger
+            .categories
+            .iter()
+            .position(|category| category.id == id)
+            .ok_or_else(|| CoreError::CategoryNotFound(id.to_string()))?;
+        let category = ledger.categories[index].clone();
+        let group_ids = ledger
+            .category_groups
+            .iter()
+            .filter(|group| group.category_ids.contains(&id))
+            .map(|group| group.id)
+            .collect();
+        Self::remove(ledger, id)?;
+        journal.record(JournalEntry::CategoryRemoved {
+            index,
+            category,
+            group_ids,
+        });
+        Ok(())
+    }
+
     /// Assigns a budget definition to the given category.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            id = %id,
+            amount = amount,
+            reference_date = ?reference_date
+        ),
+        err(level = "debug")
+    )]
     pub fn set_budget(
         ledger: &mut Ledger,
         id: Uuid,
@@ -76,6 +235,8 @@ impl CategoryService {
         period: BudgetPeriod,
         reference_date: Option<NaiveDate>,
     ) -> Result<(), CoreError> {
+        check_amount("budget amount", amount, AmountRule::NonNegative)?;
+        let amount = Money::from_major(amount, ledger.base_currency().clone())?;
         let category = ledger
             .category_mut(id)
             .ok_or_else(|| CoreError::CategoryNotFound(id.to_string()))?;
@@ -84,7 +245,68 @@ impl CategoryService {
         Ok(())
     }
 
+    /// Changes how a category budget is scaled to summary windows.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn set_budget_proration(
+        ledger: &mut Ledger,
+        id: Uuid,
+        proration: BudgetProration,
+    ) -> Result<(), CoreError> {
+        let category = ledger
+            .category_mut(id)
+            .ok_or_else(|| CoreError::CategoryNotFound(id.to_string()))?;
+        let budget = category.budget.as_mut().ok_or_else(|| {
+            CoreError::InvalidOperation(format!("category `{}` has no budget", category.name))
+        })?;
+        budget.proration = proration;
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Sets or removes the note explaining a category's budget. Blank notes
+    /// remove it.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            id = %id,
+            note = ?note
+        ),
+        err(level = "debug")
+    )]
+    pub fn set_budget_note(
+        ledger: &mut Ledger,
+        id: Uuid,
+        note: Option<&str>,
+    ) -> Result<(), CoreError> {
+        let category = ledger
+            .category_mut(id)
+            .ok_or_else(|| CoreError::CategoryNotFound(id.to_string()))?;
+        let budget = category.budget.as_mut().ok_or_else(|| {
+            CoreError::InvalidOperation(format!("category `{}` has no budget", category.name))
+        })?;
+        budget.note = note
+            .map(str::trim)
+            .filter(|note| !note.is_empty())
+            .map(str::to_string);
+        ledger.touch();
+        Ok(())
+    }
+
     /// Clears the budget assigned to a category, returning whether it existed.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
     pub fn clear_budget(ledger: &mut Ledger, id: Uuid) -> Result<bool, CoreError> {
         let category = ledger
             .category_mut(id)
@@ -97,28 +319,196 @@ impl CategoryService {
         Ok(had_budget)
     }
 
+    /// Moves a category within its block (favorites or the rest) of the
+    /// display order, stepping over hidden categories. Returns its new index.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id, direction = %direction),
+        err(level = "debug")
+    )]
+    pub fn reorder(
+        ledger: &mut Ledger,
+        id: Uuid,
+        direction: MoveDirection,
+    ) -> Result<usize, CoreError> {
+        pin_favorites(&mut ledger.categories);
+        let index = Self::position(ledger, id)?;
+        let moved = move_within_block(&mut ledger.categories, index, direction, |category| {
+            !category.hidden
+        });
+        if moved != index {
+            ledger.touch();
+        }
+        Ok(moved)
+    }
+
+    /// Sets the color and icon shown with the category's name; an empty
+    /// [`Appearance`] clears both.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn set_appearance(
+        ledger: &mut Ledger,
+        id: Uuid,
+        appearance: Appearance,
+    ) -> Result<(), CoreError> {
+        let icon = match appearance.icon.as_deref() {
+            Some(icon) => check_icon("icon", icon)?,
+            None => None,
+        };
+        let category = ledger
+            .category_mut(id)
+            .ok_or_else(|| CoreError::CategoryNotFound(id.to_string()))?;
+        category.appearance = Appearance {
+            color: appearance.color,
+            icon,
+        };
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Stars or unstars a category, pinning starred ones to the top of lists
+    /// and pickers. Returns its new index.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id, favorite),
+        err(level = "debug")
+    )]
+    pub fn set_favorite(ledger: &mut Ledger, id: Uuid, favorite: bool) -> Result<usize, CoreError> {
+        pin_favorites(&mut ledger.categories);
+        let index = Self::position(ledger, id)?;
+        if ledger.categories[index].favorite == favorite {
+            return Ok(index);
+        }
+        let moved = set_favorite_at(&mut ledger.categories, index, favorite);
+        ledger.touch();
+        Ok(moved)
+    }
+
     /// Returns a snapshot of all categories.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn list(ledger: &Ledger) -> Vec<&Category> {
         ledger.categories.iter().collect()
     }
 
+    /// Computes spend and activity figures for a category as of `today`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            id = %id,
+            today = %today
+        ),
+        err(level = "debug")
+    )]
+    pub fn stats(ledger: &Ledger, id: Uuid, today: NaiveDate) -> Result<CategoryStats, CoreError> {
+        if ledger.category(id).is_none() {
+            return Err(CoreError::CategoryNotFound(id.to_string()));
+        }
+        let filed = ledger
+            .transactions
+            .iter()
+            .filter(|txn| txn.category_id == Some(id));
+        Ok(Self::stats_over(ledger, filed, today))
+    }
+
+    /// [`Self::stats`] for every category, walking the transactions once.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn stats_by_category(ledger: &Ledger, today: NaiveDate) -> HashMap<Uuid, CategoryStats> {
+        let mut filed: HashMap<Uuid, Vec<&Transaction>> = HashMap::new();
+        for txn in &ledger.transactions {
+            if let Some(id) = txn.category_id {
+                filed.entry(id).or_default().push(txn);
+            }
+        }
+        ledger
+            .categories
+            .iter()
+            .map(|category| {
+                let transactions = filed.get(&category.id).into_iter().flatten().copied();
+                (category.id, Self::stats_over(ledger, transactions, today))
+            })
+            .collect()
+    }
+
+    /// Stats of the transactions filed under one category.
+    fn stats_over<'a>(
+        ledger: &Ledger,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+        today: NaiveDate,
+    ) -> CategoryStats {
+        let period = ledger.budget_window_containing(today);
+        let trailing = trailing_window(today);
+        let currency = ledger.base_currency();
+        let mut period_spend = 0i64;
+        let mut trailing_spend = 0i64;
+        let mut activity = ActivityTracker::default();
+
+        for txn in transactions {
+            let Some((date, amount)) = completed_amount(txn).filter(|(date, _)| *date <= today)
+            else {
+                continue;
+            };
+            let amount = minor_in(amount, currency);
+            if period.contains(date) {
+                period_spend = period_spend.saturating_add(amount);
+            }
+            if trailing.contains(date) {
+                trailing_spend = trailing_spend.saturating_add(amount);
+            }
+            activity.record(txn, date, to_major(amount, currency));
+        }
+
+        CategoryStats {
+            period_spend: to_major(period_spend, currency),
+            monthly_average: to_major(trailing_spend, currency) / STATS_AVERAGE_MONTHS as f64,
+            largest_transaction: activity.largest,
+            last_activity: activity.last_activity,
+        }
+    }
+
+    fn position(ledger: &Ledger, id: Uuid) -> Result<usize, CoreError> {
+        ledger
+            .categories
+            .iter()
+            .position(|item| item.id == id)
+            .ok_or_else(|| CoreError::CategoryNotFound(id.to_string()))
+    }
+
     fn validate_name(
         ledger: &Ledger,
         exclude: Option<Uuid>,
         candidate: &str,
-    ) -> Result<(), CoreError> {
-        let normalized = candidate.trim().to_ascii_lowercase();
+    ) -> Result<String, CoreError> {
+        let name = check_name("category name", candidate)?;
+        let normalized = name.to_ascii_lowercase();
         let duplicate = ledger.categories.iter().any(|category| {
-            let name = category.name.trim().to_ascii_lowercase();
-            name == normalized && (exclude != Some(category.id))
+            category.name.trim().to_ascii_lowercase() == normalized
+                && (exclude != Some(category.id))
         });
         if duplicate {
-            Err(CoreError::Validation(format!(
-                "category `{}` already exists",
-                candidate
-            )))
+            Err(ValidationIssue::new(
+                "category name",
+                ValidationIssueKind::Duplicate { value: name },
+            )
+            .into())
         } else {
-            Ok(())
+            Ok(name)
         }
     }
 
@@ -0,0 +1,208 @@
+//! Record-level change events for tools that follow a ledger from outside.
+//!
+//! A change feed starts with a [`LedgerChange::Snapshot`] of the whole
+//! ledger and continues with the records added, changed, or removed at each
+//! save. Any snapshot resets the state, so compacting a feed down to one
+//! snapshot leaves its replay unchanged. Storage backends decide where the
+//! events live and assign their sequence numbers.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::instrument;
+
+use bufy_domain::Ledger;
+
+use crate::CoreError;
+
+/// A numbered entry in a ledger's change feed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEvent {
+    /// Increases by one per event and is never reused, even by compaction.
+    pub seq: u64,
+    pub recorded_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub change: LedgerChange,
+}
+
+/// What changed between two saved states of a ledger.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LedgerChange {
+    /// The full ledger, replacing any earlier state.
+    Snapshot { ledger: Value },
+    /// A record in a collection such as `accounts` or `transactions` was
+    /// added or edited.
+    Upserted {
+        collection: String,
+        id: String,
+        record: Value,
+    },
+    /// A record was removed from a collection.
+    Removed { collection: String, id: String },
+    /// A ledger-level field changed; `null` means it was cleared.
+    FieldChanged { field: String, value: Value },
+}
+
+pub struct ChangeFeedService;
+
+impl ChangeFeedService {
+    /// Lists the changes that turn `previous` into `current`. Without a
+    /// previous state the result is a single snapshot.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %current.id, revision = %current.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn diff(
+        previous: Option<&Ledger>,
+        current: &Ledger,
+    ) -> Result<Vec<LedgerChange>, CoreError> {
+        let current = to_object(current)?;
+        let Some(previous) = previous else {
+            return Ok(vec![LedgerChange::Snapshot {
+                ledger: Value::Object(current),
+            }]);
+        };
+        let previous = to_object(previous)?;
+        let fields: BTreeSet<&String> = previous.keys().chain(current.keys()).collect();
+        let mut changes = Vec::new();
+        for field in fields {
+            let before = previous.get(field);
+            let after = current.get(field);
+            if before == after {
+                continue;
+            }
+            match (records(before), records(after)) {
+                (Some(before), Some(after)) => {
+                    for (id, record) in &after {
+                        let unchanged = before
+                            .iter()
+                            .any(|(old_id, old)| old_id == id && old == record);
+                        if !unchanged {
+                            changes.push(LedgerChange::Upserted {
+                                collection: field.clone(),
+                                id: id.to_string(),
+                                record: (*record).clone(),
+                            });
+                        }
+                    }
+                    for (id, _) in &before {
+                        if !after.iter().any(|(new_id, _)| new_id == id) {
+                            changes.push(LedgerChange::Removed {
+                                collection: field.clone(),
+                                id: id.to_string(),
+                            });
+                        }
+                    }
+                }
+                _ => changes.push(LedgerChange::FieldChanged {
+                    field: field.clone(),
+                    value: after.cloned().unwrap_or(Value::Null),
+                }),
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Rebuilds the ledger described by a feed, starting from its latest
+    /// snapshot.
+    #[instrument(level = "debug", skip_all, err(level = "debug"))]
+    pub fn replay<'a>(
+        events: impl IntoIterator<Item = &'a LedgerEvent>,
+    ) -> Result<Ledger, CoreError> {
+        let mut state: Option<Map<String, Value>> = None;
+        for event in events {
+            match &event.change {
+                LedgerChange::Snapshot { ledger } => {
+                    state = Some(ledger.as_object().cloned().ok_or_else(|| {
+                        CoreError::Serde(format!("event {}: snapshot is not an object", event.seq))
+                    })?);
+                }
+                change => {
+                    let state = state.as_mut().ok_or_else(|| {
+                        CoreError::Validation(format!(
+                            "change feed has event {} before any snapshot",
+                            event.seq
+                        ))
+                    })?;
+                    apply(state, change);
+                }
+            }
+        }
+        let state =
+            state.ok_or_else(|| CoreError::Validation("change feed has no snapshot".into()))?;
+        serde_json::from_value(Value::Object(state))
+            .map_err(|err| CoreError::Serde(err.to_string()))
+    }
+}
+
+pub(crate) fn to_object(ledger: &Ledger) -> Result<Map<String, Value>, CoreError> {
+    match serde_json::to_value(ledger).map_err(|err| CoreError::Serde(err.to_string()))? {
+        Value::Object(map) => Ok(map),
+        _ => Err(CoreError::Serde(
+            "ledger did not serialize to an object".into(),
+        )),
+    }
+}
+
+/// Treats an array whose elements all carry an `id` as a record collection.
+/// A missing field counts as an empty collection, since empty lists are
+/// often left out of the saved ledger.
+pub(crate) fn records(value: Option<&Value>) -> Option<Vec<(&str, &Value)>> {
+    match value {
+        None => Some(Vec::new()),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| item.get("id").and_then(Value::as_str).map(|id| (id, item)))
+            .collect(),
+        Some(_) => None,
+    }
+}
+
+fn apply(state: &mut Map<String, Value>, change: &LedgerChange) {
+    match change {
+        LedgerChange::Snapshot { .. } => {}
+        LedgerChange::Upserted {
+            collection,
+            id,
+            record,
+        } => {
+            let items = collection_mut(state, collection);
+            match items
+                .iter_mut()
+                .find(|item| item.get("id").and_then(Value::as_str) == Some(id))
+            {
+                Some(existing) => *existing = record.clone(),
+                None => items.push(record.clone()),
+            }
+        }
+        LedgerChange::Removed { collection, id } => {
+            collection_mut(state, collection)
+                .retain(|item| item.get("id").and_then(Value::as_str) != Some(id));
+        }
+        LedgerChange::FieldChanged { field, value } => {
+            if value.is_null() {
+                state.remove(field);
+            } else {
+                state.insert(field.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn collection_mut<'a>(state: &'a mut Map<String, Value>, name: &str) -> &'a mut Vec<Value> {
+    let slot = state
+        .entry(name.to_string())
+        .or_insert_with(|| Value::Array(Vec::new()));
+    if !slot.is_array() {
+        *slot = Value::Array(Vec::new());
+    }
+    match slot {
+        Value::Array(items) => items,
+        _ => unreachable!("collection slot was just made an array"),
+    }
+}
@@ -0,0 +1,176 @@
+//! Contracts behind recurring bills, and when notice is due so they can be
+//! renegotiated before they renew automatically.
+
+use std::fmt;
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    common::TimeInterval,
+    transaction::{ContractTerms, RecurrenceStatus},
+    Ledger,
+};
+
+use crate::CoreError;
+
+/// Notice deadlines this many days away or fewer raise an alert.
+pub const CONTRACT_NOTICE_ALERT_DAYS: i64 = 30;
+
+/// Where a contract stands relative to its notice deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContractStage {
+    /// The notice deadline is more than [`CONTRACT_NOTICE_ALERT_DAYS`] away.
+    Running,
+    /// Notice can still be given, and the deadline is close.
+    NoticeDue,
+    /// Too late to give notice; the contract renews at its end date.
+    Renewing,
+    /// The end date has passed and the terms need a new end date.
+    Lapsed,
+}
+
+impl fmt::Display for ContractStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ContractStage::Running => "Running",
+            ContractStage::NoticeDue => "Notice due",
+            ContractStage::Renewing => "Renewing",
+            ContractStage::Lapsed => "Lapsed",
+        };
+        f.write_str(label)
+    }
+}
+
+/// The renewal window of one recurring contract on a given day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractWindow {
+    pub series_id: Uuid,
+    /// Template transaction carrying the recurrence.
+    pub transaction_id: Uuid,
+    pub provider: String,
+    pub amount: f64,
+    pub interval: TimeInterval,
+    pub end_date: NaiveDate,
+    pub notice_deadline: NaiveDate,
+    /// Days from today to the notice deadline; negative once it has passed.
+    pub days_to_notice: i64,
+    pub stage: ContractStage,
+}
+
+pub struct ContractService;
+
+impl ContractService {
+    /// Attaches contract terms to a recurring transaction, replacing any
+    /// it had.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn set(
+        ledger: &mut Ledger,
+        transaction_id: Uuid,
+        terms: ContractTerms,
+    ) -> Result<(), CoreError> {
+        let provider = terms.provider.trim();
+        if provider.is_empty() {
+            return Err(CoreError::Validation("provider cannot be empty".into()));
+        }
+        if terms.notice_period.as_ref().is_some_and(|n| n.every == 0) {
+            return Err(CoreError::Validation(
+                "notice period must be at least one unit".into(),
+            ));
+        }
+        let terms = ContractTerms {
+            provider: provider.to_string(),
+            ..terms
+        };
+        let recurrence = ledger
+            .transaction_mut(transaction_id)
+            .ok_or(CoreError::TransactionNotFound(transaction_id))?
+            .recurrence
+            .as_mut()
+            .ok_or_else(|| CoreError::InvalidOperation("transaction has no recurrence".into()))?;
+        recurrence.contract = Some(terms);
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Removes a recurring transaction's contract terms, returning whether
+    /// it had any.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn clear(ledger: &mut Ledger, transaction_id: Uuid) -> Result<bool, CoreError> {
+        let had_contract = ledger
+            .transaction_mut(transaction_id)
+            .ok_or(CoreError::TransactionNotFound(transaction_id))?
+            .recurrence
+            .as_mut()
+            .and_then(|recurrence| recurrence.contract.take())
+            .is_some();
+        if had_contract {
+            ledger.touch();
+        }
+        Ok(had_contract)
+    }
+
+    /// Renewal windows of every contract on a recurrence that has not
+    /// finished, soonest notice deadline first.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn windows(ledger: &Ledger, today: NaiveDate) -> Vec<ContractWindow> {
+        let mut windows: Vec<ContractWindow> = ledger
+            .transactions
+            .iter()
+            .filter_map(|txn| {
+                let recurrence = txn.recurrence.as_ref()?;
+                if recurrence.status == RecurrenceStatus::Completed {
+                    return None;
+                }
+                let contract = recurrence.contract.as_ref()?;
+                let notice_deadline = contract.notice_deadline();
+                let days_to_notice = (notice_deadline - today).num_days();
+                let stage = if contract.end_date < today {
+                    ContractStage::Lapsed
+                } else if days_to_notice < 0 {
+                    ContractStage::Renewing
+                } else if days_to_notice <= CONTRACT_NOTICE_ALERT_DAYS {
+                    ContractStage::NoticeDue
+                } else {
+                    ContractStage::Running
+                };
+                Some(ContractWindow {
+                    series_id: recurrence.series_id,
+                    transaction_id: txn.id,
+                    provider: contract.provider.clone(),
+                    amount: txn.budgeted_amount.to_major(),
+                    interval: recurrence.interval.clone(),
+                    end_date: contract.end_date,
+                    notice_deadline,
+                    days_to_notice,
+                    stage,
+                })
+            })
+            .collect();
+        windows.sort_by_key(|window| window.notice_deadline);
+        windows
+    }
+}
@@ -0,0 +1,135 @@
+//! Review queue for transaction drafts submitted by external clients.
+
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{pending_draft::PendingDraft, transaction::Transaction, Ledger, Money};
+
+use crate::{
+    categorization_service::CategorizationService, transfer_rule_service::TransferRuleService,
+    CoreError,
+};
+
+pub struct DraftService;
+
+impl DraftService {
+    /// Queues `draft` for review and returns its identifier.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn submit(ledger: &mut Ledger, draft: PendingDraft) -> Result<Uuid, CoreError> {
+        Self::validate(ledger, &draft)?;
+        let id = draft.id;
+        ledger.pending_drafts.push(draft);
+        ledger.touch();
+        Ok(id)
+    }
+
+    /// Edits a queued draft; the result must still reference existing
+    /// accounts and categories.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn update<F>(ledger: &mut Ledger, id: Uuid, mutator: F) -> Result<(), CoreError>
+    where
+        F: FnOnce(&mut PendingDraft),
+    {
+        let mut draft = Self::draft(ledger, id)?.clone();
+        mutator(&mut draft);
+        Self::validate(ledger, &draft)?;
+        if let Some(slot) = ledger.pending_drafts.iter_mut().find(|d| d.id == id) {
+            *slot = draft;
+        }
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Drops a draft without recording anything.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn discard(ledger: &mut Ledger, id: Uuid) -> Result<PendingDraft, CoreError> {
+        let index = ledger
+            .pending_drafts
+            .iter()
+            .position(|draft| draft.id == id)
+            .ok_or_else(|| CoreError::DraftNotFound(id.to_string()))?;
+        let removed = ledger.pending_drafts.remove(index);
+        ledger.touch();
+        Ok(removed)
+    }
+
+    /// Turns a complete draft into a completed transaction, filling in a
+    /// learned category when none was given. Returns the transaction id.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn approve(ledger: &mut Ledger, id: Uuid) -> Result<Uuid, CoreError> {
+        let draft = Self::draft(ledger, id)?;
+        let (Some(date), Some(amount), Some(from), Some(to)) = (
+            draft.date,
+            draft.amount,
+            draft.from_account,
+            draft.to_account,
+        ) else {
+            return Err(CoreError::Validation(format!(
+                "draft is missing {}",
+                draft.missing_fields().join(", ")
+            )));
+        };
+        let amount = Money::from_major(amount, ledger.transfer_currency(from, to))?;
+        let mut txn = Transaction::new(from, to, draft.category_id, date, amount.clone());
+        txn.notes = match (&draft.payee, &draft.notes) {
+            (Some(payee), Some(notes)) => Some(format!("{}: {}", payee, notes)),
+            (payee, notes) => notes.clone().or_else(|| payee.clone()),
+        };
+        txn.mark_completed(date, amount);
+        CategorizationService::apply(ledger, &mut txn);
+        let txn_id = ledger.add_transaction(txn);
+        TransferRuleService::apply(ledger, txn_id)?;
+        ledger.pending_drafts.retain(|draft| draft.id != id);
+        ledger.touch();
+        Ok(txn_id)
+    }
+
+    fn draft(ledger: &Ledger, id: Uuid) -> Result<&PendingDraft, CoreError> {
+        ledger
+            .pending_drafts
+            .iter()
+            .find(|draft| draft.id == id)
+            .ok_or_else(|| CoreError::DraftNotFound(id.to_string()))
+    }
+
+    fn validate(ledger: &Ledger, draft: &PendingDraft) -> Result<(), CoreError> {
+        if let Some(amount) = draft.amount {
+            if !amount.is_finite() || amount < 0.0 {
+                return Err(CoreError::Validation(
+                    "draft amount cannot be negative".into(),
+                ));
+            }
+        }
+        for id in [draft.from_account, draft.to_account].into_iter().flatten() {
+            if ledger.account(id).is_none() {
+                return Err(CoreError::AccountNotFound(id.to_string()));
+            }
+        }
+        if let Some(id) = draft.category_id {
+            if ledger.category(id).is_none() {
+                return Err(CoreError::CategoryNotFound(id.to_string()));
+            }
+        }
+        Ok(())
+    }
+}
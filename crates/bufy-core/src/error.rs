@@ -1,8 +1,12 @@
-use std::io;
+use std::{fmt, io, path::PathBuf};
 
 use thiserror::Error;
 use uuid::Uuid;
 
+use bufy_domain::{amount::AmountOverflow, MoneyError};
+
+use crate::validation::ValidationIssue;
+
 #[derive(Debug, Error)]
 pub enum CoreError {
     #[error("Ledger not loaded")]
@@ -17,14 +21,78 @@ pub enum CoreError {
     TransactionNotFound(Uuid),
     #[error("Simulation not found: {0}")]
     SimulationNotFound(String),
+    #[error("Member not found: {0}")]
+    MemberNotFound(String),
+    #[error("View not found: {0}")]
+    ViewNotFound(String),
+    #[error("Learned rule not found: {0}")]
+    RuleNotFound(String),
+    #[error("Draft not found: {0}")]
+    DraftNotFound(String),
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
     #[error("Validation failed: {0}")]
     Validation(String),
+    /// A rejected input, with the field and reason for callers to inspect.
+    #[error("Validation failed: {0}")]
+    Invalid(ValidationIssue),
+    #[error("Numeric overflow: {0}")]
+    NumericOverflow(String),
     #[error("Storage error: {0}")]
     Storage(String),
+    #[error("Atomic write to {} failed while {stage}: {source}", path.display())]
+    AtomicWrite {
+        path: PathBuf,
+        stage: WriteStage,
+        #[source]
+        source: io::Error,
+    },
     #[error("I/O error: {0}")]
     Io(#[from] io::Error),
     #[error("Serialization error: {0}")]
     Serde(String),
 }
+
+/// Step of an atomic file replacement that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteStage {
+    CreateTemp,
+    Write,
+    SyncFile,
+    Replace,
+    SyncDirectory,
+}
+
+impl fmt::Display for WriteStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            WriteStage::CreateTemp => "creating the temporary file",
+            WriteStage::Write => "writing data",
+            WriteStage::SyncFile => "syncing the file",
+            WriteStage::Replace => "replacing the destination",
+            WriteStage::SyncDirectory => "syncing the parent directory",
+        };
+        f.write_str(label)
+    }
+}
+
+impl From<AmountOverflow> for CoreError {
+    fn from(err: AmountOverflow) -> Self {
+        CoreError::NumericOverflow(err.to_string())
+    }
+}
+
+impl From<MoneyError> for CoreError {
+    fn from(err: MoneyError) -> Self {
+        match err {
+            MoneyError::Overflow(err) => err.into(),
+            MoneyError::CurrencyMismatch { .. } => CoreError::Validation(err.to_string()),
+        }
+    }
+}
+
+impl From<ValidationIssue> for CoreError {
+    fn from(issue: ValidationIssue) -> Self {
+        CoreError::Invalid(issue)
+    }
+}
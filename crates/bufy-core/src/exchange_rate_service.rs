@@ -0,0 +1,155 @@
+//! Exchange rates fixed on the ledger for a date range. Conversions use an
+//! override whenever one covers the date being valued.
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{exchange_rate::ExchangeRateOverride, iso4217::CurrencyData, Ledger};
+
+use crate::CoreError;
+
+pub struct ExchangeRateService;
+
+impl ExchangeRateService {
+    /// Stores `rate` after checking it against the overrides already on the
+    /// ledger. Two overrides linking the same currencies, in either
+    /// direction, may not share a day.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn add_override(
+        ledger: &mut Ledger,
+        rate: ExchangeRateOverride,
+    ) -> Result<Uuid, CoreError> {
+        for code in [&rate.from, &rate.to] {
+            if CurrencyData::lookup(code.as_str()).is_none() {
+                return Err(CoreError::Validation(format!(
+                    "unknown currency code `{}`",
+                    code.as_str()
+                )));
+            }
+        }
+        if rate.from == rate.to {
+            return Err(CoreError::Validation(
+                "an exchange rate needs two different currencies".into(),
+            ));
+        }
+        if !rate.rate.is_finite() || rate.rate <= 0.0 {
+            return Err(CoreError::Validation(
+                "exchange rate must be greater than 0".into(),
+            ));
+        }
+        if rate
+            .valid_until
+            .is_some_and(|until| until < rate.valid_from)
+        {
+            return Err(CoreError::Validation(
+                "the rate must end on or after the day it starts".into(),
+            ));
+        }
+        if let Some(existing) = ledger
+            .exchange_rate_overrides
+            .iter()
+            .find(|existing| existing.overlaps(&rate))
+        {
+            return Err(CoreError::Validation(format!(
+                "overlaps the {} → {} rate of {} valid {}",
+                existing.from.as_str(),
+                existing.to.as_str(),
+                existing.rate,
+                existing.validity_label()
+            )));
+        }
+
+        let id = rate.id;
+        ledger.exchange_rate_overrides.push(rate);
+        ledger.exchange_rate_overrides.sort_by(|a, b| {
+            (a.from.as_str(), a.to.as_str(), a.valid_from).cmp(&(
+                b.from.as_str(),
+                b.to.as_str(),
+                b.valid_from,
+            ))
+        });
+        ledger.touch();
+        Ok(id)
+    }
+
+    /// Removes an override. Amounts it converted are revalued on the next
+    /// report.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn remove_override(
+        ledger: &mut Ledger,
+        id: Uuid,
+    ) -> Result<ExchangeRateOverride, CoreError> {
+        let index = ledger
+            .exchange_rate_overrides
+            .iter()
+            .position(|rate| rate.id == id)
+            .ok_or_else(|| {
+                CoreError::Validation(format!("no exchange rate override with id {}", id))
+            })?;
+        let removed = ledger.exchange_rate_overrides.remove(index);
+        ledger.touch();
+        Ok(removed)
+    }
+
+    /// Replaces the open-ended override between the same currencies with
+    /// `rate` from its first day on. The current one now ends the day
+    /// before, so amounts it converted keep their rate.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn refresh_override(
+        ledger: &mut Ledger,
+        rate: ExchangeRateOverride,
+    ) -> Result<Uuid, CoreError> {
+        let index = ledger
+            .exchange_rate_overrides
+            .iter()
+            .position(|current| {
+                current.links(&rate.from, &rate.to)
+                    && current.valid_until.is_none()
+                    && current.valid_from < rate.valid_from
+            })
+            .ok_or_else(|| {
+                CoreError::Validation(format!(
+                    "no open-ended {} → {} rate starting before {} to refresh",
+                    rate.from.as_str(),
+                    rate.to.as_str(),
+                    rate.valid_from
+                ))
+            })?;
+        ledger.exchange_rate_overrides[index].valid_until = rate.valid_from.pred_opt();
+        Self::add_override(ledger, rate).inspect_err(|_| {
+            ledger.exchange_rate_overrides[index].valid_until = None;
+        })
+    }
+
+    /// Open-ended overrides in force on `on` that took effect more than
+    /// `max_age_days` before it. A closed range is a rate agreed for that
+    /// period; an open one stands in for the current rate and goes stale.
+    pub fn stale_overrides(
+        ledger: &Ledger,
+        on: NaiveDate,
+        max_age_days: u32,
+    ) -> Vec<&ExchangeRateOverride> {
+        ledger
+            .exchange_rate_overrides
+            .iter()
+            .filter(|rate| rate.valid_until.is_none() && rate.covers(on))
+            .filter(|rate| (on - rate.valid_from).num_days() > i64::from(max_age_days))
+            .collect()
+    }
+}
@@ -0,0 +1,836 @@
+//! Spreadsheet-friendly exports of transaction sets.
+//!
+//! Output is locale-independent: dates are ISO 8601 (`YYYY-MM-DD`) and
+//! amounts use `.` as the decimal separator with no grouping, rounded to the
+//! transaction currency's minor units.
+//! Text a spreadsheet would evaluate as a formula is prefixed with `'`.
+//!
+//! Reporting snapshots are a fixed, denormalized layout meant for analysis
+//! tools: one row per transaction with account, category, and member names
+//! resolved and amounts converted to the base currency. They are written as
+//! CSV or Parquet.
+//!
+//! Redacted exports drop the fields that place a purchase: merchant,
+//! location, and free-text notes.
+//!
+//! Account statements are meant to be shared with people, so their text and
+//! HTML forms use the caller's locale formatting; only their CSV form follows
+//! the locale-independent rules above.
+//!
+//! Budget summaries are exported as JSON for other programs, or as a
+//! per-category budget report in CSV. Both are in the base currency.
+//!
+//! Upcoming bills are exported as an iCalendar feed; see
+//! [`crate::calendar_export`].
+
+use std::{io::Write, sync::Arc};
+
+use chrono::NaiveDate;
+use parquet::{
+    basic::Compression,
+    data_type::{BoolType, ByteArray, ByteArrayType, DataType, DoubleType, Int32Type},
+    file::{properties::WriterProperties, writer::SerializedFileWriter},
+    schema::parser::parse_message_type,
+};
+
+use serde::Serialize;
+use tracing::instrument;
+
+use bufy_domain::{
+    currency::minor_units_for,
+    ledger::{BudgetSummary, CategoryBudgetSummary, DateWindow},
+    transaction::Transaction,
+    view::ViewColumn,
+    Ledger, Money,
+};
+
+use crate::{
+    account_service::AccountStatement,
+    calendar_export::{calendar_events, to_ics, CalendarEvent},
+    simulation_service::SimulationService,
+    Clock, CoreError, CurrencyFormatter, DateFormatter,
+};
+
+pub struct ExportService;
+
+impl ExportService {
+    /// Copies of `transactions` with merchant, location, and notes removed.
+    #[instrument(level = "debug", skip_all)]
+    pub fn redacted(transactions: &[&Transaction]) -> Vec<Transaction> {
+        transactions
+            .iter()
+            .map(|txn| Transaction {
+                merchant: None,
+                location: None,
+                notes: None,
+                ..(*txn).clone()
+            })
+            .collect()
+    }
+
+    /// Renders `transactions` as CSV with a header row of column keys.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn transactions_csv(
+        ledger: &Ledger,
+        transactions: &[&Transaction],
+        columns: &[ViewColumn],
+    ) -> String {
+        let mut out = String::new();
+        let header: Vec<_> = columns.iter().map(|column| column.key()).collect();
+        push_record(&mut out, header.iter().map(|key| key.to_string()));
+        for txn in transactions {
+            push_record(
+                &mut out,
+                columns.iter().map(|column| csv_value(ledger, txn, *column)),
+            );
+        }
+        out
+    }
+
+    /// Builds one reporting row per transaction. Base-currency amounts use
+    /// the ledger's valuation policy relative to `report_date` and are left
+    /// empty when no conversion is available.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            report_date = %report_date
+        )
+    )]
+    pub fn snapshot_rows(
+        ledger: &Ledger,
+        transactions: &[&Transaction],
+        report_date: NaiveDate,
+    ) -> Vec<SnapshotRow> {
+        let ctx = ledger.conversion_context(report_date);
+        let account = |id| ledger.account(id);
+        transactions
+            .iter()
+            .map(|txn| {
+                let currency = ledger.transaction_currency(txn);
+                let to_base = |amount: f64, date: NaiveDate| {
+                    ledger
+                        .convert_amount(amount, &currency, date, &ctx)
+                        .ok()
+                        .map(|converted| converted.amount)
+                };
+                let from = account(txn.from_account);
+                let to = account(txn.to_account);
+                SnapshotRow {
+                    transaction_id: txn.id.to_string(),
+                    scheduled_date: txn.scheduled_date,
+                    actual_date: txn.actual_date,
+                    booking_date: txn.booking_date,
+                    value_date: txn.value_date,
+                    reporting_date: txn.reporting_date(ledger.summary_date_basis),
+                    status: txn.status.to_string().to_ascii_lowercase(),
+                    from_account: from.map(|a| a.name.clone()).unwrap_or_default(),
+                    from_kind: from.map(|a| a.kind.to_string()).unwrap_or_default(),
+                    to_account: to.map(|a| a.name.clone()).unwrap_or_default(),
+                    to_kind: to.map(|a| a.kind.to_string()).unwrap_or_default(),
+                    category: txn
+                        .category_id
+                        .and_then(|id| ledger.category(id))
+                        .map(|category| category.name.clone()),
+                    member: txn
+                        .member_id
+                        .and_then(|id| ledger.member(id))
+                        .map(|member| member.name.clone()),
+                    recurring: txn.recurrence.is_some() || txn.recurrence_series_id.is_some(),
+                    currency: currency.as_str().to_string(),
+                    budgeted: txn.budgeted_amount.to_major(),
+                    actual: txn.actual_amount.as_ref().map(Money::to_major),
+                    base_currency: ledger.base_currency().as_str().to_string(),
+                    budgeted_base: to_base(txn.budgeted_amount.to_major(), txn.scheduled_date),
+                    actual_base: txn.actual_amount.as_ref().and_then(|amount| {
+                        to_base(
+                            amount.to_major(),
+                            txn.actual_date.unwrap_or(txn.scheduled_date),
+                        )
+                    }),
+                    notes: txn.notes.clone(),
+                    merchant: txn.merchant.clone(),
+                    latitude: txn.location.as_ref().map(|location| location.latitude),
+                    longitude: txn.location.as_ref().map(|location| location.longitude),
+                }
+            })
+            .collect()
+    }
+
+    /// Renders snapshot rows as CSV with [`SNAPSHOT_COLUMNS`] as the header.
+    /// Amounts keep full precision so totals match the ledger.
+    #[instrument(level = "debug", skip_all)]
+    pub fn snapshot_csv(rows: &[SnapshotRow]) -> String {
+        let mut out = String::new();
+        push_record(
+            &mut out,
+            SNAPSHOT_COLUMNS.iter().map(|(name, _)| name.to_string()),
+        );
+        for row in rows {
+            push_record(
+                &mut out,
+                row.values().into_iter().map(|value| match value {
+                    SnapshotValue::Text(text) => text.unwrap_or_default(),
+                    SnapshotValue::Date(date) => iso_date(date),
+                    SnapshotValue::Number(number) => {
+                        number.map(|n| n.to_string()).unwrap_or_default()
+                    }
+                    SnapshotValue::Flag(flag) => flag.to_string(),
+                }),
+            );
+        }
+        out
+    }
+
+    /// Writes snapshot rows as a single-row-group Parquet file. Dates use the
+    /// `DATE` logical type, amounts are doubles, and empty values are nulls.
+    #[instrument(level = "debug", skip_all, err(level = "debug"))]
+    pub fn snapshot_parquet<W: Write + Send>(
+        rows: &[SnapshotRow],
+        sink: W,
+    ) -> Result<(), CoreError> {
+        let fields: Vec<String> = SNAPSHOT_COLUMNS
+            .iter()
+            .map(|(name, kind)| match kind {
+                SnapshotKind::Text => format!("OPTIONAL BYTE_ARRAY {} (UTF8);", name),
+                SnapshotKind::Date => format!("OPTIONAL INT32 {} (DATE);", name),
+                SnapshotKind::Number => format!("OPTIONAL DOUBLE {};", name),
+                SnapshotKind::Flag => format!("REQUIRED BOOLEAN {};", name),
+            })
+            .collect();
+        let schema =
+            parse_message_type(&format!("message bufy_snapshot {{ {} }}", fields.join(" ")))
+                .map_err(parquet_error)?;
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::UNCOMPRESSED)
+            .build();
+        let mut writer = SerializedFileWriter::new(sink, Arc::new(schema), Arc::new(properties))
+            .map_err(parquet_error)?;
+        let mut group = writer.next_row_group().map_err(parquet_error)?;
+        let table: Vec<_> = rows.iter().map(SnapshotRow::values).collect();
+        for index in 0..SNAPSHOT_COLUMNS.len() {
+            let Some(mut column) = group.next_column().map_err(parquet_error)? else {
+                break;
+            };
+            let cells = table.iter().map(|values| &values[index]);
+            match SNAPSHOT_COLUMNS[index].1 {
+                SnapshotKind::Text => write_optional::<ByteArrayType>(
+                    column.typed(),
+                    cells.map(|cell| match cell {
+                        SnapshotValue::Text(text) => text.as_deref().map(ByteArray::from),
+                        _ => None,
+                    }),
+                ),
+                SnapshotKind::Date => write_optional::<Int32Type>(
+                    column.typed(),
+                    cells.map(|cell| match cell {
+                        SnapshotValue::Date(date) => date.map(days_since_epoch),
+                        _ => None,
+                    }),
+                ),
+                SnapshotKind::Number => write_optional::<DoubleType>(
+                    column.typed(),
+                    cells.map(|cell| match cell {
+                        SnapshotValue::Number(number) => *number,
+                        _ => None,
+                    }),
+                ),
+                SnapshotKind::Flag => {
+                    let flags: Vec<bool> = cells
+                        .map(|cell| matches!(cell, SnapshotValue::Flag(true)))
+                        .collect();
+                    column
+                        .typed::<BoolType>()
+                        .write_batch(&flags, None, None)
+                        .map(|_| ())
+                }
+            }
+            .map_err(parquet_error)?;
+            column.close().map_err(parquet_error)?;
+        }
+        group.close().map_err(parquet_error)?;
+        writer.close().map_err(parquet_error)?;
+        Ok(())
+    }
+}
+
+impl ExportService {
+    /// Renders an account statement as CSV. The opening and closing
+    /// balances are the first and last records, with an empty amount.
+    #[instrument(level = "debug", skip_all)]
+    pub fn statement_csv(statement: &AccountStatement) -> String {
+        let precision = minor_units_for(&statement.currency) as usize;
+        let amount = |value: f64| format!("{:.*}", precision, value);
+        let mut out = String::new();
+        push_record(
+            &mut out,
+            [
+                "date",
+                "description",
+                "category",
+                "notes",
+                "amount",
+                "balance",
+            ]
+            .into_iter()
+            .map(String::from),
+        );
+        let (first, last) = statement_dates(statement);
+        push_record(
+            &mut out,
+            [
+                iso_date(Some(first)),
+                "Opening balance".into(),
+                String::new(),
+                String::new(),
+                String::new(),
+                amount(statement.opening_balance),
+            ]
+            .into_iter(),
+        );
+        for line in &statement.lines {
+            push_record(
+                &mut out,
+                [
+                    iso_date(Some(line.date)),
+                    line.counterparty.clone(),
+                    line.category.clone().unwrap_or_default(),
+                    line.notes.clone().unwrap_or_default(),
+                    amount(line.amount),
+                    amount(line.balance),
+                ]
+                .into_iter(),
+            );
+        }
+        push_record(
+            &mut out,
+            [
+                iso_date(Some(last)),
+                "Closing balance".into(),
+                String::new(),
+                String::new(),
+                String::new(),
+                amount(statement.closing_balance),
+            ]
+            .into_iter(),
+        );
+        out
+    }
+
+    /// Renders an account statement as plain text with aligned columns,
+    /// using `formatter` for dates and amounts.
+    #[instrument(level = "debug", skip_all)]
+    pub fn statement_text<F>(statement: &AccountStatement, formatter: &F) -> String
+    where
+        F: CurrencyFormatter + DateFormatter,
+    {
+        let rows = statement_rows(statement, formatter);
+        let widths: Vec<usize> = (0..STATEMENT_HEADERS.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .chain([STATEMENT_HEADERS[column].len()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        let render = |cells: &[String]| {
+            let line: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (cell, width))| {
+                    if column >= 3 {
+                        format!("{:>width$}", cell, width = width)
+                    } else {
+                        format!("{:<width$}", cell, width = width)
+                    }
+                })
+                .collect();
+            line.join("  ").trim_end().to_string()
+        };
+        let mut out = format!("{}\n\n", statement_title(statement, formatter));
+        let headers: Vec<String> = STATEMENT_HEADERS.iter().map(|h| h.to_string()).collect();
+        out.push_str(&render(&headers));
+        out.push('\n');
+        for row in &rows {
+            out.push_str(&render(row));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Renders an account statement as a standalone HTML page, using
+    /// `formatter` for dates and amounts.
+    #[instrument(level = "debug", skip_all)]
+    pub fn statement_html<F>(statement: &AccountStatement, formatter: &F) -> String
+    where
+        F: CurrencyFormatter + DateFormatter,
+    {
+        let title = html_escape(&statement_title(statement, formatter));
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>table {{ border-collapse: collapse; }} th, td {{ padding: 4px 8px; }} \
+             td.num {{ text-align: right; }}</style>\n</head>\n<body>\n<h1>{title}</h1>\n<table>\n<tr>"
+        );
+        for header in STATEMENT_HEADERS {
+            out.push_str(&format!("<th>{}</th>", header));
+        }
+        out.push_str("</tr>\n");
+        for row in statement_rows(statement, formatter) {
+            out.push_str("<tr>");
+            for (column, cell) in row.iter().enumerate() {
+                let class = if column >= 3 { " class=\"num\"" } else { "" };
+                out.push_str(&format!("<td{}>{}</td>", class, html_escape(cell)));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n</body>\n</html>\n");
+        out
+    }
+}
+
+impl ExportService {
+    /// Calendar entries for planned transactions and projected recurrences
+    /// due in `window`, optionally with a simulation applied.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            reference = %reference
+        ),
+        err(level = "debug")
+    )]
+    pub fn forecast_events(
+        ledger: &Ledger,
+        window: DateWindow,
+        simulation: Option<&str>,
+        reference: NaiveDate,
+    ) -> Result<Vec<CalendarEvent>, CoreError> {
+        let transactions = match simulation {
+            Some(name) => SimulationService::run(ledger, name)?.transactions,
+            None => ledger.transactions.clone(),
+        };
+        Ok(calendar_events(ledger, &transactions, window, reference))
+    }
+
+    /// Renders `events` as an iCalendar feed named after the ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn events_ics(ledger: &Ledger, events: &[CalendarEvent], clock: &dyn Clock) -> String {
+        to_ics(&ledger.name, events, clock.now())
+    }
+
+    /// Renders a budget summary as pretty-printed JSON, together with the
+    /// category budget statuses for the same window.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn summary_json(
+        ledger: &Ledger,
+        summary: &BudgetSummary,
+        category_budgets: &[CategoryBudgetSummary],
+    ) -> Result<String, CoreError> {
+        let export = SummaryExport {
+            ledger: &ledger.name,
+            base_currency: ledger.base_currency().as_str(),
+            summary,
+            category_budgets,
+        };
+        serde_json::to_string_pretty(&export).map_err(|err| CoreError::Serde(err.to_string()))
+    }
+
+    /// Renders a budget summary as CSV: one record per category with its
+    /// planned and actual amounts, its budget limit when it has one, and the
+    /// budget's note, followed by a `Total` record.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn budget_report_csv(
+        ledger: &Ledger,
+        summary: &BudgetSummary,
+        category_budgets: &[CategoryBudgetSummary],
+    ) -> String {
+        let precision = minor_units_for(ledger.base_currency().as_str()) as usize;
+        let amount = |value: f64| format!("{:.*}", precision, value);
+        let (from, to) = window_dates(summary.window);
+        let mut out = String::new();
+        push_record(
+            &mut out,
+            [
+                "from",
+                "to",
+                "category",
+                "budgeted",
+                "actual",
+                "remaining",
+                "status",
+                "budget_limit",
+                "budget_remaining",
+                "note",
+            ]
+            .into_iter()
+            .map(String::from),
+        );
+        for row in &summary.per_category {
+            let limit = row.category_id.and_then(|id| {
+                category_budgets
+                    .iter()
+                    .find(|budget| budget.category_id == id)
+            });
+            push_record(
+                &mut out,
+                [
+                    iso_date(Some(from)),
+                    iso_date(Some(to)),
+                    row.name.clone(),
+                    amount(row.totals.budgeted),
+                    amount(row.totals.real),
+                    amount(row.totals.remaining),
+                    status_key(&row.totals.status.to_string()),
+                    limit
+                        .map(|budget| amount(budget.budget_amount))
+                        .unwrap_or_default(),
+                    limit
+                        .map(|budget| amount(budget.remaining_amount))
+                        .unwrap_or_default(),
+                    row.note.clone().unwrap_or_default(),
+                ]
+                .into_iter(),
+            );
+        }
+        let totals = &summary.totals;
+        push_record(
+            &mut out,
+            [
+                iso_date(Some(from)),
+                iso_date(Some(to)),
+                "Total".into(),
+                amount(totals.budgeted),
+                amount(totals.real),
+                amount(totals.remaining),
+                status_key(&totals.status.to_string()),
+                String::new(),
+                String::new(),
+                String::new(),
+            ]
+            .into_iter(),
+        );
+        out
+    }
+}
+
+#[derive(Serialize)]
+struct SummaryExport<'a> {
+    ledger: &'a str,
+    base_currency: &'a str,
+    #[serde(flatten)]
+    summary: &'a BudgetSummary,
+    category_budgets: &'a [CategoryBudgetSummary],
+}
+
+/// `On Track` becomes `on_track`.
+fn status_key(label: &str) -> String {
+    label.to_ascii_lowercase().replace(' ', "_")
+}
+
+/// First and last day of a window whose end is exclusive.
+fn window_dates(window: DateWindow) -> (NaiveDate, NaiveDate) {
+    (window.start, window.end.pred_opt().unwrap_or(window.end))
+}
+
+const STATEMENT_HEADERS: [&str; 5] = ["Date", "Description", "Category", "Amount", "Balance"];
+
+/// First and last day covered by the statement (the window end is exclusive).
+fn statement_dates(statement: &AccountStatement) -> (NaiveDate, NaiveDate) {
+    window_dates(statement.window)
+}
+
+fn statement_title<F: DateFormatter>(statement: &AccountStatement, formatter: &F) -> String {
+    let (first, last) = statement_dates(statement);
+    format!(
+        "Statement for {}: {} to {}",
+        statement.account_name,
+        formatter.format_date(first),
+        formatter.format_date(last)
+    )
+}
+
+/// Display rows for text and HTML statements, bracketed by the opening and
+/// closing balances.
+fn statement_rows<F>(statement: &AccountStatement, formatter: &F) -> Vec<Vec<String>>
+where
+    F: CurrencyFormatter + DateFormatter,
+{
+    let amount = |value: f64| formatter.format_amount(value, &statement.currency);
+    let (first, last) = statement_dates(statement);
+    let mut rows = vec![vec![
+        formatter.format_date(first),
+        "Opening balance".into(),
+        String::new(),
+        String::new(),
+        amount(statement.opening_balance),
+    ]];
+    rows.extend(statement.lines.iter().map(|line| {
+        let description = match &line.notes {
+            Some(notes) => format!("{} ({})", line.counterparty, notes),
+            None => line.counterparty.clone(),
+        };
+        vec![
+            formatter.format_date(line.date),
+            description,
+            line.category.clone().unwrap_or_default(),
+            amount(line.amount),
+            amount(line.balance),
+        ]
+    }));
+    rows.push(vec![
+        formatter.format_date(last),
+        "Closing balance".into(),
+        String::new(),
+        String::new(),
+        amount(statement.closing_balance),
+    ]);
+    rows
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Column names and types of a reporting snapshot, in output order.
+pub const SNAPSHOT_COLUMNS: [(&str, SnapshotKind); 25] = [
+    ("transaction_id", SnapshotKind::Text),
+    ("scheduled_date", SnapshotKind::Date),
+    ("actual_date", SnapshotKind::Date),
+    ("booking_date", SnapshotKind::Date),
+    ("value_date", SnapshotKind::Date),
+    ("reporting_date", SnapshotKind::Date),
+    ("reporting_month", SnapshotKind::Text),
+    ("status", SnapshotKind::Text),
+    ("from_account", SnapshotKind::Text),
+    ("from_kind", SnapshotKind::Text),
+    ("to_account", SnapshotKind::Text),
+    ("to_kind", SnapshotKind::Text),
+    ("category", SnapshotKind::Text),
+    ("member", SnapshotKind::Text),
+    ("recurring", SnapshotKind::Flag),
+    ("currency", SnapshotKind::Text),
+    ("budgeted", SnapshotKind::Number),
+    ("actual", SnapshotKind::Number),
+    ("base_currency", SnapshotKind::Text),
+    ("budgeted_base", SnapshotKind::Number),
+    ("actual_base", SnapshotKind::Number),
+    ("notes", SnapshotKind::Text),
+    ("merchant", SnapshotKind::Text),
+    ("latitude", SnapshotKind::Number),
+    ("longitude", SnapshotKind::Number),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotKind {
+    Text,
+    Date,
+    Number,
+    Flag,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotValue {
+    Text(Option<String>),
+    Date(Option<NaiveDate>),
+    Number(Option<f64>),
+    Flag(bool),
+}
+
+/// One denormalized transaction in a reporting snapshot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotRow {
+    pub transaction_id: String,
+    pub scheduled_date: NaiveDate,
+    pub actual_date: Option<NaiveDate>,
+    pub booking_date: Option<NaiveDate>,
+    pub value_date: Option<NaiveDate>,
+    /// Date that places the row in summaries under the ledger's date basis.
+    pub reporting_date: Option<NaiveDate>,
+    pub status: String,
+    pub from_account: String,
+    pub from_kind: String,
+    pub to_account: String,
+    pub to_kind: String,
+    pub category: Option<String>,
+    pub member: Option<String>,
+    pub recurring: bool,
+    pub currency: String,
+    pub budgeted: f64,
+    pub actual: Option<f64>,
+    pub base_currency: String,
+    pub budgeted_base: Option<f64>,
+    pub actual_base: Option<f64>,
+    pub notes: Option<String>,
+    pub merchant: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+impl SnapshotRow {
+    /// Values in [`SNAPSHOT_COLUMNS`] order.
+    pub fn values(&self) -> Vec<SnapshotValue> {
+        use SnapshotValue::{Date, Flag, Number, Text};
+        vec![
+            Text(Some(self.transaction_id.clone())),
+            Date(Some(self.scheduled_date)),
+            Date(self.actual_date),
+            Date(self.booking_date),
+            Date(self.value_date),
+            Date(self.reporting_date),
+            Text(
+                self.reporting_date
+                    .map(|date| date.format("%Y-%m").to_string()),
+            ),
+            Text(Some(self.status.clone())),
+            Text(Some(self.from_account.clone())),
+            Text(Some(self.from_kind.clone())),
+            Text(Some(self.to_account.clone())),
+            Text(Some(self.to_kind.clone())),
+            Text(self.category.clone()),
+            Text(self.member.clone()),
+            Flag(self.recurring),
+            Text(Some(self.currency.clone())),
+            Number(Some(self.budgeted)),
+            Number(self.actual),
+            Text(Some(self.base_currency.clone())),
+            Number(self.budgeted_base),
+            Number(self.actual_base),
+            Text(self.notes.clone()),
+            Text(self.merchant.clone()),
+            Number(self.latitude),
+            Number(self.longitude),
+        ]
+    }
+}
+
+fn write_optional<T: DataType>(
+    writer: &mut parquet::column::writer::ColumnWriterImpl<'_, T>,
+    cells: impl Iterator<Item = Option<T::T>>,
+) -> parquet::errors::Result<()> {
+    let mut values = Vec::new();
+    let mut levels = Vec::new();
+    for cell in cells {
+        levels.push(i16::from(cell.is_some()));
+        values.extend(cell);
+    }
+    writer.write_batch(&values, Some(&levels), None).map(|_| ())
+}
+
+/// Parquet `DATE` values count days from 1970-01-01.
+fn days_since_epoch(date: NaiveDate) -> i32 {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("valid epoch");
+    date.signed_duration_since(epoch).num_days() as i32
+}
+
+fn parquet_error(err: parquet::errors::ParquetError) -> CoreError {
+    CoreError::Storage(format!("parquet export failed: {}", err))
+}
+
+fn csv_value(ledger: &Ledger, txn: &Transaction, column: ViewColumn) -> String {
+    let currency = ledger.transaction_currency(txn);
+    let precision = minor_units_for(currency.as_str()) as usize;
+    let account_name = |id| {
+        ledger
+            .account(id)
+            .map(|account| account.name.clone())
+            .unwrap_or_default()
+    };
+    match column {
+        ViewColumn::Reference => txn
+            .short_ref()
+            .map(|code| code.to_string())
+            .unwrap_or_default(),
+        ViewColumn::Date => txn.scheduled_date.format("%Y-%m-%d").to_string(),
+        ViewColumn::ActualDate => iso_date(txn.actual_date),
+        ViewColumn::BookingDate => iso_date(txn.booking_date),
+        ViewColumn::ValueDate => iso_date(txn.value_date),
+        ViewColumn::From => account_name(txn.from_account),
+        ViewColumn::To => account_name(txn.to_account),
+        ViewColumn::Category => txn
+            .category_id
+            .and_then(|id| ledger.category(id))
+            .map(|category| category.name.clone())
+            .unwrap_or_default(),
+        ViewColumn::Member => txn
+            .member_id
+            .and_then(|id| ledger.member(id))
+            .map(|member| member.name.clone())
+            .unwrap_or_default(),
+        ViewColumn::Budgeted => format!("{:.*}", precision, txn.budgeted_amount.to_major()),
+        ViewColumn::Actual => txn
+            .actual_amount
+            .as_ref()
+            .map(|amount| format!("{:.*}", precision, amount.to_major()))
+            .unwrap_or_default(),
+        ViewColumn::Currency => currency.as_str().to_string(),
+        ViewColumn::Status => txn.status.to_string().to_ascii_lowercase(),
+        ViewColumn::Notes => txn.notes.clone().unwrap_or_default(),
+        ViewColumn::Merchant => txn.merchant.clone().unwrap_or_default(),
+        ViewColumn::Location => txn
+            .location
+            .as_ref()
+            .map(|location| format!("{},{}", location.latitude, location.longitude))
+            .unwrap_or_default(),
+        ViewColumn::Tags => txn.tags.join(" "),
+    }
+}
+
+fn iso_date(date: Option<NaiveDate>) -> String {
+    date.map(|date| date.format("%Y-%m-%d").to_string())
+        .unwrap_or_default()
+}
+
+/// Appends one CSV record, quoting fields per RFC 4180 where needed.
+fn push_record(out: &mut String, fields: impl Iterator<Item = String>) {
+    for (idx, field) in fields.enumerate() {
+        if idx > 0 {
+            out.push(',');
+        }
+        let field = defuse_formula(field);
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(&field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+/// Prefixes `'` to text a spreadsheet would evaluate as a formula, i.e.
+/// text starting with `=`, `+`, `-`, `@`, a tab, or a carriage return.
+/// Numbers such as `-45.50` are left as they are.
+fn defuse_formula(field: String) -> String {
+    if field.starts_with(['=', '+', '-', '@', '\t', '\r']) && field.parse::<f64>().is_err() {
+        format!("'{}", field)
+    } else {
+        field
+    }
+}
@@ -1,33 +1,333 @@
 //! Forecasting helpers that combine recurring schedules with ledger data.
+//!
+//! Projected balances count completed transactions on the day they moved
+//! and planned ones, including projected recurrences, on their scheduled
+//! day. Missed transactions are left out.
 
 use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
 
 use bufy_domain::{
     ledger::{CategoryBudgetSummaryKind, DateWindow},
-    recurring::forecast_for_window,
-    ForecastReport, Ledger,
+    recurring::{forecast_for_window, forecast_with_snapshots, ForecastResult, RecurrenceSnapshot},
+    transaction::TransactionStatus,
+    Account, ForecastReport, Ledger, Money, Transaction,
 };
 
-use crate::{budget_service::BudgetService, simulation_service::SimulationService, CoreError};
+use crate::{
+    budget_service::BudgetService,
+    goal_service::GoalService,
+    simulation_service::SimulationService,
+    stats::{completed_amount, is_asset_account, RunningBalance},
+    CoreError,
+};
+
+/// One budget window of a multi-period forecast. Amounts are the combined
+/// bank, cash, and savings accounts; transfers between them are left out.
+#[derive(Debug, Clone)]
+pub struct PeriodForecast {
+    pub window: DateWindow,
+    /// Balance when the window starts.
+    pub opening_balance: f64,
+    pub inflow: f64,
+    pub outflow: f64,
+    /// Projected balance when the window ends.
+    pub closing_balance: f64,
+    /// Recurring occurrences projected into the window.
+    pub projected: usize,
+}
+
+impl PeriodForecast {
+    pub fn net(&self) -> f64 {
+        self.inflow - self.outflow
+    }
+}
+
+/// Projected balance of one account through a forecast window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountProjection {
+    pub account_id: Uuid,
+    pub name: String,
+    pub currency: String,
+    /// Balance when the window starts.
+    pub opening_balance: f64,
+    /// Balance when the window ends.
+    pub closing_balance: f64,
+    /// Balance at the end of the window's first day and of each day money
+    /// moves, earliest first.
+    pub points: Vec<(NaiveDate, f64)>,
+}
+
+impl AccountProjection {
+    /// Lowest projected balance in the window and the first day it is
+    /// reached.
+    pub fn lowest(&self) -> (NaiveDate, f64) {
+        self.points
+            .iter()
+            .copied()
+            .reduce(|lowest, point| if point.1 < lowest.1 { point } else { lowest })
+            .unwrap_or_default()
+    }
+
+    /// First day the balance is projected to be below `minimum`.
+    pub fn first_below(&self, minimum: f64) -> Option<(NaiveDate, f64)> {
+        self.points
+            .iter()
+            .copied()
+            .find(|(_, balance)| *balance < minimum)
+    }
+}
+
+/// Projected balances of every bank, cash, and savings account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceForecast {
+    pub window: DateWindow,
+    pub accounts: Vec<AccountProjection>,
+}
 
 pub struct ForecastService;
 
 impl ForecastService {
     /// Produces a forecast report for the given window and optional simulation overlay.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            reference = %reference
+        ),
+        err(level = "debug")
+    )]
     pub fn window_report(
         ledger: &Ledger,
         window: DateWindow,
         reference: NaiveDate,
         simulation: Option<&str>,
     ) -> Result<ForecastReport, CoreError> {
-        let scope = window.scope(reference);
         let base_transactions = if let Some(name) = simulation {
             SimulationService::run(ledger, name)?.transactions
         } else {
             ledger.transactions.clone()
         };
-        let forecast = forecast_for_window(window, reference, &base_transactions);
-        let mut overlay = base_transactions.clone();
+        let forecast = forecast_for_window(
+            window,
+            reference,
+            &base_transactions,
+            ledger.business_calendar(),
+        );
+        forecast.checked_totals()?;
+        Ok(Self::report(ledger, forecast, base_transactions))
+    }
+
+    /// Breaks the projection into `count` consecutive budget windows,
+    /// starting with the one containing `reference`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            reference = %reference
+        ),
+        err(level = "debug")
+    )]
+    pub fn periods(
+        ledger: &Ledger,
+        reference: NaiveDate,
+        count: usize,
+        simulation: Option<&str>,
+    ) -> Result<Vec<PeriodForecast>, CoreError> {
+        if count == 0 {
+            return Err(CoreError::Validation("forecast at least one period".into()));
+        }
+        let first = ledger.budget_window_containing(reference);
+        let windows: Vec<DateWindow> = (0..count)
+            .map(|step| first.shift(&ledger.budget_period.0, step as i32))
+            .collect();
+        let (transactions, projected) =
+            Self::with_projections(ledger, &windows, reference, simulation)?;
+
+        let accounts: Vec<&Account> = ledger
+            .accounts
+            .iter()
+            .filter(|account| is_asset_account(account))
+            .collect();
+        let running = RunningBalance::new(
+            ledger.base_currency(),
+            accounts
+                .iter()
+                .filter_map(|account| account.opening_balance.as_ref()),
+            &transactions,
+            |id| accounts.iter().any(|account| account.id == id),
+            projected_movement,
+            first.start,
+        );
+        let mut balance = running.opening;
+
+        Ok(windows
+            .into_iter()
+            .zip(projected)
+            .map(|(window, projected)| {
+                let opening_balance = balance;
+                let (mut inflow, mut outflow) = (0i64, 0i64);
+                for movement in running
+                    .movements
+                    .iter()
+                    .filter(|movement| window.contains(movement.date))
+                {
+                    if movement.amount >= 0 {
+                        inflow = inflow.saturating_add(movement.amount);
+                    } else {
+                        outflow = outflow.saturating_sub(movement.amount);
+                    }
+                }
+                balance = balance.saturating_add(inflow).saturating_sub(outflow);
+                PeriodForecast {
+                    window,
+                    opening_balance: running.major(opening_balance),
+                    inflow: running.major(inflow),
+                    outflow: running.major(outflow),
+                    closing_balance: running.major(balance),
+                    projected,
+                }
+            })
+            .collect())
+    }
+
+    /// Projected day-by-day balance of each bank, cash, and savings account
+    /// through `window`, in the account's currency.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            reference = %reference
+        ),
+        err(level = "debug")
+    )]
+    pub fn balances(
+        ledger: &Ledger,
+        window: DateWindow,
+        reference: NaiveDate,
+        simulation: Option<&str>,
+    ) -> Result<BalanceForecast, CoreError> {
+        let (transactions, _) = Self::with_projections(ledger, &[window], reference, simulation)?;
+        let accounts = ledger
+            .accounts
+            .iter()
+            .filter(|account| is_asset_account(account))
+            .map(|account| {
+                let running = RunningBalance::new(
+                    &ledger.holding_currency(account),
+                    &account.opening_balance,
+                    &transactions,
+                    |id| id == account.id,
+                    projected_movement,
+                    window.start,
+                );
+                let mut balance = running.opening;
+                let mut points = vec![(window.start, running.major(balance))];
+                for movement in running
+                    .movements
+                    .iter()
+                    .take_while(|movement| movement.date < window.end)
+                {
+                    balance = balance.saturating_add(movement.amount);
+                    let value = running.major(balance);
+                    match points.last_mut() {
+                        Some((last, point)) if *last == movement.date => *point = value,
+                        _ => points.push((movement.date, value)),
+                    }
+                }
+                AccountProjection {
+                    account_id: account.id,
+                    name: account.name.clone(),
+                    currency: account
+                        .currency
+                        .clone()
+                        .unwrap_or_else(|| ledger.base_currency().as_str().to_string()),
+                    opening_balance: running.major(running.opening),
+                    closing_balance: running.major(balance),
+                    points,
+                }
+            })
+            .collect();
+        Ok(BalanceForecast { window, accounts })
+    }
+
+    /// The ledger's transactions, or a simulation's, plus the recurring
+    /// occurrences projected into each window and how many each received.
+    fn with_projections(
+        ledger: &Ledger,
+        windows: &[DateWindow],
+        reference: NaiveDate,
+        simulation: Option<&str>,
+    ) -> Result<(Vec<Transaction>, Vec<usize>), CoreError> {
+        let mut transactions = if let Some(name) = simulation {
+            SimulationService::run(ledger, name)?.transactions
+        } else {
+            ledger.transactions.clone()
+        };
+        let mut projected = Vec::with_capacity(windows.len());
+        let mut generated = Vec::new();
+        for window in windows {
+            let forecast = forecast_for_window(
+                *window,
+                reference,
+                &transactions,
+                ledger.business_calendar(),
+            );
+            projected.push(forecast.transactions.len());
+            generated.extend(
+                forecast
+                    .transactions
+                    .into_iter()
+                    .map(|item| item.transaction),
+            );
+        }
+        transactions.extend(generated);
+        Ok((transactions, projected))
+    }
+
+    /// Forecast for the budget period containing `reference`, plus the
+    /// recurrence snapshots, with the recurring series grouped once for both.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            reference = %reference
+        )
+    )]
+    pub fn period_report_with_recurrences(
+        ledger: &Ledger,
+        reference: NaiveDate,
+    ) -> (ForecastReport, Vec<RecurrenceSnapshot>) {
+        let window = ledger.budget_window_containing(reference);
+        let (forecast, recurrences) = forecast_with_snapshots(
+            window,
+            reference,
+            &ledger.transactions,
+            ledger.business_calendar(),
+        );
+        (
+            Self::report(ledger, forecast, ledger.transactions.clone()),
+            recurrences,
+        )
+    }
+
+    fn report(
+        ledger: &Ledger,
+        forecast: ForecastResult,
+        base_transactions: Vec<Transaction>,
+    ) -> ForecastReport {
+        let window = forecast.window;
+        let scope = window.scope(forecast.reference_date);
+        let mut overlay = base_transactions;
         overlay.extend(
             forecast
                 .transactions
@@ -43,11 +343,25 @@ impl ForecastService {
             Some(&overlay),
             CategoryBudgetSummaryKind::Projected,
         );
-        Ok(ForecastReport {
+        let goal_contributions =
+            GoalService::contributions(ledger, &window, forecast.reference_date);
+        ForecastReport {
             scope,
             forecast,
             summary,
             category_budgets,
-        })
+            goal_contributions,
+        }
+    }
+}
+
+/// Day and amount a transaction moves money in a projection.
+fn projected_movement(txn: &Transaction) -> Option<(NaiveDate, &Money)> {
+    match txn.status {
+        TransactionStatus::Completed => completed_amount(txn),
+        TransactionStatus::Planned | TransactionStatus::Simulated => {
+            Some((txn.scheduled_date, &txn.budgeted_amount))
+        }
+        TransactionStatus::Missed => None,
     }
 }
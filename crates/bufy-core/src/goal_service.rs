@@ -0,0 +1,258 @@
+//! Savings goals: progress towards a target, the monthly contribution needed
+//! to reach it on time, and when the planned contributions get there.
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    goal::{FundingSchedule, Goal, GoalContribution, GoalLink},
+    ledger::DateWindow,
+    Ledger,
+};
+
+use crate::{
+    sinking_fund_service::months_until,
+    stats::{account_balance, completed_movement},
+    Clock, CoreError,
+};
+
+/// Funding schedules are projected at most this many contributions ahead.
+const MAX_PROJECTED_CONTRIBUTIONS: usize = 1200;
+
+/// Progress of one goal on a given day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub goal_id: Uuid,
+    pub name: String,
+    pub target_amount: f64,
+    pub target_date: NaiveDate,
+    /// Balance of the linked account, or money recorded under the linked category.
+    pub saved: f64,
+    pub remaining: f64,
+    /// Share of the target saved so far, from 0 to 100.
+    pub percent: f64,
+    /// Monthly contribution needed from today to reach the target by its date.
+    pub required_monthly: f64,
+    /// When the funding schedule reaches the target; `None` without a
+    /// schedule or when it never gets there.
+    pub projected_completion: Option<NaiveDate>,
+}
+
+impl GoalProgress {
+    pub fn is_reached(&self) -> bool {
+        self.remaining <= 0.0
+    }
+
+    /// Whether the funding schedule reaches the target by the target date.
+    pub fn is_on_track(&self) -> bool {
+        self.is_reached()
+            || self
+                .projected_completion
+                .is_some_and(|date| date <= self.target_date)
+    }
+}
+
+pub struct GoalService;
+
+impl GoalService {
+    /// Adds a goal to save `target_amount` by `target_date`, measured through
+    /// `link`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            target_amount = target_amount,
+            target_date = %target_date
+        ),
+        err(level = "debug")
+    )]
+    pub fn create(
+        ledger: &mut Ledger,
+        name: &str,
+        target_amount: f64,
+        target_date: NaiveDate,
+        link: GoalLink,
+        funding: Option<FundingSchedule>,
+        clock: &dyn Clock,
+    ) -> Result<Uuid, CoreError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(CoreError::Validation("goal name cannot be empty".into()));
+        }
+        if ledger.goal(name).is_some() {
+            return Err(CoreError::Validation(format!(
+                "goal `{}` already exists",
+                name
+            )));
+        }
+        if !target_amount.is_finite() || target_amount <= 0.0 {
+            return Err(CoreError::Validation(
+                "target amount must be positive".into(),
+            ));
+        }
+        match link {
+            GoalLink::Account(id) if ledger.account(id).is_none() => {
+                return Err(CoreError::AccountNotFound(id.to_string()));
+            }
+            GoalLink::Category(id) if ledger.category(id).is_none() => {
+                return Err(CoreError::CategoryNotFound(id.to_string()));
+            }
+            _ => {}
+        }
+        if let Some(funding) = &funding {
+            if !funding.amount.is_finite() || funding.amount <= 0.0 {
+                return Err(CoreError::Validation(
+                    "contribution amount must be positive".into(),
+                ));
+            }
+            if funding.interval.every == 0 {
+                return Err(CoreError::Validation(
+                    "interval must be at least one unit".into(),
+                ));
+            }
+        }
+        let mut goal = Goal::new(name, target_amount, target_date, link, clock.now());
+        goal.funding = funding;
+        let id = goal.id;
+        ledger.goals.push(goal);
+        ledger.touch();
+        Ok(id)
+    }
+
+    /// Money counted towards `goal` on `today`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn saved(ledger: &Ledger, goal: &Goal, today: NaiveDate) -> f64 {
+        match goal.link {
+            GoalLink::Account(id) => ledger
+                .account(id)
+                .map(|account| {
+                    account_balance(
+                        account,
+                        &ledger.holding_currency(account),
+                        &ledger.transactions,
+                        today,
+                    )
+                })
+                .unwrap_or(0.0),
+            GoalLink::Category(id) => ledger
+                .transactions
+                .iter()
+                .filter(|txn| txn.category_id == Some(id))
+                .filter_map(completed_movement)
+                .filter(|(date, _)| *date <= today)
+                .map(|(_, amount)| amount)
+                .sum(),
+        }
+    }
+
+    /// Progress of `goal` on `today`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn progress(ledger: &Ledger, goal: &Goal, today: NaiveDate) -> GoalProgress {
+        let saved = Self::saved(ledger, goal, today);
+        let remaining = (goal.target_amount - saved).max(0.0);
+        let projected_completion = if remaining <= 0.0 {
+            Some(today)
+        } else {
+            goal.funding.as_ref().and_then(|funding| {
+                scheduled_dates(funding, today)
+                    .scan(saved, |total, date| {
+                        *total += funding.amount;
+                        Some((date, *total))
+                    })
+                    .find(|(_, total)| *total >= goal.target_amount)
+                    .map(|(date, _)| date)
+            })
+        };
+        GoalProgress {
+            goal_id: goal.id,
+            name: goal.name.clone(),
+            target_amount: goal.target_amount,
+            target_date: goal.target_date,
+            saved,
+            remaining,
+            percent: (saved / goal.target_amount * 100.0).clamp(0.0, 100.0),
+            required_monthly: remaining / months_until(today, goal.target_date) as f64,
+            projected_completion,
+        }
+    }
+
+    /// Progress of every goal, soonest target date first.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn progress_all(ledger: &Ledger, today: NaiveDate) -> Vec<GoalProgress> {
+        let mut progress: Vec<_> = ledger
+            .goals
+            .iter()
+            .map(|goal| Self::progress(ledger, goal, today))
+            .collect();
+        progress.sort_by(|a, b| a.target_date.cmp(&b.target_date).then(a.name.cmp(&b.name)));
+        progress
+    }
+
+    /// Scheduled contributions dated within `window`, counting from
+    /// `reference` and stopping once each goal's target is reached.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            reference = %reference
+        )
+    )]
+    pub fn contributions(
+        ledger: &Ledger,
+        window: &DateWindow,
+        reference: NaiveDate,
+    ) -> Vec<GoalContribution> {
+        let mut contributions = Vec::new();
+        for goal in &ledger.goals {
+            let Some(funding) = &goal.funding else {
+                continue;
+            };
+            let mut total = Self::saved(ledger, goal, reference);
+            for date in scheduled_dates(funding, reference) {
+                if total >= goal.target_amount || date >= window.end {
+                    break;
+                }
+                total += funding.amount;
+                if date >= window.start {
+                    contributions.push(GoalContribution {
+                        goal_id: goal.id,
+                        name: goal.name.clone(),
+                        date,
+                        amount: funding.amount,
+                    });
+                }
+            }
+        }
+        contributions.sort_by(|a, b| a.date.cmp(&b.date).then(a.name.cmp(&b.name)));
+        contributions
+    }
+}
+
+/// Contribution dates of `funding` on or after `from`.
+fn scheduled_dates(
+    funding: &FundingSchedule,
+    from: NaiveDate,
+) -> impl Iterator<Item = NaiveDate> + '_ {
+    std::iter::successors(Some(funding.start), |date| {
+        Some(funding.interval.next_date(*date))
+    })
+    .take(MAX_PROJECTED_CONTRIBUTIONS)
+    .skip_while(move |date| *date < from)
+}
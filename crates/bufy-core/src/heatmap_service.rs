@@ -0,0 +1,101 @@
+//! Daily spending laid out as a month calendar.
+//!
+//! Weeks start on the locale's first weekday and weekend days are marked,
+//! so a Sunday-first or Friday/Saturday-weekend region reads its own
+//! calendar.
+
+use chrono::{Datelike, Months, NaiveDate, Weekday};
+use tracing::instrument;
+
+use bufy_domain::{common::days_since_week_start, DateWindow, Ledger};
+
+use crate::{stats::completed_movement, CoreError, MerchantService};
+
+/// Completed spending on one day of the month.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatmapDay {
+    pub date: NaiveDate,
+    /// Spend in the ledger's base currency.
+    pub total: f64,
+    pub weekend: bool,
+}
+
+/// A month of daily spending, one row per calendar week.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpendingHeatmap {
+    pub month: NaiveDate,
+    /// Column order, starting on the locale's first weekday.
+    pub weekdays: Vec<Weekday>,
+    /// Seven cells per week; `None` pads days outside the month.
+    pub weeks: Vec<Vec<Option<HeatmapDay>>>,
+    /// Largest daily total, for scaling.
+    pub busiest: f64,
+    /// Spending whose amount could not be converted to the base currency
+    /// and was left out.
+    pub unconverted: usize,
+}
+
+pub struct HeatmapService;
+
+impl HeatmapService {
+    /// Daily completed spending for the month starting at `year`-`month`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn month(ledger: &Ledger, year: i32, month: u32) -> Result<SpendingHeatmap, CoreError> {
+        let start = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(|| {
+            CoreError::Validation(format!("{}-{:02} is not a month", year, month))
+        })?;
+        let end = start.checked_add_months(Months::new(1)).ok_or_else(|| {
+            CoreError::Validation(format!("{}-{:02} is out of range", year, month))
+        })?;
+        let window =
+            DateWindow::new(start, end).map_err(|err| CoreError::Validation(err.to_string()))?;
+
+        let days = (end - start).num_days() as usize;
+        let mut totals = vec![0.0; days];
+        let mut unconverted = 0;
+        let ctx = ledger.conversion_context(window.end);
+        for txn in &ledger.transactions {
+            if !MerchantService::is_spending(ledger, txn) {
+                continue;
+            }
+            let Some((_, amount)) = completed_movement(txn) else {
+                continue;
+            };
+            let Some(date) = txn
+                .reporting_date(ledger.summary_date_basis)
+                .filter(|date| window.contains(*date))
+            else {
+                continue;
+            };
+            let currency = ledger.transaction_currency(txn);
+            match ledger.convert_amount(amount, &currency, date, &ctx) {
+                Ok(converted) => totals[date.day0() as usize] += converted.amount,
+                Err(_) => unconverted += 1,
+            }
+        }
+
+        let first_weekday = ledger.locale.first_weekday;
+        let lead = days_since_week_start(start, first_weekday) as usize;
+        let mut cells: Vec<Option<HeatmapDay>> = vec![None; lead];
+        cells.extend(start.iter_days().zip(&totals).map(|(date, total)| {
+            Some(HeatmapDay {
+                date,
+                total: *total,
+                weekend: ledger.locale.is_weekend(date),
+            })
+        }));
+        cells.resize(cells.len().div_ceil(7) * 7, None);
+        Ok(SpendingHeatmap {
+            month: start,
+            weekdays: ledger.locale.ordered_weekdays(),
+            weeks: cells.chunks(7).map(<[_]>::to_vec).collect(),
+            busiest: totals.iter().copied().fold(0.0, f64::max),
+            unconverted,
+        })
+    }
+}
@@ -0,0 +1,327 @@
+//! Fills the ledger's holiday calendar from a built-in country table or an
+//! iCalendar (RFC 5545) file, and refreshes it each year.
+//!
+//! Imports cover last year through next year. Country calendars are
+//! extended by expanding their rules again; file calendars are replaced by
+//! a fresh read of the file, with yearly recurring events expanded through
+//! next year.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use tracing::instrument;
+
+use bufy_domain::{Holiday, HolidayCalendar, HolidayCountry, HolidaySource, Ledger};
+
+use crate::CoreError;
+
+/// Longest event an import expands day by day.
+const MAX_EVENT_DAYS: i64 = 31;
+
+/// What an import or refresh left in the calendar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HolidayImport {
+    pub source: HolidaySource,
+    pub holidays: usize,
+    pub through_year: i32,
+    /// Events in the file that could not be read as all-day holidays.
+    pub skipped: usize,
+}
+
+pub struct HolidayService;
+
+impl HolidayService {
+    /// Replaces the calendar with the built-in table for `code`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), country = code),
+        err(level = "debug")
+    )]
+    pub fn import_country(
+        ledger: &mut Ledger,
+        code: &str,
+        today: NaiveDate,
+    ) -> Result<HolidayImport, CoreError> {
+        let country = HolidayCountry::lookup(code).ok_or_else(|| unknown_country(code))?;
+        let through_year = today.year() + 1;
+        let holidays = country_range(country, today.year() - 1, through_year);
+        Ok(store(
+            ledger,
+            HolidaySource::Country(country.code.to_string()),
+            holidays,
+            through_year,
+            0,
+        ))
+    }
+
+    /// Replaces the calendar with the all-day events of an iCalendar file.
+    /// `path` is kept so [`HolidayService::refresh`] can read it again.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), path = path),
+        err(level = "debug")
+    )]
+    pub fn import_ics(
+        ledger: &mut Ledger,
+        path: &str,
+        contents: &str,
+        today: NaiveDate,
+    ) -> Result<HolidayImport, CoreError> {
+        let through_year = today.year() + 1;
+        let (holidays, skipped) = holidays_from_ics(contents, through_year)?;
+        let last_year = holidays
+            .iter()
+            .map(|holiday| holiday.date.year())
+            .max()
+            .unwrap_or(through_year);
+        Ok(store(
+            ledger,
+            HolidaySource::File(path.to_string()),
+            holidays,
+            last_year.max(through_year),
+            skipped,
+        ))
+    }
+
+    /// Extends a country calendar through next year, keeping the years it
+    /// already had. File calendars need the file read again: pass its
+    /// `contents`, or `None` to fail with the path to read.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn refresh(
+        ledger: &mut Ledger,
+        contents: Option<&str>,
+        today: NaiveDate,
+    ) -> Result<HolidayImport, CoreError> {
+        match ledger.holidays.source.clone() {
+            None => Err(CoreError::InvalidOperation(
+                "no holiday calendar imported yet".into(),
+            )),
+            Some(HolidaySource::Country(code)) => {
+                let country =
+                    HolidayCountry::lookup(&code).ok_or_else(|| unknown_country(&code))?;
+                let first_year = ledger
+                    .holidays
+                    .holidays
+                    .first()
+                    .map_or(today.year() - 1, |holiday| holiday.date.year());
+                let through_year = today.year() + 1;
+                let holidays = country_range(country, first_year, through_year);
+                Ok(store(
+                    ledger,
+                    HolidaySource::Country(code),
+                    holidays,
+                    through_year,
+                    0,
+                ))
+            }
+            Some(HolidaySource::File(path)) => match contents {
+                Some(contents) => Self::import_ics(ledger, &path, contents, today),
+                None => Err(CoreError::InvalidOperation(format!(
+                    "the holiday calendar comes from `{}`; read it again to refresh",
+                    path
+                ))),
+            },
+        }
+    }
+
+    /// Removes the calendar; only weekends remain non-business days.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn clear(ledger: &mut Ledger) -> bool {
+        if ledger.holidays.is_empty() {
+            return false;
+        }
+        ledger.holidays = HolidayCalendar::default();
+        ledger.touch();
+        true
+    }
+}
+
+fn store(
+    ledger: &mut Ledger,
+    source: HolidaySource,
+    holidays: Vec<Holiday>,
+    through_year: i32,
+    skipped: usize,
+) -> HolidayImport {
+    let mut calendar = HolidayCalendar {
+        source: Some(source.clone()),
+        through_year: Some(through_year),
+        holidays: Vec::new(),
+    };
+    calendar.set_holidays(holidays);
+    let count = calendar.holidays.len();
+    ledger.holidays = calendar;
+    ledger.touch();
+    HolidayImport {
+        source,
+        holidays: count,
+        through_year,
+        skipped,
+    }
+}
+
+fn country_range(country: &HolidayCountry, from: i32, through: i32) -> Vec<Holiday> {
+    (from..=through)
+        .flat_map(|year| country.holidays(year))
+        .filter(|holiday| (from..=through).contains(&holiday.date.year()))
+        .collect()
+}
+
+fn unknown_country(code: &str) -> CoreError {
+    let known: Vec<&str> = HolidayCountry::all()
+        .iter()
+        .map(|country| country.code)
+        .collect();
+    CoreError::Validation(format!(
+        "no built-in holidays for `{}`; available: {}",
+        code,
+        known.join(", ")
+    ))
+}
+
+/// Reads every `VEVENT` of an iCalendar document as holidays, one per day
+/// it spans. Events with `RRULE:FREQ=YEARLY` repeat through `through_year`;
+/// events with other recurrence rules, or without a readable start date, are
+/// counted as skipped.
+pub fn holidays_from_ics(
+    contents: &str,
+    through_year: i32,
+) -> Result<(Vec<Holiday>, usize), CoreError> {
+    let lines = unfold(contents);
+    if !lines
+        .iter()
+        .any(|line| line.eq_ignore_ascii_case("BEGIN:VCALENDAR"))
+    {
+        return Err(CoreError::Validation(
+            "not an iCalendar file: BEGIN:VCALENDAR is missing".into(),
+        ));
+    }
+    let mut holidays = Vec::new();
+    let mut skipped = 0;
+    let mut event: Option<IcsEvent> = None;
+    for line in &lines {
+        let Some((head, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = head
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        match (name.as_str(), event.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                event = Some(IcsEvent::default())
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                match event.take().and_then(|event| event.expand(through_year)) {
+                    Some(days) => holidays.extend(days),
+                    None => skipped += 1,
+                }
+            }
+            ("DTSTART", Some(event)) => event.start = parse_ics_date(value),
+            ("DTEND", Some(event)) => event.end = parse_ics_date(value),
+            ("SUMMARY", Some(event)) => event.summary = unescape(value),
+            ("RRULE", Some(event)) => event.rule = Some(value.to_ascii_uppercase()),
+            _ => {}
+        }
+    }
+    Ok((holidays, skipped))
+}
+
+#[derive(Debug, Default)]
+struct IcsEvent {
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+    summary: String,
+    rule: Option<String>,
+}
+
+impl IcsEvent {
+    fn expand(self, through_year: i32) -> Option<Vec<Holiday>> {
+        let start = self.start?;
+        let days = self
+            .end
+            .map_or(1, |end| (end - start).num_days())
+            .clamp(1, MAX_EVENT_DAYS);
+        let name = if self.summary.is_empty() {
+            "Holiday".to_string()
+        } else {
+            self.summary
+        };
+        let years = match self.rule.as_deref() {
+            None => start.year()..=start.year(),
+            Some(rule) => {
+                let parts = rule.split(';').filter_map(|part| part.split_once('='));
+                let mut last_year = through_year;
+                let mut yearly = false;
+                for (key, value) in parts {
+                    match key {
+                        "FREQ" if value == "YEARLY" => yearly = true,
+                        "UNTIL" => last_year = last_year.min(parse_ics_date(value)?.year()),
+                        "COUNT" => {
+                            let count: i32 = value.parse().ok()?;
+                            last_year = last_year.min(start.year() + count - 1);
+                        }
+                        "INTERVAL" if value == "1" => {}
+                        _ => return None,
+                    }
+                }
+                if !yearly {
+                    return None;
+                }
+                start.year()..=last_year
+            }
+        };
+        Some(
+            years
+                .filter_map(|year| start.with_year(year))
+                .flat_map(|first| (0..days).map(move |offset| first + Duration::days(offset)))
+                .map(|date| Holiday::new(date, name.clone()))
+                .collect(),
+        )
+    }
+}
+
+/// Joins folded content lines and drops line endings.
+fn unfold(contents: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in contents.lines() {
+        match (raw.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(previous)) => previous.push_str(rest),
+            _ => lines.push(raw.trim_end().to_string()),
+        }
+    }
+    lines
+}
+
+/// `20250101` or `20250101T090000Z`; only the date is kept.
+fn parse_ics_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// Undoes RFC 5545 TEXT escaping.
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push(' '),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out.trim().to_string()
+}
@@ -0,0 +1,642 @@
+//! Migration of data exported by other budgeting apps.
+//!
+//! Parsing turns an export into an [`ImportBundle`] without touching the
+//! ledger. Callers then decide how each account and category in the bundle
+//! maps onto the ledger through an [`ImportMapping`] and hand both to
+//! [`ImportService::apply`].
+//!
+//! Payees become counterparty accounts: an outflow to "Grocer" is recorded
+//! from the imported account to an expense destination named "Grocer", and
+//! an inflow from an income source of the payee's name.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    io::{Read, Seek},
+};
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    account::{Account, AccountKind},
+    category::{Category, CategoryKind},
+    transaction::Transaction,
+    BudgetPeriod, Ledger, Money,
+};
+
+use crate::{AccountService, CategoryService, CoreError};
+
+/// App that produced an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    Ynab,
+    Mint,
+    /// A bank connection, see [`crate::bank_service`].
+    Bank,
+}
+
+impl fmt::Display for ImportSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ImportSource::Ynab => "YNAB",
+            ImportSource::Mint => "Mint",
+            ImportSource::Bank => "bank",
+        })
+    }
+}
+
+/// One register row from an export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedTransaction {
+    pub account: String,
+    pub date: NaiveDate,
+    pub payee: String,
+    pub category: Option<String>,
+    pub group: Option<String>,
+    pub memo: Option<String>,
+    /// Signed amount; inflows are positive.
+    pub amount: f64,
+    /// Other account of a transfer between imported accounts.
+    pub transfer_account: Option<String>,
+}
+
+/// Amount assigned to a category for one month.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedBudget {
+    pub category: String,
+    pub group: Option<String>,
+    pub month: NaiveDate,
+    pub amount: f64,
+}
+
+/// Everything read from an export, before it is mapped onto a ledger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportBundle {
+    pub source: ImportSource,
+    pub transactions: Vec<ImportedTransaction>,
+    pub budgets: Vec<ImportedBudget>,
+}
+
+impl ImportBundle {
+    /// Names of the asset accounts in the export, including transfer targets.
+    pub fn accounts(&self) -> Vec<String> {
+        let mut names = BTreeSet::new();
+        for txn in &self.transactions {
+            names.insert(txn.account.clone());
+            if let Some(other) = &txn.transfer_account {
+                names.insert(other.clone());
+            }
+        }
+        names.into_iter().collect()
+    }
+
+    /// Categories in the export, with their group and inferred kind.
+    pub fn categories(&self) -> Vec<ImportedCategory> {
+        let mut net: BTreeMap<String, (Option<String>, f64)> = BTreeMap::new();
+        for txn in &self.transactions {
+            if let Some(name) = &txn.category {
+                let entry = net
+                    .entry(name.clone())
+                    .or_insert_with(|| (txn.group.clone(), 0.0));
+                entry.1 += txn.amount;
+            }
+        }
+        for budget in &self.budgets {
+            net.entry(budget.category.clone())
+                .or_insert_with(|| (budget.group.clone(), 0.0));
+        }
+        net.into_iter()
+            .map(|(name, (group, total))| {
+                let income = total > 0.0
+                    || group
+                        .as_deref()
+                        .is_some_and(|group| group.eq_ignore_ascii_case("inflow"));
+                ImportedCategory {
+                    name,
+                    group,
+                    kind: if income {
+                        CategoryKind::Income
+                    } else {
+                        CategoryKind::Expense
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Category found in an export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedCategory {
+    pub name: String,
+    pub group: Option<String>,
+    pub kind: CategoryKind,
+}
+
+/// Where an imported account or category ends up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportTarget {
+    /// Use an existing ledger entry.
+    Existing(Uuid),
+    /// Create a new entry with the imported name.
+    Create,
+    /// Leave it out; skipped accounts drop their transactions and skipped
+    /// categories leave transactions uncategorized.
+    Skip,
+}
+
+/// Import targets keyed by the names used in the export.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportMapping {
+    pub accounts: BTreeMap<String, ImportTarget>,
+    pub categories: BTreeMap<String, ImportTarget>,
+}
+
+impl ImportMapping {
+    /// Maps each name to the ledger entry with the same name, ignoring case,
+    /// and to [`ImportTarget::Create`] otherwise.
+    pub fn suggest(ledger: &Ledger, bundle: &ImportBundle) -> Self {
+        let accounts = bundle
+            .accounts()
+            .into_iter()
+            .map(|name| {
+                let target = find_account(ledger, &name)
+                    .map(ImportTarget::Existing)
+                    .unwrap_or(ImportTarget::Create);
+                (name, target)
+            })
+            .collect();
+        let categories = bundle
+            .categories()
+            .into_iter()
+            .map(|category| {
+                let target = find_category(ledger, &category.name)
+                    .map(ImportTarget::Existing)
+                    .unwrap_or(ImportTarget::Create);
+                (category.name, target)
+            })
+            .collect();
+        Self {
+            accounts,
+            categories,
+        }
+    }
+}
+
+/// Counts of what an import changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub accounts_created: usize,
+    pub categories_created: usize,
+    pub transactions_imported: usize,
+    /// Rows matching a transaction already in the ledger.
+    pub duplicates_skipped: usize,
+    /// Rows belonging to skipped accounts.
+    pub rows_skipped: usize,
+    pub budgets_set: usize,
+}
+
+pub struct ImportService;
+
+impl ImportService {
+    /// Reads the register and budget CSV files from a YNAB export archive.
+    #[instrument(level = "debug", skip_all, err(level = "debug"))]
+    pub fn read_ynab_zip<R: Read + Seek>(reader: R) -> Result<ImportBundle, CoreError> {
+        let mut archive = zip::ZipArchive::new(reader)
+            .map_err(|err| CoreError::Validation(format!("not a YNAB export archive: {}", err)))?;
+        let mut register = None;
+        let mut budget = None;
+        for index in 0..archive.len() {
+            let mut file = archive
+                .by_index(index)
+                .map_err(|err| CoreError::Validation(format!("unreadable archive: {}", err)))?;
+            let name = file.name().to_ascii_lowercase();
+            let slot = if name.ends_with("register.csv") {
+                &mut register
+            } else if name.ends_with("budget.csv") {
+                &mut budget
+            } else {
+                continue;
+            };
+            let mut contents = String::new();
+            file.read_to_string(&mut contents)?;
+            *slot = Some(contents);
+        }
+        let register = register.ok_or_else(|| {
+            CoreError::Validation("YNAB export archive has no register CSV".into())
+        })?;
+        Self::parse_ynab(&register, budget.as_deref())
+    }
+
+    /// Parses YNAB register and budget CSV exports.
+    #[instrument(level = "debug", skip_all, err(level = "debug"))]
+    pub fn parse_ynab(register: &str, budget: Option<&str>) -> Result<ImportBundle, CoreError> {
+        let mut transactions = Vec::new();
+        let table = CsvTable::parse(register, "YNAB register")?;
+        let account = table.column("Account")?;
+        let date = table.column("Date")?;
+        let payee = table.column("Payee")?;
+        let group = table.optional_column("Category Group");
+        let category = table.column("Category")?;
+        let memo = table.optional_column("Memo");
+        let outflow = table.column("Outflow")?;
+        let inflow = table.column("Inflow")?;
+        for (line, row) in table.rows() {
+            let payee_name = row.get(payee);
+            let transfer_account = payee_name
+                .strip_prefix("Transfer :")
+                .map(|name| name.trim().to_string());
+            let amount = parse_money(row.get(inflow), line)? - parse_money(row.get(outflow), line)?;
+            transactions.push(ImportedTransaction {
+                account: row.get(account).to_string(),
+                date: parse_date(row.get(date), line)?,
+                payee: payee_name.to_string(),
+                category: row.non_empty(Some(category)),
+                group: row.non_empty(group),
+                memo: row.non_empty(memo),
+                amount,
+                transfer_account,
+            });
+        }
+
+        let mut budgets = Vec::new();
+        if let Some(budget) = budget {
+            let table = CsvTable::parse(budget, "YNAB budget")?;
+            let month = table.column("Month")?;
+            let group = table.optional_column("Category Group");
+            let category = table.column("Category")?;
+            let budgeted = table.column("Budgeted")?;
+            for (line, row) in table.rows() {
+                let Some(name) = row.non_empty(Some(category)) else {
+                    continue;
+                };
+                budgets.push(ImportedBudget {
+                    category: name,
+                    group: row.non_empty(group),
+                    month: parse_month(row.get(month), line)?,
+                    amount: parse_money(row.get(budgeted), line)?,
+                });
+            }
+        }
+
+        Ok(ImportBundle {
+            source: ImportSource::Ynab,
+            transactions,
+            budgets,
+        })
+    }
+
+    /// Parses a Mint transactions CSV export.
+    #[instrument(level = "debug", skip_all, err(level = "debug"))]
+    pub fn parse_mint(csv: &str) -> Result<ImportBundle, CoreError> {
+        let table = CsvTable::parse(csv, "Mint export")?;
+        let date = table.column("Date")?;
+        let description = table.column("Description")?;
+        let amount = table.column("Amount")?;
+        let kind = table.column("Transaction Type")?;
+        let category = table.column("Category")?;
+        let account = table.column("Account Name")?;
+        let notes = table.optional_column("Notes");
+        let mut transactions = Vec::new();
+        for (line, row) in table.rows() {
+            let value = parse_money(row.get(amount), line)?.abs();
+            let signed = if row.get(kind).eq_ignore_ascii_case("credit") {
+                value
+            } else {
+                -value
+            };
+            transactions.push(ImportedTransaction {
+                account: row.get(account).to_string(),
+                date: parse_date(row.get(date), line)?,
+                payee: row.get(description).to_string(),
+                category: row
+                    .non_empty(Some(category))
+                    .filter(|name| !name.eq_ignore_ascii_case("uncategorized")),
+                group: None,
+                memo: row.non_empty(notes),
+                amount: signed,
+                transfer_account: None,
+            });
+        }
+        Ok(ImportBundle {
+            source: ImportSource::Mint,
+            transactions,
+            budgets: Vec::new(),
+        })
+    }
+
+    /// Records a bundle in the ledger as completed transactions.
+    ///
+    /// Rows that match an existing transaction on accounts, date, and amount
+    /// are skipped, so importing the same export twice is harmless. Only the
+    /// outgoing side of a transfer is recorded. Each category gets a monthly
+    /// budget from its most recent budgeted month.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn apply(
+        ledger: &mut Ledger,
+        bundle: &ImportBundle,
+        mapping: &ImportMapping,
+    ) -> Result<ImportReport, CoreError> {
+        let mut report = ImportReport::default();
+
+        let mut accounts = BTreeMap::new();
+        for name in bundle.accounts() {
+            let id = match mapping.accounts.get(&name) {
+                Some(ImportTarget::Existing(id)) => {
+                    ledger
+                        .account(*id)
+                        .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
+                    *id
+                }
+                Some(ImportTarget::Skip) => continue,
+                Some(ImportTarget::Create) | None => match find_account(ledger, &name) {
+                    Some(id) => id,
+                    None => {
+                        let account = Account::new(name.clone(), AccountKind::Bank);
+                        let id = account.id;
+                        AccountService::add(ledger, account)?;
+                        report.accounts_created += 1;
+                        id
+                    }
+                },
+            };
+            accounts.insert(name, id);
+        }
+
+        let mut categories = BTreeMap::new();
+        for imported in bundle.categories() {
+            let id = match mapping.categories.get(&imported.name) {
+                Some(ImportTarget::Existing(id)) => {
+                    ledger
+                        .category(*id)
+                        .ok_or_else(|| CoreError::CategoryNotFound(id.to_string()))?;
+                    *id
+                }
+                Some(ImportTarget::Skip) => continue,
+                Some(ImportTarget::Create) | None => match find_category(ledger, &imported.name) {
+                    Some(id) => id,
+                    None => {
+                        let created = Self::create_category(ledger, &imported)?;
+                        report.categories_created += created.1;
+                        created.0
+                    }
+                },
+            };
+            categories.insert(imported.name, id);
+        }
+
+        for row in &bundle.transactions {
+            let Some(&account) = accounts.get(&row.account) else {
+                report.rows_skipped += 1;
+                continue;
+            };
+            let counterparty = match &row.transfer_account {
+                Some(_) if row.amount >= 0.0 => continue,
+                Some(other) => match accounts.get(other) {
+                    Some(&id) => id,
+                    None => {
+                        report.rows_skipped += 1;
+                        continue;
+                    }
+                },
+                None => Self::counterparty(ledger, bundle.source, row, &mut report)?,
+            };
+            let (from, to) = if row.amount < 0.0 {
+                (account, counterparty)
+            } else {
+                (counterparty, account)
+            };
+            let amount = Money::from_major(row.amount.abs(), ledger.transfer_currency(from, to))?;
+            let duplicate = ledger.transactions.iter().any(|txn| {
+                txn.from_account == from
+                    && txn.to_account == to
+                    && txn.scheduled_date == row.date
+                    && txn.budgeted_amount == amount
+            });
+            if duplicate {
+                report.duplicates_skipped += 1;
+                continue;
+            }
+            let category = row
+                .category
+                .as_ref()
+                .and_then(|name| categories.get(name).copied());
+            let mut txn = Transaction::new(from, to, category, row.date, amount.clone());
+            txn.notes = row.memo.clone();
+            txn.mark_completed(row.date, amount);
+            ledger.add_transaction(txn);
+            report.transactions_imported += 1;
+        }
+
+        let mut latest: BTreeMap<&str, &ImportedBudget> = BTreeMap::new();
+        for budget in bundle.budgets.iter().filter(|budget| budget.amount > 0.0) {
+            let slot = latest.entry(budget.category.as_str()).or_insert(budget);
+            if budget.month > slot.month {
+                *slot = budget;
+            }
+        }
+        for (name, budget) in latest {
+            if let Some(&id) = categories.get(name) {
+                CategoryService::set_budget(
+                    ledger,
+                    id,
+                    budget.amount,
+                    BudgetPeriod::Monthly,
+                    None,
+                )?;
+                report.budgets_set += 1;
+            }
+        }
+
+        ledger.touch();
+        Ok(report)
+    }
+
+    /// Creates an imported category under a parent named after its group,
+    /// returning its id and how many categories were added.
+    fn create_category(
+        ledger: &mut Ledger,
+        imported: &ImportedCategory,
+    ) -> Result<(Uuid, usize), CoreError> {
+        let mut created = 0;
+        let parent = match imported.group.as_deref() {
+            Some(group) if !group.eq_ignore_ascii_case(&imported.name) => {
+                match find_category(ledger, group) {
+                    Some(id) => Some(id),
+                    None => {
+                        let parent = Category::new(group, imported.kind.clone());
+                        let id = parent.id;
+                        CategoryService::add(ledger, parent)?;
+                        created += 1;
+                        Some(id)
+                    }
+                }
+            }
+            _ => None,
+        };
+        let mut category = Category::new(imported.name.clone(), imported.kind.clone());
+        category.parent_id = parent;
+        let id = category.id;
+        CategoryService::add(ledger, category)?;
+        Ok((id, created + 1))
+    }
+
+    /// Finds or creates the account standing in for a row's payee.
+    fn counterparty(
+        ledger: &mut Ledger,
+        source: ImportSource,
+        row: &ImportedTransaction,
+        report: &mut ImportReport,
+    ) -> Result<Uuid, CoreError> {
+        let name = if row.payee.trim().is_empty() {
+            format!("Unknown payee ({})", source)
+        } else {
+            row.payee.trim().to_string()
+        };
+        if let Some(id) = find_account(ledger, &name) {
+            return Ok(id);
+        }
+        let kind = if row.amount < 0.0 {
+            AccountKind::ExpenseDestination
+        } else {
+            AccountKind::IncomeSource
+        };
+        let account = Account::new(name, kind);
+        let id = account.id;
+        AccountService::add(ledger, account)?;
+        report.accounts_created += 1;
+        Ok(id)
+    }
+}
+
+fn find_account(ledger: &Ledger, name: &str) -> Option<Uuid> {
+    ledger
+        .accounts
+        .iter()
+        .find(|account| account.name.trim().eq_ignore_ascii_case(name.trim()))
+        .map(|account| account.id)
+}
+
+fn find_category(ledger: &Ledger, name: &str) -> Option<Uuid> {
+    ledger
+        .categories
+        .iter()
+        .find(|category| category.name.trim().eq_ignore_ascii_case(name.trim()))
+        .map(|category| category.id)
+}
+
+/// CSV file with its columns located by header name.
+struct CsvTable {
+    label: &'static str,
+    headers: Vec<String>,
+    records: Vec<(usize, csv::StringRecord)>,
+}
+
+impl CsvTable {
+    fn parse(contents: &str, label: &'static str) -> Result<Self, CoreError> {
+        let contents = contents.trim_start_matches('\u{feff}');
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(contents.as_bytes());
+        let invalid = |err: csv::Error| CoreError::Validation(format!("{}: {}", label, err));
+        let headers = reader
+            .headers()
+            .map_err(invalid)?
+            .iter()
+            .map(|header| header.trim().to_string())
+            .collect();
+        let mut records = Vec::new();
+        for (index, record) in reader.records().enumerate() {
+            let record = record.map_err(invalid)?;
+            if record.iter().all(|field| field.trim().is_empty()) {
+                continue;
+            }
+            records.push((index + 2, record));
+        }
+        Ok(Self {
+            label,
+            headers,
+            records,
+        })
+    }
+
+    fn optional_column(&self, name: &str) -> Option<usize> {
+        self.headers
+            .iter()
+            .position(|header| header.eq_ignore_ascii_case(name))
+    }
+
+    fn column(&self, name: &str) -> Result<usize, CoreError> {
+        self.optional_column(name).ok_or_else(|| {
+            CoreError::Validation(format!("{} is missing the `{}` column", self.label, name))
+        })
+    }
+
+    fn rows(&self) -> impl Iterator<Item = (usize, CsvRow<'_>)> {
+        self.records
+            .iter()
+            .map(|(line, record)| (*line, CsvRow(record)))
+    }
+}
+
+struct CsvRow<'a>(&'a csv::StringRecord);
+
+impl CsvRow<'_> {
+    fn get(&self, column: usize) -> &str {
+        self.0.get(column).unwrap_or_default().trim()
+    }
+
+    fn non_empty(&self, column: Option<usize>) -> Option<String> {
+        column
+            .map(|column| self.get(column))
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    }
+}
+
+/// Parses amounts such as `$1,234.50`, `(12.00)`, or `-3,50`.
+fn parse_money(value: &str, line: usize) -> Result<f64, CoreError> {
+    let negative = value.starts_with('(') || value.contains('-');
+    let mut digits: String = value
+        .chars()
+        .filter(|ch| ch.is_ascii_digit() || *ch == '.' || *ch == ',')
+        .collect();
+    if digits.is_empty() {
+        return Ok(0.0);
+    }
+    match (digits.rfind('.'), digits.rfind(',')) {
+        (Some(dot), Some(comma)) if comma > dot => {
+            digits = digits.replace('.', "").replace(',', ".");
+        }
+        (None, Some(comma)) if digits.len() - comma != 4 => {
+            digits = digits.replace(',', ".");
+        }
+        _ => digits.retain(|ch| ch != ','),
+    }
+    let amount = digits
+        .parse::<f64>()
+        .map_err(|_| CoreError::Validation(format!("line {}: invalid amount `{}`", line, value)))?;
+    Ok(if negative { -amount } else { amount })
+}
+
+/// Parses US-style (`MM/DD/YYYY`) or ISO dates, the formats both apps use.
+fn parse_date(value: &str, line: usize) -> Result<NaiveDate, CoreError> {
+    ["%m/%d/%Y", "%Y-%m-%d"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .ok_or_else(|| CoreError::Validation(format!("line {}: invalid date `{}`", line, value)))
+}
+
+/// Parses YNAB budget months such as `Jan 2025` to their first day.
+fn parse_month(value: &str, line: usize) -> Result<NaiveDate, CoreError> {
+    NaiveDate::parse_from_str(&format!("1 {}", value), "%d %b %Y")
+        .or_else(|_| NaiveDate::parse_from_str(&format!("{}-01", value), "%Y-%m-%d"))
+        .map_err(|_| CoreError::Validation(format!("line {}: invalid month `{}`", line, value)))
+}
@@ -0,0 +1,125 @@
+//! Income models attached to recurring paychecks.
+
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    simulation::IncomeModelPatch,
+    transaction::{sync_income_series, IncomeModel},
+    Ledger,
+};
+
+use crate::CoreError;
+
+pub struct IncomeService;
+
+impl IncomeService {
+    /// Attaches `model` to the recurring transaction `template_id` and sets the
+    /// series' pending amounts to its net pay, which is returned.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            template_id = %template_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn set_model(
+        ledger: &mut Ledger,
+        template_id: Uuid,
+        model: IncomeModel,
+    ) -> Result<f64, CoreError> {
+        Self::validate(&model)?;
+        let recurrence = ledger
+            .transaction_mut(template_id)
+            .ok_or(CoreError::TransactionNotFound(template_id))?
+            .recurrence
+            .as_mut()
+            .ok_or_else(|| {
+                CoreError::InvalidOperation(
+                    "income models can only be attached to recurring transactions".into(),
+                )
+            })?;
+        let net = model.net();
+        recurrence.income = Some(model);
+        sync_income_series(&mut ledger.transactions, template_id);
+        ledger.touch();
+        Ok(net)
+    }
+
+    /// Detaches the income model, leaving the current amounts in place.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            template_id = %template_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn clear_model(ledger: &mut Ledger, template_id: Uuid) -> Result<IncomeModel, CoreError> {
+        let removed = ledger
+            .transaction_mut(template_id)
+            .ok_or(CoreError::TransactionNotFound(template_id))?
+            .recurrence
+            .as_mut()
+            .and_then(|recurrence| recurrence.income.take())
+            .ok_or_else(Self::missing_model)?;
+        ledger.touch();
+        Ok(removed)
+    }
+
+    /// Checks that applying `patch` to the template's model yields a valid model.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn validate_patch(ledger: &Ledger, patch: &IncomeModelPatch) -> Result<(), CoreError> {
+        let mut model = ledger
+            .transaction(patch.transaction_id)
+            .ok_or(CoreError::TransactionNotFound(patch.transaction_id))?
+            .recurrence
+            .as_ref()
+            .and_then(|recurrence| recurrence.income.clone())
+            .ok_or_else(Self::missing_model)?;
+        if let Some(gross) = patch.gross {
+            model.gross = gross;
+        }
+        if let Some(percent) = patch.withholding_percent {
+            model.withholding_percent = percent;
+        }
+        if let Some(benefits) = patch.benefits {
+            model.benefits = benefits;
+        }
+        Self::validate(&model)
+    }
+
+    fn validate(model: &IncomeModel) -> Result<(), CoreError> {
+        if !model.gross.is_finite() || model.gross <= 0.0 {
+            return Err(CoreError::Validation("gross pay must be positive".into()));
+        }
+        if !(0.0..=100.0).contains(&model.withholding_percent) {
+            return Err(CoreError::Validation(
+                "withholding must be between 0 and 100 percent".into(),
+            ));
+        }
+        if !model.benefits.is_finite() || model.benefits < 0.0 {
+            return Err(CoreError::Validation(
+                "benefit deductions cannot be negative".into(),
+            ));
+        }
+        if model.net() < 0.0 {
+            return Err(CoreError::Validation("deductions exceed gross pay".into()));
+        }
+        Ok(())
+    }
+
+    fn missing_model() -> CoreError {
+        CoreError::InvalidOperation("transaction has no income model".into())
+    }
+}
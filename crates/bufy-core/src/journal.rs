@@ -0,0 +1,256 @@
+//! Change journal of reversible ledger edits, backing undo and redo.
+//!
+//! The `*_journaled` methods on [`AccountService`], [`CategoryService`], and
+//! [`TransactionService`] record a [`JournalEntry`] holding enough state to
+//! reverse the edit and to apply it again.
+//!
+//! [`AccountService`]: crate::account_service::AccountService
+//! [`CategoryService`]: crate::category_service::CategoryService
+//! [`TransactionService`]: crate::transaction_service::TransactionService
+
+use uuid::Uuid;
+
+use bufy_domain::{
+    account::Account, category::Category, transaction::Transaction, transfer_rule::TransferRule,
+    Ledger,
+};
+
+use crate::{
+    account_service::AccountService, category_service::CategoryService,
+    transaction_service::TransactionService, CoreError,
+};
+
+/// Edits kept for undo; older ones are dropped.
+pub const JOURNAL_DEPTH: usize = 100;
+
+/// One reversible edit, with the state before and after it.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum JournalEntry {
+    AccountAdded(Account),
+    AccountEdited {
+        before: Account,
+        after: Account,
+    },
+    AccountRemoved {
+        index: usize,
+        account: Account,
+        /// Transfer rules removed along with the account.
+        transfer_rules: Vec<TransferRule>,
+    },
+    CategoryAdded(Category),
+    CategoryEdited {
+        before: Category,
+        after: Category,
+    },
+    CategoryRemoved {
+        index: usize,
+        category: Category,
+        /// Groups the category belonged to.
+        group_ids: Vec<Uuid>,
+    },
+    TransactionAdded(Transaction),
+    TransactionEdited {
+        before: Transaction,
+        after: Transaction,
+    },
+    TransactionRemoved {
+        index: usize,
+        transaction: Transaction,
+    },
+}
+
+impl JournalEntry {
+    /// Short description such as ``add account `Checking` ``.
+    pub fn describe(&self) -> String {
+        match self {
+            JournalEntry::AccountAdded(account) => format!("add account `{}`", account.name),
+            JournalEntry::AccountEdited { after, .. } => format!("edit account `{}`", after.name),
+            JournalEntry::AccountRemoved { account, .. } => {
+                format!("remove account `{}`", account.name)
+            }
+            JournalEntry::CategoryAdded(category) => format!("add category `{}`", category.name),
+            JournalEntry::CategoryEdited { after, .. } => {
+                format!("edit category `{}`", after.name)
+            }
+            JournalEntry::CategoryRemoved { category, .. } => {
+                format!("remove category `{}`", category.name)
+            }
+            JournalEntry::TransactionAdded(txn) => {
+                format!("add transaction {}", transaction_label(txn))
+            }
+            JournalEntry::TransactionEdited { after, .. } => {
+                format!("edit transaction {}", transaction_label(after))
+            }
+            JournalEntry::TransactionRemoved { transaction, .. } => {
+                format!("remove transaction {}", transaction_label(transaction))
+            }
+        }
+    }
+
+    /// Reverses the edit on `ledger`.
+    pub fn revert(&self, ledger: &mut Ledger) -> Result<(), CoreError> {
+        match self {
+            JournalEntry::AccountAdded(account) => AccountService::remove(ledger, account.id),
+            JournalEntry::AccountEdited { before, .. } => replace_account(ledger, before),
+            JournalEntry::AccountRemoved {
+                index,
+                account,
+                transfer_rules,
+            } => {
+                let index = (*index).min(ledger.accounts.len());
+                ledger.accounts.insert(index, account.clone());
+                ledger.transfer_rules.extend(transfer_rules.iter().cloned());
+                ledger.touch();
+                Ok(())
+            }
+            JournalEntry::CategoryAdded(category) => CategoryService::remove(ledger, category.id),
+            JournalEntry::CategoryEdited { before, .. } => replace_category(ledger, before),
+            JournalEntry::CategoryRemoved {
+                index,
+                category,
+                group_ids,
+            } => {
+                let index = (*index).min(ledger.categories.len());
+                ledger.categories.insert(index, category.clone());
+                for group in &mut ledger.category_groups {
+                    if group_ids.contains(&group.id) {
+                        group.category_ids.push(category.id);
+                    }
+                }
+                ledger.touch();
+                Ok(())
+            }
+            JournalEntry::TransactionAdded(txn) => {
+                TransactionService::remove(ledger, txn.id).map(|_| ())
+            }
+            JournalEntry::TransactionEdited { before, .. } => replace_transaction(ledger, before),
+            JournalEntry::TransactionRemoved { index, transaction } => {
+                let index = (*index).min(ledger.transactions.len());
+                ledger.transactions.insert(index, transaction.clone());
+                ledger.refresh_recurrence_metadata();
+                ledger.touch();
+                Ok(())
+            }
+        }
+    }
+
+    /// Applies the edit to `ledger` again after it was reverted.
+    pub fn reapply(&self, ledger: &mut Ledger) -> Result<(), CoreError> {
+        match self {
+            JournalEntry::AccountAdded(account) => AccountService::add(ledger, account.clone()),
+            JournalEntry::AccountEdited { after, .. } => replace_account(ledger, after),
+            JournalEntry::AccountRemoved { account, .. } => {
+                AccountService::remove(ledger, account.id)
+            }
+            JournalEntry::CategoryAdded(category) => CategoryService::add(ledger, category.clone()),
+            JournalEntry::CategoryEdited { after, .. } => replace_category(ledger, after),
+            JournalEntry::CategoryRemoved { category, .. } => {
+                CategoryService::remove(ledger, category.id)
+            }
+            JournalEntry::TransactionAdded(txn) => {
+                TransactionService::add(ledger, txn.clone()).map(|_| ())
+            }
+            JournalEntry::TransactionEdited { after, .. } => replace_transaction(ledger, after),
+            JournalEntry::TransactionRemoved { transaction, .. } => {
+                TransactionService::remove(ledger, transaction.id).map(|_| ())
+            }
+        }
+    }
+}
+
+/// Undo and redo stacks of [`JournalEntry`] values for one ledger.
+#[derive(Debug, Default)]
+pub struct ChangeJournal {
+    undo: Vec<JournalEntry>,
+    redo: Vec<JournalEntry>,
+}
+
+impl ChangeJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a new edit. Anything that could be redone is forgotten.
+    pub fn record(&mut self, entry: JournalEntry) {
+        self.redo.clear();
+        self.undo.push(entry);
+        if self.undo.len() > JOURNAL_DEPTH {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Reverts the latest edit, returning its description, or `None` when
+    /// there is nothing to undo. A failed revert stays on the undo stack.
+    pub fn undo(&mut self, ledger: &mut Ledger) -> Result<Option<String>, CoreError> {
+        let Some(entry) = self.undo.pop() else {
+            return Ok(None);
+        };
+        if let Err(err) = entry.revert(ledger) {
+            self.undo.push(entry);
+            return Err(err);
+        }
+        let description = entry.describe();
+        self.redo.push(entry);
+        Ok(Some(description))
+    }
+
+    /// Applies the latest undone edit again, returning its description, or
+    /// `None` when there is nothing to redo.
+    pub fn redo(&mut self, ledger: &mut Ledger) -> Result<Option<String>, CoreError> {
+        let Some(entry) = self.redo.pop() else {
+            return Ok(None);
+        };
+        if let Err(err) = entry.reapply(ledger) {
+            self.redo.push(entry);
+            return Err(err);
+        }
+        let description = entry.describe();
+        self.undo.push(entry);
+        Ok(Some(description))
+    }
+
+    /// Descriptions of the edits `undo` would revert, newest first.
+    pub fn undo_history(&self) -> Vec<String> {
+        self.undo.iter().rev().map(JournalEntry::describe).collect()
+    }
+
+    /// Descriptions of the edits `redo` would apply, next first.
+    pub fn redo_history(&self) -> Vec<String> {
+        self.redo.iter().rev().map(JournalEntry::describe).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+fn transaction_label(txn: &Transaction) -> String {
+    match txn.short_ref() {
+        Some(code) => format!("{} of {:.2}", code, txn.budgeted_amount),
+        None => format!("of {:.2} on {}", txn.budgeted_amount, txn.scheduled_date),
+    }
+}
+
+fn replace_account(ledger: &mut Ledger, state: &Account) -> Result<(), CoreError> {
+    let account = ledger
+        .account_mut(state.id)
+        .ok_or_else(|| CoreError::AccountNotFound(state.id.to_string()))?;
+    *account = state.clone();
+    ledger.touch();
+    Ok(())
+}
+
+fn replace_category(ledger: &mut Ledger, state: &Category) -> Result<(), CoreError> {
+    let category = ledger
+        .category_mut(state.id)
+        .ok_or_else(|| CoreError::CategoryNotFound(state.id.to_string()))?;
+    *category = state.clone();
+    ledger.touch();
+    Ok(())
+}
+
+fn replace_transaction(ledger: &mut Ledger, state: &Transaction) -> Result<(), CoreError> {
+    TransactionService::update(ledger, state.id, |txn| *txn = state.clone())
+}
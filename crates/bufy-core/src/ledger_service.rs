@@ -1,32 +1,68 @@
 //! Helper functions for high-level ledger orchestration.
 
 use chrono::NaiveDate;
+use tracing::instrument;
 
 use bufy_domain::{ledger::DateWindow, Ledger, LedgerBudgetPeriod};
 
+use crate::{merge::merge_ledgers, CoreError, LedgerMerge};
+
 /// Provides constructor and mutation helpers for [`Ledger`] instances.
 pub struct LedgerService;
 
 impl LedgerService {
     /// Creates a new ledger with the supplied name and budgeting period.
+    #[instrument(level = "debug", skip_all)]
     pub fn create(name: impl Into<String>, period: LedgerBudgetPeriod) -> Ledger {
         Ledger::new(name, period)
     }
 
     /// Renames a ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn rename(ledger: &mut Ledger, new_name: impl Into<String>) {
         ledger.name = new_name.into();
         ledger.touch();
     }
 
     /// Updates the budgeting cadence for the ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn set_budget_period(ledger: &mut Ledger, period: LedgerBudgetPeriod) {
         ledger.budget_period = period;
         ledger.touch();
     }
 
     /// Returns the budgeting window that contains `reference`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            reference = %reference
+        )
+    )]
     pub fn budget_window_containing(ledger: &Ledger, reference: NaiveDate) -> DateWindow {
         ledger.budget_window_containing(reference)
     }
+
+    /// Three-way merges two copies of a ledger that diverged from `base`.
+    /// Conflicts keep our value until resolved on the returned
+    /// [`LedgerMerge`].
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %base.id, revision = %base.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn merge(base: &Ledger, theirs: &Ledger, ours: &Ledger) -> Result<LedgerMerge, CoreError> {
+        merge_ledgers(base, theirs, ours)
+    }
 }
@@ -4,34 +4,104 @@
 //! Depends on bufy-domain. No CLI, no terminal I/O, no direct storage interactions.
 
 pub mod account_service;
+pub mod alert_service;
+pub mod allocation_service;
+pub mod asset_service;
+pub mod attachment_service;
+pub mod audit;
+pub mod bank_service;
 pub mod budget_service;
+pub mod calendar_export;
+pub mod categorization_service;
+pub mod category_group_service;
 pub mod category_service;
+pub mod change_feed_service;
+pub mod contract_service;
+pub mod draft_service;
 pub mod error;
+pub mod exchange_rate_service;
+pub mod export_service;
 pub mod forecast_service;
 pub mod format;
+pub mod goal_service;
+pub mod heatmap_service;
+pub mod holiday_service;
+pub mod import_service;
+pub mod income_service;
+pub mod journal;
 pub mod ledger_service;
+pub mod member_service;
+pub mod merchant_service;
+pub mod merge;
+pub mod migration;
+pub mod paycheck_service;
 pub mod public_api;
+pub mod reconciliation_service;
 pub mod recurrence_service;
+pub mod retention_service;
+pub mod review_service;
 pub mod simulation_service;
+pub mod sinking_fund_service;
+pub mod stats;
 pub mod storage;
+pub mod sub_ledger_service;
 pub mod summary_service;
 pub mod time;
 pub mod transaction_service;
+pub mod transfer_rule_service;
+pub mod uncategorized_service;
+pub mod validation;
+pub mod view_service;
 
 pub use account_service::*;
+pub use alert_service::*;
+pub use allocation_service::*;
+pub use asset_service::*;
+pub use attachment_service::*;
+pub use audit::{AuditAction, AuditChange, AuditEntry, AuditService, LEDGER_ENTITY};
+pub use bank_service::*;
 pub use budget_service::*;
+pub use calendar_export::CalendarEvent;
+pub use categorization_service::*;
+pub use category_group_service::*;
 pub use category_service::*;
-pub use error::CoreError;
+pub use change_feed_service::*;
+pub use contract_service::*;
+pub use draft_service::*;
+pub use error::{CoreError, WriteStage};
+pub use exchange_rate_service::*;
+pub use export_service::*;
 pub use forecast_service::*;
 pub use format::{CurrencyFormatter, DateFormatter};
+pub use goal_service::*;
+pub use heatmap_service::*;
+pub use holiday_service::{HolidayImport, HolidayService};
+pub use import_service::*;
+pub use income_service::*;
+pub use journal::{ChangeJournal, JournalEntry, JOURNAL_DEPTH};
 pub use ledger_service::*;
+pub use member_service::*;
+pub use merchant_service::*;
+pub use merge::{LedgerMerge, MergeConflict, MergeSide};
+pub use migration::{check_schema_version, LedgerMigrator, MigrationStep};
+pub use paycheck_service::*;
 pub use public_api::*;
+pub use reconciliation_service::*;
 pub use recurrence_service::*;
+pub use retention_service::*;
+pub use review_service::*;
 pub use simulation_service::*;
+pub use sinking_fund_service::*;
+pub use stats::{AccountStats, CategoryStats, TransactionHighlight, STATS_AVERAGE_MONTHS};
 pub use storage::*;
+pub use sub_ledger_service::*;
 pub use summary_service::*;
 pub use time::Clock;
 pub use transaction_service::*;
+pub use transfer_rule_service::*;
+pub use uncategorized_service::*;
+pub use validation::{AmountRule, ValidationIssue, ValidationIssueKind};
+pub use view_service::*;
 
 #[cfg(test)]
 mod tests;
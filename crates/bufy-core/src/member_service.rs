@@ -0,0 +1,123 @@
+//! Management of household members and transaction attribution.
+
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{member::Member, Ledger};
+
+use crate::CoreError;
+
+pub struct MemberService;
+
+impl MemberService {
+    /// Adds a member, rejecting blank or duplicate names.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn add(ledger: &mut Ledger, name: &str) -> Result<Uuid, CoreError> {
+        let name = Self::validate_name(ledger, None, name)?;
+        let member = Member::new(name);
+        let id = member.id;
+        ledger.members.push(member);
+        ledger.touch();
+        Ok(id)
+    }
+
+    /// Renames the member identified by `id`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn rename(ledger: &mut Ledger, id: Uuid, new_name: &str) -> Result<(), CoreError> {
+        let new_name = Self::validate_name(ledger, Some(id), new_name)?;
+        let member = ledger
+            .members
+            .iter_mut()
+            .find(|member| member.id == id)
+            .ok_or_else(|| CoreError::MemberNotFound(id.to_string()))?;
+        member.name = new_name;
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Removes a member and clears their attribution from every transaction.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn remove(ledger: &mut Ledger, id: Uuid) -> Result<Member, CoreError> {
+        let index = ledger
+            .members
+            .iter()
+            .position(|member| member.id == id)
+            .ok_or_else(|| CoreError::MemberNotFound(id.to_string()))?;
+        let removed = ledger.members.remove(index);
+        for txn in ledger
+            .transactions
+            .iter_mut()
+            .filter(|txn| txn.member_id == Some(id))
+        {
+            txn.member_id = None;
+        }
+        ledger.touch();
+        Ok(removed)
+    }
+
+    /// Attributes transaction `txn_id` to `member_id`, or clears it with `None`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            txn_id = %txn_id,
+            member_id = ?member_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn assign(
+        ledger: &mut Ledger,
+        txn_id: Uuid,
+        member_id: Option<Uuid>,
+    ) -> Result<(), CoreError> {
+        if let Some(id) = member_id {
+            if ledger.member(id).is_none() {
+                return Err(CoreError::MemberNotFound(id.to_string()));
+            }
+        }
+        let txn = ledger
+            .transaction_mut(txn_id)
+            .ok_or(CoreError::TransactionNotFound(txn_id))?;
+        txn.member_id = member_id;
+        ledger.touch();
+        Ok(())
+    }
+
+    fn validate_name(
+        ledger: &Ledger,
+        exclude: Option<Uuid>,
+        candidate: &str,
+    ) -> Result<String, CoreError> {
+        let trimmed = candidate.trim();
+        if trimmed.is_empty() {
+            return Err(CoreError::Validation("member name cannot be empty".into()));
+        }
+        if ledger
+            .member_by_name(trimmed)
+            .is_some_and(|member| exclude != Some(member.id))
+        {
+            return Err(CoreError::Validation(format!(
+                "member `{}` already exists",
+                trimmed
+            )));
+        }
+        Ok(trimmed.to_string())
+    }
+}
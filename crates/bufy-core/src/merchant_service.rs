@@ -0,0 +1,137 @@
+//! Merchant and location metadata on transactions, and spend by merchant.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    account::AccountKind, category::CategoryKind, transaction::GeoLocation, DateWindow, Ledger,
+    Transaction,
+};
+
+use crate::{stats::completed_movement, CoreError};
+
+/// Completed spend at one merchant within a report window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerchantSpend {
+    pub merchant: String,
+    /// Spend in the ledger's base currency.
+    pub total: f64,
+    pub transactions: usize,
+    pub last_date: NaiveDate,
+}
+
+/// Merchants ranked by spend, largest first.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MerchantReport {
+    pub merchants: Vec<MerchantSpend>,
+    /// Matching transactions whose amount could not be converted to the base
+    /// currency and were left out.
+    pub unconverted: usize,
+}
+
+pub struct MerchantService;
+
+impl MerchantService {
+    /// Sets or clears a transaction's merchant and location. Blank merchant
+    /// names are treated as none.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), txn_id = %txn_id),
+        err(level = "debug")
+    )]
+    pub fn set_metadata(
+        ledger: &mut Ledger,
+        txn_id: Uuid,
+        merchant: Option<String>,
+        location: Option<GeoLocation>,
+    ) -> Result<(), CoreError> {
+        if let Some(location) = &location {
+            if !location.is_valid() {
+                return Err(CoreError::Validation(format!(
+                    "location {}, {} is not a valid latitude and longitude",
+                    location.latitude, location.longitude
+                )));
+            }
+        }
+        let txn = ledger
+            .transaction_mut(txn_id)
+            .ok_or(CoreError::TransactionNotFound(txn_id))?;
+        txn.merchant = merchant
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+        txn.location = location;
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Totals completed spending with a merchant inside `window`, grouping
+    /// merchant names case-insensitively. Returns at most `limit` merchants.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn top_merchants(ledger: &Ledger, window: &DateWindow, limit: usize) -> MerchantReport {
+        let ctx = ledger.conversion_context(window.end);
+        let mut report = MerchantReport::default();
+        let mut totals: HashMap<String, MerchantSpend> = HashMap::new();
+        for txn in &ledger.transactions {
+            let Some(merchant) = txn.merchant.as_deref() else {
+                continue;
+            };
+            if !Self::is_spending(ledger, txn) {
+                continue;
+            }
+            let Some((_, amount)) = completed_movement(txn) else {
+                continue;
+            };
+            let Some(date) = txn
+                .reporting_date(ledger.summary_date_basis)
+                .filter(|date| window.contains(*date))
+            else {
+                continue;
+            };
+            let currency = ledger.transaction_currency(txn);
+            let Ok(converted) = ledger.convert_amount(amount, &currency, date, &ctx) else {
+                report.unconverted += 1;
+                continue;
+            };
+            let entry = totals
+                .entry(merchant.to_lowercase())
+                .or_insert_with(|| MerchantSpend {
+                    merchant: merchant.to_string(),
+                    total: 0.0,
+                    transactions: 0,
+                    last_date: date,
+                });
+            entry.total += converted.amount;
+            entry.transactions += 1;
+            entry.last_date = entry.last_date.max(date);
+        }
+        report.merchants = totals.into_values().collect();
+        report.merchants.sort_by(|a, b| {
+            b.total
+                .total_cmp(&a.total)
+                .then_with(|| a.merchant.cmp(&b.merchant))
+        });
+        report.merchants.truncate(limit);
+        report
+    }
+
+    /// Money paid to an expense destination or booked to a visible expense
+    /// category.
+    pub(crate) fn is_spending(ledger: &Ledger, txn: &Transaction) -> bool {
+        let category = txn.category_id.and_then(|id| ledger.category(id));
+        if category.is_some_and(|category| category.hidden) {
+            return false;
+        }
+        let to_expense = ledger
+            .account(txn.to_account)
+            .is_some_and(|account| account.kind == AccountKind::ExpenseDestination);
+        to_expense || category.is_some_and(|category| category.kind == CategoryKind::Expense)
+    }
+}
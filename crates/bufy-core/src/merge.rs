@@ -0,0 +1,343 @@
+//! Three-way merge of two copies of a ledger that diverged from a common
+//! base, such as a file synced between devices that was edited on both.
+//!
+//! Records are matched by id across the three copies. A record changed on
+//! one side only takes that side's version; a record changed on both sides
+//! is merged field by field. Whatever cannot be merged becomes a
+//! [`MergeConflict`] that keeps our value until it is resolved.
+
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use bufy_domain::Ledger;
+
+use crate::{
+    audit::{label, LEDGER_ENTITY},
+    change_feed_service::{records, to_object},
+    CoreError,
+};
+
+/// Ledger fields that advance on every edit; the merge keeps the later one.
+const ADVANCING_FIELDS: &[&str] = &["updated_at", "next_reference"];
+
+/// Which copy's value a conflict keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeSide {
+    #[default]
+    Ours,
+    Theirs,
+}
+
+impl fmt::Display for MergeSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MergeSide::Ours => "ours",
+            MergeSide::Theirs => "theirs",
+        })
+    }
+}
+
+/// A value both copies changed in different ways.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// Collection such as `accounts` or `transactions`, or [`LEDGER_ENTITY`].
+    pub entity: String,
+    /// Record id, or the field name for ledger-level values.
+    pub entity_id: String,
+    /// Record name or transaction reference, when it has one.
+    pub label: Option<String>,
+    /// Record field both sides edited; `None` when one side removed the
+    /// record the other edited, or for ledger-level values.
+    pub field: Option<String>,
+    pub base: Option<Value>,
+    pub theirs: Option<Value>,
+    pub ours: Option<Value>,
+    pub resolution: MergeSide,
+}
+
+impl MergeConflict {
+    /// Short description such as ``transactions T-0004 `amount` ``.
+    pub fn describe(&self) -> String {
+        let record = self.label.as_deref().unwrap_or(&self.entity_id);
+        let mut text = if self.entity == LEDGER_ENTITY {
+            format!("ledger `{}`", self.entity_id)
+        } else {
+            format!("{} {}", self.entity, record)
+        };
+        match &self.field {
+            Some(field) => text.push_str(&format!(" `{}`", field)),
+            None if self.entity != LEDGER_ENTITY => {
+                let removed = if self.ours.is_none() {
+                    "ours"
+                } else {
+                    "theirs"
+                };
+                text.push_str(&format!(" (removed in {})", removed));
+            }
+            None => {}
+        }
+        text
+    }
+
+    /// The value the conflict currently keeps; `None` means absent.
+    pub fn chosen(&self) -> Option<&Value> {
+        match self.resolution {
+            MergeSide::Ours => self.ours.as_ref(),
+            MergeSide::Theirs => self.theirs.as_ref(),
+        }
+    }
+}
+
+/// Result of a three-way merge, pending conflict resolution.
+#[derive(Debug, Clone)]
+pub struct LedgerMerge {
+    merged: Map<String, Value>,
+    conflicts: Vec<MergeConflict>,
+}
+
+impl LedgerMerge {
+    pub fn conflicts(&self) -> &[MergeConflict] {
+        &self.conflicts
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Chooses which copy's value conflict `index` keeps.
+    pub fn resolve(&mut self, index: usize, side: MergeSide) -> Result<(), CoreError> {
+        let conflict = self.conflicts.get_mut(index).ok_or_else(|| {
+            CoreError::Validation(format!("no merge conflict numbered {}", index + 1))
+        })?;
+        conflict.resolution = side;
+        Ok(())
+    }
+
+    pub fn resolve_all(&mut self, side: MergeSide) {
+        for conflict in &mut self.conflicts {
+            conflict.resolution = side;
+        }
+    }
+
+    /// Builds the merged ledger with every conflict's chosen value.
+    /// Transactions added on both sides under the same reference keep ours;
+    /// theirs are renumbered.
+    pub fn into_ledger(self) -> Result<Ledger, CoreError> {
+        let mut merged = self.merged;
+        for conflict in &self.conflicts {
+            apply_choice(&mut merged, conflict);
+        }
+        let mut ledger: Ledger = serde_json::from_value(Value::Object(merged))
+            .map_err(|err| CoreError::Serde(err.to_string()))?;
+        ledger.assign_references();
+        ledger.refresh_recurrence_metadata();
+        Ok(ledger)
+    }
+}
+
+/// Merges `theirs` and `ours`, both descended from `base`.
+pub(crate) fn merge_ledgers(
+    base: &Ledger,
+    theirs: &Ledger,
+    ours: &Ledger,
+) -> Result<LedgerMerge, CoreError> {
+    let base = to_object(base)?;
+    let theirs = to_object(theirs)?;
+    let ours = to_object(ours)?;
+    let mut fields: Vec<&String> = ours.keys().collect();
+    for field in theirs.keys().chain(base.keys()) {
+        if !fields.contains(&field) {
+            fields.push(field);
+        }
+    }
+
+    let mut merged = Map::new();
+    let mut conflicts = Vec::new();
+    for field in fields {
+        let (b, t, o) = (base.get(field), theirs.get(field), ours.get(field));
+        if ADVANCING_FIELDS.contains(&field.as_str()) {
+            if let Some(value) = later(t, o) {
+                merged.insert(field.clone(), value.clone());
+            }
+            continue;
+        }
+        match (records(b), records(t), records(o)) {
+            (Some(b), Some(t), Some(o)) => {
+                let items = merge_records(field, &b, &t, &o, &mut conflicts);
+                if !items.is_empty() || ours.contains_key(field) || theirs.contains_key(field) {
+                    merged.insert(field.clone(), Value::Array(items));
+                }
+            }
+            _ => {
+                let value = match pick(b, t, o) {
+                    Some(value) => value,
+                    None => {
+                        conflicts.push(MergeConflict {
+                            entity: LEDGER_ENTITY.into(),
+                            entity_id: field.clone(),
+                            label: None,
+                            field: None,
+                            base: b.cloned(),
+                            theirs: t.cloned(),
+                            ours: o.cloned(),
+                            resolution: MergeSide::Ours,
+                        });
+                        o
+                    }
+                };
+                if let Some(value) = value {
+                    merged.insert(field.clone(), value.clone());
+                }
+            }
+        }
+    }
+    Ok(LedgerMerge { merged, conflicts })
+}
+
+/// Merges one record collection, keeping our order and appending records
+/// only they added.
+fn merge_records(
+    entity: &str,
+    base: &[(&str, &Value)],
+    theirs: &[(&str, &Value)],
+    ours: &[(&str, &Value)],
+    conflicts: &mut Vec<MergeConflict>,
+) -> Vec<Value> {
+    let mut ids: Vec<&str> = ours.iter().map(|(id, _)| *id).collect();
+    for (id, _) in theirs.iter().chain(base) {
+        if !ids.contains(id) {
+            ids.push(id);
+        }
+    }
+
+    let mut items = Vec::new();
+    for id in ids {
+        let (b, t, o) = (find(base, id), find(theirs, id), find(ours, id));
+        if let Some(record) = pick(b, t, o) {
+            items.extend(record.cloned());
+            continue;
+        }
+        let record_label = o.or(t).and_then(|record| label(entity, record));
+        match (t, o) {
+            (Some(Value::Object(t)), Some(Value::Object(o))) => {
+                let b = b.and_then(Value::as_object);
+                let mut keys: Vec<&String> = o.keys().collect();
+                for key in t.keys().chain(b.into_iter().flat_map(|b| b.keys())) {
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+                let mut record = Map::new();
+                for key in keys {
+                    let base_value = b.and_then(|b| b.get(key));
+                    let value = match pick(base_value, t.get(key), o.get(key)) {
+                        Some(value) => value,
+                        None => {
+                            conflicts.push(MergeConflict {
+                                entity: entity.to_string(),
+                                entity_id: id.to_string(),
+                                label: record_label.clone(),
+                                field: Some(key.clone()),
+                                base: base_value.cloned(),
+                                theirs: t.get(key).cloned(),
+                                ours: o.get(key).cloned(),
+                                resolution: MergeSide::Ours,
+                            });
+                            o.get(key)
+                        }
+                    };
+                    if let Some(value) = value {
+                        record.insert(key.clone(), value.clone());
+                    }
+                }
+                items.push(Value::Object(record));
+            }
+            _ => {
+                conflicts.push(MergeConflict {
+                    entity: entity.to_string(),
+                    entity_id: id.to_string(),
+                    label: record_label,
+                    field: None,
+                    base: b.cloned(),
+                    theirs: t.cloned(),
+                    ours: o.cloned(),
+                    resolution: MergeSide::Ours,
+                });
+                items.extend(o.cloned());
+            }
+        }
+    }
+    items
+}
+
+fn find<'a>(items: &[(&str, &'a Value)], id: &str) -> Option<&'a Value> {
+    items
+        .iter()
+        .find(|(item_id, _)| *item_id == id)
+        .map(|(_, record)| *record)
+}
+
+/// The merged value when at most one side changed it (or both changed it
+/// the same way); `None` when the sides disagree.
+fn pick<'a>(
+    base: Option<&'a Value>,
+    theirs: Option<&'a Value>,
+    ours: Option<&'a Value>,
+) -> Option<Option<&'a Value>> {
+    if theirs == ours || theirs == base {
+        Some(ours)
+    } else if ours == base {
+        Some(theirs)
+    } else {
+        None
+    }
+}
+
+/// The larger of two timestamps or counters.
+fn later<'a>(theirs: Option<&'a Value>, ours: Option<&'a Value>) -> Option<&'a Value> {
+    match (theirs, ours) {
+        (Some(t), Some(o)) => {
+            let theirs_later = match (t, o) {
+                (Value::Number(t), Value::Number(o)) => t.as_f64() > o.as_f64(),
+                (Value::String(t), Value::String(o)) => t > o,
+                _ => false,
+            };
+            Some(if theirs_later { t } else { o })
+        }
+        (t, o) => o.or(t),
+    }
+}
+
+fn apply_choice(merged: &mut Map<String, Value>, conflict: &MergeConflict) {
+    let chosen = conflict.chosen().cloned();
+    if conflict.entity == LEDGER_ENTITY {
+        match chosen {
+            Some(value) => merged.insert(conflict.entity_id.clone(), value),
+            None => merged.remove(&conflict.entity_id),
+        };
+        return;
+    }
+    let Some(Value::Array(items)) = merged.get_mut(&conflict.entity) else {
+        return;
+    };
+    let position = items
+        .iter()
+        .position(|item| item.get("id").and_then(Value::as_str) == Some(&conflict.entity_id));
+    match (&conflict.field, position, chosen) {
+        (Some(field), Some(index), chosen) => {
+            if let Some(record) = items[index].as_object_mut() {
+                match chosen {
+                    Some(value) => record.insert(field.clone(), value),
+                    None => record.remove(field),
+                };
+            }
+        }
+        (None, Some(index), Some(record)) => items[index] = record,
+        (None, Some(index), None) => {
+            items.remove(index);
+        }
+        (None, None, Some(record)) => items.push(record),
+        _ => {}
+    }
+}
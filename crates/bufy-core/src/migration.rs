@@ -0,0 +1,154 @@
+//! Forward migrations for ledgers stored with an older schema version.
+//!
+//! Each schema bump registers one [`MigrationStep`] with the builtin
+//! [`LedgerMigrator`]. Loading runs every step newer than the stored version,
+//! oldest first, and reports a note per step.
+
+use bufy_domain::{
+    CurrencyCode, FormatOptions, Ledger, LocaleConfig, ValuationPolicy, CURRENT_SCHEMA_MINOR,
+    CURRENT_SCHEMA_VERSION,
+};
+
+use crate::CoreError;
+
+/// Upgrades a ledger from schema `to - 1` to schema `to`.
+#[derive(Debug, Clone, Copy)]
+pub struct MigrationStep {
+    /// Schema version the ledger has once this step ran.
+    pub to: u8,
+    /// What the step changed, reported when it runs.
+    pub note: &'static str,
+    pub apply: fn(&mut Ledger) -> Result<(), CoreError>,
+}
+
+/// Ordered migration steps that bring a stored ledger up to
+/// [`CURRENT_SCHEMA_VERSION`].
+#[derive(Debug, Clone)]
+pub struct LedgerMigrator {
+    steps: Vec<MigrationStep>,
+}
+
+impl Default for LedgerMigrator {
+    /// Every migration this build knows.
+    fn default() -> Self {
+        Self::empty()
+            .register(
+                3,
+                "refreshed recurrence metadata for schema v3",
+                refresh_recurrence_metadata,
+            )
+            .register(
+                4,
+                "initialized currency/localization defaults for schema v4",
+                init_currency_defaults,
+            )
+            .register(
+                5,
+                "counted stored amounts in minor units of their currency for schema v5",
+                count_amounts_in_minor_units,
+            )
+    }
+}
+
+impl LedgerMigrator {
+    /// A migrator without steps; loading only stamps the current version.
+    pub fn empty() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    /// Adds the step that upgrades ledgers to schema `to`.
+    ///
+    /// # Panics
+    ///
+    /// When `to` is not above every registered step or above
+    /// [`CURRENT_SCHEMA_VERSION`]; both are programming errors.
+    pub fn register(
+        mut self,
+        to: u8,
+        note: &'static str,
+        apply: fn(&mut Ledger) -> Result<(), CoreError>,
+    ) -> Self {
+        assert!(
+            self.steps.last().is_none_or(|last| last.to < to),
+            "migration to v{to} registered out of order"
+        );
+        assert!(
+            to <= CURRENT_SCHEMA_VERSION,
+            "migration to v{to} is newer than schema v{CURRENT_SCHEMA_VERSION}"
+        );
+        self.steps.push(MigrationStep { to, note, apply });
+        self
+    }
+
+    pub fn steps(&self) -> &[MigrationStep] {
+        &self.steps
+    }
+
+    /// Runs every step newer than the ledger's stored schema and stamps it
+    /// with the current version. Returns one note per change; nothing when
+    /// the ledger was already current. Ledgers from a newer major schema are
+    /// rejected untouched.
+    pub fn migrate(&self, ledger: &mut Ledger) -> Result<Vec<String>, CoreError> {
+        let from = ledger.schema_version;
+        check_schema_version(from)?;
+        let mut notes = Vec::new();
+        if from < CURRENT_SCHEMA_VERSION {
+            notes.push(format!(
+                "upgraded schema from v{} to v{}",
+                from, CURRENT_SCHEMA_VERSION
+            ));
+        }
+        for step in self.steps.iter().filter(|step| step.to > from) {
+            (step.apply)(ledger)?;
+            ledger.schema_version = step.to;
+            notes.push(step.note.to_string());
+        }
+        // The minor revision is 0 right after a major bump, which makes the
+        // second test always false until the next additive change.
+        #[allow(clippy::absurd_extreme_comparisons)]
+        if from < CURRENT_SCHEMA_VERSION || ledger.schema_minor < CURRENT_SCHEMA_MINOR {
+            ledger.schema_minor = CURRENT_SCHEMA_MINOR;
+        }
+        ledger.schema_version = CURRENT_SCHEMA_VERSION;
+        Ok(notes)
+    }
+}
+
+/// Rejects ledgers stored with a newer major schema than this build reads.
+/// Storage backends call this before reading the rest of the file, so a
+/// newer format is reported as such rather than as a parse error.
+pub fn check_schema_version(version: u8) -> Result<(), CoreError> {
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(CoreError::Storage(format!(
+            "ledger schema v{} is newer than supported v{}",
+            version, CURRENT_SCHEMA_VERSION
+        )));
+    }
+    Ok(())
+}
+
+fn refresh_recurrence_metadata(ledger: &mut Ledger) -> Result<(), CoreError> {
+    ledger.refresh_recurrence_metadata();
+    Ok(())
+}
+
+/// Schema v4 added multi-currency and locale settings.
+fn init_currency_defaults(ledger: &mut Ledger) -> Result<(), CoreError> {
+    if ledger.base_currency.as_str().is_empty() {
+        ledger.base_currency = CurrencyCode::default();
+    }
+    if ledger.locale.language_tag.is_empty() {
+        ledger.locale = LocaleConfig::default();
+    }
+    ledger.format = FormatOptions::default();
+    ledger.valuation_policy = ValuationPolicy::TransactionDate;
+    Ok(())
+}
+
+/// Schema v5 stores amounts as whole minor units with their currency. Older
+/// files kept bare numbers, which load in no currency; this counts each in
+/// the currency it belongs to.
+fn count_amounts_in_minor_units(ledger: &mut Ledger) -> Result<(), CoreError> {
+    ledger.assign_amount_currencies()?;
+    Ok(())
+}
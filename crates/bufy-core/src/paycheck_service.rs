@@ -0,0 +1,315 @@
+//! Paycheck profiles: one salary event expanded into income, deductions, and
+//! allocation transactions.
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    account::AccountKind,
+    paycheck::{PaycheckAmount, PaycheckLine, PaycheckProfile},
+    Ledger, Money, Transaction,
+};
+
+use crate::{Clock, CoreError, TransferRuleService};
+
+/// Which side of a paycheck a line belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaycheckLineKind {
+    Deduction,
+    Allocation,
+}
+
+/// How one paycheck of a given gross amount breaks down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaycheckSplit {
+    pub gross: f64,
+    pub deductions: Vec<(PaycheckLine, f64)>,
+    /// Gross pay less deductions; what reaches the deposit account.
+    pub net: f64,
+    pub allocations: Vec<(PaycheckLine, f64)>,
+    /// Net pay left in the deposit account after allocations.
+    pub unallocated: f64,
+}
+
+/// Transactions recorded for a received paycheck.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaycheckReceipt {
+    pub income_id: Uuid,
+    /// Deduction and allocation transactions, in profile order.
+    pub line_ids: Vec<Uuid>,
+    /// Transfers added by transfer rules on the deposit account.
+    pub transfer_ids: Vec<Uuid>,
+    pub split: PaycheckSplit,
+}
+
+pub struct PaycheckService;
+
+impl PaycheckService {
+    /// Adds a profile paying `gross` from `income_account` into
+    /// `deposit_account`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            income_account = %income_account,
+            deposit_account = %deposit_account,
+            income_category = ?income_category
+        ),
+        err(level = "debug")
+    )]
+    pub fn create(
+        ledger: &mut Ledger,
+        name: &str,
+        income_account: Uuid,
+        deposit_account: Uuid,
+        gross: f64,
+        income_category: Option<Uuid>,
+        clock: &dyn Clock,
+    ) -> Result<Uuid, CoreError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(CoreError::Validation(
+                "paycheck profile name cannot be empty".into(),
+            ));
+        }
+        if ledger.paycheck_profile(name).is_some() {
+            return Err(CoreError::Validation(format!(
+                "paycheck profile `{}` already exists",
+                name
+            )));
+        }
+        Self::validate_positive(gross, "gross pay")?;
+        if income_account == deposit_account {
+            return Err(CoreError::Validation(
+                "pay must come from a different account than it is deposited in".into(),
+            ));
+        }
+        if ledger.account(income_account).is_none() {
+            return Err(CoreError::AccountNotFound(income_account.to_string()));
+        }
+        let deposit = ledger
+            .account(deposit_account)
+            .ok_or_else(|| CoreError::AccountNotFound(deposit_account.to_string()))?;
+        if matches!(
+            deposit.kind,
+            AccountKind::IncomeSource | AccountKind::ExpenseDestination
+        ) {
+            return Err(CoreError::Validation(format!(
+                "pay must be deposited in one of your own accounts, not the {} account `{}`",
+                deposit.kind, deposit.name
+            )));
+        }
+        if let Some(id) = income_category {
+            if ledger.category(id).is_none() {
+                return Err(CoreError::CategoryNotFound(id.to_string()));
+            }
+        }
+        let mut profile =
+            PaycheckProfile::new(name, income_account, deposit_account, gross, clock.now());
+        profile.income_category = income_category;
+        let id = profile.id;
+        ledger.paycheck_profiles.push(profile);
+        ledger.touch();
+        Ok(id)
+    }
+
+    /// Appends a deduction or allocation to the profile called `name`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn add_line(
+        ledger: &mut Ledger,
+        name: &str,
+        kind: PaycheckLineKind,
+        line: PaycheckLine,
+    ) -> Result<(), CoreError> {
+        match line.amount {
+            PaycheckAmount::Percent(percent) if percent > 100.0 => {
+                return Err(CoreError::Validation(
+                    "a percentage cannot exceed 100".into(),
+                ))
+            }
+            PaycheckAmount::Percent(value) | PaycheckAmount::Fixed(value) => {
+                Self::validate_positive(value, "amount")?
+            }
+        }
+        if ledger.account(line.account).is_none() {
+            return Err(CoreError::AccountNotFound(line.account.to_string()));
+        }
+        if let Some(id) = line.category_id {
+            if ledger.category(id).is_none() {
+                return Err(CoreError::CategoryNotFound(id.to_string()));
+            }
+        }
+        let profile = ledger
+            .paycheck_profile_mut(name)
+            .ok_or_else(|| Self::not_found(name))?;
+        if line.account == profile.deposit_account {
+            return Err(CoreError::Validation(
+                "a paycheck line must move money out of the deposit account".into(),
+            ));
+        }
+        let mut candidate = profile.clone();
+        match kind {
+            PaycheckLineKind::Deduction => candidate.deductions.push(line),
+            PaycheckLineKind::Allocation => candidate.allocations.push(line),
+        }
+        Self::split(&candidate, candidate.gross)?;
+        *profile = candidate;
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Removes a profile. Paychecks it already recorded stay in the ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn remove(ledger: &mut Ledger, name: &str) -> Result<PaycheckProfile, CoreError> {
+        let index = ledger
+            .paycheck_profiles
+            .iter()
+            .position(|profile| profile.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| Self::not_found(name))?;
+        let removed = ledger.paycheck_profiles.remove(index);
+        ledger.touch();
+        Ok(removed)
+    }
+
+    /// Breaks a paycheck of `gross` down by the profile's lines. Fails when
+    /// deductions exceed gross pay or allocations exceed net pay.
+    #[instrument(level = "debug", skip_all, err(level = "debug"))]
+    pub fn split(profile: &PaycheckProfile, gross: f64) -> Result<PaycheckSplit, CoreError> {
+        Self::validate_positive(gross, "gross pay")?;
+        let deductions: Vec<_> = profile
+            .deductions
+            .iter()
+            .map(|line| (line.clone(), line.amount.of(gross)))
+            .collect();
+        let net = round_cents(gross - deductions.iter().map(|(_, amount)| amount).sum::<f64>());
+        if net < 0.0 {
+            return Err(CoreError::Validation(format!(
+                "deductions of paycheck `{}` exceed its gross pay",
+                profile.name
+            )));
+        }
+        let allocations: Vec<_> = profile
+            .allocations
+            .iter()
+            .map(|line| (line.clone(), line.amount.of(net)))
+            .collect();
+        let unallocated =
+            round_cents(net - allocations.iter().map(|(_, amount)| amount).sum::<f64>());
+        if unallocated < 0.0 {
+            return Err(CoreError::Validation(format!(
+                "allocations of paycheck `{}` exceed its net pay",
+                profile.name
+            )));
+        }
+        Ok(PaycheckSplit {
+            gross,
+            deductions,
+            net,
+            allocations,
+            unallocated,
+        })
+    }
+
+    /// Records a paycheck from the profile called `name` on `date`: gross
+    /// income into the deposit account, then one completed transaction per
+    /// deduction and allocation, each linked to the income. `gross` overrides
+    /// the profile's usual pay.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), date = %date),
+        err(level = "debug")
+    )]
+    pub fn receive(
+        ledger: &mut Ledger,
+        name: &str,
+        date: NaiveDate,
+        gross: Option<f64>,
+    ) -> Result<PaycheckReceipt, CoreError> {
+        let profile = ledger
+            .paycheck_profile(name)
+            .ok_or_else(|| Self::not_found(name))?
+            .clone();
+        let split = Self::split(&profile, gross.unwrap_or(profile.gross))?;
+
+        let gross = Money::from_major(
+            split.gross,
+            ledger.transfer_currency(profile.income_account, profile.deposit_account),
+        )?;
+        let mut income = Transaction::new(
+            profile.income_account,
+            profile.deposit_account,
+            profile.income_category,
+            date,
+            gross.clone(),
+        );
+        income.notes = Some(format!("Paycheck `{}`", profile.name));
+        income.mark_completed(date, gross);
+        let income_id = ledger.add_transaction(income);
+        // Rules go first: they skip accounts that already hold a transaction
+        // linked to this income, which would include allocation targets.
+        let transfer_ids = TransferRuleService::apply(ledger, income_id)?;
+
+        let lines = split
+            .deductions
+            .iter()
+            .map(|line| (line, "deduction"))
+            .chain(split.allocations.iter().map(|line| (line, "allocation")));
+        let mut line_ids = Vec::new();
+        for ((line, amount), label) in lines {
+            if *amount <= 0.0 {
+                continue;
+            }
+            let amount = Money::from_major(
+                *amount,
+                ledger.transfer_currency(profile.deposit_account, line.account),
+            )?;
+            let mut txn = Transaction::new(
+                profile.deposit_account,
+                line.account,
+                line.category_id,
+                date,
+                amount.clone(),
+            );
+            txn.notes = Some(format!("Paycheck `{}` {}", profile.name, label));
+            txn.source_transaction_id = Some(income_id);
+            txn.mark_completed(date, amount);
+            line_ids.push(ledger.add_transaction(txn));
+        }
+        Ok(PaycheckReceipt {
+            income_id,
+            line_ids,
+            transfer_ids,
+            split,
+        })
+    }
+
+    fn validate_positive(value: f64, what: &str) -> Result<(), CoreError> {
+        if value.is_finite() && value > 0.0 {
+            Ok(())
+        } else {
+            Err(CoreError::Validation(format!("{} must be positive", what)))
+        }
+    }
+
+    fn not_found(name: &str) -> CoreError {
+        CoreError::InvalidOperation(format!("paycheck profile `{}` not found", name.trim()))
+    }
+}
+
+fn round_cents(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
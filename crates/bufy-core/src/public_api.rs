@@ -3,23 +3,48 @@
 //! This module exposes a simplified API that other frontends (CLI, GUI, FFI)
 //! can rely on without depending on the entire service surface area.
 
+use std::{collections::VecDeque, thread};
+
 use chrono::NaiveDate;
+use serde::Serialize;
 use uuid::Uuid;
 
 use bufy_domain::{
     account::{Account, AccountKind},
-    ledger::BudgetScope,
-    transaction::Transaction,
-    Ledger, LedgerBudgetPeriod,
+    alert::BudgetAlert,
+    category::{Category, CategoryKind},
+    common::{TimeInterval, TimeUnit},
+    currency::{format_currency_value, minor_units_for, CurrencyCode},
+    ledger::{
+        BudgetScope, BudgetSummary, BudgetTotals, CategoryBudget, CategoryBudgetSummary,
+        CategoryBudgetSummaryKind,
+    },
+    pending_draft::PendingDraft,
+    recurring::RecurrenceSnapshot,
+    time,
+    transaction::{GeoLocation, RecurrenceStatus, Transaction, TransactionStatus},
+    Ledger, LedgerBudgetPeriod, Money,
 };
 
 use crate::{
-    account_service::AccountService, budget_service::BudgetService, ledger_service::LedgerService,
-    transaction_service::TransactionService, CoreError,
+    account_service::AccountService,
+    alert_service::AlertService,
+    budget_service::BudgetService,
+    category_service::CategoryService,
+    draft_service::DraftService,
+    forecast_service::ForecastService,
+    ledger_service::LedgerService,
+    merchant_service::MerchantService,
+    stats::RunningBalance,
+    transaction_service::{
+        TransactionQuery, TransactionService, TransactionSort, TransactionSortKey,
+    },
+    validation::{check_amount, AmountRule},
+    CoreError,
 };
 
 /// Summarized budgeting totals for a ledger window.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ApiLedgerSummary {
     pub scope: BudgetScope,
     pub window_start: NaiveDate,
@@ -30,6 +55,296 @@ pub struct ApiLedgerSummary {
     pub variance_total: f64,
     pub incomplete_transactions: usize,
     pub orphaned_transactions: usize,
+    /// Alerts active on the reference date.
+    pub alerts: Vec<BudgetAlert>,
+    /// Drafts waiting in the review inbox.
+    pub pending_drafts: usize,
+}
+
+/// An account's balance at the close of one charted period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiBalancePoint {
+    pub start: NaiveDate,
+    /// Last day of the period, inclusive.
+    pub end: NaiveDate,
+    pub balance: f64,
+}
+
+/// Balances of one account over a date range, one point per period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiBalanceSeries {
+    pub account_id: Uuid,
+    pub account_name: String,
+    pub currency: String,
+    pub granularity: TimeUnit,
+    /// Balance at the close of the day before the first period.
+    pub opening_balance: f64,
+    pub points: Vec<ApiBalancePoint>,
+}
+
+/// Projected figures for the budget period once recurring entries land.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiForecastSummary {
+    pub window_start: NaiveDate,
+    pub window_end: NaiveDate,
+    /// Recurring entries projected into the window.
+    pub projected_transactions: usize,
+    pub projected_inflow: f64,
+    pub projected_outflow: f64,
+    pub projected_net: f64,
+    /// Budget totals with the projected entries included.
+    pub projected_totals: BudgetTotals,
+}
+
+/// Where one recurring series stands on the reference date.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiRecurrenceSummary {
+    pub series_id: Uuid,
+    pub template_id: Uuid,
+    pub interval: String,
+    pub next_due: Option<NaiveDate>,
+    pub overdue: usize,
+    pub pending: usize,
+    pub status: RecurrenceStatus,
+}
+
+impl From<RecurrenceSnapshot> for ApiRecurrenceSummary {
+    fn from(snapshot: RecurrenceSnapshot) -> Self {
+        Self {
+            series_id: snapshot.series_id,
+            template_id: snapshot.template_id,
+            interval: snapshot.interval_label,
+            next_due: snapshot.next_due,
+            overdue: snapshot.overdue,
+            pending: snapshot.pending,
+            status: snapshot.status,
+        }
+    }
+}
+
+/// Summary, forecast, and recurrences for one dashboard refresh.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiDashboardSnapshot {
+    pub reference_date: NaiveDate,
+    pub summary: ApiLedgerSummary,
+    pub forecast: ApiForecastSummary,
+    pub recurrences: Vec<ApiRecurrenceSummary>,
+}
+
+/// One transaction as a list row: the stored fields plus the names a screen
+/// shows next to them.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiTransaction {
+    pub id: Uuid,
+    /// Short code such as `T-0012`.
+    pub reference: Option<String>,
+    pub scheduled_date: NaiveDate,
+    pub actual_date: Option<NaiveDate>,
+    pub from_account_id: Uuid,
+    pub from_account: String,
+    pub to_account_id: Uuid,
+    pub to_account: String,
+    pub category_id: Option<Uuid>,
+    pub category: Option<String>,
+    pub budgeted_amount: f64,
+    pub actual_amount: Option<f64>,
+    /// `None` means the ledger base currency.
+    pub currency: Option<String>,
+    pub notes: Option<String>,
+    pub merchant: Option<String>,
+    pub tags: Vec<String>,
+    pub status: TransactionStatus,
+    pub recurring: bool,
+}
+
+impl ApiTransaction {
+    fn new(ledger: &Ledger, transaction: &Transaction) -> Self {
+        let account_name = |id| {
+            ledger
+                .account(id)
+                .map(|account| account.name.clone())
+                .unwrap_or_default()
+        };
+        Self {
+            id: transaction.id,
+            reference: transaction.short_ref().map(|code| code.to_string()),
+            scheduled_date: transaction.scheduled_date,
+            actual_date: transaction.actual_date,
+            from_account_id: transaction.from_account,
+            from_account: account_name(transaction.from_account),
+            to_account_id: transaction.to_account,
+            to_account: account_name(transaction.to_account),
+            category_id: transaction.category_id,
+            category: transaction
+                .category_id
+                .and_then(|id| ledger.category(id))
+                .map(|category| category.name.clone()),
+            budgeted_amount: transaction.budgeted_amount.to_major(),
+            actual_amount: transaction.actual_amount.as_ref().map(Money::to_major),
+            currency: transaction.currency.clone(),
+            notes: transaction.notes.clone(),
+            merchant: transaction.merchant.clone(),
+            tags: transaction.tags.clone(),
+            status: transaction.status.clone(),
+            recurring: transaction.recurrence.is_some()
+                || transaction.recurrence_series_id.is_some(),
+        }
+    }
+}
+
+/// The budget period containing `reference_date` in full: the fields of
+/// [`BudgetSummary`] plus progress against every category budget.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiBudgetSummary {
+    pub reference_date: NaiveDate,
+    #[serde(flatten)]
+    pub summary: BudgetSummary,
+    pub category_budgets: Vec<CategoryBudgetSummary>,
+}
+
+/// Number of summaries an [`ApiSummaryTracker`] keeps to diff against.
+pub const SUMMARY_HISTORY: usize = 8;
+
+/// Budget totals for one category in the summary window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApiCategoryTotals {
+    /// `None` for uncategorized activity.
+    pub category_id: Option<Uuid>,
+    pub name: String,
+    pub totals: BudgetTotals,
+}
+
+impl From<CategoryBudget> for ApiCategoryTotals {
+    fn from(category: CategoryBudget) -> Self {
+        Self {
+            category_id: category.category_id,
+            name: category.name,
+            totals: category.totals,
+        }
+    }
+}
+
+/// What changed in the summary since the revision a client last saw.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiSummaryDelta {
+    /// Revision to pass to the next request.
+    pub revision: u64,
+    /// Revision the delta is relative to; `None` when the client's revision
+    /// was unknown and everything is sent.
+    pub base_revision: Option<u64>,
+    /// The new summary, when any of its figures or alerts changed.
+    pub summary: Option<ApiLedgerSummary>,
+    /// Categories that are new or whose totals changed.
+    pub changed_categories: Vec<ApiCategoryTotals>,
+    /// Categories that no longer have activity in the window.
+    pub removed_categories: Vec<Option<Uuid>>,
+}
+
+impl ApiSummaryDelta {
+    pub fn is_empty(&self) -> bool {
+        self.summary.is_none()
+            && self.changed_categories.is_empty()
+            && self.removed_categories.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct SummaryState {
+    summary: ApiLedgerSummary,
+    categories: Vec<ApiCategoryTotals>,
+}
+
+/// Numbers a ledger's edits and remembers the last summaries it handed out,
+/// so a client can ask for only what changed since its copy.
+#[derive(Debug)]
+pub struct ApiSummaryTracker {
+    revision: u64,
+    sent: VecDeque<(u64, SummaryState)>,
+}
+
+impl Default for ApiSummaryTracker {
+    fn default() -> Self {
+        Self {
+            revision: 1,
+            sent: VecDeque::new(),
+        }
+    }
+}
+
+impl ApiSummaryTracker {
+    /// Current revision; it starts at 1, so 0 never matches and always
+    /// returns the full summary.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Records that the ledger was edited.
+    pub fn bump(&mut self) {
+        self.revision += 1;
+    }
+
+    /// Compares the summary for the period containing `reference_date` with
+    /// the one sent at `last_revision`. Revisions that are unknown or too old
+    /// to still be kept get the full summary.
+    pub fn delta(
+        &mut self,
+        ledger: &Ledger,
+        reference_date: NaiveDate,
+        last_revision: u64,
+    ) -> ApiSummaryDelta {
+        let summary = BudgetService::summarize_period_containing(ledger, reference_date);
+        let current = SummaryState {
+            categories: summary
+                .per_category
+                .iter()
+                .cloned()
+                .map(Into::into)
+                .collect(),
+            summary: ledger_summary(ledger, reference_date, summary),
+        };
+        let base = self
+            .sent
+            .iter()
+            .find(|(revision, _)| *revision == last_revision)
+            .map(|(_, state)| state);
+        let delta = match base {
+            Some(base) => ApiSummaryDelta {
+                revision: self.revision,
+                base_revision: Some(last_revision),
+                summary: (base.summary != current.summary).then(|| current.summary.clone()),
+                changed_categories: current
+                    .categories
+                    .iter()
+                    .filter(|category| !base.categories.contains(category))
+                    .cloned()
+                    .collect(),
+                removed_categories: base
+                    .categories
+                    .iter()
+                    .map(|category| category.category_id)
+                    .filter(|id| {
+                        !current
+                            .categories
+                            .iter()
+                            .any(|category| category.category_id == *id)
+                    })
+                    .collect(),
+            },
+            None => ApiSummaryDelta {
+                revision: self.revision,
+                base_revision: None,
+                summary: Some(current.summary.clone()),
+                changed_categories: current.categories.clone(),
+                removed_categories: Vec::new(),
+            },
+        };
+        self.sent.retain(|(revision, _)| *revision != self.revision);
+        self.sent.push_back((self.revision, current));
+        while self.sent.len() > SUMMARY_HISTORY {
+            self.sent.pop_front();
+        }
+        delta
+    }
 }
 
 /// Creates a new ledger with the supplied name and budgeting period.
@@ -51,6 +366,20 @@ pub fn api_add_account(
     Ok(account_id)
 }
 
+/// Adds a category to the ledger and returns its identifier.
+pub fn api_add_category(
+    ledger: &mut Ledger,
+    name: impl Into<String>,
+    kind: CategoryKind,
+    parent_id: Option<Uuid>,
+) -> Result<Uuid, CoreError> {
+    let mut category = Category::new(name, kind);
+    category.parent_id = parent_id;
+    let category_id = category.id;
+    CategoryService::add(ledger, category)?;
+    Ok(category_id)
+}
+
 /// Adds a transaction to the ledger and returns the transaction identifier.
 #[allow(clippy::too_many_arguments)]
 pub fn api_add_transaction(
@@ -62,6 +391,11 @@ pub fn api_add_transaction(
     budgeted_amount: f64,
     notes: Option<String>,
 ) -> Result<Uuid, CoreError> {
+    check_amount("amount", budgeted_amount, AmountRule::NonNegative)?;
+    let budgeted_amount = Money::from_major(
+        budgeted_amount,
+        ledger.transfer_currency(from_account, to_account),
+    )?;
     let mut transaction = Transaction::new(
         from_account,
         to_account,
@@ -73,22 +407,232 @@ pub fn api_add_transaction(
     TransactionService::add(ledger, transaction)
 }
 
-/// Marks the transaction identified by `txn_id` as completed.
+/// Adds a transaction whose amount is expressed in integer minor units of
+/// `currency_code`, falling back to the ledger base currency when omitted.
+#[allow(clippy::too_many_arguments)]
+pub fn api_add_transaction_minor(
+    ledger: &mut Ledger,
+    from_account: Uuid,
+    to_account: Uuid,
+    category_id: Option<Uuid>,
+    scheduled_date: NaiveDate,
+    amount_minor: i64,
+    currency_code: Option<String>,
+    notes: Option<String>,
+) -> Result<Uuid, CoreError> {
+    let currency = match currency_code {
+        Some(code) => Some(normalize_currency_code(&code)?),
+        None => None,
+    };
+    let amount = Money::from_minor(
+        amount_minor,
+        currency
+            .as_deref()
+            .map(CurrencyCode::new)
+            .unwrap_or_else(|| ledger.base_currency().clone()),
+    );
+    let mut transaction = Transaction::new(
+        from_account,
+        to_account,
+        category_id,
+        scheduled_date,
+        amount,
+    );
+    transaction.currency = currency;
+    transaction.notes = notes;
+    TransactionService::add(ledger, transaction)
+}
+
+/// Returns the number of minor-unit digits used by the ledger's base currency.
+pub fn api_currency_precision(ledger: &Ledger) -> u8 {
+    minor_units_for(ledger.base_currency().as_str())
+}
+
+/// Formats `amount` the way the ledger's locale writes money, e.g.
+/// `1.234,56 €` for de-DE. Uses the base currency unless `currency_code` is
+/// given.
+pub fn api_format_amount(
+    ledger: &Ledger,
+    amount: f64,
+    currency_code: Option<&str>,
+) -> Result<String, CoreError> {
+    let currency = match currency_code {
+        Some(code) => CurrencyCode::new(normalize_currency_code(code)?),
+        None => ledger.base_currency().clone(),
+    };
+    Ok(format_currency_value(
+        amount,
+        &currency,
+        &ledger.locale,
+        &ledger.format,
+    ))
+}
+
+/// Marks the transaction identified by `txn_id` as completed, creating any
+/// transfers its account's transfer rules call for.
 pub fn api_complete_transaction(
     ledger: &mut Ledger,
     txn_id: Uuid,
     actual_date: NaiveDate,
     actual_amount: f64,
 ) -> Result<(), CoreError> {
-    TransactionService::update(ledger, txn_id, |txn| {
-        txn.mark_completed(actual_date, actual_amount);
-    })
+    TransactionService::complete(ledger, txn_id, actual_date, actual_amount).map(|_| ())
+}
+
+/// Id of the transaction with the short code `code`, e.g. `T-0012`.
+pub fn api_transaction_by_reference(ledger: &Ledger, code: &str) -> Result<Uuid, CoreError> {
+    TransactionService::find_by_reference(ledger, code).map(|txn| txn.id)
+}
+
+/// Attaches merchant and location metadata captured by a client (for
+/// example a phone at the till) to an existing transaction. `None` clears a
+/// field.
+pub fn api_set_transaction_metadata(
+    ledger: &mut Ledger,
+    txn_id: Uuid,
+    merchant: Option<String>,
+    location: Option<GeoLocation>,
+) -> Result<(), CoreError> {
+    MerchantService::set_metadata(ledger, txn_id, merchant, location)
+}
+
+/// Queues an incomplete transaction for review in the ledger's inbox. The
+/// draft does not affect balances or summaries until it is approved.
+pub fn api_submit_draft(
+    ledger: &mut Ledger,
+    source: impl Into<String>,
+    date: Option<NaiveDate>,
+    amount: Option<f64>,
+    payee: Option<String>,
+    notes: Option<String>,
+) -> Result<Uuid, CoreError> {
+    let mut draft = PendingDraft::new(source, time::now());
+    draft.date = date;
+    draft.amount = amount;
+    draft.payee = payee;
+    draft.notes = notes;
+    DraftService::submit(ledger, draft)
 }
 
 /// Provides a simplified ledger summary for the budgeting period that
 /// contains `reference_date`.
 pub fn api_ledger_summary(ledger: &Ledger, reference_date: NaiveDate) -> ApiLedgerSummary {
     let summary = BudgetService::summarize_period_containing(ledger, reference_date);
+    ledger_summary(ledger, reference_date, summary)
+}
+
+/// Transactions scheduled between `from` and `to` (both inclusive, either
+/// open when `None`), newest first.
+pub fn api_list_transactions(
+    ledger: &Ledger,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Vec<ApiTransaction> {
+    let query = TransactionQuery {
+        from,
+        to,
+        sort: Some(TransactionSort::descending(TransactionSortKey::Date)),
+        ..TransactionQuery::default()
+    };
+    TransactionService::query(ledger, &query)
+        .into_iter()
+        .map(|transaction| ApiTransaction::new(ledger, transaction))
+        .collect()
+}
+
+/// Totals, per-category and per-account figures, and category budget
+/// progress for the budget period that contains `reference_date`.
+pub fn api_budget_summary(ledger: &Ledger, reference_date: NaiveDate) -> ApiBudgetSummary {
+    let summary = BudgetService::summarize_period_containing(ledger, reference_date);
+    let category_budgets = BudgetService::category_budget_summaries(
+        ledger,
+        summary.window,
+        summary.scope,
+        CategoryBudgetSummaryKind::Actual,
+    );
+    ApiBudgetSummary {
+        reference_date,
+        summary,
+        category_budgets,
+    }
+}
+
+/// Balances of `account` from `from` to `to` (both inclusive) for charts.
+/// Periods of one `granularity` unit start on `from`, so monthly points from
+/// the 1st close on month ends; the last period is cut short at `to`.
+/// Completed transactions count on their actual date, as in
+/// [`AccountService::balance`]. The ledger is read once, then each period
+/// takes the sorted movements up to its end.
+pub fn api_balance_series(
+    ledger: &Ledger,
+    account: Uuid,
+    from: NaiveDate,
+    to: NaiveDate,
+    granularity: TimeUnit,
+) -> Result<ApiBalanceSeries, CoreError> {
+    let details = ledger
+        .account(account)
+        .ok_or_else(|| CoreError::AccountNotFound(account.to_string()))?;
+    if from > to {
+        return Err(CoreError::Validation(format!(
+            "balance series cannot start on {} after it ends on {}",
+            from, to
+        )));
+    }
+
+    let currency = ledger.holding_currency(details);
+    let running = RunningBalance::completed(details, &currency, &ledger.transactions, from);
+    let mut movements = running.movements.iter().peekable();
+    let mut balance = running.opening;
+    let mut points = Vec::new();
+    let mut start = from;
+    // Each boundary is shifted from `from` itself so that month ends do not
+    // drift after a short month.
+    for periods in 1u32.. {
+        let next = TimeInterval {
+            every: periods,
+            unit: granularity.clone(),
+        }
+        .checked_next_date(from)
+        .ok_or_else(|| {
+            CoreError::Validation(format!(
+                "balance series from {} runs past the last supported date",
+                from
+            ))
+        })?;
+        let end = next.pred_opt().unwrap_or(next).min(to);
+        while let Some(movement) = movements.next_if(|movement| movement.date <= end) {
+            balance = balance.saturating_add(movement.amount);
+        }
+        points.push(ApiBalancePoint {
+            start,
+            end,
+            balance: running.major(balance),
+        });
+        if end >= to {
+            break;
+        }
+        start = next;
+    }
+
+    Ok(ApiBalanceSeries {
+        account_id: account,
+        account_name: details.name.clone(),
+        currency: details
+            .currency
+            .clone()
+            .unwrap_or_else(|| ledger.base_currency().as_str().to_string()),
+        granularity,
+        opening_balance: running.major(running.opening),
+        points,
+    })
+}
+
+fn ledger_summary(
+    ledger: &Ledger,
+    reference_date: NaiveDate,
+    summary: BudgetSummary,
+) -> ApiLedgerSummary {
     let totals = summary.totals;
     ApiLedgerSummary {
         scope: summary.scope,
@@ -100,5 +644,49 @@ pub fn api_ledger_summary(ledger: &Ledger, reference_date: NaiveDate) -> ApiLedg
         variance_total: totals.variance,
         incomplete_transactions: summary.incomplete_transactions,
         orphaned_transactions: summary.orphaned_transactions,
+        alerts: AlertService::evaluate(ledger, reference_date),
+        pending_drafts: ledger.pending_drafts.len(),
+    }
+}
+
+/// Builds everything the dashboard shows for the period containing
+/// `reference_date`. The summary runs on its own thread while the forecast
+/// and recurrence snapshots share one pass over the recurring series.
+pub fn api_dashboard_snapshot(ledger: &Ledger, reference_date: NaiveDate) -> ApiDashboardSnapshot {
+    let (summary, (forecast, recurrences)) = thread::scope(|scope| {
+        let summary = scope.spawn(|| api_ledger_summary(ledger, reference_date));
+        let projected = ForecastService::period_report_with_recurrences(ledger, reference_date);
+        (
+            summary
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+            projected,
+        )
+    });
+    let totals = &forecast.forecast.totals;
+    ApiDashboardSnapshot {
+        reference_date,
+        forecast: ApiForecastSummary {
+            window_start: forecast.forecast.window.start,
+            window_end: forecast.forecast.window.end,
+            projected_transactions: totals.generated,
+            projected_inflow: totals.projected_inflow,
+            projected_outflow: totals.projected_outflow,
+            projected_net: totals.net,
+            projected_totals: forecast.summary.totals,
+        },
+        summary,
+        recurrences: recurrences.into_iter().map(Into::into).collect(),
+    }
+}
+
+fn normalize_currency_code(code: &str) -> Result<String, CoreError> {
+    let trimmed = code.trim();
+    if trimmed.len() != 3 || !trimmed.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(CoreError::Validation(format!(
+            "invalid currency code `{}`",
+            code
+        )));
     }
+    Ok(trimmed.to_ascii_uppercase())
 }
@@ -0,0 +1,358 @@
+//! Reconciliation: ticking off completed transactions against a bank
+//! statement until the cleared balance agrees with the bank's.
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{account::Account, reconciliation::ReconciliationStatement, Ledger, Money};
+
+use crate::stats::{completed_movement, is_asset_account};
+use crate::{Clock, CoreError};
+
+/// Differences smaller than this count as balanced.
+const BALANCE_TOLERANCE: f64 = 0.005;
+
+/// A completed transaction that may appear on a statement, signed from the
+/// account's point of view.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconciliationLine {
+    pub transaction_id: Uuid,
+    pub date: NaiveDate,
+    pub amount: f64,
+}
+
+/// Where a statement stands: what is cleared, what is not, and how far the
+/// cleared balance is from the bank's closing balance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconciliationReport {
+    pub account_id: Uuid,
+    pub statement_id: Uuid,
+    pub statement_date: NaiveDate,
+    /// Closing balance of the previous reconciled statement, or the
+    /// account's opening balance for the first one.
+    pub opening_balance: f64,
+    pub closing_balance: f64,
+    pub cleared: Vec<ReconciliationLine>,
+    pub uncleared: Vec<ReconciliationLine>,
+    pub cleared_total: f64,
+    pub uncleared_total: f64,
+    pub reconciled: bool,
+}
+
+impl ReconciliationReport {
+    pub fn cleared_balance(&self) -> f64 {
+        self.opening_balance + self.cleared_total
+    }
+
+    /// Closing balance minus cleared balance; zero once reconciled.
+    pub fn difference(&self) -> f64 {
+        self.closing_balance - self.cleared_balance()
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        self.difference().abs() < BALANCE_TOLERANCE
+    }
+}
+
+pub struct ReconciliationService;
+
+impl ReconciliationService {
+    /// Starts reconciling `account_id` against a statement ending on
+    /// `statement_date`. A statement still open for the account is reused
+    /// with the new date and balance, keeping what was already cleared.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            account_id = %account_id,
+            statement_date = %statement_date
+        ),
+        err(level = "debug")
+    )]
+    pub fn start(
+        ledger: &mut Ledger,
+        account_id: Uuid,
+        statement_date: NaiveDate,
+        closing_balance: f64,
+    ) -> Result<Uuid, CoreError> {
+        let account = Self::account(ledger, account_id)?;
+        if !is_asset_account(account) {
+            return Err(CoreError::InvalidOperation(format!(
+                "`{}` does not hold money, so it has no statements to reconcile",
+                account.name
+            )));
+        }
+        if !closing_balance.is_finite() {
+            return Err(CoreError::Validation(
+                "closing balance must be a number".into(),
+            ));
+        }
+        if let Some(last) = Self::last_reconciled(account) {
+            if statement_date <= last.statement_date {
+                return Err(CoreError::Validation(format!(
+                    "statement date must be after the last reconciled statement ({})",
+                    last.statement_date
+                )));
+            }
+        }
+        let account = ledger
+            .account_mut(account_id)
+            .ok_or_else(|| CoreError::AccountNotFound(account_id.to_string()))?;
+        let id = match account.statements.last_mut() {
+            Some(open) if !open.is_reconciled() => {
+                open.statement_date = statement_date;
+                open.closing_balance = closing_balance;
+                open.id
+            }
+            _ => {
+                let statement = ReconciliationStatement::new(statement_date, closing_balance);
+                let id = statement.id;
+                account.statements.push(statement);
+                id
+            }
+        };
+        ledger.touch();
+        Ok(id)
+    }
+
+    /// The account's statement that is still being reconciled, if any.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            account_id = %account_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn open_statement(
+        ledger: &Ledger,
+        account_id: Uuid,
+    ) -> Result<Option<&ReconciliationStatement>, CoreError> {
+        Ok(Self::account(ledger, account_id)?
+            .statements
+            .last()
+            .filter(|statement| !statement.is_reconciled()))
+    }
+
+    /// Clears every completed transaction up to the open statement's date
+    /// that no earlier statement has claimed. Returns how many were added.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            account_id = %account_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn match_completed(ledger: &mut Ledger, account_id: Uuid) -> Result<usize, CoreError> {
+        let candidates: Vec<Uuid> = Self::report(ledger, account_id)?
+            .uncleared
+            .into_iter()
+            .map(|line| line.transaction_id)
+            .collect();
+        let statement = Self::open_statement_mut(ledger, account_id)?;
+        statement.cleared.extend(&candidates);
+        if !candidates.is_empty() {
+            ledger.touch();
+        }
+        Ok(candidates.len())
+    }
+
+    /// Marks one transaction as on or off the open statement.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            account_id = %account_id,
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn set_cleared(
+        ledger: &mut Ledger,
+        account_id: Uuid,
+        transaction_id: Uuid,
+        cleared: bool,
+    ) -> Result<(), CoreError> {
+        let report = Self::report(ledger, account_id)?;
+        let listed = report
+            .cleared
+            .iter()
+            .chain(&report.uncleared)
+            .any(|line| line.transaction_id == transaction_id);
+        if !listed {
+            return Err(CoreError::InvalidOperation(
+                "transaction is not a completed movement covered by this statement".into(),
+            ));
+        }
+        let statement = Self::open_statement_mut(ledger, account_id)?;
+        if cleared {
+            if !statement.is_cleared(transaction_id) {
+                statement.cleared.push(transaction_id);
+            }
+        } else {
+            statement.cleared.retain(|id| *id != transaction_id);
+        }
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Cleared and uncleared totals for the account's latest statement.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            account_id = %account_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn report(ledger: &Ledger, account_id: Uuid) -> Result<ReconciliationReport, CoreError> {
+        let account = Self::account(ledger, account_id)?;
+        let Some((statement, earlier)) = account.statements.split_last() else {
+            return Err(CoreError::InvalidOperation(format!(
+                "`{}` has no statement to reconcile",
+                account.name
+            )));
+        };
+        let opening_balance = earlier
+            .iter()
+            .rev()
+            .find(|previous| previous.is_reconciled())
+            .map(|previous| previous.closing_balance)
+            .unwrap_or_else(|| {
+                account
+                    .opening_balance
+                    .as_ref()
+                    .map_or(0.0, Money::to_major)
+            });
+
+        let mut cleared = Vec::new();
+        let mut uncleared = Vec::new();
+        for txn in &ledger.transactions {
+            let sign = match (txn.to_account == account_id, txn.from_account == account_id) {
+                (true, false) => 1.0,
+                (false, true) => -1.0,
+                _ => continue,
+            };
+            let Some((date, amount)) = completed_movement(txn) else {
+                continue;
+            };
+            if earlier.iter().any(|previous| previous.is_cleared(txn.id)) {
+                continue;
+            }
+            let line = ReconciliationLine {
+                transaction_id: txn.id,
+                date,
+                amount: sign * amount,
+            };
+            if statement.is_cleared(txn.id) {
+                cleared.push(line);
+            } else if date <= statement.statement_date {
+                uncleared.push(line);
+            }
+        }
+        cleared.sort_by_key(|line| line.date);
+        uncleared.sort_by_key(|line| line.date);
+
+        Ok(ReconciliationReport {
+            account_id,
+            statement_id: statement.id,
+            statement_date: statement.statement_date,
+            opening_balance,
+            closing_balance: statement.closing_balance,
+            cleared_total: Self::total(&cleared),
+            uncleared_total: Self::total(&uncleared),
+            cleared,
+            uncleared,
+            reconciled: statement.is_reconciled(),
+        })
+    }
+
+    /// Closes the open statement once its cleared balance matches the bank.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            account_id = %account_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn finish(
+        ledger: &mut Ledger,
+        account_id: Uuid,
+        clock: &dyn Clock,
+    ) -> Result<ReconciliationReport, CoreError> {
+        let mut report = Self::report(ledger, account_id)?;
+        if report.reconciled {
+            return Err(Self::nothing_open(ledger, account_id));
+        }
+        if !report.is_balanced() {
+            return Err(CoreError::InvalidOperation(format!(
+                "cleared balance {:.2} differs from the statement's {:.2} by {:.2}",
+                report.cleared_balance(),
+                report.closing_balance,
+                report.difference()
+            )));
+        }
+        Self::open_statement_mut(ledger, account_id)?.reconciled_at = Some(clock.now());
+        ledger.touch();
+        report.reconciled = true;
+        Ok(report)
+    }
+
+    /// Sum of `lines`, reading 0.00 rather than -0.00 when there are none.
+    fn total(lines: &[ReconciliationLine]) -> f64 {
+        lines.iter().fold(0.0, |total, line| total + line.amount)
+    }
+
+    fn account(ledger: &Ledger, account_id: Uuid) -> Result<&Account, CoreError> {
+        ledger
+            .account(account_id)
+            .ok_or_else(|| CoreError::AccountNotFound(account_id.to_string()))
+    }
+
+    fn last_reconciled(account: &Account) -> Option<&ReconciliationStatement> {
+        account
+            .statements
+            .iter()
+            .rev()
+            .find(|statement| statement.is_reconciled())
+    }
+
+    fn open_statement_mut(
+        ledger: &mut Ledger,
+        account_id: Uuid,
+    ) -> Result<&mut ReconciliationStatement, CoreError> {
+        if Self::open_statement(ledger, account_id)?.is_none() {
+            return Err(Self::nothing_open(ledger, account_id));
+        }
+        let account = ledger
+            .account_mut(account_id)
+            .ok_or_else(|| CoreError::AccountNotFound(account_id.to_string()))?;
+        Ok(account
+            .statements
+            .last_mut()
+            .expect("open statement checked above"))
+    }
+
+    fn nothing_open(ledger: &Ledger, account_id: Uuid) -> CoreError {
+        let name = ledger
+            .account(account_id)
+            .map(|account| account.name.as_str())
+            .unwrap_or_default();
+        CoreError::InvalidOperation(format!("`{}` has no open statement", name))
+    }
+}
@@ -1,17 +1,54 @@
 //! Services related to transaction recurrence maintenance.
 
-use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+use tracing::instrument;
 use uuid::Uuid;
 
-use bufy_domain::{Ledger, Recurrence, RecurrenceStatus};
+use bufy_domain::{
+    ledger::{BudgetScope, DateWindow},
+    BusinessDayShift, Ledger, Money, PricePoint, Recurrence, RecurrenceStatus, TransactionStatus,
+};
+
+use crate::{budget_service::BudgetService, CoreError};
+
+/// Change in a category's actual spending between two windows, split into the
+/// part explained by recurring price changes and the remainder.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryPriceChange {
+    pub category_id: Option<Uuid>,
+    pub name: String,
+    pub previous: f64,
+    pub current: f64,
+    /// Extra cost of the current window's recurring occurrences at their new
+    /// prices compared with the prices in effect at the end of the previous window.
+    pub price_effect: f64,
+    /// Remaining change, from buying more or less.
+    pub usage_effect: f64,
+}
 
-use crate::CoreError;
+impl CategoryPriceChange {
+    pub fn change(&self) -> f64 {
+        self.current - self.previous
+    }
+}
 
 /// Provides safe helpers for modifying recurrence metadata on ledger transactions.
 pub struct RecurrenceService;
 
 impl RecurrenceService {
     /// Assigns or replaces the recurrence definition for a transaction.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
     pub fn set_rule(
         ledger: &mut Ledger,
         transaction_id: Uuid,
@@ -27,6 +64,16 @@ impl RecurrenceService {
     }
 
     /// Clears any recurrence information associated with the transaction.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
     pub fn clear_rule(ledger: &mut Ledger, transaction_id: Uuid) -> Result<bool, CoreError> {
         let txn = ledger
             .transaction_mut(transaction_id)
@@ -42,6 +89,16 @@ impl RecurrenceService {
     }
 
     /// Updates the status of the recurrence.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
     pub fn set_status(
         ledger: &mut Ledger,
         transaction_id: Uuid,
@@ -60,7 +117,47 @@ impl RecurrenceService {
         Ok(())
     }
 
+    /// Sets where the recurrence moves occurrences that miss a business day.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn set_business_day_shift(
+        ledger: &mut Ledger,
+        transaction_id: Uuid,
+        shift: BusinessDayShift,
+    ) -> Result<(), CoreError> {
+        let txn = ledger
+            .transaction_mut(transaction_id)
+            .ok_or(CoreError::TransactionNotFound(transaction_id))?;
+        let recurrence = txn
+            .recurrence
+            .as_mut()
+            .ok_or_else(|| CoreError::InvalidOperation("transaction has no recurrence".into()))?;
+        recurrence.business_day_shift = shift;
+        ledger.refresh_recurrence_metadata();
+        ledger.touch();
+        Ok(())
+    }
+
     /// Adds a skipped date to the recurrence, returning whether it was newly added.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id,
+            date = %date
+        ),
+        err(level = "debug")
+    )]
     pub fn skip_date(
         ledger: &mut Ledger,
         transaction_id: Uuid,
@@ -83,7 +180,214 @@ impl RecurrenceService {
         Ok(true)
     }
 
+    /// Changes the amount of a recurring transaction from `effective` onwards,
+    /// recording the change in its price history. Returns the previous amount.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id,
+            amount = amount,
+            effective = %effective
+        ),
+        err(level = "debug")
+    )]
+    pub fn change_price(
+        ledger: &mut Ledger,
+        transaction_id: Uuid,
+        amount: f64,
+        effective: NaiveDate,
+    ) -> Result<f64, CoreError> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(CoreError::Validation("amount cannot be negative".into()));
+        }
+        let txn = ledger
+            .transaction_mut(transaction_id)
+            .ok_or(CoreError::TransactionNotFound(transaction_id))?;
+        if txn.recurrence.is_none() {
+            return Err(CoreError::InvalidOperation(
+                "transaction has no recurrence".into(),
+            ));
+        }
+        let amount = Money::from_major(amount, txn.budgeted_amount.currency().clone())?;
+        if txn.budgeted_amount == amount {
+            return Err(CoreError::Validation(format!(
+                "amount is already {:.2}",
+                amount.to_major()
+            )));
+        }
+        let previous = std::mem::replace(&mut txn.budgeted_amount, amount).to_major();
+        Self::record_price(ledger, transaction_id, previous, effective)?;
+        Ok(previous)
+    }
+
+    /// Records that the template's current amount replaced `previous` on
+    /// `effective`, repricing planned occurrences scheduled from that date.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id,
+            effective = %effective
+        ),
+        err(level = "debug")
+    )]
+    pub fn record_price(
+        ledger: &mut Ledger,
+        transaction_id: Uuid,
+        previous: f64,
+        effective: NaiveDate,
+    ) -> Result<(), CoreError> {
+        let txn = ledger
+            .transaction_mut(transaction_id)
+            .ok_or(CoreError::TransactionNotFound(transaction_id))?;
+        let amount = txn.budgeted_amount.clone();
+        let series_id = txn.recurrence_series().unwrap_or(transaction_id);
+        txn.recurrence
+            .as_mut()
+            .ok_or_else(|| CoreError::InvalidOperation("transaction has no recurrence".into()))?
+            .record_price(previous, amount.to_major(), effective);
+        for occurrence in ledger.transactions.iter_mut() {
+            if occurrence.recurrence.is_none()
+                && occurrence.recurrence_series_id == Some(series_id)
+                && occurrence.status != TransactionStatus::Completed
+                && occurrence.scheduled_date >= effective
+            {
+                occurrence.budgeted_amount = amount.clone();
+            }
+        }
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Price history of a recurring transaction, oldest first. A recurrence
+    /// whose amount never changed reports its current amount since its start.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn price_history(
+        ledger: &Ledger,
+        transaction_id: Uuid,
+    ) -> Result<Vec<PricePoint>, CoreError> {
+        let txn = ledger
+            .transaction(transaction_id)
+            .ok_or(CoreError::TransactionNotFound(transaction_id))?;
+        let recurrence = txn
+            .recurrence
+            .as_ref()
+            .ok_or_else(|| CoreError::InvalidOperation("transaction has no recurrence".into()))?;
+        if recurrence.price_history.is_empty() {
+            return Ok(vec![PricePoint {
+                effective: recurrence.start_date,
+                amount: txn.budgeted_amount.to_major(),
+            }]);
+        }
+        Ok(recurrence.price_history.clone())
+    }
+
+    /// Splits each category's change in actual spending from `previous` to
+    /// `current` into price and usage effects. Only categories whose spending
+    /// changed or that hold repriced recurrences are listed.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn category_price_changes(
+        ledger: &Ledger,
+        previous: DateWindow,
+        current: DateWindow,
+    ) -> Vec<CategoryPriceChange> {
+        let mut rows: BTreeMap<Option<Uuid>, CategoryPriceChange> = BTreeMap::new();
+        let before = BudgetService::summarize_window_scope(ledger, previous, BudgetScope::Custom);
+        for category in &before.per_category {
+            Self::row(&mut rows, category.category_id, &category.name).previous =
+                category.totals.real;
+        }
+        let after = BudgetService::summarize_window_scope(ledger, current, BudgetScope::Custom);
+        for category in &after.per_category {
+            Self::row(&mut rows, category.category_id, &category.name).current =
+                category.totals.real;
+        }
+
+        let previous_last_day = previous.end - Duration::days(1);
+        for template in &ledger.transactions {
+            let Some(recurrence) = template.recurrence.as_ref() else {
+                continue;
+            };
+            let Some(baseline) = recurrence.price_on(previous_last_day) else {
+                continue;
+            };
+            let series_id = template.recurrence_series().unwrap_or(template.id);
+            let effect: f64 = ledger
+                .transactions
+                .iter()
+                .filter(|txn| txn.id == template.id || txn.recurrence_series_id == Some(series_id))
+                .filter(|txn| txn.actual_amount.is_some())
+                .filter(|txn| current.contains(txn.actual_date.unwrap_or(txn.scheduled_date)))
+                .map(|txn| recurrence.price_on(txn.scheduled_date).unwrap_or(baseline) - baseline)
+                .sum();
+            if effect == 0.0 {
+                continue;
+            }
+            let name = template
+                .category_id
+                .and_then(|id| ledger.category(id))
+                .map(|category| category.name.clone())
+                .unwrap_or_else(|| "Uncategorized".into());
+            Self::row(&mut rows, template.category_id, &name).price_effect += effect;
+        }
+
+        let mut changes: Vec<_> = rows
+            .into_values()
+            .filter(|row| row.previous != row.current || row.price_effect != 0.0)
+            .map(|mut row| {
+                row.usage_effect = row.change() - row.price_effect;
+                row
+            })
+            .collect();
+        changes.sort_by(|a, b| a.name.cmp(&b.name));
+        changes
+    }
+
+    fn row<'a>(
+        rows: &'a mut BTreeMap<Option<Uuid>, CategoryPriceChange>,
+        category_id: Option<Uuid>,
+        name: &str,
+    ) -> &'a mut CategoryPriceChange {
+        rows.entry(category_id)
+            .or_insert_with(|| CategoryPriceChange {
+                category_id,
+                name: name.to_string(),
+                previous: 0.0,
+                current: 0.0,
+                price_effect: 0.0,
+                usage_effect: 0.0,
+            })
+    }
+
     /// Materializes due recurrence instances as transactions.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            reference = %reference
+        ),
+        err(level = "debug")
+    )]
     pub fn materialize_due(ledger: &mut Ledger, reference: NaiveDate) -> Result<usize, CoreError> {
         let created = ledger.materialize_due_recurrences(reference);
         Ok(created)
@@ -0,0 +1,225 @@
+//! Permanently removes old transactions from a ledger while keeping account
+//! balances intact, for data-retention and erasure requests.
+//!
+//! Completed flows that leave the ledger are folded into the opening
+//! balance of every account they touched, so balances, forecasts, and
+//! per-account totals do not move. Recurring series are kept whole: future occurrences are generated
+//! from the template, and its past occurrences mark which dates are already
+//! done, so removing them would schedule those dates again. Attached files
+//! that only removed transactions used are deleted with them, and so are
+//! purchased-item records, simulation changes, undo records, and review
+//! marks that name them.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    simulation::SimulationChange, transaction::AttachmentRef, CurrencyCode, Ledger, Money,
+    Transaction,
+};
+
+use crate::{
+    attachment_service::{stored_locations, AttachmentService},
+    stats::completed_movement,
+    CoreError,
+};
+
+/// What a purge removes, or would remove in a dry run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PurgeReport {
+    /// Transactions dated before this day are removed.
+    pub before: NaiveDate,
+    pub transactions: usize,
+    /// Transactions of a live recurring series dated before the cutoff,
+    /// kept so the series does not regenerate them.
+    pub recurring_kept: usize,
+    /// Amount added to each account's opening balance, by account id.
+    pub opening_adjustments: Vec<(Uuid, f64)>,
+    /// Attached files used only by removed transactions.
+    pub attachments: usize,
+    /// Purchased-item records carried by removed transactions.
+    pub assets: usize,
+    /// Simulation changes that target removed transactions and are dropped
+    /// with them.
+    pub simulation_changes: usize,
+}
+
+impl PurgeReport {
+    pub fn is_empty(&self) -> bool {
+        self.transactions == 0
+    }
+}
+
+pub struct RetentionService;
+
+impl RetentionService {
+    /// Reports what [`RetentionService::purge`] would remove without
+    /// changing the ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), before = %before)
+    )]
+    pub fn preview(ledger: &Ledger, before: NaiveDate) -> PurgeReport {
+        let (report, _) = plan(ledger, before);
+        report
+    }
+
+    /// Removes every transaction dated before `before`, except those of a
+    /// live recurring series, and moves their completed flows into opening
+    /// balances. References to removed transactions from statements, other
+    /// transactions, simulations, and reviews are cleared. With `attachments_dir`, the files
+    /// attached to removed transactions are deleted from it unless a kept
+    /// transaction uses them too.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), before = %before),
+        err(level = "debug")
+    )]
+    pub fn purge(
+        ledger: &mut Ledger,
+        before: NaiveDate,
+        attachments_dir: Option<&Path>,
+    ) -> Result<PurgeReport, CoreError> {
+        let (report, removed) = plan(ledger, before);
+        if removed.is_empty() {
+            return Ok(report);
+        }
+        let released: Vec<AttachmentRef> = ledger
+            .transactions
+            .iter()
+            .filter(|txn| removed.contains(&txn.id))
+            .flat_map(|txn| txn.attachments.iter().cloned())
+            .collect();
+        ledger.transactions.retain(|txn| !removed.contains(&txn.id));
+        for txn in &mut ledger.transactions {
+            if txn
+                .source_transaction_id
+                .is_some_and(|id| removed.contains(&id))
+            {
+                txn.source_transaction_id = None;
+            }
+            if txn.installment_of.is_some_and(|id| removed.contains(&id)) {
+                txn.installment_of = None;
+            }
+        }
+        let adjustments: HashMap<Uuid, f64> = report.opening_adjustments.iter().copied().collect();
+        let currencies: HashMap<Uuid, CurrencyCode> = ledger
+            .accounts
+            .iter()
+            .map(|account| (account.id, ledger.holding_currency(account)))
+            .collect();
+        for account in &mut ledger.accounts {
+            if let Some(amount) = adjustments.get(&account.id) {
+                let currency = &currencies[&account.id];
+                let opening = match account.opening_balance.take() {
+                    Some(opening) => opening.in_currency(currency)?,
+                    None => Money::zero(currency.clone()),
+                };
+                let amount = Money::from_major(*amount, currency.clone())?;
+                account.opening_balance = Some(opening.checked_add(&amount)?);
+            }
+            for statement in &mut account.statements {
+                statement.cleared.retain(|id| !removed.contains(id));
+            }
+        }
+        for simulation in &mut ledger.simulations {
+            simulation
+                .changes
+                .retain(|change| !targets(change, &removed));
+            for set in &mut simulation.applied {
+                set.changes.retain(|change| !targets(change, &removed));
+                set.transactions
+                    .retain(|revert| !removed.contains(&revert.transaction_id()));
+            }
+        }
+        for review in &mut ledger.reviews {
+            review.handled.retain(|id| !removed.contains(id));
+        }
+        ledger.touch();
+        if let Some(dir) = attachments_dir {
+            AttachmentService::remove_unused(ledger, dir, &released)?;
+        }
+        Ok(report)
+    }
+}
+
+fn plan(ledger: &Ledger, before: NaiveDate) -> (PurgeReport, HashSet<Uuid>) {
+    let mut report = PurgeReport {
+        before,
+        transactions: 0,
+        recurring_kept: 0,
+        opening_adjustments: Vec::new(),
+        attachments: 0,
+        assets: 0,
+        simulation_changes: 0,
+    };
+    let mut removed = HashSet::new();
+    let live_series: HashSet<Uuid> = ledger
+        .transactions
+        .iter()
+        .filter(|txn| txn.recurrence.is_some())
+        .filter_map(Transaction::recurrence_series)
+        .collect();
+    let mut adjustments: Vec<(Uuid, f64)> = Vec::new();
+    let mut adjust = |account: Uuid, amount: f64| {
+        if ledger.account(account).is_none() {
+            return;
+        }
+        match adjustments.iter_mut().find(|(id, _)| *id == account) {
+            Some((_, total)) => *total += amount,
+            None => adjustments.push((account, amount)),
+        }
+    };
+    for txn in ledger.transactions.iter().filter(|txn| is_old(txn, before)) {
+        if txn
+            .recurrence_series()
+            .is_some_and(|series| live_series.contains(&series))
+        {
+            report.recurring_kept += 1;
+            continue;
+        }
+        if let Some((_, amount)) = completed_movement(txn) {
+            adjust(txn.to_account, amount);
+            adjust(txn.from_account, -amount);
+        }
+        removed.insert(txn.id);
+    }
+    adjustments.retain(|(_, amount)| *amount != 0.0);
+    report.transactions = removed.len();
+    report.opening_adjustments = adjustments;
+    let (gone, kept): (Vec<&Transaction>, Vec<&Transaction>) = ledger
+        .transactions
+        .iter()
+        .partition(|txn| removed.contains(&txn.id));
+    report.assets = gone.iter().filter(|txn| txn.asset.is_some()).count();
+    let kept = stored_locations(kept);
+    report.attachments = stored_locations(gone)
+        .into_iter()
+        .filter(|location| !kept.contains(location))
+        .count();
+    report.simulation_changes = ledger
+        .simulations
+        .iter()
+        .flat_map(|simulation| &simulation.changes)
+        .filter(|change| targets(change, &removed))
+        .count();
+    (report, removed)
+}
+
+fn targets(change: &SimulationChange, removed: &HashSet<Uuid>) -> bool {
+    change
+        .target_transaction()
+        .is_some_and(|id| removed.contains(&id))
+}
+
+fn is_old(txn: &Transaction, before: NaiveDate) -> bool {
+    txn.actual_date.unwrap_or(txn.scheduled_date) < before
+}
@@ -0,0 +1,411 @@
+//! Guided budget reviews: what needs attention at each step, and the
+//! decisions recorded in the review log.
+
+use chrono::{Days, NaiveDate};
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    account::AccountKind,
+    category::CategoryKind,
+    common::BudgetPeriod,
+    ledger::BudgetStatus,
+    review::{BudgetReview, ReviewLogEntry, ReviewStep},
+    transaction::TransactionStatus,
+    Ledger, Money, Transaction,
+};
+
+use crate::{
+    stats::is_asset_account, CategorizationService, CategoryService, Clock, CoreError,
+    SummaryService,
+};
+
+/// Planned bills due within this many days show up in a review.
+pub const UPCOMING_BILL_DAYS: u64 = 14;
+/// Monthly budgets this far (in percent) from the trailing average spend get
+/// an adjustment proposal.
+pub const ADJUSTMENT_THRESHOLD_PERCENT: f64 = 10.0;
+
+/// Something a review step asks the user to look at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReviewItem {
+    Uncategorized {
+        transaction_id: Uuid,
+        /// Category the learned rules would pick.
+        suggestion: Option<Uuid>,
+    },
+    OverBudget {
+        category_id: Uuid,
+        budget: f64,
+        spent: f64,
+        /// Budget amount that would have covered this period's spending.
+        proposed: f64,
+    },
+    UpcomingBill {
+        transaction_id: Uuid,
+        due: NaiveDate,
+        amount: f64,
+    },
+    BudgetAdjustment {
+        category_id: Uuid,
+        current: f64,
+        /// Trailing monthly average spend, rounded to whole units.
+        proposed: f64,
+    },
+}
+
+impl ReviewItem {
+    /// Transaction or category the item is about.
+    pub fn subject_id(&self) -> Uuid {
+        match self {
+            ReviewItem::Uncategorized { transaction_id, .. }
+            | ReviewItem::UpcomingBill { transaction_id, .. } => *transaction_id,
+            ReviewItem::OverBudget { category_id, .. }
+            | ReviewItem::BudgetAdjustment { category_id, .. } => *category_id,
+        }
+    }
+
+    /// New budget amount the item suggests, if any.
+    pub fn proposed_budget(&self) -> Option<f64> {
+        match self {
+            ReviewItem::OverBudget { proposed, .. }
+            | ReviewItem::BudgetAdjustment { proposed, .. } => Some(*proposed),
+            _ => None,
+        }
+    }
+}
+
+pub struct ReviewService;
+
+impl ReviewService {
+    /// Returns the unfinished review, starting a new one when there is none.
+    /// The flag is `true` when an earlier session was resumed.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn start_or_resume(ledger: &mut Ledger, clock: &dyn Clock) -> (Uuid, bool) {
+        if let Some(review) = ledger.open_review() {
+            return (review.id, true);
+        }
+        let review = BudgetReview::new(clock.now());
+        let id = review.id;
+        ledger.reviews.push(review);
+        ledger.touch();
+        (id, false)
+    }
+
+    /// The most recently finished review.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn last_completed(ledger: &Ledger) -> Option<&BudgetReview> {
+        ledger
+            .reviews
+            .iter()
+            .rev()
+            .find(|review| review.is_complete())
+    }
+
+    /// Items still waiting in the open review's current step.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn pending_items(ledger: &Ledger, today: NaiveDate) -> Vec<ReviewItem> {
+        let Some(review) = ledger.open_review() else {
+            return Vec::new();
+        };
+        Self::items(ledger, review.step, today)
+            .into_iter()
+            .filter(|item| !review.handled.contains(&item.subject_id()))
+            .collect()
+    }
+
+    /// Everything `step` would show on `today`, handled or not.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn items(ledger: &Ledger, step: ReviewStep, today: NaiveDate) -> Vec<ReviewItem> {
+        match step {
+            ReviewStep::Uncategorized => ledger
+                .transactions
+                .iter()
+                .filter(|txn| txn.category_id.is_none() && txn.scheduled_date <= today)
+                .filter(|txn| !Self::is_internal_transfer(ledger, txn))
+                .map(|txn| ReviewItem::Uncategorized {
+                    transaction_id: txn.id,
+                    suggestion: CategorizationService::suggest(ledger, txn),
+                })
+                .collect(),
+            ReviewStep::OverBudget => {
+                let window = ledger.budget_window_containing(today);
+                SummaryService::category_budget_summaries(ledger, window, window.scope(today))
+                    .into_iter()
+                    .filter(|summary| summary.status == BudgetStatus::OverBudget)
+                    .filter_map(|summary| {
+                        let definition = ledger.category(summary.category_id)?.budget.as_ref()?;
+                        let currency = definition.amount.currency();
+                        let in_minor_units =
+                            |amount| Money::from_major(amount, currency.clone()).ok();
+                        let excess = in_minor_units(summary.spent_amount)?
+                            .checked_sub(&in_minor_units(summary.budget_amount)?)
+                            .ok()?;
+                        let proposed = definition.amount.checked_add(&excess).ok()?;
+                        Some(ReviewItem::OverBudget {
+                            category_id: summary.category_id,
+                            budget: summary.budget_amount,
+                            spent: summary.spent_amount,
+                            proposed: proposed.to_major().ceil(),
+                        })
+                    })
+                    .collect()
+            }
+            ReviewStep::UpcomingBills => {
+                let horizon = today
+                    .checked_add_days(Days::new(UPCOMING_BILL_DAYS))
+                    .unwrap_or(today);
+                let mut bills: Vec<_> = ledger
+                    .transactions
+                    .iter()
+                    .filter(|txn| txn.status == TransactionStatus::Planned)
+                    .filter(|txn| (today..=horizon).contains(&txn.scheduled_date))
+                    .filter(|txn| Self::is_bill(ledger, txn))
+                    .map(|txn| ReviewItem::UpcomingBill {
+                        transaction_id: txn.id,
+                        due: txn.scheduled_date,
+                        amount: txn.budgeted_amount.to_major(),
+                    })
+                    .collect();
+                bills.sort_by_key(|item| match item {
+                    ReviewItem::UpcomingBill { due, .. } => *due,
+                    _ => today,
+                });
+                bills
+            }
+            ReviewStep::BudgetAdjustments => {
+                let stats = CategoryService::stats_by_category(ledger, today);
+                ledger
+                    .categories
+                    .iter()
+                    .filter(|category| !category.hidden)
+                    .filter_map(|category| {
+                        let budget = category.budget.as_ref()?;
+                        let current = budget.amount.to_major();
+                        if budget.period != BudgetPeriod::Monthly || current <= 0.0 {
+                            return None;
+                        }
+                        let average = stats.get(&category.id)?.monthly_average;
+                        let drift = (average - current).abs() / current * 100.0;
+                        (average > 0.0 && drift > ADJUSTMENT_THRESHOLD_PERCENT).then(|| {
+                            ReviewItem::BudgetAdjustment {
+                                category_id: category.id,
+                                current,
+                                proposed: average.round(),
+                            }
+                        })
+                    })
+                    .collect()
+            }
+            ReviewStep::Done => Vec::new(),
+        }
+    }
+
+    /// Files an uncategorized transaction under `category_id` and teaches the
+    /// learned rules from it.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id,
+            category_id = %category_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn categorize(
+        ledger: &mut Ledger,
+        transaction_id: Uuid,
+        category_id: Uuid,
+        clock: &dyn Clock,
+    ) -> Result<(), CoreError> {
+        let category = ledger
+            .category(category_id)
+            .ok_or_else(|| CoreError::CategoryNotFound(category_id.to_string()))?
+            .name
+            .clone();
+        let subject = Self::describe(ledger, transaction_id);
+        ledger
+            .transaction_mut(transaction_id)
+            .ok_or(CoreError::TransactionNotFound(transaction_id))?
+            .category_id = Some(category_id);
+        CategorizationService::learn(ledger, transaction_id, clock)?;
+        Self::mark(
+            ledger,
+            transaction_id,
+            format!("Categorized {} as {}", subject, category),
+            clock,
+        )
+    }
+
+    /// Sets a category's budget amount, keeping its period and proration.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            category_id = %category_id,
+            amount = amount
+        ),
+        err(level = "debug")
+    )]
+    pub fn adjust_budget(
+        ledger: &mut Ledger,
+        category_id: Uuid,
+        amount: f64,
+        clock: &dyn Clock,
+    ) -> Result<(), CoreError> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(CoreError::Validation(
+                "budget amount cannot be negative".into(),
+            ));
+        }
+        let category = ledger
+            .category_mut(category_id)
+            .ok_or_else(|| CoreError::CategoryNotFound(category_id.to_string()))?;
+        let name = category.name.clone();
+        let budget = category.budget.as_mut().ok_or_else(|| {
+            CoreError::InvalidOperation(format!("category `{}` has no budget", name))
+        })?;
+        let amount = Money::from_major(amount, budget.amount.currency().clone())?;
+        let previous = std::mem::replace(&mut budget.amount, amount.clone());
+        Self::mark(
+            ledger,
+            category_id,
+            format!(
+                "Changed {} budget from {:.2} to {:.2}",
+                name,
+                previous.to_major(),
+                amount.to_major()
+            ),
+            clock,
+        )
+    }
+
+    /// Marks an item as seen without changing anything.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            subject_id = %subject_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn dismiss(
+        ledger: &mut Ledger,
+        subject_id: Uuid,
+        clock: &dyn Clock,
+    ) -> Result<(), CoreError> {
+        let subject = Self::describe(ledger, subject_id);
+        Self::mark(ledger, subject_id, format!("Left {} as is", subject), clock)
+    }
+
+    /// Moves the open review to its next step, finishing it after the last
+    /// one. Returns the step now current.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn advance(ledger: &mut Ledger, clock: &dyn Clock) -> Result<ReviewStep, CoreError> {
+        let now = clock.now();
+        let review = Self::open_mut(ledger)?;
+        review.step = review.step.next();
+        review.handled.clear();
+        if review.step == ReviewStep::Done {
+            review.completed_at = Some(now);
+            review.log.push(ReviewLogEntry {
+                at: now,
+                step: ReviewStep::Done,
+                message: "Finished review".into(),
+            });
+        }
+        let step = review.step;
+        ledger.touch();
+        Ok(step)
+    }
+
+    fn mark(
+        ledger: &mut Ledger,
+        subject_id: Uuid,
+        message: String,
+        clock: &dyn Clock,
+    ) -> Result<(), CoreError> {
+        let now = clock.now();
+        let review = Self::open_mut(ledger)?;
+        if !review.handled.contains(&subject_id) {
+            review.handled.push(subject_id);
+        }
+        review.log.push(ReviewLogEntry {
+            at: now,
+            step: review.step,
+            message,
+        });
+        ledger.touch();
+        Ok(())
+    }
+
+    fn open_mut(ledger: &mut Ledger) -> Result<&mut BudgetReview, CoreError> {
+        ledger
+            .open_review_mut()
+            .ok_or_else(|| CoreError::InvalidOperation("no review in progress".into()))
+    }
+
+    /// Names a category, or a transaction by amount, payee, and date.
+    fn describe(ledger: &Ledger, subject_id: Uuid) -> String {
+        if let Some(category) = ledger.category(subject_id) {
+            return category.name.clone();
+        }
+        match ledger.transaction(subject_id) {
+            Some(txn) => format!(
+                "{:.2} to {} on {}",
+                txn.amount().to_major(),
+                ledger
+                    .account(txn.to_account)
+                    .map(|account| account.name.as_str())
+                    .unwrap_or("(deleted)"),
+                txn.actual_date.unwrap_or(txn.scheduled_date)
+            ),
+            None => subject_id.to_string(),
+        }
+    }
+
+    /// Moves between two of the user's own accounts need no category.
+    fn is_internal_transfer(ledger: &Ledger, txn: &Transaction) -> bool {
+        [txn.from_account, txn.to_account]
+            .iter()
+            .all(|id| ledger.account(*id).is_some_and(is_asset_account))
+    }
+
+    fn is_bill(ledger: &Ledger, txn: &Transaction) -> bool {
+        let to_expense = ledger
+            .account(txn.to_account)
+            .is_some_and(|account| account.kind == AccountKind::ExpenseDestination);
+        let expense_category = txn
+            .category_id
+            .and_then(|id| ledger.category(id))
+            .is_some_and(|category| category.kind == CategoryKind::Expense);
+        to_expense || expense_category
+    }
+}
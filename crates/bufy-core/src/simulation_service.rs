@@ -1,23 +1,77 @@
 //! Simulation orchestration helpers built on top of the domain ledger.
 
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use tracing::instrument;
 use uuid::Uuid;
 
 use bufy_domain::{
+    ids::new_id,
     ledger::{BudgetScope, BudgetTotalsDelta, CategoryBudgetSummaryKind, DateWindow},
     simulation::{
-        Simulation, SimulationBudgetImpact, SimulationChange, SimulationStatus,
-        SimulationTransactionPatch,
+        AppliedChangeSet, IncomeModelPatch, Simulation, SimulationBudgetImpact, SimulationChange,
+        SimulationStatus, SimulationTransactionPatch, TransactionRevert,
     },
     transaction::Transaction,
-    Ledger,
+    Ledger, Money,
 };
 
-use crate::{budget_service::BudgetService, Clock, CoreError};
+use crate::{budget_service::BudgetService, income_service::IncomeService, Clock, CoreError};
+
+/// A simulation change whose transaction was removed from the ledger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrphanedChange {
+    pub simulation: String,
+    /// Position of the change within the simulation.
+    pub index: usize,
+    pub transaction_id: Uuid,
+    pub summary: String,
+}
+
+/// A transaction an applied simulation touched that was changed again
+/// afterwards, so rolling back would overwrite that later edit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RollbackConflict {
+    pub transaction_id: Uuid,
+    pub kind: RollbackConflictKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollbackConflictKind {
+    /// Edited since the apply.
+    Edited,
+    /// Deleted since the apply.
+    Deleted,
+    /// Removed by the apply, then added back.
+    Restored,
+}
+
+impl fmt::Display for RollbackConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            RollbackConflictKind::Edited => "was edited",
+            RollbackConflictKind::Deleted => "was deleted",
+            RollbackConflictKind::Restored => "was added back",
+        };
+        write!(
+            f,
+            "transaction {} {} after the simulation was applied",
+            self.transaction_id, what
+        )
+    }
+}
 
 pub struct SimulationService;
 
 impl SimulationService {
     /// Creates a new simulation within the ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), notes = ?notes),
+        err(level = "debug")
+    )]
     pub fn create<'a>(
         ledger: &'a mut Ledger,
         name: impl Into<String>,
@@ -37,14 +91,18 @@ impl SimulationService {
         }
         let now = clock.now();
         ledger.simulations.push(Simulation {
-            id: Uuid::new_v4(),
+            id: new_id(),
             name,
             notes,
+            parent: None,
             status: SimulationStatus::Pending,
             created_at: now,
             updated_at: now,
             applied_at: None,
+            pre_apply_backup: None,
             changes: Vec::new(),
+            applied: Vec::new(),
+            extra: Default::default(),
         });
         ledger.touch();
         Ok(ledger
@@ -53,7 +111,52 @@ impl SimulationService {
             .expect("simulation just inserted"))
     }
 
+    /// Creates a simulation that builds on `parent_name`: previews and
+    /// budget impact include the parent's pending changes before its own.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), notes = ?notes),
+        err(level = "debug")
+    )]
+    pub fn branch<'a>(
+        ledger: &'a mut Ledger,
+        parent_name: &str,
+        name: impl Into<String>,
+        notes: Option<String>,
+        clock: &dyn Clock,
+    ) -> Result<&'a Simulation, CoreError> {
+        let parent = ledger
+            .simulation(parent_name)
+            .ok_or_else(|| CoreError::SimulationNotFound(parent_name.into()))?;
+        if parent.status != SimulationStatus::Pending {
+            return Err(CoreError::InvalidOperation(format!(
+                "simulation `{}` is not pending",
+                parent.name
+            )));
+        }
+        let parent_id = parent.id;
+        Self::create(ledger, name, notes, clock)?;
+        let simulation = ledger
+            .simulations
+            .last_mut()
+            .expect("simulation just inserted");
+        simulation.parent = Some(parent_id);
+        Ok(simulation)
+    }
+
     /// Adds a transaction change to a simulation.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction = %transaction.id,
+            amount = %transaction.budgeted_amount
+        ),
+        err(level = "debug")
+    )]
     pub fn add_transaction(
         ledger: &mut Ledger,
         sim_name: &str,
@@ -67,6 +170,16 @@ impl SimulationService {
     }
 
     /// Excludes a transaction from a simulation overlay.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_id = %transaction_id
+        ),
+        err(level = "debug")
+    )]
     pub fn exclude_transaction(
         ledger: &mut Ledger,
         sim_name: &str,
@@ -87,6 +200,12 @@ impl SimulationService {
     }
 
     /// Applies a partial modification to an existing transaction.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
     pub fn modify_transaction(
         ledger: &mut Ledger,
         sim_name: &str,
@@ -106,8 +225,58 @@ impl SimulationService {
         }
     }
 
-    /// Removes an entire simulation by name.
+    /// Changes the income model of a recurring paycheck within a simulation.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn modify_income(
+        ledger: &mut Ledger,
+        sim_name: &str,
+        patch: IncomeModelPatch,
+    ) -> Result<(), CoreError> {
+        if !patch.has_effect() {
+            return Err(CoreError::Validation(
+                "no income changes were specified".into(),
+            ));
+        }
+        IncomeService::validate_patch(ledger, &patch)?;
+        if ledger.modify_income_in_simulation_raw(sim_name, patch) {
+            Ok(())
+        } else {
+            Err(CoreError::SimulationNotFound(sim_name.into()))
+        }
+    }
+
+    /// Removes an entire simulation by name. Pending simulations based on
+    /// it must be discarded first.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
     pub fn discard(ledger: &mut Ledger, sim_name: &str) -> Result<(), CoreError> {
+        let simulation = ledger
+            .simulation(sim_name)
+            .ok_or_else(|| CoreError::SimulationNotFound(sim_name.into()))?;
+        let branches: Vec<&str> = ledger
+            .simulations()
+            .iter()
+            .filter(|sim| {
+                sim.parent == Some(simulation.id) && sim.status == SimulationStatus::Pending
+            })
+            .map(|sim| sim.name.as_str())
+            .collect();
+        if !branches.is_empty() {
+            return Err(CoreError::InvalidOperation(format!(
+                "simulation `{}` has branches ({}); discard them first",
+                simulation.name,
+                branches.join(", ")
+            )));
+        }
         if ledger.discard_simulation_raw(sim_name) {
             Ok(())
         } else {
@@ -115,21 +284,271 @@ impl SimulationService {
         }
     }
 
-    /// Applies a simulation, mutating the ledger transactions.
+    /// Applies a simulation, mutating the ledger transactions. A branch can
+    /// only be applied once the simulation it is based on has been.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
     pub fn apply(ledger: &mut Ledger, sim_name: &str, clock: &dyn Clock) -> Result<(), CoreError> {
         let index = ledger
             .simulations()
             .iter()
             .position(|sim| sim.name.eq_ignore_ascii_case(sim_name))
             .ok_or_else(|| CoreError::SimulationNotFound(sim_name.into()))?;
+        ensure_parent_applied(ledger, &ledger.simulations[index])?;
+        let orphaned = orphans(ledger, &ledger.simulations[index]).len();
+        if orphaned > 0 {
+            return Err(CoreError::InvalidOperation(format!(
+                "simulation `{}` has {} change(s) for transactions that no longer exist; run `simulation check` to remove them",
+                sim_name, orphaned
+            )));
+        }
         let mut simulation = ledger.simulations.remove(index);
-        SimulationEngine::apply(ledger, &mut simulation, clock)?;
+        let before = ledger.transactions.clone();
+        let applied = SimulationEngine::apply(ledger, &mut simulation, clock);
+        if applied.is_ok() {
+            simulation.applied.push(AppliedChangeSet {
+                applied_at: clock.now(),
+                changes: Vec::new(),
+                transactions: revert_set(&before, &ledger.transactions),
+            });
+        }
         ledger.simulations.insert(index, simulation);
+        applied?;
         ledger.touch();
         Ok(())
     }
 
+    /// Applies only the changes at `indices` (positions as listed by
+    /// [`SimulationService::changes`]) and removes them from the simulation.
+    /// The rest stay pending; once none remain the simulation counts as
+    /// applied. A change that edits a transaction added by another change
+    /// needs that change selected too.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn apply_changes(
+        ledger: &mut Ledger,
+        sim_name: &str,
+        indices: &[usize],
+        clock: &dyn Clock,
+    ) -> Result<Vec<SimulationChange>, CoreError> {
+        let position = ledger
+            .simulations()
+            .iter()
+            .position(|sim| sim.name.eq_ignore_ascii_case(sim_name))
+            .ok_or_else(|| CoreError::SimulationNotFound(sim_name.into()))?;
+        let simulation = &ledger.simulations[position];
+        if simulation.status != SimulationStatus::Pending {
+            return Err(CoreError::InvalidOperation(format!(
+                "simulation `{}` is not pending",
+                simulation.name
+            )));
+        }
+        ensure_parent_applied(ledger, simulation)?;
+        let mut selected = indices.to_vec();
+        selected.sort_unstable();
+        selected.dedup();
+        if selected.is_empty() {
+            return Err(CoreError::Validation("select at least one change".into()));
+        }
+        if let Some(index) = selected
+            .iter()
+            .find(|index| **index >= simulation.changes.len())
+        {
+            return Err(CoreError::Validation(format!(
+                "simulation `{}` has no change {}",
+                simulation.name, index
+            )));
+        }
+
+        let subset = Simulation {
+            changes: selected
+                .iter()
+                .map(|index| simulation.changes[*index].clone())
+                .collect(),
+            ..simulation.clone()
+        };
+        if let Some(orphan) = orphans(ledger, &subset).first() {
+            let index = selected[orphan.index];
+            let adder = simulation.changes.iter().position(|change| {
+                matches!(change, SimulationChange::AddTransaction { transaction }
+                    if transaction.id == orphan.transaction_id)
+            });
+            return Err(match adder {
+                Some(adder) => CoreError::Validation(format!(
+                    "change {} edits the transaction added by change {}; apply both together",
+                    index, adder
+                )),
+                None => CoreError::InvalidOperation(format!(
+                    "change {} is for a transaction that no longer exists; run `simulation check` to remove it",
+                    index
+                )),
+            });
+        }
+
+        let before = ledger.transactions.clone();
+        SimulationEngine::apply_changes(&mut ledger.transactions, &subset.changes)?;
+        ledger.assign_references();
+        ledger.refresh_recurrence_metadata();
+        let now = clock.now();
+        let transactions = revert_set(&before, &ledger.transactions);
+        let simulation = &mut ledger.simulations[position];
+        simulation.applied.push(AppliedChangeSet {
+            applied_at: now,
+            changes: subset.changes.clone(),
+            transactions,
+        });
+        let mut index = 0;
+        simulation.changes.retain(|_| {
+            let keep = selected.binary_search(&index).is_err();
+            index += 1;
+            keep
+        });
+        if simulation.changes.is_empty() {
+            simulation.status = SimulationStatus::Applied;
+            simulation.applied_at = Some(now);
+        }
+        simulation.updated_at = now;
+        ledger.touch();
+        Ok(subset.changes)
+    }
+
+    /// Transactions touched by the simulation's applies that changed since,
+    /// which [`Self::rollback`] would overwrite.
+    pub fn rollback_conflicts(
+        ledger: &Ledger,
+        sim_name: &str,
+    ) -> Result<Vec<RollbackConflict>, CoreError> {
+        let simulation = ledger
+            .simulation(sim_name)
+            .ok_or_else(|| CoreError::SimulationNotFound(sim_name.into()))?;
+        let current: HashMap<Uuid, &Transaction> = ledger
+            .transactions
+            .iter()
+            .map(|txn| (txn.id, txn))
+            .collect();
+        let mut conflicts = Vec::new();
+        let mut seen = HashSet::new();
+        // The latest apply holds the state each transaction should be in now.
+        for revert in simulation
+            .applied
+            .iter()
+            .rev()
+            .flat_map(|set| &set.transactions)
+        {
+            let transaction_id = revert.transaction_id();
+            if !seen.insert(transaction_id) {
+                continue;
+            }
+            let kind = match (&revert.after, current.get(&transaction_id)) {
+                (Some(after), Some(now)) if !same_transaction(after, now) => {
+                    RollbackConflictKind::Edited
+                }
+                (Some(_), None) => RollbackConflictKind::Deleted,
+                (None, Some(_)) => RollbackConflictKind::Restored,
+                _ => continue,
+            };
+            conflicts.push(RollbackConflict {
+                transaction_id,
+                kind,
+            });
+        }
+        Ok(conflicts)
+    }
+
+    /// Undoes every apply of a simulation, newest first: transactions it
+    /// added are removed, and ones it edited or removed are put back as they
+    /// were. Other edits to the ledger are kept. The simulation becomes
+    /// pending again with all its changes. Fails when a touched transaction
+    /// changed since, unless `force` is set, in which case those changes are
+    /// overwritten. Returns how many transactions were reverted.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), force = force),
+        err(level = "debug")
+    )]
+    pub fn rollback(
+        ledger: &mut Ledger,
+        sim_name: &str,
+        force: bool,
+        clock: &dyn Clock,
+    ) -> Result<usize, CoreError> {
+        let conflicts = Self::rollback_conflicts(ledger, sim_name)?;
+        let simulation = ledger
+            .simulation(sim_name)
+            .ok_or_else(|| CoreError::SimulationNotFound(sim_name.into()))?;
+        if simulation.applied.is_empty() {
+            return Err(CoreError::InvalidOperation(format!(
+                "simulation `{}` has no recorded apply to roll back",
+                simulation.name
+            )));
+        }
+        if !conflicts.is_empty() && !force {
+            return Err(CoreError::InvalidOperation(format!(
+                "{} transaction(s) changed since simulation `{}` was applied: {}",
+                conflicts.len(),
+                simulation.name,
+                conflicts
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            )));
+        }
+        let mut simulation = simulation.clone();
+        let applied = std::mem::take(&mut simulation.applied);
+        let mut reverted = HashSet::new();
+        for revert in applied
+            .iter()
+            .rev()
+            .flat_map(|set| set.transactions.iter().rev())
+        {
+            let transaction_id = revert.transaction_id();
+            reverted.insert(transaction_id);
+            let position = ledger
+                .transactions
+                .iter()
+                .position(|txn| txn.id == transaction_id);
+            match (&revert.before, position) {
+                (Some(before), Some(index)) => ledger.transactions[index] = before.clone(),
+                (Some(before), None) => ledger.transactions.push(before.clone()),
+                (None, Some(index)) => {
+                    ledger.transactions.remove(index);
+                }
+                (None, None) => {}
+            }
+        }
+        let mut changes: Vec<SimulationChange> =
+            applied.into_iter().flat_map(|set| set.changes).collect();
+        changes.append(&mut simulation.changes);
+        simulation.changes = changes;
+        simulation.status = SimulationStatus::Pending;
+        simulation.applied_at = None;
+        simulation.pre_apply_backup = None;
+        simulation.updated_at = clock.now();
+        if let Some(slot) = ledger.simulation_mut(sim_name) {
+            *slot = simulation;
+        }
+        ledger.refresh_recurrence_metadata();
+        ledger.touch();
+        Ok(reverted.len())
+    }
+
     /// Returns the list of changes recorded in the simulation.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
     pub fn changes<'a>(
         ledger: &'a Ledger,
         sim_name: &str,
@@ -139,15 +558,117 @@ impl SimulationService {
             .ok_or_else(|| CoreError::SimulationNotFound(sim_name.into()))
     }
 
+    /// The changes a preview of `sim_name` applies: those of each pending
+    /// simulation it is based on, oldest first, then its own.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn change_chain(
+        ledger: &Ledger,
+        sim_name: &str,
+    ) -> Result<Vec<SimulationChange>, CoreError> {
+        let simulation = ledger
+            .simulation(sim_name)
+            .ok_or_else(|| CoreError::SimulationNotFound(sim_name.into()))?;
+        chain_changes(ledger, simulation)
+    }
+
+    /// Lists changes in `sim_name` that target transactions no longer in
+    /// the ledger or added earlier in the simulation.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn orphaned_changes(
+        ledger: &Ledger,
+        sim_name: &str,
+    ) -> Result<Vec<OrphanedChange>, CoreError> {
+        let simulation = ledger
+            .simulation(sim_name)
+            .ok_or_else(|| CoreError::SimulationNotFound(sim_name.into()))?;
+        Ok(orphans(ledger, simulation))
+    }
+
+    /// Orphaned changes across every pending simulation.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn check(ledger: &Ledger) -> Vec<OrphanedChange> {
+        ledger
+            .simulations()
+            .iter()
+            .filter(|sim| sim.status == SimulationStatus::Pending)
+            .flat_map(|sim| orphans(ledger, sim))
+            .collect()
+    }
+
+    /// Removes the orphaned changes from `sim_name`, returning how many were
+    /// removed.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn prune_orphans(
+        ledger: &mut Ledger,
+        sim_name: &str,
+        clock: &dyn Clock,
+    ) -> Result<usize, CoreError> {
+        let orphaned: Vec<usize> = Self::orphaned_changes(ledger, sim_name)?
+            .into_iter()
+            .map(|orphan| orphan.index)
+            .collect();
+        if orphaned.is_empty() {
+            return Ok(0);
+        }
+        let simulation = ledger
+            .simulations
+            .iter_mut()
+            .find(|sim| sim.name.eq_ignore_ascii_case(sim_name))
+            .ok_or_else(|| CoreError::SimulationNotFound(sim_name.into()))?;
+        let mut index = 0;
+        simulation.changes.retain(|_| {
+            let keep = !orphaned.contains(&index);
+            index += 1;
+            keep
+        });
+        simulation.updated_at = clock.now();
+        ledger.touch();
+        Ok(orphaned.len())
+    }
+
     /// Runs a simulation against the ledger, returning an overlay ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
     pub fn run(ledger: &Ledger, sim_name: &str) -> Result<Ledger, CoreError> {
         let simulation = ledger
             .simulation(sim_name)
             .ok_or_else(|| CoreError::SimulationNotFound(sim_name.into()))?;
-        Ok(SimulationEngine::run(ledger, simulation))
+        Ok(SimulationEngine::run(
+            ledger,
+            &chain_changes(ledger, simulation)?,
+        ))
     }
 
     /// Summarizes the effect of a simulation in a window.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
     pub fn summarize_in_window(
         ledger: &Ledger,
         simulation_name: &str,
@@ -163,9 +684,9 @@ impl SimulationService {
                 simulation_name
             )));
         }
-        let simulated_ledger = SimulationEngine::run(ledger, simulation);
-        let base = BudgetService::summarize_window_scope(ledger, window, scope);
-        let simulated = BudgetService::summarize_window_scope(&simulated_ledger, window, scope);
+        let simulated_ledger = SimulationEngine::run(ledger, &chain_changes(ledger, simulation)?);
+        let base = BudgetService::summarize_window_checked(ledger, window, scope)?;
+        let simulated = BudgetService::summarize_window_checked(&simulated_ledger, window, scope)?;
         let delta = BudgetTotalsDelta {
             budgeted: simulated.totals.budgeted - base.totals.budgeted,
             real: simulated.totals.real - base.totals.real,
@@ -198,10 +719,12 @@ impl SimulationService {
 struct SimulationEngine;
 
 impl SimulationEngine {
-    fn run(ledger: &Ledger, sim: &Simulation) -> Ledger {
+    /// Builds a preview copy. Orphaned changes are skipped one by one so the
+    /// rest of the simulation still shows; applying rejects them.
+    fn run(ledger: &Ledger, changes: &[SimulationChange]) -> Ledger {
         let mut clone = ledger.clone();
-        if Self::apply_changes(&mut clone.transactions, &sim.changes).is_err() {
-            // Ignore failures when building preview copies; validation happens when applying.
+        for change in changes {
+            let _ = Self::apply_changes(&mut clone.transactions, std::slice::from_ref(change));
         }
         clone
     }
@@ -219,6 +742,8 @@ impl SimulationEngine {
         }
 
         Self::apply_changes(&mut ledger.transactions, &simulation.changes)?;
+        ledger.assign_amount_currencies()?;
+        ledger.assign_references();
         ledger.refresh_recurrence_metadata();
 
         let now = clock.now();
@@ -241,8 +766,8 @@ impl SimulationEngine {
                     let txn = transactions
                         .iter_mut()
                         .find(|t| t.id == patch.transaction_id)
-                        .ok_or_else(|| CoreError::TransactionNotFound(patch.transaction_id))?;
-                    apply_patch(txn, patch);
+                        .ok_or(CoreError::TransactionNotFound(patch.transaction_id))?;
+                    apply_patch(txn, patch)?;
                 }
                 SimulationChange::ExcludeTransaction { transaction_id } => {
                     let before = transactions.len();
@@ -251,13 +776,154 @@ impl SimulationEngine {
                         return Err(CoreError::TransactionNotFound(*transaction_id));
                     }
                 }
+                SimulationChange::ModifyIncome(patch) => {
+                    if !patch.apply(transactions) {
+                        return Err(CoreError::TransactionNotFound(patch.transaction_id));
+                    }
+                }
             }
         }
         Ok(())
     }
 }
 
-fn apply_patch(txn: &mut Transaction, patch: &SimulationTransactionPatch) {
+/// Every transaction that differs between `before` and `after`, in the
+/// order they appear.
+fn revert_set(before: &[Transaction], after: &[Transaction]) -> Vec<TransactionRevert> {
+    let old: HashMap<Uuid, &Transaction> = before.iter().map(|txn| (txn.id, txn)).collect();
+    let new_ids: HashSet<Uuid> = after.iter().map(|txn| txn.id).collect();
+    let mut reverts: Vec<TransactionRevert> = after
+        .iter()
+        .filter_map(|txn| match old.get(&txn.id) {
+            Some(previous) if same_transaction(previous, txn) => None,
+            previous => Some(TransactionRevert {
+                before: previous.map(|previous| (*previous).clone()),
+                after: Some(txn.clone()),
+            }),
+        })
+        .collect();
+    reverts.extend(
+        before
+            .iter()
+            .filter(|txn| !new_ids.contains(&txn.id))
+            .map(|txn| TransactionRevert {
+                before: Some(txn.clone()),
+                after: None,
+            }),
+    );
+    reverts
+}
+
+/// Compares two transactions, ignoring the reference number and recurrence
+/// bookkeeping that the ledger derives on its own.
+fn same_transaction(a: &Transaction, b: &Transaction) -> bool {
+    fn comparable(txn: &Transaction) -> serde_json::Value {
+        let mut txn = txn.clone();
+        txn.reference = None;
+        if let Some(recurrence) = txn.recurrence.as_mut() {
+            recurrence.update_metadata(None, None, None, 0);
+        }
+        serde_json::to_value(txn).unwrap_or_default()
+    }
+    comparable(a) == comparable(b)
+}
+
+/// Pending simulations `simulation` is based on, root first. An applied
+/// ancestor ends the chain, since its changes are already in the ledger.
+fn lineage<'a>(
+    ledger: &'a Ledger,
+    simulation: &Simulation,
+) -> Result<Vec<&'a Simulation>, CoreError> {
+    let mut chain: Vec<&Simulation> = Vec::new();
+    let mut next = simulation.parent;
+    while let Some(id) = next {
+        let parent = ledger
+            .simulations()
+            .iter()
+            .find(|sim| sim.id == id)
+            .ok_or_else(|| {
+                CoreError::InvalidOperation(format!(
+                    "simulation `{}` is based on a simulation that no longer exists",
+                    simulation.name
+                ))
+            })?;
+        match parent.status {
+            SimulationStatus::Applied => break,
+            SimulationStatus::Discarded => {
+                return Err(CoreError::InvalidOperation(format!(
+                    "simulation `{}` is based on `{}`, which was discarded",
+                    simulation.name, parent.name
+                )))
+            }
+            SimulationStatus::Pending => {}
+        }
+        if parent.id == simulation.id || chain.iter().any(|sim| sim.id == parent.id) {
+            return Err(CoreError::InvalidOperation(format!(
+                "simulation `{}` is based on itself",
+                simulation.name
+            )));
+        }
+        chain.push(parent);
+        next = parent.parent;
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+fn chain_changes(
+    ledger: &Ledger,
+    simulation: &Simulation,
+) -> Result<Vec<SimulationChange>, CoreError> {
+    Ok(lineage(ledger, simulation)?
+        .into_iter()
+        .chain(std::iter::once(simulation))
+        .flat_map(|sim| sim.changes.iter().cloned())
+        .collect())
+}
+
+fn ensure_parent_applied(ledger: &Ledger, simulation: &Simulation) -> Result<(), CoreError> {
+    match lineage(ledger, simulation)?.last() {
+        Some(parent) => Err(CoreError::InvalidOperation(format!(
+            "simulation `{}` is based on `{}`, which is still pending; apply `{}` first",
+            simulation.name, parent.name, parent.name
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn orphans(ledger: &Ledger, simulation: &Simulation) -> Vec<OrphanedChange> {
+    let mut known: HashSet<Uuid> = ledger.transactions.iter().map(|txn| txn.id).collect();
+    for change in lineage(ledger, simulation)
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|sim| &sim.changes)
+    {
+        if let SimulationChange::AddTransaction { transaction } = change {
+            known.insert(transaction.id);
+        }
+    }
+    let mut orphaned = Vec::new();
+    for (index, change) in simulation.changes.iter().enumerate() {
+        if let SimulationChange::AddTransaction { transaction } = change {
+            known.insert(transaction.id);
+        }
+        let Some(transaction_id) = change.target_transaction() else {
+            continue;
+        };
+        if !known.contains(&transaction_id) {
+            orphaned.push(OrphanedChange {
+                simulation: simulation.name.clone(),
+                index,
+                transaction_id,
+                summary: change.summary(),
+            });
+        }
+    }
+    orphaned
+}
+
+fn apply_patch(txn: &mut Transaction, patch: &SimulationTransactionPatch) -> Result<(), CoreError> {
+    let currency = txn.budgeted_amount.currency().clone();
     if let Some(account) = patch.from_account {
         txn.from_account = account;
     }
@@ -274,9 +940,12 @@ fn apply_patch(txn: &mut Transaction, patch: &SimulationTransactionPatch) {
         txn.actual_date = actual_date;
     }
     if let Some(amount) = patch.budgeted_amount {
-        txn.budgeted_amount = amount;
+        txn.budgeted_amount = Money::from_major(amount, currency.clone())?;
     }
     if let Some(amount) = patch.actual_amount {
-        txn.actual_amount = amount;
+        txn.actual_amount = amount
+            .map(|amount| Money::from_major(amount, currency))
+            .transpose()?;
     }
+    Ok(())
 }
@@ -0,0 +1,233 @@
+//! Sinking funds: monthly set-asides for annual or irregular bills.
+
+use chrono::{Datelike, NaiveDate};
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    common::TimeInterval,
+    ledger::DateWindow,
+    sinking_fund::{SinkingFund, SinkingFundAccrual},
+    Ledger,
+};
+
+use crate::{Clock, CoreError};
+
+/// Shortfalls smaller than this are treated as fully funded.
+const FUNDING_TOLERANCE: f64 = 0.005;
+
+/// Funding position of one sinking fund on a given day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SinkingFundStatus {
+    pub fund_id: Uuid,
+    pub name: String,
+    pub amount: f64,
+    pub next_due: NaiveDate,
+    pub saved: f64,
+    /// What should be set aside by now if saving evenly across the cycle.
+    pub expected: f64,
+    /// Monthly set-aside needed from today to have the full bill by its due date.
+    pub monthly_set_aside: f64,
+    /// Amount by which `saved` trails `expected`, zero when on track.
+    pub shortfall: f64,
+}
+
+impl SinkingFundStatus {
+    pub fn is_underfunded(&self) -> bool {
+        self.shortfall > FUNDING_TOLERANCE
+    }
+}
+
+pub struct SinkingFundService;
+
+impl SinkingFundService {
+    /// Adds a fund for a bill of `amount` due on `next_due` and every
+    /// `interval` after that.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            amount = amount,
+            next_due = %next_due,
+            category_id = ?category_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn create(
+        ledger: &mut Ledger,
+        name: &str,
+        amount: f64,
+        next_due: NaiveDate,
+        interval: TimeInterval,
+        category_id: Option<Uuid>,
+        clock: &dyn Clock,
+    ) -> Result<Uuid, CoreError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(CoreError::Validation(
+                "sinking fund name cannot be empty".into(),
+            ));
+        }
+        if ledger.sinking_fund(name).is_some() {
+            return Err(CoreError::Validation(format!(
+                "sinking fund `{}` already exists",
+                name
+            )));
+        }
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(CoreError::Validation("amount must be positive".into()));
+        }
+        if interval.every == 0 {
+            return Err(CoreError::Validation(
+                "interval must be at least one unit".into(),
+            ));
+        }
+        if let Some(id) = category_id {
+            if ledger.category(id).is_none() {
+                return Err(CoreError::CategoryNotFound(id.to_string()));
+            }
+        }
+        let mut fund = SinkingFund::new(name, amount, next_due, clock.now());
+        fund.interval = interval;
+        fund.category_id = category_id;
+        let id = fund.id;
+        ledger.sinking_funds.push(fund);
+        ledger.touch();
+        Ok(id)
+    }
+
+    /// Removes a fund; money already set aside is not touched.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn remove(ledger: &mut Ledger, name: &str) -> Result<SinkingFund, CoreError> {
+        let index = ledger
+            .sinking_funds
+            .iter()
+            .position(|fund| fund.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| Self::not_found(name))?;
+        let removed = ledger.sinking_funds.remove(index);
+        ledger.touch();
+        Ok(removed)
+    }
+
+    /// Records money set aside towards the next bill and returns the new total.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), amount = amount),
+        err(level = "debug")
+    )]
+    pub fn contribute(ledger: &mut Ledger, name: &str, amount: f64) -> Result<f64, CoreError> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(CoreError::Validation("amount must be positive".into()));
+        }
+        let fund = ledger
+            .sinking_fund_mut(name)
+            .ok_or_else(|| Self::not_found(name))?;
+        fund.saved += amount;
+        let saved = fund.saved;
+        ledger.touch();
+        Ok(saved)
+    }
+
+    /// Marks the current bill as paid from the fund and moves on to the next
+    /// cycle, returning the new due date. Any surplus carries over.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn pay(ledger: &mut Ledger, name: &str) -> Result<NaiveDate, CoreError> {
+        let fund = ledger
+            .sinking_fund_mut(name)
+            .ok_or_else(|| Self::not_found(name))?;
+        fund.saved = (fund.saved - fund.amount).max(0.0);
+        fund.next_due = fund.interval.next_date(fund.next_due);
+        let next_due = fund.next_due;
+        ledger.touch();
+        Ok(next_due)
+    }
+
+    /// Funding position of `fund` on `today`.
+    #[instrument(level = "debug", skip_all, fields(today = %today))]
+    pub fn status(fund: &SinkingFund, today: NaiveDate) -> SinkingFundStatus {
+        let start = fund.cycle_start();
+        let cycle_days = (fund.next_due - start).num_days().max(1);
+        let elapsed = (today - start).num_days().clamp(0, cycle_days);
+        let expected = fund.amount * elapsed as f64 / cycle_days as f64;
+        let remaining = (fund.amount - fund.saved).max(0.0);
+        SinkingFundStatus {
+            fund_id: fund.id,
+            name: fund.name.clone(),
+            amount: fund.amount,
+            next_due: fund.next_due,
+            saved: fund.saved,
+            expected,
+            monthly_set_aside: remaining / months_until(today, fund.next_due) as f64,
+            shortfall: (expected - fund.saved).max(0.0),
+        }
+    }
+
+    /// Funding positions of every fund, soonest due first.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn statuses(ledger: &Ledger, today: NaiveDate) -> Vec<SinkingFundStatus> {
+        let mut statuses: Vec<_> = ledger
+            .sinking_funds
+            .iter()
+            .map(|fund| Self::status(fund, today))
+            .collect();
+        statuses.sort_by(|a, b| a.next_due.cmp(&b.next_due).then(a.name.cmp(&b.name)));
+        statuses
+    }
+
+    /// Virtual accruals earmarking each fund's bill evenly across its cycle,
+    /// prorated to the days of `window`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn accruals(ledger: &Ledger, window: &DateWindow) -> Vec<SinkingFundAccrual> {
+        let window_days = (window.end - window.start).num_days().max(0) as f64;
+        ledger
+            .sinking_funds
+            .iter()
+            .map(|fund| {
+                let cycle_days = (fund.next_due - fund.cycle_start()).num_days().max(1) as f64;
+                SinkingFundAccrual {
+                    fund_id: fund.id,
+                    name: fund.name.clone(),
+                    category_id: fund.category_id,
+                    amount: fund.amount * window_days / cycle_days,
+                }
+            })
+            .collect()
+    }
+
+    fn not_found(name: &str) -> CoreError {
+        CoreError::InvalidOperation(format!("sinking fund `{}` not found", name.trim()))
+    }
+}
+
+/// Number of monthly set-asides left before `due`, counting this month's; at least one.
+pub(crate) fn months_until(today: NaiveDate, due: NaiveDate) -> u32 {
+    if due <= today {
+        return 1;
+    }
+    let mut months = (due.year() - today.year()) * 12 + due.month() as i32 - today.month() as i32;
+    if due.day() > today.day() {
+        months += 1;
+    }
+    months.max(1) as u32
+}
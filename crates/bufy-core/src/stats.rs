@@ -0,0 +1,245 @@
+//! Quick-stat aggregates shown alongside account and category details.
+//!
+//! Only completed transactions dated on or before today count as activity;
+//! planned entries describe intent rather than money that has moved.
+
+use chrono::{Months, NaiveDate};
+use uuid::Uuid;
+
+use bufy_domain::{
+    account::{Account, AccountKind},
+    transaction::{Transaction, TransactionStatus},
+    CurrencyCode, DateWindow, Money,
+};
+
+/// Number of trailing months averaged by the quick stats.
+pub const STATS_AVERAGE_MONTHS: u32 = 3;
+
+/// Identifies a single notable transaction within a stats report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionHighlight {
+    pub transaction_id: Uuid,
+    pub date: NaiveDate,
+    pub amount: f64,
+}
+
+/// Computed figures for an account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountStats {
+    /// Opening balance plus completed inflows minus completed outflows up to today.
+    pub balance: f64,
+    /// Net completed flow (inflows minus outflows) within the current budget period.
+    pub period_net: f64,
+    /// Average monthly net flow over the trailing [`STATS_AVERAGE_MONTHS`] months.
+    pub monthly_average: f64,
+    pub largest_transaction: Option<TransactionHighlight>,
+    pub last_activity: Option<NaiveDate>,
+}
+
+/// Computed figures for a category.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryStats {
+    /// Completed spend within the current budget period.
+    pub period_spend: f64,
+    /// Average monthly spend over the trailing [`STATS_AVERAGE_MONTHS`] months.
+    pub monthly_average: f64,
+    pub largest_transaction: Option<TransactionHighlight>,
+    pub last_activity: Option<NaiveDate>,
+}
+
+/// Amount and date a completed transaction actually moved, if it did.
+pub(crate) fn completed_movement(txn: &Transaction) -> Option<(NaiveDate, f64)> {
+    completed_amount(txn).map(|(date, amount)| (date, amount.to_major()))
+}
+
+/// Like [`completed_movement`], keeping the amount in minor units.
+pub(crate) fn completed_amount(txn: &Transaction) -> Option<(NaiveDate, &Money)> {
+    if txn.status != TransactionStatus::Completed {
+        return None;
+    }
+    Some((txn.actual_date.unwrap_or(txn.scheduled_date), txn.amount()))
+}
+
+/// `amount` counted in minor units of `currency`. Totals are kept this way
+/// so that adding many amounts never drifts; one too large to count
+/// saturates rather than failing the report.
+pub(crate) fn minor_in(amount: &Money, currency: &CurrencyCode) -> i64 {
+    amount.in_currency(currency).map_or_else(
+        |_| {
+            if amount.minor() < 0 {
+                i64::MIN
+            } else {
+                i64::MAX
+            }
+        },
+        |amount| amount.minor(),
+    )
+}
+
+/// Sum of report amounts in `currency`, added in its minor units.
+pub(crate) fn sum_major(amounts: impl IntoIterator<Item = f64>, currency: &CurrencyCode) -> f64 {
+    let total = amounts.into_iter().fold(0i64, |total, amount| {
+        let minor = Money::from_major(amount, currency.clone()).map_or_else(
+            |_| if amount < 0.0 { i64::MIN } else { i64::MAX },
+            |amount| amount.minor(),
+        );
+        total.saturating_add(minor)
+    });
+    to_major(total, currency)
+}
+
+/// A total of `currency`'s minor units in major units, for reporting.
+pub(crate) fn to_major(minor: i64, currency: &CurrencyCode) -> f64 {
+    Money::from_minor(minor, currency.clone()).to_major()
+}
+
+/// Returns `true` for accounts that hold money (as opposed to income sources
+/// and expense destinations, which only describe where money flows).
+pub(crate) fn is_asset_account(account: &Account) -> bool {
+    matches!(
+        account.kind,
+        AccountKind::Bank | AccountKind::Cash | AccountKind::Savings
+    )
+}
+
+/// Opening balance plus completed flows through `account` up to `today`.
+pub(crate) fn account_balance(
+    account: &Account,
+    currency: &CurrencyCode,
+    transactions: &[Transaction],
+    today: NaiveDate,
+) -> f64 {
+    let running = RunningBalance::completed(account, currency, transactions, NaiveDate::MIN);
+    running.major(running.through(today))
+}
+
+/// Money moving into (positive) or out of (negative) the accounts of a
+/// [`RunningBalance`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BalanceMovement<'a> {
+    pub date: NaiveDate,
+    /// Minor units of the running balance's currency.
+    pub amount: i64,
+    pub transaction: &'a Transaction,
+}
+
+/// Balance of one or more accounts from a start day on: every movement
+/// before that day is folded into `opening`, the rest are kept in date
+/// order for the caller to walk. Amounts are minor units of `currency`.
+pub(crate) struct RunningBalance<'a> {
+    pub currency: CurrencyCode,
+    pub opening: i64,
+    pub movements: Vec<BalanceMovement<'a>>,
+}
+
+impl<'a> RunningBalance<'a> {
+    /// `holds` picks the accounts, and transfers between two of them leave
+    /// the balance alone. `movement` gives the day and amount a transaction
+    /// moves money, or `None` when it does not count.
+    pub(crate) fn new<'m>(
+        currency: &CurrencyCode,
+        opening: impl IntoIterator<Item = &'m Money>,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+        holds: impl Fn(Uuid) -> bool,
+        movement: impl Fn(&Transaction) -> Option<(NaiveDate, &Money)>,
+        start: NaiveDate,
+    ) -> Self {
+        let mut running = Self {
+            currency: currency.clone(),
+            opening: opening.into_iter().fold(0, |total, amount| {
+                total.saturating_add(minor_in(amount, currency))
+            }),
+            movements: Vec::new(),
+        };
+        for txn in transactions {
+            let sign = match (holds(txn.to_account), holds(txn.from_account)) {
+                (true, false) => 1,
+                (false, true) => -1,
+                _ => continue,
+            };
+            let Some((date, amount)) = movement(txn) else {
+                continue;
+            };
+            let amount = minor_in(amount, currency).saturating_mul(sign);
+            if date < start {
+                running.opening = running.opening.saturating_add(amount);
+            } else {
+                running.movements.push(BalanceMovement {
+                    date,
+                    amount,
+                    transaction: txn,
+                });
+            }
+        }
+        running.movements.sort_by_key(|movement| movement.date);
+        running
+    }
+
+    /// Completed movements through `account`, starting from its opening
+    /// balance, counted in `currency`.
+    pub(crate) fn completed(
+        account: &Account,
+        currency: &CurrencyCode,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+        start: NaiveDate,
+    ) -> Self {
+        Self::new(
+            currency,
+            &account.opening_balance,
+            transactions,
+            |id| id == account.id,
+            completed_amount,
+            start,
+        )
+    }
+
+    /// Balance at the end of `day`.
+    pub(crate) fn through(&self, day: NaiveDate) -> i64 {
+        self.movements
+            .iter()
+            .take_while(|movement| movement.date <= day)
+            .fold(self.opening, |balance, movement| {
+                balance.saturating_add(movement.amount)
+            })
+    }
+
+    /// `minor` units of the balance's currency in major units.
+    pub(crate) fn major(&self, minor: i64) -> f64 {
+        to_major(minor, &self.currency)
+    }
+}
+
+/// Window covering the trailing average months, ending with `today`.
+pub(crate) fn trailing_window(today: NaiveDate) -> DateWindow {
+    let end = today.succ_opt().unwrap_or(today);
+    let start = today
+        .checked_sub_months(Months::new(STATS_AVERAGE_MONTHS))
+        .unwrap_or(today);
+    DateWindow { start, end }
+}
+
+/// Tracks the largest absolute movement and the latest activity date.
+#[derive(Default)]
+pub(crate) struct ActivityTracker {
+    pub largest: Option<TransactionHighlight>,
+    pub last_activity: Option<NaiveDate>,
+}
+
+impl ActivityTracker {
+    pub(crate) fn record(&mut self, txn: &Transaction, date: NaiveDate, amount: f64) {
+        if self
+            .largest
+            .as_ref()
+            .is_none_or(|current| amount.abs() > current.amount.abs())
+        {
+            self.largest = Some(TransactionHighlight {
+                transaction_id: txn.id,
+                date,
+                amount,
+            });
+        }
+        if self.last_activity.is_none_or(|last| date > last) {
+            self.last_activity = Some(date);
+        }
+    }
+}
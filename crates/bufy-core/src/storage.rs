@@ -3,9 +3,11 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use bufy_domain::Ledger;
+use chrono::{DateTime, Utc};
 
-use crate::CoreError;
+use bufy_domain::{Ledger, CURRENT_SCHEMA_MINOR, CURRENT_SCHEMA_VERSION};
+
+use crate::{AuditChange, AuditEntry, CoreError, LedgerChange, LedgerEvent, LedgerMigrator};
 
 /// Describes a persisted backup artifact for a ledger.
 #[derive(Debug, Clone)]
@@ -32,6 +34,82 @@ pub trait LedgerStorage: Send + Sync {
     ) -> Result<LedgerBackupInfo, CoreError>;
     fn list_backups(&self, name: &str) -> Result<Vec<LedgerBackupInfo>, CoreError>;
     fn restore_backup(&self, backup: &LedgerBackupInfo) -> Result<Ledger, CoreError>;
+
+    /// Loads a ledger and runs [`prepare_loaded_ledger`] on it, returning the
+    /// migrated ledger with its load report.
+    fn load_migrated(&self, name: &str) -> Result<(Ledger, LoadReport), CoreError> {
+        let mut ledger = self.load_ledger(name)?;
+        let report = prepare_loaded_ledger(&mut ledger)?;
+        Ok((ledger, report))
+    }
+
+    /// Sequence number of the last event in a ledger's change feed, or
+    /// `None` when the feed has not been started.
+    fn change_feed_head(&self, name: &str) -> Result<Option<u64>, CoreError> {
+        let _ = name;
+        Err(change_feed_unsupported())
+    }
+
+    /// Appends changes to a ledger's change feed, numbering them after the
+    /// current head.
+    fn append_changes(
+        &self,
+        name: &str,
+        changes: Vec<LedgerChange>,
+    ) -> Result<Vec<LedgerEvent>, CoreError> {
+        let _ = (name, changes);
+        Err(change_feed_unsupported())
+    }
+
+    /// Reads the events numbered after `after` from a ledger's change feed.
+    fn read_changes(&self, name: &str, after: u64) -> Result<Vec<LedgerEvent>, CoreError> {
+        let _ = (name, after);
+        Err(change_feed_unsupported())
+    }
+
+    /// Replaces a ledger's change feed with one snapshot of `ledger`, keeping
+    /// the sequence numbering.
+    fn compact_changes(&self, name: &str, ledger: &Ledger) -> Result<LedgerEvent, CoreError> {
+        let _ = (name, ledger);
+        Err(change_feed_unsupported())
+    }
+
+    /// Appends changes saved by `actor` to a ledger's audit log, numbering
+    /// them after its last entry. Backends without an audit log keep nothing.
+    fn append_audit(
+        &self,
+        name: &str,
+        actor: &str,
+        changes: Vec<AuditChange>,
+    ) -> Result<Vec<AuditEntry>, CoreError> {
+        let _ = (name, actor, changes);
+        Ok(Vec::new())
+    }
+
+    /// Reads every entry of a ledger's audit log, oldest first.
+    fn read_audit(&self, name: &str) -> Result<Vec<AuditEntry>, CoreError> {
+        let _ = name;
+        Err(CoreError::InvalidOperation(
+            "this storage backend does not keep an audit log".into(),
+        ))
+    }
+
+    /// Drops audit entries recorded before `before` and returns how many
+    /// were removed. Later entries keep their numbers.
+    fn purge_audit(&self, name: &str, before: DateTime<Utc>) -> Result<usize, CoreError> {
+        let _ = (name, before);
+        Ok(0)
+    }
+
+    /// Overwrites an existing backup with `ledger`, keeping its id and
+    /// creation time.
+    fn rewrite_backup(&self, backup: &LedgerBackupInfo, ledger: &Ledger) -> Result<(), CoreError> {
+        self.save_ledger_to_path(ledger, &backup.path)
+    }
+}
+
+fn change_feed_unsupported() -> CoreError {
+    CoreError::InvalidOperation("this storage backend does not keep a change feed".into())
 }
 
 /// Detects dangling references and other anomalies within a ledger snapshot.
@@ -40,6 +118,13 @@ pub fn ledger_warnings(ledger: &Ledger) -> Vec<String> {
     let category_ids: HashSet<_> = ledger.categories.iter().map(|c| c.id).collect();
     let mut warnings = Vec::new();
 
+    if ledger.is_newer_minor_schema() {
+        warnings.push(format!(
+            "ledger was saved by a newer BUFY (schema v{}.{}; this build reads v{}.{}); fields it does not recognize are kept unchanged",
+            ledger.schema_version, ledger.schema_minor, CURRENT_SCHEMA_VERSION, CURRENT_SCHEMA_MINOR
+        ));
+    }
+
     for txn in &ledger.transactions {
         if !account_ids.contains(&txn.from_account) {
             warnings.push(format!(
@@ -72,3 +157,27 @@ pub fn ledger_warnings(ledger: &Ledger) -> Vec<String> {
     }
     warnings
 }
+
+/// What preparing a freshly loaded ledger changed or noticed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadReport {
+    /// Schema version the ledger was stored with, before migrating.
+    pub schema_version: u8,
+    pub migrations: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Brings a ledger read from storage up to the current schema with the
+/// builtin [`LedgerMigrator`] and fills in derived data, so every client sees
+/// the same ledger after a load.
+pub fn prepare_loaded_ledger(ledger: &mut Ledger) -> Result<LoadReport, CoreError> {
+    let schema_version = ledger.schema_version;
+    let migrations = LedgerMigrator::default().migrate(ledger)?;
+    ledger.refresh_recurrence_metadata();
+    ledger.assign_references();
+    Ok(LoadReport {
+        schema_version,
+        migrations,
+        warnings: ledger_warnings(ledger),
+    })
+}
@@ -0,0 +1,296 @@
+//! Operations on allowance-style sub-ledgers nested in a parent ledger.
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{sub_ledger::SubLedger, transaction::Transaction, Ledger, Money};
+
+use crate::stats::{account_balance, completed_movement, is_asset_account};
+use crate::{Clock, CoreError};
+
+/// Figures reported for one sub-ledger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubLedgerSummary {
+    pub name: String,
+    pub balance: f64,
+    /// Deposits completed within the parent's current budget period.
+    pub period_deposits: f64,
+    /// Spending completed within the parent's current budget period.
+    pub period_spending: f64,
+    pub transaction_count: usize,
+}
+
+/// Net worth of a ledger with its sub-ledgers consolidated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetWorth {
+    /// Balance of the parent ledger's bank, cash, and savings accounts.
+    pub main: f64,
+    /// Balance held in each sub-ledger, in ledger order.
+    pub sub_ledgers: Vec<(String, f64)>,
+    pub total: f64,
+}
+
+pub struct SubLedgerService;
+
+impl SubLedgerService {
+    /// Creates a sub-ledger whose wallet starts at `opening_balance`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            member_id = ?member_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn create(
+        ledger: &mut Ledger,
+        name: &str,
+        member_id: Option<Uuid>,
+        opening_balance: f64,
+        clock: &dyn Clock,
+    ) -> Result<Uuid, CoreError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(CoreError::Validation(
+                "sub-ledger name cannot be empty".into(),
+            ));
+        }
+        if ledger.sub_ledger(name).is_some() {
+            return Err(CoreError::Validation(format!(
+                "sub-ledger `{}` already exists",
+                name
+            )));
+        }
+        if let Some(id) = member_id {
+            if ledger.member(id).is_none() {
+                return Err(CoreError::MemberNotFound(id.to_string()));
+            }
+        }
+        if !opening_balance.is_finite() {
+            return Err(CoreError::Validation(
+                "opening balance must be finite".into(),
+            ));
+        }
+        let mut sub = SubLedger::new(name, clock.now());
+        sub.member_id = member_id;
+        if opening_balance != 0.0 {
+            let opening_balance =
+                Money::from_major(opening_balance, ledger.base_currency().clone())?;
+            if let Some(wallet) = sub.account_by_name_mut(SubLedger::WALLET) {
+                wallet.opening_balance = Some(opening_balance);
+            }
+        }
+        let id = sub.id;
+        ledger.sub_ledgers.push(sub);
+        ledger.touch();
+        Ok(id)
+    }
+
+    /// Removes a sub-ledger with all of its accounts and transactions.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn remove(ledger: &mut Ledger, name: &str) -> Result<SubLedger, CoreError> {
+        let index = ledger
+            .sub_ledgers
+            .iter()
+            .position(|sub| sub.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| Self::not_found(name))?;
+        let removed = ledger.sub_ledgers.remove(index);
+        ledger.touch();
+        Ok(removed)
+    }
+
+    /// Records money added to the sub-ledger's wallet.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            amount = amount,
+            date = %date,
+            notes = ?notes
+        ),
+        err(level = "debug")
+    )]
+    pub fn deposit(
+        ledger: &mut Ledger,
+        name: &str,
+        amount: f64,
+        date: NaiveDate,
+        notes: Option<String>,
+    ) -> Result<Uuid, CoreError> {
+        Self::record(
+            ledger,
+            name,
+            SubLedger::DEPOSITS,
+            SubLedger::WALLET,
+            amount,
+            date,
+            notes,
+        )
+    }
+
+    /// Records money spent from the sub-ledger's wallet.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            amount = amount,
+            date = %date,
+            notes = ?notes
+        ),
+        err(level = "debug")
+    )]
+    pub fn spend(
+        ledger: &mut Ledger,
+        name: &str,
+        amount: f64,
+        date: NaiveDate,
+        notes: Option<String>,
+    ) -> Result<Uuid, CoreError> {
+        Self::record(
+            ledger,
+            name,
+            SubLedger::WALLET,
+            SubLedger::SPENDING,
+            amount,
+            date,
+            notes,
+        )
+    }
+
+    /// Summarizes a sub-ledger against the parent's budget period containing `today`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today),
+        err(level = "debug")
+    )]
+    pub fn summary(
+        ledger: &Ledger,
+        name: &str,
+        today: NaiveDate,
+    ) -> Result<SubLedgerSummary, CoreError> {
+        let sub = ledger
+            .sub_ledger(name)
+            .ok_or_else(|| Self::not_found(name))?;
+        let period = ledger.budget_window_containing(today);
+        let wallet = sub.account_by_name(SubLedger::WALLET);
+        let mut period_deposits = 0.0;
+        let mut period_spending = 0.0;
+        for txn in &sub.transactions {
+            let Some((date, amount)) = completed_movement(txn) else {
+                continue;
+            };
+            if !period.contains(date) || date > today {
+                continue;
+            }
+            match wallet {
+                Some(wallet) if txn.to_account == wallet.id => period_deposits += amount,
+                Some(wallet) if txn.from_account == wallet.id => period_spending += amount,
+                _ => {}
+            }
+        }
+        Ok(SubLedgerSummary {
+            name: sub.name.clone(),
+            balance: Self::balance(ledger, sub, today),
+            period_deposits,
+            period_spending,
+            transaction_count: sub.transactions.len(),
+        })
+    }
+
+    /// Consolidates the parent's asset accounts with every sub-ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn net_worth(ledger: &Ledger, today: NaiveDate) -> NetWorth {
+        let main: f64 = ledger
+            .accounts
+            .iter()
+            .filter(|account| is_asset_account(account))
+            .map(|account| {
+                account_balance(
+                    account,
+                    &ledger.holding_currency(account),
+                    &ledger.transactions,
+                    today,
+                )
+            })
+            .sum();
+        let sub_ledgers: Vec<(String, f64)> = ledger
+            .sub_ledgers
+            .iter()
+            .map(|sub| (sub.name.clone(), Self::balance(ledger, sub, today)))
+            .collect();
+        let total = main + sub_ledgers.iter().map(|(_, balance)| balance).sum::<f64>();
+        NetWorth {
+            main,
+            sub_ledgers,
+            total,
+        }
+    }
+
+    fn balance(ledger: &Ledger, sub: &SubLedger, today: NaiveDate) -> f64 {
+        sub.accounts
+            .iter()
+            .filter(|account| is_asset_account(account))
+            .map(|account| {
+                account_balance(
+                    account,
+                    &ledger.holding_currency(account),
+                    &sub.transactions,
+                    today,
+                )
+            })
+            .sum()
+    }
+
+    fn record(
+        ledger: &mut Ledger,
+        name: &str,
+        from: &str,
+        to: &str,
+        amount: f64,
+        date: NaiveDate,
+        notes: Option<String>,
+    ) -> Result<Uuid, CoreError> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(CoreError::Validation("amount must be positive".into()));
+        }
+        let amount = Money::from_major(amount, ledger.base_currency().clone())?;
+        let sub = ledger
+            .sub_ledger_mut(name)
+            .ok_or_else(|| Self::not_found(name))?;
+        let account_id = |sub: &SubLedger, account: &str| {
+            sub.account_by_name(account)
+                .map(|account| account.id)
+                .ok_or_else(|| CoreError::AccountNotFound(account.to_string()))
+        };
+        let from_id = account_id(sub, from)?;
+        let to_id = account_id(sub, to)?;
+        let mut txn = Transaction::new(from_id, to_id, None, date, amount.clone());
+        txn.mark_completed(date, amount);
+        txn.notes = notes.filter(|note| !note.trim().is_empty());
+        let id = txn.id;
+        sub.transactions.push(txn);
+        ledger.touch();
+        Ok(id)
+    }
+
+    fn not_found(name: &str) -> CoreError {
+        CoreError::InvalidOperation(format!("sub-ledger `{}` not found", name.trim()))
+    }
+}
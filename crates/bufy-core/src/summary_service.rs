@@ -1,11 +1,15 @@
 //! Aggregation helpers for budgeting summaries and forecasts.
 
+use std::collections::HashMap;
+
 use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
 
 use bufy_domain::{
     ledger::{
-        BudgetScope, BudgetSummary, CategoryBudgetAssignment, CategoryBudgetStatus,
-        CategoryBudgetSummary, CategoryBudgetSummaryKind, DateWindow,
+        BudgetScope, BudgetSummary, BudgetTotals, CategoryBudget, CategoryBudgetAssignment,
+        CategoryBudgetStatus, CategoryBudgetSummary, CategoryBudgetSummaryKind, DateWindow,
     },
     simulation::SimulationBudgetImpact,
     ForecastReport, Ledger,
@@ -21,11 +25,21 @@ pub struct SummaryService;
 
 impl SummaryService {
     /// Summarizes the ledger's current budget window.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn current_totals(ledger: &Ledger, clock: &dyn Clock) -> BudgetSummary {
         BudgetService::summarize_current_period(ledger, clock)
     }
 
     /// Summarizes the supplied window and scope against the ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn summarize_window(
         ledger: &Ledger,
         window: DateWindow,
@@ -34,7 +48,48 @@ impl SummaryService {
         BudgetService::summarize_window_scope(ledger, window, scope)
     }
 
+    /// Like [`Self::summarize_window`], but fails with
+    /// [`CoreError::NumericOverflow`] when a total loses precision.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn summarize_window_checked(
+        ledger: &Ledger,
+        window: DateWindow,
+        scope: BudgetScope,
+    ) -> Result<BudgetSummary, CoreError> {
+        BudgetService::summarize_window_checked(ledger, window, scope)
+    }
+
+    /// Like [`Self::summarize_window_checked`], but with subcategories rolled
+    /// up: `per_category` lists top-level categories only, each totalling
+    /// itself and every descendant, with one subtotal per direct child in
+    /// [`CategoryBudget::children`].
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn summarize_window_with_rollup(
+        ledger: &Ledger,
+        window: DateWindow,
+        scope: BudgetScope,
+    ) -> Result<BudgetSummary, CoreError> {
+        let mut summary = BudgetService::summarize_window_checked(ledger, window, scope)?;
+        summary.per_category = roll_up_categories(ledger, summary.per_category)?;
+        Ok(summary)
+    }
+
     /// Returns category budget usage for the supplied window.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn category_budget_statuses(
         ledger: &Ledger,
         window: DateWindow,
@@ -44,6 +99,11 @@ impl SummaryService {
     }
 
     /// Returns category budget usage for the ledger's current budgeting period.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn current_category_budget_statuses(
         ledger: &Ledger,
         clock: &dyn Clock,
@@ -55,11 +115,21 @@ impl SummaryService {
     }
 
     /// Lists every category with an explicit budget assignment.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn categories_with_budgets(ledger: &Ledger) -> Vec<CategoryBudgetAssignment> {
         BudgetService::categories_with_budgets(ledger)
     }
 
     /// Provides detailed category budget summaries for the supplied window.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn category_budget_summaries(
         ledger: &Ledger,
         window: DateWindow,
@@ -74,6 +144,12 @@ impl SummaryService {
     }
 
     /// Summarizes the impact of a simulation in a specific window and scope.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
     pub fn summarize_simulation(
         ledger: &Ledger,
         simulation_name: &str,
@@ -84,6 +160,16 @@ impl SummaryService {
     }
 
     /// Produces a forecast report for the given window and optional simulation.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            reference = %reference
+        ),
+        err(level = "debug")
+    )]
     pub fn forecast_window(
         ledger: &Ledger,
         window: DateWindow,
@@ -93,3 +179,79 @@ impl SummaryService {
         ForecastService::window_report(ledger, window, reference, simulation)
     }
 }
+
+/// Nests `rows` under their parent categories. Rows without a known
+/// category, and any left over by a broken parent chain, stay top level.
+fn roll_up_categories(
+    ledger: &Ledger,
+    rows: Vec<CategoryBudget>,
+) -> Result<Vec<CategoryBudget>, CoreError> {
+    let mut own: HashMap<Uuid, CategoryBudget> = HashMap::new();
+    let mut rolled = Vec::new();
+    for row in rows {
+        match row.category_id.filter(|id| ledger.category(*id).is_some()) {
+            Some(id) => {
+                own.insert(id, row);
+            }
+            None => rolled.push(row),
+        }
+    }
+    let mut children: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for category in &ledger.categories {
+        if let Some(parent) = category.parent_id {
+            children.entry(parent).or_default().push(category.id);
+        }
+    }
+    for category in &ledger.categories {
+        let is_root = category
+            .parent_id
+            .is_none_or(|parent| ledger.category(parent).is_none());
+        if is_root {
+            if let Some(row) = roll_up_node(ledger, category.id, &mut own, &children)? {
+                rolled.push(row);
+            }
+        }
+    }
+    rolled.extend(own.into_values());
+    rolled.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(rolled)
+}
+
+fn roll_up_node(
+    ledger: &Ledger,
+    id: Uuid,
+    own: &mut HashMap<Uuid, CategoryBudget>,
+    children: &HashMap<Uuid, Vec<Uuid>>,
+) -> Result<Option<CategoryBudget>, CoreError> {
+    let mut rows = Vec::new();
+    for child in children.get(&id).into_iter().flatten() {
+        if let Some(row) = roll_up_node(ledger, *child, own, children)? {
+            rows.push(row);
+        }
+    }
+    let row = own.remove(&id);
+    if row.is_none() && rows.is_empty() {
+        return Ok(None);
+    }
+    rows.sort_by(|a, b| a.name.cmp(&b.name));
+    let (mut budgeted, mut real, mut incomplete) = row
+        .as_ref()
+        .map(|row| (row.totals.budgeted, row.totals.real, row.totals.incomplete))
+        .unwrap_or_default();
+    for child in &rows {
+        budgeted += child.totals.budgeted;
+        real += child.totals.real;
+        incomplete |= child.totals.incomplete;
+    }
+    let name = ledger
+        .category(id)
+        .map(|category| category.name.clone())
+        .unwrap_or_default();
+    Ok(Some(CategoryBudget {
+        category_id: Some(id),
+        name,
+        totals: BudgetTotals::checked(budgeted, real, incomplete)?,
+        note: row.and_then(|row| row.note),
+        children: rows,
+    }))
+}
@@ -1,17 +1,31 @@
 use chrono::NaiveDate;
 
 use crate::{
-    account_service::AccountService, category_service::CategoryService,
-    ledger_service::LedgerService, summary_service::SummaryService,
-    transaction_service::TransactionService,
+    account_service::AccountService,
+    alert_service::AlertService,
+    category_service::CategoryService,
+    ledger_service::LedgerService,
+    migration::LedgerMigrator,
+    summary_service::SummaryService,
+    transaction_service::{TransactionQuery, TransactionService},
+    validation::MAX_NAME_LENGTH,
+    CoreError, ValidationIssueKind,
 };
 use bufy_domain::{
-    account::{Account, AccountKind},
+    account::{Account, AccountKind, AccountStatus},
+    alert::{AlertCounts, AlertKind},
+    appearance::{Appearance, EntityColor},
     category::{Category, CategoryKind},
-    common::{BudgetPeriod, Identifiable},
-    LedgerBudgetPeriod, Transaction, TransactionStatus,
+    common::{BudgetPeriod, Identifiable, TimeInterval, TimeUnit},
+    currency::CurrencyCode,
+    transaction::{Recurrence, RecurrenceMode},
+    LedgerBudgetPeriod, Money, Transaction, TransactionStatus,
 };
 
+fn usd(amount: f64) -> Money {
+    Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+}
+
 #[test]
 fn ledger_service_creates_empty_ledger() {
     let ledger = LedgerService::create("CoreTest", LedgerBudgetPeriod::monthly());
@@ -49,7 +63,7 @@ fn category_service_assigns_budget() {
     let stored = ledger.category(category_id).expect("category exists");
     assert!(stored.budget().is_some());
     let budget = stored.budget().unwrap();
-    assert_eq!(budget.amount, 500.0);
+    assert_eq!(budget.amount, usd(500.0));
     assert_eq!(budget.period, BudgetPeriod::Monthly);
 }
 
@@ -61,16 +75,278 @@ fn transaction_service_adds_and_updates_transactions() {
     AccountService::add(&mut ledger, account).expect("add account");
 
     let planned = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
-    let transaction = Transaction::new(account_id, account_id, None, planned, 100.0);
+    let transaction = Transaction::new(account_id, account_id, None, planned, usd(100.0));
     let txn_id = TransactionService::add(&mut ledger, transaction).expect("add transaction");
     assert_eq!(ledger.transactions.len(), 1);
 
     let actual = NaiveDate::from_ymd_opt(2025, 1, 2).unwrap();
-    TransactionService::update(&mut ledger, txn_id, |txn| txn.mark_completed(actual, 125.0))
-        .expect("complete transaction");
+    TransactionService::update(&mut ledger, txn_id, |txn| {
+        txn.mark_completed(actual, usd(125.0))
+    })
+    .expect("complete transaction");
     let stored = ledger.transaction(txn_id).expect("transaction exists");
     assert_eq!(stored.status, TransactionStatus::Completed);
-    assert_eq!(stored.actual_amount, Some(125.0));
+    assert_eq!(stored.actual_amount, Some(usd(125.0)));
+}
+
+#[test]
+fn transaction_service_query_applies_all_criteria() {
+    let mut ledger = LedgerService::create("Query", LedgerBudgetPeriod::monthly());
+    let checking_account = Account::new("Checking", AccountKind::Bank);
+    let savings_account = Account::new("Savings", AccountKind::Savings);
+    let checking = checking_account.id();
+    let savings = savings_account.id();
+    AccountService::add(&mut ledger, checking_account).expect("add account");
+    AccountService::add(&mut ledger, savings_account).expect("add account");
+    let food = Category::new("Food", CategoryKind::Expense);
+    let food_id = food.id();
+    CategoryService::add(&mut ledger, food).expect("add category");
+
+    let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+    let small = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking, savings, Some(food_id), day(2), usd(20.0)),
+    )
+    .unwrap();
+    let large = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking, savings, Some(food_id), day(10), usd(200.0)),
+    )
+    .unwrap();
+    TransactionService::add(
+        &mut ledger,
+        Transaction::new(savings, savings, None, day(15), usd(50.0)),
+    )
+    .unwrap();
+    TransactionService::update(&mut ledger, large, |txn| {
+        txn.mark_completed(day(11), usd(200.0))
+    })
+    .unwrap();
+
+    assert_eq!(
+        TransactionService::query(&ledger, &TransactionQuery::default()).len(),
+        3
+    );
+
+    let by_account = TransactionQuery {
+        account_id: Some(checking),
+        ..TransactionQuery::default()
+    };
+    assert_eq!(TransactionService::query(&ledger, &by_account).len(), 2);
+
+    let by_window = TransactionQuery {
+        from: Some(day(1)),
+        to: Some(day(10)),
+        category_id: Some(food_id),
+        max_amount: Some(100.0),
+        ..TransactionQuery::default()
+    };
+    let ids: Vec<_> = TransactionService::query(&ledger, &by_window)
+        .iter()
+        .map(|txn| txn.id)
+        .collect();
+    assert_eq!(ids, vec![small]);
+
+    let completed = TransactionQuery {
+        status: Some(TransactionStatus::Completed),
+        min_amount: Some(100.0),
+        ..TransactionQuery::default()
+    };
+    let ids: Vec<_> = TransactionService::query(&ledger, &completed)
+        .iter()
+        .map(|txn| txn.id)
+        .collect();
+    assert_eq!(ids, vec![large]);
+}
+
+#[test]
+fn transaction_service_query_sorts_pages_and_matches_notes() {
+    use crate::transaction_service::{TransactionSort, TransactionSortKey};
+
+    let mut ledger = LedgerService::create("Search", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+    let mut add = |d, amount, notes: Option<&str>| {
+        let mut txn = Transaction::new(checking, shop, None, day(d), amount);
+        txn.notes = notes.map(str::to_string);
+        ledger.add_transaction(txn)
+    };
+    let rent = add(1, usd(900.0), Some("March rent"));
+    let coffee = add(9, usd(4.5), Some("Coffee with Sam"));
+    let books = add(5, usd(30.0), None);
+    ledger.transactions[2].merchant = Some("Corner Bookshop".into());
+    let mut gym = Transaction::new(checking, shop, None, day(3), usd(40.0));
+    gym.set_recurrence(Some(Recurrence::new(
+        day(3),
+        TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        RecurrenceMode::FixedSchedule,
+    )));
+    let gym = ledger.add_transaction(gym);
+    let ids = |query: &TransactionQuery| -> Vec<_> {
+        TransactionService::query(&ledger, query)
+            .iter()
+            .map(|txn| txn.id)
+            .collect()
+    };
+
+    let notes = TransactionQuery {
+        notes: Some("RENT".into()),
+        ..TransactionQuery::default()
+    };
+    assert_eq!(ids(&notes), vec![rent]);
+    let text = |needle: &str| TransactionQuery {
+        text: Some(needle.into()),
+        ..TransactionQuery::default()
+    };
+    assert_eq!(ids(&text("bookshop")), vec![books]);
+    assert_eq!(ids(&text("coffee")), vec![coffee]);
+    assert!(ids(&text("gym")).is_empty());
+
+    let recurring = TransactionQuery {
+        recurring: Some(true),
+        ..TransactionQuery::default()
+    };
+    assert_eq!(ids(&recurring), vec![gym]);
+    let one_off = TransactionQuery {
+        recurring: Some(false),
+        ..TransactionQuery::default()
+    };
+    assert_eq!(ids(&one_off), vec![rent, coffee, books]);
+
+    let by_date = TransactionQuery {
+        sort: Some(TransactionSort::ascending(TransactionSortKey::Date)),
+        ..TransactionQuery::default()
+    };
+    assert_eq!(ids(&by_date), vec![rent, gym, books, coffee]);
+
+    let second_page = TransactionQuery {
+        sort: Some(TransactionSort::descending(TransactionSortKey::Amount)),
+        offset: 2,
+        limit: Some(2),
+        ..TransactionQuery::default()
+    };
+    assert_eq!(ids(&second_page), vec![books, coffee]);
+    assert_eq!(TransactionService::count(&ledger, &second_page), 4);
+}
+
+#[test]
+fn account_and_category_stats_aggregate_completed_activity() {
+    let mut ledger = LedgerService::create("Stats", LedgerBudgetPeriod::monthly());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(1000.0));
+    let checking_id = checking.id();
+    let savings = Account::new("Savings", AccountKind::Savings);
+    let savings_id = savings.id();
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    AccountService::add(&mut ledger, savings).expect("add savings");
+    let category = Category::new("Food", CategoryKind::Expense);
+    let category_id = category.id();
+    CategoryService::add(&mut ledger, category).expect("add category");
+
+    let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let mut add = |scheduled: NaiveDate, amount: f64, completed: bool| {
+        let mut txn = Transaction::new(
+            checking_id,
+            savings_id,
+            Some(category_id),
+            scheduled,
+            usd(amount),
+        );
+        if completed {
+            txn.mark_completed(scheduled, usd(amount));
+        }
+        TransactionService::add(&mut ledger, txn).expect("add transaction")
+    };
+    add(NaiveDate::from_ymd_opt(2024, 11, 1).unwrap(), 100.0, true);
+    add(date(1, 10), 50.0, true);
+    let largest = add(date(3, 5), 200.0, true);
+    add(date(3, 25), 500.0, false);
+
+    let today = date(3, 20);
+    let account = AccountService::stats(&ledger, checking_id, today).expect("account stats");
+    assert_eq!(account.balance, 650.0);
+    assert_eq!(account.period_net, -200.0);
+    assert!((account.monthly_average + 250.0 / 3.0).abs() < 1e-9);
+    let highlight = account.largest_transaction.expect("largest transaction");
+    assert_eq!(highlight.transaction_id, largest);
+    assert_eq!(highlight.amount, -200.0);
+    assert_eq!(account.last_activity, Some(date(3, 5)));
+
+    let savings = AccountService::stats(&ledger, savings_id, today).expect("savings stats");
+    assert_eq!(savings.balance, 350.0);
+
+    let category = CategoryService::stats(&ledger, category_id, today).expect("category stats");
+    assert_eq!(category.period_spend, 200.0);
+    assert!((category.monthly_average - 250.0 / 3.0).abs() < 1e-9);
+    assert_eq!(category.last_activity, Some(date(3, 5)));
+}
+
+#[test]
+fn alert_service_reports_over_budget_low_balance_and_overdue() {
+    let mut ledger = LedgerService::create("Alerts", LedgerBudgetPeriod::monthly());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(100.0));
+    let checking_id = checking.id();
+    let grocer = Account::new("Grocer", AccountKind::ExpenseDestination);
+    let grocer_id = grocer.id();
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    AccountService::add(&mut ledger, grocer).expect("add grocer");
+    let category = Category::new("Food", CategoryKind::Expense);
+    let category_id = category.id();
+    CategoryService::add(&mut ledger, category).expect("add category");
+    CategoryService::set_budget(&mut ledger, category_id, 100.0, BudgetPeriod::Monthly, None)
+        .expect("set budget");
+
+    let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let mut groceries = Transaction::new(
+        checking_id,
+        grocer_id,
+        Some(category_id),
+        date(3, 2),
+        usd(150.0),
+    );
+    groceries.mark_completed(date(3, 2), usd(150.0));
+    TransactionService::add(&mut ledger, groceries).expect("add groceries");
+
+    let mut gym = Transaction::new(checking_id, grocer_id, None, date(1, 1), usd(20.0));
+    gym.notes = Some("Gym".into());
+    gym.set_recurrence(Some(Recurrence::new(
+        date(1, 1),
+        TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        RecurrenceMode::FixedSchedule,
+    )));
+    TransactionService::add(&mut ledger, gym).expect("add recurrence");
+
+    let alerts = AlertService::evaluate(&ledger, date(3, 20));
+    let counts = AlertCounts::from_alerts(&alerts);
+    assert_eq!(counts.over_budget, 1);
+    assert_eq!(counts.low_balance, 1);
+    assert_eq!(counts.overdue_recurrences, 1);
+    let over = alerts
+        .iter()
+        .find(|alert| alert.kind == AlertKind::OverBudget)
+        .expect("over-budget alert");
+    assert_eq!(over.subject_id, category_id);
+    let low = alerts
+        .iter()
+        .find(|alert| alert.kind == AlertKind::LowBalance)
+        .expect("low-balance alert");
+    assert_eq!(low.subject, "Checking");
+    let overdue = alerts
+        .iter()
+        .find(|alert| alert.kind == AlertKind::OverdueRecurrence)
+        .expect("overdue alert");
+    assert_eq!(overdue.subject, "Gym");
+
+    let summary = crate::api_ledger_summary(&ledger, date(3, 20));
+    assert_eq!(summary.alerts, alerts);
 }
 
 #[test]
@@ -86,3 +362,4248 @@ fn summary_service_lists_budget_assignments() {
     assert_eq!(assignments.len(), 1);
     assert_eq!(assignments[0].category_id, category_id);
 }
+
+#[test]
+fn public_api_adds_transactions_in_minor_units() {
+    let mut ledger = LedgerService::create("Minor", LedgerBudgetPeriod::monthly());
+    let account = Account::new("Checking", AccountKind::Bank);
+    let account_id = account.id();
+    AccountService::add(&mut ledger, account).expect("add account");
+    assert_eq!(crate::api_currency_precision(&ledger), 2);
+
+    let planned = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+    let base_id = crate::api_add_transaction_minor(
+        &mut ledger,
+        account_id,
+        account_id,
+        None,
+        planned,
+        12_345,
+        None,
+        None,
+    )
+    .expect("add base-currency transaction");
+    let yen_id = crate::api_add_transaction_minor(
+        &mut ledger,
+        account_id,
+        account_id,
+        None,
+        planned,
+        500,
+        Some("jpy".into()),
+        None,
+    )
+    .expect("add yen transaction");
+
+    assert_eq!(
+        ledger.transaction(base_id).unwrap().budgeted_amount,
+        usd(123.45)
+    );
+    let yen = ledger.transaction(yen_id).unwrap();
+    assert_eq!(
+        yen.budgeted_amount,
+        Money::from_minor(500, CurrencyCode::new("JPY"))
+    );
+    assert_eq!(yen.currency.as_deref(), Some("JPY"));
+
+    let err = crate::api_add_transaction_minor(
+        &mut ledger,
+        account_id,
+        account_id,
+        None,
+        planned,
+        100,
+        Some("dollars".into()),
+        None,
+    );
+    assert!(err.is_err());
+}
+
+#[test]
+fn public_api_lists_transactions_and_full_budget_summary() {
+    let mut ledger = LedgerService::create("Screens", LedgerBudgetPeriod::monthly());
+    let checking =
+        crate::api_add_account(&mut ledger, "Checking", AccountKind::Bank, None).unwrap();
+    let shop =
+        crate::api_add_account(&mut ledger, "Shop", AccountKind::ExpenseDestination, None).unwrap();
+    let food = crate::api_add_category(&mut ledger, "Food", CategoryKind::Expense, None).unwrap();
+    let snacks =
+        crate::api_add_category(&mut ledger, "Snacks", CategoryKind::Expense, Some(food)).unwrap();
+    assert_eq!(ledger.category(snacks).unwrap().parent_id, Some(food));
+    assert!(crate::api_add_category(&mut ledger, "food", CategoryKind::Expense, None).is_err());
+    CategoryService::set_budget(&mut ledger, food, 200.0, BudgetPeriod::Monthly, None).unwrap();
+
+    let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+    for (date, amount) in [(day(2), 20.0), (day(9), 35.0)] {
+        crate::api_add_transaction(&mut ledger, checking, shop, Some(food), date, amount, None)
+            .unwrap();
+    }
+    crate::api_add_transaction(
+        &mut ledger,
+        checking,
+        shop,
+        None,
+        NaiveDate::from_ymd_opt(2025, 4, 2).unwrap(),
+        5.0,
+        None,
+    )
+    .unwrap();
+
+    let rows = crate::api_list_transactions(&ledger, Some(day(1)), Some(day(31)));
+    let dates: Vec<_> = rows.iter().map(|row| row.scheduled_date).collect();
+    assert_eq!(dates, [day(9), day(2)], "newest first, April left out");
+    assert_eq!(rows[0].from_account, "Checking");
+    assert_eq!(rows[0].to_account, "Shop");
+    assert_eq!(rows[0].category.as_deref(), Some("Food"));
+    assert_eq!(rows[1].reference.as_deref(), Some("T-0001"));
+    assert_eq!(crate::api_list_transactions(&ledger, None, None).len(), 3);
+
+    let summary = crate::api_budget_summary(&ledger, day(20));
+    assert_eq!(summary.summary.window.start, day(1));
+    assert_eq!(summary.category_budgets.len(), 1);
+    assert_eq!(summary.category_budgets[0].budget_amount, 200.0);
+    let json = serde_json::to_value(&summary).unwrap();
+    assert_eq!(json["reference_date"], "2025-03-20");
+    assert!(json["per_category"].is_array(), "{json}");
+}
+
+struct FixedClock(chrono::DateTime<chrono::Utc>);
+
+impl crate::Clock for FixedClock {
+    fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        self.0
+    }
+}
+
+#[test]
+fn view_service_saves_runs_and_manages_views() {
+    use bufy_domain::view::{TransactionFilter, ViewColumn};
+
+    use crate::view_service::ViewService;
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Views", LedgerBudgetPeriod::monthly());
+    let account = Account::new("Checking", AccountKind::Bank);
+    let account_id = account.id();
+    AccountService::add(&mut ledger, account).expect("add account");
+    let travel = Category::new("Travel", CategoryKind::Expense);
+    let travel_id = travel.id();
+    CategoryService::add(&mut ledger, travel).expect("add category");
+    let day = NaiveDate::from_ymd_opt(2025, 4, 2).unwrap();
+    TransactionService::add(
+        &mut ledger,
+        Transaction::new(account_id, account_id, Some(travel_id), day, usd(80.0)),
+    )
+    .unwrap();
+    TransactionService::add(
+        &mut ledger,
+        Transaction::new(account_id, account_id, None, day, usd(15.0)),
+    )
+    .unwrap();
+
+    let filter = TransactionFilter {
+        category: Some("travel".into()),
+        ..TransactionFilter::default()
+    };
+    ViewService::save(
+        &mut ledger,
+        "Reimbursable",
+        filter,
+        vec![ViewColumn::Date, ViewColumn::Budgeted],
+        &clock,
+    )
+    .expect("save view");
+    let (view, matches) = ViewService::run(&ledger, "reimbursable").expect("run view");
+    assert_eq!(view.columns, vec![ViewColumn::Date, ViewColumn::Budgeted]);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].budgeted_amount, usd(80.0));
+
+    // Saving under an existing name replaces the definition in place.
+    ViewService::save(
+        &mut ledger,
+        "REIMBURSABLE",
+        TransactionFilter::default(),
+        Vec::new(),
+        &clock,
+    )
+    .expect("replace view");
+    assert_eq!(ledger.views().len(), 1);
+    assert_eq!(ledger.views()[0].columns, ViewColumn::default_columns());
+    assert_eq!(
+        ViewService::run(&ledger, "Reimbursable").unwrap().1.len(),
+        2
+    );
+
+    ViewService::rename(&mut ledger, "Reimbursable", "Everything", &clock).expect("rename");
+    assert!(ledger.view("Reimbursable").is_none());
+    ViewService::remove(&mut ledger, "everything").expect("remove");
+    assert!(matches!(
+        ViewService::run(&ledger, "Everything"),
+        Err(crate::CoreError::ViewNotFound(_))
+    ));
+}
+
+#[test]
+fn categorization_service_learns_from_corrections() {
+    use crate::categorization_service::CategorizationService;
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Learning", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id();
+    AccountService::add(&mut ledger, checking).expect("add account");
+    let city = Account::new("City", AccountKind::ExpenseDestination);
+    let city_id = city.id();
+    AccountService::add(&mut ledger, city).expect("add account");
+    let groceries = Category::new("Groceries", CategoryKind::Expense);
+    let groceries_id = groceries.id();
+    CategoryService::add(&mut ledger, groceries).expect("add category");
+    let day = NaiveDate::from_ymd_opt(2025, 5, 3).unwrap();
+
+    let mut corrected = Transaction::new(checking_id, checking_id, None, day, usd(42.0));
+    corrected.notes = Some("WHOLE FOODS #123".into());
+    let corrected_id = TransactionService::add(&mut ledger, corrected).unwrap();
+    TransactionService::update(&mut ledger, corrected_id, |txn| {
+        txn.category_id = Some(groceries_id)
+    })
+    .unwrap();
+    let learned = CategorizationService::learn(&mut ledger, corrected_id, &clock).unwrap();
+    assert!(learned > 0);
+    assert!(ledger
+        .learned_rules()
+        .iter()
+        .any(|rule| rule.pattern == "whole foods" && rule.category_id == groceries_id));
+
+    let mut imported = Transaction::new(checking_id, checking_id, None, day, usd(17.0));
+    imported.notes = Some("Whole Foods Market".into());
+    assert!(CategorizationService::apply(&ledger, &mut imported));
+    assert_eq!(imported.category_id, Some(groceries_id));
+
+    let mut unrelated = Transaction::new(checking_id, city_id, None, day, usd(9.0));
+    unrelated.notes = Some("Parking".into());
+    assert!(!CategorizationService::apply(&ledger, &mut unrelated));
+
+    CategorizationService::forget(&mut ledger, "Whole Foods").unwrap();
+    assert!(CategorizationService::forget(&mut ledger, "whole foods").is_err());
+    assert!(CategorizationService::forget_all(&mut ledger) > 0);
+    assert!(ledger.learned_rules().is_empty());
+}
+
+#[test]
+fn member_service_attributes_transactions_and_breaks_down_summaries() {
+    use crate::member_service::MemberService;
+    use bufy_domain::{ledger::BudgetScope, DateWindow};
+
+    let mut ledger = LedgerService::create("Household", LedgerBudgetPeriod::monthly());
+    let account = Account::new("Checking", AccountKind::Bank);
+    let account_id = account.id();
+    AccountService::add(&mut ledger, account).expect("add account");
+    let alex = MemberService::add(&mut ledger, "Alex").expect("add member");
+    assert!(MemberService::add(&mut ledger, " alex ").is_err());
+
+    let day = NaiveDate::from_ymd_opt(2025, 6, 10).unwrap();
+    let by_alex = TransactionService::add(
+        &mut ledger,
+        Transaction::new(account_id, account_id, None, day, usd(30.0)),
+    )
+    .unwrap();
+    TransactionService::add(
+        &mut ledger,
+        Transaction::new(account_id, account_id, None, day, usd(12.0)),
+    )
+    .unwrap();
+    MemberService::assign(&mut ledger, by_alex, Some(alex)).expect("assign member");
+
+    let query = TransactionService::resolve_filter(
+        &ledger,
+        &bufy_domain::view::TransactionFilter {
+            member: Some("ALEX".into()),
+            ..Default::default()
+        },
+    )
+    .expect("resolve member filter");
+    let ids: Vec<_> = TransactionService::query(&ledger, &query)
+        .iter()
+        .map(|txn| txn.id)
+        .collect();
+    assert_eq!(ids, vec![by_alex]);
+
+    let window = DateWindow::new(day, day.succ_opt().unwrap()).unwrap();
+    let summary = SummaryService::summarize_window(&ledger, window, BudgetScope::Custom);
+    let breakdown: Vec<_> = summary
+        .per_member
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.totals.budgeted))
+        .collect();
+    assert_eq!(breakdown, vec![("Alex", 30.0), ("Unassigned", 12.0)]);
+
+    MemberService::remove(&mut ledger, alex).expect("remove member");
+    assert!(ledger
+        .transactions
+        .iter()
+        .all(|txn| txn.member_id.is_none()));
+    let summary = SummaryService::summarize_window(&ledger, window, BudgetScope::Custom);
+    assert!(summary.per_member.is_empty());
+}
+
+#[test]
+fn rollup_summaries_fold_subcategories_into_parents() {
+    use bufy_domain::{ledger::BudgetScope, DateWindow};
+
+    let mut ledger = LedgerService::create("Rollup", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let food = ledger.add_category(Category::new("Food", CategoryKind::Expense));
+    let mut groceries = Category::new("Groceries", CategoryKind::Expense);
+    groceries.parent_id = Some(food);
+    let groceries = ledger.add_category(groceries);
+    let mut restaurants = Category::new("Restaurants", CategoryKind::Expense);
+    restaurants.parent_id = Some(food);
+    let restaurants = ledger.add_category(restaurants);
+    let mut takeout = Category::new("Takeout", CategoryKind::Expense);
+    takeout.parent_id = Some(restaurants);
+    let takeout = ledger.add_category(takeout);
+    let rent = ledger.add_category(Category::new("Rent", CategoryKind::Expense));
+    let day = NaiveDate::from_ymd_opt(2025, 5, 3).unwrap();
+    for (category, amount) in [
+        (Some(groceries), 40.0),
+        (Some(restaurants), 25.0),
+        (Some(takeout), 10.0),
+        (Some(rent), 900.0),
+        (None, 5.0),
+    ] {
+        ledger.add_transaction(Transaction::new(checking, shop, category, day, usd(amount)));
+    }
+
+    let window = DateWindow::new(day, day.succ_opt().unwrap()).unwrap();
+    let flat = SummaryService::summarize_window(&ledger, window, BudgetScope::Custom);
+    assert_eq!(flat.per_category.len(), 5);
+    assert!(flat.per_category.iter().all(|row| row.children.is_empty()));
+
+    let rolled = SummaryService::summarize_window_with_rollup(&ledger, window, BudgetScope::Custom)
+        .expect("rollup");
+    let top: Vec<_> = rolled
+        .per_category
+        .iter()
+        .map(|row| (row.name.as_str(), row.totals.budgeted))
+        .collect();
+    assert_eq!(
+        top,
+        vec![("Food", 75.0), ("Rent", 900.0), ("Uncategorized", 5.0)]
+    );
+    let food_row = &rolled.per_category[0];
+    let children: Vec<_> = food_row
+        .children
+        .iter()
+        .map(|row| (row.name.as_str(), row.totals.budgeted))
+        .collect();
+    assert_eq!(children, vec![("Groceries", 40.0), ("Restaurants", 35.0)]);
+    assert_eq!(food_row.children[1].children[0].name, "Takeout");
+    assert_eq!(rolled.totals.budgeted, flat.totals.budgeted);
+}
+
+#[test]
+fn sub_ledgers_stay_out_of_summaries_but_count_toward_net_worth() {
+    use crate::sub_ledger_service::SubLedgerService;
+    use bufy_domain::{ledger::BudgetScope, DateWindow};
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Family", LedgerBudgetPeriod::monthly());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(500.0));
+    AccountService::add(&mut ledger, checking).expect("add account");
+
+    SubLedgerService::create(&mut ledger, "Mia", None, 5.0, &clock).expect("create sub-ledger");
+    assert!(SubLedgerService::create(&mut ledger, "mia", None, 0.0, &clock).is_err());
+    let day = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+    SubLedgerService::deposit(&mut ledger, "mia", 10.0, day, None).expect("deposit");
+    SubLedgerService::spend(&mut ledger, "Mia", 3.5, day, Some("Comic".into())).expect("spend");
+    assert!(SubLedgerService::spend(&mut ledger, "Mia", -1.0, day, None).is_err());
+
+    let summary = SubLedgerService::summary(&ledger, "Mia", day).expect("summary");
+    assert_eq!(summary.balance, 11.5);
+    assert_eq!(summary.period_deposits, 10.0);
+    assert_eq!(summary.period_spending, 3.5);
+
+    let window = DateWindow::new(day, day.succ_opt().unwrap()).unwrap();
+    let parent = SummaryService::summarize_window(&ledger, window, BudgetScope::Custom);
+    assert_eq!(parent.totals.budgeted, 0.0);
+    assert!(ledger.transactions.is_empty());
+
+    let worth = SubLedgerService::net_worth(&ledger, day);
+    assert_eq!(worth.main, 500.0);
+    assert_eq!(worth.sub_ledgers, vec![("Mia".to_string(), 11.5)]);
+    assert_eq!(worth.total, 511.5);
+}
+
+#[test]
+fn sinking_funds_spread_bills_and_flag_shortfalls() {
+    use crate::sinking_fund_service::SinkingFundService;
+    use bufy_domain::{ledger::BudgetScope, DateWindow};
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Bills", LedgerBudgetPeriod::monthly());
+    let yearly = TimeInterval {
+        every: 1,
+        unit: TimeUnit::Year,
+    };
+    let due = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    SinkingFundService::create(
+        &mut ledger,
+        "Car tax",
+        365.0,
+        due,
+        yearly.clone(),
+        None,
+        &clock,
+    )
+    .expect("create fund");
+    assert!(
+        SinkingFundService::create(&mut ledger, "car tax", 10.0, due, yearly, None, &clock)
+            .is_err()
+    );
+
+    // Halfway through the cycle with nothing saved: behind by half the bill.
+    let today = NaiveDate::from_ymd_opt(2025, 7, 2).unwrap();
+    let status = SinkingFundService::status(ledger.sinking_fund("Car tax").unwrap(), today);
+    assert!((status.expected - 182.0).abs() < 1e-9);
+    assert!(status.is_underfunded());
+    assert!((status.monthly_set_aside - 365.0 / 6.0).abs() < 1e-9);
+    let alerts = AlertService::evaluate(&ledger, today);
+    assert_eq!(
+        AlertCounts::from_alerts(&alerts).underfunded_sinking_funds,
+        1
+    );
+    assert_eq!(alerts[0].kind, AlertKind::UnderfundedSinkingFund);
+
+    SinkingFundService::contribute(&mut ledger, "car tax", 200.0).expect("contribute");
+    let status = SinkingFundService::status(ledger.sinking_fund("Car tax").unwrap(), today);
+    assert!(!status.is_underfunded());
+    assert!(AlertService::evaluate(&ledger, today).is_empty());
+
+    let july = DateWindow::new(
+        NaiveDate::from_ymd_opt(2025, 7, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2025, 8, 1).unwrap(),
+    )
+    .unwrap();
+    let summary = SummaryService::summarize_window(&ledger, july, BudgetScope::Custom);
+    assert_eq!(summary.earmarked.len(), 1);
+    assert!((summary.earmarked[0].amount - 31.0).abs() < 1e-9);
+
+    let next = SinkingFundService::pay(&mut ledger, "Car tax").expect("pay");
+    assert_eq!(next, NaiveDate::from_ymd_opt(2027, 1, 1).unwrap());
+    assert_eq!(ledger.sinking_fund("Car tax").unwrap().saved, 0.0);
+}
+
+#[test]
+fn income_model_withholding_changes_flow_through_simulations() {
+    use crate::{income_service::IncomeService, simulation_service::SimulationService};
+    use bufy_domain::{simulation::IncomeModelPatch, IncomeModel};
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Payroll", LedgerBudgetPeriod::monthly());
+    let employer = Account::new("Employer", AccountKind::IncomeSource);
+    let employer_id = employer.id;
+    AccountService::add(&mut ledger, employer).expect("add employer");
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let start = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+    let monthly = TimeInterval {
+        every: 1,
+        unit: TimeUnit::Month,
+    };
+    let paycheck =
+        Transaction::new(employer_id, checking_id, None, start, usd(0.0)).with_recurrence(
+            Recurrence::new(start, monthly, RecurrenceMode::FixedSchedule),
+        );
+    let template_id = TransactionService::add(&mut ledger, paycheck).expect("add paycheck");
+    let mut paid = Transaction::new(employer_id, checking_id, None, start, usd(0.0));
+    paid.recurrence_series_id = ledger.transaction(template_id).unwrap().recurrence_series();
+    paid.mark_completed(start, usd(3700.0));
+    TransactionService::add(&mut ledger, paid).expect("add paid occurrence");
+
+    let model = IncomeModel {
+        gross: 5000.0,
+        withholding_percent: 20.0,
+        benefits: 200.0,
+    };
+    assert_eq!(
+        IncomeService::set_model(&mut ledger, template_id, model).expect("set model"),
+        3800.0
+    );
+    assert_eq!(
+        ledger.transaction(template_id).unwrap().budgeted_amount,
+        usd(3800.0)
+    );
+
+    SimulationService::create(&mut ledger, "Higher withholding", None, &clock).expect("create");
+    let patch = |percent| IncomeModelPatch {
+        transaction_id: template_id,
+        gross: None,
+        withholding_percent: Some(percent),
+        benefits: None,
+    };
+    assert!(
+        SimulationService::modify_income(&mut ledger, "Higher withholding", patch(120.0)).is_err()
+    );
+    SimulationService::modify_income(&mut ledger, "Higher withholding", patch(25.0))
+        .expect("modify income");
+
+    let simulated = SimulationService::run(&ledger, "Higher withholding").expect("run");
+    assert_eq!(
+        simulated.transaction(template_id).unwrap().budgeted_amount,
+        usd(3550.0)
+    );
+    assert!(simulated
+        .transactions
+        .iter()
+        .filter(|txn| txn.status == TransactionStatus::Completed)
+        .all(|txn| txn.actual_amount == Some(usd(3700.0)) && txn.budgeted_amount == usd(0.0)));
+    let window = bufy_domain::DateWindow::new(
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2025, 5, 1).unwrap(),
+    )
+    .unwrap();
+    let projected =
+        SummaryService::forecast_window(&ledger, window, start, Some("Higher withholding"))
+            .expect("forecast")
+            .forecast;
+    assert_eq!(projected.transactions.len(), 2);
+    assert!(projected
+        .transactions
+        .iter()
+        .all(|item| item.transaction.budgeted_amount == usd(3550.0)));
+    assert_eq!(
+        ledger.transaction(template_id).unwrap().budgeted_amount,
+        usd(3800.0)
+    );
+}
+
+#[test]
+fn recurring_price_changes_split_category_increase_into_price_and_usage() {
+    use crate::recurrence_service::RecurrenceService;
+
+    let mut ledger = LedgerService::create("Utilities", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let utility = Account::new("Power Co", AccountKind::ExpenseDestination);
+    let utility_id = utility.id;
+    AccountService::add(&mut ledger, utility).expect("add utility");
+    let energy = Category::new("Energy", CategoryKind::Expense);
+    let energy_id = energy.id;
+    CategoryService::add(&mut ledger, energy).expect("add category");
+
+    let date = |month| NaiveDate::from_ymd_opt(2025, month, 1).unwrap();
+    let monthly = TimeInterval {
+        every: 1,
+        unit: TimeUnit::Month,
+    };
+    let bill = Transaction::new(
+        checking_id,
+        utility_id,
+        Some(energy_id),
+        date(1),
+        usd(100.0),
+    )
+    .with_recurrence(Recurrence::new(
+        date(1),
+        monthly,
+        RecurrenceMode::FixedSchedule,
+    ));
+    let template_id = TransactionService::add(&mut ledger, bill).expect("add bill");
+    let series = ledger.transaction(template_id).unwrap().recurrence_series();
+    for (month, actual) in [(1, Some(100.0)), (2, Some(130.0)), (3, None)] {
+        let mut occurrence = Transaction::new(
+            checking_id,
+            utility_id,
+            Some(energy_id),
+            date(month),
+            usd(100.0),
+        );
+        occurrence.recurrence_series_id = series;
+        if let Some(actual) = actual {
+            occurrence.mark_completed(date(month), usd(actual));
+        }
+        TransactionService::add(&mut ledger, occurrence).expect("add occurrence");
+    }
+
+    assert_eq!(
+        RecurrenceService::change_price(&mut ledger, template_id, 110.0, date(2))
+            .expect("change price"),
+        100.0
+    );
+    assert!(RecurrenceService::change_price(&mut ledger, template_id, 110.0, date(2)).is_err());
+    let history = RecurrenceService::price_history(&ledger, template_id).expect("history");
+    assert_eq!(
+        history
+            .iter()
+            .map(|point| (point.effective, point.amount))
+            .collect::<Vec<_>>(),
+        vec![(date(1), 100.0), (date(2), 110.0)]
+    );
+    let march = ledger
+        .transactions
+        .iter()
+        .find(|txn| txn.scheduled_date == date(3))
+        .unwrap();
+    assert_eq!(march.budgeted_amount, usd(110.0));
+    assert!(ledger
+        .transactions
+        .iter()
+        .filter(|txn| txn.status == TransactionStatus::Completed)
+        .all(|txn| txn.budgeted_amount == usd(100.0)));
+
+    let january = bufy_domain::DateWindow::new(date(1), date(2)).unwrap();
+    let february = bufy_domain::DateWindow::new(date(2), date(3)).unwrap();
+    let changes = RecurrenceService::category_price_changes(&ledger, january, february);
+    assert_eq!(changes.len(), 1);
+    let change = &changes[0];
+    assert_eq!(change.name, "Energy");
+    assert_eq!((change.previous, change.current), (100.0, 130.0));
+    assert_eq!(change.price_effect, 10.0);
+    assert_eq!(change.usage_effect, 20.0);
+}
+
+#[test]
+fn summary_date_basis_selects_booking_or_value_date() {
+    use crate::budget_service::BudgetService;
+    use bufy_domain::transaction::DateBasis;
+
+    let mut ledger = LedgerService::create("Statements", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let store = Account::new("Store", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    AccountService::add(&mut ledger, store).expect("add store");
+
+    let date = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+    let mut purchase = Transaction::new(checking_id, store_id, None, date(1, 30), usd(80.0));
+    purchase.mark_completed(date(1, 30), usd(80.0));
+    purchase.booking_date = Some(date(2, 1));
+    purchase.value_date = Some(date(1, 31));
+    TransactionService::add(&mut ledger, purchase).expect("add purchase");
+
+    let real_in_february = |ledger: &bufy_domain::Ledger| {
+        BudgetService::summarize_period_containing(ledger, date(2, 10))
+            .totals
+            .real
+    };
+    assert_eq!(real_in_february(&ledger), 0.0);
+    ledger.summary_date_basis = DateBasis::Booking;
+    assert_eq!(real_in_february(&ledger), 80.0);
+    ledger.summary_date_basis = DateBasis::Value;
+    assert_eq!(real_in_february(&ledger), 0.0);
+
+    let mut undated = Transaction::new(checking_id, store_id, None, date(2, 3), usd(20.0));
+    undated.mark_completed(date(2, 3), usd(20.0));
+    TransactionService::add(&mut ledger, undated).expect("add undated");
+    ledger.summary_date_basis = DateBasis::Booking;
+    assert_eq!(real_in_february(&ledger), 100.0);
+}
+
+#[test]
+fn pending_drafts_stay_out_of_summaries_until_approved() {
+    use crate::{budget_service::BudgetService, draft_service::DraftService, public_api};
+
+    let mut ledger = LedgerService::create("Inbox", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let cafe = Account::new("Cafe", AccountKind::ExpenseDestination);
+    let cafe_id = cafe.id;
+    AccountService::add(&mut ledger, cafe).expect("add cafe");
+
+    let date = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+    let draft_id = public_api::api_submit_draft(
+        &mut ledger,
+        "mobile",
+        Some(date),
+        Some(4.5),
+        Some("Corner Cafe".into()),
+        None,
+    )
+    .expect("submit draft");
+    assert!(
+        public_api::api_submit_draft(&mut ledger, "ocr", None, Some(-1.0), None, None).is_err()
+    );
+    assert_eq!(ledger.pending_drafts.len(), 1);
+    assert!(ledger.transactions.is_empty());
+    assert_eq!(
+        BudgetService::summarize_period_containing(&ledger, date)
+            .totals
+            .real,
+        0.0
+    );
+
+    let err = DraftService::approve(&mut ledger, draft_id).unwrap_err();
+    assert!(err.to_string().contains("from account, to account"));
+    DraftService::update(&mut ledger, draft_id, |draft| {
+        draft.from_account = Some(checking_id);
+        draft.to_account = Some(cafe_id);
+    })
+    .expect("update draft");
+    let txn_id = DraftService::approve(&mut ledger, draft_id).expect("approve");
+    assert!(ledger.pending_drafts.is_empty());
+    let txn = ledger.transaction(txn_id).unwrap();
+    assert_eq!(txn.status, TransactionStatus::Completed);
+    assert_eq!(txn.notes.as_deref(), Some("Corner Cafe"));
+    assert_eq!(
+        BudgetService::summarize_period_containing(&ledger, date)
+            .totals
+            .real,
+        4.5
+    );
+
+    let discarded =
+        public_api::api_submit_draft(&mut ledger, "email", None, None, None, None).unwrap();
+    DraftService::discard(&mut ledger, discarded).expect("discard");
+    assert!(DraftService::discard(&mut ledger, discarded).is_err());
+    assert_eq!(ledger.transactions.len(), 1);
+}
+
+#[test]
+fn csv_exports_defuse_spreadsheet_formulas() {
+    use crate::export_service::ExportService;
+    use bufy_domain::view::ViewColumn;
+
+    let mut ledger = LedgerService::create("Formulas", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let payee = ledger.add_account(Account::new("+Payee", AccountKind::ExpenseDestination));
+    let mut txn = Transaction::new(
+        checking,
+        payee,
+        None,
+        NaiveDate::from_ymd_opt(2025, 4, 2).unwrap(),
+        usd(12.0),
+    );
+    txn.notes = Some("=HYPERLINK(\"http://x\",\"y\")".into());
+    txn.merchant = Some("@SUM(A1)".into());
+    let id = ledger.add_transaction(txn);
+    let txn = ledger.transaction(id).unwrap();
+
+    let csv = ExportService::transactions_csv(
+        &ledger,
+        &[txn],
+        &[
+            ViewColumn::To,
+            ViewColumn::Notes,
+            ViewColumn::Merchant,
+            ViewColumn::Budgeted,
+        ],
+    );
+    assert_eq!(
+        csv.lines().nth(1).unwrap(),
+        "'+Payee,\"'=HYPERLINK(\"\"http://x\"\",\"\"y\"\")\",'@SUM(A1),12.00"
+    );
+}
+
+#[test]
+fn reporting_snapshot_joins_names_and_writes_csv_and_parquet() {
+    use crate::export_service::{ExportService, SNAPSHOT_COLUMNS};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let mut ledger = LedgerService::create("Snapshot", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let store = Account::new("Store", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    AccountService::add(&mut ledger, store).expect("add store");
+    let food = Category::new("Food, Home", CategoryKind::Expense);
+    let food_id = food.id;
+    CategoryService::add(&mut ledger, food).expect("add category");
+
+    let date = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+    let mut txn = Transaction::new(checking_id, store_id, Some(food_id), date, usd(42.5));
+    txn.mark_completed(date, usd(40.25));
+    TransactionService::add(&mut ledger, txn).expect("add completed");
+    let planned = Transaction::new(checking_id, store_id, None, date, usd(10.0));
+    TransactionService::add(&mut ledger, planned).expect("add planned");
+
+    let transactions: Vec<_> = ledger.transactions.iter().collect();
+    let rows = ExportService::snapshot_rows(&ledger, &transactions, date);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].from_account, "Checking");
+    assert_eq!(rows[0].to_kind, "Expense Destination");
+    assert_eq!(rows[0].category.as_deref(), Some("Food, Home"));
+    assert_eq!(rows[0].actual_base, Some(40.25));
+    assert_eq!(rows[1].actual, None);
+    assert_eq!(rows[1].reporting_date, None);
+
+    let csv = ExportService::snapshot_csv(&rows);
+    let mut lines = csv.lines();
+    assert!(lines
+        .next()
+        .unwrap()
+        .starts_with("transaction_id,scheduled_date,actual_date"));
+    let first = lines.next().unwrap();
+    assert!(first.contains(",2025-03,"));
+    assert!(first.contains("\"Food, Home\""));
+    assert!(first.contains(",42.5,40.25,USD,42.5,40.25,"));
+
+    let mut parquet = Vec::new();
+    ExportService::snapshot_parquet(&rows, &mut parquet).expect("write parquet");
+    let reader = SerializedFileReader::new(bytes::Bytes::from(parquet)).expect("read parquet");
+    let metadata = reader.metadata().file_metadata();
+    assert_eq!(metadata.num_rows(), 2);
+    assert_eq!(
+        metadata.schema_descr().num_columns(),
+        SNAPSHOT_COLUMNS.len()
+    );
+}
+
+#[test]
+fn category_budget_summaries_apply_each_budgets_proration_rule() {
+    use crate::budget_service::BudgetService;
+    use bufy_domain::{
+        category::BudgetProration,
+        ledger::{BudgetScope, CategoryBudgetSummaryKind, DateWindow},
+    };
+
+    let mut ledger = LedgerService::create("Proration", LedgerBudgetPeriod::monthly());
+    let food = Category::new("Food", CategoryKind::Expense);
+    let food_id = food.id;
+    CategoryService::add(&mut ledger, food).expect("add category");
+    CategoryService::set_budget(&mut ledger, food_id, 70.0, BudgetPeriod::Weekly, None)
+        .expect("set budget");
+
+    let january = DateWindow::new(
+        NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+    )
+    .unwrap();
+    let budget_in_january = |ledger: &bufy_domain::Ledger| {
+        let summaries = BudgetService::category_budget_summaries(
+            ledger,
+            january,
+            BudgetScope::Past,
+            CategoryBudgetSummaryKind::Actual,
+        );
+        let statuses = BudgetService::category_budget_statuses(ledger, january, BudgetScope::Past);
+        assert_eq!(
+            Some(summaries[0].budget_amount),
+            statuses[0].window_budget,
+            "statuses and summaries agree"
+        );
+        summaries[0].budget_amount
+    };
+
+    assert!((budget_in_january(&ledger) - 310.0).abs() < 1e-9);
+    CategoryService::set_budget_proration(&mut ledger, food_id, BudgetProration::AlignToLedger)
+        .expect("align");
+    assert_eq!(budget_in_january(&ledger), 280.0);
+    CategoryService::set_budget(&mut ledger, food_id, 80.0, BudgetPeriod::Weekly, None)
+        .expect("update amount");
+    assert_eq!(budget_in_january(&ledger), 320.0, "rule survives edits");
+    CategoryService::set_budget_proration(&mut ledger, food_id, BudgetProration::Strict)
+        .expect("strict");
+    assert_eq!(budget_in_january(&ledger), 80.0);
+}
+
+#[test]
+fn system_categories_are_created_once_and_kept_out_of_summaries() {
+    use crate::budget_service::BudgetService;
+    use bufy_domain::category::SystemCategory;
+
+    let mut ledger = LedgerService::create("System", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let store = Account::new("Store", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    AccountService::add(&mut ledger, store).expect("add store");
+    CategoryService::add(
+        &mut ledger,
+        Category::new("FX Adjustments", CategoryKind::Expense),
+    )
+    .expect("user category");
+
+    let fx = CategoryService::ensure_system(&mut ledger, SystemCategory::FxAdjustment);
+    assert_eq!(
+        CategoryService::ensure_system(&mut ledger, SystemCategory::FxAdjustment),
+        fx
+    );
+    let category = ledger.category(fx).unwrap();
+    assert!(category.hidden);
+    assert_eq!(category.name, "FX Adjustments (system)");
+    assert_eq!(CategoryService::visible(&ledger).count(), 1);
+
+    let date = NaiveDate::from_ymd_opt(2025, 1, 10).unwrap();
+    let mut adjustment = Transaction::new(checking_id, store_id, Some(fx), date, usd(3.0));
+    adjustment.mark_completed(date, usd(3.0));
+    TransactionService::add(&mut ledger, adjustment).expect("add adjustment");
+    let mut purchase = Transaction::new(checking_id, store_id, None, date, usd(20.0));
+    purchase.mark_completed(date, usd(20.0));
+    TransactionService::add(&mut ledger, purchase).expect("add purchase");
+
+    let summary = BudgetService::summarize_period_containing(&ledger, date);
+    assert_eq!(summary.totals.real, 20.0);
+    assert!(summary
+        .per_category
+        .iter()
+        .all(|entry| entry.category_id != Some(fx)));
+    let window = ledger.budget_window_containing(date);
+    let statuses = BudgetService::category_budget_statuses(&ledger, window, window.scope(date));
+    assert!(statuses.iter().all(|status| status.category_id != fx));
+}
+
+#[test]
+fn account_balance_counts_completed_movements_up_to_the_day() {
+    let mut ledger = LedgerService::create("Balance", LedgerBudgetPeriod::monthly());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(100.0));
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let shop_id = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let salary_id = ledger.add_account(Account::new("Salary", AccountKind::IncomeSource));
+    let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+
+    let mut groceries = Transaction::new(checking_id, shop_id, None, date(1), usd(30.0));
+    groceries.mark_completed(date(2), usd(32.5));
+    let mut pay = Transaction::new(salary_id, checking_id, None, date(10), usd(50.0));
+    pay.mark_completed(date(10), usd(50.0));
+    let rent = Transaction::new(checking_id, shop_id, None, date(5), usd(20.0));
+    let later = Transaction::new(checking_id, shop_id, None, date(20), usd(5.0));
+    for txn in [groceries, pay, rent, later] {
+        ledger.transactions.push(txn);
+    }
+
+    let early = AccountService::balance(&ledger, checking_id, date(1)).unwrap();
+    assert_eq!((early.balance, early.planned), (100.0, 0.0));
+    let mid = AccountService::balance(&ledger, checking_id, date(5)).unwrap();
+    assert_eq!((mid.balance, mid.planned), (67.5, -20.0));
+    assert_eq!(mid.with_planned(), 47.5);
+    let end = AccountService::balance(&ledger, checking_id, date(31)).unwrap();
+    assert_eq!((end.balance, end.planned), (117.5, -25.0));
+    assert_eq!(end.currency, "USD");
+    assert!(matches!(
+        AccountService::balance(&ledger, uuid::Uuid::new_v4(), date(31)),
+        Err(CoreError::AccountNotFound(_))
+    ));
+}
+
+#[test]
+fn balance_series_buckets_completed_movements_by_period() {
+    let mut ledger = LedgerService::create("Series", LedgerBudgetPeriod::monthly());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(100.0));
+    let checking_id = ledger.add_account(checking);
+    let shop_id = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let salary_id = ledger.add_account(Account::new("Salary", AccountKind::IncomeSource));
+    let date = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+
+    let mut before = Transaction::new(checking_id, shop_id, None, date(1, 20), usd(10.0));
+    before.mark_completed(date(1, 20), usd(10.0));
+    let mut pay = Transaction::new(salary_id, checking_id, None, date(2, 28), usd(500.0));
+    pay.mark_completed(date(3, 1), usd(500.0));
+    let mut rent = Transaction::new(checking_id, shop_id, None, date(3, 31), usd(300.0));
+    rent.mark_completed(date(3, 31), usd(300.0));
+    let planned = Transaction::new(checking_id, shop_id, None, date(2, 10), usd(50.0));
+    for txn in [rent, planned, pay, before] {
+        ledger.transactions.push(txn);
+    }
+
+    let series = crate::api_balance_series(
+        &ledger,
+        checking_id,
+        date(2, 1),
+        date(4, 15),
+        TimeUnit::Month,
+    )
+    .expect("series");
+    assert_eq!(series.opening_balance, 90.0);
+    let points: Vec<_> = series
+        .points
+        .iter()
+        .map(|point| (point.start, point.end, point.balance))
+        .collect();
+    assert_eq!(
+        points,
+        vec![
+            (date(2, 1), date(2, 28), 90.0),
+            (date(3, 1), date(3, 31), 290.0),
+            (date(4, 1), date(4, 15), 290.0),
+        ]
+    );
+    assert_eq!(
+        series.points[1].balance,
+        AccountService::balance(&ledger, checking_id, date(3, 31))
+            .unwrap()
+            .balance
+    );
+
+    let days =
+        crate::api_balance_series(&ledger, checking_id, date(3, 1), date(3, 1), TimeUnit::Day)
+            .expect("one day");
+    assert_eq!(days.points.len(), 1);
+    assert_eq!(days.points[0].balance, 590.0);
+
+    let month_end = crate::api_balance_series(
+        &ledger,
+        checking_id,
+        date(1, 31),
+        date(4, 29),
+        TimeUnit::Month,
+    )
+    .expect("month ends");
+    let ends: Vec<_> = month_end.points.iter().map(|point| point.end).collect();
+    assert_eq!(ends, vec![date(2, 27), date(3, 30), date(4, 29)]);
+
+    assert!(
+        crate::api_balance_series(&ledger, checking_id, date(4, 1), date(3, 1), TimeUnit::Day)
+            .is_err()
+    );
+    assert!(matches!(
+        crate::api_balance_series(
+            &ledger,
+            uuid::Uuid::new_v4(),
+            date(3, 1),
+            date(4, 1),
+            TimeUnit::Week
+        ),
+        Err(CoreError::AccountNotFound(_))
+    ));
+    assert!(matches!(
+        crate::api_balance_series(
+            &ledger,
+            checking_id,
+            NaiveDate::MAX.pred_opt().unwrap(),
+            NaiveDate::MAX,
+            TimeUnit::Month
+        ),
+        Err(CoreError::Validation(_))
+    ));
+}
+
+#[test]
+fn tags_normalize_filter_queries_and_break_down_summaries() {
+    use bufy_domain::{ledger::BudgetScope, view::TransactionFilter, DateWindow};
+
+    let mut ledger = LedgerService::create("Tags", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let day = NaiveDate::from_ymd_opt(2025, 7, 4).unwrap();
+    let hotel = ledger.add_transaction(Transaction::new(checking, shop, None, day, usd(200.0)));
+    let taxi = ledger.add_transaction(Transaction::new(checking, shop, None, day, usd(30.0)));
+    ledger.add_transaction(Transaction::new(checking, shop, None, day, usd(12.0)));
+
+    let added =
+        TransactionService::add_tags(&mut ledger, hotel, &["#Vacation2025", "reimbursable"])
+            .expect("tag hotel");
+    assert_eq!(added, vec!["vacation2025", "reimbursable"]);
+    assert_eq!(
+        ledger.transaction(hotel).unwrap().tags,
+        vec!["reimbursable", "vacation2025"]
+    );
+    assert!(
+        TransactionService::add_tags(&mut ledger, hotel, &["VACATION2025"])
+            .unwrap()
+            .is_empty()
+    );
+    TransactionService::add_tags(&mut ledger, taxi, &["vacation 2025", "vacation2025"]).unwrap();
+    assert_eq!(
+        ledger.transaction(taxi).unwrap().tags,
+        vec!["vacation-2025", "vacation2025"]
+    );
+    assert_eq!(
+        TransactionService::remove_tags(&mut ledger, taxi, &["Vacation 2025", "missing"]).unwrap(),
+        vec!["vacation-2025"]
+    );
+    assert!(matches!(
+        TransactionService::add_tags(&mut ledger, taxi, &["  #  "]),
+        Err(CoreError::Invalid(_))
+    ));
+    assert_eq!(
+        TransactionService::tag_counts(&ledger),
+        vec![
+            ("reimbursable".to_string(), 1),
+            ("vacation2025".to_string(), 2)
+        ]
+    );
+
+    let query = TransactionService::resolve_filter(
+        &ledger,
+        &TransactionFilter {
+            tag: Some("#Vacation2025".into()),
+            ..Default::default()
+        },
+    )
+    .expect("resolve tag filter");
+    let ids: Vec<_> = TransactionService::query(&ledger, &query)
+        .iter()
+        .map(|txn| txn.id)
+        .collect();
+    assert_eq!(ids, vec![hotel, taxi]);
+
+    let window = DateWindow::new(day, day.succ_opt().unwrap()).unwrap();
+    let summary = SummaryService::summarize_window(&ledger, window, BudgetScope::Custom);
+    let per_tag: Vec<_> = summary
+        .per_tag
+        .iter()
+        .map(|entry| (entry.tag.as_str(), entry.totals.budgeted))
+        .collect();
+    assert_eq!(
+        per_tag,
+        vec![("reimbursable", 200.0), ("vacation2025", 230.0)]
+    );
+    assert_eq!(summary.totals.budgeted, 242.0);
+}
+
+#[test]
+fn closing_an_account_moves_its_balance_and_blocks_new_transactions() {
+    let mut ledger = LedgerService::create("Close", LedgerBudgetPeriod::monthly());
+    let mut old = Account::new("Old Bank", AccountKind::Bank);
+    old.opening_balance = Some(usd(40.0));
+    let old_id = old.id;
+    AccountService::add(&mut ledger, old).expect("add old bank");
+    let new_id = ledger.add_account(Account::new("New Bank", AccountKind::Bank));
+    let shop_id = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+
+    assert!(matches!(
+        AccountService::close(&mut ledger, old_id, date(15), None),
+        Err(CoreError::InvalidOperation(_))
+    ));
+    assert!(matches!(
+        AccountService::close(&mut ledger, old_id, date(15), Some(old_id)),
+        Err(CoreError::InvalidOperation(_))
+    ));
+    let transfer = AccountService::close(&mut ledger, old_id, date(15), Some(new_id))
+        .unwrap()
+        .expect("closing transfer");
+    let txn = ledger.transaction(transfer).unwrap();
+    assert_eq!((txn.from_account, txn.to_account), (old_id, new_id));
+    assert_eq!(txn.actual_amount, Some(usd(40.0)));
+    assert_eq!(
+        ledger.account(old_id).unwrap().status,
+        AccountStatus::Closed { on: date(15) }
+    );
+    assert_eq!(
+        AccountService::balance(&ledger, old_id, date(31))
+            .unwrap()
+            .balance,
+        0.0
+    );
+    assert_eq!(
+        AccountService::balance(&ledger, new_id, date(31))
+            .unwrap()
+            .balance,
+        40.0
+    );
+
+    let err = TransactionService::add(
+        &mut ledger,
+        Transaction::new(old_id, shop_id, None, date(20), usd(5.0)),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("closed on 2025-03-15"));
+    assert!(AccountService::close(&mut ledger, old_id, date(16), None).is_err());
+
+    AccountService::reopen(&mut ledger, old_id).unwrap();
+    assert!(TransactionService::add(
+        &mut ledger,
+        Transaction::new(old_id, shop_id, None, date(20), usd(5.0)),
+    )
+    .is_ok());
+    assert!(AccountService::reopen(&mut ledger, old_id).is_err());
+}
+
+#[test]
+fn account_statement_carries_balances_and_renders_each_format() {
+    use crate::export_service::ExportService;
+    use crate::{CurrencyFormatter, DateFormatter};
+    use bufy_domain::ledger::DateWindow;
+
+    struct Plain;
+    impl CurrencyFormatter for Plain {
+        fn format_amount(&self, amount: f64, currency: &str) -> String {
+            format!("{:.2} {}", amount, currency)
+        }
+    }
+    impl DateFormatter for Plain {
+        fn format_date(&self, date: NaiveDate) -> String {
+            date.to_string()
+        }
+    }
+
+    let mut ledger = LedgerService::create("Statement", LedgerBudgetPeriod::monthly());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(100.0));
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let employer = Account::new("Employer", AccountKind::IncomeSource);
+    let employer_id = employer.id;
+    AccountService::add(&mut ledger, employer).expect("add employer");
+    let store = Account::new("Store <B&Q>", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    AccountService::add(&mut ledger, store).expect("add store");
+
+    let date = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+    let mut completed = |from, to, on, amount: Money| {
+        let mut txn = Transaction::new(from, to, None, on, amount.clone());
+        txn.mark_completed(on, amount);
+        TransactionService::add(&mut ledger, txn).expect("add transaction");
+    };
+    completed(checking_id, store_id, date(1, 20), usd(30.0));
+    completed(employer_id, checking_id, date(2, 1), usd(500.0));
+    completed(checking_id, store_id, date(2, 10), usd(45.5));
+    completed(checking_id, store_id, date(3, 2), usd(10.0));
+    TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, store_id, None, date(2, 15), usd(99.0)),
+    )
+    .expect("planned");
+
+    let february = DateWindow::new(date(2, 1), date(3, 1)).unwrap();
+    let statement = AccountService::statement(&ledger, checking_id, february).expect("statement");
+    assert_eq!(statement.opening_balance, 70.0);
+    assert_eq!(statement.lines.len(), 2);
+    assert_eq!(statement.lines[0].counterparty, "Employer");
+    assert_eq!(statement.lines[1].amount, -45.5);
+    assert_eq!(statement.lines[1].balance, 524.5);
+    assert_eq!(statement.closing_balance, 524.5);
+
+    let csv = ExportService::statement_csv(&statement);
+    assert!(csv.starts_with("date,description,category,notes,amount,balance\r\n"));
+    assert!(csv.contains("2025-02-01,Opening balance,,,,70.00\r\n"));
+    assert!(csv.ends_with("2025-02-28,Closing balance,,,,524.50\r\n"));
+    assert!(
+        csv.contains(",-45.50,524.50\r\n"),
+        "negative amounts stay numbers"
+    );
+
+    let text = ExportService::statement_text(&statement, &Plain);
+    assert!(text.starts_with("Statement for Checking: 2025-02-01 to 2025-02-28"));
+    assert!(text.contains("-45.50 USD"));
+
+    let html = ExportService::statement_html(&statement, &Plain);
+    assert!(html.contains("Store &lt;B&amp;Q&gt;"));
+    assert!(html.contains("<td class=\"num\">524.50 USD</td>"));
+}
+
+#[test]
+fn ynab_export_imports_once_with_transfers_groups_and_budgets() {
+    use crate::import_service::{ImportMapping, ImportService, ImportTarget};
+    use std::io::{Cursor, Write};
+
+    let register = "\u{feff}\"Account\",\"Flag\",\"Date\",\"Payee\",\"Category Group/Category\",\"Category Group\",\"Category\",\"Memo\",\"Outflow\",\"Inflow\",\"Cleared\"\n\
+        \"Checking\",\"\",\"01/03/2025\",\"Employer\",\"Inflow: Ready to Assign\",\"Inflow\",\"Ready to Assign\",\"\",\"$0.00\",\"$2,000.00\",\"Cleared\"\n\
+        \"Checking\",\"\",\"01/05/2025\",\"Grocer\",\"Everyday: Groceries\",\"Everyday\",\"Groceries\",\"weekly shop\",\"$82.40\",\"$0.00\",\"Cleared\"\n\
+        \"Checking\",\"\",\"01/06/2025\",\"Transfer : Savings\",\"\",\"\",\"\",\"\",\"$500.00\",\"$0.00\",\"Cleared\"\n\
+        \"Savings\",\"\",\"01/06/2025\",\"Transfer : Checking\",\"\",\"\",\"\",\"\",\"$0.00\",\"$500.00\",\"Cleared\"\n";
+    let budget = "\"Month\",\"Category Group/Category\",\"Category Group\",\"Category\",\"Budgeted\",\"Activity\",\"Available\"\n\
+        \"Dec 2024\",\"Everyday: Groceries\",\"Everyday\",\"Groceries\",\"$300.00\",\"-$290.00\",\"$10.00\"\n\
+        \"Jan 2025\",\"Everyday: Groceries\",\"Everyday\",\"Groceries\",\"$350.00\",\"-$82.40\",\"$277.60\"\n";
+
+    let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (name, contents) in [
+        ("Budget as of 2025-01-31 - Register.csv", register),
+        ("Budget as of 2025-01-31 - Budget.csv", budget),
+    ] {
+        archive.start_file(name, options).unwrap();
+        archive.write_all(contents.as_bytes()).unwrap();
+    }
+    let bytes = archive.finish().unwrap().into_inner();
+    let bundle = ImportService::read_ynab_zip(Cursor::new(bytes)).expect("read archive");
+    assert_eq!(bundle.accounts(), vec!["Checking", "Savings"]);
+    assert_eq!(bundle.transactions[1].amount, -82.4);
+
+    let mut ledger = LedgerService::create("Migration", LedgerBudgetPeriod::monthly());
+    let existing = Account::new("checking", AccountKind::Bank);
+    let existing_id = existing.id;
+    AccountService::add(&mut ledger, existing).expect("add checking");
+    let mapping = ImportMapping::suggest(&ledger, &bundle);
+    assert_eq!(
+        mapping.accounts.get("Checking"),
+        Some(&ImportTarget::Existing(existing_id))
+    );
+
+    let report = ImportService::apply(&mut ledger, &bundle, &mapping).expect("import");
+    assert_eq!(report.transactions_imported, 3);
+    assert_eq!(report.categories_created, 4);
+    assert_eq!(report.budgets_set, 1);
+    let groceries = ledger
+        .categories
+        .iter()
+        .find(|category| category.name == "Groceries")
+        .expect("groceries");
+    let parent = ledger.category(groceries.parent_id.unwrap()).unwrap();
+    assert_eq!(parent.name, "Everyday");
+    assert_eq!(groceries.budget.as_ref().unwrap().amount, usd(350.0));
+    let income = ledger
+        .categories
+        .iter()
+        .find(|category| category.name == "Ready to Assign")
+        .unwrap();
+    assert_eq!(income.kind, CategoryKind::Income);
+    let savings = ledger
+        .accounts
+        .iter()
+        .find(|account| account.name == "Savings")
+        .unwrap();
+    assert!(ledger.transactions.iter().any(|txn| {
+        txn.from_account == existing_id && txn.to_account == savings.id && txn.category_id.is_none()
+    }));
+    let grocer = ledger
+        .accounts
+        .iter()
+        .find(|account| account.name == "Grocer")
+        .unwrap();
+    assert_eq!(grocer.kind, AccountKind::ExpenseDestination);
+
+    let again = ImportService::apply(&mut ledger, &bundle, &mapping).expect("reimport");
+    assert_eq!(again.transactions_imported, 0);
+    assert_eq!(again.duplicates_skipped, 3);
+}
+
+#[test]
+fn mint_export_maps_debits_credits_and_skipped_accounts() {
+    use crate::import_service::{ImportMapping, ImportService, ImportTarget};
+
+    let csv = "\"Date\",\"Description\",\"Original Description\",\"Amount\",\"Transaction Type\",\"Category\",\"Account Name\",\"Labels\",\"Notes\"\n\
+        \"2/14/2025\",\"Cafe\",\"CAFE 123\",\"4.50\",\"debit\",\"Coffee Shops\",\"Visa\",\"\",\"\"\n\
+        \"2/15/2025\",\"Refund\",\"REFUND\",\"1,250.00\",\"credit\",\"Uncategorized\",\"Visa\",\"\",\"returned\"\n\
+        \"2/16/2025\",\"Bakery\",\"BAKERY\",\"3.00\",\"debit\",\"Coffee Shops\",\"Old Card\",\"\",\"\"\n";
+    let bundle = ImportService::parse_mint(csv).expect("parse");
+    assert_eq!(bundle.transactions[0].amount, -4.5);
+    assert_eq!(bundle.transactions[1].amount, 1250.0);
+    assert_eq!(bundle.transactions[1].category, None);
+
+    let mut ledger = LedgerService::create("Mint", LedgerBudgetPeriod::monthly());
+    let mut mapping = ImportMapping::suggest(&ledger, &bundle);
+    mapping
+        .accounts
+        .insert("Old Card".into(), ImportTarget::Skip);
+    let report = ImportService::apply(&mut ledger, &bundle, &mapping).expect("import");
+    assert_eq!(report.transactions_imported, 2);
+    assert_eq!(report.rows_skipped, 1);
+    let refund = ledger
+        .accounts
+        .iter()
+        .find(|account| account.name == "Refund")
+        .unwrap();
+    assert_eq!(refund.kind, AccountKind::IncomeSource);
+    assert!(ledger
+        .accounts
+        .iter()
+        .all(|account| account.name != "Old Card"));
+
+    let missing = ImportService::parse_mint("Date,Amount\n2/1/2025,1\n").unwrap_err();
+    assert!(missing.to_string().contains("`Description`"));
+}
+
+#[test]
+fn bank_sync_resumes_from_cursors_and_dedupes_through_import() {
+    use crate::bank_service::{BankConnector, BankSyncService, MockBankConnector};
+    use crate::import_service::{ImportMapping, ImportService};
+    use std::collections::BTreeMap;
+
+    let connector = MockBankConnector::from_json(
+        r#"{"accounts": [
+            {"id": "chk", "name": "Checking", "transactions": [
+                {"id": "t1", "date": "2025-03-01", "amount": -12.5, "payee": "Cafe"},
+                {"id": "t2", "date": "2025-03-02", "amount": 900.0, "payee": "Employer"}
+            ]},
+            {"id": "sav", "name": "Savings"}
+        ]}"#,
+    )
+    .expect("mock data");
+    assert_eq!(connector.list_accounts().unwrap().len(), 2);
+    assert_eq!(
+        connector
+            .fetch_transactions("chk", Some("t1"))
+            .unwrap()
+            .transactions
+            .len(),
+        1
+    );
+    assert!(connector.fetch_transactions("chk", Some("t9")).is_err());
+
+    let first = BankSyncService::fetch(&connector, &BTreeMap::new()).expect("fetch");
+    assert_eq!(first.bundle.transactions.len(), 2);
+    assert_eq!(first.cursors.get("chk").map(String::as_str), Some("t2"));
+    assert!(!first.cursors.contains_key("sav"));
+
+    let mut ledger = LedgerService::create("Bank", LedgerBudgetPeriod::monthly());
+    let mapping = ImportMapping::suggest(&ledger, &first.bundle);
+    let report = ImportService::apply(&mut ledger, &first.bundle, &mapping).expect("import");
+    assert_eq!(report.transactions_imported, 2);
+
+    let again = BankSyncService::fetch(&connector, &first.cursors).expect("refetch");
+    assert!(again.bundle.transactions.is_empty());
+    assert_eq!(again.cursors, first.cursors);
+
+    let full = BankSyncService::fetch(&connector, &BTreeMap::new()).expect("full fetch");
+    let mapping = ImportMapping::suggest(&ledger, &full.bundle);
+    let report = ImportService::apply(&mut ledger, &full.bundle, &mapping).expect("reimport");
+    assert_eq!(report.transactions_imported, 0);
+    assert_eq!(report.duplicates_skipped, 2);
+}
+
+#[test]
+fn change_feed_diff_replays_to_the_current_ledger() {
+    use crate::change_feed_service::{ChangeFeedService, LedgerChange, LedgerEvent};
+
+    let mut ledger = LedgerService::create("Feed", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let cash = Account::new("Cash", AccountKind::Cash);
+    let cash_id = cash.id;
+    AccountService::add(&mut ledger, cash).expect("add cash");
+
+    let mut events = Vec::new();
+    let mut record = |changes: Vec<LedgerChange>| {
+        for change in changes {
+            events.push(LedgerEvent {
+                seq: events.len() as u64 + 1,
+                recorded_at: chrono::Utc::now(),
+                change,
+            });
+        }
+    };
+    record(ChangeFeedService::diff(None, &ledger).unwrap());
+
+    let previous = ledger.clone();
+    AccountService::remove(&mut ledger, cash_id).expect("remove cash");
+    ledger.account_mut(checking_id).unwrap().notes = Some("joint".into());
+    CategoryService::add(&mut ledger, Category::new("Rent", CategoryKind::Expense))
+        .expect("add category");
+    let changes = ChangeFeedService::diff(Some(&previous), &ledger).unwrap();
+    assert!(changes.contains(&LedgerChange::Removed {
+        collection: "accounts".into(),
+        id: cash_id.to_string(),
+    }));
+    assert!(changes.iter().any(|change| matches!(
+        change,
+        LedgerChange::Upserted { collection, id, .. }
+            if collection == "accounts" && *id == checking_id.to_string()
+    )));
+    assert!(changes.iter().any(|change| matches!(
+        change,
+        LedgerChange::Upserted { collection, .. } if collection == "categories"
+    )));
+    record(changes);
+
+    let replayed = ChangeFeedService::replay(&events).expect("replay");
+    assert_eq!(
+        serde_json::to_value(&replayed).unwrap(),
+        serde_json::to_value(&ledger).unwrap()
+    );
+    assert!(ChangeFeedService::replay(&events[1..]).is_err());
+}
+
+#[test]
+fn transfer_rules_move_a_share_of_completed_income_once() {
+    use crate::TransferRuleService;
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Auto savings", LedgerBudgetPeriod::monthly());
+    let employer = ledger.add_account(Account::new("Employer", AccountKind::IncomeSource));
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let savings = ledger.add_account(Account::new("Savings", AccountKind::Savings));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 3, 25).unwrap();
+
+    assert!(TransferRuleService::set(&mut ledger, checking, shop, 10.0, &clock).is_err());
+    assert!(TransferRuleService::set(&mut ledger, checking, savings, 0.0, &clock).is_err());
+    let rule = TransferRuleService::set(&mut ledger, checking, savings, 5.0, &clock).unwrap();
+    assert_eq!(
+        TransferRuleService::set(&mut ledger, checking, savings, 10.0, &clock).unwrap(),
+        rule
+    );
+    assert_eq!(ledger.transfer_rules.len(), 1);
+
+    let rent = ledger.add_transaction(Transaction::new(checking, shop, None, date, usd(800.0)));
+    assert!(TransactionService::complete(&mut ledger, rent, date, 800.0)
+        .unwrap()
+        .is_empty());
+
+    let pay = ledger.add_transaction(Transaction::new(
+        employer,
+        checking,
+        None,
+        date,
+        usd(2000.0),
+    ));
+    let transfers = TransactionService::complete(&mut ledger, pay, date, 2_345.67).unwrap();
+    assert_eq!(transfers.len(), 1);
+    let transfer = ledger.transaction(transfers[0]).unwrap();
+    assert_eq!(
+        (transfer.from_account, transfer.to_account),
+        (checking, savings)
+    );
+    assert_eq!(transfer.actual_amount, Some(usd(234.57)));
+    assert_eq!(transfer.source_transaction_id, Some(pay));
+    assert_eq!(transfer.status, TransactionStatus::Completed);
+
+    assert!(
+        TransactionService::complete(&mut ledger, pay, date, 2_345.67)
+            .unwrap()
+            .is_empty()
+    );
+    TransferRuleService::remove(&mut ledger, rule).unwrap();
+    assert!(ledger.transfer_rules.is_empty());
+    assert_eq!(ledger.transactions.len(), 3);
+}
+
+#[test]
+fn paycheck_profiles_split_gross_pay_into_linked_transactions() {
+    use crate::{PaycheckLineKind, PaycheckService, TransferRuleService};
+    use bufy_domain::paycheck::{PaycheckAmount, PaycheckLine};
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Paychecks", LedgerBudgetPeriod::monthly());
+    let employer = ledger.add_account(Account::new("Employer", AccountKind::IncomeSource));
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let savings = ledger.add_account(Account::new("Savings", AccountKind::Savings));
+    let taxes = ledger.add_account(Account::new("Tax office", AccountKind::ExpenseDestination));
+    let brokerage = ledger.add_account(Account::new("Brokerage", AccountKind::Bank));
+    let date = NaiveDate::from_ymd_opt(2025, 3, 25).unwrap();
+
+    assert!(
+        PaycheckService::create(&mut ledger, "Salary", employer, taxes, 3000.0, None, &clock)
+            .is_err()
+    );
+    PaycheckService::create(
+        &mut ledger,
+        "Salary",
+        employer,
+        checking,
+        3000.0,
+        None,
+        &clock,
+    )
+    .unwrap();
+    assert!(
+        PaycheckService::create(&mut ledger, "salary", employer, checking, 1.0, None, &clock)
+            .is_err()
+    );
+    let line = |amount, account| PaycheckLine {
+        amount,
+        account,
+        category_id: None,
+    };
+    PaycheckService::add_line(
+        &mut ledger,
+        "Salary",
+        PaycheckLineKind::Deduction,
+        line(PaycheckAmount::Percent(20.0), taxes),
+    )
+    .unwrap();
+    PaycheckService::add_line(
+        &mut ledger,
+        "Salary",
+        PaycheckLineKind::Allocation,
+        line(PaycheckAmount::Percent(25.0), savings),
+    )
+    .unwrap();
+    PaycheckService::add_line(
+        &mut ledger,
+        "Salary",
+        PaycheckLineKind::Allocation,
+        line(PaycheckAmount::Fixed(300.0), brokerage),
+    )
+    .unwrap();
+    assert!(PaycheckService::add_line(
+        &mut ledger,
+        "Salary",
+        PaycheckLineKind::Allocation,
+        line(PaycheckAmount::Fixed(5000.0), brokerage),
+    )
+    .is_err());
+    assert_eq!(
+        ledger.paycheck_profile("salary").unwrap().allocations.len(),
+        2
+    );
+
+    let split = PaycheckService::split(ledger.paycheck_profile("Salary").unwrap(), 3000.0).unwrap();
+    assert_eq!(split.net, 2400.0);
+    assert_eq!(split.unallocated, 1500.0);
+
+    TransferRuleService::set(&mut ledger, checking, brokerage, 10.0, &clock).unwrap();
+    let receipt = PaycheckService::receive(&mut ledger, "Salary", date, Some(3200.0)).unwrap();
+    let income = ledger.transaction(receipt.income_id).unwrap();
+    assert_eq!(
+        (income.from_account, income.to_account),
+        (employer, checking)
+    );
+    assert_eq!(income.actual_amount, Some(usd(3200.0)));
+    let amounts: Vec<_> = receipt
+        .line_ids
+        .iter()
+        .map(|id| ledger.transaction(*id).unwrap())
+        .map(|txn| {
+            assert_eq!(txn.source_transaction_id, Some(receipt.income_id));
+            assert_eq!(txn.status, TransactionStatus::Completed);
+            (
+                txn.to_account,
+                txn.actual_amount.as_ref().unwrap().to_major(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        amounts,
+        vec![(taxes, 640.0), (savings, 640.0), (brokerage, 300.0)]
+    );
+    assert_eq!(receipt.transfer_ids.len(), 1);
+    assert_eq!(receipt.split.unallocated, 1620.0);
+    assert_eq!(ledger.transactions.len(), 5);
+}
+
+#[test]
+fn merchant_report_ranks_spend_and_exports_can_be_redacted() {
+    use crate::{export_service::ExportService, public_api, MerchantService};
+    use bufy_domain::{transaction::GeoLocation, DateWindow};
+
+    let mut ledger = LedgerService::create("Merchants", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shops = ledger.add_account(Account::new("Shops", AccountKind::ExpenseDestination));
+    let savings = ledger.add_account(Account::new("Savings", AccountKind::Savings));
+    let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+    let mut purchase = |to, day, amount: Money, merchant: &str| {
+        let mut txn = Transaction::new(checking, to, None, date(day), amount.clone());
+        txn.mark_completed(date(day), amount);
+        let id = ledger.add_transaction(txn);
+        public_api::api_set_transaction_metadata(&mut ledger, id, Some(merchant.into()), None)
+            .unwrap();
+        id
+    };
+    let cafe = purchase(shops, 3, usd(4.5), "Corner Cafe");
+    purchase(shops, 9, usd(5.5), "corner cafe");
+    purchase(shops, 12, usd(60.0), "Grocer");
+    purchase(savings, 14, usd(500.0), "Bank transfer");
+    ledger.add_transaction(Transaction::new(checking, shops, None, date(20), usd(99.0)));
+
+    let berlin = GeoLocation {
+        latitude: 52.52,
+        longitude: 13.405,
+        place: None,
+    };
+    let invalid = GeoLocation {
+        latitude: 91.0,
+        ..berlin.clone()
+    };
+    assert!(MerchantService::set_metadata(&mut ledger, cafe, None, Some(invalid)).is_err());
+    MerchantService::set_metadata(&mut ledger, cafe, Some("Corner Cafe".into()), Some(berlin))
+        .unwrap();
+
+    let march = DateWindow::new(date(1), date(31)).unwrap();
+    let report = MerchantService::top_merchants(&ledger, &march, 10);
+    let ranked: Vec<_> = report
+        .merchants
+        .iter()
+        .map(|m| (m.merchant.as_str(), m.total, m.transactions))
+        .collect();
+    assert_eq!(ranked[0], ("Grocer", 60.0, 1));
+    assert_eq!(ranked[1].1, 10.0);
+    assert_eq!(ranked[1].2, 2);
+    assert_eq!(ranked.len(), 2);
+    assert_eq!(
+        MerchantService::top_merchants(&ledger, &march, 1)
+            .merchants
+            .len(),
+        1
+    );
+
+    let transactions: Vec<_> = ledger.transactions.iter().take(1).collect();
+    let rows = ExportService::snapshot_rows(&ledger, &transactions, date(31));
+    assert_eq!(rows[0].merchant.as_deref(), Some("Corner Cafe"));
+    assert_eq!(rows[0].latitude, Some(52.52));
+    let redacted = ExportService::redacted(&transactions);
+    assert!(redacted[0].merchant.is_none() && redacted[0].location.is_none());
+    assert_eq!(redacted[0].actual_amount, Some(usd(4.5)));
+}
+
+#[test]
+fn budget_review_walks_steps_and_resumes_from_the_log() {
+    use crate::{ReviewItem, ReviewService};
+    use bufy_domain::review::ReviewStep;
+    use chrono::TimeZone;
+
+    let clock = FixedClock(chrono::Utc.with_ymd_and_hms(2025, 3, 20, 9, 0, 0).unwrap());
+    let today = NaiveDate::from_ymd_opt(2025, 3, 20).unwrap();
+    let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+    let mut ledger = LedgerService::create("Review", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let savings = ledger.add_account(Account::new("Savings", AccountKind::Savings));
+    let shops = ledger.add_account(Account::new("Shops", AccountKind::ExpenseDestination));
+    let groceries = Category::new("Groceries", CategoryKind::Expense);
+    let groceries_id = groceries.id();
+    CategoryService::add(&mut ledger, groceries).unwrap();
+    CategoryService::set_budget(
+        &mut ledger,
+        groceries_id,
+        100.0,
+        BudgetPeriod::Monthly,
+        None,
+    )
+    .unwrap();
+
+    let mut spent = Transaction::new(checking, shops, Some(groceries_id), date(5), usd(150.0));
+    spent.mark_completed(date(5), usd(150.0));
+    ledger.add_transaction(spent);
+    let loose =
+        ledger.add_transaction(Transaction::new(checking, shops, None, date(10), usd(12.0)));
+    ledger.add_transaction(Transaction::new(
+        checking,
+        savings,
+        None,
+        date(11),
+        usd(200.0),
+    ));
+    let bill = ledger.add_transaction(Transaction::new(checking, shops, None, date(28), usd(40.0)));
+
+    let (review_id, resumed) = ReviewService::start_or_resume(&mut ledger, &clock);
+    assert!(!resumed);
+    assert_eq!(
+        ReviewService::pending_items(&ledger, today),
+        vec![ReviewItem::Uncategorized {
+            transaction_id: loose,
+            suggestion: None,
+        }]
+    );
+    ReviewService::categorize(&mut ledger, loose, groceries_id, &clock).unwrap();
+    assert_eq!(
+        ledger.transaction(loose).unwrap().category_id,
+        Some(groceries_id)
+    );
+    assert!(ReviewService::pending_items(&ledger, today).is_empty());
+
+    assert_eq!(
+        ReviewService::advance(&mut ledger, &clock).unwrap(),
+        ReviewStep::OverBudget
+    );
+    let over = ReviewService::pending_items(&ledger, today);
+    assert_eq!(over.len(), 1);
+    assert_eq!(over[0].subject_id(), groceries_id);
+    assert_eq!(over[0].proposed_budget(), Some(150.0));
+
+    // A later session picks up the same review at the same step.
+    let (resumed_id, resumed) = ReviewService::start_or_resume(&mut ledger, &clock);
+    assert_eq!((resumed_id, resumed), (review_id, true));
+    ReviewService::adjust_budget(&mut ledger, groceries_id, 150.0, &clock).unwrap();
+    assert_eq!(
+        ledger
+            .category(groceries_id)
+            .unwrap()
+            .budget
+            .as_ref()
+            .unwrap()
+            .amount,
+        usd(150.0)
+    );
+
+    ReviewService::advance(&mut ledger, &clock).unwrap();
+    let bills = ReviewService::pending_items(&ledger, today);
+    assert_eq!(bills.len(), 1);
+    assert_eq!(bills[0].subject_id(), bill);
+    ReviewService::dismiss(&mut ledger, bill, &clock).unwrap();
+
+    ReviewService::advance(&mut ledger, &clock).unwrap();
+    assert_eq!(
+        ReviewService::advance(&mut ledger, &clock).unwrap(),
+        ReviewStep::Done
+    );
+    assert!(ledger.open_review().is_none());
+    let finished = ReviewService::last_completed(&ledger).unwrap();
+    assert_eq!(finished.id, review_id);
+    assert_eq!(finished.log.len(), 4);
+    assert!(finished.log[1].message.contains("from 100.00 to 150.00"));
+    assert!(ReviewService::advance(&mut ledger, &clock).is_err());
+    assert!(!ReviewService::start_or_resume(&mut ledger, &clock).1);
+}
+
+#[test]
+fn dashboard_snapshot_matches_separate_summary_forecast_and_recurrences() {
+    use crate::{forecast_service::ForecastService, public_api};
+
+    let mut ledger = LedgerService::create("Dashboard", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let gym = ledger.add_account(Account::new("Gym", AccountKind::ExpenseDestination));
+    let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let mut spent = Transaction::new(checking, gym, None, date(3, 2), usd(15.0));
+    spent.mark_completed(date(3, 2), usd(15.0));
+    ledger.add_transaction(spent);
+    let mut membership = Transaction::new(checking, gym, None, date(1, 25), usd(20.0));
+    membership.set_recurrence(Some(Recurrence::new(
+        date(1, 25),
+        TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        RecurrenceMode::FixedSchedule,
+    )));
+    ledger.add_transaction(membership);
+
+    let reference = date(3, 10);
+    let snapshot = public_api::api_dashboard_snapshot(&ledger, reference);
+    let summary = public_api::api_ledger_summary(&ledger, reference);
+    assert_eq!(snapshot.summary.actual_total, summary.actual_total);
+    assert_eq!(snapshot.summary.window_start, date(3, 1));
+
+    let window = ledger.budget_window_containing(reference);
+    let forecast = ForecastService::window_report(&ledger, window, reference, None).unwrap();
+    assert_eq!(
+        snapshot.forecast.projected_transactions,
+        forecast.forecast.totals.generated
+    );
+    assert!(snapshot.forecast.projected_transactions > 0);
+    assert_eq!(snapshot.forecast.projected_totals, forecast.summary.totals);
+
+    let recurrences = ledger.recurrence_snapshots(reference);
+    assert_eq!(snapshot.recurrences.len(), recurrences.len());
+    assert_eq!(snapshot.recurrences[0].next_due, recurrences[0].next_due);
+    assert_eq!(snapshot.recurrences[0].overdue, 2);
+
+    let json: serde_json::Value = serde_json::to_value(&snapshot).unwrap();
+    assert_eq!(json["reference_date"], "2025-03-10");
+    assert!(json["summary"]["alerts"].is_array());
+    assert!(json["recurrences"][0]["next_due"].is_string());
+}
+
+#[test]
+fn category_groups_aggregate_members_and_enforce_group_budgets() {
+    use crate::category_group_service::CategoryGroupService;
+    use bufy_domain::{
+        ledger::{BudgetScope, BudgetStatus},
+        DateWindow,
+    };
+
+    let mut ledger = LedgerService::create("Groups", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id();
+    let shops = Account::new("Shops", AccountKind::ExpenseDestination);
+    let shops_id = shops.id();
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    AccountService::add(&mut ledger, shops).expect("add shops");
+    let mut category_ids = Vec::new();
+    for (name, budget) in [("Rent", 800.0), ("Groceries", 300.0), ("Dining", 100.0)] {
+        let category = Category::new(name, CategoryKind::Expense);
+        category_ids.push(category.id());
+        CategoryService::add(&mut ledger, category).expect("add category");
+        CategoryService::set_budget(
+            &mut ledger,
+            *category_ids.last().unwrap(),
+            budget,
+            BudgetPeriod::Monthly,
+            None,
+        )
+        .expect("set budget");
+    }
+    let [rent, groceries, dining] = category_ids[..] else {
+        unreachable!()
+    };
+    let date = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+    for (category, amount) in [(rent, 800.0), (groceries, 250.0), (dining, 90.0)] {
+        let mut txn = Transaction::new(checking_id, shops_id, Some(category), date(5), usd(amount));
+        txn.mark_completed(date(5), usd(amount));
+        TransactionService::add(&mut ledger, txn).expect("add spending");
+    }
+
+    CategoryGroupService::create(&mut ledger, "Essentials").expect("create group");
+    assert!(CategoryGroupService::create(&mut ledger, " essentials ").is_err());
+    CategoryGroupService::assign(&mut ledger, "essentials", rent).expect("assign rent");
+    CategoryGroupService::assign(&mut ledger, "Essentials", dining).expect("assign dining");
+    CategoryGroupService::create(&mut ledger, "Lifestyle").expect("create group");
+    CategoryGroupService::assign(&mut ledger, "Lifestyle", dining).expect("move dining");
+    CategoryGroupService::assign(&mut ledger, "Essentials", groceries).expect("assign");
+    assert_eq!(ledger.group_of(dining).unwrap().name, "Lifestyle");
+
+    let window = DateWindow::new(date(1), NaiveDate::from_ymd_opt(2025, 4, 1).unwrap()).unwrap();
+    let summaries = CategoryGroupService::summaries(&ledger, window, BudgetScope::Custom);
+    let essentials = &summaries[0];
+    // Without a group budget the members' budgets are summed.
+    assert!(!essentials.group_budget);
+    assert_eq!(essentials.budget_amount, Some(1100.0));
+    assert_eq!(essentials.totals.real, 1050.0);
+    assert_eq!(essentials.members.len(), 2);
+    assert_eq!(essentials.totals.status, BudgetStatus::UnderBudget);
+
+    // A group budget tighter than the members' own is enforced across them.
+    CategoryGroupService::set_budget(
+        &mut ledger,
+        "Essentials",
+        1000.0,
+        BudgetPeriod::Monthly,
+        None,
+    )
+    .expect("set group budget");
+    let essentials = &CategoryGroupService::summaries(&ledger, window, BudgetScope::Custom)[0];
+    assert_eq!(essentials.budget_amount, Some(1000.0));
+    assert_eq!(essentials.totals.status, BudgetStatus::OverBudget);
+    let group_id = essentials.group_id;
+    let alerts = AlertService::evaluate(&ledger, date(20));
+    assert!(alerts
+        .iter()
+        .any(|alert| alert.kind == AlertKind::OverBudget && alert.subject_id == group_id));
+
+    assert!(CategoryGroupService::clear_budget(&mut ledger, "Essentials").unwrap());
+    assert!(!AlertService::evaluate(&ledger, date(20))
+        .iter()
+        .any(|alert| alert.subject_id == group_id));
+
+    assert!(CategoryGroupService::unassign(&mut ledger, rent));
+    assert!(!CategoryGroupService::unassign(&mut ledger, rent));
+    CategoryGroupService::remove(&mut ledger, "Lifestyle").expect("remove group");
+    assert!(ledger.group_of(dining).is_none());
+    assert!(ledger.category(dining).is_some());
+}
+
+#[test]
+fn summary_delta_returns_only_what_changed_since_a_revision() {
+    use crate::public_api::{ApiSummaryTracker, SUMMARY_HISTORY};
+
+    let mut ledger = LedgerService::create("Delta", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let food = ledger.add_category(Category::new("Food", CategoryKind::Expense));
+    let fuel = ledger.add_category(Category::new("Fuel", CategoryKind::Expense));
+    let date = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+    let spend = |ledger: &mut bufy_domain::Ledger, category, amount: Money| {
+        let mut txn = Transaction::new(checking, shop, Some(category), date(2), amount.clone());
+        txn.mark_completed(date(2), amount);
+        ledger.add_transaction(txn);
+    };
+    spend(&mut ledger, food, usd(40.0));
+    spend(&mut ledger, fuel, usd(30.0));
+
+    let mut tracker = ApiSummaryTracker::default();
+    let full = tracker.delta(&ledger, date(10), 0);
+    assert_eq!(full.base_revision, None);
+    assert_eq!(full.summary.as_ref().unwrap().actual_total, 70.0);
+    assert_eq!(full.changed_categories.len(), 2);
+    let first = full.revision;
+
+    // Nothing edited: nothing to send.
+    assert!(tracker.delta(&ledger, date(10), first).is_empty());
+
+    tracker.bump();
+    spend(&mut ledger, food, usd(10.0));
+    let delta = tracker.delta(&ledger, date(10), first);
+    assert_eq!(delta.base_revision, Some(first));
+    assert_eq!(delta.revision, first + 1);
+    assert_eq!(delta.summary.unwrap().actual_total, 80.0);
+    assert_eq!(delta.changed_categories.len(), 1);
+    assert_eq!(delta.changed_categories[0].category_id, Some(food));
+    assert_eq!(delta.changed_categories[0].totals.real, 50.0);
+
+    tracker.bump();
+    ledger
+        .transactions
+        .retain(|txn| txn.category_id != Some(fuel));
+    let delta = tracker.delta(&ledger, date(10), first + 1);
+    assert_eq!(delta.removed_categories, vec![Some(fuel)]);
+    assert!(delta.changed_categories.is_empty());
+
+    // Revisions that fell out of the history get everything again.
+    for _ in 0..SUMMARY_HISTORY {
+        tracker.bump();
+        tracker.delta(&ledger, date(10), tracker.revision());
+    }
+    assert_eq!(tracker.delta(&ledger, date(10), first).base_revision, None);
+}
+
+#[test]
+fn transactions_resolve_by_short_reference() {
+    use crate::public_api::api_transaction_by_reference;
+
+    let mut ledger = LedgerService::create("Refs", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 3, 2).unwrap();
+    let first = ledger.add_transaction(Transaction::new(checking, shop, None, date, usd(10.0)));
+    let second = ledger.add_transaction(Transaction::new(checking, shop, None, date, usd(20.0)));
+
+    let found = TransactionService::find_by_reference(&ledger, "t-2").unwrap();
+    assert_eq!(found.id, second);
+    assert_eq!(
+        api_transaction_by_reference(&ledger, "T-0001").unwrap(),
+        first
+    );
+
+    TransactionService::remove(&mut ledger, first).unwrap();
+    assert!(matches!(
+        TransactionService::find_by_reference(&ledger, "T-0001"),
+        Err(crate::CoreError::InvalidOperation(_))
+    ));
+    assert!(matches!(
+        TransactionService::find_by_reference(&ledger, "first"),
+        Err(crate::CoreError::Validation(_))
+    ));
+    assert_eq!(
+        TransactionService::find_by_reference(&ledger, "T0002")
+            .unwrap()
+            .id,
+        second
+    );
+}
+
+#[test]
+fn reconciliation_clears_transactions_until_the_statement_balances() {
+    use crate::reconciliation_service::ReconciliationService;
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Reconcile", LedgerBudgetPeriod::monthly());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(1000.0));
+    let checking_id = checking.id();
+    let shop = Account::new("Shop", AccountKind::ExpenseDestination);
+    let shop_id = shop.id();
+    AccountService::add(&mut ledger, checking).unwrap();
+    AccountService::add(&mut ledger, shop).unwrap();
+    let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+    let mut ids = Vec::new();
+    for (date, amount, completed) in [
+        (2, 40.0, true),
+        (9, 60.0, true),
+        (20, 25.0, true),
+        (12, 5.0, false),
+    ] {
+        let mut txn = Transaction::new(checking_id, shop_id, None, day(date), usd(amount));
+        if completed {
+            txn.mark_completed(day(date), usd(amount));
+        }
+        ids.push(TransactionService::add(&mut ledger, txn).unwrap());
+    }
+
+    assert!(ReconciliationService::start(&mut ledger, shop_id, day(15), 0.0).is_err());
+    ReconciliationService::start(&mut ledger, checking_id, day(15), 960.0).unwrap();
+    let report = ReconciliationService::report(&ledger, checking_id).unwrap();
+    // The planned transaction and the one after the statement date are left out.
+    assert_eq!(report.uncleared.len(), 2);
+    assert_eq!(report.uncleared_total, -100.0);
+    assert_eq!(report.difference(), -40.0);
+
+    assert_eq!(
+        ReconciliationService::match_completed(&mut ledger, checking_id).unwrap(),
+        2
+    );
+    assert!(ReconciliationService::set_cleared(&mut ledger, checking_id, ids[3], true).is_err());
+    let report = ReconciliationService::report(&ledger, checking_id).unwrap();
+    assert_eq!(report.cleared_balance(), 900.0);
+    assert!(!report.is_balanced());
+    assert!(ReconciliationService::finish(&mut ledger, checking_id, &clock).is_err());
+
+    // The bank has not seen the second payment yet.
+    ReconciliationService::set_cleared(&mut ledger, checking_id, ids[1], false).unwrap();
+    let report = ReconciliationService::finish(&mut ledger, checking_id, &clock).unwrap();
+    assert!(report.reconciled);
+    assert!(ReconciliationService::open_statement(&ledger, checking_id)
+        .unwrap()
+        .is_none());
+
+    // The next statement opens at the last closing balance and only sees
+    // what the first one did not claim.
+    assert!(ReconciliationService::start(&mut ledger, checking_id, day(10), 900.0).is_err());
+    ReconciliationService::start(&mut ledger, checking_id, day(31), 875.0).unwrap();
+    ReconciliationService::match_completed(&mut ledger, checking_id).unwrap();
+    let report = ReconciliationService::report(&ledger, checking_id).unwrap();
+    assert_eq!(report.opening_balance, 960.0);
+    assert_eq!(report.cleared.len(), 2);
+    assert!(report.is_balanced());
+}
+
+#[test]
+fn contract_windows_flag_notice_deadlines_for_renegotiation() {
+    use crate::contract_service::{ContractService, ContractStage};
+    use bufy_domain::transaction::ContractTerms;
+
+    let mut ledger = LedgerService::create("Contracts", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let isp = Account::new("Fibre Co", AccountKind::ExpenseDestination);
+    let isp_id = isp.id;
+    AccountService::add(&mut ledger, isp).expect("add provider");
+
+    let date = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+    let monthly = TimeInterval {
+        every: 1,
+        unit: TimeUnit::Month,
+    };
+    let bill = Transaction::new(checking_id, isp_id, None, date(1, 5), usd(45.0)).with_recurrence(
+        Recurrence::new(date(1, 5), monthly, RecurrenceMode::FixedSchedule),
+    );
+    let bill_id = TransactionService::add(&mut ledger, bill).expect("add bill");
+    let one_off = Transaction::new(checking_id, isp_id, None, date(1, 5), usd(90.0));
+    let one_off_id = TransactionService::add(&mut ledger, one_off).expect("add one-off");
+
+    let terms = |provider: &str| ContractTerms {
+        provider: provider.into(),
+        end_date: date(6, 30),
+        notice_period: Some(TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        }),
+    };
+    assert!(ContractService::set(&mut ledger, one_off_id, terms("Fibre Co")).is_err());
+    assert!(ContractService::set(&mut ledger, bill_id, terms("  ")).is_err());
+    ContractService::set(&mut ledger, bill_id, terms(" Fibre Co ")).expect("set contract");
+
+    let stage_on = |today| ContractService::windows(&ledger, today)[0].stage;
+    let window = &ContractService::windows(&ledger, date(4, 1))[0];
+    assert_eq!(window.provider, "Fibre Co");
+    assert_eq!(window.notice_deadline, date(5, 30));
+    assert_eq!(window.days_to_notice, 59);
+    assert_eq!(window.stage, ContractStage::Running);
+    assert_eq!(stage_on(date(5, 10)), ContractStage::NoticeDue);
+    assert_eq!(stage_on(date(6, 1)), ContractStage::Renewing);
+    assert_eq!(stage_on(date(7, 1)), ContractStage::Lapsed);
+
+    let notices = |today| {
+        AlertService::evaluate(&ledger, today)
+            .into_iter()
+            .filter(|alert| alert.kind == AlertKind::ContractNotice)
+            .count()
+    };
+    assert_eq!(notices(date(4, 1)), 0);
+    assert_eq!(notices(date(5, 10)), 1);
+    assert_eq!(notices(date(6, 1)), 0);
+
+    assert!(ContractService::clear(&mut ledger, bill_id).unwrap());
+    assert!(!ContractService::clear(&mut ledger, bill_id).unwrap());
+    assert!(ContractService::windows(&ledger, date(5, 10)).is_empty());
+}
+
+#[test]
+fn goal_progress_projects_completion_from_the_funding_schedule() {
+    use crate::goal_service::GoalService;
+    use bufy_domain::goal::{FundingSchedule, GoalLink};
+
+    let mut ledger = LedgerService::create("Goals", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let savings = Account::new("Holiday pot", AccountKind::Savings);
+    let savings_id = savings.id;
+    AccountService::add(&mut ledger, savings).expect("add savings");
+
+    let date = |month, day| NaiveDate::from_ymd_opt(2025, month, day).unwrap();
+    let mut deposit = Transaction::new(checking_id, savings_id, None, date(1, 15), usd(300.0));
+    deposit.status = TransactionStatus::Completed;
+    TransactionService::add(&mut ledger, deposit).expect("add deposit");
+
+    let clock = FixedClock(date(2, 1).and_hms_opt(0, 0, 0).unwrap().and_utc());
+    let funding = FundingSchedule {
+        amount: 200.0,
+        interval: TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        start: date(2, 1),
+    };
+    assert!(GoalService::create(
+        &mut ledger,
+        " ",
+        1000.0,
+        date(6, 30),
+        GoalLink::Account(savings_id),
+        None,
+        &clock,
+    )
+    .is_err());
+    GoalService::create(
+        &mut ledger,
+        "Holiday",
+        1200.0,
+        date(6, 30),
+        GoalLink::Account(savings_id),
+        Some(funding),
+        &clock,
+    )
+    .expect("create goal");
+
+    let goal = ledger.goal("holiday").expect("goal exists");
+    let progress = GoalService::progress(&ledger, goal, date(2, 1));
+    assert_eq!(progress.saved, 300.0);
+    assert_eq!(progress.remaining, 900.0);
+    assert_eq!(progress.percent, 25.0);
+    assert_eq!(progress.required_monthly, 180.0);
+    assert_eq!(progress.projected_completion, Some(date(6, 1)));
+    assert!(progress.is_on_track());
+
+    let window = bufy_domain::ledger::DateWindow::new(date(2, 1), date(5, 1)).unwrap();
+    let contributions = GoalService::contributions(&ledger, &window, date(2, 1));
+    assert_eq!(contributions.len(), 3);
+    assert_eq!(contributions[0].date, date(2, 1));
+
+    let report = crate::forecast_service::ForecastService::window_report(
+        &ledger,
+        bufy_domain::ledger::DateWindow::new(date(5, 1), date(12, 1)).unwrap(),
+        date(2, 1),
+        None,
+    )
+    .expect("forecast");
+    let dates: Vec<_> = report.goal_contributions.iter().map(|c| c.date).collect();
+    assert_eq!(dates, vec![date(5, 1), date(6, 1)]);
+}
+
+#[test]
+fn uncategorized_report_groups_by_payee_and_batch_assigns() {
+    use crate::uncategorized_service::{FlowKind, UncategorizedService};
+
+    let mut ledger = LedgerService::create("Uncategorized", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let savings = Account::new("Savings", AccountKind::Savings);
+    let savings_id = savings.id;
+    AccountService::add(&mut ledger, savings).expect("add savings");
+    let employer = Account::new("Employer", AccountKind::IncomeSource);
+    let employer_id = employer.id;
+    AccountService::add(&mut ledger, employer).expect("add employer");
+    let store = Account::new("Card payments", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    AccountService::add(&mut ledger, store).expect("add store");
+    let groceries = Category::new("Groceries", CategoryKind::Expense);
+    let groceries_id = groceries.id;
+    CategoryService::add(&mut ledger, groceries).expect("add category");
+
+    let date = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+    let completed = |mut txn: Transaction| {
+        txn.status = TransactionStatus::Completed;
+        txn.actual_date = Some(txn.scheduled_date);
+        txn
+    };
+    let mut market = Transaction::new(checking_id, store_id, None, date(3), usd(40.0));
+    market.merchant = Some("Corner Market".into());
+    let mut market_again = completed(Transaction::new(
+        checking_id,
+        store_id,
+        None,
+        date(9),
+        usd(25.0),
+    ));
+    market_again.merchant = Some("corner market".into());
+    let other_shop = completed(Transaction::new(
+        checking_id,
+        store_id,
+        None,
+        date(5),
+        usd(12.0),
+    ));
+    let salary = completed(Transaction::new(
+        employer_id,
+        checking_id,
+        None,
+        date(1),
+        usd(2000.0),
+    ));
+    let transfer = Transaction::new(checking_id, savings_id, None, date(2), usd(300.0));
+    let future = Transaction::new(checking_id, store_id, None, date(28), usd(99.0));
+    for txn in [market, market_again, other_shop, salary, transfer, future] {
+        TransactionService::add(&mut ledger, txn).expect("add transaction");
+    }
+
+    let report = UncategorizedService::report(&ledger, date(15));
+    assert_eq!(report.transaction_count(), 4);
+    assert_eq!(report.period_income, 2000.0);
+    assert_eq!(report.period_expense, 37.0);
+    let market = report
+        .groups
+        .iter()
+        .find(|group| group.payee.eq_ignore_ascii_case("corner market"))
+        .expect("merchant group");
+    assert_eq!(market.kind, FlowKind::Expense);
+    assert_eq!(market.transaction_ids.len(), 2);
+    assert_eq!(market.total, 65.0);
+    assert_eq!(market.period_total, 25.0);
+    assert!(report
+        .groups
+        .iter()
+        .any(|group| group.payee == "Employer" && group.kind == FlowKind::Income));
+
+    let clock = FixedClock(chrono::Utc::now());
+    let assigned =
+        UncategorizedService::assign(&mut ledger, &market.transaction_ids, groceries_id, &clock)
+            .expect("assign");
+    assert_eq!(assigned, 2);
+    assert!(!ledger.learned_rules().is_empty());
+    assert_eq!(
+        UncategorizedService::report(&ledger, date(15)).transaction_count(),
+        2
+    );
+}
+
+#[test]
+fn change_journal_undoes_and_redoes_ledger_edits() {
+    use crate::journal::ChangeJournal;
+
+    let mut ledger = LedgerService::create("Journal", LedgerBudgetPeriod::monthly());
+    let mut journal = ChangeJournal::new();
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add_journaled(&mut ledger, checking, &mut journal).expect("add checking");
+    let store = Account::new("Store", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    AccountService::add_journaled(&mut ledger, store, &mut journal).expect("add store");
+    let groceries = Category::new("Groceries", CategoryKind::Expense);
+    let groceries_id = groceries.id;
+    CategoryService::add_journaled(&mut ledger, groceries, &mut journal).expect("add category");
+
+    let date = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+    let txn_id = TransactionService::add_journaled(
+        &mut ledger,
+        Transaction::new(checking_id, store_id, None, date, usd(40.0)),
+        &mut journal,
+    )
+    .expect("add transaction");
+    TransactionService::update_journaled(
+        &mut ledger,
+        txn_id,
+        |txn| txn.budgeted_amount = usd(55.0),
+        &mut journal,
+    )
+    .expect("edit transaction");
+    let mut renamed = ledger.account(checking_id).unwrap().clone();
+    renamed.name = "Main".into();
+    AccountService::edit_journaled(&mut ledger, checking_id, renamed, &mut journal)
+        .expect("edit account");
+    CategoryService::remove_journaled(&mut ledger, groceries_id, &mut journal)
+        .expect("remove category");
+    assert_eq!(journal.undo_history().len(), 7);
+
+    assert!(journal.undo(&mut ledger).unwrap().is_some());
+    assert!(ledger.category(groceries_id).is_some());
+    journal.undo(&mut ledger).unwrap();
+    assert_eq!(ledger.account(checking_id).unwrap().name, "Checking");
+    journal.undo(&mut ledger).unwrap();
+    assert_eq!(
+        ledger.transaction(txn_id).unwrap().budgeted_amount,
+        usd(40.0)
+    );
+    journal.undo(&mut ledger).unwrap();
+    assert!(ledger.transaction(txn_id).is_none());
+    assert_eq!(journal.redo_history().len(), 4);
+
+    let redone = journal.redo(&mut ledger).unwrap().expect("redo add");
+    assert!(redone.starts_with("add transaction"));
+    assert!(ledger.transaction(txn_id).is_some());
+
+    TransactionService::remove_journaled(&mut ledger, txn_id, &mut journal)
+        .expect("remove transaction");
+    assert!(journal.redo_history().is_empty());
+    journal.undo(&mut ledger).unwrap();
+    assert_eq!(
+        ledger.transaction(txn_id).unwrap().budgeted_amount,
+        usd(40.0)
+    );
+
+    while journal.undo(&mut ledger).unwrap().is_some() {}
+    assert!(ledger.accounts.is_empty());
+    assert!(ledger.categories.is_empty());
+    assert!(ledger.transactions.is_empty());
+}
+
+#[test]
+fn audit_changes_name_added_edited_and_removed_records() {
+    use crate::audit::{AuditAction, AuditService, LEDGER_ENTITY};
+
+    let mut ledger = LedgerService::create("Audit", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let store = Account::new("Store", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    AccountService::add(&mut ledger, store).expect("add store");
+    let cash = Account::new("Cash", AccountKind::Cash);
+    let cash_id = cash.id;
+    AccountService::add(&mut ledger, cash).expect("add cash");
+
+    let initial = AuditService::changes(None, &ledger).expect("initial changes");
+    assert_eq!(initial.len(), 3);
+    assert!(initial
+        .iter()
+        .all(|change| change.action == AuditAction::Added && change.before.is_none()));
+
+    let previous = ledger.clone();
+    let date = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+    let txn_id = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, store_id, None, date, usd(40.0)),
+    )
+    .expect("add transaction");
+    ledger.account_mut(checking_id).unwrap().name = "Main".into();
+    AccountService::remove(&mut ledger, cash_id).expect("remove cash");
+    ledger.name = "Household".into();
+
+    let changes = AuditService::changes(Some(&previous), &ledger).expect("changes");
+    let added = changes
+        .iter()
+        .find(|change| change.entity_id == txn_id.to_string())
+        .expect("transaction entry");
+    assert_eq!(added.action, AuditAction::Added);
+    assert_eq!(added.label.as_deref(), Some("T-0001"));
+    let edited = changes
+        .iter()
+        .find(|change| change.entity_id == checking_id.to_string())
+        .expect("account entry");
+    assert_eq!(edited.action, AuditAction::Edited);
+    assert_eq!(edited.label.as_deref(), Some("Main"));
+    assert_ne!(edited.before, edited.after);
+    let removed = changes
+        .iter()
+        .find(|change| change.entity_id == cash_id.to_string())
+        .expect("removed entry");
+    assert_eq!(removed.action, AuditAction::Removed);
+    assert!(removed.before.is_some() && removed.after.is_none());
+    assert!(changes
+        .iter()
+        .any(|change| change.entity == LEDGER_ENTITY && change.entity_id == "name"));
+    assert!(!changes
+        .iter()
+        .any(|change| change.entity_id == "updated_at" || change.entity_id == "next_reference"));
+    assert!(AuditService::changes(Some(&ledger), &ledger)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn partial_completion_splits_planned_amount_without_changing_budget() {
+    use bufy_domain::ledger::{BudgetScope, DateWindow};
+
+    let mut ledger = LedgerService::create("Installments", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let insurer = Account::new("Insurer", AccountKind::ExpenseDestination);
+    let insurer_id = insurer.id;
+    AccountService::add(&mut ledger, insurer).expect("add insurer");
+
+    let due = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+    let bill_id = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, insurer_id, None, due, usd(300.0)),
+    )
+    .expect("add bill");
+    let window = DateWindow::new(
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2025, 4, 1).unwrap(),
+    )
+    .unwrap();
+    let before = SummaryService::summarize_window(&ledger, window, BudgetScope::Custom);
+
+    let paid_on = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+    let (installment_id, _) =
+        TransactionService::complete_partial(&mut ledger, bill_id, paid_on, 120.0)
+            .expect("pay in part");
+    let bill = ledger.transaction(bill_id).unwrap();
+    assert_eq!(bill.status, TransactionStatus::Planned);
+    assert_eq!(bill.budgeted_amount, usd(180.0));
+    let installment = ledger.transaction(installment_id).unwrap();
+    assert_eq!(installment.status, TransactionStatus::Completed);
+    assert_eq!(installment.actual_amount, Some(usd(120.0)));
+    assert_eq!(installment.installment_of, Some(bill_id));
+    assert_eq!(TransactionService::installments(&ledger, bill_id).len(), 1);
+
+    let after = SummaryService::summarize_window(&ledger, window, BudgetScope::Custom);
+    assert_eq!(after.totals.budgeted, before.totals.budgeted);
+    assert_eq!(after.totals.real, 120.0);
+
+    let err = TransactionService::complete_partial(&mut ledger, bill_id, paid_on, 180.0)
+        .expect_err("paying it all is a completion");
+    assert!(err.to_string().contains("$180.00 still planned"), "{err}");
+    assert!(
+        TransactionService::complete_partial(&mut ledger, installment_id, paid_on, 10.0).is_err()
+    );
+
+    ledger.account_mut(insurer_id).unwrap().status = AccountStatus::Closed { on: paid_on };
+    assert!(TransactionService::complete_partial(&mut ledger, bill_id, paid_on, 50.0).is_err());
+    assert_eq!(
+        ledger.transaction(bill_id).unwrap().budgeted_amount,
+        usd(180.0)
+    );
+    assert_eq!(TransactionService::installments(&ledger, bill_id).len(), 1);
+}
+
+#[test]
+fn asset_report_spreads_cost_over_lifetime_and_tracks_warranties() {
+    use crate::asset_service::AssetService;
+    use bufy_domain::transaction::AssetRecord;
+
+    let mut ledger = LedgerService::create("Assets", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let store = Account::new("Store", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    AccountService::add(&mut ledger, store).expect("add store");
+
+    let bought = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+    let mut add_purchase = |amount: f64| {
+        let mut txn = Transaction::new(checking_id, store_id, None, bought, usd(amount));
+        txn.status = TransactionStatus::Completed;
+        txn.actual_date = Some(bought);
+        txn.actual_amount = Some(usd(amount));
+        TransactionService::add(&mut ledger, txn).expect("add purchase")
+    };
+    let laptop_id = add_purchase(1200.0);
+    let kettle_id = add_purchase(60.0);
+
+    AssetService::set(
+        &mut ledger,
+        laptop_id,
+        AssetRecord {
+            name: " Laptop ".into(),
+            warranty_until: Some(NaiveDate::from_ymd_opt(2027, 1, 15).unwrap()),
+            lifetime_months: Some(36),
+        },
+    )
+    .expect("record laptop");
+    AssetService::set(
+        &mut ledger,
+        kettle_id,
+        AssetRecord {
+            name: "Kettle".into(),
+            warranty_until: Some(NaiveDate::from_ymd_opt(2025, 7, 15).unwrap()),
+            lifetime_months: Some(6),
+        },
+    )
+    .expect("record kettle");
+
+    let today = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+    let report = AssetService::report(&ledger, today);
+    assert_eq!(report.assets.len(), 2);
+    let kettle = &report.assets[0];
+    assert_eq!(kettle.name, "Kettle");
+    assert!(!kettle.has_active_warranty());
+    assert!(!kettle.in_service(today));
+    assert_eq!(kettle.remaining_value, Some(0.0));
+    let laptop = &report.assets[1];
+    assert_eq!(laptop.name, "Laptop");
+    assert!(laptop.has_active_warranty());
+    assert_eq!(laptop.warranty_days_left, Some(365));
+    assert_eq!(laptop.monthly_cost, Some(1200.0 / 36.0));
+    assert_eq!(laptop.remaining_value, Some(800.0));
+    assert!((report.monthly_cost - 1200.0 / 36.0).abs() < 1e-9);
+
+    let blank = AssetRecord {
+        name: "  ".into(),
+        warranty_until: None,
+        lifetime_months: None,
+    };
+    assert!(AssetService::set(&mut ledger, kettle_id, blank).is_err());
+    assert!(AssetService::clear(&mut ledger, kettle_id).expect("clear kettle"));
+    assert!(!AssetService::clear(&mut ledger, kettle_id).expect("clear again"));
+    assert_eq!(AssetService::report(&ledger, today).assets.len(), 1);
+}
+
+#[test]
+fn budget_notes_follow_categories_into_summaries() {
+    use bufy_domain::ledger::{BudgetScope, DateWindow};
+
+    let mut ledger = LedgerService::create("Notes", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id();
+    let gym = Account::new("Gym", AccountKind::ExpenseDestination);
+    let gym_id = gym.id();
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    AccountService::add(&mut ledger, gym).expect("add gym");
+    let sports = Category::new("Sports", CategoryKind::Expense);
+    let sports_id = sports.id();
+    CategoryService::add(&mut ledger, sports).expect("add category");
+
+    assert!(CategoryService::set_budget_note(&mut ledger, sports_id, Some("gym")).is_err());
+    CategoryService::set_budget(&mut ledger, sports_id, 90.0, BudgetPeriod::Monthly, None)
+        .expect("set budget");
+    CategoryService::set_budget_note(
+        &mut ledger,
+        sports_id,
+        Some("  includes gym + sports gear "),
+    )
+    .expect("set note");
+
+    let date = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+    TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, gym_id, Some(sports_id), date, usd(40.0)),
+    )
+    .expect("add spending");
+    let window = DateWindow::new(date, NaiveDate::from_ymd_opt(2025, 4, 1).unwrap()).unwrap();
+    let summary = SummaryService::summarize_window(&ledger, window, BudgetScope::Custom);
+    let row = summary
+        .per_category
+        .iter()
+        .find(|row| row.category_id == Some(sports_id))
+        .expect("sports row");
+    assert_eq!(row.note.as_deref(), Some("includes gym + sports gear"));
+
+    CategoryService::set_budget_note(&mut ledger, sports_id, Some(" ")).expect("clear note");
+    let budget = ledger.category(sports_id).unwrap().budget.as_ref().unwrap();
+    assert_eq!(budget.note, None);
+}
+
+#[test]
+fn ledger_merge_combines_divergent_copies_and_flags_conflicts() {
+    use crate::MergeSide;
+
+    let mut base = LedgerService::create("Shared", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id();
+    let grocer = Account::new("Grocer", AccountKind::ExpenseDestination);
+    let grocer_id = grocer.id();
+    AccountService::add(&mut base, checking).expect("add checking");
+    AccountService::add(&mut base, grocer).expect("add grocer");
+    let date = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+    let shared_id = TransactionService::add(
+        &mut base,
+        Transaction::new(checking_id, grocer_id, None, date, usd(40.0)),
+    )
+    .expect("add shared");
+
+    let mut theirs = base.clone();
+    let mut ours = base.clone();
+    theirs.account_mut(grocer_id).unwrap().name = "Grocery Store".into();
+    let theirs_new = TransactionService::add(
+        &mut theirs,
+        Transaction::new(checking_id, grocer_id, None, date, usd(12.0)),
+    )
+    .expect("add theirs");
+    let ours_new = TransactionService::add(
+        &mut ours,
+        Transaction::new(checking_id, grocer_id, None, date, usd(7.0)),
+    )
+    .expect("add ours");
+    TransactionService::update(&mut theirs, shared_id, |txn| {
+        txn.budgeted_amount = usd(45.0);
+        txn.notes = Some("split with flatmate".into());
+    })
+    .expect("edit theirs");
+    TransactionService::update(&mut ours, shared_id, |txn| txn.budgeted_amount = usd(50.0))
+        .expect("edit ours");
+
+    let merge = LedgerService::merge(&base, &theirs, &ours).expect("merge");
+    assert_eq!(merge.conflicts().len(), 1);
+    let conflict = &merge.conflicts()[0];
+    assert_eq!(conflict.entity, "transactions");
+    assert_eq!(conflict.field.as_deref(), Some("budgeted_amount"));
+
+    let merged = merge.clone().into_ledger().expect("build ours");
+    assert_eq!(merged.account(grocer_id).unwrap().name, "Grocery Store");
+    let shared = merged.transaction(shared_id).unwrap();
+    assert_eq!(shared.budgeted_amount, usd(50.0));
+    assert_eq!(shared.notes.as_deref(), Some("split with flatmate"));
+    let ours_ref = merged.transaction(ours_new).unwrap().reference;
+    let theirs_ref = merged.transaction(theirs_new).unwrap().reference;
+    assert!(ours_ref.is_some() && theirs_ref.is_some());
+    assert_ne!(ours_ref, theirs_ref);
+
+    let mut merge = merge;
+    merge.resolve(0, MergeSide::Theirs).expect("resolve");
+    assert!(merge.resolve(1, MergeSide::Theirs).is_err());
+    let merged = merge.into_ledger().expect("build theirs");
+    assert_eq!(
+        merged.transaction(shared_id).unwrap().budgeted_amount,
+        usd(45.0)
+    );
+
+    TransactionService::remove(&mut theirs, shared_id).expect("remove theirs");
+    let merge = LedgerService::merge(&base, &theirs, &ours).expect("merge removal");
+    assert!(merge
+        .conflicts()
+        .iter()
+        .any(|conflict| conflict.field.is_none() && conflict.theirs.is_none()));
+}
+
+#[test]
+fn orphaned_simulation_changes_are_flagged_skipped_and_pruned() {
+    use crate::simulation_service::SimulationService;
+    use bufy_domain::simulation::SimulationTransactionPatch;
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("What-if", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let gym = Account::new("Gym", AccountKind::ExpenseDestination);
+    let gym_id = gym.id;
+    AccountService::add(&mut ledger, gym).expect("add gym");
+    let date = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+    let kept_id = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, gym_id, None, date, usd(30.0)),
+    )
+    .expect("add kept");
+    let deleted_id = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, gym_id, None, date, usd(45.0)),
+    )
+    .expect("add deleted");
+
+    SimulationService::create(&mut ledger, "Cheaper gym", None, &clock).expect("create");
+    let patch = |transaction_id, amount| SimulationTransactionPatch {
+        transaction_id,
+        from_account: None,
+        to_account: None,
+        category_id: None,
+        scheduled_date: None,
+        actual_date: None,
+        budgeted_amount: Some(amount),
+        actual_amount: None,
+    };
+    SimulationService::modify_transaction(&mut ledger, "Cheaper gym", patch(deleted_id, 20.0))
+        .expect("modify deleted");
+    SimulationService::modify_transaction(&mut ledger, "Cheaper gym", patch(kept_id, 10.0))
+        .expect("modify kept");
+    assert!(SimulationService::check(&ledger).is_empty());
+
+    TransactionService::remove(&mut ledger, deleted_id).expect("remove");
+    let orphaned = SimulationService::orphaned_changes(&ledger, "Cheaper gym").expect("check");
+    assert_eq!(orphaned.len(), 1);
+    assert_eq!(orphaned[0].index, 0);
+    assert_eq!(orphaned[0].transaction_id, deleted_id);
+    assert_eq!(SimulationService::check(&ledger), orphaned);
+
+    let preview = SimulationService::run(&ledger, "Cheaper gym").expect("run");
+    assert_eq!(
+        preview.transaction(kept_id).unwrap().budgeted_amount,
+        usd(10.0)
+    );
+    assert!(SimulationService::apply(&mut ledger, "Cheaper gym", &clock).is_err());
+
+    assert_eq!(
+        SimulationService::prune_orphans(&mut ledger, "Cheaper gym", &clock).expect("prune"),
+        1
+    );
+    assert_eq!(
+        SimulationService::changes(&ledger, "Cheaper gym")
+            .expect("changes")
+            .len(),
+        1
+    );
+    SimulationService::apply(&mut ledger, "Cheaper gym", &clock).expect("apply");
+    assert_eq!(
+        ledger.transaction(kept_id).unwrap().budgeted_amount,
+        usd(10.0)
+    );
+}
+
+#[test]
+fn simulation_rollback_undoes_its_changes_and_keeps_later_edits() {
+    use crate::simulation_service::{RollbackConflictKind, SimulationService};
+    use bufy_domain::simulation::{SimulationStatus, SimulationTransactionPatch};
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("What-if", LedgerBudgetPeriod::monthly());
+    let checking_id = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let gym_id = ledger.add_account(Account::new("Gym", AccountKind::ExpenseDestination));
+    let date = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+    let add = |ledger: &mut bufy_domain::Ledger, amount| {
+        TransactionService::add(
+            ledger,
+            Transaction::new(checking_id, gym_id, None, date, amount),
+        )
+        .expect("add")
+    };
+    let kept_id = add(&mut ledger, usd(30.0));
+    let dropped_id = add(&mut ledger, usd(45.0));
+    let other_id = add(&mut ledger, usd(12.0));
+
+    SimulationService::create(&mut ledger, "Plan", None, &clock).expect("create");
+    SimulationService::modify_transaction(
+        &mut ledger,
+        "Plan",
+        SimulationTransactionPatch {
+            transaction_id: kept_id,
+            from_account: None,
+            to_account: None,
+            category_id: None,
+            scheduled_date: None,
+            actual_date: None,
+            budgeted_amount: Some(10.0),
+            actual_amount: None,
+        },
+    )
+    .expect("modify");
+    SimulationService::exclude_transaction(&mut ledger, "Plan", dropped_id).expect("exclude");
+    SimulationService::add_transaction(
+        &mut ledger,
+        "Plan",
+        Transaction::new(checking_id, gym_id, None, date, usd(5.0)),
+    )
+    .expect("add to simulation");
+    let amounts = |ledger: &bufy_domain::Ledger| {
+        let mut amounts: Vec<f64> = ledger
+            .transactions
+            .iter()
+            .map(|txn| txn.budgeted_amount.to_major())
+            .collect();
+        amounts.sort_by(f64::total_cmp);
+        amounts
+    };
+
+    SimulationService::apply(&mut ledger, "Plan", &clock).expect("apply");
+    assert_eq!(amounts(&ledger), [5.0, 10.0, 12.0]);
+    // Edits after the apply that the simulation never touched survive.
+    ledger.transaction_mut(other_id).unwrap().budgeted_amount = usd(15.0);
+    add(&mut ledger, usd(7.0));
+    assert!(SimulationService::rollback_conflicts(&ledger, "Plan")
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        SimulationService::rollback(&mut ledger, "Plan", false, &clock).unwrap(),
+        3
+    );
+    assert_eq!(amounts(&ledger), [7.0, 15.0, 30.0, 45.0]);
+    let simulation = ledger.simulation("Plan").unwrap();
+    assert_eq!(simulation.status, SimulationStatus::Pending);
+    assert_eq!(simulation.changes.len(), 3);
+    assert!(simulation.applied.is_empty());
+    assert!(SimulationService::rollback(&mut ledger, "Plan", false, &clock).is_err());
+
+    // Applying part of it and editing what it touched is a conflict.
+    SimulationService::apply_changes(&mut ledger, "Plan", &[0], &clock).expect("apply one");
+    ledger.transaction_mut(kept_id).unwrap().budgeted_amount = usd(11.0);
+    let conflicts = SimulationService::rollback_conflicts(&ledger, "Plan").unwrap();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].transaction_id, kept_id);
+    assert_eq!(conflicts[0].kind, RollbackConflictKind::Edited);
+    assert!(matches!(
+        SimulationService::rollback(&mut ledger, "Plan", false, &clock),
+        Err(CoreError::InvalidOperation(_))
+    ));
+    SimulationService::rollback(&mut ledger, "Plan", true, &clock).expect("forced rollback");
+    assert_eq!(
+        ledger.transaction(kept_id).unwrap().budgeted_amount,
+        usd(30.0)
+    );
+    assert_eq!(ledger.simulation("Plan").unwrap().changes.len(), 3);
+}
+
+#[test]
+fn budget_report_and_summary_exports_cover_each_category() {
+    use crate::export_service::ExportService;
+    use bufy_domain::ledger::{BudgetScope, DateWindow};
+
+    let mut ledger = LedgerService::create("Exports", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id();
+    let shops = Account::new("Shops", AccountKind::ExpenseDestination);
+    let shops_id = shops.id();
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    AccountService::add(&mut ledger, shops).expect("add shops");
+    let sports = Category::new("Sports", CategoryKind::Expense);
+    let sports_id = sports.id();
+    CategoryService::add(&mut ledger, sports).expect("add category");
+    CategoryService::set_budget(&mut ledger, sports_id, 90.0, BudgetPeriod::Monthly, None)
+        .expect("set budget");
+    CategoryService::set_budget_note(&mut ledger, sports_id, Some("gym, shoes")).expect("set note");
+    let date = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+    let mut txn = Transaction::new(checking_id, shops_id, Some(sports_id), date, usd(40.0));
+    txn.mark_completed(date, usd(35.5));
+    TransactionService::add(&mut ledger, txn).expect("add spending");
+
+    let window = DateWindow::new(
+        NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2025, 4, 1).unwrap(),
+    )
+    .unwrap();
+    let summary = SummaryService::summarize_window(&ledger, window, BudgetScope::Custom);
+    let budgets = SummaryService::category_budget_summaries(&ledger, window, BudgetScope::Custom);
+
+    let csv = ExportService::budget_report_csv(&ledger, &summary, &budgets);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "from,to,category,budgeted,actual,remaining,status,budget_limit,budget_remaining,note"
+    );
+    assert!(lines[1].starts_with("2025-03-01,2025-03-31,Sports,40.00,35.50,4.50,"));
+    assert!(lines[1].ends_with(",90.00,54.50,\"gym, shoes\""));
+    assert!(lines[2].starts_with("2025-03-01,2025-03-31,Total,40.00,35.50,4.50,"));
+
+    let json = ExportService::summary_json(&ledger, &summary, &budgets).expect("summary json");
+    let value: serde_json::Value = serde_json::from_str(&json).expect("parse json");
+    assert_eq!(value["ledger"], "Exports");
+    assert_eq!(value["totals"]["real"], 35.5);
+    assert_eq!(value["per_category"][0]["note"], "gym, shoes");
+    assert_eq!(value["category_budgets"][0]["budget_amount"], 90.0);
+}
+
+#[test]
+fn forecast_periods_project_balances_window_by_window() {
+    use crate::forecast_service::ForecastService;
+
+    let mut ledger = LedgerService::create("Periods", LedgerBudgetPeriod::monthly());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(1000.0));
+    let checking = ledger.add_account(checking);
+    let savings = ledger.add_account(Account::new("Savings", AccountKind::Savings));
+    let employer = ledger.add_account(Account::new("Employer", AccountKind::IncomeSource));
+    let landlord = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
+    let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let mut salary = Transaction::new(employer, checking, None, date(3, 1), usd(3000.0));
+    salary.mark_completed(date(3, 1), usd(3000.0));
+    ledger.add_transaction(salary);
+    let mut rent = Transaction::new(checking, landlord, None, date(3, 25), usd(900.0));
+    rent.set_recurrence(Some(Recurrence::new(
+        date(3, 25),
+        TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        RecurrenceMode::FixedSchedule,
+    )));
+    ledger.add_transaction(rent);
+    ledger.add_transaction(Transaction::new(
+        checking,
+        savings,
+        None,
+        date(4, 5),
+        usd(500.0),
+    ));
+
+    let periods = ForecastService::periods(&ledger, date(3, 10), 3, None).unwrap();
+    assert_eq!(periods.len(), 3);
+    assert_eq!(periods[0].window.start, date(3, 1));
+    assert_eq!(periods[2].window.start, date(5, 1));
+    assert_eq!(periods[0].opening_balance, 1000.0);
+    assert_eq!(periods[0].inflow, 3000.0);
+    assert_eq!(periods[0].outflow, 900.0);
+    assert_eq!(periods[0].closing_balance, 3100.0);
+    assert_eq!(periods[0].projected, 0);
+    assert_eq!(periods[1].opening_balance, 3100.0);
+    assert_eq!(
+        periods[1].outflow, 900.0,
+        "transfers to savings stay inside"
+    );
+    assert_eq!(periods[1].projected, 1);
+    assert_eq!(periods[2].net(), -900.0);
+    assert_eq!(periods[2].closing_balance, 1300.0);
+
+    assert!(ForecastService::periods(&ledger, date(3, 10), 0, None).is_err());
+}
+
+#[test]
+fn forecast_calendar_feed_lists_planned_and_projected_bills() {
+    use crate::export_service::ExportService;
+    use bufy_domain::DateWindow;
+
+    let mut ledger = LedgerService::create("Bills, 2025", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let landlord = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
+    let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let mut rent = Transaction::new(checking, landlord, None, date(3, 1), usd(900.0));
+    rent.set_recurrence(Some(Recurrence::new(
+        date(3, 1),
+        TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        RecurrenceMode::FixedSchedule,
+    )));
+    ledger.add_transaction(rent);
+    let mut insurance = Transaction::new(checking, landlord, None, date(3, 20), usd(120.5));
+    insurance.merchant = Some("Acme Insurance; annual".into());
+    ledger.add_transaction(insurance);
+    let mut paid = Transaction::new(checking, landlord, None, date(3, 5), usd(10.0));
+    paid.mark_completed(date(3, 5), usd(10.0));
+    ledger.add_transaction(paid);
+
+    let window = DateWindow::new(date(3, 1), date(5, 1)).unwrap();
+    let events = ExportService::forecast_events(&ledger, window, None, date(3, 1)).unwrap();
+    let dates: Vec<_> = events.iter().map(|event| event.date).collect();
+    assert_eq!(dates, vec![date(3, 1), date(3, 20), date(4, 1)]);
+    assert_eq!(events[1].summary, "Acme Insurance; annual: $120.50");
+    let again = ExportService::forecast_events(&ledger, window, None, date(3, 1)).unwrap();
+    assert_eq!(events[2].uid, again[2].uid, "projected UIDs are stable");
+
+    let clock = FixedClock(chrono::Utc::now());
+    let ics = ExportService::events_ics(&ledger, &events, &clock);
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 3);
+    assert!(ics.contains("X-WR-CALNAME:Bills\\, 2025\r\n"));
+    assert!(ics.contains("DTSTART;VALUE=DATE:20250320\r\nDTEND;VALUE=DATE:20250321\r\n"));
+    assert!(ics.contains("SUMMARY:Acme Insurance\\; annual: $120.50\r\n"));
+    assert!(ics.lines().all(|line| line.len() <= 76));
+}
+
+#[test]
+fn forecast_balances_project_each_account_day_by_day() {
+    use crate::forecast_service::ForecastService;
+    use bufy_domain::DateWindow;
+
+    let mut ledger = LedgerService::create("Balances", LedgerBudgetPeriod::monthly());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(1000.0));
+    let checking = ledger.add_account(checking);
+    let savings = ledger.add_account(Account::new("Savings", AccountKind::Savings));
+    let landlord = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
+    let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let mut rent = Transaction::new(checking, landlord, None, date(3, 25), usd(700.0));
+    rent.set_recurrence(Some(Recurrence::new(
+        date(3, 25),
+        TimeInterval {
+            every: 1,
+            unit: TimeUnit::Month,
+        },
+        RecurrenceMode::FixedSchedule,
+    )));
+    ledger.add_transaction(rent);
+    let mut paid = Transaction::new(checking, landlord, None, date(3, 2), usd(50.0));
+    paid.mark_completed(date(3, 2), usd(50.0));
+    ledger.add_transaction(paid);
+    ledger.add_transaction(Transaction::new(
+        checking,
+        savings,
+        None,
+        date(4, 1),
+        usd(100.0),
+    ));
+
+    let window = DateWindow::new(date(3, 10), date(5, 1)).unwrap();
+    let forecast = ForecastService::balances(&ledger, window, date(3, 10), None).unwrap();
+    assert_eq!(forecast.accounts.len(), 2, "only money-holding accounts");
+    let checking = &forecast.accounts[0];
+    assert_eq!(checking.opening_balance, 950.0);
+    assert_eq!(
+        checking.points,
+        vec![
+            (date(3, 10), 950.0),
+            (date(3, 25), 250.0),
+            (date(4, 1), 150.0),
+            (date(4, 25), -550.0),
+        ]
+    );
+    assert_eq!(checking.closing_balance, -550.0);
+    assert_eq!(checking.lowest(), (date(4, 25), -550.0));
+    assert_eq!(checking.first_below(200.0), Some((date(4, 1), 150.0)));
+    let savings = &forecast.accounts[1];
+    assert_eq!(savings.closing_balance, 100.0);
+    assert_eq!(savings.first_below(0.0), None);
+}
+
+#[test]
+fn exchange_rate_overrides_convert_within_their_validity_range() {
+    use crate::exchange_rate_service::ExchangeRateService;
+    use bufy_domain::{BudgetScope, CurrencyCode, DateWindow, ExchangeRateOverride, Ledger};
+
+    let mut ledger = Ledger::new("Rates", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let store = ledger.add_account(Account::new("Store", AccountKind::ExpenseDestination));
+    let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let now = chrono::Utc::now();
+    let contract = ExchangeRateOverride::new(
+        CurrencyCode::new("EUR"),
+        CurrencyCode::new("USD"),
+        1.1,
+        date(1, 1),
+        Some(date(3, 31)),
+        now,
+    );
+    ExchangeRateService::add_override(&mut ledger, contract).expect("contract rate");
+
+    let inverse_overlap = ExchangeRateOverride::new(
+        CurrencyCode::new("USD"),
+        CurrencyCode::new("EUR"),
+        0.9,
+        date(3, 1),
+        None,
+        now,
+    );
+    let err = ExchangeRateService::add_override(&mut ledger, inverse_overlap).unwrap_err();
+    assert!(err.to_string().contains("overlaps the EUR → USD rate"));
+    let bad_range = ExchangeRateOverride::new(
+        CurrencyCode::new("GBP"),
+        CurrencyCode::new("USD"),
+        1.25,
+        date(2, 1),
+        Some(date(1, 1)),
+        now,
+    );
+    assert!(ExchangeRateService::add_override(&mut ledger, bad_range).is_err());
+    let later = ExchangeRateOverride::new(
+        CurrencyCode::new("USD"),
+        CurrencyCode::new("EUR"),
+        0.8,
+        date(4, 1),
+        None,
+        now,
+    );
+    ExchangeRateService::add_override(&mut ledger, later).expect("adjacent range");
+
+    for (month, amount) in [(2, 100.0), (4, 100.0), (5, 40.0)] {
+        let mut txn = Transaction::new(checking, store, None, date(month, 10), usd(amount));
+        txn.currency = Some("EUR".into());
+        txn.mark_completed(date(month, 10), usd(amount));
+        ledger.add_transaction(txn);
+    }
+
+    let february = DateWindow::new(date(2, 1), date(3, 1)).unwrap();
+    let summary = SummaryService::summarize_window(&ledger, february, BudgetScope::Past);
+    assert!((summary.totals.real - 110.0).abs() < 1e-9);
+    assert!(
+        summary
+            .disclosures
+            .iter()
+            .any(|line| line.contains("EUR → USD @ 1.100000")
+                && line.contains("ledger rate override"))
+    );
+
+    let april = DateWindow::new(date(4, 1), date(5, 1)).unwrap();
+    let summary = SummaryService::summarize_window(&ledger, april, BudgetScope::Past);
+    assert!((summary.totals.real - 125.0).abs() < 1e-9, "inverse of 0.8");
+
+    let without = DateWindow::new(date(1, 1), date(6, 1)).unwrap();
+    ledger.exchange_rate_overrides.clear();
+    let summary = SummaryService::summarize_window(&ledger, without, BudgetScope::Past);
+    assert!(summary.totals.incomplete);
+}
+
+#[test]
+fn summaries_revalue_foreign_currency_balances_at_the_report_date() {
+    use crate::exchange_rate_service::ExchangeRateService;
+    use bufy_domain::{BudgetScope, CurrencyCode, DateWindow, ExchangeRateOverride, Ledger};
+
+    let mut ledger = Ledger::new("Revaluation", LedgerBudgetPeriod::monthly());
+    let mut wallet = Account::new("Euro wallet", AccountKind::Bank);
+    wallet.currency = Some("EUR".into());
+    let wallet = ledger.add_account(wallet);
+    let salary = ledger.add_account(Account::new("Salary", AccountKind::IncomeSource));
+    let store = ledger.add_account(Account::new("Store", AccountKind::ExpenseDestination));
+    let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let now = chrono::Utc::now();
+    for (rate, from, until) in [
+        (1.1, date(1, 1), Some(date(3, 15))),
+        (1.2, date(3, 16), None),
+    ] {
+        let rule = ExchangeRateOverride::new(
+            CurrencyCode::new("EUR"),
+            CurrencyCode::new("USD"),
+            rate,
+            from,
+            until,
+            now,
+        );
+        ExchangeRateService::add_override(&mut ledger, rule).expect("rate");
+    }
+    let eur = |amount| Money::from_major(amount, CurrencyCode::new("EUR")).unwrap();
+    for (from, to, day, amount) in [(salary, wallet, 10, 100.0), (wallet, store, 20, 40.0)] {
+        let mut txn = Transaction::new(from, to, None, date(3, day), eur(amount));
+        txn.mark_completed(date(3, day), eur(amount));
+        ledger.add_transaction(txn);
+    }
+
+    // 100 EUR came in at 1.1 and 40 left at 1.2; the 60 left are worth 72.
+    let march = DateWindow::new(date(3, 1), date(4, 1)).unwrap();
+    let summary = SummaryService::summarize_window(&ledger, march, BudgetScope::Past);
+    assert_eq!(summary.fx_revaluation, Some(10.0));
+
+    // Carried into April at 1.2 and still worth that.
+    let april = DateWindow::new(date(4, 1), date(5, 1)).unwrap();
+    let summary = SummaryService::summarize_window(&ledger, april, BudgetScope::Past);
+    assert_eq!(summary.fx_revaluation, Some(0.0));
+
+    ledger.exchange_rate_overrides.clear();
+    let summary = SummaryService::summarize_window(&ledger, march, BudgetScope::Past);
+    assert_eq!(summary.fx_revaluation, None);
+    assert!(summary
+        .disclosures
+        .iter()
+        .any(|line| line.starts_with("Euro wallet FX revaluation skipped")));
+
+    let mut home = Ledger::new("Home", LedgerBudgetPeriod::monthly());
+    home.add_account(Account::new("Checking", AccountKind::Bank));
+    let summary = SummaryService::summarize_window(&home, march, BudgetScope::Past);
+    assert_eq!(summary.fx_revaluation, None);
+}
+
+#[test]
+fn retention_purge_folds_old_flows_into_opening_balances() {
+    use crate::retention_service::RetentionService;
+
+    let mut ledger = LedgerService::create("Retention", LedgerBudgetPeriod::monthly());
+    let mut checking = Account::new("Checking", AccountKind::Bank);
+    checking.opening_balance = Some(usd(1000.0));
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let store = Account::new("Store", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    AccountService::add(&mut ledger, store).expect("add store");
+
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let mut add = |scheduled: NaiveDate, amount: f64, completed: bool| {
+        let mut txn = Transaction::new(checking_id, store_id, None, scheduled, usd(amount));
+        if completed {
+            txn.mark_completed(scheduled, usd(amount));
+        }
+        TransactionService::add(&mut ledger, txn).expect("add transaction")
+    };
+    add(date(2023, 5, 2), 100.0, true);
+    add(date(2023, 6, 9), 40.0, false);
+    let kept = add(date(2024, 2, 1), 25.0, true);
+
+    let monthly = TimeInterval {
+        every: 1,
+        unit: TimeUnit::Month,
+    };
+    let rent = Transaction::new(checking_id, store_id, None, date(2023, 1, 1), usd(500.0))
+        .with_recurrence(Recurrence::new(
+            date(2023, 1, 1),
+            monthly,
+            RecurrenceMode::FixedSchedule,
+        ));
+    let template_id = TransactionService::add(&mut ledger, rent).expect("add rent");
+    let mut paid = Transaction::new(checking_id, store_id, None, date(2023, 1, 1), usd(500.0));
+    paid.recurrence_series_id = ledger.transaction(template_id).unwrap().recurrence_series();
+    paid.mark_completed(date(2023, 1, 1), usd(500.0));
+    TransactionService::add(&mut ledger, paid).expect("add rent payment");
+
+    let today = date(2024, 3, 1);
+    let balance_before = AccountService::stats(&ledger, checking_id, today)
+        .expect("stats")
+        .balance;
+    let cutoff = date(2024, 1, 1);
+    let preview = RetentionService::preview(&ledger, cutoff);
+    assert_eq!(preview.transactions, 2);
+    assert_eq!(preview.recurring_kept, 2);
+    assert_eq!(
+        ledger.transactions.len(),
+        5,
+        "preview leaves the ledger alone"
+    );
+
+    let report = RetentionService::purge(&mut ledger, cutoff, None).expect("purge");
+    assert_eq!(report, preview);
+    assert_eq!(
+        report.opening_adjustments,
+        vec![(store_id, 100.0), (checking_id, -100.0)]
+    );
+    assert_eq!(ledger.transactions.len(), 3);
+    assert!(ledger.transaction(kept).is_some());
+    assert!(ledger.transaction(template_id).is_some());
+    let checking = ledger.account(checking_id).unwrap();
+    assert_eq!(checking.opening_balance, Some(usd(900.0)));
+    let balance_after = AccountService::stats(&ledger, checking_id, today)
+        .expect("stats")
+        .balance;
+    assert_eq!(balance_after, balance_before);
+    assert_eq!(
+        ledger.account(store_id).unwrap().opening_balance,
+        Some(usd(100.0)),
+        "expense destinations keep their running totals too"
+    );
+    assert!(RetentionService::purge(&mut ledger, cutoff, None)
+        .expect("purge")
+        .is_empty());
+}
+
+#[test]
+fn retention_purge_drops_simulation_changes_assets_and_review_marks_of_removed_transactions() {
+    use crate::{retention_service::RetentionService, simulation_service::SimulationService};
+    use bufy_domain::{
+        review::BudgetReview,
+        simulation::{AppliedChangeSet, SimulationChange, TransactionRevert},
+        transaction::AssetRecord,
+    };
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Erasure", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let store = Account::new("Store", AccountKind::ExpenseDestination);
+    let store_id = store.id;
+    AccountService::add(&mut ledger, store).expect("add store");
+
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let mut old = Transaction::new(checking_id, store_id, None, date(2023, 3, 1), usd(800.0));
+    old.mark_completed(date(2023, 3, 1), usd(800.0));
+    old.asset = Some(AssetRecord {
+        name: "Sofa".into(),
+        warranty_until: None,
+        lifetime_months: Some(60),
+    });
+    let old_id = TransactionService::add(&mut ledger, old).expect("add old purchase");
+    let kept_id = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, store_id, None, date(2024, 2, 1), usd(30.0)),
+    )
+    .expect("add kept");
+
+    SimulationService::create(&mut ledger, "Trim", None, &clock).expect("create");
+    let removed_copy = ledger.transaction(old_id).cloned();
+    let simulation = ledger.simulation_mut("Trim").unwrap();
+    simulation.changes = vec![
+        SimulationChange::ExcludeTransaction {
+            transaction_id: old_id,
+        },
+        SimulationChange::ExcludeTransaction {
+            transaction_id: kept_id,
+        },
+    ];
+    simulation.applied.push(AppliedChangeSet {
+        applied_at: clock.0,
+        changes: vec![SimulationChange::ExcludeTransaction {
+            transaction_id: old_id,
+        }],
+        transactions: vec![TransactionRevert {
+            before: removed_copy,
+            after: None,
+        }],
+    });
+    let mut review = BudgetReview::new(clock.0);
+    review.handled = vec![old_id, kept_id];
+    ledger.reviews.push(review);
+
+    let cutoff = date(2024, 1, 1);
+    let preview = RetentionService::preview(&ledger, cutoff);
+    assert_eq!(preview.assets, 1);
+    assert_eq!(preview.simulation_changes, 1);
+
+    let report = RetentionService::purge(&mut ledger, cutoff, None).expect("purge");
+    assert_eq!(report, preview);
+    let simulation = ledger.simulation("Trim").unwrap();
+    assert_eq!(simulation.changes.len(), 1);
+    assert_eq!(simulation.changes[0].target_transaction(), Some(kept_id));
+    assert!(simulation.applied[0].changes.is_empty());
+    assert!(
+        simulation.applied[0].transactions.is_empty(),
+        "undo records must not bring purged transactions back"
+    );
+    assert_eq!(ledger.reviews[0].handled, vec![kept_id]);
+    assert_eq!(
+        ledger.account(store_id).unwrap().opening_balance,
+        Some(usd(800.0))
+    );
+}
+
+#[test]
+fn simulation_changes_can_be_applied_selectively() {
+    use crate::simulation_service::SimulationService;
+    use bufy_domain::simulation::{SimulationChange, SimulationStatus, SimulationTransactionPatch};
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Cherry-pick", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let gym = Account::new("Gym", AccountKind::ExpenseDestination);
+    let gym_id = gym.id;
+    AccountService::add(&mut ledger, gym).expect("add gym");
+    let day = NaiveDate::from_ymd_opt(2025, 5, 1).unwrap();
+    let membership = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, gym_id, None, day, usd(40.0)),
+    )
+    .expect("add membership");
+    let classes = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, gym_id, None, day, usd(15.0)),
+    )
+    .expect("add classes");
+
+    SimulationService::create(&mut ledger, "Cheaper gym", None, &clock).expect("create");
+    let patch = |transaction_id, amount| {
+        SimulationChange::ModifyTransaction(SimulationTransactionPatch {
+            transaction_id,
+            from_account: None,
+            to_account: None,
+            category_id: None,
+            scheduled_date: None,
+            actual_date: None,
+            budgeted_amount: Some(amount),
+            actual_amount: None,
+        })
+    };
+    let pool = Transaction::new(checking_id, gym_id, None, day, usd(10.0));
+    let pool_id = pool.id;
+    let simulation = ledger.simulation_mut("Cheaper gym").unwrap();
+    simulation.changes = vec![
+        SimulationChange::AddTransaction { transaction: pool },
+        patch(pool_id, 12.0),
+        SimulationChange::ExcludeTransaction {
+            transaction_id: classes,
+        },
+        patch(membership, 30.0),
+    ];
+
+    let err = SimulationService::apply_changes(&mut ledger, "Cheaper gym", &[1], &clock)
+        .expect_err("needs the change adding the pool pass");
+    assert!(err.to_string().contains("added by change 0"));
+    assert!(SimulationService::apply_changes(&mut ledger, "Cheaper gym", &[7], &clock).is_err());
+
+    let applied = SimulationService::apply_changes(&mut ledger, "Cheaper gym", &[2, 3], &clock)
+        .expect("apply two changes");
+    assert_eq!(applied.len(), 2);
+    assert!(ledger.transaction(classes).is_none());
+    assert_eq!(
+        ledger.transaction(membership).unwrap().budgeted_amount,
+        usd(30.0)
+    );
+    let simulation = ledger.simulation("Cheaper gym").unwrap();
+    assert_eq!(simulation.status, SimulationStatus::Pending);
+    assert_eq!(simulation.changes.len(), 2);
+
+    SimulationService::apply_changes(&mut ledger, "Cheaper gym", &[1, 0], &clock)
+        .expect("apply the rest");
+    assert_eq!(
+        ledger.transaction(pool_id).unwrap().budgeted_amount,
+        usd(12.0)
+    );
+    let simulation = ledger.simulation("Cheaper gym").unwrap();
+    assert_eq!(simulation.status, SimulationStatus::Applied);
+    assert!(simulation.applied_at.is_some());
+}
+
+#[test]
+fn branched_simulation_previews_parent_changes_first() {
+    use crate::simulation_service::SimulationService;
+    use bufy_domain::simulation::{SimulationChange, SimulationTransactionPatch};
+
+    let clock = FixedClock(chrono::Utc::now());
+    let mut ledger = LedgerService::create("Branches", LedgerBudgetPeriod::monthly());
+    let checking = Account::new("Checking", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).expect("add checking");
+    let landlord = Account::new("Landlord", AccountKind::ExpenseDestination);
+    let landlord_id = landlord.id;
+    AccountService::add(&mut ledger, landlord).expect("add landlord");
+    let day = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+    SimulationService::create(&mut ledger, "Move", None, &clock).expect("create");
+    let rent = Transaction::new(checking_id, landlord_id, None, day, usd(900.0));
+    SimulationService::add_transaction(&mut ledger, "Move", rent).expect("add rent");
+    let rent_id = match &SimulationService::changes(&ledger, "Move").unwrap()[0] {
+        SimulationChange::AddTransaction { transaction } => transaction.id,
+        other => panic!("unexpected change {:?}", other),
+    };
+    SimulationService::branch(&mut ledger, "Move", "Move, cheaper", None, &clock).expect("branch");
+    ledger
+        .simulation_mut("Move, cheaper")
+        .unwrap()
+        .changes
+        .push(SimulationChange::ModifyTransaction(
+            SimulationTransactionPatch {
+                transaction_id: rent_id,
+                from_account: None,
+                to_account: None,
+                category_id: None,
+                scheduled_date: None,
+                actual_date: None,
+                budgeted_amount: Some(750.0),
+                actual_amount: None,
+            },
+        ));
+
+    assert_eq!(
+        SimulationService::change_chain(&ledger, "Move, cheaper")
+            .unwrap()
+            .len(),
+        2
+    );
+    assert!(
+        SimulationService::orphaned_changes(&ledger, "Move, cheaper")
+            .unwrap()
+            .is_empty()
+    );
+    let preview = SimulationService::run(&ledger, "Move, cheaper").expect("run branch");
+    assert_eq!(
+        preview.transaction(rent_id).unwrap().budgeted_amount,
+        usd(750.0)
+    );
+    let parent = SimulationService::run(&ledger, "Move").expect("run parent");
+    assert_eq!(
+        parent.transaction(rent_id).unwrap().budgeted_amount,
+        usd(900.0)
+    );
+
+    let err = SimulationService::apply(&mut ledger, "Move, cheaper", &clock)
+        .expect_err("parent still pending");
+    assert!(err.to_string().contains("apply `Move` first"));
+    assert!(SimulationService::discard(&mut ledger, "Move").is_err());
+
+    SimulationService::apply(&mut ledger, "Move", &clock).expect("apply parent");
+    assert_eq!(
+        SimulationService::change_chain(&ledger, "Move, cheaper")
+            .unwrap()
+            .len(),
+        1
+    );
+    SimulationService::apply(&mut ledger, "Move, cheaper", &clock).expect("apply branch");
+    assert_eq!(
+        ledger.transaction(rent_id).unwrap().budgeted_amount,
+        usd(750.0)
+    );
+}
+
+mod extreme_amounts {
+    use chrono::NaiveDate;
+    use proptest::prelude::*;
+
+    use super::usd;
+    use crate::{
+        account_service::AccountService, budget_service::BudgetService,
+        forecast_service::ForecastService, ledger_service::LedgerService, CoreError,
+    };
+    use bufy_domain::{
+        account::{Account, AccountKind},
+        amount::{checked_sum, is_exact_amount, MAX_EXACT_AMOUNT},
+        common::{TimeInterval, TimeUnit},
+        ledger::{BudgetScope, BudgetTotals, DateWindow},
+        transaction::{Recurrence, RecurrenceMode},
+        Ledger, LedgerBudgetPeriod, Transaction,
+    };
+
+    fn ledger_with_budgeted(amounts: &[f64]) -> (Ledger, DateWindow) {
+        let mut ledger = LedgerService::create("Extremes", LedgerBudgetPeriod::monthly());
+        let checking = Account::new("Checking", AccountKind::Bank);
+        let checking_id = checking.id;
+        AccountService::add(&mut ledger, checking).expect("add checking");
+        let shop = Account::new("Shop", AccountKind::ExpenseDestination);
+        let shop_id = shop.id;
+        AccountService::add(&mut ledger, shop).expect("add shop");
+        let day = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        for amount in amounts {
+            ledger.add_transaction(Transaction::new(
+                checking_id,
+                shop_id,
+                None,
+                day,
+                usd(*amount),
+            ));
+        }
+        (
+            ledger,
+            DateWindow::new(day, day.succ_opt().unwrap()).unwrap(),
+        )
+    }
+
+    fn beyond_exact_range() -> impl Strategy<Value = f64> {
+        prop_oneof![
+            (MAX_EXACT_AMOUNT * 1.01)..f64::MAX,
+            -f64::MAX..-(MAX_EXACT_AMOUNT * 1.01),
+            Just(f64::INFINITY),
+            Just(f64::NEG_INFINITY),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn everyday_amounts_sum_exactly(
+            amounts in prop::collection::vec(-1.0e12..1.0e12f64, 0..40),
+        ) {
+            let (ledger, window) = ledger_with_budgeted(&amounts);
+            let summary =
+                BudgetService::summarize_window_checked(&ledger, window, BudgetScope::Custom)
+                    .unwrap();
+            // Each amount counts as whole cents; the sum itself adds no error.
+            let cents: i64 = amounts.iter().map(|amount| (amount * 100.0).round() as i64).sum();
+            prop_assert_eq!(summary.totals.budgeted, cents as f64 / 100.0);
+        }
+
+        #[test]
+        fn totals_beyond_the_exact_range_are_reported(
+            mut amounts in prop::collection::vec(-1.0e6..1.0e6f64, 0..10),
+            large in (MAX_EXACT_AMOUNT * 0.6)..MAX_EXACT_AMOUNT,
+            sign in prop_oneof![Just(1.0), Just(-1.0)],
+            positions in any::<[prop::sample::Index; 2]>(),
+        ) {
+            for position in positions {
+                amounts.insert(position.index(amounts.len() + 1), sign * large);
+            }
+            let (ledger, window) = ledger_with_budgeted(&amounts);
+            let checked =
+                BudgetService::summarize_window_checked(&ledger, window, BudgetScope::Custom);
+            prop_assert!(matches!(checked, Err(CoreError::NumericOverflow(_))));
+            let summary =
+                BudgetService::summarize_window_scope(&ledger, window, BudgetScope::Custom);
+            prop_assert!(summary
+                .disclosures
+                .iter()
+                .any(|line| line.starts_with("Totals are approximate")));
+        }
+
+        #[test]
+        fn checked_sum_fails_once_a_running_total_leaves_the_range(
+            amounts in prop::collection::vec(
+                prop_oneof![4 => -1.0e14..1.0e14f64, 1 => beyond_exact_range()],
+                0..20,
+            ),
+        ) {
+            let mut running = 0.0;
+            let stays_exact = amounts.iter().all(|amount| {
+                running += amount;
+                is_exact_amount(running)
+            });
+            prop_assert_eq!(checked_sum("test", amounts.iter().copied()).is_ok(), stays_exact);
+        }
+    }
+
+    #[test]
+    fn derived_totals_are_checked_too() {
+        assert!(BudgetTotals::checked(MAX_EXACT_AMOUNT, 0.0, false).is_ok());
+        let err = BudgetTotals::checked(MAX_EXACT_AMOUNT, -MAX_EXACT_AMOUNT, false)
+            .expect_err("remaining doubles the budget");
+        assert_eq!(err.total, "remaining");
+    }
+
+    #[test]
+    fn decades_of_large_recurring_amounts_fail_the_forecast() {
+        let (mut ledger, _) = ledger_with_budgeted(&[]);
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let (from, to) = (ledger.accounts[0].id, ledger.accounts[1].id);
+        let mut transfer = Transaction::new(from, to, None, start, usd(5.0e11));
+        transfer.set_recurrence(Some(Recurrence::new(
+            start,
+            TimeInterval {
+                every: 1,
+                unit: TimeUnit::Month,
+            },
+            RecurrenceMode::FixedSchedule,
+        )));
+        ledger.add_transaction(transfer);
+
+        let decade = DateWindow::new(start, NaiveDate::from_ymd_opt(2035, 1, 1).unwrap()).unwrap();
+        assert!(ForecastService::window_report(&ledger, decade, start, None).is_ok());
+        let century = DateWindow::new(start, NaiveDate::from_ymd_opt(2125, 1, 1).unwrap()).unwrap();
+        let err = ForecastService::window_report(&ledger, century, start, None)
+            .expect_err("1,200 months of 500 billion");
+        assert!(matches!(err, CoreError::NumericOverflow(ref message)
+            if message.starts_with("projected outflow total")));
+    }
+}
+
+#[test]
+fn holiday_calendars_import_from_ics_and_refresh_by_year() {
+    use crate::{holiday_service::HolidayService, CoreError};
+
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let mut ledger = LedgerService::create("Holidays", LedgerBudgetPeriod::monthly());
+    let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+        BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20240501\r\nDTEND;VALUE=DATE:20240502\r\n\
+        RRULE:FREQ=YEARLY\r\nSUMMARY:Labour\r\n  Day\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250818\r\nDTEND;VALUE=DATE:20250820\r\n\
+        SUMMARY:Company shutdown\\, summer\r\nEND:VEVENT\r\n\
+        BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250106\r\nRRULE:FREQ=WEEKLY\r\n\
+        SUMMARY:Team lunch\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let report =
+        HolidayService::import_ics(&mut ledger, "/tmp/work.ics", ics, date(2025, 6, 1)).unwrap();
+    assert_eq!(report.skipped, 1, "weekly events are not holidays");
+    assert_eq!(report.through_year, 2026);
+    let names: Vec<_> = ledger
+        .holidays
+        .holidays
+        .iter()
+        .map(|holiday| (holiday.date, holiday.name.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            (date(2024, 5, 1), "Labour Day"),
+            (date(2025, 5, 1), "Labour Day"),
+            (date(2025, 8, 18), "Company shutdown, summer"),
+            (date(2025, 8, 19), "Company shutdown, summer"),
+            (date(2026, 5, 1), "Labour Day"),
+        ]
+    );
+    assert!(matches!(
+        HolidayService::refresh(&mut ledger, None, date(2026, 6, 1)),
+        Err(CoreError::InvalidOperation(_))
+    ));
+    assert!(HolidayService::import_ics(&mut ledger, "x", "SUMMARY:x", date(2025, 1, 1)).is_err());
+
+    HolidayService::import_country(&mut ledger, "PT", date(2025, 6, 1)).unwrap();
+    assert!(!ledger.holidays.needs_refresh(date(2025, 12, 31)));
+    assert!(ledger.holidays.needs_refresh(date(2026, 1, 2)));
+    let report = HolidayService::refresh(&mut ledger, None, date(2026, 1, 2)).unwrap();
+    assert_eq!(report.through_year, 2027);
+    let years: Vec<i32> = [2024, 2025, 2026, 2027]
+        .into_iter()
+        .filter(|year| ledger.holidays.is_holiday(date(*year, 6, 10)))
+        .collect();
+    assert_eq!(years, vec![2024, 2025, 2026, 2027]);
+    assert!(matches!(
+        HolidayService::import_country(&mut ledger, "XX", date(2025, 6, 1)),
+        Err(CoreError::Validation(_))
+    ));
+    assert!(HolidayService::clear(&mut ledger));
+    assert!(ledger.holidays.is_empty());
+}
+
+#[test]
+fn favorite_categories_pin_to_the_top_and_moves_skip_hidden_ones() {
+    use bufy_domain::{category::SystemCategory, MoveDirection};
+
+    let mut ledger = LedgerService::create("Order", LedgerBudgetPeriod::monthly());
+    let food = Category::new("Food", CategoryKind::Expense);
+    let food_id = food.id;
+    CategoryService::add(&mut ledger, food).unwrap();
+    CategoryService::ensure_system(&mut ledger, SystemCategory::FxAdjustment);
+    let rent = Category::new("Rent", CategoryKind::Expense);
+    let rent_id = rent.id;
+    CategoryService::add(&mut ledger, rent).unwrap();
+    let names = |ledger: &bufy_domain::Ledger| -> Vec<String> {
+        CategoryService::visible(ledger)
+            .map(|category| category.name.clone())
+            .collect()
+    };
+
+    let before = ledger.updated_at;
+    assert_eq!(
+        CategoryService::reorder(&mut ledger, food_id, MoveDirection::Up).unwrap(),
+        0
+    );
+    assert_eq!(
+        ledger.updated_at, before,
+        "no-op moves leave the ledger clean"
+    );
+    assert_eq!(
+        CategoryService::reorder(&mut ledger, rent_id, MoveDirection::Up).unwrap(),
+        0
+    );
+    assert_eq!(names(&ledger), ["Rent", "Food"]);
+
+    assert_eq!(
+        CategoryService::set_favorite(&mut ledger, food_id, true).unwrap(),
+        0
+    );
+    assert!(ledger.category(food_id).unwrap().favorite);
+    assert_eq!(names(&ledger), ["Food", "Rent"]);
+    assert_eq!(
+        CategoryService::reorder(&mut ledger, rent_id, MoveDirection::Top).unwrap(),
+        1,
+        "the rest stays below the favorites"
+    );
+
+    let account = Account::new("Wallet", AccountKind::Cash);
+    let wallet_id = account.id;
+    AccountService::add(&mut ledger, Account::new("Bank", AccountKind::Bank)).unwrap();
+    AccountService::add(&mut ledger, account).unwrap();
+    assert_eq!(
+        AccountService::set_favorite(&mut ledger, wallet_id, true).unwrap(),
+        0
+    );
+    assert!(matches!(
+        AccountService::reorder(&mut ledger, uuid::Uuid::nil(), MoveDirection::Down),
+        Err(crate::CoreError::AccountNotFound(_))
+    ));
+}
+
+#[test]
+fn appearance_validates_icons_and_clears_when_empty() {
+    let mut ledger = LedgerService::create("Styles", LedgerBudgetPeriod::monthly());
+    let account_id = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let category_id = ledger.add_category(Category::new("Food", CategoryKind::Expense));
+
+    AccountService::set_appearance(
+        &mut ledger,
+        account_id,
+        Appearance {
+            color: Some(EntityColor::Blue),
+            icon: Some(" 🏦 ".into()),
+        },
+    )
+    .unwrap();
+    let account = ledger.account(account_id).unwrap();
+    assert_eq!(account.appearance.icon.as_deref(), Some("🏦"));
+    assert_eq!(EntityColor::parse("BLUE"), Some(EntityColor::Blue));
+
+    let err = CategoryService::set_appearance(
+        &mut ledger,
+        category_id,
+        Appearance {
+            color: None,
+            icon: Some("far too long".into()),
+        },
+    )
+    .unwrap_err();
+    assert!(matches!(err, CoreError::Invalid(_)));
+    CategoryService::set_appearance(
+        &mut ledger,
+        category_id,
+        Appearance {
+            color: Some(EntityColor::Green),
+            icon: Some("  ".into()),
+        },
+    )
+    .unwrap();
+    assert_eq!(ledger.category(category_id).unwrap().appearance.icon, None);
+
+    AccountService::set_appearance(&mut ledger, account_id, Appearance::default()).unwrap();
+    let json = serde_json::to_string(ledger.account(account_id).unwrap()).unwrap();
+    assert!(!json.contains("appearance"));
+}
+
+#[test]
+fn allocation_plan_detects_income_and_writes_budgets_together() {
+    use crate::allocation_service::AllocationService;
+
+    let mut ledger = LedgerService::create("Zero based", LedgerBudgetPeriod::monthly());
+    let employer =
+        crate::api_add_account(&mut ledger, "Employer", AccountKind::IncomeSource, None).unwrap();
+    let checking =
+        crate::api_add_account(&mut ledger, "Checking", AccountKind::Bank, None).unwrap();
+    let shop =
+        crate::api_add_account(&mut ledger, "Shop", AccountKind::ExpenseDestination, None).unwrap();
+    let food = crate::api_add_category(&mut ledger, "Food", CategoryKind::Expense, None).unwrap();
+    let rent = crate::api_add_category(&mut ledger, "Rent", CategoryKind::Expense, None).unwrap();
+    CategoryService::set_budget(&mut ledger, rent, 900.0, BudgetPeriod::Monthly, None).unwrap();
+
+    let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    for (month, pay) in [(1, 2_000.0), (2, 2_000.0), (3, 2_600.0)] {
+        let paid = crate::api_add_transaction(
+            &mut ledger,
+            employer,
+            checking,
+            None,
+            date(month, 1),
+            pay,
+            None,
+        )
+        .unwrap();
+        TransactionService::update(&mut ledger, paid, |txn| {
+            txn.mark_completed(date(month, 1), usd(pay))
+        })
+        .unwrap();
+        let spent = crate::api_add_transaction(
+            &mut ledger,
+            checking,
+            shop,
+            Some(food),
+            date(month, 10),
+            150.0,
+            None,
+        )
+        .unwrap();
+        TransactionService::update(&mut ledger, spent, |txn| {
+            txn.mark_completed(date(month, 10), usd(150.0))
+        })
+        .unwrap();
+    }
+
+    let plan = AllocationService::plan(&ledger, date(4, 15));
+    assert_eq!(plan.window.start, date(4, 1));
+    let received: Vec<_> = plan
+        .recent_income
+        .iter()
+        .map(|period| period.amount)
+        .collect();
+    assert_eq!(received, [2_000.0, 2_000.0, 2_600.0]);
+    assert_eq!(plan.income, 2_200.0);
+    assert_eq!(plan.lines.len(), 2);
+    assert_eq!(plan.lines[0].name, "Food");
+    assert_eq!(plan.lines[0].allocated, 0.0);
+    assert_eq!(plan.lines[0].average_spend, 150.0);
+    assert_eq!(plan.unassigned(), 1_300.0);
+
+    assert!(
+        AllocationService::apply(&mut ledger, date(4, 15), &[(food, 300.0), (rent, -1.0)]).is_err()
+    );
+    assert!(
+        ledger.category(food).unwrap().budget.is_none(),
+        "a rejected allocation writes nothing"
+    );
+
+    let before = ledger.updated_at;
+    assert_eq!(
+        AllocationService::apply(&mut ledger, date(4, 15), &[(food, 300.0), (rent, 900.0)])
+            .unwrap(),
+        1,
+        "rent is unchanged"
+    );
+    assert!(ledger.updated_at > before);
+    let budget = ledger.category(food).unwrap().budget.clone().unwrap();
+    assert_eq!(budget.period, BudgetPeriod::Monthly);
+    assert_eq!(
+        AllocationService::plan(&ledger, date(4, 15)).unassigned(),
+        1_000.0
+    );
+}
+
+#[test]
+fn services_reject_invalid_input_with_typed_issues() {
+    let mut ledger = LedgerService::create("Validation", LedgerBudgetPeriod::monthly());
+    let issue = |err: CoreError| match err {
+        CoreError::Invalid(issue) => (issue.field, issue.kind),
+        other => panic!("expected a validation issue, got {other:?}"),
+    };
+
+    let err = AccountService::add(&mut ledger, Account::new("  ", AccountKind::Bank)).unwrap_err();
+    assert_eq!(issue(err), ("account name", ValidationIssueKind::Empty));
+    let long = "x".repeat(MAX_NAME_LENGTH + 1);
+    let err =
+        CategoryService::add(&mut ledger, Category::new(&long, CategoryKind::Expense)).unwrap_err();
+    assert_eq!(
+        issue(err),
+        (
+            "category name",
+            ValidationIssueKind::TooLong {
+                max: MAX_NAME_LENGTH
+            }
+        )
+    );
+
+    let checking = Account::new(" Checking ", AccountKind::Bank);
+    let checking_id = checking.id;
+    AccountService::add(&mut ledger, checking).unwrap();
+    assert_eq!(ledger.account(checking_id).unwrap().name, "Checking");
+    let err = AccountService::add(&mut ledger, Account::new("checking", AccountKind::Savings))
+        .unwrap_err();
+    assert_eq!(
+        issue(err),
+        (
+            "account name",
+            ValidationIssueKind::Duplicate {
+                value: "checking".into()
+            }
+        )
+    );
+
+    let groceries = Account::new("Groceries", AccountKind::ExpenseDestination);
+    let groceries_id = groceries.id;
+    AccountService::add(&mut ledger, groceries).unwrap();
+    let today = ledger.created_at.date_naive();
+    let err = crate::api_add_transaction(
+        &mut ledger,
+        checking_id,
+        groceries_id,
+        None,
+        today,
+        f64::NAN,
+        None,
+    )
+    .unwrap_err();
+    assert_eq!(issue(err), ("amount", ValidationIssueKind::NotANumber));
+    let typo = NaiveDate::from_ymd_opt(202, 3, 5).unwrap();
+    let err = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, groceries_id, None, typo, usd(10.0)),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        issue(err),
+        ("date", ValidationIssueKind::DateOutOfRange { .. })
+    ));
+    let err = TransactionService::add(
+        &mut ledger,
+        Transaction::new(checking_id, uuid::Uuid::new_v4(), None, today, usd(10.0)),
+    )
+    .unwrap_err();
+    assert!(matches!(err, CoreError::AccountNotFound(_)));
+    assert!(ledger.transactions.is_empty());
+}
+
+#[test]
+fn ledger_migrator_runs_newer_steps_in_order() {
+    use bufy_domain::{CurrencyCode, CURRENT_SCHEMA_MINOR, CURRENT_SCHEMA_VERSION};
+
+    let mut ledger = LedgerService::create("Old", LedgerBudgetPeriod::monthly());
+    ledger.schema_version = 3;
+    ledger.schema_minor = 0;
+    ledger.base_currency = CurrencyCode::new("");
+    let notes = LedgerMigrator::default().migrate(&mut ledger).unwrap();
+    assert_eq!(
+        notes,
+        [
+            format!("upgraded schema from v3 to v{CURRENT_SCHEMA_VERSION}"),
+            "initialized currency/localization defaults for schema v4".to_string(),
+            "counted stored amounts in minor units of their currency for schema v5".to_string(),
+        ]
+    );
+    assert_eq!(ledger.base_currency, CurrencyCode::default());
+    assert_eq!(
+        (ledger.schema_version, ledger.schema_minor),
+        (CURRENT_SCHEMA_VERSION, CURRENT_SCHEMA_MINOR)
+    );
+    assert!(LedgerMigrator::default()
+        .migrate(&mut ledger)
+        .unwrap()
+        .is_empty());
+
+    let seen = |ledger: &mut bufy_domain::Ledger| {
+        ledger.name.push_str(" v2");
+        Ok(())
+    };
+    let mut ancient = LedgerService::create("Ancient", LedgerBudgetPeriod::monthly());
+    ancient.schema_version = 1;
+    let notes = LedgerMigrator::empty()
+        .register(2, "renamed for v2", seen)
+        .migrate(&mut ancient)
+        .unwrap();
+    assert_eq!(notes.len(), 2);
+    assert_eq!(ancient.name, "Ancient v2");
+
+    ancient.schema_version = CURRENT_SCHEMA_VERSION + 1;
+    assert!(matches!(
+        LedgerMigrator::default().migrate(&mut ancient),
+        Err(CoreError::Storage(_))
+    ));
+    assert_eq!(ancient.schema_version, CURRENT_SCHEMA_VERSION + 1);
+}
+
+#[test]
+#[should_panic(expected = "out of order")]
+fn ledger_migrator_rejects_steps_out_of_order() {
+    let noop = |_: &mut bufy_domain::Ledger| Ok(());
+    let _ = LedgerMigrator::empty()
+        .register(3, "v3", noop)
+        .register(2, "v2", noop);
+}
+
+#[test]
+fn summaries_sum_in_minor_units_and_legacy_amounts_load_in_their_currency() {
+    use crate::budget_service::BudgetService;
+
+    let mut ledger = LedgerService::create("Cents", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let cafe = ledger.add_account(Account::new("Cafe", AccountKind::ExpenseDestination));
+    let day = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+    for _ in 0..10 {
+        let mut txn = Transaction::new(checking, cafe, None, day, usd(0.1));
+        txn.mark_completed(day, usd(0.1));
+        ledger.transactions.push(txn);
+    }
+    assert_ne!((0..10).map(|_| 0.1).sum::<f64>(), 1.0);
+    let totals = BudgetService::summarize_period_containing(&ledger, day).totals;
+    assert_eq!((totals.budgeted, totals.real), (1.0, 1.0));
+
+    // Schema v4 files stored bare numbers; the v5 migration counts each in
+    // the currency it moves.
+    ledger.transactions[1].currency = Some("JPY".into());
+    let mut stored = serde_json::to_value(&ledger).unwrap();
+    stored["schema_version"] = 4.into();
+    stored["schema_minor"] = 1.into();
+    stored["transactions"][0]["budgeted_amount"] = serde_json::json!(0.30000000000000004);
+    stored["transactions"][1]["budgeted_amount"] = serde_json::json!(120.4);
+    stored["transactions"][1]["actual_amount"] = serde_json::json!(75);
+    let mut loaded: bufy_domain::Ledger = serde_json::from_value(stored).unwrap();
+    let report = crate::storage::prepare_loaded_ledger(&mut loaded).expect("prepare");
+    assert_eq!(report.schema_version, 4);
+    assert!(report.migrations.contains(
+        &"counted stored amounts in minor units of their currency for schema v5".to_string()
+    ));
+    assert_eq!(loaded.transactions[0].budgeted_amount, usd(0.3));
+    let yen = |minor| Money::from_minor(minor, CurrencyCode::new("JPY"));
+    assert_eq!(loaded.transactions[1].budgeted_amount, yen(120));
+    assert_eq!(loaded.transactions[1].actual_amount, Some(yen(75)));
+    assert_eq!(loaded.transactions[2].budgeted_amount, usd(0.1));
+    let report = crate::storage::prepare_loaded_ledger(&mut loaded).expect("prepare again");
+    assert!(report.migrations.is_empty());
+}
+
+#[test]
+fn account_edits_that_do_not_fit_the_new_currency_change_nothing() {
+    let mut ledger = LedgerService::create("Yen", LedgerBudgetPeriod::monthly());
+    let mut wallet = Account::new("Wallet", AccountKind::Cash);
+    wallet.currency = Some("JPY".into());
+    let wallet = ledger.add_account(wallet);
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let day = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+    let huge = Money::from_minor(i64::MAX / 10, CurrencyCode::new("JPY"));
+    ledger.add_transaction(Transaction::new(wallet, shop, None, day, huge.clone()));
+
+    let mut changes = ledger.account(wallet).unwrap().clone();
+    changes.name = "Dinar wallet".into();
+    changes.currency = Some("BHD".into());
+    assert!(AccountService::edit(&mut ledger, wallet, changes).is_err());
+    let account = ledger.account(wallet).unwrap();
+    assert_eq!(account.name, "Wallet");
+    assert_eq!(account.currency.as_deref(), Some("JPY"));
+    assert_eq!(ledger.transactions[0].budgeted_amount, huge);
+
+    let mut changes = ledger.account(wallet).unwrap().clone();
+    changes.currency = Some("KRW".into());
+    AccountService::edit(&mut ledger, wallet, changes).expect("same decimals");
+    assert_eq!(
+        ledger.transactions[0].budgeted_amount,
+        Money::from_minor(i64::MAX / 10, CurrencyCode::new("KRW"))
+    );
+}
+
+#[test]
+fn attachments_link_once_and_live_next_to_the_ledger_file() {
+    use crate::attachment_service::{AttachmentService, AttachmentStatus};
+
+    let mut ledger = LedgerService::create("Receipts", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let day = NaiveDate::from_ymd_opt(2025, 3, 4).unwrap();
+    let id = ledger.add_transaction(Transaction::new(checking, shop, None, day, usd(20.0)));
+
+    let link = AttachmentService::attach_link(&mut ledger, id, " https://example.com/r/1 ")
+        .expect("attach link");
+    assert_eq!(link.location, "https://example.com/r/1");
+    assert_eq!(link.checksum, None);
+    assert!(matches!(
+        AttachmentService::attach_link(&mut ledger, id, "https://example.com/r/1"),
+        Err(CoreError::InvalidOperation(_))
+    ));
+    assert!(matches!(
+        AttachmentService::attach_link(&mut ledger, id, "receipt.pdf"),
+        Err(CoreError::Validation(_))
+    ));
+    assert_eq!(
+        ledger.transaction(id).unwrap().attachments,
+        vec![link.clone()]
+    );
+
+    let dir = AttachmentService::attachments_dir(std::path::Path::new("data/home.bfy"));
+    assert_eq!(dir, std::path::Path::new("data/home.attachments"));
+    assert_eq!(
+        AttachmentService::status(&dir, &link),
+        AttachmentStatus::Link
+    );
+}
+
+#[test]
+fn attached_files_are_stored_by_sha256_and_removed_with_their_transactions() {
+    use crate::attachment_service::{AttachmentService, AttachmentStatus};
+    use crate::retention_service::RetentionService;
+
+    let temp = tempfile::tempdir().expect("temp dir");
+    let source = temp.path().join("Receipt.TXT");
+    std::fs::write(&source, "abc").expect("write receipt");
+    let dir = temp.path().join("home.attachments");
+
+    let mut ledger = LedgerService::create("Receipts", LedgerBudgetPeriod::monthly());
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let shop = ledger.add_account(Account::new("Shop", AccountKind::ExpenseDestination));
+    let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+    let old = ledger.add_transaction(Transaction::new(checking, shop, None, day(1), usd(20.0)));
+    let shared = ledger.add_transaction(Transaction::new(checking, shop, None, day(2), usd(5.0)));
+    let recent = ledger.add_transaction(Transaction::new(checking, shop, None, day(20), usd(9.0)));
+
+    let receipt =
+        AttachmentService::attach_file(&mut ledger, old, &dir, &source).expect("attach file");
+    let sha256 = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    assert_eq!(receipt.checksum.as_deref(), Some(sha256));
+    assert_eq!(receipt.location, format!("{}.txt", sha256));
+    assert_eq!(receipt.name, "Receipt.TXT");
+    assert_eq!(
+        AttachmentService::status(&dir, &receipt),
+        AttachmentStatus::Present
+    );
+    AttachmentService::attach_file(&mut ledger, shared, &dir, &source).expect("attach again");
+    let stored = dir.join(&receipt.location);
+
+    // Backing out one attachment keeps the copy the other still uses.
+    AttachmentService::detach(&mut ledger, shared, &dir, &receipt.location).expect("detach");
+    assert!(ledger.transaction(shared).unwrap().attachments.is_empty());
+    assert!(stored.exists());
+
+    let other = temp.path().join("other.pdf");
+    std::fs::write(&other, "other").expect("write other");
+    let kept = AttachmentService::attach_file(&mut ledger, recent, &dir, &other).expect("attach");
+    let preview = RetentionService::preview(&ledger, day(10));
+    assert_eq!(preview.attachments, 1);
+    let report = RetentionService::purge(&mut ledger, day(10), Some(&dir)).expect("purge");
+    assert_eq!(report, preview);
+    assert!(!stored.exists());
+    assert_eq!(
+        AttachmentService::status(&dir, &kept),
+        AttachmentStatus::Present
+    );
+
+    let escaping = bufy_domain::transaction::AttachmentRef {
+        location: "../home.bfy".into(),
+        name: "home.bfy".into(),
+        checksum: None,
+    };
+    assert_eq!(
+        AttachmentService::status(&dir, &escaping),
+        AttachmentStatus::Missing
+    );
+}
+
+#[test]
+fn recurrences_move_off_the_locale_weekend_and_heatmap_follows_its_week() {
+    use crate::{heatmap_service::HeatmapService, recurrence_service::RecurrenceService};
+    use bufy_domain::BusinessDayShift;
+    use chrono::Weekday;
+
+    let mut ledger = LedgerService::create("Weekends", LedgerBudgetPeriod::monthly());
+    ledger.locale.weekend = vec![Weekday::Fri, Weekday::Sat];
+    ledger.locale.first_weekday = Weekday::Sun;
+    let checking = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+    let landlord = ledger.add_account(Account::new("Landlord", AccountKind::ExpenseDestination));
+    let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+    let monthly = TimeInterval {
+        every: 1,
+        unit: TimeUnit::Month,
+    };
+    let mut rent = Transaction::new(checking, landlord, None, date(3, 5), usd(900.0));
+    rent.set_recurrence(Some(Recurrence::new(
+        date(3, 5),
+        monthly.clone(),
+        RecurrenceMode::FixedSchedule,
+    )));
+    let rent = ledger.add_transaction(rent);
+    let mut gym = Transaction::new(checking, landlord, None, date(3, 5), usd(30.0));
+    gym.set_recurrence(Some(Recurrence::new(
+        date(3, 5),
+        monthly,
+        RecurrenceMode::FixedSchedule,
+    )));
+    let gym = ledger.add_transaction(gym);
+    RecurrenceService::set_business_day_shift(&mut ledger, rent, BusinessDayShift::Following)
+        .unwrap();
+    RecurrenceService::set_business_day_shift(&mut ledger, gym, BusinessDayShift::Preceding)
+        .unwrap();
+
+    assert_eq!(
+        RecurrenceService::materialize_due(&mut ledger, date(6, 10)).unwrap(),
+        6
+    );
+    let dates = |template| {
+        let series = ledger.transaction(template).unwrap().recurrence_series();
+        let mut dates: Vec<_> = ledger
+            .transactions
+            .iter()
+            .filter(|txn| txn.recurrence_series() == series)
+            .map(|txn| txn.scheduled_date)
+            .collect();
+        dates.sort();
+        dates
+    };
+    // 5 April 2025 is a Saturday.
+    assert_eq!(
+        dates(rent),
+        vec![date(3, 5), date(4, 6), date(5, 5), date(6, 5)]
+    );
+    assert_eq!(
+        dates(gym),
+        vec![date(3, 5), date(4, 3), date(5, 5), date(6, 5)]
+    );
+    assert_eq!(
+        RecurrenceService::materialize_due(&mut ledger, date(6, 10)).unwrap(),
+        0,
+        "shifted occurrences are recognised as already created"
+    );
+    let next = |id| {
+        ledger
+            .transaction(id)
+            .and_then(|txn| txn.recurrence.as_ref())
+            .and_then(|recurrence| recurrence.next_scheduled)
+    };
+    // 5 July 2025 is a Saturday too.
+    assert_eq!(next(rent), Some(date(7, 6)));
+    assert_eq!(next(gym), Some(date(7, 3)));
+
+    let mut paid = Transaction::new(checking, landlord, None, date(3, 3), usd(100.0));
+    paid.mark_completed(date(3, 3), usd(100.0));
+    ledger.add_transaction(paid);
+    let mut coffee = Transaction::new(checking, landlord, None, date(3, 8), usd(25.0));
+    coffee.mark_completed(date(3, 8), usd(25.0));
+    ledger.add_transaction(coffee);
+    let heatmap = HeatmapService::month(&ledger, 2025, 3).unwrap();
+    assert_eq!(heatmap.weekdays.first(), Some(&Weekday::Sun));
+    assert_eq!(heatmap.weeks.len(), 6, "1 March 2025 is a Saturday");
+    let first = heatmap.weeks[0][6].as_ref().unwrap();
+    assert_eq!(first.date, date(3, 1));
+    assert!(first.weekend);
+    assert!(heatmap.weeks[0][..6].iter().all(Option::is_none));
+    let third = heatmap.weeks[1][1].as_ref().unwrap();
+    assert_eq!(
+        (third.date, third.total, third.weekend),
+        (date(3, 3), 100.0, false)
+    );
+    assert_eq!(heatmap.busiest, 100.0);
+    assert!(HeatmapService::month(&ledger, 2025, 13).is_err());
+}
@@ -1,44 +1,688 @@
 //! Business logic helpers for managing transactions.
 
+use std::cmp::Ordering;
+
+use chrono::NaiveDate;
+use tracing::instrument;
 use uuid::Uuid;
 
-use bufy_domain::{transaction::Transaction, Ledger};
+use bufy_domain::{
+    account::AccountStatus,
+    currency::format_currency_value,
+    ids::ShortRef,
+    transaction::{Transaction, TransactionStatus},
+    view::TransactionFilter,
+    Ledger, Money,
+};
+
+use crate::{
+    journal::{ChangeJournal, JournalEntry},
+    validation::{check_amount, check_date, check_tag, AmountRule},
+    CoreError, TransferRuleService,
+};
+
+/// Criteria used to narrow a transaction listing. Unset fields match everything.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransactionQuery {
+    /// Earliest scheduled date to include (inclusive).
+    pub from: Option<NaiveDate>,
+    /// Latest scheduled date to include (inclusive).
+    pub to: Option<NaiveDate>,
+    /// Account that must appear on either side of the transfer.
+    pub account_id: Option<Uuid>,
+    pub category_id: Option<Uuid>,
+    /// Household member the transaction is attributed to.
+    pub member_id: Option<Uuid>,
+    pub status: Option<TransactionStatus>,
+    /// Minimum budgeted amount (inclusive).
+    pub min_amount: Option<f64>,
+    /// Maximum budgeted amount (inclusive).
+    pub max_amount: Option<f64>,
+    /// Text the notes must contain, ignoring case.
+    pub notes: Option<String>,
+    /// Text the notes or merchant must contain, ignoring case.
+    pub text: Option<String>,
+    /// `true` keeps only transactions in a recurring series, `false` only
+    /// one-off transactions.
+    pub recurring: Option<bool>,
+    /// Tag the transaction must carry, already normalized.
+    pub tag: Option<String>,
+    /// Order of the results; ledger order when unset.
+    pub sort: Option<TransactionSort>,
+    /// Matching transactions to skip before the first result.
+    pub offset: usize,
+    /// Most results to return; all of them when unset.
+    pub limit: Option<usize>,
+}
+
+/// Field a transaction query is ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionSortKey {
+    Date,
+    /// Actual amount when recorded, else the budgeted amount.
+    Amount,
+    /// Short reference, i.e. the order transactions were added.
+    Reference,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionSort {
+    pub key: TransactionSortKey,
+    pub descending: bool,
+}
+
+impl TransactionSort {
+    pub fn ascending(key: TransactionSortKey) -> Self {
+        Self {
+            key,
+            descending: false,
+        }
+    }
+
+    pub fn descending(key: TransactionSortKey) -> Self {
+        Self {
+            key,
+            descending: true,
+        }
+    }
+
+    fn compare(&self, a: &Transaction, b: &Transaction) -> Ordering {
+        let ordering = match self.key {
+            TransactionSortKey::Date => a.scheduled_date.cmp(&b.scheduled_date),
+            TransactionSortKey::Amount => {
+                let amount = |txn: &Transaction| txn.amount().to_major();
+                amount(a).total_cmp(&amount(b))
+            }
+            TransactionSortKey::Reference => a.reference.cmp(&b.reference),
+        };
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
 
-use crate::CoreError;
+impl TransactionQuery {
+    /// Returns `true` when the transaction satisfies every configured criterion.
+    pub fn matches(&self, txn: &Transaction) -> bool {
+        if self.from.is_some_and(|from| txn.scheduled_date < from) {
+            return false;
+        }
+        if self.to.is_some_and(|to| txn.scheduled_date > to) {
+            return false;
+        }
+        if self
+            .account_id
+            .is_some_and(|id| txn.from_account != id && txn.to_account != id)
+        {
+            return false;
+        }
+        if self
+            .category_id
+            .is_some_and(|id| txn.category_id != Some(id))
+        {
+            return false;
+        }
+        if self.member_id.is_some_and(|id| txn.member_id != Some(id)) {
+            return false;
+        }
+        if self
+            .status
+            .as_ref()
+            .is_some_and(|status| &txn.status != status)
+        {
+            return false;
+        }
+        let amount = txn.budgeted_amount.to_major();
+        if self.min_amount.is_some_and(|min| amount < min) {
+            return false;
+        }
+        if self.max_amount.is_some_and(|max| amount > max) {
+            return false;
+        }
+        if let Some(needle) = self.notes.as_deref() {
+            let needle = needle.to_lowercase();
+            if !txn
+                .notes
+                .as_deref()
+                .is_some_and(|notes| notes.to_lowercase().contains(&needle))
+            {
+                return false;
+            }
+        }
+        if let Some(needle) = self.text.as_deref() {
+            let needle = needle.to_lowercase();
+            let contains = |field: Option<&str>| {
+                field.is_some_and(|field| field.to_lowercase().contains(&needle))
+            };
+            if !contains(txn.notes.as_deref()) && !contains(txn.merchant.as_deref()) {
+                return false;
+            }
+        }
+        if self
+            .recurring
+            .is_some_and(|recurring| txn.recurrence_series().is_some() != recurring)
+        {
+            return false;
+        }
+        if self.tag.as_ref().is_some_and(|tag| !txn.tags.contains(tag)) {
+            return false;
+        }
+        true
+    }
+}
 
 /// Provides validated CRUD helpers for [`Transaction`] entities.
 pub struct TransactionService;
 
 impl TransactionService {
-    /// Adds a new transaction and returns its identifier.
-    pub fn add(ledger: &mut Ledger, transaction: Transaction) -> Result<Uuid, CoreError> {
+    /// Adds a new transaction, counting its amounts in the currency it
+    /// moves, and returns its identifier.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction = %transaction.id,
+            amount = %transaction.budgeted_amount
+        ),
+        err(level = "debug")
+    )]
+    pub fn add(ledger: &mut Ledger, mut transaction: Transaction) -> Result<Uuid, CoreError> {
+        Self::validate(ledger, &transaction)?;
+        ledger.assign_transaction_currency(&mut transaction)?;
         let id = ledger.add_transaction(transaction);
         Ok(id)
     }
 
-    /// Updates the transaction identified by `id` via the provided mutator.
+    /// Updates the transaction identified by `id` via the provided mutator,
+    /// then counts its amounts in the currency it moves.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
     pub fn update<F>(ledger: &mut Ledger, id: Uuid, mutator: F) -> Result<(), CoreError>
     where
         F: FnOnce(&mut Transaction),
     {
-        let txn = ledger
-            .transaction_mut(id)
-            .ok_or_else(|| CoreError::TransactionNotFound(id))?;
-        mutator(txn);
+        let mut txn = ledger
+            .transaction(id)
+            .cloned()
+            .ok_or(CoreError::TransactionNotFound(id))?;
+        mutator(&mut txn);
+        ledger.assign_transaction_currency(&mut txn)?;
+        if let Some(stored) = ledger.transaction_mut(id) {
+            *stored = txn;
+        }
         ledger.refresh_recurrence_metadata();
         ledger.touch();
         Ok(())
     }
 
+    /// Marks a transaction completed and runs the transfer rules for the
+    /// account it pays into, returning any transfers they created.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            id = %id,
+            actual_date = %actual_date,
+            actual_amount = actual_amount
+        ),
+        err(level = "debug")
+    )]
+    pub fn complete(
+        ledger: &mut Ledger,
+        id: Uuid,
+        actual_date: NaiveDate,
+        actual_amount: f64,
+    ) -> Result<Vec<Uuid>, CoreError> {
+        check_amount("actual amount", actual_amount, AmountRule::NonNegative)?;
+        check_date(ledger, "actual date", actual_date)?;
+        let txn = ledger
+            .transaction(id)
+            .ok_or(CoreError::TransactionNotFound(id))?;
+        let actual_amount = Money::from_major(actual_amount, ledger.transaction_currency(txn))?;
+        Self::update(ledger, id, |txn| {
+            txn.mark_completed(actual_date, actual_amount)
+        })?;
+        TransferRuleService::apply(ledger, id)
+    }
+
+    /// Pays `amount` of the planned transaction `id` as a separate completed
+    /// installment, lowering the planned amount by the same sum so budget
+    /// totals stay unchanged. Returns the installment and any transfers the
+    /// transfer rules created for it.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            id = %id,
+            actual_date = %actual_date,
+            amount = amount
+        ),
+        err(level = "debug")
+    )]
+    pub fn complete_partial(
+        ledger: &mut Ledger,
+        id: Uuid,
+        actual_date: NaiveDate,
+        amount: f64,
+    ) -> Result<(Uuid, Vec<Uuid>), CoreError> {
+        let planned = ledger
+            .transaction(id)
+            .ok_or(CoreError::TransactionNotFound(id))?;
+        if planned.status != TransactionStatus::Planned {
+            return Err(CoreError::InvalidOperation(
+                "only planned transactions can be paid in part".into(),
+            ));
+        }
+        if planned.recurrence.is_some() {
+            return Err(CoreError::InvalidOperation(
+                "a recurring schedule cannot be paid in part; complete it instead".into(),
+            ));
+        }
+        check_amount("amount", amount, AmountRule::Positive)?;
+        check_date(ledger, "actual date", actual_date)?;
+        let amount = Money::from_major(amount, ledger.transaction_currency(planned))?;
+        let remaining = planned.budgeted_amount.in_currency(amount.currency())?;
+        if amount.minor() >= remaining.minor() {
+            return Err(CoreError::Validation(format!(
+                "amount must be less than the {} still planned; complete the transaction to pay it all",
+                format_currency_value(
+                    remaining.to_major(),
+                    remaining.currency(),
+                    &ledger.locale,
+                    &ledger.format,
+                )
+            )));
+        }
+        let remaining = remaining.checked_sub(&amount)?;
+        let mut installment = Transaction::new(
+            planned.from_account,
+            planned.to_account,
+            planned.category_id,
+            planned.scheduled_date,
+            amount.clone(),
+        );
+        installment.currency = planned.currency.clone();
+        installment.member_id = planned.member_id;
+        installment.merchant = planned.merchant.clone();
+        installment.notes = planned.notes.clone();
+        installment.installment_of = Some(id);
+        installment.mark_completed(actual_date, amount);
+        // The installment is checked and stored first so that a rejected
+        // payment leaves the planned amount as it was.
+        let installment_id = Self::add(ledger, installment)?;
+        if let Err(err) = Self::update(ledger, id, |txn| txn.budgeted_amount = remaining) {
+            ledger.remove_transaction(installment_id);
+            return Err(err);
+        }
+        let transfers = TransferRuleService::apply(ledger, installment_id)?;
+        Ok((installment_id, transfers))
+    }
+
+    /// Installments already paid towards the planned transaction `id`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id)
+    )]
+    pub fn installments(ledger: &Ledger, id: Uuid) -> Vec<&Transaction> {
+        ledger
+            .transactions
+            .iter()
+            .filter(|txn| txn.installment_of == Some(id))
+            .collect()
+    }
+
+    /// Adds `tags` to a transaction, returning the normalized tags it did
+    /// not carry yet.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn add_tags(
+        ledger: &mut Ledger,
+        id: Uuid,
+        tags: &[&str],
+    ) -> Result<Vec<String>, CoreError> {
+        let tags = tags
+            .iter()
+            .map(|tag| check_tag(tag))
+            .collect::<Result<Vec<_>, _>>()?;
+        let txn = ledger
+            .transaction_mut(id)
+            .ok_or(CoreError::TransactionNotFound(id))?;
+        let mut added = Vec::new();
+        for tag in tags {
+            if !txn.tags.contains(&tag) {
+                txn.tags.push(tag.clone());
+                added.push(tag);
+            }
+        }
+        txn.tags.sort();
+        if !added.is_empty() {
+            ledger.touch();
+        }
+        Ok(added)
+    }
+
+    /// Removes `tags` from a transaction, returning the normalized tags it
+    /// carried.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn remove_tags(
+        ledger: &mut Ledger,
+        id: Uuid,
+        tags: &[&str],
+    ) -> Result<Vec<String>, CoreError> {
+        let tags = tags
+            .iter()
+            .map(|tag| check_tag(tag))
+            .collect::<Result<Vec<_>, _>>()?;
+        let txn = ledger
+            .transaction_mut(id)
+            .ok_or(CoreError::TransactionNotFound(id))?;
+        let mut removed: Vec<String> = tags
+            .into_iter()
+            .filter(|tag| txn.tags.contains(tag))
+            .collect();
+        removed.sort();
+        removed.dedup();
+        txn.tags.retain(|tag| !removed.contains(tag));
+        if !removed.is_empty() {
+            ledger.touch();
+        }
+        Ok(removed)
+    }
+
+    /// Every tag in use with the number of transactions carrying it, by tag.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn tag_counts(ledger: &Ledger) -> Vec<(String, usize)> {
+        let mut counts = std::collections::BTreeMap::new();
+        for tag in ledger.transactions.iter().flat_map(|txn| &txn.tags) {
+            *counts.entry(tag.clone()).or_insert(0) += 1;
+        }
+        counts.into_iter().collect()
+    }
+
     /// Removes the transaction identified by `id`, returning the removed instance.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
     pub fn remove(ledger: &mut Ledger, id: Uuid) -> Result<Transaction, CoreError> {
         ledger
             .remove_transaction(id)
-            .ok_or_else(|| CoreError::TransactionNotFound(id))
+            .ok_or(CoreError::TransactionNotFound(id))
+    }
+
+    /// Like [`Self::add`], recording the edit in `journal` for undo.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction = %transaction.id,
+            amount = %transaction.budgeted_amount
+        ),
+        err(level = "debug")
+    )]
+    pub fn add_journaled(
+        ledger: &mut Ledger,
+        transaction: Transaction,
+        journal: &mut ChangeJournal,
+    ) -> Result<Uuid, CoreError> {
+        let id = Self::add(ledger, transaction)?;
+        // Capture after adding so the entry carries the assigned reference.
+        let added = ledger
+            .transaction(id)
+            .cloned()
+            .expect("transaction exists after add");
+        journal.record(JournalEntry::TransactionAdded(added));
+        Ok(id)
+    }
+
+    /// Like [`Self::update`], recording the edit in `journal` for undo.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn update_journaled<F>(
+        ledger: &mut Ledger,
+        id: Uuid,
+        mutator: F,
+        journal: &mut ChangeJournal,
+    ) -> Result<(), CoreError>
+    where
+        F: FnOnce(&mut Transaction),
+    {
+        let before = ledger
+            .transaction(id)
+            .cloned()
+            .ok_or(CoreError::TransactionNotFound(id))?;
+        Self::update(ledger, id, mutator)?;
+        let after = ledger
+            .transaction(id)
+            .cloned()
+            .expect("transaction exists after update");
+        journal.record(JournalEntry::TransactionEdited { before, after });
+        Ok(())
+    }
+
+    /// Like [`Self::remove`], recording the edit in `journal` for undo.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn remove_journaled(
+        ledger: &mut Ledger,
+        id: Uuid,
+        journal: &mut ChangeJournal,
+    ) -> Result<Transaction, CoreError> {
+        let index = ledger
+            .transactions
+            .iter()
+            .position(|txn| txn.id == id)
+            .ok_or(CoreError::TransactionNotFound(id))?;
+        let removed = Self::remove(ledger, id)?;
+        journal.record(JournalEntry::TransactionRemoved {
+            index,
+            transaction: removed.clone(),
+        });
+        Ok(removed)
     }
 
     /// Returns a snapshot of the ledger's transactions.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
     pub fn list(ledger: &Ledger) -> Vec<&Transaction> {
         ledger.transactions.iter().collect()
     }
+
+    /// Finds a transaction by its short code, such as `T-0012`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn find_by_reference<'a>(
+        ledger: &'a Ledger,
+        code: &str,
+    ) -> Result<&'a Transaction, CoreError> {
+        let reference = ShortRef::parse(code).ok_or_else(|| {
+            CoreError::Validation(format!(
+                "`{}` is not a transaction reference (expected e.g. T-0012)",
+                code.trim()
+            ))
+        })?;
+        ledger.transaction_by_reference(reference).ok_or_else(|| {
+            CoreError::InvalidOperation(format!("transaction `{}` not found", reference))
+        })
+    }
+
+    /// Resolves the account, category, and member names in `filter` into a [`TransactionQuery`].
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn resolve_filter(
+        ledger: &Ledger,
+        filter: &TransactionFilter,
+    ) -> Result<TransactionQuery, CoreError> {
+        let account_id = match filter.account.as_deref() {
+            Some(name) => Some(
+                ledger
+                    .accounts
+                    .iter()
+                    .find(|account| account.name.eq_ignore_ascii_case(name))
+                    .map(|account| account.id)
+                    .ok_or_else(|| CoreError::AccountNotFound(name.to_string()))?,
+            ),
+            None => None,
+        };
+        let category_id = match filter.category.as_deref() {
+            Some(name) => Some(
+                ledger
+                    .categories
+                    .iter()
+                    .find(|category| category.name.eq_ignore_ascii_case(name))
+                    .map(|category| category.id)
+                    .ok_or_else(|| CoreError::CategoryNotFound(name.to_string()))?,
+            ),
+            None => None,
+        };
+        let member_id = match filter.member.as_deref() {
+            Some(name) => Some(
+                ledger
+                    .member_by_name(name)
+                    .map(|member| member.id)
+                    .ok_or_else(|| CoreError::MemberNotFound(name.to_string()))?,
+            ),
+            None => None,
+        };
+        Ok(TransactionQuery {
+            from: filter.from,
+            to: filter.to,
+            account_id,
+            category_id,
+            member_id,
+            status: filter.status.clone(),
+            min_amount: filter.min_amount,
+            max_amount: filter.max_amount,
+            notes: filter.notes.clone(),
+            text: filter.text.clone(),
+            recurring: filter.recurring,
+            tag: filter.tag.as_deref().map(check_tag).transpose()?,
+            ..TransactionQuery::default()
+        })
+    }
+
+    /// Returns the page of the ledger's transactions that satisfy `query`, in
+    /// the query's order (ledger order unless it sets one).
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn query<'a>(ledger: &'a Ledger, query: &TransactionQuery) -> Vec<&'a Transaction> {
+        let mut matches: Vec<_> = ledger
+            .transactions
+            .iter()
+            .filter(|txn| query.matches(txn))
+            .collect();
+        if let Some(sort) = query.sort {
+            matches.sort_by(|a, b| sort.compare(a, b));
+        }
+        matches
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+
+    /// Number of transactions that satisfy `query`, ignoring its offset and
+    /// limit.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339())
+    )]
+    pub fn count(ledger: &Ledger, query: &TransactionQuery) -> usize {
+        ledger
+            .transactions
+            .iter()
+            .filter(|txn| query.matches(txn))
+            .count()
+    }
+
+    /// Rejects a new transaction that points at missing accounts or
+    /// categories, or whose amounts or dates are out of range.
+    fn validate(ledger: &Ledger, txn: &Transaction) -> Result<(), CoreError> {
+        for account_id in [txn.from_account, txn.to_account] {
+            let account = ledger
+                .account(account_id)
+                .ok_or_else(|| CoreError::AccountNotFound(account_id.to_string()))?;
+            if let AccountStatus::Closed { on } = account.status {
+                return Err(CoreError::InvalidOperation(format!(
+                    "account `{}` was closed on {}",
+                    account.name, on
+                )));
+            }
+        }
+        if let Some(category_id) = txn.category_id {
+            if ledger.category(category_id).is_none() {
+                return Err(CoreError::CategoryNotFound(category_id.to_string()));
+            }
+        }
+        check_amount(
+            "amount",
+            txn.budgeted_amount.to_major(),
+            AmountRule::NonNegative,
+        )?;
+        check_date(ledger, "date", txn.scheduled_date)?;
+        if let Some(actual_amount) = &txn.actual_amount {
+            check_amount(
+                "actual amount",
+                actual_amount.to_major(),
+                AmountRule::NonNegative,
+            )?;
+        }
+        if let Some(actual_date) = txn.actual_date {
+            check_date(ledger, "actual date", actual_date)?;
+        }
+        Ok(())
+    }
 }
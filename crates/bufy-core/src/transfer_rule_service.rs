@@ -0,0 +1,196 @@
+//! Automatic transfers that follow income into other accounts (auto-savings).
+
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{
+    account::AccountKind, category::CategoryKind, transaction::TransactionStatus,
+    transfer_rule::TransferRule, Ledger, Money, Transaction,
+};
+
+use crate::{Clock, CoreError};
+
+pub struct TransferRuleService;
+
+impl TransferRuleService {
+    /// Sets the share of income arriving in `from_account` that moves on to
+    /// `to_account`. An existing rule for the same pair is updated in place.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            from_account = %from_account,
+            to_account = %to_account
+        ),
+        err(level = "debug")
+    )]
+    pub fn set(
+        ledger: &mut Ledger,
+        from_account: Uuid,
+        to_account: Uuid,
+        percent: f64,
+        clock: &dyn Clock,
+    ) -> Result<Uuid, CoreError> {
+        if !percent.is_finite() || percent <= 0.0 || percent > 100.0 {
+            return Err(CoreError::Validation(
+                "percent must be greater than 0 and at most 100".into(),
+            ));
+        }
+        if from_account == to_account {
+            return Err(CoreError::Validation(
+                "a transfer rule needs two different accounts".into(),
+            ));
+        }
+        for id in [from_account, to_account] {
+            let account = ledger
+                .account(id)
+                .ok_or_else(|| CoreError::AccountNotFound(id.to_string()))?;
+            if matches!(
+                account.kind,
+                AccountKind::IncomeSource | AccountKind::ExpenseDestination
+            ) {
+                return Err(CoreError::Validation(format!(
+                    "`{}` is a {} account; transfer rules move money between your own accounts",
+                    account.name, account.kind
+                )));
+            }
+        }
+        let committed: f64 = ledger
+            .transfer_rules
+            .iter()
+            .filter(|rule| rule.from_account == from_account && rule.to_account != to_account)
+            .map(|rule| rule.percent)
+            .sum();
+        if committed + percent > 100.0 {
+            return Err(CoreError::Validation(format!(
+                "rules would transfer {}% of each income, more than it brings in",
+                committed + percent
+            )));
+        }
+
+        let id = match ledger
+            .transfer_rules
+            .iter_mut()
+            .find(|rule| rule.from_account == from_account && rule.to_account == to_account)
+        {
+            Some(rule) => {
+                rule.percent = percent;
+                rule.id
+            }
+            None => {
+                let rule = TransferRule::new(from_account, to_account, percent, clock.now());
+                let id = rule.id;
+                ledger.transfer_rules.push(rule);
+                id
+            }
+        };
+        ledger.touch();
+        Ok(id)
+    }
+
+    /// Removes a rule. Transfers it already created stay in the ledger.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), id = %id),
+        err(level = "debug")
+    )]
+    pub fn remove(ledger: &mut Ledger, id: Uuid) -> Result<TransferRule, CoreError> {
+        let index = ledger
+            .transfer_rules
+            .iter()
+            .position(|rule| rule.id == id)
+            .ok_or_else(|| CoreError::RuleNotFound(id.to_string()))?;
+        let removed = ledger.transfer_rules.remove(index);
+        ledger.touch();
+        Ok(removed)
+    }
+
+    /// Creates the transfers owed for the income transaction `income_id`,
+    /// returning their ids. Each rule transfers at most once per income, so
+    /// completing the same transaction again adds nothing.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            income_id = %income_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn apply(ledger: &mut Ledger, income_id: Uuid) -> Result<Vec<Uuid>, CoreError> {
+        let income = ledger
+            .transaction(income_id)
+            .ok_or(CoreError::TransactionNotFound(income_id))?;
+        if !Self::is_income(ledger, income) {
+            return Ok(Vec::new());
+        }
+        let (Some(date), Some(amount)) = (income.actual_date, &income.actual_amount) else {
+            return Ok(Vec::new());
+        };
+        let mut transfers = Vec::new();
+        for rule in ledger
+            .transfer_rules
+            .iter()
+            .filter(|rule| rule.from_account == income.to_account)
+            .filter(|rule| {
+                !ledger.transactions.iter().any(|txn| {
+                    txn.source_transaction_id == Some(income_id)
+                        && txn.to_account == rule.to_account
+                })
+            })
+        {
+            let share =
+                Money::from_major(rule.share_of(amount.to_major()), amount.currency().clone())?;
+            if share.minor() <= 0 {
+                continue;
+            }
+            let mut txn = Transaction::new(
+                rule.from_account,
+                rule.to_account,
+                None,
+                date,
+                share.clone(),
+            );
+            txn.currency = income.currency.clone();
+            txn.member_id = income.member_id;
+            txn.notes = Some(format!("Auto-transfer {}% of income", rule.percent));
+            txn.source_transaction_id = Some(income_id);
+            txn.mark_completed(date, share);
+            transfers.push(txn);
+        }
+        Ok(transfers
+            .into_iter()
+            .map(|txn| ledger.add_transaction(txn))
+            .collect())
+    }
+
+    /// Completed money coming in from an income source or booked to an
+    /// income category.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction = %txn.id,
+            amount = %txn.budgeted_amount
+        )
+    )]
+    pub fn is_income(ledger: &Ledger, txn: &Transaction) -> bool {
+        if txn.status != TransactionStatus::Completed {
+            return false;
+        }
+        let from_income_source = ledger
+            .account(txn.from_account)
+            .is_some_and(|account| account.kind == AccountKind::IncomeSource);
+        let income_category = txn
+            .category_id
+            .and_then(|id| ledger.category(id))
+            .is_some_and(|category| category.kind == CategoryKind::Income);
+        from_income_source || income_category
+    }
+}
@@ -0,0 +1,212 @@
+//! Transactions without a category, grouped by inferred payee so they can be
+//! classified as income or expense and categorized in batches.
+
+use std::{collections::HashMap, fmt};
+
+use chrono::NaiveDate;
+use tracing::instrument;
+use uuid::Uuid;
+
+use bufy_domain::{DateWindow, Ledger, Transaction};
+
+use crate::{
+    categorization_service::CategorizationService,
+    stats::{completed_movement, is_asset_account},
+    Clock, CoreError,
+};
+
+/// Direction of money for an uncategorized transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlowKind {
+    Income,
+    Expense,
+}
+
+impl fmt::Display for FlowKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FlowKind::Income => "Income",
+            FlowKind::Expense => "Expense",
+        })
+    }
+}
+
+/// Uncategorized transactions sharing a payee and direction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayeeGroup {
+    /// Merchant name, or the counterparty account when no merchant is set.
+    pub payee: String,
+    pub kind: FlowKind,
+    pub transaction_ids: Vec<Uuid>,
+    /// Amount across every transaction in the group.
+    pub total: f64,
+    /// Completed amount inside the report window, in the base currency.
+    pub period_total: f64,
+    /// Category most learned rules point at, if any.
+    pub suggestion: Option<Uuid>,
+}
+
+/// Uncategorized transactions grouped by payee, largest impact first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UncategorizedReport {
+    pub window: DateWindow,
+    pub groups: Vec<PayeeGroup>,
+    /// Completed uncategorized income inside the window.
+    pub period_income: f64,
+    /// Completed uncategorized spending inside the window.
+    pub period_expense: f64,
+    /// Transactions in the window whose amount could not be converted to the
+    /// base currency and were left out of the period totals.
+    pub unconverted: usize,
+}
+
+impl UncategorizedReport {
+    pub fn transaction_count(&self) -> usize {
+        self.groups
+            .iter()
+            .map(|group| group.transaction_ids.len())
+            .sum()
+    }
+}
+
+pub struct UncategorizedService;
+
+impl UncategorizedService {
+    /// Groups transactions dated up to `today` that have no category,
+    /// leaving out transfers between the ledger's own accounts. Period totals
+    /// use the budget period containing `today`.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339(), today = %today)
+    )]
+    pub fn report(ledger: &Ledger, today: NaiveDate) -> UncategorizedReport {
+        let window = ledger.budget_window_containing(today);
+        let ctx = ledger.conversion_context(window.end);
+        let mut report = UncategorizedReport {
+            window,
+            groups: Vec::new(),
+            period_income: 0.0,
+            period_expense: 0.0,
+            unconverted: 0,
+        };
+        let mut groups: HashMap<(String, FlowKind), PayeeGroup> = HashMap::new();
+        for txn in &ledger.transactions {
+            if txn.category_id.is_some() || txn.scheduled_date > today {
+                continue;
+            }
+            let Some(kind) = Self::classify(ledger, txn) else {
+                continue;
+            };
+            let payee = Self::payee(ledger, txn, kind);
+            let group = groups
+                .entry((payee.to_lowercase(), kind))
+                .or_insert_with(|| PayeeGroup {
+                    payee,
+                    kind,
+                    transaction_ids: Vec::new(),
+                    total: 0.0,
+                    period_total: 0.0,
+                    suggestion: None,
+                });
+            group.transaction_ids.push(txn.id);
+            group.total += txn.amount().to_major();
+            if group.suggestion.is_none() {
+                group.suggestion = CategorizationService::suggest(ledger, txn);
+            }
+
+            let Some((_, amount)) = completed_movement(txn) else {
+                continue;
+            };
+            let Some(date) = txn
+                .reporting_date(ledger.summary_date_basis)
+                .filter(|date| window.contains(*date))
+            else {
+                continue;
+            };
+            let currency = ledger.transaction_currency(txn);
+            let Ok(converted) = ledger.convert_amount(amount, &currency, date, &ctx) else {
+                report.unconverted += 1;
+                continue;
+            };
+            group.period_total += converted.amount;
+            match kind {
+                FlowKind::Income => report.period_income += converted.amount,
+                FlowKind::Expense => report.period_expense += converted.amount,
+            }
+        }
+        report.groups = groups.into_values().collect();
+        report.groups.sort_by(|a, b| {
+            b.period_total
+                .total_cmp(&a.period_total)
+                .then_with(|| b.total.total_cmp(&a.total))
+                .then_with(|| a.payee.cmp(&b.payee))
+        });
+        report
+    }
+
+    /// Assigns `category_id` to every transaction in `transaction_ids` and
+    /// learns from each, returning how many were changed.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(
+            ledger = %ledger.id,
+            revision = %ledger.updated_at.to_rfc3339(),
+            transaction_ids = ?transaction_ids,
+            category_id = %category_id
+        ),
+        err(level = "debug")
+    )]
+    pub fn assign(
+        ledger: &mut Ledger,
+        transaction_ids: &[Uuid],
+        category_id: Uuid,
+        clock: &dyn Clock,
+    ) -> Result<usize, CoreError> {
+        if ledger.category(category_id).is_none() {
+            return Err(CoreError::CategoryNotFound(category_id.to_string()));
+        }
+        let mut assigned = 0;
+        for id in transaction_ids {
+            let txn = ledger
+                .transaction_mut(*id)
+                .ok_or(CoreError::TransactionNotFound(*id))?;
+            if txn.category_id == Some(category_id) {
+                continue;
+            }
+            txn.category_id = Some(category_id);
+            CategorizationService::learn(ledger, *id, clock)?;
+            assigned += 1;
+        }
+        if assigned > 0 {
+            ledger.touch();
+        }
+        Ok(assigned)
+    }
+
+    /// Income when money arrives in one of the ledger's own accounts from
+    /// outside; expense otherwise. `None` for transfers between own accounts.
+    fn classify(ledger: &Ledger, txn: &Transaction) -> Option<FlowKind> {
+        let is_own = |id| ledger.account(id).is_some_and(is_asset_account);
+        match (is_own(txn.from_account), is_own(txn.to_account)) {
+            (true, true) => None,
+            (false, true) => Some(FlowKind::Income),
+            _ => Some(FlowKind::Expense),
+        }
+    }
+
+    fn payee(ledger: &Ledger, txn: &Transaction, kind: FlowKind) -> String {
+        if let Some(merchant) = txn.merchant.as_deref() {
+            return merchant.to_string();
+        }
+        let counterparty = match kind {
+            FlowKind::Income => txn.from_account,
+            FlowKind::Expense => txn.to_account,
+        };
+        ledger
+            .account(counterparty)
+            .map(|account| account.name.clone())
+            .unwrap_or_else(|| "(unknown)".into())
+    }
+}
@@ -0,0 +1,193 @@
+//! Input checks shared by every service, so the CLI, the FFI, and the HTTP
+//! server reject the same names, amounts, and dates.
+
+use std::fmt;
+
+use chrono::{Months, NaiveDate};
+use serde::Serialize;
+
+use bufy_domain::Ledger;
+
+/// Longest account, category, or ledger name, in characters.
+pub const MAX_NAME_LENGTH: usize = 80;
+/// Largest amount accepted for a transaction, budget, or balance.
+pub const MAX_AMOUNT: f64 = 1_000_000_000_000.0;
+/// Longest account or category icon, in characters. Leaves room for emoji
+/// built from several code points, such as flags.
+pub const MAX_ICON_LENGTH: usize = 8;
+/// Longest transaction tag, in characters.
+pub const MAX_TAG_LENGTH: usize = 32;
+/// How many years before or after a ledger's creation its dates may fall.
+pub const DATE_RANGE_YEARS: u32 = 100;
+
+/// One rejected input: which field and what is wrong with it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationIssue {
+    pub field: &'static str,
+    #[serde(flatten)]
+    pub kind: ValidationIssueKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "issue", rename_all = "snake_case")]
+pub enum ValidationIssueKind {
+    Empty,
+    TooLong {
+        max: usize,
+    },
+    Duplicate {
+        value: String,
+    },
+    NotANumber,
+    NotPositive,
+    Negative,
+    TooLarge {
+        max: f64,
+    },
+    DateOutOfRange {
+        earliest: NaiveDate,
+        latest: NaiveDate,
+    },
+}
+
+/// Which amounts a field accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountRule {
+    Positive,
+    NonNegative,
+    /// Either sign, e.g. an opening balance that starts in debt.
+    Signed,
+}
+
+impl ValidationIssue {
+    pub fn new(field: &'static str, kind: ValidationIssueKind) -> Self {
+        Self { field, kind }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field = self.field;
+        match &self.kind {
+            ValidationIssueKind::Empty => write!(f, "{field} must not be empty"),
+            ValidationIssueKind::TooLong { max } => {
+                write!(f, "{field} must be at most {max} characters")
+            }
+            ValidationIssueKind::Duplicate { value } => {
+                write!(f, "{field} `{value}` already exists")
+            }
+            ValidationIssueKind::NotANumber => write!(f, "{field} must be a number"),
+            ValidationIssueKind::NotPositive => write!(f, "{field} must be greater than 0"),
+            ValidationIssueKind::Negative => write!(f, "{field} must be zero or positive"),
+            ValidationIssueKind::TooLarge { max } => {
+                write!(f, "{field} must not exceed {max:.0}")
+            }
+            ValidationIssueKind::DateOutOfRange { earliest, latest } => {
+                write!(f, "{field} must fall between {earliest} and {latest}")
+            }
+        }
+    }
+}
+
+/// Trims `value` and checks it is a usable name.
+pub fn check_name(field: &'static str, value: &str) -> Result<String, ValidationIssue> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(ValidationIssue::new(field, ValidationIssueKind::Empty));
+    }
+    if trimmed.chars().count() > MAX_NAME_LENGTH {
+        return Err(ValidationIssue::new(
+            field,
+            ValidationIssueKind::TooLong {
+                max: MAX_NAME_LENGTH,
+            },
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Trims an account or category icon; `None` when blank.
+pub fn check_icon(field: &'static str, value: &str) -> Result<Option<String>, ValidationIssue> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+    if trimmed.chars().count() > MAX_ICON_LENGTH
+        || trimmed
+            .chars()
+            .any(|ch| ch.is_whitespace() || ch.is_control())
+    {
+        return Err(ValidationIssue::new(
+            field,
+            ValidationIssueKind::TooLong {
+                max: MAX_ICON_LENGTH,
+            },
+        ));
+    }
+    Ok(Some(trimmed.to_string()))
+}
+
+/// Normalizes a transaction tag: trimmed, without a leading `#`, lowercase,
+/// and with runs of whitespace turned into `-`.
+pub fn check_tag(value: &str) -> Result<String, ValidationIssue> {
+    let tag = value
+        .trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    if tag.is_empty() {
+        return Err(ValidationIssue::new("tag", ValidationIssueKind::Empty));
+    }
+    if tag.chars().count() > MAX_TAG_LENGTH {
+        return Err(ValidationIssue::new(
+            "tag",
+            ValidationIssueKind::TooLong {
+                max: MAX_TAG_LENGTH,
+            },
+        ));
+    }
+    Ok(tag)
+}
+
+/// Checks `value` is a finite amount no larger than [`MAX_AMOUNT`] that
+/// `rule` allows.
+pub fn check_amount(
+    field: &'static str,
+    value: f64,
+    rule: AmountRule,
+) -> Result<f64, ValidationIssue> {
+    let kind = if !value.is_finite() {
+        ValidationIssueKind::NotANumber
+    } else if rule == AmountRule::Positive && value <= 0.0 {
+        ValidationIssueKind::NotPositive
+    } else if rule == AmountRule::NonNegative && value < 0.0 {
+        ValidationIssueKind::Negative
+    } else if value.abs() > MAX_AMOUNT {
+        ValidationIssueKind::TooLarge { max: MAX_AMOUNT }
+    } else {
+        return Ok(value);
+    };
+    Err(ValidationIssue::new(field, kind))
+}
+
+/// Checks `date` is within [`DATE_RANGE_YEARS`] of the ledger's creation,
+/// catching typos such as a year of 202 or 20025.
+pub fn check_date(
+    ledger: &Ledger,
+    field: &'static str,
+    date: NaiveDate,
+) -> Result<NaiveDate, ValidationIssue> {
+    let created = ledger.created_at.date_naive();
+    let span = Months::new(DATE_RANGE_YEARS * 12);
+    let earliest = created.checked_sub_months(span).unwrap_or(NaiveDate::MIN);
+    let latest = created.checked_add_months(span).unwrap_or(NaiveDate::MAX);
+    if date < earliest || date > latest {
+        return Err(ValidationIssue::new(
+            field,
+            ValidationIssueKind::DateOutOfRange { earliest, latest },
+        ));
+    }
+    Ok(date)
+}
@@ -0,0 +1,133 @@
+//! Management helpers for saved transaction views.
+
+use tracing::instrument;
+
+use bufy_domain::{
+    transaction::Transaction,
+    view::{SavedView, TransactionFilter, ViewColumn},
+    Ledger,
+};
+
+use crate::{transaction_service::TransactionService, Clock, CoreError};
+
+pub struct ViewService;
+
+impl ViewService {
+    /// Saves a view, replacing the filter and columns of an existing view with
+    /// the same name.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn save<'a>(
+        ledger: &'a mut Ledger,
+        name: impl Into<String>,
+        filter: TransactionFilter,
+        columns: Vec<ViewColumn>,
+        clock: &dyn Clock,
+    ) -> Result<&'a SavedView, CoreError> {
+        let name = name.into();
+        let trimmed = name.trim();
+        if trimmed.is_empty() {
+            return Err(CoreError::Validation("view name cannot be empty".into()));
+        }
+        let columns = if columns.is_empty() {
+            ViewColumn::default_columns()
+        } else {
+            columns
+        };
+        let now = clock.now();
+        let index = match ledger
+            .views
+            .iter()
+            .position(|view| view.name.eq_ignore_ascii_case(trimmed))
+        {
+            Some(index) => {
+                let view = &mut ledger.views[index];
+                view.filter = filter;
+                view.columns = columns;
+                view.updated_at = now;
+                index
+            }
+            None => {
+                let mut view = SavedView::new(trimmed, filter, columns);
+                view.created_at = now;
+                view.updated_at = now;
+                ledger.views.push(view);
+                ledger.views.len() - 1
+            }
+        };
+        ledger.touch();
+        Ok(&ledger.views[index])
+    }
+
+    /// Renames a view, rejecting names already used by another view.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn rename(
+        ledger: &mut Ledger,
+        name: &str,
+        new_name: &str,
+        clock: &dyn Clock,
+    ) -> Result<(), CoreError> {
+        let new_name = new_name.trim();
+        if new_name.is_empty() {
+            return Err(CoreError::Validation("view name cannot be empty".into()));
+        }
+        if !name.eq_ignore_ascii_case(new_name) && ledger.view(new_name).is_some() {
+            return Err(CoreError::Validation(format!(
+                "view `{}` already exists",
+                new_name
+            )));
+        }
+        let view = ledger
+            .view_mut(name)
+            .ok_or_else(|| CoreError::ViewNotFound(name.to_string()))?;
+        view.name = new_name.to_string();
+        view.updated_at = clock.now();
+        ledger.touch();
+        Ok(())
+    }
+
+    /// Removes a view, returning the removed definition.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn remove(ledger: &mut Ledger, name: &str) -> Result<SavedView, CoreError> {
+        let index = ledger
+            .views
+            .iter()
+            .position(|view| view.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| CoreError::ViewNotFound(name.to_string()))?;
+        let removed = ledger.views.remove(index);
+        ledger.touch();
+        Ok(removed)
+    }
+
+    /// Returns the view named `name` together with the transactions it selects.
+    #[instrument(
+        level = "debug",
+        skip_all,
+        fields(ledger = %ledger.id, revision = %ledger.updated_at.to_rfc3339()),
+        err(level = "debug")
+    )]
+    pub fn run<'a>(
+        ledger: &'a Ledger,
+        name: &str,
+    ) -> Result<(&'a SavedView, Vec<&'a Transaction>), CoreError> {
+        let view = ledger
+            .view(name)
+            .ok_or_else(|| CoreError::ViewNotFound(name.to_string()))?;
+        let query = TransactionService::resolve_filter(ledger, &view.filter)?;
+        Ok((view, TransactionService::query(ledger, &query)))
+    }
+}
@@ -2,10 +2,14 @@
 
 use std::fmt;
 
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::common::*;
+use crate::{
+    appearance::Appearance, common::*, ids::new_id, money::Money,
+    reconciliation::ReconciliationStatement,
+};
 
 /// Represents a financial account tracked within the ledger.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -17,25 +21,47 @@ pub struct Account {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub currency: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub opening_balance: Option<f64>,
+    /// Counted in the account's currency.
+    pub opening_balance: Option<Money>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
+    /// Statements reconciled or being reconciled, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub statements: Vec<ReconciliationStatement>,
+    /// Starred accounts sort ahead of the rest in lists and pickers.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,
+    #[serde(default, skip_serializing_if = "AccountStatus::is_active")]
+    pub status: AccountStatus,
+    #[serde(default, skip_serializing_if = "Appearance::is_empty")]
+    pub appearance: Appearance,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl Account {
     /// Creates a new account with the provided kind and optional linked category.
     pub fn new(name: impl Into<String>, kind: AccountKind) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: new_id(),
             name: name.into(),
             kind,
             category_id: None,
             currency: None,
             opening_balance: None,
             notes: None,
+            statements: Vec::new(),
+            favorite: false,
+            status: AccountStatus::Active,
+            appearance: Appearance::default(),
+            extra: ExtraFields::new(),
         }
     }
 
+    pub fn is_closed(&self) -> bool {
+        !self.status.is_active()
+    }
+
     /// Links the account to a category identifier.
     pub fn with_category(mut self, category_id: Uuid) -> Self {
         self.category_id = Some(category_id);
@@ -61,6 +87,24 @@ impl Displayable for Account {
     }
 }
 
+/// Whether an account still takes new transactions. Closed accounts keep
+/// their history but are hidden from lists by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum AccountStatus {
+    #[default]
+    Active,
+    Closed {
+        on: NaiveDate,
+    },
+}
+
+impl AccountStatus {
+    pub fn is_active(&self) -> bool {
+        matches!(self, AccountStatus::Active)
+    }
+}
+
 /// Enumerates the supported account classifications.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum AccountKind {
@@ -0,0 +1,63 @@
+//! Budget alerts surfaced to frontends as badges or warnings.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    /// A category has spent more than its budget in the current period.
+    OverBudget,
+    /// An account holding money fell below the low-balance threshold.
+    LowBalance,
+    /// A recurring transaction has occurrences past due and not completed.
+    OverdueRecurrence,
+    /// A sinking fund holds less than it should for its next bill.
+    UnderfundedSinkingFund,
+    /// The last day to give notice on a recurring contract is near.
+    ContractNotice,
+}
+
+/// A single active alert, identifying the entity it concerns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BudgetAlert {
+    pub kind: AlertKind,
+    /// Category, account, recurrence series, or sinking fund the alert refers to.
+    pub subject_id: Uuid,
+    pub subject: String,
+    pub message: String,
+}
+
+/// Number of active alerts per kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AlertCounts {
+    pub over_budget: usize,
+    pub low_balance: usize,
+    pub overdue_recurrences: usize,
+    pub underfunded_sinking_funds: usize,
+    pub contract_notices: usize,
+}
+
+impl AlertCounts {
+    pub fn from_alerts(alerts: &[BudgetAlert]) -> Self {
+        let mut counts = Self::default();
+        for alert in alerts {
+            match alert.kind {
+                AlertKind::OverBudget => counts.over_budget += 1,
+                AlertKind::LowBalance => counts.low_balance += 1,
+                AlertKind::OverdueRecurrence => counts.overdue_recurrences += 1,
+                AlertKind::UnderfundedSinkingFund => counts.underfunded_sinking_funds += 1,
+                AlertKind::ContractNotice => counts.contract_notices += 1,
+            }
+        }
+        counts
+    }
+
+    pub fn total(&self) -> usize {
+        self.over_budget
+            + self.low_balance
+            + self.overdue_recurrences
+            + self.underfunded_sinking_funds
+            + self.contract_notices
+    }
+}
@@ -0,0 +1,60 @@
+//! Overflow checks for money totals.
+//!
+//! Amounts are `f64`, which holds every cent exactly only up to 2^53 cents.
+//! Totals past that, or ones that become infinite, are reported instead of
+//! being rounded silently.
+
+use std::fmt;
+
+/// Largest magnitude at which every cent is still exact: 2^53 cents.
+pub const MAX_EXACT_AMOUNT: f64 = 90_071_992_547_409.92;
+
+/// A total that left the range where cents are exact.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmountOverflow {
+    /// Which total overflowed, such as `budgeted` or `projected inflow`.
+    pub total: &'static str,
+    pub value: f64,
+}
+
+impl fmt::Display for AmountOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} total {} is beyond ±{:.2}, where cents are no longer exact",
+            self.total, self.value, MAX_EXACT_AMOUNT
+        )
+    }
+}
+
+impl std::error::Error for AmountOverflow {}
+
+/// Whether `value` is finite and small enough to keep every cent.
+pub fn is_exact_amount(value: f64) -> bool {
+    value.is_finite() && value.abs() <= MAX_EXACT_AMOUNT
+}
+
+/// Checks `value` as the `total` it names.
+pub fn check_amount(total: &'static str, value: f64) -> Result<f64, AmountOverflow> {
+    if is_exact_amount(value) {
+        Ok(value)
+    } else {
+        Err(AmountOverflow { total, value })
+    }
+}
+
+/// `a + b`, unless the sum leaves the exact range.
+pub fn checked_add(total: &'static str, a: f64, b: f64) -> Result<f64, AmountOverflow> {
+    check_amount(total, a + b)
+}
+
+/// Sums `amounts`, failing at the first running total that leaves the
+/// exact range.
+pub fn checked_sum<I>(total: &'static str, amounts: I) -> Result<f64, AmountOverflow>
+where
+    I: IntoIterator<Item = f64>,
+{
+    amounts
+        .into_iter()
+        .try_fold(0.0, |sum, amount| checked_add(total, sum, amount))
+}
@@ -0,0 +1,69 @@
+//! Colors and icons that mark accounts and categories in listings.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// One of the basic terminal colors, so every theme can show it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl EntityColor {
+    pub const ALL: [EntityColor; 7] = [
+        EntityColor::Red,
+        EntityColor::Green,
+        EntityColor::Yellow,
+        EntityColor::Blue,
+        EntityColor::Magenta,
+        EntityColor::Cyan,
+        EntityColor::White,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::ALL
+            .into_iter()
+            .find(|color| color.to_string().eq_ignore_ascii_case(value))
+    }
+}
+
+impl fmt::Display for EntityColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            EntityColor::Red => "red",
+            EntityColor::Green => "green",
+            EntityColor::Yellow => "yellow",
+            EntityColor::Blue => "blue",
+            EntityColor::Magenta => "magenta",
+            EntityColor::Cyan => "cyan",
+            EntityColor::White => "white",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Color and icon shown with an account or category name. Frontends drop
+/// either when the output cannot carry it, e.g. the icon in plain mode.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Appearance {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<EntityColor>,
+    /// A short symbol, usually one emoji.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+impl Appearance {
+    pub fn is_empty(&self) -> bool {
+        self.color.is_none() && self.icon.is_none()
+    }
+}
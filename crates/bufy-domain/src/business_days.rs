@@ -0,0 +1,101 @@
+//! Business days for schedules.
+//!
+//! A recurrence can ask for occurrences that fall on a weekend to move to
+//! the nearest business day, the way banks settle standing orders. The
+//! weekend comes from the ledger's locale, so regions with a Friday and
+//! Saturday weekend shift on those days instead, and imported public
+//! holidays are skipped alongside it.
+
+use std::fmt;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::holiday::HolidayCalendar;
+
+/// Longest run of non-business days a shift walks past before giving up.
+const MAX_SHIFT_DAYS: usize = 366;
+
+/// Where a recurrence moves an occurrence that is not a business day.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum BusinessDayShift {
+    /// Keep the scheduled date.
+    #[default]
+    Keep,
+    /// Move forward to the next business day.
+    Following,
+    /// Move back to the previous business day.
+    Preceding,
+}
+
+impl BusinessDayShift {
+    pub fn is_keep(&self) -> bool {
+        matches!(self, BusinessDayShift::Keep)
+    }
+}
+
+impl fmt::Display for BusinessDayShift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            BusinessDayShift::Keep => "Keep",
+            BusinessDayShift::Following => "Following",
+            BusinessDayShift::Preceding => "Preceding",
+        };
+        f.write_str(label)
+    }
+}
+
+/// The days that count as business days.
+#[derive(Debug, Clone, Copy)]
+pub struct BusinessCalendar<'a> {
+    weekend: &'a [Weekday],
+    holidays: Option<&'a HolidayCalendar>,
+}
+
+impl<'a> BusinessCalendar<'a> {
+    /// A calendar where every day is a business day.
+    pub const EVERY_DAY: BusinessCalendar<'static> = BusinessCalendar {
+        weekend: &[],
+        holidays: None,
+    };
+
+    pub fn new(weekend: &'a [Weekday]) -> Self {
+        Self {
+            weekend,
+            holidays: None,
+        }
+    }
+
+    /// Also treats the days in `holidays` as non-business days.
+    pub fn with_holidays(self, holidays: &'a HolidayCalendar) -> Self {
+        Self {
+            holidays: Some(holidays),
+            ..self
+        }
+    }
+
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !self.weekend.contains(&date.weekday())
+            && !self
+                .holidays
+                .is_some_and(|holidays| holidays.is_holiday(date))
+    }
+
+    /// Moves `date` as `shift` asks, leaving business days untouched. A
+    /// calendar without business days leaves every date alone.
+    pub fn shift(&self, date: NaiveDate, shift: BusinessDayShift) -> NaiveDate {
+        let step = match shift {
+            BusinessDayShift::Keep => return date,
+            BusinessDayShift::Following => Duration::days(1),
+            BusinessDayShift::Preceding => Duration::days(-1),
+        };
+        let mut current = date;
+        for _ in 0..MAX_SHIFT_DAYS {
+            if self.is_business_day(current) {
+                return current;
+            }
+            current += step;
+        }
+        date
+    }
+}
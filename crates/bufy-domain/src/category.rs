@@ -2,11 +2,11 @@
 
 use std::fmt;
 
-use chrono::NaiveDate;
+use chrono::{Months, NaiveDate};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::common::*;
+use crate::{appearance::Appearance, common::*, ids::new_id, ledger::DateWindow, money::Money};
 
 /// Categorises ledger activity for budgeting and reporting.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -20,18 +20,46 @@ pub struct Category {
     pub notes: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub budget: Option<CategoryBudgetDefinition>,
+    /// Hidden categories stay out of budget summaries and pickers.
+    #[serde(default)]
+    pub hidden: bool,
+    /// Marks a category created automatically for an internal flow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<SystemCategory>,
+    /// Starred categories sort ahead of the rest in lists and pickers.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub favorite: bool,
+    #[serde(default, skip_serializing_if = "Appearance::is_empty")]
+    pub appearance: Appearance,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl Category {
     pub fn new(name: impl Into<String>, kind: CategoryKind) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: new_id(),
             name: name.into(),
             kind,
             parent_id: None,
             is_custom: true,
             notes: None,
             budget: None,
+            hidden: false,
+            system: None,
+            favorite: false,
+            appearance: Appearance::default(),
+            extra: ExtraFields::new(),
+        }
+    }
+
+    /// Builds the hidden category backing an internal flow.
+    pub fn system(role: SystemCategory) -> Self {
+        Self {
+            is_custom: false,
+            hidden: true,
+            system: Some(role),
+            ..Self::new(role.default_name(), CategoryKind::Transfer)
         }
     }
 
@@ -45,17 +73,26 @@ impl Category {
         self.budget.is_some()
     }
 
-    /// Assigns a budget using primitive values, overwriting prior data.
+    /// Assigns a budget using primitive values, overwriting prior data
+    /// except the proration rule and note.
     pub fn set_budget(
         &mut self,
-        amount: f64,
+        amount: Money,
         period: BudgetPeriod,
         reference_date: Option<NaiveDate>,
     ) {
+        let (proration, note) = self
+            .budget
+            .take()
+            .map(|budget| (budget.proration, budget.note))
+            .unwrap_or_default();
         self.budget = Some(CategoryBudgetDefinition {
             amount,
             period,
             reference_date,
+            proration,
+            note,
+            extra: ExtraFields::new(),
         });
     }
 
@@ -88,21 +125,75 @@ impl Displayable for Category {
     }
 }
 
+/// A named set of categories, such as "Essentials", budgeted and reported
+/// together. A category belongs to at most one group.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CategoryGroup {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub category_ids: Vec<Uuid>,
+    /// Limit on the members' combined spending, on top of their own budgets.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<CategoryBudgetDefinition>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl CategoryGroup {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            id: new_id(),
+            name: name.into(),
+            category_ids: Vec::new(),
+            budget: None,
+            extra: ExtraFields::new(),
+        }
+    }
+
+    pub fn contains(&self, category_id: Uuid) -> bool {
+        self.category_ids.contains(&category_id)
+    }
+}
+
+impl Identifiable for CategoryGroup {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+impl NamedEntity for CategoryGroup {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
 /// Budget settings attached directly to a category.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CategoryBudgetDefinition {
-    pub amount: f64,
+    /// Counted in the ledger's base currency.
+    pub amount: Money,
     pub period: BudgetPeriod,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reference_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub proration: BudgetProration,
+    /// Short rationale for the amount, such as "includes gym + sports gear".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl CategoryBudgetDefinition {
-    pub fn new(amount: f64, period: BudgetPeriod) -> Self {
+    pub fn new(amount: Money, period: BudgetPeriod) -> Self {
         Self {
             amount,
             period,
             reference_date: None,
+            proration: BudgetProration::default(),
+            note: None,
+            extra: ExtraFields::new(),
         }
     }
 
@@ -110,6 +201,136 @@ impl CategoryBudgetDefinition {
         self.reference_date = Some(reference_date);
         self
     }
+
+    pub fn with_proration(mut self, proration: BudgetProration) -> Self {
+        self.proration = proration;
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    /// Budget available in `window` under the definition's proration rule.
+    ///
+    /// Budget periods start at the reference date when one is set; otherwise
+    /// months and years start on the 1st, weeks on Monday. A prorated
+    /// amount is rounded once to the nearest minor unit.
+    pub fn amount_for_window(&self, window: DateWindow) -> Money {
+        let minor = match self.proration {
+            BudgetProration::Strict => return self.amount.clone(),
+            BudgetProration::Prorate => {
+                let periods: f64 = self
+                    .periods_overlapping(window)
+                    .map(|(start, end)| {
+                        let overlap = end.min(window.end) - start.max(window.start);
+                        overlap.num_days() as f64 / (end - start).num_days() as f64
+                    })
+                    .sum();
+                (self.amount.minor() as f64 * periods).round() as i64
+            }
+            BudgetProration::AlignToLedger => {
+                let starts = self
+                    .periods_overlapping(window)
+                    .filter(|(start, _)| window.contains(*start))
+                    .count();
+                self.amount
+                    .minor()
+                    .saturating_mul(i64::try_from(starts).unwrap_or(i64::MAX))
+            }
+        };
+        Money::from_minor(minor, self.amount.currency().clone())
+    }
+
+    /// Budget periods `[start, end)` that intersect `window`, in order.
+    fn periods_overlapping(
+        &self,
+        window: DateWindow,
+    ) -> impl Iterator<Item = (NaiveDate, NaiveDate)> + '_ {
+        let anchor = self.reference_date.unwrap_or_else(|| {
+            let monday = matches!(self.period, BudgetPeriod::Weekly);
+            NaiveDate::from_ymd_opt(2000, 1, if monday { 3 } else { 1 }).expect("valid anchor")
+        });
+        let nominal = i64::from(self.period.days().unwrap_or(1));
+        let mut index = (window.start - anchor).num_days().div_euclid(nominal);
+        while self.period_start(anchor, index) > window.start {
+            index -= 1;
+        }
+        while self.period_start(anchor, index + 1) <= window.start {
+            index += 1;
+        }
+        std::iter::successors(Some(index), |index| Some(index + 1))
+            .map(move |index| {
+                (
+                    self.period_start(anchor, index),
+                    self.period_start(anchor, index + 1),
+                )
+            })
+            .take_while(move |(start, _)| *start < window.end)
+    }
+
+    fn period_start(&self, anchor: NaiveDate, index: i64) -> NaiveDate {
+        let months = match self.period {
+            BudgetPeriod::Monthly => Some(index),
+            BudgetPeriod::Yearly => Some(index * 12),
+            _ => None,
+        };
+        match months {
+            Some(months) if months >= 0 => anchor.checked_add_months(Months::new(months as u32)),
+            Some(months) => anchor.checked_sub_months(Months::new(months.unsigned_abs() as u32)),
+            None => anchor.checked_add_signed(chrono::Duration::days(
+                index * i64::from(self.period.days().unwrap_or(1)),
+            )),
+        }
+        .unwrap_or(anchor)
+    }
+}
+
+/// How a category budget is scaled when its period differs from the
+/// summary window.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetProration {
+    /// Each budget period contributes in proportion to the days it shares
+    /// with the window, e.g. a weekly budget counts 31/7 times in January.
+    #[default]
+    Prorate,
+    /// Each budget period counts in full in the window where it starts, so
+    /// a weekly budget counts four or five times in a month.
+    AlignToLedger,
+    /// The amount applies to any window unchanged.
+    Strict,
+}
+
+impl fmt::Display for BudgetProration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            BudgetProration::Prorate => "prorate",
+            BudgetProration::AlignToLedger => "align-to-ledger",
+            BudgetProration::Strict => "strict",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Internal flows that book against a dedicated hidden category.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemCategory {
+    OpeningBalance,
+    FxAdjustment,
+    Rounding,
+}
+
+impl SystemCategory {
+    pub fn default_name(self) -> &'static str {
+        match self {
+            SystemCategory::OpeningBalance => "Opening Balances",
+            SystemCategory::FxAdjustment => "FX Adjustments",
+            SystemCategory::Rounding => "Rounding Differences",
+        }
+    }
 }
 
 /// Supported category types.
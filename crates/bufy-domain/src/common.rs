@@ -2,10 +2,15 @@
 
 use std::fmt;
 
-use chrono::{Datelike, Duration, NaiveDate};
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Fields a stored record carries that this version does not know about,
+/// typically written by a newer BUFY. Captured on load and written back
+/// unchanged on save so older installs do not strip newer data.
+pub type ExtraFields = serde_json::Map<String, serde_json::Value>;
+
 /// Exposes a stable identifier for entities stored in the ledger.
 pub trait Identifiable {
     fn id(&self) -> Uuid;
@@ -95,6 +100,17 @@ impl TimeInterval {
         }
     }
 
+    /// Like [`TimeInterval::next_date`], but `None` instead of panicking
+    /// past the last date chrono can represent.
+    pub fn checked_next_date(&self, from: NaiveDate) -> Option<NaiveDate> {
+        match self.unit {
+            TimeUnit::Day => from.checked_add_signed(Duration::days(self.every as i64)),
+            TimeUnit::Week => from.checked_add_signed(Duration::weeks(self.every as i64)),
+            TimeUnit::Month => from.checked_add_months(Months::new(self.every)),
+            TimeUnit::Year => from.checked_add_months(Months::new(self.every.checked_mul(12)?)),
+        }
+    }
+
     pub fn previous_date(&self, from: NaiveDate) -> NaiveDate {
         match self.unit {
             TimeUnit::Day => from - Duration::days(self.every as i64),
@@ -116,10 +132,20 @@ impl TimeInterval {
     }
 
     pub fn normalize_anchor(&self, date: NaiveDate) -> NaiveDate {
+        self.normalize_anchor_with_week_start(date, Weekday::Mon)
+    }
+
+    /// Aligns `date` to the start of its period, treating `week_start` as the
+    /// first day of weekly periods.
+    pub fn normalize_anchor_with_week_start(
+        &self,
+        date: NaiveDate,
+        week_start: Weekday,
+    ) -> NaiveDate {
         match self.unit {
             TimeUnit::Day => date,
             TimeUnit::Week => {
-                let delta = date.weekday().num_days_from_monday() as i64;
+                let delta = days_since_week_start(date, week_start);
                 date - Duration::days(delta)
             }
             TimeUnit::Month => {
@@ -174,6 +200,13 @@ impl fmt::Display for BudgetPeriod {
     }
 }
 
+/// Number of days between the most recent `week_start` (inclusive) and `date`.
+pub fn days_since_week_start(date: NaiveDate, week_start: Weekday) -> i64 {
+    let current = date.weekday().num_days_from_monday() as i64;
+    let start = week_start.num_days_from_monday() as i64;
+    (current - start).rem_euclid(7)
+}
+
 fn cycle_start_linear(anchor: NaiveDate, reference: NaiveDate, interval_days: i64) -> NaiveDate {
     let diff = reference - anchor;
     let steps = diff.num_days().div_euclid(interval_days);
@@ -1,6 +1,11 @@
-use chrono::{Datelike, NaiveDate, Weekday};
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
 use serde::{Deserialize, Serialize};
 
+use crate::business_days::BusinessCalendar;
+use crate::common::{days_since_week_start, ExtraFields};
+use crate::iso4217::CurrencyData;
+use crate::locale::LocaleData;
+
 /// ISO 4217 currency representation.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct CurrencyCode(pub String);
@@ -29,6 +34,17 @@ pub struct LocaleConfig {
     pub grouping_separator: char,
     pub date_format: DateFormatStyle,
     pub first_weekday: Weekday,
+    #[serde(default = "LocaleConfig::default_weekend")]
+    pub weekend: Vec<Weekday>,
+    /// Where the currency symbol goes; `None` follows the language tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_position: Option<SymbolPosition>,
+    /// Whether a space separates symbol and number; `None` follows the
+    /// language tag.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol_spacing: Option<bool>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl Default for LocaleConfig {
@@ -39,16 +55,71 @@ impl Default for LocaleConfig {
             grouping_separator: ',',
             date_format: DateFormatStyle::Medium,
             first_weekday: Weekday::Mon,
+            weekend: Self::default_weekend(),
+            symbol_position: None,
+            symbol_spacing: None,
+            extra: ExtraFields::new(),
         }
     }
 }
 
+impl LocaleConfig {
+    pub fn default_weekend() -> Vec<Weekday> {
+        vec![Weekday::Sat, Weekday::Sun]
+    }
+
+    /// The symbol position, from the override or the language tag's
+    /// convention, defaulting to a prefix.
+    pub fn resolved_symbol_position(&self) -> SymbolPosition {
+        self.symbol_position
+            .or_else(|| LocaleData::lookup(&self.language_tag).map(|data| data.symbol_position))
+            .unwrap_or_default()
+    }
+
+    /// Whether a space separates symbol and number, from the override or
+    /// the language tag's convention.
+    pub fn resolved_symbol_spacing(&self) -> bool {
+        self.symbol_spacing
+            .or_else(|| LocaleData::lookup(&self.language_tag).map(|data| data.symbol_spacing))
+            .unwrap_or(false)
+    }
+
+    /// Returns the first day of the locale week containing `date`.
+    pub fn week_start(&self, date: NaiveDate) -> NaiveDate {
+        date - Duration::days(days_since_week_start(date, self.first_weekday))
+    }
+
+    /// Returns the locale weekdays in display order, starting at `first_weekday`.
+    pub fn ordered_weekdays(&self) -> Vec<Weekday> {
+        let mut day = self.first_weekday;
+        (0..7)
+            .map(|_| {
+                let current = day;
+                day = day.succ();
+                current
+            })
+            .collect()
+    }
+
+    pub fn is_weekend(&self, date: NaiveDate) -> bool {
+        self.weekend.contains(&date.weekday())
+    }
+
+    /// Business days under this locale's weekend. Holidays are added by
+    /// [`crate::Ledger::business_calendar`], which date shifting goes through.
+    pub fn business_calendar(&self) -> BusinessCalendar<'_> {
+        BusinessCalendar::new(&self.weekend)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FormatOptions {
     pub currency_display: CurrencyDisplay,
     pub negative_style: NegativeStyle,
     pub screen_reader_mode: bool,
     pub high_contrast_mode: bool,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl Default for FormatOptions {
@@ -58,6 +129,7 @@ impl Default for FormatOptions {
             negative_style: NegativeStyle::Sign,
             screen_reader_mode: false,
             high_contrast_mode: false,
+            extra: ExtraFields::new(),
         }
     }
 }
@@ -81,7 +153,15 @@ pub enum CurrencyDisplay {
     SymbolAndCode,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// Which side of the number the currency symbol is written on.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SymbolPosition {
+    #[default]
+    Prefix,
+    Suffix,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum DateFormatStyle {
     Short,
     Medium,
@@ -132,25 +212,18 @@ pub fn policy_date(
     }
 }
 
+/// Display symbol for `code`, or the code itself when it has none.
 pub fn symbol_for(code: &str) -> String {
-    match code {
-        "USD" => "$".into(),
-        "EUR" => "€".into(),
-        "GBP" => "£".into(),
-        "JPY" => "¥".into(),
-        "CAD" => "CAD".into(),
-        "AUD" => "A$".into(),
-        "CHF" => "CHF".into(),
-        _ => code.into(),
-    }
+    CurrencyData::lookup(code)
+        .map(|data| data.symbol.to_string())
+        .unwrap_or_else(|| code.to_string())
 }
 
+/// Digits after the decimal point for `code`; unknown codes use 2.
 pub fn minor_units_for(code: &str) -> u8 {
-    match code {
-        "JPY" => 0,
-        "KWD" | "BHD" => 3,
-        _ => 2,
-    }
+    CurrencyData::lookup(code)
+        .map(|data| data.minor_units)
+        .unwrap_or(2)
 }
 
 pub fn format_number(locale: &LocaleConfig, value: f64, precision: u8) -> String {
@@ -170,6 +243,55 @@ pub fn format_number(locale: &LocaleConfig, value: f64, precision: u8) -> String
     body
 }
 
+/// Reads a number typed the way `locale` writes it (`1.234,56` in de-DE),
+/// also accepting plain `1234.56` so scripts work under any locale. Grouping
+/// separators must sit between groups of three digits; spaces are ignored.
+pub fn parse_number(locale: &LocaleConfig, input: &str) -> Option<f64> {
+    let cleaned: String = input
+        .trim()
+        .chars()
+        .filter(|ch| !matches!(ch, ' ' | '\u{a0}' | '\u{202f}'))
+        .collect();
+    parse_localized(locale, &cleaned)
+        .or_else(|| cleaned.parse::<f64>().ok())
+        .filter(|value| value.is_finite())
+}
+
+fn parse_localized(locale: &LocaleConfig, text: &str) -> Option<f64> {
+    let (sign, body) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (whole, fraction) = match body.split_once(locale.decimal_separator) {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (body, None),
+    };
+    let mut groups = whole.split(locale.grouping_separator);
+    let first = groups.next()?;
+    let digits = |part: &str| part.chars().all(|ch| ch.is_ascii_digit());
+    let grouped = whole.contains(locale.grouping_separator);
+    if first.is_empty() && fraction.is_none()
+        || !digits(first)
+        || grouped && (first.is_empty() || first.len() > 3)
+        || !groups.all(|group| group.len() == 3 && digits(group))
+        || fraction.is_some_and(|part| part.is_empty() || !digits(part))
+    {
+        return None;
+    }
+    let integer: String = whole
+        .chars()
+        .filter(|ch| *ch != locale.grouping_separator)
+        .collect();
+    format!(
+        "{}{}.{}",
+        sign,
+        if integer.is_empty() { "0" } else { &integer },
+        fraction.unwrap_or("0")
+    )
+    .parse()
+    .ok()
+}
+
 fn insert_grouping(int_part: &mut String, separator: char) {
     let mut cleaned = int_part.replace(separator, "");
     if cleaned.starts_with('-') {
@@ -209,15 +331,15 @@ pub fn format_currency_value_with_precision(
         };
     }
     let symbol = symbol_for(code.as_str());
-    let mut rendered_body = body.clone();
-    if rendered_body.starts_with('(') {
-        rendered_body = format!(" {}", body);
-    }
     let formatted = match options.currency_display {
-        CurrencyDisplay::Symbol => format!("{}{}", symbol, rendered_body),
+        CurrencyDisplay::Symbol => place_symbol(locale, &symbol, &body),
         CurrencyDisplay::Code => format!("{} {}", code.as_str(), body),
         CurrencyDisplay::SymbolAndCode => {
-            format!("{} {} ({})", symbol, rendered_body, code.as_str())
+            format!(
+                "{} ({})",
+                place_symbol(locale, &symbol, &body),
+                code.as_str()
+            )
         }
     };
     if options.screen_reader_mode {
@@ -237,6 +359,18 @@ pub fn format_currency_value_with_precision(
     }
 }
 
+/// Joins `symbol` and the formatted number the way `locale` writes them:
+/// `$1,234.56`, `1.234,56 €`, `€ 1.234,56`. A parenthesized negative always
+/// gets a space so the symbol does not touch the bracket.
+fn place_symbol(locale: &LocaleConfig, symbol: &str, body: &str) -> String {
+    let spaced = locale.resolved_symbol_spacing() || body.starts_with('(');
+    let gap = if spaced { " " } else { "" };
+    match locale.resolved_symbol_position() {
+        SymbolPosition::Prefix => format!("{}{}{}", symbol, gap, body),
+        SymbolPosition::Suffix => format!("{}{}{}", body, gap, symbol),
+    }
+}
+
 pub fn format_currency_value(
     amount: f64,
     code: &CurrencyCode,
@@ -0,0 +1,91 @@
+//! Exchange rates fixed by the user for a date range, such as a contract
+//! rate agreed for a quarter.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{common::ExtraFields, currency::CurrencyCode, ids::new_id};
+
+/// One `from` unit is worth `rate` units of `to` on every day from
+/// `valid_from` through `valid_until`. The rate also converts the other way
+/// at `1 / rate`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeRateOverride {
+    pub id: Uuid,
+    pub from: CurrencyCode,
+    pub to: CurrencyCode,
+    pub rate: f64,
+    pub valid_from: NaiveDate,
+    /// Last day the rate applies; `None` keeps it in force indefinitely.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl ExchangeRateOverride {
+    pub fn new(
+        from: CurrencyCode,
+        to: CurrencyCode,
+        rate: f64,
+        valid_from: NaiveDate,
+        valid_until: Option<NaiveDate>,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: new_id(),
+            from,
+            to,
+            rate,
+            valid_from,
+            valid_until,
+            note: None,
+            created_at,
+            extra: ExtraFields::new(),
+        }
+    }
+
+    pub fn covers(&self, date: NaiveDate) -> bool {
+        date >= self.valid_from && self.valid_until.is_none_or(|until| date <= until)
+    }
+
+    /// Whether this rate converts between `a` and `b`, in either direction.
+    pub fn links(&self, a: &CurrencyCode, b: &CurrencyCode) -> bool {
+        (&self.from == a && &self.to == b) || (&self.from == b && &self.to == a)
+    }
+
+    /// Whether both rates link the same currencies on at least one shared day.
+    pub fn overlaps(&self, other: &ExchangeRateOverride) -> bool {
+        let starts_before_other_ends = other
+            .valid_until
+            .is_none_or(|until| self.valid_from <= until);
+        let ends_after_other_starts = self
+            .valid_until
+            .is_none_or(|until| other.valid_from <= until);
+        self.links(&other.from, &other.to) && starts_before_other_ends && ends_after_other_starts
+    }
+
+    /// Units of `to` per unit of `from`, inverting the stored rate when the
+    /// override is recorded the other way round.
+    pub fn rate_between(&self, from: &CurrencyCode, to: &CurrencyCode) -> Option<f64> {
+        if &self.from == from && &self.to == to {
+            Some(self.rate)
+        } else if &self.from == to && &self.to == from {
+            Some(1.0 / self.rate)
+        } else {
+            None
+        }
+    }
+
+    /// Validity range such as `2025-01-01 to 2025-03-31` or `from 2025-01-01`.
+    pub fn validity_label(&self) -> String {
+        match self.valid_until {
+            Some(until) => format!("{} to {}", self.valid_from, until),
+            None => format!("from {}", self.valid_from),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Savings goals: a target amount to reach by a date, tracked through the
+//! balance of a linked account or the money recorded under a linked category.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    common::{ExtraFields, TimeInterval},
+    ids::new_id,
+};
+
+/// Where a goal's progress is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "id")]
+pub enum GoalLink {
+    /// Progress is the balance of this account.
+    Account(Uuid),
+    /// Progress is the completed money recorded under this category.
+    Category(Uuid),
+}
+
+/// A planned contribution of `amount` every `interval`, starting on `start`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingSchedule {
+    pub amount: f64,
+    pub interval: TimeInterval,
+    pub start: NaiveDate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Goal {
+    pub id: Uuid,
+    pub name: String,
+    pub target_amount: f64,
+    pub target_date: NaiveDate,
+    pub link: GoalLink,
+    /// Planned contributions, used to project when the goal is reached.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub funding: Option<FundingSchedule>,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl Goal {
+    pub fn new(
+        name: impl Into<String>,
+        target_amount: f64,
+        target_date: NaiveDate,
+        link: GoalLink,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: new_id(),
+            name: name.into(),
+            target_amount,
+            target_date,
+            link,
+            funding: None,
+            created_at,
+            extra: ExtraFields::new(),
+        }
+    }
+}
+
+/// A scheduled goal contribution falling inside a forecast window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoalContribution {
+    pub goal_id: Uuid,
+    pub name: String,
+    pub date: NaiveDate,
+    pub amount: f64,
+}
@@ -0,0 +1,367 @@
+//! Public holidays that count as non-business days.
+//!
+//! A ledger keeps one [`HolidayCalendar`], filled from a built-in country
+//! table or an imported iCalendar file. Country tables are rules, so they
+//! can be expanded to any year; the calendar stores the expanded dates and
+//! the last year it covers, and a yearly refresh extends it.
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// One non-business day.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+impl Holiday {
+    pub fn new(date: NaiveDate, name: impl Into<String>) -> Self {
+        Self {
+            date,
+            name: name.into(),
+        }
+    }
+}
+
+/// Where a calendar's holidays came from, so a refresh can fetch them again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HolidaySource {
+    /// A built-in table, by ISO 3166 alpha-2 code.
+    Country(String),
+    /// An iCalendar file, by the path it was imported from.
+    File(String),
+}
+
+impl HolidaySource {
+    pub fn label(&self) -> String {
+        match self {
+            HolidaySource::Country(code) => match HolidayCountry::lookup(code) {
+                Some(country) => format!("{} ({})", country.name, country.code),
+                None => code.clone(),
+            },
+            HolidaySource::File(path) => path.clone(),
+        }
+    }
+}
+
+/// The ledger's holidays, sorted by date.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HolidayCalendar {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<HolidaySource>,
+    /// Last year the calendar is complete through.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub through_year: Option<i32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub holidays: Vec<Holiday>,
+}
+
+impl HolidayCalendar {
+    pub fn is_empty(&self) -> bool {
+        self.source.is_none() && self.holidays.is_empty()
+    }
+
+    /// Replaces the holidays, keeping them sorted with one entry per day.
+    pub fn set_holidays(&mut self, mut holidays: Vec<Holiday>) {
+        holidays.sort_by_key(|holiday| holiday.date);
+        holidays.dedup_by_key(|holiday| holiday.date);
+        self.holidays = holidays;
+    }
+
+    pub fn holiday_on(&self, date: NaiveDate) -> Option<&Holiday> {
+        self.holidays
+            .binary_search_by_key(&date, |holiday| holiday.date)
+            .ok()
+            .map(|index| &self.holidays[index])
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holiday_on(date).is_some()
+    }
+
+    pub fn in_year(&self, year: i32) -> impl Iterator<Item = &Holiday> {
+        self.holidays
+            .iter()
+            .filter(move |holiday| holiday.date.year() == year)
+    }
+
+    /// Whether the calendar stops before the end of next year, so business
+    /// days looked up a year ahead could miss holidays.
+    pub fn needs_refresh(&self, today: NaiveDate) -> bool {
+        self.source.is_some() && self.through_year.is_none_or(|year| year < today.year() + 1)
+    }
+}
+
+/// A built-in country table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HolidayCountry {
+    pub code: &'static str,
+    pub name: &'static str,
+    rules: &'static [HolidayRule],
+    substitute: Substitute,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HolidayRule {
+    name: &'static str,
+    date: RuleDate,
+    /// First year the holiday was observed.
+    since: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleDate {
+    /// Month and day.
+    Fixed(u32, u32),
+    /// Days from Easter Sunday.
+    Easter(i64),
+    /// The `n`th weekday of a month, counted from the end when negative.
+    Nth(u32, Weekday, i8),
+}
+
+/// What happens to a holiday that falls on a weekend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Substitute {
+    /// Nothing; the weekend day is the holiday.
+    None,
+    /// Sunday holidays move to Monday; Saturday ones are not replaced.
+    SundayToMonday,
+    /// Weekend holidays move to the next weekday that is not already one.
+    NextWeekday,
+}
+
+const fn fixed(name: &'static str, month: u32, day: u32) -> HolidayRule {
+    HolidayRule {
+        name,
+        date: RuleDate::Fixed(month, day),
+        since: i32::MIN,
+    }
+}
+
+const fn easter(name: &'static str, offset: i64) -> HolidayRule {
+    HolidayRule {
+        name,
+        date: RuleDate::Easter(offset),
+        since: i32::MIN,
+    }
+}
+
+const fn nth(name: &'static str, month: u32, weekday: Weekday, n: i8) -> HolidayRule {
+    HolidayRule {
+        name,
+        date: RuleDate::Nth(month, weekday, n),
+        since: i32::MIN,
+    }
+}
+
+const fn since(rule: HolidayRule, year: i32) -> HolidayRule {
+    HolidayRule {
+        since: year,
+        ..rule
+    }
+}
+
+const US: &[HolidayRule] = &[
+    fixed("New Year's Day", 1, 1),
+    nth("Martin Luther King Jr. Day", 1, Weekday::Mon, 3),
+    nth("Washington's Birthday", 2, Weekday::Mon, 3),
+    nth("Memorial Day", 5, Weekday::Mon, -1),
+    since(fixed("Juneteenth", 6, 19), 2021),
+    fixed("Independence Day", 7, 4),
+    nth("Labor Day", 9, Weekday::Mon, 1),
+    nth("Columbus Day", 10, Weekday::Mon, 2),
+    fixed("Veterans Day", 11, 11),
+    nth("Thanksgiving Day", 11, Weekday::Thu, 4),
+    fixed("Christmas Day", 12, 25),
+];
+
+const GB: &[HolidayRule] = &[
+    fixed("New Year's Day", 1, 1),
+    easter("Good Friday", -2),
+    easter("Easter Monday", 1),
+    nth("Early May bank holiday", 5, Weekday::Mon, 1),
+    nth("Spring bank holiday", 5, Weekday::Mon, -1),
+    nth("Summer bank holiday", 8, Weekday::Mon, -1),
+    fixed("Christmas Day", 12, 25),
+    fixed("Boxing Day", 12, 26),
+];
+
+const DE: &[HolidayRule] = &[
+    fixed("New Year's Day", 1, 1),
+    easter("Good Friday", -2),
+    easter("Easter Monday", 1),
+    fixed("Labour Day", 5, 1),
+    easter("Ascension Day", 39),
+    easter("Whit Monday", 50),
+    fixed("German Unity Day", 10, 3),
+    fixed("Christmas Day", 12, 25),
+    fixed("Second Day of Christmas", 12, 26),
+];
+
+const FR: &[HolidayRule] = &[
+    fixed("New Year's Day", 1, 1),
+    easter("Easter Monday", 1),
+    fixed("Labour Day", 5, 1),
+    fixed("Victory in Europe Day", 5, 8),
+    easter("Ascension Day", 39),
+    easter("Whit Monday", 50),
+    fixed("Bastille Day", 7, 14),
+    fixed("Assumption of Mary", 8, 15),
+    fixed("All Saints' Day", 11, 1),
+    fixed("Armistice Day", 11, 11),
+    fixed("Christmas Day", 12, 25),
+];
+
+const PT: &[HolidayRule] = &[
+    fixed("New Year's Day", 1, 1),
+    easter("Good Friday", -2),
+    easter("Easter Sunday", 0),
+    fixed("Freedom Day", 4, 25),
+    fixed("Labour Day", 5, 1),
+    easter("Corpus Christi", 60),
+    fixed("Portugal Day", 6, 10),
+    fixed("Assumption of Mary", 8, 15),
+    fixed("Republic Day", 10, 5),
+    fixed("All Saints' Day", 11, 1),
+    fixed("Restoration of Independence", 12, 1),
+    fixed("Immaculate Conception", 12, 8),
+    fixed("Christmas Day", 12, 25),
+];
+
+const COUNTRIES: &[HolidayCountry] = &[
+    HolidayCountry {
+        code: "DE",
+        name: "Germany",
+        rules: DE,
+        substitute: Substitute::None,
+    },
+    HolidayCountry {
+        code: "FR",
+        name: "France",
+        rules: FR,
+        substitute: Substitute::None,
+    },
+    HolidayCountry {
+        code: "GB",
+        name: "United Kingdom (England and Wales)",
+        rules: GB,
+        substitute: Substitute::NextWeekday,
+    },
+    HolidayCountry {
+        code: "PT",
+        name: "Portugal",
+        rules: PT,
+        substitute: Substitute::None,
+    },
+    HolidayCountry {
+        code: "US",
+        name: "United States (Federal Reserve)",
+        rules: US,
+        substitute: Substitute::SundayToMonday,
+    },
+];
+
+impl HolidayCountry {
+    pub fn all() -> &'static [HolidayCountry] {
+        COUNTRIES
+    }
+
+    /// Finds a table by code, accepting `UK` for `GB`.
+    pub fn lookup(code: &str) -> Option<&'static HolidayCountry> {
+        let code = if code.eq_ignore_ascii_case("UK") {
+            "GB"
+        } else {
+            code
+        };
+        COUNTRIES
+            .iter()
+            .find(|country| country.code.eq_ignore_ascii_case(code))
+    }
+
+    /// Holidays observed in `year`, sorted by date. A substitute day is named
+    /// after its holiday with `(substitute day)` appended.
+    pub fn holidays(&self, year: i32) -> Vec<Holiday> {
+        let mut days: Vec<Holiday> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.since <= year)
+            .filter_map(|rule| Some(Holiday::new(rule.date.in_year(year)?, rule.name)))
+            .collect();
+        days.sort_by_key(|holiday| holiday.date);
+        let dates: Vec<NaiveDate> = days.iter().map(|holiday| holiday.date).collect();
+        let mut observed: Vec<Holiday> = Vec::with_capacity(days.len());
+        for holiday in days {
+            let weekday = holiday.date.weekday();
+            let moved = match self.substitute {
+                Substitute::None => None,
+                Substitute::SundayToMonday => {
+                    (weekday == Weekday::Sun).then(|| holiday.date + Duration::days(1))
+                }
+                Substitute::NextWeekday if matches!(weekday, Weekday::Sat | Weekday::Sun) => {
+                    let mut date = holiday.date;
+                    while matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+                        || dates.contains(&date)
+                        || observed.iter().any(|taken| taken.date == date)
+                    {
+                        date += Duration::days(1);
+                    }
+                    Some(date)
+                }
+                Substitute::NextWeekday => None,
+            };
+            if let Some(date) = moved {
+                observed.push(Holiday::new(
+                    date,
+                    format!("{} (substitute day)", holiday.name),
+                ));
+            }
+            observed.push(holiday);
+        }
+        observed.sort_by_key(|holiday| holiday.date);
+        observed
+    }
+}
+
+impl RuleDate {
+    fn in_year(self, year: i32) -> Option<NaiveDate> {
+        match self {
+            RuleDate::Fixed(month, day) => NaiveDate::from_ymd_opt(year, month, day),
+            RuleDate::Easter(offset) => Some(easter_sunday(year)? + Duration::days(offset)),
+            RuleDate::Nth(month, weekday, n) if n > 0 => {
+                NaiveDate::from_weekday_of_month_opt(year, month, weekday, n as u8)
+            }
+            RuleDate::Nth(month, weekday, n) => {
+                let (next_year, next_month) = if month == 12 {
+                    (year + 1, 1)
+                } else {
+                    (year, month + 1)
+                };
+                let mut date = NaiveDate::from_ymd_opt(next_year, next_month, 1)?.pred_opt()?;
+                while date.weekday() != weekday {
+                    date = date.pred_opt()?;
+                }
+                Some(date - Duration::weeks(i64::from(-n - 1)))
+            }
+        }
+    }
+}
+
+/// Western Easter Sunday, by the anonymous Gregorian algorithm.
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year.rem_euclid(19);
+    let b = year.div_euclid(100);
+    let c = year.rem_euclid(100);
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
@@ -0,0 +1,203 @@
+//! ISO 4217 currencies with the symbol shown for each and the number of
+//! minor-unit digits amounts are rounded to.
+//!
+//! Symbols follow common English usage. Where a symbol is shared by several
+//! currencies, the less common ones are prefixed (`CN¥`, `MX$`) or shown by
+//! their code. Funds codes and precious metals are left out.
+
+/// Display data for one currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrencyData {
+    pub code: &'static str,
+    pub symbol: &'static str,
+    pub minor_units: u8,
+}
+
+const fn currency(code: &'static str, symbol: &'static str, minor_units: u8) -> CurrencyData {
+    CurrencyData {
+        code,
+        symbol,
+        minor_units,
+    }
+}
+
+/// Active ISO 4217 currencies, sorted by code.
+#[rustfmt::skip]
+pub const CURRENCIES: &[CurrencyData] = &[
+    currency("AED", "د.إ", 2),
+    currency("AFN", "؋", 2),
+    currency("ALL", "L", 2),
+    currency("AMD", "֏", 2),
+    currency("ANG", "ƒ", 2),
+    currency("AOA", "Kz", 2),
+    currency("ARS", "ARS", 2),
+    currency("AUD", "A$", 2),
+    currency("AWG", "ƒ", 2),
+    currency("AZN", "₼", 2),
+    currency("BAM", "KM", 2),
+    currency("BBD", "Bds$", 2),
+    currency("BDT", "৳", 2),
+    currency("BGN", "лв", 2),
+    currency("BHD", "BHD", 3),
+    currency("BIF", "FBu", 0),
+    currency("BMD", "BD$", 2),
+    currency("BND", "B$", 2),
+    currency("BOB", "Bs", 2),
+    currency("BOV", "BOV", 2),
+    currency("BRL", "R$", 2),
+    currency("BSD", "B$", 2),
+    currency("BTN", "Nu.", 2),
+    currency("BWP", "P", 2),
+    currency("BYN", "Br", 2),
+    currency("BZD", "BZ$", 2),
+    currency("CAD", "CAD", 2),
+    currency("CDF", "FC", 2),
+    currency("CHE", "CHE", 2),
+    currency("CHF", "CHF", 2),
+    currency("CHW", "CHW", 2),
+    currency("CLF", "CLF", 4),
+    currency("CLP", "CLP", 0),
+    currency("CNY", "CN¥", 2),
+    currency("COP", "COP", 2),
+    currency("COU", "COU", 2),
+    currency("CRC", "₡", 2),
+    currency("CUP", "CUP", 2),
+    currency("CVE", "CVE", 2),
+    currency("CZK", "Kč", 2),
+    currency("DJF", "Fdj", 0),
+    currency("DKK", "kr.", 2),
+    currency("DOP", "RD$", 2),
+    currency("DZD", "DZD", 2),
+    currency("EGP", "E£", 2),
+    currency("ERN", "Nfk", 2),
+    currency("ETB", "Br", 2),
+    currency("EUR", "€", 2),
+    currency("FJD", "FJ$", 2),
+    currency("FKP", "FK£", 2),
+    currency("GBP", "£", 2),
+    currency("GEL", "₾", 2),
+    currency("GHS", "GH₵", 2),
+    currency("GIP", "£", 2),
+    currency("GMD", "D", 2),
+    currency("GNF", "FG", 0),
+    currency("GTQ", "Q", 2),
+    currency("GYD", "G$", 2),
+    currency("HKD", "HK$", 2),
+    currency("HNL", "L", 2),
+    currency("HTG", "G", 2),
+    currency("HUF", "Ft", 2),
+    currency("IDR", "Rp", 2),
+    currency("ILS", "₪", 2),
+    currency("INR", "₹", 2),
+    currency("IQD", "IQD", 3),
+    currency("IRR", "IRR", 2),
+    currency("ISK", "kr", 0),
+    currency("JMD", "J$", 2),
+    currency("JOD", "JOD", 3),
+    currency("JPY", "¥", 0),
+    currency("KES", "KSh", 2),
+    currency("KGS", "сом", 2),
+    currency("KHR", "៛", 2),
+    currency("KMF", "CF", 0),
+    currency("KPW", "₩", 2),
+    currency("KRW", "₩", 0),
+    currency("KWD", "KWD", 3),
+    currency("KYD", "CI$", 2),
+    currency("KZT", "₸", 2),
+    currency("LAK", "₭", 2),
+    currency("LBP", "LBP", 2),
+    currency("LKR", "Rs", 2),
+    currency("LRD", "L$", 2),
+    currency("LSL", "L", 2),
+    currency("LYD", "LD", 3),
+    currency("MAD", "MAD", 2),
+    currency("MDL", "L", 2),
+    currency("MGA", "Ar", 2),
+    currency("MKD", "ден", 2),
+    currency("MMK", "K", 2),
+    currency("MNT", "₮", 2),
+    currency("MOP", "MOP$", 2),
+    currency("MRU", "UM", 2),
+    currency("MUR", "Rs", 2),
+    currency("MVR", "Rf", 2),
+    currency("MWK", "MK", 2),
+    currency("MXN", "MX$", 2),
+    currency("MXV", "MXV", 2),
+    currency("MYR", "RM", 2),
+    currency("MZN", "MT", 2),
+    currency("NAD", "N$", 2),
+    currency("NGN", "₦", 2),
+    currency("NIO", "C$", 2),
+    currency("NOK", "kr", 2),
+    currency("NPR", "Rs", 2),
+    currency("NZD", "NZ$", 2),
+    currency("OMR", "OMR", 3),
+    currency("PAB", "B/.", 2),
+    currency("PEN", "S/", 2),
+    currency("PGK", "K", 2),
+    currency("PHP", "₱", 2),
+    currency("PKR", "Rs", 2),
+    currency("PLN", "zł", 2),
+    currency("PYG", "₲", 0),
+    currency("QAR", "QR", 2),
+    currency("RON", "lei", 2),
+    currency("RSD", "din.", 2),
+    currency("RUB", "₽", 2),
+    currency("RWF", "RF", 0),
+    currency("SAR", "SR", 2),
+    currency("SBD", "SI$", 2),
+    currency("SCR", "SR", 2),
+    currency("SDG", "SDG", 2),
+    currency("SEK", "kr", 2),
+    currency("SGD", "S$", 2),
+    currency("SHP", "£", 2),
+    currency("SLE", "Le", 2),
+    currency("SOS", "Sh", 2),
+    currency("SRD", "SRD", 2),
+    currency("SSP", "SSP", 2),
+    currency("STN", "Db", 2),
+    currency("SVC", "SVC", 2),
+    currency("SYP", "SYP", 2),
+    currency("SZL", "E", 2),
+    currency("THB", "฿", 2),
+    currency("TJS", "SM", 2),
+    currency("TMT", "m", 2),
+    currency("TND", "DT", 3),
+    currency("TOP", "T$", 2),
+    currency("TRY", "₺", 2),
+    currency("TTD", "TT$", 2),
+    currency("TWD", "NT$", 2),
+    currency("TZS", "TSh", 2),
+    currency("UAH", "₴", 2),
+    currency("UGX", "USh", 0),
+    currency("USD", "$", 2),
+    currency("USN", "USN", 2),
+    currency("UYI", "UYI", 0),
+    currency("UYU", "$U", 2),
+    currency("UYW", "UYW", 4),
+    currency("UZS", "UZS", 2),
+    currency("VED", "VED", 2),
+    currency("VES", "Bs.S", 2),
+    currency("VND", "₫", 0),
+    currency("VUV", "VT", 0),
+    currency("WST", "WS$", 2),
+    currency("XAF", "FCFA", 0),
+    currency("XCD", "EC$", 2),
+    currency("XOF", "CFA", 0),
+    currency("XPF", "CFPF", 0),
+    currency("YER", "YER", 2),
+    currency("ZAR", "R", 2),
+    currency("ZMW", "ZK", 2),
+    currency("ZWG", "ZWG", 2),
+];
+
+impl CurrencyData {
+    /// Finds a currency by code, ignoring case.
+    pub fn lookup(code: &str) -> Option<&'static CurrencyData> {
+        let code = code.trim().to_ascii_uppercase();
+        CURRENCIES
+            .binary_search_by(|data| data.code.cmp(code.as_str()))
+            .ok()
+            .map(|index| &CURRENCIES[index])
+    }
+}
@@ -0,0 +1,37 @@
+//! Categorization rules learned from the user's manual corrections.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::common::ExtraFields;
+
+/// Maps a normalized phrase (one or two words taken from a transaction's
+/// notes or counterparty) to the category the user chose for it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LearnedRule {
+    pub pattern: String,
+    pub category_id: Uuid,
+    /// Number of corrections that confirmed this mapping.
+    #[serde(default = "LearnedRule::default_hits")]
+    pub hits: u32,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl LearnedRule {
+    pub fn new(pattern: impl Into<String>, category_id: Uuid, updated_at: DateTime<Utc>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            category_id,
+            hits: Self::default_hits(),
+            updated_at,
+            extra: ExtraFields::new(),
+        }
+    }
+
+    fn default_hits() -> u32 {
+        1
+    }
+}
@@ -6,7 +6,12 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{category::CategoryBudgetDefinition, common::*};
+use crate::{
+    amount::{check_amount, AmountOverflow},
+    category::CategoryBudgetDefinition,
+    common::*,
+    sinking_fund::SinkingFundAccrual,
+};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 /// Defines a reporting window for budget summaries.
@@ -130,6 +135,16 @@ impl BudgetTotals {
             incomplete,
         }
     }
+
+    /// Like [`BudgetTotals::from_parts`], but fails when any total has
+    /// left the range where cents are exact.
+    pub fn checked(budgeted: f64, real: f64, incomplete: bool) -> Result<Self, AmountOverflow> {
+        check_amount("budgeted", budgeted)?;
+        check_amount("real", real)?;
+        let totals = Self::from_parts(budgeted, real, incomplete);
+        check_amount("remaining", totals.remaining)?;
+        Ok(totals)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -161,6 +176,13 @@ pub struct CategoryBudget {
     pub category_id: Option<Uuid>,
     pub name: String,
     pub totals: BudgetTotals,
+    /// The category budget's note, when it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Subtotals of the direct subcategories already counted in `totals`.
+    /// Only filled by rolled-up summaries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<CategoryBudget>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -171,6 +193,22 @@ pub struct AccountBudget {
     pub totals: BudgetTotals,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Aggregated totals attributed to a household member.
+pub struct MemberBudget {
+    /// `None` collects transactions without a member.
+    pub member_id: Option<Uuid>,
+    pub name: String,
+    pub totals: BudgetTotals,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Aggregated totals of the transactions carrying one tag.
+pub struct TagBudget {
+    pub tag: String,
+    pub totals: BudgetTotals,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Complete summary for a selected window, including per-category/account totals.
 pub struct BudgetSummary {
@@ -179,6 +217,21 @@ pub struct BudgetSummary {
     pub totals: BudgetTotals,
     pub per_category: Vec<CategoryBudget>,
     pub per_account: Vec<AccountBudget>,
+    /// Populated only when the ledger defines household members.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub per_member: Vec<MemberBudget>,
+    /// One row per tag used in the window. A transaction with several tags
+    /// counts toward each, so the rows need not add up to the totals.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub per_tag: Vec<TagBudget>,
+    /// Money set aside in this window for upcoming sinking-fund bills.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub earmarked: Vec<SinkingFundAccrual>,
+    /// Unrealized exchange gain (positive) or loss over the window on asset
+    /// accounts held in another currency, in the base currency. `None` when
+    /// no such account has a balance to revalue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fx_revaluation: Option<f64>,
     pub orphaned_transactions: usize,
     pub incomplete_transactions: usize,
     #[serde(default)]
@@ -211,6 +264,9 @@ pub struct CategoryBudgetStatus {
     pub category_id: Uuid,
     pub name: String,
     pub budget: Option<CategoryBudgetDefinition>,
+    /// Budget amount scaled to the status window by its proration rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_budget: Option<f64>,
     pub totals: BudgetTotals,
 }
 
@@ -221,6 +277,22 @@ pub enum CategoryBudgetSummaryKind {
     Simulated,
 }
 
+/// Spending of a category group against its budget for one window.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CategoryGroupSummary {
+    pub group_id: Uuid,
+    pub name: String,
+    /// The group budget scaled to the window, or the sum of the members'
+    /// budgets when the group has none; `None` when neither exists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_amount: Option<f64>,
+    /// Whether `budget_amount` comes from the group's own budget.
+    pub group_budget: bool,
+    pub totals: BudgetTotals,
+    /// One row per visible member, for expanding the group row.
+    pub members: Vec<CategoryBudgetStatus>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CategoryBudgetSummary {
     pub category_id: Uuid,
@@ -242,19 +314,21 @@ impl CategoryBudgetSummary {
         category_id: Uuid,
         name: String,
         budget: &CategoryBudgetDefinition,
+        window: DateWindow,
         spent: f64,
         kind: CategoryBudgetSummaryKind,
     ) -> Self {
-        let totals = BudgetTotals::from_parts(budget.amount, spent, false);
+        let amount = budget.amount_for_window(window).to_major();
+        let totals = BudgetTotals::from_parts(amount, spent, false);
         Self {
             category_id,
             name,
-            budget_amount: budget.amount,
+            budget_amount: amount,
             spent_amount: spent,
-            remaining_amount: budget.amount - spent,
+            remaining_amount: amount - spent,
             utilization_percent: totals.percent_used,
             status: totals.status,
-            period: budget.period.clone(),
+            period: budget.period,
             reference_date: budget.reference_date,
             kind,
         }
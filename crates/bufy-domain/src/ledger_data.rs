@@ -1,6 +1,6 @@
 //! Core ledger data structures and helpers that remain free of CLI/storage logic.
 
-use std::fmt;
+use std::{collections::HashSet, fmt};
 
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
@@ -8,21 +8,45 @@ use uuid::Uuid;
 
 use crate::{
     account::Account,
-    category::Category,
-    common::{TimeInterval, TimeUnit},
+    business_days::{BusinessCalendar, BusinessDayShift},
+    category::{Category, CategoryGroup},
+    common::{ExtraFields, TimeInterval, TimeUnit},
     currency::{
         policy_date, ConvertedAmount, CurrencyCode, FormatOptions, LocaleConfig, ValuationPolicy,
     },
+    exchange_rate::ExchangeRateOverride,
+    goal::{Goal, GoalContribution},
+    holiday::HolidayCalendar,
+    ids::{new_id, ShortRef},
+    learned_rule::LearnedRule,
     ledger::{BudgetScope, BudgetSummary, CategoryBudgetSummary, DateWindow},
+    member::Member,
+    money::MoneyError,
+    ordering::pin_favorites,
+    paycheck::PaycheckProfile,
+    pending_draft::PendingDraft,
     recurring::{
         materialize_due_instances, rebuild_metadata, snapshot_recurrences, ForecastResult,
         RecurrenceSnapshot,
     },
-    simulation::{Simulation, SimulationChange, SimulationStatus, SimulationTransactionPatch},
-    transaction::Transaction,
+    review::BudgetReview,
+    simulation::{
+        IncomeModelPatch, Simulation, SimulationChange, SimulationStatus,
+        SimulationTransactionPatch,
+    },
+    sinking_fund::SinkingFund,
+    sub_ledger::SubLedger,
+    time,
+    transaction::{DateBasis, Transaction},
+    transfer_rule::TransferRule,
+    view::SavedView,
 };
 
-pub const CURRENT_SCHEMA_VERSION: u8 = 4;
+pub const CURRENT_SCHEMA_VERSION: u8 = 5;
+/// Additive revision within [`CURRENT_SCHEMA_VERSION`]. Ledgers with a newer
+/// minor revision still load; fields this build does not know are kept in
+/// each record's `extra` map and written back on save.
+pub const CURRENT_SCHEMA_MINOR: u8 = 0;
 
 /// Captures forecast and summary data for a specific budgeting window.
 #[derive(Debug, Clone)]
@@ -31,6 +55,8 @@ pub struct ForecastReport {
     pub forecast: ForecastResult,
     pub summary: BudgetSummary,
     pub category_budgets: Vec<CategoryBudgetSummary>,
+    /// Scheduled savings-goal contributions within the window.
+    pub goal_contributions: Vec<GoalContribution>,
 }
 
 /// Describes the valuation policy used to convert amounts within a report.
@@ -76,12 +102,13 @@ pub struct CurrencyConversionError {
 }
 
 impl CurrencyConversionError {
-    pub fn unsupported_pair(from: &CurrencyCode, to: &CurrencyCode) -> Self {
+    pub fn unsupported_pair(from: &CurrencyCode, to: &CurrencyCode, date: NaiveDate) -> Self {
         Self {
             message: format!(
-                "cannot convert from {} to {}: FX rates are disabled",
+                "cannot convert from {} to {}: no exchange rate override covers {}",
                 from.as_str(),
-                to.as_str()
+                to.as_str(),
+                date
             ),
         }
     }
@@ -108,38 +135,95 @@ pub struct Ledger {
     pub format: FormatOptions,
     #[serde(default)]
     pub valuation_policy: ValuationPolicy,
+    /// User-fixed exchange rates, sorted by currency pair and start date.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exchange_rate_overrides: Vec<ExchangeRateOverride>,
+    /// Public holidays skipped by business-day calculations.
+    #[serde(default, skip_serializing_if = "HolidayCalendar::is_empty")]
+    pub holidays: HolidayCalendar,
+    /// Date that places completed transactions in summary windows.
+    #[serde(default)]
+    pub summary_date_basis: DateBasis,
     #[serde(default)]
     pub accounts: Vec<Account>,
     #[serde(default)]
     pub categories: Vec<Category>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub category_groups: Vec<CategoryGroup>,
     #[serde(default)]
     pub transactions: Vec<Transaction>,
     #[serde(default)]
     pub simulations: Vec<Simulation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub views: Vec<SavedView>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub learned_rules: Vec<LearnedRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<Member>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sub_ledgers: Vec<SubLedger>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sinking_funds: Vec<SinkingFund>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub goals: Vec<Goal>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transfer_rules: Vec<TransferRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paycheck_profiles: Vec<PaycheckProfile>,
+    /// Budget review sessions, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reviews: Vec<BudgetReview>,
+    /// Drafts from external clients awaiting review; not part of any summary.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_drafts: Vec<PendingDraft>,
+    /// Next transaction reference number; numbers are never reused.
+    #[serde(default)]
+    pub next_reference: u32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     #[serde(default = "Ledger::schema_version_default")]
     pub schema_version: u8,
+    #[serde(default)]
+    pub schema_minor: u8,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl Ledger {
     pub fn new(name: impl Into<String>, budget_period: LedgerBudgetPeriod) -> Self {
-        let now = Utc::now();
+        let now = time::now();
         Self {
-            id: Uuid::new_v4(),
+            id: new_id(),
             name: name.into(),
             budget_period,
             base_currency: CurrencyCode::default(),
             locale: LocaleConfig::default(),
             format: FormatOptions::default(),
             valuation_policy: ValuationPolicy::default(),
+            exchange_rate_overrides: Vec::new(),
+            holidays: HolidayCalendar::default(),
+            summary_date_basis: DateBasis::default(),
             accounts: Vec::new(),
             categories: Vec::new(),
             transactions: Vec::new(),
             simulations: Vec::new(),
+            views: Vec::new(),
+            learned_rules: Vec::new(),
+            members: Vec::new(),
+            sub_ledgers: Vec::new(),
+            sinking_funds: Vec::new(),
+            goals: Vec::new(),
+            transfer_rules: Vec::new(),
+            paycheck_profiles: Vec::new(),
+            category_groups: Vec::new(),
+            reviews: Vec::new(),
+            pending_drafts: Vec::new(),
+            next_reference: 1,
             created_at: now,
             updated_at: now,
             schema_version: CURRENT_SCHEMA_VERSION,
+            schema_minor: CURRENT_SCHEMA_MINOR,
+            extra: ExtraFields::new(),
         }
     }
 
@@ -154,6 +238,33 @@ impl Ledger {
         }
     }
 
+    /// The business days recurrences move their occurrences onto: neither
+    /// a locale weekend day nor an imported holiday.
+    pub fn business_calendar(&self) -> BusinessCalendar<'_> {
+        self.locale
+            .business_calendar()
+            .with_holidays(&self.holidays)
+    }
+
+    /// Whether `date` is neither a locale weekend day nor a holiday.
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        self.business_calendar().is_business_day(date)
+    }
+
+    /// Moves `date` forward past weekends and holidays, leaving business
+    /// days untouched.
+    pub fn next_business_day(&self, date: NaiveDate) -> NaiveDate {
+        self.business_calendar()
+            .shift(date, BusinessDayShift::Following)
+    }
+
+    /// Moves `date` back past weekends and holidays, leaving business days
+    /// untouched.
+    pub fn previous_business_day(&self, date: NaiveDate) -> NaiveDate {
+        self.business_calendar()
+            .shift(date, BusinessDayShift::Preceding)
+    }
+
     fn account_currency(&self, id: Uuid) -> Option<String> {
         self.account(id).and_then(|acct| acct.currency.clone())
     }
@@ -162,12 +273,120 @@ impl Ledger {
         if let Some(code) = &txn.currency {
             return CurrencyCode::new(code.clone());
         }
-        self.account_currency(txn.from_account)
-            .or_else(|| self.account_currency(txn.to_account))
+        self.transfer_currency(txn.from_account, txn.to_account)
+    }
+
+    /// Currency of money moving from `from` to `to`: the sending account's,
+    /// else the receiving account's, else the base currency.
+    pub fn transfer_currency(&self, from: Uuid, to: Uuid) -> CurrencyCode {
+        self.account_currency(from)
+            .or_else(|| self.account_currency(to))
+            .map(CurrencyCode::new)
+            .unwrap_or_else(|| self.base_currency.clone())
+    }
+
+    /// Currency `account` holds: its own, else the base currency.
+    pub fn holding_currency(&self, account: &Account) -> CurrencyCode {
+        account
+            .currency
+            .as_deref()
             .map(CurrencyCode::new)
             .unwrap_or_else(|| self.base_currency.clone())
     }
 
+    /// Counts `txn`'s amounts in the currency it moves. The amounts keep
+    /// their value; only the currency they are counted in follows the
+    /// accounts.
+    pub fn assign_transaction_currency(&self, txn: &mut Transaction) -> Result<(), MoneyError> {
+        txn.count_amounts_in(&self.transaction_currency(txn))
+    }
+
+    /// Applies `change` and recounts every amount in the currency it then
+    /// belongs to. When an amount has no exact count in its new currency the
+    /// ledger is left untouched.
+    pub fn change_currencies(&mut self, change: impl FnOnce(&mut Self)) -> Result<(), MoneyError> {
+        let mut next = self.clone();
+        change(&mut next);
+        next.assign_amount_currencies()?;
+        *self = next;
+        Ok(())
+    }
+
+    /// Counts every transaction amount in the currency it moves, simulated
+    /// additions included, opening balances in their account's currency, and
+    /// category budgets in the base currency, sub-ledgers included. Amounts read from ledgers that
+    /// stored bare numbers get their currency here.
+    pub fn assign_amount_currencies(&mut self) -> Result<(), MoneyError> {
+        let base = self.base_currency.clone();
+        let currencies: Vec<CurrencyCode> = self
+            .transactions
+            .iter()
+            .map(|txn| self.transaction_currency(txn))
+            .collect();
+        for (txn, currency) in self.transactions.iter_mut().zip(currencies) {
+            txn.count_amounts_in(&currency)?;
+        }
+        let currencies: Vec<CurrencyCode> = self
+            .simulations
+            .iter()
+            .flat_map(|simulation| simulation.changes.iter())
+            .filter_map(|change| match change {
+                SimulationChange::AddTransaction { transaction } => {
+                    Some(self.transaction_currency(transaction))
+                }
+                _ => None,
+            })
+            .collect();
+        let simulated = self
+            .simulations
+            .iter_mut()
+            .flat_map(|simulation| simulation.changes.iter_mut())
+            .filter_map(|change| match change {
+                SimulationChange::AddTransaction { transaction } => Some(transaction),
+                _ => None,
+            });
+        for (txn, currency) in simulated.zip(currencies) {
+            txn.count_amounts_in(&currency)?;
+        }
+        count_opening_balances(&mut self.accounts, &base)?;
+        let budgets = self
+            .categories
+            .iter_mut()
+            .filter_map(|category| category.budget.as_mut())
+            .chain(
+                self.category_groups
+                    .iter_mut()
+                    .filter_map(|group| group.budget.as_mut()),
+            );
+        for budget in budgets {
+            budget.amount = budget.amount.in_currency(&base)?;
+        }
+        for sub in &mut self.sub_ledgers {
+            let currency_of = |id| {
+                sub.account(id)
+                    .and_then(|account| account.currency.as_deref())
+                    .map(CurrencyCode::new)
+            };
+            let currencies: Vec<CurrencyCode> = sub
+                .transactions
+                .iter()
+                .map(|txn| {
+                    txn.currency
+                        .as_deref()
+                        .map(CurrencyCode::new)
+                        .or_else(|| currency_of(txn.from_account))
+                        .or_else(|| currency_of(txn.to_account))
+                        .unwrap_or_else(|| base.clone())
+                })
+                .collect();
+            for (txn, currency) in sub.transactions.iter_mut().zip(currencies) {
+                txn.count_amounts_in(&currency)?;
+            }
+            count_opening_balances(&mut sub.accounts, &base)?;
+        }
+        Ok(())
+    }
+
     pub fn convert_amount(
         &self,
         amount: f64,
@@ -186,19 +405,49 @@ impl Ledger {
                 to: target.clone(),
             });
         }
-        Err(CurrencyConversionError::unsupported_pair(from, target))
+        let rate_date = ctx.effective_date(txn_date);
+        let rate = self
+            .exchange_rate_override(from, target, rate_date)
+            .and_then(|rule| rule.rate_between(from, target))
+            .ok_or_else(|| CurrencyConversionError::unsupported_pair(from, target, rate_date))?;
+        Ok(ConvertedAmount {
+            amount: amount * rate,
+            rate_used: rate,
+            rate_date,
+            source: "ledger rate override".into(),
+            from: from.clone(),
+            to: target.clone(),
+        })
     }
 
+    /// The override converting between `from` and `to` on `date`. Overrides
+    /// take precedence over any other rate source; with no rate provider
+    /// configured they are the only one.
+    pub fn exchange_rate_override(
+        &self,
+        from: &CurrencyCode,
+        to: &CurrencyCode,
+        date: NaiveDate,
+    ) -> Option<&ExchangeRateOverride> {
+        self.exchange_rate_overrides
+            .iter()
+            .find(|rule| rule.links(from, to) && rule.covers(date))
+    }
+
+    /// Appends `account`, or adds it after the other favorites when starred.
     pub fn add_account(&mut self, account: Account) -> Uuid {
         let id = account.id;
         self.accounts.push(account);
+        pin_favorites(&mut self.accounts);
         self.touch();
         id
     }
 
+    /// Appends `category`, or adds it after the other favorites when starred.
     pub fn add_category(&mut self, category: Category) -> Uuid {
         let id = category.id;
         self.categories.push(category);
+        pin_favorites(&mut self.categories);
         self.touch();
         id
     }
@@ -206,11 +455,38 @@ impl Ledger {
     pub fn add_transaction(&mut self, transaction: Transaction) -> Uuid {
         let id = transaction.id;
         self.transactions.push(transaction);
+        self.assign_references();
         self.refresh_recurrence_metadata();
         self.touch();
         id
     }
 
+    /// Numbers transactions that have no reference yet, or share one with an
+    /// earlier transaction (e.g. a copy), in list order. Returns how many were
+    /// numbered.
+    pub fn assign_references(&mut self) -> usize {
+        let highest = self
+            .transactions
+            .iter()
+            .filter_map(|txn| txn.reference)
+            .max()
+            .unwrap_or(0);
+        let mut next = self.next_reference.max(highest + 1);
+        let mut seen = HashSet::new();
+        let mut assigned = 0;
+        for txn in &mut self.transactions {
+            if txn.reference.is_some_and(|number| seen.insert(number)) {
+                continue;
+            }
+            txn.reference = Some(next);
+            seen.insert(next);
+            next += 1;
+            assigned += 1;
+        }
+        self.next_reference = next;
+        assigned
+    }
+
     pub fn transaction_count(&self) -> usize {
         self.transactions.len()
     }
@@ -227,6 +503,27 @@ impl Ledger {
         self.categories.iter().find(|category| category.id == id)
     }
 
+    pub fn category_group(&self, name: &str) -> Option<&CategoryGroup> {
+        let name = name.trim();
+        self.category_groups
+            .iter()
+            .find(|group| group.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn category_group_mut(&mut self, name: &str) -> Option<&mut CategoryGroup> {
+        let name = name.trim();
+        self.category_groups
+            .iter_mut()
+            .find(|group| group.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The group `category_id` belongs to, if any.
+    pub fn group_of(&self, category_id: Uuid) -> Option<&CategoryGroup> {
+        self.category_groups
+            .iter()
+            .find(|group| group.contains(category_id))
+    }
+
     pub fn category_mut(&mut self, id: Uuid) -> Option<&mut Category> {
         self.categories
             .iter_mut()
@@ -239,6 +536,21 @@ impl Ledger {
             .find(|transaction| transaction.id == id)
     }
 
+    /// Position of the transaction numbered `reference`, e.g. `T-0012`.
+    pub fn transaction_position(&self, reference: ShortRef) -> Option<usize> {
+        if reference.prefix != ShortRef::TRANSACTION {
+            return None;
+        }
+        self.transactions
+            .iter()
+            .position(|transaction| transaction.reference == Some(reference.number))
+    }
+
+    pub fn transaction_by_reference(&self, reference: ShortRef) -> Option<&Transaction> {
+        self.transaction_position(reference)
+            .map(|index| &self.transactions[index])
+    }
+
     pub fn transaction_mut(&mut self, id: Uuid) -> Option<&mut Transaction> {
         self.transactions
             .iter_mut()
@@ -276,62 +588,124 @@ impl Ledger {
             .find(|sim| sim.name.eq_ignore_ascii_case(name))
     }
 
+    pub fn views(&self) -> &[SavedView] {
+        &self.views
+    }
+
+    pub fn view(&self, name: &str) -> Option<&SavedView> {
+        self.views
+            .iter()
+            .find(|view| view.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn view_mut(&mut self, name: &str) -> Option<&mut SavedView> {
+        self.views
+            .iter_mut()
+            .find(|view| view.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn member(&self, id: Uuid) -> Option<&Member> {
+        self.members.iter().find(|member| member.id == id)
+    }
+
+    pub fn member_by_name(&self, name: &str) -> Option<&Member> {
+        let name = name.trim();
+        self.members
+            .iter()
+            .find(|member| member.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn sub_ledger(&self, name: &str) -> Option<&SubLedger> {
+        let name = name.trim();
+        self.sub_ledgers
+            .iter()
+            .find(|sub| sub.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn sub_ledger_mut(&mut self, name: &str) -> Option<&mut SubLedger> {
+        let name = name.trim();
+        self.sub_ledgers
+            .iter_mut()
+            .find(|sub| sub.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn sinking_fund(&self, name: &str) -> Option<&SinkingFund> {
+        let name = name.trim();
+        self.sinking_funds
+            .iter()
+            .find(|fund| fund.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn sinking_fund_mut(&mut self, name: &str) -> Option<&mut SinkingFund> {
+        let name = name.trim();
+        self.sinking_funds
+            .iter_mut()
+            .find(|fund| fund.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn goal(&self, name: &str) -> Option<&Goal> {
+        let name = name.trim();
+        self.goals
+            .iter()
+            .find(|goal| goal.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn paycheck_profile(&self, name: &str) -> Option<&PaycheckProfile> {
+        let name = name.trim();
+        self.paycheck_profiles
+            .iter()
+            .find(|profile| profile.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn paycheck_profile_mut(&mut self, name: &str) -> Option<&mut PaycheckProfile> {
+        let name = name.trim();
+        self.paycheck_profiles
+            .iter_mut()
+            .find(|profile| profile.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The latest review if it has not been finished.
+    pub fn open_review(&self) -> Option<&BudgetReview> {
+        self.reviews.last().filter(|review| !review.is_complete())
+    }
+
+    pub fn open_review_mut(&mut self) -> Option<&mut BudgetReview> {
+        self.reviews
+            .last_mut()
+            .filter(|review| !review.is_complete())
+    }
+
+    pub fn learned_rules(&self) -> &[LearnedRule] {
+        &self.learned_rules
+    }
+
     pub fn touch(&mut self) {
-        self.updated_at = Utc::now();
+        self.updated_at = time::now();
     }
 
     pub fn schema_version_default() -> u8 {
         CURRENT_SCHEMA_VERSION
     }
 
-    pub fn migrate_from_schema(&mut self, original_version: u8) -> Vec<String> {
-        let mut notes = Vec::new();
-        if original_version < CURRENT_SCHEMA_VERSION {
-            notes.push(format!(
-                "upgraded schema from v{} to v{}",
-                original_version, CURRENT_SCHEMA_VERSION
-            ));
-        }
-        if original_version < 3 {
-            self.refresh_recurrence_metadata();
-            notes.push("refreshed recurrence metadata for schema v3".into());
-        }
-        if original_version < 4 {
-            if self.base_currency.as_str().is_empty() {
-                self.base_currency = CurrencyCode::default();
-            }
-            if self.locale.language_tag.is_empty() {
-                self.locale = LocaleConfig::default();
-            }
-            self.format = FormatOptions::default();
-            self.valuation_policy = ValuationPolicy::TransactionDate;
-            notes.push("initialized currency/localization defaults for schema v4".into());
-        }
-        self.schema_version = CURRENT_SCHEMA_VERSION;
-        notes
-    }
-
-    pub fn upgrade_schema_if_needed(&mut self) -> bool {
-        if self.schema_version < CURRENT_SCHEMA_VERSION {
-            self.schema_version = CURRENT_SCHEMA_VERSION;
-            self.touch();
-            true
-        } else {
-            false
-        }
+    /// True when a newer BUFY wrote this ledger with a minor schema revision
+    /// this build does not know.
+    pub fn is_newer_minor_schema(&self) -> bool {
+        self.schema_version == CURRENT_SCHEMA_VERSION && self.schema_minor > CURRENT_SCHEMA_MINOR
     }
 
     pub fn recurrence_snapshots(&self, reference: NaiveDate) -> Vec<RecurrenceSnapshot> {
-        snapshot_recurrences(&self.transactions, reference)
+        snapshot_recurrences(&self.transactions, reference, self.business_calendar())
     }
 
     pub fn materialize_due_recurrences(&mut self, reference: NaiveDate) -> usize {
-        let pending = materialize_due_instances(reference, &self.transactions);
+        let pending =
+            materialize_due_instances(reference, &self.transactions, self.business_calendar());
         if pending.is_empty() {
             return 0;
         }
         let created = pending.len();
         self.transactions.extend(pending);
+        self.assign_references();
         self.refresh_recurrence_metadata();
         self.touch();
         created
@@ -345,7 +719,7 @@ impl Ledger {
         {
             return;
         }
-        let metadata = rebuild_metadata(&self.transactions);
+        let metadata = rebuild_metadata(&self.transactions, self.business_calendar());
         if metadata.is_empty() {
             return;
         }
@@ -373,12 +747,13 @@ impl Ledger {
         sim_name: &str,
         mut transaction: Transaction,
     ) -> bool {
-        transaction.id = Uuid::new_v4();
+        transaction.id = new_id();
+        transaction.reference = None;
         let updated = {
             if let Some(sim) = self.editable_simulation(sim_name) {
                 sim.changes
                     .push(SimulationChange::AddTransaction { transaction });
-                sim.updated_at = Utc::now();
+                sim.updated_at = time::now();
                 true
             } else {
                 false
@@ -402,7 +777,7 @@ impl Ledger {
             if let Some(sim) = self.editable_simulation(sim_name) {
                 sim.changes
                     .push(SimulationChange::ExcludeTransaction { transaction_id });
-                sim.updated_at = Utc::now();
+                sim.updated_at = time::now();
                 true
             } else {
                 false
@@ -429,7 +804,27 @@ impl Ledger {
         let updated = {
             if let Some(sim) = self.editable_simulation(sim_name) {
                 sim.changes.push(SimulationChange::ModifyTransaction(patch));
-                sim.updated_at = Utc::now();
+                sim.updated_at = time::now();
+                true
+            } else {
+                false
+            }
+        };
+        if updated {
+            self.touch();
+        }
+        updated
+    }
+
+    pub fn modify_income_in_simulation_raw(
+        &mut self,
+        sim_name: &str,
+        patch: IncomeModelPatch,
+    ) -> bool {
+        let updated = {
+            if let Some(sim) = self.editable_simulation(sim_name) {
+                sim.changes.push(SimulationChange::ModifyIncome(patch));
+                sim.updated_at = time::now();
                 true
             } else {
                 false
@@ -471,7 +866,9 @@ impl Ledger {
             .map(|t| t.scheduled_date)
             .min()
             .unwrap_or_else(|| self.created_at.date_naive());
-        self.budget_period.0.normalize_anchor(base)
+        self.budget_period
+            .0
+            .normalize_anchor_with_week_start(base, self.locale.first_weekday)
     }
 
     pub fn budget_window_containing(&self, reference: NaiveDate) -> DateWindow {
@@ -481,3 +878,19 @@ impl Ledger {
         DateWindow { start, end }
     }
 }
+
+/// Counts each opening balance in its account's currency, or `base` for
+/// accounts without one.
+fn count_opening_balances(accounts: &mut [Account], base: &CurrencyCode) -> Result<(), MoneyError> {
+    for account in accounts {
+        let currency = account
+            .currency
+            .as_deref()
+            .map(CurrencyCode::new)
+            .unwrap_or_else(|| base.clone());
+        if let Some(balance) = account.opening_balance.as_mut() {
+            *balance = balance.in_currency(&currency)?;
+        }
+    }
+    Ok(())
+}
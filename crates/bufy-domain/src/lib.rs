@@ -4,29 +4,79 @@
 //! No I/O, no CLI, no storage. Only data types and core enums.
 
 pub mod account;
+pub mod alert;
+pub mod amount;
+pub mod appearance;
+pub mod business_days;
 pub mod category;
 pub mod common;
 pub mod currency;
+pub mod exchange_rate;
+pub mod goal;
+pub mod holiday;
+pub mod ids;
+pub mod iso4217;
+pub mod learned_rule;
 pub mod ledger;
 pub mod ledger_data;
+pub mod locale;
+pub mod member;
+pub mod money;
+pub mod ordering;
+pub mod paycheck;
+pub mod pending_draft;
+pub mod reconciliation;
 pub mod recurring;
+pub mod review;
 pub mod simulation;
+pub mod sinking_fund;
+pub mod sub_ledger;
+pub mod time;
 pub mod transaction;
+pub mod transfer_rule;
+pub mod view;
 
 pub use account::*;
+pub use alert::*;
+pub use amount::*;
+pub use appearance::*;
+pub use business_days::*;
 pub use category::*;
 pub use common::*;
 pub use currency::*;
+pub use exchange_rate::*;
+pub use goal::*;
+pub use holiday::*;
+pub use ids::*;
+pub use iso4217::*;
+pub use learned_rule::*;
 pub use ledger::*;
 pub use ledger_data::*;
+pub use locale::*;
+pub use member::*;
+pub use money::*;
+pub use ordering::*;
+pub use paycheck::*;
+pub use pending_draft::*;
+pub use reconciliation::*;
 pub use recurring::*;
+pub use review::*;
 pub use simulation::*;
+pub use sinking_fund::*;
+pub use sub_ledger::*;
+pub use time::*;
 pub use transaction::*;
+pub use transfer_rule::*;
+pub use view::*;
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use chrono::{Datelike, NaiveDate};
+
+    fn usd(amount: f64) -> Money {
+        Money::from_major(amount, CurrencyCode::new("USD")).unwrap()
+    }
 
     #[test]
     fn ledger_can_hold_accounts_categories_and_transactions() {
@@ -45,7 +95,7 @@ mod tests {
             account_id,
             Some(category_id),
             NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
-            100.0,
+            usd(100.0),
         );
         transaction.notes = Some("Initial grocery budget".to_string());
         ledger.transactions.push(transaction);
@@ -65,4 +115,380 @@ mod tests {
         assert_eq!(decoded.name, "RoundTrip");
         assert_eq!(decoded.budget_period, ledger.budget_period);
     }
+
+    #[test]
+    fn unknown_fields_from_newer_versions_survive_a_roundtrip() {
+        let mut ledger = Ledger::new("Future", LedgerBudgetPeriod::monthly());
+        let account_id = ledger.add_account(Account::new("Checking", AccountKind::Bank));
+        let mut transaction = Transaction::new(
+            account_id,
+            account_id,
+            None,
+            NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            usd(10.0),
+        );
+        transaction.set_recurrence(Some(Recurrence::new(
+            transaction.scheduled_date,
+            TimeInterval {
+                every: 1,
+                unit: TimeUnit::Month,
+            },
+            RecurrenceMode::FixedSchedule,
+        )));
+        ledger.transactions.push(transaction);
+
+        let mut value = serde_json::to_value(&ledger).expect("serialize ledger");
+        value["schema_minor"] = serde_json::json!(CURRENT_SCHEMA_MINOR + 1);
+        value["sync_token"] = serde_json::json!("abc");
+        value["accounts"][0]["iban"] = serde_json::json!("PT50 0000");
+        value["transactions"][0]["labels"] = serde_json::json!(["rent"]);
+        value["transactions"][0]["recurrence"]["timezone"] = serde_json::json!("UTC");
+
+        let decoded: Ledger = serde_json::from_value(value.clone()).expect("deserialize ledger");
+        assert!(decoded.is_newer_minor_schema());
+        assert_eq!(decoded.extra["sync_token"], "abc");
+        assert_eq!(decoded.accounts[0].extra["iban"], "PT50 0000");
+        let recurrence = decoded.transactions[0].recurrence.as_ref().unwrap();
+        assert_eq!(recurrence.extra["timezone"], "UTC");
+
+        let resaved = serde_json::to_value(&decoded).expect("serialize again");
+        assert_eq!(resaved, value);
+    }
+
+    #[test]
+    fn locale_lookup_normalizes_tags_and_falls_back_to_language() {
+        let german = LocaleConfig::for_tag("de_de").expect("de-DE");
+        assert_eq!(german.language_tag, "de-DE");
+        assert_eq!(german.decimal_separator, ',');
+        assert_eq!(german.grouping_separator, '.');
+        assert_eq!(german.first_weekday, chrono::Weekday::Mon);
+
+        let fallback = LocaleData::lookup("de-LU").expect("language fallback");
+        assert_eq!(fallback.tag, "de-DE");
+        assert_eq!(LocaleData::lookup("pt").map(|data| data.tag), Some("pt-PT"));
+        assert!(LocaleData::lookup("xx-YY").is_none());
+        assert!(LocaleData::lookup("").is_none());
+    }
+
+    #[test]
+    fn numbers_parse_with_locale_separators_or_plain_dots() {
+        let german = LocaleConfig::for_tag("de-DE").expect("de-DE");
+        let english = LocaleConfig::default();
+
+        assert_eq!(parse_number(&german, "1.234,56"), Some(1234.56));
+        assert_eq!(parse_number(&german, "-12,5"), Some(-12.5));
+        assert_eq!(parse_number(&german, " 1 234,5 "), Some(1234.5));
+        assert_eq!(parse_number(&german, "12.50"), Some(12.5));
+        assert_eq!(parse_number(&german, "1.500"), Some(1500.0));
+        assert_eq!(parse_number(&english, "1,234.56"), Some(1234.56));
+        assert_eq!(parse_number(&english, ".75"), Some(0.75));
+        assert_eq!(parse_number(&english, "1,5"), None);
+        assert_eq!(parse_number(&german, "1,234.56"), None);
+        assert_eq!(parse_number(&english, "12,34,567"), None);
+        assert_eq!(parse_number(&english, "inf"), None);
+        assert_eq!(parse_number(&english, ""), None);
+    }
+
+    #[test]
+    fn currency_symbols_follow_locale_placement() {
+        let eur = CurrencyCode::new("EUR");
+        let options = FormatOptions::default();
+        let german = LocaleConfig::for_tag("de-DE").expect("de-DE");
+        assert_eq!(
+            format_currency_value(1234.56, &eur, &german, &options),
+            "1.234,56 €"
+        );
+        assert_eq!(
+            format_currency_value(-5.0, &eur, &german, &options),
+            "-5,00 €"
+        );
+        let dutch = LocaleConfig::for_tag("nl-NL").expect("nl-NL");
+        assert_eq!(
+            format_currency_value(1234.56, &eur, &dutch, &options),
+            "€ 1.234,56"
+        );
+        let usd = CurrencyCode::new("USD");
+        assert_eq!(
+            format_currency_value(1234.56, &usd, &LocaleConfig::default(), &options),
+            "$1,234.56"
+        );
+
+        let overridden = LocaleConfig {
+            symbol_position: Some(SymbolPosition::Prefix),
+            symbol_spacing: Some(false),
+            ..german
+        };
+        assert_eq!(
+            format_currency_value(1234.56, &eur, &overridden, &options),
+            "€1.234,56"
+        );
+    }
+
+    #[test]
+    fn iso4217_table_supplies_symbols_and_minor_units() {
+        assert_eq!(minor_units_for("JPY"), 0);
+        assert_eq!(minor_units_for("ISK"), 0);
+        assert_eq!(minor_units_for("KWD"), 3);
+        assert_eq!(minor_units_for("TND"), 3);
+        assert_eq!(minor_units_for("CLF"), 4);
+        assert_eq!(minor_units_for("XYZ"), 2);
+        assert_eq!(symbol_for("inr"), "₹");
+        assert_eq!(symbol_for("XYZ"), "XYZ");
+        assert!(CURRENCIES
+            .windows(2)
+            .all(|pair| pair[0].code < pair[1].code));
+    }
+
+    #[test]
+    fn money_rounds_to_minor_units_and_reads_float_amounts() {
+        let usd = CurrencyCode::new("USD");
+        let cents = Money::from_major(0.1 + 0.2, usd.clone()).unwrap();
+        assert_eq!(cents.minor(), 30);
+        assert_eq!(Money::from_major(-0.125, usd.clone()).unwrap().minor(), -13);
+        assert_eq!(
+            Money::from_major(1234.5, CurrencyCode::new("JPY"))
+                .unwrap()
+                .minor(),
+            1235
+        );
+        assert_eq!(
+            Money::from_minor(1234, CurrencyCode::new("KWD")).to_major(),
+            1.234
+        );
+        assert!(Money::from_major(f64::NAN, usd.clone()).is_err());
+
+        let sum = cents
+            .checked_add(&Money::from_minor(70, usd.clone()))
+            .unwrap();
+        assert_eq!(sum.to_string(), "1.00 USD");
+        assert!(matches!(
+            sum.checked_sub(&Money::zero(CurrencyCode::new("EUR"))),
+            Err(MoneyError::CurrencyMismatch { .. })
+        ));
+
+        let json = serde_json::to_value(&sum).unwrap();
+        assert_eq!(json, serde_json::json!({"minor": 100, "currency": "USD"}));
+        assert_eq!(serde_json::from_value::<Money>(json).unwrap(), sum);
+        let legacy: Money =
+            serde_json::from_str(r#"{"amount": 19.999999999, "currency": "USD"}"#).unwrap();
+        assert_eq!(legacy.minor(), 2000);
+
+        let bare: Money = serde_json::from_str("12.34567").unwrap();
+        assert_eq!(bare.minor(), 123457);
+        let jpy = bare.in_currency(&CurrencyCode::new("JPY")).unwrap();
+        assert_eq!((jpy.minor(), jpy.currency().as_str()), (12, "JPY"));
+        assert_eq!(bare.in_currency(&usd).unwrap().minor(), 1235);
+        assert_eq!(
+            Money::from_minor(-125, usd.clone())
+                .in_currency(&CurrencyCode::new("KWD"))
+                .unwrap()
+                .minor(),
+            -1250
+        );
+    }
+
+    #[test]
+    fn category_budget_proration_scales_to_the_window() {
+        let date = |year, month, day| NaiveDate::from_ymd_opt(year, month, day).unwrap();
+        let january = DateWindow::new(date(2025, 1, 1), date(2025, 2, 1)).unwrap();
+        let weekly = CategoryBudgetDefinition::new(usd(70.0), BudgetPeriod::Weekly);
+
+        assert_eq!(weekly.amount_for_window(january), usd(310.0));
+        // Mondays in January 2025: 6, 13, 20, 27.
+        let aligned = weekly
+            .clone()
+            .with_proration(BudgetProration::AlignToLedger)
+            .amount_for_window(january);
+        assert_eq!(aligned, usd(280.0));
+        let strict = weekly
+            .with_proration(BudgetProration::Strict)
+            .amount_for_window(january);
+        assert_eq!(strict, usd(70.0));
+
+        let monthly = CategoryBudgetDefinition::new(usd(300.0), BudgetPeriod::Monthly);
+        assert_eq!(monthly.amount_for_window(january), usd(300.0));
+        let mid_month = DateWindow::new(date(2025, 1, 15), date(2025, 2, 15)).unwrap();
+        // 300 × 17/31 + 300 × 14/28, rounded to the cent.
+        assert_eq!(monthly.amount_for_window(mid_month), usd(314.52));
+
+        let yearly = CategoryBudgetDefinition::new(usd(1200.0), BudgetPeriod::Yearly)
+            .with_reference_date(date(2024, 3, 1))
+            .with_proration(BudgetProration::AlignToLedger);
+        assert_eq!(yearly.amount_for_window(january), usd(0.0));
+        let march = DateWindow::new(date(2025, 3, 1), date(2025, 4, 1)).unwrap();
+        assert_eq!(yearly.amount_for_window(march), usd(1200.0));
+    }
+
+    #[test]
+    fn transactions_get_stable_short_references() {
+        let ids = SequentialIds::new();
+        assert_eq!(
+            ids.next_id().to_string(),
+            "00000000-0000-0000-0000-000000000001"
+        );
+        assert_eq!(ids.next_id().as_u128(), 2);
+        assert_eq!(
+            SequentialIds::seeded(7).next_id().to_string(),
+            "00000000-0000-0007-0000-000000000001"
+        );
+
+        let mut ledger = Ledger::new("Refs", LedgerBudgetPeriod::monthly());
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let (from, to) = (ids.next_id(), ids.next_id());
+        let first = ledger.add_transaction(Transaction::new(from, to, None, day, usd(1.0)));
+        let copy = ledger.transaction(first).unwrap().clone();
+        ledger.add_transaction(Transaction {
+            id: ids.next_id(),
+            ..copy
+        });
+        assert_eq!(
+            ledger.transactions[0].short_ref().unwrap().to_string(),
+            "T-0001"
+        );
+        // A copy keeps nothing of the original's number.
+        assert_eq!(ledger.transactions[1].reference, Some(2));
+
+        // Numbers are not reused after a removal.
+        let last = ledger.transactions[1].id;
+        ledger.remove_transaction(last);
+        ledger.add_transaction(Transaction::new(from, to, None, day, usd(3.0)));
+        assert_eq!(ledger.transactions[1].reference, Some(3));
+
+        assert_eq!(ShortRef::parse("t-12"), Some(ShortRef::transaction(12)));
+        assert_eq!(ShortRef::parse("T0012"), Some(ShortRef::transaction(12)));
+        assert_eq!(ShortRef::parse("12"), None);
+        assert_eq!(ShortRef::parse("T-"), None);
+    }
+
+    #[test]
+    fn country_holidays_follow_their_rules_and_skip_business_days() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(easter_sunday(2025), Some(date(2025, 4, 20)));
+        assert_eq!(easter_sunday(2024), Some(date(2024, 3, 31)));
+
+        let us = HolidayCountry::lookup("us").unwrap().holidays(2022);
+        let us_dates: Vec<_> = us.iter().map(|holiday| holiday.date).collect();
+        assert!(us_dates.contains(&date(2022, 1, 17)), "third Monday");
+        assert!(us_dates.contains(&date(2022, 5, 30)), "last Monday");
+        assert!(us_dates.contains(&date(2022, 11, 24)), "fourth Thursday");
+        // Christmas falls on a Sunday and is observed on Monday.
+        assert!(us_dates.contains(&date(2022, 12, 26)));
+        assert!(!HolidayCountry::lookup("US")
+            .unwrap()
+            .holidays(2020)
+            .iter()
+            .any(|holiday| holiday.name == "Juneteenth"));
+
+        // Both Christmas days move past the weekend without colliding.
+        let uk = HolidayCountry::lookup("UK").unwrap().holidays(2022);
+        let december: Vec<_> = uk
+            .iter()
+            .filter(|holiday| holiday.date >= date(2022, 12, 25))
+            .map(|holiday| (holiday.date.day(), holiday.name.as_str()))
+            .collect();
+        assert_eq!(
+            december,
+            vec![
+                (25, "Christmas Day"),
+                (26, "Boxing Day"),
+                (27, "Christmas Day (substitute day)"),
+            ]
+        );
+
+        let mut ledger = Ledger::new("Holidays", LedgerBudgetPeriod::monthly());
+        ledger.holidays.set_holidays(uk);
+        // Friday before Easter, then the Monday after.
+        let good_friday = date(2022, 4, 15);
+        assert!(ledger
+            .locale
+            .business_calendar()
+            .is_business_day(good_friday));
+        assert!(!ledger.is_business_day(good_friday));
+        assert_eq!(ledger.next_business_day(good_friday), date(2022, 4, 19));
+        assert_eq!(
+            ledger.previous_business_day(date(2022, 4, 18)),
+            date(2022, 4, 14)
+        );
+
+        // A monthly bill due on the 15th moves past Good Friday and Easter
+        // Monday, and its next due date does too.
+        let mut bill = Transaction::new(
+            uuid::Uuid::new_v4(),
+            uuid::Uuid::new_v4(),
+            None,
+            date(2022, 3, 15),
+            usd(50.0),
+        );
+        let mut rule = Recurrence::new(
+            date(2022, 3, 15),
+            TimeInterval {
+                every: 1,
+                unit: TimeUnit::Month,
+            },
+            RecurrenceMode::FixedSchedule,
+        );
+        rule.business_day_shift = BusinessDayShift::Following;
+        bill.set_recurrence(Some(rule));
+        let template = bill.id;
+        ledger.add_transaction(bill);
+        ledger.materialize_due_recurrences(date(2022, 4, 10));
+        ledger.refresh_recurrence_metadata();
+        let next = |ledger: &Ledger| {
+            ledger
+                .transaction(template)
+                .unwrap()
+                .recurrence
+                .as_ref()
+                .unwrap()
+                .next_scheduled
+        };
+        assert_eq!(next(&ledger), Some(date(2022, 4, 19)));
+        assert_eq!(ledger.materialize_due_recurrences(date(2022, 4, 20)), 1);
+        assert!(ledger
+            .transactions
+            .iter()
+            .any(|txn| txn.id != template && txn.scheduled_date == date(2022, 4, 19)));
+    }
+
+    #[test]
+    fn favorites_stay_ahead_of_the_rest_when_moved() {
+        let names = |accounts: &[Account]| -> Vec<String> {
+            accounts
+                .iter()
+                .map(|account| account.name.clone())
+                .collect()
+        };
+        let mut ledger = Ledger::new("Order", LedgerBudgetPeriod::monthly());
+        for name in ["A", "B", "C"] {
+            ledger.add_account(Account::new(name, AccountKind::Bank));
+        }
+        let mut starred = Account::new("Star", AccountKind::Cash);
+        starred.favorite = true;
+        ledger.add_account(starred);
+        assert_eq!(names(&ledger.accounts), ["Star", "A", "B", "C"]);
+
+        // The first non-favorite cannot climb into the favorites.
+        assert_eq!(
+            move_within_block(&mut ledger.accounts, 1, MoveDirection::Up, |_| true),
+            1
+        );
+        assert_eq!(
+            move_within_block(&mut ledger.accounts, 1, MoveDirection::Bottom, |_| true),
+            3
+        );
+        assert_eq!(names(&ledger.accounts), ["Star", "B", "C", "A"]);
+
+        assert_eq!(set_favorite_at(&mut ledger.accounts, 2, true), 1);
+        assert_eq!(names(&ledger.accounts), ["Star", "C", "B", "A"]);
+        assert_eq!(set_favorite_at(&mut ledger.accounts, 0, false), 1);
+        assert_eq!(names(&ledger.accounts), ["C", "Star", "B", "A"]);
+
+        // Steps skip items the user cannot see.
+        let visible = |account: &Account| account.name != "B";
+        assert_eq!(
+            move_within_block(&mut ledger.accounts, 3, MoveDirection::Up, visible),
+            1
+        );
+        assert_eq!(names(&ledger.accounts), ["C", "A", "Star", "B"]);
+    }
 }
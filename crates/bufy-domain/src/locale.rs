@@ -0,0 +1,134 @@
+//! Built-in regional conventions keyed by BCP 47 language tag.
+//!
+//! A small subset of CLDR data: number separators, currency symbol placement,
+//! the numeric short date pattern, the first day of the week, and weekend
+//! days. Looking up a tag
+//! yields a ready-to-use [`LocaleConfig`] that callers may further override.
+
+use chrono::Weekday;
+
+use crate::common::ExtraFields;
+use crate::currency::{DateFormatStyle, LocaleConfig, SymbolPosition};
+
+/// Regional conventions for one locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocaleData {
+    pub tag: &'static str,
+    pub decimal_separator: char,
+    pub grouping_separator: char,
+    pub symbol_position: SymbolPosition,
+    /// Whether a space separates the currency symbol from the number.
+    pub symbol_spacing: bool,
+    pub date_style: DateFormatStyle,
+    /// `strftime` pattern for compact numeric dates.
+    pub short_date_pattern: &'static str,
+    pub first_weekday: Weekday,
+    pub weekend: &'static [Weekday],
+}
+
+const SAT_SUN: &[Weekday] = &[Weekday::Sat, Weekday::Sun];
+const FRI_SAT: &[Weekday] = &[Weekday::Fri, Weekday::Sat];
+
+/// Symbol placements: `$1.00`, `€ 1,00`, `1,00 €`.
+const BEFORE: (SymbolPosition, bool) = (SymbolPosition::Prefix, false);
+const BEFORE_SPACED: (SymbolPosition, bool) = (SymbolPosition::Prefix, true);
+const AFTER_SPACED: (SymbolPosition, bool) = (SymbolPosition::Suffix, true);
+
+#[allow(clippy::too_many_arguments)]
+const fn locale(
+    tag: &'static str,
+    decimal_separator: char,
+    grouping_separator: char,
+    symbol: (SymbolPosition, bool),
+    date_style: DateFormatStyle,
+    short_date_pattern: &'static str,
+    first_weekday: Weekday,
+    weekend: &'static [Weekday],
+) -> LocaleData {
+    LocaleData {
+        tag,
+        decimal_separator,
+        grouping_separator,
+        symbol_position: symbol.0,
+        symbol_spacing: symbol.1,
+        date_style,
+        short_date_pattern,
+        first_weekday,
+        weekend,
+    }
+}
+
+/// Supported locales. The first entry for a language is its fallback when
+/// only the language subtag is given. `en-US` keeps ISO dates, the
+/// application's long-standing default, rather than CLDR's month-first form.
+#[rustfmt::skip]
+pub const LOCALES: &[LocaleData] = &[
+    locale("en-US", '.', ',', BEFORE, DateFormatStyle::Medium, "%Y-%m-%d", Weekday::Sun, SAT_SUN),
+    locale("en-GB", '.', ',', BEFORE, DateFormatStyle::Long, "%d/%m/%Y", Weekday::Mon, SAT_SUN),
+    locale("en-AU", '.', ',', BEFORE, DateFormatStyle::Medium, "%d/%m/%Y", Weekday::Mon, SAT_SUN),
+    locale("en-CA", '.', ',', BEFORE, DateFormatStyle::Medium, "%Y-%m-%d", Weekday::Sun, SAT_SUN),
+    locale("en-IE", '.', ',', BEFORE, DateFormatStyle::Medium, "%d/%m/%Y", Weekday::Mon, SAT_SUN),
+    locale("de-DE", ',', '.', AFTER_SPACED, DateFormatStyle::Medium, "%d.%m.%Y", Weekday::Mon, SAT_SUN),
+    locale("de-AT", ',', ' ', BEFORE_SPACED, DateFormatStyle::Medium, "%d.%m.%Y", Weekday::Mon, SAT_SUN),
+    locale("de-CH", '.', '\'', BEFORE_SPACED, DateFormatStyle::Medium, "%d.%m.%Y", Weekday::Mon, SAT_SUN),
+    locale("fr-FR", ',', ' ', AFTER_SPACED, DateFormatStyle::Long, "%d/%m/%Y", Weekday::Mon, SAT_SUN),
+    locale("fr-CA", ',', ' ', AFTER_SPACED, DateFormatStyle::Medium, "%Y-%m-%d", Weekday::Sun, SAT_SUN),
+    locale("fr-CH", ',', ' ', AFTER_SPACED, DateFormatStyle::Medium, "%d.%m.%Y", Weekday::Mon, SAT_SUN),
+    locale("es-ES", ',', '.', AFTER_SPACED, DateFormatStyle::Medium, "%d/%m/%Y", Weekday::Mon, SAT_SUN),
+    locale("es-MX", '.', ',', BEFORE, DateFormatStyle::Medium, "%d/%m/%Y", Weekday::Sun, SAT_SUN),
+    locale("it-IT", ',', '.', AFTER_SPACED, DateFormatStyle::Medium, "%d/%m/%Y", Weekday::Mon, SAT_SUN),
+    locale("pt-PT", ',', ' ', AFTER_SPACED, DateFormatStyle::Medium, "%d/%m/%Y", Weekday::Mon, SAT_SUN),
+    locale("pt-BR", ',', '.', BEFORE_SPACED, DateFormatStyle::Medium, "%d/%m/%Y", Weekday::Sun, SAT_SUN),
+    locale("nl-NL", ',', '.', BEFORE_SPACED, DateFormatStyle::Medium, "%d-%m-%Y", Weekday::Mon, SAT_SUN),
+    locale("sv-SE", ',', ' ', AFTER_SPACED, DateFormatStyle::Medium, "%Y-%m-%d", Weekday::Mon, SAT_SUN),
+    locale("da-DK", ',', '.', AFTER_SPACED, DateFormatStyle::Medium, "%d.%m.%Y", Weekday::Mon, SAT_SUN),
+    locale("nb-NO", ',', ' ', AFTER_SPACED, DateFormatStyle::Medium, "%d.%m.%Y", Weekday::Mon, SAT_SUN),
+    locale("fi-FI", ',', ' ', AFTER_SPACED, DateFormatStyle::Medium, "%d.%m.%Y", Weekday::Mon, SAT_SUN),
+    locale("pl-PL", ',', ' ', AFTER_SPACED, DateFormatStyle::Medium, "%d.%m.%Y", Weekday::Mon, SAT_SUN),
+    locale("ja-JP", '.', ',', BEFORE, DateFormatStyle::Medium, "%Y/%m/%d", Weekday::Sun, SAT_SUN),
+    locale("he-IL", '.', ',', AFTER_SPACED, DateFormatStyle::Medium, "%d.%m.%Y", Weekday::Sun, FRI_SAT),
+];
+
+impl LocaleData {
+    /// Finds conventions for `tag`, accepting `_` or `-` separators in any
+    /// case and falling back to the first locale sharing the language subtag.
+    pub fn lookup(tag: &str) -> Option<&'static LocaleData> {
+        let normalized = tag.trim().replace('_', "-");
+        if normalized.is_empty() {
+            return None;
+        }
+        LOCALES
+            .iter()
+            .find(|data| data.tag.eq_ignore_ascii_case(&normalized))
+            .or_else(|| {
+                let language = normalized.split('-').next()?;
+                LOCALES.iter().find(|data| {
+                    data.tag
+                        .split('-')
+                        .next()
+                        .is_some_and(|candidate| candidate.eq_ignore_ascii_case(language))
+                })
+            })
+    }
+
+    pub fn to_config(&self) -> LocaleConfig {
+        LocaleConfig {
+            language_tag: self.tag.into(),
+            decimal_separator: self.decimal_separator,
+            grouping_separator: self.grouping_separator,
+            date_format: self.date_style,
+            first_weekday: self.first_weekday,
+            weekend: self.weekend.to_vec(),
+            symbol_position: None,
+            symbol_spacing: None,
+            extra: ExtraFields::new(),
+        }
+    }
+}
+
+impl LocaleConfig {
+    /// Builds the configuration for a known locale tag.
+    pub fn for_tag(tag: &str) -> Option<LocaleConfig> {
+        LocaleData::lookup(tag).map(LocaleData::to_config)
+    }
+}
@@ -0,0 +1,46 @@
+//! Household members that transactions can be attributed to.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    common::{Displayable, ExtraFields, Identifiable, NamedEntity},
+    ids::new_id,
+};
+
+/// A person sharing the ledger, recorded as the one who made a transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Member {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl Member {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            id: new_id(),
+            name: name.into(),
+            extra: ExtraFields::new(),
+        }
+    }
+}
+
+impl Identifiable for Member {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+impl NamedEntity for Member {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Displayable for Member {
+    fn display_label(&self) -> String {
+        self.name.clone()
+    }
+}
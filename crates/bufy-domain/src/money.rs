@@ -0,0 +1,213 @@
+//! Fixed-point money: a whole number of minor units in one currency.
+//!
+//! Transaction amounts, category budgets, and opening balances are stored as
+//! [`Money`], so adding many cents never drifts. Ledgers written before that
+//! kept bare `f64` amounts; those still load, in no currency until the ledger
+//! assigns each amount the currency it is in.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::amount::{is_exact_amount, AmountOverflow};
+use crate::currency::{minor_units_for, CurrencyCode};
+
+/// ISO 4217's code for "no currency", given to bare amounts read from older
+/// ledgers.
+const UNASSIGNED: &str = "XXX";
+
+/// Decimal places kept for an unassigned amount: the most any ISO 4217
+/// currency uses, so nothing is lost before its currency is known.
+const UNASSIGNED_MINOR_UNITS: u8 = 4;
+
+/// An amount as a count of the currency's minor units, e.g. cents for USD
+/// and yen for JPY.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Money {
+    minor: i64,
+    currency: CurrencyCode,
+}
+
+/// Why a [`Money`] value could not be built or combined.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoneyError {
+    /// The amount is not finite or too large to count in minor units.
+    Overflow(AmountOverflow),
+    /// Amounts in different currencies were combined without conversion.
+    CurrencyMismatch {
+        left: CurrencyCode,
+        right: CurrencyCode,
+    },
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoneyError::Overflow(err) => err.fmt(f),
+            MoneyError::CurrencyMismatch { left, right } => write!(
+                f,
+                "cannot combine {} with {} without conversion",
+                left.as_str(),
+                right.as_str()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MoneyError {}
+
+impl From<AmountOverflow> for MoneyError {
+    fn from(err: AmountOverflow) -> Self {
+        MoneyError::Overflow(err)
+    }
+}
+
+impl Money {
+    pub fn from_minor(minor: i64, currency: CurrencyCode) -> Self {
+        Self { minor, currency }
+    }
+
+    pub fn zero(currency: CurrencyCode) -> Self {
+        Self::from_minor(0, currency)
+    }
+
+    /// Rounds `amount` to the nearest minor unit, halves away from zero.
+    pub fn from_major(amount: f64, currency: CurrencyCode) -> Result<Self, MoneyError> {
+        let overflow = AmountOverflow {
+            total: "amount",
+            value: amount,
+        };
+        if !is_exact_amount(amount) {
+            return Err(overflow.into());
+        }
+        let scaled = (amount * minor_scale(&currency)).round();
+        if !scaled.is_finite() || scaled.abs() >= i64::MAX as f64 {
+            return Err(overflow.into());
+        }
+        Ok(Self::from_minor(scaled as i64, currency))
+    }
+
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+
+    pub fn currency(&self) -> &CurrencyCode {
+        &self.currency
+    }
+
+    /// The amount in major units, for display, ratios, and conversion.
+    pub fn to_major(&self) -> f64 {
+        self.minor as f64 / minor_scale(&self.currency)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.minor == 0
+    }
+
+    /// The same amount counted in `currency`'s minor units, rounding halves
+    /// away from zero when `currency` has fewer decimal places. This relabels
+    /// an amount whose currency follows its account; it does not convert.
+    pub fn in_currency(&self, currency: &CurrencyCode) -> Result<Money, MoneyError> {
+        let from = minor_units(&self.currency);
+        let to = minor_units(currency);
+        let minor = if to >= from {
+            10i64
+                .checked_pow(u32::from(to - from))
+                .and_then(|factor| self.minor.checked_mul(factor))
+                .ok_or(AmountOverflow {
+                    total: "amount",
+                    value: self.to_major(),
+                })?
+        } else {
+            let factor = 10i64.pow(u32::from(from - to));
+            let (whole, rest) = (self.minor / factor, self.minor % factor);
+            if rest.abs() * 2 >= factor {
+                whole + self.minor.signum()
+            } else {
+                whole
+            }
+        };
+        Ok(Money::from_minor(minor, currency.clone()))
+    }
+
+    pub fn checked_add(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.combine(other, "sum", i64::checked_add)
+    }
+
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.combine(other, "difference", i64::checked_sub)
+    }
+
+    fn combine(
+        &self,
+        other: &Money,
+        total: &'static str,
+        op: fn(i64, i64) -> Option<i64>,
+    ) -> Result<Money, MoneyError> {
+        if self.currency != other.currency {
+            return Err(MoneyError::CurrencyMismatch {
+                left: self.currency.clone(),
+                right: other.currency.clone(),
+            });
+        }
+        let minor = op(self.minor, other.minor).ok_or(AmountOverflow {
+            total,
+            value: self.to_major() + other.to_major(),
+        })?;
+        let sum = Money::from_minor(minor, self.currency.clone());
+        crate::amount::check_amount(total, sum.to_major())?;
+        Ok(sum)
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = usize::from(minor_units(&self.currency));
+        write!(
+            f,
+            "{:.*} {}",
+            digits,
+            self.to_major(),
+            self.currency.as_str()
+        )
+    }
+}
+
+/// Accepts `{"minor", "currency"}` as well as the float form
+/// `{"amount", "currency"}` and the bare numbers written before amounts were
+/// fixed-point. A bare number has no currency until the ledger assigns one.
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Minor { minor: i64, currency: CurrencyCode },
+            Float { amount: f64, currency: CurrencyCode },
+            Bare(f64),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Minor { minor, currency } => Ok(Money::from_minor(minor, currency)),
+            Repr::Float { amount, currency } => {
+                Money::from_major(amount, currency).map_err(serde::de::Error::custom)
+            }
+            Repr::Bare(amount) => Money::from_major(amount, CurrencyCode::new(UNASSIGNED))
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+fn minor_units(currency: &CurrencyCode) -> u8 {
+    if currency.as_str() == UNASSIGNED {
+        UNASSIGNED_MINOR_UNITS
+    } else {
+        minor_units_for(currency.as_str())
+    }
+}
+
+fn minor_scale(currency: &CurrencyCode) -> f64 {
+    10f64.powi(i32::from(minor_units(currency)))
+}
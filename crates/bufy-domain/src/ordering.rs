@@ -0,0 +1,118 @@
+//! Display order for accounts and categories.
+//!
+//! The order of the ledger's vectors is the order users see in lists and
+//! pickers. Favorites form a block at the front; within each block items
+//! keep the order the user arranged.
+
+use std::fmt;
+
+use crate::{account::Account, category::Category};
+
+/// Entities the user can star so they sort ahead of the rest.
+pub trait Pinnable {
+    fn is_favorite(&self) -> bool;
+    fn set_favorite(&mut self, favorite: bool);
+}
+
+impl Pinnable for Account {
+    fn is_favorite(&self) -> bool {
+        self.favorite
+    }
+
+    fn set_favorite(&mut self, favorite: bool) {
+        self.favorite = favorite;
+    }
+}
+
+impl Pinnable for Category {
+    fn is_favorite(&self) -> bool {
+        self.favorite
+    }
+
+    fn set_favorite(&mut self, favorite: bool) {
+        self.favorite = favorite;
+    }
+}
+
+/// Where to move an item within its block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveDirection {
+    Up,
+    Down,
+    Top,
+    Bottom,
+}
+
+impl MoveDirection {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "up" => Some(Self::Up),
+            "down" => Some(Self::Down),
+            "top" | "first" => Some(Self::Top),
+            "bottom" | "last" => Some(Self::Bottom),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MoveDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            MoveDirection::Up => "up",
+            MoveDirection::Down => "down",
+            MoveDirection::Top => "top",
+            MoveDirection::Bottom => "bottom",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Moves favorites ahead of the rest, keeping the relative order of both.
+pub fn pin_favorites<T: Pinnable>(items: &mut Vec<T>) {
+    if items
+        .windows(2)
+        .all(|pair| pair[0].is_favorite() || !pair[1].is_favorite())
+    {
+        return;
+    }
+    let (mut favorites, rest): (Vec<T>, Vec<T>) = items.drain(..).partition(T::is_favorite);
+    favorites.extend(rest);
+    *items = favorites;
+}
+
+/// Moves the item at `index` one step (or to the edge) within its block,
+/// skipping items `visible` rejects so each step is one the user can see.
+/// Returns the item's new index; it stays put at the block's edge.
+pub fn move_within_block<T: Pinnable>(
+    items: &mut Vec<T>,
+    index: usize,
+    direction: MoveDirection,
+    visible: impl Fn(&T) -> bool,
+) -> usize {
+    let favorite = items[index].is_favorite();
+    let in_block = |item: &T| item.is_favorite() == favorite && visible(item);
+    let target = match direction {
+        MoveDirection::Up => (0..index).rev().find(|&i| in_block(&items[i])),
+        MoveDirection::Down => (index + 1..items.len()).find(|&i| in_block(&items[i])),
+        MoveDirection::Top => (0..index).find(|&i| in_block(&items[i])),
+        MoveDirection::Bottom => (index + 1..items.len())
+            .rev()
+            .find(|&i| in_block(&items[i])),
+    };
+    let Some(target) = target else {
+        return index;
+    };
+    let item = items.remove(index);
+    items.insert(target, item);
+    target
+}
+
+/// Stars or unstars the item at `index`, moving it to the end of the
+/// favorites or the start of the rest. Returns the item's new index.
+pub fn set_favorite_at<T: Pinnable>(items: &mut Vec<T>, index: usize, favorite: bool) -> usize {
+    let mut item = items.remove(index);
+    item.set_favorite(favorite);
+    let boundary = items.iter().take_while(|item| item.is_favorite()).count();
+    items.insert(boundary, item);
+    boundary
+}
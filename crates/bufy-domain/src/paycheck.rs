@@ -0,0 +1,92 @@
+//! Paycheck profiles that split one salary payment into the deductions taken
+//! before it arrives and the allocations made once it does.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{common::ExtraFields, ids::new_id};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaycheckProfile {
+    pub id: Uuid,
+    pub name: String,
+    /// Employer (usually an income-source account) the pay comes from.
+    pub income_account: Uuid,
+    /// Account the pay lands in and allocations are paid from.
+    pub deposit_account: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub income_category: Option<Uuid>,
+    /// Gross pay used when a paycheck is received without an amount.
+    pub gross: f64,
+    /// Taxes and other withholdings; percentages apply to gross pay.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deductions: Vec<PaycheckLine>,
+    /// Where the pay goes next; percentages apply to net pay.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allocations: Vec<PaycheckLine>,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl PaycheckProfile {
+    pub fn new(
+        name: impl Into<String>,
+        income_account: Uuid,
+        deposit_account: Uuid,
+        gross: f64,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: new_id(),
+            name: name.into(),
+            income_account,
+            deposit_account,
+            income_category: None,
+            gross,
+            deductions: Vec::new(),
+            allocations: Vec::new(),
+            created_at,
+            extra: ExtraFields::new(),
+        }
+    }
+}
+
+/// One deduction or allocation: an amount moved to `account`, optionally
+/// booked to a category.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaycheckLine {
+    pub amount: PaycheckAmount,
+    pub account: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum PaycheckAmount {
+    Percent(f64),
+    Fixed(f64),
+}
+
+impl PaycheckAmount {
+    /// Amount taken from `base`, rounded to cents.
+    pub fn of(&self, base: f64) -> f64 {
+        match self {
+            PaycheckAmount::Percent(percent) => (base * percent).round() / 100.0,
+            PaycheckAmount::Fixed(amount) => *amount,
+        }
+    }
+}
+
+impl fmt::Display for PaycheckAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaycheckAmount::Percent(percent) => write!(f, "{}%", percent),
+            PaycheckAmount::Fixed(amount) => write!(f, "{:.2}", amount),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! Incomplete transactions dropped off by external clients (quick-capture
+//! apps, email parsers, OCR) and held for review before they reach the ledger.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    common::{ExtraFields, Identifiable},
+    ids::new_id,
+};
+
+/// A transaction draft awaiting approval. Drafts live outside
+/// `Ledger::transactions`, so they never affect balances or summaries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingDraft {
+    pub id: Uuid,
+    /// Client that submitted the draft, e.g. `mobile` or `email`.
+    pub source: String,
+    pub received_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    /// Merchant or counterparty text as captured by the client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_account: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_account: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl PendingDraft {
+    pub fn new(source: impl Into<String>, received_at: DateTime<Utc>) -> Self {
+        Self {
+            id: new_id(),
+            source: source.into(),
+            received_at,
+            date: None,
+            amount: None,
+            payee: None,
+            from_account: None,
+            to_account: None,
+            category_id: None,
+            notes: None,
+            extra: ExtraFields::new(),
+        }
+    }
+
+    /// Names of the fields still needed before the draft can become a
+    /// transaction.
+    pub fn missing_fields(&self) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        if self.date.is_none() {
+            missing.push("date");
+        }
+        if self.amount.is_none() {
+            missing.push("amount");
+        }
+        if self.from_account.is_none() {
+            missing.push("from account");
+        }
+        if self.to_account.is_none() {
+            missing.push("to account");
+        }
+        missing
+    }
+}
+
+impl Identifiable for PendingDraft {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
@@ -0,0 +1,46 @@
+//! Bank statements that an account's recorded activity is checked against.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{common::ExtraFields, ids::new_id};
+
+/// One statement from the bank: the balance it reports at the end of
+/// `statement_date`, and the transactions ticked off as appearing on it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReconciliationStatement {
+    pub id: Uuid,
+    /// Last day the statement covers.
+    pub statement_date: NaiveDate,
+    pub closing_balance: f64,
+    /// Transactions cleared against this statement.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cleared: Vec<Uuid>,
+    /// Set once the cleared balance matched the closing balance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconciled_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl ReconciliationStatement {
+    pub fn new(statement_date: NaiveDate, closing_balance: f64) -> Self {
+        Self {
+            id: new_id(),
+            statement_date,
+            closing_balance,
+            cleared: Vec::new(),
+            reconciled_at: None,
+            extra: ExtraFields::new(),
+        }
+    }
+
+    pub fn is_reconciled(&self) -> bool {
+        self.reconciled_at.is_some()
+    }
+
+    pub fn is_cleared(&self, transaction_id: Uuid) -> bool {
+        self.cleared.contains(&transaction_id)
+    }
+}
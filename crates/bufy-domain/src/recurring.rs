@@ -4,6 +4,9 @@ use chrono::{Duration, NaiveDate};
 use uuid::Uuid;
 
 use crate::{
+    amount::{check_amount, checked_sum, AmountOverflow},
+    business_days::BusinessCalendar,
+    ids::new_id,
     ledger::DateWindow,
     transaction::{Recurrence, RecurrenceMode, RecurrenceStatus, Transaction, TransactionStatus},
 };
@@ -66,7 +69,7 @@ impl ForecastTotals {
             ..ForecastTotals::default()
         };
         for item in transactions {
-            let amount = item.transaction.budgeted_amount;
+            let amount = item.transaction.budgeted_amount.to_major();
             if amount >= 0.0 {
                 totals.projected_outflow += amount;
             } else {
@@ -76,6 +79,30 @@ impl ForecastTotals {
         totals.net = totals.projected_inflow - totals.projected_outflow;
         totals
     }
+
+    /// Like the totals on a [`ForecastResult`], but fails at the first
+    /// running total that leaves the range where cents are exact.
+    pub fn checked(transactions: &[ForecastTransaction]) -> Result<Self, AmountOverflow> {
+        let amounts = || {
+            transactions
+                .iter()
+                .map(|item| item.transaction.budgeted_amount.to_major())
+        };
+        let projected_outflow = checked_sum(
+            "projected outflow",
+            amounts().filter(|amount| *amount >= 0.0),
+        )?;
+        let projected_inflow = checked_sum(
+            "projected inflow",
+            amounts().filter(|amount| *amount < 0.0).map(f64::abs),
+        )?;
+        Ok(ForecastTotals {
+            generated: transactions.len(),
+            projected_inflow,
+            projected_outflow,
+            net: check_amount("net", projected_inflow - projected_outflow)?,
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -87,6 +114,13 @@ pub struct ForecastResult {
     pub totals: ForecastTotals,
 }
 
+impl ForecastResult {
+    /// Recomputes [`ForecastResult::totals`] with overflow checks.
+    pub fn checked_totals(&self) -> Result<ForecastTotals, AmountOverflow> {
+        ForecastTotals::checked(&self.transactions)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RecurrenceSnapshot {
     pub series_id: Uuid,
@@ -121,12 +155,40 @@ struct Occurrence<'a> {
     transaction: Option<&'a Transaction>,
 }
 
+/// Occurrences of the recurring series scheduled inside `window`. Dates
+/// move off non-business days of `calendar` as each recurrence asks.
 pub fn forecast_for_window(
     window: DateWindow,
     reference: NaiveDate,
     transactions: &[Transaction],
+    calendar: BusinessCalendar<'_>,
 ) -> ForecastResult {
     let series_map = collect_series_entries(transactions);
+    forecast_from_series(window, reference, transactions, &series_map, calendar)
+}
+
+/// Forecast for `window` plus recurrence snapshots, grouping the recurring
+/// series once for both.
+pub fn forecast_with_snapshots(
+    window: DateWindow,
+    reference: NaiveDate,
+    transactions: &[Transaction],
+    calendar: BusinessCalendar<'_>,
+) -> (ForecastResult, Vec<RecurrenceSnapshot>) {
+    let series_map = collect_series_entries(transactions);
+    (
+        forecast_from_series(window, reference, transactions, &series_map, calendar),
+        snapshots_from_series(reference, transactions, &series_map, calendar),
+    )
+}
+
+fn forecast_from_series(
+    window: DateWindow,
+    reference: NaiveDate,
+    transactions: &[Transaction],
+    series_map: &HashMap<Uuid, Vec<&Transaction>>,
+    calendar: BusinessCalendar<'_>,
+) -> ForecastResult {
     let mut instances = Vec::new();
     let mut generated = Vec::new();
 
@@ -139,7 +201,7 @@ pub fn forecast_for_window(
             .unwrap_or_else(|| vec![template]);
         entries.sort_by_key(|txn| txn.scheduled_date);
         let (series_instances, series_generated) =
-            project_series_in_window(template, recurrence, &entries, window, reference);
+            project_series_in_window(template, recurrence, &entries, window, reference, calendar);
         instances.extend(series_instances);
         generated.extend(series_generated);
     }
@@ -159,8 +221,18 @@ pub fn forecast_for_window(
 pub fn snapshot_recurrences(
     transactions: &[Transaction],
     reference: NaiveDate,
+    calendar: BusinessCalendar<'_>,
 ) -> Vec<RecurrenceSnapshot> {
     let series_map = collect_series_entries(transactions);
+    snapshots_from_series(reference, transactions, &series_map, calendar)
+}
+
+fn snapshots_from_series(
+    reference: NaiveDate,
+    transactions: &[Transaction],
+    series_map: &HashMap<Uuid, Vec<&Transaction>>,
+    calendar: BusinessCalendar<'_>,
+) -> Vec<RecurrenceSnapshot> {
     let mut snapshots = Vec::new();
     let lookahead_end = reference + Duration::days(SNAPSHOT_LOOKAHEAD_DAYS);
 
@@ -172,7 +244,7 @@ pub fn snapshot_recurrences(
             .cloned()
             .unwrap_or_else(|| vec![template]);
         entries.sort_by_key(|txn| txn.scheduled_date);
-        let occurrences = build_occurrences(recurrence, &entries, lookahead_end);
+        let occurrences = build_occurrences(recurrence, &entries, lookahead_end, calendar);
         let mut overdue = 0usize;
         let mut pending = 0usize;
         let mut next_due = recurrence.next_scheduled;
@@ -223,7 +295,10 @@ pub fn snapshot_recurrences(
     snapshots
 }
 
-pub fn rebuild_metadata(transactions: &[Transaction]) -> HashMap<Uuid, SeriesMetadata> {
+pub fn rebuild_metadata(
+    transactions: &[Transaction],
+    calendar: BusinessCalendar<'_>,
+) -> HashMap<Uuid, SeriesMetadata> {
     let mut states: HashMap<Uuid, Vec<StateInfo>> = HashMap::new();
     for txn in transactions {
         if let Some(series_id) = txn.recurrence_series() {
@@ -259,7 +334,7 @@ pub fn rebuild_metadata(transactions: &[Transaction]) -> HashMap<Uuid, SeriesMet
             .iter()
             .filter_map(|state| state.actual_date)
             .max();
-        let next_due = next_due_from_states(recurrence, &series_states);
+        let next_due = next_due_from_states(recurrence, &series_states, calendar);
         metadata.insert(
             series_id,
             SeriesMetadata {
@@ -282,6 +357,7 @@ pub fn rebuild_metadata(transactions: &[Transaction]) -> HashMap<Uuid, SeriesMet
 pub fn materialize_due_instances(
     reference: NaiveDate,
     transactions: &[Transaction],
+    calendar: BusinessCalendar<'_>,
 ) -> Vec<Transaction> {
     let mut creations = Vec::new();
     let limit_end = reference + Duration::days(1);
@@ -297,7 +373,7 @@ pub fn materialize_due_instances(
             .get(&series_id)
             .cloned()
             .unwrap_or_else(|| vec![template]);
-        let occurrences = build_occurrences(recurrence, &entries, limit_end);
+        let occurrences = build_occurrences(recurrence, &entries, limit_end, calendar);
         for occurrence in occurrences {
             if occurrence.scheduled_date > reference {
                 continue;
@@ -306,7 +382,8 @@ pub fn materialize_due_instances(
                 continue;
             }
             let mut txn = template.clone();
-            txn.id = Uuid::new_v4();
+            txn.id = new_id();
+            txn.reference = None;
             txn.scheduled_date = occurrence.scheduled_date;
             txn.actual_date = None;
             txn.actual_amount = None;
@@ -342,8 +419,9 @@ fn project_series_in_window(
     entries: &[&Transaction],
     window: DateWindow,
     reference: NaiveDate,
+    calendar: BusinessCalendar<'_>,
 ) -> (Vec<ScheduledInstance>, Vec<ForecastTransaction>) {
-    let occurrences = build_occurrences(recurrence, entries, window.end);
+    let occurrences = build_occurrences(recurrence, entries, window.end, calendar);
     let mut instances = Vec::new();
     let mut generated = Vec::new();
     let series_id = template.recurrence_series().unwrap_or(template.id);
@@ -373,7 +451,7 @@ fn project_series_in_window(
                 }
                 let status = ScheduledStatus::classify(occurrence.scheduled_date, reference);
                 let mut forecast = template.clone();
-                forecast.id = Uuid::new_v4();
+                forecast.id = new_id();
                 forecast.scheduled_date = occurrence.scheduled_date;
                 forecast.actual_date = None;
                 forecast.actual_amount = None;
@@ -403,10 +481,15 @@ fn project_series_in_window(
     (instances, generated)
 }
 
+/// The series' occurrences up to `limit_end`, each paired with the ledger
+/// entry recorded for it. The cadence follows the nominal dates; each
+/// occurrence is then scheduled on the business day its recurrence asks
+/// for, and an entry on either date belongs to it.
 fn build_occurrences<'a>(
     recurrence: &Recurrence,
     entries: &[&'a Transaction],
     limit_end: NaiveDate,
+    calendar: BusinessCalendar<'_>,
 ) -> Vec<Occurrence<'a>> {
     let mut result = Vec::new();
     if limit_end <= recurrence.start_date {
@@ -418,26 +501,28 @@ fn build_occurrences<'a>(
     let mut iter = sorted_entries.into_iter().peekable();
 
     let mut occurrence_index = 0u32;
-    let mut scheduled_date = recurrence.start_date;
+    let mut nominal = recurrence.start_date;
     let mut guard = 0usize;
 
-    while scheduled_date < limit_end && guard < MAX_FORECAST_OCCURRENCES {
-        if !recurrence.allows_occurrence(occurrence_index, scheduled_date) {
+    while nominal < limit_end && guard < MAX_FORECAST_OCCURRENCES {
+        if !recurrence.allows_occurrence(occurrence_index, nominal) {
             break;
         }
-        if recurrence.is_exception(scheduled_date) {
-            scheduled_date = recurrence.interval.next_date(scheduled_date);
+        let scheduled_date = calendar.shift(nominal, recurrence.business_day_shift);
+        if recurrence.is_exception(nominal) || recurrence.is_exception(scheduled_date) {
+            nominal = recurrence.interval.next_date(nominal);
             continue;
         }
+        let earliest = nominal.min(scheduled_date);
         while let Some(next_txn) = iter.peek() {
-            if next_txn.scheduled_date < scheduled_date {
+            if next_txn.scheduled_date < earliest {
                 iter.next();
             } else {
                 break;
             }
         }
         let txn = if let Some(next_txn) = iter.peek() {
-            if next_txn.scheduled_date == scheduled_date {
+            if next_txn.scheduled_date == scheduled_date || next_txn.scheduled_date == nominal {
                 iter.next()
             } else {
                 None
@@ -451,12 +536,12 @@ fn build_occurrences<'a>(
             transaction: txn,
         });
         let anchor = match recurrence.mode {
-            RecurrenceMode::FixedSchedule => scheduled_date,
+            RecurrenceMode::FixedSchedule => nominal,
             RecurrenceMode::AfterLastPerformed => {
-                txn.and_then(|t| t.actual_date).unwrap_or(scheduled_date)
+                txn.and_then(|t| t.actual_date).unwrap_or(nominal)
             }
         };
-        scheduled_date = recurrence.interval.next_date(anchor);
+        nominal = recurrence.interval.next_date(anchor);
         occurrence_index += 1;
         guard += 1;
     }
@@ -464,12 +549,16 @@ fn build_occurrences<'a>(
     result
 }
 
-fn next_due_from_states(recurrence: &Recurrence, states: &[StateInfo]) -> Option<NaiveDate> {
+fn next_due_from_states(
+    recurrence: &Recurrence,
+    states: &[StateInfo],
+    calendar: BusinessCalendar<'_>,
+) -> Option<NaiveDate> {
     if recurrence.status == RecurrenceStatus::Completed {
         return None;
     }
     if states.is_empty() {
-        return Some(recurrence.start_date);
+        return Some(calendar.shift(recurrence.start_date, recurrence.business_day_shift));
     }
     let last_scheduled = states
         .iter()
@@ -481,6 +570,11 @@ fn next_due_from_states(recurrence: &Recurrence, states: &[StateInfo]) -> Option
         .rev()
         .find(|state| state.scheduled_date == last_scheduled)
         .and_then(|state| state.actual_date);
+    if !recurrence.business_day_shift.is_keep() && recurrence.mode == RecurrenceMode::FixedSchedule
+    {
+        // A shifted date is off the cadence, so step from the start instead.
+        return next_shifted_due(recurrence, last_scheduled, calendar);
+    }
     let mut candidate = recurrence.next_occurrence(last_scheduled, last_actual);
     let mut attempts = 0usize;
     while recurrence.is_exception(candidate) {
@@ -491,8 +585,36 @@ fn next_due_from_states(recurrence: &Recurrence, states: &[StateInfo]) -> Option
         }
     }
     if recurrence.allows_occurrence(states.len() as u32, candidate) {
-        Some(candidate)
+        Some(calendar.shift(candidate, recurrence.business_day_shift))
     } else {
         None
     }
 }
+
+/// First business-day-shifted occurrence of a fixed schedule after
+/// `last_scheduled`.
+fn next_shifted_due(
+    recurrence: &Recurrence,
+    last_scheduled: NaiveDate,
+    calendar: BusinessCalendar<'_>,
+) -> Option<NaiveDate> {
+    let mut nominal = recurrence.start_date;
+    let mut occurrence_index = 0u32;
+    loop {
+        if !recurrence.allows_occurrence(occurrence_index, nominal) {
+            return None;
+        }
+        let scheduled = calendar.shift(nominal, recurrence.business_day_shift);
+        if !recurrence.is_exception(nominal) && !recurrence.is_exception(scheduled) {
+            if scheduled > last_scheduled {
+                return Some(scheduled);
+            }
+            occurrence_index += 1;
+        }
+        let next = recurrence.interval.next_date(nominal);
+        if next <= nominal {
+            return None;
+        }
+        nominal = next;
+    }
+}
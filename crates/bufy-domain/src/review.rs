@@ -0,0 +1,98 @@
+//! Guided budget reviews and the log that lets an unfinished review resume.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{common::ExtraFields, ids::new_id};
+
+/// Stages of a review, visited in declaration order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewStep {
+    #[default]
+    Uncategorized,
+    OverBudget,
+    UpcomingBills,
+    BudgetAdjustments,
+    Done,
+}
+
+impl ReviewStep {
+    /// Steps that have items to work through, excluding [`ReviewStep::Done`].
+    pub const ALL: [ReviewStep; 4] = [
+        ReviewStep::Uncategorized,
+        ReviewStep::OverBudget,
+        ReviewStep::UpcomingBills,
+        ReviewStep::BudgetAdjustments,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            ReviewStep::Uncategorized => ReviewStep::OverBudget,
+            ReviewStep::OverBudget => ReviewStep::UpcomingBills,
+            ReviewStep::UpcomingBills => ReviewStep::BudgetAdjustments,
+            ReviewStep::BudgetAdjustments | ReviewStep::Done => ReviewStep::Done,
+        }
+    }
+}
+
+impl fmt::Display for ReviewStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ReviewStep::Uncategorized => "Uncategorized transactions",
+            ReviewStep::OverBudget => "Over-budget categories",
+            ReviewStep::UpcomingBills => "Upcoming bills",
+            ReviewStep::BudgetAdjustments => "Budget adjustments",
+            ReviewStep::Done => "Done",
+        };
+        f.write_str(label)
+    }
+}
+
+/// One review session. A session without `completed_at` is picked up again
+/// by the next review.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetReview {
+    pub id: Uuid,
+    pub started_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub step: ReviewStep,
+    /// Items of the current step already dealt with; cleared on each new step.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub handled: Vec<Uuid>,
+    /// What was decided during the session, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log: Vec<ReviewLogEntry>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl BudgetReview {
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            id: new_id(),
+            started_at,
+            completed_at: None,
+            step: ReviewStep::default(),
+            handled: Vec::new(),
+            log: Vec::new(),
+            extra: ExtraFields::new(),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.completed_at.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewLogEntry {
+    pub at: DateTime<Utc>,
+    pub step: ReviewStep,
+    pub message: String,
+}
@@ -7,13 +7,16 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
+    common::ExtraFields,
+    ids::new_id,
     ledger::{BudgetSummary, BudgetTotalsDelta},
-    transaction::Transaction,
+    time,
+    transaction::{sync_income_series, Transaction},
     CategoryBudgetSummary,
 };
 
 fn default_simulation_id() -> Uuid {
-    Uuid::new_v4()
+    new_id()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +40,10 @@ pub struct Simulation {
     pub name: String,
     #[serde(default)]
     pub notes: Option<String>,
+    /// The simulation this one branches from. Its pending changes come
+    /// first whenever this simulation is previewed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Uuid>,
     #[serde(default)]
     pub status: SimulationStatus,
     pub created_at: DateTime<Utc>,
@@ -44,27 +51,72 @@ pub struct Simulation {
     pub updated_at: DateTime<Utc>,
     #[serde(default)]
     pub applied_at: Option<DateTime<Utc>>,
+    /// Backup taken, or found fresh enough, right before the simulation was
+    /// applied; restoring it undoes the apply.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_apply_backup: Option<String>,
     #[serde(default)]
     pub changes: Vec<SimulationChange>,
+    /// One entry per apply, oldest first, holding what is needed to undo it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub applied: Vec<AppliedChangeSet>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl Simulation {
     /// Creates a new simulation with the provided display name.
     pub fn new(name: impl Into<String>) -> Self {
-        let now = Utc::now();
+        let now = time::now();
         Self {
-            id: Uuid::new_v4(),
+            id: new_id(),
             name: name.into(),
             notes: None,
+            parent: None,
             status: SimulationStatus::Pending,
             created_at: now,
             updated_at: now,
             applied_at: None,
+            pre_apply_backup: None,
             changes: Vec::new(),
+            applied: Vec::new(),
+            extra: ExtraFields::new(),
         }
     }
 }
 
+/// The inverse of one apply: every transaction it touched, as it was before
+/// and right after.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedChangeSet {
+    pub applied_at: DateTime<Utc>,
+    /// Changes the apply took off the pending list. Empty when the whole
+    /// simulation was applied, since its changes stay listed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<SimulationChange>,
+    pub transactions: Vec<TransactionRevert>,
+}
+
+/// One transaction touched by an apply. `before` is `None` for a transaction
+/// the simulation added, `after` is `None` for one it removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionRevert {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before: Option<Transaction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<Transaction>,
+}
+
+impl TransactionRevert {
+    pub fn transaction_id(&self) -> Uuid {
+        self.after
+            .as_ref()
+            .or(self.before.as_ref())
+            .map(|txn| txn.id)
+            .unwrap_or_default()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 /// Enumerates the lifecycle state of a simulation.
 #[derive(Default)]
@@ -89,13 +141,25 @@ impl fmt::Display for SimulationStatus {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 /// Tracks an individual change within a simulation.
+#[allow(clippy::large_enum_variant)]
 pub enum SimulationChange {
     AddTransaction { transaction: Transaction },
     ModifyTransaction(SimulationTransactionPatch),
     ExcludeTransaction { transaction_id: Uuid },
+    ModifyIncome(IncomeModelPatch),
 }
 
 impl SimulationChange {
+    /// The existing transaction the change targets; `None` for additions.
+    pub fn target_transaction(&self) -> Option<Uuid> {
+        match self {
+            SimulationChange::AddTransaction { .. } => None,
+            SimulationChange::ModifyTransaction(patch) => Some(patch.transaction_id),
+            SimulationChange::ExcludeTransaction { transaction_id } => Some(*transaction_id),
+            SimulationChange::ModifyIncome(patch) => Some(patch.transaction_id),
+        }
+    }
+
     pub fn summary(&self) -> String {
         match self {
             SimulationChange::AddTransaction { transaction } => {
@@ -107,6 +171,9 @@ impl SimulationChange {
             SimulationChange::ExcludeTransaction { transaction_id } => {
                 format!("Remove transaction {}", transaction_id)
             }
+            SimulationChange::ModifyIncome(patch) => {
+                format!("Modify income {}", patch.transaction_id)
+            }
         }
     }
 }
@@ -144,6 +211,49 @@ impl SimulationTransactionPatch {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Changes the income model of a recurring paycheck; its net amount follows.
+pub struct IncomeModelPatch {
+    /// Template transaction carrying the income recurrence.
+    pub transaction_id: Uuid,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gross: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withholding_percent: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub benefits: Option<f64>,
+}
+
+impl IncomeModelPatch {
+    /// Determines whether the patch mutates at least one attribute.
+    pub fn has_effect(&self) -> bool {
+        self.gross.is_some() || self.withholding_percent.is_some() || self.benefits.is_some()
+    }
+
+    /// Applies the patch to `transactions`, re-deriving the series' net pay.
+    /// Returns `false` when the target is not an income recurrence.
+    pub fn apply(&self, transactions: &mut [Transaction]) -> bool {
+        let Some(income) = transactions
+            .iter_mut()
+            .find(|txn| txn.id == self.transaction_id)
+            .and_then(|txn| txn.recurrence.as_mut())
+            .and_then(|recurrence| recurrence.income.as_mut())
+        else {
+            return false;
+        };
+        if let Some(gross) = self.gross {
+            income.gross = gross;
+        }
+        if let Some(percent) = self.withholding_percent {
+            income.withholding_percent = percent;
+        }
+        if let Some(benefits) = self.benefits {
+            income.benefits = benefits;
+        }
+        sync_income_series(transactions, self.transaction_id)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Simplified change log entry used after simulation evaluation.
 pub struct SimulatedChange {
@@ -177,10 +287,7 @@ impl From<&SimulationChange> for SimulatedChange {
             SimulationChange::AddTransaction { transaction } => Self {
                 target_id: transaction.id,
                 change_type: ChangeKind::Add,
-                delta: transaction
-                    .actual_amount
-                    .or(Some(transaction.budgeted_amount))
-                    .unwrap_or(0.0),
+                delta: transaction.amount().to_major(),
             },
             SimulationChange::ModifyTransaction(patch) => Self {
                 target_id: patch.transaction_id,
@@ -196,6 +303,11 @@ impl From<&SimulationChange> for SimulatedChange {
                 change_type: ChangeKind::Remove,
                 delta: 0.0,
             },
+            SimulationChange::ModifyIncome(patch) => Self {
+                target_id: patch.transaction_id,
+                change_type: ChangeKind::Modify,
+                delta: 0.0,
+            },
         }
     }
 }
@@ -0,0 +1,72 @@
+//! Sinking funds that spread large annual or irregular bills (insurance, car
+//! tax) over the months leading up to their due date.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    common::{ExtraFields, TimeInterval, TimeUnit},
+    ids::new_id,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinkingFund {
+    pub id: Uuid,
+    pub name: String,
+    /// Amount of the bill due at the end of each cycle.
+    pub amount: f64,
+    /// How often the bill recurs.
+    pub interval: TimeInterval,
+    /// Date the next bill is due.
+    pub next_due: NaiveDate,
+    /// Category the bill is paid from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category_id: Option<Uuid>,
+    /// Money set aside so far towards the next bill.
+    #[serde(default)]
+    pub saved: f64,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl SinkingFund {
+    /// Creates a yearly fund with nothing set aside yet.
+    pub fn new(
+        name: impl Into<String>,
+        amount: f64,
+        next_due: NaiveDate,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: new_id(),
+            name: name.into(),
+            amount,
+            interval: TimeInterval {
+                every: 1,
+                unit: TimeUnit::Year,
+            },
+            next_due,
+            category_id: None,
+            saved: 0.0,
+            created_at,
+            extra: ExtraFields::new(),
+        }
+    }
+
+    /// First day of the saving cycle that ends at [`Self::next_due`].
+    pub fn cycle_start(&self) -> NaiveDate {
+        self.interval.previous_date(self.next_due)
+    }
+}
+
+/// Share of a sinking fund's bill earmarked within a summary window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SinkingFundAccrual {
+    pub fund_id: Uuid,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category_id: Option<Uuid>,
+    pub amount: f64,
+}
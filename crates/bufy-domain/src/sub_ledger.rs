@@ -0,0 +1,72 @@
+//! Lightweight sub-ledgers (e.g. a child's allowance) kept inside a parent
+//! ledger. Their accounts and transactions are stored separately so they never
+//! appear in the parent's categories or budget summaries.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    account::{Account, AccountKind},
+    common::ExtraFields,
+    ids::new_id,
+    transaction::Transaction,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubLedger {
+    pub id: Uuid,
+    pub name: String,
+    /// Household member the sub-ledger belongs to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member_id: Option<Uuid>,
+    #[serde(default)]
+    pub accounts: Vec<Account>,
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl SubLedger {
+    /// Name of the account holding the sub-ledger's money.
+    pub const WALLET: &'static str = "Wallet";
+    /// Name of the account deposits are drawn from.
+    pub const DEPOSITS: &'static str = "Allowance";
+    /// Name of the account spending is sent to.
+    pub const SPENDING: &'static str = "Spending";
+
+    /// Creates a sub-ledger with a wallet plus deposit and spending accounts.
+    pub fn new(name: impl Into<String>, created_at: DateTime<Utc>) -> Self {
+        Self {
+            id: new_id(),
+            name: name.into(),
+            member_id: None,
+            accounts: vec![
+                Account::new(Self::WALLET, AccountKind::Cash),
+                Account::new(Self::DEPOSITS, AccountKind::IncomeSource),
+                Account::new(Self::SPENDING, AccountKind::ExpenseDestination),
+            ],
+            transactions: Vec::new(),
+            created_at,
+            extra: ExtraFields::new(),
+        }
+    }
+
+    pub fn account(&self, id: Uuid) -> Option<&Account> {
+        self.accounts.iter().find(|account| account.id == id)
+    }
+
+    pub fn account_by_name(&self, name: &str) -> Option<&Account> {
+        self.accounts
+            .iter()
+            .find(|account| account.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn account_by_name_mut(&mut self, name: &str) -> Option<&mut Account> {
+        self.accounts
+            .iter_mut()
+            .find(|account| account.name.eq_ignore_ascii_case(name))
+    }
+}
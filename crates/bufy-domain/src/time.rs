@@ -0,0 +1,34 @@
+//! Where record timestamps come from.
+//!
+//! Constructors and [`crate::Ledger::touch`] call [`now`], which reads the
+//! system clock unless [`freeze_time`] pinned it to one instant, so tests
+//! and demos save identical ledgers on every run.
+
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+
+static FROZEN: RwLock<Option<DateTime<Utc>>> = RwLock::new(None);
+
+/// Makes [`now`] return `at` for the rest of the process.
+pub fn freeze_time(at: DateTime<Utc>) {
+    if let Ok(mut frozen) = FROZEN.write() {
+        *frozen = Some(at);
+    }
+}
+
+/// Goes back to the system clock.
+pub fn unfreeze_time() {
+    if let Ok(mut frozen) = FROZEN.write() {
+        *frozen = None;
+    }
+}
+
+/// The current instant, or the frozen one.
+pub fn now() -> DateTime<Utc> {
+    FROZEN
+        .read()
+        .ok()
+        .and_then(|frozen| *frozen)
+        .unwrap_or_else(Utc::now)
+}
@@ -6,26 +6,70 @@ use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::common::*;
+use crate::{
+    business_days::BusinessDayShift,
+    common::*,
+    currency::CurrencyCode,
+    ids::{new_id, ShortRef},
+    money::{Money, MoneyError},
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transaction {
     pub id: Uuid,
+    /// Ledger sequence number, shown as `T-0012`; assigned when the
+    /// transaction joins a ledger.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<u32>,
     pub from_account: Uuid,
     pub to_account: Uuid,
     pub category_id: Option<Uuid>,
     pub scheduled_date: NaiveDate,
     pub actual_date: Option<NaiveDate>,
-    pub budgeted_amount: f64,
-    pub actual_amount: Option<f64>,
+    /// Counted in the currency the transaction moves; see
+    /// `Ledger::transaction_currency`.
+    pub budgeted_amount: Money,
+    pub actual_amount: Option<Money>,
+    /// Date the bank booked the transaction, when a statement provides one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub booking_date: Option<NaiveDate>,
+    /// Date the money actually moved (value date), when a statement provides one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_date: Option<NaiveDate>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub currency: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
+    /// Household member who made the transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member_id: Option<Uuid>,
+    /// Merchant name as captured by the recording client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merchant: Option<String>,
+    /// Where the transaction took place, as captured by the recording client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<GeoLocation>,
+    /// Free-form labels such as `vacation2025`, lowercase and sorted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Receipts and other documents kept with the transaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<AttachmentRef>,
     pub recurrence: Option<Recurrence>,
     #[serde(default)]
     pub recurrence_series_id: Option<Uuid>,
+    /// Income transaction whose completion created this one via a transfer rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_transaction_id: Option<Uuid>,
+    /// Planned transaction this completed installment paid part of.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installment_of: Option<Uuid>,
+    /// Durable item this transaction bought.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset: Option<AssetRecord>,
     pub status: TransactionStatus,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
 }
 
 impl Transaction {
@@ -35,10 +79,11 @@ impl Transaction {
         to_account: Uuid,
         category_id: Option<Uuid>,
         scheduled_date: NaiveDate,
-        budgeted_amount: f64,
+        budgeted_amount: Money,
     ) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: new_id(),
+            reference: None,
             from_account,
             to_account,
             category_id,
@@ -46,14 +91,30 @@ impl Transaction {
             actual_date: None,
             budgeted_amount,
             actual_amount: None,
+            booking_date: None,
+            value_date: None,
             currency: None,
             notes: None,
+            member_id: None,
+            merchant: None,
+            location: None,
+            tags: Vec::new(),
+            attachments: Vec::new(),
             recurrence: None,
             recurrence_series_id: None,
+            source_transaction_id: None,
+            installment_of: None,
+            asset: None,
             status: TransactionStatus::Planned,
+            extra: ExtraFields::new(),
         }
     }
 
+    /// Short code such as `T-0012`, once the ledger has numbered it.
+    pub fn short_ref(&self) -> Option<ShortRef> {
+        self.reference.map(ShortRef::transaction)
+    }
+
     pub fn with_recurrence(mut self, recurrence: Recurrence) -> Self {
         self.set_recurrence(Some(recurrence));
         self
@@ -77,11 +138,101 @@ impl Transaction {
             .or_else(|| self.recurrence.as_ref().map(|_| self.id))
     }
 
-    pub fn mark_completed(&mut self, actual_date: NaiveDate, actual_amount: f64) {
+    pub fn mark_completed(&mut self, actual_date: NaiveDate, actual_amount: Money) {
         self.actual_date = Some(actual_date);
         self.actual_amount = Some(actual_amount);
         self.status = TransactionStatus::Completed;
     }
+
+    /// Counts the budgeted and actual amounts in `currency`; see
+    /// [`Money::in_currency`].
+    pub fn count_amounts_in(&mut self, currency: &CurrencyCode) -> Result<(), MoneyError> {
+        self.budgeted_amount = self.budgeted_amount.in_currency(currency)?;
+        if let Some(actual) = self.actual_amount.as_mut() {
+            *actual = actual.in_currency(currency)?;
+        }
+        Ok(())
+    }
+
+    /// The actual amount once recorded, else the budgeted one.
+    pub fn amount(&self) -> &Money {
+        self.actual_amount.as_ref().unwrap_or(&self.budgeted_amount)
+    }
+
+    /// Date the actual amount is reported on under `basis`, falling back to
+    /// the actual date when the transaction lacks the requested statement date.
+    pub fn reporting_date(&self, basis: DateBasis) -> Option<NaiveDate> {
+        match basis {
+            DateBasis::Actual => self.actual_date,
+            DateBasis::Booking => self.booking_date.or(self.actual_date),
+            DateBasis::Value => self.value_date.or(self.actual_date),
+        }
+    }
+}
+
+/// A document kept with a transaction: a file copied into the ledger's
+/// attachments folder, or a link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttachmentRef {
+    /// File name inside the attachments folder, or a URL.
+    pub location: String,
+    /// Name of the file as it was attached, shown in listings.
+    pub name: String,
+    /// 64-bit FNV-1a of the contents, in hex; `None` for links.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+}
+
+impl AttachmentRef {
+    pub fn is_link(&self) -> bool {
+        self.location.starts_with("http://") || self.location.starts_with("https://")
+    }
+}
+
+/// A point on the map, optionally with a human-readable place name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place: Option<String>,
+}
+
+impl GeoLocation {
+    /// Latitude within ±90° and longitude within ±180°.
+    pub fn is_valid(&self) -> bool {
+        (-90.0..=90.0).contains(&self.latitude) && (-180.0..=180.0).contains(&self.longitude)
+    }
+}
+
+impl fmt::Display for GeoLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.place {
+            Some(place) => f.write_str(place),
+            None => write!(f, "{:.5}, {:.5}", self.latitude, self.longitude),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+/// Selects which transaction date places actual amounts in summary windows.
+pub enum DateBasis {
+    #[default]
+    Actual,
+    Booking,
+    Value,
+}
+
+impl fmt::Display for DateBasis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            DateBasis::Actual => "actual",
+            DateBasis::Booking => "booking",
+            DateBasis::Value => "value",
+        };
+        f.write_str(label)
+    }
 }
 
 impl Identifiable for Transaction {
@@ -117,7 +268,7 @@ impl fmt::Display for TransactionStatus {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 /// Represents a recurrence rule attached to a transaction.
 pub struct Recurrence {
     #[serde(default = "Recurrence::default_series_id")]
@@ -140,6 +291,104 @@ pub struct Recurrence {
     pub generated_occurrences: u32,
     #[serde(default)]
     pub next_scheduled: Option<NaiveDate>,
+    /// Pay breakdown for income recurrences; the template amount is its net pay.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub income: Option<IncomeModel>,
+    /// Amounts the recurrence has had, oldest first. Empty until the amount
+    /// first changes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub price_history: Vec<PricePoint>,
+    /// Contract behind the payments, for bills that renew automatically.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<ContractTerms>,
+    /// Where occurrences that fall on a non-business day move.
+    #[serde(default, skip_serializing_if = "BusinessDayShift::is_keep")]
+    pub business_day_shift: BusinessDayShift,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// An item bought with a transaction, with its warranty and expected lifetime.
+pub struct AssetRecord {
+    pub name: String,
+    /// Last day the warranty covers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warranty_until: Option<NaiveDate>,
+    /// Months the item is expected to last, spreading its cost over them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifetime_months: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+/// Amount charged by a recurrence from `effective` onwards.
+pub struct PricePoint {
+    pub effective: NaiveDate,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Who a recurring bill is paid to under contract, and when it can be left.
+pub struct ContractTerms {
+    pub provider: String,
+    /// Last day of the current term; the contract renews after it unless
+    /// notice was given.
+    pub end_date: NaiveDate,
+    /// How long before `end_date` notice must be given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notice_period: Option<TimeInterval>,
+}
+
+impl ContractTerms {
+    /// Last day to give notice and avoid the automatic renewal.
+    pub fn notice_deadline(&self) -> NaiveDate {
+        self.notice_period
+            .as_ref()
+            .map(|notice| notice.previous_date(self.end_date))
+            .unwrap_or(self.end_date)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// Gross pay, withholding, and benefit deductions behind a recurring paycheck.
+pub struct IncomeModel {
+    pub gross: f64,
+    /// Share of gross pay withheld for income tax, in percent.
+    pub withholding_percent: f64,
+    /// Benefit deductions (health cover, pension) taken from each paycheck.
+    #[serde(default)]
+    pub benefits: f64,
+}
+
+impl IncomeModel {
+    /// Pay received after withholding and benefit deductions.
+    pub fn net(&self) -> f64 {
+        self.gross * (1.0 - self.withholding_percent / 100.0) - self.benefits
+    }
+}
+
+/// Sets the amount of an income series' template and of every occurrence not
+/// yet completed to the template's net pay. Returns `false` when `template_id`
+/// is not a recurrence with an income model or its net pay is not an amount.
+pub fn sync_income_series(transactions: &mut [Transaction], template_id: Uuid) -> bool {
+    let Some((series_id, net)) = transactions
+        .iter()
+        .find(|txn| txn.id == template_id)
+        .and_then(|txn| {
+            let income = txn.recurrence.as_ref()?.income.as_ref()?;
+            let net = Money::from_major(income.net(), txn.budgeted_amount.currency().clone());
+            Some((txn.recurrence_series().unwrap_or(txn.id), net.ok()?))
+        })
+    else {
+        return false;
+    };
+    for txn in transactions.iter_mut() {
+        let in_series = txn.id == template_id || txn.recurrence_series_id == Some(series_id);
+        if in_series && txn.status != TransactionStatus::Completed {
+            txn.budgeted_amount = net.clone();
+        }
+    }
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -165,7 +414,7 @@ impl fmt::Display for RecurrenceMode {
 impl Recurrence {
     pub fn new(start_date: NaiveDate, interval: TimeInterval, mode: RecurrenceMode) -> Recurrence {
         Self {
-            series_id: Uuid::new_v4(),
+            series_id: new_id(),
             start_date,
             interval,
             mode,
@@ -176,9 +425,40 @@ impl Recurrence {
             last_completed: None,
             generated_occurrences: 0,
             next_scheduled: None,
+            income: None,
+            price_history: Vec::new(),
+            contract: None,
+            business_day_shift: BusinessDayShift::Keep,
+            extra: ExtraFields::new(),
         }
     }
 
+    /// Records that the amount changed from `previous` to `amount` on
+    /// `effective`. The first change also records `previous` as the price in
+    /// effect since the start date; later entries dated on or after
+    /// `effective` are replaced.
+    pub fn record_price(&mut self, previous: f64, amount: f64, effective: NaiveDate) {
+        if self.price_history.is_empty() {
+            self.price_history.push(PricePoint {
+                effective: self.start_date.min(effective),
+                amount: previous,
+            });
+        }
+        self.price_history
+            .retain(|point| point.effective < effective);
+        self.price_history.push(PricePoint { effective, amount });
+    }
+
+    /// Amount in effect on `date` according to the price history, if any.
+    pub fn price_on(&self, date: NaiveDate) -> Option<f64> {
+        self.price_history
+            .iter()
+            .rev()
+            .find(|point| point.effective <= date)
+            .or_else(|| self.price_history.first())
+            .map(|point| point.amount)
+    }
+
     pub fn ensure_series_id(&mut self, fallback: Uuid) {
         if self.series_id.is_nil() {
             self.series_id = fallback;
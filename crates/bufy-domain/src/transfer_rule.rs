@@ -0,0 +1,44 @@
+//! Standing rules that move part of each income payment between accounts.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{common::ExtraFields, ids::new_id};
+
+/// Moves `percent` of every completed income transaction that lands in
+/// `from_account` on to `to_account`, e.g. 10% of each paycheck into savings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TransferRule {
+    pub id: Uuid,
+    pub from_account: Uuid,
+    pub to_account: Uuid,
+    /// Share of the income amount to transfer, between 0 and 100.
+    pub percent: f64,
+    pub created_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl TransferRule {
+    pub fn new(
+        from_account: Uuid,
+        to_account: Uuid,
+        percent: f64,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: new_id(),
+            from_account,
+            to_account,
+            percent,
+            created_at,
+            extra: ExtraFields::new(),
+        }
+    }
+
+    /// Amount this rule moves out of an income payment, rounded to cents.
+    pub fn share_of(&self, income: f64) -> f64 {
+        (income * self.percent).round() / 100.0
+    }
+}
@@ -0,0 +1,212 @@
+//! Saved transaction views: named filters plus the columns to display.
+
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::common::ExtraFields;
+use crate::ids::new_id;
+use crate::time;
+use crate::transaction::TransactionStatus;
+
+fn default_view_id() -> Uuid {
+    new_id()
+}
+
+/// Filter criteria expressed the way users type them. Account and category
+/// names are resolved against the ledger each time the filter runs, so a view
+/// keeps working after the underlying entities are recreated.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TransactionFilter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<NaiveDate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<TransactionStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_amount: Option<f64>,
+    /// Text the notes must contain, ignoring case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Text the notes or merchant must contain, ignoring case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// Keep only recurring (`true`) or one-off (`false`) transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurring: Option<bool>,
+    /// Tag the transaction must carry, ignoring case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl TransactionFilter {
+    /// Returns `true` when no criterion is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Columns a transaction view can render or export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ViewColumn {
+    Reference,
+    Date,
+    ActualDate,
+    BookingDate,
+    ValueDate,
+    From,
+    To,
+    Category,
+    Member,
+    Budgeted,
+    Actual,
+    Currency,
+    Status,
+    Notes,
+    Merchant,
+    Location,
+    Tags,
+}
+
+impl ViewColumn {
+    pub const ALL: [ViewColumn; 17] = [
+        ViewColumn::Reference,
+        ViewColumn::Date,
+        ViewColumn::ActualDate,
+        ViewColumn::BookingDate,
+        ViewColumn::ValueDate,
+        ViewColumn::From,
+        ViewColumn::To,
+        ViewColumn::Category,
+        ViewColumn::Member,
+        ViewColumn::Budgeted,
+        ViewColumn::Actual,
+        ViewColumn::Currency,
+        ViewColumn::Status,
+        ViewColumn::Notes,
+        ViewColumn::Merchant,
+        ViewColumn::Location,
+        ViewColumn::Tags,
+    ];
+
+    /// Columns used when a view does not choose its own.
+    pub fn default_columns() -> Vec<ViewColumn> {
+        vec![
+            ViewColumn::Reference,
+            ViewColumn::Date,
+            ViewColumn::From,
+            ViewColumn::To,
+            ViewColumn::Category,
+            ViewColumn::Budgeted,
+            ViewColumn::Status,
+        ]
+    }
+
+    /// Stable lowercase key used on the command line and in exports.
+    pub fn key(&self) -> &'static str {
+        match self {
+            ViewColumn::Reference => "ref",
+            ViewColumn::Date => "date",
+            ViewColumn::ActualDate => "actual_date",
+            ViewColumn::BookingDate => "booking_date",
+            ViewColumn::ValueDate => "value_date",
+            ViewColumn::From => "from",
+            ViewColumn::To => "to",
+            ViewColumn::Category => "category",
+            ViewColumn::Member => "member",
+            ViewColumn::Budgeted => "budgeted",
+            ViewColumn::Actual => "actual",
+            ViewColumn::Currency => "currency",
+            ViewColumn::Status => "status",
+            ViewColumn::Notes => "notes",
+            ViewColumn::Merchant => "merchant",
+            ViewColumn::Location => "location",
+            ViewColumn::Tags => "tags",
+        }
+    }
+
+    /// Parses a column key (case-insensitive, `-` accepted in place of `_`).
+    pub fn from_key(value: &str) -> Option<Self> {
+        let normalized = value.trim().to_ascii_lowercase().replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|column| column.key() == normalized)
+    }
+}
+
+impl fmt::Display for ViewColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ViewColumn::Reference => "Ref",
+            ViewColumn::Date => "Date",
+            ViewColumn::ActualDate => "Actual Date",
+            ViewColumn::BookingDate => "Booking Date",
+            ViewColumn::ValueDate => "Value Date",
+            ViewColumn::From => "From",
+            ViewColumn::To => "To",
+            ViewColumn::Category => "Category",
+            ViewColumn::Member => "Member",
+            ViewColumn::Budgeted => "Budgeted",
+            ViewColumn::Actual => "Actual",
+            ViewColumn::Currency => "Currency",
+            ViewColumn::Status => "Status",
+            ViewColumn::Notes => "Notes",
+            ViewColumn::Merchant => "Merchant",
+            ViewColumn::Location => "Location",
+            ViewColumn::Tags => "Tags",
+        };
+        f.write_str(label)
+    }
+}
+
+/// A named transaction filter with its column selection, stored on the ledger.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedView {
+    #[serde(default = "default_view_id")]
+    pub id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub filter: TransactionFilter,
+    #[serde(default = "ViewColumn::default_columns")]
+    pub columns: Vec<ViewColumn>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl SavedView {
+    pub fn new(
+        name: impl Into<String>,
+        filter: TransactionFilter,
+        columns: Vec<ViewColumn>,
+    ) -> Self {
+        let now = time::now();
+        Self {
+            id: new_id(),
+            name: name.into(),
+            filter,
+            columns: if columns.is_empty() {
+                ViewColumn::default_columns()
+            } else {
+                columns
+            },
+            created_at: now,
+            updated_at: now,
+            extra: ExtraFields::new(),
+        }
+    }
+}
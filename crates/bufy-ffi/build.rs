@@ -0,0 +1,27 @@
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src");
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("manifest dir"));
+    // Build scripts may only write under `OUT_DIR`; projects that need the
+    // header at a fixed path run the `cbindgen` CLI with the same config.
+    let header = PathBuf::from(env::var("OUT_DIR").expect("out dir")).join("bufy_ffi.h");
+
+    // A broken header must not stop the library from building; the Rust
+    // compiler reports real source errors on its own.
+    let generated = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .map_err(|err| err.to_string())
+        .and_then(|config| {
+            cbindgen::generate_with_config(&crate_dir, config).map_err(|err| err.to_string())
+        });
+    match generated {
+        Ok(bindings) => {
+            bindings.write_to_file(&header);
+        }
+        Err(err) => println!("cargo:warning=bufy_ffi.h was not generated: {err}"),
+    }
+}
@@ -1,39 +1,184 @@
 //! bufy-ffi
 //!
 //! Minimal FFI surface that exposes selected bufy-core APIs for external clients.
+//!
+//! Exported functions stay safe Rust functions so C callers see plain
+//! symbols; each one that reads a pointer argument checks it for null and
+//! allows `clippy::not_unsafe_ptr_arg_deref` on its own, so helpers that
+//! take pointers keep the lint.
 
 use std::{
+    collections::{hash_map::DefaultHasher, BTreeMap},
     ffi::{CStr, CString},
-    os::raw::{c_char, c_double, c_int},
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    os::raw::{c_char, c_double, c_int, c_longlong, c_ulonglong},
+    path::{Path, PathBuf},
     ptr,
+    sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, Weak},
 };
 
-use chrono::{Datelike, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use uuid::Uuid;
 
 use bufy_core::{
-    api_add_account, api_add_transaction, api_complete_transaction, api_create_ledger,
-    api_ledger_summary, CoreError,
+    api_add_account, api_add_category, api_add_transaction, api_add_transaction_minor,
+    api_balance_series, api_budget_summary, api_complete_transaction, api_create_ledger,
+    api_currency_precision, api_dashboard_snapshot, api_format_amount, api_ledger_summary,
+    api_list_transactions, api_set_transaction_metadata, api_submit_draft,
+    api_transaction_by_reference,
+    storage::{LedgerStorage, LoadReport},
+    ApiSummaryTracker, CoreError,
 };
 use bufy_domain::{
     account::AccountKind,
+    alert::AlertCounts,
+    category::CategoryKind,
     common::{TimeInterval, TimeUnit},
+    ids::{reset_id_generator, set_id_generator, SequentialIds},
     ledger::BudgetScope,
+    transaction::GeoLocation,
     Ledger, LedgerBudgetPeriod,
 };
+use bufy_storage_json::{canonical_name, JsonLedgerStorage, MemoryLedgerStorage, StoragePaths};
+
+/// Bumped when a function is removed or changes its signature or meaning,
+/// or a shared struct changes layout. Bindings built for another major
+/// version must not call into the library.
+pub const BUFY_FFI_ABI_MAJOR: u32 = 2;
+/// Bumped when functions are added; older bindings keep working.
+pub const BUFY_FFI_ABI_MINOR: u32 = 0;
+/// `BUFY_FFI_ABI_MAJOR` in the high 16 bits, `BUFY_FFI_ABI_MINOR` in the low.
+pub const BUFY_FFI_ABI_VERSION: u32 = (BUFY_FFI_ABI_MAJOR << 16) | BUFY_FFI_ABI_MINOR;
+
+/// Where `bufy_ledger_save` and `bufy_ledger_load` keep ledgers; unset until
+/// `bufy_set_storage_root` or `bufy_set_test_mode` is called.
+static STORAGE: RwLock<Option<SessionStore>> = RwLock::new(None);
+
+/// Live sessions over stored ledgers, keyed by [`SessionStore::session_key`].
+/// Every handle loaded from the same file shares one session, so edits made
+/// through one handle are seen by the others and saves cannot overwrite
+/// each other.
+static SESSIONS: Mutex<BTreeMap<PathBuf, Weak<Mutex<LedgerSession>>>> = Mutex::new(BTreeMap::new());
 
 /// Opaque pointer used by external callers to hold ledger state.
-#[repr(C)]
 pub struct LedgerHandle {
-    inner: Ledger,
+    session: Arc<Mutex<LedgerSession>>,
 }
 
 impl LedgerHandle {
     fn new(ledger: Ledger) -> *mut Self {
-        Box::into_raw(Box::new(Self { inner: ledger }))
+        Self::attach(Arc::new(Mutex::new(LedgerSession {
+            ledger,
+            summaries: ApiSummaryTracker::default(),
+            file: None,
+        })))
+    }
+
+    fn attach(session: Arc<Mutex<LedgerSession>>) -> *mut Self {
+        Box::into_raw(Box::new(Self { session }))
+    }
+
+    /// Locks the session for the rest of the call. A call that panicked
+    /// while holding the lock leaves the ledger as it was, so the lock is
+    /// taken over rather than reported.
+    fn session(&self) -> MutexGuard<'_, LedgerSession> {
+        self.session.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Ledger state shared by every handle over the same file.
+struct LedgerSession {
+    ledger: Ledger,
+    summaries: ApiSummaryTracker,
+    /// The file the ledger was loaded from or first saved to.
+    file: Option<SessionFile>,
+}
+
+impl LedgerSession {
+    /// Ledger access for calls that change it; each one starts a new
+    /// revision, so take it only once the call's arguments are valid.
+    fn edit(&mut self) -> &mut Ledger {
+        self.summaries.bump();
+        &mut self.ledger
+    }
+}
+
+struct SessionFile {
+    store: SessionStore,
+    name: String,
+    key: PathBuf,
+    /// Contents of the file when the session last read or wrote it.
+    stamp: Option<u64>,
+}
+
+/// Where a session's ledger is stored.
+#[derive(Clone)]
+enum SessionStore {
+    Files(JsonLedgerStorage),
+    /// The store of `bufy_set_test_mode`.
+    Memory(Arc<MemoryLedgerStorage>),
+}
+
+impl SessionStore {
+    fn storage(&self) -> &dyn LedgerStorage {
+        match self {
+            SessionStore::Files(storage) => storage,
+            SessionStore::Memory(storage) => storage.as_ref(),
+        }
+    }
+
+    /// Key of `name` in [`SESSIONS`]: the file path with its folder
+    /// resolved, so that storage handles opened with different spellings
+    /// of one folder agree. Each in-memory store has keys of its own.
+    fn session_key(&self, name: &str) -> PathBuf {
+        match self {
+            SessionStore::Files(storage) => {
+                let path = storage.ledger_path(name);
+                match (
+                    path.parent().and_then(|dir| fs::canonicalize(dir).ok()),
+                    path.file_name(),
+                ) {
+                    (Some(dir), Some(file)) => dir.join(file),
+                    _ => path,
+                }
+            }
+            SessionStore::Memory(storage) => {
+                PathBuf::from(format!("memory-{:p}", Arc::as_ptr(storage)))
+                    .join(canonical_name(name))
+            }
+        }
+    }
+
+    /// Hash of what is stored under `name`, `None` when nothing is. Only
+    /// compared within one process, so the standard hasher is enough.
+    fn stamp(&self, name: &str, key: &Path) -> Result<Option<u64>, CoreError> {
+        let bytes = match self {
+            SessionStore::Files(_) => match fs::read(key) {
+                Ok(bytes) => bytes,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(err) => return Err(err.into()),
+            },
+            SessionStore::Memory(storage) => match storage.load_ledger(name) {
+                Ok(ledger) => {
+                    serde_json::to_vec(&ledger).map_err(|err| CoreError::Serde(err.to_string()))?
+                }
+                Err(CoreError::LedgerNotFound(_)) => return Ok(None),
+                Err(err) => return Err(err),
+            },
+        };
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        Ok(Some(hasher.finish()))
     }
 }
 
+/// Opaque pointer to a ledger folder opened with `bufy_storage_open`.
+pub struct StorageHandle {
+    inner: JsonLedgerStorage,
+}
+
 /// Simple budgeting snapshot exposed over FFI.
 #[repr(C)]
 pub struct FfiLedgerSummary {
@@ -50,9 +195,54 @@ pub struct FfiLedgerSummary {
     pub variance_total: c_double,
     pub incomplete_transactions: c_int,
     pub orphaned_transactions: c_int,
+    pub over_budget_alerts: c_int,
+    pub low_balance_alerts: c_int,
+    pub overdue_recurrence_alerts: c_int,
+    pub underfunded_sinking_fund_alerts: c_int,
+    pub contract_notice_alerts: c_int,
+    /// Drafts waiting in the review inbox.
+    pub pending_drafts: c_int,
+    /// JSON array of active alerts (`kind`, `subject_id`, `subject`, `message`).
+    /// Owned by the caller; release with `bufy_string_free`.
+    pub alerts_json: *mut c_char,
+}
+
+/// ABI version of the loaded library, encoded like `BUFY_FFI_ABI_VERSION`.
+#[no_mangle]
+pub extern "C" fn bufy_ffi_abi_version() -> u32 {
+    BUFY_FFI_ABI_VERSION
+}
+
+/// Checks that bindings generated from the header of ABI `major.minor` can
+/// use this library: the major versions must match and the library must be
+/// at least as new. Returns 0 when compatible, 1 for a different major
+/// version, and 2 when the library is older; `out_error` then says which
+/// versions differ. Call it before anything else.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ffi_abi_check(major: u32, minor: u32, out_error: *mut *mut c_char) -> c_int {
+    clear_error(out_error);
+    let code = if major != BUFY_FFI_ABI_MAJOR {
+        1
+    } else if minor > BUFY_FFI_ABI_MINOR {
+        2
+    } else {
+        return 0;
+    };
+    unsafe {
+        write_error(
+            out_error,
+            &format!(
+                "bindings expect bufy-ffi ABI {}.{} but the library provides {}.{}",
+                major, minor, BUFY_FFI_ABI_MAJOR, BUFY_FFI_ABI_MINOR
+            ),
+        );
+    }
+    code
 }
 
 #[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn bufy_ledger_create(
     name: *const c_char,
     period_code: c_int,
@@ -75,6 +265,7 @@ pub extern "C" fn bufy_ledger_create(
 }
 
 #[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn bufy_ledger_free(handle: *mut LedgerHandle) {
     if handle.is_null() {
         return;
@@ -84,13 +275,79 @@ pub extern "C" fn bufy_ledger_free(handle: *mut LedgerHandle) {
     }
 }
 
+/// Releases a string allocated by this library.
 #[no_mangle]
-pub extern "C" fn bufy_ledger_add_account(
-    handle: *mut LedgerHandle,
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_string_free(value: *mut c_char) {
+    if value.is_null() {
+        return;
+    }
+    unsafe {
+        drop(CString::from_raw(value));
+    }
+}
+
+/// Keeps ledgers saved with `bufy_ledger_save` as files under `root`, with
+/// backups in `root/backups`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_set_storage_root(root: *const c_char, out_error: *mut *mut c_char) -> c_int {
+    clear_error(out_error);
+    let root = match unsafe { c_string_argument(root) } {
+        Ok(value) => PathBuf::from(value),
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 1;
+        }
+    };
+    match json_storage(root) {
+        Ok(storage) => {
+            set_storage(SessionStore::Files(storage));
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            2
+        }
+    }
+}
+
+/// Switches the library to predictable data for UI tests: the clock stops
+/// at 2025-01-01 12:00 UTC, new ids count up from
+/// `00000000-0000-<seed>-0000-000000000001`, and ledgers are saved to an
+/// empty in-memory store instead of the filesystem. Calling it again starts
+/// over with a fresh store; `bufy_clear_test_mode` switches it off.
+#[no_mangle]
+pub extern "C" fn bufy_set_test_mode(seed: c_ulonglong) {
+    bufy_domain::freeze_time(test_mode_now());
+    set_id_generator(Arc::new(SequentialIds::seeded(seed)));
+    set_storage(SessionStore::Memory(Arc::new(MemoryLedgerStorage::new())));
+}
+
+/// Goes back to the system clock and random ids, and drops the in-memory
+/// store. Call `bufy_set_storage_root` again before saving.
+#[no_mangle]
+pub extern "C" fn bufy_clear_test_mode() {
+    bufy_domain::unfreeze_time();
+    reset_id_generator();
+    if let Ok(mut storage) = STORAGE.write() {
+        *storage = None;
+    }
+}
+
+/// Saves the ledger under `name` in the configured storage. Like
+/// `bufy_storage_save_ledger`, returns 4 without writing when the stored
+/// ledger changed since this ledger's session last read or wrote it, when
+/// another session holds it, or when it exists but is not this ledger's.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_save(
+    handle: *const LedgerHandle,
     name: *const c_char,
-    kind_code: c_int,
-    category_id: *const c_char,
-    out_account_id: *mut *mut c_char,
     out_error: *mut *mut c_char,
 ) -> c_int {
     clear_error(out_error);
@@ -100,8 +357,951 @@ pub extern "C" fn bufy_ledger_add_account(
         }
         return 1;
     }
-    let ledger = unsafe { &mut (*handle).inner };
-    let account_name = match unsafe { c_string_argument(name) } {
+    let handle = unsafe { &*handle };
+    let ledger_name = match unsafe { c_string_argument(name) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+
+    match storage().and_then(|store| save_session(handle, &store, ledger_name)) {
+        Ok(SaveOutcome::Saved) => 0,
+        Ok(SaveOutcome::Conflict(message)) => {
+            unsafe {
+                write_error(out_error, &message);
+            }
+            4
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            3
+        }
+    }
+}
+
+/// Loads the ledger saved under `name`. Loading a ledger that another live
+/// handle already loaded returns a handle to the same session, as
+/// `bufy_storage_load_ledger` does. Returns null and sets `out_error`
+/// when it does not exist. Release the handle with `bufy_ledger_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_load(
+    name: *const c_char,
+    out_error: *mut *mut c_char,
+) -> *mut LedgerHandle {
+    clear_error(out_error);
+    let ledger_name = match unsafe { c_string_argument(name) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return ptr::null_mut();
+        }
+    };
+
+    match storage().and_then(|store| open_session(&store, ledger_name)) {
+        Ok((session, _)) => LedgerHandle::attach(session),
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Opens the ledger folder at `root`, creating it when missing, with backups
+/// in `root/backups` like the CLI. Returns null and sets `out_error` when the
+/// folder cannot be created. Release the handle with `bufy_storage_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_storage_open(
+    root: *const c_char,
+    out_error: *mut *mut c_char,
+) -> *mut StorageHandle {
+    clear_error(out_error);
+    let opened = unsafe { c_string_argument(root) }.and_then(|root| json_storage(root.into()));
+    match opened {
+        Ok(inner) => Box::into_raw(Box::new(StorageHandle { inner })),
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_storage_free(storage: *mut StorageHandle) {
+    if storage.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Box::from_raw(storage));
+    }
+}
+
+/// Loads the ledger stored under `name`, migrating older files to the
+/// current schema. When `out_report_json` is not null it receives
+/// `schema_version`, `migrations`, and `warnings` for the app to show.
+/// Loading a file that another live handle already loaded returns a handle
+/// to the same session, with its unsaved edits and an empty report.
+/// Returns null and sets `out_error` on failure. Release the handle with
+/// `bufy_ledger_free` and the report with `bufy_string_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_storage_load_ledger(
+    storage: *const StorageHandle,
+    name: *const c_char,
+    out_report_json: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> *mut LedgerHandle {
+    clear_error(out_error);
+    if storage.is_null() {
+        unsafe {
+            write_error(out_error, "storage handle is null");
+        }
+        return ptr::null_mut();
+    }
+    let store = SessionStore::Files(unsafe { (*storage).inner.clone() });
+    let loaded = unsafe { c_string_argument(name) }.and_then(|name| open_session(&store, name));
+    match loaded {
+        Ok((session, report)) => {
+            unsafe {
+                write_string(out_report_json, load_report_json(&report).to_string());
+            }
+            LedgerHandle::attach(session)
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Saves the ledger under `name`. An existing file is first copied to a
+/// backup, keeping the newest few, exactly as the CLI does. A ledger that
+/// was not loaded from a file is tied to the file of its first save.
+///
+/// Returns 4 without writing when the file changed since this ledger's
+/// session last read or wrote it, when another session holds the file, or
+/// when the file exists but is not this ledger's; call `bufy_ledger_reload`
+/// to pick up the other changes, or save under another name.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_storage_save_ledger(
+    storage: *const StorageHandle,
+    handle: *const LedgerHandle,
+    name: *const c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if storage.is_null() || handle.is_null() {
+        unsafe {
+            write_error(out_error, "storage or ledger handle is null");
+        }
+        return 1;
+    }
+    let store = SessionStore::Files(unsafe { (*storage).inner.clone() });
+    let handle = unsafe { &*handle };
+    let ledger_name = match unsafe { c_string_argument(name) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+
+    match save_session(handle, &store, ledger_name) {
+        Ok(SaveOutcome::Saved) => 0,
+        Ok(SaveOutcome::Conflict(message)) => {
+            unsafe {
+                write_error(out_error, &message);
+            }
+            4
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            3
+        }
+    }
+}
+
+/// Reads the ledger's file again, replacing the session's ledger for every
+/// handle that shares it; unsaved edits are dropped. Use it after
+/// `bufy_storage_save_ledger` or `bufy_ledger_save` reports a conflict.
+/// `out_report_json` gets the same report as a load when it is not null.
+/// Returns 2 when the ledger was never loaded from or saved to a file.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_reload(
+    handle: *const LedgerHandle,
+    out_report_json: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle is null");
+        }
+        return 1;
+    }
+    let mut session = unsafe { (*handle).session() };
+    let session = &mut *session;
+    let Some(file) = session.file.as_mut() else {
+        unsafe {
+            write_error(out_error, "ledger was not loaded from a file");
+        }
+        return 2;
+    };
+    let reloaded = file.store.stamp(&file.name, &file.key).and_then(|stamp| {
+        let (ledger, report) = file.store.storage().load_migrated(&file.name)?;
+        Ok((stamp, ledger, report))
+    });
+    match reloaded {
+        Ok((stamp, ledger, report)) => {
+            file.stamp = stamp;
+            *session.edit() = ledger;
+            unsafe {
+                write_string(out_report_json, load_report_json(&report).to_string());
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            3
+        }
+    }
+}
+
+/// Writes the names of the stored ledgers as a JSON array of strings.
+/// Release `out_json` with `bufy_string_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_storage_list_ledgers_json(
+    storage: *const StorageHandle,
+    out_json: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if storage.is_null() || out_json.is_null() {
+        unsafe {
+            write_error(out_error, "storage handle or output pointer is null");
+        }
+        return 1;
+    }
+    let storage = unsafe { &(*storage).inner };
+    match storage.list_ledgers() {
+        Ok(names) => {
+            unsafe {
+                write_string(out_json, serde_json::json!(names).to_string());
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            3
+        }
+    }
+}
+
+/// Writes the backups of ledger `name` as a JSON array, newest first. Each
+/// entry has `id`, `created_at` (RFC 3339, or null when the file name carries
+/// no timestamp), and `size_bytes`. Release `out_json` with `bufy_string_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_storage_list_backups_json(
+    storage: *const StorageHandle,
+    name: *const c_char,
+    out_json: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if storage.is_null() || out_json.is_null() {
+        unsafe {
+            write_error(out_error, "storage handle or output pointer is null");
+        }
+        return 1;
+    }
+    let storage = unsafe { &(*storage).inner };
+    let ledger_name = match unsafe { c_string_argument(name) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+
+    match storage.list_backup_metadata(&ledger_name) {
+        Ok(backups) => {
+            let entries: Vec<_> = backups
+                .iter()
+                .map(|backup| {
+                    serde_json::json!({
+                        "id": backup.name,
+                        "created_at": backup.created_at.map(|at| at.to_rfc3339()),
+                        "size_bytes": backup.size_bytes,
+                    })
+                })
+                .collect();
+            unsafe {
+                write_string(out_json, serde_json::Value::from(entries).to_string());
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            3
+        }
+    }
+}
+
+/// Writes a backup of the ledger as it is in memory, labelled with `note`
+/// when it is not null. Release `out_backup_id` with `bufy_string_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_storage_backup_ledger(
+    storage: *const StorageHandle,
+    handle: *const LedgerHandle,
+    name: *const c_char,
+    note: *const c_char,
+    out_backup_id: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if storage.is_null() || handle.is_null() {
+        unsafe {
+            write_error(out_error, "storage or ledger handle is null");
+        }
+        return 1;
+    }
+    let storage = unsafe { &(*storage).inner };
+    let session = unsafe { (*handle).session() };
+    let ledger = &session.ledger;
+    let arguments = unsafe { c_string_argument(name) }
+        .and_then(|name| Ok((name, unsafe { optional_string_argument(note) }?)));
+    let (ledger_name, note) = match arguments {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+
+    match storage.backup_ledger(&ledger_name, ledger, note.as_deref()) {
+        Ok(backup) => {
+            unsafe {
+                write_string(out_backup_id, backup.id);
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            3
+        }
+    }
+}
+
+/// Replaces the stored ledger `name` with backup `backup_id` (an `id` from
+/// `bufy_storage_list_backups_json`) and loads it like
+/// `bufy_storage_load_ledger`. Returns null and sets `out_error` when the
+/// backup does not exist or a live handle still holds the ledger.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_storage_restore_backup(
+    storage: *const StorageHandle,
+    name: *const c_char,
+    backup_id: *const c_char,
+    out_report_json: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> *mut LedgerHandle {
+    clear_error(out_error);
+    if storage.is_null() {
+        unsafe {
+            write_error(out_error, "storage handle is null");
+        }
+        return ptr::null_mut();
+    }
+    let storage = unsafe { &(*storage).inner };
+    let store = SessionStore::Files(storage.clone());
+    let restored = unsafe { c_string_argument(name) }
+        .and_then(|name| Ok((name, unsafe { c_string_argument(backup_id) }?)))
+        .and_then(|(name, backup_id)| {
+            let key = store.session_key(&name);
+            let open = SESSIONS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get(&key)
+                .and_then(Weak::upgrade)
+                .is_some();
+            if open {
+                return Err(CoreError::InvalidOperation(format!(
+                    "{} is open in another session; free its handles before restoring",
+                    key.display()
+                )));
+            }
+            let backup = storage
+                .list_backups(&name)?
+                .into_iter()
+                .find(|backup| backup.id == backup_id)
+                .ok_or_else(|| {
+                    CoreError::Storage(format!(
+                        "backup `{}` not found for ledger `{}`",
+                        backup_id, name
+                    ))
+                })?;
+            storage.restore_backup(&backup)?;
+            open_session(&store, name)
+        });
+    match restored {
+        Ok((session, report)) => {
+            unsafe {
+                write_string(out_report_json, load_report_json(&report).to_string());
+            }
+            LedgerHandle::attach(session)
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_add_account(
+    handle: *mut LedgerHandle,
+    name: *const c_char,
+    kind_code: c_int,
+    category_id: *const c_char,
+    out_account_id: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle is null");
+        }
+        return 1;
+    }
+    let mut session = unsafe { (*handle).session() };
+    let account_name = match unsafe { c_string_argument(name) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+
+    let category = match unsafe { parse_optional_uuid(category_id) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 3;
+        }
+    };
+
+    let kind = account_kind_from_code(kind_code);
+
+    let ledger = session.edit();
+    match api_add_account(ledger, account_name, kind, category) {
+        Ok(account_id) => {
+            unsafe {
+                write_string(out_account_id, account_id.to_string());
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            4
+        }
+    }
+}
+
+/// Adds a category (`kind_code` 0 expense, 1 income, 2 transfer) under the
+/// optional `parent_id`. Release `out_category_id` with `bufy_string_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_add_category(
+    handle: *mut LedgerHandle,
+    name: *const c_char,
+    kind_code: c_int,
+    parent_id: *const c_char,
+    out_category_id: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle is null");
+        }
+        return 1;
+    }
+    let mut session = unsafe { (*handle).session() };
+    let category_name = match unsafe { c_string_argument(name) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+    let parent = match unsafe { parse_optional_uuid(parent_id) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 3;
+        }
+    };
+
+    let kind = category_kind_from_code(kind_code);
+
+    let ledger = session.edit();
+    match api_add_category(ledger, category_name, kind, parent) {
+        Ok(category_id) => {
+            unsafe {
+                write_string(out_category_id, category_id.to_string());
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            4
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_add_transaction(
+    handle: *mut LedgerHandle,
+    from_account_id: *const c_char,
+    to_account_id: *const c_char,
+    category_id: *const c_char,
+    scheduled_year: c_int,
+    scheduled_month: c_int,
+    scheduled_day: c_int,
+    budgeted_amount: c_double,
+    notes: *const c_char,
+    out_transaction_id: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle is null");
+        }
+        return 1;
+    }
+    let mut session = unsafe { (*handle).session() };
+    let from = match unsafe { parse_uuid_arg(from_account_id) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+    let to = match unsafe { parse_uuid_arg(to_account_id) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 3;
+        }
+    };
+    let category = match unsafe { parse_optional_uuid(category_id) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 4;
+        }
+    };
+    let scheduled_date = match parse_date(scheduled_year, scheduled_month, scheduled_day) {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 5;
+        }
+    };
+    let note_value = match unsafe { optional_string_argument(notes) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 6;
+        }
+    };
+
+    let ledger = session.edit();
+    match api_add_transaction(
+        ledger,
+        from,
+        to,
+        category,
+        scheduled_date,
+        budgeted_amount,
+        note_value,
+    ) {
+        Ok(tx_id) => {
+            unsafe {
+                write_string(out_transaction_id, tx_id.to_string());
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            7
+        }
+    }
+}
+
+/// Integer counterpart of `bufy_ledger_add_transaction` that takes the amount in
+/// minor units (e.g. cents) of `currency_code`, or of the ledger base currency
+/// when `currency_code` is null or empty.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_add_transaction_minor(
+    handle: *mut LedgerHandle,
+    from_account_id: *const c_char,
+    to_account_id: *const c_char,
+    category_id: *const c_char,
+    scheduled_year: c_int,
+    scheduled_month: c_int,
+    scheduled_day: c_int,
+    amount_minor: c_longlong,
+    currency_code: *const c_char,
+    notes: *const c_char,
+    out_transaction_id: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle is null");
+        }
+        return 1;
+    }
+    let mut session = unsafe { (*handle).session() };
+    let from = match unsafe { parse_uuid_arg(from_account_id) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+    let to = match unsafe { parse_uuid_arg(to_account_id) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 3;
+        }
+    };
+    let category = match unsafe { parse_optional_uuid(category_id) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 4;
+        }
+    };
+    let scheduled_date = match parse_date(scheduled_year, scheduled_month, scheduled_day) {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 5;
+        }
+    };
+    let currency = match unsafe { optional_string_argument(currency_code) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 6;
+        }
+    };
+    let note_value = match unsafe { optional_string_argument(notes) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 7;
+        }
+    };
+
+    let ledger = session.edit();
+    match api_add_transaction_minor(
+        ledger,
+        from,
+        to,
+        category,
+        scheduled_date,
+        amount_minor,
+        currency,
+        note_value,
+    ) {
+        Ok(tx_id) => {
+            unsafe {
+                write_string(out_transaction_id, tx_id.to_string());
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            8
+        }
+    }
+}
+
+/// Writes the number of minor-unit digits of the ledger base currency
+/// (e.g. 2 for USD, 0 for JPY) into `out_precision`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_currency_precision(
+    handle: *const LedgerHandle,
+    out_precision: *mut c_int,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() || out_precision.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle or output precision is null");
+        }
+        return 1;
+    }
+
+    let session = unsafe { (*handle).session() };
+    let ledger = &session.ledger;
+    unsafe {
+        *out_precision = api_currency_precision(ledger) as c_int;
+    }
+    0
+}
+
+/// Formats `amount` with the ledger's locale and currency settings, e.g.
+/// `1.234,56 €`. `currency_code` may be null to use the base currency.
+/// Release `out_text` with `bufy_string_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_format_amount(
+    handle: *const LedgerHandle,
+    amount: c_double,
+    currency_code: *const c_char,
+    out_text: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() || out_text.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle or output pointer is null");
+        }
+        return 1;
+    }
+    let session = unsafe { (*handle).session() };
+    let ledger = &session.ledger;
+    let currency = match unsafe { optional_string_argument(currency_code) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+
+    match api_format_amount(ledger, amount, currency.as_deref()) {
+        Ok(text) => {
+            unsafe {
+                write_string(out_text, text);
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            3
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_complete_transaction(
+    handle: *mut LedgerHandle,
+    transaction_id: *const c_char,
+    actual_year: c_int,
+    actual_month: c_int,
+    actual_day: c_int,
+    actual_amount: c_double,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle is null");
+        }
+        return 1;
+    }
+    let mut session = unsafe { (*handle).session() };
+    let txn = match unsafe { parse_uuid_arg(transaction_id) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+    let actual_date = match parse_date(actual_year, actual_month, actual_day) {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 3;
+        }
+    };
+
+    let ledger = session.edit();
+    match api_complete_transaction(ledger, txn, actual_date, actual_amount) {
+        Ok(()) => 0,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            4
+        }
+    }
+}
+
+/// Looks up the id of the transaction with a short code such as `T-0012`.
+/// Release `out_transaction_id` with `bufy_string_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_transaction_by_reference(
+    handle: *const LedgerHandle,
+    reference: *const c_char,
+    out_transaction_id: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() || out_transaction_id.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle or output pointer is null");
+        }
+        return 1;
+    }
+    let session = unsafe { (*handle).session() };
+    let ledger = &session.ledger;
+    let code = match unsafe { c_string_argument(reference) } {
+        Ok(value) => value,
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            return 2;
+        }
+    };
+
+    match api_transaction_by_reference(ledger, &code) {
+        Ok(id) => {
+            unsafe {
+                write_string(out_transaction_id, id.to_string());
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_core_error(out_error, err);
+            }
+            3
+        }
+    }
+}
+
+/// Sets the merchant and location of a transaction. `merchant` and `place`
+/// may be null; pass NaN for `latitude` or `longitude` to clear the location.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_set_transaction_metadata(
+    handle: *mut LedgerHandle,
+    transaction_id: *const c_char,
+    merchant: *const c_char,
+    latitude: c_double,
+    longitude: c_double,
+    place: *const c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle is null");
+        }
+        return 1;
+    }
+    let mut session = unsafe { (*handle).session() };
+    let txn = match unsafe { parse_uuid_arg(transaction_id) } {
         Ok(value) => value,
         Err(err) => {
             unsafe {
@@ -110,8 +1310,7 @@ pub extern "C" fn bufy_ledger_add_account(
             return 2;
         }
     };
-
-    let category = match unsafe { parse_optional_uuid(category_id) } {
+    let merchant = match unsafe { optional_string_argument(merchant) } {
         Ok(value) => value,
         Err(err) => {
             unsafe {
@@ -120,16 +1319,24 @@ pub extern "C" fn bufy_ledger_add_account(
             return 3;
         }
     };
-
-    let kind = account_kind_from_code(kind_code);
-
-    match api_add_account(ledger, account_name, kind, category) {
-        Ok(account_id) => {
+    let place = match unsafe { optional_string_argument(place) } {
+        Ok(value) => value,
+        Err(err) => {
             unsafe {
-                write_string(out_account_id, account_id.to_string());
+                write_core_error(out_error, err);
             }
-            0
+            return 3;
         }
+    };
+    let location = (!latitude.is_nan() && !longitude.is_nan()).then_some(GeoLocation {
+        latitude,
+        longitude,
+        place,
+    });
+
+    let ledger = session.edit();
+    match api_set_transaction_metadata(ledger, txn, merchant, location) {
+        Ok(()) => 0,
         Err(err) => {
             unsafe {
                 write_core_error(out_error, err);
@@ -139,18 +1346,22 @@ pub extern "C" fn bufy_ledger_add_account(
     }
 }
 
+/// Drops an incomplete transaction into the ledger's review inbox. Pass a
+/// `year` of 0 when the date is unknown and NaN when the amount is unknown;
+/// `payee` and `notes` may be null. Drafts stay out of summaries until a
+/// reviewer approves them.
 #[no_mangle]
-pub extern "C" fn bufy_ledger_add_transaction(
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_submit_draft(
     handle: *mut LedgerHandle,
-    from_account_id: *const c_char,
-    to_account_id: *const c_char,
-    category_id: *const c_char,
-    scheduled_year: c_int,
-    scheduled_month: c_int,
-    scheduled_day: c_int,
-    budgeted_amount: c_double,
+    source: *const c_char,
+    year: c_int,
+    month: c_int,
+    day: c_int,
+    amount: c_double,
+    payee: *const c_char,
     notes: *const c_char,
-    out_transaction_id: *mut *mut c_char,
+    out_draft_id: *mut *mut c_char,
     out_error: *mut *mut c_char,
 ) -> c_int {
     clear_error(out_error);
@@ -160,8 +1371,8 @@ pub extern "C" fn bufy_ledger_add_transaction(
         }
         return 1;
     }
-    let ledger = unsafe { &mut (*handle).inner };
-    let from = match unsafe { parse_uuid_arg(from_account_id) } {
+    let mut session = unsafe { (*handle).session() };
+    let source = match unsafe { c_string_argument(source) } {
         Ok(value) => value,
         Err(err) => {
             unsafe {
@@ -170,87 +1381,245 @@ pub extern "C" fn bufy_ledger_add_transaction(
             return 2;
         }
     };
-    let to = match unsafe { parse_uuid_arg(to_account_id) } {
+    let date = if year == 0 {
+        None
+    } else {
+        match parse_date(year, month, day) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                unsafe {
+                    write_core_error(out_error, err);
+                }
+                return 3;
+            }
+        }
+    };
+    let payee = match unsafe { optional_string_argument(payee) } {
         Ok(value) => value,
         Err(err) => {
             unsafe {
                 write_core_error(out_error, err);
             }
-            return 3;
+            return 4;
         }
     };
-    let category = match unsafe { parse_optional_uuid(category_id) } {
+    let notes = match unsafe { optional_string_argument(notes) } {
         Ok(value) => value,
         Err(err) => {
             unsafe {
                 write_core_error(out_error, err);
             }
-            return 4;
+            return 5;
         }
     };
-    let scheduled_date = match parse_date(scheduled_year, scheduled_month, scheduled_day) {
-        Ok(value) => value,
+    let amount = (!amount.is_nan()).then_some(amount);
+
+    let ledger = session.edit();
+    match api_submit_draft(ledger, source, date, amount, payee, notes) {
+        Ok(draft_id) => {
+            unsafe {
+                write_string(out_draft_id, draft_id.to_string());
+            }
+            0
+        }
         Err(err) => {
             unsafe {
                 write_core_error(out_error, err);
             }
-            return 5;
+            6
+        }
+    }
+}
+
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_get_summary(
+    handle: *const LedgerHandle,
+    out_summary: *mut FfiLedgerSummary,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() || out_summary.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle or output summary is null");
+        }
+        return 1;
+    }
+
+    let session = unsafe { (*handle).session() };
+    let ledger = &session.ledger;
+    let reference = bufy_domain::now().date_naive();
+    let summary = api_ledger_summary(ledger, reference);
+    let counts = AlertCounts::from_alerts(&summary.alerts);
+    let alerts_json = match serde_json::to_string(&summary.alerts) {
+        Ok(json) => json,
+        Err(err) => {
+            unsafe {
+                write_error(out_error, &err.to_string());
+            }
+            return 2;
         }
     };
-    let note_value = match unsafe { optional_string_argument(notes) } {
+
+    unsafe {
+        (*out_summary).window_start_year = summary.window_start.year();
+        (*out_summary).window_start_month = summary.window_start.month() as i32;
+        (*out_summary).window_start_day = summary.window_start.day() as i32;
+        (*out_summary).window_end_year = summary.window_end.year();
+        (*out_summary).window_end_month = summary.window_end.month() as i32;
+        (*out_summary).window_end_day = summary.window_end.day() as i32;
+        (*out_summary).scope = scope_to_code(summary.scope);
+        (*out_summary).budgeted_total = summary.budgeted_total;
+        (*out_summary).actual_total = summary.actual_total;
+        (*out_summary).remaining_total = summary.remaining_total;
+        (*out_summary).variance_total = summary.variance_total;
+        (*out_summary).incomplete_transactions = summary.incomplete_transactions as c_int;
+        (*out_summary).orphaned_transactions = summary.orphaned_transactions as c_int;
+        (*out_summary).over_budget_alerts = counts.over_budget as c_int;
+        (*out_summary).low_balance_alerts = counts.low_balance as c_int;
+        (*out_summary).overdue_recurrence_alerts = counts.overdue_recurrences as c_int;
+        (*out_summary).underfunded_sinking_fund_alerts = counts.underfunded_sinking_funds as c_int;
+        (*out_summary).contract_notice_alerts = counts.contract_notices as c_int;
+        (*out_summary).pending_drafts = summary.pending_drafts as c_int;
+        (*out_summary).alerts_json = ptr::null_mut();
+        write_string(&mut (*out_summary).alerts_json, alerts_json);
+    }
+
+    0
+}
+
+/// Writes the summary, forecast, and recurrence snapshots for the budget
+/// period containing the given date as one JSON document. Pass a `year` of 0
+/// for today. Release `out_json` with `bufy_string_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_dashboard_snapshot(
+    handle: *const LedgerHandle,
+    year: c_int,
+    month: c_int,
+    day: c_int,
+    out_json: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() || out_json.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle or output pointer is null");
+        }
+        return 1;
+    }
+    let session = unsafe { (*handle).session() };
+    let ledger = &session.ledger;
+    let reference = if year == 0 {
+        bufy_domain::now().date_naive()
+    } else {
+        match parse_date(year, month, day) {
+            Ok(value) => value,
+            Err(err) => {
+                unsafe {
+                    write_core_error(out_error, err);
+                }
+                return 2;
+            }
+        }
+    };
+
+    match serde_json::to_string(&api_dashboard_snapshot(ledger, reference)) {
+        Ok(json) => {
+            unsafe {
+                write_string(out_json, json);
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_error(out_error, &err.to_string());
+            }
+            3
+        }
+    }
+}
+
+/// Writes the transactions scheduled in a date range as a JSON array, newest
+/// first. Each entry carries the ids and names of both accounts and the
+/// category, the short `reference`, amounts, `tags`, `status`, and `recurring`. Pass
+/// a `from_year` or `to_year` of 0 to leave that end open. Release `out_json`
+/// with `bufy_string_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_list_transactions_json(
+    handle: *const LedgerHandle,
+    from_year: c_int,
+    from_month: c_int,
+    from_day: c_int,
+    to_year: c_int,
+    to_month: c_int,
+    to_day: c_int,
+    out_json: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() || out_json.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle or output pointer is null");
+        }
+        return 1;
+    }
+    let session = unsafe { (*handle).session() };
+    let ledger = &session.ledger;
+    let bounds = optional_date(from_year, from_month, from_day)
+        .and_then(|from| Ok((from, optional_date(to_year, to_month, to_day)?)));
+    let (from, to) = match bounds {
         Ok(value) => value,
         Err(err) => {
             unsafe {
                 write_core_error(out_error, err);
             }
-            return 6;
+            return 2;
         }
     };
 
-    match api_add_transaction(
-        ledger,
-        from,
-        to,
-        category,
-        scheduled_date,
-        budgeted_amount as f64,
-        note_value,
-    ) {
-        Ok(tx_id) => {
+    match serde_json::to_string(&api_list_transactions(ledger, from, to)) {
+        Ok(json) => {
             unsafe {
-                write_string(out_transaction_id, tx_id.to_string());
+                write_string(out_json, json);
             }
             0
         }
         Err(err) => {
             unsafe {
-                write_core_error(out_error, err);
+                write_error(out_error, &err.to_string());
             }
-            7
+            3
         }
     }
 }
 
+/// Writes the budget period containing the given date as one JSON document:
+/// `window`, `scope`, `totals`, `per_category`, `per_account`, and
+/// `category_budgets` with each budgeted category's spending and status.
+/// Pass a `year` of 0 for today. Release `out_json` with `bufy_string_free`.
 #[no_mangle]
-pub extern "C" fn bufy_ledger_complete_transaction(
-    handle: *mut LedgerHandle,
-    transaction_id: *const c_char,
-    actual_year: c_int,
-    actual_month: c_int,
-    actual_day: c_int,
-    actual_amount: c_double,
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_budget_summary_json(
+    handle: *const LedgerHandle,
+    year: c_int,
+    month: c_int,
+    day: c_int,
+    out_json: *mut *mut c_char,
     out_error: *mut *mut c_char,
 ) -> c_int {
     clear_error(out_error);
-    if handle.is_null() {
+    if handle.is_null() || out_json.is_null() {
         unsafe {
-            write_error(out_error, "ledger handle is null");
+            write_error(out_error, "ledger handle or output pointer is null");
         }
         return 1;
     }
-    let ledger = unsafe { &mut (*handle).inner };
-    let txn = match unsafe { parse_uuid_arg(transaction_id) } {
-        Ok(value) => value,
+    let session = unsafe { (*handle).session() };
+    let ledger = &session.ledger;
+    let reference = match optional_date(year, month, day) {
+        Ok(value) => value.unwrap_or_else(|| bufy_domain::now().date_naive()),
         Err(err) => {
             unsafe {
                 write_core_error(out_error, err);
@@ -258,62 +1627,268 @@ pub extern "C" fn bufy_ledger_complete_transaction(
             return 2;
         }
     };
-    let actual_date = match parse_date(actual_year, actual_month, actual_day) {
+
+    match serde_json::to_string(&api_budget_summary(ledger, reference)) {
+        Ok(json) => {
+            unsafe {
+                write_string(out_json, json);
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_error(out_error, &err.to_string());
+            }
+            3
+        }
+    }
+}
+
+/// Writes an account's balances from one date to another (both inclusive) as
+/// JSON for charts: `opening_balance` and one `points` entry per period, each
+/// with `start`, `end`, and the `balance` at its close. `granularity` uses the
+/// ledger period codes: 0 day, 1 week, 2 month, 3 year. Release `out_json`
+/// with `bufy_string_free`.
+#[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_balance_series_json(
+    handle: *const LedgerHandle,
+    account_id: *const c_char,
+    from_year: c_int,
+    from_month: c_int,
+    from_day: c_int,
+    to_year: c_int,
+    to_month: c_int,
+    to_day: c_int,
+    granularity: c_int,
+    out_json: *mut *mut c_char,
+    out_error: *mut *mut c_char,
+) -> c_int {
+    clear_error(out_error);
+    if handle.is_null() || out_json.is_null() {
+        unsafe {
+            write_error(out_error, "ledger handle or output pointer is null");
+        }
+        return 1;
+    }
+    let session = unsafe { (*handle).session() };
+    let ledger = &session.ledger;
+    let series = unsafe { parse_uuid_arg(account_id) }.and_then(|account| {
+        let from = parse_date(from_year, from_month, from_day)?;
+        let to = parse_date(to_year, to_month, to_day)?;
+        let unit = time_unit_from_code(granularity)?;
+        api_balance_series(ledger, account, from, to, unit)
+    });
+    let series = match series {
         Ok(value) => value,
         Err(err) => {
             unsafe {
                 write_core_error(out_error, err);
             }
-            return 3;
+            return 2;
         }
     };
 
-    match api_complete_transaction(ledger, txn, actual_date, actual_amount as f64) {
-        Ok(()) => 0,
+    match serde_json::to_string(&series) {
+        Ok(json) => {
+            unsafe {
+                write_string(out_json, json);
+            }
+            0
+        }
         Err(err) => {
             unsafe {
-                write_core_error(out_error, err);
+                write_error(out_error, &err.to_string());
             }
-            4
+            3
         }
     }
 }
 
+/// Writes what changed in the current period's summary since `last_revision`
+/// as JSON: the new `revision`, the `summary` when its totals or alerts
+/// changed, `changed_categories`, and `removed_categories`. Pass 0 to get the
+/// full summary; unknown or old revisions also get it, with `base_revision`
+/// set to null. Release `out_json` with `bufy_string_free`.
 #[no_mangle]
-pub extern "C" fn bufy_ledger_get_summary(
-    handle: *const LedgerHandle,
-    out_summary: *mut FfiLedgerSummary,
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub extern "C" fn bufy_ledger_summary_delta(
+    handle: *mut LedgerHandle,
+    last_revision: c_ulonglong,
+    out_json: *mut *mut c_char,
     out_error: *mut *mut c_char,
 ) -> c_int {
     clear_error(out_error);
-    if handle.is_null() || out_summary.is_null() {
+    if handle.is_null() || out_json.is_null() {
         unsafe {
-            write_error(out_error, "ledger handle or output summary is null");
+            write_error(out_error, "ledger handle or output pointer is null");
         }
         return 1;
     }
+    let mut session = unsafe { (*handle).session() };
+    let session = &mut *session;
+    let reference = bufy_domain::now().date_naive();
+    let delta = session
+        .summaries
+        .delta(&session.ledger, reference, last_revision);
 
-    let ledger = unsafe { &(*handle).inner };
-    let reference = Utc::now().date_naive();
-    let summary = api_ledger_summary(ledger, reference);
+    match serde_json::to_string(&delta) {
+        Ok(json) => {
+            unsafe {
+                write_string(out_json, json);
+            }
+            0
+        }
+        Err(err) => {
+            unsafe {
+                write_error(out_error, &err.to_string());
+            }
+            2
+        }
+    }
+}
 
-    unsafe {
-        (*out_summary).window_start_year = summary.window_start.year();
-        (*out_summary).window_start_month = summary.window_start.month() as i32;
-        (*out_summary).window_start_day = summary.window_start.day() as i32;
-        (*out_summary).window_end_year = summary.window_end.year();
-        (*out_summary).window_end_month = summary.window_end.month() as i32;
-        (*out_summary).window_end_day = summary.window_end.day() as i32;
-        (*out_summary).scope = scope_to_code(summary.scope);
-        (*out_summary).budgeted_total = summary.budgeted_total;
-        (*out_summary).actual_total = summary.actual_total;
-        (*out_summary).remaining_total = summary.remaining_total;
-        (*out_summary).variance_total = summary.variance_total;
-        (*out_summary).incomplete_transactions = summary.incomplete_transactions as c_int;
-        (*out_summary).orphaned_transactions = summary.orphaned_transactions as c_int;
+fn test_mode_now() -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(2025, 1, 1)
+        .and_then(|date| date.and_hms_opt(12, 0, 0))
+        .unwrap_or_default()
+        .and_utc()
+}
+
+/// JSON storage laid out like the CLI's: ledgers in `root`, backups in
+/// `root/backups`.
+fn json_storage(root: PathBuf) -> Result<JsonLedgerStorage, CoreError> {
+    JsonLedgerStorage::new(StoragePaths {
+        backup_root: root.join("backups"),
+        ledger_root: root,
+    })
+}
+
+enum SaveOutcome {
+    Saved,
+    Conflict(String),
+}
+
+/// The session over `name` in `store`, shared with other live handles, or a
+/// fresh one read from the store.
+fn open_session(
+    store: &SessionStore,
+    name: String,
+) -> Result<(Arc<Mutex<LedgerSession>>, LoadReport), CoreError> {
+    let key = store.session_key(&name);
+    let mut sessions = SESSIONS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(session) = sessions.get(&key).and_then(Weak::upgrade) {
+        // Saves lock their session before the registry; never the reverse.
+        drop(sessions);
+        let schema_version = session
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .ledger
+            .schema_version;
+        let report = LoadReport {
+            schema_version,
+            migrations: Vec::new(),
+            warnings: Vec::new(),
+        };
+        return Ok((session, report));
+    }
+    // Read the stamp first: a write in between then shows up as a conflict
+    // on the next save instead of being lost.
+    let stamp = store.stamp(&name, &key)?;
+    let (ledger, report) = store.storage().load_migrated(&name)?;
+    let session = Arc::new(Mutex::new(LedgerSession {
+        ledger,
+        summaries: ApiSummaryTracker::default(),
+        file: Some(SessionFile {
+            store: store.clone(),
+            name,
+            key: key.clone(),
+            stamp,
+        }),
+    }));
+    sessions.retain(|_, session| session.strong_count() > 0);
+    sessions.insert(key, Arc::downgrade(&session));
+    Ok((session, report))
+}
+
+/// Writes the handle's ledger under `name` unless someone else changed or
+/// holds it.
+fn save_session(
+    handle: &LedgerHandle,
+    store: &SessionStore,
+    name: String,
+) -> Result<SaveOutcome, CoreError> {
+    let key = store.session_key(&name);
+    let mut session = handle.session();
+    let session = &mut *session;
+    let own_file = session.file.as_ref().is_some_and(|file| file.key == key);
+    if own_file {
+        let known = session.file.as_ref().and_then(|file| file.stamp);
+        if store.stamp(&name, &key)? != known {
+            return Ok(SaveOutcome::Conflict(format!(
+                "{} changed since this ledger was loaded; call bufy_ledger_reload \
+                 or save under another name",
+                key.display()
+            )));
+        }
+    } else {
+        let mut sessions = SESSIONS.lock().unwrap_or_else(PoisonError::into_inner);
+        if sessions.get(&key).and_then(Weak::upgrade).is_some() {
+            return Ok(SaveOutcome::Conflict(format!(
+                "{} is open in another session; save under another name",
+                key.display()
+            )));
+        }
+        if store.stamp(&name, &key)?.is_some() {
+            return Ok(SaveOutcome::Conflict(format!(
+                "{} already exists; load it to change it, or save under another name",
+                key.display()
+            )));
+        }
+        if session.file.is_none() {
+            sessions.retain(|_, session| session.strong_count() > 0);
+            sessions.insert(key.clone(), Arc::downgrade(&handle.session));
+            session.file = Some(SessionFile {
+                store: store.clone(),
+                name: name.clone(),
+                key: key.clone(),
+                stamp: None,
+            });
+        }
     }
 
-    0
+    store.storage().save_ledger(&name, &session.ledger)?;
+    if let Some(file) = session.file.as_mut().filter(|file| file.key == key) {
+        file.stamp = store.stamp(&name, &key)?;
+    }
+    Ok(SaveOutcome::Saved)
+}
+
+fn load_report_json(report: &LoadReport) -> serde_json::Value {
+    serde_json::json!({
+        "schema_version": report.schema_version,
+        "migrations": report.migrations,
+        "warnings": report.warnings,
+    })
+}
+
+fn set_storage(store: SessionStore) {
+    if let Ok(mut current) = STORAGE.write() {
+        *current = Some(store);
+    }
+}
+
+fn storage() -> Result<SessionStore, CoreError> {
+    STORAGE
+        .read()
+        .ok()
+        .and_then(|storage| storage.clone())
+        .ok_or_else(|| {
+            CoreError::InvalidOperation(
+                "no storage configured; call bufy_set_storage_root or bufy_set_test_mode first"
+                    .into(),
+            )
+        })
 }
 
 fn ledger_period_from_code(code: c_int) -> LedgerBudgetPeriod {
@@ -335,6 +1910,18 @@ fn ledger_period_from_code(code: c_int) -> LedgerBudgetPeriod {
     }
 }
 
+fn time_unit_from_code(code: c_int) -> Result<TimeUnit, CoreError> {
+    match code {
+        0 => Ok(TimeUnit::Day),
+        1 => Ok(TimeUnit::Week),
+        2 => Ok(TimeUnit::Month),
+        3 => Ok(TimeUnit::Year),
+        _ => Err(CoreError::Validation(format!(
+            "unknown granularity code {code}; use 0 day, 1 week, 2 month, or 3 year"
+        ))),
+    }
+}
+
 fn account_kind_from_code(code: c_int) -> AccountKind {
     match code {
         0 => AccountKind::Bank,
@@ -346,6 +1933,14 @@ fn account_kind_from_code(code: c_int) -> AccountKind {
     }
 }
 
+fn category_kind_from_code(code: c_int) -> CategoryKind {
+    match code {
+        1 => CategoryKind::Income,
+        2 => CategoryKind::Transfer,
+        _ => CategoryKind::Expense,
+    }
+}
+
 fn scope_to_code(scope: BudgetScope) -> c_int {
     match scope {
         BudgetScope::Past => 0,
@@ -419,11 +2014,20 @@ unsafe fn parse_uuid_arg(ptr: *const c_char) -> Result<Uuid, CoreError> {
 }
 
 fn parse_date(year: c_int, month: c_int, day: c_int) -> Result<NaiveDate, CoreError> {
-    NaiveDate::from_ymd_opt(year as i32, month as u32, day as u32).ok_or_else(|| {
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).ok_or_else(|| {
         CoreError::Validation(format!("invalid date: {year:04}-{month:02}-{day:02}"))
     })
 }
 
+/// `None` when `year` is 0.
+fn optional_date(year: c_int, month: c_int, day: c_int) -> Result<Option<NaiveDate>, CoreError> {
+    if year == 0 {
+        Ok(None)
+    } else {
+        parse_date(year, month, day).map(Some)
+    }
+}
+
 unsafe fn optional_string_argument(ptr: *const c_char) -> Result<Option<String>, CoreError> {
     if ptr.is_null() {
         return Ok(None);
@@ -435,3 +2039,6 @@ unsafe fn optional_string_argument(ptr: *const c_char) -> Result<Option<String>,
         Ok(Some(value))
     }
 }
+
+#[cfg(test)]
+mod tests;
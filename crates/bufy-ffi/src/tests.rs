@@ -0,0 +1,250 @@
+use std::{
+    ffi::{CStr, CString},
+    os::raw::c_char,
+    ptr,
+    sync::Arc,
+};
+
+use bufy_core::storage::LedgerStorage;
+
+use crate::*;
+
+fn text(value: &str) -> CString {
+    CString::new(value).expect("no interior nul")
+}
+
+/// Takes ownership of a string written by the library.
+fn take_string(value: *mut c_char) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    let owned = unsafe { CStr::from_ptr(value) }
+        .to_string_lossy()
+        .into_owned();
+    bufy_string_free(value);
+    Some(owned)
+}
+
+fn create(name: &str) -> *mut LedgerHandle {
+    let name = text(name);
+    let handle = bufy_ledger_create(name.as_ptr(), 2, ptr::null_mut());
+    assert!(!handle.is_null());
+    handle
+}
+
+fn add_account(handle: *mut LedgerHandle, name: &str) {
+    let name = text(name);
+    let mut error = ptr::null_mut();
+    let code = bufy_ledger_add_account(
+        handle,
+        name.as_ptr(),
+        0,
+        ptr::null(),
+        ptr::null_mut(),
+        &mut error,
+    );
+    assert_eq!(code, 0, "{:?}", take_string(error));
+}
+
+fn account_names(handle: *mut LedgerHandle) -> Vec<String> {
+    let session = unsafe { (*handle).session() };
+    session
+        .ledger
+        .accounts
+        .iter()
+        .map(|account| account.name.clone())
+        .collect()
+}
+
+fn same_session(left: *mut LedgerHandle, right: *mut LedgerHandle) -> bool {
+    unsafe { Arc::ptr_eq(&(*left).session, &(*right).session) }
+}
+
+#[test]
+fn storage_handles_share_sessions_and_refuse_stale_saves() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let root = text(dir.path().to_str().expect("utf-8 path"));
+    let storage = bufy_storage_open(root.as_ptr(), ptr::null_mut());
+    assert!(!storage.is_null());
+    let name = text("Home");
+
+    let created = create("Home");
+    assert_eq!(
+        bufy_storage_save_ledger(storage, created, name.as_ptr(), ptr::null_mut()),
+        0
+    );
+    let first = bufy_storage_load_ledger(storage, name.as_ptr(), ptr::null_mut(), ptr::null_mut());
+    let second = bufy_storage_load_ledger(storage, name.as_ptr(), ptr::null_mut(), ptr::null_mut());
+    assert!(same_session(first, second));
+    assert!(same_session(created, first));
+    add_account(first, "Checking");
+    assert_eq!(account_names(second), vec!["Checking".to_string()]);
+
+    // Another writer, such as the CLI, saves the file behind the session.
+    let other = json_storage(dir.path().to_path_buf()).expect("storage");
+    let mut outside = other.load_ledger("Home").expect("stored ledger");
+    outside.name = "Changed outside".into();
+    other.save_ledger("Home", &outside).expect("outside save");
+
+    let mut error = ptr::null_mut();
+    let code = bufy_storage_save_ledger(storage, second, name.as_ptr(), &mut error);
+    assert_eq!(code, 4);
+    assert!(take_string(error).is_some_and(|message| message.contains("changed")));
+
+    let mut report = ptr::null_mut();
+    assert_eq!(bufy_ledger_reload(first, &mut report, ptr::null_mut()), 0);
+    assert!(take_string(report).is_some_and(|report| report.contains("schema_version")));
+    assert!(account_names(second).is_empty());
+    assert_eq!(
+        unsafe { (*second).session() }.ledger.name,
+        "Changed outside"
+    );
+    assert_eq!(
+        bufy_storage_save_ledger(storage, second, name.as_ptr(), ptr::null_mut()),
+        0
+    );
+
+    let unsaved = create("Scratch");
+    assert_eq!(
+        bufy_ledger_reload(unsaved, ptr::null_mut(), ptr::null_mut()),
+        2
+    );
+
+    for handle in [created, first, second, unsaved] {
+        bufy_ledger_free(handle);
+    }
+    bufy_storage_free(storage);
+}
+
+#[test]
+fn configured_storage_saves_and_loads_through_sessions() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let root = text(dir.path().to_str().expect("utf-8 path"));
+    assert_eq!(bufy_set_storage_root(root.as_ptr(), ptr::null_mut()), 0);
+    let name = text("Home");
+
+    let created = create("Home");
+    assert_eq!(bufy_ledger_save(created, name.as_ptr(), ptr::null_mut()), 0);
+    let first = bufy_ledger_load(name.as_ptr(), ptr::null_mut());
+    let second = bufy_ledger_load(name.as_ptr(), ptr::null_mut());
+    assert!(same_session(first, second));
+    assert!(same_session(created, first));
+
+    let outside = json_storage(dir.path().to_path_buf()).expect("storage");
+    let mut ledger = outside.load_ledger("Home").expect("stored ledger");
+    ledger.name = "Changed outside".into();
+    outside.save_ledger("Home", &ledger).expect("outside save");
+    let mut error = ptr::null_mut();
+    assert_eq!(bufy_ledger_save(first, name.as_ptr(), &mut error), 4);
+    assert!(take_string(error).is_some());
+    assert_eq!(
+        bufy_ledger_reload(first, ptr::null_mut(), ptr::null_mut()),
+        0
+    );
+    assert_eq!(bufy_ledger_save(first, name.as_ptr(), ptr::null_mut()), 0);
+
+    // The in-memory store of test mode keeps the same rules.
+    bufy_set_test_mode(7);
+    let stored = create("Home");
+    assert_eq!(bufy_ledger_save(stored, name.as_ptr(), ptr::null_mut()), 0);
+    let loaded = bufy_ledger_load(name.as_ptr(), ptr::null_mut());
+    assert!(same_session(stored, loaded));
+    assert!(!same_session(first, loaded));
+    let rival = create("Home");
+    assert_eq!(bufy_ledger_save(rival, name.as_ptr(), ptr::null_mut()), 4);
+    add_account(loaded, "Cash");
+    assert_eq!(bufy_ledger_save(stored, name.as_ptr(), ptr::null_mut()), 0);
+    bufy_clear_test_mode();
+
+    for handle in [created, first, second, stored, loaded, rival] {
+        bufy_ledger_free(handle);
+    }
+}
+
+#[test]
+fn rejected_calls_restores_and_first_saves_respect_sessions() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let root = text(dir.path().to_str().expect("utf-8 path"));
+    let storage = bufy_storage_open(root.as_ptr(), ptr::null_mut());
+    assert!(!storage.is_null());
+    let name = text("Home");
+
+    // A call with invalid arguments leaves the revision alone.
+    let created = create("Home");
+    let revision = || unsafe { (*created).session() }.summaries.revision();
+    let before = revision();
+    let code = bufy_ledger_add_account(
+        created,
+        ptr::null(),
+        0,
+        ptr::null(),
+        ptr::null_mut(),
+        ptr::null_mut(),
+    );
+    assert_ne!(code, 0);
+    assert_eq!(revision(), before);
+    add_account(created, "Checking");
+    assert_eq!(revision(), before + 1);
+
+    assert_eq!(
+        bufy_storage_save_ledger(storage, created, name.as_ptr(), ptr::null_mut()),
+        0
+    );
+    let mut backup_id = ptr::null_mut();
+    assert_eq!(
+        bufy_storage_backup_ledger(
+            storage,
+            created,
+            name.as_ptr(),
+            ptr::null(),
+            &mut backup_id,
+            ptr::null_mut(),
+        ),
+        0
+    );
+    let backup_id = text(&take_string(backup_id).expect("backup id"));
+
+    // The stored ledger is live, so restoring over it is refused.
+    let mut error = ptr::null_mut();
+    let refused = bufy_storage_restore_backup(
+        storage,
+        name.as_ptr(),
+        backup_id.as_ptr(),
+        ptr::null_mut(),
+        &mut error,
+    );
+    assert!(refused.is_null());
+    assert!(take_string(error).is_some_and(|message| message.contains("open in another session")));
+    bufy_ledger_free(created);
+
+    // A new ledger does not replace the file once no session holds it.
+    let rival = create("Home");
+    let mut error = ptr::null_mut();
+    assert_eq!(
+        bufy_storage_save_ledger(storage, rival, name.as_ptr(), &mut error),
+        4
+    );
+    assert!(take_string(error).is_some_and(|message| message.contains("already exists")));
+
+    let restored = bufy_storage_restore_backup(
+        storage,
+        name.as_ptr(),
+        backup_id.as_ptr(),
+        ptr::null_mut(),
+        ptr::null_mut(),
+    );
+    assert!(!restored.is_null());
+    let loaded = bufy_storage_load_ledger(storage, name.as_ptr(), ptr::null_mut(), ptr::null_mut());
+    assert!(same_session(restored, loaded));
+    assert_eq!(account_names(loaded), vec!["Checking".to_string()]);
+    add_account(restored, "Cash");
+    assert_eq!(
+        bufy_storage_save_ledger(storage, loaded, name.as_ptr(), ptr::null_mut()),
+        0
+    );
+
+    for handle in [rival, restored, loaded] {
+        bufy_ledger_free(handle);
+    }
+    bufy_storage_free(storage);
+}
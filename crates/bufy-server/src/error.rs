@@ -0,0 +1,82 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+
+use bufy_core::{CoreError, ValidationIssue};
+
+/// An error returned to the client as `{"error": "..."}` with a matching
+/// status code. Rejected inputs also carry `issue`: the `field` and the
+/// `issue` kind with its limits.
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+    pub issue: Option<ValidationIssue>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            issue: None,
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, message)
+    }
+}
+
+impl From<CoreError> for ApiError {
+    fn from(err: CoreError) -> Self {
+        let status = match &err {
+            CoreError::LedgerNotFound(_)
+            | CoreError::AccountNotFound(_)
+            | CoreError::CategoryNotFound(_)
+            | CoreError::TransactionNotFound(_)
+            | CoreError::SimulationNotFound(_)
+            | CoreError::MemberNotFound(_)
+            | CoreError::ViewNotFound(_)
+            | CoreError::RuleNotFound(_)
+            | CoreError::DraftNotFound(_) => StatusCode::NOT_FOUND,
+            CoreError::Validation(_) | CoreError::Invalid(_) | CoreError::NumericOverflow(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            CoreError::InvalidOperation(_) => StatusCode::CONFLICT,
+            CoreError::LedgerNotLoaded
+            | CoreError::Storage(_)
+            | CoreError::AtomicWrite { .. }
+            | CoreError::Io(_)
+            | CoreError::Serde(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let issue = match &err {
+            CoreError::Invalid(issue) => Some(issue.clone()),
+            _ => None,
+        };
+        Self {
+            issue,
+            ..Self::new(status, err.to_string())
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if self.status.is_server_error() {
+            tracing::warn!(status = %self.status, error = %self.message, "request failed");
+        }
+        let body = match self.issue {
+            Some(issue) => json!({ "error": self.message, "issue": issue }),
+            None => json!({ "error": self.message }),
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
@@ -0,0 +1,20 @@
+//! bufy-server
+//!
+//! HTTP API over the same core services the CLI uses, so a web frontend
+//! can manage ledgers without its own budgeting logic. Ledgers live in any
+//! [`LedgerStorage`](bufy_core::storage::LedgerStorage); requests and
+//! responses are JSON in the persisted ledger schema.
+//!
+//! Requests for one ledger are serialized by a per-ledger lock: reads share
+//! it, and each change loads, edits, and saves the ledger while holding it
+//! alone. Responses carry the ledger revision as an `ETag`; sending it back
+//! as `If-Match` makes a change fail with `412` when someone else saved the
+//! ledger in between.
+
+mod error;
+mod routes;
+mod state;
+
+pub use error::ApiError;
+pub use routes::router;
+pub use state::{AppState, LedgerLocks};
@@ -0,0 +1,54 @@
+//! `bufy-server [--root <dir>] [--addr <host:port>]`: serves the ledgers
+//! under `dir` (default `./ledgers`, backups in `dir/backups`) over HTTP.
+
+use std::{net::SocketAddr, path::PathBuf, process::ExitCode, sync::Arc};
+
+use bufy_server::{router, AppState};
+use bufy_storage_json::{JsonLedgerStorage, StoragePaths};
+
+const USAGE: &str = "usage: bufy-server [--root <dir>] [--addr <host:port>]";
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("bufy-server: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run() -> Result<(), String> {
+    let mut root = PathBuf::from("ledgers");
+    let mut addr = DEFAULT_ADDR.to_string();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--root" => root = args.next().ok_or(USAGE)?.into(),
+            "--addr" => addr = args.next().ok_or(USAGE)?,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            other => return Err(format!("unknown argument `{other}`\n{USAGE}")),
+        }
+    }
+    let addr: SocketAddr = addr
+        .parse()
+        .map_err(|err| format!("invalid address `{addr}`: {err}"))?;
+    let storage = JsonLedgerStorage::new(StoragePaths {
+        backup_root: root.join("backups"),
+        ledger_root: root.clone(),
+    })
+    .map_err(|err| err.to_string())?;
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|err| format!("cannot listen on {addr}: {err}"))?;
+    println!("Serving ledgers from {} on http://{addr}", root.display());
+    axum::serve(listener, router(AppState::new(Arc::new(storage))))
+        .await
+        .map_err(|err| err.to_string())
+}
@@ -0,0 +1,381 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use bufy_core::{
+    api_add_account, api_add_transaction, api_create_ledger, api_dashboard_snapshot,
+    api_ledger_summary, storage::prepare_loaded_ledger, time::Clock, validation::check_name,
+    CoreError, SimulationService,
+};
+use bufy_domain::{account::AccountKind, Ledger, LedgerBudgetPeriod};
+
+use crate::{
+    state::{AppState, ServerClock},
+    ApiError,
+};
+
+/// Every route, backed by `state`.
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/ledgers", get(list_ledgers).post(create_ledger))
+        .route(
+            "/ledgers/:ledger",
+            get(get_ledger).put(replace_ledger).delete(delete_ledger),
+        )
+        .route(
+            "/ledgers/:ledger/accounts",
+            get(list_accounts).post(add_account),
+        )
+        .route(
+            "/ledgers/:ledger/transactions",
+            get(list_transactions).post(add_transaction),
+        )
+        .route("/ledgers/:ledger/summary", get(summary))
+        .route("/ledgers/:ledger/forecast", get(forecast))
+        .route("/ledgers/:ledger/dashboard", get(dashboard))
+        .route(
+            "/ledgers/:ledger/simulations",
+            get(list_simulations).post(create_simulation),
+        )
+        .route(
+            "/ledgers/:ledger/simulations/:simulation",
+            axum::routing::delete(discard_simulation),
+        )
+        .route(
+            "/ledgers/:ledger/simulations/:simulation/impact",
+            get(simulation_impact),
+        )
+        .route(
+            "/ledgers/:ledger/simulations/:simulation/apply",
+            post(apply_simulation),
+        )
+        .with_state(state)
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateLedger {
+    name: String,
+    #[serde(default)]
+    budget_period: LedgerBudgetPeriod,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddAccount {
+    name: String,
+    kind: AccountKind,
+    #[serde(default)]
+    category_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AddTransaction {
+    from_account: Uuid,
+    to_account: Uuid,
+    #[serde(default)]
+    category_id: Option<Uuid>,
+    date: NaiveDate,
+    amount: f64,
+    #[serde(default)]
+    notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateSimulation {
+    name: String,
+    #[serde(default)]
+    notes: Option<String>,
+    /// Branches from this pending simulation.
+    #[serde(default)]
+    parent: Option<String>,
+}
+
+/// `?date=YYYY-MM-DD`; today when omitted.
+#[derive(Debug, Deserialize)]
+struct DateQuery {
+    date: Option<NaiveDate>,
+}
+
+impl DateQuery {
+    fn or_today(&self) -> NaiveDate {
+        self.date.unwrap_or_else(|| ServerClock.today())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Created {
+    id: Uuid,
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(json!({ "status": "ok", "version": env!("CARGO_PKG_VERSION") }))
+}
+
+async fn list_ledgers(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let ledgers = state.list().await?;
+    Ok(Json(json!({ "ledgers": ledgers })).into_response())
+}
+
+async fn create_ledger(
+    State(state): State<AppState>,
+    Json(body): Json<CreateLedger>,
+) -> Result<Response, ApiError> {
+    let name = check_name("ledger name", &body.name).map_err(CoreError::from)?;
+    let ledger = api_create_ledger(name.clone(), body.budget_period);
+    let revision = state.create(&name, ledger.clone()).await?;
+    Ok(tagged(StatusCode::CREATED, &revision, &ledger))
+}
+
+async fn get_ledger(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, ApiError> {
+    let (ledger, revision) = state.read(&name, |ledger| Ok(ledger.clone())).await?;
+    Ok(tagged(StatusCode::OK, &revision, &ledger))
+}
+
+async fn replace_ledger(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(body): Json<Ledger>,
+) -> Result<Response, ApiError> {
+    let body = prepare_replacement(&name, body)?;
+    let (ledger, revision) = state
+        .edit(&name, if_match(&headers), move |ledger| {
+            *ledger = body;
+            ledger.touch();
+            Ok(ledger.clone())
+        })
+        .await?;
+    Ok(tagged(StatusCode::OK, &revision, &ledger))
+}
+
+/// Brings an uploaded ledger to the current schema the way a load does, and
+/// rejects one whose transactions point at accounts or categories it lacks.
+fn prepare_replacement(name: &str, mut ledger: Ledger) -> Result<Ledger, CoreError> {
+    ledger.name = check_name("ledger name", &ledger.name)?;
+    let report = prepare_loaded_ledger(&mut ledger)?;
+    for note in &report.migrations {
+        tracing::info!(ledger = %name, "migration: {}", note);
+    }
+    let dangling = ledger.transactions.iter().find(|txn| {
+        ledger.account(txn.from_account).is_none()
+            || ledger.account(txn.to_account).is_none()
+            || txn
+                .category_id
+                .is_some_and(|id| ledger.category(id).is_none())
+    });
+    if let Some(txn) = dangling {
+        return Err(CoreError::Validation(format!(
+            "transaction {} refers to an account or category the ledger does not have",
+            txn.id
+        )));
+    }
+    Ok(ledger)
+}
+
+async fn delete_ledger(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+) -> Result<StatusCode, ApiError> {
+    state.delete(&name, if_match(&headers)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_accounts(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, ApiError> {
+    let (accounts, revision) = state
+        .read(&name, |ledger| Ok(ledger.accounts.clone()))
+        .await?;
+    Ok(tagged(StatusCode::OK, &revision, &accounts))
+}
+
+async fn add_account(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(body): Json<AddAccount>,
+) -> Result<Response, ApiError> {
+    let (id, revision) = state
+        .edit(&name, if_match(&headers), move |ledger| {
+            api_add_account(ledger, body.name, body.kind, body.category_id)
+        })
+        .await?;
+    Ok(tagged(StatusCode::CREATED, &revision, &Created { id }))
+}
+
+async fn list_transactions(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, ApiError> {
+    let (transactions, revision) = state
+        .read(&name, |ledger| Ok(ledger.transactions.clone()))
+        .await?;
+    Ok(tagged(StatusCode::OK, &revision, &transactions))
+}
+
+async fn add_transaction(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(body): Json<AddTransaction>,
+) -> Result<Response, ApiError> {
+    let (id, revision) = state
+        .edit(&name, if_match(&headers), move |ledger| {
+            api_add_transaction(
+                ledger,
+                body.from_account,
+                body.to_account,
+                body.category_id,
+                body.date,
+                body.amount,
+                body.notes,
+            )
+        })
+        .await?;
+    Ok(tagged(StatusCode::CREATED, &revision, &Created { id }))
+}
+
+async fn summary(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<DateQuery>,
+) -> Result<Response, ApiError> {
+    let date = query.or_today();
+    let (summary, revision) = state
+        .read(&name, move |ledger| Ok(api_ledger_summary(ledger, date)))
+        .await?;
+    Ok(tagged(StatusCode::OK, &revision, &summary))
+}
+
+async fn forecast(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<DateQuery>,
+) -> Result<Response, ApiError> {
+    let date = query.or_today();
+    let (forecast, revision) = state
+        .read(&name, move |ledger| {
+            Ok(api_dashboard_snapshot(ledger, date).forecast)
+        })
+        .await?;
+    Ok(tagged(StatusCode::OK, &revision, &forecast))
+}
+
+async fn dashboard(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<DateQuery>,
+) -> Result<Response, ApiError> {
+    let date = query.or_today();
+    let (snapshot, revision) = state
+        .read(&name, move |ledger| {
+            Ok(api_dashboard_snapshot(ledger, date))
+        })
+        .await?;
+    Ok(tagged(StatusCode::OK, &revision, &snapshot))
+}
+
+async fn list_simulations(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Response, ApiError> {
+    let (simulations, revision) = state
+        .read(&name, |ledger| Ok(ledger.simulations().to_vec()))
+        .await?;
+    Ok(tagged(StatusCode::OK, &revision, &simulations))
+}
+
+async fn create_simulation(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    headers: HeaderMap,
+    Json(body): Json<CreateSimulation>,
+) -> Result<Response, ApiError> {
+    let (simulation, revision) = state
+        .edit(&name, if_match(&headers), move |ledger| {
+            let simulation = match body.parent {
+                Some(parent) => {
+                    SimulationService::branch(ledger, &parent, body.name, body.notes, &ServerClock)?
+                }
+                None => SimulationService::create(ledger, body.name, body.notes, &ServerClock)?,
+            };
+            Ok(simulation.clone())
+        })
+        .await?;
+    Ok(tagged(StatusCode::CREATED, &revision, &simulation))
+}
+
+async fn simulation_impact(
+    State(state): State<AppState>,
+    Path((name, simulation)): Path<(String, String)>,
+    Query(query): Query<DateQuery>,
+) -> Result<Response, ApiError> {
+    let date = query.or_today();
+    let (impact, revision) = state
+        .read(&name, move |ledger| {
+            let window = ledger.budget_window_containing(date);
+            SimulationService::summarize_in_window(ledger, &simulation, window, window.scope(date))
+        })
+        .await?;
+    Ok(tagged(StatusCode::OK, &revision, &impact))
+}
+
+async fn apply_simulation(
+    State(state): State<AppState>,
+    Path((name, simulation)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let (((), backup), revision) = state
+        .edit_after_backup(&name, if_match(&headers), "pre-apply", move |ledger| {
+            SimulationService::apply(ledger, &simulation, &ServerClock)
+        })
+        .await?;
+    Ok(tagged(
+        StatusCode::OK,
+        &revision,
+        &json!({ "applied": true, "backup": backup }),
+    ))
+}
+
+async fn discard_simulation(
+    State(state): State<AppState>,
+    Path((name, simulation)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<StatusCode, ApiError> {
+    state
+        .edit(&name, if_match(&headers), move |ledger| {
+            SimulationService::discard(ledger, &simulation)
+        })
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `value` as JSON with the ledger revision as its `ETag`.
+fn tagged<T: Serialize>(status: StatusCode, revision: &str, value: &T) -> Response {
+    (
+        status,
+        [(header::ETAG, format!("\"{}\"", revision))],
+        Json(value),
+    )
+        .into_response()
+}
+
+/// The revision a change expects, from `If-Match`.
+fn if_match(headers: &HeaderMap) -> Option<String> {
+    let value = headers.get(header::IF_MATCH)?.to_str().ok()?.trim();
+    let value = value.strip_prefix("W/").unwrap_or(value);
+    Some(value.trim_matches('"').to_string())
+}
@@ -0,0 +1,254 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::http::StatusCode;
+use chrono::{DateTime, SecondsFormat, Utc};
+use tokio::sync::RwLock;
+
+use bufy_core::{storage::LedgerStorage, time::Clock, CoreError};
+use bufy_domain::{time, Ledger};
+use bufy_storage_json::canonical_name;
+
+use crate::ApiError;
+
+/// One lock per ledger name, created on first use.
+#[derive(Default)]
+pub struct LedgerLocks {
+    locks: Mutex<HashMap<String, Arc<RwLock<()>>>>,
+}
+
+impl LedgerLocks {
+    /// The lock guarding `name`. Names that storage maps to the same file
+    /// share a lock.
+    pub fn lock_for(&self, name: &str) -> Arc<RwLock<()>> {
+        let mut locks = self
+            .locks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        locks.entry(lock_key(name)).or_default().clone()
+    }
+}
+
+/// Shared by every request: the storage and the per-ledger locks.
+#[derive(Clone)]
+pub struct AppState {
+    storage: Arc<dyn LedgerStorage>,
+    locks: Arc<LedgerLocks>,
+}
+
+impl AppState {
+    pub fn new(storage: Arc<dyn LedgerStorage>) -> Self {
+        Self {
+            storage,
+            locks: Arc::new(LedgerLocks::default()),
+        }
+    }
+
+    pub(crate) async fn list(&self) -> Result<Vec<String>, ApiError> {
+        let storage = self.storage.clone();
+        blocking(move || storage.list_ledgers()).await
+    }
+
+    /// Runs `read` on the stored ledger while holding its lock shared.
+    pub(crate) async fn read<T, F>(&self, name: &str, read: F) -> Result<(T, String), ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Ledger) -> Result<T, CoreError> + Send + 'static,
+    {
+        let lock = self.locks.lock_for(name);
+        let _guard = lock.read().await;
+        let storage = self.storage.clone();
+        let name = name.to_string();
+        blocking(move || {
+            let ledger = load(storage.as_ref(), &name)?;
+            Ok((read(&ledger)?, revision(&ledger)))
+        })
+        .await
+    }
+
+    /// Loads the ledger, runs `edit`, and saves the result while holding the
+    /// ledger's lock alone. Nothing is saved when `edit` fails, or when
+    /// `expected` is set and the stored revision differs.
+    pub(crate) async fn edit<T, F>(
+        &self,
+        name: &str,
+        expected: Option<String>,
+        edit: F,
+    ) -> Result<(T, String), ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Ledger) -> Result<T, CoreError> + Send + 'static,
+    {
+        let ((value, _), revision) = self.edit_inner(name, expected, None, edit).await?;
+        Ok((value, revision))
+    }
+
+    /// Like [`AppState::edit`], but first backs up the stored ledger with
+    /// `note`, returning the backup id with the value.
+    pub(crate) async fn edit_after_backup<T, F>(
+        &self,
+        name: &str,
+        expected: Option<String>,
+        note: &'static str,
+        edit: F,
+    ) -> Result<((T, String), String), ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Ledger) -> Result<T, CoreError> + Send + 'static,
+    {
+        let ((value, backup), revision) = self.edit_inner(name, expected, Some(note), edit).await?;
+        let backup = backup.unwrap_or_default();
+        Ok(((value, backup), revision))
+    }
+
+    async fn edit_inner<T, F>(
+        &self,
+        name: &str,
+        expected: Option<String>,
+        backup_note: Option<&'static str>,
+        edit: F,
+    ) -> Result<((T, Option<String>), String), ApiError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Ledger) -> Result<T, CoreError> + Send + 'static,
+    {
+        let lock = self.locks.lock_for(name);
+        let _guard = lock.write().await;
+        let storage = self.storage.clone();
+        let name = name.to_string();
+        let loaded = {
+            let storage = storage.clone();
+            let name = name.clone();
+            blocking(move || load(storage.as_ref(), &name)).await?
+        };
+        check_revision(&loaded, expected.as_deref())?;
+        blocking(move || {
+            let original = backup_note.map(|_| loaded.clone());
+            let mut ledger = loaded;
+            let value = edit(&mut ledger)?;
+            // Backed up only once the edit succeeded, so a rejected edit
+            // leaves no backup behind.
+            let backup = match (original, backup_note) {
+                (Some(original), Some(note)) => {
+                    Some(storage.backup_ledger(&name, &original, Some(note))?.id)
+                }
+                _ => None,
+            };
+            storage.save_ledger(&name, &ledger)?;
+            Ok(((value, backup), revision(&ledger)))
+        })
+        .await
+    }
+
+    /// Saves `ledger` under `name` unless a ledger is already stored there.
+    pub(crate) async fn create(&self, name: &str, ledger: Ledger) -> Result<String, ApiError> {
+        let lock = self.locks.lock_for(name);
+        let _guard = lock.write().await;
+        let storage = self.storage.clone();
+        let name = name.to_string();
+        let exists = {
+            let storage = storage.clone();
+            let name = name.clone();
+            blocking(move || match storage.load_ledger(&name) {
+                Ok(_) => Ok(true),
+                Err(CoreError::LedgerNotFound(_)) => Ok(false),
+                Err(err) => Err(err),
+            })
+            .await?
+        };
+        if exists {
+            return Err(ApiError::conflict(format!(
+                "ledger `{}` already exists",
+                name
+            )));
+        }
+        blocking(move || {
+            storage.save_ledger(&name, &ledger)?;
+            Ok(revision(&ledger))
+        })
+        .await
+    }
+
+    pub(crate) async fn delete(
+        &self,
+        name: &str,
+        expected: Option<String>,
+    ) -> Result<(), ApiError> {
+        let lock = self.locks.lock_for(name);
+        let _guard = lock.write().await;
+        let storage = self.storage.clone();
+        let name = name.to_string();
+        let loaded = {
+            let storage = storage.clone();
+            let name = name.clone();
+            blocking(move || storage.load_ledger(&name)).await?
+        };
+        check_revision(&loaded, expected.as_deref())?;
+        blocking(move || storage.delete_ledger(&name)).await
+    }
+}
+
+/// Loads `name` migrated to the current schema. Migrating leaves
+/// `updated_at` alone, so the revision matches the stored file.
+fn load(storage: &dyn LedgerStorage, name: &str) -> Result<Ledger, CoreError> {
+    let (ledger, report) = storage.load_migrated(name)?;
+    for note in &report.migrations {
+        tracing::info!(ledger = %name, "migration: {}", note);
+    }
+    Ok(ledger)
+}
+
+/// The ledger revision sent as `ETag`: when it was last changed.
+pub(crate) fn revision(ledger: &Ledger) -> String {
+    ledger
+        .updated_at
+        .to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+fn check_revision(ledger: &Ledger, expected: Option<&str>) -> Result<(), ApiError> {
+    match expected {
+        Some(expected) if expected != "*" && expected != revision(ledger) => Err(ApiError::new(
+            StatusCode::PRECONDITION_FAILED,
+            format!(
+                "ledger changed since revision {}; it is now at {}",
+                expected,
+                revision(ledger)
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Runs storage work off the async workers.
+async fn blocking<T, F>(work: F) -> Result<T, ApiError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, CoreError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|err| {
+            ApiError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("storage task failed: {}", err),
+            )
+        })?
+        .map_err(ApiError::from)
+}
+
+/// The file name slug the JSON storage uses, so names stored in one file
+/// share a lock and names stored apart do not.
+fn lock_key(name: &str) -> String {
+    canonical_name(name)
+}
+
+/// Timestamps for simulations come from the same source as ledger edits.
+pub(crate) struct ServerClock;
+
+impl Clock for ServerClock {
+    fn now(&self) -> DateTime<Utc> {
+        time::now()
+    }
+}
@@ -0,0 +1,388 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use bufy_core::storage::LedgerStorage;
+use bufy_server::{router, AppState};
+use bufy_storage_json::MemoryLedgerStorage;
+
+fn app() -> Router {
+    router(AppState::new(Arc::new(MemoryLedgerStorage::new())))
+}
+
+async fn send(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+    if_match: Option<&str>,
+) -> (StatusCode, Option<String>, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(revision) = if_match {
+        request = request.header(header::IF_MATCH, revision);
+    }
+    let request = match body {
+        Some(body) => request
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let etag = response
+        .headers()
+        .get(header::ETAG)
+        .map(|value| value.to_str().unwrap().to_string());
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let json = if bytes.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&bytes).unwrap()
+    };
+    (status, etag, json)
+}
+
+#[tokio::test]
+async fn ledgers_are_created_edited_and_summarized_over_http() {
+    let app = app();
+    let (status, etag, ledger) = send(
+        &app,
+        Method::POST,
+        "/ledgers",
+        Some(json!({ "name": "Household" })),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(ledger["name"], "Household");
+    let created = etag.expect("revision sent as ETag");
+
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        "/ledgers",
+        Some(json!({ "name": "household" })),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+
+    let (status, _, checking) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Household/accounts",
+        Some(json!({ "name": "Checking", "kind": "Bank" })),
+        Some(&created),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (_, _, groceries) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Household/accounts",
+        Some(json!({ "name": "Groceries", "kind": "ExpenseDestination" })),
+        None,
+    )
+    .await;
+
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Household/accounts",
+        Some(json!({ "name": "Savings", "kind": "Savings" })),
+        Some(&created),
+    )
+    .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED, "{body}");
+
+    let (status, _, _) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Household/transactions",
+        Some(json!({
+            "from_account": checking["id"],
+            "to_account": groceries["id"],
+            "date": "2025-03-05",
+            "amount": 42.5
+        })),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, _, summary) = send(
+        &app,
+        Method::GET,
+        "/ledgers/Household/summary?date=2025-03-20",
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(summary["window_start"], "2025-03-01");
+
+    let (status, _, accounts) =
+        send(&app, Method::GET, "/ledgers/Household/accounts", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(accounts.as_array().unwrap().len(), 2);
+
+    let (status, _, body) = send(&app, Method::GET, "/ledgers/Missing", None, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(
+        body["error"].as_str().unwrap().contains("Missing"),
+        "{body}"
+    );
+
+    let (status, _, _) = send(&app, Method::DELETE, "/ledgers/Household", None, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, _, list) = send(&app, Method::GET, "/ledgers", None, None).await;
+    assert_eq!(list, json!({ "ledgers": [] }));
+}
+
+#[tokio::test]
+async fn rejected_inputs_report_the_field_and_issue() {
+    let app = app();
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        "/ledgers",
+        Some(json!({ "name": "   " })),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    assert_eq!(
+        body["issue"],
+        json!({ "field": "ledger name", "issue": "empty" })
+    );
+
+    send(
+        &app,
+        Method::POST,
+        "/ledgers",
+        Some(json!({ "name": "Household" })),
+        None,
+    )
+    .await;
+    let (_, _, checking) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Household/accounts",
+        Some(json!({ "name": "Checking", "kind": "Bank" })),
+        None,
+    )
+    .await;
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Household/accounts",
+        Some(json!({ "name": " checking ", "kind": "Savings" })),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    assert_eq!(body["issue"]["issue"], "duplicate");
+
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Household/transactions",
+        Some(json!({
+            "from_account": checking["id"],
+            "to_account": checking["id"],
+            "date": "2025-03-05",
+            "amount": -5.0
+        })),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    assert_eq!(
+        body["issue"],
+        json!({ "field": "amount", "issue": "negative" })
+    );
+    assert!(body.get("error").is_some());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_changes_to_one_ledger_are_not_lost() {
+    let app = app();
+    send(
+        &app,
+        Method::POST,
+        "/ledgers",
+        Some(json!({ "name": "Shared" })),
+        None,
+    )
+    .await;
+
+    let requests = (0..24).map(|index| {
+        let app = app.clone();
+        tokio::spawn(async move {
+            send(
+                &app,
+                Method::POST,
+                "/ledgers/Shared/accounts",
+                Some(json!({ "name": format!("Account {index}"), "kind": "Cash" })),
+                None,
+            )
+            .await
+            .0
+        })
+    });
+    for request in requests.collect::<Vec<_>>() {
+        assert_eq!(request.await.unwrap(), StatusCode::CREATED);
+    }
+
+    let (_, _, accounts) = send(&app, Method::GET, "/ledgers/Shared/accounts", None, None).await;
+    assert_eq!(accounts.as_array().unwrap().len(), 24);
+}
+
+#[tokio::test]
+async fn simulations_are_created_previewed_and_discarded() {
+    let app = app();
+    send(
+        &app,
+        Method::POST,
+        "/ledgers",
+        Some(json!({ "name": "Plans" })),
+        None,
+    )
+    .await;
+
+    let (status, _, simulation) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Plans/simulations",
+        Some(json!({ "name": "New car", "notes": "lease" })),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(simulation["status"], "Pending");
+
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Plans/simulations",
+        Some(json!({ "name": "Bigger car", "parent": "Boat" })),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "{body}");
+
+    let (status, _, impact) = send(
+        &app,
+        Method::GET,
+        "/ledgers/Plans/simulations/New%20car/impact?date=2025-03-10",
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{impact}");
+
+    let (status, _, _) = send(
+        &app,
+        Method::DELETE,
+        "/ledgers/Plans/simulations/New%20car",
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, _, simulations) =
+        send(&app, Method::GET, "/ledgers/Plans/simulations", None, None).await;
+    assert_eq!(simulations.as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn replacing_checks_the_ledger_and_applying_backs_it_up() {
+    let storage = Arc::new(MemoryLedgerStorage::new());
+    let app = router(AppState::new(storage.clone()));
+    let (_, _, mut ledger) = send(
+        &app,
+        Method::POST,
+        "/ledgers",
+        Some(json!({ "name": "Plans" })),
+        None,
+    )
+    .await;
+
+    ledger["transactions"] = json!([{
+        "id": "6f1d8a4e-0000-4000-8000-000000000001",
+        "from_account": "6f1d8a4e-0000-4000-8000-0000000000aa",
+        "to_account": "6f1d8a4e-0000-4000-8000-0000000000bb",
+        "scheduled_date": "2025-03-10",
+        "budgeted_amount": 10.0,
+        "status": "Planned"
+    }]);
+    let (status, _, body) = send(
+        &app,
+        Method::PUT,
+        "/ledgers/Plans",
+        Some(ledger.clone()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    assert!(body.to_string().contains("does not have"), "{body}");
+
+    ledger["transactions"] = json!([]);
+    ledger["name"] = json!("  ");
+    let (status, _, _) = send(
+        &app,
+        Method::PUT,
+        "/ledgers/Plans",
+        Some(ledger.clone()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    ledger["name"] = json!("Plans");
+    let (status, _, body) = send(&app, Method::PUT, "/ledgers/Plans", Some(ledger), None).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    send(
+        &app,
+        Method::POST,
+        "/ledgers/Plans/simulations",
+        Some(json!({ "name": "New car" })),
+        None,
+    )
+    .await;
+    let (status, _, body) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Plans/simulations/New%20car/apply",
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let backups = storage.list_backups("Plans").unwrap();
+    let pre_apply: Vec<_> = backups
+        .iter()
+        .filter(|backup| backup.id.contains("pre-apply"))
+        .collect();
+    assert_eq!(pre_apply.len(), 1);
+    assert_eq!(body["backup"], pre_apply[0].id.as_str());
+
+    let (status, _, _) = send(
+        &app,
+        Method::POST,
+        "/ledgers/Plans/simulations/Boat/apply",
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(storage.list_backups("Plans").unwrap().len(), backups.len());
+}
@@ -0,0 +1,139 @@
+//! Ledgers compiled into the binary and served read-only.
+//!
+//! The sample ledger is stored with dates around March 2025. On load every
+//! date is moved forward by whole months so that the sample's "current"
+//! month is the caller's, keeping its budgets and upcoming items relevant.
+
+use std::path::Path;
+
+use bufy_core::{
+    storage::{LedgerBackupInfo, LedgerStorage},
+    CoreError,
+};
+use bufy_domain::Ledger;
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use serde_json::Value;
+
+/// Name under which [`EmbeddedLedgerStorage`] serves the sample ledger.
+pub const SAMPLE_LEDGER_NAME: &str = "sample";
+
+const SAMPLE_LEDGER_JSON: &str = include_str!("../assets/sample_ledger.json");
+/// Year and month the sample's dates treat as "now".
+const SAMPLE_ANCHOR: (i32, u32) = (2025, 3);
+
+/// Read-only storage over the ledgers embedded in the binary. Loads always
+/// succeed for known names; anything that would write is refused.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbeddedLedgerStorage {
+    today: Option<NaiveDate>,
+}
+
+impl EmbeddedLedgerStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shifts sample dates relative to `today` instead of the system date.
+    pub fn anchored_at(today: NaiveDate) -> Self {
+        Self { today: Some(today) }
+    }
+
+    fn today(&self) -> NaiveDate {
+        self.today.unwrap_or_else(|| Utc::now().date_naive())
+    }
+}
+
+/// The sample ledger with its dates moved into the month of `today`.
+pub fn sample_ledger(today: NaiveDate) -> Result<Ledger, CoreError> {
+    let mut value: Value = serde_json::from_str(SAMPLE_LEDGER_JSON)
+        .map_err(|err| CoreError::Serde(err.to_string()))?;
+    let (year, month) = SAMPLE_ANCHOR;
+    let months = (today.year() - year) * 12 + today.month() as i32 - month as i32;
+    shift_dates(&mut value, months);
+    serde_json::from_value(value).map_err(|err| CoreError::Serde(err.to_string()))
+}
+
+/// Moves every date and timestamp string in `value` by `months`.
+fn shift_dates(value: &mut Value, months: i32) {
+    match value {
+        Value::String(text) => {
+            if let Some(shifted) = shift_date_text(text, months) {
+                *text = shifted;
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| shift_dates(item, months)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| shift_dates(field, months)),
+        _ => {}
+    }
+}
+
+fn shift_date_text(text: &str, months: i32) -> Option<String> {
+    let step = Months::new(months.unsigned_abs());
+    let shift = |date: NaiveDate| {
+        if months >= 0 {
+            date.checked_add_months(step)
+        } else {
+            date.checked_sub_months(step)
+        }
+    };
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return shift(date).map(|date| date.format("%Y-%m-%d").to_string());
+    }
+    let stamp = DateTime::parse_from_rfc3339(text).ok()?;
+    let date = shift(stamp.date_naive())?;
+    let shifted = date.and_time(stamp.time()).and_utc();
+    Some(shifted.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+}
+
+fn read_only() -> CoreError {
+    CoreError::InvalidOperation("embedded ledgers are read-only".into())
+}
+
+impl LedgerStorage for EmbeddedLedgerStorage {
+    fn save_ledger(&self, _name: &str, _ledger: &Ledger) -> Result<(), CoreError> {
+        Err(read_only())
+    }
+
+    fn load_ledger(&self, name: &str) -> Result<Ledger, CoreError> {
+        if name.trim().eq_ignore_ascii_case(SAMPLE_LEDGER_NAME) {
+            sample_ledger(self.today())
+        } else {
+            Err(CoreError::LedgerNotFound(name.to_string()))
+        }
+    }
+
+    fn list_ledgers(&self) -> Result<Vec<String>, CoreError> {
+        Ok(vec![SAMPLE_LEDGER_NAME.to_string()])
+    }
+
+    fn delete_ledger(&self, _name: &str) -> Result<(), CoreError> {
+        Err(read_only())
+    }
+
+    fn save_ledger_to_path(&self, _ledger: &Ledger, _path: &Path) -> Result<(), CoreError> {
+        Err(read_only())
+    }
+
+    fn load_ledger_from_path(&self, path: &Path) -> Result<Ledger, CoreError> {
+        Err(CoreError::LedgerNotFound(path.display().to_string()))
+    }
+
+    fn backup_ledger(
+        &self,
+        _name: &str,
+        _ledger: &Ledger,
+        _note: Option<&str>,
+    ) -> Result<LedgerBackupInfo, CoreError> {
+        Err(read_only())
+    }
+
+    fn list_backups(&self, _name: &str) -> Result<Vec<LedgerBackupInfo>, CoreError> {
+        Ok(Vec::new())
+    }
+
+    fn restore_backup(&self, _backup: &LedgerBackupInfo) -> Result<Ledger, CoreError> {
+        Err(read_only())
+    }
+}
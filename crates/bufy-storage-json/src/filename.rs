@@ -0,0 +1,168 @@
+//! File names derived from ledger names and backup notes.
+//!
+//! Every name written by the JSON storage goes through this module so that a
+//! ledger directory copied between Windows, macOS, and Linux stays readable
+//! on all three:
+//!
+//! * slugs use lowercase ASCII only, so case-insensitive file systems never
+//!   see two names that differ by case alone;
+//! * slugs and notes have a byte budget well under the 255-byte `NAME_MAX`,
+//!   and backup notes shrink further to keep the whole path within the
+//!   260-character Windows `MAX_PATH`;
+//! * names that sanitizing changed beyond lowercasing, names too long for
+//!   the budget, and names with nothing left after sanitizing end in a hash
+//!   of the original name, so `a/b` and `a?b` get distinct files. The `-`
+//!   before it never occurs in a plain slug, so the two kinds cannot
+//!   collide. A slug passed back in, such as a file stem, maps to
+//!   itself;
+//! * device names Windows reserves (`con`, `nul`, `com1`, ...) get a `_`
+//!   appended.
+
+use std::path::Path;
+
+/// Longest slug, in bytes, including a hash suffix.
+pub(crate) const MAX_SLUG_BYTES: usize = 64;
+/// Longest backup note, in bytes, before the path budget is applied.
+pub(crate) const MAX_NOTE_BYTES: usize = 40;
+/// Windows `MAX_PATH`, applied on every platform.
+pub(crate) const MAX_PATH_CHARS: usize = 260;
+
+const HASH_HEX_LEN: usize = 16;
+const EMPTY_SLUG: &str = "ledger";
+const RESERVED_NAMES: [&str; 22] = [
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+    "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+/// The slug used for a ledger's file, backup directory, change feed, and
+/// audit log.
+pub fn canonical_name(name: &str) -> String {
+    if is_hashed_slug(name) {
+        return name.to_string();
+    }
+    let sanitized = sanitize_slug(name);
+    let slug = if sanitized.trim_matches('_').is_empty() {
+        if name.trim().is_empty() {
+            EMPTY_SLUG.into()
+        } else {
+            hashed(EMPTY_SLUG, name)
+        }
+    } else if sanitized.len() > MAX_SLUG_BYTES || sanitized != name.trim().to_lowercase() {
+        hashed(&sanitized, name)
+    } else {
+        sanitized
+    };
+    avoid_reserved(slug)
+}
+
+/// The slug written before names were budgeted and hashed, still looked up
+/// so that such files load and are renamed on their next save.
+pub(crate) fn legacy_canonical_name(name: &str) -> String {
+    let sanitized = sanitize_slug(name);
+    if sanitized.trim_matches('_').is_empty() {
+        EMPTY_SLUG.into()
+    } else {
+        sanitized
+    }
+}
+
+/// The note part of a backup file name: lowercase words joined by `-`,
+/// cut to [`MAX_NOTE_BYTES`]. `None` when nothing printable remains.
+pub(crate) fn sanitize_backup_note(note: Option<&str>) -> Option<String> {
+    let raw = note?.trim();
+    if raw.is_empty() {
+        return None;
+    }
+    let mut sanitized = String::new();
+    let mut last_dash = false;
+    for ch in raw.chars() {
+        if ch.is_ascii_alphanumeric() {
+            sanitized.push(ch.to_ascii_lowercase());
+            last_dash = false;
+        } else if (ch.is_whitespace() || matches!(ch, '-' | '.'))
+            && !sanitized.is_empty()
+            && !last_dash
+        {
+            sanitized.push('-');
+            last_dash = true;
+        }
+    }
+    trim_note(&sanitized, MAX_NOTE_BYTES)
+}
+
+/// Shortens `note` so that `dir/<stem>_<note>.<extension>` stays within
+/// [`MAX_PATH_CHARS`]. Drops the note when even one character would not
+/// fit; the rest of the name is never shortened.
+pub(crate) fn fit_backup_note(
+    dir: &Path,
+    stem: &str,
+    note: Option<String>,
+    extension: &str,
+) -> Option<String> {
+    let note = note?;
+    // Separator after the directory, `_` before the note, `.` before the extension.
+    let used = dir.to_string_lossy().chars().count() + stem.len() + extension.len() + 3;
+    trim_note(&note, MAX_PATH_CHARS.saturating_sub(used))
+}
+
+fn sanitize_slug(name: &str) -> String {
+    name.trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+fn trim_note(note: &str, budget: usize) -> Option<String> {
+    // Notes are ASCII, so any byte index is a character boundary.
+    let cut = &note[..note.len().min(budget)];
+    let trimmed = cut.trim_matches('-');
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// `prefix` cut to leave room for `-<hash>`, where the hash covers the
+/// lowercased original name so that names differing only in the part that
+/// was cut or replaced still get distinct slugs.
+fn hashed(prefix: &str, name: &str) -> String {
+    let keep = MAX_SLUG_BYTES - HASH_HEX_LEN - 1;
+    let prefix = prefix[..prefix.len().min(keep)].trim_end_matches('_');
+    format!(
+        "{}-{:0width$x}",
+        prefix,
+        fnv1a(name.trim().to_lowercase().as_bytes()),
+        width = HASH_HEX_LEN
+    )
+}
+
+/// Whether `name` already has the `<slug>-<hash>` shape [`hashed`] writes.
+fn is_hashed_slug(name: &str) -> bool {
+    let Some((prefix, hash)) = name.split_once('-') else {
+        return false;
+    };
+    name.len() <= MAX_SLUG_BYTES
+        && !prefix.is_empty()
+        && prefix
+            .chars()
+            .all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_'))
+        && hash.len() == HASH_HEX_LEN
+        && hash.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+}
+
+fn avoid_reserved(slug: String) -> String {
+    if RESERVED_NAMES.contains(&slug.as_str()) {
+        format!("{}_", slug)
+    } else {
+        slug
+    }
+}
+
+/// 64-bit FNV-1a. Stable across platforms and releases, unlike the
+/// standard library's hasher, which file names depend on.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,151 @@
+//! Cached ledger metadata so listings avoid loading every ledger file.
+//!
+//! The index lives next to the ledgers and maps each slug to a snapshot of
+//! its metadata plus the size and modification time of the file it was taken
+//! from. An entry is reused only while the file is unchanged and the snapshot
+//! was taken on the current day, since period totals depend on the date.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use bufy_domain::LedgerBudgetPeriod;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::LedgerMetadata;
+
+pub(crate) const INDEX_FILE_NAME: &str = ".ledger-index";
+const INDEX_VERSION: u32 = 1;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct LedgerIndex {
+    version: u32,
+    entries: BTreeMap<String, IndexEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    file_name: String,
+    file_size: u64,
+    file_modified: Option<DateTime<Utc>>,
+    indexed_at: DateTime<Utc>,
+    name: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    budget_period: LedgerBudgetPeriod,
+    account_count: usize,
+    category_count: usize,
+    transaction_count: usize,
+    simulation_count: usize,
+    total_budgeted: f64,
+    total_available: f64,
+}
+
+/// Size and modification time identifying one version of a ledger file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FileStamp {
+    size: u64,
+    modified: Option<DateTime<Utc>>,
+}
+
+impl FileStamp {
+    pub(crate) fn of(path: &Path) -> Option<Self> {
+        let meta = fs::metadata(path).ok()?;
+        Some(Self {
+            size: meta.len(),
+            modified: meta.modified().ok().map(DateTime::<Utc>::from),
+        })
+    }
+}
+
+impl LedgerIndex {
+    /// Reads the index, starting afresh when it is missing, unreadable, or
+    /// written by a different index version.
+    pub(crate) fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|data| serde_json::from_str::<Self>(&data).ok())
+            .filter(|index| index.version == INDEX_VERSION)
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&Self {
+            version: INDEX_VERSION,
+            entries: self.entries.clone(),
+        })
+    }
+
+    /// Returns the cached metadata for `slug` when it still describes the file
+    /// at `path`.
+    pub(crate) fn fresh(
+        &self,
+        slug: &str,
+        path: &Path,
+        now: DateTime<Utc>,
+    ) -> Option<LedgerMetadata> {
+        let entry = self.entries.get(slug)?;
+        let stamp = FileStamp::of(path)?;
+        let file_name = path.file_name()?.to_str()?;
+        let unchanged = entry.file_name == file_name
+            && entry.file_size == stamp.size
+            && entry.file_modified == stamp.modified;
+        let same_day = entry.indexed_at.date_naive() == now.date_naive();
+        (unchanged && same_day).then(|| LedgerMetadata {
+            slug: slug.to_string(),
+            name: entry.name.clone(),
+            path: path.to_path_buf(),
+            created_at: entry.created_at,
+            updated_at: entry.updated_at,
+            budget_period: entry.budget_period.clone(),
+            account_count: entry.account_count,
+            category_count: entry.category_count,
+            transaction_count: entry.transaction_count,
+            simulation_count: entry.simulation_count,
+            total_budgeted: entry.total_budgeted,
+            total_available: entry.total_available,
+            indexed_at: entry.indexed_at,
+            from_index: true,
+        })
+    }
+
+    /// Records `meta` as the snapshot of the file identified by `stamp`.
+    pub(crate) fn record(&mut self, meta: &LedgerMetadata, stamp: FileStamp) {
+        let file_name = meta
+            .path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default()
+            .to_string();
+        self.entries.insert(
+            meta.slug.clone(),
+            IndexEntry {
+                file_name,
+                file_size: stamp.size,
+                file_modified: stamp.modified,
+                indexed_at: meta.indexed_at,
+                name: meta.name.clone(),
+                created_at: meta.created_at,
+                updated_at: meta.updated_at,
+                budget_period: meta.budget_period.clone(),
+                account_count: meta.account_count,
+                category_count: meta.category_count,
+                transaction_count: meta.transaction_count,
+                simulation_count: meta.simulation_count,
+                total_budgeted: meta.total_budgeted,
+                total_available: meta.total_available,
+            },
+        );
+    }
+
+    /// Drops entries whose ledgers no longer exist. Returns `true` when any
+    /// entry was removed.
+    pub(crate) fn retain(&mut self, slugs: &[String]) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|slug, _| slugs.contains(slug));
+        self.entries.len() != before
+    }
+
+    pub(crate) fn remove(&mut self, slug: &str) -> bool {
+        self.entries.remove(slug).is_some()
+    }
+}
@@ -2,25 +2,43 @@ use std::{
     cmp::Reverse,
     collections::BTreeSet,
     fs::{self, File},
-    io::Write,
+    io::{self, BufRead, BufReader, BufWriter, Write},
     path::{Path, PathBuf},
+    time::SystemTime,
 };
 
 use bufy_core::{
+    check_schema_version,
     storage::{LedgerBackupInfo, LedgerStorage},
-    BudgetService, Clock, CoreError,
+    AuditChange, AuditEntry, BudgetService, CoreError, LedgerChange, LedgerEvent, WriteStage,
 };
 use bufy_domain::{Ledger, LedgerBudgetPeriod};
 use chrono::{DateTime, NaiveDateTime, Utc};
+use uuid::Uuid;
+
+mod embedded;
+mod filename;
+mod index;
+mod memory;
+
+pub use embedded::{sample_ledger, EmbeddedLedgerStorage, SAMPLE_LEDGER_NAME};
+pub use filename::canonical_name;
+use filename::{fit_backup_note, legacy_canonical_name, sanitize_backup_note};
+use index::{FileStamp, LedgerIndex, INDEX_FILE_NAME};
+pub use memory::MemoryLedgerStorage;
 
 const LEDGER_EXTENSION: &str = "bfy";
 const BACKUP_EXTENSION: &str = "bbfy";
 const LEGACY_EXTENSION: &str = "json";
 const BACKUP_SUFFIX: &str = ".bbfy";
 const LEGACY_SUFFIX: &str = ".json";
-const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M";
+const CHANGE_FEED_SUFFIX: &str = ".changes.jsonl";
+const AUDIT_LOG_SUFFIX: &str = ".audit.jsonl";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+const RANDOM_SUFFIX_LEN: usize = 4;
 const TMP_SUFFIX: &str = "tmp";
 const DEFAULT_RETENTION: usize = 5;
+const WRITE_BUFFER_BYTES: usize = 64 * 1024;
 
 /// Filesystem-backed JSON persistence for ledgers and their backups.
 #[derive(Clone)]
@@ -55,33 +73,52 @@ impl JsonLedgerStorage {
             .join(format!("{}.{}", canonical_name(name), LEDGER_EXTENSION))
     }
 
+    /// Change feed written next to the ledger file, one JSON event per line.
+    pub fn change_feed_path(&self, name: &str) -> PathBuf {
+        self.paths
+            .ledger_root
+            .join(format!("{}{}", canonical_name(name), CHANGE_FEED_SUFFIX))
+    }
+
+    /// Audit log written next to the ledger file, one JSON entry per line.
+    pub fn audit_log_path(&self, name: &str) -> PathBuf {
+        self.paths
+            .ledger_root
+            .join(format!("{}{}", canonical_name(name), AUDIT_LOG_SUFFIX))
+    }
+
     pub fn backup_path(&self, name: &str, backup: &str) -> PathBuf {
         self.backup_dir_for_ledger(name).join(backup)
     }
 
+    /// Lists metadata for every stored ledger. Snapshots from the ledger index
+    /// are reused while their files are unchanged; other ledgers are loaded,
+    /// summarized, and written back to the index.
     pub fn list_ledger_metadata(&self) -> Result<Vec<LedgerMetadata>, CoreError> {
+        let now = Utc::now();
+        let slugs = self.list_ledgers()?;
+        let mut index = LedgerIndex::load(&self.index_path());
+        let mut dirty = index.retain(&slugs);
         let mut entries = Vec::new();
-        let clock = StorageClock;
-        for slug in self.list_ledgers()? {
-            let ledger = self.load_ledger(&slug)?;
-            let summary = BudgetService::summarize_current_period(&ledger, &clock);
+        for slug in &slugs {
             let path = self
-                .resolve_ledger_path(&slug)
-                .unwrap_or_else(|_| self.ledger_path(&slug));
-            entries.push(LedgerMetadata {
-                slug: slug.clone(),
-                name: ledger.name.clone(),
-                path,
-                created_at: ledger.created_at,
-                updated_at: ledger.updated_at,
-                budget_period: ledger.budget_period.clone(),
-                account_count: ledger.accounts.len(),
-                category_count: ledger.categories.len(),
-                transaction_count: ledger.transactions.len(),
-                simulation_count: ledger.simulations.len(),
-                total_budgeted: summary.totals.budgeted,
-                total_available: summary.totals.remaining,
-            });
+                .resolve_ledger_path(slug)
+                .unwrap_or_else(|_| self.ledger_path(slug));
+            if let Some(meta) = index.fresh(slug, &path, now) {
+                entries.push(meta);
+                continue;
+            }
+            let ledger = load_ledger_from_path(&path)?;
+            let meta = ledger_metadata(slug, &path, &ledger, now);
+            if let Some(stamp) = FileStamp::of(&path) {
+                index.record(&meta, stamp);
+                dirty = true;
+            }
+            entries.push(meta);
+        }
+        if dirty {
+            // The index is only a cache; failing to persist it must not fail the listing.
+            let _ = self.write_index(&index);
         }
         entries.sort_by(|a, b| a.name.cmp(&b.name));
         Ok(entries)
@@ -126,22 +163,86 @@ impl JsonLedgerStorage {
         Ok(())
     }
 
+    fn index_path(&self) -> PathBuf {
+        self.paths.ledger_root.join(INDEX_FILE_NAME)
+    }
+
+    fn write_index(&self, index: &LedgerIndex) -> Result<(), CoreError> {
+        let json = index
+            .to_json()
+            .map_err(|err| CoreError::Serde(err.to_string()))?;
+        replace_atomic(&self.index_path(), |tmp| {
+            fs::write(tmp, json).map_err(|err| atomic_error(tmp, WriteStage::Write, err))
+        })
+    }
+
+    /// Refreshes the index entry for a ledger that was just written to `path`.
+    fn update_index(&self, slug: &str, path: &Path, ledger: &Ledger) -> Result<(), CoreError> {
+        let Some(stamp) = FileStamp::of(path) else {
+            return Ok(());
+        };
+        let mut index = LedgerIndex::load(&self.index_path());
+        index.record(&ledger_metadata(slug, path, ledger, Utc::now()), stamp);
+        self.write_index(&index)
+    }
+
     fn backup_dir_for_ledger(&self, name: &str) -> PathBuf {
         self.paths
             .backup_root
             .join(format!("{}-backups", canonical_name(name)))
     }
 
+    /// Paths a ledger may be stored under, preferred first: the current
+    /// slug, then the unbudgeted slug of older releases, each as `.bfy` and
+    /// legacy `.json`.
     fn ledger_file_candidates(&self, name: &str) -> Vec<PathBuf> {
-        let slug = canonical_name(name);
-        vec![
-            self.paths
-                .ledger_root
-                .join(format!("{}.{}", slug, LEDGER_EXTENSION)),
-            self.paths
-                .ledger_root
-                .join(format!("{}.{}", slug, LEGACY_EXTENSION)),
+        let mut slugs = vec![canonical_name(name)];
+        let legacy = legacy_canonical_name(name);
+        if legacy != slugs[0] {
+            slugs.push(legacy);
+        }
+        slugs
+            .iter()
+            .flat_map(|slug| {
+                [LEDGER_EXTENSION, LEGACY_EXTENSION]
+                    .map(|ext| self.paths.ledger_root.join(format!("{}.{}", slug, ext)))
+            })
+            .collect()
+    }
+
+    /// Moves files stored under the slug older releases derived for `name`
+    /// to its current slug: the ledger, its change feed and audit log, and
+    /// the backup directory. Files already at the current slug win.
+    fn rename_legacy_files(&self, name: &str) -> Result<(), CoreError> {
+        let (slug, legacy) = (canonical_name(name), legacy_canonical_name(name));
+        if slug == legacy {
+            return Ok(());
+        }
+        let root = &self.paths.ledger_root;
+        let mut moves: Vec<(PathBuf, PathBuf)> = [
+            format!(".{}", LEDGER_EXTENSION),
+            format!(".{}", LEGACY_EXTENSION),
+            CHANGE_FEED_SUFFIX.to_string(),
+            AUDIT_LOG_SUFFIX.to_string(),
         ]
+        .iter()
+        .map(|suffix| {
+            (
+                root.join(format!("{}{}", legacy, suffix)),
+                root.join(format!("{}{}", slug, suffix)),
+            )
+        })
+        .collect();
+        moves.push((
+            self.paths.backup_root.join(format!("{}-backups", legacy)),
+            self.backup_dir_for_ledger(name),
+        ));
+        for (from, to) in moves {
+            if from.exists() && !to.exists() {
+                fs::rename(&from, &to)?;
+            }
+        }
+        Ok(())
     }
 
     fn find_existing_ledger_path(&self, name: &str) -> Option<PathBuf> {
@@ -161,21 +262,12 @@ impl JsonLedgerStorage {
         name: &str,
         note: Option<&str>,
     ) -> Result<LedgerBackupInfo, CoreError> {
-        let dir = self.backup_dir_for_ledger(name);
-        fs::create_dir_all(&dir)?;
-        let timestamp = Utc::now().format(BACKUP_TIMESTAMP_FORMAT).to_string();
-        let mut stem = format!("{}_{}", canonical_name(name), timestamp);
-        if let Some(label) = sanitize_backup_note(note) {
-            stem.push('_');
-            stem.push_str(&label);
-        }
-        let file_name = format!("{}.{}", stem, BACKUP_EXTENSION);
-        let path = dir.join(&file_name);
-        write_atomic(&path, &serialize_ledger(ledger)?)?;
+        let (timestamp, path) = self.next_backup_path(name, note)?;
+        write_ledger_atomic(&path, ledger)?;
         self.prune_backups(name)?;
         Ok(LedgerBackupInfo {
             ledger: canonical_name(name),
-            id: file_name.clone(),
+            id: file_name_of(&path),
             created_at: timestamp,
             path,
         })
@@ -185,24 +277,47 @@ impl JsonLedgerStorage {
         if !path.exists() {
             return Ok(());
         }
+        let (_, backup_path) = self.next_backup_path(name, None)?;
+        copy_atomic(path, &backup_path)?;
+        self.prune_backups(name)?;
+        Ok(())
+    }
+
+    /// Picks an unused backup path named
+    /// `<slug>_<YYYYMMDD>_<HHMMSS>-<suffix>[_<note>].bbfy`. The random suffix
+    /// keeps backups taken within the same second apart; the note is
+    /// shortened when the path would otherwise exceed the Windows limit.
+    fn next_backup_path(
+        &self,
+        name: &str,
+        note: Option<&str>,
+    ) -> Result<(String, PathBuf), CoreError> {
         let dir = self.backup_dir_for_ledger(name);
         fs::create_dir_all(&dir)?;
         let timestamp = Utc::now().format(BACKUP_TIMESTAMP_FORMAT).to_string();
-        let file_name = format!(
-            "{}_{}.{}",
-            canonical_name(name),
-            timestamp,
-            BACKUP_EXTENSION
-        );
-        let backup_path = dir.join(&file_name);
-        fs::copy(path, &backup_path)?;
-        self.prune_backups(name)?;
-        Ok(())
+        let note = sanitize_backup_note(note);
+        loop {
+            let suffix = Uuid::new_v4().simple().to_string();
+            let mut stem = format!(
+                "{}_{}-{}",
+                canonical_name(name),
+                timestamp,
+                &suffix[..RANDOM_SUFFIX_LEN]
+            );
+            if let Some(label) = fit_backup_note(&dir, &stem, note.clone(), BACKUP_EXTENSION) {
+                stem.push('_');
+                stem.push_str(&label);
+            }
+            let path = dir.join(format!("{}.{}", stem, BACKUP_EXTENSION));
+            if !path.exists() {
+                return Ok((timestamp, path));
+            }
+        }
     }
 
     fn prune_backups(&self, name: &str) -> Result<(), CoreError> {
         let mut entries = self.list_backups(name)?;
-        entries.sort_by_key(|info| Reverse(parse_backup_timestamp(&info.id)));
+        entries.sort_by_key(|info| Reverse(backup_sort_key(info)));
         for entry in entries.into_iter().skip(self.retention) {
             let _ = fs::remove_file(entry.path);
         }
@@ -210,17 +325,9 @@ impl JsonLedgerStorage {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-struct StorageClock;
-
-impl Clock for StorageClock {
-    fn now(&self) -> chrono::DateTime<Utc> {
-        Utc::now()
-    }
-}
-
 impl LedgerStorage for JsonLedgerStorage {
     fn save_ledger(&self, name: &str, ledger: &Ledger) -> Result<(), CoreError> {
+        self.rename_legacy_files(name)?;
         let path = self.ledger_path(name);
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
@@ -228,9 +335,8 @@ impl LedgerStorage for JsonLedgerStorage {
         if let Some(existing) = self.find_existing_ledger_path(name) {
             self.backup_existing_file(name, &existing)?;
         }
-        let tmp = tmp_path(&path);
-        write_atomic(&tmp, &serialize_ledger(ledger)?)?;
-        fs::rename(&tmp, &path)?;
+        write_ledger_atomic(&path, ledger)?;
+        let _ = self.update_index(&canonical_name(name), &path, ledger);
         Ok(())
     }
 
@@ -262,11 +368,19 @@ impl LedgerStorage for JsonLedgerStorage {
     }
 
     fn delete_ledger(&self, name: &str) -> Result<(), CoreError> {
-        for path in self.ledger_file_candidates(name) {
+        for path in self
+            .ledger_file_candidates(name)
+            .into_iter()
+            .chain([self.change_feed_path(name), self.audit_log_path(name)])
+        {
             if path.exists() {
                 fs::remove_file(path)?;
             }
         }
+        let mut index = LedgerIndex::load(&self.index_path());
+        if index.remove(&canonical_name(name)) {
+            let _ = self.write_index(&index);
+        }
         Ok(())
     }
 
@@ -313,7 +427,7 @@ impl LedgerStorage for JsonLedgerStorage {
                 });
             }
         }
-        entries.sort_by_key(|info| Reverse(parse_backup_timestamp(&info.id)));
+        entries.sort_by_key(|info| Reverse(backup_sort_key(info)));
         Ok(entries)
     }
 
@@ -328,9 +442,182 @@ impl LedgerStorage for JsonLedgerStorage {
         if let Some(parent) = target.parent() {
             fs::create_dir_all(parent)?;
         }
-        fs::copy(&backup.path, &target)?;
+        copy_atomic(&backup.path, &target)?;
         load_ledger_from_path(&target)
     }
+
+    fn change_feed_head(&self, name: &str) -> Result<Option<u64>, CoreError> {
+        Ok(read_lines::<LedgerEvent>(&self.change_feed_path(name))?
+            .last()
+            .map(|event| event.seq))
+    }
+
+    fn append_changes(
+        &self,
+        name: &str,
+        changes: Vec<LedgerChange>,
+    ) -> Result<Vec<LedgerEvent>, CoreError> {
+        if changes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let path = self.change_feed_path(name);
+        let head = self.change_feed_head(name)?.unwrap_or(0);
+        let recorded_at = Utc::now();
+        let events: Vec<LedgerEvent> = changes
+            .into_iter()
+            .zip(head + 1..)
+            .map(|(change, seq)| LedgerEvent {
+                seq,
+                recorded_at,
+                change,
+            })
+            .collect();
+        append_lines(&path, &events)?;
+        Ok(events)
+    }
+
+    fn read_changes(&self, name: &str, after: u64) -> Result<Vec<LedgerEvent>, CoreError> {
+        let mut events: Vec<LedgerEvent> = read_lines(&self.change_feed_path(name))?;
+        events.retain(|event| event.seq > after);
+        Ok(events)
+    }
+
+    fn compact_changes(&self, name: &str, ledger: &Ledger) -> Result<LedgerEvent, CoreError> {
+        let path = self.change_feed_path(name);
+        let seq = self.change_feed_head(name)?.unwrap_or(0) + 1;
+        let ledger =
+            serde_json::to_value(ledger).map_err(|err| CoreError::Serde(err.to_string()))?;
+        let event = LedgerEvent {
+            seq,
+            recorded_at: Utc::now(),
+            change: LedgerChange::Snapshot { ledger },
+        };
+        let mut line =
+            serde_json::to_vec(&event).map_err(|err| CoreError::Serde(err.to_string()))?;
+        line.push(b'\n');
+        replace_atomic(&path, |tmp| {
+            fs::write(tmp, &line).map_err(|err| atomic_error(tmp, WriteStage::Write, err))?;
+            File::open(tmp)
+                .and_then(|file| file.sync_all())
+                .map_err(|err| atomic_error(tmp, WriteStage::SyncFile, err))
+        })?;
+        Ok(event)
+    }
+
+    fn append_audit(
+        &self,
+        name: &str,
+        actor: &str,
+        changes: Vec<AuditChange>,
+    ) -> Result<Vec<AuditEntry>, CoreError> {
+        if changes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let path = self.audit_log_path(name);
+        let head = read_lines::<AuditEntry>(&path)?
+            .last()
+            .map_or(0, |entry| entry.seq);
+        let recorded_at = Utc::now();
+        let entries: Vec<AuditEntry> = changes
+            .into_iter()
+            .zip(head + 1..)
+            .map(|(change, seq)| AuditEntry {
+                seq,
+                recorded_at,
+                actor: actor.to_string(),
+                change,
+            })
+            .collect();
+        append_lines(&path, &entries)?;
+        Ok(entries)
+    }
+
+    fn read_audit(&self, name: &str) -> Result<Vec<AuditEntry>, CoreError> {
+        read_lines(&self.audit_log_path(name))
+    }
+
+    fn purge_audit(&self, name: &str, before: DateTime<Utc>) -> Result<usize, CoreError> {
+        let path = self.audit_log_path(name);
+        let entries = read_lines::<AuditEntry>(&path)?;
+        let total = entries.len();
+        let kept: Vec<AuditEntry> = entries
+            .into_iter()
+            .filter(|entry| entry.recorded_at >= before)
+            .collect();
+        let removed = total - kept.len();
+        if removed == 0 {
+            return Ok(0);
+        }
+        let mut data = Vec::new();
+        for entry in &kept {
+            serde_json::to_writer(&mut data, entry)
+                .map_err(|err| CoreError::Serde(err.to_string()))?;
+            data.push(b'\n');
+        }
+        replace_atomic(&path, |tmp| {
+            fs::write(tmp, &data).map_err(|err| atomic_error(tmp, WriteStage::Write, err))?;
+            File::open(tmp)
+                .and_then(|file| file.sync_all())
+                .map_err(|err| atomic_error(tmp, WriteStage::SyncFile, err))
+        })?;
+        Ok(removed)
+    }
+
+    fn rewrite_backup(&self, backup: &LedgerBackupInfo, ledger: &Ledger) -> Result<(), CoreError> {
+        save_ledger_to_path(ledger, &backup.path)
+    }
+}
+
+/// Appends one JSON value per line to a change feed or audit log and syncs
+/// the file.
+fn append_lines<T: serde::Serialize>(path: &Path, items: &[T]) -> Result<(), CoreError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let mut writer = BufWriter::with_capacity(WRITE_BUFFER_BYTES, file);
+    for item in items {
+        serde_json::to_writer(&mut writer, item)
+            .map_err(|err| CoreError::Serde(err.to_string()))?;
+        writer.write_all(b"\n")?;
+    }
+    let file = writer.into_inner().map_err(|err| err.into_error())?;
+    file.sync_data()?;
+    Ok(())
+}
+
+/// Reads every line of a change feed or audit log. A final line that does
+/// not parse is ignored, since another process may still be appending it.
+fn read_lines<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Vec<T>, CoreError> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let lines: Vec<String> = BufReader::new(file).lines().collect::<Result<_, _>>()?;
+    let last = lines.len().saturating_sub(1);
+    let mut events = Vec::with_capacity(lines.len());
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(line) {
+            Ok(event) => events.push(event),
+            Err(_) if index == last => break,
+            Err(err) => {
+                return Err(CoreError::Serde(format!(
+                    "{} line {}: {}",
+                    path.display(),
+                    index + 1,
+                    err
+                )))
+            }
+        }
+    }
+    Ok(events)
 }
 
 /// Saves a ledger to an arbitrary path on disk.
@@ -338,16 +625,25 @@ pub fn save_ledger_to_path(ledger: &Ledger, path: &Path) -> Result<(), CoreError
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
-    let tmp = tmp_path(path);
-    write_atomic(&tmp, &serialize_ledger(ledger)?)?;
-    fs::rename(&tmp, path)?;
-    Ok(())
+    write_ledger_atomic(path, ledger)
 }
 
-/// Loads a ledger from the provided filesystem path.
+/// Loads a ledger from the provided filesystem path. The schema version is
+/// checked first, so a file from a newer major schema is refused as such
+/// instead of failing on fields this build reads differently.
 pub fn load_ledger_from_path(path: &Path) -> Result<Ledger, CoreError> {
     let data = fs::read_to_string(path)?;
-    Ok(serde_json::from_str(&data).map_err(|err| CoreError::Serde(err.to_string()))?)
+    if let Ok(stamp) = serde_json::from_str::<SchemaStamp>(&data) {
+        check_schema_version(stamp.schema_version)?;
+    }
+    serde_json::from_str(&data).map_err(|err| CoreError::Serde(err.to_string()))
+}
+
+/// The schema fields of a stored ledger, read before the rest of it.
+#[derive(serde::Deserialize)]
+struct SchemaStamp {
+    #[serde(default)]
+    schema_version: u8,
 }
 
 #[derive(Debug, Clone)]
@@ -364,6 +660,30 @@ pub struct LedgerMetadata {
     pub simulation_count: usize,
     pub total_budgeted: f64,
     pub total_available: f64,
+    /// When the metadata snapshot was computed.
+    pub indexed_at: DateTime<Utc>,
+    /// `true` when served from the ledger index instead of loading the file.
+    pub from_index: bool,
+}
+
+fn ledger_metadata(slug: &str, path: &Path, ledger: &Ledger, now: DateTime<Utc>) -> LedgerMetadata {
+    let summary = BudgetService::summarize_period_containing(ledger, now.date_naive());
+    LedgerMetadata {
+        slug: slug.to_string(),
+        name: ledger.name.clone(),
+        path: path.to_path_buf(),
+        created_at: ledger.created_at,
+        updated_at: ledger.updated_at,
+        budget_period: ledger.budget_period.clone(),
+        account_count: ledger.accounts.len(),
+        category_count: ledger.categories.len(),
+        transaction_count: ledger.transactions.len(),
+        simulation_count: ledger.simulations.len(),
+        total_budgeted: summary.totals.budgeted,
+        total_available: summary.totals.remaining,
+        indexed_at: now,
+        from_index: false,
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -374,65 +694,46 @@ pub struct BackupMetadata {
     pub path: PathBuf,
 }
 
-fn canonical_name(name: &str) -> String {
-    let sanitized: String = name
-        .trim()
-        .to_lowercase()
-        .chars()
-        .map(|c| match c {
-            'a'..='z' | '0'..='9' => c,
-            _ => '_',
-        })
-        .collect();
-    if sanitized.trim_matches('_').is_empty() {
-        "ledger".into()
-    } else {
-        sanitized
-    }
+/// Extracts the creation time from a backup file name.
+///
+/// Understands current names (`<slug>_<YYYYMMDD>_<HHMMSS>-<suffix>[_<note>]`)
+/// as well as the older minute-resolution `<slug>_<YYYYMMDD>_<HHMM>[_<note>]`.
+pub fn parse_backup_timestamp(name: &str) -> Option<DateTime<Utc>> {
+    let trimmed = strip_backup_extension(name)?;
+    let segments = trimmed.split('_').collect::<Vec<_>>();
+    segments.windows(2).rev().find_map(|pair| {
+        let (date, time) = (pair[0], pair[1]);
+        if !is_digits(date, 8) {
+            return None;
+        }
+        let time = time.split('-').next().unwrap_or(time);
+        let format = if is_digits(time, 6) {
+            "%Y%m%d%H%M%S"
+        } else if is_digits(time, 4) {
+            "%Y%m%d%H%M"
+        } else {
+            return None;
+        };
+        NaiveDateTime::parse_from_str(&format!("{}{}", date, time), format)
+            .ok()
+            .map(|naive| DateTime::from_naive_utc_and_offset(naive, Utc))
+    })
 }
 
-fn sanitize_backup_note(note: Option<&str>) -> Option<String> {
-    let raw = note?.trim();
-    if raw.is_empty() {
-        return None;
-    }
-    let mut sanitized = String::new();
-    let mut last_dash = false;
-    for ch in raw.chars() {
-        if ch.is_ascii_alphanumeric() {
-            sanitized.push(ch.to_ascii_lowercase());
-            last_dash = false;
-        } else if (ch.is_whitespace() || matches!(ch, '-' | '.'))
-            && !sanitized.is_empty()
-            && !last_dash
-        {
-            sanitized.push('-');
-            last_dash = true;
-        }
-    }
-    let trimmed = sanitized.trim_matches('-').to_string();
-    if trimmed.is_empty() {
-        None
-    } else {
-        Some(trimmed)
-    }
+/// Orders backups by the timestamp in their name, falling back to the file's
+/// modification time to break ties between backups from the same minute.
+fn backup_sort_key(info: &LedgerBackupInfo) -> (Option<DateTime<Utc>>, Option<SystemTime>) {
+    let modified = fs::metadata(&info.path)
+        .and_then(|meta| meta.modified())
+        .ok();
+    (parse_backup_timestamp(&info.id), modified)
 }
 
-fn parse_backup_timestamp(name: &str) -> Option<DateTime<Utc>> {
-    let trimmed = strip_backup_extension(name)?;
-    let mut segments = trimmed.split('_').collect::<Vec<_>>();
-    if segments.len() < 2 {
-        return None;
-    }
-    let time = segments.pop().unwrap();
-    let date = segments.pop().unwrap();
-    if !is_digits(date, 8) || !is_digits(time, 4) {
-        return None;
-    }
-    let raw = format!("{}{}", date, time);
-    NaiveDateTime::parse_from_str(&raw, "%Y%m%d%H%M")
-        .ok()
-        .map(|naive| DateTime::from_naive_utc_and_offset(naive, Utc))
+fn file_name_of(path: &Path) -> String {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string()
 }
 
 fn is_digits(value: &str, len: usize) -> bool {
@@ -440,13 +741,8 @@ fn is_digits(value: &str, len: usize) -> bool {
 }
 
 fn strip_backup_extension(name: &str) -> Option<&str> {
-    if name.ends_with(BACKUP_SUFFIX) {
-        Some(&name[..name.len() - BACKUP_SUFFIX.len()])
-    } else if name.ends_with(LEGACY_SUFFIX) {
-        Some(&name[..name.len() - LEGACY_SUFFIX.len()])
-    } else {
-        None
-    }
+    name.strip_suffix(BACKUP_SUFFIX)
+        .or_else(|| name.strip_suffix(LEGACY_SUFFIX))
 }
 
 fn tmp_path(path: &Path) -> PathBuf {
@@ -459,16 +755,137 @@ fn tmp_path(path: &Path) -> PathBuf {
     tmp
 }
 
-fn write_atomic(path: &Path, data: &str) -> Result<(), CoreError> {
+/// Atomically replaces `path` with the serialized ledger.
+///
+/// The data is written to a sibling temporary file, which keeps it on the same
+/// volume as the destination, and synced before being moved into place. The
+/// parent directory is synced afterwards so the rename itself survives a crash.
+/// Readers therefore observe either the previous file or the complete new one.
+fn write_ledger_atomic(path: &Path, ledger: &Ledger) -> Result<(), CoreError> {
+    replace_atomic(path, |tmp| write_ledger_file(tmp, ledger))
+}
+
+/// Atomically replaces `target` with a copy of `source`.
+fn copy_atomic(source: &Path, target: &Path) -> Result<(), CoreError> {
+    replace_atomic(target, |tmp| {
+        fs::copy(source, tmp).map_err(|err| atomic_error(tmp, WriteStage::Write, err))?;
+        File::open(tmp)
+            .and_then(|file| file.sync_all())
+            .map_err(|err| atomic_error(tmp, WriteStage::SyncFile, err))
+    })
+}
+
+fn replace_atomic(
+    path: &Path,
+    write: impl FnOnce(&Path) -> Result<(), CoreError>,
+) -> Result<(), CoreError> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
-    let mut file = File::create(path)?;
-    file.write_all(data.as_bytes())?;
-    file.flush()?;
-    Ok(())
+    let tmp = tmp_path(path);
+    let result = write(&tmp)
+        .and_then(|()| {
+            replace_file(&tmp, path).map_err(|err| atomic_error(path, WriteStage::Replace, err))
+        })
+        .and_then(|()| {
+            sync_parent_dir(path).map_err(|err| atomic_error(path, WriteStage::SyncDirectory, err))
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
 }
 
-fn serialize_ledger(ledger: &Ledger) -> Result<String, CoreError> {
-    serde_json::to_string_pretty(ledger).map_err(|err| CoreError::Serde(err.to_string()))
+/// Streams the pretty-printed ledger into `path` through a buffered writer
+/// and syncs it to disk. The serialized document is never held in memory as
+/// a whole.
+fn write_ledger_file(path: &Path, ledger: &Ledger) -> Result<(), CoreError> {
+    let file = File::create(path).map_err(|err| atomic_error(path, WriteStage::CreateTemp, err))?;
+    let mut writer = BufWriter::with_capacity(WRITE_BUFFER_BYTES, file);
+    serde_json::to_writer_pretty(&mut writer, ledger).map_err(|err| {
+        if err.is_io() {
+            atomic_error(path, WriteStage::Write, err.into())
+        } else {
+            CoreError::Serde(err.to_string())
+        }
+    })?;
+    let file = writer
+        .into_inner()
+        .map_err(|err| atomic_error(path, WriteStage::Write, err.into_error()))?;
+    file.sync_all()
+        .map_err(|err| atomic_error(path, WriteStage::SyncFile, err))
+}
+
+fn atomic_error(path: &Path, stage: WriteStage, source: io::Error) -> CoreError {
+    CoreError::AtomicWrite {
+        path: path.to_path_buf(),
+        stage,
+        source,
+    }
+}
+
+#[cfg(not(windows))]
+fn replace_file(source: &Path, target: &Path) -> io::Result<()> {
+    fs::rename(source, target)
+}
+
+/// Uses `ReplaceFileW` when the destination exists so its attributes and ACLs
+/// carry over, and `MoveFileExW` otherwise. Both write through to disk.
+#[cfg(windows)]
+fn replace_file(source: &Path, target: &Path) -> io::Result<()> {
+    use std::{iter, os::windows::ffi::OsStrExt, ptr};
+    use windows_sys::Win32::Storage::FileSystem::{
+        MoveFileExW, ReplaceFileW, MOVEFILE_REPLACE_EXISTING, MOVEFILE_WRITE_THROUGH,
+        REPLACEFILE_WRITE_THROUGH,
+    };
+
+    let wide = |path: &Path| -> Vec<u16> {
+        path.as_os_str()
+            .encode_wide()
+            .chain(iter::once(0))
+            .collect()
+    };
+    let source = wide(source);
+    let target_wide = wide(target);
+    let replaced = target.exists()
+        && unsafe {
+            ReplaceFileW(
+                target_wide.as_ptr(),
+                source.as_ptr(),
+                ptr::null(),
+                REPLACEFILE_WRITE_THROUGH,
+                ptr::null(),
+                ptr::null(),
+            )
+        } != 0;
+    if replaced {
+        return Ok(());
+    }
+    let moved = unsafe {
+        MoveFileExW(
+            source.as_ptr(),
+            target_wide.as_ptr(),
+            MOVEFILE_REPLACE_EXISTING | MOVEFILE_WRITE_THROUGH,
+        )
+    };
+    if moved != 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Flushes the directory entry so a completed rename is durable.
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => File::open(parent)?.sync_all(),
+        _ => File::open(".")?.sync_all(),
+    }
+}
+
+/// Directory handles cannot be synced here; `replace_file` writes through.
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
 }
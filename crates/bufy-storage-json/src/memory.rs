@@ -0,0 +1,222 @@
+//! Ledgers kept in process memory, for tests and UI automation that must not
+//! touch the filesystem.
+//!
+//! Behaves like [`crate::JsonLedgerStorage`] for saves, backups, and the
+//! audit log, but everything is gone once the storage is dropped. Paths
+//! passed to the `*_path` methods are only used as keys.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use bufy_core::{
+    storage::{LedgerBackupInfo, LedgerStorage},
+    AuditChange, AuditEntry, CoreError,
+};
+use bufy_domain::Ledger;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    canonical_name, filename::sanitize_backup_note, BACKUP_SUFFIX, BACKUP_TIMESTAMP_FORMAT,
+    DEFAULT_RETENTION,
+};
+
+/// In-memory storage; see the module docs.
+#[derive(Debug, Default)]
+pub struct MemoryLedgerStorage {
+    state: Mutex<MemoryState>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    ledgers: BTreeMap<String, Ledger>,
+    files: HashMap<PathBuf, Ledger>,
+    /// Oldest first, per ledger slug.
+    backups: HashMap<String, Vec<(LedgerBackupInfo, Ledger)>>,
+    audit: HashMap<String, Vec<AuditEntry>>,
+    next_backup: u64,
+}
+
+impl MemoryLedgerStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, MemoryState>, CoreError> {
+        self.state
+            .lock()
+            .map_err(|_| CoreError::Storage("in-memory storage lock poisoned".into()))
+    }
+}
+
+impl MemoryState {
+    fn push_backup(&mut self, name: &str, ledger: Ledger, note: Option<&str>) -> LedgerBackupInfo {
+        let slug = canonical_name(name);
+        self.next_backup += 1;
+        let created_at = bufy_domain::now()
+            .format(BACKUP_TIMESTAMP_FORMAT)
+            .to_string();
+        let mut id = format!("{}_{}-{:04}", slug, created_at, self.next_backup);
+        if let Some(note) = sanitize_backup_note(note) {
+            id = format!("{}_{}", id, note);
+        }
+        id.push_str(BACKUP_SUFFIX);
+        let info = LedgerBackupInfo {
+            ledger: slug.clone(),
+            path: PathBuf::from(format!("memory/{}-backups/{}", slug, id)),
+            id,
+            created_at,
+        };
+        let backups = self.backups.entry(slug).or_default();
+        backups.push((info.clone(), ledger));
+        if backups.len() > DEFAULT_RETENTION {
+            backups.remove(0);
+        }
+        info
+    }
+
+    fn backup_mut(&mut self, backup: &LedgerBackupInfo) -> Result<&mut Ledger, CoreError> {
+        self.backups
+            .get_mut(&backup.ledger)
+            .and_then(|backups| {
+                backups
+                    .iter_mut()
+                    .find(|(info, _)| info.path == backup.path)
+            })
+            .map(|(_, ledger)| ledger)
+            .ok_or_else(|| CoreError::Storage(format!("backup `{}` not found", backup.id)))
+    }
+}
+
+impl LedgerStorage for MemoryLedgerStorage {
+    fn save_ledger(&self, name: &str, ledger: &Ledger) -> Result<(), CoreError> {
+        let mut state = self.state()?;
+        let slug = canonical_name(name);
+        if let Some(previous) = state.ledgers.get(&slug).cloned() {
+            state.push_backup(name, previous, None);
+        }
+        state.ledgers.insert(slug, ledger.clone());
+        Ok(())
+    }
+
+    fn load_ledger(&self, name: &str) -> Result<Ledger, CoreError> {
+        self.state()?
+            .ledgers
+            .get(&canonical_name(name))
+            .cloned()
+            .ok_or_else(|| CoreError::LedgerNotFound(name.to_string()))
+    }
+
+    fn list_ledgers(&self) -> Result<Vec<String>, CoreError> {
+        Ok(self.state()?.ledgers.keys().cloned().collect())
+    }
+
+    fn delete_ledger(&self, name: &str) -> Result<(), CoreError> {
+        let mut state = self.state()?;
+        let slug = canonical_name(name);
+        state.ledgers.remove(&slug);
+        state.audit.remove(&slug);
+        Ok(())
+    }
+
+    fn save_ledger_to_path(&self, ledger: &Ledger, path: &Path) -> Result<(), CoreError> {
+        self.state()?
+            .files
+            .insert(path.to_path_buf(), ledger.clone());
+        Ok(())
+    }
+
+    fn load_ledger_from_path(&self, path: &Path) -> Result<Ledger, CoreError> {
+        let state = self.state()?;
+        state
+            .files
+            .get(path)
+            .or_else(|| {
+                state
+                    .backups
+                    .values()
+                    .flatten()
+                    .find(|(info, _)| info.path == path)
+                    .map(|(_, ledger)| ledger)
+            })
+            .cloned()
+            .ok_or_else(|| CoreError::LedgerNotFound(path.display().to_string()))
+    }
+
+    fn backup_ledger(
+        &self,
+        name: &str,
+        ledger: &Ledger,
+        note: Option<&str>,
+    ) -> Result<LedgerBackupInfo, CoreError> {
+        Ok(self.state()?.push_backup(name, ledger.clone(), note))
+    }
+
+    fn list_backups(&self, name: &str) -> Result<Vec<LedgerBackupInfo>, CoreError> {
+        let state = self.state()?;
+        let mut backups: Vec<LedgerBackupInfo> = state
+            .backups
+            .get(&canonical_name(name))
+            .map(|backups| backups.iter().map(|(info, _)| info.clone()).collect())
+            .unwrap_or_default();
+        backups.reverse();
+        Ok(backups)
+    }
+
+    fn restore_backup(&self, backup: &LedgerBackupInfo) -> Result<Ledger, CoreError> {
+        let mut state = self.state()?;
+        let ledger = state.backup_mut(backup)?.clone();
+        state.ledgers.insert(backup.ledger.clone(), ledger.clone());
+        Ok(ledger)
+    }
+
+    fn append_audit(
+        &self,
+        name: &str,
+        actor: &str,
+        changes: Vec<AuditChange>,
+    ) -> Result<Vec<AuditEntry>, CoreError> {
+        let mut state = self.state()?;
+        let log = state.audit.entry(canonical_name(name)).or_default();
+        let head = log.last().map_or(0, |entry| entry.seq);
+        let recorded_at = bufy_domain::now();
+        let entries: Vec<AuditEntry> = changes
+            .into_iter()
+            .zip(head + 1..)
+            .map(|(change, seq)| AuditEntry {
+                seq,
+                recorded_at,
+                actor: actor.to_string(),
+                change,
+            })
+            .collect();
+        log.extend(entries.iter().cloned());
+        Ok(entries)
+    }
+
+    fn read_audit(&self, name: &str) -> Result<Vec<AuditEntry>, CoreError> {
+        Ok(self
+            .state()?
+            .audit
+            .get(&canonical_name(name))
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn purge_audit(&self, name: &str, before: DateTime<Utc>) -> Result<usize, CoreError> {
+        let mut state = self.state()?;
+        let Some(log) = state.audit.get_mut(&canonical_name(name)) else {
+            return Ok(0);
+        };
+        let total = log.len();
+        log.retain(|entry| entry.recorded_at >= before);
+        Ok(total - log.len())
+    }
+
+    fn rewrite_backup(&self, backup: &LedgerBackupInfo, ledger: &Ledger) -> Result<(), CoreError> {
+        *self.state()?.backup_mut(backup)? = ledger.clone();
+        Ok(())
+    }
+}
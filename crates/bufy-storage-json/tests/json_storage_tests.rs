@@ -1,6 +1,8 @@
-use bufy_core::storage::LedgerStorage;
-use bufy_domain::{Ledger, LedgerBudgetPeriod};
-use bufy_storage_json::{JsonLedgerStorage, StoragePaths};
+use bufy_core::{storage::LedgerStorage, CoreError, WriteStage};
+use bufy_domain::{Ledger, LedgerBudgetPeriod, CURRENT_SCHEMA_VERSION};
+use bufy_storage_json::{
+    canonical_name, parse_backup_timestamp, save_ledger_to_path, JsonLedgerStorage, StoragePaths,
+};
 use serde_json::to_string;
 use std::fs;
 use tempfile::tempdir;
@@ -104,3 +106,427 @@ fn json_storage_loads_legacy_json_ledgers() {
         .unwrap()
         .contains(&legacy_slug.to_string()));
 }
+
+#[test]
+fn ledgers_from_a_newer_schema_are_refused_before_parsing() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("future.bfy");
+    let mut value = serde_json::to_value(Ledger::new("Future", LedgerBudgetPeriod::monthly()))
+        .expect("serialize ledger");
+    value["schema_version"] = (CURRENT_SCHEMA_VERSION + 1).into();
+    value["transactions"] = "stored some other way".into();
+    fs::write(&path, value.to_string()).expect("write future file");
+
+    match bufy_storage_json::load_ledger_from_path(&path) {
+        Err(CoreError::Storage(message)) => assert!(message.contains("newer than supported")),
+        other => panic!("expected a schema error, got {other:?}"),
+    }
+}
+
+#[test]
+fn atomic_save_leaves_no_temporary_files() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("ledger.bfy");
+    let ledger = Ledger::new("Atomic", LedgerBudgetPeriod::monthly());
+
+    save_ledger_to_path(&ledger, &path).expect("first save");
+    save_ledger_to_path(&ledger, &path).expect("overwrite");
+
+    let entries: Vec<_> = fs::read_dir(dir.path())
+        .expect("read dir")
+        .map(|entry| entry.expect("entry").file_name())
+        .collect();
+    assert_eq!(entries, vec![std::ffi::OsString::from("ledger.bfy")]);
+}
+
+#[test]
+fn atomic_save_reports_typed_error_and_cleans_up() {
+    let dir = tempdir().expect("tempdir");
+    let path = dir.path().join("occupied.bfy");
+    fs::create_dir(&path).expect("create blocking directory");
+    fs::write(path.join("keep.txt"), "x").expect("populate directory");
+    let ledger = Ledger::new("Atomic", LedgerBudgetPeriod::monthly());
+
+    let err = save_ledger_to_path(&ledger, &path).expect_err("rename onto a directory fails");
+    match err {
+        CoreError::AtomicWrite {
+            path: failed,
+            stage,
+            ..
+        } => {
+            assert_eq!(failed, path);
+            assert_eq!(stage, WriteStage::Replace);
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+    assert!(!dir.path().join("occupied.bfy.tmp").exists());
+    assert!(path.join("keep.txt").exists());
+}
+
+#[test]
+fn backups_within_the_same_second_do_not_collide() {
+    let dir = tempdir().expect("tempdir");
+    let paths = StoragePaths {
+        ledger_root: dir.path().join("ledgers"),
+        backup_root: dir.path().join("backups"),
+    };
+    let storage = JsonLedgerStorage::new(paths).expect("create storage");
+    let ledger = Ledger::new("Burst", LedgerBudgetPeriod::monthly());
+
+    let first = storage
+        .backup_ledger("burst", &ledger, Some("before import"))
+        .expect("first backup");
+    let second = storage
+        .backup_ledger("burst", &ledger, Some("before import"))
+        .expect("second backup");
+
+    assert_ne!(first.id, second.id);
+    let backups = storage.list_backups("burst").expect("list backups");
+    assert_eq!(backups.len(), 2);
+    assert!(parse_backup_timestamp(&first.id).is_some());
+    assert!(first.id.ends_with("_before-import.bbfy"));
+}
+
+#[test]
+fn backup_timestamps_parse_current_and_legacy_names() {
+    let legacy = parse_backup_timestamp("home_20250102_0930.bbfy").expect("legacy name");
+    assert_eq!(legacy.to_rfc3339(), "2025-01-02T09:30:00+00:00");
+    let noted = parse_backup_timestamp("home_20250102_0930_pre-sync.bbfy").expect("legacy note");
+    assert_eq!(noted, legacy);
+    let current =
+        parse_backup_timestamp("home_2024_20250102_093015-a1b2_pre-sync.bbfy").expect("current");
+    assert_eq!(current.to_rfc3339(), "2025-01-02T09:30:15+00:00");
+    assert!(parse_backup_timestamp("home.bbfy").is_none());
+}
+
+#[test]
+fn ledger_metadata_is_served_from_index_until_file_changes() {
+    let dir = tempdir().expect("tempdir");
+    let paths = StoragePaths {
+        ledger_root: dir.path().join("ledgers"),
+        backup_root: dir.path().join("backups"),
+    };
+    let storage = JsonLedgerStorage::new(paths).expect("create storage");
+    storage
+        .save_ledger("home", &Ledger::new("Home", LedgerBudgetPeriod::monthly()))
+        .expect("save home");
+    storage
+        .save_ledger("work", &Ledger::new("Work", LedgerBudgetPeriod::monthly()))
+        .expect("save work");
+
+    let listed = storage.list_ledger_metadata().expect("list metadata");
+    assert_eq!(listed.len(), 2);
+    assert!(listed.iter().all(|meta| meta.from_index));
+
+    let renamed = Ledger::new("Work renamed", LedgerBudgetPeriod::monthly());
+    save_ledger_to_path(&renamed, &storage.ledger_path("work")).expect("external write");
+
+    let listed = storage.list_ledger_metadata().expect("list metadata");
+    let work = listed
+        .iter()
+        .find(|meta| meta.slug == "work")
+        .expect("work metadata");
+    assert!(!work.from_index);
+    assert_eq!(work.name, "Work renamed");
+
+    storage.delete_ledger("home").expect("delete home");
+    let listed = storage.list_ledger_metadata().expect("list metadata");
+    assert_eq!(listed.len(), 1);
+    assert!(listed[0].from_index);
+}
+
+#[test]
+fn json_storage_change_feed_appends_reads_and_compacts() {
+    use bufy_core::{ChangeFeedService, LedgerChange};
+    use std::io::Write;
+
+    let dir = tempdir().expect("tempdir");
+    let paths = StoragePaths {
+        ledger_root: dir.path().join("ledgers"),
+        backup_root: dir.path().join("backups"),
+    };
+    let storage = JsonLedgerStorage::new(paths).expect("create storage");
+    assert_eq!(storage.change_feed_head("feed").unwrap(), None);
+
+    let mut ledger = Ledger::new("Feed", LedgerBudgetPeriod::monthly());
+    let first = ChangeFeedService::diff(None, &ledger).unwrap();
+    storage.append_changes("feed", first).unwrap();
+    let previous = ledger.clone();
+    ledger.name = "Feed Renamed".into();
+    let changes = ChangeFeedService::diff(Some(&previous), &ledger).unwrap();
+    let appended = storage.append_changes("feed", changes).unwrap();
+    assert_eq!(appended.first().map(|event| event.seq), Some(2));
+
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(storage.change_feed_path("feed"))
+        .unwrap();
+    file.write_all(b"{\"seq\":99,\"recor").unwrap();
+    let events = storage.read_changes("feed", 0).unwrap();
+    assert_eq!(
+        ChangeFeedService::replay(&events).unwrap().name,
+        "Feed Renamed"
+    );
+    assert!(storage
+        .read_changes("feed", 1)
+        .unwrap()
+        .iter()
+        .all(|event| event.seq > 1));
+
+    let head = storage.change_feed_head("feed").unwrap().unwrap();
+    let snapshot = storage.compact_changes("feed", &ledger).unwrap();
+    assert_eq!(snapshot.seq, head + 1);
+    let events = storage.read_changes("feed", 0).unwrap();
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0].change, LedgerChange::Snapshot { .. }));
+
+    storage.save_ledger("feed", &ledger).unwrap();
+    storage.delete_ledger("feed").unwrap();
+    assert!(!storage.change_feed_path("feed").exists());
+}
+
+#[test]
+fn json_storage_audit_log_appends_with_actor_and_sequence() {
+    use bufy_core::{AuditAction, AuditService};
+
+    let dir = tempdir().expect("tempdir");
+    let paths = StoragePaths {
+        ledger_root: dir.path().join("ledgers"),
+        backup_root: dir.path().join("backups"),
+    };
+    let storage = JsonLedgerStorage::new(paths).expect("create storage");
+    assert!(storage.read_audit("home").unwrap().is_empty());
+
+    let mut ledger = Ledger::new("Home", LedgerBudgetPeriod::monthly());
+    let previous = ledger.clone();
+    ledger.name = "Household".into();
+    let changes = AuditService::changes(Some(&previous), &ledger).unwrap();
+    storage.append_audit("home", "alex", changes).unwrap();
+    let changes = AuditService::changes(Some(&ledger), &previous).unwrap();
+    let appended = storage.append_audit("home", "sam", changes).unwrap();
+    assert_eq!(appended.first().map(|entry| entry.seq), Some(2));
+
+    let entries = storage.read_audit("home").unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].actor, "alex");
+    assert_eq!(entries[1].actor, "sam");
+    assert_eq!(entries[1].change.action, AuditAction::Edited);
+    assert_eq!(entries[0].change.after, entries[1].change.before);
+
+    storage.save_ledger("home", &ledger).unwrap();
+    storage.delete_ledger("home").unwrap();
+    assert!(!storage.audit_log_path("home").exists());
+}
+
+#[test]
+fn embedded_sample_is_shifted_to_the_current_month_and_read_only() {
+    use bufy_storage_json::{EmbeddedLedgerStorage, SAMPLE_LEDGER_NAME};
+    use chrono::{Datelike, NaiveDate};
+
+    let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+    let storage = EmbeddedLedgerStorage::anchored_at(today);
+    let ledger = storage
+        .load_ledger(SAMPLE_LEDGER_NAME)
+        .expect("load sample");
+
+    assert!(!ledger.accounts.is_empty());
+    assert!(ledger
+        .transactions
+        .iter()
+        .any(|txn| txn.recurrence.is_some()));
+    assert_eq!(ledger.simulations.len(), 1);
+    let latest = ledger
+        .transactions
+        .iter()
+        .map(|txn| txn.scheduled_date)
+        .max()
+        .unwrap();
+    assert_eq!((latest.year(), latest.month()), (2026, 10));
+
+    assert!(matches!(
+        storage.save_ledger(SAMPLE_LEDGER_NAME, &ledger),
+        Err(CoreError::InvalidOperation(_))
+    ));
+    assert!(matches!(
+        storage.load_ledger("household"),
+        Err(CoreError::LedgerNotFound(_))
+    ));
+}
+
+#[test]
+fn memory_storage_keeps_ledgers_backups_and_audit_without_files() {
+    use bufy_core::{AuditAction, AuditChange};
+    use bufy_storage_json::MemoryLedgerStorage;
+
+    let storage = MemoryLedgerStorage::new();
+    let mut ledger = Ledger::new("Household", LedgerBudgetPeriod::monthly());
+    storage.save_ledger("Household", &ledger).expect("save");
+    ledger.name = "Household v2".into();
+    storage
+        .save_ledger("household", &ledger)
+        .expect("save again");
+
+    assert_eq!(
+        storage.list_ledgers().unwrap(),
+        vec!["household".to_string()]
+    );
+    assert_eq!(
+        storage.load_ledger("HOUSEHOLD").unwrap().name,
+        "Household v2"
+    );
+    let backups = storage.list_backups("household").unwrap();
+    assert_eq!(backups.len(), 1);
+    assert!(!backups[0].path.exists());
+    assert_eq!(
+        storage
+            .load_ledger_from_path(&backups[0].path)
+            .unwrap()
+            .name,
+        "Household"
+    );
+    assert_eq!(
+        storage.restore_backup(&backups[0]).unwrap().name,
+        "Household"
+    );
+    assert_eq!(storage.load_ledger("household").unwrap().name, "Household");
+
+    let change = AuditChange {
+        action: AuditAction::Added,
+        entity: "accounts".into(),
+        entity_id: "1".into(),
+        label: None,
+        before: None,
+        after: Some("abc".into()),
+    };
+    let entries = storage
+        .append_audit("household", "alex", vec![change.clone(), change])
+        .expect("append audit");
+    assert_eq!(entries.last().map(|entry| entry.seq), Some(2));
+    assert_eq!(storage.read_audit("household").unwrap().len(), 2);
+    assert!(matches!(
+        storage.load_ledger("missing"),
+        Err(CoreError::LedgerNotFound(_))
+    ));
+}
+
+#[test]
+fn long_and_unicode_ledger_names_get_bounded_distinct_file_names() {
+    let dir = tempdir().expect("tempdir");
+    let storage = JsonLedgerStorage::new(StoragePaths {
+        ledger_root: dir.path().join("ledgers"),
+        backup_root: dir.path().join("backups"),
+    })
+    .expect("create storage");
+    let stem = |name: &str| {
+        storage
+            .ledger_path(name)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap()
+            .to_string()
+    };
+
+    let long = "household budget ".repeat(20);
+    let other = format!("{}2", long);
+    assert!(stem(&long).len() <= 64);
+    assert!(stem(&long).starts_with("household_budget_"));
+    assert_ne!(stem(&long), stem(&other));
+    // Case-insensitive file systems (Windows, macOS) must see one file.
+    assert_eq!(stem(&long), stem(&long.to_uppercase()));
+    assert_eq!(stem("Household"), "household");
+
+    // Any character sanitizing replaces adds a hash, so these stay apart.
+    assert_ne!(stem("a/b"), stem("a?b"));
+    assert_ne!(stem("a b"), stem("a_b"));
+    assert!(stem("a/b").starts_with("a_b-"));
+    assert_eq!(stem("a_b"), "a_b");
+    // A slug handed back in, such as a listed ledger, names the same file.
+    assert_eq!(canonical_name(&stem("a/b")), stem("a/b"));
+    assert_eq!(canonical_name(&stem(&long)), stem(&long));
+
+    assert_ne!(stem("家計"), stem("予算"));
+    assert!(stem("家計").starts_with("ledger-"));
+    assert_eq!(stem("   "), "ledger");
+
+    // Windows device names cannot be used as file names on any drive.
+    assert_eq!(stem("CON"), "con_");
+    assert_eq!(stem("lpt1"), "lpt1_");
+    assert_eq!(stem("console"), "console");
+
+    let ledger = Ledger::new("Long", LedgerBudgetPeriod::monthly());
+    storage.save_ledger(&long, &ledger).expect("save long");
+    storage.save_ledger(&other, &ledger).expect("save other");
+    storage.save_ledger("CON", &ledger).expect("save reserved");
+    assert_eq!(storage.load_ledger(&long).expect("load long").name, "Long");
+    assert_eq!(storage.list_ledgers().expect("list").len(), 3);
+}
+
+#[test]
+fn backup_names_stay_within_path_and_name_limits() {
+    let dir = tempdir().expect("tempdir");
+    let storage = JsonLedgerStorage::new(StoragePaths {
+        ledger_root: dir.path().join("ledgers"),
+        backup_root: dir.path().join("backups"),
+    })
+    .expect("create storage");
+    let ledger = Ledger::new("Notes", LedgerBudgetPeriod::monthly());
+    let name = "a ledger whose name is far longer than any file system would like";
+    let note = "before importing the whole of last year's statements ".repeat(10);
+
+    let first = storage
+        .backup_ledger(name, &ledger, Some(&note))
+        .expect("first backup");
+    let second = storage
+        .backup_ledger(name, &ledger, Some(&note))
+        .expect("second backup");
+
+    assert_ne!(first.id, second.id);
+    for info in [&first, &second] {
+        // NAME_MAX on Linux and macOS, MAX_PATH on Windows.
+        assert!(info.id.len() <= 255);
+        assert!(info.path.to_string_lossy().chars().count() <= 260);
+        assert!(info.id.contains("_before-importing"));
+        assert!(parse_backup_timestamp(&info.id).is_some());
+    }
+    assert_eq!(storage.list_backups(name).expect("list").len(), 2);
+}
+
+#[test]
+fn ledgers_saved_under_unbudgeted_names_load_and_move_on_save() {
+    let dir = tempdir().expect("tempdir");
+    let paths = StoragePaths {
+        ledger_root: dir.path().join("ledgers"),
+        backup_root: dir.path().join("backups"),
+    };
+    let storage = JsonLedgerStorage::new(paths.clone()).expect("create storage");
+    let name = "x".repeat(80);
+    let ledger = Ledger::new("Legacy", LedgerBudgetPeriod::monthly());
+    let legacy_path = paths.ledger_root.join(format!("{}.bfy", name));
+    save_ledger_to_path(&ledger, &legacy_path).expect("write legacy file");
+    fs::create_dir_all(paths.backup_root.join(format!("{}-backups", name))).unwrap();
+
+    assert_eq!(
+        storage.load_ledger(&name).expect("load legacy").name,
+        "Legacy"
+    );
+
+    storage.save_ledger(&name, &ledger).expect("save");
+    assert!(!legacy_path.exists());
+    assert!(storage.ledger_path(&name).exists());
+    assert_eq!(storage.list_ledgers().expect("list").len(), 1);
+    assert_eq!(storage.list_backups(&name).expect("backups").len(), 1);
+
+    // Short names saved before sanitized names were hashed.
+    let plain_path = paths.ledger_root.join("family_budget.bfy");
+    save_ledger_to_path(&ledger, &plain_path).expect("write plain file");
+    assert_eq!(
+        storage
+            .load_ledger("Family Budget")
+            .expect("load plain")
+            .name,
+        "Legacy"
+    );
+    storage.save_ledger("Family Budget", &ledger).expect("save");
+    assert!(!plain_path.exists());
+    assert!(storage.ledger_path("Family Budget").exists());
+}